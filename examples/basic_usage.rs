@@ -22,6 +22,7 @@ async fn test_audio_pipeline() -> Result<(), Box<dyn std::error::Error>> {
         channels: 1,
         bitrate: 64000,
         frame_duration_ms: 20,
+        ..OpusConfig::default()
     };
     let codec = OpusCodec::with_config(opus_config)?;
 
@@ -82,6 +82,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         max_objects_per_track: 100,
         object_ttl: std::time::Duration::from_secs(30),
         enable_lru_eviction: true,
+        ..Default::default()
     };
     
     let mut delivery_system = MoqObjectDelivery::new(cache_config);
@@ -178,6 +179,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             object_status: MoqObjectStatus::Normal,
             created_at: std::time::Instant::now(),
             size: 10,
+            metadata: Default::default(),
         };
         delivery_system.enqueue_object(object)?;
     }
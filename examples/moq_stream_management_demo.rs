@@ -133,5 +133,6 @@ fn create_sample_object(namespace: TrackNamespace, group_id: u64, object_id: u64
         object_status: quicrtc_core::MoqObjectStatus::Normal,
         created_at: std::time::Instant::now(),
         size,
+        metadata: Default::default(),
     }
 }
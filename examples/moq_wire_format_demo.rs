@@ -79,6 +79,7 @@ fn demo_control_messages() -> Result<(), Box<dyn std::error::Error>> {
         supported_track_types: vec![MoqTrackType::Audio, MoqTrackType::Video, MoqTrackType::Data],
         max_object_size: 10 * 1024 * 1024, // 10MB
         supports_caching: true,
+        ..MoqCapabilities::default()
     };
 
     // Demo CLIENT_SETUP message
@@ -107,15 +108,16 @@ fn demo_control_messages() -> Result<(), Box<dyn std::error::Error>> {
         track_name: "live/camera1".to_string(),
     };
 
-    let track = MoqTrack {
-        namespace: track_ns.clone(),
-        name: "high-quality-stream".to_string(),
-        track_type: MoqTrackType::Video,
-    };
+    let track = MoqTrack::new(
+        track_ns.clone(),
+        "high-quality-stream".to_string(),
+        MoqTrackType::Video,
+    );
 
     let announce_msg = MoqControlMessage::Announce {
         track_namespace: track_ns,
         track,
+        auth_token: None,
     };
 
     buf.clear();
@@ -144,6 +146,7 @@ fn demo_control_messages() -> Result<(), Box<dyn std::error::Error>> {
         priority: 5,
         start_group: Some(100),
         end_group: None, // Subscribe to all future groups
+        auth_token: None,
     };
 
     buf.clear();
@@ -189,6 +192,7 @@ fn demo_object_encoding() -> Result<(), Box<dyn std::error::Error>> {
         object_status: MoqObjectStatus::Normal,
         created_at: Instant::now(),
         size: 1024,
+        metadata: Default::default(),
     };
 
     let track_alias = 123u64;
@@ -261,6 +265,7 @@ fn demo_session_simulation() -> Result<(), Box<dyn std::error::Error>> {
             supported_track_types: vec![MoqTrackType::Audio, MoqTrackType::Video],
             max_object_size: 5 * 1024 * 1024,
             supports_caching: true,
+            ..MoqCapabilities::default()
         },
     };
 
@@ -281,6 +286,7 @@ fn demo_session_simulation() -> Result<(), Box<dyn std::error::Error>> {
             ],
             max_object_size: 10 * 1024 * 1024,
             supports_caching: true,
+            ..MoqCapabilities::default()
         },
     };
 
@@ -294,14 +300,15 @@ fn demo_session_simulation() -> Result<(), Box<dyn std::error::Error>> {
             namespace: "sports.tv".to_string(),
             track_name: "football/live/field1".to_string(),
         },
-        track: MoqTrack {
-            namespace: TrackNamespace {
+        track: MoqTrack::new(
+            TrackNamespace {
                 namespace: "sports.tv".to_string(),
                 track_name: "football/live/field1".to_string(),
             },
-            name: "4k-main-feed".to_string(),
-            track_type: MoqTrackType::Video,
-        },
+            "4k-main-feed".to_string(),
+            MoqTrackType::Video,
+        ),
+        auth_token: None,
     };
 
     buf.clear();
@@ -317,6 +324,7 @@ fn demo_session_simulation() -> Result<(), Box<dyn std::error::Error>> {
         priority: 1,             // High priority for live sports
         start_group: Some(1000), // Start from current position
         end_group: None,         // Subscribe indefinitely
+        auth_token: None,
     };
 
     buf.clear();
@@ -348,6 +356,7 @@ fn demo_session_simulation() -> Result<(), Box<dyn std::error::Error>> {
             object_status: status.clone(),
             created_at: Instant::now(),
             size: *size,
+            metadata: Default::default(),
         };
 
         // Use datagram for low latency
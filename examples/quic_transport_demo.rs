@@ -142,6 +142,11 @@ async fn test_client_server_communication() -> Result<(), QuicRtcError> {
         "  - Congestion controller: {:?}",
         transport_config.congestion_controller
     );
+    info!(
+        "  - Socket backend: {:?} ({} socket(s))",
+        transport_config.socket_config.backend,
+        transport_config.socket_config.shard_count()
+    );
 
     info!("📋 Resource limits:");
     info!("  - Max memory: {:?} MB", resource_limits.max_memory_mb);
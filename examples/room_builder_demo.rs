@@ -10,8 +10,8 @@
 //! - Configuration validation
 
 use quicrtc::{
-    AudioProcessingConfig, GlobalConfig, QuicRtc, ResourceLimits, VideoProcessingConfig,
-    VideoQuality,
+    AudioProcessingConfig, GlobalConfig, QuicRtc, ResourceLimits, VideoContentHint,
+    VideoProcessingConfig, VideoQuality, VideoRotationMode,
 };
 use std::time::Duration;
 
@@ -183,8 +183,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         let audio_config = AudioProcessingConfig {
             enable_echo_cancellation: true,
             enable_noise_suppression: true,
+            enable_auto_gain_control: true,
+            profile: None,
             buffer_size: 960, // 20ms at 48kHz
             default_volume: 0.9,
+            follow_default_devices: true,
+            ducking: None,
         };
 
         let video_config = VideoProcessingConfig {
@@ -192,6 +196,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             enable_auto_white_balance: true,
             default_framerate: 60.0,
             enable_preprocessing: true,
+            rotation_mode: VideoRotationMode::Renderer,
+            content_hint: VideoContentHint::Motion,
+            thumbnails: None,
         };
 
         let processing_room = quic_rtc
@@ -44,6 +44,7 @@ async fn test_transport_layer() -> Result<(), QuicRtcError> {
         ]),
         quic_transport_config: Default::default(),
         resource_limits: Default::default(),
+        packet_capture: None,
     };
 
     // Try to establish connection (will fail since no server, but tests the API)
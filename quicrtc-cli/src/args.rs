@@ -0,0 +1,99 @@
+//! Minimal command-line argument parsing
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Arguments of one subcommand
+///
+/// Options take a value (`--name value` or `--name=value`) unless they are
+/// listed as flags when parsing.
+#[derive(Debug, Default)]
+pub struct Args {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+    flags: Vec<String>,
+}
+
+impl Args {
+    /// Split `args` into positionals, options and the given `flags`
+    pub fn parse(args: impl IntoIterator<Item = String>, flags: &[&str]) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if !arg.starts_with("--") {
+                parsed.positional.push(arg);
+            } else if flags.contains(&arg.as_str()) {
+                parsed.flags.push(arg);
+            } else if let Some((name, value)) = arg.split_once('=') {
+                parsed.options.insert(name.to_string(), value.to_string());
+            } else {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("{} needs a value", arg))?;
+                parsed.options.insert(arg, value);
+            }
+        }
+        parsed.positional.reverse();
+        Ok(parsed)
+    }
+
+    /// Take the next positional argument
+    pub fn positional(&mut self, name: &str) -> Result<String, String> {
+        self.positional
+            .pop()
+            .ok_or_else(|| format!("missing <{}>", name))
+    }
+
+    /// Whether `flag` was given
+    pub fn flag(&mut self, flag: &str) -> bool {
+        let given = self.flags.iter().any(|f| f == flag);
+        self.flags.retain(|f| f != flag);
+        given
+    }
+
+    /// Take the value of option `name`
+    pub fn value(&mut self, name: &str) -> Option<String> {
+        self.options.remove(name)
+    }
+
+    /// Take and parse the value of option `name`
+    pub fn parsed<T: FromStr>(&mut self, name: &str) -> Result<Option<T>, String> {
+        self.value(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| format!("invalid value '{}' for {}", value, name))
+            })
+            .transpose()
+    }
+
+    /// Take option `name` as a number of seconds
+    pub fn seconds(&mut self, name: &str) -> Result<Option<Duration>, String> {
+        Ok(self.parsed::<f64>(name)?.map(Duration::from_secs_f64))
+    }
+
+    /// Fail on anything that was not taken
+    pub fn finish(self) -> Result<(), String> {
+        if let Some(arg) = self.positional.last() {
+            return Err(format!("unexpected argument '{}'", arg));
+        }
+        if let Some(flag) = self.flags.first() {
+            return Err(format!("unknown flag {}", flag));
+        }
+        if let Some(option) = self.options.keys().next() {
+            return Err(format!("unknown option {}", option));
+        }
+        Ok(())
+    }
+}
+
+/// Resolve `host:port` to a socket address
+pub async fn resolve(address: &str) -> Result<SocketAddr, String> {
+    tokio::net::lookup_host(address)
+        .await
+        .map_err(|e| format!("cannot resolve {}: {}", address, e))?
+        .next()
+        .ok_or_else(|| format!("{} has no addresses", address))
+}
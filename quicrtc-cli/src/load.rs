@@ -0,0 +1,562 @@
+//! Load test against a relay with synthetic participants
+//!
+//! Every participant opens its own MoQ session to the relay, optionally
+//! after joining a room on the signaling server. Publishers send objects
+//! at a fixed bitrate whose payload starts with the send time, so every
+//! participant can measure end-to-end latency on the objects the relay
+//! forwards to it. Joins are spread over a ramp-up period, and when the
+//! server's metrics endpoint is given its resource usage is sampled every
+//! second for the whole run.
+
+use crate::args::{resolve, Args};
+use crate::{kbps, object, CliError};
+use quicrtc_core::{
+    ConnectionConfig, MoqOverQuicTransport, MoqTrack, MoqTrackType, MoqTransportEvent,
+    QuicRtcError, TrackNamespace,
+};
+use quicrtc_signaling::protocol::{SignalingMessage, SignalingResponse};
+use quicrtc_signaling::{CapabilitySet, SignalingClient};
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Namespace synthetic publishers announce their tracks in
+const LOAD_NAMESPACE: &str = "quicrtc-cli-load";
+
+/// Participants the load test runs at most
+///
+/// Each participant holds its own QUIC and WebSocket connections, so this
+/// keeps a run within common file descriptor limits.
+const MAX_PARTICIPANTS: usize = 512;
+
+/// Objects per group sent by each publisher
+const GROUP_SIZE: u64 = 30;
+
+/// How long each step of joining the room and setting up a session may take
+const JOIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often the server's metrics endpoint is sampled
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What the participants do and when
+struct LoadPlan {
+    relay: SocketAddr,
+    signaling: Option<String>,
+    room: String,
+    token: Option<String>,
+    publishers: usize,
+    participants: usize,
+    object_size: usize,
+    object_interval: Duration,
+    ramp: Duration,
+    started: Instant,
+    deadline: Instant,
+}
+
+impl LoadPlan {
+    /// When participant `index` joins
+    fn join_at(&self, index: usize) -> Instant {
+        self.started + self.ramp.mul_f64(index as f64 / self.participants as f64)
+    }
+
+    /// Payload carrying the time it was built at
+    fn payload(&self) -> Vec<u8> {
+        let sent_at = self.started.elapsed().as_micros() as u64;
+        let mut payload = vec![0u8; self.object_size];
+        payload[..8].copy_from_slice(&sent_at.to_be_bytes());
+        payload
+    }
+
+    /// Time since `payload` was built
+    fn latency(&self, payload: &[u8]) -> Option<Duration> {
+        let sent_at = u64::from_be_bytes(payload.get(..8)?.try_into().ok()?);
+        self.started
+            .elapsed()
+            .checked_sub(Duration::from_micros(sent_at))
+    }
+}
+
+/// What one participant saw
+#[derive(Debug, Default)]
+struct ParticipantResult {
+    join_time: Option<Duration>,
+    error: Option<String>,
+    objects_sent: u64,
+    send_failures: u64,
+    objects_received: u64,
+    latencies: Vec<Duration>,
+}
+
+/// One reading of the server's metrics endpoint
+#[derive(Debug)]
+struct ServerSample {
+    at: Duration,
+    values: HashMap<String, f64>,
+}
+
+/// Run synthetic publishers and subscribers against a relay
+pub async fn load(args: impl Iterator<Item = String>) -> Result<(), CliError> {
+    let mut args = Args::parse(args, &[])?;
+    let relay = args.positional("relay")?;
+    let publishers = args.parsed::<usize>("--publishers")?.unwrap_or(10);
+    let subscribers = args.parsed::<usize>("--subscribers")?.unwrap_or(40);
+    let bitrate_kbps = args.parsed::<u64>("--bitrate")?.unwrap_or(500);
+    let object_size = args.parsed::<usize>("--object-size")?.unwrap_or(1200);
+    let duration = args
+        .seconds("--duration")?
+        .unwrap_or(Duration::from_secs(30));
+    let ramp = args.seconds("--ramp")?.unwrap_or(Duration::from_secs(10));
+    let signaling = args.value("--signaling");
+    let room = args
+        .value("--room")
+        .unwrap_or_else(|| "load-test".to_string());
+    let token = args.value("--token");
+    let metrics = args.value("--metrics");
+    let report_path = args.value("--report");
+    args.finish()?;
+
+    let participants = publishers + subscribers;
+    if participants == 0 || participants > MAX_PARTICIPANTS {
+        return Err(CliError::Usage(format!(
+            "--publishers plus --subscribers must be between 1 and {}",
+            MAX_PARTICIPANTS
+        )));
+    }
+    if bitrate_kbps == 0 {
+        return Err(CliError::Usage("--bitrate must be positive".to_string()));
+    }
+    if object_size < 8 {
+        return Err(CliError::Usage(
+            "--object-size must be at least 8 bytes".to_string(),
+        ));
+    }
+    let relay = resolve(&relay).await.map_err(CliError::Failed)?;
+    let metrics = match metrics {
+        Some(address) => Some(resolve(&address).await.map_err(CliError::Failed)?),
+        None => None,
+    };
+    if let Some(url) = &signaling {
+        create_room(url, &room, token.as_deref(), participants).await?;
+    }
+
+    let started = Instant::now();
+    let plan = Arc::new(LoadPlan {
+        relay,
+        signaling,
+        room,
+        token,
+        publishers,
+        participants,
+        object_size,
+        object_interval: Duration::from_secs_f64(
+            object_size as f64 * 8.0 / (bitrate_kbps * 1000) as f64,
+        ),
+        ramp,
+        started,
+        deadline: started + ramp + duration,
+    });
+    println!(
+        "starting {} publishers at {} kbps and {} subscribers against {} over {:?}, \
+         then running for {:?}",
+        publishers, bitrate_kbps, subscribers, relay, ramp, duration
+    );
+
+    let sampler = metrics.map(|address| tokio::spawn(sample_server(address, plan.clone())));
+    let tasks: Vec<_> = (0..participants)
+        .map(|index| tokio::spawn(participant(plan.clone(), index)))
+        .collect();
+
+    let mut results = Vec::with_capacity(participants);
+    for task in tasks {
+        results.push(task.await.unwrap_or_else(|e| ParticipantResult {
+            error: Some(format!("participant task failed: {}", e)),
+            ..ParticipantResult::default()
+        }));
+    }
+    let samples = match sampler {
+        Some(sampler) => sampler.await.unwrap_or_default(),
+        None => Vec::new(),
+    };
+
+    let report = summarize(&plan, &results, &samples, started.elapsed());
+    if let Some(path) = report_path {
+        let json = serde_json::to_string_pretty(&report)
+            .map_err(|e| CliError::Failed(format!("cannot render load report: {}", e)))?;
+        std::fs::write(&path, json)
+            .map_err(|e| CliError::Failed(format!("cannot write {}: {}", path, e)))?;
+        println!("report written to {}", path);
+    }
+
+    let joined = results.iter().filter(|r| r.join_time.is_some()).count();
+    if joined == 0 {
+        return Err(CliError::Failed("no participant could join".to_string()));
+    }
+    Ok(())
+}
+
+/// Create the room participants join, unless it already exists
+async fn create_room(
+    url: &str,
+    room: &str,
+    token: Option<&str>,
+    participants: usize,
+) -> Result<(), CliError> {
+    let client = SignalingClient::connect(url).await?;
+    client
+        .send(&SignalingMessage::CreateRoom {
+            room_id: room.to_string(),
+            room_name: None,
+            max_participants: Some(participants),
+            access_token: token.map(str::to_string),
+            schedule: None,
+        })
+        .await?;
+    let created = client
+        .expect("create room", JOIN_TIMEOUT, |response| match response {
+            SignalingResponse::RoomCreated { .. } => Some(Ok(())),
+            SignalingResponse::Error { error_code, .. } if error_code == "ROOM_ALREADY_EXISTS" => {
+                Some(Ok(()))
+            }
+            SignalingResponse::Error { error, .. } => Some(Err(error)),
+            _ => None,
+        })
+        .await?;
+    let _ = client.close().await;
+    created.map_err(|error| CliError::Failed(format!("cannot create room {}: {}", room, error)))
+}
+
+/// Join, publish or subscribe until the deadline, then leave
+async fn participant(plan: Arc<LoadPlan>, index: usize) -> ParticipantResult {
+    let mut result = ParticipantResult::default();
+    let id = format!("load-{}", index);
+    tokio::time::sleep_until(plan.join_at(index).into()).await;
+
+    let joining = Instant::now();
+    let (signaling, transport) = match join(&plan, &id).await {
+        Ok(joined) => joined,
+        Err(e) => {
+            result.error = Some(e.to_string());
+            return result;
+        }
+    };
+    result.join_time = Some(joining.elapsed());
+
+    let Some(mut events) = transport.take_event_receiver() else {
+        result.error = Some("transport events already taken".to_string());
+        return result;
+    };
+    let track = if index < plan.publishers {
+        match announce(&transport, &id).await {
+            Ok(track) => Some(track),
+            Err(e) => {
+                result.error = Some(e.to_string());
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let mut next_send = tokio::time::Instant::now();
+    let mut sequence = 0u64;
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep_until(plan.deadline.into()) => break,
+            _ = tokio::time::sleep_until(next_send), if track.is_some() => {
+                if let Some(track) = &track {
+                    let object = object(
+                        track,
+                        sequence / GROUP_SIZE,
+                        sequence % GROUP_SIZE,
+                        plan.payload(),
+                    );
+                    match transport.send_moq_object(object).await {
+                        Ok(()) => result.objects_sent += 1,
+                        Err(_) => result.send_failures += 1,
+                    }
+                }
+                sequence += 1;
+                next_send += plan.object_interval;
+            }
+            event = events.recv() => match event {
+                Some(MoqTransportEvent::ObjectReceived { .. }) => {
+                    while let Ok(object) = transport.receive_moq_object().await {
+                        result.objects_received += 1;
+                        result.latencies.extend(plan.latency(&object.payload));
+                    }
+                }
+                Some(MoqTransportEvent::TransportError { error }) => {
+                    result.error = Some(error);
+                }
+                Some(MoqTransportEvent::SessionTerminated { reason, .. }) => {
+                    result.error.get_or_insert(reason);
+                    break;
+                }
+                Some(_) => {}
+                None => break,
+            },
+        }
+    }
+
+    let _ = transport.close().await;
+    if let Some(client) = signaling {
+        let _ = client
+            .send(&SignalingMessage::LeaveRoom {
+                room_id: plan.room.clone(),
+                participant_id: id,
+            })
+            .await;
+        let _ = client.close().await;
+    }
+    result
+}
+
+/// Join the room, if signaling is used, and set up a MoQ session
+///
+/// Each step gets [`JOIN_TIMEOUT`], so the report tells which one stalls.
+async fn join(
+    plan: &LoadPlan,
+    id: &str,
+) -> Result<(Option<SignalingClient>, MoqOverQuicTransport), QuicRtcError> {
+    let signaling = match &plan.signaling {
+        Some(url) => {
+            let client = within("signaling connect", SignalingClient::connect(url)).await?;
+            client
+                .send(&SignalingMessage::JoinRoom {
+                    room_id: plan.room.clone(),
+                    participant_id: id.to_string(),
+                    participant_name: None,
+                    capabilities: CapabilitySet::new(),
+                    quic_endpoint: None,
+                    access_token: plan.token.clone(),
+                })
+                .await?;
+            client
+                .expect("join room", JOIN_TIMEOUT, |response| match response {
+                    SignalingResponse::JoinedRoom { .. } => Some(Ok(())),
+                    SignalingResponse::Error { error, .. } => Some(Err(error)),
+                    _ => None,
+                })
+                .await?
+                .map_err(|reason| QuicRtcError::InvalidOperation {
+                    operation: format!("join room {}: {}", plan.room, reason),
+                })?;
+            Some(client)
+        }
+        None => None,
+    };
+
+    let transport = within(
+        "relay connect",
+        MoqOverQuicTransport::new(plan.relay, ConnectionConfig::default(), rand::random()),
+    )
+    .await?;
+    within("MoQ session setup", transport.establish_session()).await?;
+    Ok((signaling, transport))
+}
+
+async fn within<T>(
+    operation: &str,
+    step: impl Future<Output = Result<T, QuicRtcError>>,
+) -> Result<T, QuicRtcError> {
+    tokio::time::timeout(JOIN_TIMEOUT, step)
+        .await
+        .map_err(|_| QuicRtcError::Timeout {
+            operation: operation.to_string(),
+            duration: JOIN_TIMEOUT,
+        })?
+}
+
+async fn announce(
+    transport: &MoqOverQuicTransport,
+    id: &str,
+) -> Result<TrackNamespace, QuicRtcError> {
+    let track = TrackNamespace::parse(&format!("{}/{}", LOAD_NAMESPACE, id))?;
+    transport
+        .announce_track(MoqTrack::new(
+            track.clone(),
+            track.track_name.clone(),
+            MoqTrackType::Data,
+        ))
+        .await?;
+    Ok(track)
+}
+
+/// Read the server's metrics every second until the run ends
+async fn sample_server(address: SocketAddr, plan: Arc<LoadPlan>) -> Vec<ServerSample> {
+    let mut samples = Vec::new();
+    let mut interval = tokio::time::interval(SAMPLE_INTERVAL);
+    while Instant::now() < plan.deadline {
+        interval.tick().await;
+        // A busy server missing a sample shows up as a gap in the curve
+        if let Ok(values) = scrape(address).await {
+            samples.push(ServerSample {
+                at: plan.started.elapsed(),
+                values,
+            });
+        }
+    }
+    samples
+}
+
+/// Fetch `/metrics` and parse its samples by name
+async fn scrape(address: SocketAddr) -> std::io::Result<HashMap<String, f64>> {
+    let mut stream = TcpStream::connect(address).await?;
+    stream
+        .write_all(b"GET /metrics HTTP/1.0\r\nHost: quicrtc\r\n\r\n")
+        .await?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await?;
+
+    let body = response.split_once("\r\n\r\n").map_or("", |(_, body)| body);
+    Ok(body
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let (name, value) = line.split_once(' ')?;
+            Some((name.to_string(), value.trim().parse().ok()?))
+        })
+        .collect())
+}
+
+/// Print the results and build the JSON report
+fn summarize(
+    plan: &LoadPlan,
+    results: &[ParticipantResult],
+    samples: &[ServerSample],
+    elapsed: Duration,
+) -> serde_json::Value {
+    let mut join_times: Vec<_> = results.iter().filter_map(|r| r.join_time).collect();
+    join_times.sort();
+    let mut latencies: Vec<_> = results
+        .iter()
+        .flat_map(|r| r.latencies.iter().copied())
+        .collect();
+    latencies.sort();
+    let joined = join_times.len();
+    let sent: u64 = results.iter().map(|r| r.objects_sent).sum();
+    let send_failures: u64 = results.iter().map(|r| r.send_failures).sum();
+    let received: u64 = results.iter().map(|r| r.objects_received).sum();
+
+    let mut errors: HashMap<&str, usize> = HashMap::new();
+    for error in results.iter().filter_map(|r| r.error.as_deref()) {
+        *errors.entry(error).or_default() += 1;
+    }
+
+    println!(
+        "joined {}/{} participants ({:.1}%)",
+        joined,
+        plan.participants,
+        joined as f64 * 100.0 / plan.participants as f64
+    );
+    for (error, count) in &errors {
+        println!("  {} x {}", count, error);
+    }
+    println!(
+        "join time: p50 {:?}, p95 {:?}, max {:?}",
+        percentile(&join_times, 0.5),
+        percentile(&join_times, 0.95),
+        join_times.last().copied().unwrap_or_default()
+    );
+    println!(
+        "sent {} objects ({} failed, {:.0} kbps), received {} in {:?}",
+        sent,
+        send_failures,
+        kbps(sent * plan.object_size as u64, elapsed),
+        received,
+        elapsed
+    );
+    println!(
+        "end-to-end latency: p50 {:?}, p95 {:?}, p99 {:?}, max {:?}",
+        percentile(&latencies, 0.5),
+        percentile(&latencies, 0.95),
+        percentile(&latencies, 0.99),
+        latencies.last().copied().unwrap_or_default()
+    );
+
+    let curve = server_curve(samples);
+    if !curve.is_empty() {
+        println!("server:");
+        println!(
+            "  {:>7}  {:>8}  {:>6}  {:>9}  {:>10}",
+            "time", "sessions", "cpu", "rss", "fwd/s"
+        );
+        for point in &curve {
+            println!(
+                "  {:>6.0}s  {:>8}  {:>5.0}%  {:>6.1} MB  {:>10.0}",
+                point["seconds"].as_f64().unwrap_or_default(),
+                point["relay_sessions"].as_f64().unwrap_or_default(),
+                point["cpu_percent"].as_f64().unwrap_or_default(),
+                point["resident_bytes"].as_f64().unwrap_or_default() / 1e6,
+                point["objects_forwarded_per_second"]
+                    .as_f64()
+                    .unwrap_or_default()
+            );
+        }
+    }
+
+    let millis = |duration: Duration| duration.as_secs_f64() * 1000.0;
+    serde_json::json!({
+        "participants": plan.participants,
+        "publishers": plan.publishers,
+        "joined": joined,
+        "join_success_rate": joined as f64 / plan.participants as f64,
+        "errors": errors,
+        "join_ms": {
+            "p50": millis(percentile(&join_times, 0.5)),
+            "p95": millis(percentile(&join_times, 0.95)),
+            "max": millis(join_times.last().copied().unwrap_or_default()),
+        },
+        "objects_sent": sent,
+        "send_failures": send_failures,
+        "objects_received": received,
+        "latency_ms": {
+            "p50": millis(percentile(&latencies, 0.5)),
+            "p95": millis(percentile(&latencies, 0.95)),
+            "p99": millis(percentile(&latencies, 0.99)),
+            "max": millis(latencies.last().copied().unwrap_or_default()),
+        },
+        "elapsed_seconds": elapsed.as_secs_f64(),
+        "server": curve,
+    })
+}
+
+/// Per-sample server resource usage, with counters turned into rates
+fn server_curve(samples: &[ServerSample]) -> Vec<serde_json::Value> {
+    let value = |sample: &ServerSample, name: &str| {
+        sample
+            .values
+            .get(&format!("quicrtc_{}", name))
+            .copied()
+            .unwrap_or_default()
+    };
+    samples
+        .windows(2)
+        .map(|pair| {
+            let (previous, sample) = (&pair[0], &pair[1]);
+            let seconds = (sample.at - previous.at).as_secs_f64().max(f64::EPSILON);
+            let rate = |name: &str| (value(sample, name) - value(previous, name)) / seconds;
+            serde_json::json!({
+                "seconds": sample.at.as_secs_f64(),
+                "relay_sessions": value(sample, "relay_sessions"),
+                "participants": value(sample, "participants"),
+                "cpu_percent": rate("process_cpu_seconds_total") * 100.0,
+                "resident_bytes": value(sample, "process_resident_memory_bytes"),
+                "threads": value(sample, "process_threads"),
+                "objects_forwarded_per_second": rate("relay_objects_forwarded_total"),
+                "bytes_forwarded_per_second": rate("relay_bytes_forwarded_total"),
+            })
+        })
+        .collect()
+}
+
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    sorted
+        .get(((sorted.len() as f64 * p) as usize).min(sorted.len().saturating_sub(1)))
+        .copied()
+        .unwrap_or_default()
+}
@@ -0,0 +1,542 @@
+//! Command-line tool for QUIC RTC deployments
+//!
+//! ```text
+//! quicrtc-cli <command> [options]
+//! ```
+//!
+//! Joins rooms, probes and loads relays, publishes files as MoQ objects,
+//! lists media devices and runs a signaling server, so deployments can be
+//! debugged and demoed without writing Rust. Set `RUST_LOG` for more output.
+
+mod args;
+mod load;
+
+use args::{resolve, Args};
+use quicrtc::{DebugLogger, QuicRtc, Room, SignalingServer};
+use quicrtc_core::{
+    ConnectionConfig, MoqObject, MoqObjectStatus, MoqOverQuicTransport, MoqTrack, MoqTrackType,
+    PacketCapture, PacketCaptureConfig, QuicRtcError, TrackNamespace,
+};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+/// Subcommands and their usage lines
+const COMMANDS: &[(&str, &str)] = &[
+    (
+        "join",
+        "join <room> [--participant <id>] [--signaling <url>] [--create] [--camera] \
+         [--microphone] [--duration <secs>] [--report <path>] [--capture <path>]",
+    ),
+    (
+        "publish-file",
+        "publish-file <relay> <file> [--track <namespace/name>] [--chunk-size <bytes>] \
+         [--rate <kbps>] [--capture <path>]",
+    ),
+    ("probe", "probe <relay> [--count <n>] [--timeout <secs>]"),
+    (
+        "bench",
+        "bench <relay> [--duration <secs>] [--object-size <bytes>] [--group-size <objects>] \
+         [--capture <path>]",
+    ),
+    (
+        "load",
+        "load <relay> [--publishers <n>] [--subscribers <n>] [--bitrate <kbps>] \
+         [--object-size <bytes>] [--duration <secs>] [--ramp <secs>] [--signaling <url>] \
+         [--room <id>] [--token <token>] [--metrics <host:port>] [--report <path>]",
+    ),
+    ("list-devices", "list-devices"),
+    ("serve-signaling", "serve-signaling [--bind <addr>]"),
+];
+
+/// Track `publish-file` publishes on by default
+const DEFAULT_FILE_TRACK: &str = "quicrtc-cli/file";
+
+/// Track `bench` publishes on
+const BENCH_TRACK: &str = "quicrtc-cli/bench";
+
+/// Object payload size used by `publish-file` unless overridden
+const DEFAULT_CHUNK_SIZE: usize = 16 * 1024;
+
+/// Address `serve-signaling` listens on unless overridden
+const DEFAULT_SIGNALING_BIND: SocketAddr =
+    SocketAddr::new(std::net::IpAddr::V4(Ipv4Addr::UNSPECIFIED), 8080);
+
+/// Why a command failed
+enum CliError {
+    /// The command line was wrong; usage is printed
+    Usage(String),
+    /// The command ran and failed
+    Failed(String),
+}
+
+impl From<String> for CliError {
+    fn from(reason: String) -> Self {
+        Self::Usage(reason)
+    }
+}
+
+impl From<QuicRtcError> for CliError {
+    fn from(error: QuicRtcError) -> Self {
+        Self::Failed(error.to_string())
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let Some(command) = args.next() else {
+        eprintln!("{}", usage());
+        return ExitCode::FAILURE;
+    };
+    // Keep the console quiet unless RUST_LOG asks otherwise
+    let _ = DebugLogger::init_logging("warn");
+
+    let result = match command.as_str() {
+        "join" => join(args).await,
+        "publish-file" => publish_file(args).await,
+        "probe" => probe(args).await,
+        "bench" => bench(args).await,
+        "load" => load::load(args).await,
+        "list-devices" => list_devices(args),
+        "serve-signaling" => serve_signaling(args).await,
+        "-h" | "--help" | "help" => {
+            println!("{}", usage());
+            return ExitCode::SUCCESS;
+        }
+        _ => {
+            eprintln!("quicrtc-cli: unknown command '{}'\n\n{}", command, usage());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(CliError::Usage(reason)) => {
+            let line = COMMANDS
+                .iter()
+                .find(|(name, _)| *name == command)
+                .map_or("", |(_, line)| line);
+            eprintln!(
+                "quicrtc-cli {}: {}\nusage: quicrtc-cli {}",
+                command, reason, line
+            );
+            ExitCode::FAILURE
+        }
+        Err(CliError::Failed(reason)) => {
+            eprintln!("quicrtc-cli {}: {}", command, reason);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn usage() -> String {
+    let mut usage = String::from("usage: quicrtc-cli <command> [options]\n\ncommands:");
+    for (_, line) in COMMANDS {
+        usage.push_str("\n  ");
+        usage.push_str(line);
+    }
+    usage
+}
+
+/// Join a room and print its events until interrupted
+async fn join(args: impl Iterator<Item = String>) -> Result<(), CliError> {
+    let mut args = Args::parse(args, &["--create", "--camera", "--microphone"])?;
+    let room_id = args.positional("room")?;
+    let participant = args
+        .value("--participant")
+        .unwrap_or_else(|| format!("cli-{}", std::process::id()));
+    let signaling = args.value("--signaling");
+    let create = args.flag("--create");
+    let camera = args.flag("--camera");
+    let microphone = args.flag("--microphone");
+    let duration = args.seconds("--duration")?;
+    let report_path = args.value("--report");
+    let capture = args.value("--capture");
+    args.finish()?;
+
+    let quic_rtc = QuicRtc::init().await?;
+    let mut builder = quic_rtc
+        .room(&room_id)
+        .participant(&participant)
+        .call_report();
+    if let Some(url) = &signaling {
+        builder = builder.signaling_server(url);
+    }
+    if let Some(path) = report_path {
+        builder = builder.call_report_path(path);
+    }
+    if let Some(path) = capture {
+        builder = builder.packet_capture(path);
+    }
+    let mut room = if create {
+        builder.create_and_join().await?
+    } else {
+        builder.join().await?
+    };
+    println!("joined {} as {}", room.id(), room.participant_id());
+    publish_media(&mut room, camera, microphone).await?;
+
+    let mut events = room.events();
+    let started = Instant::now();
+    let deadline = async {
+        match duration {
+            Some(duration) => tokio::time::sleep(duration).await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::pin!(deadline);
+    loop {
+        tokio::select! {
+            event = events.next() => match event {
+                Some(event) => println!("{:>9.3}s  {:?}", started.elapsed().as_secs_f64(), event),
+                None => break,
+            },
+            _ = tokio::signal::ctrl_c() => break,
+            _ = &mut deadline => break,
+        }
+    }
+    drop(events);
+
+    println!("leaving {}", room.id());
+    if let Some(report) = room.leave().await? {
+        let json = serde_json::to_string_pretty(&report)
+            .map_err(|e| CliError::Failed(format!("cannot render call report: {}", e)))?;
+        println!("{}", json);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "media")]
+async fn publish_media(room: &mut Room, camera: bool, microphone: bool) -> Result<(), CliError> {
+    if camera {
+        room.publish_camera().await?;
+        println!("publishing camera");
+    }
+    if microphone {
+        room.publish_microphone().await?;
+        println!("publishing microphone");
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "media"))]
+async fn publish_media(_room: &mut Room, camera: bool, microphone: bool) -> Result<(), CliError> {
+    if camera || microphone {
+        return Err(CliError::Failed(
+            "publishing media needs the `media` feature".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Publish a file's contents as one group of MoQ objects
+async fn publish_file(args: impl Iterator<Item = String>) -> Result<(), CliError> {
+    let mut args = Args::parse(args, &[])?;
+    let relay = args.positional("relay")?;
+    let path = args.positional("file")?;
+    let track = args
+        .value("--track")
+        .unwrap_or_else(|| DEFAULT_FILE_TRACK.to_string());
+    let chunk_size = args
+        .parsed::<usize>("--chunk-size")?
+        .unwrap_or(DEFAULT_CHUNK_SIZE);
+    let rate_kbps = args.parsed::<u64>("--rate")?;
+    let capture = args.value("--capture");
+    args.finish()?;
+    if chunk_size == 0 {
+        return Err(CliError::Usage("--chunk-size must be positive".to_string()));
+    }
+    if rate_kbps == Some(0) {
+        return Err(CliError::Usage("--rate must be positive".to_string()));
+    }
+    let track = TrackNamespace::parse(&track).map_err(QuicRtcError::from)?;
+    let data = std::fs::read(&path)
+        .map_err(|e| CliError::Failed(format!("cannot read {}: {}", path, e)))?;
+
+    let transport = connect(&relay, capture).await?;
+    announce(&transport, &track).await?;
+
+    let started = Instant::now();
+    let mut sent = 0u64;
+    let mut objects = 0u64;
+    for chunk in data.chunks(chunk_size) {
+        if let Some(rate_kbps) = rate_kbps {
+            let due =
+                started + Duration::from_secs_f64(sent as f64 * 8.0 / (rate_kbps * 1000) as f64);
+            tokio::time::sleep_until(due.into()).await;
+        }
+        transport
+            .send_moq_object(object(&track, 0, objects, chunk.to_vec()))
+            .await?;
+        sent += chunk.len() as u64;
+        objects += 1;
+    }
+    transport
+        .send_moq_object(MoqObject::end_of_group(
+            track.clone(),
+            track.track_name.clone(),
+            0,
+            objects,
+        ))
+        .await?;
+    let elapsed = started.elapsed();
+
+    println!(
+        "published {} ({} bytes) as {} objects on {}/{} in {:?} ({:.0} kbps)",
+        path,
+        sent,
+        objects,
+        track.namespace,
+        track.track_name,
+        elapsed,
+        kbps(sent, elapsed)
+    );
+    transport.close().await?;
+    Ok(())
+}
+
+/// Time QUIC and MoQ session setup against a relay
+async fn probe(args: impl Iterator<Item = String>) -> Result<(), CliError> {
+    let mut args = Args::parse(args, &[])?;
+    let relay = args.positional("relay")?;
+    let count = args.parsed::<u32>("--count")?.unwrap_or(3);
+    let timeout = args.seconds("--timeout")?.unwrap_or(Duration::from_secs(5));
+    args.finish()?;
+
+    let address = resolve(&relay).await.map_err(CliError::Failed)?;
+    println!("probing {} ({})", relay, address);
+
+    let mut setups = Vec::new();
+    for attempt in 1..=count {
+        let started = Instant::now();
+        let result = tokio::time::timeout(timeout, async {
+            let transport =
+                MoqOverQuicTransport::new(address, ConnectionConfig::default(), rand::random())
+                    .await?;
+            let connected = started.elapsed();
+            transport.establish_session().await?;
+            Ok::<_, QuicRtcError>((transport, connected))
+        })
+        .await;
+
+        match result {
+            Ok(Ok((transport, connected))) => {
+                let total = started.elapsed();
+                println!(
+                    "  #{}: connected over {:?} in {:?}, MoQ session ready after {:?}",
+                    attempt,
+                    transport.transport_mode(),
+                    connected,
+                    total
+                );
+                setups.push(total);
+                let _ = transport.close().await;
+            }
+            Ok(Err(e)) => println!("  #{}: failed: {}", attempt, e),
+            Err(_) => println!("  #{}: timed out after {:?}", attempt, timeout),
+        }
+    }
+
+    println!("{}/{} attempts succeeded", setups.len(), count);
+    if setups.is_empty() {
+        return Err(CliError::Failed(format!("{} is unreachable", relay)));
+    }
+    let mean = setups.iter().sum::<Duration>() / setups.len() as u32;
+    println!(
+        "session setup: min {:?}, mean {:?}, max {:?}",
+        setups.iter().min().copied().unwrap_or_default(),
+        mean,
+        setups.iter().max().copied().unwrap_or_default()
+    );
+    Ok(())
+}
+
+/// Publish synthetic objects to a relay as fast as it accepts them
+async fn bench(args: impl Iterator<Item = String>) -> Result<(), CliError> {
+    let mut args = Args::parse(args, &[])?;
+    let relay = args.positional("relay")?;
+    let duration = args
+        .seconds("--duration")?
+        .unwrap_or(Duration::from_secs(10));
+    let object_size = args.parsed::<usize>("--object-size")?.unwrap_or(1200);
+    let group_size = args.parsed::<u64>("--group-size")?.unwrap_or(30);
+    let capture = args.value("--capture");
+    args.finish()?;
+    if group_size == 0 {
+        return Err(CliError::Usage("--group-size must be positive".to_string()));
+    }
+    let track = TrackNamespace::parse(BENCH_TRACK).map_err(QuicRtcError::from)?;
+
+    let transport = connect(&relay, capture).await?;
+    announce(&transport, &track).await?;
+    println!(
+        "sending {}-byte objects to {} for {:?}",
+        object_size, relay, duration
+    );
+
+    let payload = vec![0u8; object_size];
+    let mut send_times = Vec::new();
+    let started = Instant::now();
+    while started.elapsed() < duration {
+        let sequence = send_times.len() as u64;
+        let object = object(
+            &track,
+            sequence / group_size,
+            sequence % group_size,
+            payload.clone(),
+        );
+        let send_started = Instant::now();
+        transport.send_moq_object(object).await?;
+        send_times.push(send_started.elapsed());
+    }
+    let elapsed = started.elapsed();
+    transport.close().await?;
+
+    let objects = send_times.len();
+    let bytes = (objects * object_size) as u64;
+    send_times.sort();
+    let percentile = |p: f64| {
+        send_times
+            .get(((objects as f64 * p) as usize).min(objects.saturating_sub(1)))
+            .copied()
+            .unwrap_or_default()
+    };
+    println!("sent {} objects, {} bytes in {:?}", objects, bytes, elapsed);
+    println!(
+        "throughput: {:.0} objects/s, {:.0} kbps",
+        objects as f64 / elapsed.as_secs_f64(),
+        kbps(bytes, elapsed)
+    );
+    println!(
+        "send time: p50 {:?}, p99 {:?}, max {:?}",
+        percentile(0.5),
+        percentile(0.99),
+        send_times.last().copied().unwrap_or_default()
+    );
+    Ok(())
+}
+
+/// Print the cameras, microphones and speakers media capture can use
+#[cfg(feature = "media")]
+fn list_devices(args: impl Iterator<Item = String>) -> Result<(), CliError> {
+    use quicrtc_media::{AudioRenderer, CpalAudioCapture, CpalAudioRenderer, VideoCaptureManager};
+
+    Args::parse(args, &[])?.finish()?;
+    let default_marker = |is_default: bool| if is_default { "*" } else { " " };
+
+    println!("Cameras:");
+    match VideoCaptureManager::new().and_then(|manager| manager.enumerate_devices()) {
+        Ok(devices) if devices.is_empty() => println!("    (none)"),
+        Ok(devices) => {
+            for device in devices {
+                println!(
+                    "    {}  {} (up to {} fps)",
+                    device.id, device.name, device.max_framerate
+                );
+            }
+        }
+        Err(e) => println!("    unavailable: {}", e),
+    }
+
+    println!("Microphones:");
+    match CpalAudioCapture::new().list_devices() {
+        Ok(devices) if devices.is_empty() => println!("    (none)"),
+        Ok(devices) => {
+            for device in devices {
+                println!("  {} {}", default_marker(device.is_default), device.name);
+            }
+        }
+        Err(e) => println!("    unavailable: {}", e),
+    }
+
+    println!("Speakers:");
+    match CpalAudioRenderer::new().list_devices() {
+        Ok(devices) if devices.is_empty() => println!("    (none)"),
+        Ok(devices) => {
+            for device in devices {
+                println!("  {} {}", default_marker(device.is_default), device.name);
+            }
+        }
+        Err(e) => println!("    unavailable: {}", e),
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "media"))]
+fn list_devices(args: impl Iterator<Item = String>) -> Result<(), CliError> {
+    Args::parse(args, &[])?.finish()?;
+    Err(CliError::Failed(
+        "listing devices needs the `media` feature".to_string(),
+    ))
+}
+
+/// Run a signaling server until interrupted
+async fn serve_signaling(args: impl Iterator<Item = String>) -> Result<(), CliError> {
+    let mut args = Args::parse(args, &[])?;
+    let bind = args
+        .parsed::<SocketAddr>("--bind")?
+        .unwrap_or(DEFAULT_SIGNALING_BIND);
+    args.finish()?;
+
+    let server = SignalingServer::new(bind);
+    println!("signaling server listening on ws://{}", bind);
+    tokio::select! {
+        result = server.start() => result?,
+        _ = tokio::signal::ctrl_c() => {}
+    }
+    server.stop().await?;
+    println!("signaling server stopped");
+    Ok(())
+}
+
+/// Connect to a relay and set up a MoQ session
+async fn connect(relay: &str, capture: Option<String>) -> Result<MoqOverQuicTransport, CliError> {
+    let address = resolve(relay).await.map_err(CliError::Failed)?;
+    let mut config = ConnectionConfig::default();
+    if let Some(path) = capture {
+        config.packet_capture = Some(PacketCapture::create(PacketCaptureConfig::new(path))?);
+    }
+    let transport = MoqOverQuicTransport::new(address, config, rand::random()).await?;
+    transport.establish_session().await?;
+    println!(
+        "connected to {} ({}) over {:?}",
+        relay,
+        address,
+        transport.transport_mode()
+    );
+    Ok(transport)
+}
+
+async fn announce(
+    transport: &MoqOverQuicTransport,
+    track: &TrackNamespace,
+) -> Result<(), CliError> {
+    transport
+        .announce_track(MoqTrack::new(
+            track.clone(),
+            track.track_name.clone(),
+            MoqTrackType::Data,
+        ))
+        .await?;
+    Ok(())
+}
+
+fn object(track: &TrackNamespace, group_id: u64, object_id: u64, payload: Vec<u8>) -> MoqObject {
+    MoqObject {
+        track_namespace: track.clone(),
+        track_name: track.track_name.clone(),
+        group_id,
+        object_id,
+        publisher_priority: 128,
+        size: payload.len(),
+        payload,
+        object_status: MoqObjectStatus::Normal,
+        created_at: Instant::now(),
+        metadata: Default::default(),
+    }
+}
+
+fn kbps(bytes: u64, elapsed: Duration) -> f64 {
+    bytes as f64 * 8.0 / 1000.0 / elapsed.as_secs_f64().max(f64::EPSILON)
+}
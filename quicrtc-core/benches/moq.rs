@@ -0,0 +1,93 @@
+//! MoQ object delivery and cache benchmarks
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use quicrtc_core::{
+    H264Frame, MoqCacheConfig, MoqObject, MoqObjectCache, MoqObjectDelivery, TrackNamespace,
+};
+
+const OBJECTS: u64 = 256;
+
+fn namespace() -> TrackNamespace {
+    TrackNamespace {
+        namespace: "bench.example.com".to_string(),
+        track_name: "alice/camera".to_string(),
+    }
+}
+
+fn video_objects(count: u64) -> Vec<MoqObject> {
+    (0..count)
+        .map(|sequence_number| {
+            MoqObject::from_h264_frame(
+                namespace(),
+                H264Frame {
+                    nal_units: vec![0u8; 1200],
+                    is_keyframe: sequence_number % 30 == 0,
+                    timestamp_us: sequence_number * 33_333,
+                    sequence_number,
+                },
+            )
+        })
+        .collect()
+}
+
+fn bench_delivery_queue(c: &mut Criterion) {
+    let objects = video_objects(OBJECTS);
+    let mut group = c.benchmark_group("moq_delivery");
+    group.throughput(Throughput::Elements(OBJECTS));
+
+    group.bench_function("enqueue_dequeue", |b| {
+        b.iter_batched(
+            || objects.clone(),
+            |objects| {
+                let mut delivery = MoqObjectDelivery::new(MoqCacheConfig::default());
+                for object in objects {
+                    delivery.enqueue_object(object).unwrap();
+                }
+                while let Some(object) = delivery.dequeue_object() {
+                    black_box(object);
+                }
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.finish();
+}
+
+fn bench_object_cache(c: &mut Criterion) {
+    let objects = video_objects(OBJECTS);
+    let track = namespace();
+    let mut group = c.benchmark_group("moq_cache");
+    group.throughput(Throughput::Elements(OBJECTS));
+
+    group.bench_function("store", |b| {
+        b.iter_batched(
+            || objects.clone(),
+            |objects| {
+                let mut cache = MoqObjectCache::new(MoqCacheConfig::default());
+                for object in objects {
+                    cache.store_object(object).unwrap();
+                }
+                cache
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    let mut cache = MoqObjectCache::new(MoqCacheConfig::default());
+    for object in objects.iter().cloned() {
+        cache.store_object(object).unwrap();
+    }
+    group.bench_function("get", |b| {
+        b.iter(|| {
+            for object_id in 0..OBJECTS {
+                black_box(cache.get_object(&track, object_id));
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_delivery_queue, bench_object_cache);
+criterion_main!(benches);
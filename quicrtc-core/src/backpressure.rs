@@ -0,0 +1,163 @@
+//! Backpressure from the send path to encoders
+//!
+//! Rate adaptation looks at the connection on a fixed tick, so after a
+//! sudden drop in capacity encoders keep producing at the old bitrate until
+//! the next one. In the meantime objects pile up in the send queue, miss
+//! their latency budget and get dropped. The send path reports each of these
+//! incidents to a [`BackpressureSignal`]; once they keep coming, subscribers
+//! receive a [`Backpressure`] and can cut their bitrate straight away.
+
+use crate::moq::TrackNamespace;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+/// Signals buffered per subscriber; a lagging subscriber only misses stale ones
+const SIGNAL_CHANNEL_CAPACITY: usize = 16;
+
+/// Send path incident that points at congestion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BackpressureReason {
+    /// A track's send queue filled up to its high-water mark
+    SendQueueHighWater,
+    /// A track's send queue was full, so an object was dropped
+    SendQueueOverflow,
+    /// An object missed its track's latency budget and was dropped, or its
+    /// stream reset mid-write
+    LatencyBudgetExceeded,
+    /// Opening a stream had to wait for stream credit
+    StreamCreditWait,
+}
+
+/// Sustained congestion on the send path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backpressure {
+    /// Most recent incident
+    pub reason: BackpressureReason,
+    /// Track of the most recent incident, `None` when it concerned the
+    /// whole connection
+    pub track_namespace: Option<TrackNamespace>,
+    /// Incidents within [`BackpressureConfig::window`]
+    pub incidents: usize,
+}
+
+/// When incidents on the send path count as sustained congestion
+#[derive(Debug, Clone)]
+pub struct BackpressureConfig {
+    /// How far back incidents are counted
+    pub window: Duration,
+    /// Incidents within `window` that make congestion sustained
+    pub min_incidents: usize,
+    /// Minimum time between two signals, so encoders see the effect of one
+    /// reduction before being asked for the next
+    pub cooldown: Duration,
+}
+
+impl Default for BackpressureConfig {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(1),
+            min_incidents: 3,
+            cooldown: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Collects send path incidents and signals sustained congestion to
+/// subscribers
+///
+/// A single incident, such as one object dropped after a loss burst, is not
+/// signalled; [`BackpressureConfig::min_incidents`] within the window are.
+/// Clones share the same incidents and subscribers.
+#[derive(Debug, Clone)]
+pub struct BackpressureSignal {
+    state: Arc<Mutex<BackpressureState>>,
+    sender: broadcast::Sender<Backpressure>,
+}
+
+/// Incidents counted towards the next signal
+#[derive(Debug)]
+struct BackpressureState {
+    config: BackpressureConfig,
+    incidents: VecDeque<Instant>,
+    last_signal: Option<Instant>,
+    signals_sent: u64,
+}
+
+impl BackpressureSignal {
+    /// Create a signal detecting sustained congestion by `config`
+    pub fn new(config: BackpressureConfig) -> Self {
+        let (sender, _) = broadcast::channel(SIGNAL_CHANNEL_CAPACITY);
+        Self {
+            state: Arc::new(Mutex::new(BackpressureState {
+                config,
+                incidents: VecDeque::new(),
+                last_signal: None,
+                signals_sent: 0,
+            })),
+            sender,
+        }
+    }
+
+    /// Receive a [`Backpressure`] whenever congestion turns out sustained
+    pub fn subscribe(&self) -> broadcast::Receiver<Backpressure> {
+        self.sender.subscribe()
+    }
+
+    /// Report an incident, of `track_namespace` if it concerned one track
+    ///
+    /// Returns whether this incident made congestion sustained and was
+    /// signalled to subscribers.
+    pub fn report(
+        &self,
+        reason: BackpressureReason,
+        track_namespace: Option<&TrackNamespace>,
+    ) -> bool {
+        let now = Instant::now();
+        let incidents = {
+            let mut state = self.state.lock();
+            let window = state.config.window;
+            while state
+                .incidents
+                .front()
+                .is_some_and(|&at| now.duration_since(at) > window)
+            {
+                state.incidents.pop_front();
+            }
+            state.incidents.push_back(now);
+
+            let cooled_down = state
+                .last_signal
+                .is_none_or(|at| now.duration_since(at) >= state.config.cooldown);
+            if state.incidents.len() < state.config.min_incidents || !cooled_down {
+                return false;
+            }
+            let incidents = state.incidents.len();
+            state.incidents.clear();
+            state.last_signal = Some(now);
+            state.signals_sent += 1;
+            incidents
+        };
+
+        // No subscribers is fine, nobody is encoding
+        let _ = self.sender.send(Backpressure {
+            reason,
+            track_namespace: track_namespace.cloned(),
+            incidents,
+        });
+        true
+    }
+
+    /// Signals sent so far
+    pub fn signals_sent(&self) -> u64 {
+        self.state.lock().signals_sent
+    }
+}
+
+impl Default for BackpressureSignal {
+    fn default() -> Self {
+        Self::new(BackpressureConfig::default())
+    }
+}
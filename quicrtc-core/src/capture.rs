@@ -0,0 +1,507 @@
+//! Packet metadata capture
+//!
+//! Records per-packet metadata — timestamps, sizes, ECN marks, stream
+//! openings and loss signals, never payloads — into a compact binary file,
+//! for networks where running tcpdump is impossible. Enable it with
+//! [`ConnectionConfig::packet_capture`](crate::ConnectionConfig::packet_capture)
+//! and read files back with [`PacketCaptureReader`] or the
+//! `quicrtc-capture` analyzer shipped with `quicrtc-diagnostics`.
+//!
+//! The file is a 16-byte header (magic, version, capture start time) followed
+//! by fixed-size 24-byte records.
+
+use crate::QuicRtcError;
+use parking_lot::Mutex;
+use quinn::udp::{EcnCodepoint, RecvMeta, Transmit};
+use quinn::{AsyncUdpSocket, UdpPoller};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, IoSliceMut, Read, Write};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant, SystemTime};
+use tracing::warn;
+
+/// File magic of packet capture files
+const MAGIC: &[u8; 4] = b"QRPM";
+
+/// Capture file format version
+const VERSION: u8 = 1;
+
+const HEADER_LEN: usize = 16;
+const RECORD_LEN: usize = 24;
+
+const KIND_SENT: u8 = 1;
+const KIND_RECEIVED: u8 = 2;
+const KIND_STREAM_OPENED: u8 = 3;
+const KIND_LOSS: u8 = 4;
+
+const FLAG_BIDIRECTIONAL: u8 = 0b01;
+const FLAG_LOCAL: u8 = 0b10;
+
+/// How often connection loss statistics are sampled into the capture
+const LOSS_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Packet capture configuration
+#[derive(Debug, Clone)]
+pub struct PacketCaptureConfig {
+    /// File to write; replaced if it exists
+    pub path: PathBuf,
+    /// Stop recording once the file reaches this size
+    pub max_bytes: u64,
+}
+
+impl PacketCaptureConfig {
+    /// Capture into `path` with the default 64 MiB size limit
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
+
+/// ECN codepoint carried by a packet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EcnMark {
+    /// ECN-capable transport, ECT(0)
+    Ect0,
+    /// ECN-capable transport, ECT(1)
+    Ect1,
+    /// Congestion experienced
+    Ce,
+}
+
+impl EcnMark {
+    fn from_codepoint(codepoint: Option<EcnCodepoint>) -> Option<Self> {
+        codepoint.map(|codepoint| match codepoint {
+            EcnCodepoint::Ect0 => Self::Ect0,
+            EcnCodepoint::Ect1 => Self::Ect1,
+            EcnCodepoint::Ce => Self::Ce,
+        })
+    }
+
+    fn to_bits(mark: Option<Self>) -> u8 {
+        match mark {
+            None => 0,
+            Some(Self::Ect0) => 1,
+            Some(Self::Ect1) => 2,
+            Some(Self::Ce) => 3,
+        }
+    }
+
+    fn from_bits(bits: u8) -> Option<Self> {
+        match bits & 0b11 {
+            1 => Some(Self::Ect0),
+            2 => Some(Self::Ect1),
+            3 => Some(Self::Ce),
+            _ => None,
+        }
+    }
+}
+
+/// What a capture record describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketEvent {
+    /// UDP datagrams handed to the socket
+    Sent {
+        /// Total bytes, across segments
+        bytes: u32,
+        /// Datagrams in a segmented (GSO) send
+        segments: u16,
+        /// ECN codepoint set on the datagrams
+        ecn: Option<EcnMark>,
+    },
+    /// UDP datagrams read from the socket
+    Received {
+        /// Total bytes, across segments
+        bytes: u32,
+        /// Datagrams coalesced by the socket (GRO)
+        segments: u16,
+        /// ECN codepoint the datagrams arrived with
+        ecn: Option<EcnMark>,
+    },
+    /// A QUIC stream was opened
+    StreamOpened {
+        /// Stream index
+        stream_id: u64,
+        /// Whether the stream is bidirectional
+        bidirectional: bool,
+        /// Whether we opened it, rather than the peer
+        local: bool,
+    },
+    /// Loss statistics since the previous sample
+    Loss {
+        /// Packets declared lost
+        lost_packets: u32,
+        /// Congestion events
+        congestion_events: u16,
+        /// Smoothed round-trip time
+        rtt: Duration,
+    },
+}
+
+/// One entry of a packet capture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PacketRecord {
+    /// Time since the capture started
+    pub at: Duration,
+    /// What happened
+    pub event: PacketEvent,
+}
+
+impl PacketRecord {
+    fn encode(&self) -> [u8; RECORD_LEN] {
+        let (kind, flags, small, medium, large) = match self.event {
+            PacketEvent::Sent {
+                bytes,
+                segments,
+                ecn,
+            } => (KIND_SENT, EcnMark::to_bits(ecn), segments, bytes, 0),
+            PacketEvent::Received {
+                bytes,
+                segments,
+                ecn,
+            } => (KIND_RECEIVED, EcnMark::to_bits(ecn), segments, bytes, 0),
+            PacketEvent::StreamOpened {
+                stream_id,
+                bidirectional,
+                local,
+            } => {
+                let mut flags = 0;
+                if bidirectional {
+                    flags |= FLAG_BIDIRECTIONAL;
+                }
+                if local {
+                    flags |= FLAG_LOCAL;
+                }
+                (KIND_STREAM_OPENED, flags, 0, 0, stream_id)
+            }
+            PacketEvent::Loss {
+                lost_packets,
+                congestion_events,
+                rtt,
+            } => (
+                KIND_LOSS,
+                0,
+                congestion_events,
+                lost_packets,
+                rtt.as_micros() as u64,
+            ),
+        };
+
+        let mut record = [0u8; RECORD_LEN];
+        record[0..8].copy_from_slice(&(self.at.as_micros() as u64).to_le_bytes());
+        record[8] = kind;
+        record[9] = flags;
+        record[10..12].copy_from_slice(&small.to_le_bytes());
+        record[12..16].copy_from_slice(&medium.to_le_bytes());
+        record[16..24].copy_from_slice(&large.to_le_bytes());
+        record
+    }
+
+    fn decode(record: &[u8; RECORD_LEN]) -> Result<Self, QuicRtcError> {
+        let at = Duration::from_micros(u64::from_le_bytes(record[0..8].try_into().unwrap()));
+        let flags = record[9];
+        let small = u16::from_le_bytes(record[10..12].try_into().unwrap());
+        let medium = u32::from_le_bytes(record[12..16].try_into().unwrap());
+        let large = u64::from_le_bytes(record[16..24].try_into().unwrap());
+
+        let event = match record[8] {
+            KIND_SENT => PacketEvent::Sent {
+                bytes: medium,
+                segments: small,
+                ecn: EcnMark::from_bits(flags),
+            },
+            KIND_RECEIVED => PacketEvent::Received {
+                bytes: medium,
+                segments: small,
+                ecn: EcnMark::from_bits(flags),
+            },
+            KIND_STREAM_OPENED => PacketEvent::StreamOpened {
+                stream_id: large,
+                bidirectional: flags & FLAG_BIDIRECTIONAL != 0,
+                local: flags & FLAG_LOCAL != 0,
+            },
+            KIND_LOSS => PacketEvent::Loss {
+                lost_packets: medium,
+                congestion_events: small,
+                rtt: Duration::from_micros(large),
+            },
+            kind => {
+                return Err(QuicRtcError::InvalidData {
+                    reason: format!("Unknown packet capture record kind {}", kind),
+                })
+            }
+        };
+        Ok(Self { at, event })
+    }
+}
+
+/// Packet capture counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PacketCaptureStats {
+    /// Records written
+    pub records: u64,
+    /// Records dropped because the file reached its size limit
+    pub dropped_records: u64,
+    /// Bytes written, including the header
+    pub bytes_written: u64,
+}
+
+/// Handle to a packet capture file being written
+///
+/// Cloning is cheap; clones write to the same file.
+#[derive(Debug, Clone)]
+pub struct PacketCapture {
+    inner: Arc<CaptureInner>,
+}
+
+#[derive(Debug)]
+struct CaptureInner {
+    path: PathBuf,
+    max_bytes: u64,
+    started: Instant,
+    /// `None` once writing failed
+    writer: Mutex<Option<BufWriter<File>>>,
+    records: AtomicU64,
+    dropped_records: AtomicU64,
+    bytes_written: AtomicU64,
+}
+
+impl PacketCapture {
+    /// Create the capture file and write its header
+    pub fn create(config: PacketCaptureConfig) -> Result<Self, QuicRtcError> {
+        let io_error = |e: std::io::Error| QuicRtcError::InvalidOperation {
+            operation: format!("create packet capture {}: {}", config.path.display(), e),
+        };
+        let mut writer = BufWriter::new(File::create(&config.path).map_err(io_error)?);
+
+        let started_at = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        let mut header = [0u8; HEADER_LEN];
+        header[0..4].copy_from_slice(MAGIC);
+        header[4] = VERSION;
+        header[8..16].copy_from_slice(&(started_at.as_micros() as u64).to_le_bytes());
+        writer.write_all(&header).map_err(io_error)?;
+
+        Ok(Self {
+            inner: Arc::new(CaptureInner {
+                path: config.path,
+                max_bytes: config.max_bytes,
+                started: Instant::now(),
+                writer: Mutex::new(Some(writer)),
+                records: AtomicU64::new(0),
+                dropped_records: AtomicU64::new(0),
+                bytes_written: AtomicU64::new(HEADER_LEN as u64),
+            }),
+        })
+    }
+
+    /// Append a record stamped with the current time
+    pub fn record(&self, event: PacketEvent) {
+        let record = PacketRecord {
+            at: self.inner.started.elapsed(),
+            event,
+        };
+        let inner = &self.inner;
+        let mut writer = inner.writer.lock();
+        let Some(file) = writer.as_mut() else {
+            return;
+        };
+        let written = inner.bytes_written.load(Ordering::Relaxed);
+        if written + RECORD_LEN as u64 > inner.max_bytes {
+            inner.dropped_records.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        if let Err(e) = file.write_all(&record.encode()) {
+            warn!("Stopping packet capture {}: {}", inner.path.display(), e);
+            *writer = None;
+            return;
+        }
+        inner.records.fetch_add(1, Ordering::Relaxed);
+        inner
+            .bytes_written
+            .store(written + RECORD_LEN as u64, Ordering::Relaxed);
+    }
+
+    /// Write buffered records to disk
+    pub fn flush(&self) {
+        if let Some(file) = self.inner.writer.lock().as_mut() {
+            let _ = file.flush();
+        }
+    }
+
+    /// File the capture is written to
+    pub fn path(&self) -> &Path {
+        &self.inner.path
+    }
+
+    /// Current counters
+    pub fn stats(&self) -> PacketCaptureStats {
+        PacketCaptureStats {
+            records: self.inner.records.load(Ordering::Relaxed),
+            dropped_records: self.inner.dropped_records.load(Ordering::Relaxed),
+            bytes_written: self.inner.bytes_written.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Reads the records of a packet capture file
+///
+/// A record cut short at the end of the file, as left by a crash, ends
+/// iteration without an error.
+#[derive(Debug)]
+pub struct PacketCaptureReader {
+    reader: BufReader<File>,
+    started_at: SystemTime,
+}
+
+impl PacketCaptureReader {
+    /// Open a capture file and check its header
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, QuicRtcError> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| QuicRtcError::InvalidOperation {
+            operation: format!("open packet capture {}: {}", path.display(), e),
+        })?;
+        let mut reader = BufReader::new(file);
+
+        let mut header = [0u8; HEADER_LEN];
+        reader
+            .read_exact(&mut header)
+            .map_err(|_| QuicRtcError::InvalidData {
+                reason: format!("{} is too short for a packet capture", path.display()),
+            })?;
+        if &header[0..4] != MAGIC {
+            return Err(QuicRtcError::InvalidData {
+                reason: format!("{} is not a packet capture", path.display()),
+            });
+        }
+        if header[4] != VERSION {
+            return Err(QuicRtcError::InvalidData {
+                reason: format!("Unsupported packet capture version {}", header[4]),
+            });
+        }
+        let started_micros = u64::from_le_bytes(header[8..16].try_into().unwrap());
+
+        Ok(Self {
+            reader,
+            started_at: SystemTime::UNIX_EPOCH + Duration::from_micros(started_micros),
+        })
+    }
+
+    /// When the capture started
+    pub fn started_at(&self) -> SystemTime {
+        self.started_at
+    }
+}
+
+impl Iterator for PacketCaptureReader {
+    type Item = Result<PacketRecord, QuicRtcError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut record = [0u8; RECORD_LEN];
+        self.reader.read_exact(&mut record).ok()?;
+        Some(PacketRecord::decode(&record))
+    }
+}
+
+/// Wrap `socket` so every datagram it carries is recorded into `capture`
+pub(crate) fn capturing_socket(
+    socket: Arc<dyn AsyncUdpSocket>,
+    capture: PacketCapture,
+) -> Arc<dyn AsyncUdpSocket> {
+    Arc::new(CapturingSocket { socket, capture })
+}
+
+/// Record the connection's loss statistics every second until it closes
+pub(crate) fn spawn_loss_sampler(connection: quinn::Connection, capture: PacketCapture) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(LOSS_SAMPLE_INTERVAL);
+        let mut last = connection.stats().path;
+        loop {
+            interval.tick().await;
+            if connection.close_reason().is_some() {
+                break;
+            }
+            let path = connection.stats().path;
+            capture.record(PacketEvent::Loss {
+                lost_packets: path.lost_packets.saturating_sub(last.lost_packets) as u32,
+                congestion_events: path
+                    .congestion_events
+                    .saturating_sub(last.congestion_events)
+                    as u16,
+                rtt: path.rtt,
+            });
+            capture.flush();
+            last = path;
+        }
+        capture.flush();
+    });
+}
+
+/// UDP socket recording the metadata of every datagram it carries
+#[derive(Debug)]
+struct CapturingSocket {
+    socket: Arc<dyn AsyncUdpSocket>,
+    capture: PacketCapture,
+}
+
+impl AsyncUdpSocket for CapturingSocket {
+    fn create_io_poller(self: Arc<Self>) -> Pin<Box<dyn UdpPoller>> {
+        self.socket.clone().create_io_poller()
+    }
+
+    fn try_send(&self, transmit: &Transmit) -> std::io::Result<()> {
+        self.socket.try_send(transmit)?;
+        let segments = match transmit.segment_size {
+            Some(size) if size > 0 => transmit.contents.len().div_ceil(size),
+            _ => 1,
+        };
+        self.capture.record(PacketEvent::Sent {
+            bytes: transmit.contents.len() as u32,
+            segments: segments as u16,
+            ecn: EcnMark::from_codepoint(transmit.ecn),
+        });
+        Ok(())
+    }
+
+    fn poll_recv(
+        &self,
+        cx: &mut Context,
+        bufs: &mut [IoSliceMut<'_>],
+        meta: &mut [RecvMeta],
+    ) -> Poll<std::io::Result<usize>> {
+        let poll = self.socket.poll_recv(cx, bufs, meta);
+        if let Poll::Ready(Ok(count)) = &poll {
+            for meta in &meta[..*count] {
+                self.capture.record(PacketEvent::Received {
+                    bytes: meta.len as u32,
+                    segments: meta.len.div_ceil(meta.stride.max(1)) as u16,
+                    ecn: EcnMark::from_codepoint(meta.ecn),
+                });
+            }
+        }
+        poll
+    }
+
+    fn local_addr(&self) -> std::io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    fn max_transmit_segments(&self) -> usize {
+        self.socket.max_transmit_segments()
+    }
+
+    fn max_receive_segments(&self) -> usize {
+        self.socket.max_receive_segments()
+    }
+
+    fn may_fragment(&self) -> bool {
+        self.socket.may_fragment()
+    }
+}
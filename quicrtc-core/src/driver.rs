@@ -0,0 +1,324 @@
+//! Pluggable QUIC stacks for the MoQ layer
+//!
+//! The MoQ session and stream manager only need to open streams, send
+//! datagrams and hear about incoming traffic. [`MoqTransportDriver`] captures
+//! exactly that, so [`crate::MoqOverQuicTransport`] can run over the built-in
+//! [`QuinnDriver`] or over quiche, s2n-quic or an in-memory mock supplied by
+//! the integrator.
+
+use crate::capture::{self, PacketCapture, PacketEvent};
+use crate::error::QuicRtcError;
+use crate::moq::{MoqTrackType, StreamId};
+use crate::qos::DscpMarker;
+use crate::transport::{QuicStream, StreamType, TransportConnection, TransportMode};
+use async_trait::async_trait;
+use bytes::Bytes;
+use parking_lot::Mutex;
+use tokio::sync::mpsc;
+use tracing::debug;
+use uuid::Uuid;
+
+/// A single stream opened by a [`MoqTransportDriver`]
+#[async_trait]
+pub trait DriverStream: Send + Sync + std::fmt::Debug {
+    /// Stream ID, unique within the connection
+    fn id(&self) -> StreamId;
+
+    /// Whether the stream is bidirectional or unidirectional
+    fn stream_type(&self) -> StreamType;
+
+    /// Write all of `data` to the stream
+    async fn send(&mut self, data: &[u8]) -> Result<(), QuicRtcError>;
+
+    /// Read the next chunk; `None` once the peer has finished the stream
+    async fn recv(&mut self) -> Result<Option<Bytes>, QuicRtcError>;
+
+    /// Finish the sending side of the stream
+    async fn finish(&mut self) -> Result<(), QuicRtcError>;
+
+    /// Abandon the sending side of the stream, telling the peer to discard
+    /// what it has not read yet, with an application `error_code`
+    async fn reset(&mut self, error_code: u32) -> Result<(), QuicRtcError>;
+}
+
+/// Incoming traffic reported by a [`MoqTransportDriver`]
+#[derive(Debug)]
+pub enum DriverEvent {
+    /// Peer opened a stream
+    StreamOpened(Box<dyn DriverStream>),
+    /// Peer sent a datagram
+    DatagramReceived(Bytes),
+    /// Connection closed; no further events follow
+    Closed {
+        /// Close reason
+        reason: String,
+    },
+}
+
+/// QUIC stack used by the MoQ layer
+///
+/// Implement this to run MoQ over a QUIC library other than quinn. Every
+/// method takes `&self`; drivers handle their own synchronization.
+#[async_trait]
+pub trait MoqTransportDriver: Send + Sync + std::fmt::Debug {
+    /// Open a new outgoing stream
+    async fn open_stream(
+        &self,
+        stream_type: StreamType,
+    ) -> Result<Box<dyn DriverStream>, QuicRtcError>;
+
+    /// Send an unreliable datagram
+    async fn send_datagram(&self, data: Bytes) -> Result<(), QuicRtcError>;
+
+    /// Largest datagram that currently fits the path, if known
+    ///
+    /// Objects sent as datagrams are fragmented to fit. The default of
+    /// `None` sends every object whole.
+    fn max_datagram_size(&self) -> Option<usize> {
+        None
+    }
+
+    /// Take the receiver for incoming streams and datagrams (can only be taken once)
+    fn take_events(&self) -> Option<mpsc::UnboundedReceiver<DriverEvent>>;
+
+    /// Connection ID for tracking
+    fn connection_id(&self) -> Uuid;
+
+    /// Transport mode in use
+    fn transport_mode(&self) -> TransportMode {
+        TransportMode::QuicNative
+    }
+
+    /// Check if the connection is still alive
+    fn is_connected(&self) -> bool;
+
+    /// Close the connection gracefully
+    async fn close(&self) -> Result<(), QuicRtcError>;
+
+    /// A track of `track_type` is now published over this connection
+    ///
+    /// Drivers that mark packets by media class, see [`crate::qos`], use
+    /// this to pick the class; the default does nothing.
+    fn track_published(&self, _track_type: MoqTrackType) {}
+}
+
+#[async_trait]
+impl DriverStream for QuicStream {
+    fn id(&self) -> StreamId {
+        self.id
+    }
+
+    fn stream_type(&self) -> StreamType {
+        self.stream_type
+    }
+
+    async fn send(&mut self, data: &[u8]) -> Result<(), QuicRtcError> {
+        QuicStream::send(self, data).await
+    }
+
+    async fn recv(&mut self) -> Result<Option<Bytes>, QuicRtcError> {
+        QuicStream::recv(self).await
+    }
+
+    async fn finish(&mut self) -> Result<(), QuicRtcError> {
+        QuicStream::finish(self).await
+    }
+
+    async fn reset(&mut self, error_code: u32) -> Result<(), QuicRtcError> {
+        QuicStream::reset(self, error_code)
+    }
+}
+
+/// Built-in driver backed by quinn, including the WebSocket fallback modes
+#[derive(Debug)]
+pub struct QuinnDriver {
+    /// Underlying connection
+    connection: tokio::sync::Mutex<TransportConnection>,
+    /// Native QUIC connection, absent in fallback modes
+    quinn: Option<quinn::Connection>,
+    connection_id: Uuid,
+    mode: TransportMode,
+    dscp_marker: Option<DscpMarker>,
+    events: Mutex<Option<mpsc::UnboundedReceiver<DriverEvent>>>,
+}
+
+impl QuinnDriver {
+    /// Wrap an established connection
+    ///
+    /// Incoming streams and datagrams are accepted on background tasks, so
+    /// this must be called from within a Tokio runtime.
+    pub fn new(connection: TransportConnection) -> Self {
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        let quinn = connection.quinn_connection();
+        let capture = connection.packet_capture().cloned();
+        if let Some(quinn) = &quinn {
+            if let Some(capture) = &capture {
+                capture::spawn_loss_sampler(quinn.clone(), capture.clone());
+            }
+            Self::spawn_accept_loops(quinn.clone(), event_tx, capture);
+        }
+
+        Self {
+            connection_id: connection.connection_id(),
+            mode: connection.current_transport_mode(),
+            dscp_marker: connection.dscp_marker().cloned(),
+            connection: tokio::sync::Mutex::new(connection),
+            quinn,
+            events: Mutex::new(Some(event_rx)),
+        }
+    }
+
+    /// Forward streams and datagrams opened by the peer as driver events
+    fn spawn_accept_loops(
+        connection: quinn::Connection,
+        event_tx: mpsc::UnboundedSender<DriverEvent>,
+        capture: Option<PacketCapture>,
+    ) {
+        // The peer opens the control stream of a session it starts
+        let bi_connection = connection.clone();
+        let bi_tx = event_tx.clone();
+        let bi_capture = capture.clone();
+        tokio::spawn(async move {
+            // Closing is reported by the unidirectional loop
+            while let Ok((send, recv)) = bi_connection.accept_bi().await {
+                if let Some(capture) = &bi_capture {
+                    capture.record(PacketEvent::StreamOpened {
+                        stream_id: recv.id().index(),
+                        bidirectional: true,
+                        local: false,
+                    });
+                }
+                let stream = QuicStream {
+                    id: peer_stream_id(recv.id()),
+                    stream_type: StreamType::Bidirectional,
+                    send: Some(send),
+                    recv: Some(recv),
+                };
+                if bi_tx
+                    .send(DriverEvent::StreamOpened(Box::new(stream)))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        let streams_connection = connection.clone();
+        let streams_tx = event_tx.clone();
+        tokio::spawn(async move {
+            loop {
+                match streams_connection.accept_uni().await {
+                    Ok(recv) => {
+                        if let Some(capture) = &capture {
+                            capture.record(PacketEvent::StreamOpened {
+                                stream_id: recv.id().index(),
+                                bidirectional: false,
+                                local: false,
+                            });
+                        }
+                        let stream = QuicStream {
+                            id: peer_stream_id(recv.id()),
+                            stream_type: StreamType::Unidirectional,
+                            send: None,
+                            recv: Some(recv),
+                        };
+                        if streams_tx
+                            .send(DriverEvent::StreamOpened(Box::new(stream)))
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        debug!("Stopped accepting streams: {}", e);
+                        let _ = streams_tx.send(DriverEvent::Closed {
+                            reason: e.to_string(),
+                        });
+                        break;
+                    }
+                }
+            }
+        });
+
+        tokio::spawn(async move {
+            while let Ok(datagram) = connection.read_datagram().await {
+                if event_tx
+                    .send(DriverEvent::DatagramReceived(datagram))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+/// ID of a stream the peer opened
+///
+/// Our streams are numbered by index, which each side and direction counts
+/// separately. Peer streams use their full QUIC stream ID, which is unique
+/// within the connection, with the top bit set to keep them apart from ours.
+fn peer_stream_id(id: quinn::StreamId) -> StreamId {
+    u64::from(id) | 1 << 63
+}
+
+#[async_trait]
+impl MoqTransportDriver for QuinnDriver {
+    async fn open_stream(
+        &self,
+        stream_type: StreamType,
+    ) -> Result<Box<dyn DriverStream>, QuicRtcError> {
+        let mut connection = self.connection.lock().await;
+        let stream = connection.open_stream(stream_type).await?;
+        Ok(Box::new(stream))
+    }
+
+    async fn send_datagram(&self, data: Bytes) -> Result<(), QuicRtcError> {
+        let quinn = self.quinn.as_ref().ok_or_else(|| QuicRtcError::Transport {
+            reason: format!("Datagrams are not supported in {:?} mode", self.mode),
+        })?;
+        quinn
+            .send_datagram(data)
+            .map_err(|e| QuicRtcError::Transport {
+                reason: format!("Failed to send datagram: {}", e),
+            })
+    }
+
+    fn max_datagram_size(&self) -> Option<usize> {
+        // Follows path MTU discovery
+        self.quinn.as_ref()?.max_datagram_size()
+    }
+
+    fn take_events(&self) -> Option<mpsc::UnboundedReceiver<DriverEvent>> {
+        self.events.lock().take()
+    }
+
+    fn connection_id(&self) -> Uuid {
+        self.connection_id
+    }
+
+    fn transport_mode(&self) -> TransportMode {
+        self.mode
+    }
+
+    fn is_connected(&self) -> bool {
+        match &self.quinn {
+            Some(quinn) => quinn.close_reason().is_none(),
+            None => self
+                .connection
+                .try_lock()
+                .map(|connection| connection.is_connected())
+                .unwrap_or(true),
+        }
+    }
+
+    async fn close(&self) -> Result<(), QuicRtcError> {
+        self.connection.lock().await.close().await
+    }
+
+    fn track_published(&self, track_type: MoqTrackType) {
+        if let Some(marker) = &self.dscp_marker {
+            marker.add_track(track_type);
+        }
+    }
+}
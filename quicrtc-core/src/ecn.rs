@@ -0,0 +1,168 @@
+//! ECN accounting
+//!
+//! quinn sends packets as ECN-capable (ECT(0)) wherever the platform lets
+//! it set and read the ECN bits, verifies that the path passes them through,
+//! and backs off as soon as its peer reports congestion-experienced (CE)
+//! marks, before any packet is lost. [`EcnCounters`] counts the codepoints a
+//! connection's packets are sent and received with, so applications can see
+//! whether ECN is in use and how often the network signals congestion.
+
+use quinn::udp::{EcnCodepoint, RecvMeta, Transmit};
+use quinn::{AsyncUdpSocket, UdpPoller};
+use std::io::IoSliceMut;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Packets counted per ECN codepoint
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EcnCounts {
+    /// ECN-capable, classic congestion control (ECT(0))
+    pub ect0: u64,
+    /// ECN-capable, L4S scalable congestion control (ECT(1))
+    pub ect1: u64,
+    /// Congestion experienced (CE)
+    pub ce: u64,
+}
+
+impl EcnCounts {
+    /// Packets carrying any ECN codepoint
+    pub fn total(&self) -> u64 {
+        self.ect0 + self.ect1 + self.ce
+    }
+
+    /// Share of ECN-capable packets that were marked CE
+    pub fn ce_fraction(&self) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => self.ce as f64 / total as f64,
+        }
+    }
+
+    /// Whether most packets use L4S (ECT(1)) rather than classic ECN
+    pub fn is_l4s(&self) -> bool {
+        self.ect1 > self.ect0
+    }
+
+    /// Packets counted since `earlier`, a previous sample of the same counters
+    pub fn since(&self, earlier: &EcnCounts) -> EcnCounts {
+        EcnCounts {
+            ect0: self.ect0.saturating_sub(earlier.ect0),
+            ect1: self.ect1.saturating_sub(earlier.ect1),
+            ce: self.ce.saturating_sub(earlier.ce),
+        }
+    }
+}
+
+/// Running ECN counts of a connection's packets
+///
+/// Clones share the same counts.
+#[derive(Debug, Clone, Default)]
+pub struct EcnCounters {
+    sent: Arc<AtomicCounts>,
+    received: Arc<AtomicCounts>,
+}
+
+impl EcnCounters {
+    /// Packets sent, by the codepoint they were sent with
+    pub fn sent(&self) -> EcnCounts {
+        self.sent.load()
+    }
+
+    /// Packets received, by the codepoint they arrived with
+    pub fn received(&self) -> EcnCounts {
+        self.received.load()
+    }
+}
+
+#[derive(Debug, Default)]
+struct AtomicCounts {
+    ect0: AtomicU64,
+    ect1: AtomicU64,
+    ce: AtomicU64,
+}
+
+impl AtomicCounts {
+    fn add(&self, ecn: Option<EcnCodepoint>, packets: u64) {
+        let counter = match ecn {
+            Some(EcnCodepoint::Ect0) => &self.ect0,
+            Some(EcnCodepoint::Ect1) => &self.ect1,
+            Some(EcnCodepoint::Ce) => &self.ce,
+            None => return,
+        };
+        counter.fetch_add(packets, Ordering::Relaxed);
+    }
+
+    fn load(&self) -> EcnCounts {
+        EcnCounts {
+            ect0: self.ect0.load(Ordering::Relaxed),
+            ect1: self.ect1.load(Ordering::Relaxed),
+            ce: self.ce.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Wrap `socket` so the ECN codepoints of its packets are counted
+pub(crate) fn counting_socket(
+    socket: Arc<dyn AsyncUdpSocket>,
+    counters: EcnCounters,
+) -> Arc<dyn AsyncUdpSocket> {
+    Arc::new(EcnCountingSocket { socket, counters })
+}
+
+/// UDP socket counting the ECN codepoints of the datagrams it carries
+#[derive(Debug)]
+struct EcnCountingSocket {
+    socket: Arc<dyn AsyncUdpSocket>,
+    counters: EcnCounters,
+}
+
+impl AsyncUdpSocket for EcnCountingSocket {
+    fn create_io_poller(self: Arc<Self>) -> Pin<Box<dyn UdpPoller>> {
+        self.socket.clone().create_io_poller()
+    }
+
+    fn try_send(&self, transmit: &Transmit) -> std::io::Result<()> {
+        self.socket.try_send(transmit)?;
+        let segments = match transmit.segment_size {
+            Some(size) if size > 0 => transmit.contents.len().div_ceil(size),
+            _ => 1,
+        };
+        self.counters.sent.add(transmit.ecn, segments as u64);
+        Ok(())
+    }
+
+    fn poll_recv(
+        &self,
+        cx: &mut Context,
+        bufs: &mut [IoSliceMut<'_>],
+        meta: &mut [RecvMeta],
+    ) -> Poll<std::io::Result<usize>> {
+        let poll = self.socket.poll_recv(cx, bufs, meta);
+        if let Poll::Ready(Ok(count)) = &poll {
+            for meta in &meta[..*count] {
+                let segments = meta.len.div_ceil(meta.stride.max(1));
+                self.counters.received.add(meta.ecn, segments as u64);
+            }
+        }
+        poll
+    }
+
+    fn local_addr(&self) -> std::io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    fn max_transmit_segments(&self) -> usize {
+        self.socket.max_transmit_segments()
+    }
+
+    fn max_receive_segments(&self) -> usize {
+        self.socket.max_receive_segments()
+    }
+
+    fn may_fragment(&self) -> bool {
+        self.socket.may_fragment()
+    }
+}
@@ -12,14 +12,14 @@ pub enum QuicRtcError {
         /// Reason for initialization failure
         reason: String,
     },
-    
+
     /// Missing configuration error
     #[error("Missing required configuration: {field}")]
     MissingConfiguration {
         /// Missing configuration field
         field: String,
     },
-    
+
     /// Connection error
     #[error("Connection failed for room {room_id}: {reason}")]
     Connection {
@@ -32,35 +32,35 @@ pub enum QuicRtcError {
         /// Suggested action to resolve the issue
         suggested_action: String,
     },
-    
+
     /// Transport error
     #[error("Transport error: {reason}")]
     Transport {
         /// Reason for transport error
         reason: String,
     },
-    
+
     /// MoQ protocol error
     #[error("MoQ protocol error: {reason}")]
     MoqProtocol {
         /// Reason for protocol error
         reason: String,
     },
-    
+
     /// Media processing error
     #[error("Media processing error: {reason}")]
     MediaProcessing {
         /// Reason for media error
         reason: String,
     },
-    
+
     /// Resource limit exceeded
     #[error("Resource limit exceeded: {resource}")]
     ResourceLimit {
         /// Resource that exceeded limit
         resource: String,
     },
-    
+
     /// Invalid state error
     #[error("Invalid state: expected {expected}, got {actual}")]
     InvalidState {
@@ -69,14 +69,14 @@ pub enum QuicRtcError {
         /// Actual state
         actual: String,
     },
-    
+
     /// Protocol error
     #[error("Protocol error: {message}")]
     ProtocolError {
         /// Error message
         message: String,
     },
-    
+
     /// Session setup failed
     #[error("Session setup failed (code {code}): {reason}")]
     SessionSetupFailed {
@@ -85,28 +85,28 @@ pub enum QuicRtcError {
         /// Error reason
         reason: String,
     },
-    
+
     /// Unsupported version
     #[error("Unsupported version: {version}")]
     UnsupportedVersion {
         /// Unsupported version number
         version: u32,
     },
-    
+
     /// Unsupported track type
     #[error("Unsupported track type: {track_type}")]
     UnsupportedTrackType {
         /// Unsupported track type
         track_type: String,
     },
-    
+
     /// Track limit exceeded
     #[error("Track limit exceeded: {limit}")]
     TrackLimitExceeded {
         /// Maximum number of tracks allowed
         limit: u32,
     },
-    
+
     /// Track announce failed
     #[error("Track announce failed for {track_namespace} (code {code}): {reason}")]
     TrackAnnounceFailed {
@@ -117,7 +117,7 @@ pub enum QuicRtcError {
         /// Error reason
         reason: String,
     },
-    
+
     /// Subscription failed
     #[error("Subscription failed for {track_namespace} (code {code}): {reason}")]
     SubscriptionFailed {
@@ -128,7 +128,7 @@ pub enum QuicRtcError {
         /// Error reason
         reason: String,
     },
-    
+
     /// Cache full error
     #[error("Cache full: current size {current_size} bytes exceeds maximum {max_size} bytes")]
     CacheFull {
@@ -137,7 +137,7 @@ pub enum QuicRtcError {
         /// Maximum cache size in bytes
         max_size: usize,
     },
-    
+
     /// Track cache full error
     #[error("Track cache full for {track_name}: current objects {current_objects} exceeds maximum {max_objects}")]
     TrackCacheFull {
@@ -148,25 +148,25 @@ pub enum QuicRtcError {
         /// Maximum number of objects
         max_objects: usize,
     },
-    
+
     /// Track not found error
     #[error("Track not found: {track_namespace}")]
     TrackNotFound {
         /// Track namespace
         track_namespace: String,
     },
-    
+
     /// Stream not found error
     #[error("Stream not found: {stream_id}")]
     StreamNotFound {
         /// Stream ID
         stream_id: u64,
     },
-    
+
     /// No data available error
     #[error("No data available")]
     NoDataAvailable,
-    
+
     /// Invalid operation error
     #[error("Invalid operation: {operation}")]
     InvalidOperation {
@@ -189,14 +189,14 @@ pub enum QuicRtcError {
         /// Duration after which timeout occurred
         duration: std::time::Duration,
     },
-    
+
     /// Invalid data error
     #[error("Invalid data: {reason}")]
     InvalidData {
         /// Reason for invalid data
         reason: String,
     },
-    
+
     /// Invalid media type error
     #[error("Invalid media type: expected {expected}, got {actual}")]
     InvalidMediaType {
@@ -205,7 +205,7 @@ pub enum QuicRtcError {
         /// Actual media type
         actual: String,
     },
-    
+
     /// Unsupported codec error
     #[error("Unsupported codec: {codec}")]
     UnsupportedCodec {
@@ -259,6 +259,15 @@ pub enum QuicRtcError {
         max_participants: usize,
     },
 
+    /// Scheduled room is not open for joining yet
+    #[error("Room {room_id} is not open until {opens_at}")]
+    RoomNotOpen {
+        /// Room ID
+        room_id: String,
+        /// When the room opens, as an RFC 3339 timestamp
+        opens_at: String,
+    },
+
     /// Participant already exists
     #[error("Participant {participant_id} already exists in room {room_id}")]
     ParticipantAlreadyExists {
@@ -285,6 +294,36 @@ pub enum QuicRtcError {
         /// Parsing error
         source: Box<dyn std::error::Error + Send + Sync>,
     },
+
+    /// Missing, invalid or insufficient credentials
+    #[error("Unauthorized: {reason}")]
+    Unauthorized {
+        /// Why the request was not authorized
+        reason: String,
+    },
+
+    /// Peer is sending faster than it is allowed to
+    #[error("Rate limited: retry after {retry_after:?}")]
+    RateLimited {
+        /// How long the peer should back off
+        retry_after: std::time::Duration,
+    },
+
+    /// Operation was cancelled before it completed
+    #[error("Operation cancelled: {operation}")]
+    Cancelled {
+        /// Operation that was cancelled
+        operation: String,
+    },
+
+    /// Participants declined, or did not answer, a recording or transcription request
+    #[error("Capture consent refused in room {room_id} by {participants:?}")]
+    ConsentRefused {
+        /// Room ID
+        room_id: String,
+        /// Participants that declined or did not answer
+        participants: Vec<String>,
+    },
 }
 
 impl QuicRtcError {
@@ -317,15 +356,48 @@ impl QuicRtcError {
             QuicRtcError::InvalidOperation { .. } => "INVALID_OPERATION".to_string(),
             QuicRtcError::ResourceExhausted { .. } => "RESOURCE_EXHAUSTED".to_string(),
             QuicRtcError::Timeout { .. } => "TIMEOUT".to_string(),
-        QuicRtcError::EncodingFailed { .. } => "ENCODING_FAILED".to_string(),
-        QuicRtcError::DecodingFailed { .. } => "DECODING_FAILED".to_string(),
+            QuicRtcError::EncodingFailed { .. } => "ENCODING_FAILED".to_string(),
+            QuicRtcError::DecodingFailed { .. } => "DECODING_FAILED".to_string(),
             QuicRtcError::ServerStartFailed { .. } => "SERVER_START_FAILED".to_string(),
             QuicRtcError::RoomNotFound { .. } => "ROOM_NOT_FOUND".to_string(),
             QuicRtcError::RoomAlreadyExists { .. } => "ROOM_ALREADY_EXISTS".to_string(),
             QuicRtcError::RoomFull { .. } => "ROOM_FULL".to_string(),
-            QuicRtcError::ParticipantAlreadyExists { .. } => "PARTICIPANT_ALREADY_EXISTS".to_string(),
+            QuicRtcError::RoomNotOpen { .. } => "ROOM_NOT_OPEN".to_string(),
+            QuicRtcError::ParticipantAlreadyExists { .. } => {
+                "PARTICIPANT_ALREADY_EXISTS".to_string()
+            }
             QuicRtcError::ParticipantNotFound { .. } => "PARTICIPANT_NOT_FOUND".to_string(),
             QuicRtcError::InvalidMessage { .. } => "INVALID_MESSAGE".to_string(),
+            QuicRtcError::Unauthorized { .. } => "UNAUTHORIZED".to_string(),
+            QuicRtcError::RateLimited { .. } => "RATE_LIMITED".to_string(),
+            QuicRtcError::Cancelled { .. } => "CANCELLED".to_string(),
+            QuicRtcError::ConsentRefused { .. } => "CONSENT_REFUSED".to_string(),
         }
     }
-}
\ No newline at end of file
+
+    /// Whether trying the operation again may succeed
+    ///
+    /// True for timeouts and other transient failures, false for errors a
+    /// retry would only repeat, such as a refused subscription.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            QuicRtcError::Timeout { .. }
+                | QuicRtcError::Connection { .. }
+                | QuicRtcError::Transport { .. }
+                | QuicRtcError::ResourceExhausted { .. }
+                | QuicRtcError::RateLimited { .. }
+        )
+    }
+}
+impl From<quicrtc_wire::WireError> for QuicRtcError {
+    fn from(error: quicrtc_wire::WireError) -> Self {
+        match error {
+            quicrtc_wire::WireError::InvalidData { reason } => QuicRtcError::InvalidData { reason },
+            quicrtc_wire::WireError::Protocol { reason } => QuicRtcError::MoqProtocol { reason },
+            quicrtc_wire::WireError::ResourceLimit { resource } => {
+                QuicRtcError::ResourceLimit { resource }
+            }
+        }
+    }
+}
@@ -0,0 +1,423 @@
+//! Datagram object fragmentation
+//!
+//! A QUIC datagram must fit in a single packet, so objects sent as
+//! datagrams are limited by the path MTU. A [`DatagramFragmenter`] splits
+//! larger objects into fragments that each fit the current maximum
+//! datagram size, marked with the [`MoqObjectMetadata::FRAGMENT`] header,
+//! and reassembles them on the receiving side.
+//!
+//! The first fragment carries the object's extension headers; the others
+//! only carry their position. Datagrams are never retransmitted, so an
+//! object with a lost fragment can't be completed: it is abandoned once
+//! its first fragment is older than the reassembly timeout, or when too
+//! many objects are waiting for fragments at once, and counted in
+//! [`FragmentStats`].
+//!
+//! [`MoqObjectMetadata::FRAGMENT`]: crate::moq::MoqObjectMetadata::FRAGMENT
+
+use crate::error::QuicRtcError;
+use crate::moq::{MoqObject, MoqObjectMetadata, MoqWireFormat};
+use bytes::{Bytes, BytesMut};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Limits of fragment reassembly
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FragmentConfig {
+    /// How long an object may wait for its missing fragments
+    pub reassembly_timeout: Duration,
+    /// Objects waiting for fragments at once; the oldest is abandoned to
+    /// make room for a new one
+    pub max_pending_objects: usize,
+}
+
+impl Default for FragmentConfig {
+    fn default() -> Self {
+        Self {
+            reassembly_timeout: Duration::from_millis(500),
+            max_pending_objects: 64,
+        }
+    }
+}
+
+/// Fragment counters in both directions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FragmentStats {
+    /// Outgoing objects that needed more than one datagram
+    pub objects_fragmented: u64,
+    /// Fragments sent for those objects
+    pub fragments_sent: u64,
+    /// Incoming fragments accepted for reassembly
+    pub fragments_received: u64,
+    /// Incoming objects completed from their fragments
+    pub objects_reassembled: u64,
+    /// Incoming objects given up on because fragments were missing
+    pub objects_abandoned: u64,
+    /// Fragments thrown away with abandoned objects, or inconsistent with
+    /// the other fragments of their object
+    pub fragments_abandoned: u64,
+}
+
+/// Identifies an object across its fragments
+type ObjectKey = (u64, u64, u64);
+
+/// Object waiting for the rest of its fragments
+#[derive(Debug)]
+struct PendingObject {
+    first_seen: Instant,
+    /// Fragment payloads by index
+    fragments: Vec<Option<Vec<u8>>>,
+    received: usize,
+    /// Fragment 0, carrying the object's extension headers
+    head: Option<MoqObject>,
+}
+
+#[derive(Debug, Default)]
+struct FragmentState {
+    config: FragmentConfig,
+    pending: HashMap<ObjectKey, PendingObject>,
+    stats: FragmentStats,
+}
+
+impl FragmentState {
+    fn abandon(&mut self, key: &ObjectKey) {
+        if let Some(pending) = self.pending.remove(key) {
+            debug!(
+                "Abandoning object {}/{} with {} of {} fragments",
+                key.1,
+                key.2,
+                pending.received,
+                pending.fragments.len()
+            );
+            self.stats.objects_abandoned += 1;
+            self.stats.fragments_abandoned += pending.received as u64;
+        }
+    }
+
+    fn expire(&mut self, now: Instant) {
+        let timeout = self.config.reassembly_timeout;
+        let expired: Vec<ObjectKey> = self
+            .pending
+            .iter()
+            .filter(|(_, pending)| now.saturating_duration_since(pending.first_seen) >= timeout)
+            .map(|(key, _)| *key)
+            .collect();
+        for key in &expired {
+            self.abandon(key);
+        }
+    }
+
+    fn make_room(&mut self) {
+        while self.pending.len() >= self.config.max_pending_objects.max(1) {
+            let oldest = self
+                .pending
+                .iter()
+                .min_by_key(|(_, pending)| pending.first_seen)
+                .map(|(key, _)| *key);
+            match oldest {
+                Some(key) => self.abandon(&key),
+                None => break,
+            }
+        }
+    }
+}
+
+/// Splits outgoing datagram objects and reassembles incoming ones
+///
+/// Clones share their state and counters.
+#[derive(Debug, Clone, Default)]
+pub struct DatagramFragmenter {
+    state: Arc<Mutex<FragmentState>>,
+}
+
+impl DatagramFragmenter {
+    /// Fragmenter with the default [`FragmentConfig`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fragmenter with custom reassembly limits
+    pub fn with_config(config: FragmentConfig) -> Self {
+        let fragmenter = Self::new();
+        fragmenter.set_config(config);
+        fragmenter
+    }
+
+    /// Change the reassembly limits, applied from the next fragment on
+    pub fn set_config(&self, config: FragmentConfig) {
+        self.state.lock().config = config;
+    }
+
+    /// Reassembly limits in use
+    pub fn config(&self) -> FragmentConfig {
+        self.state.lock().config.clone()
+    }
+
+    /// Encode `object` as one or more datagrams of at most
+    /// `max_datagram_size` bytes
+    ///
+    /// Without a size limit the object is always sent whole. Fails if the
+    /// limit is too small for even an empty fragment, or the object would
+    /// need more fragments than the header can number.
+    pub fn encode(
+        &self,
+        object: &MoqObject,
+        track_alias: u64,
+        max_datagram_size: Option<usize>,
+    ) -> Result<Vec<Bytes>, QuicRtcError> {
+        let mut whole = BytesMut::new();
+        MoqWireFormat::encode_object_datagram(object, track_alias, &mut whole)?;
+        let Some(max_size) = max_datagram_size else {
+            return Ok(vec![whole.freeze()]);
+        };
+        if whole.len() <= max_size {
+            return Ok(vec![whole.freeze()]);
+        }
+
+        // Fragment 0 carries every extension header, so its header is the
+        // largest; size all fragments by it
+        let mut head = fragment_of(object, object.metadata.clone(), &[]);
+        head.metadata
+            .set_fragment(0, u16::MAX)
+            .map_err(|e| QuicRtcError::ResourceLimit {
+                resource: e.to_string(),
+            })?;
+        let mut header = BytesMut::new();
+        MoqWireFormat::encode_object_datagram(&head, track_alias, &mut header)?;
+        let chunk_size = max_size.saturating_sub(header.len());
+        if chunk_size == 0 {
+            return Err(QuicRtcError::ResourceLimit {
+                resource: format!(
+                    "Datagram size {} leaves no room for fragment payload",
+                    max_size
+                ),
+            });
+        }
+
+        let count = object.payload.len().div_ceil(chunk_size);
+        let count = u16::try_from(count).map_err(|_| QuicRtcError::ResourceLimit {
+            resource: format!(
+                "Object of {} bytes needs {} fragments of {} bytes",
+                object.payload.len(),
+                count,
+                chunk_size
+            ),
+        })?;
+
+        let mut datagrams = Vec::with_capacity(count as usize);
+        for (index, chunk) in object.payload.chunks(chunk_size).enumerate() {
+            let metadata = if index == 0 {
+                object.metadata.clone()
+            } else {
+                MoqObjectMetadata::new()
+            };
+            let mut fragment = fragment_of(object, metadata, chunk);
+            fragment
+                .metadata
+                .set_fragment(index as u16, count)
+                .map_err(|e| QuicRtcError::ResourceLimit {
+                    resource: e.to_string(),
+                })?;
+            let mut buffer = BytesMut::new();
+            MoqWireFormat::encode_object_datagram(&fragment, track_alias, &mut buffer)?;
+            datagrams.push(buffer.freeze());
+        }
+
+        let mut state = self.state.lock();
+        state.stats.objects_fragmented += 1;
+        state.stats.fragments_sent += datagrams.len() as u64;
+        Ok(datagrams)
+    }
+
+    /// Accept a decoded datagram object
+    ///
+    /// Objects that are not fragments are returned as they are. Fragments
+    /// are held until their object is complete, which is then returned
+    /// without the fragment header.
+    pub fn reassemble(&self, track_alias: u64, object: MoqObject) -> Option<MoqObject> {
+        self.reassemble_at(track_alias, object, Instant::now())
+    }
+
+    fn reassemble_at(
+        &self,
+        track_alias: u64,
+        mut object: MoqObject,
+        now: Instant,
+    ) -> Option<MoqObject> {
+        let Some((index, count)) = object.metadata.fragment() else {
+            return Some(object);
+        };
+        let (index, count) = (index as usize, count as usize);
+
+        let mut state = self.state.lock();
+        state.expire(now);
+
+        let key = (track_alias, object.group_id, object.object_id);
+        if !state.pending.contains_key(&key) {
+            state.make_room();
+            state.pending.insert(
+                key,
+                PendingObject {
+                    first_seen: now,
+                    fragments: vec![None; count],
+                    received: 0,
+                    head: None,
+                },
+            );
+        }
+
+        let pending = state.pending.get_mut(&key)?;
+        if pending.fragments.len() != count || pending.fragments[index].is_some() {
+            state.stats.fragments_abandoned += 1;
+            return None;
+        }
+        pending.fragments[index] = Some(std::mem::take(&mut object.payload));
+        pending.received += 1;
+        if index == 0 {
+            pending.head = Some(object);
+        }
+        state.stats.fragments_received += 1;
+
+        let pending = state.pending.get(&key)?;
+        if pending.received < count {
+            return None;
+        }
+        let mut pending = state.pending.remove(&key)?;
+        let mut object = pending.head.take()?;
+        object.payload = pending.fragments.into_iter().flatten().flatten().collect();
+        object.size = object.payload.len();
+        object.metadata.remove(MoqObjectMetadata::FRAGMENT);
+        state.stats.objects_reassembled += 1;
+        Some(object)
+    }
+
+    /// Objects currently waiting for fragments
+    pub fn pending_objects(&self) -> usize {
+        self.state.lock().pending.len()
+    }
+
+    /// Fragment counters, after abandoning objects whose timeout passed
+    pub fn stats(&self) -> FragmentStats {
+        let mut state = self.state.lock();
+        state.expire(Instant::now());
+        state.stats
+    }
+}
+
+/// Copy of `object` carrying `payload` and `metadata`
+fn fragment_of(object: &MoqObject, metadata: MoqObjectMetadata, payload: &[u8]) -> MoqObject {
+    MoqObject {
+        payload: payload.to_vec(),
+        size: payload.len(),
+        metadata,
+        ..object.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moq::{MoqObjectStatus, TrackNamespace, VideoRotation};
+
+    fn large_object(len: usize) -> MoqObject {
+        let mut metadata = MoqObjectMetadata::new();
+        metadata.set_rotation(VideoRotation::Deg90).unwrap();
+        MoqObject {
+            track_namespace: TrackNamespace {
+                namespace: "default".to_string(),
+                track_name: "unknown".to_string(),
+            },
+            track_name: "unknown".to_string(),
+            group_id: 7,
+            object_id: 3,
+            publisher_priority: 1,
+            payload: (0..len).map(|i| i as u8).collect(),
+            object_status: MoqObjectStatus::Normal,
+            created_at: Instant::now(),
+            size: len,
+            metadata,
+        }
+    }
+
+    fn decode(datagram: &[u8]) -> MoqObject {
+        MoqWireFormat::decode_object_datagram(datagram).unwrap().1
+    }
+
+    #[test]
+    fn test_fragments_fit_and_reassemble_out_of_order() {
+        let sender = DatagramFragmenter::new();
+        let receiver = DatagramFragmenter::new();
+        let object = large_object(5000);
+
+        let datagrams = sender.encode(&object, 1, Some(1200)).unwrap();
+        assert_eq!(datagrams.len(), 5);
+        assert!(datagrams.iter().all(|datagram| datagram.len() <= 1200));
+
+        let mut reassembled = None;
+        for datagram in datagrams.iter().rev() {
+            assert!(reassembled.is_none());
+            reassembled = receiver.reassemble(1, decode(datagram));
+        }
+        let reassembled = reassembled.unwrap();
+        assert_eq!(reassembled.payload, object.payload);
+        assert_eq!(reassembled.size, 5000);
+        assert_eq!(reassembled.metadata.rotation(), Some(VideoRotation::Deg90));
+        assert_eq!(reassembled.metadata.fragment(), None);
+        assert_eq!(receiver.pending_objects(), 0);
+
+        assert_eq!(sender.stats().objects_fragmented, 1);
+        assert_eq!(sender.stats().fragments_sent, 5);
+        let stats = receiver.stats();
+        assert_eq!(stats.fragments_received, 5);
+        assert_eq!(stats.objects_reassembled, 1);
+
+        // Small objects and unlimited datagrams are sent whole
+        let whole = sender.encode(&large_object(100), 1, Some(1200)).unwrap();
+        assert_eq!(whole.len(), 1);
+        assert!(receiver.reassemble(1, decode(&whole[0])).is_some());
+        assert_eq!(sender.encode(&object, 1, None).unwrap().len(), 1);
+
+        assert!(sender.encode(&object, 1, Some(8)).is_err());
+    }
+
+    #[test]
+    fn test_incomplete_objects_are_abandoned() {
+        let sender = DatagramFragmenter::new();
+        let receiver = DatagramFragmenter::with_config(FragmentConfig {
+            reassembly_timeout: Duration::from_millis(100),
+            max_pending_objects: 1,
+        });
+        let start = Instant::now();
+
+        // Fragment 1 of 3 is lost
+        let lost = sender.encode(&large_object(3000), 1, Some(1200)).unwrap();
+        assert_eq!(lost.len(), 3);
+        assert!(receiver.reassemble_at(1, decode(&lost[0]), start).is_none());
+        assert!(receiver.reassemble_at(1, decode(&lost[2]), start).is_none());
+        // Duplicates are dropped
+        assert!(receiver.reassemble_at(1, decode(&lost[2]), start).is_none());
+        assert_eq!(receiver.pending_objects(), 1);
+
+        // A new object takes the only slot
+        let next = MoqObject {
+            object_id: 4,
+            ..large_object(3000)
+        };
+        let next = sender.encode(&next, 1, Some(1200)).unwrap();
+        assert!(receiver.reassemble_at(1, decode(&next[0]), start).is_none());
+        let stats = receiver.stats();
+        assert_eq!(stats.objects_abandoned, 1);
+        assert_eq!(stats.fragments_abandoned, 3);
+
+        // And times out
+        let later = start + Duration::from_millis(150);
+        assert!(receiver.reassemble_at(1, decode(&next[1]), later).is_none());
+        let stats = receiver.stats();
+        assert_eq!(stats.objects_abandoned, 2);
+        assert_eq!(stats.fragments_abandoned, 4);
+        assert_eq!(stats.objects_reassembled, 0);
+    }
+}
@@ -0,0 +1,264 @@
+//! Object integrity hashes
+//!
+//! Relays forward objects they could alter, by accident or on purpose. A
+//! publisher with [`ObjectIntegrity`] attaches a hash over each object's
+//! track, position, payload and extension headers in the
+//! [`MoqObjectMetadata::INTEGRITY_HASH`] header, and a subscriber with the
+//! same settings drops objects whose hash doesn't match.
+//!
+//! An unkeyed SHA-256 hash catches corruption and relays that rewrite
+//! payloads without knowing about the header. A relay that recomputes the
+//! hash can only be caught with a key shared by publisher and subscribers,
+//! which turns the hash into an HMAC; this protects integrity even where
+//! payloads are not end-to-end encrypted.
+//!
+//! [`MoqObjectMetadata::INTEGRITY_HASH`]: crate::moq::MoqObjectMetadata::INTEGRITY_HASH
+
+use crate::error::QuicRtcError;
+use crate::moq::{MoqObject, MoqObjectMetadata};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Separates integrity hashes from any other use of the same key
+const DOMAIN: &[u8] = b"quicrtc object integrity v1";
+
+/// Outcome of checking an incoming object
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegrityCheck {
+    /// Hash present and matching
+    Verified,
+    /// No hash attached
+    Missing,
+    /// Hash doesn't match the object, which was altered on the way
+    Mismatch,
+}
+
+/// Integrity counters of incoming objects
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IntegrityStats {
+    /// Objects whose hash matched
+    pub verified: u64,
+    /// Objects without a hash
+    pub missing: u64,
+    /// Objects whose hash didn't match, or that had none when hashes are
+    /// required
+    pub failed: u64,
+}
+
+#[derive(Debug, Default)]
+struct IntegrityCounters {
+    verified: AtomicU64,
+    missing: AtomicU64,
+    failed: AtomicU64,
+}
+
+/// Attaches integrity hashes to outgoing objects and verifies incoming ones
+///
+/// Clones share their counters.
+#[derive(Clone)]
+pub struct ObjectIntegrity {
+    key: Option<Arc<[u8]>>,
+    require: bool,
+    counters: Arc<IntegrityCounters>,
+}
+
+impl ObjectIntegrity {
+    /// Unkeyed SHA-256 hashes, catching corruption on the way
+    pub fn new() -> Self {
+        Self {
+            key: None,
+            require: false,
+            counters: Arc::default(),
+        }
+    }
+
+    /// HMAC-SHA256 hashes under `key`, which relays without the key can't
+    /// forge
+    pub fn with_key(key: impl Into<Vec<u8>>) -> Self {
+        Self {
+            key: Some(Arc::from(key.into())),
+            ..Self::new()
+        }
+    }
+
+    /// Treat incoming objects without a hash as failed
+    ///
+    /// Off by default so objects from publishers that don't hash still get
+    /// through; with a key, turn it on, or a relay could strip the header.
+    pub fn require_hashes(mut self, require: bool) -> Self {
+        self.require = require;
+        self
+    }
+
+    /// Hash `object` and attach the hash to its extension headers
+    ///
+    /// Must run after anything else that changes the object, such as an
+    /// encoded frame transform.
+    pub fn sign(&self, object: &mut MoqObject) -> Result<(), QuicRtcError> {
+        let hash = self.hash(object);
+        object
+            .metadata
+            .set_integrity_hash(hash)
+            .map_err(|e| QuicRtcError::ResourceLimit {
+                resource: e.to_string(),
+            })
+    }
+
+    /// Check the hash attached to `object` and count the outcome
+    pub fn verify(&self, object: &MoqObject) -> IntegrityCheck {
+        let check = match object.metadata.integrity_hash() {
+            None => IntegrityCheck::Missing,
+            Some(attached) if self.matches(object, &attached) => IntegrityCheck::Verified,
+            Some(_) => IntegrityCheck::Mismatch,
+        };
+        let counter = match check {
+            IntegrityCheck::Verified => &self.counters.verified,
+            IntegrityCheck::Missing if !self.require => &self.counters.missing,
+            IntegrityCheck::Missing | IntegrityCheck::Mismatch => &self.counters.failed,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        check
+    }
+
+    /// Whether an object with this outcome should be delivered
+    pub fn accepts(&self, check: IntegrityCheck) -> bool {
+        match check {
+            IntegrityCheck::Verified => true,
+            IntegrityCheck::Missing => !self.require,
+            IntegrityCheck::Mismatch => false,
+        }
+    }
+
+    /// Counters of the objects verified so far
+    pub fn stats(&self) -> IntegrityStats {
+        IntegrityStats {
+            verified: self.counters.verified.load(Ordering::Relaxed),
+            missing: self.counters.missing.load(Ordering::Relaxed),
+            failed: self.counters.failed.load(Ordering::Relaxed),
+        }
+    }
+
+    fn hash(&self, object: &MoqObject) -> [u8; 32] {
+        match &self.key {
+            Some(key) => {
+                let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key");
+                feed(object, |bytes| mac.update(bytes));
+                mac.finalize().into_bytes().into()
+            }
+            None => {
+                let mut hasher = Sha256::new();
+                feed(object, |bytes| hasher.update(bytes));
+                hasher.finalize().into()
+            }
+        }
+    }
+
+    fn matches(&self, object: &MoqObject, attached: &[u8; 32]) -> bool {
+        match &self.key {
+            // Constant time, so a relay can't probe the tag byte by byte
+            Some(key) => {
+                let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key");
+                feed(object, |bytes| mac.update(bytes));
+                mac.verify_slice(attached).is_ok()
+            }
+            None => self.hash(object) == *attached,
+        }
+    }
+}
+
+impl Default for ObjectIntegrity {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for ObjectIntegrity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ObjectIntegrity")
+            .field("keyed", &self.key.is_some())
+            .field("require", &self.require)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+/// Feed everything the hash covers to `update`
+///
+/// Covers where the object belongs, so a relay can't pass one object off as
+/// another, and every extension header except the hash itself.
+fn feed(object: &MoqObject, mut update: impl FnMut(&[u8])) {
+    let mut field = |bytes: &[u8]| {
+        update(&(bytes.len() as u64).to_be_bytes());
+        update(bytes);
+    };
+    field(DOMAIN);
+    field(object.track_namespace.namespace.as_bytes());
+    field(object.track_namespace.track_name.as_bytes());
+    field(&object.group_id.to_be_bytes());
+    field(&object.object_id.to_be_bytes());
+    field(&object.payload);
+    for (key, value) in object.metadata.iter() {
+        if key != MoqObjectMetadata::INTEGRITY_HASH {
+            field(&key.to_be_bytes());
+            field(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moq::{H264Frame, TrackNamespace};
+
+    fn object() -> MoqObject {
+        MoqObject::from_h264_frame(
+            TrackNamespace {
+                namespace: "room.a".to_string(),
+                track_name: "alice/camera".to_string(),
+            },
+            H264Frame {
+                nal_units: vec![0, 0, 0, 1, 0x65, 1, 2, 3],
+                is_keyframe: true,
+                timestamp_us: 0,
+                sequence_number: 7,
+            },
+        )
+    }
+
+    #[test]
+    fn test_tampered_objects_are_detected() {
+        let publisher = ObjectIntegrity::with_key(b"room secret".to_vec());
+        let subscriber = publisher.clone().require_hashes(true);
+
+        let mut signed = object();
+        publisher.sign(&mut signed).unwrap();
+        assert_eq!(subscriber.verify(&signed), IntegrityCheck::Verified);
+
+        // A relay rewriting the payload, even rehashing without the key
+        let mut tampered = signed.clone();
+        tampered.payload[5] ^= 0xff;
+        assert_eq!(subscriber.verify(&tampered), IntegrityCheck::Mismatch);
+        ObjectIntegrity::new().sign(&mut tampered).unwrap();
+        assert_eq!(subscriber.verify(&tampered), IntegrityCheck::Mismatch);
+
+        // ...or stripping the hash
+        let mut stripped = signed.clone();
+        stripped.metadata.remove(MoqObjectMetadata::INTEGRITY_HASH);
+        let check = subscriber.verify(&stripped);
+        assert_eq!(check, IntegrityCheck::Missing);
+        assert!(!subscriber.accepts(check));
+
+        assert_eq!(
+            subscriber.stats(),
+            IntegrityStats {
+                verified: 1,
+                missing: 0,
+                failed: 3,
+            }
+        );
+    }
+}
@@ -0,0 +1,346 @@
+//! Adaptive keep-alive
+//!
+//! Every packet sent while the cellular radio is idle wakes it up and keeps
+//! it powered for several seconds afterwards, so a fixed keep-alive on each
+//! connection costs battery out of proportion to its size. With an
+//! [`AdaptiveKeepAlive`] configured, native QUIC connections send their own
+//! heartbeats instead: only after a full interval without sending anything,
+//! at longer intervals once the connection is idle or the app is in the
+//! background, and early when other traffic has already woken the radio.
+//!
+//! Heartbeats are empty QUIC datagrams, which peers ignore. quinn's own
+//! keep-alive stays on at a longer interval as a safety net for peers that
+//! do not accept datagrams.
+
+use bytes::Bytes;
+use parking_lot::Mutex;
+use quinn::udp::{RecvMeta, Transmit};
+use quinn::{AsyncUdpSocket, Connection, ConnectionStats, UdpPoller};
+use std::io::IoSliceMut;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tracing::debug;
+
+/// Heartbeat intervals and radio model of an [`AdaptiveKeepAlive`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeepAliveConfig {
+    /// Longest time without sending while the connection carries traffic
+    pub active_interval: Duration,
+    /// Longest time without sending once the connection is idle
+    pub idle_interval: Duration,
+    /// Longest time without sending while the app is in the background
+    pub background_interval: Duration,
+    /// Time without application traffic after which a connection is idle
+    pub idle_after: Duration,
+    /// How early a heartbeat is sent when the radio is already awake
+    pub coalesce_window: Duration,
+    /// How long the radio stays powered after the last packet
+    pub radio_tail: Duration,
+}
+
+impl Default for KeepAliveConfig {
+    fn default() -> Self {
+        Self {
+            active_interval: Duration::from_secs(15),
+            idle_interval: Duration::from_secs(30),
+            background_interval: Duration::from_secs(60),
+            idle_after: Duration::from_secs(30),
+            coalesce_window: Duration::from_secs(10),
+            radio_tail: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Measured heartbeat and radio wakeup counts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeepAliveStats {
+    /// Time since the keep-alive was created
+    pub observed_for: Duration,
+    /// Times traffic started after the radio had gone idle
+    pub radio_wakeups: u64,
+    /// Heartbeats sent
+    pub heartbeats_sent: u64,
+    /// Heartbeats sent early to share a radio wakeup with other traffic
+    pub heartbeats_coalesced: u64,
+    /// Heartbeats that woke the radio themselves
+    pub heartbeat_wakeups: u64,
+}
+
+/// Keep-alive policy shared by connections and the app
+///
+/// Clones share state, so one instance can be configured on every
+/// connection of the app: their heartbeats then align with each other's
+/// traffic. Call [`set_backgrounded`](Self::set_backgrounded) when the app
+/// moves between foreground and background.
+#[derive(Debug, Clone)]
+pub struct AdaptiveKeepAlive {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    config: KeepAliveConfig,
+    created_at: Instant,
+    backgrounded: AtomicBool,
+    /// Last packet sent or received by any connection
+    last_radio_activity: Mutex<Option<Instant>>,
+    radio_woke: Notify,
+    radio_wakeups: AtomicU64,
+    heartbeats_sent: AtomicU64,
+    heartbeats_coalesced: AtomicU64,
+    heartbeat_wakeups: AtomicU64,
+}
+
+impl AdaptiveKeepAlive {
+    /// Keep-alive following `config`
+    pub fn new(config: KeepAliveConfig) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                config,
+                created_at: Instant::now(),
+                backgrounded: AtomicBool::new(false),
+                last_radio_activity: Mutex::new(None),
+                radio_woke: Notify::new(),
+                radio_wakeups: AtomicU64::new(0),
+                heartbeats_sent: AtomicU64::new(0),
+                heartbeats_coalesced: AtomicU64::new(0),
+                heartbeat_wakeups: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Intervals and radio model in use
+    pub fn config(&self) -> &KeepAliveConfig {
+        &self.shared.config
+    }
+
+    /// Note whether the app is in the background
+    pub fn set_backgrounded(&self, backgrounded: bool) {
+        self.shared
+            .backgrounded
+            .store(backgrounded, Ordering::Relaxed);
+    }
+
+    /// Whether the app is in the background
+    pub fn is_backgrounded(&self) -> bool {
+        self.shared.backgrounded.load(Ordering::Relaxed)
+    }
+
+    /// Longest time a connection may go without sending, given how long
+    /// it has been without application traffic
+    pub fn interval(&self, idle_for: Duration) -> Duration {
+        let config = &self.shared.config;
+        if self.is_backgrounded() {
+            config.background_interval
+        } else if idle_for >= config.idle_after {
+            config.idle_interval
+        } else {
+            config.active_interval
+        }
+    }
+
+    /// Note a packet sent or received at `at`
+    ///
+    /// Connections using this keep-alive report their own packets. Report
+    /// other traffic of the app, such as signaling, so heartbeats can share
+    /// its radio wakeups.
+    pub fn record_activity(&self, at: Instant) {
+        let woke = {
+            let mut last = self.shared.last_radio_activity.lock();
+            let woke =
+                last.is_none_or(|last| at.saturating_duration_since(last) >= self.radio_tail());
+            *last = Some(last.map_or(at, |last| last.max(at)));
+            woke
+        };
+        if woke {
+            self.shared.radio_wakeups.fetch_add(1, Ordering::Relaxed);
+            self.shared.radio_woke.notify_waiters();
+        }
+    }
+
+    /// Whether the radio is still powered from traffic before `now`
+    pub fn is_radio_awake(&self, now: Instant) -> bool {
+        self.shared
+            .last_radio_activity
+            .lock()
+            .is_some_and(|last| now.saturating_duration_since(last) < self.radio_tail())
+    }
+
+    /// Heartbeat and radio wakeup counts so far
+    pub fn stats(&self) -> KeepAliveStats {
+        let shared = &self.shared;
+        KeepAliveStats {
+            observed_for: shared.created_at.elapsed(),
+            radio_wakeups: shared.radio_wakeups.load(Ordering::Relaxed),
+            heartbeats_sent: shared.heartbeats_sent.load(Ordering::Relaxed),
+            heartbeats_coalesced: shared.heartbeats_coalesced.load(Ordering::Relaxed),
+            heartbeat_wakeups: shared.heartbeat_wakeups.load(Ordering::Relaxed),
+        }
+    }
+
+    fn radio_tail(&self) -> Duration {
+        self.shared.config.radio_tail
+    }
+
+    fn record_heartbeat(&self, coalesced: bool, woke_radio: bool) {
+        let shared = &self.shared;
+        shared.heartbeats_sent.fetch_add(1, Ordering::Relaxed);
+        if coalesced {
+            shared.heartbeats_coalesced.fetch_add(1, Ordering::Relaxed);
+        }
+        if woke_radio {
+            shared.heartbeat_wakeups.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// When one connection last sent a packet
+#[derive(Debug, Default)]
+pub(crate) struct ConnectionActivity {
+    last_sent: Mutex<Option<Instant>>,
+}
+
+impl ConnectionActivity {
+    fn record_sent(&self, at: Instant) {
+        let mut last = self.last_sent.lock();
+        *last = Some(last.map_or(at, |last| last.max(at)));
+    }
+
+    fn last_sent(&self) -> Option<Instant> {
+        *self.last_sent.lock()
+    }
+}
+
+/// Wrap `socket` so its packets are reported to `keep_alive` and `activity`
+pub(crate) fn activity_socket(
+    socket: Arc<dyn AsyncUdpSocket>,
+    keep_alive: AdaptiveKeepAlive,
+    activity: Arc<ConnectionActivity>,
+) -> Arc<dyn AsyncUdpSocket> {
+    Arc::new(ActivitySocket {
+        socket,
+        keep_alive,
+        activity,
+    })
+}
+
+/// UDP socket reporting when packets are sent and received
+#[derive(Debug)]
+struct ActivitySocket {
+    socket: Arc<dyn AsyncUdpSocket>,
+    keep_alive: AdaptiveKeepAlive,
+    activity: Arc<ConnectionActivity>,
+}
+
+impl AsyncUdpSocket for ActivitySocket {
+    fn create_io_poller(self: Arc<Self>) -> Pin<Box<dyn UdpPoller>> {
+        self.socket.clone().create_io_poller()
+    }
+
+    fn try_send(&self, transmit: &Transmit) -> std::io::Result<()> {
+        self.socket.try_send(transmit)?;
+        let now = Instant::now();
+        self.activity.record_sent(now);
+        self.keep_alive.record_activity(now);
+        Ok(())
+    }
+
+    fn poll_recv(
+        &self,
+        cx: &mut Context,
+        bufs: &mut [IoSliceMut<'_>],
+        meta: &mut [RecvMeta],
+    ) -> Poll<std::io::Result<usize>> {
+        let poll = self.socket.poll_recv(cx, bufs, meta);
+        if let Poll::Ready(Ok(count)) = &poll {
+            if *count > 0 {
+                self.keep_alive.record_activity(Instant::now());
+            }
+        }
+        poll
+    }
+
+    fn local_addr(&self) -> std::io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    fn max_transmit_segments(&self) -> usize {
+        self.socket.max_transmit_segments()
+    }
+
+    fn max_receive_segments(&self) -> usize {
+        self.socket.max_receive_segments()
+    }
+
+    fn may_fragment(&self) -> bool {
+        self.socket.may_fragment()
+    }
+}
+
+/// Send heartbeats on `connection` until it closes
+///
+/// Intervals are capped at `max_interval` so the connection never reaches
+/// its idle timeout.
+pub(crate) fn spawn_heartbeats(
+    connection: Connection,
+    keep_alive: AdaptiveKeepAlive,
+    activity: Arc<ConnectionActivity>,
+    max_interval: Duration,
+) {
+    tokio::spawn(async move {
+        let mut heartbeats = 0;
+        let mut traffic = application_frames(&connection.stats(), heartbeats);
+        let mut last_traffic = Instant::now();
+
+        loop {
+            let now = Instant::now();
+            let frames = application_frames(&connection.stats(), heartbeats);
+            if frames != traffic {
+                traffic = frames;
+                last_traffic = now;
+            }
+
+            let interval = keep_alive
+                .interval(now.saturating_duration_since(last_traffic))
+                .min(max_interval);
+            let deadline = activity.last_sent().unwrap_or(now) + interval;
+            let radio_awake = keep_alive.is_radio_awake(now);
+            let due = now >= deadline;
+            let coalesce = !due
+                && radio_awake
+                && deadline.saturating_duration_since(now) <= keep_alive.config().coalesce_window;
+
+            if due || coalesce {
+                if let Err(e) = connection.send_datagram(Bytes::new()) {
+                    // quinn's keep-alive takes over
+                    debug!("Stopping adaptive keep-alive heartbeats: {}", e);
+                    break;
+                }
+                heartbeats += 1;
+                activity.record_sent(now);
+                keep_alive.record_heartbeat(coalesce, !radio_awake);
+                continue;
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep_until(deadline.into()) => {}
+                _ = keep_alive.shared.radio_woke.notified() => {}
+                _ = connection.closed() => break,
+            }
+        }
+    });
+}
+
+/// Stream and datagram frames exchanged, not counting our own heartbeats
+///
+/// Datagrams from the peer are left out as they may be its heartbeats.
+fn application_frames(stats: &ConnectionStats, heartbeats: u64) -> u64 {
+    stats.frame_tx.stream
+        + stats.frame_rx.stream
+        + stats.frame_tx.datagram.saturating_sub(heartbeats)
+}
@@ -7,27 +7,60 @@
 #![deny(missing_docs)]
 #![warn(clippy::all)]
 
+pub mod backpressure;
+pub mod capture;
+pub mod driver;
+pub mod ecn;
 pub mod error;
+pub mod fragment;
+pub mod integrity;
+pub mod keepalive;
 pub mod moq;
 pub mod moq_transport;
+pub mod qos;
 pub mod resource;
+pub mod telemetry;
 pub mod transport;
 
 // Re-export main types
+pub use backpressure::{Backpressure, BackpressureConfig, BackpressureReason, BackpressureSignal};
+pub use capture::{
+    EcnMark, PacketCapture, PacketCaptureConfig, PacketCaptureReader, PacketCaptureStats,
+    PacketEvent, PacketRecord,
+};
+pub use driver::{DriverEvent, DriverStream, MoqTransportDriver, QuinnDriver};
+pub use ecn::{EcnCounters, EcnCounts};
 pub use error::QuicRtcError;
+pub use fragment::{DatagramFragmenter, FragmentConfig, FragmentStats};
+pub use integrity::{IntegrityCheck, IntegrityStats, ObjectIntegrity};
+pub use keepalive::{AdaptiveKeepAlive, KeepAliveConfig, KeepAliveStats};
 pub use moq::{
-    H264Frame, ManagedMoqStream, MoqCacheConfig, MoqCacheStats, MoqCapabilities, MoqControlMessage,
-    MoqDeliveryStats, MoqObject, MoqObjectCache, MoqObjectDelivery, MoqObjectStatus, MoqSession,
-    MoqSessionState, MoqStreamEvent, MoqStreamManager, MoqStreamState, MoqStreamType,
-    MoqSubscription, MoqSubscriptionState, MoqTrack, MoqTrackType, MoqWireFormat, OpusFrame,
-    StreamId, StreamManagerConfig, StreamStats, TrackAlias, TrackNamespace,
+    room_namespace, AudioContentHint, ChannelLayout, DvrBuffer, DvrHandle, H264Frame,
+    KeyframeCache, ManagedMoqStream, MoqCacheBudget, MoqCacheConfig, MoqCacheStats,
+    MoqCapabilities, MoqControlMessage, MoqDeliveryStats, MoqErrorCode, MoqEvictionPolicy,
+    MoqNamespaceSubscription, MoqObject, MoqObjectCache, MoqObjectDelivery, MoqObjectMetadata,
+    MoqObjectStatus, MoqPermissions, MoqRole, MoqSendBufferConfig, MoqSession, MoqSessionState,
+    MoqSpillCache, MoqSpillConfig, MoqSpillKey, MoqSpillSegmentReader, MoqSpillStats,
+    MoqStreamEvent, MoqStreamManager, MoqStreamState, MoqStreamType, MoqSubscription,
+    MoqSubscriptionState, MoqTrack, MoqTrackType, MoqWireFormat, OpusFrame, SendBufferWatermark,
+    SendBufferWatermarkCallback, StreamId, StreamManagerConfig, StreamStats, TrackAccess,
+    TrackAlias, TrackAuthorizer, TrackFilter, TrackNamespace, TrackNamespaceBuilder,
+    TrackNamespacePattern, TrackTransferStats, VideoContentHint, VideoRotation,
+};
+pub use moq_transport::{
+    ControlRetryPolicy, EncodedFrameTransform, MoqOverQuicTransport, MoqStream, MoqTransportEvent,
+    TransformDirection,
 };
-pub use moq_transport::{MoqOverQuicTransport, MoqStream, MoqTransportEvent};
+pub use qos::{Dscp, DscpMarker, QosConfig};
 pub use resource::{
     ConnectionPool, ConnectionPoolConfig, ConnectionPoolMetrics, ConnectionPoolStats,
     ResourceLimits, ResourceManager, ResourceMonitorConfig, ResourceUsage, ResourceWarning,
     WarningSeverity,
 };
+pub use telemetry::{
+    default_sink, DiagnosticsSink, HistogramSummary, LogLevel, MetricsRecorder, MetricsSnapshot,
+    TracingSink,
+};
 pub use transport::{
     ConnectionConfig, ConnectionMetrics, ConnectionStats, NetworkPath, QuicStream, StreamType,
     Transport, TransportConnection, TransportMode,
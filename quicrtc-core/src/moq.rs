@@ -1,19 +1,27 @@
 //! IETF Media over QUIC (MoQ) protocol implementation
 
+use crate::backpressure::{BackpressureReason, BackpressureSignal};
 use crate::error::QuicRtcError;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+pub mod spill;
 pub mod stream_manager;
-pub mod wire_format;
 
+pub use quicrtc_wire::{
+    room_namespace, AudioContentHint, ChannelLayout, H264Frame, MoqCapabilities, MoqControlMessage,
+    MoqErrorCode, MoqObject, MoqObjectMetadata, MoqObjectStatus, MoqRole, MoqTrack, MoqTrackType,
+    MoqWireFormat, OpusFrame, TrackNamespace, TrackNamespaceBuilder, TrackNamespacePattern,
+    VideoContentHint, VideoRotation,
+};
+pub use spill::{MoqSpillCache, MoqSpillConfig, MoqSpillKey, MoqSpillSegmentReader, MoqSpillStats};
 pub use stream_manager::{
     ManagedMoqStream, MoqStreamEvent, MoqStreamManager, MoqStreamState, MoqStreamType, StreamId,
     StreamManagerConfig, StreamStats, TrackAlias,
 };
-pub use wire_format::MoqWireFormat;
 
 /// MoQ session management with track management and subscription handling
 #[derive(Debug)]
@@ -26,6 +34,38 @@ pub struct MoqSession {
     peer_capabilities: Option<MoqCapabilities>,
     /// Stream manager for control message transport
     stream_manager: Option<Arc<MoqStreamManager>>,
+    /// What the peer may announce and subscribe to
+    permissions: MoqPermissions,
+    /// Announce and subscribe requests rejected by `permissions` or
+    /// `track_authorizer`
+    permission_violations: u64,
+    /// Per-track check of the tokens the peer sends with its requests
+    track_authorizer: Option<Arc<dyn TrackAuthorizer>>,
+    /// Token sent with our announce and subscribe requests
+    auth_token: Option<String>,
+    /// Patterns we subscribed to, with the tracks subscribed through them
+    namespace_subscriptions: HashMap<TrackNamespacePattern, MoqNamespaceSubscription>,
+    /// Patterns the peer subscribed to
+    peer_namespace_subscriptions: HashSet<TrackNamespacePattern>,
+}
+
+/// Reply to a peer's control request, with the result of handling it
+#[derive(Debug)]
+pub(crate) struct ControlReply<T> {
+    /// Message to send back to the peer
+    pub message: MoqControlMessage,
+    /// Result to report once the reply is sent
+    pub outcome: Result<T, QuicRtcError>,
+}
+
+impl ControlReply<()> {
+    /// Reply whose sending is all there is to report
+    fn sent(message: MoqControlMessage) -> Self {
+        Self {
+            message,
+            outcome: Ok(()),
+        }
+    }
 }
 
 /// MoQ session state
@@ -41,37 +81,6 @@ pub enum MoqSessionState {
     Terminated,
 }
 
-/// MoQ session capabilities
-#[derive(Debug, Clone)]
-pub struct MoqCapabilities {
-    /// Supported MoQ version
-    pub version: u32,
-    /// Maximum number of concurrent tracks
-    pub max_tracks: u32,
-    /// Supported track types
-    pub supported_track_types: Vec<MoqTrackType>,
-    /// Maximum object size in bytes
-    pub max_object_size: u64,
-    /// Support for object caching
-    pub supports_caching: bool,
-}
-
-impl Default for MoqCapabilities {
-    fn default() -> Self {
-        Self {
-            version: 1,
-            max_tracks: 100,
-            supported_track_types: vec![
-                MoqTrackType::Audio,
-                MoqTrackType::Video,
-                MoqTrackType::Data,
-            ],
-            max_object_size: 1024 * 1024, // 1MB
-            supports_caching: true,
-        }
-    }
-}
-
 /// MoQ subscription information
 #[derive(Debug, Clone)]
 pub struct MoqSubscription {
@@ -87,6 +96,20 @@ pub struct MoqSubscription {
     pub end_group: Option<u64>,
 }
 
+/// Subscription to every current and future track matching a pattern
+///
+/// The peer announces each matching track and the session subscribes to it
+/// at `priority`, so objects flow without per-track subscribe calls.
+#[derive(Debug, Clone)]
+pub struct MoqNamespaceSubscription {
+    /// Tracks of interest
+    pub pattern: TrackNamespacePattern,
+    /// Priority given to each track subscribed through the pattern
+    pub priority: u8,
+    /// Tracks subscribed through the pattern so far
+    pub tracks: HashSet<TrackNamespace>,
+}
+
 /// MoQ subscription state
 #[derive(Debug, Clone, PartialEq)]
 pub enum MoqSubscriptionState {
@@ -100,184 +123,14 @@ pub enum MoqSubscriptionState {
     Terminated,
 }
 
-/// MoQ control messages for session management
-#[derive(Debug, Clone)]
-pub enum MoqControlMessage {
-    /// Session setup message
-    Setup {
-        /// MoQ protocol version
-        version: u32,
-        /// Session capabilities
-        capabilities: MoqCapabilities,
-    },
-    /// Session setup response
-    SetupOk {
-        /// Agreed MoQ protocol version
-        version: u32,
-        /// Peer capabilities
-        capabilities: MoqCapabilities,
-    },
-    /// Session setup error
-    SetupError {
-        /// Error code
-        code: u32,
-        /// Error reason
-        reason: String,
-    },
-    /// Announce a track
-    Announce {
-        /// Track namespace
-        track_namespace: TrackNamespace,
-        /// Track information
-        track: MoqTrack,
-    },
-    /// Announce response
-    AnnounceOk {
-        /// Track namespace
-        track_namespace: TrackNamespace,
-    },
-    /// Announce error
-    AnnounceError {
-        /// Track namespace
-        track_namespace: TrackNamespace,
-        /// Error code
-        code: u32,
-        /// Error reason
-        reason: String,
-    },
-    /// Subscribe to a track
-    Subscribe {
-        /// Track namespace
-        track_namespace: TrackNamespace,
-        /// Subscription priority
-        priority: u8,
-        /// Start group ID (None for live)
-        start_group: Option<u64>,
-        /// End group ID (None for ongoing)
-        end_group: Option<u64>,
-    },
-    /// Subscribe response
-    SubscribeOk {
-        /// Track namespace
-        track_namespace: TrackNamespace,
-    },
-    /// Subscribe error
-    SubscribeError {
-        /// Track namespace
-        track_namespace: TrackNamespace,
-        /// Error code
-        code: u32,
-        /// Error reason
-        reason: String,
-    },
-    /// Unsubscribe from a track
-    Unsubscribe {
-        /// Track namespace
-        track_namespace: TrackNamespace,
-    },
-    /// Session termination
-    Terminate {
-        /// Termination code
-        code: u32,
-        /// Termination reason
-        reason: String,
-    },
-}
-
-impl MoqObject {
-    /// Create MoQ object from H.264 frame (no RTP packetization)
-    pub fn from_h264_frame(track_namespace: TrackNamespace, frame: H264Frame) -> Self {
-        let size = frame.nal_units.len();
-        Self {
-            track_namespace,
-            track_name: "video".to_string(),
-            group_id: frame.timestamp_us / 1000, // Convert to milliseconds for grouping
-            object_id: frame.sequence_number,
-            publisher_priority: if frame.is_keyframe { 1 } else { 2 }, // Keyframes have higher priority
-            payload: frame.nal_units,
-            object_status: MoqObjectStatus::Normal,
-            created_at: std::time::Instant::now(),
-            size,
-        }
-    }
-
-    /// Create MoQ object from Opus audio frame (no RTP packetization)
-    pub fn from_opus_frame(track_namespace: TrackNamespace, frame: OpusFrame) -> Self {
-        let size = frame.opus_data.len();
-        Self {
-            track_namespace,
-            track_name: "audio".to_string(),
-            group_id: frame.timestamp_us / 20000, // 20ms audio groups
-            object_id: frame.sequence_number,
-            publisher_priority: 1, // Audio always high priority
-            payload: frame.opus_data,
-            object_status: MoqObjectStatus::Normal,
-            created_at: std::time::Instant::now(),
-            size,
-        }
-    }
-
-    /// Create end-of-group marker object
-    pub fn end_of_group(
-        track_namespace: TrackNamespace,
-        track_name: String,
-        group_id: u64,
-        object_id: u64,
-    ) -> Self {
-        Self {
-            track_namespace,
-            track_name,
-            group_id,
-            object_id,
-            publisher_priority: 1, // End markers have high priority
-            payload: Vec::new(),
-            object_status: MoqObjectStatus::EndOfGroup,
-            created_at: std::time::Instant::now(),
-            size: 0,
-        }
-    }
-
-    /// Create end-of-track marker object
-    pub fn end_of_track(
-        track_namespace: TrackNamespace,
-        track_name: String,
-        group_id: u64,
-        object_id: u64,
-    ) -> Self {
-        Self {
-            track_namespace,
-            track_name,
-            group_id,
-            object_id,
-            publisher_priority: 1, // End markers have high priority
-            payload: Vec::new(),
-            object_status: MoqObjectStatus::EndOfTrack,
-            created_at: std::time::Instant::now(),
-            size: 0,
-        }
-    }
-
-    /// Get object priority for delivery ordering
-    pub fn delivery_priority(&self) -> u8 {
-        match self.object_status {
-            MoqObjectStatus::EndOfTrack => 0, // Highest priority
-            MoqObjectStatus::EndOfGroup => 1,
-            MoqObjectStatus::Normal => self.publisher_priority,
-        }
-    }
-
-    /// Check if object is a control/marker object
-    pub fn is_control_object(&self) -> bool {
-        matches!(
-            self.object_status,
-            MoqObjectStatus::EndOfGroup | MoqObjectStatus::EndOfTrack
-        )
-    }
-
-    /// Get object age since creation
-    pub fn age(&self) -> std::time::Duration {
-        self.created_at.elapsed()
-    }
+/// SETUP_ERROR reason for roles that can't carry any track
+///
+/// Roles only clash when both sides publish only or both subscribe only.
+fn incompatible_roles(role: MoqRole) -> String {
+    format!(
+        "Both sides take the {:?} role, so no track can be exchanged",
+        role
+    )
 }
 
 impl MoqSession {
@@ -291,6 +144,12 @@ impl MoqSession {
             capabilities: MoqCapabilities::default(),
             peer_capabilities: None,
             stream_manager: None,
+            permissions: MoqPermissions::default(),
+            permission_violations: 0,
+            track_authorizer: None,
+            auth_token: None,
+            namespace_subscriptions: HashMap::new(),
+            peer_namespace_subscriptions: HashSet::new(),
         }
     }
 
@@ -304,6 +163,12 @@ impl MoqSession {
             capabilities,
             peer_capabilities: None,
             stream_manager: None,
+            permissions: MoqPermissions::default(),
+            permission_violations: 0,
+            track_authorizer: None,
+            auth_token: None,
+            namespace_subscriptions: HashMap::new(),
+            peer_namespace_subscriptions: HashSet::new(),
         }
     }
 
@@ -322,11 +187,113 @@ impl MoqSession {
         &self.capabilities
     }
 
+    /// Replace the capabilities offered in SETUP
+    ///
+    /// Only possible before the session is established, as they are
+    /// exchanged once.
+    pub fn set_capabilities(&mut self, capabilities: MoqCapabilities) -> Result<(), QuicRtcError> {
+        if self.state != MoqSessionState::Establishing {
+            return Err(QuicRtcError::InvalidState {
+                expected: "Establishing".to_string(),
+                actual: format!("{:?}", self.state),
+            });
+        }
+        self.capabilities = capabilities;
+        Ok(())
+    }
+
+    /// Role the peer took at SETUP; before SETUP it may do anything
+    pub fn peer_role(&self) -> MoqRole {
+        self.peer_capabilities
+            .as_ref()
+            .map_or(MoqRole::PubSub, |caps| caps.role)
+    }
+
+    /// Error for subscribing in a role that only publishes
+    fn check_can_subscribe(&self) -> Result<(), QuicRtcError> {
+        if self.capabilities.role.can_subscribe() {
+            return Ok(());
+        }
+        Err(QuicRtcError::MoqProtocol {
+            reason: format!(
+                "Cannot subscribe to tracks in the {:?} role",
+                self.capabilities.role
+            ),
+        })
+    }
+
+    /// Log a peer request its role doesn't allow, returning the reason
+    fn role_violation(&self, action: &str, target: &str) -> String {
+        let role = self.peer_role();
+        tracing::warn!(
+            "Session {} rejected {:?} peer trying to {} {}",
+            self.session_id,
+            role,
+            action,
+            target
+        );
+        format!("A {:?} may not {} tracks", role, action)
+    }
+
     /// Get peer capabilities (if session is established)
     pub fn peer_capabilities(&self) -> Option<&MoqCapabilities> {
         self.peer_capabilities.as_ref()
     }
 
+    /// Restrict which tracks the peer may announce and subscribe to
+    ///
+    /// Requests outside these permissions are answered with
+    /// [`MoqErrorCode::PermissionDenied`] and logged.
+    pub fn set_permissions(&mut self, permissions: MoqPermissions) {
+        self.permissions = permissions;
+    }
+
+    /// Get the peer's permissions
+    pub fn permissions(&self) -> &MoqPermissions {
+        &self.permissions
+    }
+
+    /// Number of announce and subscribe requests rejected by permissions
+    pub fn permission_violations(&self) -> u64 {
+        self.permission_violations
+    }
+
+    /// Check the token sent with each announce and subscribe request
+    /// against `authorizer`, after [`Self::set_permissions`]
+    pub fn set_track_authorizer(&mut self, authorizer: Arc<dyn TrackAuthorizer>) {
+        self.track_authorizer = Some(authorizer);
+    }
+
+    /// Send `token` with our announce and subscribe requests
+    pub fn set_auth_token(&mut self, token: Option<String>) {
+        self.auth_token = token;
+    }
+
+    /// Whether the track authorizer, if any, lets the peer holding `token`
+    /// have `access` to `track_namespace`; rejections are logged and counted
+    fn authorize(
+        &mut self,
+        access: TrackAccess,
+        track_namespace: &TrackNamespace,
+        token: Option<&str>,
+    ) -> Result<(), QuicRtcError> {
+        let Some(authorizer) = &self.track_authorizer else {
+            return Ok(());
+        };
+        authorizer
+            .authorize(access, track_namespace, token)
+            .inspect_err(|e| {
+                self.permission_violations += 1;
+                tracing::warn!(
+                    "Session {} denied {:?} of {}: {}",
+                    self.session_id,
+                    access,
+                    track_namespace,
+                    e
+                );
+            })
+    }
+
     /// Set the stream manager for transport integration
     pub fn set_stream_manager(&mut self, stream_manager: Arc<MoqStreamManager>) {
         self.stream_manager = Some(stream_manager);
@@ -334,6 +301,16 @@ impl MoqSession {
 
     /// Establish MoQ session with capability exchange
     pub async fn establish_session(&mut self) -> Result<(), QuicRtcError> {
+        let setup_msg = self.setup_request()?;
+        self.send_control_message(setup_msg).await?;
+
+        // Wait for setup response
+        let response = self.receive_control_message().await?;
+        self.handle_setup_response(response)
+    }
+
+    /// SETUP message offering our capabilities
+    pub(crate) fn setup_request(&self) -> Result<MoqControlMessage, QuicRtcError> {
         if self.state != MoqSessionState::Establishing {
             return Err(QuicRtcError::InvalidState {
                 expected: "Establishing".to_string(),
@@ -341,16 +318,18 @@ impl MoqSession {
             });
         }
 
-        // Send setup message with our capabilities
-        let setup_msg = MoqControlMessage::Setup {
+        Ok(MoqControlMessage::Setup {
             version: self.capabilities.version,
             capabilities: self.capabilities.clone(),
-        };
-
-        self.send_control_message(setup_msg).await?;
+        })
+    }
 
-        // Wait for setup response
-        match self.receive_control_message().await? {
+    /// Apply the peer's answer to [`Self::setup_request`]
+    pub(crate) fn handle_setup_response(
+        &mut self,
+        response: MoqControlMessage,
+    ) -> Result<(), QuicRtcError> {
+        match response {
             MoqControlMessage::SetupOk {
                 version,
                 capabilities,
@@ -364,6 +343,14 @@ impl MoqSession {
                     });
                 }
 
+                if !self.capabilities.role.is_compatible_with(capabilities.role) {
+                    self.state = MoqSessionState::Terminated;
+                    return Err(QuicRtcError::SessionSetupFailed {
+                        code: MoqErrorCode::IncompatibleRoles.code(),
+                        reason: incompatible_roles(capabilities.role),
+                    });
+                }
+
                 self.peer_capabilities = Some(capabilities);
                 self.state = MoqSessionState::Active;
                 Ok(())
@@ -387,6 +374,22 @@ impl MoqSession {
         version: u32,
         peer_capabilities: MoqCapabilities,
     ) -> Result<(), QuicRtcError> {
+        let reply = self.answer_setup_request(version, peer_capabilities, Ok(()))?;
+        self.send_control_message(reply.message).await?;
+        reply.outcome
+    }
+
+    /// Decide on an incoming SETUP; the session is active straight away if
+    /// it is accepted
+    ///
+    /// `admission` is the caller's own verdict on the peer, e.g. on its
+    /// auth token; an error is sent back as SETUP_ERROR.
+    pub(crate) fn answer_setup_request(
+        &mut self,
+        version: u32,
+        peer_capabilities: MoqCapabilities,
+        admission: Result<(), QuicRtcError>,
+    ) -> Result<ControlReply<()>, QuicRtcError> {
         if self.state != MoqSessionState::Establishing {
             return Err(QuicRtcError::InvalidState {
                 expected: "Establishing".to_string(),
@@ -396,31 +399,71 @@ impl MoqSession {
 
         // Check version compatibility
         if version != self.capabilities.version {
-            let error_msg = MoqControlMessage::SetupError {
-                code: 1,
-                reason: format!("Unsupported version: {}", version),
-            };
-            self.send_control_message(error_msg).await?;
             self.state = MoqSessionState::Terminated;
-            return Err(QuicRtcError::UnsupportedVersion { version });
+            return Ok(ControlReply {
+                message: MoqControlMessage::SetupError {
+                    code: MoqErrorCode::UnsupportedVersion.code(),
+                    reason: format!("Unsupported version: {}", version),
+                },
+                outcome: Err(QuicRtcError::UnsupportedVersion { version }),
+            });
         }
 
-        // Store peer capabilities and send response
-        self.peer_capabilities = Some(peer_capabilities);
+        // Check that one side publishes what the other subscribes to
+        if !self
+            .capabilities
+            .role
+            .is_compatible_with(peer_capabilities.role)
+        {
+            self.state = MoqSessionState::Terminated;
+            let reason = incompatible_roles(peer_capabilities.role);
+            return Ok(ControlReply {
+                message: MoqControlMessage::SetupError {
+                    code: MoqErrorCode::IncompatibleRoles.code(),
+                    reason: reason.clone(),
+                },
+                outcome: Err(QuicRtcError::SessionSetupFailed {
+                    code: MoqErrorCode::IncompatibleRoles.code(),
+                    reason,
+                }),
+            });
+        }
 
-        let response = MoqControlMessage::SetupOk {
-            version: self.capabilities.version,
-            capabilities: self.capabilities.clone(),
-        };
+        if let Err(error) = admission {
+            self.state = MoqSessionState::Terminated;
+            return Ok(ControlReply {
+                message: MoqControlMessage::SetupError {
+                    code: MoqErrorCode::Unauthorized.code(),
+                    reason: error.to_string(),
+                },
+                outcome: Err(error),
+            });
+        }
 
-        self.send_control_message(response).await?;
+        // Store peer capabilities and accept
+        self.peer_capabilities = Some(peer_capabilities);
         self.state = MoqSessionState::Active;
-
-        Ok(())
+        Ok(ControlReply::sent(MoqControlMessage::SetupOk {
+            version: self.capabilities.version,
+            capabilities: self.capabilities.clone(),
+        }))
     }
 
     /// Announce a track for publishing
     pub async fn announce_track(&mut self, track: MoqTrack) -> Result<(), QuicRtcError> {
+        let announce_msg = self.announce_request(&track)?;
+        self.send_control_message(announce_msg).await?;
+
+        // Wait for announce response
+        let response = self.receive_control_message().await?;
+        self.handle_announce_response(track, response)
+    }
+
+    /// ANNOUNCE message for `track`, if the peer can take it
+    pub(crate) fn announce_request(
+        &self,
+        track: &MoqTrack,
+    ) -> Result<MoqControlMessage, QuicRtcError> {
         if self.state != MoqSessionState::Active {
             return Err(QuicRtcError::InvalidState {
                 expected: "Active".to_string(),
@@ -428,6 +471,15 @@ impl MoqSession {
             });
         }
 
+        if !self.capabilities.role.can_publish() {
+            return Err(QuicRtcError::MoqProtocol {
+                reason: format!(
+                    "Cannot announce tracks in the {:?} role",
+                    self.capabilities.role
+                ),
+            });
+        }
+
         // Check if track type is supported by peer
         if let Some(peer_caps) = &self.peer_capabilities {
             if !peer_caps.supported_track_types.contains(&track.track_type) {
@@ -437,24 +489,31 @@ impl MoqSession {
             }
         }
 
-        // Check track limits
+        // Check track limits; announcing a track again doesn't take a new slot
         if let Some(peer_caps) = &self.peer_capabilities {
-            if self.announced_tracks.len() >= peer_caps.max_tracks as usize {
+            if !self.announced_tracks.contains_key(&track.namespace)
+                && self.announced_tracks.len() >= peer_caps.max_tracks as usize
+            {
                 return Err(QuicRtcError::TrackLimitExceeded {
                     limit: peer_caps.max_tracks,
                 });
             }
         }
 
-        let announce_msg = MoqControlMessage::Announce {
+        Ok(MoqControlMessage::Announce {
             track_namespace: track.namespace.clone(),
             track: track.clone(),
-        };
-
-        self.send_control_message(announce_msg).await?;
+            auth_token: self.auth_token.clone(),
+        })
+    }
 
-        // Wait for announce response
-        match self.receive_control_message().await? {
+    /// Apply the peer's answer to [`Self::announce_request`]
+    pub(crate) fn handle_announce_response(
+        &mut self,
+        track: MoqTrack,
+        response: MoqControlMessage,
+    ) -> Result<(), QuicRtcError> {
+        match response {
             MoqControlMessage::AnnounceOk { track_namespace } => {
                 if track_namespace == track.namespace {
                     self.announced_tracks.insert(track.namespace.clone(), track);
@@ -493,7 +552,22 @@ impl MoqSession {
         &mut self,
         track_namespace: TrackNamespace,
         track: MoqTrack,
+        auth_token: Option<String>,
     ) -> Result<(), QuicRtcError> {
+        let reply =
+            self.answer_track_announcement(track_namespace, track, auth_token.as_deref())?;
+        self.send_control_message(reply.message).await?;
+        reply.outcome
+    }
+
+    /// Decide on an incoming track announcement; an accepted track is
+    /// recorded straight away
+    pub(crate) fn answer_track_announcement(
+        &mut self,
+        track_namespace: TrackNamespace,
+        track: MoqTrack,
+        auth_token: Option<&str>,
+    ) -> Result<ControlReply<()>, QuicRtcError> {
         if self.state != MoqSessionState::Active {
             return Err(QuicRtcError::InvalidState {
                 expected: "Active".to_string(),
@@ -501,44 +575,80 @@ impl MoqSession {
             });
         }
 
+        if !self.peer_role().can_publish() {
+            let reason = self.role_violation("announce", &track_namespace.to_string());
+            return Ok(ControlReply {
+                message: MoqControlMessage::AnnounceError {
+                    track_namespace,
+                    code: MoqErrorCode::RoleViolation.code(),
+                    reason: reason.clone(),
+                },
+                outcome: Err(QuicRtcError::MoqProtocol { reason }),
+            });
+        }
+
+        if !self.permissions.allows_announce(&track_namespace) {
+            self.permission_violations += 1;
+            tracing::warn!(
+                "Session {} denied announce of {}/{}",
+                self.session_id,
+                track_namespace.namespace,
+                track_namespace.track_name
+            );
+            let reason = "Not permitted to announce this track".to_string();
+            return Ok(ControlReply {
+                message: MoqControlMessage::AnnounceError {
+                    track_namespace,
+                    code: MoqErrorCode::PermissionDenied.code(),
+                    reason: reason.clone(),
+                },
+                outcome: Err(QuicRtcError::Unauthorized { reason }),
+            });
+        }
+
+        if let Err(error) = self.authorize(TrackAccess::Announce, &track_namespace, auth_token) {
+            return Ok(ControlReply {
+                message: MoqControlMessage::AnnounceError {
+                    track_namespace,
+                    code: MoqErrorCode::Unauthorized.code(),
+                    reason: error.to_string(),
+                },
+                outcome: Err(error),
+            });
+        }
+
         // Check if we support this track type
         if !self
             .capabilities
             .supported_track_types
             .contains(&track.track_type)
         {
-            let error_msg = MoqControlMessage::AnnounceError {
-                track_namespace: track_namespace.clone(),
-                code: 2,
+            return Ok(ControlReply::sent(MoqControlMessage::AnnounceError {
+                track_namespace,
+                code: MoqErrorCode::UnsupportedTrackType.code(),
                 reason: format!("Unsupported track type: {:?}", track.track_type),
-            };
-            self.send_control_message(error_msg).await?;
-            return Ok(());
+            }));
         }
 
-        // Check track limits
-        if self.announced_tracks.len() >= self.capabilities.max_tracks as usize {
-            let error_msg = MoqControlMessage::AnnounceError {
-                track_namespace: track_namespace.clone(),
-                code: 3,
+        // Check track limits; a resent announcement doesn't take a new slot
+        if !self.announced_tracks.contains_key(&track_namespace)
+            && self.announced_tracks.len() >= self.capabilities.max_tracks as usize
+        {
+            return Ok(ControlReply::sent(MoqControlMessage::AnnounceError {
+                track_namespace,
+                code: MoqErrorCode::TrackLimitExceeded.code(),
                 reason: "Track limit exceeded".to_string(),
-            };
-            self.send_control_message(error_msg).await?;
-            return Ok(());
+            }));
         }
 
-        // Accept the track announcement
-        let response = MoqControlMessage::AnnounceOk {
-            track_namespace: track_namespace.clone(),
-        };
-
-        self.send_control_message(response).await?;
-
         // Store the announced track (from peer)
         // Note: In a real implementation, we might want to separate our tracks from peer tracks
-        self.announced_tracks.insert(track_namespace, track);
+        self.announced_tracks.insert(track_namespace.clone(), track);
 
-        Ok(())
+        // Accept the track announcement
+        Ok(ControlReply::sent(MoqControlMessage::AnnounceOk {
+            track_namespace,
+        }))
     }
 
     /// Subscribe to a track
@@ -549,6 +659,23 @@ impl MoqSession {
         start_group: Option<u64>,
         end_group: Option<u64>,
     ) -> Result<MoqSubscription, QuicRtcError> {
+        let (subscribe_msg, subscription) =
+            self.subscribe_request(track_namespace, priority, start_group, end_group)?;
+        self.send_control_message(subscribe_msg).await?;
+
+        // Wait for subscribe response
+        let response = self.receive_control_message().await?;
+        self.handle_subscribe_response(subscription, response)
+    }
+
+    /// SUBSCRIBE message with the pending subscription it asks for
+    pub(crate) fn subscribe_request(
+        &self,
+        track_namespace: TrackNamespace,
+        priority: u8,
+        start_group: Option<u64>,
+        end_group: Option<u64>,
+    ) -> Result<(MoqControlMessage, MoqSubscription), QuicRtcError> {
         if self.state != MoqSessionState::Active {
             return Err(QuicRtcError::InvalidState {
                 expected: "Active".to_string(),
@@ -556,27 +683,33 @@ impl MoqSession {
             });
         }
 
-        // Create subscription
-        let subscription = MoqSubscription {
+        self.check_can_subscribe()?;
+
+        let subscribe_msg = MoqControlMessage::Subscribe {
             track_namespace: track_namespace.clone(),
-            state: MoqSubscriptionState::Pending,
             priority,
             start_group,
             end_group,
+            auth_token: self.auth_token.clone(),
         };
-
-        // Send subscribe message
-        let subscribe_msg = MoqControlMessage::Subscribe {
-            track_namespace: track_namespace.clone(),
+        let subscription = MoqSubscription {
+            track_namespace,
+            state: MoqSubscriptionState::Pending,
             priority,
             start_group,
             end_group,
         };
+        Ok((subscribe_msg, subscription))
+    }
 
-        self.send_control_message(subscribe_msg).await?;
-
-        // Wait for subscribe response
-        match self.receive_control_message().await? {
+    /// Apply the peer's answer to [`Self::subscribe_request`]
+    pub(crate) fn handle_subscribe_response(
+        &mut self,
+        subscription: MoqSubscription,
+        response: MoqControlMessage,
+    ) -> Result<MoqSubscription, QuicRtcError> {
+        let track_namespace = subscription.track_namespace.clone();
+        match response {
             MoqControlMessage::SubscribeOk {
                 track_namespace: resp_namespace,
             } => {
@@ -622,7 +755,29 @@ impl MoqSession {
         priority: u8,
         start_group: Option<u64>,
         end_group: Option<u64>,
+        auth_token: Option<String>,
     ) -> Result<(), QuicRtcError> {
+        let reply = self.answer_subscription_request(
+            track_namespace,
+            priority,
+            start_group,
+            end_group,
+            auth_token.as_deref(),
+        )?;
+        self.send_control_message(reply.message).await?;
+        reply.outcome
+    }
+
+    /// Decide on an incoming subscription request; an accepted subscription
+    /// is recorded straight away
+    pub(crate) fn answer_subscription_request(
+        &mut self,
+        track_namespace: TrackNamespace,
+        priority: u8,
+        start_group: Option<u64>,
+        end_group: Option<u64>,
+        auth_token: Option<&str>,
+    ) -> Result<ControlReply<()>, QuicRtcError> {
         if self.state != MoqSessionState::Active {
             return Err(QuicRtcError::InvalidState {
                 expected: "Active".to_string(),
@@ -630,23 +785,56 @@ impl MoqSession {
             });
         }
 
-        // Check if we have announced this track
-        if !self.announced_tracks.contains_key(&track_namespace) {
-            let error_msg = MoqControlMessage::SubscribeError {
-                track_namespace: track_namespace.clone(),
-                code: 4,
-                reason: "Track not found".to_string(),
-            };
-            self.send_control_message(error_msg).await?;
-            return Ok(());
+        if !self.peer_role().can_subscribe() {
+            let reason = self.role_violation("subscribe to", &track_namespace.to_string());
+            return Ok(ControlReply {
+                message: MoqControlMessage::SubscribeError {
+                    track_namespace,
+                    code: MoqErrorCode::RoleViolation.code(),
+                    reason: reason.clone(),
+                },
+                outcome: Err(QuicRtcError::MoqProtocol { reason }),
+            });
         }
 
-        // Accept the subscription
-        let response = MoqControlMessage::SubscribeOk {
-            track_namespace: track_namespace.clone(),
-        };
+        if !self.permissions.allows_subscribe(&track_namespace) {
+            self.permission_violations += 1;
+            tracing::warn!(
+                "Session {} denied subscribe to {}/{}",
+                self.session_id,
+                track_namespace.namespace,
+                track_namespace.track_name
+            );
+            let reason = "Not permitted to subscribe to this track".to_string();
+            return Ok(ControlReply {
+                message: MoqControlMessage::SubscribeError {
+                    track_namespace,
+                    code: MoqErrorCode::PermissionDenied.code(),
+                    reason: reason.clone(),
+                },
+                outcome: Err(QuicRtcError::Unauthorized { reason }),
+            });
+        }
+
+        if let Err(error) = self.authorize(TrackAccess::Subscribe, &track_namespace, auth_token) {
+            return Ok(ControlReply {
+                message: MoqControlMessage::SubscribeError {
+                    track_namespace,
+                    code: MoqErrorCode::Unauthorized.code(),
+                    reason: error.to_string(),
+                },
+                outcome: Err(error),
+            });
+        }
 
-        self.send_control_message(response).await?;
+        // Check if we have announced this track
+        if !self.announced_tracks.contains_key(&track_namespace) {
+            return Ok(ControlReply::sent(MoqControlMessage::SubscribeError {
+                track_namespace,
+                code: MoqErrorCode::TrackNotFound.code(),
+                reason: "Track not found".to_string(),
+            }));
+        }
 
         // Store the subscription (from peer)
         let subscription = MoqSubscription {
@@ -656,9 +844,99 @@ impl MoqSession {
             start_group,
             end_group,
         };
+        self.subscriptions
+            .insert(track_namespace.clone(), subscription);
+
+        // Accept the subscription
+        Ok(ControlReply::sent(MoqControlMessage::SubscribeOk {
+            track_namespace,
+        }))
+    }
+
+    /// Change the priority or group range of one of our subscriptions
+    ///
+    /// Sends SUBSCRIBE_UPDATE, so objects keep flowing while the publisher
+    /// applies the new values; there is no unsubscribe/resubscribe gap.
+    pub async fn update_subscription(
+        &mut self,
+        track_namespace: &TrackNamespace,
+        priority: u8,
+        start_group: Option<u64>,
+        end_group: Option<u64>,
+    ) -> Result<MoqSubscription, QuicRtcError> {
+        let (subscription, update_msg) =
+            self.apply_subscription_update(track_namespace, priority, start_group, end_group)?;
+        if let Some(update_msg) = update_msg {
+            self.send_control_message(update_msg).await?;
+        }
+        Ok(subscription)
+    }
+
+    /// Change one of our subscriptions, returning it with the
+    /// SUBSCRIBE_UPDATE telling the peer if anything changed
+    pub(crate) fn apply_subscription_update(
+        &mut self,
+        track_namespace: &TrackNamespace,
+        priority: u8,
+        start_group: Option<u64>,
+        end_group: Option<u64>,
+    ) -> Result<(MoqSubscription, Option<MoqControlMessage>), QuicRtcError> {
+        if self.state != MoqSessionState::Active {
+            return Err(QuicRtcError::InvalidState {
+                expected: "Active".to_string(),
+                actual: format!("{:?}", self.state),
+            });
+        }
+
+        let subscription = self
+            .subscriptions
+            .get_mut(track_namespace)
+            .filter(|subscription| subscription.state == MoqSubscriptionState::Active)
+            .ok_or_else(|| QuicRtcError::TrackNotFound {
+                track_namespace: track_namespace.to_string(),
+            })?;
+        if subscription.priority == priority
+            && subscription.start_group == start_group
+            && subscription.end_group == end_group
+        {
+            return Ok((subscription.clone(), None));
+        }
+
+        subscription.priority = priority;
+        subscription.start_group = start_group;
+        subscription.end_group = end_group;
+        let update_msg = MoqControlMessage::SubscribeUpdate {
+            track_namespace: track_namespace.clone(),
+            priority,
+            start_group,
+            end_group,
+        };
+        Ok((subscription.clone(), Some(update_msg)))
+    }
 
-        self.subscriptions.insert(track_namespace, subscription);
+    /// Handle an incoming SUBSCRIBE_UPDATE for one of the peer's subscriptions
+    pub fn handle_subscription_update(
+        &mut self,
+        track_namespace: &TrackNamespace,
+        priority: u8,
+        start_group: Option<u64>,
+        end_group: Option<u64>,
+    ) -> Result<(), QuicRtcError> {
+        if self.state != MoqSessionState::Active {
+            return Err(QuicRtcError::InvalidState {
+                expected: "Active".to_string(),
+                actual: format!("{:?}", self.state),
+            });
+        }
 
+        let subscription = self.subscriptions.get_mut(track_namespace).ok_or_else(|| {
+            QuicRtcError::TrackNotFound {
+                track_namespace: track_namespace.to_string(),
+            }
+        })?;
+        subscription.priority = priority;
+        subscription.start_group = start_group;
+        subscription.end_group = end_group;
         Ok(())
     }
 
@@ -667,6 +945,18 @@ impl MoqSession {
         &mut self,
         track_namespace: &TrackNamespace,
     ) -> Result<(), QuicRtcError> {
+        if let Some(unsubscribe_msg) = self.unsubscribe_request(track_namespace)? {
+            self.send_control_message(unsubscribe_msg).await?;
+        }
+        Ok(())
+    }
+
+    /// Drop our subscription to a track, returning the UNSUBSCRIBE telling
+    /// the peer if there was one
+    pub(crate) fn unsubscribe_request(
+        &mut self,
+        track_namespace: &TrackNamespace,
+    ) -> Result<Option<MoqControlMessage>, QuicRtcError> {
         if self.state != MoqSessionState::Active {
             return Err(QuicRtcError::InvalidState {
                 expected: "Active".to_string(),
@@ -675,18 +965,313 @@ impl MoqSession {
         }
 
         // Remove subscription
-        if let Some(mut subscription) = self.subscriptions.remove(track_namespace) {
-            subscription.state = MoqSubscriptionState::Terminated;
+        Ok(self
+            .subscriptions
+            .remove(track_namespace)
+            .map(|_| MoqControlMessage::Unsubscribe {
+                track_namespace: track_namespace.clone(),
+            }))
+    }
+
+    /// End a subscription from the peer, telling it why
+    pub async fn end_subscription(
+        &mut self,
+        track_namespace: &TrackNamespace,
+        code: u32,
+        reason: String,
+    ) -> Result<(), QuicRtcError> {
+        if let Some(error_msg) = self.end_subscription_request(track_namespace, code, reason)? {
+            self.send_control_message(error_msg).await?;
+        }
+        Ok(())
+    }
+
+    /// Drop a subscription from the peer, returning the SUBSCRIBE_ERROR
+    /// telling it why if there was one
+    pub(crate) fn end_subscription_request(
+        &mut self,
+        track_namespace: &TrackNamespace,
+        code: u32,
+        reason: String,
+    ) -> Result<Option<MoqControlMessage>, QuicRtcError> {
+        if self.state != MoqSessionState::Active {
+            return Err(QuicRtcError::InvalidState {
+                expected: "Active".to_string(),
+                actual: format!("{:?}", self.state),
+            });
+        }
 
-            // Send unsubscribe message
-            let unsubscribe_msg = MoqControlMessage::Unsubscribe {
+        Ok(self
+            .subscriptions
+            .remove(track_namespace)
+            .map(|_| MoqControlMessage::SubscribeError {
                 track_namespace: track_namespace.clone(),
+                code,
+                reason,
+            }))
+    }
+
+    /// Subscribe to every current and future track matching `pattern`
+    ///
+    /// The peer answers by announcing the matching tracks; each announcement
+    /// accepted by [`Self::handle_track_announcement`] can then be subscribed
+    /// to at `priority` (see [`Self::namespace_subscription_for`]).
+    pub async fn subscribe_to_namespace(
+        &mut self,
+        pattern: TrackNamespacePattern,
+        priority: u8,
+    ) -> Result<(), QuicRtcError> {
+        let subscribe_msg = self.subscribe_namespace_request(&pattern)?;
+        self.send_control_message(subscribe_msg).await?;
+
+        let response = self.receive_control_message().await?;
+        self.handle_subscribe_namespace_response(pattern, priority, response)
+    }
+
+    /// SUBSCRIBE_NAMESPACE message for `pattern`
+    pub(crate) fn subscribe_namespace_request(
+        &self,
+        pattern: &TrackNamespacePattern,
+    ) -> Result<MoqControlMessage, QuicRtcError> {
+        if self.state != MoqSessionState::Active {
+            return Err(QuicRtcError::InvalidState {
+                expected: "Active".to_string(),
+                actual: format!("{:?}", self.state),
+            });
+        }
+
+        self.check_can_subscribe()?;
+
+        Ok(MoqControlMessage::SubscribeNamespace {
+            pattern: pattern.clone(),
+        })
+    }
+
+    /// Apply the peer's answer to [`Self::subscribe_namespace_request`]
+    pub(crate) fn handle_subscribe_namespace_response(
+        &mut self,
+        pattern: TrackNamespacePattern,
+        priority: u8,
+        response: MoqControlMessage,
+    ) -> Result<(), QuicRtcError> {
+        match response {
+            MoqControlMessage::SubscribeNamespaceOk {
+                pattern: resp_pattern,
+            } if resp_pattern == pattern => {
+                self.namespace_subscriptions.insert(
+                    pattern.clone(),
+                    MoqNamespaceSubscription {
+                        pattern,
+                        priority,
+                        tracks: HashSet::new(),
+                    },
+                );
+                Ok(())
+            }
+            MoqControlMessage::SubscribeNamespaceError {
+                pattern: resp_pattern,
+                code,
+                reason,
+            } if resp_pattern == pattern => Err(QuicRtcError::SubscriptionFailed {
+                track_namespace: pattern.to_string(),
+                code,
+                reason,
+            }),
+            MoqControlMessage::SubscribeNamespaceOk { .. }
+            | MoqControlMessage::SubscribeNamespaceError { .. } => {
+                Err(QuicRtcError::ProtocolError {
+                    message: "Pattern mismatch in namespace subscribe response".to_string(),
+                })
+            }
+            _ => Err(QuicRtcError::ProtocolError {
+                message: "Unexpected message during namespace subscribe".to_string(),
+            }),
+        }
+    }
+
+    /// Stop receiving announcements for `pattern`
+    ///
+    /// Returns the ended namespace subscription so the caller can drop the
+    /// track subscriptions made through it.
+    pub async fn unsubscribe_from_namespace(
+        &mut self,
+        pattern: &TrackNamespacePattern,
+    ) -> Result<Option<MoqNamespaceSubscription>, QuicRtcError> {
+        match self.unsubscribe_namespace_request(pattern)? {
+            Some((removed, unsubscribe_msg)) => {
+                self.send_control_message(unsubscribe_msg).await?;
+                Ok(Some(removed))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Drop a namespace subscription, returning it with the
+    /// UNSUBSCRIBE_NAMESPACE telling the peer if there was one
+    pub(crate) fn unsubscribe_namespace_request(
+        &mut self,
+        pattern: &TrackNamespacePattern,
+    ) -> Result<Option<(MoqNamespaceSubscription, MoqControlMessage)>, QuicRtcError> {
+        if self.state != MoqSessionState::Active {
+            return Err(QuicRtcError::InvalidState {
+                expected: "Active".to_string(),
+                actual: format!("{:?}", self.state),
+            });
+        }
+
+        Ok(self.namespace_subscriptions.remove(pattern).map(|removed| {
+            let unsubscribe_msg = MoqControlMessage::UnsubscribeNamespace {
+                pattern: pattern.clone(),
             };
+            (removed, unsubscribe_msg)
+        }))
+    }
 
-            self.send_control_message(unsubscribe_msg).await?;
+    /// Namespace subscription covering `track_namespace`, if any
+    pub fn namespace_subscription_for(
+        &self,
+        track_namespace: &TrackNamespace,
+    ) -> Option<&MoqNamespaceSubscription> {
+        self.namespace_subscriptions
+            .values()
+            .find(|subscription| subscription.pattern.matches(track_namespace))
+    }
+
+    /// Record a track subscribed to through a namespace subscription
+    pub fn add_namespace_track(
+        &mut self,
+        pattern: &TrackNamespacePattern,
+        track_namespace: TrackNamespace,
+    ) {
+        if let Some(subscription) = self.namespace_subscriptions.get_mut(pattern) {
+            subscription.tracks.insert(track_namespace);
         }
+    }
 
-        Ok(())
+    /// Get our namespace subscriptions
+    pub fn namespace_subscriptions(
+        &self,
+    ) -> &HashMap<TrackNamespacePattern, MoqNamespaceSubscription> {
+        &self.namespace_subscriptions
+    }
+
+    /// Handle an incoming namespace subscription
+    ///
+    /// Returns the tracks already announced that match the pattern and the
+    /// peer may subscribe to; they should be announced to the peer again so
+    /// it learns about them. Tracks announced later reach the peer through
+    /// [`Self::announce_track`] as usual.
+    pub async fn handle_namespace_subscription_request(
+        &mut self,
+        pattern: TrackNamespacePattern,
+    ) -> Result<Vec<MoqTrack>, QuicRtcError> {
+        let reply = self.answer_namespace_subscription_request(pattern)?;
+        self.send_control_message(reply.message).await?;
+        reply.outcome
+    }
+
+    /// Decide on an incoming namespace subscription; an accepted pattern is
+    /// recorded straight away
+    pub(crate) fn answer_namespace_subscription_request(
+        &mut self,
+        pattern: TrackNamespacePattern,
+    ) -> Result<ControlReply<Vec<MoqTrack>>, QuicRtcError> {
+        if self.state != MoqSessionState::Active {
+            return Err(QuicRtcError::InvalidState {
+                expected: "Active".to_string(),
+                actual: format!("{:?}", self.state),
+            });
+        }
+
+        if !self.peer_role().can_subscribe() {
+            let reason = self.role_violation("subscribe to", &pattern.to_string());
+            return Ok(ControlReply {
+                message: MoqControlMessage::SubscribeNamespaceError {
+                    pattern,
+                    code: MoqErrorCode::RoleViolation.code(),
+                    reason: reason.clone(),
+                },
+                outcome: Err(QuicRtcError::MoqProtocol { reason }),
+            });
+        }
+
+        if !self.permissions.can_subscribe {
+            self.permission_violations += 1;
+            tracing::warn!(
+                "Session {} denied namespace subscribe to {}",
+                self.session_id,
+                pattern
+            );
+            let reason = "Not permitted to subscribe".to_string();
+            return Ok(ControlReply {
+                message: MoqControlMessage::SubscribeNamespaceError {
+                    pattern,
+                    code: MoqErrorCode::PermissionDenied.code(),
+                    reason: reason.clone(),
+                },
+                outcome: Err(QuicRtcError::Unauthorized { reason }),
+            });
+        }
+
+        let matching = self
+            .announced_tracks
+            .iter()
+            .filter(|(track_namespace, _)| {
+                pattern.matches(track_namespace)
+                    && self.permissions.allows_subscribe(track_namespace)
+                    && self.track_authorizer.as_ref().is_none_or(|authorizer| {
+                        authorizer
+                            .authorize(TrackAccess::Subscribe, track_namespace, None)
+                            .is_ok()
+                    })
+            })
+            .map(|(_, track)| track.clone())
+            .collect();
+        self.peer_namespace_subscriptions.insert(pattern.clone());
+
+        Ok(ControlReply {
+            message: MoqControlMessage::SubscribeNamespaceOk { pattern },
+            outcome: Ok(matching),
+        })
+    }
+
+    /// Handle an incoming UNSUBSCRIBE by dropping the peer's subscription
+    pub(crate) fn handle_unsubscribe(&mut self, track_namespace: &TrackNamespace) {
+        if let Some(mut subscription) = self.subscriptions.remove(track_namespace) {
+            subscription.state = MoqSubscriptionState::Terminated;
+        }
+    }
+
+    /// Handle an incoming UNSUBSCRIBE_NAMESPACE
+    pub(crate) fn handle_unsubscribe_namespace(&mut self, pattern: &TrackNamespacePattern) {
+        self.peer_namespace_subscriptions.remove(pattern);
+    }
+
+    /// Let the peer subscribe to `track` without announcing it
+    ///
+    /// For relays passing on tracks another session announced; the peer
+    /// learns of them some other way, such as signaling.
+    pub fn offer_track(&mut self, track: MoqTrack) {
+        self.announced_tracks.insert(track.namespace.clone(), track);
+    }
+
+    /// Take back a track from [`Self::offer_track`], ending the peer's
+    /// subscription to it; returns whether the track was offered
+    pub fn withdraw_track(&mut self, track_namespace: &TrackNamespace) -> bool {
+        self.handle_unsubscribe(track_namespace);
+        self.announced_tracks.remove(track_namespace).is_some()
+    }
+
+    /// Whether one of the peer's namespace subscriptions covers `track_namespace`
+    pub fn peer_subscribed_to_namespace(&self, track_namespace: &TrackNamespace) -> bool {
+        self.peer_namespace_subscriptions
+            .iter()
+            .any(|pattern| pattern.matches(track_namespace))
+    }
+
+    /// Get the peer's namespace subscriptions
+    pub fn peer_namespace_subscriptions(&self) -> &HashSet<TrackNamespacePattern> {
+        &self.peer_namespace_subscriptions
     }
 
     /// Get all announced tracks
@@ -706,18 +1291,33 @@ impl MoqSession {
 
     /// Terminate the session
     pub async fn terminate(&mut self, code: u32, reason: String) -> Result<(), QuicRtcError> {
-        if self.state == MoqSessionState::Terminated {
-            return Ok(());
+        if let Some(terminate_msg) = self.terminate_request(code, reason) {
+            self.send_control_message(terminate_msg).await?;
         }
+        Ok(())
+    }
 
-        self.state = MoqSessionState::Terminating;
-
-        let terminate_msg = MoqControlMessage::Terminate { code, reason };
-        self.send_control_message(terminate_msg).await?;
+    /// Mark the session terminated, returning the TERMINATE telling the peer
+    /// unless it already was
+    pub(crate) fn terminate_request(
+        &mut self,
+        code: u32,
+        reason: String,
+    ) -> Option<MoqControlMessage> {
+        if self.state == MoqSessionState::Terminated {
+            return None;
+        }
 
         self.state = MoqSessionState::Terminated;
+        Some(MoqControlMessage::Terminate { code, reason })
+    }
 
-        Ok(())
+    /// Mark the session terminated by the peer, returning whether it was
+    /// still live
+    pub(crate) fn handle_peer_terminate(&mut self) -> bool {
+        let live = self.state != MoqSessionState::Terminated;
+        self.state = MoqSessionState::Terminated;
+        live
     }
 
     /// Process incoming control message
@@ -733,25 +1333,52 @@ impl MoqSession {
             MoqControlMessage::Announce {
                 track_namespace,
                 track,
-            } => self.handle_track_announcement(track_namespace, track).await,
+                auth_token,
+            } => {
+                self.handle_track_announcement(track_namespace, track, auth_token)
+                    .await
+            }
             MoqControlMessage::Subscribe {
                 track_namespace,
                 priority,
                 start_group,
                 end_group,
+                auth_token,
             } => {
-                self.handle_subscription_request(track_namespace, priority, start_group, end_group)
-                    .await
+                self.handle_subscription_request(
+                    track_namespace,
+                    priority,
+                    start_group,
+                    end_group,
+                    auth_token,
+                )
+                .await
+            }
+            MoqControlMessage::SubscribeUpdate {
+                track_namespace,
+                priority,
+                start_group,
+                end_group,
+            } => {
+                self.handle_subscription_update(&track_namespace, priority, start_group, end_group)
             }
             MoqControlMessage::Unsubscribe { track_namespace } => {
-                // Handle unsubscribe by removing the subscription
-                if let Some(mut subscription) = self.subscriptions.remove(&track_namespace) {
-                    subscription.state = MoqSubscriptionState::Terminated;
+                self.handle_unsubscribe(&track_namespace);
+                Ok(())
+            }
+            MoqControlMessage::SubscribeNamespace { pattern } => {
+                // Tell the peer about the tracks it missed
+                for track in self.handle_namespace_subscription_request(pattern).await? {
+                    self.announce_track(track).await?;
                 }
                 Ok(())
             }
-            MoqControlMessage::Terminate { code: _, reason: _ } => {
-                self.state = MoqSessionState::Terminated;
+            MoqControlMessage::UnsubscribeNamespace { pattern } => {
+                self.handle_unsubscribe_namespace(&pattern);
+                Ok(())
+            }
+            MoqControlMessage::Terminate { .. } => {
+                self.handle_peer_terminate();
                 Ok(())
             }
             _ => {
@@ -784,121 +1411,260 @@ impl MoqSession {
                     actual: "No stream manager".to_string(),
                 })?;
 
-        stream_manager.receive_control_message().await
+        let received = stream_manager.receive_control_message().await;
+        if let Some(reason) = stream_manager.control_sequence_violation() {
+            let stream_manager = Arc::clone(stream_manager);
+            if let Some(terminate) =
+                self.terminate_request(MoqErrorCode::ProtocolViolation.code(), reason)
+            {
+                let _ = stream_manager.send_control_message(terminate).await;
+            }
+        }
+        received
     }
 }
 
-/// MoQ Object as defined by IETF specification
-#[derive(Debug, Clone)]
-pub struct MoqObject {
-    /// Track namespace
-    pub track_namespace: TrackNamespace,
-    /// Track name
-    pub track_name: String,
-    /// Group ID (for video: timestamp, for audio: time window)
-    pub group_id: u64,
-    /// Object ID (for video: frame sequence, for audio: sample sequence)
-    pub object_id: u64,
-    /// Publisher priority (lower number = higher priority)
-    pub publisher_priority: u8,
-    /// Payload data (direct codec output)
-    pub payload: Vec<u8>,
-    /// Object status
-    pub object_status: MoqObjectStatus,
-    /// Object creation timestamp (for delivery ordering)
-    pub created_at: std::time::Instant,
-    /// Object size in bytes (for caching decisions)
-    pub size: usize,
+/// Matches tracks by namespace and track name prefix
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrackFilter {
+    /// Namespace that must match exactly
+    pub namespace: String,
+    /// Prefix the track name must start with (empty matches every track)
+    pub track_prefix: String,
 }
 
-/// MoQ object delivery status
-#[derive(Debug, Clone, PartialEq)]
-pub enum MoqObjectStatus {
-    /// Normal object
-    Normal,
-    /// Last object in group (e.g., end of video frame)
-    EndOfGroup,
-    /// Track is ending
-    EndOfTrack,
-}
+impl TrackFilter {
+    /// Match every track in `namespace`
+    pub fn namespace(namespace: impl Into<String>) -> Self {
+        Self {
+            namespace: namespace.into(),
+            track_prefix: String::new(),
+        }
+    }
 
-/// MoQ Track representation
-#[derive(Debug, Clone)]
-pub struct MoqTrack {
-    /// Track namespace
-    pub namespace: TrackNamespace,
-    /// Track name
-    pub name: String,
-    /// Track type
-    pub track_type: MoqTrackType,
-}
+    /// Only match track names starting with `prefix`
+    pub fn with_track_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.track_prefix = prefix.into();
+        self
+    }
 
-/// MoQ track types
-#[derive(Debug, Clone, PartialEq)]
-pub enum MoqTrackType {
-    /// Audio track
-    Audio,
-    /// Video track
-    Video,
-    /// Data track
-    Data,
+    /// Whether `track_namespace` matches this filter
+    pub fn matches(&self, track_namespace: &TrackNamespace) -> bool {
+        track_namespace.namespace == self.namespace
+            && track_namespace.track_name.starts_with(&self.track_prefix)
+    }
 }
 
-/// Track namespace following MoQ specification
-#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
-pub struct TrackNamespace {
-    /// Namespace (e.g., "conference.example.com")
-    pub namespace: String,
-    /// Track name (e.g., "alice/camera")
-    pub track_name: String,
+/// Kind of access a peer request asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrackAccess {
+    /// Announce the track, to publish it
+    Announce,
+    /// Subscribe to the track
+    Subscribe,
 }
 
-/// H.264 video frame for MoQ object creation
-#[derive(Debug, Clone)]
-pub struct H264Frame {
-    /// NAL units (direct H.264 output, no RTP headers)
-    pub nal_units: Vec<u8>,
-    /// Whether this is a keyframe (I-frame)
-    pub is_keyframe: bool,
-    /// Frame timestamp in microseconds
-    pub timestamp_us: u64,
-    /// Frame sequence number
-    pub sequence_number: u64,
+/// Per-track access control based on the token sent with each request
+///
+/// Set on the receiving side, typically a relay, with
+/// [`MoqSession::set_track_authorizer`]. Where [`MoqPermissions`] fix what
+/// a peer may do for the whole session, an authorizer decides per request
+/// from the token the peer presents, for example only letting moderators
+/// subscribe to the "stage" namespace. Rejected requests are answered with
+/// [`MoqErrorCode::Unauthorized`].
+///
+/// Namespace subscriptions carry no token, so they only cover tracks the
+/// authorizer grants without one.
+pub trait TrackAuthorizer: Send + Sync + std::fmt::Debug {
+    /// Whether the peer presenting `token` may have `access` to
+    /// `track_namespace`
+    ///
+    /// The error, usually [`QuicRtcError::Unauthorized`], is sent back to
+    /// the peer as the rejection reason.
+    fn authorize(
+        &self,
+        access: TrackAccess,
+        track_namespace: &TrackNamespace,
+        token: Option<&str>,
+    ) -> Result<(), QuicRtcError>;
 }
 
-/// Opus audio frame for MoQ object creation
-#[derive(Debug, Clone)]
-pub struct OpusFrame {
-    /// Opus encoded data (direct Opus output, no RTP headers)
-    pub opus_data: Vec<u8>,
-    /// Frame timestamp in microseconds
-    pub timestamp_us: u64,
-    /// Frame sequence number
-    pub sequence_number: u64,
-    /// Sample rate in Hz
-    pub sample_rate: u32,
-    /// Number of channels
-    pub channels: u8,
+/// What a peer may announce and subscribe to, enforced by the receiving side
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoqPermissions {
+    /// May announce tracks at all
+    pub can_publish: bool,
+    /// May subscribe to tracks at all
+    pub can_subscribe: bool,
+    /// Tracks that may be announced (empty = any)
+    pub publish_filters: Vec<TrackFilter>,
+    /// Tracks that may be subscribed to (empty = any)
+    pub subscribe_filters: Vec<TrackFilter>,
 }
 
-/// MoQ object delivery system over QUIC streams
-#[derive(Debug)]
-pub struct MoqObjectDelivery {
-    /// Pending objects waiting for delivery
-    pending_objects: std::collections::BinaryHeap<PrioritizedObject>,
-    /// Object cache for efficient delivery
-    object_cache: MoqObjectCache,
-    /// Delivery statistics
-    stats: MoqDeliveryStats,
+impl Default for MoqPermissions {
+    fn default() -> Self {
+        Self::allow_all()
+    }
 }
 
-/// Prioritized object wrapper for delivery ordering
-#[derive(Debug)]
-struct PrioritizedObject {
-    object: MoqObject,
-    priority: u8,
-    enqueue_time: std::time::Instant,
-}
+impl MoqPermissions {
+    /// Allow announcing and subscribing to any track
+    pub fn allow_all() -> Self {
+        Self {
+            can_publish: true,
+            can_subscribe: true,
+            publish_filters: Vec::new(),
+            subscribe_filters: Vec::new(),
+        }
+    }
+
+    /// Allow subscribing to any track but announcing none, e.g. for viewers
+    pub fn subscribe_only() -> Self {
+        Self {
+            can_publish: false,
+            ..Self::allow_all()
+        }
+    }
+
+    /// Only allow announcing tracks matching `filter`, in addition to any
+    /// filters already added
+    pub fn with_publish_filter(mut self, filter: TrackFilter) -> Self {
+        self.publish_filters.push(filter);
+        self
+    }
+
+    /// Only allow subscribing to tracks matching `filter`, in addition to
+    /// any filters already added
+    pub fn with_subscribe_filter(mut self, filter: TrackFilter) -> Self {
+        self.subscribe_filters.push(filter);
+        self
+    }
+
+    /// Whether the peer may announce `track_namespace`
+    pub fn allows_announce(&self, track_namespace: &TrackNamespace) -> bool {
+        self.can_publish && Self::matches_any(&self.publish_filters, track_namespace)
+    }
+
+    /// Whether the peer may subscribe to `track_namespace`
+    pub fn allows_subscribe(&self, track_namespace: &TrackNamespace) -> bool {
+        self.can_subscribe && Self::matches_any(&self.subscribe_filters, track_namespace)
+    }
+
+    fn matches_any(filters: &[TrackFilter], track_namespace: &TrackNamespace) -> bool {
+        filters.is_empty() || filters.iter().any(|f| f.matches(track_namespace))
+    }
+}
+
+/// MoQ object delivery system over QUIC streams
+#[derive(Debug)]
+pub struct MoqObjectDelivery {
+    /// Pending objects waiting for delivery
+    pending_objects: std::collections::BinaryHeap<PrioritizedObject>,
+    /// Object cache for efficient delivery
+    object_cache: MoqObjectCache,
+    /// Delivery statistics
+    stats: MoqDeliveryStats,
+    /// Bounds of each track's share of the queue
+    send_buffer: MoqSendBufferConfig,
+    /// Queued objects per track
+    track_depths: HashMap<TrackNamespace, usize>,
+    /// Tracks past the high-water mark, not yet drained to the low one
+    paused_tracks: HashSet<TrackNamespace>,
+    /// Told when a track crosses a watermark
+    watermark_callback: WatermarkCallback,
+    /// Enqueue order of the next object
+    next_sequence: u64,
+    /// Told about overflowing queues and late objects
+    backpressure: Option<BackpressureSignal>,
+}
+
+/// Prioritized object wrapper for delivery ordering
+#[derive(Debug)]
+struct PrioritizedObject {
+    object: MoqObject,
+    priority: u8,
+    enqueue_time: std::time::Instant,
+    /// Enqueue order, to find a track's oldest object
+    sequence: u64,
+}
+
+/// Bounds of the per-track send queue of a [`MoqObjectDelivery`]
+///
+/// Objects queue up while the network stalls. Once a track has
+/// `max_objects_per_track` objects queued, its oldest object is dropped for
+/// each new one; keyframes and end-of-group and end-of-track markers are
+/// never dropped. The watermarks let the encoder pause before dropping
+/// starts, see [`MoqObjectDelivery::set_watermark_callback`].
+#[derive(Debug, Clone)]
+pub struct MoqSendBufferConfig {
+    /// Maximum queued objects per track
+    pub max_objects_per_track: usize,
+    /// Fraction of `max_objects_per_track` at which the track is reported
+    /// as [`SendBufferWatermark::High`]
+    pub high_water_mark: f64,
+    /// Fraction of `max_objects_per_track` a reported track has to drain
+    /// to before it is reported as [`SendBufferWatermark::Low`]
+    pub low_water_mark: f64,
+}
+
+impl Default for MoqSendBufferConfig {
+    fn default() -> Self {
+        Self {
+            max_objects_per_track: 512,
+            high_water_mark: 0.75,
+            low_water_mark: 0.25,
+        }
+    }
+}
+
+/// Watermark a track's send queue crossed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendBufferWatermark {
+    /// Queue filled up to the high-water mark; pause encoding the track
+    High,
+    /// Queue drained to the low-water mark; resume encoding the track
+    Low,
+}
+
+/// Callback told when a track's send queue crosses a watermark
+pub type SendBufferWatermarkCallback =
+    Arc<dyn Fn(&TrackNamespace, SendBufferWatermark) + Send + Sync>;
+
+/// Optional [`SendBufferWatermarkCallback`]
+#[derive(Default)]
+struct WatermarkCallback(Option<SendBufferWatermarkCallback>);
+
+impl WatermarkCallback {
+    fn notify(&self, track_namespace: &TrackNamespace, watermark: SendBufferWatermark) {
+        if let Some(callback) = &self.0 {
+            callback(track_namespace, watermark);
+        }
+    }
+}
+
+impl std::fmt::Debug for WatermarkCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("WatermarkCallback")
+            .field(&self.0.is_some())
+            .finish()
+    }
+}
+
+/// Whether the send queue may drop an object to make room
+fn is_droppable(object: &MoqObject) -> bool {
+    !object.is_control_object() && !is_keyframe(object)
+}
+
+/// Whether an object is the keyframe starting its group
+///
+/// Keyframes are recognised as object 0 of a group carrying the video
+/// resolution header.
+fn is_keyframe(object: &MoqObject) -> bool {
+    object.object_id == 0
+        && object.object_status == MoqObjectStatus::Normal
+        && object.metadata.video_resolution().is_some()
+}
 
 impl PartialEq for PrioritizedObject {
     fn eq(&self, other: &Self) -> bool {
@@ -933,6 +1699,107 @@ pub struct MoqObjectCache {
     config: MoqCacheConfig,
     /// Cache statistics
     stats: MoqCacheStats,
+    /// Memory budget shared with other caches
+    budget: Option<MoqCacheBudget>,
+    /// Disk tier evicted objects spill to
+    spill: Option<MoqSpillCache>,
+    /// Latency budgets of tracks whose objects go stale
+    latency_budgets: HashMap<TrackNamespace, Duration>,
+}
+
+/// Memory budget shared by object caches, bounding their combined size
+///
+/// Every track holding cached bytes gets an equal fair share of the budget.
+/// When the budget is full, a cache makes room by evicting from its tracks
+/// that are over their share. A track under its share may always store, so
+/// a busy track cannot starve a quiet one; the overshoot is reclaimed from
+/// over-share tracks as the caches holding them store or clean up.
+#[derive(Debug, Clone)]
+pub struct MoqCacheBudget {
+    state: Arc<parking_lot::Mutex<CacheBudgetState>>,
+}
+
+/// Bytes charged against a [`MoqCacheBudget`]
+#[derive(Debug)]
+struct CacheBudgetState {
+    max_bytes: usize,
+    used_bytes: usize,
+    track_bytes: HashMap<TrackNamespace, usize>,
+}
+
+impl MoqCacheBudget {
+    /// Create a budget of `max_bytes` shared by all caches it is given to
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            state: Arc::new(parking_lot::Mutex::new(CacheBudgetState {
+                max_bytes,
+                used_bytes: 0,
+                track_bytes: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Budget size in bytes
+    pub fn max_bytes(&self) -> usize {
+        self.state.lock().max_bytes
+    }
+
+    /// Resize the budget; caches shrink into a smaller budget as they store
+    /// or clean up
+    pub fn set_max_bytes(&self, max_bytes: usize) {
+        self.state.lock().max_bytes = max_bytes;
+    }
+
+    /// Bytes cached across all caches sharing the budget
+    pub fn used_bytes(&self) -> usize {
+        self.state.lock().used_bytes
+    }
+
+    /// Bytes cached for one track across all caches sharing the budget
+    pub fn track_bytes(&self, track: &TrackNamespace) -> usize {
+        self.state
+            .lock()
+            .track_bytes
+            .get(track)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Fair share of the budget for each track currently holding bytes
+    pub fn fair_share_bytes(&self) -> usize {
+        let state = self.state.lock();
+        state.max_bytes / state.track_bytes.len().max(1)
+    }
+
+    /// Fair share of the budget once `track` holds bytes too
+    fn fair_share_with(&self, track: &TrackNamespace) -> usize {
+        let state = self.state.lock();
+        let tracks = state.track_bytes.len() + usize::from(!state.track_bytes.contains_key(track));
+        state.max_bytes / tracks.max(1)
+    }
+
+    /// Whether `bytes` more fit in the budget
+    fn fits(&self, bytes: usize) -> bool {
+        let state = self.state.lock();
+        state.used_bytes.saturating_add(bytes) <= state.max_bytes
+    }
+
+    fn charge(&self, track: &TrackNamespace, bytes: usize) {
+        let mut state = self.state.lock();
+        state.used_bytes += bytes;
+        *state.track_bytes.entry(track.clone()).or_insert(0) += bytes;
+    }
+
+    fn release(&self, track: &TrackNamespace, bytes: usize) {
+        let mut state = self.state.lock();
+        state.used_bytes = state.used_bytes.saturating_sub(bytes);
+        if let Some(track_bytes) = state.track_bytes.get_mut(track) {
+            *track_bytes = track_bytes.saturating_sub(bytes);
+            if *track_bytes == 0 {
+                state.track_bytes.remove(track);
+            }
+        }
+    }
 }
 
 /// Cached object with metadata
@@ -942,6 +1809,17 @@ struct CachedObject {
     access_count: u64,
     last_accessed: std::time::Instant,
     cache_time: std::time::Instant,
+    /// Pinned against eviction as the track's newest keyframe
+    pinned: bool,
+}
+
+impl CachedObject {
+    /// Whether the object was cached longer than `object_ttl` ago or, unless
+    /// pinned, is past its track's latency budget
+    fn is_expired(&self, object_ttl: Duration, latency_budget: Option<Duration>) -> bool {
+        self.cache_time.elapsed() > object_ttl
+            || (!self.pinned && self.object.exceeds_latency_budget(latency_budget))
+    }
 }
 
 /// MoQ cache configuration
@@ -953,8 +1831,19 @@ pub struct MoqCacheConfig {
     pub max_objects_per_track: usize,
     /// Object TTL in cache
     pub object_ttl: std::time::Duration,
-    /// Enable LRU eviction
+    /// Enable eviction; when disabled, storing into a full cache fails
     pub enable_lru_eviction: bool,
+    /// Order in which objects are evicted
+    pub eviction_policy: MoqEvictionPolicy,
+    /// Never evict the newest keyframe of each video track
+    ///
+    /// Keyframes are recognised as object 0 of a group carrying the video
+    /// resolution header. Pinned objects still expire after `object_ttl`.
+    pub pin_keyframes: bool,
+    /// Fraction of the size and per-track limits at which eviction starts
+    pub high_water_mark: f64,
+    /// Fraction of the size and per-track limits eviction frees space down to
+    pub low_water_mark: f64,
 }
 
 impl Default for MoqCacheConfig {
@@ -964,6 +1853,67 @@ impl Default for MoqCacheConfig {
             max_objects_per_track: 1000,
             object_ttl: std::time::Duration::from_secs(30),
             enable_lru_eviction: true,
+            eviction_policy: MoqEvictionPolicy::default(),
+            pin_keyframes: true,
+            high_water_mark: 0.9,
+            low_water_mark: 0.5,
+        }
+    }
+}
+
+impl MoqCacheConfig {
+    /// Cache size in bytes at which eviction starts
+    fn high_water_bytes(&self) -> usize {
+        water_mark(self.max_size_bytes, self.high_water_mark)
+    }
+
+    /// Cache size in bytes eviction frees space down to
+    fn low_water_bytes(&self) -> usize {
+        water_mark(self.max_size_bytes, self.low_water_mark)
+    }
+
+    /// Object count per track at which eviction starts
+    fn high_water_objects(&self) -> usize {
+        water_mark(self.max_objects_per_track, self.high_water_mark).max(1)
+    }
+
+    /// Object count per track eviction frees space down to
+    fn low_water_objects(&self) -> usize {
+        water_mark(self.max_objects_per_track, self.low_water_mark)
+    }
+}
+
+/// Scale a cache limit by a water mark fraction
+fn water_mark(limit: usize, fraction: f64) -> usize {
+    (limit as f64 * fraction.clamp(0.0, 1.0)) as usize
+}
+
+/// Order in which [`MoqObjectCache`] evicts objects when it is full
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MoqEvictionPolicy {
+    /// Least recently accessed objects first
+    #[default]
+    Lru,
+    /// Least frequently accessed objects first, least recently accessed
+    /// breaking ties
+    Lfu,
+    /// Lowest-priority objects (highest publisher priority value) first,
+    /// least recently accessed breaking ties
+    Priority,
+}
+
+impl MoqEvictionPolicy {
+    /// Compare two cached objects, the one to evict first ordering first
+    fn eviction_order(self, a: &CachedObject, b: &CachedObject) -> std::cmp::Ordering {
+        let by_recency = a.last_accessed.cmp(&b.last_accessed);
+        match self {
+            Self::Lru => by_recency,
+            Self::Lfu => a.access_count.cmp(&b.access_count).then(by_recency),
+            Self::Priority => b
+                .object
+                .publisher_priority
+                .cmp(&a.object.publisher_priority)
+                .then(by_recency),
         }
     }
 }
@@ -981,6 +1931,14 @@ pub struct MoqCacheStats {
     pub objects_evicted: u64,
     /// Current cache size in bytes
     pub current_size_bytes: usize,
+    /// Keyframes currently pinned against eviction
+    pub pinned_keyframes: usize,
+    /// Objects evicted to stay within the shared memory budget
+    pub budget_evictions: u64,
+    /// Evicted objects written to the disk tier
+    pub spilled_objects: u64,
+    /// Cache hits served from the disk tier (included in `cache_hits`)
+    pub disk_hits: u64,
 }
 
 /// MoQ delivery statistics
@@ -1005,20 +1963,117 @@ impl MoqObjectDelivery {
             pending_objects: std::collections::BinaryHeap::new(),
             object_cache: MoqObjectCache::new(cache_config),
             stats: MoqDeliveryStats::default(),
+            send_buffer: MoqSendBufferConfig::default(),
+            track_depths: HashMap::new(),
+            paused_tracks: HashSet::new(),
+            watermark_callback: WatermarkCallback::default(),
+            next_sequence: 0,
+            backpressure: None,
         }
     }
 
+    /// Bound each track's share of the queue by `config` instead of the default
+    pub fn with_send_buffer(mut self, config: MoqSendBufferConfig) -> Self {
+        self.send_buffer = config;
+        self
+    }
+
+    /// Tell `callback` when a track's queue reaches the high-water mark and
+    /// when it has drained to the low-water mark again
+    ///
+    /// Encoders pause on [`SendBufferWatermark::High`] and resume on
+    /// [`SendBufferWatermark::Low`], so a stalled connection doesn't make
+    /// the queue drop media that was just encoded.
+    pub fn set_watermark_callback(&mut self, callback: SendBufferWatermarkCallback) {
+        self.watermark_callback = WatermarkCallback(Some(callback));
+    }
+
+    /// Report tracks reaching the high-water mark and objects dropped from
+    /// full queues or past their latency budget to `backpressure`
+    ///
+    /// Usually the transport's [`crate::MoqOverQuicTransport::backpressure`],
+    /// so encoders hear about congestion in the queue and on the wire alike.
+    pub fn with_backpressure(mut self, backpressure: BackpressureSignal) -> Self {
+        self.backpressure = Some(backpressure);
+        self
+    }
+
+    /// Objects of a track waiting for delivery
+    pub fn track_queue_depth(&self, track_namespace: &TrackNamespace) -> usize {
+        self.track_depths.get(track_namespace).copied().unwrap_or(0)
+    }
+
+    /// Account the object cache against a memory budget shared with other caches
+    pub fn with_cache_budget(mut self, budget: MoqCacheBudget) -> Self {
+        self.object_cache = self.object_cache.with_budget(budget);
+        self
+    }
+
+    /// Drop objects of a track once they are older than `latency_budget`,
+    /// usually the track's [`MoqTrack::latency_budget`]
+    ///
+    /// Objects of tracks without a budget are delivered however late.
+    pub fn set_latency_budget(
+        &mut self,
+        track_namespace: &TrackNamespace,
+        latency_budget: Option<Duration>,
+    ) {
+        self.object_cache
+            .set_latency_budget(track_namespace, latency_budget);
+    }
+
     /// Enqueue object for delivery
+    ///
+    /// When the track's queue is full, its oldest object that is neither a
+    /// keyframe nor an end marker is dropped to make room, or `object`
+    /// itself if there is none.
     pub fn enqueue_object(&mut self, object: MoqObject) -> Result<(), QuicRtcError> {
+        let track_namespace = object.track_namespace.clone();
+        if self.track_queue_depth(&track_namespace) >= self.send_buffer.max_objects_per_track {
+            let oldest = self
+                .pending_objects
+                .iter()
+                .filter(|queued| {
+                    queued.object.track_namespace == track_namespace && is_droppable(&queued.object)
+                })
+                .map(|queued| queued.sequence)
+                .min();
+            match oldest {
+                Some(sequence) => {
+                    self.pending_objects
+                        .retain(|queued| queued.sequence != sequence);
+                    self.stats.objects_dropped += 1;
+                    self.dequeued(&track_namespace);
+                    self.report_backpressure(
+                        BackpressureReason::SendQueueOverflow,
+                        &track_namespace,
+                    );
+                }
+                None if is_droppable(&object) => {
+                    self.stats.objects_dropped += 1;
+                    self.report_backpressure(
+                        BackpressureReason::SendQueueOverflow,
+                        &track_namespace,
+                    );
+                    return Ok(());
+                }
+                // Keyframes and markers are queued over the bound
+                None => {}
+            }
+        }
+
         let priority = object.delivery_priority();
         let prioritized = PrioritizedObject {
             object: object.clone(),
             priority,
             enqueue_time: std::time::Instant::now(),
+            sequence: self.next_sequence,
         };
+        self.next_sequence += 1;
 
         self.pending_objects.push(prioritized);
         self.stats.queue_depth = self.pending_objects.len();
+        self.enqueued(&track_namespace);
 
         if self.stats.queue_depth > self.stats.peak_queue_depth {
             self.stats.peak_queue_depth = self.stats.queue_depth;
@@ -1031,19 +2086,29 @@ impl MoqObjectDelivery {
     }
 
     /// Dequeue next object for delivery (highest priority first)
+    ///
+    /// Objects past their track's latency budget are dropped on the way.
     pub fn dequeue_object(&mut self) -> Option<MoqObject> {
-        if let Some(prioritized) = self.pending_objects.pop() {
+        while let Some(prioritized) = self.pending_objects.pop() {
             self.stats.queue_depth = self.pending_objects.len();
+            self.dequeued(&prioritized.object.track_namespace);
+            if self.is_late(&prioritized.object) {
+                self.stats.objects_dropped += 1;
+                self.report_backpressure(
+                    BackpressureReason::LatencyBudgetExceeded,
+                    &prioritized.object.track_namespace,
+                );
+                continue;
+            }
 
             // Update delivery statistics
             let delivery_latency = prioritized.enqueue_time.elapsed();
             self.update_delivery_latency(delivery_latency.as_millis() as f64);
             self.stats.objects_delivered += 1;
 
-            Some(prioritized.object)
-        } else {
-            None
+            return Some(prioritized.object);
         }
+        None
     }
 
     /// Get object from cache
@@ -1066,6 +2131,7 @@ impl MoqObjectDelivery {
                 objects_to_keep.push(prioritized);
             } else {
                 self.stats.objects_dropped += 1;
+                self.dequeued(&prioritized.object.track_namespace);
             }
         }
 
@@ -1074,262 +2140,1019 @@ impl MoqObjectDelivery {
             self.pending_objects.push(obj);
         }
 
-        let dropped_count = original_len - self.pending_objects.len();
-        self.stats.queue_depth = self.pending_objects.len();
+        let dropped_count = original_len - self.pending_objects.len();
+        self.stats.queue_depth = self.pending_objects.len();
+
+        dropped_count
+    }
+
+    /// Get current delivery statistics
+    pub fn delivery_stats(&self) -> &MoqDeliveryStats {
+        &self.stats
+    }
+
+    /// Get cache statistics
+    pub fn cache_stats(&self) -> &MoqCacheStats {
+        self.object_cache.stats()
+    }
+
+    /// Clear objects past their track's latency budget from the queue, and
+    /// expired objects from the cache
+    pub fn cleanup_expired(&mut self) {
+        // Clean up expired objects from queue
+        let mut objects_to_keep = Vec::new();
+        while let Some(prioritized) = self.pending_objects.pop() {
+            if !self.is_late(&prioritized.object) {
+                objects_to_keep.push(prioritized);
+            } else {
+                self.stats.objects_dropped += 1;
+                self.dequeued(&prioritized.object.track_namespace);
+                self.report_backpressure(
+                    BackpressureReason::LatencyBudgetExceeded,
+                    &prioritized.object.track_namespace,
+                );
+            }
+        }
+
+        // Re-add non-expired objects
+        for obj in objects_to_keep {
+            self.pending_objects.push(obj);
+        }
+
+        self.stats.queue_depth = self.pending_objects.len();
+
+        // Clean up cache
+        self.object_cache.cleanup_expired();
+    }
+
+    /// Count an object of a track into the queue, reporting the track once
+    /// it reaches the high-water mark
+    fn enqueued(&mut self, track_namespace: &TrackNamespace) {
+        let depth = self
+            .track_depths
+            .entry(track_namespace.clone())
+            .or_insert(0);
+        *depth += 1;
+        let high_water = water_mark(
+            self.send_buffer.max_objects_per_track,
+            self.send_buffer.high_water_mark,
+        )
+        .max(1);
+        if *depth >= high_water && self.paused_tracks.insert(track_namespace.clone()) {
+            self.watermark_callback
+                .notify(track_namespace, SendBufferWatermark::High);
+            self.report_backpressure(BackpressureReason::SendQueueHighWater, track_namespace);
+        }
+    }
+
+    /// Count an object of a track out of the queue, reporting a reported
+    /// track again once it drains to the low-water mark
+    fn dequeued(&mut self, track_namespace: &TrackNamespace) {
+        let depth = match self.track_depths.get_mut(track_namespace) {
+            Some(depth) => {
+                *depth = depth.saturating_sub(1);
+                *depth
+            }
+            None => 0,
+        };
+        if depth == 0 {
+            self.track_depths.remove(track_namespace);
+        }
+        let low_water = water_mark(
+            self.send_buffer.max_objects_per_track,
+            self.send_buffer.low_water_mark,
+        );
+        if depth <= low_water && self.paused_tracks.remove(track_namespace) {
+            self.watermark_callback
+                .notify(track_namespace, SendBufferWatermark::Low);
+        }
+    }
+
+    /// Count a congestion incident of a track towards backpressure
+    fn report_backpressure(&self, reason: BackpressureReason, track_namespace: &TrackNamespace) {
+        if let Some(backpressure) = &self.backpressure {
+            backpressure.report(reason, Some(track_namespace));
+        }
+    }
+
+    /// Whether an object is past its track's latency budget
+    fn is_late(&self, object: &MoqObject) -> bool {
+        object.exceeds_latency_budget(self.object_cache.latency_budget(&object.track_namespace))
+    }
+
+    fn update_delivery_latency(&mut self, latency_ms: f64) {
+        // Simple exponential moving average
+        let alpha = 0.1;
+        if self.stats.avg_delivery_latency_ms == 0.0 {
+            self.stats.avg_delivery_latency_ms = latency_ms;
+        } else {
+            self.stats.avg_delivery_latency_ms =
+                alpha * latency_ms + (1.0 - alpha) * self.stats.avg_delivery_latency_ms;
+        }
+    }
+}
+
+impl MoqObjectCache {
+    /// Create new object cache
+    pub fn new(config: MoqCacheConfig) -> Self {
+        Self {
+            objects: HashMap::new(),
+            config,
+            stats: MoqCacheStats::default(),
+            budget: None,
+            spill: None,
+            latency_budgets: HashMap::new(),
+        }
+    }
+
+    /// Spill evicted objects to a disk tier instead of dropping them
+    ///
+    /// Objects that expire are not spilled. A spilled object is moved back
+    /// into memory when it is next accessed.
+    pub fn with_spill(mut self, spill: MoqSpillCache) -> Self {
+        self.spill = Some(spill);
+        self
+    }
+
+    /// Disk tier evicted objects spill to, if any
+    pub fn spill(&self) -> Option<&MoqSpillCache> {
+        self.spill.as_ref()
+    }
+
+    /// Account this cache against a memory budget shared with other caches
+    pub fn with_budget(mut self, budget: MoqCacheBudget) -> Self {
+        if let Some(previous) = self.budget.take() {
+            self.release_budget(&previous);
+        }
+        for (track_namespace, track_objects) in &self.objects {
+            let bytes = track_objects
+                .values()
+                .map(|cached| cached.object.size)
+                .sum();
+            budget.charge(track_namespace, bytes);
+        }
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Shared memory budget this cache is accounted against, if any
+    pub fn budget(&self) -> Option<&MoqCacheBudget> {
+        self.budget.as_ref()
+    }
+
+    /// Expire objects of a track once they are older than `latency_budget`,
+    /// as well as after `object_ttl`
+    ///
+    /// Retransmitting an object past its track's budget would only deliver
+    /// it too late to be played. Pinned keyframes, kept for subscribers
+    /// joining later, only expire after `object_ttl`.
+    pub fn set_latency_budget(
+        &mut self,
+        track_namespace: &TrackNamespace,
+        latency_budget: Option<Duration>,
+    ) {
+        match latency_budget {
+            Some(budget) => {
+                self.latency_budgets.insert(track_namespace.clone(), budget);
+            }
+            None => {
+                self.latency_budgets.remove(track_namespace);
+            }
+        }
+    }
+
+    /// Latency budget of a track, `None` when its objects don't go stale
+    pub fn latency_budget(&self, track_namespace: &TrackNamespace) -> Option<Duration> {
+        self.latency_budgets.get(track_namespace).copied()
+    }
+
+    /// Store object in cache
+    pub fn store_object(&mut self, object: MoqObject) -> Result<(), QuicRtcError> {
+        // A stored copy of the same object is replaced rather than added to
+        self.remove_object(&object.track_namespace, object.object_id);
+
+        // Check cache size limits
+        if self.stats.current_size_bytes + object.size > self.config.high_water_bytes() {
+            self.evict_objects()?;
+        }
+
+        // Check per-track object limit before getting mutable reference
+        let needs_track_eviction =
+            if let Some(track_objects) = self.objects.get(&object.track_namespace) {
+                track_objects.len() >= self.config.high_water_objects()
+            } else {
+                false
+            };
+
+        if needs_track_eviction {
+            self.evict_track_objects(&object.track_namespace)?;
+        }
+
+        if let Some(budget) = self.budget.clone() {
+            let track_namespace = &object.track_namespace;
+            let fits = budget.fits(object.size)
+                || (self.config.enable_lru_eviction
+                    && self.reclaim_budget(&budget, Some(track_namespace), object.size));
+            // Tracks under their fair share store anyway so they are never starved
+            if !fits
+                && budget.track_bytes(track_namespace) + object.size
+                    > budget.fair_share_with(track_namespace)
+            {
+                return Err(QuicRtcError::CacheFull {
+                    current_size: budget.used_bytes(),
+                    max_size: budget.max_bytes(),
+                });
+            }
+        }
+
+        let pinned = self.is_pinned_keyframe(&object);
+        let track_objects = self
+            .objects
+            .entry(object.track_namespace.clone())
+            .or_insert_with(HashMap::new);
+
+        let cached_object = CachedObject {
+            object: object.clone(),
+            access_count: 0,
+            last_accessed: std::time::Instant::now(),
+            cache_time: std::time::Instant::now(),
+            pinned,
+        };
+
+        track_objects.insert(object.object_id, cached_object);
+        self.stats.total_objects += 1;
+        self.stats.current_size_bytes += object.size;
+        if pinned {
+            self.stats.pinned_keyframes += 1;
+        }
+        if let Some(budget) = &self.budget {
+            budget.charge(&object.track_namespace, object.size);
+        }
+
+        Ok(())
+    }
+
+    /// Get object from cache
+    pub fn get_object(
+        &mut self,
+        track_namespace: &TrackNamespace,
+        object_id: u64,
+    ) -> Option<MoqObject> {
+        if let Some(track_objects) = self.objects.get_mut(track_namespace) {
+            if let Some(cached_object) = track_objects.get_mut(&object_id) {
+                // Check if object has expired
+                let latency_budget = self.latency_budgets.get(track_namespace).copied();
+                if cached_object.is_expired(self.config.object_ttl, latency_budget) {
+                    self.remove_object(track_namespace, object_id);
+                    self.stats.cache_misses += 1;
+                    return None;
+                }
+
+                // Update access statistics
+                cached_object.access_count += 1;
+                cached_object.last_accessed = std::time::Instant::now();
+                self.stats.cache_hits += 1;
+
+                return Some(cached_object.object.clone());
+            }
+        }
+
+        // Cold objects come back from the disk tier into memory
+        if let Some(object) = self
+            .spill
+            .as_mut()
+            .and_then(|spill| spill.take(track_namespace, object_id))
+        {
+            self.stats.cache_hits += 1;
+            self.stats.disk_hits += 1;
+            if let Err(e) = self.store_object(object.clone()) {
+                tracing::debug!("Spilled object {} not promoted: {}", object_id, e);
+            }
+            return Some(object);
+        }
+
+        self.stats.cache_misses += 1;
+        None
+    }
+
+    /// Get cache statistics
+    pub fn stats(&self) -> &MoqCacheStats {
+        &self.stats
+    }
+
+    /// Clean up expired objects
+    pub fn cleanup_expired(&mut self) {
+        let mut expired_objects = Vec::new();
+
+        for (track_namespace, track_objects) in &self.objects {
+            let latency_budget = self.latency_budget(track_namespace);
+            for (object_id, cached_object) in track_objects {
+                if cached_object.is_expired(self.config.object_ttl, latency_budget) {
+                    expired_objects.push((track_namespace.clone(), *object_id));
+                }
+            }
+        }
+
+        for (track_namespace, object_id) in expired_objects {
+            if self.remove_object(&track_namespace, object_id).is_some() {
+                self.stats.objects_evicted += 1;
+            }
+        }
+
+        // Give back budget other caches' tracks were promised
+        if let Some(budget) = self.budget.clone() {
+            if self.config.enable_lru_eviction {
+                self.reclaim_budget(&budget, None, 0);
+            }
+        }
+    }
+
+    /// Evict objects to free space
+    fn evict_objects(&mut self) -> Result<(), QuicRtcError> {
+        if !self.config.enable_lru_eviction {
+            return Err(QuicRtcError::CacheFull {
+                current_size: self.stats.current_size_bytes,
+                max_size: self.config.max_size_bytes,
+            });
+        }
+
+        let candidates = self.eviction_candidates(None);
+
+        // Evict in policy order until we are down to the low-water mark
+        let target_size = self.config.low_water_bytes();
+        for (track_namespace, object_id) in candidates {
+            if self.stats.current_size_bytes <= target_size {
+                break;
+            }
+            self.evict_object(&track_namespace, object_id);
+        }
+
+        Ok(())
+    }
+
+    /// Evict objects from a specific track
+    fn evict_track_objects(
+        &mut self,
+        track_namespace: &TrackNamespace,
+    ) -> Result<(), QuicRtcError> {
+        let Some(track_objects) = self.objects.get(track_namespace) else {
+            return Ok(());
+        };
+        if !self.config.enable_lru_eviction {
+            return Err(QuicRtcError::TrackCacheFull {
+                track_name: track_namespace.track_name.clone(),
+                current_objects: track_objects.len(),
+                max_objects: self.config.max_objects_per_track,
+            });
+        }
+
+        let candidates = self.eviction_candidates(Some(track_namespace));
+
+        // Evict in policy order until the track is down to the low-water mark
+        let target_count = self.config.low_water_objects();
+        for (track_namespace, object_id) in candidates {
+            let current_count = self.objects.get(&track_namespace).map_or(0, HashMap::len);
+            if current_count <= target_count {
+                break;
+            }
+            self.evict_object(&track_namespace, object_id);
+        }
+
+        Ok(())
+    }
+
+    /// Unpinned objects, of one track or all, in the order the eviction
+    /// policy evicts them
+    fn eviction_candidates(&self, track: Option<&TrackNamespace>) -> Vec<(TrackNamespace, u64)> {
+        let mut candidates: Vec<(&TrackNamespace, u64, &CachedObject)> = self
+            .objects
+            .iter()
+            .filter(|(track_namespace, _)| track.is_none_or(|track| track == *track_namespace))
+            .flat_map(|(track_namespace, track_objects)| {
+                track_objects
+                    .iter()
+                    .map(move |(object_id, cached)| (track_namespace, *object_id, cached))
+            })
+            .filter(|(_, _, cached)| !cached.pinned)
+            .collect();
+
+        let policy = self.config.eviction_policy;
+        candidates.sort_by(|(_, _, a), (_, _, b)| policy.eviction_order(a, b));
+        candidates
+            .into_iter()
+            .map(|(track_namespace, object_id, _)| (track_namespace.clone(), object_id))
+            .collect()
+    }
+
+    /// Evict from this cache's tracks that are over their fair share of the
+    /// budget until `incoming` more bytes fit; returns whether they do
+    ///
+    /// `track` is the track the incoming bytes are for, counted as holding
+    /// them when working out shares.
+    fn reclaim_budget(
+        &mut self,
+        budget: &MoqCacheBudget,
+        track: Option<&TrackNamespace>,
+        incoming: usize,
+    ) -> bool {
+        loop {
+            if budget.fits(incoming) {
+                return true;
+            }
+
+            let fair_share = match track {
+                Some(track) => budget.fair_share_with(track),
+                None => budget.fair_share_bytes(),
+            };
+            let held = |victim: &TrackNamespace| {
+                budget.track_bytes(victim) + if Some(victim) == track { incoming } else { 0 }
+            };
+            let mut over_share: Vec<(usize, TrackNamespace)> = self
+                .objects
+                .keys()
+                .map(|victim| (held(victim), victim.clone()))
+                .filter(|(bytes, _)| *bytes > fair_share)
+                .collect();
+            // Furthest over its share first
+            over_share.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+            let mut evicted = false;
+            for (_, victim) in over_share {
+                for (track_namespace, object_id) in self.eviction_candidates(Some(&victim)) {
+                    if budget.fits(incoming) || held(&victim) <= fair_share {
+                        break;
+                    }
+                    if self.evict_object(&track_namespace, object_id) {
+                        self.stats.budget_evictions += 1;
+                        evicted = true;
+                    }
+                }
+                if evicted {
+                    // Shares change as tracks empty out
+                    break;
+                }
+            }
+
+            if !evicted {
+                return false;
+            }
+        }
+    }
+
+    /// Evict one object, spilling it to the disk tier if there is one
+    fn evict_object(&mut self, track_namespace: &TrackNamespace, object_id: u64) -> bool {
+        let Some(object) = self.remove_object(track_namespace, object_id) else {
+            return false;
+        };
+        self.stats.objects_evicted += 1;
+        if self
+            .spill
+            .as_mut()
+            .is_some_and(|spill| spill.store(&object))
+        {
+            self.stats.spilled_objects += 1;
+        }
+        true
+    }
+
+    /// Give every byte this cache holds back to `budget`
+    fn release_budget(&self, budget: &MoqCacheBudget) {
+        for (track_namespace, track_objects) in &self.objects {
+            let bytes = track_objects
+                .values()
+                .map(|cached| cached.object.size)
+                .sum();
+            budget.release(track_namespace, bytes);
+        }
+    }
+
+    /// Whether an object is a keyframe to pin against eviction
+    fn is_pinned_keyframe(&self, object: &MoqObject) -> bool {
+        self.config.pin_keyframes && is_keyframe(object)
+    }
+
+    /// Remove one object, keeping the size and pin accounting in step
+    fn remove_object(
+        &mut self,
+        track_namespace: &TrackNamespace,
+        object_id: u64,
+    ) -> Option<MoqObject> {
+        let track_objects = self.objects.get_mut(track_namespace)?;
+        let cached = track_objects.remove(&object_id)?;
+        if track_objects.is_empty() {
+            self.objects.remove(track_namespace);
+        }
+        self.stats.current_size_bytes -= cached.object.size;
+        if cached.pinned {
+            self.stats.pinned_keyframes -= 1;
+        }
+        if let Some(budget) = &self.budget {
+            budget.release(track_namespace, cached.object.size);
+        }
+        Some(cached.object)
+    }
+}
+
+impl Drop for MoqObjectCache {
+    fn drop(&mut self) {
+        if let Some(budget) = self.budget.take() {
+            self.release_budget(&budget);
+        }
+    }
+}
+
+/// Per-track cache of the newest keyframe group, for fast start of late subscribers
+///
+/// Every group starts with a keyframe at object 0, so the objects of the
+/// newest group seen from its first object let a new subscriber start
+/// decoding immediately instead of waiting for the next keyframe.
+#[derive(Debug, Clone)]
+pub struct KeyframeCache {
+    /// Newest complete-from-the-start group per track
+    groups: HashMap<TrackNamespace, CachedGroup>,
+    /// Groups larger than this are not cached
+    max_group_bytes: usize,
+}
+
+/// Objects of one group, in send order
+#[derive(Debug, Clone)]
+struct CachedGroup {
+    group_id: u64,
+    objects: Vec<MoqObject>,
+    size: usize,
+}
+
+impl Default for KeyframeCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KeyframeCache {
+    /// Default limit on the size of a cached group
+    pub const DEFAULT_MAX_GROUP_BYTES: usize = 4 * 1024 * 1024; // 4MB
+
+    /// Create an empty cache
+    pub fn new() -> Self {
+        Self {
+            groups: HashMap::new(),
+            max_group_bytes: Self::DEFAULT_MAX_GROUP_BYTES,
+        }
+    }
+
+    /// Limit the size of a cached group; larger groups are dropped
+    pub fn with_max_group_bytes(mut self, max_group_bytes: usize) -> Self {
+        self.max_group_bytes = max_group_bytes;
+        self
+    }
+
+    /// Record an outgoing object; returns whether it was cached
+    ///
+    /// Object 0 of a group replaces the track's cached group. Later objects
+    /// of that group are appended. Objects of a newer group whose keyframe
+    /// was never seen invalidate the cache, since they can't be decoded
+    /// from the cached keyframe.
+    pub fn store(&mut self, object: &MoqObject) -> bool {
+        let track = &object.track_namespace;
+
+        if object.object_status == MoqObjectStatus::EndOfTrack {
+            self.groups.remove(track);
+            return false;
+        }
+
+        if object.object_id == 0 && object.object_status == MoqObjectStatus::Normal {
+            let stale = self
+                .groups
+                .get(track)
+                .is_some_and(|group| group.group_id > object.group_id);
+            if stale || object.size > self.max_group_bytes {
+                return false;
+            }
+            self.groups.insert(
+                track.clone(),
+                CachedGroup {
+                    group_id: object.group_id,
+                    objects: vec![object.clone()],
+                    size: object.size,
+                },
+            );
+            return true;
+        }
+
+        let Some(group) = self.groups.get_mut(track) else {
+            return false;
+        };
+        if object.group_id > group.group_id {
+            self.groups.remove(track);
+            return false;
+        }
+        if object.group_id < group.group_id {
+            return false;
+        }
+        if group.size + object.size > self.max_group_bytes {
+            self.groups.remove(track);
+            return false;
+        }
 
-        dropped_count
+        group.size += object.size;
+        group.objects.push(object.clone());
+        true
     }
 
-    /// Get current delivery statistics
-    pub fn delivery_stats(&self) -> &MoqDeliveryStats {
-        &self.stats
+    /// Cached group for a track, keyframe first
+    pub fn group(&self, track_namespace: &TrackNamespace) -> Option<&[MoqObject]> {
+        self.groups
+            .get(track_namespace)
+            .map(|group| group.objects.as_slice())
     }
 
-    /// Get cache statistics
-    pub fn cache_stats(&self) -> &MoqCacheStats {
-        self.object_cache.stats()
+    /// ID of the cached group for a track
+    pub fn group_id(&self, track_namespace: &TrackNamespace) -> Option<u64> {
+        self.groups.get(track_namespace).map(|group| group.group_id)
     }
 
-    /// Clear expired objects from queue and cache
-    pub fn cleanup_expired(&mut self, max_age: std::time::Duration) {
-        // Clean up expired objects from queue
-        let mut objects_to_keep = Vec::new();
-        while let Some(prioritized) = self.pending_objects.pop() {
-            if prioritized.object.age() <= max_age {
-                objects_to_keep.push(prioritized);
-            } else {
-                self.stats.objects_dropped += 1;
-            }
-        }
+    /// Forget a track's cached group
+    pub fn remove_track(&mut self, track_namespace: &TrackNamespace) {
+        self.groups.remove(track_namespace);
+    }
 
-        // Re-add non-expired objects
-        for obj in objects_to_keep {
-            self.pending_objects.push(obj);
-        }
+    /// Forget all cached groups
+    pub fn clear(&mut self) {
+        self.groups.clear();
+    }
+}
 
-        self.stats.queue_depth = self.pending_objects.len();
+/// Time-shift buffer for one live track, for pausing and seeking back
+///
+/// Received objects are kept for the retention window and played out with a
+/// delay behind the live edge. Pausing grows the delay; seeking sets it,
+/// snapping to the start of a group so playout resumes on a keyframe. If the
+/// playout position falls out of the window it jumps to the oldest group
+/// still buffered.
+#[derive(Debug, Clone)]
+pub struct DvrBuffer {
+    /// How long received objects are kept
+    retention: Duration,
+    /// Buffered objects, oldest first
+    entries: VecDeque<DvrEntry>,
+    /// Sequence number of the oldest buffered object
+    first_seq: u64,
+    /// Sequence number of the next object to play
+    cursor: u64,
+    /// How far playout lags the live edge, excluding a current pause
+    delay: Duration,
+    /// When playout was paused
+    paused_at: Option<Instant>,
+}
 
-        // Clean up cache
-        self.object_cache.cleanup_expired();
-    }
+/// A buffered object and when it arrived
+#[derive(Debug, Clone)]
+struct DvrEntry {
+    received_at: Instant,
+    object: MoqObject,
+}
 
-    fn update_delivery_latency(&mut self, latency_ms: f64) {
-        // Simple exponential moving average
-        let alpha = 0.1;
-        if self.stats.avg_delivery_latency_ms == 0.0 {
-            self.stats.avg_delivery_latency_ms = latency_ms;
-        } else {
-            self.stats.avg_delivery_latency_ms =
-                alpha * latency_ms + (1.0 - alpha) * self.stats.avg_delivery_latency_ms;
-        }
+impl DvrEntry {
+    /// Whether the object starts a group, i.e. is a keyframe
+    fn starts_group(&self) -> bool {
+        self.object.object_id == 0 && self.object.object_status == MoqObjectStatus::Normal
     }
 }
 
-impl MoqObjectCache {
-    /// Create new object cache
-    pub fn new(config: MoqCacheConfig) -> Self {
+impl DvrBuffer {
+    /// Create an empty buffer playing live
+    pub fn new(retention: Duration) -> Self {
         Self {
-            objects: HashMap::new(),
-            config,
-            stats: MoqCacheStats::default(),
+            retention,
+            entries: VecDeque::new(),
+            first_seq: 0,
+            cursor: 0,
+            delay: Duration::ZERO,
+            paused_at: None,
         }
     }
 
-    /// Store object in cache
-    pub fn store_object(&mut self, object: MoqObject) -> Result<(), QuicRtcError> {
-        // Check cache size limits
-        if self.stats.current_size_bytes + object.size > self.config.max_size_bytes {
-            self.evict_objects()?;
-        }
-
-        // Check per-track object limit before getting mutable reference
-        let needs_track_eviction =
-            if let Some(track_objects) = self.objects.get(&object.track_namespace) {
-                track_objects.len() >= self.config.max_objects_per_track
-            } else {
-                false
-            };
+    /// How long received objects are kept
+    pub fn retention(&self) -> Duration {
+        self.retention
+    }
 
-        if needs_track_eviction {
-            self.evict_track_objects(&object.track_namespace)?;
-        }
+    /// Number of buffered objects
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
 
-        let track_objects = self
-            .objects
-            .entry(object.track_namespace.clone())
-            .or_insert_with(HashMap::new);
+    /// Whether no objects are buffered
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
 
-        let cached_object = CachedObject {
-            object: object.clone(),
-            access_count: 0,
-            last_accessed: std::time::Instant::now(),
-            cache_time: std::time::Instant::now(),
-        };
+    /// Whether playout is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
 
-        track_objects.insert(object.object_id, cached_object);
-        self.stats.total_objects += 1;
-        self.stats.current_size_bytes += object.size;
+    /// How far playout lags the live edge
+    pub fn time_shift(&self, now: Instant) -> Duration {
+        let paused = self.paused_at.map_or(Duration::ZERO, |paused_at| {
+            now.saturating_duration_since(paused_at)
+        });
+        self.delay + paused
+    }
 
-        Ok(())
+    /// Buffer a received object and drop objects older than the retention
+    pub fn push(&mut self, object: MoqObject, now: Instant) {
+        self.entries.push_back(DvrEntry {
+            received_at: now,
+            object,
+        });
+        self.evict(now);
     }
 
-    /// Get object from cache
-    pub fn get_object(
-        &mut self,
-        track_namespace: &TrackNamespace,
-        object_id: u64,
-    ) -> Option<MoqObject> {
-        if let Some(track_objects) = self.objects.get_mut(track_namespace) {
-            if let Some(cached_object) = track_objects.get_mut(&object_id) {
-                // Check if object has expired
-                if cached_object.cache_time.elapsed() > self.config.object_ttl {
-                    self.stats.current_size_bytes -= cached_object.object.size;
-                    track_objects.remove(&object_id);
-                    self.stats.cache_misses += 1;
-                    return None;
-                }
+    /// Next object due for playout, if any
+    pub fn next_due(&mut self, now: Instant) -> Option<MoqObject> {
+        if self.is_paused() {
+            return None;
+        }
+        let entry = self.entries.get((self.cursor - self.first_seq) as usize)?;
+        if entry.received_at + self.delay > now {
+            return None;
+        }
+        self.cursor += 1;
+        Some(entry.object.clone())
+    }
 
-                // Update access statistics
-                cached_object.access_count += 1;
-                cached_object.last_accessed = std::time::Instant::now();
-                self.stats.cache_hits += 1;
+    /// Stop playout; objects keep being buffered
+    pub fn pause(&mut self, now: Instant) {
+        if self.paused_at.is_none() {
+            self.paused_at = Some(now);
+        }
+    }
 
-                return Some(cached_object.object.clone());
-            }
+    /// Continue playout from where it was paused
+    pub fn resume(&mut self, now: Instant) {
+        if let Some(paused_at) = self.paused_at.take() {
+            self.delay += now.saturating_duration_since(paused_at);
         }
+        self.evict(now);
+    }
 
-        self.stats.cache_misses += 1;
-        None
+    /// Move playout to `behind` the live edge, returning the actual time shift
+    ///
+    /// Playout starts at the newest group that began at or before the
+    /// requested point, or the oldest buffered group if the request reaches
+    /// past the retention window. A paused buffer stays paused.
+    pub fn seek(&mut self, behind: Duration, now: Instant) -> Duration {
+        let target = now.checked_sub(behind);
+        let start = self
+            .entries
+            .iter()
+            .rposition(|entry| {
+                entry.starts_group() && target.is_some_and(|target| entry.received_at <= target)
+            })
+            .or_else(|| self.entries.iter().position(DvrEntry::starts_group));
+
+        match start {
+            Some(index) => self.play_from(index, now),
+            None => self.go_live(),
+        }
+        self.time_shift(now)
     }
 
-    /// Get cache statistics
-    pub fn stats(&self) -> &MoqCacheStats {
-        &self.stats
+    /// Return to live playout, starting at the newest group
+    pub fn go_live(&mut self) {
+        let newest_group = self.entries.iter().rposition(DvrEntry::starts_group);
+        self.cursor = self.first_seq + newest_group.unwrap_or(self.entries.len()) as u64;
+        self.delay = Duration::ZERO;
+        self.paused_at = None;
     }
 
-    /// Clean up expired objects
-    pub fn cleanup_expired(&mut self) {
-        let mut expired_objects = Vec::new();
+    /// Play from the buffered object at `index`, keeping the pause state
+    fn play_from(&mut self, index: usize, now: Instant) {
+        self.cursor = self.first_seq + index as u64;
+        self.delay = now.saturating_duration_since(self.entries[index].received_at);
+        if self.paused_at.is_some() {
+            self.paused_at = Some(now);
+        }
+    }
 
-        for (track_namespace, track_objects) in &self.objects {
-            for (object_id, cached_object) in track_objects {
-                if cached_object.cache_time.elapsed() > self.config.object_ttl {
-                    expired_objects.push((
-                        track_namespace.clone(),
-                        *object_id,
-                        cached_object.object.size,
-                    ));
-                }
-            }
+    /// Drop objects older than the retention window
+    fn evict(&mut self, now: Instant) {
+        let Some(cutoff) = now.checked_sub(self.retention) else {
+            return;
+        };
+        while self
+            .entries
+            .front()
+            .is_some_and(|entry| entry.received_at < cutoff)
+        {
+            self.entries.pop_front();
+            self.first_seq += 1;
         }
 
-        for (track_namespace, object_id, size) in expired_objects {
-            if let Some(track_objects) = self.objects.get_mut(&track_namespace) {
-                track_objects.remove(&object_id);
-                self.stats.current_size_bytes -= size;
-                self.stats.objects_evicted += 1;
+        if self.cursor < self.first_seq {
+            match self.entries.iter().position(DvrEntry::starts_group) {
+                Some(index) => self.play_from(index, now),
+                None => self.go_live(),
             }
         }
-
-        // Remove empty track entries
-        self.objects
-            .retain(|_, track_objects| !track_objects.is_empty());
     }
+}
 
-    /// Evict objects to free space
-    fn evict_objects(&mut self) -> Result<(), QuicRtcError> {
-        if !self.config.enable_lru_eviction {
-            return Err(QuicRtcError::CacheFull {
-                current_size: self.stats.current_size_bytes,
-                max_size: self.config.max_size_bytes,
-            });
-        }
+/// Shared control of a track's [`DvrBuffer`]
+///
+/// The transport fills the buffer as objects arrive; clones of the handle
+/// pause, resume and seek it.
+#[derive(Debug, Clone)]
+pub struct DvrHandle {
+    buffer: Arc<RwLock<DvrBuffer>>,
+}
 
-        // Collect all objects with their last access time
-        let mut all_objects = Vec::new();
-        for (track_namespace, track_objects) in &self.objects {
-            for (object_id, cached_object) in track_objects {
-                all_objects.push((
-                    track_namespace.clone(),
-                    *object_id,
-                    cached_object.last_accessed,
-                    cached_object.object.size,
-                ));
-            }
+impl DvrHandle {
+    /// Wrap a buffer for sharing
+    pub fn new(buffer: DvrBuffer) -> Self {
+        Self {
+            buffer: Arc::new(RwLock::new(buffer)),
         }
+    }
 
-        // Sort by last accessed time (oldest first)
-        all_objects.sort_by_key(|(_, _, last_accessed, _)| *last_accessed);
+    /// How long received objects are kept
+    pub fn retention(&self) -> Duration {
+        self.buffer.read().retention()
+    }
 
-        // Evict oldest objects until we have enough space
-        let target_size = self.config.max_size_bytes / 2; // Free up to 50% of cache
-        let mut current_size = self.stats.current_size_bytes;
+    /// Whether playout is paused
+    pub fn is_paused(&self) -> bool {
+        self.buffer.read().is_paused()
+    }
 
-        for (track_namespace, object_id, _, size) in all_objects {
-            if current_size <= target_size {
-                break;
-            }
+    /// How far playout lags the live edge
+    pub fn time_shift(&self) -> Duration {
+        self.buffer.read().time_shift(Instant::now())
+    }
 
-            if let Some(track_objects) = self.objects.get_mut(&track_namespace) {
-                track_objects.remove(&object_id);
-                current_size -= size;
-                self.stats.objects_evicted += 1;
-            }
-        }
+    /// Stop playout; objects keep being buffered
+    pub fn pause(&self) {
+        self.buffer.write().pause(Instant::now());
+    }
 
-        self.stats.current_size_bytes = current_size;
+    /// Continue playout from where it was paused
+    pub fn resume(&self) {
+        self.buffer.write().resume(Instant::now());
+    }
 
-        // Remove empty track entries
-        self.objects
-            .retain(|_, track_objects| !track_objects.is_empty());
+    /// Move playout to `behind` the live edge; see [`DvrBuffer::seek`]
+    pub fn seek(&self, behind: Duration) -> Duration {
+        self.buffer.write().seek(behind, Instant::now())
+    }
 
-        Ok(())
+    /// Return to live playout
+    pub fn go_live(&self) {
+        self.buffer.write().go_live();
     }
 
-    /// Evict objects from a specific track
-    fn evict_track_objects(
-        &mut self,
-        track_namespace: &TrackNamespace,
-    ) -> Result<(), QuicRtcError> {
-        if let Some(track_objects) = self.objects.get_mut(track_namespace) {
-            if !self.config.enable_lru_eviction {
-                return Err(QuicRtcError::TrackCacheFull {
-                    track_name: track_namespace.track_name.clone(),
-                    current_objects: track_objects.len(),
-                    max_objects: self.config.max_objects_per_track,
-                });
-            }
+    /// Buffer a received object
+    pub fn push(&self, object: MoqObject) {
+        self.buffer.write().push(object, Instant::now());
+    }
 
-            // Collect objects with their last access time
-            let mut track_object_list = Vec::new();
-            for (object_id, cached_object) in track_objects.iter() {
-                track_object_list.push((
-                    *object_id,
-                    cached_object.last_accessed,
-                    cached_object.object.size,
-                ));
-            }
+    /// Next object due for playout, if any
+    pub fn next_due(&self) -> Option<MoqObject> {
+        self.buffer.write().next_due(Instant::now())
+    }
+}
 
-            // Sort by last accessed time (oldest first)
-            track_object_list.sort_by_key(|(_, last_accessed, _)| *last_accessed);
+/// Cumulative per-track counters for objects crossing the transport
+///
+/// Outgoing objects update the sent counters. Incoming objects update the
+/// received counters, plus loss (gaps in object IDs within a group),
+/// interarrival jitter (RFC 3550, from the capture timestamp extension) and
+/// freezes (gaps between objects far longer than the usual interval).
+#[derive(Debug, Clone, Default)]
+pub struct TrackTransferStats {
+    /// Objects sent
+    pub objects_sent: u64,
+    /// Payload bytes sent
+    pub bytes_sent: u64,
+    /// Objects received
+    pub objects_received: u64,
+    /// Payload bytes received
+    pub bytes_received: u64,
+    /// Objects detected as missing on receive
+    pub objects_lost: u64,
+    /// Interarrival jitter of received objects
+    pub jitter: Duration,
+    /// Number of receive gaps long enough to be seen as a freeze
+    pub freeze_count: u64,
+    /// Total time spent frozen
+    pub total_freeze_duration: Duration,
+    /// Most recent video resolution seen in object metadata
+    pub resolution: Option<(u32, u32)>,
+    /// (group, object) of the newest object received
+    last_received: Option<(u64, u64)>,
+    /// Arrival time of the first object, the epoch for transit times
+    first_arrival: Option<Instant>,
+    /// Arrival time of the previous object
+    last_arrival: Option<Instant>,
+    /// Transit time of the previous timestamped object, in microseconds
+    last_transit_us: Option<i64>,
+    /// Smoothed interval between non-frozen arrivals
+    average_interval: Option<Duration>,
+}
 
-            // Evict oldest objects until we're under the limit
-            let target_count = self.config.max_objects_per_track / 2; // Free up to 50% of track cache
-            let mut current_count = track_objects.len();
+impl TrackTransferStats {
+    /// Shortest gap counted as a freeze, regardless of the usual interval
+    pub const MIN_FREEZE_GAP: Duration = Duration::from_millis(150);
 
-            for (object_id, _, size) in track_object_list {
-                if current_count <= target_count {
-                    break;
-                }
+    /// Create empty counters
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-                track_objects.remove(&object_id);
-                self.stats.current_size_bytes -= size;
-                self.stats.objects_evicted += 1;
-                current_count -= 1;
+    /// Count an outgoing object
+    pub fn record_sent(&mut self, object: &MoqObject) {
+        self.objects_sent += 1;
+        self.bytes_sent += object.size as u64;
+        if let Some(resolution) = object.metadata.video_resolution() {
+            self.resolution = Some(resolution);
+        }
+    }
+
+    /// Count an incoming object that arrived at `now`
+    pub fn record_received(&mut self, object: &MoqObject, now: Instant) {
+        self.objects_received += 1;
+        self.bytes_received += object.size as u64;
+        if let Some(resolution) = object.metadata.video_resolution() {
+            self.resolution = Some(resolution);
+        }
+
+        self.record_loss(object.group_id, object.object_id);
+        self.record_arrival(now);
+
+        let first_arrival = *self.first_arrival.get_or_insert(now);
+        if let Some(capture_us) = object.metadata.capture_timestamp_us() {
+            let arrival_us = now.duration_since(first_arrival).as_micros() as i64;
+            let transit_us = arrival_us - capture_us as i64;
+            if let Some(last_transit_us) = self.last_transit_us {
+                let delta = Duration::from_micros((transit_us - last_transit_us).unsigned_abs());
+                // J += (|D| - J) / 16
+                self.jitter = if delta > self.jitter {
+                    self.jitter + (delta - self.jitter) / 16
+                } else {
+                    self.jitter - (self.jitter - delta) / 16
+                };
             }
+            self.last_transit_us = Some(transit_us);
         }
+    }
 
-        Ok(())
+    /// Count objects skipped since the newest received object
+    ///
+    /// Only gaps inside a group and a missed start of a newer group are
+    /// seen; objects missing from the end of a group can't be detected.
+    fn record_loss(&mut self, group_id: u64, object_id: u64) {
+        let missing = match self.last_received {
+            Some((last_group, last_object)) if group_id == last_group => {
+                object_id.saturating_sub(last_object + 1)
+            }
+            Some((last_group, _)) if group_id > last_group => object_id,
+            Some(_) => return, // late object from an older group
+            None => 0,
+        };
+        if self
+            .last_received
+            .is_some_and(|last| (group_id, object_id) <= last)
+        {
+            return;
+        }
+        self.objects_lost += missing;
+        self.last_received = Some((group_id, object_id));
+    }
+
+    /// Detect freezes from the gap since the previous arrival
+    ///
+    /// A gap longer than three times the average interval, and at least
+    /// [`Self::MIN_FREEZE_GAP`] beyond it, counts as a freeze.
+    fn record_arrival(&mut self, now: Instant) {
+        let Some(last_arrival) = self.last_arrival.replace(now) else {
+            return;
+        };
+        let gap = now.duration_since(last_arrival);
+        let Some(average) = self.average_interval else {
+            self.average_interval = Some(gap);
+            return;
+        };
+
+        if gap > (average * 3).max(average + Self::MIN_FREEZE_GAP) {
+            self.freeze_count += 1;
+            self.total_freeze_duration += gap;
+        } else {
+            self.average_interval = Some(if gap > average {
+                average + (gap - average) / 8
+            } else {
+                average - (average - gap) / 8
+            });
+        }
     }
 }
 
@@ -1361,6 +3184,7 @@ mod tests {
             supported_track_types: vec![MoqTrackType::Audio],
             max_object_size: 2048,
             supports_caching: false,
+            ..MoqCapabilities::default()
         };
 
         let session_with_caps = MoqSession::new_with_capabilities(session_id, capabilities.clone());
@@ -1369,6 +3193,133 @@ mod tests {
         assert!(!session_with_caps.capabilities().supports_caching);
     }
 
+    #[test]
+    fn test_role_enforcement() {
+        let publisher = MoqCapabilities {
+            role: MoqRole::Publisher,
+            ..MoqCapabilities::default()
+        };
+        let setup_ok = |role| MoqControlMessage::SetupOk {
+            version: 1,
+            capabilities: MoqCapabilities {
+                role,
+                ..MoqCapabilities::default()
+            },
+        };
+
+        // Two publishers have nothing to exchange
+        let mut session = MoqSession::new_with_capabilities(1, publisher.clone());
+        match session.handle_setup_response(setup_ok(MoqRole::Publisher)) {
+            Err(QuicRtcError::SessionSetupFailed { code, .. }) => {
+                assert_eq!(code, MoqErrorCode::IncompatibleRoles.code())
+            }
+            other => panic!("Expected incompatible roles, got {:?}", other),
+        }
+        assert_eq!(session.state(), &MoqSessionState::Terminated);
+
+        let mut session = MoqSession::new_with_capabilities(2, publisher);
+        session
+            .handle_setup_response(setup_ok(MoqRole::Subscriber))
+            .unwrap();
+        assert_eq!(session.peer_role(), MoqRole::Subscriber);
+
+        // A subscriber peer may not announce
+        let namespace = TrackNamespace {
+            namespace: "room.a".to_string(),
+            track_name: "bob/camera".to_string(),
+        };
+        let track = MoqTrack::new(namespace.clone(), "camera".to_string(), MoqTrackType::Video);
+        let reply = session
+            .answer_track_announcement(namespace.clone(), track, None)
+            .unwrap();
+        match reply.message {
+            MoqControlMessage::AnnounceError { code, .. } => {
+                assert_eq!(code, MoqErrorCode::RoleViolation.code())
+            }
+            other => panic!("Expected ANNOUNCE_ERROR, got {:?}", other),
+        }
+        assert!(session.announced_tracks().is_empty());
+
+        // ...and a publisher may not subscribe
+        assert!(matches!(
+            session.subscribe_request(namespace, 0, None, None),
+            Err(QuicRtcError::MoqProtocol { .. })
+        ));
+    }
+
+    #[derive(Debug)]
+    struct StageAuthorizer;
+
+    impl TrackAuthorizer for StageAuthorizer {
+        fn authorize(
+            &self,
+            access: TrackAccess,
+            track_namespace: &TrackNamespace,
+            token: Option<&str>,
+        ) -> Result<(), QuicRtcError> {
+            if access == TrackAccess::Subscribe
+                && track_namespace.namespace == "stage"
+                && token != Some("moderator")
+            {
+                return Err(QuicRtcError::Unauthorized {
+                    reason: "Only moderators may watch the stage".to_string(),
+                });
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_track_authorizer() {
+        let mut session = MoqSession::new(1);
+        session
+            .handle_setup_response(MoqControlMessage::SetupOk {
+                version: 1,
+                capabilities: MoqCapabilities::default(),
+            })
+            .unwrap();
+        session.set_track_authorizer(Arc::new(StageAuthorizer));
+
+        let stage = TrackNamespace {
+            namespace: "stage".to_string(),
+            track_name: "speaker/camera".to_string(),
+        };
+        let track = MoqTrack::new(stage.clone(), "camera".to_string(), MoqTrackType::Video);
+        session
+            .answer_track_announcement(stage.clone(), track, None)
+            .unwrap()
+            .outcome
+            .unwrap();
+
+        let reply = session
+            .answer_subscription_request(stage.clone(), 0, None, None, Some("attendee"))
+            .unwrap();
+        match reply.message {
+            MoqControlMessage::SubscribeError { code, .. } => {
+                assert_eq!(code, MoqErrorCode::Unauthorized.code())
+            }
+            other => panic!("Expected SUBSCRIBE_ERROR, got {:?}", other),
+        }
+        assert_eq!(session.permission_violations(), 1);
+
+        let reply = session
+            .answer_subscription_request(stage.clone(), 0, None, None, Some("moderator"))
+            .unwrap();
+        assert!(matches!(
+            reply.message,
+            MoqControlMessage::SubscribeOk { .. }
+        ));
+
+        // Our own requests carry the token we were given
+        session.set_auth_token(Some("moderator".to_string()));
+        match session.subscribe_request(stage, 0, None, None).unwrap().0 {
+            MoqControlMessage::Subscribe { auth_token, .. } => {
+                assert_eq!(auth_token.as_deref(), Some("moderator"))
+            }
+            other => panic!("Expected SUBSCRIBE, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_moq_object_creation() {
         let track_namespace = TrackNamespace {
@@ -1423,4 +3374,184 @@ mod tests {
         assert_eq!(deserialized.namespace, "conference.example.com");
         assert_eq!(deserialized.track_name, "alice/webcam");
     }
+
+    #[test]
+    fn test_keyframe_cache_keeps_newest_group() {
+        let track = TrackNamespace {
+            namespace: "conference.example.com".to_string(),
+            track_name: "alice/camera".to_string(),
+        };
+        let object = |group_id: u64, object_id: u64| MoqObject {
+            track_namespace: track.clone(),
+            track_name: "camera".to_string(),
+            group_id,
+            object_id,
+            publisher_priority: 1,
+            payload: vec![0; 100],
+            object_status: MoqObjectStatus::Normal,
+            created_at: std::time::Instant::now(),
+            size: 100,
+            metadata: Default::default(),
+        };
+        let mut cache = KeyframeCache::new().with_max_group_bytes(250);
+
+        // Nothing to offer until a keyframe is seen
+        assert!(!cache.store(&object(1, 3)));
+        assert!(cache.group(&track).is_none());
+
+        assert!(cache.store(&object(2, 0)));
+        assert!(cache.store(&object(2, 1)));
+        assert!(!cache.store(&object(1, 4)));
+        let group = cache.group(&track).unwrap();
+        assert_eq!(group.len(), 2);
+        assert_eq!(group[0].object_id, 0);
+        assert_eq!(cache.group_id(&track), Some(2));
+
+        // A new keyframe replaces the group
+        assert!(cache.store(&object(3, 0)));
+        assert_eq!(cache.group(&track).unwrap().len(), 1);
+
+        // Groups over the size limit are dropped rather than cached partially
+        assert!(cache.store(&object(3, 1)));
+        assert!(!cache.store(&object(3, 2)));
+        assert!(cache.group(&track).is_none());
+
+        // Deltas of a group whose keyframe was missed invalidate the cache
+        assert!(cache.store(&object(4, 0)));
+        assert!(!cache.store(&object(5, 1)));
+        assert!(cache.group(&track).is_none());
+
+        assert!(cache.store(&object(6, 0)));
+        let end = MoqObject::end_of_track(track.clone(), "camera".to_string(), 6, 1);
+        assert!(!cache.store(&end));
+        assert!(cache.group(&track).is_none());
+    }
+
+    #[test]
+    fn test_track_transfer_stats_loss_jitter_and_freezes() {
+        let track = TrackNamespace {
+            namespace: "conference.example.com".to_string(),
+            track_name: "alice/camera".to_string(),
+        };
+        let object = |group_id: u64, object_id: u64, capture_ms: u64| {
+            let mut metadata = MoqObjectMetadata::new();
+            metadata
+                .set_capture_timestamp_us(capture_ms * 1000)
+                .unwrap();
+            metadata.set_video_resolution(1280, 720).unwrap();
+            MoqObject {
+                track_namespace: track.clone(),
+                track_name: "camera".to_string(),
+                group_id,
+                object_id,
+                publisher_priority: 1,
+                payload: vec![0; 100],
+                object_status: MoqObjectStatus::Normal,
+                created_at: std::time::Instant::now(),
+                size: 100,
+                metadata,
+            }
+        };
+        let start = std::time::Instant::now();
+        let at = |ms: u64| start + std::time::Duration::from_millis(ms);
+        let mut stats = TrackTransferStats::new();
+
+        stats.record_sent(&object(0, 0, 0));
+        assert_eq!(stats.objects_sent, 1);
+        assert_eq!(stats.bytes_sent, 100);
+        assert_eq!(stats.resolution, Some((1280, 720)));
+
+        // Steady 33ms arrivals with no loss or jitter
+        for i in 0..5 {
+            stats.record_received(&object(0, i, i * 33), at(i * 33));
+        }
+        assert_eq!(stats.objects_received, 5);
+        assert_eq!(stats.objects_lost, 0);
+        assert_eq!(stats.jitter, std::time::Duration::ZERO);
+        assert_eq!(stats.freeze_count, 0);
+
+        // Objects 5 and 6 go missing; object 7 arrives 10ms late
+        stats.record_received(&object(0, 7, 231), at(241));
+        assert_eq!(stats.objects_lost, 2);
+        assert!(stats.jitter > std::time::Duration::ZERO);
+
+        // A new group that starts at object 2 lost its first two objects
+        stats.record_received(&object(1, 2, 264), at(274));
+        assert_eq!(stats.objects_lost, 4);
+
+        // Half a second of silence is a freeze
+        stats.record_received(&object(1, 3, 297), at(800));
+        assert_eq!(stats.freeze_count, 1);
+        assert_eq!(
+            stats.total_freeze_duration,
+            std::time::Duration::from_millis(526)
+        );
+    }
+
+    #[test]
+    fn test_dvr_buffer_pause_seek_and_retention() {
+        use std::time::Duration;
+
+        let track = TrackNamespace {
+            namespace: "conference.example.com".to_string(),
+            track_name: "alice/camera".to_string(),
+        };
+        // Ten objects a second, one group per second
+        let object = |n: u64| MoqObject {
+            track_namespace: track.clone(),
+            track_name: "camera".to_string(),
+            group_id: n / 10,
+            object_id: n % 10,
+            publisher_priority: 1,
+            payload: vec![0; 10],
+            object_status: MoqObjectStatus::Normal,
+            created_at: std::time::Instant::now(),
+            size: 10,
+            metadata: MoqObjectMetadata::new(),
+        };
+        let start = std::time::Instant::now();
+        let at = |n: u64| start + Duration::from_millis(n * 100);
+        let mut dvr = DvrBuffer::new(Duration::from_secs(5));
+
+        // Live playout hands objects straight through
+        for n in 0..30 {
+            dvr.push(object(n), at(n));
+            assert_eq!(dvr.next_due(at(n)).unwrap().object_id, n % 10);
+        }
+
+        // Paused playout buffers; resuming continues a second behind live
+        dvr.pause(at(30));
+        for n in 30..40 {
+            dvr.push(object(n), at(n));
+            assert!(dvr.next_due(at(n)).is_none());
+        }
+        assert_eq!(dvr.time_shift(at(40)), Duration::from_secs(1));
+        dvr.resume(at(40));
+        let next = dvr.next_due(at(40)).unwrap();
+        assert_eq!((next.group_id, next.object_id), (3, 0));
+        assert!(dvr.next_due(at(40)).is_none());
+
+        // Seeking lands on the start of a group
+        assert_eq!(
+            dvr.seek(Duration::from_millis(2500), at(40)),
+            Duration::from_secs(3)
+        );
+        let next = dvr.next_due(at(40)).unwrap();
+        assert_eq!((next.group_id, next.object_id), (1, 0));
+
+        // Once the position leaves the window, playout jumps to the oldest group
+        for n in 40..90 {
+            dvr.push(object(n), at(n));
+        }
+        assert_eq!(dvr.len(), 51);
+        let next = dvr.next_due(at(90)).unwrap();
+        assert_eq!((next.group_id, next.object_id), (4, 0));
+        assert!(dvr.time_shift(at(90)) <= dvr.retention());
+
+        // Going live starts at the newest group
+        dvr.go_live();
+        let next = dvr.next_due(at(90)).unwrap();
+        assert_eq!((next.group_id, next.object_id), (8, 0));
+        assert_eq!(dvr.time_shift(at(90)), Duration::ZERO);
+    }
 }
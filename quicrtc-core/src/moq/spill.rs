@@ -0,0 +1,691 @@
+//! Disk-spill tier for the MoQ object cache
+//!
+//! Relays that retain more media than fits in memory give their
+//! [`MoqObjectCache`](crate::moq::MoqObjectCache) a [`MoqSpillCache`]: objects
+//! evicted from memory are appended to segment files on disk and read back on
+//! access, when the objects following them on the same track are prefetched
+//! in the background. Once the disk budget is used up, the oldest segment is
+//! dropped whole.
+//!
+//! Segments can be sealed with AES-256-GCM under a [`MoqSpillKey`] and kept
+//! once the tier is dropped, so retained media is encrypted at rest. Read
+//! them back with [`MoqSpillSegmentReader`] or the `quicrtc-spill` exporter
+//! shipped with `quicrtc-diagnostics`.
+//!
+//! A segment file is an 8-byte header (magic, version, flags) followed by
+//! records, each a 4-byte length and a body holding the object's track names
+//! and the object in wire format. Sealed bodies are a nonce followed by the
+//! encrypted body, authenticated together with their offset in the file.
+
+use crate::error::QuicRtcError;
+use crate::moq::{MoqObject, MoqWireFormat, TrackNamespace};
+use bytes::BytesMut;
+use parking_lot::Mutex;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+/// File magic of spill segment files
+const MAGIC: &[u8; 4] = b"QRSG";
+
+/// Segment file format version
+const VERSION: u8 = 1;
+
+const HEADER_LEN: u64 = 8;
+const LENGTH_LEN: u64 = 4;
+
+/// Header flag of segments whose records are sealed
+const FLAG_SEALED: u8 = 0b1;
+
+/// Disk-spill tier configuration
+#[derive(Debug, Clone)]
+pub struct MoqSpillConfig {
+    /// Directory segment files are written to; created if missing
+    pub directory: PathBuf,
+    /// Maximum bytes kept on disk before the oldest segment is dropped
+    pub max_disk_bytes: u64,
+    /// Size at which a segment file is closed and a new one started
+    pub segment_bytes: u64,
+    /// Objects following a disk hit on the same track to read ahead
+    pub prefetch_objects: usize,
+    /// Seal objects with AES-256-GCM under this key; `None` writes them in
+    /// the clear
+    pub encryption_key: Option<MoqSpillKey>,
+    /// Leave segment files on disk when the tier is dropped, such as to
+    /// archive the media; segments dropped for the disk budget still go
+    pub keep_segments: bool,
+}
+
+impl Default for MoqSpillConfig {
+    fn default() -> Self {
+        Self {
+            directory: std::env::temp_dir().join(format!("quicrtc-spill-{}", std::process::id())),
+            max_disk_bytes: 1024 * 1024 * 1024, // 1GB
+            segment_bytes: 16 * 1024 * 1024,    // 16MB
+            prefetch_objects: 8,
+            encryption_key: None,
+            keep_segments: false,
+        }
+    }
+}
+
+/// AES-256-GCM key sealing spill segments
+///
+/// Debug output leaves the key out.
+#[derive(Clone, PartialEq, Eq)]
+pub struct MoqSpillKey([u8; 32]);
+
+impl MoqSpillKey {
+    /// Use `bytes` as the key
+    pub fn new(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Parse a key written as 64 hex digits
+    pub fn from_hex(hex: &str) -> Result<Self, QuicRtcError> {
+        let invalid = || QuicRtcError::InvalidData {
+            reason: "Spill key must be 64 hex digits".to_string(),
+        };
+        let hex = hex.trim().as_bytes();
+        if hex.len() != 64 {
+            return Err(invalid());
+        }
+        let mut bytes = [0u8; 32];
+        for (byte, digits) in bytes.iter_mut().zip(hex.chunks(2)) {
+            let digits = std::str::from_utf8(digits).map_err(|_| invalid())?;
+            *byte = u8::from_str_radix(digits, 16).map_err(|_| invalid())?;
+        }
+        Ok(Self(bytes))
+    }
+
+    fn aead_key(&self) -> LessSafeKey {
+        let key = UnboundKey::new(&AES_256_GCM, &self.0).expect("AES-256 keys are 32 bytes");
+        LessSafeKey::new(key)
+    }
+}
+
+impl fmt::Debug for MoqSpillKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MoqSpillKey(..)")
+    }
+}
+
+/// Disk-spill tier statistics
+#[derive(Debug, Clone, Default)]
+pub struct MoqSpillStats {
+    /// Objects written to disk
+    pub spilled_objects: u64,
+    /// Objects read back from disk or the prefetch buffer
+    pub disk_hits: u64,
+    /// Lookups that found nothing on disk
+    pub disk_misses: u64,
+    /// Objects read ahead into the prefetch buffer
+    pub prefetched_objects: u64,
+    /// Disk hits served from the prefetch buffer
+    pub prefetch_hits: u64,
+    /// Segments dropped to stay within the disk budget
+    pub dropped_segments: u64,
+    /// Objects that failed to be written or read back
+    pub io_errors: u64,
+    /// Bytes currently held in segment files
+    pub disk_bytes: u64,
+}
+
+/// Where a spilled object's record body lives on disk
+#[derive(Debug, Clone)]
+struct SpillEntry {
+    segment_id: u64,
+    offset: u64,
+    len: usize,
+}
+
+/// One segment file, oldest segments first in [`MoqSpillCache::segments`]
+#[derive(Debug)]
+struct Segment {
+    id: u64,
+    path: PathBuf,
+    bytes: u64,
+    /// Objects written to this segment
+    objects: Vec<(TrackNamespace, u64)>,
+}
+
+/// Objects read ahead of access, keyed by track and object ID
+type PrefetchBuffer = Arc<Mutex<HashMap<(TrackNamespace, u64), MoqObject>>>;
+
+/// Second cache tier keeping cold objects in segment files on disk
+///
+/// Segment files are removed when the tier is dropped unless
+/// [`MoqSpillConfig::keep_segments`] is set; spilled objects are not read
+/// back from earlier runs.
+#[derive(Debug)]
+pub struct MoqSpillCache {
+    config: MoqSpillConfig,
+    /// Key records are sealed with, if any
+    key: Option<LessSafeKey>,
+    rng: SystemRandom,
+    /// Spilled objects per track, by object ID
+    index: HashMap<TrackNamespace, BTreeMap<u64, SpillEntry>>,
+    /// Segment files, oldest first; the last one is written to
+    segments: VecDeque<Segment>,
+    /// Append handle for the newest segment
+    writer: Option<File>,
+    next_segment_id: u64,
+    prefetched: PrefetchBuffer,
+    stats: Arc<Mutex<MoqSpillStats>>,
+}
+
+impl MoqSpillCache {
+    /// Create a spill tier writing to `config.directory`
+    pub fn open(config: MoqSpillConfig) -> Result<Self, QuicRtcError> {
+        std::fs::create_dir_all(&config.directory).map_err(|e| QuicRtcError::Initialization {
+            reason: format!(
+                "Failed to create spill directory {}: {}",
+                config.directory.display(),
+                e
+            ),
+        })?;
+
+        Ok(Self {
+            key: config.encryption_key.as_ref().map(MoqSpillKey::aead_key),
+            rng: SystemRandom::new(),
+            config,
+            index: HashMap::new(),
+            segments: VecDeque::new(),
+            writer: None,
+            next_segment_id: 0,
+            prefetched: Arc::new(Mutex::new(HashMap::new())),
+            stats: Arc::new(Mutex::new(MoqSpillStats::default())),
+        })
+    }
+
+    /// Write an object to disk, returning whether it was written
+    ///
+    /// Write failures are logged and counted; the object is then lost, as it
+    /// would have been without the spill tier.
+    pub fn store(&mut self, object: &MoqObject) -> bool {
+        let record = match encode_record(object) {
+            Ok(record) => record,
+            Err(e) => {
+                warn!("Failed to encode object for spilling: {}", e);
+                self.stats.lock().io_errors += 1;
+                return false;
+            }
+        };
+
+        if let Err(e) = self.append(object, record) {
+            warn!(
+                "Failed to spill object {} of {:?}: {}",
+                object.object_id, object.track_namespace, e
+            );
+            self.stats.lock().io_errors += 1;
+            return false;
+        }
+        self.stats.lock().spilled_objects += 1;
+        self.enforce_disk_budget();
+        true
+    }
+
+    /// Read an object back from disk, removing it from this tier
+    ///
+    /// A hit prefetches the objects following it on the same track.
+    pub fn take(&mut self, track_namespace: &TrackNamespace, object_id: u64) -> Option<MoqObject> {
+        let entry = self
+            .index
+            .get_mut(track_namespace)
+            .and_then(|track| track.remove(&object_id));
+        if self
+            .index
+            .get(track_namespace)
+            .is_some_and(BTreeMap::is_empty)
+        {
+            self.index.remove(track_namespace);
+        }
+
+        let prefetched = self
+            .prefetched
+            .lock()
+            .remove(&(track_namespace.clone(), object_id));
+        let object = match (prefetched, entry) {
+            (Some(object), _) => {
+                self.stats.lock().prefetch_hits += 1;
+                Some(object)
+            }
+            (None, Some(entry)) => match self.read(&entry) {
+                Ok(object) => Some(object),
+                Err(e) => {
+                    warn!("Failed to read spilled object {}: {}", object_id, e);
+                    self.stats.lock().io_errors += 1;
+                    None
+                }
+            },
+            (None, None) => None,
+        };
+
+        let mut stats = self.stats.lock();
+        match object {
+            Some(object) => {
+                stats.disk_hits += 1;
+                drop(stats);
+                self.prefetch_after(track_namespace, object_id);
+                Some(object)
+            }
+            None => {
+                stats.disk_misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Whether an object is on disk
+    pub fn contains(&self, track_namespace: &TrackNamespace, object_id: u64) -> bool {
+        self.index
+            .get(track_namespace)
+            .is_some_and(|track| track.contains_key(&object_id))
+    }
+
+    /// Get spill statistics
+    pub fn stats(&self) -> MoqSpillStats {
+        self.stats.lock().clone()
+    }
+
+    /// Append an encoded object to the newest segment, sealing it if there
+    /// is a key, and start a new segment when it is full
+    fn append(&mut self, object: &MoqObject, record: Vec<u8>) -> Result<(), QuicRtcError> {
+        let sealing_len = match self.key {
+            Some(_) => NONCE_LEN + AES_256_GCM.tag_len(),
+            None => 0,
+        };
+        let written = LENGTH_LEN + (record.len() + sealing_len) as u64;
+        let segment_full = self
+            .segments
+            .back()
+            .is_none_or(|segment| segment.bytes + written > self.config.segment_bytes);
+        if segment_full || self.writer.is_none() {
+            self.start_segment()?;
+        }
+
+        let (Some(writer), Some(segment)) = (self.writer.as_mut(), self.segments.back_mut()) else {
+            return Err(QuicRtcError::InvalidState {
+                expected: "open spill segment".to_string(),
+                actual: "none".to_string(),
+            });
+        };
+        let offset = segment.bytes + LENGTH_LEN;
+        let body = match &self.key {
+            Some(key) => seal(key, &self.rng, offset, record)?,
+            None => record,
+        };
+        let io_error = |e: std::io::Error| QuicRtcError::ResourceExhausted {
+            resource: format!("spill segment {}: {}", segment.path.display(), e),
+        };
+        writer
+            .write_all(&(body.len() as u32).to_le_bytes())
+            .and_then(|()| writer.write_all(&body))
+            .map_err(io_error)?;
+
+        let entry = SpillEntry {
+            segment_id: segment.id,
+            offset,
+            len: body.len(),
+        };
+        segment.bytes += written;
+        segment
+            .objects
+            .push((object.track_namespace.clone(), object.object_id));
+        self.stats.lock().disk_bytes += written;
+
+        self.prefetched
+            .lock()
+            .remove(&(object.track_namespace.clone(), object.object_id));
+        self.index
+            .entry(object.track_namespace.clone())
+            .or_default()
+            .insert(object.object_id, entry);
+        Ok(())
+    }
+
+    /// Close the newest segment and open a fresh one
+    fn start_segment(&mut self) -> Result<(), QuicRtcError> {
+        let id = self.next_segment_id;
+        self.next_segment_id += 1;
+        let path = self.config.directory.join(format!("segment-{:08}.moq", id));
+        let io_error = |e: std::io::Error| QuicRtcError::ResourceExhausted {
+            resource: format!("spill segment {}: {}", path.display(), e),
+        };
+        let mut writer = OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .open(&path)
+            .map_err(io_error)?;
+
+        let mut header = [0u8; HEADER_LEN as usize];
+        header[0..4].copy_from_slice(MAGIC);
+        header[4] = VERSION;
+        if self.key.is_some() {
+            header[5] |= FLAG_SEALED;
+        }
+        writer.write_all(&header).map_err(io_error)?;
+        self.writer = Some(writer);
+        self.stats.lock().disk_bytes += HEADER_LEN;
+
+        debug!("Started spill segment {}", path.display());
+        self.segments.push_back(Segment {
+            id,
+            path,
+            bytes: HEADER_LEN,
+            objects: Vec::new(),
+        });
+        Ok(())
+    }
+
+    /// Drop the oldest segments until the disk budget is met
+    fn enforce_disk_budget(&mut self) {
+        while self.stats.lock().disk_bytes > self.config.max_disk_bytes && self.segments.len() > 1 {
+            let Some(segment) = self.segments.pop_front() else {
+                break;
+            };
+            self.drop_segment(segment);
+        }
+    }
+
+    fn drop_segment(&mut self, segment: Segment) {
+        for (track_namespace, object_id) in &segment.objects {
+            let Some(track) = self.index.get_mut(track_namespace) else {
+                continue;
+            };
+            // A later spill of the same object lives in a newer segment
+            if track
+                .get(object_id)
+                .is_some_and(|entry| entry.segment_id == segment.id)
+            {
+                track.remove(object_id);
+                self.prefetched
+                    .lock()
+                    .remove(&(track_namespace.clone(), *object_id));
+            }
+            if track.is_empty() {
+                self.index.remove(track_namespace);
+            }
+        }
+
+        if let Err(e) = std::fs::remove_file(&segment.path) {
+            warn!(
+                "Failed to remove spill segment {}: {}",
+                segment.path.display(),
+                e
+            );
+        }
+        let mut stats = self.stats.lock();
+        stats.disk_bytes = stats.disk_bytes.saturating_sub(segment.bytes);
+        stats.dropped_segments += 1;
+    }
+
+    fn segment_path(&self, segment_id: u64) -> Option<PathBuf> {
+        self.segments
+            .iter()
+            .find(|segment| segment.id == segment_id)
+            .map(|segment| segment.path.clone())
+    }
+
+    fn read(&self, entry: &SpillEntry) -> Result<MoqObject, QuicRtcError> {
+        let path =
+            self.segment_path(entry.segment_id)
+                .ok_or_else(|| QuicRtcError::InvalidState {
+                    expected: "spill segment on disk".to_string(),
+                    actual: format!("segment {} dropped", entry.segment_id),
+                })?;
+        read_entry(&path, self.key.as_ref(), entry)
+    }
+
+    /// Read the objects following `object_id` on a track into the prefetch
+    /// buffer on a blocking thread
+    fn prefetch_after(&self, track_namespace: &TrackNamespace, object_id: u64) {
+        if self.config.prefetch_objects == 0 {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let Some(track) = self.index.get(track_namespace) else {
+            return;
+        };
+
+        let prefetched = self.prefetched.lock();
+        let reads: Vec<(u64, PathBuf, SpillEntry)> = track
+            .range(object_id.saturating_add(1)..)
+            .filter(|(next_id, _)| !prefetched.contains_key(&(track_namespace.clone(), **next_id)))
+            .take(self.config.prefetch_objects)
+            .filter_map(|(next_id, entry)| {
+                let path = self.segment_path(entry.segment_id)?;
+                Some((*next_id, path, entry.clone()))
+            })
+            .collect();
+        drop(prefetched);
+        if reads.is_empty() {
+            return;
+        }
+
+        let track_namespace = track_namespace.clone();
+        let key = self.key.clone();
+        let buffer = Arc::clone(&self.prefetched);
+        let stats = Arc::clone(&self.stats);
+        runtime.spawn_blocking(move || {
+            for (next_id, path, entry) in reads {
+                match read_entry(&path, key.as_ref(), &entry) {
+                    Ok(object) => {
+                        buffer
+                            .lock()
+                            .insert((track_namespace.clone(), next_id), object);
+                        stats.lock().prefetched_objects += 1;
+                    }
+                    // The segment may have been dropped meanwhile
+                    Err(e) => debug!("Prefetch of object {} failed: {}", next_id, e),
+                }
+            }
+        });
+    }
+}
+
+impl Drop for MoqSpillCache {
+    fn drop(&mut self) {
+        self.writer = None;
+        if self.config.keep_segments {
+            return;
+        }
+        for segment in self.segments.drain(..) {
+            let _ = std::fs::remove_file(&segment.path);
+        }
+    }
+}
+
+/// Reads the objects of a spill segment file in the order they were spilled
+///
+/// Sealed segments need the key they were written with. A record cut short
+/// at the end of the file, as left by a crash, ends iteration without an
+/// error.
+#[derive(Debug)]
+pub struct MoqSpillSegmentReader {
+    reader: BufReader<File>,
+    key: Option<LessSafeKey>,
+    sealed: bool,
+    /// Offset of the next record
+    offset: u64,
+}
+
+impl MoqSpillSegmentReader {
+    /// Open a segment file and check its header
+    pub fn open(path: impl AsRef<Path>, key: Option<&MoqSpillKey>) -> Result<Self, QuicRtcError> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| QuicRtcError::InvalidOperation {
+            operation: format!("open spill segment {}: {}", path.display(), e),
+        })?;
+        let mut reader = BufReader::new(file);
+
+        let mut header = [0u8; HEADER_LEN as usize];
+        reader
+            .read_exact(&mut header)
+            .map_err(|_| QuicRtcError::InvalidData {
+                reason: format!("{} is too short for a spill segment", path.display()),
+            })?;
+        if &header[0..4] != MAGIC {
+            return Err(QuicRtcError::InvalidData {
+                reason: format!("{} is not a spill segment", path.display()),
+            });
+        }
+        if header[4] != VERSION {
+            return Err(QuicRtcError::InvalidData {
+                reason: format!("Unsupported spill segment version {}", header[4]),
+            });
+        }
+        let sealed = header[5] & FLAG_SEALED != 0;
+        if sealed && key.is_none() {
+            return Err(QuicRtcError::Unauthorized {
+                reason: format!("{} is sealed and no key was given", path.display()),
+            });
+        }
+
+        Ok(Self {
+            reader,
+            key: key.filter(|_| sealed).map(MoqSpillKey::aead_key),
+            sealed,
+            offset: HEADER_LEN,
+        })
+    }
+
+    /// Whether the segment's objects are sealed
+    pub fn is_sealed(&self) -> bool {
+        self.sealed
+    }
+}
+
+impl Iterator for MoqSpillSegmentReader {
+    type Item = Result<MoqObject, QuicRtcError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut length = [0u8; LENGTH_LEN as usize];
+        self.reader.read_exact(&mut length).ok()?;
+        let mut body = vec![0u8; u32::from_le_bytes(length) as usize];
+        self.reader.read_exact(&mut body).ok()?;
+
+        let offset = self.offset + LENGTH_LEN;
+        self.offset = offset + body.len() as u64;
+        Some(open_record(self.key.as_ref(), offset, body))
+    }
+}
+
+/// Record body for `object`: its track names, then the object in wire format
+fn encode_record(object: &MoqObject) -> Result<Vec<u8>, QuicRtcError> {
+    let mut record = BytesMut::new();
+    for name in [
+        &object.track_namespace.namespace,
+        &object.track_namespace.track_name,
+        &object.track_name,
+    ] {
+        let len = u16::try_from(name.len()).map_err(|_| QuicRtcError::InvalidData {
+            reason: format!("Track name of {} bytes is too long to spill", name.len()),
+        })?;
+        record.extend_from_slice(&len.to_le_bytes());
+        record.extend_from_slice(name.as_bytes());
+    }
+    MoqWireFormat::encode_object_stream(object, 0, &mut record)?;
+    Ok(record.to_vec())
+}
+
+/// Decode a record body written by [`encode_record`]
+fn decode_record(mut record: &[u8]) -> Result<MoqObject, QuicRtcError> {
+    let mut name = || {
+        let invalid = || QuicRtcError::InvalidData {
+            reason: "Spill record has a damaged track name".to_string(),
+        };
+        let (len, rest) = record.split_first_chunk::<2>().ok_or_else(invalid)?;
+        let len = u16::from_le_bytes(*len) as usize;
+        if rest.len() < len {
+            return Err(invalid());
+        }
+        let (name, rest) = rest.split_at(len);
+        record = rest;
+        String::from_utf8(name.to_vec()).map_err(|_| invalid())
+    };
+    let track_namespace = TrackNamespace {
+        namespace: name()?,
+        track_name: name()?,
+    };
+    let track_name = name()?;
+
+    let (_, mut object) = MoqWireFormat::decode_object_stream(record)?;
+    object.track_namespace = track_namespace;
+    object.track_name = track_name;
+    Ok(object)
+}
+
+/// Encrypt a record body written at `offset`, prefixing the nonce
+fn seal(
+    key: &LessSafeKey,
+    rng: &SystemRandom,
+    offset: u64,
+    mut record: Vec<u8>,
+) -> Result<Vec<u8>, QuicRtcError> {
+    let sealing_failed = |_| QuicRtcError::EncodingFailed {
+        reason: "Failed to seal spill record".to_string(),
+    };
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut nonce).map_err(sealing_failed)?;
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce),
+        Aad::from(offset.to_le_bytes()),
+        &mut record,
+    )
+    .map_err(sealing_failed)?;
+
+    let mut sealed = Vec::with_capacity(NONCE_LEN + record.len());
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&record);
+    Ok(sealed)
+}
+
+/// Decode a record body read from `offset`, decrypting it first if `key` is
+/// given
+fn open_record(
+    key: Option<&LessSafeKey>,
+    offset: u64,
+    mut body: Vec<u8>,
+) -> Result<MoqObject, QuicRtcError> {
+    let Some(key) = key else {
+        return decode_record(&body);
+    };
+    let rejected = || QuicRtcError::DecodingFailed {
+        reason: "Spill record failed authentication; wrong key or altered segment".to_string(),
+    };
+    if body.len() < NONCE_LEN {
+        return Err(rejected());
+    }
+    let (nonce, sealed) = body.split_at_mut(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| rejected())?;
+    let record = key
+        .open_in_place(nonce, Aad::from(offset.to_le_bytes()), sealed)
+        .map_err(|_| rejected())?;
+    decode_record(record)
+}
+
+/// Read and decode one spilled object
+fn read_entry(
+    path: &Path,
+    key: Option<&LessSafeKey>,
+    entry: &SpillEntry,
+) -> Result<MoqObject, QuicRtcError> {
+    let io_error = |e: std::io::Error| QuicRtcError::ResourceExhausted {
+        resource: format!("spill segment {}: {}", path.display(), e),
+    };
+    let mut file = File::open(path).map_err(io_error)?;
+    file.seek(SeekFrom::Start(entry.offset)).map_err(io_error)?;
+    let mut body = vec![0u8; entry.len];
+    file.read_exact(&mut body).map_err(io_error)?;
+    open_record(key, entry.offset, body)
+}
@@ -3,15 +3,19 @@
 //! This module implements comprehensive Media over QUIC (MoQ) stream management
 //! according to IETF draft-ietf-moq-transport-13 specification.
 
+use crate::backpressure::{BackpressureReason, BackpressureSignal};
+use crate::driver::{DriverStream, MoqTransportDriver};
 use crate::error::QuicRtcError;
-use crate::moq::{MoqControlMessage, MoqObject, MoqSession, MoqWireFormat};
-use crate::transport::{QuicStream, StreamType, TransportConnection};
-use bytes::BytesMut;
-use parking_lot::RwLock;
-use std::collections::{HashMap, VecDeque};
+use crate::moq::{MoqControlMessage, MoqObject, MoqSession, MoqWireFormat, TrackNamespace};
+use crate::transport::StreamType;
+use bytes::{Bytes, BytesMut};
+use parking_lot::{Mutex, RwLock};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::{mpsc, Semaphore};
+use tokio::sync::{mpsc, oneshot};
 use tokio::time::timeout;
 use tracing::{debug, error, info, warn};
 
@@ -60,6 +64,8 @@ pub struct StreamStats {
     pub objects_received: u64,
     /// Number of times stream was blocked by flow control
     pub flow_control_blocks: u64,
+    /// Time spent waiting for stream credit before the stream opened
+    pub stream_credit_wait: Duration,
     /// Average object delivery latency
     pub avg_delivery_latency: Duration,
 }
@@ -83,8 +89,9 @@ pub struct ManagedMoqStream {
     pub created_at: Instant,
     /// Last activity timestamp
     pub last_activity: Instant,
-    /// Underlying QUIC stream
-    pub quic_stream: QuicStream,
+    /// Underlying QUIC stream, locked separately from the stream so writes
+    /// don't hold up access to its state
+    pub quic_stream: Arc<tokio::sync::Mutex<Box<dyn DriverStream>>>,
     /// Pending objects queue
     pub pending_objects: VecDeque<MoqObject>,
     /// Statistics
@@ -94,7 +101,9 @@ pub struct ManagedMoqStream {
 /// Stream manager configuration
 #[derive(Debug, Clone)]
 pub struct StreamManagerConfig {
-    /// Maximum concurrent streams
+    /// Maximum concurrently open streams; further openings queue by
+    /// priority until a stream closes. Keep this at or below the peer's
+    /// stream limit so openings don't also block inside the QUIC stack.
     pub max_concurrent_streams: u32,
     /// Control stream timeout
     pub control_stream_timeout: Duration,
@@ -185,8 +194,8 @@ pub enum MoqStreamEvent {
 /// MoQ Stream Manager - Comprehensive stream lifecycle management
 #[derive(Debug)]
 pub struct MoqStreamManager {
-    /// Transport connection for creating streams
-    transport: Arc<RwLock<TransportConnection>>,
+    /// Transport driver for creating streams
+    driver: Arc<dyn MoqTransportDriver>,
     /// MoQ session for protocol logic
     session: Arc<RwLock<MoqSession>>,
     /// Active streams by stream ID
@@ -195,8 +204,18 @@ pub struct MoqStreamManager {
     track_streams: Arc<RwLock<HashMap<TrackAlias, Vec<StreamId>>>>,
     /// Control stream ID (single bidirectional stream)
     control_stream_id: Arc<RwLock<Option<StreamId>>>,
-    /// Stream creation semaphore for flow control
-    stream_semaphore: Arc<Semaphore>,
+    /// Sequence numbers of control messages in both directions
+    control_sequence: Arc<Mutex<ControlSequence>>,
+    /// Code and reason of the TERMINATE the peer sent, if it sent one
+    peer_termination: Arc<Mutex<Option<(u32, String)>>>,
+    /// Credit for opening streams, one per open stream
+    stream_credit: Arc<StreamCredit>,
+    /// Latency budgets of tracks whose objects go stale
+    latency_budgets: Arc<RwLock<HashMap<TrackNamespace, Duration>>>,
+    /// Objects dropped or abandoned mid-write past their latency budget
+    late_objects_dropped: Arc<AtomicU64>,
+    /// Told about late objects and stream credit waits
+    backpressure: BackpressureSignal,
     /// Event notification channels
     event_tx: mpsc::UnboundedSender<MoqStreamEvent>,
     /// Configuration
@@ -222,11 +241,215 @@ pub struct MoqStreamManagerStats {
     pub total_objects_sent: u64,
     /// Total objects received
     pub total_objects_received: u64,
+    /// Stream openings waiting for stream credit
+    pub queued_stream_openings: u32,
+    /// Stream openings that had to wait for stream credit
+    pub blocked_stream_openings: u64,
+    /// Total time stream openings spent waiting for stream credit
+    pub stream_credit_wait: Duration,
+    /// Objects dropped, or abandoned by resetting their stream, because
+    /// they missed their track's latency budget
+    pub late_objects_dropped: u64,
+}
+
+/// Sequence numbers of the control messages sent and received
+///
+/// The control stream is ordered, so each message received must carry the
+/// next number. Anything else was replayed or reordered on the way, for
+/// example by a misbehaving relay, and ends the session.
+#[derive(Debug, Default)]
+struct ControlSequence {
+    /// Number of the next message sent
+    next_send: u64,
+    /// Number the next message received must carry
+    next_receive: u64,
+    /// Why the session ended, once a message arrived out of sequence
+    violation: Option<String>,
+}
+
+impl ControlSequence {
+    /// Accept the number of a received message, or record the violation
+    fn accept(&mut self, sequence: u64) -> Result<(), QuicRtcError> {
+        if let Some(reason) = &self.violation {
+            return Err(QuicRtcError::MoqProtocol {
+                reason: reason.clone(),
+            });
+        }
+        if sequence == self.next_receive {
+            self.next_receive += 1;
+            return Ok(());
+        }
+
+        let kind = if sequence < self.next_receive {
+            "Replayed"
+        } else {
+            "Out of order"
+        };
+        let reason = format!(
+            "{} control message: sequence {}, expected {}",
+            kind, sequence, self.next_receive
+        );
+        self.violation = Some(reason.clone());
+        Err(QuicRtcError::MoqProtocol { reason })
+    }
+}
+
+/// Stream reset code for objects that missed their latency budget, the
+/// transport draft's DELIVERY_TIMEOUT
+const DELIVERY_TIMEOUT_RESET_CODE: u32 = 0x2;
+
+/// Waits longer than this are logged, as they usually mean
+/// `max_concurrent_streams` is too low for the number of tracks
+const STREAM_CREDIT_WAIT_WARNING: Duration = Duration::from_millis(100);
+
+/// Credit for opening streams, shared by all streams of a connection
+///
+/// Each open stream holds one credit, up to `max_concurrent_streams`. When
+/// none are left, openings queue until a stream closes and are granted in
+/// priority order (lowest value first, oldest first within a priority), so
+/// audio isn't stuck behind a backlog of video groups.
+#[derive(Debug)]
+struct StreamCredit {
+    state: Mutex<StreamCreditState>,
+}
+
+#[derive(Debug)]
+struct StreamCreditState {
+    available: u32,
+    next_seq: u64,
+    waiters: BinaryHeap<CreditWaiter>,
+    blocked_openings: u64,
+    total_wait: Duration,
+}
+
+/// Stream opening waiting for credit
+#[derive(Debug)]
+struct CreditWaiter {
+    priority: u8,
+    seq: u64,
+    grant: oneshot::Sender<()>,
+}
+
+impl PartialEq for CreditWaiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority && self.seq == other.seq
+    }
+}
+
+impl Eq for CreditWaiter {}
+
+impl PartialOrd for CreditWaiter {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CreditWaiter {
+    // BinaryHeap pops the greatest, so lower priority values and older
+    // requests compare greater
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .priority
+            .cmp(&self.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+/// Pending credit grant that hands the credit back if dropped after being granted
+struct CreditWait<'a> {
+    credit: &'a StreamCredit,
+    grant: Option<oneshot::Receiver<()>>,
+}
+
+impl Drop for CreditWait<'_> {
+    fn drop(&mut self) {
+        if let Some(mut grant) = self.grant.take() {
+            grant.close();
+            if grant.try_recv().is_ok() {
+                self.credit.release();
+            }
+        }
+    }
+}
+
+impl StreamCredit {
+    fn new(credits: u32) -> Self {
+        Self {
+            state: Mutex::new(StreamCreditState {
+                available: credits,
+                next_seq: 0,
+                waiters: BinaryHeap::new(),
+                blocked_openings: 0,
+                total_wait: Duration::ZERO,
+            }),
+        }
+    }
+
+    /// Take a credit, waiting behind more important openings if none are
+    /// left; returns how long the wait was
+    async fn acquire(&self, priority: u8) -> Result<Duration, QuicRtcError> {
+        let requested_at = Instant::now();
+        let grant = {
+            let mut state = self.state.lock();
+            if state.available > 0 {
+                state.available -= 1;
+                return Ok(Duration::ZERO);
+            }
+            let (grant_tx, grant_rx) = oneshot::channel();
+            let seq = state.next_seq;
+            state.next_seq += 1;
+            state.waiters.push(CreditWaiter {
+                priority,
+                seq,
+                grant: grant_tx,
+            });
+            grant_rx
+        };
+
+        let mut wait = CreditWait {
+            credit: self,
+            grant: Some(grant),
+        };
+        if let Some(grant) = wait.grant.as_mut() {
+            grant.await.map_err(|_| QuicRtcError::ResourceExhausted {
+                resource: "Stream credit".to_string(),
+            })?;
+        }
+        wait.grant = None;
+
+        let waited = requested_at.elapsed();
+        let mut state = self.state.lock();
+        state.blocked_openings += 1;
+        state.total_wait += waited;
+        Ok(waited)
+    }
+
+    /// Return a credit, handing it to the most important queued opening
+    fn release(&self) {
+        let mut state = self.state.lock();
+        while let Some(waiter) = state.waiters.pop() {
+            // Openings given up on have dropped their receiver
+            if waiter.grant.send(()).is_ok() {
+                return;
+            }
+        }
+        state.available += 1;
+    }
+
+    /// Openings waiting for credit
+    fn queued(&self) -> u32 {
+        let state = self.state.lock();
+        state
+            .waiters
+            .iter()
+            .filter(|waiter| !waiter.grant.is_closed())
+            .count() as u32
+    }
 }
 
 impl ManagedMoqStream {
     /// Create a new control stream
-    pub fn new_control(stream_id: StreamId, quic_stream: QuicStream) -> Self {
+    pub fn new_control(stream_id: StreamId, quic_stream: Box<dyn DriverStream>) -> Self {
         let now = Instant::now();
         Self {
             stream_id,
@@ -237,7 +460,7 @@ impl ManagedMoqStream {
             priority: 0, // Control streams have highest priority
             created_at: now,
             last_activity: now,
-            quic_stream,
+            quic_stream: Arc::new(tokio::sync::Mutex::new(quic_stream)),
             pending_objects: VecDeque::new(),
             stats: StreamStats::default(),
         }
@@ -249,7 +472,7 @@ impl ManagedMoqStream {
         track_alias: TrackAlias,
         subgroup_id: u64,
         priority: u8,
-        quic_stream: QuicStream,
+        quic_stream: Box<dyn DriverStream>,
     ) -> Self {
         let now = Instant::now();
         Self {
@@ -261,12 +484,20 @@ impl ManagedMoqStream {
             priority,
             created_at: now,
             last_activity: now,
-            quic_stream,
+            quic_stream: Arc::new(tokio::sync::Mutex::new(quic_stream)),
             pending_objects: VecDeque::new(),
             stats: StreamStats::default(),
         }
     }
 
+    /// Record time spent waiting for stream credit before opening
+    fn record_credit_wait(&mut self, waited: Duration) {
+        if !waited.is_zero() {
+            self.stats.flow_control_blocks += 1;
+            self.stats.stream_credit_wait += waited;
+        }
+    }
+
     /// Update stream state and last activity
     pub fn set_state(&mut self, new_state: MoqStreamState) -> MoqStreamState {
         let old_state = self.state.clone();
@@ -301,69 +532,71 @@ impl ManagedMoqStream {
 
     /// Send next pending object
     pub async fn send_next_object(&mut self) -> Result<Option<MoqObject>, QuicRtcError> {
-        if let Some(object) = self.pending_objects.pop_front() {
-            let send_start = Instant::now();
+        let Some((object, buffer)) = self.encode_next_object()? else {
+            return Ok(None);
+        };
+        let send_start = Instant::now();
+        self.quic_stream.lock().await.send(&buffer).await?;
+        self.record_sent(&object, buffer.len(), send_start.elapsed());
+        Ok(Some(object))
+    }
 
-            // Encode object using wire format
-            let mut buffer = BytesMut::new();
-            match self.stream_type {
-                MoqStreamType::DataSubgroup => {
-                    if let Some(track_alias) = self.track_alias {
-                        MoqWireFormat::encode_object_stream(&object, track_alias, &mut buffer)?;
-                    } else {
-                        return Err(QuicRtcError::InvalidState {
-                            expected: "Track alias for data stream".to_string(),
-                            actual: "None".to_string(),
-                        });
-                    }
-                }
-                MoqStreamType::Datagram => {
-                    if let Some(track_alias) = self.track_alias {
-                        MoqWireFormat::encode_object_datagram(&object, track_alias, &mut buffer)?;
-                    } else {
-                        return Err(QuicRtcError::InvalidState {
-                            expected: "Track alias for datagram".to_string(),
-                            actual: "None".to_string(),
-                        });
-                    }
+    /// Take the next pending object and encode it for this stream
+    fn encode_next_object(&mut self) -> Result<Option<(MoqObject, Bytes)>, QuicRtcError> {
+        let Some(object) = self.pending_objects.pop_front() else {
+            return Ok(None);
+        };
+
+        // Encode object using wire format
+        let mut buffer = BytesMut::new();
+        match self.stream_type {
+            MoqStreamType::DataSubgroup => {
+                if let Some(track_alias) = self.track_alias {
+                    MoqWireFormat::encode_object_stream(&object, track_alias, &mut buffer)?;
+                } else {
+                    return Err(QuicRtcError::InvalidState {
+                        expected: "Track alias for data stream".to_string(),
+                        actual: "None".to_string(),
+                    });
                 }
-                MoqStreamType::Control => {
-                    return Err(QuicRtcError::InvalidOperation {
-                        operation: "Send object on control stream".to_string(),
+            }
+            MoqStreamType::Datagram => {
+                if let Some(track_alias) = self.track_alias {
+                    MoqWireFormat::encode_object_datagram(&object, track_alias, &mut buffer)?;
+                } else {
+                    return Err(QuicRtcError::InvalidState {
+                        expected: "Track alias for datagram".to_string(),
+                        actual: "None".to_string(),
                     });
                 }
             }
+            MoqStreamType::Control => {
+                return Err(QuicRtcError::InvalidOperation {
+                    operation: "Send object on control stream".to_string(),
+                });
+            }
+        }
 
-            // Send over QUIC stream
-            self.quic_stream.send(&buffer).await?;
-
-            // Update statistics
-            let delivery_latency = send_start.elapsed();
-            self.stats.bytes_sent += buffer.len() as u64;
-            self.stats.objects_sent += 1;
-            self.stats.avg_delivery_latency =
-                (self.stats.avg_delivery_latency + delivery_latency) / 2;
-            self.last_activity = Instant::now();
+        Ok(Some((object, buffer.freeze())))
+    }
 
-            debug!(
-                "Sent object on stream {}: group={}, object={}, bytes={}, latency={:?}",
-                self.stream_id,
-                object.group_id,
-                object.object_id,
-                buffer.len(),
-                delivery_latency
-            );
+    /// Update statistics for an object written to the QUIC stream
+    fn record_sent(&mut self, object: &MoqObject, bytes: usize, delivery_latency: Duration) {
+        self.stats.bytes_sent += bytes as u64;
+        self.stats.objects_sent += 1;
+        self.stats.avg_delivery_latency = (self.stats.avg_delivery_latency + delivery_latency) / 2;
+        self.last_activity = Instant::now();
 
-            Ok(Some(object))
-        } else {
-            Ok(None)
-        }
+        debug!(
+            "Sent object on stream {}: group={}, object={}, bytes={}, latency={:?}",
+            self.stream_id, object.group_id, object.object_id, bytes, delivery_latency
+        );
     }
 
     /// Close stream gracefully
     pub async fn close(&mut self) -> Result<(), QuicRtcError> {
         self.set_state(MoqStreamState::Closing);
-        self.quic_stream.finish().await?;
+        self.quic_stream.lock().await.finish().await?;
         self.set_state(MoqStreamState::Completed);
         Ok(())
     }
@@ -372,20 +605,25 @@ impl ManagedMoqStream {
 impl MoqStreamManager {
     /// Create new MoQ stream manager
     pub fn new(
-        transport: Arc<RwLock<TransportConnection>>,
+        driver: Arc<dyn MoqTransportDriver>,
         session: Arc<RwLock<MoqSession>>,
         config: StreamManagerConfig,
     ) -> (Self, mpsc::UnboundedReceiver<MoqStreamEvent>) {
         let (event_tx, event_rx) = mpsc::unbounded_channel();
-        let stream_semaphore = Arc::new(Semaphore::new(config.max_concurrent_streams as usize));
+        let stream_credit = Arc::new(StreamCredit::new(config.max_concurrent_streams));
 
         let manager = Self {
-            transport,
+            driver,
             session,
             streams: Arc::new(RwLock::new(HashMap::new())),
             track_streams: Arc::new(RwLock::new(HashMap::new())),
             control_stream_id: Arc::new(RwLock::new(None)),
-            stream_semaphore,
+            control_sequence: Arc::new(Mutex::new(ControlSequence::default())),
+            peer_termination: Arc::new(Mutex::new(None)),
+            stream_credit,
+            latency_budgets: Arc::new(RwLock::new(HashMap::new())),
+            late_objects_dropped: Arc::new(AtomicU64::new(0)),
+            backpressure: BackpressureSignal::default(),
             event_tx,
             config,
         };
@@ -408,23 +646,12 @@ impl MoqStreamManager {
             }
         }
 
-        // Acquire permit for stream creation
-        let _permit =
-            self.stream_semaphore
-                .acquire()
-                .await
-                .map_err(|_| QuicRtcError::ResourceExhausted {
-                    resource: "Stream permits".to_string(),
-                })?;
-
         // Create bidirectional QUIC stream
-        let quic_stream = {
-            let mut transport = self.transport.write();
-            transport.open_stream(StreamType::Bidirectional).await?
-        };
+        let (quic_stream, credit_wait) = self.open_stream(StreamType::Bidirectional, 0).await?;
 
-        let stream_id = quic_stream.id;
+        let stream_id = quic_stream.id();
         let mut managed_stream = ManagedMoqStream::new_control(stream_id, quic_stream);
+        managed_stream.record_credit_wait(credit_wait);
         managed_stream.set_state(MoqStreamState::Active);
 
         // Store control stream
@@ -447,6 +674,34 @@ impl MoqStreamManager {
         Ok(stream_id)
     }
 
+    /// Take a stream the peer opened as the control stream, for sessions
+    /// the peer starts
+    pub fn adopt_control_stream(
+        &self,
+        quic_stream: Box<dyn DriverStream>,
+    ) -> Result<StreamId, QuicRtcError> {
+        let mut control_id = self.control_stream_id.write();
+        if control_id.is_some() {
+            return Err(QuicRtcError::InvalidState {
+                expected: "No control stream".to_string(),
+                actual: "Control stream already exists".to_string(),
+            });
+        }
+
+        let stream_id = quic_stream.id();
+        let mut managed_stream = ManagedMoqStream::new_control(stream_id, quic_stream);
+        managed_stream.set_state(MoqStreamState::Active);
+        self.streams.write().insert(stream_id, managed_stream);
+        *control_id = Some(stream_id);
+
+        let _ = self
+            .event_tx
+            .send(MoqStreamEvent::ControlStreamEstablished { stream_id });
+
+        info!("MoQ control stream adopted: {}", stream_id);
+        Ok(stream_id)
+    }
+
     /// Send control message on control stream
     pub async fn send_control_message(
         &self,
@@ -460,26 +715,33 @@ impl MoqStreamManager {
             })?
         };
 
-        // Encode control message
-        let mut buffer = BytesMut::new();
-        MoqWireFormat::encode_control_message(&message, &mut buffer)?;
-
-        // Send with timeout
-        let send_future = async {
-            let mut streams = self.streams.write();
-            if let Some(stream) = streams.get_mut(&control_stream_id) {
-                stream.quic_stream.send(&buffer).await?;
+        let quic_stream = self.quic_stream(control_stream_id)?;
+        let streams = Arc::clone(&self.streams);
+        let control_sequence = Arc::clone(&self.control_sequence);
+        let encoded = message.clone();
+        let write = tokio::spawn(async move {
+            // Numbered under the stream lock, so messages go out in the
+            // order of their numbers
+            let mut quic_stream = quic_stream.lock().await;
+            let mut buffer = BytesMut::new();
+            {
+                let mut control_sequence = control_sequence.lock();
+                MoqWireFormat::encode_sequenced_control_message(
+                    control_sequence.next_send,
+                    &encoded,
+                    &mut buffer,
+                )?;
+                control_sequence.next_send += 1;
+            }
+            quic_stream.send(&buffer).await?;
+            if let Some(stream) = streams.write().get_mut(&control_stream_id) {
                 stream.stats.bytes_sent += buffer.len() as u64;
                 stream.last_activity = Instant::now();
-                Ok(())
-            } else {
-                Err(QuicRtcError::StreamNotFound {
-                    stream_id: control_stream_id,
-                })
             }
-        };
+            Ok(())
+        });
 
-        timeout(self.config.control_stream_timeout, send_future)
+        timeout(self.config.control_stream_timeout, finish_write(write))
             .await
             .map_err(|_| QuicRtcError::Timeout {
                 operation: "Send control message".to_string(),
@@ -500,31 +762,43 @@ impl MoqStreamManager {
             })?
         };
 
+        if let Some(reason) = self.control_sequence.lock().violation.clone() {
+            return Err(QuicRtcError::MoqProtocol { reason });
+        }
+
         // Receive data with timeout
+        let quic_stream = self.quic_stream(control_stream_id)?;
         let receive_future = async {
-            let mut streams = self.streams.write();
-            if let Some(stream) = streams.get_mut(&control_stream_id) {
-                match stream.quic_stream.recv().await? {
-                    Some(bytes) => {
+            // Reads are cancel safe, so a timed out read loses no data
+            let received = quic_stream.lock().await.recv().await?;
+            match received {
+                Some(bytes) => {
+                    if let Some(stream) = self.streams.write().get_mut(&control_stream_id) {
                         stream.stats.bytes_received += bytes.len() as u64;
                         stream.last_activity = Instant::now();
-
-                        // Decode control message
-                        let message = MoqWireFormat::decode_control_message(&bytes)?;
-                        debug!("Received control message: {:?}", message);
-                        Ok(message)
                     }
-                    None => {
-                        // Stream closed
-                        Err(QuicRtcError::Transport {
-                            reason: "Control stream closed by peer".to_string(),
-                        })
+
+                    // Decode control message
+                    let (sequence, message) =
+                        MoqWireFormat::decode_sequenced_control_message(&bytes)?;
+                    self.control_sequence
+                        .lock()
+                        .accept(sequence)
+                        .inspect_err(|e| {
+                            error!("Terminating session: {}", e);
+                        })?;
+                    debug!("Received control message: {:?}", message);
+                    if let MoqControlMessage::Terminate { code, reason } = &message {
+                        *self.peer_termination.lock() = Some((*code, reason.clone()));
                     }
+                    Ok(message)
+                }
+                None => {
+                    // Stream closed
+                    Err(QuicRtcError::Transport {
+                        reason: "Control stream closed by peer".to_string(),
+                    })
                 }
-            } else {
-                Err(QuicRtcError::StreamNotFound {
-                    stream_id: control_stream_id,
-                })
             }
         };
 
@@ -536,6 +810,30 @@ impl MoqStreamManager {
             })?
     }
 
+    /// Why the session ended because a control message arrived replayed
+    /// or out of order, if one did
+    pub fn control_sequence_violation(&self) -> Option<String> {
+        self.control_sequence.lock().violation.clone()
+    }
+
+    /// Code and reason the peer ended the session with, if it sent a
+    /// TERMINATE
+    pub fn peer_termination(&self) -> Option<(u32, String)> {
+        self.peer_termination.lock().clone()
+    }
+
+    /// QUIC stream behind a managed stream
+    fn quic_stream(
+        &self,
+        stream_id: StreamId,
+    ) -> Result<Arc<tokio::sync::Mutex<Box<dyn DriverStream>>>, QuicRtcError> {
+        self.streams
+            .read()
+            .get(&stream_id)
+            .map(|stream| Arc::clone(&stream.quic_stream))
+            .ok_or(QuicRtcError::StreamNotFound { stream_id })
+    }
+
     /// Create data stream for track objects (Section 9.4)
     pub async fn create_data_stream(
         &self,
@@ -548,22 +846,12 @@ impl MoqStreamManager {
             track_alias, subgroup_id
         );
 
-        // Acquire permit for stream creation
-        let _permit =
-            self.stream_semaphore
-                .acquire()
-                .await
-                .map_err(|_| QuicRtcError::ResourceExhausted {
-                    resource: "Stream permits".to_string(),
-                })?;
-
         // Create unidirectional QUIC stream
-        let quic_stream = {
-            let mut transport = self.transport.write();
-            transport.open_stream(StreamType::Unidirectional).await?
-        };
+        let (quic_stream, credit_wait) = self
+            .open_stream(StreamType::Unidirectional, priority)
+            .await?;
 
-        let stream_id = quic_stream.id;
+        let stream_id = quic_stream.id();
         let mut managed_stream = ManagedMoqStream::new_data_subgroup(
             stream_id,
             track_alias,
@@ -571,6 +859,7 @@ impl MoqStreamManager {
             priority,
             quic_stream,
         );
+        managed_stream.record_credit_wait(credit_wait);
         managed_stream.set_state(MoqStreamState::Active);
 
         // Store stream
@@ -602,7 +891,75 @@ impl MoqStreamManager {
         Ok(stream_id)
     }
 
+    /// Open a QUIC stream once stream credit allows
+    ///
+    /// Returns the stream with the time spent waiting for credit, including
+    /// any wait inside the driver for the peer's stream limit.
+    async fn open_stream(
+        &self,
+        stream_type: StreamType,
+        priority: u8,
+    ) -> Result<(Box<dyn DriverStream>, Duration), QuicRtcError> {
+        let requested_at = Instant::now();
+        let queued = self.stream_credit.acquire(priority).await?;
+        let quic_stream = match self.driver.open_stream(stream_type).await {
+            Ok(quic_stream) => quic_stream,
+            Err(e) => {
+                self.stream_credit.release();
+                return Err(e);
+            }
+        };
+
+        let credit_wait = if queued.is_zero() {
+            Duration::ZERO
+        } else {
+            requested_at.elapsed()
+        };
+        if credit_wait > STREAM_CREDIT_WAIT_WARNING {
+            warn!(
+                "Stream opening (priority {}) waited {:?} for stream credit; \
+                 max_concurrent_streams ({}) may be too low",
+                priority, credit_wait, self.config.max_concurrent_streams
+            );
+            self.backpressure
+                .report(BackpressureReason::StreamCreditWait, None);
+        }
+        Ok((quic_stream, credit_wait))
+    }
+
+    /// Drop objects of a track once they are older than `latency_budget`,
+    /// usually the track's [`crate::moq::MoqTrack::latency_budget`]
+    ///
+    /// Late objects are not sent, and a stream whose write is still blocked
+    /// when the object's budget runs out is reset so the peer stops waiting
+    /// for the rest of it. Objects of tracks without a budget are sent
+    /// however late.
+    pub fn set_latency_budget(
+        &self,
+        track_namespace: &TrackNamespace,
+        latency_budget: Option<Duration>,
+    ) {
+        let mut latency_budgets = self.latency_budgets.write();
+        match latency_budget {
+            Some(budget) => {
+                latency_budgets.insert(track_namespace.clone(), budget);
+            }
+            None => {
+                latency_budgets.remove(track_namespace);
+            }
+        }
+    }
+
+    /// Congestion on this connection's send path: objects missing their
+    /// latency budget and stream openings waiting for credit
+    pub fn backpressure(&self) -> &BackpressureSignal {
+        &self.backpressure
+    }
+
     /// Send object on appropriate stream
+    ///
+    /// Fails with [`QuicRtcError::Timeout`] when the stream was reset
+    /// because the object missed its latency budget mid-write.
     pub async fn send_object(
         &self,
         object: MoqObject,
@@ -610,32 +967,108 @@ impl MoqStreamManager {
     ) -> Result<(), QuicRtcError> {
         let send_start = Instant::now();
 
+        let latency_budget = self
+            .latency_budgets
+            .read()
+            .get(&object.track_namespace)
+            .copied();
+        if object.exceeds_latency_budget(latency_budget) {
+            debug!(
+                "Dropping object {} of group {}: {:?} old, past its latency budget",
+                object.object_id,
+                object.group_id,
+                object.age()
+            );
+            self.late_objects_dropped
+                .fetch_add(1, AtomicOrdering::Relaxed);
+            self.backpressure.report(
+                BackpressureReason::LatencyBudgetExceeded,
+                Some(&object.track_namespace),
+            );
+            return Ok(());
+        }
+
         // Find or create appropriate stream for this object
         let stream_id = self
             .find_or_create_stream_for_object(&object, track_alias)
             .await?;
 
-        // Send object on the stream
-        {
+        // Take the object off the stream's queue; the write happens without
+        // holding the stream table
+        let (object, buffer, quic_stream) = {
             let mut streams = self.streams.write();
-            if let Some(stream) = streams.get_mut(&stream_id) {
-                stream.enqueue_object(object.clone())?;
-                if let Some(sent_object) = stream.send_next_object().await? {
-                    let delivery_latency = send_start.elapsed();
-
-                    // Send event
-                    let _ = self.event_tx.send(MoqStreamEvent::ObjectSent {
-                        stream_id,
-                        object: sent_object,
-                        delivery_latency,
+            let stream = streams
+                .get_mut(&stream_id)
+                .ok_or(QuicRtcError::StreamNotFound { stream_id })?;
+            stream.enqueue_object(object)?;
+            match stream.encode_next_object()? {
+                Some((object, buffer)) => (object, buffer, Arc::clone(&stream.quic_stream)),
+                None => return Ok(()),
+            }
+        };
+
+        let streams = Arc::clone(&self.streams);
+        let track_streams = Arc::clone(&self.track_streams);
+        let stream_credit = Arc::clone(&self.stream_credit);
+        let late_objects_dropped = Arc::clone(&self.late_objects_dropped);
+        let backpressure = self.backpressure.clone();
+        let event_tx = self.event_tx.clone();
+        let write = tokio::spawn(async move {
+            let write_start = Instant::now();
+            let send = async { quic_stream.lock().await.send(&buffer).await };
+            let sent = match latency_budget {
+                Some(budget) => timeout(budget.saturating_sub(object.age()), send)
+                    .await
+                    .map_err(|_| budget),
+                None => Ok(send.await),
+            };
+            let sent = match sent {
+                Ok(sent) => sent,
+                Err(budget) => {
+                    // The peer can't use the rest of the object in time
+                    late_objects_dropped.fetch_add(1, AtomicOrdering::Relaxed);
+                    backpressure.report(
+                        BackpressureReason::LatencyBudgetExceeded,
+                        Some(&object.track_namespace),
+                    );
+                    let _ = quic_stream
+                        .lock()
+                        .await
+                        .reset(DELIVERY_TIMEOUT_RESET_CODE)
+                        .await;
+                    if streams.write().remove(&stream_id).is_some() {
+                        stream_credit.release();
+                        for stream_list in track_streams.write().values_mut() {
+                            stream_list.retain(|&id| id != stream_id);
+                        }
+                        let _ = event_tx.send(MoqStreamEvent::StreamClosed {
+                            stream_id,
+                            reason: "Latency budget exceeded".to_string(),
+                        });
+                    }
+                    return Err(QuicRtcError::Timeout {
+                        operation: format!(
+                            "Sending object {} of group {} within its latency budget",
+                            object.object_id, object.group_id
+                        ),
+                        duration: budget,
                     });
                 }
-            } else {
-                return Err(QuicRtcError::StreamNotFound { stream_id });
+            };
+            sent?;
+            if let Some(stream) = streams.write().get_mut(&stream_id) {
+                stream.record_sent(&object, buffer.len(), write_start.elapsed());
             }
-        }
 
-        Ok(())
+            // Send event
+            let _ = event_tx.send(MoqStreamEvent::ObjectSent {
+                stream_id,
+                object,
+                delivery_latency: send_start.elapsed(),
+            });
+            Ok(())
+        });
+        finish_write(write).await
     }
 
     /// Find or create appropriate stream for object
@@ -663,6 +1096,27 @@ impl MoqStreamManager {
             }
         }
 
+        // Streams that reached max_objects_per_stream won't be used again;
+        // close them so their credit goes to the new stream
+        let full_streams: Vec<StreamId> = {
+            let track_streams = self.track_streams.read();
+            let streams = self.streams.read();
+            track_streams
+                .get(&track_alias)
+                .into_iter()
+                .flatten()
+                .filter(|stream_id| {
+                    streams.get(stream_id).is_some_and(|stream| {
+                        stream.stats.objects_sent >= self.config.max_objects_per_stream as u64
+                    })
+                })
+                .copied()
+                .collect()
+        };
+        for stream_id in full_streams {
+            self.close_stream(stream_id).await?;
+        }
+
         // Create new stream
         let subgroup_id = object.group_id;
         let priority = object.publisher_priority;
@@ -675,15 +1129,14 @@ impl MoqStreamManager {
     pub async fn close_stream(&self, stream_id: StreamId) -> Result<(), QuicRtcError> {
         debug!("Closing stream: {}", stream_id);
 
-        let reason = {
-            let mut streams = self.streams.write();
-            if let Some(mut stream) = streams.remove(&stream_id) {
-                let _ = stream.close().await;
-                "Normal closure".to_string()
-            } else {
-                return Err(QuicRtcError::StreamNotFound { stream_id });
-            }
+        // Removed before finishing so no new object lands on the stream
+        let stream = self.streams.write().remove(&stream_id);
+        let Some(mut stream) = stream else {
+            return Err(QuicRtcError::StreamNotFound { stream_id });
         };
+        let _ = stream.close().await;
+        self.stream_credit.release();
+        let reason = "Normal closure".to_string();
 
         // Remove from track mapping
         {
@@ -740,6 +1193,12 @@ impl MoqStreamManager {
         let total_objects_sent: u64 = streams.values().map(|s| s.stats.objects_sent).sum();
         let total_objects_received: u64 = streams.values().map(|s| s.stats.objects_received).sum();
 
+        let queued_stream_openings = self.stream_credit.queued();
+        let (blocked_stream_openings, stream_credit_wait) = {
+            let credit = self.stream_credit.state.lock();
+            (credit.blocked_openings, credit.total_wait)
+        };
+
         MoqStreamManagerStats {
             total_streams,
             control_streams,
@@ -749,20 +1208,41 @@ impl MoqStreamManager {
             total_bytes_received,
             total_objects_sent,
             total_objects_received,
+            queued_stream_openings,
+            blocked_stream_openings,
+            stream_credit_wait,
+            late_objects_dropped: self.late_objects_dropped.load(AtomicOrdering::Relaxed),
         }
     }
 }
 
+/// Wait for a stream write running on its own task
+///
+/// Writes are spawned so that a caller cancelled or timed out mid-write
+/// can't leave half a message on the stream.
+async fn finish_write(
+    write: tokio::task::JoinHandle<Result<(), QuicRtcError>>,
+) -> Result<(), QuicRtcError> {
+    write.await.map_err(|e| QuicRtcError::Transport {
+        reason: format!("Stream write task failed: {}", e),
+    })?
+}
+
 // Clone implementation for sharing stream manager across tasks
 impl Clone for MoqStreamManager {
     fn clone(&self) -> Self {
         Self {
-            transport: Arc::clone(&self.transport),
+            driver: Arc::clone(&self.driver),
             session: Arc::clone(&self.session),
             streams: Arc::clone(&self.streams),
             track_streams: Arc::clone(&self.track_streams),
             control_stream_id: Arc::clone(&self.control_stream_id),
-            stream_semaphore: Arc::clone(&self.stream_semaphore),
+            control_sequence: Arc::clone(&self.control_sequence),
+            peer_termination: Arc::clone(&self.peer_termination),
+            stream_credit: Arc::clone(&self.stream_credit),
+            latency_budgets: Arc::clone(&self.latency_budgets),
+            late_objects_dropped: Arc::clone(&self.late_objects_dropped),
+            backpressure: self.backpressure.clone(),
             event_tx: self.event_tx.clone(),
             config: self.config.clone(),
         }
@@ -783,7 +1263,7 @@ mod tests {
             recv: None,
         };
 
-        let control_stream = ManagedMoqStream::new_control(123, quic_stream);
+        let control_stream = ManagedMoqStream::new_control(123, Box::new(quic_stream));
         assert_eq!(control_stream.stream_id, 123);
         assert_eq!(control_stream.stream_type, MoqStreamType::Control);
         assert_eq!(control_stream.state, MoqStreamState::Opening);
@@ -796,7 +1276,8 @@ mod tests {
             recv: None,
         };
 
-        let data_stream = ManagedMoqStream::new_data_subgroup(456, 1, 100, 128, quic_stream2);
+        let data_stream =
+            ManagedMoqStream::new_data_subgroup(456, 1, 100, 128, Box::new(quic_stream2));
         assert_eq!(data_stream.stream_id, 456);
         assert_eq!(data_stream.stream_type, MoqStreamType::DataSubgroup);
         assert_eq!(data_stream.track_alias, Some(1));
@@ -813,7 +1294,8 @@ mod tests {
             recv: None,
         };
 
-        let mut stream = ManagedMoqStream::new_data_subgroup(789, 2, 200, 64, quic_stream);
+        let mut stream =
+            ManagedMoqStream::new_data_subgroup(789, 2, 200, 64, Box::new(quic_stream));
 
         assert_eq!(stream.state, MoqStreamState::Opening);
 
@@ -825,6 +1307,45 @@ mod tests {
         assert_eq!(stream.state, MoqStreamState::Closing);
     }
 
+    #[tokio::test]
+    async fn test_stream_credit_queues_openings_by_priority() {
+        let credit = Arc::new(StreamCredit::new(1));
+        assert_eq!(credit.acquire(100).await.unwrap(), Duration::ZERO);
+
+        // Queue a low-priority opening, then a high-priority one
+        let granted = Arc::new(Mutex::new(Vec::new()));
+        let mut openings = Vec::new();
+        for priority in [200, 1] {
+            let opening_credit = Arc::clone(&credit);
+            let granted = Arc::clone(&granted);
+            openings.push(tokio::spawn(async move {
+                let waited = opening_credit.acquire(priority).await.unwrap();
+                granted.lock().push(priority);
+                opening_credit.release();
+                waited
+            }));
+            while credit.queued() < openings.len() as u32 {
+                tokio::task::yield_now().await;
+            }
+        }
+
+        // An opening given up on leaves the queue without taking credit
+        let abandoned = tokio::time::timeout(Duration::from_millis(10), credit.acquire(0)).await;
+        assert!(abandoned.is_err());
+        assert_eq!(credit.queued(), 2);
+
+        credit.release();
+        for opening in openings {
+            assert!(opening.await.unwrap() > Duration::ZERO);
+        }
+        assert_eq!(*granted.lock(), vec![1, 200]);
+
+        let state = credit.state.lock();
+        assert_eq!(state.available, 1);
+        assert_eq!(state.blocked_openings, 2);
+        assert!(state.total_wait > Duration::ZERO);
+    }
+
     #[test]
     fn test_stream_manager_config() {
         let config = StreamManagerConfig::default();
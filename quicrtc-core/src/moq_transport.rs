@@ -3,19 +3,32 @@
 //! This module provides the integration between IETF Media over QUIC (MoQ) protocol
 //! and QUIC transport, implementing the core functionality for MoQ over QUIC communication.
 
+use crate::backpressure::BackpressureSignal;
+use crate::driver::{DriverEvent, DriverStream, MoqTransportDriver, QuinnDriver};
 use crate::error::QuicRtcError;
+use crate::fragment::{DatagramFragmenter, FragmentConfig, FragmentStats};
+use crate::integrity::{IntegrityStats, ObjectIntegrity};
 use crate::moq::{
-    MoqObject, MoqSession, MoqSessionState, MoqStreamManager, MoqStreamType, MoqSubscription,
-    MoqTrack, StreamId, StreamManagerConfig, TrackNamespace,
+    DvrBuffer, DvrHandle, KeyframeCache, MoqCapabilities, MoqControlMessage, MoqErrorCode,
+    MoqNamespaceSubscription, MoqObject, MoqPermissions, MoqSession, MoqSessionState,
+    MoqStreamManager, MoqStreamType, MoqSubscription, MoqSubscriptionState, MoqTrack, MoqTrackType,
+    MoqWireFormat, StreamId, StreamManagerConfig, TrackAlias, TrackAuthorizer, TrackNamespace,
+    TrackNamespacePattern, TrackTransferStats,
+};
+use crate::telemetry::{default_sink, DiagnosticsSink, LogLevel};
+use crate::transport::{
+    ConnectionConfig, StreamType, TransportConnection, SERVER_OVERLOADED_ERROR_CODE,
 };
-use crate::transport::{ConnectionConfig, QuicStream, StreamType, TransportConnection};
 use bytes::Bytes;
-use parking_lot::RwLock;
-use std::collections::HashMap;
+use parking_lot::{Mutex, RwLock};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::mpsc;
-use tracing::{debug, info};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, mpsc};
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 /// MoQ stream wrapper with metadata
@@ -28,12 +41,12 @@ pub struct MoqStream {
     /// Associated track namespace (for data streams)
     pub track_namespace: Option<TrackNamespace>,
     /// Underlying QUIC stream
-    pub quic_stream: QuicStream,
+    pub quic_stream: Box<dyn DriverStream>,
 }
 
 impl MoqStream {
     /// Create a new MoQ control stream
-    pub fn control(stream_id: StreamId, quic_stream: QuicStream) -> Self {
+    pub fn control(stream_id: StreamId, quic_stream: Box<dyn DriverStream>) -> Self {
         Self {
             stream_id,
             stream_type: MoqStreamType::Control,
@@ -46,7 +59,7 @@ impl MoqStream {
     pub fn data(
         stream_id: StreamId,
         track_namespace: TrackNamespace,
-        quic_stream: QuicStream,
+        quic_stream: Box<dyn DriverStream>,
     ) -> Self {
         Self {
             stream_id,
@@ -73,26 +86,71 @@ impl MoqStream {
 }
 
 /// MoQ over QUIC transport integration
+///
+/// Runs over any [`MoqTransportDriver`]; [`MoqOverQuicTransport::new`] uses
+/// the built-in [`QuinnDriver`].
 #[derive(Debug)]
 pub struct MoqOverQuicTransport {
     /// Transport connection ID
     connection_id: Uuid,
-    /// QUIC transport connection
-    quic_connection: Arc<RwLock<TransportConnection>>,
+    /// QUIC stack carrying the session
+    driver: Arc<dyn MoqTransportDriver>,
     /// MoQ session
     moq_session: Arc<RwLock<MoqSession>>,
     /// MoQ stream manager
     stream_manager: Arc<MoqStreamManager>,
+    /// Held for the length of a request/response exchange on the control stream
+    control_exchange: Arc<tokio::sync::Mutex<()>>,
+    /// Timeouts and retries for control round-trips
+    control_retry_policy: RwLock<ControlRetryPolicy>,
     /// Track to stream mapping for data streams
     track_streams: Arc<RwLock<HashMap<TrackNamespace, StreamId>>>,
     /// Object delivery queue
-    object_queue: Arc<RwLock<Vec<MoqObject>>>,
-    /// Event channels
-    event_tx: mpsc::UnboundedSender<MoqTransportEvent>,
-    event_rx: Arc<RwLock<Option<mpsc::UnboundedReceiver<MoqTransportEvent>>>>,
+    object_queue: Arc<ObjectQueue>,
+    /// Encoded frame transforms applied on send and receive
+    frame_transforms: Arc<RwLock<FrameTransforms>>,
+    /// Integrity hashes attached on send and verified on receive
+    object_integrity: RwLock<Option<ObjectIntegrity>>,
+    /// Splits datagram objects to fit the path and reassembles them
+    fragmenter: DatagramFragmenter,
+    /// Where log lines and metrics of this transport go
+    diagnostics: RwLock<Arc<dyn DiagnosticsSink>>,
+    /// Newest keyframe group per outgoing video track, replayed to new subscribers
+    keyframe_cache: Arc<RwLock<KeyframeCache>>,
+    /// Object counters per track, in both directions
+    track_stats: Arc<RwLock<HashMap<TrackNamespace, TrackTransferStats>>>,
+    /// Time-shift buffers of incoming tracks that can be paused and seeked
+    dvr_buffers: Arc<RwLock<HashMap<TrackNamespace, DvrHandle>>>,
+    /// Subscribed tracks and tracks the peer announced by their wire alias,
+    /// naming incoming objects
+    track_aliases: Arc<RwLock<HashMap<TrackAlias, TrackNamespace>>>,
+    /// Bidirectional streams opened by the peer, the first of which is the
+    /// control stream of a session it starts
+    peer_control_streams: tokio::sync::Mutex<mpsc::UnboundedReceiver<Box<dyn DriverStream>>>,
+    /// Where transport events are delivered
+    events: TransportEvents,
 }
 
 /// Events from MoQ transport
+///
+/// Events are delivered through [`MoqOverQuicTransport::take_event_receiver`]
+/// and every [`MoqOverQuicTransport::subscribe_events`] receiver in the
+/// order they happened, with these guarantees:
+///
+/// - `SessionEstablished` is emitted once, when the peer accepts SETUP.
+/// - `TrackAnnounced` and `SubscriptionRequested` are emitted after our
+///   reply to the peer was sent, and only if the request was accepted.
+///   `SubscriptionAccepted` is emitted before
+///   [`MoqOverQuicTransport::subscribe_to_track`] returns.
+/// - `StreamEstablished` for a peer stream comes before any
+///   `ObjectReceived` read from it, and `StreamReset` after the last one.
+///   Objects of one stream are reported in the order they were sent;
+///   objects of different streams and datagrams may interleave.
+/// - `TransportError` reports a failure that may or may not end the session.
+/// - `SessionTerminated` is emitted once, whichever side or failure ended
+///   the session, and is always the last event. A TERMINATE from the peer
+///   counts once read during a control exchange or passed to
+///   [`MoqOverQuicTransport::handle_session_termination`].
 #[derive(Debug, Clone)]
 pub enum MoqTransportEvent {
     /// Session established successfully
@@ -114,10 +172,22 @@ pub enum MoqTransportEvent {
         /// Subscription priority
         priority: u8,
     },
-    /// Object received
+    /// Peer accepted one of our subscriptions
+    SubscriptionAccepted {
+        /// Track namespace
+        track_namespace: TrackNamespace,
+        /// Subscription as accepted
+        subscription: MoqSubscription,
+    },
+    /// Object received and queued for
+    /// [`MoqOverQuicTransport::receive_moq_object`]
     ObjectReceived {
-        /// MoQ object
-        object: MoqObject,
+        /// Track the object belongs to
+        track_namespace: TrackNamespace,
+        /// Group ID
+        group_id: u64,
+        /// Object ID within the group
+        object_id: u64,
     },
     /// Stream established
     StreamEstablished {
@@ -128,11 +198,202 @@ pub enum MoqTransportEvent {
         /// Track namespace (for data streams)
         track_namespace: Option<TrackNamespace>,
     },
+    /// Peer stream ended abruptly; objects still in flight on it are lost
+    StreamReset {
+        /// Stream ID
+        stream_id: StreamId,
+        /// Why the stream ended
+        reason: String,
+    },
     /// Transport error
     TransportError {
         /// Error message
         error: String,
     },
+    /// Session ended; no events follow
+    SessionTerminated {
+        /// MoQ error code sent in TERMINATE, or `None` when the connection
+        /// closed without one
+        code: Option<u32>,
+        /// Why the session ended
+        reason: String,
+    },
+}
+
+/// Buffered events per [`MoqOverQuicTransport::subscribe_events`] receiver
+const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Sending side of the transport's event channels
+///
+/// Clones share the channels. Nothing is sent after `SessionTerminated`.
+#[derive(Debug, Clone)]
+struct TransportEvents {
+    broadcast: broadcast::Sender<MoqTransportEvent>,
+    state: Arc<Mutex<EventsState>>,
+}
+
+/// What clones of [`TransportEvents`] share besides the broadcast channel
+#[derive(Debug, Default)]
+struct EventsState {
+    /// Channel behind the receiver handed out by `take_receiver`, created
+    /// then so no events pile up before anyone reads them
+    sender: Option<mpsc::UnboundedSender<MoqTransportEvent>>,
+    /// Whether `take_receiver` was called
+    taken: bool,
+    /// Whether `SessionTerminated` was sent, held across each send so no
+    /// event can follow it
+    terminated: bool,
+}
+
+impl TransportEvents {
+    fn new() -> Self {
+        let (broadcast, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self {
+            broadcast,
+            state: Arc::new(Mutex::new(EventsState::default())),
+        }
+    }
+
+    fn send(&self, event: MoqTransportEvent) {
+        let mut state = self.state.lock();
+        if state.terminated {
+            return;
+        }
+        state.terminated = matches!(event, MoqTransportEvent::SessionTerminated { .. });
+        // Receivers may have been dropped; that's not an error
+        match &state.sender {
+            Some(sender) => {
+                let _ = self.broadcast.send(event.clone());
+                let _ = sender.send(event);
+            }
+            None => {
+                let _ = self.broadcast.send(event);
+            }
+        }
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<MoqTransportEvent> {
+        self.broadcast.subscribe()
+    }
+
+    /// The one unbounded receiver, getting events sent from now on
+    fn take_receiver(&self) -> Option<mpsc::UnboundedReceiver<MoqTransportEvent>> {
+        let mut state = self.state.lock();
+        if state.taken {
+            return None;
+        }
+        state.taken = true;
+        let (sender, receiver) = mpsc::unbounded_channel();
+        if !state.terminated {
+            state.sender = Some(sender);
+        }
+        Some(receiver)
+    }
+}
+
+/// Incoming objects kept for [`MoqOverQuicTransport::receive_moq_object`]
+const OBJECT_QUEUE_CAPACITY: usize = 1024;
+
+/// Incoming objects waiting to be received, oldest first
+///
+/// Holds at most [`OBJECT_QUEUE_CAPACITY`] objects. When nobody receives
+/// them fast enough the oldest are dropped: late live media is worthless,
+/// and an unread queue must not grow for the length of the session.
+#[derive(Debug, Default)]
+struct ObjectQueue {
+    objects: Mutex<VecDeque<MoqObject>>,
+    /// Objects dropped to make room for newer ones
+    dropped: AtomicU64,
+}
+
+impl ObjectQueue {
+    fn push(&self, object: MoqObject) {
+        let mut objects = self.objects.lock();
+        if objects.len() >= OBJECT_QUEUE_CAPACITY {
+            objects.pop_front();
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        objects.push_back(object);
+    }
+
+    fn pop(&self) -> Option<MoqObject> {
+        self.objects.lock().pop_front()
+    }
+
+    fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// Timeout and retry policy for control round-trips
+///
+/// A request the peer doesn't answer within `response_timeout` is sent
+/// again unchanged. Requests are keyed by track namespace or pattern, so a
+/// peer that sees one twice ends up in the same state, and an answer to an
+/// earlier attempt is as good as one to the latest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ControlRetryPolicy {
+    /// How long to wait for the peer to answer one attempt
+    pub response_timeout: Duration,
+    /// Attempts made at most, counting the first
+    pub max_attempts: u32,
+    /// Wait before the first resend, doubled for each one after
+    pub backoff: Duration,
+}
+
+impl Default for ControlRetryPolicy {
+    fn default() -> Self {
+        Self {
+            response_timeout: Duration::from_secs(5),
+            max_attempts: 3,
+            backoff: Duration::from_millis(250),
+        }
+    }
+}
+
+/// Direction of an encoded frame passing through a transform
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransformDirection {
+    /// Object is about to be sent
+    Outgoing,
+    /// Object has just been received
+    Incoming,
+}
+
+/// Transform run on encoded MoQ objects before send and after receive
+///
+/// Transforms may rewrite the payload (e.g. end-to-end encryption or
+/// watermarking) or attach metadata. Returning an error drops the object.
+pub type EncodedFrameTransform =
+    Arc<dyn Fn(TransformDirection, &mut MoqObject) -> Result<(), QuicRtcError> + Send + Sync>;
+
+/// Encoded frame transforms keyed by track
+#[derive(Default)]
+struct FrameTransforms {
+    transforms: HashMap<TrackNamespace, EncodedFrameTransform>,
+}
+
+impl FrameTransforms {
+    /// Run the transform registered for the object's track, if any
+    fn apply(
+        &self,
+        direction: TransformDirection,
+        object: &mut MoqObject,
+    ) -> Result<(), QuicRtcError> {
+        if let Some(transform) = self.transforms.get(&object.track_namespace) {
+            transform(direction, object)?;
+            object.size = object.payload.len();
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for FrameTransforms {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FrameTransforms")
+            .field("tracks", &self.transforms.keys().collect::<Vec<_>>())
+            .finish()
+    }
 }
 
 impl MoqOverQuicTransport {
@@ -147,21 +408,30 @@ impl MoqOverQuicTransport {
         // Establish QUIC connection
         let quic_connection =
             TransportConnection::establish_with_fallback(endpoint, config).await?;
-        let connection_id = quic_connection.connection_id();
 
         info!(
             "QUIC connection established using {:?}",
             quic_connection.current_transport_mode()
         );
 
+        let driver = Arc::new(QuinnDriver::new(quic_connection));
+        Ok(Self::with_driver(driver, session_id))
+    }
+
+    /// Create a MoQ transport over an already connected driver
+    ///
+    /// Incoming objects are read on a background task, so this must be
+    /// called from within a Tokio runtime.
+    pub fn with_driver(driver: Arc<dyn MoqTransportDriver>, session_id: u64) -> Self {
+        let connection_id = driver.connection_id();
+
         // Create MoQ session
-        let mut moq_session = MoqSession::new(session_id);
+        let moq_session = MoqSession::new(session_id);
         let moq_session_arc = Arc::new(RwLock::new(moq_session));
 
         // Create stream manager with the session and transport
-        let quic_connection_arc = Arc::new(RwLock::new(quic_connection));
         let (stream_manager, _stream_events) = MoqStreamManager::new(
-            Arc::clone(&quic_connection_arc),
+            Arc::clone(&driver),
             Arc::clone(&moq_session_arc),
             StreamManagerConfig::default(),
         );
@@ -174,25 +444,170 @@ impl MoqOverQuicTransport {
         }
 
         // Create event channel
-        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        let events = TransportEvents::new();
+        let object_queue = Arc::new(ObjectQueue::default());
+        let fragmenter = DatagramFragmenter::new();
+        let track_aliases = Arc::new(RwLock::new(HashMap::new()));
+        let (control_stream_tx, peer_control_streams) = mpsc::unbounded_channel();
+
+        if let Some(driver_events) = driver.take_events() {
+            Self::spawn_driver_pump(
+                driver_events,
+                Arc::clone(&object_queue),
+                fragmenter.clone(),
+                Arc::clone(&track_aliases),
+                control_stream_tx,
+                events.clone(),
+            );
+        }
 
-        let transport = Self {
+        debug!(
+            "MoQ over QUIC transport created with connection ID: {}",
+            connection_id
+        );
+
+        Self {
             connection_id,
-            quic_connection: quic_connection_arc,
+            driver,
             moq_session: moq_session_arc,
             stream_manager: stream_manager_arc,
+            control_exchange: Arc::new(tokio::sync::Mutex::new(())),
+            control_retry_policy: RwLock::new(ControlRetryPolicy::default()),
             track_streams: Arc::new(RwLock::new(HashMap::new())),
-            object_queue: Arc::new(RwLock::new(Vec::new())),
-            event_tx,
-            event_rx: Arc::new(RwLock::new(Some(event_rx))),
-        };
+            object_queue,
+            frame_transforms: Arc::new(RwLock::new(FrameTransforms::default())),
+            object_integrity: RwLock::new(None),
+            fragmenter,
+            diagnostics: RwLock::new(default_sink()),
+            keyframe_cache: Arc::new(RwLock::new(KeyframeCache::new())),
+            track_stats: Arc::new(RwLock::new(HashMap::new())),
+            dvr_buffers: Arc::new(RwLock::new(HashMap::new())),
+            track_aliases,
+            peer_control_streams: tokio::sync::Mutex::new(peer_control_streams),
+            events,
+        }
+    }
 
-        debug!(
-            "MoQ over QUIC transport created with connection ID: {}",
-            connection_id
-        );
+    /// Decode objects arriving on peer streams and datagrams into the object
+    /// queue, passing on bidirectional peer streams as control streams
+    fn spawn_driver_pump(
+        mut driver_events: mpsc::UnboundedReceiver<DriverEvent>,
+        object_queue: Arc<ObjectQueue>,
+        fragmenter: DatagramFragmenter,
+        track_aliases: Arc<RwLock<HashMap<TrackAlias, TrackNamespace>>>,
+        control_streams: mpsc::UnboundedSender<Box<dyn DriverStream>>,
+        events: TransportEvents,
+    ) {
+        tokio::spawn(async move {
+            while let Some(event) = driver_events.recv().await {
+                match event {
+                    // Empty datagrams are keep-alive heartbeats
+                    DriverEvent::DatagramReceived(data) if data.is_empty() => {}
+                    DriverEvent::DatagramReceived(data) => {
+                        match MoqWireFormat::decode_object_datagram(&data) {
+                            Ok((track_alias, object)) => {
+                                if let Some(object) = fragmenter.reassemble(track_alias, object) {
+                                    let object =
+                                        Self::name_object(&track_aliases, track_alias, object);
+                                    Self::queue_incoming_object(&object_queue, &events, object)
+                                }
+                            }
+                            Err(e) => warn!("Dropping malformed object datagram: {}", e),
+                        }
+                    }
+                    DriverEvent::StreamOpened(stream)
+                        if stream.stream_type() == StreamType::Bidirectional =>
+                    {
+                        events.send(MoqTransportEvent::StreamEstablished {
+                            stream_id: stream.id(),
+                            stream_type: MoqStreamType::Control,
+                            track_namespace: None,
+                        });
+                        // Unclaimed unless accept_session is waiting for it
+                        let _ = control_streams.send(stream);
+                    }
+                    DriverEvent::StreamOpened(mut stream) => {
+                        events.send(MoqTransportEvent::StreamEstablished {
+                            stream_id: stream.id(),
+                            stream_type: MoqStreamType::DataSubgroup,
+                            track_namespace: None,
+                        });
+
+                        let object_queue = Arc::clone(&object_queue);
+                        let track_aliases = Arc::clone(&track_aliases);
+                        let events = events.clone();
+                        tokio::spawn(async move {
+                            // The stream manager writes one object per chunk
+                            loop {
+                                let chunk = match stream.recv().await {
+                                    Ok(Some(chunk)) => chunk,
+                                    Ok(None) => break,
+                                    Err(e) => {
+                                        events.send(MoqTransportEvent::StreamReset {
+                                            stream_id: stream.id(),
+                                            reason: e.to_string(),
+                                        });
+                                        break;
+                                    }
+                                };
+                                match MoqWireFormat::decode_object_stream(&chunk) {
+                                    Ok((track_alias, object)) => {
+                                        let object =
+                                            Self::name_object(&track_aliases, track_alias, object);
+                                        Self::queue_incoming_object(&object_queue, &events, object)
+                                    }
+                                    Err(e) => warn!(
+                                        "Dropping malformed object on stream {}: {}",
+                                        stream.id(),
+                                        e
+                                    ),
+                                }
+                            }
+                        });
+                    }
+                    DriverEvent::Closed { reason } => {
+                        events.send(MoqTransportEvent::TransportError {
+                            error: reason.clone(),
+                        });
+                        events.send(MoqTransportEvent::SessionTerminated { code: None, reason });
+                        break;
+                    }
+                }
+            }
+        });
+    }
 
-        Ok(transport)
+    /// Give an incoming object the namespace of the subscribed or
+    /// peer-announced track its alias belongs to
+    ///
+    /// The wire format only carries the alias; objects of tracks we neither
+    /// subscribed to nor accepted an announcement of keep the placeholder
+    /// namespace they were decoded with.
+    fn name_object(
+        track_aliases: &RwLock<HashMap<TrackAlias, TrackNamespace>>,
+        track_alias: TrackAlias,
+        mut object: MoqObject,
+    ) -> MoqObject {
+        if let Some(track_namespace) = track_aliases.read().get(&track_alias) {
+            object.track_name = track_namespace.track_name.clone();
+            object.track_namespace = track_namespace.clone();
+        }
+        object
+    }
+
+    /// Queue an incoming object for [`Self::receive_moq_object`]
+    fn queue_incoming_object(
+        object_queue: &ObjectQueue,
+        events: &TransportEvents,
+        object: MoqObject,
+    ) {
+        let event = MoqTransportEvent::ObjectReceived {
+            track_namespace: object.track_namespace.clone(),
+            group_id: object.group_id,
+            object_id: object.object_id,
+        };
+        object_queue.push(object);
+        events.send(event);
     }
 
     /// Establish MoQ session over QUIC
@@ -203,14 +618,21 @@ impl MoqOverQuicTransport {
         let control_stream_id = self.stream_manager.establish_control_stream().await?;
         info!("Control stream established: {}", control_stream_id);
 
-        // Establish MoQ session
-        {
-            let mut session = self.moq_session.write();
-            session.establish_session().await?;
-        }
+        // Establish MoQ session. A peer only answers SETUP once, so it is
+        // never resent.
+        let policy = ControlRetryPolicy {
+            max_attempts: 1,
+            ..self.control_retry_policy()
+        };
+        self.exchange(|session, stream_manager| async move {
+            let setup_msg = session.read().setup_request()?;
+            let response = round_trip(&stream_manager, setup_msg, policy).await?;
+            session.write().handle_setup_response(response)
+        })
+        .await?;
 
         // Send session established event
-        let _ = self.event_tx.send(MoqTransportEvent::SessionEstablished {
+        self.events.send(MoqTransportEvent::SessionEstablished {
             session_id: self.moq_session.read().session_id(),
         });
 
@@ -218,22 +640,234 @@ impl MoqOverQuicTransport {
         Ok(())
     }
 
+    /// Accept a MoQ session the peer starts on this connection
+    ///
+    /// The server side of [`Self::establish_session`]: waits for the peer's
+    /// control stream and answers its SETUP. `admit` checks the peer's
+    /// capabilities, such as its auth token, before the session is
+    /// accepted; its error is sent back in SETUP_ERROR and returned.
+    pub async fn accept_session<F>(&self, admit: F) -> Result<(), QuicRtcError>
+    where
+        F: FnOnce(&MoqCapabilities) -> Result<(), QuicRtcError>,
+    {
+        info!("Accepting MoQ session");
+
+        let wait = self.control_retry_policy().response_timeout;
+        let stream = tokio::time::timeout(wait, async {
+            self.peer_control_streams.lock().await.recv().await
+        })
+        .await
+        .map_err(|_| QuicRtcError::Timeout {
+            operation: "Wait for control stream".to_string(),
+            duration: wait,
+        })?
+        .ok_or_else(|| QuicRtcError::Transport {
+            reason: "Connection closed before the peer opened a control stream".to_string(),
+        })?;
+        let control_stream_id = self.stream_manager.adopt_control_stream(stream)?;
+        info!("Control stream accepted: {}", control_stream_id);
+
+        let (version, capabilities) = match self.stream_manager.receive_control_message().await? {
+            MoqControlMessage::Setup {
+                version,
+                capabilities,
+            } => (version, capabilities),
+            other => {
+                return Err(QuicRtcError::ProtocolError {
+                    message: format!("Expected SETUP, got {}", request_name(&other)),
+                })
+            }
+        };
+        let admission = admit(&capabilities);
+        let reply =
+            self.moq_session
+                .write()
+                .answer_setup_request(version, capabilities, admission)?;
+        self.stream_manager
+            .send_control_message(reply.message)
+            .await?;
+        reply.outcome?;
+
+        self.events.send(MoqTransportEvent::SessionEstablished {
+            session_id: self.moq_session.read().session_id(),
+        });
+
+        info!("MoQ session accepted");
+        Ok(())
+    }
+
+    /// Answer the peer's control requests until the session ends
+    ///
+    /// For the accepting side of a session (see [`Self::accept_session`]),
+    /// which makes no control requests of its own while this runs. Rejected
+    /// requests are answered and logged. Returns once the peer terminates
+    /// the session, or with the error that ended the control stream.
+    pub async fn serve_peer_requests(&self) -> Result<(), QuicRtcError> {
+        loop {
+            let message = match self.stream_manager.receive_control_message().await {
+                Ok(message) => message,
+                // A quiet peer is no reason to stop
+                Err(QuicRtcError::Timeout { .. }) => continue,
+                Err(e) => {
+                    terminate_on_sequence_violation(
+                        &self.moq_session,
+                        &self.stream_manager,
+                        &self.events,
+                    )
+                    .await;
+                    return Err(e);
+                }
+            };
+
+            let request = request_name(&message);
+            let result = match message {
+                MoqControlMessage::Announce {
+                    track_namespace,
+                    track,
+                    auth_token,
+                } => {
+                    self.handle_track_announcement(track_namespace, track, auth_token)
+                        .await
+                }
+                MoqControlMessage::Subscribe {
+                    track_namespace,
+                    priority,
+                    start_group,
+                    end_group,
+                    auth_token,
+                } => {
+                    self.handle_subscription_request(
+                        track_namespace,
+                        priority,
+                        start_group,
+                        end_group,
+                        auth_token,
+                    )
+                    .await
+                }
+                MoqControlMessage::SubscribeUpdate {
+                    track_namespace,
+                    priority,
+                    start_group,
+                    end_group,
+                } => self.handle_subscription_update(
+                    &track_namespace,
+                    priority,
+                    start_group,
+                    end_group,
+                ),
+                MoqControlMessage::Unsubscribe { track_namespace } => {
+                    self.moq_session
+                        .write()
+                        .handle_unsubscribe(&track_namespace);
+                    Ok(())
+                }
+                MoqControlMessage::SubscribeNamespace { pattern } => {
+                    self.answer_namespace_subscription(pattern).await
+                }
+                MoqControlMessage::UnsubscribeNamespace { pattern } => {
+                    self.moq_session
+                        .write()
+                        .handle_unsubscribe_namespace(&pattern);
+                    Ok(())
+                }
+                MoqControlMessage::Terminate { code, reason } => {
+                    self.handle_session_termination(code, reason);
+                    return Ok(());
+                }
+                other => {
+                    debug!("Ignoring unexpected control message: {:?}", other);
+                    Ok(())
+                }
+            };
+            if let Err(e) = result {
+                warn!("Rejected {} from peer: {}", request, e);
+            }
+        }
+    }
+
+    /// Answer a namespace subscription without announcing the matching
+    /// tracks again, since the peer doesn't read requests from us
+    async fn answer_namespace_subscription(
+        &self,
+        pattern: TrackNamespacePattern,
+    ) -> Result<(), QuicRtcError> {
+        let reply = self
+            .moq_session
+            .write()
+            .answer_namespace_subscription_request(pattern)?;
+        self.stream_manager
+            .send_control_message(reply.message)
+            .await?;
+        reply.outcome.map(|_| ())
+    }
+
+    /// Run a request/response exchange on the control stream
+    ///
+    /// Exchanges run one at a time so each response reaches the request
+    /// that asked for it. The exchange runs on its own task: a caller that
+    /// is cancelled or times out midway leaves it to finish, so no response
+    /// is left unread on the stream and the session still records the
+    /// outcome.
+    async fn exchange<T, F, Fut>(&self, exchange: F) -> Result<T, QuicRtcError>
+    where
+        F: FnOnce(Arc<RwLock<MoqSession>>, Arc<MoqStreamManager>) -> Fut,
+        Fut: Future<Output = Result<T, QuicRtcError>> + Send + 'static,
+        T: Send + 'static,
+    {
+        let turn = Arc::clone(&self.control_exchange);
+        let session = Arc::clone(&self.moq_session);
+        let stream_manager = Arc::clone(&self.stream_manager);
+        let diagnostics = self.diagnostics();
+        let events = self.events.clone();
+        let exchange = exchange(Arc::clone(&session), Arc::clone(&stream_manager));
+        tokio::spawn(async move {
+            let _turn = turn.lock_owned().await;
+            let started = Instant::now();
+            let result = exchange.await;
+            diagnostics.histogram(
+                "moq.control_exchange_ms",
+                started.elapsed().as_secs_f64() * 1000.0,
+            );
+            if result.is_err() {
+                diagnostics.counter("moq.control_exchange_failures", 1);
+                terminate_on_sequence_violation(&session, &stream_manager, &events).await;
+                if let Some((code, reason)) = stream_manager.peer_termination() {
+                    peer_terminated(&session, &events, code, reason);
+                }
+            }
+            result
+        })
+        .await
+        .map_err(|e| QuicRtcError::Transport {
+            reason: format!("Control exchange task failed: {}", e),
+        })?
+    }
+
+    /// Change how long control round-trips wait for the peer and how often
+    /// they are retried
+    pub fn set_control_retry_policy(&self, policy: ControlRetryPolicy) {
+        *self.control_retry_policy.write() = policy;
+    }
+
+    /// Timeout and retry policy for control round-trips
+    pub fn control_retry_policy(&self) -> ControlRetryPolicy {
+        *self.control_retry_policy.read()
+    }
+
     /// Create a control stream for MoQ session management
     async fn create_control_stream(&self) -> Result<MoqStream, QuicRtcError> {
         debug!("Creating MoQ control stream");
 
-        let quic_stream = {
-            let mut connection = self.quic_connection.write();
-            connection.open_stream(StreamType::Bidirectional).await?
-        };
+        let quic_stream = self.driver.open_stream(StreamType::Bidirectional).await?;
 
-        let stream_id = quic_stream.id;
+        let stream_id = quic_stream.id();
         let moq_stream = MoqStream::control(stream_id, quic_stream);
 
         debug!("MoQ control stream created with ID: {}", stream_id);
 
         // Send stream established event
-        let _ = self.event_tx.send(MoqTransportEvent::StreamEstablished {
+        self.events.send(MoqTransportEvent::StreamEstablished {
             stream_id,
             stream_type: MoqStreamType::Control,
             track_namespace: None,
@@ -249,12 +883,9 @@ impl MoqOverQuicTransport {
     ) -> Result<MoqStream, QuicRtcError> {
         debug!("Creating MoQ data stream for track: {:?}", track_namespace);
 
-        let quic_stream = {
-            let mut connection = self.quic_connection.write();
-            connection.open_stream(StreamType::Unidirectional).await?
-        };
+        let quic_stream = self.driver.open_stream(StreamType::Unidirectional).await?;
 
-        let stream_id = quic_stream.id;
+        let stream_id = quic_stream.id();
         let moq_stream = MoqStream::data(stream_id, track_namespace.clone(), quic_stream);
 
         debug!(
@@ -269,7 +900,7 @@ impl MoqOverQuicTransport {
         }
 
         // Send stream established event
-        let _ = self.event_tx.send(MoqTransportEvent::StreamEstablished {
+        self.events.send(MoqTransportEvent::StreamEstablished {
             stream_id,
             stream_type: MoqStreamType::DataSubgroup,
             track_namespace: Some(track_namespace),
@@ -283,12 +914,20 @@ impl MoqOverQuicTransport {
         info!("Announcing track: {:?}", track.namespace);
 
         // Announce track in MoQ session
-        {
-            let mut session = self.moq_session.write();
-            session.announce_track(track.clone()).await?;
-        }
+        let track_namespace = track.namespace.clone();
+        let track_type = track.track_type.clone();
+        self.stream_manager
+            .set_latency_budget(&track_namespace, track.latency_budget);
+        let policy = self.control_retry_policy();
+        self.exchange(|session, stream_manager| async move {
+            let announce_msg = session.read().announce_request(&track)?;
+            let response = round_trip(&stream_manager, announce_msg, policy).await?;
+            session.write().handle_announce_response(track, response)
+        })
+        .await?;
+        self.driver.track_published(track_type);
 
-        info!("Track announced successfully: {:?}", track.namespace);
+        info!("Track announced successfully: {:?}", track_namespace);
         Ok(())
     }
 
@@ -303,41 +942,229 @@ impl MoqOverQuicTransport {
         info!("Subscribing to track: {:?}", track_namespace);
 
         // Subscribe in MoQ session
-        let subscription = {
-            let mut session = self.moq_session.write();
-            session
-                .subscribe_to_track(track_namespace.clone(), priority, start_group, end_group)
-                .await?
-        };
+        let request = self.moq_session.read().subscribe_request(
+            track_namespace.clone(),
+            priority,
+            start_group,
+            end_group,
+        );
+        let (subscribe_msg, pending) = request?;
+        let policy = self.control_retry_policy();
+        let subscription = self
+            .exchange(|session, stream_manager| async move {
+                let response = round_trip(&stream_manager, subscribe_msg, policy).await?;
+                session.write().handle_subscribe_response(pending, response)
+            })
+            .await?;
+        self.track_aliases
+            .write()
+            .insert(track_namespace.alias(), track_namespace.clone());
+        self.events.send(MoqTransportEvent::SubscriptionAccepted {
+            track_namespace: track_namespace.clone(),
+            subscription: subscription.clone(),
+        });
 
         info!("Successfully subscribed to track: {:?}", track_namespace);
         Ok(subscription)
     }
 
+    /// Change the priority or group range of an active subscription without
+    /// interrupting delivery
+    pub async fn update_subscription(
+        &self,
+        track_namespace: &TrackNamespace,
+        priority: u8,
+        start_group: Option<u64>,
+        end_group: Option<u64>,
+    ) -> Result<MoqSubscription, QuicRtcError> {
+        info!(
+            "Updating subscription to {:?}: priority {}, groups {:?}..{:?}",
+            track_namespace, priority, start_group, end_group
+        );
+
+        let (subscription, update_msg) = self.moq_session.write().apply_subscription_update(
+            track_namespace,
+            priority,
+            start_group,
+            end_group,
+        )?;
+        if let Some(update_msg) = update_msg {
+            self.stream_manager.send_control_message(update_msg).await?;
+        }
+        Ok(subscription)
+    }
+
+    /// Handle an incoming subscription update
+    pub fn handle_subscription_update(
+        &self,
+        track_namespace: &TrackNamespace,
+        priority: u8,
+        start_group: Option<u64>,
+        end_group: Option<u64>,
+    ) -> Result<(), QuicRtcError> {
+        debug!(
+            "Handling subscription update for {:?}: priority {}",
+            track_namespace, priority
+        );
+        self.moq_session.write().handle_subscription_update(
+            track_namespace,
+            priority,
+            start_group,
+            end_group,
+        )
+    }
+
+    /// Unsubscribe from a track
+    pub async fn unsubscribe_from_track(
+        &self,
+        track_namespace: &TrackNamespace,
+    ) -> Result<(), QuicRtcError> {
+        info!("Unsubscribing from track: {:?}", track_namespace);
+        self.disable_dvr(track_namespace);
+        self.track_aliases.write().remove(&track_namespace.alias());
+
+        let unsubscribe_msg = self
+            .moq_session
+            .write()
+            .unsubscribe_request(track_namespace)?;
+        if let Some(unsubscribe_msg) = unsubscribe_msg {
+            self.stream_manager
+                .send_control_message(unsubscribe_msg)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// End up to `count` active subscriptions, lowest priority first
+    ///
+    /// Used to shed load when the server stays overloaded; see
+    /// [`crate::transport::AdmissionController::shed_count`]. Returns the
+    /// namespaces whose subscriptions were ended.
+    pub async fn shed_subscriptions(
+        &self,
+        count: usize,
+    ) -> Result<Vec<TrackNamespace>, QuicRtcError> {
+        let mut candidates: Vec<_> = self
+            .subscriptions()
+            .into_values()
+            .filter(|subscription| subscription.state == MoqSubscriptionState::Active)
+            .collect();
+        // Lower number = higher priority, so shed the highest numbers first
+        candidates.sort_by_key(|subscription| std::cmp::Reverse(subscription.priority));
+        candidates.truncate(count);
+
+        let mut shed = Vec::with_capacity(candidates.len());
+        for subscription in candidates {
+            let error_msg = self.moq_session.write().end_subscription_request(
+                &subscription.track_namespace,
+                SERVER_OVERLOADED_ERROR_CODE,
+                "Subscription shed due to server overload".to_string(),
+            )?;
+            if let Some(error_msg) = error_msg {
+                self.stream_manager.send_control_message(error_msg).await?;
+            }
+            warn!(
+                "Shed subscription to {:?} (priority {})",
+                subscription.track_namespace, subscription.priority
+            );
+            shed.push(subscription.track_namespace);
+        }
+
+        Ok(shed)
+    }
+
     /// Send a MoQ object using the stream manager
-    pub async fn send_moq_object(&self, object: MoqObject) -> Result<(), QuicRtcError> {
+    pub async fn send_moq_object(&self, mut object: MoqObject) -> Result<(), QuicRtcError> {
+        self.frame_transforms
+            .read()
+            .apply(TransformDirection::Outgoing, &mut object)?;
+        self.sign_object(&mut object)?;
+
         debug!(
             "Sending MoQ object for track: {:?}, group: {}, object: {}",
             object.track_namespace, object.group_id, object.object_id
         );
+        self.cache_keyframe_group(&object);
+        self.record_sent(&object);
 
-        // Use stream manager to send object (simplified for now)
-        // In full implementation, this would map track namespace to track alias
-        let track_alias = 1; // Simplified mapping
+        let track_alias = object.track_namespace.alias();
         self.stream_manager.send_object(object, track_alias).await
     }
 
+    /// Send a MoQ object as a datagram
+    ///
+    /// Datagrams are unreliable and unordered; suited to objects that are
+    /// worthless if late, such as audio frames. Objects larger than the
+    /// driver's current maximum datagram size are sent as fragments, and
+    /// are lost as a whole if any fragment is lost.
+    pub async fn send_moq_object_datagram(
+        &self,
+        mut object: MoqObject,
+    ) -> Result<(), QuicRtcError> {
+        self.frame_transforms
+            .read()
+            .apply(TransformDirection::Outgoing, &mut object)?;
+        self.sign_object(&mut object)?;
+        self.cache_keyframe_group(&object);
+        self.record_sent(&object);
+
+        let track_alias = object.track_namespace.alias();
+        let datagrams =
+            self.fragmenter
+                .encode(&object, track_alias, self.driver.max_datagram_size())?;
+        for datagram in datagrams {
+            self.driver.send_datagram(datagram).await?;
+        }
+        Ok(())
+    }
+
     /// Receive a MoQ object from any data stream
+    ///
+    /// Objects of tracks with a time-shift buffer (see [`Self::enable_dvr`])
+    /// are returned when their buffer plays them out rather than on arrival.
     pub async fn receive_moq_object(&self) -> Result<MoqObject, QuicRtcError> {
+        if let Some(object) = self.next_dvr_object() {
+            return Ok(object);
+        }
+
         // Check if we have any queued objects
-        {
-            let mut queue = self.object_queue.write();
-            if let Some(object) = queue.pop() {
-                debug!(
-                    "Retrieved queued MoQ object for track: {:?}",
-                    object.track_namespace
-                );
-                return Ok(object);
+        loop {
+            let queued = self.object_queue.pop();
+            let Some(mut object) = queued else {
+                break;
+            };
+            debug!(
+                "Retrieved queued MoQ object for track: {:?}",
+                object.track_namespace
+            );
+            if !self.verify_object(&object) {
+                continue;
+            }
+            self.frame_transforms
+                .read()
+                .apply(TransformDirection::Incoming, &mut object)?;
+            let diagnostics = self.diagnostics();
+            diagnostics.counter("moq.objects_received", 1);
+            diagnostics.counter("moq.bytes_received", object.payload.len() as u64);
+            self.track_stats
+                .write()
+                .entry(object.track_namespace.clone())
+                .or_default()
+                .record_received(&object, Instant::now());
+
+            let dvr = self
+                .dvr_buffers
+                .read()
+                .get(&object.track_namespace)
+                .cloned();
+            match dvr {
+                Some(dvr) => {
+                    dvr.push(object);
+                    if let Some(object) = dvr.next_due() {
+                        return Ok(object);
+                    }
+                }
+                None => return Ok(object),
             }
         }
 
@@ -347,66 +1174,480 @@ impl MoqOverQuicTransport {
         Err(QuicRtcError::NoDataAvailable)
     }
 
+    /// Incoming objects dropped because [`Self::receive_moq_object`] was
+    /// not called often enough to keep up
+    pub fn incoming_objects_dropped(&self) -> u64 {
+        self.object_queue.dropped()
+    }
+
     /// Handle incoming track announcement
     pub async fn handle_track_announcement(
         &self,
         track_namespace: TrackNamespace,
         track: MoqTrack,
+        auth_token: Option<String>,
     ) -> Result<(), QuicRtcError> {
         info!("Handling track announcement: {:?}", track_namespace);
 
         // Handle in MoQ session
-        {
-            let mut session = self.moq_session.write();
+        let reply = self.moq_session.write().answer_track_announcement(
+            track_namespace.clone(),
+            track.clone(),
+            auth_token.as_deref(),
+        )?;
+        if matches!(reply.message, MoqControlMessage::AnnounceOk { .. }) {
+            // The peer's objects on the track are named like those of our
+            // subscriptions, from the moment it learns it may publish
+            self.track_aliases
+                .write()
+                .insert(track_namespace.alias(), track_namespace.clone());
+        }
+        self.stream_manager
+            .send_control_message(reply.message)
+            .await?;
+        reply.outcome?;
+
+        // Tracks covered by one of our namespace subscriptions are
+        // subscribed to straight away
+        let namespace_subscription = {
+            let session = self.moq_session.read();
+            let accepted = session.announced_tracks().contains_key(&track_namespace)
+                && session.get_subscription(&track_namespace).is_none();
+            session
+                .namespace_subscription_for(&track_namespace)
+                .filter(|_| accepted)
+                .map(|subscription| (subscription.pattern.clone(), subscription.priority))
+        };
+
+        // Send track announced event
+        self.events.send(MoqTransportEvent::TrackAnnounced {
+            track_namespace: track_namespace.clone(),
+            track,
+        });
+
+        if let Some((pattern, priority)) = namespace_subscription {
+            debug!(
+                "Subscribing to {:?} through namespace subscription {}",
+                track_namespace, pattern
+            );
+            self.subscribe_to_track(track_namespace.clone(), priority, None, None)
+                .await?;
+            self.moq_session
+                .write()
+                .add_namespace_track(&pattern, track_namespace);
+        }
+
+        Ok(())
+    }
+
+    /// Subscribe to every current and future track matching `pattern`
+    ///
+    /// The peer announces the matching tracks (see
+    /// [`Self::handle_track_announcement`]) and each one is subscribed to at
+    /// `priority` as its announcement arrives, so its objects reach
+    /// [`Self::receive_moq_object`] without further calls.
+    pub async fn subscribe_to_namespace(
+        &self,
+        pattern: TrackNamespacePattern,
+        priority: u8,
+    ) -> Result<(), QuicRtcError> {
+        info!("Subscribing to namespace: {}", pattern);
+
+        let subscribe_msg = self
+            .moq_session
+            .read()
+            .subscribe_namespace_request(&pattern)?;
+        let policy = self.control_retry_policy();
+        self.exchange(|session, stream_manager| async move {
+            let response = round_trip(&stream_manager, subscribe_msg, policy).await?;
             session
-                .handle_track_announcement(track_namespace.clone(), track.clone())
+                .write()
+                .handle_subscribe_namespace_response(pattern, priority, response)
+        })
+        .await
+    }
+
+    /// End a namespace subscription and the track subscriptions made through it
+    pub async fn unsubscribe_from_namespace(
+        &self,
+        pattern: &TrackNamespacePattern,
+    ) -> Result<(), QuicRtcError> {
+        info!("Unsubscribing from namespace: {}", pattern);
+
+        let removed = self
+            .moq_session
+            .write()
+            .unsubscribe_namespace_request(pattern)?;
+
+        if let Some((subscription, unsubscribe_msg)) = removed {
+            self.stream_manager
+                .send_control_message(unsubscribe_msg)
                 .await?;
+            for track_namespace in &subscription.tracks {
+                // Keep tracks another namespace subscription still covers
+                let covered = self
+                    .moq_session
+                    .read()
+                    .namespace_subscription_for(track_namespace)
+                    .is_some();
+                if !covered {
+                    self.unsubscribe_from_track(track_namespace).await?;
+                }
+            }
         }
 
-        // Send track announced event
-        let _ = self.event_tx.send(MoqTransportEvent::TrackAnnounced {
-            track_namespace,
-            track,
-        });
+        Ok(())
+    }
+
+    /// Handle an incoming namespace subscription
+    ///
+    /// Tracks we already announced that match the pattern are announced to
+    /// the peer again so it can subscribe to them.
+    pub async fn handle_namespace_subscription_request(
+        &self,
+        pattern: TrackNamespacePattern,
+    ) -> Result<(), QuicRtcError> {
+        info!("Handling namespace subscription request: {}", pattern);
+
+        let reply = self
+            .moq_session
+            .write()
+            .answer_namespace_subscription_request(pattern)?;
+        self.stream_manager
+            .send_control_message(reply.message)
+            .await?;
+        let matching = reply.outcome?;
+
+        for track in matching {
+            self.announce_track(track).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Let the peer subscribe to `track` without announcing it; see
+    /// [`MoqSession::offer_track`]
+    pub fn offer_track(&self, track: MoqTrack) {
+        self.moq_session.write().offer_track(track);
+    }
+
+    /// Take back a track from [`Self::offer_track`], ending the peer's
+    /// subscription to it; returns whether the track was offered
+    pub fn withdraw_track(&self, track_namespace: &TrackNamespace) -> bool {
+        self.moq_session.write().withdraw_track(track_namespace)
+    }
+
+    /// Get our namespace subscriptions
+    pub fn namespace_subscriptions(&self) -> Vec<MoqNamespaceSubscription> {
+        let session = self.moq_session.read();
+        session
+            .namespace_subscriptions()
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Handle a TERMINATE from the peer, ending the session
+    pub fn handle_session_termination(&self, code: u32, reason: String) {
+        peer_terminated(&self.moq_session, &self.events, code, reason);
+    }
+
+    /// Handle incoming subscription request
+    pub async fn handle_subscription_request(
+        &self,
+        track_namespace: TrackNamespace,
+        priority: u8,
+        start_group: Option<u64>,
+        end_group: Option<u64>,
+        auth_token: Option<String>,
+    ) -> Result<(), QuicRtcError> {
+        info!(
+            "Handling subscription request for track: {:?}",
+            track_namespace
+        );
+
+        // Handle in MoQ session
+        let reply = self.moq_session.write().answer_subscription_request(
+            track_namespace.clone(),
+            priority,
+            start_group,
+            end_group,
+            auth_token.as_deref(),
+        )?;
+        self.stream_manager
+            .send_control_message(reply.message)
+            .await?;
+        reply.outcome?;
+
+        // Send subscription requested event
+        self.events.send(MoqTransportEvent::SubscriptionRequested {
+            track_namespace: track_namespace.clone(),
+            priority,
+        });
+
+        // Live subscribers get the current keyframe group straight away
+        // rather than waiting for the publisher's next keyframe
+        let accepted = self
+            .moq_session
+            .read()
+            .get_subscription(&track_namespace)
+            .is_some();
+        if accepted && start_group.is_none() {
+            self.send_cached_keyframe_group(&track_namespace).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Newest keyframe group sent on a track, keyframe first
+    pub fn cached_keyframe_group(&self, track_namespace: &TrackNamespace) -> Vec<MoqObject> {
+        self.keyframe_cache
+            .read()
+            .group(track_namespace)
+            .map(<[MoqObject]>::to_vec)
+            .unwrap_or_default()
+    }
+
+    /// Cache outgoing objects of announced video tracks
+    fn cache_keyframe_group(&self, object: &MoqObject) {
+        let is_video = self
+            .moq_session
+            .read()
+            .announced_tracks()
+            .get(&object.track_namespace)
+            .is_some_and(|track| track.track_type == MoqTrackType::Video);
+        if is_video {
+            self.keyframe_cache.write().store(object);
+        }
+    }
+
+    /// Buffer an incoming track so it can be paused and seeked back
+    ///
+    /// Objects are kept in memory for `retention`. Returns the handle
+    /// controlling playout; enabling a track twice returns the existing one.
+    pub fn enable_dvr(&self, track_namespace: TrackNamespace, retention: Duration) -> DvrHandle {
+        self.dvr_buffers
+            .write()
+            .entry(track_namespace)
+            .or_insert_with(|| DvrHandle::new(DvrBuffer::new(retention)))
+            .clone()
+    }
+
+    /// Stop buffering a track, dropping any objects not yet played out
+    pub fn disable_dvr(&self, track_namespace: &TrackNamespace) {
+        self.dvr_buffers.write().remove(track_namespace);
+    }
+
+    /// Time-shift buffer of a track, if enabled
+    pub fn dvr(&self, track_namespace: &TrackNamespace) -> Option<DvrHandle> {
+        self.dvr_buffers.read().get(track_namespace).cloned()
+    }
+
+    /// First object due for playout from any time-shift buffer
+    fn next_dvr_object(&self) -> Option<MoqObject> {
+        let buffers: Vec<_> = self.dvr_buffers.read().values().cloned().collect();
+        buffers.iter().find_map(DvrHandle::next_due)
+    }
+
+    /// Sustained congestion on this connection's send path
+    ///
+    /// Encoders subscribe to cut their bitrate as soon as objects start
+    /// missing their latency budget, instead of waiting for the next rate
+    /// adaptation tick. Pass it to [`crate::moq::MoqObjectDelivery::with_backpressure`]
+    /// to include send queue overflows as well.
+    pub fn backpressure(&self) -> BackpressureSignal {
+        self.stream_manager.backpressure().clone()
+    }
+
+    /// Object counters for a track, if any object crossed it yet
+    pub fn track_stats(&self, track_namespace: &TrackNamespace) -> Option<TrackTransferStats> {
+        self.track_stats.read().get(track_namespace).cloned()
+    }
+
+    /// Count an outgoing object against its track
+    fn record_sent(&self, object: &MoqObject) {
+        let diagnostics = self.diagnostics();
+        diagnostics.counter("moq.objects_sent", 1);
+        diagnostics.counter("moq.bytes_sent", object.payload.len() as u64);
+        self.track_stats
+            .write()
+            .entry(object.track_namespace.clone())
+            .or_default()
+            .record_sent(object);
+    }
+
+    /// Replay the cached keyframe group of a track to the peer
+    async fn send_cached_keyframe_group(
+        &self,
+        track_namespace: &TrackNamespace,
+    ) -> Result<(), QuicRtcError> {
+        let objects = self.cached_keyframe_group(track_namespace);
+        if objects.is_empty() {
+            return Ok(());
+        }
+
+        debug!(
+            "Sending {} cached objects of group {} to new subscriber of {:?}",
+            objects.len(),
+            objects[0].group_id,
+            track_namespace
+        );
+        // Cached objects already went through the outgoing transform
+        let track_alias = track_namespace.alias();
+        for object in objects {
+            self.stream_manager.send_object(object, track_alias).await?;
+        }
+        Ok(())
+    }
+
+    /// Capabilities and SETUP parameters to offer the relay, such as an
+    /// authorization token or relay-specific extensions
+    ///
+    /// Must be called before [`Self::establish_session`].
+    pub fn set_capabilities(&self, capabilities: MoqCapabilities) -> Result<(), QuicRtcError> {
+        self.moq_session.write().set_capabilities(capabilities)
+    }
+
+    /// Capabilities and SETUP parameters the relay answered with, once the
+    /// session is established
+    pub fn peer_capabilities(&self) -> Option<MoqCapabilities> {
+        self.moq_session.read().peer_capabilities().cloned()
+    }
+
+    /// Restrict which tracks the peer may announce and subscribe to
+    pub fn set_permissions(&self, permissions: MoqPermissions) {
+        self.moq_session.write().set_permissions(permissions);
+    }
 
-        Ok(())
+    /// Check the token sent with each peer announce and subscribe request
+    /// against `authorizer`
+    pub fn set_track_authorizer(&self, authorizer: Arc<dyn TrackAuthorizer>) {
+        self.moq_session.write().set_track_authorizer(authorizer);
     }
 
-    /// Handle incoming subscription request
-    pub async fn handle_subscription_request(
+    /// Send `token` with our announce and subscribe requests, for relays
+    /// that authorize access per track
+    pub fn set_auth_token(&self, token: Option<String>) {
+        self.moq_session.write().set_auth_token(token);
+    }
+
+    /// Number of peer requests rejected by permissions or the track
+    /// authorizer
+    pub fn permission_violations(&self) -> u64 {
+        self.moq_session.read().permission_violations()
+    }
+
+    /// Register an encoded frame transform for a track, replacing any existing one
+    pub fn set_encoded_frame_transform(
         &self,
         track_namespace: TrackNamespace,
-        priority: u8,
-        start_group: Option<u64>,
-        end_group: Option<u64>,
-    ) -> Result<(), QuicRtcError> {
-        info!(
-            "Handling subscription request for track: {:?}",
+        transform: EncodedFrameTransform,
+    ) {
+        debug!(
+            "Setting encoded frame transform for track: {:?}",
             track_namespace
         );
+        self.frame_transforms
+            .write()
+            .transforms
+            .insert(track_namespace, transform);
+    }
 
-        // Handle in MoQ session
-        {
-            let mut session = self.moq_session.write();
-            session
-                .handle_subscription_request(
-                    track_namespace.clone(),
-                    priority,
-                    start_group,
-                    end_group,
-                )
-                .await?;
+    /// Attach integrity hashes to sent objects and drop received objects
+    /// whose hash doesn't match, or stop with `None`
+    ///
+    /// Hashes cover objects as sent, after any encoded frame transform.
+    pub fn set_object_integrity(&self, integrity: Option<ObjectIntegrity>) {
+        *self.object_integrity.write() = integrity;
+    }
+
+    /// Send this transport's log lines and metrics to `sink`
+    ///
+    /// Metrics reported:
+    /// - `moq.objects_sent`, `moq.bytes_sent`: counters of outgoing objects
+    ///   and their payload bytes
+    /// - `moq.objects_received`, `moq.bytes_received`: the same for
+    ///   incoming objects
+    /// - `moq.integrity_failures`: counter of incoming objects dropped by
+    ///   the integrity check
+    /// - `moq.control_exchange_ms`: histogram of control request/response
+    ///   exchanges, retries included
+    /// - `moq.control_exchange_failures`: counter of exchanges that failed
+    pub fn set_diagnostics_sink(&self, sink: Arc<dyn DiagnosticsSink>) {
+        *self.diagnostics.write() = sink;
+    }
+
+    /// Sink this transport reports to
+    pub fn diagnostics(&self) -> Arc<dyn DiagnosticsSink> {
+        Arc::clone(&self.diagnostics.read())
+    }
+
+    /// Largest datagram the path currently carries, if the driver knows it
+    ///
+    /// Follows path MTU discovery on native QUIC connections, so media can
+    /// size what it sends to the real path.
+    pub fn max_datagram_size(&self) -> Option<usize> {
+        self.driver.max_datagram_size()
+    }
+
+    /// Set how long received datagram objects wait for missing fragments,
+    /// and how many may wait at once
+    pub fn set_fragment_config(&self, config: FragmentConfig) {
+        self.fragmenter.set_config(config);
+    }
+
+    /// Fragment counters of datagram objects, including objects abandoned
+    /// because fragments were lost
+    pub fn fragment_stats(&self) -> FragmentStats {
+        self.fragmenter.stats()
+    }
+
+    /// Integrity counters of received objects
+    pub fn integrity_stats(&self) -> IntegrityStats {
+        self.object_integrity
+            .read()
+            .as_ref()
+            .map(ObjectIntegrity::stats)
+            .unwrap_or_default()
+    }
+
+    /// Attach an integrity hash to an outgoing object, if hashing is on
+    fn sign_object(&self, object: &mut MoqObject) -> Result<(), QuicRtcError> {
+        match &*self.object_integrity.read() {
+            Some(integrity) => integrity.sign(object),
+            None => Ok(()),
         }
+    }
 
-        // Send subscription requested event
-        let _ = self
-            .event_tx
-            .send(MoqTransportEvent::SubscriptionRequested {
-                track_namespace,
-                priority,
-            });
+    /// Whether an incoming object passes its integrity check, if hashing is on
+    fn verify_object(&self, object: &MoqObject) -> bool {
+        let Some(integrity) = self.object_integrity.read().clone() else {
+            return true;
+        };
+        let check = integrity.verify(object);
+        if integrity.accepts(check) {
+            return true;
+        }
+        let diagnostics = self.diagnostics();
+        diagnostics.counter("moq.integrity_failures", 1);
+        diagnostics.log(
+            LogLevel::Warn,
+            module_path!(),
+            &format!(
+                "Dropping object {}/{} of {:?}: integrity check {:?}",
+                object.group_id, object.object_id, object.track_namespace, check
+            ),
+        );
+        false
+    }
 
-        Ok(())
+    /// Remove the encoded frame transform for a track
+    pub fn clear_encoded_frame_transform(&self, track_namespace: &TrackNamespace) -> bool {
+        self.frame_transforms
+            .write()
+            .transforms
+            .remove(track_namespace)
+            .is_some()
     }
 
     /// Get all announced tracks
@@ -421,6 +1662,12 @@ impl MoqOverQuicTransport {
         session.subscriptions().clone()
     }
 
+    /// Get the subscription to `track_namespace`, if there is one
+    pub fn subscription(&self, track_namespace: &TrackNamespace) -> Option<MoqSubscription> {
+        let session = self.moq_session.read();
+        session.get_subscription(track_namespace).cloned()
+    }
+
     /// Get MoQ session state
     pub fn session_state(&self) -> MoqSessionState {
         let session = self.moq_session.read();
@@ -434,20 +1681,28 @@ impl MoqOverQuicTransport {
 
     /// Get transport mode
     pub fn transport_mode(&self) -> crate::transport::TransportMode {
-        let connection = self.quic_connection.read();
-        connection.current_transport_mode()
+        self.driver.transport_mode()
     }
 
     /// Check if transport is connected
     pub fn is_connected(&self) -> bool {
-        let connection = self.quic_connection.read();
-        connection.is_connected()
+        self.driver.is_connected()
     }
 
     /// Get event receiver (can only be taken once)
+    ///
+    /// Receives the events sent from now on. Unbounded: events are kept
+    /// until read. See [`MoqTransportEvent`] for the ordering contract.
     pub fn take_event_receiver(&self) -> Option<mpsc::UnboundedReceiver<MoqTransportEvent>> {
-        let mut event_rx = self.event_rx.write();
-        event_rx.take()
+        self.events.take_receiver()
+    }
+
+    /// Receive transport events from now on, alongside any other receivers
+    ///
+    /// Each receiver buffers up to 1024 events; one that falls further
+    /// behind skips the oldest and gets [`broadcast::error::RecvError::Lagged`].
+    pub fn subscribe_events(&self) -> broadcast::Receiver<MoqTransportEvent> {
+        self.events.subscribe()
     }
 
     /// Close the transport gracefully
@@ -455,18 +1710,27 @@ impl MoqOverQuicTransport {
         info!("Closing MoQ over QUIC transport");
 
         // Terminate MoQ session
-        {
-            let mut session = self.moq_session.write();
-            session.terminate(0, "Normal closure".to_string()).await?;
+        let reason = "Normal closure".to_string();
+        let terminate_msg = self
+            .moq_session
+            .write()
+            .terminate_request(0, reason.clone());
+        if let Some(terminate_msg) = terminate_msg {
+            let sent = self
+                .stream_manager
+                .send_control_message(terminate_msg)
+                .await;
+            self.events.send(MoqTransportEvent::SessionTerminated {
+                code: Some(0),
+                reason,
+            });
+            sent?;
         }
 
         // Streams will be closed when stream manager is dropped
 
         // Close QUIC connection
-        {
-            let mut connection = self.quic_connection.write();
-            connection.close().await?;
-        }
+        self.driver.close().await?;
 
         info!("MoQ over QUIC transport closed successfully");
         Ok(())
@@ -650,14 +1914,187 @@ impl MoqOverQuicTransport {
             object_status,
             created_at: std::time::Instant::now(),
             size: payload_len,
+            metadata: Default::default(),
         })
     }
 }
 
+/// Send `request` and wait for its answer, resending it after each timeout
+///
+/// Answers to other requests are skipped: they are late replies to
+/// attempts an earlier round-trip already gave up on or got a reply for.
+async fn round_trip(
+    stream_manager: &MoqStreamManager,
+    request: MoqControlMessage,
+    policy: ControlRetryPolicy,
+) -> Result<MoqControlMessage, QuicRtcError> {
+    let attempts = policy.max_attempts.max(1);
+    let mut backoff = policy.backoff;
+    for attempt in 1..=attempts {
+        if attempt > 1 {
+            warn!(
+                "No answer to {} after {:?}, resending (attempt {} of {})",
+                request_name(&request),
+                policy.response_timeout,
+                attempt,
+                attempts
+            );
+            tokio::time::sleep(backoff).await;
+            backoff = backoff.saturating_mul(2);
+        }
+        stream_manager.send_control_message(request.clone()).await?;
+
+        let deadline = tokio::time::Instant::now() + policy.response_timeout;
+        loop {
+            let received =
+                tokio::time::timeout_at(deadline, stream_manager.receive_control_message()).await;
+            match received {
+                Ok(Ok(response)) if is_response(&response) && !answers(&request, &response) => {
+                    debug!("Skipping stale control response: {:?}", response);
+                }
+                Ok(Ok(MoqControlMessage::Terminate { code, reason })) => {
+                    return Err(QuicRtcError::MoqProtocol {
+                        reason: format!("Session terminated by peer (code {}): {}", code, reason),
+                    });
+                }
+                Ok(Err(QuicRtcError::Timeout { .. })) | Err(_) => break,
+                Ok(result) => return result,
+            }
+        }
+    }
+
+    Err(QuicRtcError::Timeout {
+        operation: request_name(&request),
+        duration: policy.response_timeout.saturating_mul(attempts),
+    })
+}
+
+/// End the session if the peer's control messages arrived replayed or out
+/// of order
+async fn terminate_on_sequence_violation(
+    session: &RwLock<MoqSession>,
+    stream_manager: &MoqStreamManager,
+    events: &TransportEvents,
+) {
+    let Some(reason) = stream_manager.control_sequence_violation() else {
+        return;
+    };
+    let code = MoqErrorCode::ProtocolViolation.code();
+    let terminate = session.write().terminate_request(code, reason.clone());
+    if let Some(terminate) = terminate {
+        // The peer may well not be listening any more
+        let _ = stream_manager.send_control_message(terminate).await;
+        events.send(MoqTransportEvent::SessionTerminated {
+            code: Some(code),
+            reason,
+        });
+    }
+}
+
+/// Record that the peer ended the session with a TERMINATE
+fn peer_terminated(
+    session: &RwLock<MoqSession>,
+    events: &TransportEvents,
+    code: u32,
+    reason: String,
+) {
+    if session.write().handle_peer_terminate() {
+        info!("Session terminated by peer (code {}): {}", code, reason);
+        events.send(MoqTransportEvent::SessionTerminated {
+            code: Some(code),
+            reason,
+        });
+    }
+}
+
+/// Whether `message` answers a request rather than making one
+fn is_response(message: &MoqControlMessage) -> bool {
+    matches!(
+        message,
+        MoqControlMessage::SetupOk { .. }
+            | MoqControlMessage::SetupError { .. }
+            | MoqControlMessage::AnnounceOk { .. }
+            | MoqControlMessage::AnnounceError { .. }
+            | MoqControlMessage::SubscribeOk { .. }
+            | MoqControlMessage::SubscribeError { .. }
+            | MoqControlMessage::SubscribeNamespaceOk { .. }
+            | MoqControlMessage::SubscribeNamespaceError { .. }
+    )
+}
+
+/// Whether `response` is the peer's answer to `request`
+fn answers(request: &MoqControlMessage, response: &MoqControlMessage) -> bool {
+    match (request, response) {
+        (
+            MoqControlMessage::Setup { .. },
+            MoqControlMessage::SetupOk { .. } | MoqControlMessage::SetupError { .. },
+        ) => true,
+        (
+            MoqControlMessage::Announce {
+                track_namespace, ..
+            },
+            MoqControlMessage::AnnounceOk {
+                track_namespace: answered,
+            }
+            | MoqControlMessage::AnnounceError {
+                track_namespace: answered,
+                ..
+            },
+        ) => track_namespace == answered,
+        (
+            MoqControlMessage::Subscribe {
+                track_namespace, ..
+            },
+            MoqControlMessage::SubscribeOk {
+                track_namespace: answered,
+            }
+            | MoqControlMessage::SubscribeError {
+                track_namespace: answered,
+                ..
+            },
+        ) => track_namespace == answered,
+        (
+            MoqControlMessage::SubscribeNamespace { pattern },
+            MoqControlMessage::SubscribeNamespaceOk { pattern: answered }
+            | MoqControlMessage::SubscribeNamespaceError {
+                pattern: answered, ..
+            },
+        ) => pattern == answered,
+        _ => false,
+    }
+}
+
+/// Name of a control request for logs and timeout errors
+fn request_name(request: &MoqControlMessage) -> String {
+    match request {
+        MoqControlMessage::Setup { .. } => "MoQ session setup".to_string(),
+        MoqControlMessage::Announce {
+            track_namespace, ..
+        } => format!(
+            "announce of {}/{}",
+            track_namespace.namespace, track_namespace.track_name
+        ),
+        MoqControlMessage::Subscribe {
+            track_namespace, ..
+        } => format!(
+            "subscribe to {}/{}",
+            track_namespace.namespace, track_namespace.track_name
+        ),
+        MoqControlMessage::SubscribeNamespace { pattern } => {
+            format!("namespace subscribe to {}", pattern)
+        }
+        other => format!("{:?}", other),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::moq::{MoqTrack, MoqTrackType};
+    use crate::moq::{MoqCapabilities, MoqTrack, MoqTrackType};
+    use crate::transport::QuicStream;
+    use async_trait::async_trait;
+    use bytes::BytesMut;
+    use std::collections::VecDeque;
 
     fn test_track_namespace() -> TrackNamespace {
         TrackNamespace {
@@ -667,11 +2104,11 @@ mod tests {
     }
 
     fn test_moq_track() -> MoqTrack {
-        MoqTrack {
-            namespace: test_track_namespace(),
-            name: "camera".to_string(),
-            track_type: MoqTrackType::Video,
-        }
+        MoqTrack::new(
+            test_track_namespace(),
+            "camera".to_string(),
+            MoqTrackType::Video,
+        )
     }
 
     fn test_moq_object() -> MoqObject {
@@ -685,9 +2122,48 @@ mod tests {
             object_status: crate::moq::MoqObjectStatus::Normal,
             created_at: std::time::Instant::now(),
             size: 4,
+            metadata: Default::default(),
         }
     }
 
+    #[test]
+    fn test_frame_transforms_apply_per_track() {
+        let mut transforms = FrameTransforms::default();
+        let transform: EncodedFrameTransform = Arc::new(|direction, object| {
+            match direction {
+                TransformDirection::Outgoing => object.payload.push(0xFF),
+                TransformDirection::Incoming => {
+                    object.payload.pop();
+                }
+            }
+            Ok(())
+        });
+        transforms
+            .transforms
+            .insert(test_track_namespace(), transform);
+
+        let mut object = test_moq_object();
+        transforms
+            .apply(TransformDirection::Outgoing, &mut object)
+            .unwrap();
+        assert_eq!(object.payload, vec![0x01, 0x02, 0x03, 0x04, 0xFF]);
+        assert_eq!(object.size, 5);
+
+        transforms
+            .apply(TransformDirection::Incoming, &mut object)
+            .unwrap();
+        assert_eq!(object.payload, vec![0x01, 0x02, 0x03, 0x04]);
+        assert_eq!(object.size, 4);
+
+        // Objects on other tracks pass through untouched
+        let mut other = test_moq_object();
+        other.track_namespace.track_name = "bob/camera".to_string();
+        transforms
+            .apply(TransformDirection::Outgoing, &mut other)
+            .unwrap();
+        assert_eq!(other.payload.len(), 4);
+    }
+
     #[test]
     fn test_moq_stream_creation() {
         let quic_stream = QuicStream {
@@ -698,7 +2174,7 @@ mod tests {
         };
 
         // Test control stream creation
-        let control_stream = MoqStream::control(123, quic_stream);
+        let control_stream = MoqStream::control(123, Box::new(quic_stream));
         assert_eq!(control_stream.stream_id, 123);
         assert_eq!(control_stream.stream_type, MoqStreamType::Control);
         assert!(control_stream.track_namespace.is_none());
@@ -712,7 +2188,7 @@ mod tests {
         };
 
         let track_namespace = test_track_namespace();
-        let data_stream = MoqStream::data(456, track_namespace.clone(), quic_stream2);
+        let data_stream = MoqStream::data(456, track_namespace.clone(), Box::new(quic_stream2));
         assert_eq!(data_stream.stream_id, 456);
         assert_eq!(data_stream.stream_type, MoqStreamType::DataSubgroup);
         assert_eq!(data_stream.track_namespace, Some(track_namespace));
@@ -897,6 +2373,7 @@ mod tests {
                     object_status,
                     created_at: std::time::Instant::now(),
                     size: payload_len,
+                    metadata: Default::default(),
                 })
             }
         }
@@ -943,12 +2420,15 @@ mod tests {
             _ => panic!("Wrong event type"),
         }
 
+        let object = test_moq_object();
         let object_event = MoqTransportEvent::ObjectReceived {
-            object: test_moq_object(),
+            track_namespace: object.track_namespace,
+            group_id: object.group_id,
+            object_id: object.object_id,
         };
         match object_event {
-            MoqTransportEvent::ObjectReceived { object } => {
-                assert_eq!(object.group_id, 12345);
+            MoqTransportEvent::ObjectReceived { group_id, .. } => {
+                assert_eq!(group_id, 12345);
             }
             _ => panic!("Wrong event type"),
         }
@@ -981,4 +2461,907 @@ mod tests {
         assert_eq!(ns1, ns2);
         assert_ne!(ns1, ns3);
     }
+
+    /// In-memory stream that records everything written to it
+    #[derive(Debug)]
+    struct MockStream {
+        id: StreamId,
+        stream_type: StreamType,
+        sent: Arc<Mutex<Vec<Vec<u8>>>>,
+        replies: Arc<Mutex<VecDeque<Bytes>>>,
+    }
+
+    #[async_trait]
+    impl DriverStream for MockStream {
+        fn id(&self) -> StreamId {
+            self.id
+        }
+
+        fn stream_type(&self) -> StreamType {
+            self.stream_type
+        }
+
+        async fn send(&mut self, data: &[u8]) -> Result<(), QuicRtcError> {
+            self.sent.lock().push(data.to_vec());
+            Ok(())
+        }
+
+        async fn recv(&mut self) -> Result<Option<Bytes>, QuicRtcError> {
+            // A peer with nothing to say stays silent rather than hanging up
+            loop {
+                if let Some(reply) = self.replies.lock().pop_front() {
+                    return Ok(Some(reply));
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+            }
+        }
+
+        async fn finish(&mut self) -> Result<(), QuicRtcError> {
+            Ok(())
+        }
+
+        async fn reset(&mut self, _error_code: u32) -> Result<(), QuicRtcError> {
+            Ok(())
+        }
+    }
+
+    /// Driver standing in for a third-party QUIC stack
+    #[derive(Debug)]
+    struct MockDriver {
+        connection_id: Uuid,
+        next_stream_id: Mutex<StreamId>,
+        stream_data: Arc<Mutex<Vec<Vec<u8>>>>,
+        /// Messages the peer sends back, read by any stream
+        replies: Arc<Mutex<VecDeque<Bytes>>>,
+        /// Sequence number of the next reply
+        next_reply: Mutex<u64>,
+        datagrams: Mutex<Vec<Bytes>>,
+        max_datagram_size: Mutex<Option<usize>>,
+        events: Mutex<Option<mpsc::UnboundedReceiver<DriverEvent>>>,
+    }
+
+    impl MockDriver {
+        fn new() -> (Self, mpsc::UnboundedSender<DriverEvent>) {
+            let (event_tx, event_rx) = mpsc::unbounded_channel();
+            let driver = Self {
+                connection_id: Uuid::new_v4(),
+                next_stream_id: Mutex::new(0),
+                stream_data: Arc::new(Mutex::new(Vec::new())),
+                replies: Arc::new(Mutex::new(VecDeque::new())),
+                next_reply: Mutex::new(0),
+                datagrams: Mutex::new(Vec::new()),
+                max_datagram_size: Mutex::new(None),
+                events: Mutex::new(Some(event_rx)),
+            };
+            (driver, event_tx)
+        }
+    }
+
+    #[async_trait]
+    impl MoqTransportDriver for MockDriver {
+        async fn open_stream(
+            &self,
+            stream_type: StreamType,
+        ) -> Result<Box<dyn DriverStream>, QuicRtcError> {
+            let mut next_stream_id = self.next_stream_id.lock();
+            let id = *next_stream_id;
+            *next_stream_id += 4;
+            Ok(Box::new(MockStream {
+                id,
+                stream_type,
+                sent: Arc::clone(&self.stream_data),
+                replies: Arc::clone(&self.replies),
+            }))
+        }
+
+        async fn send_datagram(&self, data: Bytes) -> Result<(), QuicRtcError> {
+            self.datagrams.lock().push(data);
+            Ok(())
+        }
+
+        fn max_datagram_size(&self) -> Option<usize> {
+            *self.max_datagram_size.lock()
+        }
+
+        fn take_events(&self) -> Option<mpsc::UnboundedReceiver<DriverEvent>> {
+            self.events.lock().take()
+        }
+
+        fn connection_id(&self) -> Uuid {
+            self.connection_id
+        }
+
+        fn is_connected(&self) -> bool {
+            true
+        }
+
+        async fn close(&self) -> Result<(), QuicRtcError> {
+            Ok(())
+        }
+    }
+
+    impl MockDriver {
+        /// Queue control messages for the peer to send
+        fn reply(&self, messages: Vec<MoqControlMessage>) {
+            for message in messages {
+                let sequence = {
+                    let mut next_reply = self.next_reply.lock();
+                    *next_reply += 1;
+                    *next_reply - 1
+                };
+                self.reply_with_sequence(sequence, message);
+            }
+        }
+
+        /// Queue a control message carrying `sequence` whatever came before
+        fn reply_with_sequence(&self, sequence: u64, message: MoqControlMessage) {
+            let mut buf = BytesMut::new();
+            MoqWireFormat::encode_sequenced_control_message(sequence, &message, &mut buf).unwrap();
+            self.replies.lock().push_back(buf.freeze());
+        }
+
+        /// Control messages written so far, skipping anything else
+        fn sent_control_messages(&self) -> Vec<MoqControlMessage> {
+            self.stream_data
+                .lock()
+                .iter()
+                .filter_map(|data| MoqWireFormat::decode_sequenced_control_message(data).ok())
+                .map(|(_, message)| message)
+                .collect()
+        }
+    }
+
+    fn setup_ok() -> MoqControlMessage {
+        MoqControlMessage::SetupOk {
+            version: MoqCapabilities::default().version,
+            capabilities: MoqCapabilities::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transport_over_custom_driver() {
+        let (driver, driver_events) = MockDriver::new();
+        let driver = Arc::new(driver);
+        let transport = MoqOverQuicTransport::with_driver(driver.clone(), 1);
+        assert_eq!(transport.connection_id(), driver.connection_id);
+        let metrics = crate::telemetry::MetricsRecorder::new();
+        transport.set_diagnostics_sink(Arc::new(metrics.clone()));
+
+        // Objects sent on a stream reach the driver encoded
+        transport.send_moq_object(test_moq_object()).await.unwrap();
+        let written = driver.stream_data.lock().clone();
+        assert_eq!(written.len(), 1);
+        let (_, decoded) = MoqWireFormat::decode_object_stream(&written[0]).unwrap();
+        assert_eq!(decoded.payload, test_moq_object().payload);
+
+        transport
+            .send_moq_object_datagram(test_moq_object())
+            .await
+            .unwrap();
+        assert_eq!(driver.datagrams.lock().len(), 1);
+
+        // Datagrams from the peer are decoded into the receive queue
+        let datagram = driver.datagrams.lock()[0].clone();
+        driver_events
+            .send(DriverEvent::DatagramReceived(datagram))
+            .unwrap();
+
+        let received = tokio::time::timeout(std::time::Duration::from_secs(1), async {
+            loop {
+                match transport.receive_moq_object().await {
+                    Ok(object) => break object,
+                    Err(_) => tokio::task::yield_now().await,
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(received.group_id, test_moq_object().group_id);
+        assert_eq!(received.payload, test_moq_object().payload);
+
+        let counters = metrics.snapshot().counters;
+        assert_eq!(counters["moq.objects_sent"], 2);
+        assert_eq!(counters["moq.objects_received"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_large_datagram_objects_are_fragmented() {
+        let (driver, driver_events) = MockDriver::new();
+        let driver = Arc::new(driver);
+        *driver.max_datagram_size.lock() = Some(100);
+        let transport = MoqOverQuicTransport::with_driver(driver.clone(), 1);
+        let object = MoqObject {
+            payload: vec![7; 250],
+            size: 250,
+            ..test_moq_object()
+        };
+
+        transport
+            .send_moq_object_datagram(object.clone())
+            .await
+            .unwrap();
+        let datagrams = driver.datagrams.lock().clone();
+        assert!(datagrams.len() > 1);
+        assert!(datagrams.iter().all(|datagram| datagram.len() <= 100));
+        assert_eq!(transport.fragment_stats().objects_fragmented, 1);
+
+        // The peer queues the object once its last fragment arrives
+        for datagram in datagrams {
+            driver_events
+                .send(DriverEvent::DatagramReceived(datagram))
+                .unwrap();
+        }
+        let received = tokio::time::timeout(Duration::from_secs(1), async {
+            loop {
+                match transport.receive_moq_object().await {
+                    Ok(object) => break object,
+                    Err(_) => tokio::task::yield_now().await,
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(received.payload, object.payload);
+        assert_eq!(received.metadata.fragment(), None);
+        assert_eq!(transport.fragment_stats().objects_reassembled, 1);
+    }
+
+    #[tokio::test]
+    async fn test_dvr_holds_objects_while_paused() {
+        let (driver, _driver_events) = MockDriver::new();
+        let transport = MoqOverQuicTransport::with_driver(Arc::new(driver), 1);
+        let dvr = transport.enable_dvr(test_track_namespace(), Duration::from_secs(30));
+        let keyframe = MoqObject {
+            object_id: 0,
+            ..test_moq_object()
+        };
+
+        dvr.pause();
+        MoqOverQuicTransport::queue_incoming_object(
+            &transport.object_queue,
+            &transport.events,
+            keyframe.clone(),
+        );
+        assert!(matches!(
+            transport.receive_moq_object().await,
+            Err(QuicRtcError::NoDataAvailable)
+        ));
+        // Received objects are counted even while playout is paused
+        let stats = transport.track_stats(&test_track_namespace()).unwrap();
+        assert_eq!(stats.objects_received, 1);
+
+        dvr.seek(Duration::from_secs(10));
+        dvr.resume();
+        let object = transport.receive_moq_object().await.unwrap();
+        assert_eq!(object.group_id, keyframe.group_id);
+
+        transport.disable_dvr(&test_track_namespace());
+        assert!(transport.dvr(&test_track_namespace()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_namespace_subscription_follows_announcements() {
+        let pattern = TrackNamespacePattern::parse("room.standup/*").unwrap();
+        let camera = TrackNamespace::parse("room.standup/alice/camera").unwrap();
+        let elsewhere = TrackNamespace::parse("room.other/bob/camera").unwrap();
+        let track = |namespace: &TrackNamespace| {
+            MoqTrack::new(namespace.clone(), "camera".to_string(), MoqTrackType::Video)
+        };
+
+        // Subscriber: every track announced under the prefix is subscribed to
+        let (driver, _driver_events) = MockDriver::new();
+        let driver = Arc::new(driver);
+        driver.reply(vec![
+            setup_ok(),
+            MoqControlMessage::SubscribeNamespaceOk {
+                pattern: pattern.clone(),
+            },
+            MoqControlMessage::SubscribeOk {
+                track_namespace: camera.clone(),
+            },
+        ]);
+        let subscriber = MoqOverQuicTransport::with_driver(driver.clone(), 1);
+        subscriber.establish_session().await.unwrap();
+        subscriber
+            .subscribe_to_namespace(pattern.clone(), 3)
+            .await
+            .unwrap();
+
+        subscriber
+            .handle_track_announcement(camera.clone(), track(&camera), None)
+            .await
+            .unwrap();
+        subscriber
+            .handle_track_announcement(elsewhere.clone(), track(&elsewhere), None)
+            .await
+            .unwrap();
+
+        let subscriptions = subscriber.subscriptions();
+        assert_eq!(subscriptions.len(), 1);
+        assert_eq!(subscriptions[&camera].priority, 3);
+        let namespace_subscriptions = subscriber.namespace_subscriptions();
+        assert_eq!(namespace_subscriptions.len(), 1);
+        assert!(namespace_subscriptions[0].tracks.contains(&camera));
+
+        // Ending the namespace subscription ends the tracks subscribed through it
+        subscriber
+            .unsubscribe_from_namespace(&pattern)
+            .await
+            .unwrap();
+        assert!(subscriber.subscriptions().is_empty());
+        assert!(subscriber.namespace_subscriptions().is_empty());
+        let sent = driver.sent_control_messages();
+        assert!(sent
+            .iter()
+            .any(|message| matches!(message, MoqControlMessage::UnsubscribeNamespace { .. })));
+        assert!(sent.iter().any(|message| matches!(
+            message,
+            MoqControlMessage::Unsubscribe { track_namespace } if *track_namespace == camera
+        )));
+
+        // Publisher: tracks announced before the namespace subscription are announced again
+        let (driver, _driver_events) = MockDriver::new();
+        let driver = Arc::new(driver);
+        driver.reply(vec![
+            setup_ok(),
+            MoqControlMessage::AnnounceOk {
+                track_namespace: camera.clone(),
+            },
+            MoqControlMessage::AnnounceOk {
+                track_namespace: elsewhere.clone(),
+            },
+            MoqControlMessage::AnnounceOk {
+                track_namespace: camera.clone(),
+            },
+        ]);
+        let publisher = MoqOverQuicTransport::with_driver(driver.clone(), 2);
+        publisher.establish_session().await.unwrap();
+        publisher.announce_track(track(&camera)).await.unwrap();
+        publisher.announce_track(track(&elsewhere)).await.unwrap();
+
+        publisher
+            .handle_namespace_subscription_request(pattern.clone())
+            .await
+            .unwrap();
+        assert!(publisher
+            .moq_session
+            .read()
+            .peer_subscribed_to_namespace(&camera));
+        let announced: Vec<_> = driver
+            .sent_control_messages()
+            .into_iter()
+            .filter_map(|message| match message {
+                MoqControlMessage::Announce {
+                    track_namespace, ..
+                } => Some(track_namespace),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(announced, vec![camera.clone(), elsewhere, camera]);
+    }
+
+    /// Bidirectional stream the peer opens to start a session
+    fn peer_control_stream(driver: &MockDriver) -> DriverEvent {
+        DriverEvent::StreamOpened(Box::new(MockStream {
+            id: 1 << 63,
+            stream_type: StreamType::Bidirectional,
+            sent: Arc::clone(&driver.stream_data),
+            replies: Arc::clone(&driver.replies),
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_accepted_session_answers_peer_requests() {
+        let (driver, driver_events) = MockDriver::new();
+        let driver = Arc::new(driver);
+        let camera = TrackNamespace {
+            namespace: "room.standup".to_string(),
+            track_name: "alice/camera".to_string(),
+        };
+        let offered = TrackNamespace {
+            namespace: "room.standup".to_string(),
+            track_name: "bob/camera".to_string(),
+        };
+        let track = |track_namespace: &TrackNamespace| {
+            MoqTrack::new(
+                track_namespace.clone(),
+                "camera".to_string(),
+                MoqTrackType::Video,
+            )
+        };
+        let subscribe = |track_namespace: &TrackNamespace| MoqControlMessage::Subscribe {
+            track_namespace: track_namespace.clone(),
+            priority: 1,
+            start_group: None,
+            end_group: None,
+            auth_token: None,
+        };
+        let capabilities = MoqCapabilities {
+            auth_token: Some("secret".to_string()),
+            ..MoqCapabilities::default()
+        };
+        driver.reply(vec![
+            MoqControlMessage::Setup {
+                version: capabilities.version,
+                capabilities,
+            },
+            MoqControlMessage::Announce {
+                track_namespace: camera.clone(),
+                track: track(&camera),
+                auth_token: None,
+            },
+            subscribe(&camera),
+            subscribe(&offered),
+            MoqControlMessage::Terminate {
+                code: 0,
+                reason: "bye".to_string(),
+            },
+        ]);
+        let transport = MoqOverQuicTransport::with_driver(driver.clone(), 1);
+        transport.offer_track(track(&offered));
+        driver_events.send(peer_control_stream(&driver)).unwrap();
+
+        transport
+            .accept_session(|capabilities| {
+                assert_eq!(capabilities.auth_token.as_deref(), Some("secret"));
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert_eq!(transport.session_state(), MoqSessionState::Active);
+        transport.serve_peer_requests().await.unwrap();
+        assert_eq!(transport.session_state(), MoqSessionState::Terminated);
+
+        let sent = driver.sent_control_messages();
+        assert!(matches!(
+            sent.as_slice(),
+            [
+                MoqControlMessage::SetupOk { .. },
+                MoqControlMessage::AnnounceOk { .. },
+                MoqControlMessage::SubscribeOk { track_namespace },
+                MoqControlMessage::SubscribeOk { track_namespace: answered },
+            ] if *track_namespace == camera && *answered == offered
+        ));
+        assert!(transport.subscriptions().contains_key(&offered));
+        assert!(transport.withdraw_track(&offered));
+        assert!(!transport.subscriptions().contains_key(&offered));
+
+        // Objects of the announced track are named after it
+        let object = MoqOverQuicTransport::name_object(
+            &transport.track_aliases,
+            camera.alias(),
+            test_moq_object(),
+        );
+        assert_eq!(object.track_namespace, camera);
+    }
+
+    #[tokio::test]
+    async fn test_accept_session_sends_setup_error_when_not_admitted() {
+        let (driver, driver_events) = MockDriver::new();
+        let driver = Arc::new(driver);
+        driver.reply(vec![MoqControlMessage::Setup {
+            version: MoqCapabilities::default().version,
+            capabilities: MoqCapabilities::default(),
+        }]);
+        let transport = MoqOverQuicTransport::with_driver(driver.clone(), 1);
+        driver_events.send(peer_control_stream(&driver)).unwrap();
+
+        let result = transport
+            .accept_session(|_| {
+                Err(QuicRtcError::Unauthorized {
+                    reason: "No auth token".to_string(),
+                })
+            })
+            .await;
+        assert!(matches!(result, Err(QuicRtcError::Unauthorized { .. })));
+        assert_eq!(transport.session_state(), MoqSessionState::Terminated);
+        assert!(matches!(
+            driver.sent_control_messages().as_slice(),
+            [MoqControlMessage::SetupError { code, .. }] if *code == MoqErrorCode::Unauthorized.code()
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_transport_events_end_with_session_terminated() {
+        let (driver, driver_events) = MockDriver::new();
+        let driver = Arc::new(driver);
+        driver.reply(vec![
+            setup_ok(),
+            MoqControlMessage::SubscribeOk {
+                track_namespace: test_track_namespace(),
+            },
+        ]);
+        let transport = MoqOverQuicTransport::with_driver(driver.clone(), 1);
+        let mut events = transport.subscribe_events();
+        let mut taken = transport.take_event_receiver().unwrap();
+        transport.establish_session().await.unwrap();
+        transport
+            .subscribe_to_track(test_track_namespace(), 2, None, None)
+            .await
+            .unwrap();
+
+        driver_events
+            .send(DriverEvent::Closed {
+                reason: "idle timeout".to_string(),
+            })
+            .unwrap();
+        let mut received = Vec::new();
+        while !matches!(
+            received.last(),
+            Some(MoqTransportEvent::SessionTerminated { .. })
+        ) {
+            let event = tokio::time::timeout(Duration::from_secs(1), events.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            received.push(event);
+        }
+        assert!(matches!(
+            received.as_slice(),
+            [
+                MoqTransportEvent::SessionEstablished { session_id: 1 },
+                MoqTransportEvent::SubscriptionAccepted { .. },
+                MoqTransportEvent::TransportError { .. },
+                MoqTransportEvent::SessionTerminated { code: None, .. },
+            ]
+        ));
+
+        // Closing afterwards doesn't report the session ending twice
+        let _ = transport.close().await;
+        assert!(events.try_recv().is_err());
+        // The taken receiver saw the same events
+        let mut taken_events = Vec::new();
+        while let Ok(event) = taken.try_recv() {
+            taken_events.push(event);
+        }
+        assert_eq!(taken_events.len(), received.len());
+        assert!(matches!(
+            taken_events.last(),
+            Some(MoqTransportEvent::SessionTerminated { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_event_receiver_only_holds_events_after_it_is_taken() {
+        let (driver, driver_events) = MockDriver::new();
+        let driver = Arc::new(driver);
+        driver.reply(vec![setup_ok()]);
+        let transport = MoqOverQuicTransport::with_driver(driver.clone(), 1);
+        transport.establish_session().await.unwrap();
+
+        // Objects received before anyone asked for events aren't kept twice
+        transport
+            .send_moq_object_datagram(test_moq_object())
+            .await
+            .unwrap();
+        let datagram = driver.datagrams.lock()[0].clone();
+        driver_events
+            .send(DriverEvent::DatagramReceived(datagram.clone()))
+            .unwrap();
+        let received = tokio::time::timeout(Duration::from_secs(1), async {
+            loop {
+                if let Ok(object) = transport.receive_moq_object().await {
+                    return object;
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(received.payload, test_moq_object().payload);
+
+        let mut taken = transport.take_event_receiver().unwrap();
+        assert!(transport.take_event_receiver().is_none());
+        assert!(taken.try_recv().is_err());
+
+        driver_events
+            .send(DriverEvent::DatagramReceived(datagram))
+            .unwrap();
+        let event = tokio::time::timeout(Duration::from_secs(1), taken.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            event,
+            MoqTransportEvent::ObjectReceived {
+                group_id: 12345,
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unread_objects_drop_oldest_first() {
+        let (driver, _driver_events) = MockDriver::new();
+        let transport = MoqOverQuicTransport::with_driver(Arc::new(driver), 1);
+        for object_id in 0..OBJECT_QUEUE_CAPACITY as u64 + 2 {
+            MoqOverQuicTransport::queue_incoming_object(
+                &transport.object_queue,
+                &transport.events,
+                MoqObject {
+                    object_id,
+                    ..test_moq_object()
+                },
+            );
+        }
+        assert_eq!(transport.incoming_objects_dropped(), 2);
+
+        // Objects come out in arrival order, starting after the dropped ones
+        let first = transport.receive_moq_object().await.unwrap();
+        assert_eq!(first.object_id, 2);
+        let second = transport.receive_moq_object().await.unwrap();
+        assert_eq!(second.object_id, 3);
+    }
+
+    #[tokio::test]
+    async fn test_peer_terminate_ends_session() {
+        let (driver, _driver_events) = MockDriver::new();
+        let driver = Arc::new(driver);
+        driver.reply(vec![
+            setup_ok(),
+            MoqControlMessage::Terminate {
+                code: 7,
+                reason: "going away".to_string(),
+            },
+        ]);
+        let transport = MoqOverQuicTransport::with_driver(driver.clone(), 1);
+        let mut events = transport.subscribe_events();
+        transport.establish_session().await.unwrap();
+
+        // The TERMINATE arrives instead of an answer
+        assert!(transport
+            .subscribe_to_track(test_track_namespace(), 2, None, None)
+            .await
+            .is_err());
+        assert_eq!(transport.session_state(), MoqSessionState::Terminated);
+        assert!(matches!(
+            events.try_recv(),
+            Ok(MoqTransportEvent::SessionEstablished { .. })
+        ));
+        match events.try_recv() {
+            Ok(MoqTransportEvent::SessionTerminated { code, reason }) => {
+                assert_eq!(code, Some(7));
+                assert_eq!(reason, "going away");
+            }
+            other => panic!("expected SessionTerminated, got {:?}", other),
+        }
+
+        // Nothing follows, from the peer or anywhere else
+        transport.handle_session_termination(7, "going away".to_string());
+        MoqOverQuicTransport::queue_incoming_object(
+            &transport.object_queue,
+            &transport.events,
+            test_moq_object(),
+        );
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_subscription_update_keeps_subscription() {
+        let (driver, _driver_events) = MockDriver::new();
+        let driver = Arc::new(driver);
+        driver.reply(vec![
+            setup_ok(),
+            MoqControlMessage::SubscribeOk {
+                track_namespace: test_track_namespace(),
+            },
+        ]);
+        let transport = MoqOverQuicTransport::with_driver(driver.clone(), 1);
+        transport.establish_session().await.unwrap();
+        transport
+            .subscribe_to_track(test_track_namespace(), 2, None, None)
+            .await
+            .unwrap();
+
+        let updated = transport
+            .update_subscription(&test_track_namespace(), 0, Some(10), None)
+            .await
+            .unwrap();
+        assert_eq!(updated.priority, 0);
+        assert_eq!(updated.start_group, Some(10));
+        assert_eq!(updated.state, MoqSubscriptionState::Active);
+        assert_eq!(
+            transport.subscriptions()[&test_track_namespace()].priority,
+            0
+        );
+
+        // One SUBSCRIBE_UPDATE, no unsubscribe or second subscribe
+        let sent = driver.sent_control_messages();
+        let updates = sent
+            .iter()
+            .filter(|message| matches!(message, MoqControlMessage::SubscribeUpdate { .. }))
+            .count();
+        assert_eq!(updates, 1);
+        assert!(!sent
+            .iter()
+            .any(|message| matches!(message, MoqControlMessage::Unsubscribe { .. })));
+        let subscribes = sent
+            .iter()
+            .filter(|message| matches!(message, MoqControlMessage::Subscribe { .. }))
+            .count();
+        assert_eq!(subscribes, 1);
+
+        // Updating a track we aren't subscribed to fails
+        let other = TrackNamespace::parse("room.standup/bob/camera").unwrap();
+        assert!(matches!(
+            transport.update_subscription(&other, 0, None, None).await,
+            Err(QuicRtcError::TrackNotFound { .. })
+        ));
+
+        // Peer updates change the stored subscription
+        transport
+            .handle_subscription_update(&test_track_namespace(), 5, None, Some(20))
+            .unwrap();
+        let subscription = &transport.subscriptions()[&test_track_namespace()];
+        assert_eq!(subscription.priority, 5);
+        assert_eq!(subscription.end_group, Some(20));
+    }
+
+    #[tokio::test]
+    async fn test_incoming_objects_are_named_by_track_alias() {
+        let (driver, driver_events) = MockDriver::new();
+        let driver = Arc::new(driver);
+        driver.reply(vec![
+            setup_ok(),
+            MoqControlMessage::SubscribeOk {
+                track_namespace: test_track_namespace(),
+            },
+        ]);
+        let transport = MoqOverQuicTransport::with_driver(driver.clone(), 1);
+        transport.establish_session().await.unwrap();
+        transport
+            .subscribe_to_track(test_track_namespace(), 2, None, None)
+            .await
+            .unwrap();
+
+        // Both ways of sending carry the track's alias
+        transport
+            .send_moq_object_datagram(test_moq_object())
+            .await
+            .unwrap();
+        let datagram = driver.datagrams.lock()[0].clone();
+        let (track_alias, _) = MoqWireFormat::decode_object_datagram(&datagram).unwrap();
+        assert_eq!(track_alias, test_track_namespace().alias());
+
+        // Objects of a subscribed track arrive under its namespace and are counted
+        driver_events
+            .send(DriverEvent::DatagramReceived(datagram))
+            .unwrap();
+        let received = tokio::time::timeout(Duration::from_secs(1), async {
+            loop {
+                match transport.receive_moq_object().await {
+                    Ok(object) => break object,
+                    Err(_) => tokio::task::yield_now().await,
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(received.track_namespace, test_track_namespace());
+        assert_eq!(received.track_name, "alice/camera");
+        let stats = transport.track_stats(&test_track_namespace()).unwrap();
+        assert_eq!(stats.objects_received, 1);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_exchange_still_completes() {
+        let (driver, _driver_events) = MockDriver::new();
+        let driver = Arc::new(driver);
+        driver.reply(vec![
+            setup_ok(),
+            MoqControlMessage::SubscribeOk {
+                track_namespace: test_track_namespace(),
+            },
+            MoqControlMessage::AnnounceOk {
+                track_namespace: test_moq_track().namespace,
+            },
+        ]);
+        let transport = MoqOverQuicTransport::with_driver(driver.clone(), 1);
+        transport.establish_session().await.unwrap();
+
+        // Give up on the subscribe before it gets a chance to run
+        let mut subscribe =
+            Box::pin(transport.subscribe_to_track(test_track_namespace(), 2, None, None));
+        assert!(futures::poll!(subscribe.as_mut()).is_pending());
+        drop(subscribe);
+
+        // The next exchange waits its turn and gets its own response
+        transport.announce_track(test_moq_track()).await.unwrap();
+        assert_eq!(
+            transport.subscriptions()[&test_track_namespace()].state,
+            MoqSubscriptionState::Active
+        );
+        assert!(transport
+            .announced_tracks()
+            .contains_key(&test_moq_track().namespace));
+    }
+
+    #[tokio::test]
+    async fn test_control_round_trip_retries_silent_peer() {
+        let (driver, _driver_events) = MockDriver::new();
+        let driver = Arc::new(driver);
+        driver.reply(vec![setup_ok()]);
+        let transport = MoqOverQuicTransport::with_driver(driver.clone(), 1);
+        transport.establish_session().await.unwrap();
+        transport.set_control_retry_policy(ControlRetryPolicy {
+            response_timeout: Duration::from_millis(100),
+            max_attempts: 3,
+            backoff: Duration::from_millis(10),
+        });
+
+        // The answer only arrives once the first attempt has timed out
+        let late = driver.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(150)).await;
+            late.reply(vec![MoqControlMessage::SubscribeOk {
+                track_namespace: test_track_namespace(),
+            }]);
+        });
+        let subscription = transport
+            .subscribe_to_track(test_track_namespace(), 2, None, None)
+            .await
+            .unwrap();
+        assert_eq!(subscription.state, MoqSubscriptionState::Active);
+        let subscribes = driver
+            .sent_control_messages()
+            .into_iter()
+            .filter(|message| matches!(message, MoqControlMessage::Subscribe { .. }))
+            .count();
+        assert_eq!(subscribes, 2);
+
+        // The peer's answer to the resent subscribe is skipped
+        driver.reply(vec![
+            MoqControlMessage::SubscribeOk {
+                track_namespace: test_track_namespace(),
+            },
+            MoqControlMessage::AnnounceOk {
+                track_namespace: test_moq_track().namespace,
+            },
+        ]);
+        transport.announce_track(test_moq_track()).await.unwrap();
+
+        // A peer that never answers runs out of attempts
+        let mut silent_track = test_moq_track();
+        silent_track.namespace.track_name = "silent".to_string();
+        let error = transport.announce_track(silent_track).await.unwrap_err();
+        assert!(matches!(error, QuicRtcError::Timeout { .. }));
+        assert!(error.is_retryable());
+        let announces = driver
+            .sent_control_messages()
+            .into_iter()
+            .filter(|message| matches!(message, MoqControlMessage::Announce { .. }))
+            .count();
+        assert_eq!(announces, 4);
+    }
+
+    #[tokio::test]
+    async fn test_replayed_control_message_terminates_session() {
+        let (driver, _driver_events) = MockDriver::new();
+        let driver = Arc::new(driver);
+        driver.reply(vec![setup_ok()]);
+        let transport = MoqOverQuicTransport::with_driver(driver.clone(), 1);
+        transport.establish_session().await.unwrap();
+
+        // A relay replaying the SETUP_OK's sequence number with another message
+        driver.reply_with_sequence(
+            0,
+            MoqControlMessage::SubscribeOk {
+                track_namespace: test_track_namespace(),
+            },
+        );
+        let error = transport
+            .subscribe_to_track(test_track_namespace(), 2, None, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, QuicRtcError::MoqProtocol { .. }));
+        assert_eq!(transport.session_state(), MoqSessionState::Terminated);
+        assert!(driver
+            .sent_control_messages()
+            .iter()
+            .any(|message| matches!(
+                message,
+                MoqControlMessage::Terminate { code, .. }
+                    if *code == MoqErrorCode::ProtocolViolation.code()
+            )));
+    }
 }
@@ -0,0 +1,266 @@
+//! DSCP marking of outgoing media packets
+//!
+//! Networks that honor QoS prioritize packets by the Differentiated
+//! Services code point in their IP header. [`QosConfig`] maps each kind of
+//! track to a class, by default EF for audio and AF41 for video, and
+//! [`DscpMarker`] applies it to a connection's UDP socket.
+//!
+//! QUIC packs frames from every stream of a connection into the same
+//! packets, so packets cannot be marked per track. A connection is marked
+//! with the class of the media it carries: the audio class while it only
+//! carries audio, the video class once it carries video as well. Marking
+//! video EF would risk it being policed as excess expedited traffic.
+
+use crate::error::QuicRtcError;
+use crate::moq::MoqTrackType;
+use quinn::udp::{RecvMeta, Transmit};
+use quinn::{AsyncUdpSocket, UdpPoller};
+use std::io::IoSliceMut;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU16, AtomicU8, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tracing::warn;
+
+/// No TOS value has been applied to the socket yet
+const TOS_UNSET: u16 = u16::MAX;
+
+const CARRIES_AUDIO: u8 = 1;
+const CARRIES_VIDEO: u8 = 2;
+
+/// Differentiated Services code point (RFC 2474)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Dscp(u8);
+
+impl Dscp {
+    /// Default forwarding (CS0)
+    pub const BEST_EFFORT: Self = Self(0);
+    /// Assured forwarding class 4, low drop precedence; interactive video
+    pub const AF41: Self = Self(34);
+    /// Assured forwarding class 4, medium drop precedence
+    pub const AF42: Self = Self(36);
+    /// Class selector 5; signaling
+    pub const CS5: Self = Self(40);
+    /// Expedited forwarding; interactive audio
+    pub const EF: Self = Self(46);
+
+    /// Code point from its 6-bit value, or `None` if out of range
+    pub fn new(value: u8) -> Option<Self> {
+        (value < 64).then_some(Self(value))
+    }
+
+    /// 6-bit code point value
+    pub fn value(self) -> u8 {
+        self.0
+    }
+
+    /// IP TOS / traffic class byte with this code point and `ecn` bits
+    pub fn tos(self, ecn: u8) -> u8 {
+        (self.0 << 2) | (ecn & 0b11)
+    }
+}
+
+/// Classes outgoing packets are marked with, per kind of track
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QosConfig {
+    /// Class for audio tracks
+    pub audio: Dscp,
+    /// Class for video tracks
+    pub video: Dscp,
+}
+
+impl Default for QosConfig {
+    fn default() -> Self {
+        Self {
+            audio: Dscp::EF,
+            video: Dscp::AF41,
+        }
+    }
+}
+
+impl QosConfig {
+    /// Class for tracks of `track_type`; data tracks are best effort
+    pub fn class_for(&self, track_type: MoqTrackType) -> Dscp {
+        match track_type {
+            MoqTrackType::Audio => self.audio,
+            MoqTrackType::Video => self.video,
+            MoqTrackType::Data => Dscp::BEST_EFFORT,
+        }
+    }
+}
+
+/// Picks the class a connection's packets are marked with
+///
+/// Clones share state. Packets are best effort until a media track is
+/// added with [`DscpMarker::add_track`].
+#[derive(Debug, Clone)]
+pub struct DscpMarker {
+    config: Arc<QosConfig>,
+    carried: Arc<AtomicU8>,
+}
+
+impl DscpMarker {
+    /// Marker applying `config`
+    pub fn new(config: QosConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            carried: Arc::new(AtomicU8::new(0)),
+        }
+    }
+
+    /// Class mapping in use
+    pub fn config(&self) -> &QosConfig {
+        &self.config
+    }
+
+    /// Note that the connection now carries a track of `track_type`
+    pub fn add_track(&self, track_type: MoqTrackType) {
+        let bit = match track_type {
+            MoqTrackType::Audio => CARRIES_AUDIO,
+            MoqTrackType::Video => CARRIES_VIDEO,
+            MoqTrackType::Data => return,
+        };
+        self.carried.fetch_or(bit, Ordering::Relaxed);
+    }
+
+    /// Class packets are currently marked with
+    pub fn dscp(&self) -> Dscp {
+        let carried = self.carried.load(Ordering::Relaxed);
+        if carried & CARRIES_VIDEO != 0 {
+            self.config.video
+        } else if carried & CARRIES_AUDIO != 0 {
+            self.config.audio
+        } else {
+            Dscp::BEST_EFFORT
+        }
+    }
+}
+
+/// Wrap `socket` so the packets it sends are marked by `marker`
+///
+/// quinn attaches the ECN bits to every packet as a TOS control message,
+/// which would clear a DSCP set on the socket, so marked packets are sent
+/// with a plain `sendto` after setting the whole TOS byte on the socket.
+/// That also means they are sent one datagram at a time, without GSO.
+pub(crate) fn marking_socket(
+    socket: std::net::UdpSocket,
+    runtime: &Arc<dyn quinn::Runtime>,
+    marker: DscpMarker,
+) -> Result<Arc<dyn AsyncUdpSocket>, QuicRtcError> {
+    let socket_error = |e: std::io::Error| QuicRtcError::Transport {
+        reason: format!("Failed to create DSCP-marking UDP socket: {}", e),
+    };
+    let sender = socket.try_clone().map_err(socket_error)?;
+    let ipv6 = sender.local_addr().map_err(socket_error)?.is_ipv6();
+    let inner = runtime.wrap_udp_socket(socket).map_err(socket_error)?;
+    Ok(Arc::new(MarkingSocket {
+        inner,
+        sender,
+        ipv6,
+        marker,
+        applied_tos: AtomicU16::new(TOS_UNSET),
+    }))
+}
+
+/// UDP socket marking outgoing packets with a DSCP
+#[derive(Debug)]
+struct MarkingSocket {
+    /// quinn's socket, used for receiving and readiness
+    inner: Arc<dyn AsyncUdpSocket>,
+    /// The same socket, used for marked sends
+    sender: std::net::UdpSocket,
+    ipv6: bool,
+    marker: DscpMarker,
+    /// TOS byte last set on the socket
+    applied_tos: AtomicU16,
+}
+
+impl MarkingSocket {
+    /// Set the TOS byte on the socket unless it is already set
+    fn apply_tos(&self, tos: u8) {
+        if self.applied_tos.swap(tos as u16, Ordering::Relaxed) == tos as u16 {
+            return;
+        }
+        let socket = socket2::SockRef::from(&self.sender);
+        let result = if self.ipv6 {
+            set_traffic_class_v6(&socket, tos)
+        } else {
+            socket.set_tos(tos as u32)
+        };
+        if let Err(e) = result {
+            warn!("Failed to mark UDP socket with TOS {:#04x}: {}", tos, e);
+        }
+    }
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "linux",
+    target_os = "macos",
+))]
+fn set_traffic_class_v6(socket: &socket2::SockRef<'_>, tos: u8) -> std::io::Result<()> {
+    // Dual-stack sockets send to IPv4-mapped addresses with IP_TOS
+    let _ = socket.set_tos(tos as u32);
+    socket.set_tclass_v6(tos as u32)
+}
+
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "linux",
+    target_os = "macos",
+)))]
+fn set_traffic_class_v6(socket: &socket2::SockRef<'_>, tos: u8) -> std::io::Result<()> {
+    socket.set_tos(tos as u32)
+}
+
+impl AsyncUdpSocket for MarkingSocket {
+    fn create_io_poller(self: Arc<Self>) -> Pin<Box<dyn UdpPoller>> {
+        self.inner.clone().create_io_poller()
+    }
+
+    fn try_send(&self, transmit: &Transmit) -> std::io::Result<()> {
+        let ecn = transmit.ecn.map_or(0, |ecn| ecn as u8);
+        self.apply_tos(self.marker.dscp().tos(ecn));
+
+        match transmit.segment_size {
+            Some(size) if size > 0 && size < transmit.contents.len() => {
+                for segment in transmit.contents.chunks(size) {
+                    self.sender.send_to(segment, transmit.destination)?;
+                }
+            }
+            _ => {
+                self.sender
+                    .send_to(transmit.contents, transmit.destination)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn poll_recv(
+        &self,
+        cx: &mut Context,
+        bufs: &mut [IoSliceMut<'_>],
+        meta: &mut [RecvMeta],
+    ) -> Poll<std::io::Result<usize>> {
+        self.inner.poll_recv(cx, bufs, meta)
+    }
+
+    fn local_addr(&self) -> std::io::Result<SocketAddr> {
+        self.inner.local_addr()
+    }
+
+    fn max_transmit_segments(&self) -> usize {
+        1
+    }
+
+    fn max_receive_segments(&self) -> usize {
+        self.inner.max_receive_segments()
+    }
+
+    fn may_fragment(&self) -> bool {
+        self.inner.may_fragment()
+    }
+}
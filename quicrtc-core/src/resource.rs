@@ -4,8 +4,10 @@
 //! mechanisms to ensure efficient operation across mobile and desktop platforms.
 
 use crate::error::QuicRtcError;
+use crate::moq::MoqCacheBudget;
 use crate::transport::TransportConnection;
 use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -15,7 +17,10 @@ use tracing::{debug, info};
 use uuid::Uuid;
 
 /// Resource limits configuration
-#[derive(Debug, Clone)]
+///
+/// Fields missing from a deserialized config take the desktop defaults.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct ResourceLimits {
     /// Maximum memory usage in MB (None = unlimited)
     pub max_memory_mb: Option<u64>,
@@ -28,6 +33,7 @@ pub struct ResourceLimits {
     /// Maximum number of MoQ objects in cache
     pub max_cached_objects: Option<u32>,
     /// Resource cleanup timeout
+    #[serde(with = "humantime_serde")]
     pub cleanup_timeout: Duration,
     /// Warning threshold (percentage of limit)
     pub warning_threshold: f32,
@@ -289,11 +295,13 @@ pub struct ConnectionPool {
 }
 
 /// Connection pool configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct ConnectionPoolConfig {
     /// Maximum number of idle connections to keep
     pub max_idle_connections: u32,
     /// How long to keep idle connections before cleanup
+    #[serde(with = "humantime_serde")]
     pub idle_timeout: Duration,
     /// Maximum total connections (active + idle)
     pub max_total_connections: u32,
@@ -446,8 +454,9 @@ impl ConnectionPool {
                 debug!("Cleaning up idle connection: {}", connection_id);
 
                 // Close the connection gracefully
-                if let Some(mut conn) = connection.try_write() {
-                    let _ = conn.close().await;
+                let closing = connection.try_write().map(|mut conn| conn.start_close());
+                if let Some(closing) = closing {
+                    let _ = closing.finish().await;
                 }
             }
             self.idle_connections.remove(index);
@@ -509,6 +518,8 @@ pub struct ResourceManager {
     cleanup_handle: Option<tokio::task::JoinHandle<()>>,
     /// Monitoring task handle
     monitor_handle: Option<tokio::task::JoinHandle<()>>,
+    /// Memory budget shared by all object caches
+    cache_budget: MoqCacheBudget,
 }
 
 impl ResourceManager {
@@ -516,6 +527,7 @@ impl ResourceManager {
     pub fn new(limits: ResourceLimits) -> (Self, mpsc::UnboundedReceiver<ResourceWarning>) {
         let (warning_tx, warning_rx) = mpsc::unbounded_channel();
 
+        let cache_budget = MoqCacheBudget::new(Self::cache_budget_bytes(&limits));
         let manager = Self {
             limits,
             current_usage: Arc::new(RwLock::new(ResourceUsage::default())),
@@ -527,6 +539,7 @@ impl ResourceManager {
             warning_tx,
             cleanup_handle: None,
             monitor_handle: None,
+            cache_budget,
         };
 
         (manager, warning_rx)
@@ -738,12 +751,34 @@ impl ResourceManager {
         self.connection_pool.clone()
     }
 
+    /// Memory budget to share across object caches so cached media stays
+    /// within `max_memory_mb`
+    ///
+    /// Caches given the budget with [`MoqObjectCache::with_budget`] split it
+    /// fairly between their tracks.
+    ///
+    /// [`MoqObjectCache::with_budget`]: crate::moq::MoqObjectCache::with_budget
+    pub fn cache_budget(&self) -> MoqCacheBudget {
+        self.cache_budget.clone()
+    }
+
     /// Update resource limits
     pub fn update_limits(&mut self, new_limits: ResourceLimits) {
+        self.cache_budget
+            .set_max_bytes(Self::cache_budget_bytes(&new_limits));
         self.limits = new_limits;
         info!("Updated resource limits");
     }
 
+    /// Cache budget allowed by the memory limit
+    fn cache_budget_bytes(limits: &ResourceLimits) -> usize {
+        limits.max_memory_mb.map_or(usize::MAX, |mb| {
+            usize::try_from(mb)
+                .unwrap_or(usize::MAX)
+                .saturating_mul(1024 * 1024)
+        })
+    }
+
     /// Get current resource limits
     pub fn limits(&self) -> &ResourceLimits {
         &self.limits
@@ -949,6 +984,17 @@ mod tests {
         assert_eq!(limits.max_memory_mb, Some(50)); // Mobile preset
     }
 
+    #[tokio::test]
+    async fn test_cache_budget_follows_memory_limit() {
+        let (mut manager, _warning_rx) = ResourceManager::new(ResourceLimits::mobile());
+        let budget = manager.cache_budget();
+        assert_eq!(budget.max_bytes(), 50 * 1024 * 1024);
+
+        // Caches holding the budget see new limits
+        manager.update_limits(ResourceLimits::unlimited());
+        assert_eq!(budget.max_bytes(), usize::MAX);
+    }
+
     #[tokio::test]
     async fn test_connection_pool() {
         let mut pool = ConnectionPool::new(ConnectionPoolConfig::default());
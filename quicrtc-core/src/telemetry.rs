@@ -0,0 +1,242 @@
+//! Pluggable telemetry
+//!
+//! Components report log lines and metrics to a [`DiagnosticsSink`] rather
+//! than straight to `tracing` or stderr, so embedders can route them into
+//! their own logging and monitoring. [`TracingSink`] is the default and
+//! emits everything as `tracing` events; [`MetricsRecorder`] keeps metrics
+//! in memory for the app to poll.
+//!
+//! Sinks are called from real-time threads such as audio callbacks, so
+//! they must return quickly and never block on I/O.
+
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Target of the `tracing` events [`TracingSink`] emits for metrics
+pub const METRICS_TARGET: &str = "quicrtc::metrics";
+
+/// Severity of a log line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    /// Something failed
+    Error,
+    /// Something went wrong but was handled
+    Warn,
+    /// Notable event
+    Info,
+    /// Detail for debugging
+    Debug,
+    /// Fine-grained detail
+    Trace,
+}
+
+/// Destination of log lines and metrics
+///
+/// Metric names are dot-separated, such as `moq.objects_sent`; the
+/// components that report them document theirs.
+pub trait DiagnosticsSink: Send + Sync + std::fmt::Debug {
+    /// Log `message` from `target`, a module path such as
+    /// `quicrtc_media::render`
+    fn log(&self, level: LogLevel, target: &str, message: &str);
+
+    /// Add `value` to the counter `name`
+    fn counter(&self, name: &str, value: u64);
+
+    /// Set the gauge `name` to `value`
+    fn gauge(&self, name: &str, value: f64);
+
+    /// Record `value` in the histogram `name`
+    fn histogram(&self, name: &str, value: f64);
+}
+
+/// Sink emitting `tracing` events, the default
+///
+/// Log lines keep their level; metrics are trace events under
+/// [`METRICS_TARGET`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TracingSink;
+
+impl DiagnosticsSink for TracingSink {
+    fn log(&self, level: LogLevel, target: &str, message: &str) {
+        match level {
+            LogLevel::Error => tracing::error!(module = target, "{}", message),
+            LogLevel::Warn => tracing::warn!(module = target, "{}", message),
+            LogLevel::Info => tracing::info!(module = target, "{}", message),
+            LogLevel::Debug => tracing::debug!(module = target, "{}", message),
+            LogLevel::Trace => tracing::trace!(module = target, "{}", message),
+        }
+    }
+
+    fn counter(&self, name: &str, value: u64) {
+        tracing::trace!(target: METRICS_TARGET, counter = name, value);
+    }
+
+    fn gauge(&self, name: &str, value: f64) {
+        tracing::trace!(target: METRICS_TARGET, gauge = name, value);
+    }
+
+    fn histogram(&self, name: &str, value: f64) {
+        tracing::trace!(target: METRICS_TARGET, histogram = name, value);
+    }
+}
+
+/// Sink shared by components that weren't given one
+pub fn default_sink() -> Arc<dyn DiagnosticsSink> {
+    Arc::new(TracingSink)
+}
+
+/// Summary of the values recorded in a histogram
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HistogramSummary {
+    /// Values recorded
+    pub count: u64,
+    /// Sum of the values
+    pub sum: f64,
+    /// Smallest value
+    pub min: f64,
+    /// Largest value
+    pub max: f64,
+}
+
+impl HistogramSummary {
+    /// Mean of the values, `None` before the first
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+
+    fn record(&mut self, value: f64) {
+        if self.count == 0 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        self.count += 1;
+        self.sum += value;
+    }
+}
+
+/// Metrics recorded by a [`MetricsRecorder`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricsSnapshot {
+    /// Counter totals by name
+    pub counters: BTreeMap<String, u64>,
+    /// Latest gauge values by name
+    pub gauges: BTreeMap<String, f64>,
+    /// Histogram summaries by name
+    pub histograms: BTreeMap<String, HistogramSummary>,
+}
+
+/// Sink keeping metrics in memory, for apps that poll them
+///
+/// Log lines go on to another sink, [`TracingSink`] unless set with
+/// [`MetricsRecorder::with_logs_to`]. Clones share their metrics.
+#[derive(Debug, Clone)]
+pub struct MetricsRecorder {
+    metrics: Arc<Mutex<MetricsSnapshot>>,
+    logs: Arc<dyn DiagnosticsSink>,
+}
+
+impl MetricsRecorder {
+    /// Record metrics, passing log lines to `tracing`
+    pub fn new() -> Self {
+        Self {
+            metrics: Arc::default(),
+            logs: default_sink(),
+        }
+    }
+
+    /// Pass log lines to `logs` instead
+    pub fn with_logs_to(mut self, logs: Arc<dyn DiagnosticsSink>) -> Self {
+        self.logs = logs;
+        self
+    }
+
+    /// Metrics recorded so far
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        self.metrics.lock().clone()
+    }
+
+    /// Forget everything recorded so far
+    pub fn reset(&self) {
+        *self.metrics.lock() = MetricsSnapshot::default();
+    }
+}
+
+impl Default for MetricsRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DiagnosticsSink for MetricsRecorder {
+    fn log(&self, level: LogLevel, target: &str, message: &str) {
+        self.logs.log(level, target, message);
+    }
+
+    fn counter(&self, name: &str, value: u64) {
+        let mut metrics = self.metrics.lock();
+        // Only the first report of a metric allocates its name
+        match metrics.counters.get_mut(name) {
+            Some(total) => *total += value,
+            None => {
+                metrics.counters.insert(name.to_string(), value);
+            }
+        }
+    }
+
+    fn gauge(&self, name: &str, value: f64) {
+        let mut metrics = self.metrics.lock();
+        match metrics.gauges.get_mut(name) {
+            Some(gauge) => *gauge = value,
+            None => {
+                metrics.gauges.insert(name.to_string(), value);
+            }
+        }
+    }
+
+    fn histogram(&self, name: &str, value: f64) {
+        let mut metrics = self.metrics.lock();
+        match metrics.histograms.get_mut(name) {
+            Some(summary) => summary.record(value),
+            None => {
+                let mut summary = HistogramSummary::default();
+                summary.record(value);
+                metrics.histograms.insert(name.to_string(), summary);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics_recorder_aggregates() {
+        let recorder = MetricsRecorder::new();
+        let sink: Arc<dyn DiagnosticsSink> = Arc::new(recorder.clone());
+
+        sink.counter("moq.objects_sent", 2);
+        sink.counter("moq.objects_sent", 3);
+        sink.gauge("audio.render.buffer_ms", 40.0);
+        sink.gauge("audio.render.buffer_ms", 60.0);
+        for value in [10.0, 30.0, 20.0] {
+            sink.histogram("moq.control_round_trip_ms", value);
+        }
+        sink.log(LogLevel::Warn, "quicrtc_core::telemetry", "not recorded");
+
+        let snapshot = recorder.snapshot();
+        assert_eq!(snapshot.counters["moq.objects_sent"], 5);
+        assert_eq!(snapshot.gauges["audio.render.buffer_ms"], 60.0);
+        let round_trip = snapshot.histograms["moq.control_round_trip_ms"];
+        assert_eq!(round_trip.count, 3);
+        assert_eq!((round_trip.min, round_trip.max), (10.0, 30.0));
+        assert_eq!(round_trip.mean(), Some(20.0));
+
+        recorder.reset();
+        assert_eq!(recorder.snapshot(), MetricsSnapshot::default());
+    }
+}
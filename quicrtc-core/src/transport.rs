@@ -1,6 +1,10 @@
 //! QUIC transport layer with fallback mechanisms and production-grade configuration
 
+use crate::capture::{self, PacketCapture, PacketEvent};
+use crate::ecn::{self, EcnCounters, EcnCounts};
 use crate::error::QuicRtcError;
+use crate::keepalive::{self, AdaptiveKeepAlive};
+use crate::qos::{self, DscpMarker, QosConfig};
 use async_trait::async_trait;
 use bytes::Bytes;
 use parking_lot::RwLock;
@@ -10,7 +14,7 @@ use std::collections::HashMap;
 use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tokio_tungstenite::{connect_async, WebSocketStream};
 use tracing::{debug, info, warn};
 use uuid::Uuid;
@@ -69,10 +73,82 @@ pub struct QuicTransportConfig {
     pub enable_0rtt: bool,
     /// Enable connection migration for mobile networks
     pub enable_migration: bool,
-    /// Maximum transmission unit for path MTU discovery
+    /// IP packet size to start native QUIC connections with, before path
+    /// MTU discovery finds what the path really carries
     pub initial_mtu: u16,
     /// Certificate configuration
     pub certificate_config: CertificateConfig,
+    /// UDP socket backend and tuning for server endpoints
+    pub socket_config: SocketConfig,
+    /// DSCP classes to mark outgoing packets with (`None` = unmarked)
+    ///
+    /// Servers mark with the video class since their sockets carry every
+    /// client's media; changing this with [`QuicServer::reload`] has no
+    /// effect on sockets already bound.
+    pub qos: Option<QosConfig>,
+}
+
+/// UDP socket backend for server endpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SocketBackend {
+    /// A single UDP socket shared by every connection
+    Standard,
+    /// Several sockets bound to the same address with `SO_REUSEPORT`, each
+    /// driving its own endpoint so the kernel spreads clients across them.
+    /// Linux only; other platforms fall back to a single socket.
+    ReusePort {
+        /// Number of sockets (and endpoints) to open
+        shards: usize,
+    },
+}
+
+/// UDP socket configuration
+#[derive(Debug, Clone)]
+pub struct SocketConfig {
+    /// Socket backend
+    pub backend: SocketBackend,
+    /// Kernel receive buffer size in bytes (None = OS default)
+    pub recv_buffer_size: Option<usize>,
+    /// Kernel send buffer size in bytes (None = OS default)
+    pub send_buffer_size: Option<usize>,
+}
+
+impl Default for SocketConfig {
+    fn default() -> Self {
+        Self {
+            backend: SocketBackend::Standard,
+            recv_buffer_size: None,
+            send_buffer_size: None,
+        }
+    }
+}
+
+impl SocketConfig {
+    /// One `SO_REUSEPORT` shard per CPU core with large kernel buffers
+    pub fn server() -> Self {
+        let shards = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        Self {
+            backend: SocketBackend::ReusePort { shards },
+            recv_buffer_size: Some(8 * 1024 * 1024),
+            send_buffer_size: Some(8 * 1024 * 1024),
+        }
+    }
+
+    /// Number of sockets this configuration opens on the current platform
+    pub fn shard_count(&self) -> usize {
+        match self.backend {
+            SocketBackend::Standard => 1,
+            SocketBackend::ReusePort { shards } => {
+                if cfg!(target_os = "linux") {
+                    shards.max(1)
+                } else {
+                    1
+                }
+            }
+        }
+    }
 }
 
 /// Congestion control algorithms for QUIC
@@ -106,6 +182,8 @@ impl QuicTransportConfig {
             enable_migration: true,
             initial_mtu: 1200, // Conservative for mobile networks
             certificate_config: CertificateConfig::default(),
+            socket_config: SocketConfig::default(),
+            qos: None,
         }
     }
 
@@ -122,6 +200,8 @@ impl QuicTransportConfig {
             enable_migration: false, // Desktop typically doesn't need migration
             initial_mtu: 1500,       // Standard Ethernet MTU
             certificate_config: CertificateConfig::default(),
+            socket_config: SocketConfig::default(),
+            qos: None,
         }
     }
 
@@ -138,6 +218,8 @@ impl QuicTransportConfig {
             enable_migration: false,
             initial_mtu: 1500,
             certificate_config: CertificateConfig::default(),
+            socket_config: SocketConfig::server(),
+            qos: None,
         }
     }
 }
@@ -272,7 +354,7 @@ pub enum WarningSeverity {
 
 /// Production resource manager for QUIC connections
 pub struct ResourceManager {
-    limits: ResourceLimits,
+    limits: RwLock<ResourceLimits>,
     current_usage: Arc<RwLock<ResourceUsage>>,
     monitors: Vec<Box<dyn ResourceMonitor + Send + Sync>>,
     warnings: Arc<RwLock<Vec<(ResourceWarning, WarningSeverity, Instant)>>>,
@@ -293,7 +375,7 @@ impl ResourceManager {
     /// Create a new resource manager with specified limits
     pub fn new(limits: ResourceLimits) -> Self {
         Self {
-            limits,
+            limits: RwLock::new(limits),
             current_usage: Arc::new(RwLock::new(ResourceUsage::default())),
             monitors: Vec::new(),
             warnings: Arc::new(RwLock::new(Vec::new())),
@@ -303,9 +385,10 @@ impl ResourceManager {
     /// Check if resource limits allow a new operation
     pub fn check_limits(&self) -> Result<(), QuicRtcError> {
         let usage = self.current_usage.read();
+        let limits = self.limits.read();
 
         // Check memory limits
-        if let Some(memory_limit) = self.limits.max_memory_mb {
+        if let Some(memory_limit) = limits.max_memory_mb {
             if usage.memory_mb >= memory_limit {
                 return Err(QuicRtcError::ResourceLimit {
                     resource: format!("Memory limit ({} MB) exceeded", memory_limit),
@@ -314,7 +397,7 @@ impl ResourceManager {
         }
 
         // Check bandwidth limits
-        if let Some(bandwidth_limit) = self.limits.max_bandwidth_kbps {
+        if let Some(bandwidth_limit) = limits.max_bandwidth_kbps {
             if usage.bandwidth_kbps >= bandwidth_limit {
                 return Err(QuicRtcError::ResourceLimit {
                     resource: format!("Bandwidth limit ({} kbps) exceeded", bandwidth_limit),
@@ -323,7 +406,7 @@ impl ResourceManager {
         }
 
         // Check connection limits
-        if let Some(connection_limit) = self.limits.max_connections {
+        if let Some(connection_limit) = limits.max_connections {
             if usage.active_connections >= connection_limit {
                 return Err(QuicRtcError::ResourceLimit {
                     resource: format!("Connection limit ({}) exceeded", connection_limit),
@@ -342,10 +425,11 @@ impl ResourceManager {
     /// Get current warnings approaching resource limits
     pub fn approaching_limits(&self) -> Vec<(ResourceWarning, WarningSeverity)> {
         let usage = self.current_usage.read();
+        let limits = self.limits.read();
         let mut warnings = Vec::new();
 
         // Check memory usage
-        if let Some(memory_limit) = self.limits.max_memory_mb {
+        if let Some(memory_limit) = limits.max_memory_mb {
             let usage_percent = (usage.memory_mb as f32 / memory_limit as f32) * 100.0;
             if usage_percent >= 75.0 {
                 let severity = match usage_percent {
@@ -373,7 +457,7 @@ impl ResourceManager {
         let cleanup_started = Instant::now();
         debug!(
             "Starting resource cleanup with timeout: {:?}",
-            self.limits.cleanup_timeout
+            self.limits.read().cleanup_timeout
         );
 
         // This would clean up idle connections, free unused memory, etc.
@@ -385,6 +469,45 @@ impl ResourceManager {
         Ok(())
     }
 
+    /// Utilization of the most constrained limited resource (0.0 = idle,
+    /// 1.0 = at the limit)
+    pub fn utilization(&self) -> f32 {
+        let usage = self.current_usage.read();
+        let limits = self.limits.read();
+        let ratio = |used: f64, limit: Option<f64>| match limit {
+            Some(limit) if limit > 0.0 => (used / limit) as f32,
+            _ => 0.0,
+        };
+
+        [
+            ratio(
+                usage.memory_mb as f64,
+                limits.max_memory_mb.map(|l| l as f64),
+            ),
+            ratio(
+                usage.bandwidth_kbps as f64,
+                limits.max_bandwidth_kbps.map(|l| l as f64),
+            ),
+            ratio(
+                usage.active_connections as f64,
+                limits.max_connections.map(|l| l as f64),
+            ),
+        ]
+        .into_iter()
+        .fold(0.0, f32::max)
+    }
+
+    /// Get the configured resource limits
+    pub fn limits(&self) -> ResourceLimits {
+        self.limits.read().clone()
+    }
+
+    /// Replace the resource limits; checks made after this use the new limits
+    pub fn update_limits(&self, limits: ResourceLimits) {
+        *self.limits.write() = limits;
+        info!("Updated resource limits");
+    }
+
     /// Update resource usage statistics
     pub fn update_usage(&self, usage: ResourceUsage) {
         let mut current = self.current_usage.write();
@@ -408,15 +531,257 @@ pub trait ResourceMonitor {
     fn monitor_connections(&self) -> Result<u32, QuicRtcError>;
 }
 
+/// Application error code in the CONNECTION_CLOSE sent to clients rejected
+/// by admission control
+pub const SERVER_OVERLOADED_ERROR_CODE: u32 = 0x0503;
+
+/// IPv6 and UDP headers in front of every QUIC packet
+const IP_UDP_OVERHEAD: u16 = 48;
+
+/// Smallest UDP payload QUIC allows, which every path must carry
+const QUIC_MIN_UDP_PAYLOAD: u16 = 1200;
+
+/// Start a native QUIC connection at `initial_mtu`, an IP packet size
+///
+/// quinn sizes packets by UDP payload, so the IP and UDP headers come off
+/// first: a 1500 byte Ethernet MTU must not turn into 1500 byte payloads
+/// that IP would fragment. From there quinn's path MTU discovery
+/// (DPLPMTUD) probes for larger packets on platforms that can set the
+/// don't-fragment bit, and falls back to 1200 bytes when a path such as a
+/// VPN or cellular tunnel drops them.
+fn apply_initial_mtu(transport_config: &mut quinn::TransportConfig, initial_mtu: u16) {
+    transport_config.initial_mtu(
+        initial_mtu
+            .saturating_sub(IP_UDP_OVERHEAD)
+            .max(QUIC_MIN_UDP_PAYLOAD),
+    );
+}
+
+/// Server-side admission control and load shedding thresholds
+#[derive(Debug, Clone)]
+pub struct AdmissionPolicy {
+    /// Utilization of the tightest resource limit (0.0 to 1.0) at which new
+    /// connections are rejected
+    pub reject_threshold: f32,
+    /// Retry-after hint sent to rejected clients
+    pub retry_after: Duration,
+    /// How long the server must stay overloaded before subscriptions are shed
+    pub shed_after: Duration,
+    /// Fraction of a connection's subscriptions shed per step (0.0 to 1.0)
+    pub shed_fraction: f32,
+    /// Maximum rejections completing the handshake at once; beyond this,
+    /// connections are refused without a retry-after hint
+    pub max_pending_rejections: usize,
+}
+
+impl Default for AdmissionPolicy {
+    fn default() -> Self {
+        Self {
+            reject_threshold: 0.9,
+            retry_after: Duration::from_secs(5),
+            shed_after: Duration::from_secs(10),
+            shed_fraction: 0.25,
+            max_pending_rejections: 64,
+        }
+    }
+}
+
+/// Outcome of evaluating a new connection against the admission policy
+#[derive(Debug, Clone, PartialEq)]
+pub enum AdmissionDecision {
+    /// Accept the connection
+    Accept,
+    /// Reject the connection and ask the client to retry later
+    Reject {
+        /// How long the client should wait before reconnecting
+        retry_after: Duration,
+        /// Why the connection was rejected
+        reason: String,
+    },
+}
+
+/// Tracks server load and decides on admission and load shedding
+#[derive(Debug)]
+pub struct AdmissionController {
+    policy: RwLock<AdmissionPolicy>,
+    overloaded_since: RwLock<Option<Instant>>,
+    pending_rejections: std::sync::atomic::AtomicUsize,
+    rejected: std::sync::atomic::AtomicU64,
+}
+
+impl AdmissionController {
+    /// Create a controller enforcing `policy`
+    pub fn new(policy: AdmissionPolicy) -> Self {
+        Self {
+            policy: RwLock::new(policy),
+            overloaded_since: RwLock::new(None),
+            pending_rejections: std::sync::atomic::AtomicUsize::new(0),
+            rejected: std::sync::atomic::AtomicU64::new(0),
+        }
+    }
+
+    /// Get the admission policy
+    pub fn policy(&self) -> AdmissionPolicy {
+        self.policy.read().clone()
+    }
+
+    /// Replace the admission policy; it applies from the next evaluation
+    pub fn set_policy(&self, policy: AdmissionPolicy) {
+        *self.policy.write() = policy;
+    }
+
+    /// Re-sample the overload state from current resource usage
+    ///
+    /// Returns whether the server is currently overloaded.
+    pub fn refresh(&self, resources: &ResourceManager) -> bool {
+        let overloaded = resources.utilization() >= self.policy.read().reject_threshold
+            || resources.check_limits().is_err();
+
+        let mut since = self.overloaded_since.write();
+        match (overloaded, *since) {
+            (true, None) => {
+                warn!("Server overloaded, rejecting new connections");
+                *since = Some(Instant::now());
+            }
+            (false, Some(_)) => {
+                info!("Server load back under admission threshold");
+                *since = None;
+            }
+            _ => {}
+        }
+
+        overloaded
+    }
+
+    /// Decide whether to admit a new connection
+    pub fn evaluate(&self, resources: &ResourceManager) -> AdmissionDecision {
+        if !self.refresh(resources) {
+            return AdmissionDecision::Accept;
+        }
+
+        AdmissionDecision::Reject {
+            retry_after: self.policy.read().retry_after,
+            reason: format!(
+                "server overloaded ({:.0}% utilization)",
+                resources.utilization() * 100.0
+            ),
+        }
+    }
+
+    /// Whether the server was overloaded at the last refresh
+    pub fn is_overloaded(&self) -> bool {
+        self.overloaded_since.read().is_some()
+    }
+
+    /// Whether overload has lasted long enough to start shedding subscriptions
+    pub fn should_shed(&self) -> bool {
+        self.overloaded_since
+            .read()
+            .is_some_and(|since| since.elapsed() >= self.policy.read().shed_after)
+    }
+
+    /// Number of subscriptions a connection holding `subscriptions` should
+    /// shed right now
+    ///
+    /// Zero unless overload is sustained; otherwise at least one.
+    pub fn shed_count(&self, subscriptions: usize) -> usize {
+        if subscriptions == 0 || !self.should_shed() {
+            return 0;
+        }
+        let count =
+            (subscriptions as f32 * self.policy.read().shed_fraction.clamp(0.0, 1.0)).ceil();
+        (count as usize).clamp(1, subscriptions)
+    }
+
+    /// Total connections rejected by admission control
+    pub fn rejected_connections(&self) -> u64 {
+        self.rejected.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Build the CONNECTION_CLOSE reason carrying a retry-after hint
+    pub fn retry_after_reason(reason: &str, retry_after: Duration) -> String {
+        // Round up so a sub-second hint never reads as "retry now"
+        let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+        format!("{}; retry-after={}", reason, secs)
+    }
+
+    /// Extract the retry-after hint from a CONNECTION_CLOSE reason
+    pub fn parse_retry_after(reason: &[u8]) -> Option<Duration> {
+        let reason = std::str::from_utf8(reason).ok()?;
+        reason
+            .split(';')
+            .find_map(|part| part.trim().strip_prefix("retry-after="))
+            .and_then(|secs| secs.parse().ok())
+            .map(Duration::from_secs)
+    }
+
+    /// Reserve a slot for a graceful rejection, if any are left
+    fn begin_rejection(&self) -> bool {
+        use std::sync::atomic::Ordering;
+
+        self.rejected.fetch_add(1, Ordering::Relaxed);
+        let max_pending = self.policy.read().max_pending_rejections;
+        self.pending_rejections
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |pending| {
+                (pending < max_pending).then_some(pending + 1)
+            })
+            .is_ok()
+    }
+
+    fn end_rejection(&self) {
+        self.pending_rejections
+            .fetch_sub(1, std::sync::atomic::Ordering::AcqRel);
+    }
+}
+
+/// Client authentication settings for a [`QuicServer`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ServerAuthConfig {
+    /// PEM file of CA certificates; when set, clients must present a
+    /// certificate signed by one of them
+    pub client_ca_path: Option<String>,
+    /// ALPN protocols clients must offer one of (empty = no ALPN)
+    pub alpn_protocols: Vec<String>,
+}
+
+/// Settings to apply to a running server with [`QuicServer::reload`]
+///
+/// Fields left as `None` keep their current value.
+#[derive(Debug, Clone, Default)]
+pub struct ServerConfigUpdate {
+    /// Transport settings and certificate for new connections
+    pub transport_config: Option<QuicTransportConfig>,
+    /// Resource limits, applied immediately
+    pub resource_limits: Option<ResourceLimits>,
+    /// Admission policy, applied immediately
+    pub admission_policy: Option<AdmissionPolicy>,
+    /// Client authentication for new connections
+    pub auth: Option<ServerAuthConfig>,
+}
+
 /// Production QUIC server for accepting incoming connections
 #[derive(Debug)]
 pub struct QuicServer {
-    endpoint: Endpoint,
-    server_config: ServerConfig,
-    transport_config: QuicTransportConfig,
+    endpoints: Vec<Endpoint>,
+    server_config: RwLock<ServerConfig>,
+    transport_config: RwLock<QuicTransportConfig>,
+    auth_config: RwLock<ServerAuthConfig>,
     resource_manager: ResourceManager,
     active_connections: Arc<RwLock<HashMap<Uuid, Arc<RwLock<TransportConnection>>>>>,
     connection_stats: Arc<RwLock<HashMap<Uuid, ConnectionStats>>>,
+    admission: Arc<AdmissionController>,
+    workers: usize,
+}
+
+/// Handle to a server started with [`QuicServer::run`]
+#[derive(Debug)]
+pub struct QuicServerHandle {
+    server: Arc<QuicServer>,
+    /// Carries the grace period once shutdown starts
+    shutdown_tx: watch::Sender<Option<Duration>>,
+    acceptors: Vec<tokio::task::JoinHandle<()>>,
+    workers: Vec<tokio::task::JoinHandle<()>>,
+    reloader: Option<tokio::task::JoinHandle<()>>,
 }
 
 impl QuicServer {
@@ -432,17 +797,85 @@ impl QuicServer {
         let crypto = rustls::crypto::aws_lc_rs::default_provider();
         let _ = rustls::crypto::CryptoProvider::install_default(crypto);
 
+        let auth = ServerAuthConfig::default();
+        let server_config = Self::build_server_config(&transport_config, &auth).await?;
+
+        // Create one endpoint per socket shard
+        let endpoints = Self::create_endpoints(
+            addr,
+            &server_config,
+            &transport_config.socket_config,
+            transport_config.qos.as_ref(),
+        )?;
+
+        let actual_addr = endpoints[0]
+            .local_addr()
+            .map_err(|e| QuicRtcError::Transport {
+                reason: format!("Failed to get server local address: {}", e),
+            })?;
+
+        info!(
+            "QUIC server successfully bound to {} with {} socket(s)",
+            actual_addr,
+            endpoints.len()
+        );
+
+        Ok(Self {
+            endpoints,
+            server_config: RwLock::new(server_config),
+            transport_config: RwLock::new(transport_config),
+            auth_config: RwLock::new(auth),
+            resource_manager: ResourceManager::new(resource_limits),
+            active_connections: Arc::new(RwLock::new(HashMap::new())),
+            connection_stats: Arc::new(RwLock::new(HashMap::new())),
+            admission: Arc::new(AdmissionController::new(AdmissionPolicy::default())),
+            workers: std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1),
+        })
+    }
+
+    /// Build the quinn server configuration: certificate, client
+    /// authentication and transport parameters
+    async fn build_server_config(
+        transport_config: &QuicTransportConfig,
+        auth: &ServerAuthConfig,
+    ) -> Result<ServerConfig, QuicRtcError> {
         // Generate or load certificate based on configuration
         let (cert_chain, private_key) =
             Self::setup_certificate(&transport_config.certificate_config).await?;
 
         // Configure server TLS
-        let mut server_config =
-            ServerConfig::with_single_cert(cert_chain, private_key).map_err(|e| {
+        let tls_builder =
+            rustls::ServerConfig::builder_with_protocol_versions(&[&rustls::version::TLS13]);
+        let tls_builder = match &auth.client_ca_path {
+            Some(ca_path) => {
+                let verifier = Self::load_client_verifier(ca_path)?;
+                tls_builder.with_client_cert_verifier(verifier)
+            }
+            None => tls_builder.with_no_client_auth(),
+        };
+        let mut tls_config = tls_builder
+            .with_single_cert(cert_chain, private_key)
+            .map_err(|e| QuicRtcError::Transport {
+                reason: format!("Failed to configure server TLS: {}", e),
+            })?;
+        tls_config.alpn_protocols = auth
+            .alpn_protocols
+            .iter()
+            .map(|protocol| protocol.as_bytes().to_vec())
+            .collect();
+        if transport_config.enable_0rtt {
+            tls_config.max_early_data_size = u32::MAX;
+        }
+
+        let quic_crypto =
+            quinn::crypto::rustls::QuicServerConfig::try_from(tls_config).map_err(|e| {
                 QuicRtcError::Transport {
                     reason: format!("Failed to configure server TLS: {}", e),
                 }
             })?;
+        let mut server_config = ServerConfig::with_crypto(Arc::new(quic_crypto));
 
         // Apply transport configuration
         let mut quinn_transport_config = quinn::TransportConfig::default();
@@ -455,6 +888,7 @@ impl QuicServer {
         quinn_transport_config
             .max_idle_timeout(Some(transport_config.max_idle_timeout.try_into().unwrap()));
         quinn_transport_config.keep_alive_interval(Some(transport_config.keep_alive_interval));
+        apply_initial_mtu(&mut quinn_transport_config, transport_config.initial_mtu);
 
         // Configure congestion control
         match transport_config.congestion_controller {
@@ -473,51 +907,309 @@ impl QuicServer {
         }
 
         server_config.transport_config(Arc::new(quinn_transport_config));
+        Ok(server_config)
+    }
 
-        // Create endpoint
-        let endpoint =
-            Endpoint::server(server_config.clone(), addr).map_err(|e| QuicRtcError::Transport {
-                reason: format!("Failed to create QUIC server endpoint: {}", e),
-            })?;
-
-        let actual_addr = endpoint.local_addr().map_err(|e| QuicRtcError::Transport {
-            reason: format!("Failed to get server local address: {}", e),
+    /// Build a verifier accepting client certificates signed by the CAs in
+    /// the PEM file at `ca_path`
+    fn load_client_verifier(
+        ca_path: &str,
+    ) -> Result<Arc<dyn rustls::server::danger::ClientCertVerifier>, QuicRtcError> {
+        let ca_data = std::fs::read(ca_path).map_err(|e| QuicRtcError::Transport {
+            reason: format!("Failed to read client CA file {}: {}", ca_path, e),
         })?;
 
-        info!("QUIC server successfully bound to {}", actual_addr);
+        let mut roots = rustls::RootCertStore::empty();
+        for cert in rustls_pemfile::certs(&mut ca_data.as_slice()) {
+            let cert = cert.map_err(|e| QuicRtcError::Transport {
+                reason: format!("Failed to parse client CA PEM: {}", e),
+            })?;
+            roots.add(cert).map_err(|e| QuicRtcError::Transport {
+                reason: format!("Invalid client CA certificate: {}", e),
+            })?;
+        }
 
-        Ok(Self {
-            endpoint,
-            server_config,
+        rustls::server::WebPkiClientVerifier::builder(Arc::new(roots))
+            .build()
+            .map_err(|e| QuicRtcError::Transport {
+                reason: format!("Failed to build client certificate verifier: {}", e),
+            })
+    }
+
+    /// Apply new settings to the running server without dropping connections
+    ///
+    /// Resource limits and the admission policy take effect immediately,
+    /// for live connections too. Transport parameters, the certificate and
+    /// client authentication apply to connections accepted after the
+    /// reload; established connections keep what they negotiated. Socket
+    /// settings cannot change while bound and are ignored. If any part of
+    /// the update is invalid, nothing is applied.
+    pub async fn reload(&self, update: ServerConfigUpdate) -> Result<(), QuicRtcError> {
+        let ServerConfigUpdate {
             transport_config,
-            resource_manager: ResourceManager::new(resource_limits),
-            active_connections: Arc::new(RwLock::new(HashMap::new())),
-            connection_stats: Arc::new(RwLock::new(HashMap::new())),
-        })
+            resource_limits,
+            admission_policy,
+            auth,
+        } = update;
+
+        if transport_config.is_some() || auth.is_some() {
+            let transport_config =
+                transport_config.unwrap_or_else(|| self.transport_config.read().clone());
+            let auth = auth.unwrap_or_else(|| self.auth_config.read().clone());
+
+            let server_config = Self::build_server_config(&transport_config, &auth).await?;
+            for endpoint in &self.endpoints {
+                endpoint.set_server_config(Some(server_config.clone()));
+            }
+
+            if transport_config.socket_config.shard_count()
+                != self.transport_config.read().socket_config.shard_count()
+            {
+                warn!("Socket settings changed; they apply after the server is restarted");
+            }
+
+            *self.server_config.write() = server_config;
+            *self.transport_config.write() = transport_config;
+            *self.auth_config.write() = auth;
+        }
+
+        if let Some(limits) = resource_limits {
+            self.resource_manager.update_limits(limits);
+        }
+        if let Some(policy) = admission_policy {
+            self.admission.set_policy(policy);
+        }
+        self.admission.refresh(&self.resource_manager);
+
+        info!("Reloaded QUIC server configuration");
+        Ok(())
     }
 
-    /// Accept incoming QUIC connections
-    pub async fn accept_connection(
-        &mut self,
-    ) -> Result<Arc<RwLock<TransportConnection>>, QuicRtcError> {
-        debug!("Waiting for incoming QUIC connection");
+    /// Set the number of worker tasks used by [`QuicServer::run`]
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
 
-        // Check resource limits before accepting
-        self.resource_manager.check_limits()?;
+    /// Set the admission policy applied to new connections
+    pub fn with_admission_policy(self, policy: AdmissionPolicy) -> Self {
+        self.admission.set_policy(policy);
+        self
+    }
 
-        // Accept incoming connection
-        let connecting = self
-            .endpoint
-            .accept()
-            .await
-            .ok_or_else(|| QuicRtcError::Transport {
-                reason: "Server endpoint closed".to_string(),
-            })?;
+    /// Get the admission controller, e.g. for handlers that shed
+    /// subscriptions under sustained overload
+    pub fn admission(&self) -> Arc<AdmissionController> {
+        self.admission.clone()
+    }
 
-        let connection = connecting.await.map_err(|e| QuicRtcError::Transport {
-            reason: format!("Failed to establish incoming QUIC connection: {}", e),
-        })?;
+    /// Report memory, bandwidth and stream usage measured by the application
+    ///
+    /// The active connection count is tracked by the server itself and is
+    /// not taken from `usage`.
+    pub fn update_resource_usage(&self, usage: ResourceUsage) {
+        let active_connections = self.active_connections.read().len() as u32;
+        self.resource_manager.update_usage(ResourceUsage {
+            active_connections,
+            ..usage
+        });
+        self.admission.refresh(&self.resource_manager);
+    }
+
+    /// Run the accept loop, calling `handler` for every connection
+    ///
+    /// One acceptor task per socket hands incoming connections round-robin
+    /// to the worker tasks. Each worker completes the handshake and owns
+    /// the handler tasks of its connections, which are tracked until the
+    /// handler returns. Use the returned handle to shut down gracefully.
+    pub fn run<H, Fut>(self, handler: H) -> QuicServerHandle
+    where
+        H: Fn(Arc<RwLock<TransportConnection>>) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let worker_count = self.workers;
+        let server = Arc::new(self);
+        let handler = Arc::new(handler);
+        let (shutdown_tx, shutdown_rx) = watch::channel(None);
+
+        let mut worker_txs = Vec::with_capacity(worker_count);
+        let mut workers = Vec::with_capacity(worker_count);
+        for worker_id in 0..worker_count {
+            let (tx, rx) = mpsc::channel(64);
+            worker_txs.push(tx);
+            workers.push(tokio::spawn(Self::run_worker(
+                worker_id,
+                server.clone(),
+                handler.clone(),
+                rx,
+                shutdown_rx.clone(),
+            )));
+        }
+
+        let next_worker = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let acceptors = server
+            .endpoints
+            .iter()
+            .cloned()
+            .map(|endpoint| {
+                tokio::spawn(Self::run_acceptor(
+                    endpoint,
+                    server.clone(),
+                    worker_txs.clone(),
+                    next_worker.clone(),
+                    shutdown_rx.clone(),
+                ))
+            })
+            .collect();
+
+        info!(
+            "QUIC server running on {} with {} worker(s)",
+            server.local_addr(),
+            worker_count
+        );
+
+        QuicServerHandle {
+            server,
+            shutdown_tx,
+            acceptors,
+            workers,
+            reloader: None,
+        }
+    }
+
+    async fn run_acceptor(
+        endpoint: Endpoint,
+        server: Arc<QuicServer>,
+        workers: Vec<mpsc::Sender<quinn::Incoming>>,
+        next_worker: Arc<std::sync::atomic::AtomicUsize>,
+        mut shutdown_rx: watch::Receiver<Option<Duration>>,
+    ) {
+        loop {
+            let incoming = tokio::select! {
+                incoming = endpoint.accept() => incoming,
+                _ = shutdown_rx.changed() => break,
+            };
+            let Some(incoming) = incoming else {
+                break;
+            };
+
+            let decision = server.admission.evaluate(&server.resource_manager);
+            if let AdmissionDecision::Reject {
+                retry_after,
+                reason,
+            } = decision
+            {
+                warn!(
+                    "Rejecting connection from {}: {}",
+                    incoming.remote_address(),
+                    reason
+                );
+                if server.admission.begin_rejection() {
+                    tokio::spawn(Self::reject_incoming(
+                        incoming,
+                        server.admission.clone(),
+                        reason,
+                        retry_after,
+                    ));
+                } else {
+                    incoming.refuse();
+                }
+                continue;
+            }
+
+            let index =
+                next_worker.fetch_add(1, std::sync::atomic::Ordering::Relaxed) % workers.len();
+            if workers[index].send(incoming).await.is_err() {
+                break;
+            }
+        }
+
+        debug!("QUIC acceptor for {:?} stopped", endpoint.local_addr());
+    }
+
+    /// Complete the handshake only to close the connection with a
+    /// retry-after hint the client can act on
+    async fn reject_incoming(
+        incoming: quinn::Incoming,
+        admission: Arc<AdmissionController>,
+        reason: String,
+        retry_after: Duration,
+    ) {
+        let remote_addr = incoming.remote_address();
+        match tokio::time::timeout(Duration::from_secs(5), incoming).await {
+            Ok(Ok(connection)) => {
+                let reason = AdmissionController::retry_after_reason(&reason, retry_after);
+                connection.close(
+                    VarInt::from_u32(SERVER_OVERLOADED_ERROR_CODE),
+                    reason.as_bytes(),
+                );
+            }
+            Ok(Err(e)) => debug!("Rejected client {} failed handshake: {}", remote_addr, e),
+            Err(_) => debug!("Rejected client {} timed out in handshake", remote_addr),
+        }
+        admission.end_rejection();
+    }
 
+    async fn run_worker<H, Fut>(
+        worker_id: usize,
+        server: Arc<QuicServer>,
+        handler: Arc<H>,
+        mut incoming_rx: mpsc::Receiver<quinn::Incoming>,
+        mut shutdown_rx: watch::Receiver<Option<Duration>>,
+    ) where
+        H: Fn(Arc<RwLock<TransportConnection>>) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let mut connections = tokio::task::JoinSet::new();
+
+        loop {
+            tokio::select! {
+                incoming = incoming_rx.recv() => {
+                    let Some(incoming) = incoming else {
+                        break;
+                    };
+                    let server = server.clone();
+                    let handler = handler.clone();
+                    connections.spawn(async move {
+                        let connection = match incoming.await {
+                            Ok(connection) => connection,
+                            Err(e) => {
+                                warn!("Incoming QUIC handshake failed: {}", e);
+                                return;
+                            }
+                        };
+                        let (connection_id, transport_connection) =
+                            server.track_connection(connection);
+                        handler(transport_connection).await;
+                        server.untrack_connection(connection_id);
+                    });
+                }
+                Some(_) = connections.join_next(), if !connections.is_empty() => {}
+                _ = shutdown_rx.changed() => break,
+            }
+        }
+
+        // Let in-flight handlers finish within the grace period; a dropped
+        // handle means no grace at all
+        let grace_period = shutdown_rx.borrow().unwrap_or_default();
+        let drained = tokio::time::timeout(grace_period, async {
+            while connections.join_next().await.is_some() {}
+        })
+        .await;
+        if drained.is_err() {
+            warn!(
+                "Worker {} aborting {} connection handler(s) after grace period",
+                worker_id,
+                connections.len()
+            );
+            connections.shutdown().await;
+        }
+
+        debug!("QUIC server worker {} stopped", worker_id);
+    }
+
+    /// Wrap an established connection and add it to the active set
+    fn track_connection(&self, connection: Connection) -> (Uuid, Arc<RwLock<TransportConnection>>) {
         let connection_id = Uuid::new_v4();
         let remote_addr = connection.remote_address();
 
@@ -532,22 +1224,73 @@ impl QuicServer {
             connection_id,
             TransportMode::QuicNative,
             Some(NetworkPath {
-                local_addr: self.endpoint.local_addr().unwrap(),
+                local_addr: self.local_addr(),
                 remote_addr,
                 interface_name: None,
-                mtu: Some(self.transport_config.initial_mtu),
+                mtu: Some(self.transport_config.read().initial_mtu),
             }),
         );
 
         // Track the connection
         let transport_connection_arc = Arc::new(RwLock::new(transport_connection));
-        {
+        let active = {
             let mut connections = self.active_connections.write();
             connections.insert(connection_id, transport_connection_arc.clone());
-        }
+            connections.len()
+        };
+        self.record_active_connections(active);
+
+        (connection_id, transport_connection_arc)
+    }
+
+    /// Remove a connection whose handler has finished
+    fn untrack_connection(&self, connection_id: Uuid) {
+        let active = {
+            let mut connections = self.active_connections.write();
+            connections.remove(&connection_id);
+            connections.len()
+        };
+        self.connection_stats.write().remove(&connection_id);
+        self.record_active_connections(active);
+    }
+
+    fn record_active_connections(&self, active: usize) {
+        let mut usage = self.resource_manager.current_usage();
+        usage.active_connections = active as u32;
+        self.resource_manager.update_usage(usage);
+        self.admission.refresh(&self.resource_manager);
+    }
+
+    /// Accept incoming QUIC connections
+    pub async fn accept_connection(
+        &mut self,
+    ) -> Result<Arc<RwLock<TransportConnection>>, QuicRtcError> {
+        debug!("Waiting for incoming QUIC connection");
+
+        // Check resource limits before accepting
+        self.resource_manager.check_limits()?;
+
+        // Accept incoming connection from whichever shard receives one first
+        let incoming = if self.endpoints.len() == 1 {
+            self.endpoints[0].accept().await
+        } else {
+            let accepts = self
+                .endpoints
+                .iter()
+                .map(|endpoint| Box::pin(endpoint.accept()));
+            futures::future::select_all(accepts).await.0
+        };
+        let connecting = incoming.ok_or_else(|| QuicRtcError::Transport {
+            reason: "Server endpoint closed".to_string(),
+        })?;
+
+        let connection = connecting.await.map_err(|e| QuicRtcError::Transport {
+            reason: format!("Failed to establish incoming QUIC connection: {}", e),
+        })?;
 
         // Return a reference to the stored connection
-        Ok(transport_connection_arc)
+        let (_, transport_connection) = self.track_connection(connection);
+        Ok(transport_connection)
     }
 
     /// Get statistics for all active connections
@@ -557,7 +1300,109 @@ impl QuicServer {
 
     /// Get the local address the server is bound to
     pub fn local_addr(&self) -> SocketAddr {
-        self.endpoint.local_addr().unwrap()
+        self.endpoints[0].local_addr().unwrap()
+    }
+
+    /// Number of UDP sockets (endpoints) the server listens on
+    pub fn socket_count(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    /// Bind the UDP sockets described by `socket_config` and wrap each in
+    /// an endpoint, marking packets as `qos` says
+    fn create_endpoints(
+        addr: SocketAddr,
+        server_config: &ServerConfig,
+        socket_config: &SocketConfig,
+        qos: Option<&QosConfig>,
+    ) -> Result<Vec<Endpoint>, QuicRtcError> {
+        let runtime = quinn::default_runtime().ok_or_else(|| QuicRtcError::Transport {
+            reason: "No async runtime available for QUIC server endpoint".to_string(),
+        })?;
+
+        let shards = socket_config.shard_count();
+        let mut bind_addr = addr;
+        let mut endpoints = Vec::with_capacity(shards);
+
+        for _ in 0..shards {
+            let socket = Self::bind_udp_socket(bind_addr, socket_config, shards > 1)?;
+            // A port of 0 is resolved by the first bind; later shards share it
+            bind_addr = socket.local_addr().map_err(|e| QuicRtcError::Transport {
+                reason: format!("Failed to get socket local address: {}", e),
+            })?;
+
+            let endpoint = match qos {
+                Some(qos) => {
+                    let marker = DscpMarker::new(qos.clone());
+                    marker.add_track(crate::MoqTrackType::Audio);
+                    marker.add_track(crate::MoqTrackType::Video);
+                    Endpoint::new_with_abstract_socket(
+                        quinn::EndpointConfig::default(),
+                        Some(server_config.clone()),
+                        qos::marking_socket(socket, &runtime, marker)?,
+                        runtime.clone(),
+                    )
+                }
+                None => Endpoint::new(
+                    quinn::EndpointConfig::default(),
+                    Some(server_config.clone()),
+                    socket,
+                    runtime.clone(),
+                ),
+            }
+            .map_err(|e| QuicRtcError::Transport {
+                reason: format!("Failed to create QUIC server endpoint: {}", e),
+            })?;
+            endpoints.push(endpoint);
+        }
+
+        Ok(endpoints)
+    }
+
+    /// Create a non-blocking UDP socket with the configured options
+    fn bind_udp_socket(
+        addr: SocketAddr,
+        socket_config: &SocketConfig,
+        reuse_port: bool,
+    ) -> Result<std::net::UdpSocket, QuicRtcError> {
+        use socket2::{Domain, Protocol, Socket, Type};
+
+        let socket_error = |action: &str, e: std::io::Error| QuicRtcError::Transport {
+            reason: format!("Failed to {} UDP socket on {}: {}", action, addr, e),
+        };
+
+        let socket = Socket::new(Domain::for_address(addr), Type::DGRAM, Some(Protocol::UDP))
+            .map_err(|e| socket_error("create", e))?;
+
+        #[cfg(target_os = "linux")]
+        if reuse_port {
+            socket
+                .set_reuse_port(true)
+                .map_err(|e| socket_error("enable SO_REUSEPORT on", e))?;
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = reuse_port;
+
+        // Buffer sizes are hints; the kernel may clamp them
+        if let Some(size) = socket_config.recv_buffer_size {
+            if let Err(e) = socket.set_recv_buffer_size(size) {
+                warn!("Failed to set UDP receive buffer to {} bytes: {}", size, e);
+            }
+        }
+        if let Some(size) = socket_config.send_buffer_size {
+            if let Err(e) = socket.set_send_buffer_size(size) {
+                warn!("Failed to set UDP send buffer to {} bytes: {}", size, e);
+            }
+        }
+
+        socket
+            .set_nonblocking(true)
+            .map_err(|e| socket_error("configure", e))?;
+        socket
+            .bind(&addr.into())
+            .map_err(|e| socket_error("bind", e))?;
+
+        Ok(socket.into())
     }
 
     /// Setup certificate based on configuration
@@ -692,18 +1537,129 @@ impl QuicServer {
 
         for (connection_id, connection) in connections {
             debug!("Closing connection {}", connection_id);
-            let mut conn = connection.write();
-            let _ = conn.close().await;
+            let closing = connection.write().start_close();
+            let _ = closing.finish().await;
         }
 
-        // Close endpoint
-        self.endpoint.close(VarInt::from_u32(0), b"Server shutdown");
+        // Close endpoints
+        for endpoint in &self.endpoints {
+            endpoint.close(VarInt::from_u32(0), b"Server shutdown");
+        }
 
         info!("QUIC server closed successfully");
         Ok(())
     }
 }
 
+impl QuicServerHandle {
+    /// Get the local address the server is bound to
+    pub fn local_addr(&self) -> SocketAddr {
+        self.server.local_addr()
+    }
+
+    /// Number of connections whose handlers are still running
+    pub fn active_connections(&self) -> usize {
+        self.server.active_connections.read().len()
+    }
+
+    /// Get the admission controller of the running server
+    pub fn admission(&self) -> Arc<AdmissionController> {
+        self.server.admission()
+    }
+
+    /// Report application-measured resource usage to the running server
+    pub fn update_resource_usage(&self, usage: ResourceUsage) {
+        self.server.update_resource_usage(usage);
+    }
+
+    /// Apply new settings to the running server; see [`QuicServer::reload`]
+    pub async fn reload(&self, update: ServerConfigUpdate) -> Result<(), QuicRtcError> {
+        self.server.reload(update).await
+    }
+
+    /// Reload the configuration whenever the process receives SIGHUP
+    ///
+    /// `load` runs on every signal to produce the update, typically by
+    /// re-reading a config file. A failed load or reload is logged and the
+    /// current configuration kept. Calling this again replaces the loader.
+    #[cfg(unix)]
+    pub fn reload_on_sighup<F>(&mut self, load: F) -> Result<(), QuicRtcError>
+    where
+        F: Fn() -> Result<ServerConfigUpdate, QuicRtcError> + Send + Sync + 'static,
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangup = signal(SignalKind::hangup()).map_err(|e| QuicRtcError::Transport {
+            reason: format!("Failed to listen for SIGHUP: {}", e),
+        })?;
+        let mut shutdown_rx = self.shutdown_tx.subscribe();
+        let server = self.server.clone();
+
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    received = hangup.recv() => {
+                        if received.is_none() {
+                            break;
+                        }
+                    }
+                    _ = shutdown_rx.changed() => break,
+                }
+
+                info!("SIGHUP received, reloading QUIC server configuration");
+                let result = match load() {
+                    Ok(update) => server.reload(update).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    warn!(
+                        "Configuration reload failed, keeping current settings: {}",
+                        e
+                    );
+                }
+            }
+        });
+
+        if let Some(previous) = self.reloader.replace(task) {
+            previous.abort();
+        }
+        Ok(())
+    }
+
+    /// Stop accepting connections and shut the server down
+    ///
+    /// Running handlers get up to `grace_period` to return before they are
+    /// aborted; the endpoints are then closed, which closes any connection
+    /// that is still open.
+    pub async fn shutdown(self, grace_period: Duration) -> Result<(), QuicRtcError> {
+        info!(
+            "Shutting down QUIC server with {:?} grace period",
+            grace_period
+        );
+        let _ = self.shutdown_tx.send(Some(grace_period));
+
+        for task in self
+            .acceptors
+            .into_iter()
+            .chain(self.workers)
+            .chain(self.reloader)
+        {
+            task.await.map_err(|e| QuicRtcError::Transport {
+                reason: format!("QUIC server task failed: {}", e),
+            })?;
+        }
+
+        for endpoint in &self.server.endpoints {
+            endpoint.close(VarInt::from_u32(0), b"Server shutdown");
+        }
+        self.server.active_connections.write().clear();
+        self.server.connection_stats.write().clear();
+
+        info!("QUIC server shut down");
+        Ok(())
+    }
+}
+
 /// Transport modes with automatic fallback capability
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum TransportMode {
@@ -752,6 +1708,17 @@ pub struct ConnectionStats {
     pub loss_rate: f64,
     /// Connection established time
     pub established_at: Instant,
+    /// Congestion events: lost packets and congestion-experienced marks
+    /// reported by the peer
+    pub congestion_events: u64,
+    /// Packets sent, by ECN codepoint; all zero where the platform or
+    /// path does not support ECN
+    pub ecn_sent: EcnCounts,
+    /// Packets received, by ECN codepoint
+    pub ecn_received: EcnCounts,
+    /// Largest UDP payload the path currently carries, as found by path
+    /// MTU discovery; `None` outside native QUIC
+    pub path_mtu: Option<u16>,
 }
 
 /// Connection metrics for monitoring
@@ -792,6 +1759,21 @@ enum TransportInner {
     WebRtc(WebRtcTransport),
 }
 
+/// Connection whose close was started by [`TransportConnection::start_close`]
+pub(crate) struct ClosingConnection {
+    websocket: Option<WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>>,
+}
+
+impl ClosingConnection {
+    /// Wait for the close to complete
+    pub(crate) async fn finish(self) -> Result<(), QuicRtcError> {
+        if let Some(mut stream) = self.websocket {
+            let _ = stream.close(None).await;
+        }
+        Ok(())
+    }
+}
+
 /// WebSocket transport wrapper
 #[derive(Debug)]
 pub struct WebSocketTransport {
@@ -801,8 +1783,8 @@ pub struct WebSocketTransport {
     endpoint: String,
 }
 
-/// WebRTC transport wrapper 
-/// 
+/// WebRTC transport wrapper
+///
 /// **STATUS: ARCHITECTURAL PLACEHOLDER** - Framework for future WebRTC integration
 /// This exists to provide a complete fallback chain but is not yet implemented.
 /// In production, this would integrate with a WebRTC library for maximum compatibility.
@@ -813,7 +1795,7 @@ pub struct WebRtcTransport {
     endpoint: String,
     // TODO: Add fields for:
     // - RTCPeerConnection
-    // - RTCDataChannel for data transport  
+    // - RTCDataChannel for data transport
     // - ICE candidate management
     // - STUN/TURN server configuration
 }
@@ -875,6 +1857,18 @@ impl QuicStream {
         }
         Ok(())
     }
+
+    /// Reset the sending side of the stream with an application error code
+    pub fn reset(&mut self, error_code: u32) -> Result<(), QuicRtcError> {
+        if let Some(mut send_stream) = self.send.take() {
+            send_stream
+                .reset(VarInt::from_u32(error_code))
+                .map_err(|e| QuicRtcError::Transport {
+                    reason: format!("Failed to reset stream: {}", e),
+                })?;
+        }
+        Ok(())
+    }
 }
 
 /// QUIC transport connection with fallback support
@@ -894,6 +1888,12 @@ pub struct TransportConnection {
     current_path: Option<NetworkPath>,
     /// Migration event sender
     migration_tx: Option<mpsc::UnboundedSender<NetworkPath>>,
+    /// Packet metadata capture of a native QUIC connection
+    packet_capture: Option<PacketCapture>,
+    /// DSCP marking of a native QUIC connection
+    dscp_marker: Option<DscpMarker>,
+    /// ECN counts of a native QUIC connection's packets
+    ecn_counters: Option<EcnCounters>,
 }
 
 impl TransportConnection {
@@ -910,6 +1910,12 @@ impl TransportConnection {
 
         let connection_id = Uuid::new_v4();
         let metrics = Arc::new(RwLock::new(ConnectionMetrics::default()));
+        let dscp_marker = config
+            .quic_transport_config
+            .as_ref()
+            .and_then(|transport_config| transport_config.qos.clone())
+            .map(DscpMarker::new);
+        let ecn_counters = EcnCounters::default();
 
         info!("Attempting connection to {} with fallback chain", endpoint);
 
@@ -922,8 +1928,15 @@ impl TransportConnection {
 
             debug!("Trying transport mode: {:?}", transport_mode);
 
-            match Self::try_transport(endpoint, config.clone(), *transport_mode, connection_id)
-                .await
+            match Self::try_transport(
+                endpoint,
+                config.clone(),
+                *transport_mode,
+                connection_id,
+                dscp_marker.clone(),
+                ecn_counters.clone(),
+            )
+            .await
             {
                 Ok(inner) => {
                     {
@@ -948,6 +1961,15 @@ impl TransportConnection {
                             mtu: None,
                         }),
                         migration_tx: Some(migration_tx),
+                        packet_capture: config
+                            .packet_capture
+                            .clone()
+                            .filter(|_| *transport_mode == TransportMode::QuicNative),
+                        dscp_marker: dscp_marker
+                            .clone()
+                            .filter(|_| *transport_mode == TransportMode::QuicNative),
+                        ecn_counters: Some(ecn_counters.clone())
+                            .filter(|_| *transport_mode == TransportMode::QuicNative),
                     });
                 }
                 Err(e) => {
@@ -976,10 +1998,19 @@ impl TransportConnection {
         config: ConnectionConfig,
         transport_mode: TransportMode,
         connection_id: Uuid,
+        dscp_marker: Option<DscpMarker>,
+        ecn_counters: EcnCounters,
     ) -> Result<TransportInner, QuicRtcError> {
         match transport_mode {
             TransportMode::QuicNative => {
-                Self::establish_quic_native(endpoint, config, connection_id).await
+                Self::establish_quic_native(
+                    endpoint,
+                    config,
+                    connection_id,
+                    dscp_marker,
+                    ecn_counters,
+                )
+                .await
             }
             TransportMode::QuicOverWebSocket => {
                 Self::establish_quic_over_websocket(endpoint, config, connection_id).await
@@ -995,6 +2026,8 @@ impl TransportConnection {
         endpoint: SocketAddr,
         config: ConnectionConfig,
         _connection_id: Uuid,
+        dscp_marker: Option<DscpMarker>,
+        ecn_counters: EcnCounters,
     ) -> Result<TransportInner, QuicRtcError> {
         // Set up crypto provider for QUIC
         let crypto = rustls::crypto::aws_lc_rs::default_provider();
@@ -1007,21 +2040,39 @@ impl TransportConnection {
         // Configure QUIC transport parameters
         let mut transport_config = quinn::TransportConfig::default();
         transport_config.max_idle_timeout(Some(config.max_idle_timeout.try_into().unwrap()));
-        transport_config.keep_alive_interval(Some(config.keep_alive_interval));
+        let keep_alive = config.adaptive_keep_alive.clone().map(|keep_alive| {
+            (
+                keep_alive,
+                Arc::new(keepalive::ConnectionActivity::default()),
+            )
+        });
+        if keep_alive.is_some() {
+            // Only a fallback for peers that ignore datagram heartbeats
+            transport_config.keep_alive_interval(Some(config.max_idle_timeout * 3 / 4));
+        } else {
+            transport_config.keep_alive_interval(Some(config.keep_alive_interval));
+        }
 
         // Enable connection migration if requested
         if config.enable_migration {
             transport_config.allow_spin(true);
         }
+        if let Some(quic_config) = &config.quic_transport_config {
+            apply_initial_mtu(&mut transport_config, quic_config.initial_mtu);
+        }
 
         client_config.transport_config(Arc::new(transport_config));
 
-        // Create endpoint
-        let mut quic_endpoint = Endpoint::client("0.0.0.0:0".parse().unwrap()).map_err(|e| {
-            QuicRtcError::Transport {
-                reason: format!("Failed to create QUIC endpoint: {}", e),
-            }
-        })?;
+        // Create endpoint, counting ECN marks and packet activity, and
+        // marking packets and recording their metadata if configured
+        let bind_addr: SocketAddr = "0.0.0.0:0".parse().unwrap();
+        let mut quic_endpoint = Self::client_endpoint(
+            bind_addr,
+            config.packet_capture,
+            dscp_marker,
+            ecn_counters,
+            keep_alive.clone(),
+        )?;
 
         quic_endpoint.set_default_client_config(client_config);
 
@@ -1044,10 +2095,57 @@ impl TransportConnection {
                 reason: format!("QUIC connection failed: {}", e),
             })?;
 
+        if let Some((keep_alive, activity)) = keep_alive {
+            keepalive::spawn_heartbeats(
+                connection.clone(),
+                keep_alive,
+                activity,
+                config.max_idle_timeout / 2,
+            );
+        }
+
         debug!("QUIC native connection established");
         Ok(TransportInner::Quic(connection))
     }
 
+    /// Create a client endpoint whose socket marks packets with `dscp_marker`,
+    /// counts their ECN codepoints into `ecn_counters`, reports them to
+    /// `keep_alive` and records them into `capture`
+    ///
+    /// quinn enables ECN on the socket where the platform supports it.
+    fn client_endpoint(
+        bind_addr: SocketAddr,
+        capture: Option<PacketCapture>,
+        dscp_marker: Option<DscpMarker>,
+        ecn_counters: EcnCounters,
+        keep_alive: Option<(AdaptiveKeepAlive, Arc<keepalive::ConnectionActivity>)>,
+    ) -> Result<Endpoint, QuicRtcError> {
+        let transport_error = |e: std::io::Error| QuicRtcError::Transport {
+            reason: format!("Failed to create QUIC endpoint: {}", e),
+        };
+        let runtime = quinn::default_runtime().ok_or_else(|| QuicRtcError::Transport {
+            reason: "No async runtime available for QUIC endpoint".to_string(),
+        })?;
+        let socket = std::net::UdpSocket::bind(bind_addr).map_err(transport_error)?;
+        let socket = match dscp_marker {
+            Some(marker) => qos::marking_socket(socket, &runtime, marker)?,
+            None => runtime.wrap_udp_socket(socket).map_err(transport_error)?,
+        };
+        let socket = ecn::counting_socket(socket, ecn_counters);
+        let socket = match keep_alive {
+            Some((keep_alive, activity)) => {
+                keepalive::activity_socket(socket, keep_alive, activity)
+            }
+            None => socket,
+        };
+        let socket = match capture {
+            Some(capture) => capture::capturing_socket(socket, capture),
+            None => socket,
+        };
+        Endpoint::new_with_abstract_socket(quinn::EndpointConfig::default(), None, socket, runtime)
+            .map_err(transport_error)
+    }
+
     /// Establish QUIC over WebSocket connection
     async fn establish_quic_over_websocket(
         endpoint: SocketAddr,
@@ -1074,7 +2172,7 @@ impl TransportConnection {
     }
 
     /// Establish WebRTC compatibility connection
-    /// 
+    ///
     /// **STATUS: ARCHITECTURAL PLACEHOLDER** - Framework for future implementation
     async fn establish_webrtc_compat(
         _endpoint: SocketAddr,
@@ -1087,7 +2185,7 @@ impl TransportConnection {
         // 3. Handle ICE candidate exchange via signaling server
         // 4. Establish peer-to-peer connection
         // 5. Return WebRtcTransport with active data channel
-        
+
         debug!("WebRTC compatibility mode (not yet implemented)");
 
         // Simulate connection timeout for realistic testing
@@ -1247,6 +2345,7 @@ impl TransportConnection {
                                 reason: format!("Failed to open bidirectional stream: {}", e),
                             })?;
 
+                    self.record_stream_opened(send.id().index(), true);
                     Ok(QuicStream {
                         id: send.id().index(),
                         stream_type,
@@ -1263,6 +2362,7 @@ impl TransportConnection {
                                 reason: format!("Failed to open unidirectional stream: {}", e),
                             })?;
 
+                    self.record_stream_opened(send.id().index(), false);
                     Ok(QuicStream {
                         id: send.id().index(),
                         stream_type,
@@ -1293,11 +2393,22 @@ impl TransportConnection {
         }
     }
 
+    fn record_stream_opened(&self, stream_id: u64, bidirectional: bool) {
+        if let Some(capture) = &self.packet_capture {
+            capture.record(PacketEvent::StreamOpened {
+                stream_id,
+                bidirectional,
+                local: true,
+            });
+        }
+    }
+
     /// Get connection statistics
     pub fn connection_stats(&self) -> Result<ConnectionStats, QuicRtcError> {
         match &self.inner {
             TransportInner::Quic(connection) => {
                 let stats = connection.stats();
+                let ecn = self.ecn_counters.clone().unwrap_or_default();
                 Ok(ConnectionStats {
                     rtt: stats.path.rtt,
                     cwnd: stats.path.cwnd,
@@ -1305,6 +2416,10 @@ impl TransportConnection {
                     bytes_received: stats.udp_rx.bytes as u64,
                     loss_rate: 0.0, // Would need to calculate from stats
                     established_at: Instant::now(), // Would track actual establishment time
+                    congestion_events: stats.path.congestion_events,
+                    ecn_sent: ecn.sent(),
+                    ecn_received: ecn.received(),
+                    path_mtu: Some(stats.path.current_mtu),
                 })
             }
             TransportInner::WebSocket(_) => {
@@ -1316,6 +2431,10 @@ impl TransportConnection {
                     bytes_received: 0,              // Would need to track
                     loss_rate: 0.0,
                     established_at: Instant::now(),
+                    congestion_events: 0,
+                    ecn_sent: EcnCounts::default(),
+                    ecn_received: EcnCounts::default(),
+                    path_mtu: None,
                 })
             }
             TransportInner::WebRtc(_) => {
@@ -1327,6 +2446,10 @@ impl TransportConnection {
                     bytes_received: 0,
                     loss_rate: 0.0,
                     established_at: Instant::now(),
+                    congestion_events: 0,
+                    ecn_sent: EcnCounts::default(),
+                    ecn_received: EcnCounts::default(),
+                    path_mtu: None,
                 })
             }
         }
@@ -1366,22 +2489,27 @@ impl TransportConnection {
 
     /// Close the connection gracefully
     pub async fn close(&mut self) -> Result<(), QuicRtcError> {
-        match &mut self.inner {
+        self.start_close().finish().await
+    }
+
+    /// Start closing the connection
+    ///
+    /// QUIC closes straight away; a WebSocket is detached so its closing
+    /// handshake can be awaited after the caller lets go of any lock on
+    /// this connection.
+    pub(crate) fn start_close(&mut self) -> ClosingConnection {
+        let websocket = match &mut self.inner {
             TransportInner::Quic(connection) => {
                 connection.close(VarInt::from_u32(0), b"Normal closure");
-                Ok(())
-            }
-            TransportInner::WebSocket(ws) => {
-                if let Some(mut stream) = ws.stream.write().take() {
-                    let _ = stream.close(None).await;
-                }
-                Ok(())
+                None
             }
+            TransportInner::WebSocket(ws) => ws.stream.write().take(),
             TransportInner::WebRtc(_) => {
                 // Would close WebRTC connection
-                Ok(())
+                None
             }
-        }
+        };
+        ClosingConnection { websocket }
     }
 
     /// Create TransportConnection from existing quinn Connection (simplified version)
@@ -1401,6 +2529,33 @@ impl TransportConnection {
             connection_id,
             current_path,
             migration_tx: Some(migration_tx),
+            packet_capture: None,
+            dscp_marker: None,
+            ecn_counters: None,
+        }
+    }
+
+    /// Packet metadata capture, if one records this connection
+    pub fn packet_capture(&self) -> Option<&PacketCapture> {
+        self.packet_capture.as_ref()
+    }
+
+    /// DSCP marking of this connection's packets, if configured
+    pub fn dscp_marker(&self) -> Option<&DscpMarker> {
+        self.dscp_marker.as_ref()
+    }
+
+    /// ECN counts of this connection's packets, for native QUIC connections
+    /// this side established
+    pub fn ecn_counters(&self) -> Option<&EcnCounters> {
+        self.ecn_counters.as_ref()
+    }
+
+    /// Underlying quinn connection, if this is a native QUIC connection
+    pub fn quinn_connection(&self) -> Option<Connection> {
+        match &self.inner {
+            TransportInner::Quic(connection) => Some(connection.clone()),
+            _ => None,
         }
     }
 }
@@ -1424,6 +2579,11 @@ pub struct ConnectionConfig {
     pub quic_transport_config: Option<QuicTransportConfig>,
     /// Resource limits for the connection
     pub resource_limits: Option<ResourceLimits>,
+    /// Record packet metadata of native QUIC connections into this capture
+    pub packet_capture: Option<PacketCapture>,
+    /// Send radio-friendly heartbeats on native QUIC connections instead of
+    /// keep-alives every `keep_alive_interval`
+    pub adaptive_keep_alive: Option<AdaptiveKeepAlive>,
 }
 
 impl Default for ConnectionConfig {
@@ -1437,6 +2597,8 @@ impl Default for ConnectionConfig {
             preferred_transports: None,
             quic_transport_config: Some(QuicTransportConfig::mobile()),
             resource_limits: Some(ResourceLimits::mobile()),
+            packet_capture: None,
+            adaptive_keep_alive: None,
         }
     }
 }
@@ -1457,6 +2619,8 @@ impl ConnectionConfig {
             ]),
             quic_transport_config: Some(QuicTransportConfig::mobile()),
             resource_limits: Some(ResourceLimits::mobile()),
+            packet_capture: None,
+            adaptive_keep_alive: None,
         }
     }
 
@@ -1475,6 +2639,8 @@ impl ConnectionConfig {
             ]),
             quic_transport_config: Some(QuicTransportConfig::desktop()),
             resource_limits: Some(ResourceLimits::desktop()),
+            packet_capture: None,
+            adaptive_keep_alive: None,
         }
     }
 
@@ -1489,6 +2655,8 @@ impl ConnectionConfig {
             preferred_transports: Some(vec![TransportMode::QuicNative]),
             quic_transport_config: Some(QuicTransportConfig::server()),
             resource_limits: Some(ResourceLimits::server()),
+            packet_capture: None,
+            adaptive_keep_alive: None,
         }
     }
 }
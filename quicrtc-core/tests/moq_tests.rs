@@ -25,6 +25,7 @@ async fn test_moq_session_with_custom_capabilities() {
         supported_track_types: vec![MoqTrackType::Video],
         max_object_size: 512 * 1024,
         supports_caching: false,
+        ..MoqCapabilities::default()
     };
 
     let session = MoqSession::new_with_capabilities(67890, capabilities.clone());
@@ -75,6 +76,40 @@ async fn test_track_namespace_equality() {
     assert_ne!(namespace1, namespace3);
 }
 
+#[tokio::test]
+async fn test_moq_permissions() {
+    let track = |namespace: &str, track_name: &str| TrackNamespace {
+        namespace: namespace.to_string(),
+        track_name: track_name.to_string(),
+    };
+
+    let session = MoqSession::new(1);
+    assert_eq!(session.permissions(), &MoqPermissions::allow_all());
+    assert_eq!(session.permission_violations(), 0);
+
+    // Viewers can watch anything but publish nothing
+    let viewer = MoqPermissions::subscribe_only();
+    assert!(!viewer.allows_announce(&track("room.a", "bob/camera")));
+    assert!(viewer.allows_subscribe(&track("room.b", "alice/camera")));
+
+    // Publishers limited to their own tracks in one room
+    let publisher = MoqPermissions::allow_all()
+        .with_publish_filter(TrackFilter::namespace("room.a").with_track_prefix("alice/"))
+        .with_subscribe_filter(TrackFilter::namespace("room.a"));
+    assert!(publisher.allows_announce(&track("room.a", "alice/camera")));
+    assert!(!publisher.allows_announce(&track("room.a", "bob/camera")));
+    assert!(!publisher.allows_announce(&track("room.b", "alice/camera")));
+    assert!(publisher.allows_subscribe(&track("room.a", "bob/camera")));
+    assert!(!publisher.allows_subscribe(&track("room.b", "bob/camera")));
+
+    assert_eq!(MoqErrorCode::PermissionDenied.code(), 5);
+    assert_eq!(
+        MoqErrorCode::from_code(4),
+        Some(MoqErrorCode::TrackNotFound)
+    );
+    assert_eq!(MoqErrorCode::from_code(0x0503), None);
+}
+
 #[tokio::test]
 async fn test_moq_track_creation() {
     let namespace = TrackNamespace {
@@ -82,15 +117,17 @@ async fn test_moq_track_creation() {
         track_name: "alice/camera".to_string(),
     };
 
-    let track = MoqTrack {
-        namespace: namespace.clone(),
-        name: "camera".to_string(),
-        track_type: MoqTrackType::Video,
-    };
+    let track = MoqTrack::new(namespace.clone(), "camera".to_string(), MoqTrackType::Video);
 
     assert_eq!(track.namespace, namespace);
     assert_eq!(track.name, "camera");
     assert_eq!(track.track_type, MoqTrackType::Video);
+    assert_eq!(track.latency_budget, Some(Duration::from_millis(400)));
+
+    let chat = MoqTrack::new(namespace, "chat".to_string(), MoqTrackType::Data);
+    assert_eq!(chat.latency_budget, None);
+    let chat = chat.with_latency_budget(Some(Duration::from_secs(2)));
+    assert_eq!(chat.latency_budget, Some(Duration::from_secs(2)));
 }
 
 #[tokio::test]
@@ -143,6 +180,7 @@ async fn test_moq_object_creation() {
         object_status: MoqObjectStatus::Normal,
         created_at: Instant::now(),
         size: 5,
+        metadata: Default::default(),
     };
 
     assert_eq!(object.track_namespace, namespace);
@@ -213,6 +251,94 @@ async fn test_opus_frame_to_moq_object() {
     assert_eq!(moq_object.size, 3);
     assert!(matches!(moq_object.object_status, MoqObjectStatus::Normal));
     assert_eq!(moq_object.delivery_priority(), 1);
+    assert_eq!(
+        moq_object.metadata.channel_layout(),
+        Some(ChannelLayout::Stereo)
+    );
+}
+
+#[tokio::test]
+async fn test_channel_layout_negotiation() {
+    for layout in [
+        ChannelLayout::Mono,
+        ChannelLayout::Stereo,
+        ChannelLayout::Quad,
+        ChannelLayout::Surround5_1,
+    ] {
+        assert_eq!(
+            ChannelLayout::from_channel_count(layout.channel_count()),
+            Some(layout)
+        );
+    }
+    assert_eq!(ChannelLayout::from_channel_count(3), None);
+    assert_eq!(ChannelLayout::default(), ChannelLayout::Stereo);
+
+    // Negotiation settles on the smaller layout
+    assert_eq!(
+        ChannelLayout::Surround5_1.negotiate(ChannelLayout::Stereo),
+        ChannelLayout::Stereo
+    );
+    assert_eq!(
+        ChannelLayout::Mono.negotiate(ChannelLayout::Quad),
+        ChannelLayout::Mono
+    );
+}
+
+#[tokio::test]
+async fn test_object_metadata_extension_headers() {
+    let mut metadata = MoqObjectMetadata::new();
+    assert!(metadata.is_empty());
+
+    metadata.set_rotation(VideoRotation::Deg270).unwrap();
+    metadata.set_capture_timestamp_us(42_000).unwrap();
+    assert_eq!(metadata.rotation(), Some(VideoRotation::Deg270));
+    assert_eq!(metadata.capture_timestamp_us(), Some(42_000));
+    assert_eq!(metadata.channel_layout(), None);
+    assert_eq!(metadata.content_hint(), None);
+    assert_eq!(metadata.len(), 2);
+
+    metadata.set_content_hint(VideoContentHint::Text).unwrap();
+    assert_eq!(metadata.content_hint(), Some(VideoContentHint::Text));
+    metadata.remove(MoqObjectMetadata::CONTENT_HINT);
+
+    assert_eq!(metadata.audio_content_hint(), None);
+    metadata
+        .set_audio_content_hint(AudioContentHint::Music)
+        .unwrap();
+    assert_eq!(metadata.audio_content_hint(), Some(AudioContentHint::Music));
+    metadata.remove(MoqObjectMetadata::AUDIO_CONTENT_HINT);
+
+    assert_eq!(metadata.fragment(), None);
+    metadata.set_fragment(2, 5).unwrap();
+    assert_eq!(metadata.fragment(), Some((2, 5)));
+    metadata.set_fragment(5, 5).unwrap();
+    assert_eq!(metadata.fragment(), None);
+    metadata.remove(MoqObjectMetadata::FRAGMENT);
+
+    metadata.set_video_resolution(1280, 720).unwrap();
+    metadata.set_framerate(24).unwrap();
+    assert_eq!(metadata.video_resolution(), Some((1280, 720)));
+    assert_eq!(metadata.framerate(), Some(24));
+    metadata.remove(MoqObjectMetadata::VIDEO_RESOLUTION);
+    metadata.remove(MoqObjectMetadata::FRAMERATE);
+
+    // Malformed values read back as absent
+    metadata
+        .set(MoqObjectMetadata::ROTATION, vec![0, 45])
+        .unwrap();
+    assert_eq!(metadata.rotation(), None);
+
+    // The size budget covers all headers together
+    let budget = MoqObjectMetadata::MAX_ENCODED_SIZE;
+    let used = metadata.encoded_size();
+    assert!(metadata.set(0x20, vec![0; budget - used]).is_err());
+    assert!(metadata.set(0x20, vec![0; budget - used - 3]).is_ok());
+    assert_eq!(metadata.encoded_size(), budget);
+
+    // Replacing an existing header only counts its new size
+    assert!(metadata.set(0x20, vec![1; budget - used - 3]).is_ok());
+    assert!(metadata.remove(0x20).is_some());
+    assert_eq!(metadata.encoded_size(), used);
 }
 
 #[tokio::test]
@@ -247,6 +373,7 @@ async fn test_moq_object_delivery_priority_ordering() {
         object_status: MoqObjectStatus::Normal,
         created_at: Instant::now(),
         size: 1,
+        metadata: Default::default(),
     };
 
     let eog_object = MoqObject::end_of_group(namespace.clone(), "video".to_string(), 1, 2);
@@ -274,6 +401,141 @@ async fn test_moq_object_delivery_priority_ordering() {
     assert!(matches!(dequeued3.object_status, MoqObjectStatus::Normal));
 }
 
+#[tokio::test]
+async fn test_moq_object_delivery_honors_latency_budgets() {
+    let mut delivery = MoqObjectDelivery::new(MoqCacheConfig::default());
+    let audio = TrackNamespace {
+        namespace: "test.com".to_string(),
+        track_name: "test/microphone".to_string(),
+    };
+    let data = TrackNamespace {
+        namespace: "test.com".to_string(),
+        track_name: "test/chat".to_string(),
+    };
+    delivery.set_latency_budget(&audio, MoqTrackType::Audio.default_latency_budget());
+    delivery.set_latency_budget(&data, MoqTrackType::Data.default_latency_budget());
+
+    // Objects captured 200ms ago
+    let stale = |namespace: &TrackNamespace, object_id: u64| MoqObject {
+        created_at: Instant::now() - Duration::from_millis(200),
+        ..cache_test_object(namespace, object_id, 1)
+    };
+    delivery.enqueue_object(stale(&audio, 1)).unwrap();
+    delivery.enqueue_object(stale(&data, 2)).unwrap();
+    delivery
+        .enqueue_object(cache_test_object(&audio, 3, 1))
+        .unwrap();
+
+    // Audio past its 150ms budget is neither delivered nor retransmitted
+    delivery.cleanup_expired();
+    assert_eq!(delivery.delivery_stats().objects_dropped, 1);
+    assert!(delivery.get_cached_object(&audio, 1).is_none());
+    assert!(delivery.get_cached_object(&audio, 3).is_some());
+
+    // Data is delivered however late
+    let mut delivered: Vec<u64> = std::iter::from_fn(|| delivery.dequeue_object())
+        .map(|object| object.object_id)
+        .collect();
+    delivered.sort();
+    assert_eq!(delivered, vec![2, 3]);
+}
+
+#[tokio::test]
+async fn test_moq_object_delivery_send_buffer_drops_oldest() {
+    let watermarks = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut delivery =
+        MoqObjectDelivery::new(MoqCacheConfig::default()).with_send_buffer(MoqSendBufferConfig {
+            max_objects_per_track: 4,
+            high_water_mark: 0.75,
+            low_water_mark: 0.25,
+        });
+    let recorded = watermarks.clone();
+    delivery.set_watermark_callback(std::sync::Arc::new(move |_, watermark| {
+        recorded.lock().unwrap().push(watermark);
+    }));
+    let namespace = TrackNamespace {
+        namespace: "test.com".to_string(),
+        track_name: "test/video".to_string(),
+    };
+    let mut keyframe = cache_test_object(&namespace, 0, 2);
+    keyframe.metadata.set_video_resolution(1280, 720).unwrap();
+
+    delivery.enqueue_object(keyframe).unwrap();
+    for object_id in 1..=3 {
+        delivery
+            .enqueue_object(cache_test_object(&namespace, object_id, 2))
+            .unwrap();
+    }
+    assert_eq!(*watermarks.lock().unwrap(), vec![SendBufferWatermark::High]);
+
+    // A stalled track drops its oldest delta frame, keeping the keyframe
+    delivery
+        .enqueue_object(cache_test_object(&namespace, 4, 2))
+        .unwrap();
+    assert_eq!(delivery.track_queue_depth(&namespace), 4);
+    assert_eq!(delivery.delivery_stats().objects_dropped, 1);
+
+    let mut delivered: Vec<u64> = std::iter::from_fn(|| delivery.dequeue_object())
+        .map(|object| object.object_id)
+        .collect();
+    delivered.sort();
+    assert_eq!(delivered, vec![0, 2, 3, 4]);
+    assert_eq!(
+        *watermarks.lock().unwrap(),
+        vec![SendBufferWatermark::High, SendBufferWatermark::Low]
+    );
+}
+
+#[tokio::test]
+async fn test_moq_object_delivery_signals_sustained_backpressure() {
+    let backpressure = BackpressureSignal::new(BackpressureConfig {
+        window: Duration::from_secs(10),
+        min_incidents: 3,
+        cooldown: Duration::from_secs(10),
+    });
+    let mut signals = backpressure.subscribe();
+    let mut delivery = MoqObjectDelivery::new(MoqCacheConfig::default())
+        .with_send_buffer(MoqSendBufferConfig {
+            max_objects_per_track: 2,
+            high_water_mark: 1.0,
+            low_water_mark: 0.0,
+        })
+        .with_backpressure(backpressure.clone());
+    let namespace = TrackNamespace {
+        namespace: "test.com".to_string(),
+        track_name: "test/video".to_string(),
+    };
+
+    // Reaching the high-water mark and the first overflow are not sustained yet
+    for object_id in 0..3 {
+        delivery
+            .enqueue_object(cache_test_object(&namespace, object_id, 2))
+            .unwrap();
+    }
+    assert!(signals.try_recv().is_err());
+
+    delivery
+        .enqueue_object(cache_test_object(&namespace, 3, 2))
+        .unwrap();
+    assert_eq!(
+        signals.try_recv().unwrap(),
+        Backpressure {
+            reason: BackpressureReason::SendQueueOverflow,
+            track_namespace: Some(namespace.clone()),
+            incidents: 3,
+        }
+    );
+
+    // Further overflows within the cooldown don't signal again
+    for object_id in 4..8 {
+        delivery
+            .enqueue_object(cache_test_object(&namespace, object_id, 2))
+            .unwrap();
+    }
+    assert!(signals.try_recv().is_err());
+    assert_eq!(backpressure.signals_sent(), 1);
+}
+
 #[tokio::test]
 async fn test_moq_object_cache_basic_operations() {
     let config = MoqCacheConfig::default();
@@ -294,6 +556,7 @@ async fn test_moq_object_cache_basic_operations() {
         object_status: MoqObjectStatus::Normal,
         created_at: Instant::now(),
         size: 5,
+        metadata: Default::default(),
     };
 
     // Store object
@@ -314,6 +577,268 @@ async fn test_moq_object_cache_basic_operations() {
     assert_eq!(cache.stats().cache_misses, 1);
 }
 
+fn cache_test_object(namespace: &TrackNamespace, object_id: u64, priority: u8) -> MoqObject {
+    MoqObject {
+        track_namespace: namespace.clone(),
+        track_name: "video".to_string(),
+        group_id: 1,
+        object_id,
+        publisher_priority: priority,
+        payload: vec![0; 10],
+        object_status: MoqObjectStatus::Normal,
+        created_at: Instant::now(),
+        size: 10,
+        metadata: Default::default(),
+    }
+}
+
+#[tokio::test]
+async fn test_moq_object_cache_priority_eviction_pins_keyframes() {
+    let mut cache = MoqObjectCache::new(MoqCacheConfig {
+        max_size_bytes: 100,
+        eviction_policy: MoqEvictionPolicy::Priority,
+        ..Default::default()
+    });
+    let namespace = TrackNamespace {
+        namespace: "test.com".to_string(),
+        track_name: "test/video".to_string(),
+    };
+
+    // Keyframe with the lowest priority of all, pinned regardless
+    let mut keyframe = cache_test_object(&namespace, 0, 4);
+    keyframe.metadata.set_video_resolution(1280, 720).unwrap();
+    cache.store_object(keyframe).unwrap();
+    assert_eq!(cache.stats().pinned_keyframes, 1);
+
+    // Odd objects are high priority, even ones low priority
+    for object_id in 1..=8 {
+        let priority = if object_id % 2 == 0 { 3 } else { 1 };
+        cache
+            .store_object(cache_test_object(&namespace, object_id, priority))
+            .unwrap();
+    }
+    assert_eq!(cache.stats().objects_evicted, 0);
+    assert_eq!(cache.stats().current_size_bytes, 90);
+
+    // Crossing the 90% high-water mark evicts low-priority objects down to 50%
+    cache
+        .store_object(cache_test_object(&namespace, 9, 1))
+        .unwrap();
+    assert_eq!(cache.stats().objects_evicted, 4);
+    assert_eq!(cache.stats().current_size_bytes, 60);
+    assert!(cache.get_object(&namespace, 0).is_some());
+    for object_id in [2, 4, 6, 8] {
+        assert!(cache.get_object(&namespace, object_id).is_none());
+    }
+    for object_id in [1, 3, 5, 7, 9] {
+        assert!(cache.get_object(&namespace, object_id).is_some());
+    }
+}
+
+#[tokio::test]
+async fn test_moq_object_cache_lfu_eviction() {
+    let mut cache = MoqObjectCache::new(MoqCacheConfig {
+        max_objects_per_track: 4,
+        high_water_mark: 1.0,
+        eviction_policy: MoqEvictionPolicy::Lfu,
+        ..Default::default()
+    });
+    let namespace = TrackNamespace {
+        namespace: "test.com".to_string(),
+        track_name: "test/video".to_string(),
+    };
+
+    for object_id in 0..4 {
+        cache
+            .store_object(cache_test_object(&namespace, object_id, 1))
+            .unwrap();
+    }
+    // Objects 0 and 1 are popular, 2 and 3 were never read
+    for _ in 0..3 {
+        cache.get_object(&namespace, 0).unwrap();
+        cache.get_object(&namespace, 1).unwrap();
+    }
+
+    // A full track evicts its least frequently used objects
+    cache
+        .store_object(cache_test_object(&namespace, 4, 1))
+        .unwrap();
+    assert_eq!(cache.stats().objects_evicted, 2);
+    assert!(cache.get_object(&namespace, 2).is_none());
+    assert!(cache.get_object(&namespace, 3).is_none());
+    assert!(cache.get_object(&namespace, 0).is_some());
+    assert!(cache.get_object(&namespace, 4).is_some());
+}
+
+#[tokio::test]
+async fn test_moq_cache_budget_shares_memory_across_caches() {
+    let budget = MoqCacheBudget::new(100);
+    let mut busy = MoqObjectCache::new(MoqCacheConfig::default()).with_budget(budget.clone());
+    let mut quiet = MoqObjectCache::new(MoqCacheConfig::default()).with_budget(budget.clone());
+    let busy_track = TrackNamespace {
+        namespace: "test.com".to_string(),
+        track_name: "busy/video".to_string(),
+    };
+    let quiet_track = TrackNamespace {
+        namespace: "test.com".to_string(),
+        track_name: "quiet/video".to_string(),
+    };
+
+    // A lone track may use the whole budget, evicting its own objects past it
+    for object_id in 0..12 {
+        busy.store_object(cache_test_object(&busy_track, object_id, 1))
+            .unwrap();
+    }
+    assert_eq!(budget.used_bytes(), 100);
+    assert_eq!(busy.stats().budget_evictions, 2);
+
+    // A second track under its fair share is never starved
+    for object_id in 0..3 {
+        quiet
+            .store_object(cache_test_object(&quiet_track, object_id, 1))
+            .unwrap();
+    }
+    assert_eq!(budget.used_bytes(), 130);
+    assert_eq!(budget.fair_share_bytes(), 50);
+
+    // The busy cache gives back its overshoot on its next store
+    busy.store_object(cache_test_object(&busy_track, 12, 1))
+        .unwrap();
+    assert_eq!(budget.track_bytes(&busy_track), 70);
+    assert_eq!(budget.used_bytes(), 100);
+
+    // Dropping a cache returns its bytes to the budget
+    drop(busy);
+    assert_eq!(budget.used_bytes(), 30);
+}
+
+#[tokio::test]
+async fn test_moq_object_cache_spills_to_disk() {
+    let directory = std::env::temp_dir().join(format!("quicrtc-spill-test-{}", std::process::id()));
+    let spill = MoqSpillCache::open(MoqSpillConfig {
+        directory: directory.clone(),
+        prefetch_objects: 1,
+        ..Default::default()
+    })
+    .unwrap();
+    let mut cache = MoqObjectCache::new(MoqCacheConfig {
+        max_objects_per_track: 4,
+        high_water_mark: 1.0,
+        eviction_policy: MoqEvictionPolicy::Priority,
+        ..Default::default()
+    })
+    .with_spill(spill);
+    let namespace = TrackNamespace {
+        namespace: "test.com".to_string(),
+        track_name: "test/video".to_string(),
+    };
+
+    // Earlier objects have lower priority and are evicted to disk first
+    for object_id in 0..5 {
+        cache
+            .store_object(cache_test_object(
+                &namespace,
+                object_id,
+                10 - object_id as u8,
+            ))
+            .unwrap();
+    }
+    assert_eq!(cache.stats().spilled_objects, 2);
+    let spill = cache.spill().unwrap();
+    assert!(spill.contains(&namespace, 0));
+    assert!(spill.contains(&namespace, 1));
+
+    // A disk hit counts as a cache hit and prefetches the next object
+    let object = cache.get_object(&namespace, 0).unwrap();
+    assert_eq!(object.object_id, 0);
+    assert_eq!(object.track_namespace, namespace);
+    assert_eq!(object.payload, vec![0; 10]);
+    assert_eq!(cache.stats().cache_hits, 1);
+    assert_eq!(cache.stats().disk_hits, 1);
+    assert_eq!(cache.stats().cache_misses, 0);
+
+    for _ in 0..100 {
+        if cache.spill().unwrap().stats().prefetched_objects == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(cache.get_object(&namespace, 1).is_some());
+    let spill_stats = cache.spill().unwrap().stats();
+    assert_eq!(spill_stats.prefetch_hits, 1);
+    assert_eq!(spill_stats.disk_hits, 2);
+    assert_eq!(cache.stats().disk_hits, 2);
+
+    // Misses on both tiers count once
+    assert!(cache.get_object(&namespace, 99).is_none());
+    assert_eq!(cache.stats().cache_misses, 1);
+
+    // Segment files go away with the cache
+    drop(cache);
+    assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 0);
+    std::fs::remove_dir(&directory).unwrap();
+}
+
+#[tokio::test]
+async fn test_moq_spill_segments_sealed_at_rest() {
+    let directory =
+        std::env::temp_dir().join(format!("quicrtc-spill-sealed-test-{}", std::process::id()));
+    let key = MoqSpillKey::new([7; 32]);
+    let mut spill = MoqSpillCache::open(MoqSpillConfig {
+        directory: directory.clone(),
+        prefetch_objects: 0,
+        encryption_key: Some(key.clone()),
+        keep_segments: true,
+        ..Default::default()
+    })
+    .unwrap();
+    let namespace = TrackNamespace {
+        namespace: "room.standup".to_string(),
+        track_name: "alice/camera".to_string(),
+    };
+    for object_id in 0..3 {
+        let mut object = cache_test_object(&namespace, object_id, 1);
+        object.payload = b"confidential call media".to_vec();
+        object.size = object.payload.len();
+        assert!(spill.store(&object));
+    }
+
+    // Sealing is invisible to the cache itself
+    let object = spill.take(&namespace, 0).unwrap();
+    assert_eq!(object.track_namespace, namespace);
+    assert_eq!(object.payload, b"confidential call media");
+
+    // Kept segments hold no plaintext and read back only with the key
+    drop(spill);
+    let segments: Vec<_> = std::fs::read_dir(&directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(segments.len(), 1);
+    let contents = std::fs::read(&segments[0]).unwrap();
+    assert!(!contents
+        .windows(b"confidential".len())
+        .any(|window| window == b"confidential"));
+
+    let reader = MoqSpillSegmentReader::open(&segments[0], Some(&key)).unwrap();
+    assert!(reader.is_sealed());
+    let objects: Vec<_> = reader.map(Result::unwrap).collect();
+    assert_eq!(objects.len(), 3);
+    for (object_id, object) in objects.iter().enumerate() {
+        assert_eq!(object.object_id, object_id as u64);
+        assert_eq!(object.track_namespace, namespace);
+        assert_eq!(object.track_name, "video");
+        assert_eq!(object.payload, b"confidential call media");
+    }
+
+    assert!(MoqSpillSegmentReader::open(&segments[0], None).is_err());
+    let wrong_key = MoqSpillKey::from_hex(&"ab".repeat(32)).unwrap();
+    let mut reader = MoqSpillSegmentReader::open(&segments[0], Some(&wrong_key)).unwrap();
+    assert!(reader.next().unwrap().is_err());
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
 #[tokio::test]
 async fn test_media_frame_structures() {
     // Test H264Frame
@@ -25,6 +25,8 @@ fn test_config() -> ConnectionConfig {
         preferred_transports: None,
         quic_transport_config: Default::default(),
         resource_limits: Default::default(),
+        packet_capture: None,
+        adaptive_keep_alive: None,
     }
 }
 
@@ -356,6 +358,8 @@ async fn test_connection_config_validation() {
         preferred_transports: None,
         quic_transport_config: Default::default(),
         resource_limits: Default::default(),
+        packet_capture: None,
+        adaptive_keep_alive: None,
     };
 
     assert_eq!(min_config.timeout, Duration::from_millis(1));
@@ -376,6 +380,8 @@ async fn test_connection_config_validation() {
         ]),
         quic_transport_config: Default::default(),
         resource_limits: Default::default(),
+        packet_capture: None,
+        adaptive_keep_alive: None,
     };
 
     assert_eq!(max_config.timeout, Duration::from_secs(60));
@@ -496,11 +502,11 @@ fn test_track_namespace() -> TrackNamespace {
 
 /// Helper function to create a test MoQ track
 fn test_moq_track() -> MoqTrack {
-    MoqTrack {
-        namespace: test_track_namespace(),
-        name: "camera".to_string(),
-        track_type: MoqTrackType::Video,
-    }
+    MoqTrack::new(
+        test_track_namespace(),
+        "camera".to_string(),
+        MoqTrackType::Video,
+    )
 }
 
 /// Helper function to create a test MoQ object
@@ -515,6 +521,7 @@ fn test_moq_object() -> MoqObject {
         object_status: MoqObjectStatus::Normal,
         created_at: std::time::Instant::now(),
         size: 5,
+        metadata: Default::default(),
     }
 }
 
@@ -609,6 +616,7 @@ async fn test_moq_object_priority_ordering() {
         object_status: MoqObjectStatus::Normal,
         created_at: std::time::Instant::now(),
         size: 1,
+        metadata: Default::default(),
     };
 
     let end_of_group_object = MoqObject {
@@ -621,6 +629,7 @@ async fn test_moq_object_priority_ordering() {
         object_status: MoqObjectStatus::EndOfGroup,
         created_at: std::time::Instant::now(),
         size: 0,
+        metadata: Default::default(),
     };
 
     let end_of_track_object = MoqObject {
@@ -633,6 +642,7 @@ async fn test_moq_object_priority_ordering() {
         object_status: MoqObjectStatus::EndOfTrack,
         created_at: std::time::Instant::now(),
         size: 0,
+        metadata: Default::default(),
     };
 
     // Test delivery priority ordering
@@ -688,13 +698,20 @@ async fn test_moq_transport_event_types() {
         _ => panic!("Wrong event type"),
     }
 
+    let object = test_moq_object();
     let object_event = MoqTransportEvent::ObjectReceived {
-        object: test_moq_object(),
+        track_namespace: object.track_namespace,
+        group_id: object.group_id,
+        object_id: object.object_id,
     };
     match object_event {
-        MoqTransportEvent::ObjectReceived { object } => {
-            assert_eq!(object.group_id, 12345);
-            assert_eq!(object.object_id, 67890);
+        MoqTransportEvent::ObjectReceived {
+            group_id,
+            object_id,
+            ..
+        } => {
+            assert_eq!(group_id, 12345);
+            assert_eq!(object_id, 67890);
         }
         _ => panic!("Wrong event type"),
     }
@@ -766,23 +783,23 @@ async fn test_track_namespace_validation() {
 async fn test_moq_track_types() {
     // Test different MoQ track types
 
-    let video_track = MoqTrack {
-        namespace: test_track_namespace(),
-        name: "camera".to_string(),
-        track_type: MoqTrackType::Video,
-    };
+    let video_track = MoqTrack::new(
+        test_track_namespace(),
+        "camera".to_string(),
+        MoqTrackType::Video,
+    );
 
-    let audio_track = MoqTrack {
-        namespace: test_track_namespace(),
-        name: "microphone".to_string(),
-        track_type: MoqTrackType::Audio,
-    };
+    let audio_track = MoqTrack::new(
+        test_track_namespace(),
+        "microphone".to_string(),
+        MoqTrackType::Audio,
+    );
 
-    let data_track = MoqTrack {
-        namespace: test_track_namespace(),
-        name: "chat".to_string(),
-        track_type: MoqTrackType::Data,
-    };
+    let data_track = MoqTrack::new(
+        test_track_namespace(),
+        "chat".to_string(),
+        MoqTrackType::Data,
+    );
 
     assert_eq!(video_track.track_type, MoqTrackType::Video);
     assert_eq!(audio_track.track_type, MoqTrackType::Audio);
@@ -1004,6 +1021,8 @@ async fn test_moq_transport_configuration_scenarios() {
         ]),
         quic_transport_config: Default::default(),
         resource_limits: Default::default(),
+        packet_capture: None,
+        adaptive_keep_alive: None,
     };
 
     let result = MoqOverQuicTransport::new(endpoint, mobile_config, 456).await;
@@ -1019,6 +1038,8 @@ async fn test_moq_transport_configuration_scenarios() {
         preferred_transports: Some(vec![TransportMode::QuicNative]),
         quic_transport_config: Default::default(),
         resource_limits: Default::default(),
+        packet_capture: None,
+        adaptive_keep_alive: None,
     };
 
     let result2 = MoqOverQuicTransport::new(endpoint, desktop_config, 789).await;
@@ -1044,32 +1065,32 @@ async fn test_moq_integration_realistic_scenarios() {
     // Test realistic MoQ integration scenarios
 
     // Scenario 1: Video conferencing with multiple tracks
-    let video_track = MoqTrack {
-        namespace: TrackNamespace {
+    let video_track = MoqTrack::new(
+        TrackNamespace {
             namespace: "conference.example.com".to_string(),
             track_name: "alice/camera".to_string(),
         },
-        name: "camera".to_string(),
-        track_type: MoqTrackType::Video,
-    };
+        "camera".to_string(),
+        MoqTrackType::Video,
+    );
 
-    let audio_track = MoqTrack {
-        namespace: TrackNamespace {
+    let audio_track = MoqTrack::new(
+        TrackNamespace {
             namespace: "conference.example.com".to_string(),
             track_name: "alice/microphone".to_string(),
         },
-        name: "microphone".to_string(),
-        track_type: MoqTrackType::Audio,
-    };
+        "microphone".to_string(),
+        MoqTrackType::Audio,
+    );
 
-    let screen_track = MoqTrack {
-        namespace: TrackNamespace {
+    let screen_track = MoqTrack::new(
+        TrackNamespace {
             namespace: "conference.example.com".to_string(),
             track_name: "alice/screen".to_string(),
         },
-        name: "screen".to_string(),
-        track_type: MoqTrackType::Video,
-    };
+        "screen".to_string(),
+        MoqTrackType::Video,
+    );
 
     // Validate track configurations
     assert_eq!(video_track.track_type, MoqTrackType::Video);
@@ -1096,45 +1117,45 @@ async fn test_moq_integration_realistic_scenarios() {
     );
 
     // Scenario 2: Live streaming with different quality levels
-    let hd_track = MoqTrack {
-        namespace: TrackNamespace {
+    let hd_track = MoqTrack::new(
+        TrackNamespace {
             namespace: "stream.example.com".to_string(),
             track_name: "streamer/video_hd".to_string(),
         },
-        name: "video_hd".to_string(),
-        track_type: MoqTrackType::Video,
-    };
+        "video_hd".to_string(),
+        MoqTrackType::Video,
+    );
 
-    let sd_track = MoqTrack {
-        namespace: TrackNamespace {
+    let sd_track = MoqTrack::new(
+        TrackNamespace {
             namespace: "stream.example.com".to_string(),
             track_name: "streamer/video_sd".to_string(),
         },
-        name: "video_sd".to_string(),
-        track_type: MoqTrackType::Video,
-    };
+        "video_sd".to_string(),
+        MoqTrackType::Video,
+    );
 
     assert_eq!(hd_track.namespace.namespace, sd_track.namespace.namespace);
     assert_ne!(hd_track.namespace.track_name, sd_track.namespace.track_name);
 
     // Scenario 3: Gaming with low-latency requirements
-    let game_video_track = MoqTrack {
-        namespace: TrackNamespace {
+    let game_video_track = MoqTrack::new(
+        TrackNamespace {
             namespace: "game.example.com".to_string(),
             track_name: "player1/gameplay".to_string(),
         },
-        name: "gameplay".to_string(),
-        track_type: MoqTrackType::Video,
-    };
+        "gameplay".to_string(),
+        MoqTrackType::Video,
+    );
 
-    let game_data_track = MoqTrack {
-        namespace: TrackNamespace {
+    let game_data_track = MoqTrack::new(
+        TrackNamespace {
             namespace: "game.example.com".to_string(),
             track_name: "player1/gamestate".to_string(),
         },
-        name: "gamestate".to_string(),
-        track_type: MoqTrackType::Data,
-    };
+        "gamestate".to_string(),
+        MoqTrackType::Data,
+    );
 
     assert_eq!(game_video_track.track_type, MoqTrackType::Video);
     assert_eq!(game_data_track.track_type, MoqTrackType::Data);
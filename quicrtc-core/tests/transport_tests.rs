@@ -275,6 +275,8 @@ async fn test_connection_config_scenarios() {
         ]),
         quic_transport_config: Default::default(),
         resource_limits: Default::default(),
+        packet_capture: None,
+        adaptive_keep_alive: None,
     };
 
     assert!(mobile_config.enable_migration);
@@ -319,3 +321,410 @@ async fn test_connection_metrics_comprehensive() {
     assert_eq!(metrics.migration_events, 2);
     assert!(metrics.last_attempt.is_some());
 }
+
+#[tokio::test]
+async fn test_quic_server_socket_sharding() {
+    use quicrtc_core::transport::{
+        QuicServer, QuicTransportConfig, ResourceLimits, SocketBackend, SocketConfig,
+    };
+
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0);
+
+    let standard = QuicServer::bind(
+        addr,
+        QuicTransportConfig::desktop(),
+        ResourceLimits::server(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(standard.socket_count(), 1);
+
+    let sharded_config = QuicTransportConfig {
+        socket_config: SocketConfig {
+            backend: SocketBackend::ReusePort { shards: 3 },
+            recv_buffer_size: Some(1024 * 1024),
+            send_buffer_size: Some(1024 * 1024),
+        },
+        ..QuicTransportConfig::server()
+    };
+    let expected_shards = sharded_config.socket_config.shard_count();
+    let sharded = QuicServer::bind(addr, sharded_config, ResourceLimits::server())
+        .await
+        .unwrap();
+    assert_eq!(sharded.socket_count(), expected_shards);
+    assert_ne!(sharded.local_addr().port(), 0);
+
+    if cfg!(target_os = "linux") {
+        assert_eq!(expected_shards, 3);
+    }
+}
+
+#[tokio::test]
+async fn test_quic_server_run_and_shutdown() {
+    use quicrtc_core::transport::{QuicServer, QuicTransportConfig, ResourceLimits};
+
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0);
+    let server = QuicServer::bind(
+        addr,
+        QuicTransportConfig::server(),
+        ResourceLimits::server(),
+    )
+    .await
+    .unwrap()
+    .with_workers(4);
+
+    let handle = server.run(|_connection| async {});
+    assert_ne!(handle.local_addr().port(), 0);
+    assert_eq!(handle.active_connections(), 0);
+
+    // Idle workers and acceptors stop promptly
+    tokio::time::timeout(
+        Duration::from_secs(5),
+        handle.shutdown(Duration::from_secs(1)),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_quic_server_admission_control() {
+    use quicrtc_core::transport::{
+        AdmissionController, AdmissionPolicy, QuicServer, QuicTransportConfig, ResourceLimits,
+        ResourceUsage,
+    };
+
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0);
+    let server = QuicServer::bind(
+        addr,
+        QuicTransportConfig::server(),
+        ResourceLimits::server(),
+    )
+    .await
+    .unwrap()
+    .with_admission_policy(AdmissionPolicy {
+        shed_after: Duration::ZERO,
+        ..AdmissionPolicy::default()
+    });
+    let admission = server.admission();
+    assert!(!admission.is_overloaded());
+    assert_eq!(admission.shed_count(8), 0);
+
+    // 95% of the 1000 MB memory limit is over the 90% threshold
+    server.update_resource_usage(ResourceUsage {
+        memory_mb: 950,
+        ..ResourceUsage::default()
+    });
+    assert!(admission.is_overloaded());
+    assert!(admission.should_shed());
+    assert_eq!(admission.shed_count(8), 2);
+    assert_eq!(admission.shed_count(1), 1);
+
+    server.update_resource_usage(ResourceUsage::default());
+    assert!(!admission.is_overloaded());
+    assert_eq!(admission.shed_count(8), 0);
+
+    let reason = AdmissionController::retry_after_reason("overloaded", Duration::from_millis(2500));
+    assert_eq!(reason, "overloaded; retry-after=3");
+    assert_eq!(
+        AdmissionController::parse_retry_after(reason.as_bytes()),
+        Some(Duration::from_secs(3))
+    );
+    assert_eq!(AdmissionController::parse_retry_after(b"closed"), None);
+}
+
+#[tokio::test]
+async fn test_quic_server_hot_reload() {
+    use quicrtc_core::transport::{
+        QuicServer, QuicTransportConfig, ResourceLimits, ResourceUsage, ServerAuthConfig,
+        ServerConfigUpdate,
+    };
+
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0);
+    let server = QuicServer::bind(
+        addr,
+        QuicTransportConfig::server(),
+        ResourceLimits::server(),
+    )
+    .await
+    .unwrap()
+    .with_workers(1);
+    let handle = server.run(|_connection| async {});
+    let admission = handle.admission();
+
+    handle.update_resource_usage(ResourceUsage {
+        memory_mb: 190,
+        ..ResourceUsage::default()
+    });
+    assert!(!admission.is_overloaded());
+
+    // Tighter limits apply immediately
+    handle
+        .reload(ServerConfigUpdate {
+            resource_limits: Some(ResourceLimits::desktop()),
+            ..ServerConfigUpdate::default()
+        })
+        .await
+        .unwrap();
+    assert!(admission.is_overloaded());
+
+    // New transport and auth settings are accepted for new connections
+    handle
+        .reload(ServerConfigUpdate {
+            transport_config: Some(QuicTransportConfig::desktop()),
+            auth: Some(ServerAuthConfig {
+                client_ca_path: None,
+                alpn_protocols: vec!["moq-00".to_string()],
+            }),
+            ..ServerConfigUpdate::default()
+        })
+        .await
+        .unwrap();
+
+    // A broken update is rejected as a whole
+    let result = handle
+        .reload(ServerConfigUpdate {
+            resource_limits: Some(ResourceLimits::server()),
+            auth: Some(ServerAuthConfig {
+                client_ca_path: Some("/nonexistent/ca.pem".to_string()),
+                alpn_protocols: Vec::new(),
+            }),
+            ..ServerConfigUpdate::default()
+        })
+        .await;
+    assert!(result.is_err());
+    assert!(admission.is_overloaded());
+
+    handle.shutdown(Duration::from_secs(1)).await.unwrap();
+}
+
+#[test]
+fn test_packet_capture_round_trip() {
+    let path = std::env::temp_dir().join(format!("quicrtc-capture-{}.qrpm", uuid::Uuid::new_v4()));
+    let events = [
+        PacketEvent::Sent {
+            bytes: 1200,
+            segments: 1,
+            ecn: Some(EcnMark::Ect0),
+        },
+        PacketEvent::Received {
+            bytes: 2400,
+            segments: 2,
+            ecn: Some(EcnMark::Ce),
+        },
+        PacketEvent::StreamOpened {
+            stream_id: 7,
+            bidirectional: false,
+            local: true,
+        },
+        PacketEvent::Loss {
+            lost_packets: 3,
+            congestion_events: 1,
+            rtt: Duration::from_millis(42),
+        },
+    ];
+
+    let capture = PacketCapture::create(PacketCaptureConfig {
+        path: path.clone(),
+        // Header plus room for the four records above
+        max_bytes: 16 + 4 * 24,
+    })
+    .unwrap();
+    for event in events {
+        capture.record(event);
+    }
+    // Past the size limit records are counted, not written
+    capture.record(events[0]);
+    assert_eq!(capture.stats().records, 4);
+    assert_eq!(capture.stats().dropped_records, 1);
+    drop(capture);
+
+    let read: Vec<PacketEvent> = PacketCaptureReader::open(&path)
+        .unwrap()
+        .map(|record| record.unwrap().event)
+        .collect();
+    assert_eq!(read, events);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_packet_capture_records_quic_handshake() {
+    let path = std::env::temp_dir().join(format!("quicrtc-capture-{}.qrpm", uuid::Uuid::new_v4()));
+    let capture = PacketCapture::create(PacketCaptureConfig::new(&path)).unwrap();
+
+    // Nothing listens there, so only our Initial packets go out
+    let unused_port = std::net::UdpSocket::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let config = ConnectionConfig {
+        timeout: Duration::from_millis(200),
+        packet_capture: Some(capture.clone()),
+        ..ConnectionConfig::default()
+    };
+    let result = TransportConnection::establish_with_fallback(unused_port, config).await;
+    assert!(result.is_err());
+    capture.flush();
+
+    let sent: Vec<u32> = PacketCaptureReader::open(&path)
+        .unwrap()
+        .filter_map(|record| match record.unwrap().event {
+            PacketEvent::Sent { bytes, .. } => Some(bytes),
+            _ => None,
+        })
+        .collect();
+    assert!(!sent.is_empty());
+    // QUIC pads client Initial packets to at least 1200 bytes
+    assert!(sent[0] >= 1200);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_dscp_marker_follows_published_tracks() {
+    assert_eq!(Dscp::new(46), Some(Dscp::EF));
+    assert_eq!(Dscp::new(64), None);
+    // DSCP sits above the two ECN bits
+    assert_eq!(Dscp::EF.tos(0b10), 0xba);
+    assert_eq!(Dscp::AF41.tos(0), 0x88);
+
+    let qos = QosConfig::default();
+    assert_eq!(qos.class_for(MoqTrackType::Audio), Dscp::EF);
+    assert_eq!(qos.class_for(MoqTrackType::Video), Dscp::AF41);
+    assert_eq!(qos.class_for(MoqTrackType::Data), Dscp::BEST_EFFORT);
+
+    let marker = DscpMarker::new(qos);
+    assert_eq!(marker.dscp(), Dscp::BEST_EFFORT);
+    marker.add_track(MoqTrackType::Data);
+    assert_eq!(marker.dscp(), Dscp::BEST_EFFORT);
+    marker.add_track(MoqTrackType::Audio);
+    assert_eq!(marker.dscp(), Dscp::EF);
+    // Video shares the connection, so nothing on it can be marked EF
+    marker.clone().add_track(MoqTrackType::Video);
+    assert_eq!(marker.dscp(), Dscp::AF41);
+}
+
+#[tokio::test]
+async fn test_marked_sockets_carry_quic_packets() {
+    use quicrtc_core::transport::{QuicServer, QuicTransportConfig, ResourceLimits};
+
+    let server = QuicServer::bind(
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0),
+        QuicTransportConfig {
+            qos: Some(QosConfig::default()),
+            ..QuicTransportConfig::server()
+        },
+        ResourceLimits::server(),
+    )
+    .await
+    .unwrap();
+    assert_ne!(server.local_addr().port(), 0);
+
+    let path = std::env::temp_dir().join(format!("quicrtc-qos-{}.qrpm", uuid::Uuid::new_v4()));
+    let capture = PacketCapture::create(PacketCaptureConfig::new(&path)).unwrap();
+    let unused_port = std::net::UdpSocket::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let config = ConnectionConfig {
+        timeout: Duration::from_millis(200),
+        packet_capture: Some(capture.clone()),
+        quic_transport_config: Some(QuicTransportConfig {
+            qos: Some(QosConfig {
+                audio: Dscp::EF,
+                video: Dscp::AF42,
+            }),
+            ..QuicTransportConfig::mobile()
+        }),
+        ..ConnectionConfig::default()
+    };
+    let result = TransportConnection::establish_with_fallback(unused_port, config).await;
+    assert!(result.is_err());
+    capture.flush();
+
+    // Initial packets still go out, one datagram per send
+    let sent: Vec<u32> = PacketCaptureReader::open(&path)
+        .unwrap()
+        .filter_map(|record| match record.unwrap().event {
+            PacketEvent::Sent { bytes, .. } => Some(bytes),
+            _ => None,
+        })
+        .collect();
+    assert!(!sent.is_empty());
+    assert!(sent[0] >= 1200);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_ecn_counts_between_samples() {
+    use quicrtc_core::{EcnCounters, EcnCounts};
+
+    let counters = EcnCounters::default();
+    assert_eq!(counters.sent(), EcnCounts::default());
+    assert_eq!(counters.received().ce_fraction(), 0.0);
+
+    let earlier = EcnCounts {
+        ect0: 10,
+        ect1: 0,
+        ce: 0,
+    };
+    let later = EcnCounts {
+        ect0: 40,
+        ect1: 0,
+        ce: 10,
+    };
+    let interval = later.since(&earlier);
+    assert_eq!(interval.total(), 40);
+    assert_eq!(interval.ce_fraction(), 0.25);
+    assert!(!interval.is_l4s());
+
+    // Counters never go backwards, but a reset sample must not underflow
+    assert_eq!(earlier.since(&later), EcnCounts::default());
+}
+
+#[test]
+fn test_adaptive_keep_alive_intervals() {
+    use quicrtc_core::{AdaptiveKeepAlive, KeepAliveConfig};
+
+    let config = KeepAliveConfig::default();
+    let keep_alive = AdaptiveKeepAlive::new(config.clone());
+    assert_eq!(keep_alive.interval(Duration::ZERO), config.active_interval);
+    assert_eq!(keep_alive.interval(config.idle_after), config.idle_interval);
+
+    // Background wins over activity
+    keep_alive.set_backgrounded(true);
+    assert!(keep_alive.is_backgrounded());
+    assert_eq!(
+        keep_alive.interval(Duration::ZERO),
+        config.background_interval
+    );
+    keep_alive.set_backgrounded(false);
+    assert_eq!(keep_alive.interval(Duration::ZERO), config.active_interval);
+}
+
+#[test]
+fn test_adaptive_keep_alive_counts_radio_wakeups() {
+    use quicrtc_core::{AdaptiveKeepAlive, KeepAliveConfig};
+
+    let keep_alive = AdaptiveKeepAlive::new(KeepAliveConfig {
+        radio_tail: Duration::from_secs(5),
+        ..KeepAliveConfig::default()
+    });
+    let start = std::time::Instant::now();
+    assert!(!keep_alive.is_radio_awake(start));
+
+    // Packets within the radio tail share one wakeup
+    keep_alive.record_activity(start);
+    keep_alive.record_activity(start + Duration::from_secs(2));
+    keep_alive.record_activity(start + Duration::from_secs(6));
+    assert!(keep_alive.is_radio_awake(start + Duration::from_secs(10)));
+    assert!(!keep_alive.is_radio_awake(start + Duration::from_secs(11)));
+    assert_eq!(keep_alive.stats().radio_wakeups, 1);
+
+    keep_alive.record_activity(start + Duration::from_secs(20));
+    let stats = keep_alive.stats();
+    assert_eq!(stats.radio_wakeups, 2);
+    assert_eq!(stats.heartbeats_sent, 0);
+}
@@ -0,0 +1,109 @@
+//! Analyzer for packet metadata captures
+//!
+//! ```text
+//! quicrtc-capture <capture-file> [--records]
+//! ```
+//!
+//! Prints a summary of the capture; `--records` also lists every record.
+
+use quicrtc_core::{PacketCaptureReader, PacketEvent, PacketRecord};
+use quicrtc_diagnostics::CaptureSummary;
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: quicrtc-capture <capture-file> [--records]";
+
+fn main() -> ExitCode {
+    let mut path = None;
+    let mut show_records = false;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--records" => show_records = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            _ if path.is_none() => path = Some(arg),
+            _ => {
+                eprintln!("{}", USAGE);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let Some(path) = path else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    if show_records {
+        let reader = match PacketCaptureReader::open(&path) {
+            Ok(reader) => reader,
+            Err(e) => {
+                eprintln!("quicrtc-capture: {}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        for record in reader {
+            match record {
+                Ok(record) => println!("{}", describe(&record)),
+                Err(e) => {
+                    eprintln!("quicrtc-capture: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        println!();
+    }
+
+    match CaptureSummary::read(&path) {
+        Ok(summary) => {
+            println!("{}", summary);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("quicrtc-capture: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// One line describing a record
+fn describe(record: &PacketRecord) -> String {
+    let at = record.at.as_secs_f64();
+    match record.event {
+        PacketEvent::Sent {
+            bytes,
+            segments,
+            ecn,
+        } => format!(
+            "{:>12.6}  sent      {:>6} B x{} {:?}",
+            at, bytes, segments, ecn
+        ),
+        PacketEvent::Received {
+            bytes,
+            segments,
+            ecn,
+        } => format!(
+            "{:>12.6}  received  {:>6} B x{} {:?}",
+            at, bytes, segments, ecn
+        ),
+        PacketEvent::StreamOpened {
+            stream_id,
+            bidirectional,
+            local,
+        } => format!(
+            "{:>12.6}  stream    {} {} by {}",
+            at,
+            stream_id,
+            if bidirectional { "bidi" } else { "uni" },
+            if local { "us" } else { "peer" }
+        ),
+        PacketEvent::Loss {
+            lost_packets,
+            congestion_events,
+            rtt,
+        } => format!(
+            "{:>12.6}  loss      {} lost, {} congestion events, rtt {:?}",
+            at, lost_packets, congestion_events, rtt
+        ),
+    }
+}
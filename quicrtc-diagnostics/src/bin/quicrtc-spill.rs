@@ -0,0 +1,123 @@
+//! Decrypter and exporter for spill segment files
+//!
+//! ```text
+//! quicrtc-spill <segment-file>... [--key-file <path>] [--out <directory>]
+//! ```
+//!
+//! Lists the objects in the segments, opening sealed ones with the key in
+//! `--key-file`, written as 64 hex digits. `--out` also writes the payloads
+//! of each track, in the order they were spilled, to one file per track in
+//! that directory.
+
+use quicrtc_core::{MoqObject, MoqSpillKey, MoqSpillSegmentReader, TrackNamespace};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str =
+    "usage: quicrtc-spill <segment-file>... [--key-file <path>] [--out <directory>]";
+
+fn main() -> ExitCode {
+    let mut segments = Vec::new();
+    let mut key_file = None;
+    let mut out = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--key-file" | "--out" => {
+                let Some(value) = args.next() else {
+                    eprintln!("{}", USAGE);
+                    return ExitCode::FAILURE;
+                };
+                if arg == "--key-file" {
+                    key_file = Some(PathBuf::from(value));
+                } else {
+                    out = Some(PathBuf::from(value));
+                }
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            _ => segments.push(PathBuf::from(arg)),
+        }
+    }
+    if segments.is_empty() {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    }
+
+    match run(&segments, key_file.as_deref(), out.as_deref()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("quicrtc-spill: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(segments: &[PathBuf], key_file: Option<&Path>, out: Option<&Path>) -> Result<(), String> {
+    let key = key_file
+        .map(|path| {
+            let hex = std::fs::read_to_string(path)
+                .map_err(|e| format!("read key file {}: {}", path.display(), e))?;
+            MoqSpillKey::from_hex(&hex).map_err(|e| e.to_string())
+        })
+        .transpose()?;
+    if let Some(out) = out {
+        std::fs::create_dir_all(out).map_err(|e| format!("create {}: {}", out.display(), e))?;
+    }
+
+    let mut exports: HashMap<TrackNamespace, File> = HashMap::new();
+    for segment in segments {
+        let reader =
+            MoqSpillSegmentReader::open(segment, key.as_ref()).map_err(|e| e.to_string())?;
+        println!(
+            "{}{}",
+            segment.display(),
+            if reader.is_sealed() { " (sealed)" } else { "" }
+        );
+        for object in reader {
+            let object = object.map_err(|e| format!("{}: {}", segment.display(), e))?;
+            println!("{}", describe(&object));
+            if let Some(out) = out {
+                export(&mut exports, out, &object)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Append an object's payload to its track's export file
+fn export(
+    exports: &mut HashMap<TrackNamespace, File>,
+    out: &Path,
+    object: &MoqObject,
+) -> Result<(), String> {
+    let path = out.join(format!(
+        "{}.bin",
+        object.track_namespace.to_string().replace('/', "_")
+    ));
+    let file = match exports.entry(object.track_namespace.clone()) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => entry
+            .insert(File::create(&path).map_err(|e| format!("create {}: {}", path.display(), e))?),
+    };
+    file.write_all(&object.payload)
+        .map_err(|e| format!("write {}: {}", path.display(), e))
+}
+
+/// One line describing an object
+fn describe(object: &MoqObject) -> String {
+    format!(
+        "  {}  group {} object {}  {:?}  {} B",
+        object.track_namespace,
+        object.group_id,
+        object.object_id,
+        object.object_status,
+        object.payload.len()
+    )
+}
@@ -0,0 +1,190 @@
+//! Summaries of packet metadata captures
+//!
+//! Reads files written by [`quicrtc_core::PacketCapture`] and condenses them
+//! into the figures support usually asks for first: throughput, ECN marks,
+//! loss and round-trip times.
+
+use quicrtc_core::{EcnMark, PacketCaptureReader, PacketEvent, PacketRecord, QuicRtcError};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Window throughput peaks are measured over
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(1);
+
+/// Totals of a packet capture
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CaptureSummary {
+    /// When the capture started
+    pub started_at: Option<SystemTime>,
+    /// Time between the first and last record
+    pub duration: Duration,
+    /// Datagrams sent
+    pub packets_sent: u64,
+    /// Bytes sent
+    pub bytes_sent: u64,
+    /// Datagrams received
+    pub packets_received: u64,
+    /// Bytes received
+    pub bytes_received: u64,
+    /// Datagrams sent or received with an ECT codepoint
+    pub ecn_capable_packets: u64,
+    /// Datagrams received with the congestion-experienced mark
+    pub ce_marked_packets: u64,
+    /// Streams we opened
+    pub local_streams: u64,
+    /// Streams the peer opened
+    pub remote_streams: u64,
+    /// Packets the connection declared lost
+    pub lost_packets: u64,
+    /// Congestion events
+    pub congestion_events: u64,
+    /// Lowest sampled round-trip time
+    pub min_rtt: Option<Duration>,
+    /// Mean sampled round-trip time
+    pub mean_rtt: Option<Duration>,
+    /// Highest sampled round-trip time
+    pub max_rtt: Option<Duration>,
+    /// Highest send rate over one second, in kbps
+    pub peak_send_kbps: u64,
+    /// Highest receive rate over one second, in kbps
+    pub peak_receive_kbps: u64,
+}
+
+impl CaptureSummary {
+    /// Read and summarize a capture file
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, QuicRtcError> {
+        let reader = PacketCaptureReader::open(path)?;
+        let started_at = reader.started_at();
+        let records = reader.collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            started_at: Some(started_at),
+            ..Self::from_records(records)
+        })
+    }
+
+    /// Summarize records in capture order
+    pub fn from_records(records: impl IntoIterator<Item = PacketRecord>) -> Self {
+        let mut summary = Self::default();
+        let mut first = None;
+        let mut rtt_total = Duration::ZERO;
+        let mut rtt_samples = 0u32;
+        // Bytes sent and received per throughput window
+        let mut windows: BTreeMap<u64, (u64, u64)> = BTreeMap::new();
+
+        for record in records {
+            first.get_or_insert(record.at);
+            summary.duration = record.at.saturating_sub(first.unwrap_or_default());
+            let window = (record.at.as_millis() / THROUGHPUT_WINDOW.as_millis()) as u64;
+
+            match record.event {
+                PacketEvent::Sent {
+                    bytes,
+                    segments,
+                    ecn,
+                } => {
+                    summary.packets_sent += u64::from(segments);
+                    summary.bytes_sent += u64::from(bytes);
+                    if ecn.is_some() {
+                        summary.ecn_capable_packets += u64::from(segments);
+                    }
+                    windows.entry(window).or_default().0 += u64::from(bytes);
+                }
+                PacketEvent::Received {
+                    bytes,
+                    segments,
+                    ecn,
+                } => {
+                    summary.packets_received += u64::from(segments);
+                    summary.bytes_received += u64::from(bytes);
+                    if ecn.is_some() {
+                        summary.ecn_capable_packets += u64::from(segments);
+                    }
+                    if ecn == Some(EcnMark::Ce) {
+                        summary.ce_marked_packets += u64::from(segments);
+                    }
+                    windows.entry(window).or_default().1 += u64::from(bytes);
+                }
+                PacketEvent::StreamOpened { local, .. } => {
+                    if local {
+                        summary.local_streams += 1;
+                    } else {
+                        summary.remote_streams += 1;
+                    }
+                }
+                PacketEvent::Loss {
+                    lost_packets,
+                    congestion_events,
+                    rtt,
+                } => {
+                    summary.lost_packets += u64::from(lost_packets);
+                    summary.congestion_events += u64::from(congestion_events);
+                    summary.min_rtt = Some(summary.min_rtt.map_or(rtt, |min| min.min(rtt)));
+                    summary.max_rtt = Some(summary.max_rtt.map_or(rtt, |max| max.max(rtt)));
+                    rtt_total += rtt;
+                    rtt_samples += 1;
+                }
+            }
+        }
+
+        if rtt_samples > 0 {
+            summary.mean_rtt = Some(rtt_total / rtt_samples);
+        }
+        let kbps = |bytes: u64| bytes * 8 / 1000 / THROUGHPUT_WINDOW.as_secs();
+        for (sent, received) in windows.values() {
+            summary.peak_send_kbps = summary.peak_send_kbps.max(kbps(*sent));
+            summary.peak_receive_kbps = summary.peak_receive_kbps.max(kbps(*received));
+        }
+        summary
+    }
+
+    /// Lost packets as a fraction of packets sent
+    pub fn loss_rate(&self) -> f64 {
+        if self.packets_sent == 0 {
+            0.0
+        } else {
+            self.lost_packets as f64 / self.packets_sent as f64
+        }
+    }
+}
+
+impl std::fmt::Display for CaptureSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rtt = |rtt: Option<Duration>| rtt.map_or("-".to_string(), |rtt| format!("{:?}", rtt));
+        writeln!(f, "Duration:          {:?}", self.duration)?;
+        writeln!(
+            f,
+            "Sent:              {} packets, {} bytes (peak {} kbps)",
+            self.packets_sent, self.bytes_sent, self.peak_send_kbps
+        )?;
+        writeln!(
+            f,
+            "Received:          {} packets, {} bytes (peak {} kbps)",
+            self.packets_received, self.bytes_received, self.peak_receive_kbps
+        )?;
+        writeln!(
+            f,
+            "ECN:               {} ECN-capable, {} CE-marked",
+            self.ecn_capable_packets, self.ce_marked_packets
+        )?;
+        writeln!(
+            f,
+            "Streams:           {} opened locally, {} by the peer",
+            self.local_streams, self.remote_streams
+        )?;
+        writeln!(
+            f,
+            "Loss:              {} packets ({:.2}%), {} congestion events",
+            self.lost_packets,
+            self.loss_rate() * 100.0,
+            self.congestion_events
+        )?;
+        write!(
+            f,
+            "RTT:               min {}, mean {}, max {}",
+            rtt(self.min_rtt),
+            rtt(self.mean_rtt),
+            rtt(self.max_rtt)
+        )
+    }
+}
@@ -0,0 +1,290 @@
+//! Panic hook writing a diagnostic bundle to disk
+//!
+//! [`CrashReporter::install`] is opt-in. Once installed, a panic anywhere in
+//! the process writes a [`CrashBundle`] — backtrace, the state reported by
+//! registered providers, recent debug logs and process resource usage — as
+//! JSON into the configured directory before the previous panic hook runs.
+
+use crate::debug_logger::{DebugLogger, LogRing, LogRingConfig};
+use quicrtc_core::QuicRtcError;
+use serde::{Deserialize, Serialize};
+use std::backtrace::Backtrace;
+use std::collections::BTreeMap;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, Once};
+use std::time::{Duration, SystemTime};
+
+/// Produces a snapshot of some component's state for a crash bundle
+///
+/// Runs inside the panic hook, so it must not block or panic: read shared
+/// state with `try_lock`-style accessors and report what it cannot reach.
+pub type StateProvider = Arc<dyn Fn() -> serde_json::Value + Send + Sync>;
+
+/// Reporter the panic hook writes bundles for
+static REPORTER: Mutex<Option<Arc<CrashReporterInner>>> = Mutex::new(None);
+
+/// Registered state providers by registration ID
+static STATE_PROVIDERS: Mutex<BTreeMap<u64, (String, StateProvider)>> = Mutex::new(BTreeMap::new());
+
+static NEXT_PROVIDER_ID: AtomicU64 = AtomicU64::new(0);
+
+static HOOK: Once = Once::new();
+
+/// Crash reporter configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReporterConfig {
+    /// Directory crash bundles are written to; created if missing
+    pub directory: PathBuf,
+    /// How much recent debug logging to include in a bundle
+    pub log_window: Duration,
+    /// Debug log records included at most
+    pub max_log_records: usize,
+}
+
+impl CrashReporterConfig {
+    /// Write bundles to `directory` with default limits
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            log_window: Duration::from_secs(5 * 60),
+            max_log_records: 10_000,
+        }
+    }
+}
+
+/// Everything captured about a panic
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashBundle {
+    /// When the panic happened
+    pub created_at: SystemTime,
+    /// ID of the crashed process
+    pub process_id: u32,
+    /// Name of the panicking thread, if it had one
+    pub thread: Option<String>,
+    /// Panic message
+    pub message: String,
+    /// Source location of the panic
+    pub location: Option<String>,
+    /// Backtrace of the panicking thread
+    pub backtrace: String,
+    /// Snapshots from registered state providers, by provider name
+    pub state: BTreeMap<String, serde_json::Value>,
+    /// Recent debug logs, oldest first
+    pub recent_logs: Vec<String>,
+    /// Resource usage of the process
+    pub resource_usage: ProcessUsage,
+}
+
+/// Process resource usage at the time of a crash
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProcessUsage {
+    /// Resident memory in KiB, where the platform reports it
+    pub resident_memory_kb: Option<u64>,
+    /// Peak resident memory in KiB, where the platform reports it
+    pub peak_resident_memory_kb: Option<u64>,
+    /// Number of threads, where the platform reports it
+    pub threads: Option<u64>,
+}
+
+impl ProcessUsage {
+    /// Read the current process's resource usage
+    pub fn current() -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            if let Ok(status) = std::fs::read_to_string("/proc/self/status") {
+                let field = |name: &str| {
+                    status
+                        .lines()
+                        .find_map(|line| line.strip_prefix(name))
+                        .and_then(|value| value.split_whitespace().next())
+                        .and_then(|value| value.parse().ok())
+                };
+                return Self {
+                    resident_memory_kb: field("VmRSS:"),
+                    peak_resident_memory_kb: field("VmHWM:"),
+                    threads: field("Threads:"),
+                };
+            }
+        }
+        Self::default()
+    }
+}
+
+/// Handle to the installed crash reporter
+#[derive(Debug, Clone)]
+pub struct CrashReporter {
+    inner: Arc<CrashReporterInner>,
+}
+
+#[derive(Debug)]
+struct CrashReporterInner {
+    config: CrashReporterConfig,
+    /// Recent logs, fed by [`DebugLogger::global`]
+    logs: Arc<LogRing>,
+}
+
+/// Keeps a state provider registered until dropped
+#[derive(Debug)]
+#[must_use = "the provider is unregistered when the guard is dropped"]
+pub struct CrashStateGuard {
+    id: u64,
+}
+
+impl Drop for CrashStateGuard {
+    fn drop(&mut self) {
+        lock(&STATE_PROVIDERS).remove(&self.id);
+    }
+}
+
+impl CrashReporter {
+    /// Install the panic hook, writing bundles as configured
+    ///
+    /// The previous panic hook still runs after the bundle is written.
+    /// Installing again replaces the configuration. Recent logs are only
+    /// captured when [`DebugLogger::global`] is part of the process's
+    /// `tracing` subscriber.
+    pub fn install(config: CrashReporterConfig) -> Result<Self, QuicRtcError> {
+        std::fs::create_dir_all(&config.directory).map_err(|e| QuicRtcError::Initialization {
+            reason: format!(
+                "Failed to create crash directory {}: {}",
+                config.directory.display(),
+                e
+            ),
+        })?;
+
+        let logs = Arc::new(LogRing::new(LogRingConfig {
+            max_age: config.log_window,
+            max_records: config.max_log_records,
+        }));
+        DebugLogger::global().attach(&logs);
+        let inner = Arc::new(CrashReporterInner { config, logs });
+        *lock(&REPORTER) = Some(inner.clone());
+
+        HOOK.call_once(|| {
+            let previous = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |info| {
+                if let Some(reporter) = Self::installed() {
+                    let bundle = reporter.capture(info);
+                    match reporter.write_bundle(&bundle) {
+                        Ok(path) => {
+                            eprintln!("quicrtc: crash bundle written to {}", path.display())
+                        }
+                        Err(e) => eprintln!("quicrtc: failed to write crash bundle: {}", e),
+                    }
+                }
+                previous(info);
+            }));
+        });
+
+        Ok(Self { inner })
+    }
+
+    /// The installed reporter, if any
+    pub fn installed() -> Option<Self> {
+        let reporter = REPORTER.try_lock().ok()?.clone()?;
+        Some(Self { inner: reporter })
+    }
+
+    /// Stop writing bundles; the previous panic hook keeps running
+    pub fn uninstall() {
+        lock(&REPORTER).take();
+    }
+
+    /// Include `provider`'s snapshot under `name` in crash bundles
+    ///
+    /// Providers may be registered whether or not a reporter is installed.
+    pub fn register_state(name: impl Into<String>, provider: StateProvider) -> CrashStateGuard {
+        let id = NEXT_PROVIDER_ID.fetch_add(1, Ordering::Relaxed);
+        lock(&STATE_PROVIDERS).insert(id, (name.into(), provider));
+        CrashStateGuard { id }
+    }
+
+    /// Directory bundles are written to
+    pub fn directory(&self) -> &Path {
+        &self.inner.config.directory
+    }
+
+    /// Build the bundle for a panic
+    pub fn capture(&self, info: &PanicHookInfo<'_>) -> CrashBundle {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Box<dyn Any>".to_string());
+        let location = info.location().map(|location| {
+            format!(
+                "{}:{}:{}",
+                location.file(),
+                location.line(),
+                location.column()
+            )
+        });
+        self.bundle(message, location)
+    }
+
+    /// Build a bundle for a failure that did not panic
+    pub fn snapshot(&self, message: impl Into<String>) -> CrashBundle {
+        self.bundle(message.into(), None)
+    }
+
+    /// Write `bundle` as JSON into the crash directory
+    pub fn write_bundle(&self, bundle: &CrashBundle) -> Result<PathBuf, QuicRtcError> {
+        let since_epoch = bundle
+            .created_at
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        let path = self.inner.config.directory.join(format!(
+            "quicrtc-crash-{}-{}.json",
+            since_epoch.as_millis(),
+            bundle.process_id
+        ));
+        let json = serde_json::to_vec_pretty(bundle).map_err(|e| QuicRtcError::InvalidData {
+            reason: format!("Failed to serialize crash bundle: {}", e),
+        })?;
+        std::fs::write(&path, json).map_err(|e| QuicRtcError::InvalidOperation {
+            operation: format!("write crash bundle to {}: {}", path.display(), e),
+        })?;
+        Ok(path)
+    }
+
+    fn bundle(&self, message: String, location: Option<String>) -> CrashBundle {
+        // A provider's own lock may be held by the panicking thread, so the
+        // registry is never waited on here
+        let providers: Vec<(String, StateProvider)> = match STATE_PROVIDERS.try_lock() {
+            Ok(providers) => providers.values().cloned().collect(),
+            Err(_) => Vec::new(),
+        };
+        let state = providers
+            .into_iter()
+            .map(|(name, provider)| (name, provider()))
+            .collect();
+
+        CrashBundle {
+            created_at: SystemTime::now(),
+            process_id: std::process::id(),
+            thread: std::thread::current().name().map(str::to_string),
+            message,
+            location,
+            backtrace: Backtrace::force_capture().to_string(),
+            state,
+            recent_logs: self
+                .inner
+                .logs
+                .records()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            resource_usage: ProcessUsage::current(),
+        }
+    }
+}
+
+/// Lock a registry, ignoring poisoning from an earlier panic
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
@@ -1,29 +1,289 @@
 //! Structured debug logging system
+//!
+//! [`DebugLogger`] is a `tracing` layer that copies log events into bounded
+//! in-memory [`LogRing`]s. A ring keeps the last few minutes of debug logs so
+//! they can be dumped after something went wrong, without `RUST_LOG` having
+//! been set in advance.
 
 use quicrtc_core::QuicRtcError;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::time::{Duration, SystemTime};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
 
-/// Debug logger for structured logging
+/// Field naming the room a log event belongs to
+pub const ROOM_FIELD: &str = "room_id";
+
+/// Target prefix of the events captured into rings
+const CAPTURED_TARGET: &str = "quicrtc";
+
+/// One captured log event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogRecord {
+    /// When the event was logged
+    pub timestamp: SystemTime,
+    /// Level name (`ERROR` … `TRACE`)
+    pub level: String,
+    /// Module that logged the event
+    pub target: String,
+    /// Formatted message
+    pub message: String,
+    /// Structured fields other than the message
+    pub fields: BTreeMap<String, String>,
+}
+
+impl std::fmt::Display for LogRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let since_epoch = self
+            .timestamp
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        write!(
+            f,
+            "{}.{:03} {:>5} {}: {}",
+            since_epoch.as_secs(),
+            since_epoch.subsec_millis(),
+            self.level,
+            self.target,
+            self.message
+        )?;
+        for (name, value) in &self.fields {
+            write!(f, " {}={}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Bounds of a [`LogRing`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogRingConfig {
+    /// Records older than this are dropped
+    pub max_age: Duration,
+    /// Records kept at most, oldest dropped first
+    pub max_records: usize,
+}
+
+impl Default for LogRingConfig {
+    fn default() -> Self {
+        Self {
+            max_age: Duration::from_secs(5 * 60),
+            max_records: 10_000,
+        }
+    }
+}
+
+/// Bounded buffer of recent log records
+///
+/// A ring scoped to a room skips events whose [`ROOM_FIELD`] names another
+/// room; events without the field are kept.
 #[derive(Debug)]
+pub struct LogRing {
+    config: LogRingConfig,
+    room_id: Option<String>,
+    records: Mutex<VecDeque<LogRecord>>,
+    dropped: AtomicU64,
+}
+
+impl LogRing {
+    /// Create a ring capturing events from every room
+    pub fn new(config: LogRingConfig) -> Self {
+        Self {
+            config,
+            room_id: None,
+            records: Mutex::new(VecDeque::new()),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Create a ring capturing the events of one room
+    pub fn for_room(room_id: impl Into<String>, config: LogRingConfig) -> Self {
+        Self {
+            room_id: Some(room_id.into()),
+            ..Self::new(config)
+        }
+    }
+
+    /// Add a record, dropping the oldest ones past the ring's bounds
+    pub fn push(&self, record: LogRecord) {
+        let mut records = self.records.lock().unwrap();
+        if records.len() >= self.config.max_records {
+            records.pop_front();
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        records.push_back(record);
+        self.expire(&mut records);
+    }
+
+    /// Records still within the ring's age limit, oldest first
+    pub fn records(&self) -> Vec<LogRecord> {
+        let mut records = self.records.lock().unwrap();
+        self.expire(&mut records);
+        records.iter().cloned().collect()
+    }
+
+    /// Render the buffered records, one per line
+    pub fn dump(&self) -> String {
+        let mut dump = String::new();
+        let dropped = self.dropped();
+        if dropped > 0 {
+            let _ = writeln!(dump, "... {} earlier records dropped", dropped);
+        }
+        for record in self.records() {
+            let _ = writeln!(dump, "{}", record);
+        }
+        dump
+    }
+
+    /// Records dropped because the ring was full or they grew too old
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Number of buffered records
+    pub fn len(&self) -> usize {
+        self.records.lock().unwrap().len()
+    }
+
+    /// Whether no records are buffered
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Discard every buffered record
+    pub fn clear(&self) {
+        self.records.lock().unwrap().clear();
+    }
+
+    fn accepts(&self, record: &LogRecord) -> bool {
+        match (&self.room_id, record.fields.get(ROOM_FIELD)) {
+            (Some(room_id), Some(event_room)) => room_id == event_room,
+            _ => true,
+        }
+    }
+
+    fn expire(&self, records: &mut VecDeque<LogRecord>) {
+        let Some(cutoff) = SystemTime::now().checked_sub(self.config.max_age) else {
+            return;
+        };
+        while records
+            .front()
+            .is_some_and(|record| record.timestamp < cutoff)
+        {
+            records.pop_front();
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// `tracing` layer feeding debug-level QUIC RTC events into attached rings
+///
+/// Cloning is cheap; clones share the same rings. Events are only formatted
+/// while at least one ring is attached.
+#[derive(Debug, Clone, Default)]
 pub struct DebugLogger {
-    // TODO: Add logger state
+    rings: Arc<Mutex<Vec<Weak<LogRing>>>>,
 }
 
 impl DebugLogger {
     /// Create new debug logger
     pub fn new() -> Self {
-        Self {}
+        Self::default()
+    }
+
+    /// Process-wide logger installed by [`DebugLogger::init_logging`]
+    pub fn global() -> &'static DebugLogger {
+        static GLOBAL: OnceLock<DebugLogger> = OnceLock::new();
+        GLOBAL.get_or_init(DebugLogger::new)
+    }
+
+    /// Start copying events into `ring`; it is detached once dropped
+    pub fn attach(&self, ring: &Arc<LogRing>) {
+        let mut rings = self.rings.lock().unwrap();
+        rings.retain(|ring| ring.strong_count() > 0);
+        rings.push(Arc::downgrade(ring));
     }
-    
+
     /// Initialize logging system
-    pub fn init_logging() -> Result<(), QuicRtcError> {
-        // TODO: Implement logging initialization
-        tracing_subscriber::fmt::init();
-        Ok(())
+    ///
+    /// Installs a console logger filtered by `RUST_LOG` (or `default_filter`
+    /// when unset) together with [`DebugLogger::global`], which captures
+    /// debug logs regardless of the console filter. Fails if the process
+    /// already has a global subscriber; add [`DebugLogger::global`] as a
+    /// layer to it instead.
+    pub fn init_logging(default_filter: &str) -> Result<(), QuicRtcError> {
+        let console_filter =
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_filter));
+        tracing_subscriber::registry()
+            .with(tracing_subscriber::fmt::layer().with_filter(console_filter))
+            .with(Self::global().clone())
+            .try_init()
+            .map_err(|e| QuicRtcError::Initialization {
+                reason: format!("Failed to install logger: {}", e),
+            })
+    }
+
+    fn live_rings(&self) -> Vec<Arc<LogRing>> {
+        let rings = self.rings.lock().unwrap();
+        rings.iter().filter_map(Weak::upgrade).collect()
     }
 }
 
-impl Default for DebugLogger {
-    fn default() -> Self {
-        Self::new()
+impl<S: Subscriber> Layer<S> for DebugLogger {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        if *metadata.level() > Level::DEBUG || !metadata.target().starts_with(CAPTURED_TARGET) {
+            return;
+        }
+        let rings = self.live_rings();
+        if rings.is_empty() {
+            return;
+        }
+
+        let mut visitor = RecordVisitor::default();
+        event.record(&mut visitor);
+        let record = LogRecord {
+            timestamp: SystemTime::now(),
+            level: metadata.level().to_string(),
+            target: metadata.target().to_string(),
+            message: visitor.message,
+            fields: visitor.fields,
+        };
+        for ring in rings.iter().filter(|ring| ring.accepts(&record)) {
+            ring.push(record.clone());
+        }
+    }
+}
+
+/// Collects an event's message and fields as strings
+#[derive(Default)]
+struct RecordVisitor {
+    message: String,
+    fields: BTreeMap<String, String>,
+}
+
+impl Visit for RecordVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields
+                .insert(field.name().to_string(), value.to_string());
+        }
     }
-}
\ No newline at end of file
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields
+                .insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+}
@@ -6,10 +6,21 @@
 #![deny(missing_docs)]
 #![warn(clippy::all)]
 
+pub mod capture_analyzer;
 pub mod connection_analyzer;
+pub mod crash_reporter;
 pub mod network_profiler;
 pub mod debug_logger;
+pub mod signaling_metrics;
+pub mod wakeup_report;
 
 // Re-export main types
+pub use capture_analyzer::CaptureSummary;
 pub use connection_analyzer::{ConnectionInfo, ConnectionStats};
-pub use network_profiler::NetworkProfiler;
\ No newline at end of file
+pub use crash_reporter::{
+    CrashBundle, CrashReporter, CrashReporterConfig, CrashStateGuard, ProcessUsage, StateProvider,
+};
+pub use debug_logger::{DebugLogger, LogRecord, LogRing, LogRingConfig};
+pub use network_profiler::NetworkProfiler;
+pub use signaling_metrics::{SignalingMetrics, SignalingMetricsSnapshot};
+pub use wakeup_report::WakeupReport;
\ No newline at end of file
@@ -0,0 +1,126 @@
+//! Signaling server message and flood protection counters
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Shared counters updated by the signaling server's protocol handler
+///
+/// Cloning is cheap; clones share the same counters.
+#[derive(Debug, Clone, Default)]
+pub struct SignalingMetrics {
+    counters: Arc<Counters>,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    connections_accepted: AtomicU64,
+    messages_received: AtomicU64,
+    messages_throttled: AtomicU64,
+    messages_oversized: AtomicU64,
+    messages_invalid: AtomicU64,
+    connections_rate_limited: AtomicU64,
+    connections_too_slow: AtomicU64,
+    participants_timed_out: AtomicU64,
+}
+
+impl SignalingMetrics {
+    /// Create zeroed counters
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an accepted WebSocket connection
+    pub fn record_connection_accepted(&self) {
+        self.counters
+            .connections_accepted
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a message read from a connection
+    pub fn record_message_received(&self) {
+        self.counters
+            .messages_received
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a message delayed because its connection exceeded its rate
+    pub fn record_message_throttled(&self) {
+        self.counters
+            .messages_throttled
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a message rejected for exceeding the size limit
+    pub fn record_message_oversized(&self) {
+        self.counters
+            .messages_oversized
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a message that failed to parse
+    pub fn record_message_invalid(&self) {
+        self.counters
+            .messages_invalid
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a connection closed for flooding
+    pub fn record_connection_rate_limited(&self) {
+        self.counters
+            .connections_rate_limited
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a connection closed for not reading its responses
+    pub fn record_connection_too_slow(&self) {
+        self.counters
+            .connections_too_slow
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a participant removed for no longer sending heartbeats
+    pub fn record_participant_timed_out(&self) {
+        self.counters
+            .participants_timed_out
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Current counter values
+    pub fn snapshot(&self) -> SignalingMetricsSnapshot {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        SignalingMetricsSnapshot {
+            connections_accepted: load(&self.counters.connections_accepted),
+            messages_received: load(&self.counters.messages_received),
+            messages_throttled: load(&self.counters.messages_throttled),
+            messages_oversized: load(&self.counters.messages_oversized),
+            messages_invalid: load(&self.counters.messages_invalid),
+            connections_rate_limited: load(&self.counters.connections_rate_limited),
+            connections_too_slow: load(&self.counters.connections_too_slow),
+            participants_timed_out: load(&self.counters.participants_timed_out),
+        }
+    }
+}
+
+/// Point-in-time copy of [`SignalingMetrics`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignalingMetricsSnapshot {
+    /// WebSocket connections accepted
+    pub connections_accepted: u64,
+    /// Messages read from connections
+    pub messages_received: u64,
+    /// Messages delayed by per-connection rate limits
+    pub messages_throttled: u64,
+    /// Messages rejected for exceeding the size limit
+    pub messages_oversized: u64,
+    /// Messages that failed to parse
+    pub messages_invalid: u64,
+    /// Connections closed for sustained flooding
+    pub connections_rate_limited: u64,
+    /// Connections closed for not reading their responses
+    #[serde(default)]
+    pub connections_too_slow: u64,
+    /// Participants removed for no longer sending heartbeats
+    #[serde(default)]
+    pub participants_timed_out: u64,
+}
@@ -0,0 +1,60 @@
+//! Radio wakeup figures of adaptive keep-alive
+//!
+//! Condenses the counts measured by [`quicrtc_core::AdaptiveKeepAlive`] into
+//! rates that can be compared across builds and devices.
+
+use quicrtc_core::{AdaptiveKeepAlive, KeepAliveStats};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Radio wakeups and how much of them keep-alive is responsible for
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WakeupReport {
+    /// Time the counts cover
+    pub observed_for: Duration,
+    /// Times traffic started after the radio had gone idle
+    pub radio_wakeups: u64,
+    /// Radio wakeups per hour
+    pub wakeups_per_hour: f64,
+    /// Heartbeats sent
+    pub heartbeats_sent: u64,
+    /// Heartbeats that woke the radio themselves
+    pub heartbeat_wakeups: u64,
+    /// Share of radio wakeups caused by heartbeats
+    pub heartbeat_wakeup_share: f64,
+    /// Share of heartbeats sent early alongside other traffic
+    pub coalesced_share: f64,
+}
+
+impl WakeupReport {
+    /// Report on the counts of `keep_alive` so far
+    pub fn from_keep_alive(keep_alive: &AdaptiveKeepAlive) -> Self {
+        Self::from_stats(&keep_alive.stats())
+    }
+
+    /// Report on measured keep-alive counts
+    pub fn from_stats(stats: &KeepAliveStats) -> Self {
+        let hours = stats.observed_for.as_secs_f64() / 3600.0;
+        Self {
+            observed_for: stats.observed_for,
+            radio_wakeups: stats.radio_wakeups,
+            wakeups_per_hour: if hours > 0.0 {
+                stats.radio_wakeups as f64 / hours
+            } else {
+                0.0
+            },
+            heartbeats_sent: stats.heartbeats_sent,
+            heartbeat_wakeups: stats.heartbeat_wakeups,
+            heartbeat_wakeup_share: share(stats.heartbeat_wakeups, stats.radio_wakeups),
+            coalesced_share: share(stats.heartbeats_coalesced, stats.heartbeats_sent),
+        }
+    }
+}
+
+fn share(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
@@ -0,0 +1,73 @@
+//! Pixel operation benchmarks, scalar against each supported SIMD level
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use quicrtc_media::{PixelOps, SimdLevel};
+
+const WIDTH: u32 = 1280;
+const HEIGHT: u32 = 720;
+
+fn supported_levels() -> Vec<SimdLevel> {
+    [
+        SimdLevel::Scalar,
+        SimdLevel::Sse2,
+        SimdLevel::Avx2,
+        SimdLevel::Neon,
+    ]
+    .into_iter()
+    .filter(|level| level.is_supported())
+    .collect()
+}
+
+fn test_plane(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 37 % 256) as u8).collect()
+}
+
+fn bench_display_adjustments(c: &mut Criterion) {
+    let luma = test_plane((WIDTH * HEIGHT) as usize);
+    let mut group = c.benchmark_group("display_adjustments");
+    group.throughput(Throughput::Bytes(luma.len() as u64));
+
+    for level in supported_levels() {
+        let ops = PixelOps::with_level(level);
+        let name = format!("{:?}", level);
+
+        group.bench_with_input(BenchmarkId::new("brightness", &name), &ops, |b, ops| {
+            let mut plane = luma.clone();
+            b.iter(|| ops.adjust_brightness(black_box(&mut plane), 20))
+        });
+        group.bench_with_input(BenchmarkId::new("contrast", &name), &ops, |b, ops| {
+            let mut plane = luma.clone();
+            b.iter(|| ops.scale_around_midpoint(black_box(&mut plane), 1.2))
+        });
+        group.bench_with_input(BenchmarkId::new("gamma", &name), &ops, |b, ops| {
+            let mut plane = luma.clone();
+            b.iter(|| ops.apply_gamma(black_box(&mut plane), 1.4))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_conversions(c: &mut Criterion) {
+    let rgba = test_plane((WIDTH * HEIGHT * 4) as usize);
+    let i420 = PixelOps::new().rgba_to_i420(&rgba, WIDTH, HEIGHT).unwrap();
+    let mut group = c.benchmark_group("yuv_conversion");
+    group.throughput(Throughput::Elements((WIDTH * HEIGHT) as u64));
+
+    for level in supported_levels() {
+        let ops = PixelOps::with_level(level);
+        let name = format!("{:?}", level);
+
+        group.bench_with_input(BenchmarkId::new("rgba_to_i420", &name), &ops, |b, ops| {
+            b.iter(|| ops.rgba_to_i420(black_box(&rgba), WIDTH, HEIGHT).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("i420_to_rgba", &name), &ops, |b, ops| {
+            b.iter(|| ops.i420_to_rgba(black_box(&i420), WIDTH, HEIGHT).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_display_adjustments, bench_conversions);
+criterion_main!(benches);
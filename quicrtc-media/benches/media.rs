@@ -0,0 +1,123 @@
+//! Assembler and codec round-trip benchmarks
+
+use criterion::{
+    black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput,
+};
+use quicrtc_core::{H264Frame, MoqObject, TrackNamespace, VideoRotation};
+use quicrtc_media::codecs::{H264Config, OpusConfig};
+use quicrtc_media::tracks::{AudioFrame, MediaFrame, VideoFrame};
+use quicrtc_media::{H264Codec, MoqObjectAssembler, OpusCodec, SyncDecoder, SyncEncoder};
+
+const GROUPS: u64 = 30;
+const OBJECTS_PER_GROUP: u64 = 8;
+
+fn namespace() -> TrackNamespace {
+    TrackNamespace {
+        namespace: "bench.example.com".to_string(),
+        track_name: "alice/camera".to_string(),
+    }
+}
+
+/// Video groups with every `loss_interval`-th object missing (0 = no loss)
+fn video_objects(loss_interval: u64) -> Vec<MoqObject> {
+    let mut objects = Vec::new();
+    for group_id in 0..GROUPS {
+        for object_id in 0..OBJECTS_PER_GROUP {
+            let sequence = group_id * OBJECTS_PER_GROUP + object_id;
+            if loss_interval != 0 && sequence % loss_interval == loss_interval - 1 {
+                continue;
+            }
+            let mut object = MoqObject::from_h264_frame(
+                namespace(),
+                H264Frame {
+                    nal_units: vec![0u8; 1000],
+                    is_keyframe: object_id == 0,
+                    timestamp_us: 0,
+                    sequence_number: object_id,
+                },
+            );
+            object.group_id = group_id;
+            objects.push(object);
+        }
+        objects.push(MoqObject::end_of_group(
+            namespace(),
+            "video".to_string(),
+            group_id,
+            OBJECTS_PER_GROUP,
+        ));
+    }
+    objects
+}
+
+fn bench_assembler(c: &mut Criterion) {
+    let mut group = c.benchmark_group("assembler");
+    group.throughput(Throughput::Elements(GROUPS));
+
+    for (label, loss_interval) in [("no_loss", 0), ("loss_5pct", 20), ("loss_20pct", 5)] {
+        let objects = video_objects(loss_interval);
+        group.bench_with_input(
+            BenchmarkId::new("reassemble", label),
+            &objects,
+            |b, objects| {
+                b.iter_batched(
+                    || objects.clone(),
+                    |objects| {
+                        let mut assembler = MoqObjectAssembler::new();
+                        for object in objects {
+                            black_box(assembler.add_object(object).unwrap());
+                        }
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_codec_round_trips(c: &mut Criterion) {
+    let mut group = c.benchmark_group("codec_round_trip");
+
+    let opus_config = OpusConfig::default();
+    let opus = OpusCodec::with_config(opus_config.clone()).unwrap();
+    let samples_per_channel = opus_config.sample_rate * opus_config.frame_duration_ms / 1000;
+    let audio = MediaFrame::Audio(AudioFrame {
+        samples: (0..samples_per_channel * opus_config.channels as u32)
+            .map(|i| (i as f32 * 0.05).sin() * 0.5)
+            .collect(),
+        sample_rate: opus_config.sample_rate,
+        channels: opus_config.channels,
+        timestamp: 0,
+    });
+    group.bench_function("opus_20ms", |b| {
+        b.iter(|| {
+            let encoded = opus.encode_sync(black_box(&audio)).unwrap();
+            opus.decode_sync(&encoded).unwrap()
+        })
+    });
+
+    let h264_config = H264Config::default();
+    let h264 = H264Codec::with_config(h264_config.clone()).unwrap();
+    let video = MediaFrame::Video(VideoFrame {
+        width: h264_config.width,
+        height: h264_config.height,
+        data: (0..h264_config.width * h264_config.height * 3)
+            .map(|i| (i % 251) as u8)
+            .collect(),
+        timestamp: 0,
+        is_keyframe: false,
+        rotation: VideoRotation::Deg0,
+    });
+    group.bench_function("h264_640x480", |b| {
+        b.iter(|| {
+            let encoded = h264.encode_sync(black_box(&video)).unwrap();
+            h264.decode_sync(&encoded).unwrap()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_assembler, bench_codec_round_trips);
+criterion_main!(benches);
@@ -0,0 +1,289 @@
+//! Microphone capture using CPAL
+//!
+//! This module provides access to audio input devices. CPAL input streams are
+//! not `Send`, so capture runs on a blocking worker thread and hands the
+//! recorded samples back as a single [`AudioFrame`].
+
+use crate::error::{MediaError, MediaResult};
+use crate::tracks::AudioFrame;
+use crate::virtual_device;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use quicrtc_core::{DiagnosticsSink, LogLevel};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Audio input device information
+#[derive(Debug, Clone)]
+pub struct AudioInputDevice {
+    /// Device identifier
+    pub id: String,
+
+    /// Human-readable device name
+    pub name: String,
+
+    /// Whether this is the default device
+    pub is_default: bool,
+
+    /// Supported sample rates
+    pub supported_sample_rates: Vec<u32>,
+
+    /// Supported channel counts
+    pub supported_channels: Vec<u8>,
+}
+
+/// Audio capture configuration
+#[derive(Debug, Clone)]
+pub struct AudioCaptureConfig {
+    /// Device name (None for default device)
+    pub device_name: Option<String>,
+
+    /// How long to record for
+    pub duration: Duration,
+}
+
+impl Default for AudioCaptureConfig {
+    fn default() -> Self {
+        Self {
+            device_name: None,
+            duration: Duration::from_secs(3),
+        }
+    }
+}
+
+/// Microphone capture implementation using CPAL
+#[derive(Debug)]
+pub struct CpalAudioCapture {
+    /// Where stream errors are reported
+    diagnostics: Arc<dyn DiagnosticsSink>,
+}
+
+impl Default for CpalAudioCapture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CpalAudioCapture {
+    /// Create a new CPAL audio capture instance
+    pub fn new() -> Self {
+        Self {
+            diagnostics: quicrtc_core::default_sink(),
+        }
+    }
+
+    /// Report stream errors to `sink`
+    ///
+    /// Each error is logged and counted as `audio.capture.stream_errors`.
+    pub fn set_diagnostics_sink(&mut self, sink: Arc<dyn DiagnosticsSink>) {
+        self.diagnostics = sink;
+    }
+
+    /// List available audio input devices
+    pub fn list_devices(&self) -> MediaResult<Vec<AudioInputDevice>> {
+        let host = cpal::default_host();
+
+        let devices = host
+            .input_devices()
+            .map_err(|e| MediaError::DeviceEnumerationFailed {
+                reason: format!("Failed to enumerate input devices: {}", e),
+            })?;
+
+        let default_device_name = host
+            .default_input_device()
+            .and_then(|d| d.name().ok())
+            .unwrap_or_default();
+
+        let mut audio_devices = Vec::new();
+
+        for device in devices {
+            let name = device
+                .name()
+                .unwrap_or_else(|_| "Unknown Device".to_string());
+            let is_default = name == default_device_name;
+
+            let supported_configs = device.supported_input_configs().map_err(|e| {
+                MediaError::DeviceEnumerationFailed {
+                    reason: format!("Failed to get supported configs for {}: {}", name, e),
+                }
+            })?;
+
+            let mut supported_sample_rates = Vec::new();
+            let mut supported_channels = Vec::new();
+
+            for config in supported_configs {
+                let min_rate = config.min_sample_rate().0;
+                let max_rate = config.max_sample_rate().0;
+
+                for &rate in &[8000, 16000, 22050, 44100, 48000, 96000] {
+                    if rate >= min_rate
+                        && rate <= max_rate
+                        && !supported_sample_rates.contains(&rate)
+                    {
+                        supported_sample_rates.push(rate);
+                    }
+                }
+
+                let channels = config.channels() as u8;
+                if !supported_channels.contains(&channels) {
+                    supported_channels.push(channels);
+                }
+            }
+
+            supported_sample_rates.sort();
+            supported_channels.sort();
+
+            audio_devices.push(AudioInputDevice {
+                id: name.clone(),
+                name,
+                is_default,
+                supported_sample_rates,
+                supported_channels,
+            });
+        }
+
+        audio_devices.extend(virtual_device::microphones().into_iter().map(|microphone| {
+            AudioInputDevice {
+                id: microphone.id().to_string(),
+                name: microphone.id().to_string(),
+                is_default: false,
+                supported_sample_rates: vec![microphone.sample_rate()],
+                supported_channels: vec![microphone.channels()],
+            }
+        }));
+
+        Ok(audio_devices)
+    }
+
+    /// Record from the configured input device for `config.duration`
+    ///
+    /// The device's default input format is used; the returned frame carries
+    /// the actual sample rate and channel count of the recording. Virtual
+    /// microphones return their recording straight away.
+    pub async fn record(&self, config: AudioCaptureConfig) -> MediaResult<AudioFrame> {
+        if let Some(microphone) = config
+            .device_name
+            .as_deref()
+            .and_then(virtual_device::microphone)
+        {
+            let frames = (config.duration.as_secs_f64() * microphone.sample_rate() as f64) as usize;
+            return Ok(microphone.read(0, frames));
+        }
+
+        let diagnostics = Arc::clone(&self.diagnostics);
+        tokio::task::spawn_blocking(move || Self::record_blocking(config, diagnostics))
+            .await
+            .map_err(|e| MediaError::Audio {
+                message: format!("Capture worker failed: {}", e),
+            })?
+    }
+
+    /// Blocking capture loop, run on a worker thread
+    fn record_blocking(
+        config: AudioCaptureConfig,
+        diagnostics: Arc<dyn DiagnosticsSink>,
+    ) -> MediaResult<AudioFrame> {
+        let host = cpal::default_host();
+
+        let device = if let Some(device_name) = &config.device_name {
+            host.input_devices()
+                .map_err(|e| MediaError::DeviceEnumerationFailed {
+                    reason: format!("Failed to enumerate input devices: {}", e),
+                })?
+                .find(|d| d.name().unwrap_or_default() == *device_name)
+                .ok_or_else(|| MediaError::DeviceNotFound {
+                    device_id: device_name.clone(),
+                })?
+        } else {
+            host.default_input_device()
+                .ok_or_else(|| MediaError::DeviceNotFound {
+                    device_id: "default input device".to_string(),
+                })?
+        };
+
+        let supported_config =
+            device
+                .default_input_config()
+                .map_err(|e| MediaError::ConfigurationError {
+                    message: format!("Failed to get default input config: {}", e),
+                })?;
+
+        let sample_format = supported_config.sample_format();
+        let stream_config: cpal::StreamConfig = supported_config.into();
+
+        let samples = Arc::new(Mutex::new(Vec::<f32>::new()));
+        let err_fn = move |err| {
+            diagnostics.counter("audio.capture.stream_errors", 1);
+            diagnostics.log(
+                LogLevel::Warn,
+                module_path!(),
+                &format!("Audio capture stream error: {}", err),
+            );
+        };
+
+        let stream = match sample_format {
+            cpal::SampleFormat::F32 => {
+                let samples = samples.clone();
+                device.build_input_stream(
+                    &stream_config,
+                    move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                        samples.lock().unwrap().extend_from_slice(data);
+                    },
+                    err_fn,
+                    None,
+                )
+            }
+            cpal::SampleFormat::I16 => {
+                let samples = samples.clone();
+                device.build_input_stream(
+                    &stream_config,
+                    move |data: &[i16], _: &cpal::InputCallbackInfo| {
+                        samples
+                            .lock()
+                            .unwrap()
+                            .extend(data.iter().map(|&s| s as f32 / i16::MAX as f32));
+                    },
+                    err_fn,
+                    None,
+                )
+            }
+            cpal::SampleFormat::U16 => {
+                let samples = samples.clone();
+                device.build_input_stream(
+                    &stream_config,
+                    move |data: &[u16], _: &cpal::InputCallbackInfo| {
+                        samples.lock().unwrap().extend(
+                            data.iter()
+                                .map(|&s| s as f32 / (u16::MAX as f32 / 2.0) - 1.0),
+                        );
+                    },
+                    err_fn,
+                    None,
+                )
+            }
+            sample_format => {
+                return Err(MediaError::UnsupportedFormat {
+                    format: format!("{:?}", sample_format),
+                });
+            }
+        }
+        .map_err(|e| MediaError::Audio {
+            message: format!("Failed to build input stream: {}", e),
+        })?;
+
+        stream.play().map_err(|e| MediaError::Audio {
+            message: format!("Failed to start input stream: {}", e),
+        })?;
+
+        std::thread::sleep(config.duration);
+        drop(stream);
+
+        let samples = std::mem::take(&mut *samples.lock().unwrap());
+
+        Ok(AudioFrame {
+            samples,
+            sample_rate: stream_config.sample_rate.0,
+            channels: stream_config.channels as u8,
+            timestamp: 0,
+        })
+    }
+}
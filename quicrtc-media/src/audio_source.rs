@@ -0,0 +1,285 @@
+//! Application-provided audio for publishing
+//!
+//! An [`AudioSource`] supplies PCM audio that did not come from a capture
+//! device: synthesized speech, sound effects or a file. [`AudioFramer`]
+//! converts whatever the source produces into the fixed-size frames at the
+//! rate and channel count an encoder expects.
+
+use crate::error::{MediaError, MediaResult};
+use crate::render::remix_channel_count;
+use crate::tracks::AudioFrame;
+use std::collections::VecDeque;
+use std::path::Path;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Length of frames produced by [`AudioSource::from_samples`] and WAV files
+const SOURCE_FRAME_DURATION: Duration = Duration::from_millis(20);
+
+/// Audio supplied by the application
+#[derive(Debug)]
+pub enum AudioSource {
+    /// Frames available up front, e.g. a decoded sound effect
+    Frames(VecDeque<AudioFrame>),
+    /// Frames delivered as they are produced, e.g. by a speech synthesizer;
+    /// the source ends when every sender is dropped
+    Stream(mpsc::Receiver<AudioFrame>),
+}
+
+impl AudioSource {
+    /// Source that plays `frames` in order
+    pub fn from_frames(frames: impl IntoIterator<Item = AudioFrame>) -> Self {
+        Self::Frames(frames.into_iter().collect())
+    }
+
+    /// Source fed through the returned sender
+    ///
+    /// At most `capacity` frames wait to be played; senders wait for room
+    /// beyond that, which paces a producer that runs faster than real time.
+    pub fn channel(capacity: usize) -> (mpsc::Sender<AudioFrame>, Self) {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        (sender, Self::Stream(receiver))
+    }
+
+    /// Source that plays interleaved `samples`
+    pub fn from_samples(samples: &[f32], sample_rate: u32, channels: u8) -> MediaResult<Self> {
+        if sample_rate == 0 || channels == 0 {
+            return Err(MediaError::InvalidConfiguration {
+                message: format!(
+                    "Audio source needs a sample rate and channels, got {} Hz and {} channels",
+                    sample_rate, channels
+                ),
+            });
+        }
+
+        let frame_len = (sample_rate as usize * SOURCE_FRAME_DURATION.as_millis() as usize / 1000)
+            .max(1)
+            * channels as usize;
+        let frames = samples
+            .chunks(frame_len)
+            .enumerate()
+            .map(|(index, chunk)| AudioFrame {
+                samples: chunk.to_vec(),
+                sample_rate,
+                channels,
+                timestamp: index as u64 * SOURCE_FRAME_DURATION.as_millis() as u64,
+            });
+        Ok(Self::from_frames(frames))
+    }
+
+    /// Source that plays a PCM or IEEE float WAV file
+    pub fn from_wav_file(path: impl AsRef<Path>) -> MediaResult<Self> {
+        let data = std::fs::read(path)?;
+        Self::from_wav(&data)
+    }
+
+    /// Source that plays PCM or IEEE float WAV data
+    pub fn from_wav(data: &[u8]) -> MediaResult<Self> {
+        let wav = parse_wav(data)?;
+        Self::from_samples(&wav.samples, wav.sample_rate, wav.channels)
+    }
+
+    /// Next frame to play; `None` once the source is exhausted
+    pub async fn next_frame(&mut self) -> Option<AudioFrame> {
+        match self {
+            Self::Frames(frames) => frames.pop_front(),
+            Self::Stream(receiver) => receiver.recv().await,
+        }
+    }
+}
+
+/// Regroups audio into fixed-size frames for an encoder
+///
+/// Input frames of any length, rate and channel count are remixed and
+/// resampled to the output format, then split into frames of exactly
+/// `frame_duration`.
+#[derive(Debug)]
+pub struct AudioFramer {
+    sample_rate: u32,
+    channels: u8,
+    frame_len: usize,
+    pending: Vec<f32>,
+    frames_emitted: u64,
+    frame_duration: Duration,
+}
+
+impl AudioFramer {
+    /// Produce `frame_duration` frames at `sample_rate` with `channels`
+    pub fn new(sample_rate: u32, channels: u8, frame_duration: Duration) -> Self {
+        let frames_per_channel =
+            (sample_rate as u128 * frame_duration.as_micros() / 1_000_000).max(1) as usize;
+        Self {
+            sample_rate,
+            channels,
+            frame_len: frames_per_channel * channels.max(1) as usize,
+            pending: Vec::new(),
+            frames_emitted: 0,
+            frame_duration,
+        }
+    }
+
+    /// Duration of each output frame
+    pub fn frame_duration(&self) -> Duration {
+        self.frame_duration
+    }
+
+    /// Add audio, returning every complete output frame
+    pub fn push(&mut self, frame: &AudioFrame) -> Vec<AudioFrame> {
+        let remixed = if frame.channels == self.channels {
+            frame.samples.clone()
+        } else {
+            remix_channel_count(&frame.samples, frame.channels, self.channels)
+        };
+        let resampled = resample(&remixed, self.channels, frame.sample_rate, self.sample_rate);
+        self.pending.extend_from_slice(&resampled);
+
+        let mut frames = Vec::new();
+        while self.pending.len() >= self.frame_len {
+            let samples: Vec<f32> = self.pending.drain(..self.frame_len).collect();
+            frames.push(self.output_frame(samples));
+        }
+        frames
+    }
+
+    /// Pad any remaining audio with silence into a final frame
+    pub fn flush(&mut self) -> Option<AudioFrame> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        let mut samples = std::mem::take(&mut self.pending);
+        samples.resize(self.frame_len, 0.0);
+        Some(self.output_frame(samples))
+    }
+
+    fn output_frame(&mut self, samples: Vec<f32>) -> AudioFrame {
+        let timestamp = self.frames_emitted * self.frame_duration.as_millis() as u64;
+        self.frames_emitted += 1;
+        AudioFrame {
+            samples,
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+            timestamp,
+        }
+    }
+}
+
+/// Linearly resample interleaved audio
+///
+/// Adequate for speech and effects; not a substitute for a band-limited
+/// resampler on music.
+pub fn resample(samples: &[f32], channels: u8, from_rate: u32, to_rate: u32) -> Vec<f32> {
+    let channels = channels.max(1) as usize;
+    if from_rate == to_rate || from_rate == 0 || to_rate == 0 || samples.len() < channels {
+        return samples.to_vec();
+    }
+
+    let input_frames = samples.len() / channels;
+    let output_frames = (input_frames as u64 * to_rate as u64 / from_rate as u64) as usize;
+    let step = from_rate as f64 / to_rate as f64;
+    let mut output = Vec::with_capacity(output_frames * channels);
+
+    for frame in 0..output_frames {
+        let position = frame as f64 * step;
+        let index = position as usize;
+        let next = (index + 1).min(input_frames - 1);
+        let fraction = (position - index as f64) as f32;
+        for channel in 0..channels {
+            let a = samples[index * channels + channel];
+            let b = samples[next * channels + channel];
+            output.push(a + (b - a) * fraction);
+        }
+    }
+
+    output
+}
+
+/// Decoded WAV contents
+pub(crate) struct WavData {
+    pub(crate) samples: Vec<f32>,
+    pub(crate) sample_rate: u32,
+    pub(crate) channels: u8,
+}
+
+const WAVE_FORMAT_PCM: u16 = 1;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+pub(crate) fn parse_wav(data: &[u8]) -> MediaResult<WavData> {
+    let invalid = |reason: &str| MediaError::UnsupportedFormat {
+        format: format!("WAV: {}", reason),
+    };
+
+    if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return Err(invalid("missing RIFF/WAVE header"));
+    }
+
+    let mut format = None;
+    let mut samples = None;
+    let mut offset = 12;
+    while offset + 8 <= data.len() {
+        let id = &data[offset..offset + 4];
+        let size = u32::from_le_bytes(data[offset + 4..offset + 8].try_into().unwrap()) as usize;
+        let body = &data[offset + 8..data.len().min(offset + 8 + size)];
+
+        match id {
+            b"fmt " => {
+                if body.len() < 16 {
+                    return Err(invalid("truncated fmt chunk"));
+                }
+                let mut tag = u16::from_le_bytes([body[0], body[1]]);
+                let channels = u16::from_le_bytes([body[2], body[3]]);
+                let sample_rate = u32::from_le_bytes(body[4..8].try_into().unwrap());
+                let bits = u16::from_le_bytes([body[14], body[15]]);
+                // Extensible files carry the real format in the sub-format GUID
+                if tag == WAVE_FORMAT_EXTENSIBLE && body.len() >= 26 {
+                    tag = u16::from_le_bytes([body[24], body[25]]);
+                }
+                format = Some((tag, channels, sample_rate, bits));
+            }
+            b"data" => samples = Some(body),
+            _ => {}
+        }
+
+        // Chunks are padded to an even length
+        offset += 8 + size + (size & 1);
+    }
+
+    let (tag, channels, sample_rate, bits) = format.ok_or_else(|| invalid("missing fmt chunk"))?;
+    let body = samples.ok_or_else(|| invalid("missing data chunk"))?;
+    let channels = u8::try_from(channels)
+        .ok()
+        .filter(|&channels| channels > 0)
+        .ok_or_else(|| invalid(&format!("unsupported channel count {}", channels)))?;
+
+    let samples: Vec<f32> = match (tag, bits) {
+        (WAVE_FORMAT_PCM, 8) => body.iter().map(|&b| (b as f32 - 128.0) / 128.0).collect(),
+        (WAVE_FORMAT_PCM, 16) => body
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0)
+            .collect(),
+        (WAVE_FORMAT_PCM, 24) => body
+            .chunks_exact(3)
+            .map(|b| (i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8) as f32 / 8_388_608.0)
+            .collect(),
+        (WAVE_FORMAT_PCM, 32) => body
+            .chunks_exact(4)
+            .map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2_147_483_648.0)
+            .collect(),
+        (WAVE_FORMAT_IEEE_FLOAT, 32) => body
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+        _ => {
+            return Err(invalid(&format!(
+                "unsupported encoding (format {}, {} bits)",
+                tag, bits
+            )))
+        }
+    };
+
+    Ok(WavData {
+        samples,
+        sample_rate,
+        channels,
+    })
+}
@@ -10,11 +10,24 @@
 //! - Web/WASM: MediaDevices API backend
 
 use crate::error::MediaError;
+use std::sync::mpsc;
 
 /// Cross-platform camera capture using nokhwa
 /// This is the only capture backend we need - nokhwa handles all platforms!
+///
+/// `nokhwa::Camera` isn't `Send`, so an open camera lives on a thread of its
+/// own and frames are requested from it; this keeps the capture types
+/// usable from any task.
 pub struct NokhwaCapture {
-    camera: parking_lot::Mutex<Option<nokhwa::Camera>>,
+    camera: parking_lot::Mutex<Option<CameraThread>>,
+}
+
+/// Thread owning an open camera
+struct CameraThread {
+    /// Frame requests, each answered on the channel it carries. Dropping
+    /// this stops the stream and ends the thread.
+    requests: mpsc::Sender<mpsc::Sender<Option<Vec<u8>>>>,
+    handle: std::thread::JoinHandle<Result<(), MediaError>>,
 }
 
 impl NokhwaCapture {
@@ -43,25 +56,55 @@ impl NokhwaCapture {
 
     /// Start camera capture
     pub fn start_capture(&self) -> Result<(), MediaError> {
-        use nokhwa::{
-            pixel_format::RgbFormat,
-            utils::{CameraIndex, RequestedFormat, RequestedFormatType},
-        };
+        let mut camera_guard = self.camera.lock();
+        if camera_guard.is_some() {
+            return Ok(());
+        }
 
-        let index = CameraIndex::Index(0);
-        let format =
-            RequestedFormat::new::<RgbFormat>(RequestedFormatType::AbsoluteHighestFrameRate);
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let (requests, request_rx) = mpsc::channel::<mpsc::Sender<Option<Vec<u8>>>>();
+        let handle = std::thread::Builder::new()
+            .name("quicrtc-camera".to_string())
+            .spawn(move || {
+                let mut camera = match open_camera() {
+                    Ok(camera) => camera,
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(e));
+                        return Ok(());
+                    }
+                };
+                let _ = ready_tx.send(Ok(()));
+
+                while let Ok(reply) = request_rx.recv() {
+                    let frame = match camera.frame() {
+                        // Convert buffer to raw data - buffer contains the frame data
+                        Ok(buffer) => Some(buffer.buffer().to_vec()),
+                        Err(e) => {
+                            tracing::warn!("Failed to get frame: {}", e);
+                            None
+                        }
+                    };
+                    let _ = reply.send(frame);
+                }
 
-        let mut camera =
-            nokhwa::Camera::new(index, format).map_err(|e| MediaError::DeviceError {
-                message: format!("Failed to create camera: {}", e),
+                camera.stop_stream().map_err(|e| MediaError::DeviceError {
+                    message: format!("Failed to stop camera stream: {}", e),
+                })
+            })
+            .map_err(|e| MediaError::DeviceError {
+                message: format!("Failed to spawn camera thread: {}", e),
             })?;
 
-        camera.open_stream().map_err(|e| MediaError::DeviceError {
-            message: format!("Failed to open camera stream: {}", e),
-        })?;
-
-        *self.camera.lock() = Some(camera);
+        let ready = ready_rx.recv().unwrap_or_else(|_| {
+            Err(MediaError::DeviceError {
+                message: "Camera thread exited before the stream opened".to_string(),
+            })
+        });
+        if let Err(e) = ready {
+            let _ = handle.join();
+            return Err(e);
+        }
+        *camera_guard = Some(CameraThread { requests, handle });
 
         tracing::info!("✅ Nokhwa camera capture started successfully");
         Ok(())
@@ -69,11 +112,12 @@ impl NokhwaCapture {
 
     /// Stop camera capture
     pub fn stop_capture(&self) -> Result<(), MediaError> {
-        let mut camera_guard = self.camera.lock();
-        if let Some(mut camera) = camera_guard.take() {
-            camera.stop_stream().map_err(|e| MediaError::DeviceError {
-                message: format!("Failed to stop camera stream: {}", e),
-            })?;
+        let camera = self.camera.lock().take();
+        if let Some(CameraThread { requests, handle }) = camera {
+            drop(requests);
+            handle.join().map_err(|_| MediaError::DeviceError {
+                message: "Camera thread panicked".to_string(),
+            })??;
         }
 
         tracing::info!("📷 Nokhwa camera capture stopped");
@@ -82,22 +126,16 @@ impl NokhwaCapture {
 
     /// Get a frame from the camera
     pub fn get_frame(&self) -> Result<Option<Vec<u8>>, MediaError> {
-        let mut camera_guard = self.camera.lock();
-        if let Some(camera) = camera_guard.as_mut() {
-            match camera.frame() {
-                Ok(buffer) => {
-                    // Convert buffer to raw data - buffer contains the frame data
-                    let raw_data = buffer.buffer().to_vec();
-                    Ok(Some(raw_data))
-                }
-                Err(e) => {
-                    tracing::warn!("Failed to get frame: {}", e);
-                    Ok(None)
-                }
-            }
-        } else {
-            Ok(None)
+        let camera_guard = self.camera.lock();
+        let Some(camera) = camera_guard.as_ref() else {
+            return Ok(None);
+        };
+
+        let (reply_tx, reply_rx) = mpsc::channel();
+        if camera.requests.send(reply_tx).is_err() {
+            return Ok(None);
         }
+        Ok(reply_rx.recv().ok().flatten())
     }
 
     /// Check if camera is currently capturing
@@ -106,6 +144,35 @@ impl NokhwaCapture {
     }
 }
 
+impl Drop for NokhwaCapture {
+    fn drop(&mut self) {
+        if self.camera.get_mut().is_some() {
+            let _ = self.stop_capture();
+        }
+    }
+}
+
+/// Open the first camera and start its stream
+fn open_camera() -> Result<nokhwa::Camera, MediaError> {
+    use nokhwa::{
+        pixel_format::RgbFormat,
+        utils::{CameraIndex, RequestedFormat, RequestedFormatType},
+    };
+
+    let index = CameraIndex::Index(0);
+    let format = RequestedFormat::new::<RgbFormat>(RequestedFormatType::AbsoluteHighestFrameRate);
+
+    let mut camera = nokhwa::Camera::new(index, format).map_err(|e| MediaError::DeviceError {
+        message: format!("Failed to create camera: {}", e),
+    })?;
+
+    camera.open_stream().map_err(|e| MediaError::DeviceError {
+        message: format!("Failed to open camera stream: {}", e),
+    })?;
+
+    Ok(camera)
+}
+
 /// Get the cross-platform capture backend
 /// Much simpler now - just return NokhwaCapture for all platforms!
 pub fn get_capture_backend() -> NokhwaCapture {
@@ -4,26 +4,32 @@
 //! codec implementations with proper thread safety and performance.
 
 use crate::tracks::{AudioFrame, MediaFrame, VideoFrame};
-use quicrtc_core::QuicRtcError;
+use quicrtc_core::{ChannelLayout, MoqTrackType, QuicRtcError, VideoContentHint, VideoRotation};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 // Real codec implementations - these will be feature-gated
 #[cfg(feature = "opus")]
 use audiopus::{
     coder::{Decoder as OpusDecoder, Encoder as OpusEncoder},
-    Application, Channels, SampleRate,
+    Application, Bitrate, Channels, SampleRate,
 };
 
 #[cfg(feature = "h264")]
 use openh264::{
     decoder::{DecodedYUV, Decoder as H264Decoder},
-    encoder::Encoder as H264Encoder,
+    encoder::{
+        BitRate, Encoder as H264Encoder, EncoderConfig, FrameRate, IntraFramePeriod,
+        RateControlMode, UsageType,
+    },
     formats::YUVBuffer,
+    OpenH264API,
 };
 
 /// Video quality presets for easy configuration
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum VideoQuality {
     /// 320x240, 15fps, optimized for poor networks
     Low,
@@ -41,6 +47,18 @@ impl Default for VideoQuality {
     }
 }
 
+impl VideoQuality {
+    /// Frame size of the preset as (width, height)
+    pub fn dimensions(&self) -> (u32, u32) {
+        match self {
+            VideoQuality::Low => (320, 240),
+            VideoQuality::Standard => (640, 480),
+            VideoQuality::HD => (1280, 720),
+            VideoQuality::FullHD => (1920, 1080),
+        }
+    }
+}
+
 /// Codec information
 #[derive(Debug, Clone)]
 pub struct CodecInfo {
@@ -54,6 +72,20 @@ pub struct CodecInfo {
     pub channels: Option<u8>,
 }
 
+impl CodecInfo {
+    /// Media kind derived from the MIME type's top-level type
+    pub fn track_type(&self) -> Option<MoqTrackType> {
+        let (top_level, _) = self.mime_type.split_once('/')?;
+        if top_level.eq_ignore_ascii_case("audio") {
+            Some(MoqTrackType::Audio)
+        } else if top_level.eq_ignore_ascii_case("video") {
+            Some(MoqTrackType::Video)
+        } else {
+            None
+        }
+    }
+}
+
 /// Result type for codec operations
 pub type CodecResult<T> = Result<T, QuicRtcError>;
 
@@ -151,17 +183,31 @@ pub struct OpusCodec {
     _placeholder: (),
 }
 
+/// What the Opus encoder tunes its coding for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OpusApplication {
+    /// Intelligible speech for calls
+    #[default]
+    Voip,
+    /// Faithful reproduction of music and other non-speech audio
+    Audio,
+    /// Lowest latency, for live performance over the network
+    LowDelay,
+}
+
 /// Opus codec configuration
 #[derive(Debug, Clone)]
 pub struct OpusConfig {
     /// Sample rate in Hz
     pub sample_rate: u32,
-    /// Number of audio channels
+    /// Number of audio channels (1, 2, 4 or 6; see [`ChannelLayout`])
     pub channels: u8,
     /// Bitrate in bits per second
     pub bitrate: u32,
     /// Frame duration in milliseconds
     pub frame_duration_ms: u32,
+    /// What the encoder tunes its coding for
+    pub application: OpusApplication,
 }
 
 impl Default for OpusConfig {
@@ -171,10 +217,18 @@ impl Default for OpusConfig {
             channels: 2,
             bitrate: 64000,
             frame_duration_ms: 20,
+            application: OpusApplication::Voip,
         }
     }
 }
 
+impl OpusConfig {
+    /// Channel layout implied by the channel count
+    pub fn channel_layout(&self) -> Option<ChannelLayout> {
+        ChannelLayout::from_channel_count(self.channels)
+    }
+}
+
 impl OpusCodec {
     /// Create new Opus codec with default settings
     pub fn new() -> CodecResult<Self> {
@@ -193,10 +247,10 @@ impl OpusCodec {
             });
         }
 
-        if config.channels != 1 && config.channels != 2 {
+        if config.channel_layout().is_none() {
             return Err(QuicRtcError::InvalidData {
                 reason: format!(
-                    "Unsupported channel count: {}. Opus supports 1 or 2 channels",
+                    "Unsupported channel count: {}. Supported: 1 (mono), 2 (stereo), 4 (quad), 6 (5.1)",
                     config.channels
                 ),
             });
@@ -336,32 +390,7 @@ impl Clone for OpusCodec {
 #[cfg(feature = "opus")]
 impl OpusCodec {
     fn encode_with_audiopus(&self, audio_frame: &AudioFrame) -> CodecResult<Vec<u8>> {
-        // Create encoder with proper configuration
-        let sample_rate = match self.config.sample_rate {
-            8000 => SampleRate::Hz8000,
-            12000 => SampleRate::Hz12000,
-            16000 => SampleRate::Hz16000,
-            24000 => SampleRate::Hz24000,
-            48000 => SampleRate::Hz48000,
-            _ => {
-                return Err(QuicRtcError::InvalidData {
-                    reason: format!("Unsupported sample rate: {}", self.config.sample_rate),
-                })
-            }
-        };
-
-        let channels = if self.config.channels == 1 {
-            Channels::Mono
-        } else {
-            Channels::Stereo
-        };
-
-        let mut encoder =
-            OpusEncoder::new(sample_rate, channels, Application::Voip).map_err(|e| {
-                QuicRtcError::EncodingFailed {
-                    reason: format!("Failed to create Opus encoder: {:?}", e),
-                }
-            })?;
+        let sample_rate = self.audiopus_sample_rate()?;
 
         // Validate input
         if audio_frame.sample_rate != self.config.sample_rate {
@@ -389,71 +418,213 @@ impl OpusCodec {
             .map(|&s| (s.clamp(-1.0, 1.0) * 32767.0) as i16)
             .collect();
 
-        // Encode
-        let mut output = vec![0u8; 4000]; // Max Opus frame size
-        let encoded_size = encoder.encode(&samples_i16, &mut output).map_err(|e| {
-            QuicRtcError::EncodingFailed {
-                reason: format!("Opus encoding failed: {:?}", e),
-            }
-        })?;
+        if self.config.channels <= 2 {
+            return self.encode_stream(sample_rate, self.config.channels, &samples_i16);
+        }
+
+        // Multichannel: one Opus stream per coupled pair / mono channel,
+        // packed as [stream count][u16 length][packet]...
+        let channels = self.config.channels as usize;
+        let frames = samples_i16.len() / channels;
+        let mapping = opus_stream_mapping(self.channel_layout());
+
+        let mut output = vec![mapping.len() as u8];
+        for stream in mapping {
+            let stream_samples: Vec<i16> = (0..frames)
+                .flat_map(|f| stream.iter().map(move |&ch| f * channels + ch))
+                .map(|idx| samples_i16[idx])
+                .collect();
+
+            let packet = self.encode_stream(sample_rate, stream.len() as u8, &stream_samples)?;
+            output.extend_from_slice(&(packet.len() as u16).to_be_bytes());
+            output.extend_from_slice(&packet);
+        }
 
-        output.truncate(encoded_size);
         Ok(output)
     }
 
     fn decode_with_audiopus(&self, data: &[u8]) -> CodecResult<MediaFrame> {
-        // Create decoder with proper configuration
-        let sample_rate = match self.config.sample_rate {
-            8000 => SampleRate::Hz8000,
-            12000 => SampleRate::Hz12000,
-            16000 => SampleRate::Hz16000,
-            24000 => SampleRate::Hz24000,
-            48000 => SampleRate::Hz48000,
-            _ => {
-                return Err(QuicRtcError::InvalidData {
-                    reason: format!("Unsupported sample rate: {}", self.config.sample_rate),
-                })
+        let sample_rate = self.audiopus_sample_rate()?;
+        let samples_per_frame = self.samples_per_frame();
+
+        let samples_i16 = if self.config.channels <= 2 {
+            Self::decode_stream(sample_rate, self.config.channels, samples_per_frame, data)?
+        } else {
+            let channels = self.config.channels as usize;
+            let mapping = opus_stream_mapping(self.channel_layout());
+
+            if data.is_empty() {
+                return Err(QuicRtcError::DecodingFailed {
+                    reason: "Empty multichannel Opus packet".to_string(),
+                });
+            }
+            if data[0] as usize != mapping.len() {
+                return Err(QuicRtcError::DecodingFailed {
+                    reason: format!(
+                        "Opus stream count mismatch: expected {}, got {}",
+                        mapping.len(),
+                        data[0]
+                    ),
+                });
             }
+
+            let mut interleaved = vec![0i16; samples_per_frame * channels];
+            let mut frames = samples_per_frame;
+            let mut offset = 1;
+
+            for stream in mapping {
+                if offset + 2 > data.len() {
+                    return Err(QuicRtcError::DecodingFailed {
+                        reason: "Truncated multichannel Opus packet".to_string(),
+                    });
+                }
+                let len = u16::from_be_bytes([data[offset], data[offset + 1]]) as usize;
+                offset += 2;
+                if offset + len > data.len() {
+                    return Err(QuicRtcError::DecodingFailed {
+                        reason: "Truncated multichannel Opus packet".to_string(),
+                    });
+                }
+
+                let decoded = Self::decode_stream(
+                    sample_rate,
+                    stream.len() as u8,
+                    samples_per_frame,
+                    &data[offset..offset + len],
+                )?;
+                offset += len;
+
+                frames = frames.min(decoded.len() / stream.len());
+                for (i, chunk) in decoded.chunks_exact(stream.len()).enumerate() {
+                    for (&ch, &sample) in stream.iter().zip(chunk) {
+                        interleaved[i * channels + ch] = sample;
+                    }
+                }
+            }
+
+            interleaved.truncate(frames * channels);
+            interleaved
         };
 
-        let channels = if self.config.channels == 1 {
+        // Convert back to f32
+        let samples: Vec<f32> = samples_i16.iter().map(|&s| s as f32 / 32767.0).collect();
+
+        Ok(MediaFrame::Audio(AudioFrame {
+            samples,
+            sample_rate: self.config.sample_rate,
+            channels: self.config.channels,
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+        }))
+    }
+
+    fn audiopus_sample_rate(&self) -> CodecResult<SampleRate> {
+        match self.config.sample_rate {
+            8000 => Ok(SampleRate::Hz8000),
+            12000 => Ok(SampleRate::Hz12000),
+            16000 => Ok(SampleRate::Hz16000),
+            24000 => Ok(SampleRate::Hz24000),
+            48000 => Ok(SampleRate::Hz48000),
+            _ => Err(QuicRtcError::InvalidData {
+                reason: format!("Unsupported sample rate: {}", self.config.sample_rate),
+            }),
+        }
+    }
+
+    /// Encode a single mono or stereo Opus stream
+    ///
+    /// Streams of a multichannel layout share the bitrate by channel count.
+    fn encode_stream(
+        &self,
+        sample_rate: SampleRate,
+        channels: u8,
+        samples: &[i16],
+    ) -> CodecResult<Vec<u8>> {
+        let bitrate =
+            u64::from(self.config.bitrate) * u64::from(channels) / u64::from(self.config.channels);
+        let channels = if channels == 1 {
             Channels::Mono
         } else {
             Channels::Stereo
         };
+        let application = match self.config.application {
+            OpusApplication::Voip => Application::Voip,
+            OpusApplication::Audio => Application::Audio,
+            OpusApplication::LowDelay => Application::LowDelay,
+        };
 
-        let mut decoder =
-            OpusDecoder::new(sample_rate, channels).map_err(|e| QuicRtcError::DecodingFailed {
-                reason: format!("Failed to create Opus decoder: {:?}", e),
+        let mut encoder = OpusEncoder::new(sample_rate, channels, application).map_err(|e| {
+            QuicRtcError::EncodingFailed {
+                reason: format!("Failed to create Opus encoder: {:?}", e),
+            }
+        })?;
+        encoder
+            .set_bitrate(Bitrate::BitsPerSecond(bitrate.min(i32::MAX as u64) as i32))
+            .map_err(|e| QuicRtcError::EncodingFailed {
+                reason: format!("Failed to set Opus bitrate: {:?}", e),
             })?;
 
-        // Decode
-        let samples_per_frame = self.samples_per_frame();
-        let total_samples = samples_per_frame * self.config.channels as usize;
-        let mut samples_i16 = vec![0i16; total_samples];
+        let mut output = vec![0u8; 4000]; // Max Opus frame size
+        let encoded_size =
+            encoder
+                .encode(samples, &mut output)
+                .map_err(|e| QuicRtcError::EncodingFailed {
+                    reason: format!("Opus encoding failed: {:?}", e),
+                })?;
+
+        output.truncate(encoded_size);
+        Ok(output)
+    }
 
+    /// Decode a single mono or stereo Opus stream into interleaved samples
+    fn decode_stream(
+        sample_rate: SampleRate,
+        channels: u8,
+        samples_per_frame: usize,
+        data: &[u8],
+    ) -> CodecResult<Vec<i16>> {
+        let opus_channels = if channels == 1 {
+            Channels::Mono
+        } else {
+            Channels::Stereo
+        };
+
+        let mut decoder = OpusDecoder::new(sample_rate, opus_channels).map_err(|e| {
+            QuicRtcError::DecodingFailed {
+                reason: format!("Failed to create Opus decoder: {:?}", e),
+            }
+        })?;
+
+        let mut samples_i16 = vec![0i16; samples_per_frame * channels as usize];
         let decoded_samples = decoder
             .decode(Some(data), &mut samples_i16, false)
             .map_err(|e| QuicRtcError::DecodingFailed {
                 reason: format!("Opus decoding failed: {:?}", e),
             })?;
 
-        // Convert back to f32
-        let actual_samples = decoded_samples * self.config.channels as usize;
-        let samples: Vec<f32> = samples_i16[..actual_samples]
-            .iter()
-            .map(|&s| s as f32 / 32767.0)
-            .collect();
+        samples_i16.truncate(decoded_samples * channels as usize);
+        Ok(samples_i16)
+    }
 
-        Ok(MediaFrame::Audio(AudioFrame {
-            samples,
-            sample_rate: self.config.sample_rate,
-            channels: self.config.channels,
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_millis() as u64,
-        }))
+    fn channel_layout(&self) -> ChannelLayout {
+        // Validated in `with_config`
+        self.config.channel_layout().unwrap_or_default()
+    }
+}
+
+/// Opus multistream channel mapping: coupled pairs first, then mono streams
+///
+/// Each entry lists the interleaved channel indices carried by one stream.
+#[cfg(feature = "opus")]
+fn opus_stream_mapping(layout: ChannelLayout) -> &'static [&'static [usize]] {
+    match layout {
+        ChannelLayout::Mono => &[&[0]],
+        ChannelLayout::Stereo => &[&[0, 1]],
+        ChannelLayout::Quad => &[&[0, 1], &[2, 3]],
+        // FL/FR and RL/RR coupled, C and LFE as mono streams
+        ChannelLayout::Surround5_1 => &[&[0, 1], &[4, 5], &[2], &[3]],
     }
 }
 
@@ -523,6 +694,15 @@ pub struct H264Config {
     pub bitrate: u32,
     /// Frame rate in frames per second
     pub framerate: u32,
+    /// Content hint used to tune rate control and keyframe spacing
+    pub content_hint: VideoContentHint,
+    /// Frames between keyframes; `None` derives it from the content hint
+    pub keyframe_interval_frames: Option<u32>,
+    /// Longest time between keyframes, capping the keyframe interval
+    pub max_gop_duration: Option<Duration>,
+    /// Largest encoded slice in bytes; `None` encodes each frame as one
+    /// slice
+    pub max_slice_size: Option<u32>,
 }
 
 impl Default for H264Config {
@@ -532,10 +712,41 @@ impl Default for H264Config {
             height: 480,
             bitrate: 1_000_000,
             framerate: 30,
+            content_hint: VideoContentHint::Motion,
+            keyframe_interval_frames: None,
+            max_gop_duration: None,
+            max_slice_size: None,
         }
     }
 }
 
+impl H264Config {
+    /// Keyframe interval in frames
+    ///
+    /// Without an explicit interval, motion content gets a keyframe every 2
+    /// seconds for quick recovery; mostly static detail and text content
+    /// changes rarely, so keyframes are spaced out to spend the bits on
+    /// sharpness instead. `max_gop_duration` caps the result either way.
+    pub fn keyframe_interval(&self) -> u32 {
+        let interval = self.keyframe_interval_frames.unwrap_or_else(|| {
+            let seconds = match self.content_hint {
+                VideoContentHint::Motion => 2,
+                VideoContentHint::Detail => 5,
+                VideoContentHint::Text => 10,
+            };
+            self.framerate * seconds
+        });
+        let interval = match self.max_gop_duration {
+            Some(max) => {
+                let max_frames = (max.as_secs_f64() * f64::from(self.framerate)) as u32;
+                interval.min(max_frames)
+            }
+            None => interval,
+        };
+        interval.max(1)
+    }
+}
+
 impl H264Codec {
     /// Create a new H.264 codec with default configuration
     pub fn new() -> CodecResult<Self> {
@@ -562,15 +773,22 @@ impl H264Codec {
             });
         }
 
-        // Create encoder with simple configuration
-        let mut encoder = H264Encoder::new().map_err(|e| QuicRtcError::EncodingFailed {
-            reason: format!("Failed to create H.264 encoder: {}", e),
-        })?;
+        // Create encoder tuned for the configured content hint
+        let mut encoder =
+            H264Encoder::with_api_config(OpenH264API::from_source(), self.encoder_config())
+                .map_err(|e| QuicRtcError::EncodingFailed {
+                    reason: format!("Failed to create H.264 encoder: {}", e),
+                })?;
 
         // For now, create a mock YUV buffer from our VideoFrame
         // This is a simplified approach that we can improve later
         let yuv_data = self.convert_video_frame_to_yuv(video_frame)?;
 
+        // Keyframe requests ride on the frame, see MediaProcessor::request_keyframe
+        if video_frame.is_keyframe {
+            encoder.force_intra_frame();
+        }
+
         // Encode the YUV data
         let bitstream = encoder
             .encode(&yuv_data)
@@ -581,6 +799,31 @@ impl H264Codec {
         Ok(bitstream.to_vec())
     }
 
+    /// Build the openh264 encoder configuration from the codec config
+    #[cfg(feature = "h264")]
+    fn encoder_config(&self) -> EncoderConfig {
+        let (rate_control, usage) = match self.config.content_hint {
+            VideoContentHint::Motion => (RateControlMode::Bitrate, UsageType::CameraVideoRealTime),
+            VideoContentHint::Detail => (RateControlMode::Quality, UsageType::CameraVideoRealTime),
+            VideoContentHint::Text => (RateControlMode::Quality, UsageType::ScreenContentRealTime),
+        };
+
+        let config = EncoderConfig::new()
+            .bitrate(BitRate::from_bps(self.config.bitrate))
+            .max_frame_rate(FrameRate::from_hz(self.config.framerate as f32))
+            .rate_control_mode(rate_control)
+            .usage_type(usage)
+            .intra_frame_period(IntraFramePeriod::from_num_frames(
+                self.config.keyframe_interval(),
+            ))
+            // Static content would rather drop frames than lose sharpness
+            .skip_frames(self.config.content_hint != VideoContentHint::Motion);
+        match self.config.max_slice_size {
+            Some(size) => config.max_slice_len(size),
+            None => config,
+        }
+    }
+
     #[cfg(feature = "h264")]
     fn decode_with_openh264(&self, encoded_data: &[u8]) -> CodecResult<VideoFrame> {
         // Create decoder
@@ -658,6 +901,7 @@ impl H264Codec {
                 .unwrap()
                 .as_millis() as u64,
             is_keyframe: true,
+            rotation: VideoRotation::Deg0,
         })
     }
 
@@ -730,6 +974,7 @@ impl H264Codec {
                 .unwrap()
                 .as_millis() as u64,
             is_keyframe: true,
+            rotation: VideoRotation::Deg0,
         })
     }
 }
@@ -771,6 +1016,12 @@ impl SyncEncoder for H264Codec {
         if let Some(framerate) = config.framerate {
             self.config.framerate = framerate;
         }
+        if let Some(interval) = config.keyframe_interval {
+            self.config.keyframe_interval_frames = Some(interval);
+        }
+        if let Some(duration) = config.max_gop_duration {
+            self.config.max_gop_duration = Some(duration);
+        }
         Ok(())
     }
 }
@@ -820,9 +1071,17 @@ impl Clone for H264Codec {
 }
 
 /// Codec registry for dynamic codec selection
-#[derive(Debug)]
+///
+/// Codecs are kept in preference order: registration order, adjusted with
+/// [`CodecRegistry::prefer_codec`]. Publishing encodes with the most
+/// preferred codec of each media kind and subscribing negotiates against
+/// the remote participant's advertised codecs, so externally implemented
+/// codecs work without changes to the pipeline.
+#[derive(Debug, Clone)]
 pub struct CodecRegistry {
     codecs: HashMap<String, Arc<dyn Codec>>,
+    /// Codec names, most preferred first
+    order: Vec<String>,
 }
 
 impl CodecRegistry {
@@ -830,6 +1089,7 @@ impl CodecRegistry {
     pub fn new() -> Self {
         Self {
             codecs: HashMap::new(),
+            order: Vec::new(),
         }
     }
 
@@ -847,8 +1107,27 @@ impl CodecRegistry {
     }
 
     /// Register a codec
+    ///
+    /// New codecs go to the end of the preference order; replacing an
+    /// existing codec keeps its position.
     pub fn register_codec(&mut self, name: &str, codec: Arc<dyn Codec>) -> CodecResult<()> {
-        self.codecs.insert(name.to_string(), codec);
+        if self.codecs.insert(name.to_string(), codec).is_none() {
+            self.order.push(name.to_string());
+        }
+        Ok(())
+    }
+
+    /// Move a registered codec to the front of the preference order
+    pub fn prefer_codec(&mut self, name: &str) -> CodecResult<()> {
+        let position = self
+            .order
+            .iter()
+            .position(|registered| registered == name)
+            .ok_or_else(|| QuicRtcError::UnsupportedCodec {
+                codec: name.to_string(),
+            })?;
+        let name = self.order.remove(position);
+        self.order.insert(0, name);
         Ok(())
     }
 
@@ -857,9 +1136,42 @@ impl CodecRegistry {
         self.codecs.get(name).cloned()
     }
 
-    /// List available codecs
+    /// List available codecs, most preferred first
     pub fn list_codecs(&self) -> Vec<String> {
-        self.codecs.keys().cloned().collect()
+        self.order.clone()
+    }
+
+    /// List codecs for one media kind, most preferred first
+    pub fn codecs_for(&self, track_type: &MoqTrackType) -> Vec<String> {
+        self.order
+            .iter()
+            .filter(|name| self.codec_track_type(name).as_ref() == Some(track_type))
+            .cloned()
+            .collect()
+    }
+
+    /// Most preferred codec for a media kind
+    pub fn preferred_codec(&self, track_type: &MoqTrackType) -> Option<String> {
+        self.codecs_for(track_type).into_iter().next()
+    }
+
+    /// Pick the most preferred local codec of a media kind that the remote also supports
+    ///
+    /// `remote` entries match a codec by registered name or MIME type,
+    /// ignoring case.
+    pub fn negotiate(&self, track_type: &MoqTrackType, remote: &[String]) -> Option<String> {
+        self.codecs_for(track_type).into_iter().find(|name| {
+            let mime_type = SyncEncoder::get_codec_info(self.codecs[name].as_ref()).mime_type;
+            remote.iter().any(|offered| {
+                offered.eq_ignore_ascii_case(name) || offered.eq_ignore_ascii_case(&mime_type)
+            })
+        })
+    }
+
+    /// Media kind of a registered codec
+    fn codec_track_type(&self, name: &str) -> Option<MoqTrackType> {
+        let codec = self.codecs.get(name)?;
+        SyncEncoder::get_codec_info(codec.as_ref()).track_type()
     }
 
     /// Get codec by MIME type
@@ -896,6 +1208,10 @@ pub struct CodecConfig {
     pub height: Option<u32>,
     /// Framerate (for video)
     pub framerate: Option<u32>,
+    /// Frames between keyframes (for video)
+    pub keyframe_interval: Option<u32>,
+    /// Longest time between keyframes (for video)
+    pub max_gop_duration: Option<Duration>,
 }
 
 impl CodecConfig {
@@ -909,6 +1225,8 @@ impl CodecConfig {
             width: None,
             height: None,
             framerate: None,
+            keyframe_interval: None,
+            max_gop_duration: None,
         }
     }
 
@@ -943,6 +1261,18 @@ impl CodecConfig {
         self
     }
 
+    /// Set keyframe interval in frames
+    pub fn keyframe_interval(mut self, frames: u32) -> Self {
+        self.keyframe_interval = Some(frames);
+        self
+    }
+
+    /// Set the longest time between keyframes
+    pub fn max_gop_duration(mut self, duration: Duration) -> Self {
+        self.max_gop_duration = Some(duration);
+        self
+    }
+
     /// Create codec from this configuration
     pub fn build(&self) -> CodecResult<Arc<dyn Codec>> {
         match self.name.as_str() {
@@ -952,6 +1282,7 @@ impl CodecConfig {
                     channels: self.channels.unwrap_or(2),
                     bitrate: self.bitrate.unwrap_or(64000),
                     frame_duration_ms: 20,
+                    application: OpusApplication::default(),
                 };
                 Ok(Arc::new(OpusCodec::with_config(opus_config)?))
             }
@@ -961,6 +1292,10 @@ impl CodecConfig {
                     height: self.height.unwrap_or(480),
                     bitrate: self.bitrate.unwrap_or(1_000_000),
                     framerate: self.framerate.unwrap_or(30),
+                    content_hint: VideoContentHint::Motion,
+                    keyframe_interval_frames: self.keyframe_interval,
+                    max_gop_duration: self.max_gop_duration,
+                    max_slice_size: None,
                 };
                 Ok(Arc::new(H264Codec::with_config(h264_config)?))
             }
@@ -1033,6 +1368,89 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_h264_content_hint_keyframe_interval() {
+        let mut config = H264Config::default();
+        assert_eq!(config.content_hint, VideoContentHint::Motion);
+        assert_eq!(config.keyframe_interval(), 60);
+
+        config.content_hint = VideoContentHint::Text;
+        config.framerate = 5;
+        assert_eq!(config.keyframe_interval(), 50);
+
+        // An explicit interval wins over the hint, and the GOP duration caps it
+        config.keyframe_interval_frames = Some(30);
+        assert_eq!(config.keyframe_interval(), 30);
+        config.max_gop_duration = Some(Duration::from_secs(2));
+        assert_eq!(config.keyframe_interval(), 10);
+
+        // Encoding still works with content-tuned settings
+        let codec = H264Codec::with_config(config).unwrap();
+        let frame = MediaFrame::Video(VideoFrame {
+            width: 640,
+            height: 480,
+            data: vec![200; 640 * 480 * 3],
+            timestamp: 0,
+            is_keyframe: true,
+            rotation: VideoRotation::Deg0,
+        });
+        assert!(codec.encode_sync(&frame).is_ok());
+    }
+
+    #[test]
+    fn test_opus_multichannel_layouts() {
+        for channels in [1u8, 2, 4, 6] {
+            let config = OpusConfig {
+                channels,
+                ..OpusConfig::default()
+            };
+            assert!(OpusCodec::with_config(config).is_ok());
+        }
+
+        let config = OpusConfig {
+            channels: 3,
+            ..OpusConfig::default()
+        };
+        assert!(OpusCodec::with_config(config).is_err());
+
+        // 5.1 round trip keeps all six channels
+        let opus = OpusCodec::with_config(OpusConfig {
+            channels: 6,
+            ..OpusConfig::default()
+        })
+        .unwrap();
+        let samples = vec![0.05f32; opus.samples_per_frame() * 6];
+        let frame = MediaFrame::Audio(AudioFrame {
+            samples,
+            sample_rate: 48000,
+            channels: 6,
+            timestamp: 0,
+        });
+
+        let encoded = opus.encode_sync(&frame).unwrap();
+        match opus.decode_sync(&encoded).unwrap() {
+            MediaFrame::Audio(decoded) => {
+                assert_eq!(decoded.channels, 6);
+                assert_eq!(decoded.samples.len() % 6, 0);
+            }
+            _ => panic!("Expected audio frame"),
+        }
+    }
+
+    #[cfg(feature = "opus")]
+    #[test]
+    fn test_opus_multichannel_rejects_empty_packet() {
+        let opus = OpusCodec::with_config(OpusConfig {
+            channels: 6,
+            ..OpusConfig::default()
+        })
+        .unwrap();
+        assert!(matches!(
+            opus.decode_with_audiopus(&[]),
+            Err(QuicRtcError::DecodingFailed { .. })
+        ));
+    }
+
     #[test]
     fn test_h264_codec_real_implementation() {
         // Test that H.264 codec is using real openh264 library, not placeholder
@@ -1045,6 +1463,7 @@ mod tests {
             data: vec![128; 640 * 480 * 3], // Gray frame in RGB
             timestamp: 12345,
             is_keyframe: true,
+            rotation: VideoRotation::Deg0,
         };
 
         let media_frame = MediaFrame::Video(video_frame.clone());
@@ -1125,4 +1544,96 @@ mod tests {
             "H.264"
         );
     }
+
+    /// Stand-in for a codec implemented outside this crate
+    #[derive(Debug, Clone)]
+    struct ExternalCodec {
+        name: &'static str,
+        mime_type: &'static str,
+    }
+
+    impl SyncEncoder for ExternalCodec {
+        fn encode_sync(&self, _frame: &MediaFrame) -> CodecResult<Vec<u8>> {
+            Ok(self.name.as_bytes().to_vec())
+        }
+
+        fn get_codec_info(&self) -> CodecInfo {
+            CodecInfo {
+                name: self.name.to_string(),
+                mime_type: self.mime_type.to_string(),
+                sample_rate: None,
+                channels: None,
+            }
+        }
+    }
+
+    impl SyncDecoder for ExternalCodec {
+        fn decode_sync(&self, _data: &[u8]) -> CodecResult<MediaFrame> {
+            Err(QuicRtcError::DecodingFailed {
+                reason: "not implemented".to_string(),
+            })
+        }
+
+        fn get_codec_info(&self) -> CodecInfo {
+            SyncEncoder::get_codec_info(self)
+        }
+    }
+
+    impl Codec for ExternalCodec {
+        fn clone_codec(&self) -> Box<dyn Codec> {
+            Box::new(self.clone())
+        }
+
+        fn clone_encoder(&self) -> Box<dyn SyncEncoder> {
+            Box::new(self.clone())
+        }
+
+        fn clone_decoder(&self) -> Box<dyn SyncDecoder> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[test]
+    fn test_codec_registry_negotiates_external_codecs() {
+        let mut registry = CodecRegistry::with_defaults().unwrap();
+        registry
+            .register_codec(
+                "h265",
+                Arc::new(ExternalCodec {
+                    name: "h265",
+                    mime_type: "video/H265",
+                }),
+            )
+            .unwrap();
+
+        // Registration order is the preference order, per media kind
+        assert_eq!(registry.list_codecs(), vec!["opus", "h264", "h265"]);
+        assert_eq!(
+            registry.codecs_for(&MoqTrackType::Video),
+            vec!["h264", "h265"]
+        );
+        assert_eq!(
+            registry.preferred_codec(&MoqTrackType::Audio),
+            Some("opus".to_string())
+        );
+
+        registry.prefer_codec("h265").unwrap();
+        assert_eq!(
+            registry.preferred_codec(&MoqTrackType::Video),
+            Some("h265".to_string())
+        );
+        assert!(registry.prefer_codec("vp9").is_err());
+
+        // Remote codecs match by name or MIME type
+        let remote = vec!["video/h265".to_string(), "h264".to_string()];
+        assert_eq!(
+            registry.negotiate(&MoqTrackType::Video, &remote),
+            Some("h265".to_string())
+        );
+        assert_eq!(
+            registry.negotiate(&MoqTrackType::Video, &["H264".to_string()]),
+            Some("h264".to_string())
+        );
+        assert_eq!(registry.negotiate(&MoqTrackType::Audio, &remote), None);
+    }
 }
@@ -0,0 +1,372 @@
+//! Video composition
+//!
+//! A [`VideoCompositor`] keeps the latest frame from each of several video
+//! sources and draws them into one I420 frame using a [`CompositionLayout`].
+//! The composed frame can be rendered like any other frame or encoded and
+//! published as a track of its own, e.g. for recording or RTMP egress.
+
+use crate::image_ops::PixelOps;
+use crate::tracks::VideoFrame;
+use quicrtc_core::VideoRotation;
+
+/// Luma of black in limited-range I420
+const BLACK_LUMA: u8 = 16;
+/// Chroma of any grey in I420
+const NEUTRAL_CHROMA: u8 = 128;
+
+/// How sources are arranged in the composed frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompositionLayout {
+    /// Equal-sized tiles in as square a grid as possible
+    #[default]
+    Grid,
+    /// The active speaker fills the top of the frame with the other sources
+    /// in a strip of thumbnails below
+    ActiveSpeaker,
+}
+
+/// Where a source is drawn in the composed frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompositionTile {
+    /// Source drawn in this tile
+    pub source_id: String,
+    /// Left edge in pixels
+    pub x: u32,
+    /// Top edge in pixels
+    pub y: u32,
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+}
+
+/// Combines several video sources into a single frame
+///
+/// Sources keep their aspect ratio and are centred in their cell on a black
+/// background. Sources are placed in the order they were first updated.
+#[derive(Debug)]
+pub struct VideoCompositor {
+    width: u32,
+    height: u32,
+    layout: CompositionLayout,
+    active_speaker: Option<String>,
+    /// Latest upright I420 frame per source, in placement order
+    sources: Vec<(String, VideoFrame)>,
+    pixel_ops: PixelOps,
+}
+
+impl VideoCompositor {
+    /// Compose `width` x `height` frames in a grid
+    ///
+    /// Odd dimensions are rounded down so chroma planes line up with luma.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width: (width & !1).max(2),
+            height: (height & !1).max(2),
+            layout: CompositionLayout::default(),
+            active_speaker: None,
+            sources: Vec::new(),
+            pixel_ops: PixelOps::new(),
+        }
+    }
+
+    /// Use `layout` instead of a grid
+    pub fn with_layout(mut self, layout: CompositionLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Width of composed frames
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Height of composed frames
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Current layout
+    pub fn layout(&self) -> CompositionLayout {
+        self.layout
+    }
+
+    /// Switch layout; takes effect from the next composed frame
+    pub fn set_layout(&mut self, layout: CompositionLayout) {
+        self.layout = layout;
+    }
+
+    /// Source featured by [`CompositionLayout::ActiveSpeaker`]
+    pub fn active_speaker(&self) -> Option<&str> {
+        self.active_speaker.as_deref()
+    }
+
+    /// Feature `source_id` in the active speaker layout
+    ///
+    /// With no speaker, or one that has no frames, the first source is
+    /// featured instead.
+    pub fn set_active_speaker(&mut self, source_id: Option<&str>) {
+        self.active_speaker = source_id.map(str::to_string);
+    }
+
+    /// Replace the frame shown for `source_id`, adding the source if new
+    ///
+    /// Frames must be raw I420, RGB24 or RGBA as accepted by
+    /// [`VideoFrame::scale`]; frames too short for any of them are ignored.
+    pub fn update_source(&mut self, source_id: &str, frame: &VideoFrame) {
+        let Some(frame) = self.to_i420(&frame.to_upright()) else {
+            return;
+        };
+        match self.sources.iter_mut().find(|(id, _)| id == source_id) {
+            Some((_, existing)) => *existing = frame,
+            None => self.sources.push((source_id.to_string(), frame)),
+        }
+    }
+
+    /// Stop drawing `source_id`; returns whether it was present
+    pub fn remove_source(&mut self, source_id: &str) -> bool {
+        let before = self.sources.len();
+        self.sources.retain(|(id, _)| id != source_id);
+        self.sources.len() != before
+    }
+
+    /// Check whether `source_id` is drawn
+    pub fn has_source(&self, source_id: &str) -> bool {
+        self.sources.iter().any(|(id, _)| id == source_id)
+    }
+
+    /// Number of sources drawn
+    pub fn source_count(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// Where each source will be drawn in the next composed frame
+    pub fn tiles(&self) -> Vec<CompositionTile> {
+        self.cells()
+            .into_iter()
+            .map(|(index, cell)| {
+                let (id, frame) = &self.sources[index];
+                let (x, y, width, height) = fit(cell, frame.width, frame.height);
+                CompositionTile {
+                    source_id: id.clone(),
+                    x,
+                    y,
+                    width,
+                    height,
+                }
+            })
+            .collect()
+    }
+
+    /// Draw the current frame of every source into a new I420 frame
+    pub fn compose(&self, timestamp: u64) -> VideoFrame {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let luma_size = width * height;
+        let chroma_size = (width / 2) * (height / 2);
+        let mut data = vec![NEUTRAL_CHROMA; luma_size + 2 * chroma_size];
+        data[..luma_size].fill(BLACK_LUMA);
+
+        for tile in self.tiles() {
+            // Too many sources for the frame leaves some with no room at all
+            if tile.width == 0 || tile.height == 0 {
+                continue;
+            }
+            let Some((_, frame)) = self.sources.iter().find(|(id, _)| *id == tile.source_id) else {
+                continue;
+            };
+            let scaled = frame.scale(tile.width, tile.height);
+            blit_i420(&mut data, (width, height), &scaled, &tile);
+        }
+
+        VideoFrame {
+            width: self.width,
+            height: self.height,
+            data,
+            timestamp,
+            is_keyframe: false,
+            rotation: VideoRotation::Deg0,
+        }
+    }
+
+    /// Cells as (source index, (x, y, width, height)), all even-aligned
+    fn cells(&self) -> Vec<(usize, (u32, u32, u32, u32))> {
+        let count = self.sources.len();
+        if count == 0 {
+            return Vec::new();
+        }
+
+        match self.layout {
+            CompositionLayout::Grid => {
+                let columns = (1..=count).find(|c| c * c >= count).unwrap_or(count) as u32;
+                let rows = (count as u32).div_ceil(columns);
+                let cell_width = even(self.width / columns);
+                let cell_height = even(self.height / rows);
+                (0..count)
+                    .map(|index| {
+                        let column = index as u32 % columns;
+                        let row = index as u32 / columns;
+                        (
+                            index,
+                            (
+                                column * cell_width,
+                                row * cell_height,
+                                cell_width,
+                                cell_height,
+                            ),
+                        )
+                    })
+                    .collect()
+            }
+            CompositionLayout::ActiveSpeaker => {
+                let featured = self
+                    .active_speaker
+                    .as_ref()
+                    .and_then(|speaker| self.sources.iter().position(|(id, _)| id == speaker))
+                    .unwrap_or(0);
+                if count == 1 {
+                    return vec![(featured, (0, 0, self.width, self.height))];
+                }
+
+                let strip_height = even(self.height / 4);
+                let main_height = self.height - strip_height;
+                let others = (count - 1) as u32;
+                let thumb_width = even(self.width / others);
+                let mut cells = vec![(featured, (0, 0, self.width, main_height))];
+                cells.extend(
+                    (0..count)
+                        .filter(|&index| index != featured)
+                        .enumerate()
+                        .map(|(slot, index)| {
+                            (
+                                index,
+                                (
+                                    slot as u32 * thumb_width,
+                                    main_height,
+                                    thumb_width,
+                                    strip_height,
+                                ),
+                            )
+                        }),
+                );
+                cells
+            }
+        }
+    }
+
+    /// Convert a raw frame to I420, or `None` if its layout is unknown
+    fn to_i420(&self, frame: &VideoFrame) -> Option<VideoFrame> {
+        let pixels = frame.width as usize * frame.height as usize;
+        let chroma_size = (frame.width as usize).div_ceil(2) * (frame.height as usize).div_ceil(2);
+        if pixels == 0 {
+            return None;
+        }
+
+        let data = if frame.data.len() == pixels * 4 {
+            self.pixel_ops
+                .rgba_to_i420(&frame.data, frame.width, frame.height)
+                .ok()?
+        } else if frame.data.len() == pixels * 3 {
+            let rgba: Vec<u8> = frame
+                .data
+                .chunks_exact(3)
+                .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], u8::MAX])
+                .collect();
+            self.pixel_ops
+                .rgba_to_i420(&rgba, frame.width, frame.height)
+                .ok()?
+        } else if frame.data.len() >= pixels + 2 * chroma_size {
+            frame.data[..pixels + 2 * chroma_size].to_vec()
+        } else {
+            return None;
+        };
+
+        Some(VideoFrame {
+            data,
+            ..frame.clone()
+        })
+    }
+}
+
+/// Round down to an even number of pixels
+fn even(value: u32) -> u32 {
+    value & !1
+}
+
+/// Largest even-aligned rectangle with the source aspect ratio centred in `cell`
+fn fit(
+    (x, y, cell_width, cell_height): (u32, u32, u32, u32),
+    width: u32,
+    height: u32,
+) -> (u32, u32, u32, u32) {
+    let (cell_w, cell_h) = (cell_width as u64, cell_height as u64);
+    let (w, h) = (width.max(1) as u64, height.max(1) as u64);
+    let (fitted_width, fitted_height) = if w * cell_h > h * cell_w {
+        (cell_w, cell_w * h / w)
+    } else {
+        (cell_h * w / h, cell_h)
+    };
+    let fitted_width = even(fitted_width as u32).max(2).min(cell_width);
+    let fitted_height = even(fitted_height as u32).max(2).min(cell_height);
+    (
+        x + even((cell_width - fitted_width) / 2),
+        y + even((cell_height - fitted_height) / 2),
+        fitted_width,
+        fitted_height,
+    )
+}
+
+/// Copy an I420 frame into `canvas` at the tile position
+fn blit_i420(
+    canvas: &mut [u8],
+    (canvas_width, canvas_height): (usize, usize),
+    frame: &VideoFrame,
+    tile: &CompositionTile,
+) {
+    let (width, height) = (frame.width as usize, frame.height as usize);
+    let (chroma_width, chroma_height) = (width.div_ceil(2), height.div_ceil(2));
+    if frame.data.len() < width * height + 2 * chroma_width * chroma_height {
+        return;
+    }
+    let (x, y) = (tile.x as usize, tile.y as usize);
+    let canvas_luma = canvas_width * canvas_height;
+    let canvas_chroma_width = canvas_width / 2;
+    let canvas_chroma = canvas_chroma_width * (canvas_height / 2);
+
+    copy_plane(
+        &mut canvas[..canvas_luma],
+        canvas_width,
+        &frame.data[..width * height],
+        (width, height),
+        (x, y),
+    );
+    let frame_chroma = chroma_width * chroma_height;
+    for plane in 0..2 {
+        let canvas_start = canvas_luma + plane * canvas_chroma;
+        let frame_start = width * height + plane * frame_chroma;
+        copy_plane(
+            &mut canvas[canvas_start..canvas_start + canvas_chroma],
+            canvas_chroma_width,
+            &frame.data[frame_start..frame_start + frame_chroma],
+            (chroma_width, chroma_height),
+            (x / 2, y / 2),
+        );
+    }
+}
+
+/// Copy a plane into a larger one at (`x`, `y`), clipping at its edges
+fn copy_plane(
+    dst: &mut [u8],
+    dst_stride: usize,
+    src: &[u8],
+    (width, height): (usize, usize),
+    (x, y): (usize, usize),
+) {
+    let dst_rows = dst.len() / dst_stride.max(1);
+    let copy_width = width.min(dst_stride.saturating_sub(x));
+    for row in 0..height.min(dst_rows.saturating_sub(y)) {
+        let dst_start = (y + row) * dst_stride + x;
+        let src_start = row * width;
+        dst[dst_start..dst_start + copy_width]
+            .copy_from_slice(&src[src_start..src_start + copy_width]);
+    }
+}
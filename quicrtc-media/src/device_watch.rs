@@ -0,0 +1,172 @@
+//! Audio device change detection
+//!
+//! CPAL does not report devices coming and going or the system default
+//! moving, for example to a headset that was just connected. An
+//! [`AudioDeviceSnapshot`] records the devices and defaults at one point in
+//! time; comparing a fresh snapshot with the previous one with
+//! [`AudioDeviceSnapshot::changes_since`] yields what changed in between.
+
+#[cfg(feature = "devices")]
+use crate::error::{MediaError, MediaResult};
+
+/// Whether an audio device records or plays
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AudioDeviceDirection {
+    /// Microphone or other input
+    Input,
+    /// Speaker, headphones or other output
+    Output,
+}
+
+/// Change between two [`AudioDeviceSnapshot`]s
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AudioDeviceChange {
+    /// A device became available
+    Added {
+        /// Input or output
+        direction: AudioDeviceDirection,
+        /// Device name, which CPAL also uses as its ID
+        name: String,
+    },
+    /// A device was unplugged or became unavailable
+    Removed {
+        /// Input or output
+        direction: AudioDeviceDirection,
+        /// Device name
+        name: String,
+    },
+    /// The system default device changed
+    DefaultChanged {
+        /// Input or output
+        direction: AudioDeviceDirection,
+        /// New default device, `None` when there is none
+        name: Option<String>,
+    },
+}
+
+/// Audio devices available at one point in time
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AudioDeviceSnapshot {
+    /// Input device names
+    pub inputs: Vec<String>,
+    /// Output device names
+    pub outputs: Vec<String>,
+    /// Default input device name
+    pub default_input: Option<String>,
+    /// Default output device name
+    pub default_output: Option<String>,
+}
+
+impl AudioDeviceSnapshot {
+    /// Snapshot the devices of the default audio host
+    ///
+    /// Enumerating devices can block for a while on some platforms, so call
+    /// this off the async runtime.
+    #[cfg(feature = "devices")]
+    pub fn current() -> MediaResult<Self> {
+        use cpal::traits::{DeviceTrait, HostTrait};
+
+        let host = cpal::default_host();
+        let enumeration_failed = |e: cpal::DevicesError| MediaError::DeviceEnumerationFailed {
+            reason: format!("Failed to enumerate audio devices: {}", e),
+        };
+        let inputs = host.input_devices().map_err(enumeration_failed)?;
+        let outputs = host.output_devices().map_err(enumeration_failed)?;
+
+        Ok(Self {
+            inputs: inputs.filter_map(|d| d.name().ok()).collect(),
+            outputs: outputs.filter_map(|d| d.name().ok()).collect(),
+            default_input: host.default_input_device().and_then(|d| d.name().ok()),
+            default_output: host.default_output_device().and_then(|d| d.name().ok()),
+        })
+    }
+
+    /// What changed from `previous` to this snapshot
+    ///
+    /// Additions and removals come before default changes, so a newly
+    /// connected device is reported before it becomes the default.
+    pub fn changes_since(&self, previous: &Self) -> Vec<AudioDeviceChange> {
+        let mut changes = Vec::new();
+        for (direction, now, before) in [
+            (AudioDeviceDirection::Input, &self.inputs, &previous.inputs),
+            (
+                AudioDeviceDirection::Output,
+                &self.outputs,
+                &previous.outputs,
+            ),
+        ] {
+            changes.extend(
+                now.iter()
+                    .filter(|name| !before.contains(name))
+                    .map(|name| AudioDeviceChange::Added {
+                        direction,
+                        name: name.clone(),
+                    }),
+            );
+            changes.extend(
+                before
+                    .iter()
+                    .filter(|name| !now.contains(name))
+                    .map(|name| AudioDeviceChange::Removed {
+                        direction,
+                        name: name.clone(),
+                    }),
+            );
+        }
+        for (direction, now, before) in [
+            (
+                AudioDeviceDirection::Input,
+                &self.default_input,
+                &previous.default_input,
+            ),
+            (
+                AudioDeviceDirection::Output,
+                &self.default_output,
+                &previous.default_output,
+            ),
+        ] {
+            if now != before {
+                changes.push(AudioDeviceChange::DefaultChanged {
+                    direction,
+                    name: now.clone(),
+                });
+            }
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_headset_connected_as_default_output() {
+        let before = AudioDeviceSnapshot {
+            inputs: vec!["Built-in Microphone".to_string()],
+            outputs: vec!["Built-in Speakers".to_string()],
+            default_input: Some("Built-in Microphone".to_string()),
+            default_output: Some("Built-in Speakers".to_string()),
+        };
+        let after = AudioDeviceSnapshot {
+            outputs: vec!["Built-in Speakers".to_string(), "AirPods".to_string()],
+            default_output: Some("AirPods".to_string()),
+            ..before.clone()
+        };
+
+        assert_eq!(
+            after.changes_since(&before),
+            vec![
+                AudioDeviceChange::Added {
+                    direction: AudioDeviceDirection::Output,
+                    name: "AirPods".to_string(),
+                },
+                AudioDeviceChange::DefaultChanged {
+                    direction: AudioDeviceDirection::Output,
+                    name: Some("AirPods".to_string()),
+                },
+            ]
+        );
+        assert!(before.changes_since(&before).is_empty());
+    }
+}
@@ -0,0 +1,551 @@
+//! Vectorized pixel operations
+//!
+//! Per-pixel loops for display adjustments and YUV/RGB conversion. Each
+//! operation has a scalar reference path plus SSE2/AVX2 (x86_64) or NEON
+//! (aarch64) kernels, chosen once at runtime from the detected CPU features.
+//! Kernels use the same fixed-point arithmetic as the scalar path, so the
+//! output is identical whichever one runs.
+
+use crate::error::{MediaError, MediaResult};
+use std::sync::OnceLock;
+
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+/// Instruction set used for pixel operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimdLevel {
+    /// Portable scalar code
+    Scalar,
+    /// 128-bit SSE2 (baseline on x86_64)
+    Sse2,
+    /// 256-bit AVX2
+    Avx2,
+    /// 128-bit NEON
+    Neon,
+}
+
+impl SimdLevel {
+    /// Best instruction set supported by this CPU (detected once)
+    pub fn detect() -> Self {
+        static LEVEL: OnceLock<SimdLevel> = OnceLock::new();
+        *LEVEL.get_or_init(Self::probe)
+    }
+
+    /// Whether this CPU can run code for this level
+    pub fn is_supported(self) -> bool {
+        match (self, Self::detect()) {
+            (Self::Scalar, _) => true,
+            (Self::Sse2, Self::Sse2 | Self::Avx2) => true,
+            (level, detected) => level == detected,
+        }
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn probe() -> Self {
+        if is_x86_feature_detected!("avx2") {
+            Self::Avx2
+        } else {
+            Self::Sse2
+        }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    fn probe() -> Self {
+        if std::arch::is_aarch64_feature_detected!("neon") {
+            Self::Neon
+        } else {
+            Self::Scalar
+        }
+    }
+
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    fn probe() -> Self {
+        Self::Scalar
+    }
+}
+
+/// Pixel operations bound to an instruction set
+#[derive(Debug, Clone, Copy)]
+pub struct PixelOps {
+    level: SimdLevel,
+}
+
+impl PixelOps {
+    /// Use the best instruction set available on this CPU
+    pub fn new() -> Self {
+        Self {
+            level: SimdLevel::detect(),
+        }
+    }
+
+    /// Use a specific instruction set, falling back to scalar code if the
+    /// CPU doesn't support it
+    pub fn with_level(level: SimdLevel) -> Self {
+        Self {
+            level: if level.is_supported() {
+                level
+            } else {
+                SimdLevel::Scalar
+            },
+        }
+    }
+
+    /// Instruction set in use
+    pub fn level(&self) -> SimdLevel {
+        self.level
+    }
+
+    /// Add `offset` to every sample, saturating at 0 and 255
+    pub fn adjust_brightness(&self, plane: &mut [u8], offset: i16) {
+        let offset = offset.clamp(-255, 255);
+        if offset == 0 {
+            return;
+        }
+        let magnitude = offset.unsigned_abs() as u8;
+
+        let done = match self.level {
+            // SAFETY: the level was checked against the running CPU
+            #[cfg(target_arch = "x86_64")]
+            SimdLevel::Avx2 => unsafe { brightness_avx2(plane, magnitude, offset > 0) },
+            #[cfg(target_arch = "x86_64")]
+            SimdLevel::Sse2 => unsafe { brightness_sse2(plane, magnitude, offset > 0) },
+            #[cfg(target_arch = "aarch64")]
+            SimdLevel::Neon => unsafe { brightness_neon(plane, magnitude, offset > 0) },
+            _ => 0,
+        };
+
+        for sample in &mut plane[done..] {
+            *sample = if offset > 0 {
+                sample.saturating_add(magnitude)
+            } else {
+                sample.saturating_sub(magnitude)
+            };
+        }
+    }
+
+    /// Scale every sample's distance from 128 by `factor` (0.0 to 2.0)
+    ///
+    /// This is contrast on a luma plane and saturation on chroma planes.
+    /// The factor is applied in Q7 fixed point.
+    pub fn scale_around_midpoint(&self, plane: &mut [u8], factor: f32) {
+        let factor = (factor.clamp(0.0, 2.0) * 128.0).round() as i16;
+        if factor == 128 {
+            return;
+        }
+
+        let done = match self.level {
+            // SAFETY: the level was checked against the running CPU
+            #[cfg(target_arch = "x86_64")]
+            SimdLevel::Avx2 => unsafe { midpoint_scale_avx2(plane, factor) },
+            #[cfg(target_arch = "x86_64")]
+            SimdLevel::Sse2 => unsafe { midpoint_scale_sse2(plane, factor) },
+            #[cfg(target_arch = "aarch64")]
+            SimdLevel::Neon => unsafe { midpoint_scale_neon(plane, factor) },
+            _ => 0,
+        };
+
+        for sample in &mut plane[done..] {
+            *sample = midpoint_scale_scalar(*sample, factor);
+        }
+    }
+
+    /// Apply gamma correction (`out = in^(1/gamma)`)
+    ///
+    /// A 256-entry lookup table beats any vector `powf`, so every level
+    /// shares the same table-driven loop.
+    pub fn apply_gamma(&self, plane: &mut [u8], gamma: f32) {
+        if gamma <= 0.0 || gamma == 1.0 {
+            return;
+        }
+        let mut table = [0u8; 256];
+        for (value, entry) in table.iter_mut().enumerate() {
+            let corrected = (value as f32 / 255.0).powf(1.0 / gamma);
+            *entry = (corrected * 255.0).clamp(0.0, 255.0) as u8;
+        }
+        for sample in plane {
+            *sample = table[*sample as usize];
+        }
+    }
+
+    /// Convert packed RGBA to planar I420 (BT.601, limited range)
+    ///
+    /// Luma is vectorized; chroma is averaged over each 2x2 block in scalar
+    /// code since it is a quarter of the output.
+    pub fn rgba_to_i420(&self, rgba: &[u8], width: u32, height: u32) -> MediaResult<Vec<u8>> {
+        let (w, h) = (width as usize, height as usize);
+        let expected = w * h * 4;
+        if rgba.len() < expected {
+            return Err(MediaError::InvalidFrameData {
+                expected,
+                actual: rgba.len(),
+            });
+        }
+
+        if expected == 0 {
+            return Ok(Vec::new());
+        }
+
+        let (cw, ch) = (w.div_ceil(2), h.div_ceil(2));
+        let mut out = vec![0u8; w * h + 2 * cw * ch];
+        let (luma, chroma) = out.split_at_mut(w * h);
+
+        for (src, dst) in rgba[..expected]
+            .chunks_exact(w * 4)
+            .zip(luma.chunks_exact_mut(w))
+        {
+            let done = match self.level {
+                // SAFETY: the level was checked against the running CPU
+                #[cfg(target_arch = "x86_64")]
+                SimdLevel::Avx2 => unsafe { luma_row_avx2(src, dst) },
+                #[cfg(target_arch = "x86_64")]
+                SimdLevel::Sse2 => unsafe { luma_row_sse2(src, dst) },
+                _ => 0,
+            };
+            for (pixel, y) in src[done * 4..].chunks_exact(4).zip(&mut dst[done..]) {
+                *y = rgb_to_y(pixel[0], pixel[1], pixel[2]);
+            }
+        }
+
+        let (u_plane, v_plane) = chroma.split_at_mut(cw * ch);
+        for cy in 0..ch {
+            for cx in 0..cw {
+                let (mut r, mut g, mut b, mut count) = (0u32, 0u32, 0u32, 0u32);
+                for y in (cy * 2)..(cy * 2 + 2).min(h) {
+                    for x in (cx * 2)..(cx * 2 + 2).min(w) {
+                        let idx = (y * w + x) * 4;
+                        r += rgba[idx] as u32;
+                        g += rgba[idx + 1] as u32;
+                        b += rgba[idx + 2] as u32;
+                        count += 1;
+                    }
+                }
+                let (r, g, b) = ((r / count) as i32, (g / count) as i32, (b / count) as i32);
+                u_plane[cy * cw + cx] = (((-38 * r - 74 * g + 112 * b + 128) >> 8) + 128) as u8;
+                v_plane[cy * cw + cx] = (((112 * r - 94 * g - 18 * b + 128) >> 8) + 128) as u8;
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// Convert planar I420 (BT.601, limited range) to packed RGBA
+    pub fn i420_to_rgba(&self, i420: &[u8], width: u32, height: u32) -> MediaResult<Vec<u8>> {
+        let (w, h) = (width as usize, height as usize);
+        let (cw, ch) = (w.div_ceil(2), h.div_ceil(2));
+        let expected = w * h + 2 * cw * ch;
+        if i420.len() < expected {
+            return Err(MediaError::InvalidFrameData {
+                expected,
+                actual: i420.len(),
+            });
+        }
+        if expected == 0 {
+            return Ok(Vec::new());
+        }
+
+        let (luma, chroma) = i420.split_at(w * h);
+        let (u_plane, v_plane) = chroma.split_at(cw * ch);
+        let mut out = vec![0u8; w * h * 4];
+
+        for (y, row) in out.chunks_exact_mut(w * 4).enumerate() {
+            let chroma_row = (y / 2) * cw;
+            for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+                let c = 298 * (luma[y * w + x] as i32 - 16);
+                let d = u_plane[chroma_row + x / 2] as i32 - 128;
+                let e = v_plane[chroma_row + x / 2] as i32 - 128;
+                pixel[0] = ((c + 409 * e + 128) >> 8).clamp(0, 255) as u8;
+                pixel[1] = ((c - 100 * d - 208 * e + 128) >> 8).clamp(0, 255) as u8;
+                pixel[2] = ((c + 516 * d + 128) >> 8).clamp(0, 255) as u8;
+                pixel[3] = 255;
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+impl Default for PixelOps {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn midpoint_scale_scalar(sample: u8, factor: i16) -> u8 {
+    let scaled = ((sample as i32 - 128) * factor as i32) >> 7;
+    (scaled + 128).clamp(0, 255) as u8
+}
+
+fn rgb_to_y(r: u8, g: u8, b: u8) -> u8 {
+    (((66 * r as i32 + 129 * g as i32 + 25 * b as i32 + 128) >> 8) + 16) as u8
+}
+
+// x86_64 kernels. Each returns how many samples it processed; the caller
+// finishes the tail with scalar code.
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn brightness_sse2(plane: &mut [u8], magnitude: u8, brighten: bool) -> usize {
+    let delta = _mm_set1_epi8(magnitude as i8);
+    let mut done = 0;
+    for chunk in plane.chunks_exact_mut(16) {
+        let ptr = chunk.as_mut_ptr() as *mut __m128i;
+        let v = _mm_loadu_si128(ptr);
+        let v = if brighten {
+            _mm_adds_epu8(v, delta)
+        } else {
+            _mm_subs_epu8(v, delta)
+        };
+        _mm_storeu_si128(ptr, v);
+        done += 16;
+    }
+    done
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn brightness_avx2(plane: &mut [u8], magnitude: u8, brighten: bool) -> usize {
+    let delta = _mm256_set1_epi8(magnitude as i8);
+    let mut done = 0;
+    for chunk in plane.chunks_exact_mut(32) {
+        let ptr = chunk.as_mut_ptr() as *mut __m256i;
+        let v = _mm256_loadu_si256(ptr);
+        let v = if brighten {
+            _mm256_adds_epu8(v, delta)
+        } else {
+            _mm256_subs_epu8(v, delta)
+        };
+        _mm256_storeu_si256(ptr, v);
+        done += 32;
+    }
+    done
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn midpoint_scale_sse2(plane: &mut [u8], factor: i16) -> usize {
+    let zero = _mm_setzero_si128();
+    let mid = _mm_set1_epi16(128);
+    let factor = _mm_set1_epi16(factor);
+    let mut done = 0;
+    for chunk in plane.chunks_exact_mut(16) {
+        let ptr = chunk.as_mut_ptr() as *mut __m128i;
+        let v = _mm_loadu_si128(ptr);
+        let lo = _mm_sub_epi16(_mm_unpacklo_epi8(v, zero), mid);
+        let hi = _mm_sub_epi16(_mm_unpackhi_epi8(v, zero), mid);
+        let lo = _mm_add_epi16(_mm_srai_epi16::<7>(_mm_mullo_epi16(lo, factor)), mid);
+        let hi = _mm_add_epi16(_mm_srai_epi16::<7>(_mm_mullo_epi16(hi, factor)), mid);
+        _mm_storeu_si128(ptr, _mm_packus_epi16(lo, hi));
+        done += 16;
+    }
+    done
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn midpoint_scale_avx2(plane: &mut [u8], factor: i16) -> usize {
+    let zero = _mm256_setzero_si256();
+    let mid = _mm256_set1_epi16(128);
+    let factor = _mm256_set1_epi16(factor);
+    let mut done = 0;
+    for chunk in plane.chunks_exact_mut(32) {
+        let ptr = chunk.as_mut_ptr() as *mut __m256i;
+        let v = _mm256_loadu_si256(ptr);
+        // Unpack and pack both work per 128-bit lane, so byte order survives
+        let lo = _mm256_sub_epi16(_mm256_unpacklo_epi8(v, zero), mid);
+        let hi = _mm256_sub_epi16(_mm256_unpackhi_epi8(v, zero), mid);
+        let lo = _mm256_add_epi16(_mm256_srai_epi16::<7>(_mm256_mullo_epi16(lo, factor)), mid);
+        let hi = _mm256_add_epi16(_mm256_srai_epi16::<7>(_mm256_mullo_epi16(hi, factor)), mid);
+        _mm256_storeu_si256(ptr, _mm256_packus_epi16(lo, hi));
+        done += 32;
+    }
+    done
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn luma_row_sse2(rgba: &[u8], luma: &mut [u8]) -> usize {
+    let zero = _mm_setzero_si128();
+    let coeffs = _mm_setr_epi16(66, 129, 25, 0, 66, 129, 25, 0);
+    let round = _mm_set1_epi32(128);
+    let offset = _mm_set1_epi32(16);
+    let mut done = 0;
+    for (src, dst) in rgba.chunks_exact(16).zip(luma.chunks_exact_mut(4)) {
+        let v = _mm_loadu_si128(src.as_ptr() as *const __m128i);
+        // Each madd yields [66r+129g, 25b] pairs for two pixels
+        let lo = _mm_madd_epi16(_mm_unpacklo_epi8(v, zero), coeffs);
+        let hi = _mm_madd_epi16(_mm_unpackhi_epi8(v, zero), coeffs);
+        let lo = _mm_add_epi32(lo, _mm_srli_epi64::<32>(lo));
+        let hi = _mm_add_epi32(hi, _mm_srli_epi64::<32>(hi));
+        let sums = _mm_unpacklo_epi64(
+            _mm_shuffle_epi32::<0b10_00_10_00>(lo),
+            _mm_shuffle_epi32::<0b10_00_10_00>(hi),
+        );
+        let y = _mm_add_epi32(_mm_srai_epi32::<8>(_mm_add_epi32(sums, round)), offset);
+        let y = _mm_packus_epi16(_mm_packs_epi32(y, y), zero);
+        dst.copy_from_slice(&_mm_cvtsi128_si32(y).to_le_bytes());
+        done += 4;
+    }
+    done
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn luma_row_avx2(rgba: &[u8], luma: &mut [u8]) -> usize {
+    let zero = _mm256_setzero_si256();
+    let coeffs = _mm256_setr_epi16(
+        66, 129, 25, 0, 66, 129, 25, 0, 66, 129, 25, 0, 66, 129, 25, 0,
+    );
+    let round = _mm256_set1_epi32(128);
+    let offset = _mm256_set1_epi32(16);
+    let mut done = 0;
+    for (src, dst) in rgba.chunks_exact(32).zip(luma.chunks_exact_mut(8)) {
+        let v = _mm256_loadu_si256(src.as_ptr() as *const __m256i);
+        let lo = _mm256_madd_epi16(_mm256_unpacklo_epi8(v, zero), coeffs);
+        let hi = _mm256_madd_epi16(_mm256_unpackhi_epi8(v, zero), coeffs);
+        let lo = _mm256_add_epi32(lo, _mm256_srli_epi64::<32>(lo));
+        let hi = _mm256_add_epi32(hi, _mm256_srli_epi64::<32>(hi));
+        // Per lane: pixels 0-3 in the low lane, 4-7 in the high lane
+        let sums = _mm256_unpacklo_epi64(
+            _mm256_shuffle_epi32::<0b10_00_10_00>(lo),
+            _mm256_shuffle_epi32::<0b10_00_10_00>(hi),
+        );
+        let y = _mm256_add_epi32(
+            _mm256_srai_epi32::<8>(_mm256_add_epi32(sums, round)),
+            offset,
+        );
+        let y = _mm256_packus_epi16(_mm256_packs_epi32(y, y), zero);
+        let low = _mm_cvtsi128_si32(_mm256_castsi256_si128(y));
+        let high = _mm_cvtsi128_si32(_mm256_extracti128_si256::<1>(y));
+        dst[..4].copy_from_slice(&low.to_le_bytes());
+        dst[4..].copy_from_slice(&high.to_le_bytes());
+        done += 8;
+    }
+    done
+}
+
+// aarch64 kernels
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+unsafe fn brightness_neon(plane: &mut [u8], magnitude: u8, brighten: bool) -> usize {
+    let delta = vdupq_n_u8(magnitude);
+    let mut done = 0;
+    for chunk in plane.chunks_exact_mut(16) {
+        let v = vld1q_u8(chunk.as_ptr());
+        let v = if brighten {
+            vqaddq_u8(v, delta)
+        } else {
+            vqsubq_u8(v, delta)
+        };
+        vst1q_u8(chunk.as_mut_ptr(), v);
+        done += 16;
+    }
+    done
+}
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+unsafe fn midpoint_scale_neon(plane: &mut [u8], factor: i16) -> usize {
+    let mid = vdupq_n_s16(128);
+    let factor = vdupq_n_s16(factor);
+    let mut done = 0;
+    for chunk in plane.chunks_exact_mut(16) {
+        let v = vld1q_u8(chunk.as_ptr());
+        let lo = vsubq_s16(vreinterpretq_s16_u16(vmovl_u8(vget_low_u8(v))), mid);
+        let hi = vsubq_s16(vreinterpretq_s16_u16(vmovl_u8(vget_high_u8(v))), mid);
+        let lo = vaddq_s16(vshrq_n_s16::<7>(vmulq_s16(lo, factor)), mid);
+        let hi = vaddq_s16(vshrq_n_s16::<7>(vmulq_s16(hi, factor)), mid);
+        vst1q_u8(
+            chunk.as_mut_ptr(),
+            vcombine_u8(vqmovun_s16(lo), vqmovun_s16(hi)),
+        );
+        done += 16;
+    }
+    done
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_plane(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 37 % 256) as u8).collect()
+    }
+
+    fn levels() -> Vec<PixelOps> {
+        [
+            SimdLevel::Scalar,
+            SimdLevel::Sse2,
+            SimdLevel::Avx2,
+            SimdLevel::Neon,
+        ]
+        .into_iter()
+        .filter(|level| level.is_supported())
+        .map(PixelOps::with_level)
+        .collect()
+    }
+
+    #[test]
+    fn test_simd_kernels_match_scalar() {
+        let scalar = PixelOps::with_level(SimdLevel::Scalar);
+        // Odd length exercises the scalar tail after the vector body
+        let source = test_plane(1000 + 13);
+
+        for ops in levels() {
+            for offset in [-300, -40, 25, 255] {
+                let mut expected = source.clone();
+                let mut actual = source.clone();
+                scalar.adjust_brightness(&mut expected, offset);
+                ops.adjust_brightness(&mut actual, offset);
+                assert_eq!(actual, expected, "brightness {:?}", ops.level());
+            }
+
+            for factor in [0.0, 0.5, 1.3, 2.0, 5.0] {
+                let mut expected = source.clone();
+                let mut actual = source.clone();
+                scalar.scale_around_midpoint(&mut expected, factor);
+                ops.scale_around_midpoint(&mut actual, factor);
+                assert_eq!(actual, expected, "midpoint {:?}", ops.level());
+            }
+
+            let rgba = test_plane(37 * 5 * 4);
+            assert_eq!(
+                ops.rgba_to_i420(&rgba, 37, 5).unwrap(),
+                scalar.rgba_to_i420(&rgba, 37, 5).unwrap(),
+                "rgba_to_i420 {:?}",
+                ops.level()
+            );
+        }
+
+        let mut plane = vec![0, 128, 255];
+        scalar.scale_around_midpoint(&mut plane, 2.0);
+        assert_eq!(plane, vec![0, 128, 255]);
+        scalar.adjust_brightness(&mut plane, 10);
+        assert_eq!(plane, vec![10, 138, 255]);
+    }
+
+    #[test]
+    fn test_yuv_conversion_round_trip() {
+        let ops = PixelOps::new();
+        let (width, height) = (6, 4);
+        let rgba: Vec<u8> = (0..width * height)
+            .flat_map(|_| [200u8, 80, 40, 255])
+            .collect();
+
+        let i420 = ops.rgba_to_i420(&rgba, width, height).unwrap();
+        assert_eq!(i420.len(), 6 * 4 * 3 / 2);
+
+        let back = ops.i420_to_rgba(&i420, width, height).unwrap();
+        for (original, converted) in rgba.iter().zip(&back) {
+            assert!((*original as i32 - *converted as i32).abs() <= 3);
+        }
+
+        assert!(matches!(
+            ops.i420_to_rgba(&i420[..10], width, height),
+            Err(MediaError::InvalidFrameData { .. })
+        ));
+    }
+}
@@ -3,40 +3,77 @@
 //! Media processing, codec handling, and quality control for QUIC RTC.
 //! This crate handles all media-specific functionality including encoding,
 //! decoding, and MoQ object processing.
+//!
+//! Camera capture (nokhwa) and audio device I/O (cpal) live behind the
+//! default `devices` feature. Disable it to use the codecs, pipelines and
+//! mixers without linking any system audio/video libraries, e.g. in a
+//! server-side mixer or when cross-compiling.
 
 #![warn(clippy::all)]
 
+#[cfg(feature = "devices")]
+pub mod audio_capture;
+pub mod audio_source;
+#[cfg(feature = "devices")]
 pub mod capture;
 pub mod codecs;
+pub mod compositor;
+pub mod device_watch;
 pub mod error;
+pub mod image_ops;
+pub mod pipeline;
 pub mod processing;
 pub mod render;
+pub mod telephony;
+pub mod thumbnail;
 pub mod tracks;
 pub mod video_capture;
 pub mod video_render;
+pub mod virtual_device;
 
 // Re-export main types
 // Note: capture module exports temporarily disabled due to refactoring
 // TODO: Re-enable once platform-specific implementations are complete
+#[cfg(feature = "devices")]
+pub use audio_capture::{AudioCaptureConfig, AudioInputDevice, CpalAudioCapture};
+pub use audio_source::{resample, AudioFramer, AudioSource};
 pub use codecs::{
-    Codec, CodecConfig, CodecInfo, CodecRegistry, H264Codec, OpusCodec, SyncDecoder, SyncEncoder,
-    VideoQuality,
+    Codec, CodecConfig, CodecInfo, CodecRegistry, H264Codec, OpusApplication, OpusCodec,
+    SyncDecoder, SyncEncoder, VideoQuality,
 };
+pub use compositor::{CompositionLayout, CompositionTile, VideoCompositor};
+pub use device_watch::{AudioDeviceChange, AudioDeviceDirection, AudioDeviceSnapshot};
 pub use error::{ErrorCategory, MediaError, MediaResult};
+pub use image_ops::{PixelOps, SimdLevel};
+pub use pipeline::{
+    DecodePipeline, DecodePipelineConfig, DecodeStats, DecodedFrame, EncodePipeline,
+    EncodePipelineConfig, EncodeStats, EncodedFrame, FrameDropPolicy, FrameFormat,
+};
 pub use processing::{
-    CongestionLevel, MediaProcessor, MoqDeliveryMetrics, MoqObjectAssembler, QualityControlConfig,
-    QualityController, QualitySettings, TrackStats,
+    CongestionLevel, DegradationPreference, MediaProcessor, MoqDeliveryMetrics, MoqObjectAssembler,
+    QualityControlConfig, QualityController, QualitySettings, TrackStats, VideoRotationMode,
 };
+#[cfg(feature = "devices")]
+pub use render::CpalAudioRenderer;
 pub use render::{
-    AudioOutputDevice, AudioRenderConfig, AudioRenderStats, AudioRenderer, CpalAudioRenderer,
-    DefaultAudioRenderer, DefaultVideoRenderer, RenderError, VideoDisplayConfig, VideoOutputDevice,
-    VideoRenderConfig, VideoRenderStats, VideoRenderer,
+    remix_channels, time_stretch, AudioDucker, AudioMixer, AudioOutputDevice, AudioRenderConfig,
+    AudioRenderStats, AudioRenderer, DefaultAudioRenderer, DefaultVideoRenderer, MixerGain,
+    PlaybackConfig, PlaybackController, RenderError, VideoDisplayConfig, VideoOutputDevice,
+    VideoRenderConfig, VideoRenderStats, VideoRenderer, VoiceActivityDetector,
 };
+pub use telephony::{G711Codec, G711Law, TelephonyTranscoder, G711_SAMPLE_RATE};
+pub use thumbnail::ThumbnailGenerator;
 pub use tracks::{AudioFrame, AudioTrack, MediaFrame, VideoFrame, VideoTrack};
+#[cfg(feature = "devices")]
+pub use video_capture::VideoCaptureManager;
 pub use video_capture::{
     CaptureStats, FrameMetadata, FrameProcessor, FrameProcessorConfig,
-    VideoCaptureConfig as NewVideoCaptureConfig, VideoCaptureEvent, VideoCaptureManager,
-    VideoDevice as NewVideoDevice, VideoPixelFormat, VideoResolution,
+    VideoCaptureConfig as NewVideoCaptureConfig, VideoCaptureEvent, VideoDevice as NewVideoDevice,
+    VideoPixelFormat, VideoResolution,
+};
+pub use virtual_device::{
+    compare_audio, video_psnr, AudioComparison, LoopbackRecording, VirtualAudioSignal,
+    VirtualCamera, VirtualCameraBackend, VirtualMicrophone, VirtualSpeaker, VirtualVideoPattern,
 };
 pub use video_render::{
     SoftwareRenderer, VideoDisplayMode, VideoRenderBackend,
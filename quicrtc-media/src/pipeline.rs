@@ -0,0 +1,1020 @@
+//! Encode and decode pipelines with per-track worker threads
+//!
+//! Capture callbacks must never wait on an encoder. Each track gets a
+//! dedicated worker thread fed by a small bounded queue; when encoding can't
+//! keep up, frames are dropped according to the configured policy instead of
+//! blocking the producer.
+//!
+//! The decode side mirrors this, and additionally drops frames whose render
+//! deadline has already passed so a slow decoder catches up instead of
+//! drifting further behind.
+
+use crate::codecs::{SyncDecoder, SyncEncoder};
+use crate::error::{MediaError, MediaResult};
+use crate::tracks::MediaFrame;
+use parking_lot::{Condvar, Mutex};
+use quicrtc_core::VideoRotation;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// Which frame to discard when a track's queue is full
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FrameDropPolicy {
+    /// Drop the oldest queued frame to make room (lowest latency)
+    #[default]
+    DropOldest,
+    /// Drop the incoming frame and keep what is already queued
+    DropNewest,
+}
+
+/// Encode pipeline configuration
+#[derive(Debug, Clone)]
+pub struct EncodePipelineConfig {
+    /// Maximum raw frames queued per track
+    pub queue_capacity: usize,
+    /// Maximum encoded frames buffered per track before the worker waits
+    pub output_capacity: usize,
+    /// What to drop when the queue is full
+    pub drop_policy: FrameDropPolicy,
+}
+
+impl Default for EncodePipelineConfig {
+    fn default() -> Self {
+        Self {
+            queue_capacity: 3,
+            output_capacity: 8,
+            drop_policy: FrameDropPolicy::DropOldest,
+        }
+    }
+}
+
+/// Format of the raw frame behind an encoded frame
+///
+/// Encoded payloads don't say what they contain, so the format travels
+/// beside them for whoever packages or renders the frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameFormat {
+    /// Audio samples
+    Audio {
+        /// Sample rate in Hz
+        sample_rate: u32,
+        /// Number of channels
+        channels: u8,
+    },
+    /// Video picture
+    Video {
+        /// Width in pixels
+        width: u32,
+        /// Height in pixels
+        height: u32,
+        /// Clockwise rotation needed to display the picture upright
+        rotation: VideoRotation,
+    },
+}
+
+impl FrameFormat {
+    /// Format of a raw frame
+    pub fn of(frame: &MediaFrame) -> Self {
+        match frame {
+            MediaFrame::Audio(audio) => FrameFormat::Audio {
+                sample_rate: audio.sample_rate,
+                channels: audio.channels,
+            },
+            MediaFrame::Video(video) => FrameFormat::Video {
+                width: video.width,
+                height: video.height,
+                rotation: video.rotation,
+            },
+        }
+    }
+
+    /// Present a decoded frame in this format
+    ///
+    /// Video is scaled to the signalled size and takes the signalled
+    /// rotation, neither of which the bitstream carries.
+    pub fn present(&self, frame: MediaFrame) -> MediaFrame {
+        match (self, frame) {
+            (
+                FrameFormat::Video {
+                    width,
+                    height,
+                    rotation,
+                },
+                MediaFrame::Video(video),
+            ) => {
+                let mut video = video.scale(*width, *height);
+                video.rotation = *rotation;
+                MediaFrame::Video(video)
+            }
+            (_, frame) => frame,
+        }
+    }
+}
+
+/// Encoded output of the pipeline
+#[derive(Debug, Clone)]
+pub struct EncodedFrame {
+    /// Track the frame belongs to
+    pub track_id: String,
+    /// Encoded payload
+    pub data: Vec<u8>,
+    /// Format of the source frame
+    pub format: FrameFormat,
+    /// Timestamp of the source frame in milliseconds
+    pub timestamp: u64,
+    /// Whether the source frame was a keyframe (always true for audio)
+    pub is_keyframe: bool,
+    /// Time spent in the encoder
+    pub encode_time: Duration,
+}
+
+/// Per-track queue and encoder statistics
+#[derive(Debug, Clone, Default)]
+pub struct EncodeStats {
+    /// Frames handed to the pipeline
+    pub frames_submitted: u64,
+    /// Frames encoded successfully
+    pub frames_encoded: u64,
+    /// Frames discarded because the queue was full
+    pub frames_dropped: u64,
+    /// Frames the encoder rejected
+    pub encode_errors: u64,
+    /// Frames currently queued
+    pub queue_depth: usize,
+    /// Queue capacity
+    pub queue_capacity: usize,
+    /// Highest queue depth seen
+    pub peak_queue_depth: usize,
+    /// Average time per encode
+    pub average_encode_time: Duration,
+    /// Time taken by the most recent encode
+    pub last_encode_time: Duration,
+}
+
+impl EncodeStats {
+    /// Queue occupancy from 0.0 (empty) to 1.0 (full)
+    pub fn occupancy(&self) -> f32 {
+        if self.queue_capacity == 0 {
+            0.0
+        } else {
+            self.queue_depth as f32 / self.queue_capacity as f32
+        }
+    }
+
+    /// Fraction of submitted frames that were dropped
+    pub fn drop_ratio(&self) -> f32 {
+        if self.frames_submitted == 0 {
+            0.0
+        } else {
+            self.frames_dropped as f32 / self.frames_submitted as f32
+        }
+    }
+
+    fn record_encode(&mut self, encode_time: Duration) {
+        self.frames_encoded += 1;
+        self.last_encode_time = encode_time;
+        // Running average without keeping a history
+        let count = self.frames_encoded as u32;
+        self.average_encode_time = (self.average_encode_time * (count - 1) + encode_time) / count;
+    }
+}
+
+/// Bounded frame queue shared between producer and worker
+#[derive(Debug)]
+struct FrameQueue<T> {
+    state: Mutex<QueueState<T>>,
+    ready: Condvar,
+}
+
+#[derive(Debug)]
+struct QueueState<T> {
+    frames: VecDeque<T>,
+    closed: bool,
+}
+
+impl<T> FrameQueue<T> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                frames: VecDeque::with_capacity(capacity),
+                closed: false,
+            }),
+            ready: Condvar::new(),
+        }
+    }
+
+    /// Block until a frame is available; `None` once the queue is closed
+    fn pop(&self) -> Option<T> {
+        let mut state = self.state.lock();
+        loop {
+            if state.closed {
+                return None;
+            }
+            if let Some(frame) = state.frames.pop_front() {
+                return Some(frame);
+            }
+            self.ready.wait(&mut state);
+        }
+    }
+
+    fn close(&self) {
+        let mut state = self.state.lock();
+        state.closed = true;
+        state.frames.clear();
+        self.ready.notify_all();
+    }
+}
+
+/// Worker handle for a single track
+#[derive(Debug)]
+struct EncodeWorker {
+    queue: Arc<FrameQueue<MediaFrame>>,
+    stats: Arc<Mutex<EncodeStats>>,
+}
+
+/// Encode pipeline running one worker thread per track
+#[derive(Debug)]
+pub struct EncodePipeline {
+    config: EncodePipelineConfig,
+    workers: HashMap<String, EncodeWorker>,
+}
+
+impl EncodePipeline {
+    /// Create a new encode pipeline
+    pub fn new(config: EncodePipelineConfig) -> Self {
+        Self {
+            config,
+            workers: HashMap::new(),
+        }
+    }
+
+    /// Start a worker for `track_id` and return the encoded frame stream
+    pub fn add_track(
+        &mut self,
+        track_id: &str,
+        encoder: Box<dyn SyncEncoder>,
+    ) -> MediaResult<mpsc::Receiver<EncodedFrame>> {
+        if self.workers.contains_key(track_id) {
+            return Err(MediaError::InvalidState {
+                message: format!("Track {} already has an encode worker", track_id),
+            });
+        }
+
+        let queue = Arc::new(FrameQueue::new(self.config.queue_capacity));
+        let stats = Arc::new(Mutex::new(EncodeStats {
+            queue_capacity: self.config.queue_capacity,
+            ..EncodeStats::default()
+        }));
+        let (output_tx, output_rx) = mpsc::channel(self.config.output_capacity.max(1));
+
+        let worker_queue = queue.clone();
+        let worker_stats = stats.clone();
+        let worker_track = track_id.to_string();
+        std::thread::Builder::new()
+            .name(format!("encode-{}", track_id))
+            .spawn(move || {
+                Self::run_worker(worker_track, encoder, worker_queue, worker_stats, output_tx)
+            })
+            .map_err(|e| MediaError::ResourceNotAvailable {
+                resource: format!("encode worker thread: {}", e),
+            })?;
+
+        self.workers
+            .insert(track_id.to_string(), EncodeWorker { queue, stats });
+        Ok(output_rx)
+    }
+
+    /// Stop the worker for `track_id`, discarding any queued frames
+    pub fn remove_track(&mut self, track_id: &str) -> bool {
+        match self.workers.remove(track_id) {
+            Some(worker) => {
+                worker.queue.close();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Queue a frame for encoding without blocking
+    ///
+    /// If the track's queue is full a frame is dropped according to the
+    /// drop policy. A keyframe request on a dropped video frame is carried
+    /// over to a frame that is kept.
+    pub fn submit(&self, track_id: &str, frame: MediaFrame) -> MediaResult<()> {
+        let worker =
+            self.workers
+                .get(track_id)
+                .ok_or_else(|| MediaError::ResourceNotAvailable {
+                    resource: format!("encode worker for track {}", track_id),
+                })?;
+
+        let capacity = self.config.queue_capacity.max(1);
+        let mut state = worker.queue.state.lock();
+        let mut stats = worker.stats.lock();
+        stats.frames_submitted += 1;
+
+        if state.frames.len() >= capacity {
+            stats.frames_dropped += 1;
+            match self.config.drop_policy {
+                FrameDropPolicy::DropOldest => {
+                    let dropped = state.frames.pop_front();
+                    let mut frame = frame;
+                    if let (Some(dropped), MediaFrame::Video(kept)) = (&dropped, &mut frame) {
+                        kept.is_keyframe |= is_keyframe(dropped);
+                    }
+                    state.frames.push_back(frame);
+                }
+                FrameDropPolicy::DropNewest => {
+                    if is_keyframe(&frame) {
+                        if let Some(MediaFrame::Video(kept)) = state.frames.back_mut() {
+                            kept.is_keyframe = true;
+                        }
+                    }
+                }
+            }
+        } else {
+            state.frames.push_back(frame);
+        }
+
+        stats.queue_depth = state.frames.len();
+        stats.peak_queue_depth = stats.peak_queue_depth.max(state.frames.len());
+        drop(stats);
+        drop(state);
+        worker.queue.ready.notify_one();
+        Ok(())
+    }
+
+    /// Get statistics for a track
+    pub fn stats(&self, track_id: &str) -> Option<EncodeStats> {
+        self.workers
+            .get(track_id)
+            .map(|worker| worker.stats.lock().clone())
+    }
+
+    /// Number of tracks with active workers
+    pub fn track_count(&self) -> usize {
+        self.workers.len()
+    }
+
+    fn run_worker(
+        track_id: String,
+        encoder: Box<dyn SyncEncoder>,
+        queue: Arc<FrameQueue<MediaFrame>>,
+        stats: Arc<Mutex<EncodeStats>>,
+        output: mpsc::Sender<EncodedFrame>,
+    ) {
+        while let Some(frame) = queue.pop() {
+            let queue_depth = queue.state.lock().frames.len();
+            stats.lock().queue_depth = queue_depth;
+
+            let (timestamp, is_keyframe) = match &frame {
+                MediaFrame::Video(video) => (video.timestamp, video.is_keyframe),
+                MediaFrame::Audio(audio) => (audio.timestamp, true),
+            };
+
+            let start = Instant::now();
+            let result = encoder.encode_sync(&frame);
+            let encode_time = start.elapsed();
+
+            match result {
+                Ok(data) => {
+                    stats.lock().record_encode(encode_time);
+                    let encoded = EncodedFrame {
+                        track_id: track_id.clone(),
+                        data,
+                        format: FrameFormat::of(&frame),
+                        timestamp,
+                        is_keyframe,
+                        encode_time,
+                    };
+                    // Receiver gone means nobody wants this track any more
+                    if output.blocking_send(encoded).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    stats.lock().encode_errors += 1;
+                    tracing::warn!("Encode failed on track {}: {}", track_id, e);
+                }
+            }
+        }
+
+        tracing::debug!("Encode worker for track {} stopped", track_id);
+    }
+}
+
+impl Default for EncodePipeline {
+    fn default() -> Self {
+        Self::new(EncodePipelineConfig::default())
+    }
+}
+
+impl Drop for EncodePipeline {
+    fn drop(&mut self) {
+        for worker in self.workers.values() {
+            worker.queue.close();
+        }
+    }
+}
+
+/// Decode pipeline configuration
+#[derive(Debug, Clone)]
+pub struct DecodePipelineConfig {
+    /// Maximum encoded frames queued per track
+    pub queue_capacity: usize,
+    /// Maximum decoded frames buffered per track before the worker waits
+    pub output_capacity: usize,
+    /// Skip frames whose render deadline passed before decoding started
+    pub drop_late_frames: bool,
+}
+
+impl Default for DecodePipelineConfig {
+    fn default() -> Self {
+        Self {
+            queue_capacity: 8,
+            output_capacity: 4,
+            drop_late_frames: true,
+        }
+    }
+}
+
+/// Decoded output of the pipeline
+#[derive(Debug, Clone)]
+pub struct DecodedFrame {
+    /// Track the frame belongs to
+    pub track_id: String,
+    /// Decoded media frame, presented in the format of the encoded frame
+    pub frame: MediaFrame,
+    /// Time spent in the decoder
+    pub decode_time: Duration,
+}
+
+/// Per-track queue and decoder statistics
+#[derive(Debug, Clone, Default)]
+pub struct DecodeStats {
+    /// Frames handed to the pipeline
+    pub frames_submitted: u64,
+    /// Frames decoded and delivered
+    pub frames_decoded: u64,
+    /// Frames discarded because the queue was full
+    pub frames_dropped: u64,
+    /// Frames discarded because their render deadline had passed
+    pub frames_late: u64,
+    /// Frames skipped while waiting for a keyframe after a drop
+    pub frames_skipped: u64,
+    /// Frames the decoder rejected
+    pub decode_errors: u64,
+    /// Frames currently queued
+    pub queue_depth: usize,
+    /// Queue capacity
+    pub queue_capacity: usize,
+    /// Highest queue depth seen
+    pub peak_queue_depth: usize,
+    /// Average time per decode
+    pub average_decode_time: Duration,
+    /// Time taken by the most recent decode
+    pub last_decode_time: Duration,
+}
+
+impl DecodeStats {
+    /// Queue occupancy from 0.0 (empty) to 1.0 (full)
+    pub fn occupancy(&self) -> f32 {
+        if self.queue_capacity == 0 {
+            0.0
+        } else {
+            self.queue_depth as f32 / self.queue_capacity as f32
+        }
+    }
+
+    /// Fraction of submitted frames that never reached the renderer
+    pub fn drop_ratio(&self) -> f32 {
+        if self.frames_submitted == 0 {
+            0.0
+        } else {
+            let lost = self.frames_dropped + self.frames_late + self.frames_skipped;
+            lost as f32 / self.frames_submitted as f32
+        }
+    }
+
+    /// Playback quality score (0-100) from drops and decoder load
+    ///
+    /// `frame_interval` is the time budget per frame (e.g. 33ms at 30fps).
+    /// Every lost frame costs its share of the score, and a decoder using
+    /// more than half the budget costs up to 30 further points.
+    pub fn quality_score(&self, frame_interval: Duration) -> u8 {
+        let drop_penalty = self.drop_ratio() * 100.0;
+        let load_penalty = if frame_interval.is_zero() {
+            0.0
+        } else {
+            let load = self.average_decode_time.as_secs_f32() / frame_interval.as_secs_f32();
+            ((load - 0.5) * 60.0).clamp(0.0, 30.0)
+        };
+        (100.0 - drop_penalty - load_penalty).clamp(0.0, 100.0) as u8
+    }
+
+    fn record_decode(&mut self, decode_time: Duration) {
+        self.frames_decoded += 1;
+        self.last_decode_time = decode_time;
+        let count = self.frames_decoded as u32;
+        self.average_decode_time = (self.average_decode_time * (count - 1) + decode_time) / count;
+    }
+}
+
+/// Encoded frame waiting for its decode worker
+#[derive(Debug)]
+struct PendingDecode {
+    frame: EncodedFrame,
+    render_deadline: Instant,
+    /// An earlier frame was dropped, so references may be missing
+    after_gap: bool,
+}
+
+/// Worker handle for a single track
+#[derive(Debug)]
+struct DecodeWorker {
+    queue: Arc<FrameQueue<PendingDecode>>,
+    stats: Arc<Mutex<DecodeStats>>,
+}
+
+/// Decode pipeline running one worker thread per track
+#[derive(Debug)]
+pub struct DecodePipeline {
+    config: DecodePipelineConfig,
+    workers: HashMap<String, DecodeWorker>,
+}
+
+impl DecodePipeline {
+    /// Create a new decode pipeline
+    pub fn new(config: DecodePipelineConfig) -> Self {
+        Self {
+            config,
+            workers: HashMap::new(),
+        }
+    }
+
+    /// Start a worker for `track_id` and return the decoded frame stream
+    pub fn add_track(
+        &mut self,
+        track_id: &str,
+        decoder: Box<dyn SyncDecoder>,
+    ) -> MediaResult<mpsc::Receiver<DecodedFrame>> {
+        if self.workers.contains_key(track_id) {
+            return Err(MediaError::InvalidState {
+                message: format!("Track {} already has a decode worker", track_id),
+            });
+        }
+
+        let queue = Arc::new(FrameQueue::new(self.config.queue_capacity));
+        let stats = Arc::new(Mutex::new(DecodeStats {
+            queue_capacity: self.config.queue_capacity,
+            ..DecodeStats::default()
+        }));
+        let (output_tx, output_rx) = mpsc::channel(self.config.output_capacity.max(1));
+
+        let worker_queue = queue.clone();
+        let worker_stats = stats.clone();
+        let worker_track = track_id.to_string();
+        let drop_late_frames = self.config.drop_late_frames;
+        std::thread::Builder::new()
+            .name(format!("decode-{}", track_id))
+            .spawn(move || {
+                Self::run_worker(
+                    worker_track,
+                    decoder,
+                    worker_queue,
+                    worker_stats,
+                    output_tx,
+                    drop_late_frames,
+                )
+            })
+            .map_err(|e| MediaError::ResourceNotAvailable {
+                resource: format!("decode worker thread: {}", e),
+            })?;
+
+        self.workers
+            .insert(track_id.to_string(), DecodeWorker { queue, stats });
+        Ok(output_rx)
+    }
+
+    /// Stop the worker for `track_id`, discarding any queued frames
+    pub fn remove_track(&mut self, track_id: &str) -> bool {
+        match self.workers.remove(track_id) {
+            Some(worker) => {
+                worker.queue.close();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Queue an encoded frame for decoding without blocking
+    ///
+    /// `render_deadline` is when the decoded frame is due on screen or at
+    /// the speaker. If the queue is full the oldest frame is dropped, and
+    /// video after the gap is skipped until the next keyframe.
+    pub fn submit(
+        &self,
+        track_id: &str,
+        frame: EncodedFrame,
+        render_deadline: Instant,
+    ) -> MediaResult<()> {
+        let worker =
+            self.workers
+                .get(track_id)
+                .ok_or_else(|| MediaError::ResourceNotAvailable {
+                    resource: format!("decode worker for track {}", track_id),
+                })?;
+
+        let capacity = self.config.queue_capacity.max(1);
+        let mut state = worker.queue.state.lock();
+        let mut stats = worker.stats.lock();
+        stats.frames_submitted += 1;
+
+        let mut pending = PendingDecode {
+            frame,
+            render_deadline,
+            after_gap: false,
+        };
+        if state.frames.len() >= capacity {
+            stats.frames_dropped += 1;
+            state.frames.pop_front();
+            match state.frames.front_mut() {
+                Some(next) => next.after_gap = true,
+                None => pending.after_gap = true,
+            }
+        }
+        state.frames.push_back(pending);
+
+        stats.queue_depth = state.frames.len();
+        stats.peak_queue_depth = stats.peak_queue_depth.max(state.frames.len());
+        drop(stats);
+        drop(state);
+        worker.queue.ready.notify_one();
+        Ok(())
+    }
+
+    /// Get statistics for a track
+    pub fn stats(&self, track_id: &str) -> Option<DecodeStats> {
+        self.workers
+            .get(track_id)
+            .map(|worker| worker.stats.lock().clone())
+    }
+
+    /// Number of tracks with active workers
+    pub fn track_count(&self) -> usize {
+        self.workers.len()
+    }
+
+    fn run_worker(
+        track_id: String,
+        decoder: Box<dyn SyncDecoder>,
+        queue: Arc<FrameQueue<PendingDecode>>,
+        stats: Arc<Mutex<DecodeStats>>,
+        output: mpsc::Sender<DecodedFrame>,
+        drop_late_frames: bool,
+    ) {
+        let mut awaiting_keyframe = false;
+
+        while let Some(pending) = queue.pop() {
+            let queue_depth = queue.state.lock().frames.len();
+            stats.lock().queue_depth = queue_depth;
+
+            let PendingDecode {
+                frame,
+                render_deadline,
+                after_gap,
+            } = pending;
+
+            // Delta frames after a gap reference data the decoder never saw
+            awaiting_keyframe |= after_gap;
+            if awaiting_keyframe && !frame.is_keyframe {
+                stats.lock().frames_skipped += 1;
+                continue;
+            }
+            awaiting_keyframe = false;
+
+            let late = drop_late_frames && Instant::now() > render_deadline;
+            if late && !frame.is_keyframe {
+                stats.lock().frames_late += 1;
+                awaiting_keyframe = true;
+                continue;
+            }
+
+            let start = Instant::now();
+            let result = decoder.decode_sync(&frame.data);
+            let decode_time = start.elapsed();
+
+            match result {
+                // A late keyframe is still decoded to refresh the reference
+                // picture, but there is no point rendering it
+                Ok(_) if late => {
+                    stats.lock().frames_late += 1;
+                }
+                Ok(decoded) => {
+                    stats.lock().record_decode(decode_time);
+                    let decoded = DecodedFrame {
+                        track_id: track_id.clone(),
+                        frame: frame.format.present(decoded),
+                        decode_time,
+                    };
+                    if output.blocking_send(decoded).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    stats.lock().decode_errors += 1;
+                    awaiting_keyframe = true;
+                    tracing::warn!("Decode failed on track {}: {}", track_id, e);
+                }
+            }
+        }
+
+        tracing::debug!("Decode worker for track {} stopped", track_id);
+    }
+}
+
+impl Default for DecodePipeline {
+    fn default() -> Self {
+        Self::new(DecodePipelineConfig::default())
+    }
+}
+
+impl Drop for DecodePipeline {
+    fn drop(&mut self) {
+        for worker in self.workers.values() {
+            worker.queue.close();
+        }
+    }
+}
+
+fn is_keyframe(frame: &MediaFrame) -> bool {
+    matches!(frame, MediaFrame::Video(video) if video.is_keyframe)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codecs::{CodecInfo, CodecResult};
+    use crate::tracks::VideoFrame;
+
+    /// Encoder that reports each frame it starts, then holds it until let
+    /// through, so tests control when the worker is busy
+    #[derive(Debug)]
+    struct GatedEncoder {
+        started: Mutex<std::sync::mpsc::Sender<u64>>,
+        release: Mutex<std::sync::mpsc::Receiver<()>>,
+    }
+
+    impl GatedEncoder {
+        /// Encoder, the timestamps of frames it starts, and its gate;
+        /// dropping the gate lets every frame through
+        fn new() -> (
+            Self,
+            std::sync::mpsc::Receiver<u64>,
+            std::sync::mpsc::Sender<()>,
+        ) {
+            let (started_tx, started) = std::sync::mpsc::channel();
+            let (gate, release_rx) = std::sync::mpsc::channel();
+            let encoder = Self {
+                started: Mutex::new(started_tx),
+                release: Mutex::new(release_rx),
+            };
+            (encoder, started, gate)
+        }
+    }
+
+    impl SyncEncoder for GatedEncoder {
+        fn encode_sync(&self, frame: &MediaFrame) -> CodecResult<Vec<u8>> {
+            let timestamp = match frame {
+                MediaFrame::Video(video) => video.timestamp,
+                MediaFrame::Audio(audio) => audio.timestamp,
+            };
+            let _ = self.started.lock().send(timestamp);
+            let _ = self.release.lock().recv();
+            Ok(timestamp.to_be_bytes().to_vec())
+        }
+
+        fn get_codec_info(&self) -> CodecInfo {
+            CodecInfo {
+                name: "gated".to_string(),
+                mime_type: "video/gated".to_string(),
+                sample_rate: None,
+                channels: None,
+            }
+        }
+    }
+
+    /// Decoder that takes a fixed amount of time per frame
+    #[derive(Debug)]
+    struct SlowDecoder {
+        delay: Duration,
+    }
+
+    impl SyncDecoder for SlowDecoder {
+        fn decode_sync(&self, data: &[u8]) -> CodecResult<MediaFrame> {
+            std::thread::sleep(self.delay);
+            let mut timestamp = [0u8; 8];
+            timestamp.copy_from_slice(&data[..8]);
+            Ok(video_frame(u64::from_be_bytes(timestamp), false))
+        }
+
+        fn get_codec_info(&self) -> CodecInfo {
+            CodecInfo {
+                name: "slow".to_string(),
+                mime_type: "video/slow".to_string(),
+                sample_rate: None,
+                channels: None,
+            }
+        }
+    }
+
+    fn encoded_frame(timestamp: u64, is_keyframe: bool) -> EncodedFrame {
+        EncodedFrame {
+            track_id: "camera".to_string(),
+            data: timestamp.to_be_bytes().to_vec(),
+            format: FrameFormat::Video {
+                width: 2,
+                height: 2,
+                rotation: VideoRotation::Deg0,
+            },
+            timestamp,
+            is_keyframe,
+            encode_time: Duration::ZERO,
+        }
+    }
+
+    fn decoded_timestamp(decoded: &DecodedFrame) -> u64 {
+        match &decoded.frame {
+            MediaFrame::Video(video) => video.timestamp,
+            MediaFrame::Audio(audio) => audio.timestamp,
+        }
+    }
+
+    fn video_frame(timestamp: u64, is_keyframe: bool) -> MediaFrame {
+        MediaFrame::Video(VideoFrame {
+            width: 2,
+            height: 2,
+            data: vec![0; 6],
+            timestamp,
+            is_keyframe,
+            rotation: VideoRotation::Deg0,
+        })
+    }
+
+    #[test]
+    fn test_encode_pipeline_drops_when_encoder_falls_behind() {
+        let mut pipeline = EncodePipeline::new(EncodePipelineConfig {
+            queue_capacity: 2,
+            output_capacity: 16,
+            drop_policy: FrameDropPolicy::DropOldest,
+        });
+        let (encoder, started, gate) = GatedEncoder::new();
+        let mut output = pipeline.add_track("camera", Box::new(encoder)).unwrap();
+
+        // Submitting never blocks while the encoder is stuck on a frame
+        pipeline.submit("camera", video_frame(0, true)).unwrap();
+        assert_eq!(started.recv().unwrap(), 0);
+        for timestamp in 1..10 {
+            pipeline
+                .submit("camera", video_frame(timestamp, false))
+                .unwrap();
+        }
+
+        let stats = pipeline.stats("camera").unwrap();
+        assert_eq!(stats.frames_submitted, 10);
+        assert_eq!(stats.frames_dropped, 7);
+        assert_eq!(stats.peak_queue_depth, 2);
+
+        // The newest frames always make it through
+        drop(gate);
+        let timestamps: Vec<u64> = (0..3)
+            .map(|_| output.blocking_recv().unwrap().timestamp)
+            .collect();
+        assert_eq!(timestamps, vec![0, 8, 9]);
+        assert_eq!(pipeline.stats("camera").unwrap().frames_encoded, 3);
+
+        assert!(pipeline.submit("screen", video_frame(0, false)).is_err());
+        assert!(pipeline.remove_track("camera"));
+        assert_eq!(pipeline.track_count(), 0);
+    }
+
+    #[test]
+    fn test_encode_pipeline_keeps_keyframe_requests() {
+        let mut pipeline = EncodePipeline::new(EncodePipelineConfig {
+            queue_capacity: 1,
+            output_capacity: 16,
+            drop_policy: FrameDropPolicy::DropOldest,
+        });
+        let (encoder, started, gate) = GatedEncoder::new();
+        let mut output = pipeline.add_track("camera", Box::new(encoder)).unwrap();
+
+        // Occupy the worker, then replace a queued keyframe with a delta frame
+        pipeline.submit("camera", video_frame(0, false)).unwrap();
+        assert_eq!(started.recv().unwrap(), 0);
+        pipeline.submit("camera", video_frame(1, true)).unwrap();
+        pipeline.submit("camera", video_frame(2, false)).unwrap();
+        drop(gate);
+
+        let first = output.blocking_recv().unwrap();
+        assert_eq!(first.timestamp, 0);
+        let second = output.blocking_recv().unwrap();
+        assert_eq!(second.timestamp, 2);
+        assert!(second.is_keyframe);
+    }
+
+    #[test]
+    fn test_decode_pipeline_drops_late_frames_until_keyframe() {
+        let mut pipeline = DecodePipeline::default();
+        let mut output = pipeline
+            .add_track(
+                "camera",
+                Box::new(SlowDecoder {
+                    delay: Duration::from_millis(20),
+                }),
+            )
+            .unwrap();
+
+        // Frame 1 misses its deadline while frame 0 is decoding; 2-4 depend
+        // on it and are skipped until the keyframe at 5
+        let now = Instant::now();
+        let on_time = now + Duration::from_secs(5);
+        pipeline
+            .submit("camera", encoded_frame(0, true), on_time)
+            .unwrap();
+        for timestamp in 1..5 {
+            pipeline
+                .submit(
+                    "camera",
+                    encoded_frame(timestamp, false),
+                    now + Duration::from_millis(5),
+                )
+                .unwrap();
+        }
+        pipeline
+            .submit("camera", encoded_frame(5, true), on_time)
+            .unwrap();
+        pipeline
+            .submit("camera", encoded_frame(6, false), on_time)
+            .unwrap();
+
+        let timestamps: Vec<u64> = (0..3)
+            .map(|_| decoded_timestamp(&output.blocking_recv().unwrap()))
+            .collect();
+        assert_eq!(timestamps, vec![0, 5, 6]);
+
+        let stats = pipeline.stats("camera").unwrap();
+        assert_eq!(stats.frames_submitted, 7);
+        assert_eq!(stats.frames_decoded, 3);
+        assert_eq!(stats.frames_late, 1);
+        assert_eq!(stats.frames_skipped, 3);
+        assert!(stats.average_decode_time >= Duration::from_millis(20));
+
+        // Lost frames and a busy decoder both pull the score down
+        assert_eq!(
+            DecodeStats::default().quality_score(Duration::from_millis(33)),
+            100
+        );
+        assert!(stats.quality_score(Duration::from_millis(33)) < 50);
+    }
+
+    #[test]
+    fn test_decode_pipeline_overflow_waits_for_keyframe() {
+        let mut pipeline = DecodePipeline::new(DecodePipelineConfig {
+            queue_capacity: 2,
+            output_capacity: 16,
+            drop_late_frames: true,
+        });
+        let mut output = pipeline
+            .add_track(
+                "camera",
+                Box::new(SlowDecoder {
+                    delay: Duration::from_millis(50),
+                }),
+            )
+            .unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        pipeline
+            .submit("camera", encoded_frame(0, true), deadline)
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+        for timestamp in 1..4 {
+            pipeline
+                .submit("camera", encoded_frame(timestamp, false), deadline)
+                .unwrap();
+        }
+        pipeline
+            .submit("camera", encoded_frame(4, true), deadline)
+            .unwrap();
+
+        assert_eq!(decoded_timestamp(&output.blocking_recv().unwrap()), 0);
+        assert_eq!(decoded_timestamp(&output.blocking_recv().unwrap()), 4);
+
+        let stats = pipeline.stats("camera").unwrap();
+        assert_eq!(stats.frames_dropped, 2);
+        assert_eq!(stats.frames_skipped, 1);
+        assert!(stats.peak_queue_depth <= 2);
+    }
+}
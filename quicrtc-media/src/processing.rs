@@ -1,34 +1,446 @@
 //! Media processing and quality control
 
+use crate::codecs::{Codec, CodecConfig, CodecRegistry, H264Codec, H264Config, SyncEncoder};
+use crate::pipeline::{EncodedFrame, FrameFormat};
+use crate::render::remix_channel_count;
 use crate::tracks::{AudioFrame, MediaFrame, VideoFrame};
-use quicrtc_core::{MoqObject, MoqObjectStatus, QuicRtcError, TrackNamespace};
-use std::collections::{BTreeMap, HashMap};
+use quicrtc_core::{
+    AudioContentHint, Backpressure, BackpressureReason, ChannelLayout, EcnCounts, MoqObject,
+    MoqObjectMetadata, MoqObjectStatus, MoqTrackType, QuicRtcError, TrackNamespace,
+    VideoContentHint, VideoRotation,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+/// Priority of thumbnail objects, below the full-rate media they preview
+const THUMBNAIL_PUBLISHER_PRIORITY: u8 = 4;
+
+/// Bitrate kept after congestion-experienced marks on classic ECN traffic
+const ECN_CLASSIC_BACKOFF: f32 = 0.8;
+
+/// Lowest video bitrate backpressure takes the encoder down to
+const BACKPRESSURE_MIN_VIDEO_BITRATE: u32 = 100_000;
+
+/// Room kept in a datagram next to a video slice, for the object header
+/// and the most extension headers an object can carry
+const DATAGRAM_OBJECT_OVERHEAD: usize = MoqObjectMetadata::MAX_ENCODED_SIZE + 32;
+
+/// Smallest slice size the path MTU takes the encoder down to
+const MIN_VIDEO_SLICE_SIZE: usize = 256;
+
+/// Bitrate kept after sustained backpressure from the send path
+///
+/// Dropped and late objects mean media is already being lost, so they get a
+/// deeper cut than a queue that is only filling up.
+fn backpressure_backoff(backpressure: &Backpressure) -> f32 {
+    match backpressure.reason {
+        BackpressureReason::SendQueueOverflow | BackpressureReason::LatencyBudgetExceeded => 0.7,
+        BackpressureReason::SendQueueHighWater | BackpressureReason::StreamCreditWait => 0.85,
+    }
+}
+
 /// Media processor for handling MoQ objects and media frames
 #[derive(Debug)]
 pub struct MediaProcessor {
     /// MoQ object assembler for frame reconstruction
     assembler: MoqObjectAssembler,
     /// Codec registry for encoding/decoding
-    codec_registry: crate::codecs::CodecRegistry,
+    codec_registry: CodecRegistry,
+    /// Codec used to encode outgoing audio
+    audio_codec: Option<String>,
+    /// Codec used to encode outgoing video
+    video_codec: Option<String>,
+    /// Codecs negotiated for incoming tracks
+    track_codecs: HashMap<TrackNamespace, String>,
+    /// What outgoing audio contains
+    audio_content_hint: AudioContentHint,
+    /// Audio content hints received on incoming tracks
+    track_audio_hints: HashMap<TrackNamespace, AudioContentHint>,
+    /// Where outgoing video rotation is applied
+    rotation_mode: VideoRotationMode,
+    /// Active video encoder configuration
+    video_config: H264Config,
+    /// Encoder configuration waiting for the next group boundary
+    pending_video_config: Option<H264Config>,
+    /// Whether the next video group was asked to start early
+    keyframe_requested: bool,
+    /// Video frames sent since the last keyframe
+    frames_since_keyframe: u32,
+    /// Whether the encoder runs at the configured size turned a quarter
+    /// turn, for frames pre-rotated into portrait
+    portrait_encoder: bool,
+    /// Degradation preference for outgoing video, tuned by the content hint
+    quality_controller: QualityController,
+}
+
+/// Where video rotation is applied
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VideoRotationMode {
+    /// Rotate frames upright before encoding; no rotation metadata is sent
+    PreRotate,
+    /// Send rotation as object metadata and let the receiver's renderer apply it
+    #[default]
+    Renderer,
 }
 
 impl MediaProcessor {
     /// Create new media processor
     pub fn new() -> Self {
+        let codec_registry = CodecRegistry::with_defaults().unwrap_or_default();
         Self {
             assembler: MoqObjectAssembler::new(),
-            codec_registry: crate::codecs::CodecRegistry::with_defaults().unwrap_or_default(),
+            audio_codec: codec_registry.preferred_codec(&MoqTrackType::Audio),
+            video_codec: codec_registry.preferred_codec(&MoqTrackType::Video),
+            track_codecs: HashMap::new(),
+            audio_content_hint: AudioContentHint::default(),
+            track_audio_hints: HashMap::new(),
+            codec_registry,
+            rotation_mode: VideoRotationMode::default(),
+            video_config: H264Config::default(),
+            pending_video_config: None,
+            keyframe_requested: false,
+            frames_since_keyframe: 0,
+            portrait_encoder: false,
+            quality_controller: QualityController::following(&H264Config::default()),
         }
     }
 
     /// Create new media processor with custom assembler configuration
     pub fn with_assembler_config(config: AssemblerConfig) -> Self {
+        let codec_registry = CodecRegistry::with_defaults().unwrap_or_default();
         Self {
             assembler: MoqObjectAssembler::with_config(config),
-            codec_registry: crate::codecs::CodecRegistry::with_defaults().unwrap_or_default(),
+            audio_codec: codec_registry.preferred_codec(&MoqTrackType::Audio),
+            video_codec: codec_registry.preferred_codec(&MoqTrackType::Video),
+            track_codecs: HashMap::new(),
+            audio_content_hint: AudioContentHint::default(),
+            track_audio_hints: HashMap::new(),
+            codec_registry,
+            rotation_mode: VideoRotationMode::default(),
+            video_config: H264Config::default(),
+            pending_video_config: None,
+            keyframe_requested: false,
+            frames_since_keyframe: 0,
+            portrait_encoder: false,
+            quality_controller: QualityController::following(&H264Config::default()),
+        }
+    }
+
+    /// Set where outgoing video rotation is applied
+    pub fn with_rotation_mode(mut self, mode: VideoRotationMode) -> Self {
+        self.rotation_mode = mode;
+        self
+    }
+
+    /// Where outgoing video rotation is applied
+    pub fn rotation_mode(&self) -> VideoRotationMode {
+        self.rotation_mode
+    }
+
+    /// Set what outgoing audio contains
+    ///
+    /// Anything other than speech is stamped on every audio object so
+    /// receivers can skip speech processing for the track.
+    pub fn with_audio_content_hint(mut self, hint: AudioContentHint) -> Self {
+        self.audio_content_hint = hint;
+        self
+    }
+
+    /// What outgoing audio contains
+    pub fn audio_content_hint(&self) -> AudioContentHint {
+        self.audio_content_hint
+    }
+
+    /// Set what outgoing video mostly shows, see [`Self::set_video_content_hint`]
+    pub fn with_video_content_hint(mut self, hint: VideoContentHint) -> Self {
+        self.set_video_content_hint(hint);
+        self
+    }
+
+    /// Change what outgoing video mostly shows
+    ///
+    /// The hint tunes the encoder's rate control and keyframe spacing and
+    /// what the quality controller gives up first under congestion. It is
+    /// stamped on every keyframe so receivers can tell screen content from
+    /// camera video. Like other encoder changes, this takes effect when the
+    /// next group starts.
+    pub fn set_video_content_hint(&mut self, hint: VideoContentHint) {
+        let mut config = self
+            .pending_video_config
+            .clone()
+            .unwrap_or_else(|| self.video_config.clone());
+        config.content_hint = hint;
+        self.queue_video_config(config);
+        self.quality_controller.set_content_hint(hint);
+    }
+
+    /// What outgoing video mostly shows, including a change still waiting
+    /// for the next group
+    pub fn video_content_hint(&self) -> VideoContentHint {
+        self.pending_video_config
+            .as_ref()
+            .unwrap_or(&self.video_config)
+            .content_hint
+    }
+
+    /// Quality controller adapting outgoing video
+    pub fn quality_controller(&self) -> &QualityController {
+        &self.quality_controller
+    }
+
+    /// What an incoming audio track contains, as last signalled by its publisher
+    pub fn track_audio_content_hint(&self, track: &TrackNamespace) -> AudioContentHint {
+        self.track_audio_hints
+            .get(track)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Use a custom codec registry
+    ///
+    /// Outgoing media switches to the registry's most preferred audio and
+    /// video codecs.
+    pub fn with_codec_registry(mut self, registry: CodecRegistry) -> Self {
+        self.audio_codec = registry.preferred_codec(&MoqTrackType::Audio);
+        self.video_codec = registry.preferred_codec(&MoqTrackType::Video);
+        self.track_codecs.clear();
+        self.codec_registry = registry;
+        self
+    }
+
+    /// Codec registry used for encoding and decoding
+    pub fn codec_registry(&self) -> &CodecRegistry {
+        &self.codec_registry
+    }
+
+    /// Codec used to encode outgoing audio
+    pub fn audio_codec(&self) -> Option<&str> {
+        self.audio_codec.as_deref()
+    }
+
+    /// Codec used to encode outgoing video
+    pub fn video_codec(&self) -> Option<&str> {
+        self.video_codec.as_deref()
+    }
+
+    /// Encode outgoing media of the codec's kind with a registered codec
+    pub fn set_encoder_codec(&mut self, name: &str) -> Result<(), QuicRtcError> {
+        let track_type = self
+            .codec_registry
+            .get_codec(name)
+            .and_then(|codec| SyncEncoder::get_codec_info(codec.as_ref()).track_type())
+            .ok_or_else(|| QuicRtcError::UnsupportedCodec {
+                codec: name.to_string(),
+            })?;
+
+        match track_type {
+            MoqTrackType::Audio => self.audio_codec = Some(name.to_string()),
+            MoqTrackType::Video => self.video_codec = Some(name.to_string()),
+            MoqTrackType::Data => {
+                return Err(QuicRtcError::UnsupportedCodec {
+                    codec: name.to_string(),
+                })
+            }
+        }
+        Ok(())
+    }
+
+    /// Choose the decoder for an incoming track from the codecs its publisher supports
+    ///
+    /// Returns the negotiated codec name, or an error when no registered
+    /// codec of `track_type` matches any of `remote_codecs`.
+    pub fn negotiate_track_codec(
+        &mut self,
+        track: TrackNamespace,
+        track_type: &MoqTrackType,
+        remote_codecs: &[String],
+    ) -> Result<String, QuicRtcError> {
+        let codec = self
+            .codec_registry
+            .negotiate(track_type, remote_codecs)
+            .ok_or_else(|| QuicRtcError::UnsupportedCodec {
+                codec: remote_codecs.join(", "),
+            })?;
+        self.track_codecs.insert(track, codec.clone());
+        Ok(codec)
+    }
+
+    /// Codec negotiated for an incoming track
+    pub fn track_codec(&self, track: &TrackNamespace) -> Option<&str> {
+        self.track_codecs.get(track).map(String::as_str)
+    }
+
+    /// Request a new video encoder resolution, framerate and bitrate
+    ///
+    /// The change is held until the next group starts so it lands on a
+    /// keyframe the stream was going to send anyway, instead of forcing an
+    /// extra one. Requests made within the same group are coalesced and the
+    /// latest one wins.
+    pub fn request_video_reconfiguration(&mut self, settings: &QualitySettings) {
+        let base = self
+            .pending_video_config
+            .as_ref()
+            .unwrap_or(&self.video_config);
+        let config = H264Config {
+            width: settings.video_width,
+            height: settings.video_height,
+            bitrate: settings.video_bitrate,
+            framerate: settings.video_framerate,
+            content_hint: base.content_hint,
+            keyframe_interval_frames: base.keyframe_interval_frames,
+            max_gop_duration: base.max_gop_duration,
+            max_slice_size: base.max_slice_size,
+        };
+        self.queue_video_config(config);
+    }
+
+    /// Change the keyframe interval and the longest time between keyframes
+    ///
+    /// `None` for the interval derives it from the content hint. Like other
+    /// encoder changes, this takes effect when the next group starts.
+    pub fn set_keyframe_interval(
+        &mut self,
+        frames: Option<u32>,
+        max_gop_duration: Option<Duration>,
+    ) {
+        let mut config = self
+            .pending_video_config
+            .clone()
+            .unwrap_or_else(|| self.video_config.clone());
+        config.keyframe_interval_frames = frames;
+        config.max_gop_duration = max_gop_duration;
+        self.queue_video_config(config);
+    }
+
+    /// Hold an encoder configuration for the next group, unless it matches the active one
+    fn queue_video_config(&mut self, config: H264Config) {
+        let active = &self.video_config;
+        let unchanged = config.width == active.width
+            && config.height == active.height
+            && config.bitrate == active.bitrate
+            && config.framerate == active.framerate
+            && config.content_hint == active.content_hint
+            && config.keyframe_interval_frames == active.keyframe_interval_frames
+            && config.max_gop_duration == active.max_gop_duration
+            && config.max_slice_size == active.max_slice_size;
+        self.quality_controller.follow_video_config(&config);
+        self.pending_video_config = if unchanged { None } else { Some(config) };
+    }
+
+    /// Adapt outgoing video to MoQ delivery metrics
+    ///
+    /// The quality controller's new settings are requested from the encoder
+    /// with [`Self::request_video_reconfiguration`], so they land at the next
+    /// group boundary.
+    pub fn adapt_quality(&mut self, moq_metrics: &MoqDeliveryMetrics) -> QualitySettings {
+        let settings = self.quality_controller.adapt_quality(moq_metrics);
+        self.request_video_reconfiguration(&settings);
+        settings
+    }
+
+    /// Lower outgoing video quality for detected congestion, see
+    /// [`QualityController::handle_congestion`]
+    pub fn handle_congestion(&mut self, congestion_level: CongestionLevel) {
+        self.quality_controller.handle_congestion(congestion_level);
+        self.follow_quality_controller();
+    }
+
+    /// Lower outgoing video quality for ECN marks, see
+    /// [`QualityController::handle_ecn_feedback`]
+    pub fn handle_ecn_feedback(&mut self, counts: &EcnCounts) {
+        self.quality_controller.handle_ecn_feedback(counts);
+        self.follow_quality_controller();
+    }
+
+    /// Request the quality controller's current settings from the encoder
+    fn follow_quality_controller(&mut self) {
+        let settings = self.quality_controller.current_settings().clone();
+        self.request_video_reconfiguration(&settings);
+    }
+
+    /// Cut the video bitrate on sustained congestion reported by the send
+    /// path, see [`quicrtc_core::BackpressureSignal`]
+    ///
+    /// Unlike [`Self::request_video_reconfiguration`], this doesn't wait
+    /// for the current group to run out: the next frame starts a new group
+    /// at the lower bitrate. One keyframe costs less than the rest of a
+    /// group at a bitrate the network can't carry.
+    pub fn handle_backpressure(&mut self, backpressure: &Backpressure) {
+        let mut config = self
+            .pending_video_config
+            .clone()
+            .unwrap_or_else(|| self.video_config.clone());
+        let bitrate = ((config.bitrate as f32 * backpressure_backoff(backpressure)) as u32)
+            .max(BACKPRESSURE_MIN_VIDEO_BITRATE);
+        if bitrate >= config.bitrate {
+            return;
         }
+        tracing::debug!(
+            "Send path backpressure ({:?}), lowering video bitrate to {} bps",
+            backpressure.reason,
+            bitrate
+        );
+        config.bitrate = bitrate;
+        self.queue_video_config(config);
+        self.request_keyframe();
+    }
+
+    /// Size video slices to the path MTU, given the largest datagram the
+    /// path carries as found by path MTU discovery
+    ///
+    /// Slices are capped so each fits one datagram with its object headers,
+    /// and IP never has to fragment them; a path that shrinks, such as a
+    /// move onto a VPN or cellular tunnel, shrinks the slices with it. Like
+    /// other encoder changes, this takes effect when the next group starts.
+    pub fn handle_path_mtu(&mut self, max_datagram_size: usize) {
+        let slice_size = max_datagram_size
+            .saturating_sub(DATAGRAM_OBJECT_OVERHEAD)
+            .max(MIN_VIDEO_SLICE_SIZE) as u32;
+        let mut config = self
+            .pending_video_config
+            .clone()
+            .unwrap_or_else(|| self.video_config.clone());
+        if config.max_slice_size == Some(slice_size) {
+            return;
+        }
+        tracing::debug!(
+            "Path carries {} byte datagrams, capping video slices at {} bytes",
+            max_datagram_size,
+            slice_size
+        );
+        config.max_slice_size = Some(slice_size);
+        self.queue_video_config(config);
+    }
+
+    /// Ask for a keyframe as soon as possible
+    ///
+    /// Used when a subscriber joins mid-stream or reports it lost the
+    /// reference frames. [`Self::keyframe_due`] turns true, and the next
+    /// frame sent as object 0 is encoded as a keyframe.
+    pub fn request_keyframe(&mut self) {
+        self.keyframe_requested = true;
+    }
+
+    /// Whether the next video frame should start a new group
+    ///
+    /// True once a keyframe was requested or the current group has reached
+    /// the keyframe interval. Callers start the group by sending the frame
+    /// as object 0.
+    pub fn keyframe_due(&self) -> bool {
+        self.keyframe_requested
+            || self.frames_since_keyframe + 1 >= self.video_config.keyframe_interval()
+    }
+
+    /// Active video encoder configuration
+    pub fn video_config(&self) -> &H264Config {
+        &self.video_config
+    }
+
+    /// Check if a video reconfiguration is waiting for the next group
+    pub fn has_pending_video_reconfiguration(&self) -> bool {
+        self.pending_video_config.is_some()
     }
 
     /// Process incoming MoQ object and potentially return a decoded media frame
@@ -36,16 +448,44 @@ impl MediaProcessor {
         &mut self,
         object: MoqObject,
     ) -> Result<Option<MediaFrame>, QuicRtcError> {
-        // Use the assembler to reconstruct frames from MoQ objects
-        match self.assembler.add_object(object)? {
+        let codec = self.track_codecs.get(&object.track_namespace).cloned();
+        match self.assemble_incoming_object(object)? {
             Some(assembled_frame) => {
                 // If the assembled frame contains encoded data, decode it
-                self.decode_assembled_frame(assembled_frame).map(Some)
+                self.decode_assembled_frame(assembled_frame, codec.as_deref())
+                    .map(Some)
             }
             None => Ok(None), // Frame not yet complete
         }
     }
 
+    /// Add an incoming MoQ object to its group without decoding
+    ///
+    /// Returns the group's frame once complete. Video comes back still
+    /// encoded, at the resolution and rotation the sender signalled, for a
+    /// decoder from [`Self::incoming_video_codec`].
+    pub fn assemble_incoming_object(
+        &mut self,
+        object: MoqObject,
+    ) -> Result<Option<MediaFrame>, QuicRtcError> {
+        if let Some(hint) = object.metadata.audio_content_hint() {
+            self.track_audio_hints
+                .insert(object.track_namespace.clone(), hint);
+        }
+        self.assembler.add_object(object)
+    }
+
+    /// Codec that decodes an incoming video track
+    ///
+    /// This is the codec negotiated for the track, or our own video codec
+    /// if none was.
+    pub fn incoming_video_codec(&self, track_namespace: &TrackNamespace) -> Option<Arc<dyn Codec>> {
+        self.track_codecs
+            .get(track_namespace)
+            .or(self.video_codec.as_ref())
+            .and_then(|name| self.codec_registry.get_codec(name))
+    }
+
     /// Prepare outgoing MoQ object from encoded media frame
     pub fn prepare_outgoing_object(
         &mut self,
@@ -54,21 +494,208 @@ impl MediaProcessor {
         group_id: u64,
         object_id: u64,
     ) -> Result<MoqObject, QuicRtcError> {
-        // Encode the frame first
-        let encoded_data = self.encode_media_frame(&frame)?;
-        let data_size = encoded_data.len();
+        let frame = self.prepare_outgoing_frame(frame, object_id == 0)?;
+        let payload = self.encode_media_frame(&frame)?;
+        let is_keyframe = match &frame {
+            MediaFrame::Video(video_frame) => video_frame.is_keyframe,
+            MediaFrame::Audio(_) => true,
+        };
+        self.outgoing_object(
+            FrameFormat::of(&frame),
+            is_keyframe,
+            payload,
+            track_namespace,
+            group_id,
+            object_id,
+        )
+    }
+
+    /// Get an outgoing frame ready for the encoder
+    ///
+    /// Video is turned upright in pre-rotate mode and scaled to the encoder
+    /// size. When `starts_group` is set, pending encoder settings are
+    /// applied and the frame becomes a keyframe; a change of orientation
+    /// forces one as well. Every other frame is encoded as a delta frame.
+    /// Audio is remixed to the audio encoder's channels, see
+    /// [`Self::negotiate_audio_layout`].
+    pub fn prepare_outgoing_frame(
+        &mut self,
+        frame: MediaFrame,
+        starts_group: bool,
+    ) -> Result<MediaFrame, QuicRtcError> {
+        let mut video_frame = match frame {
+            MediaFrame::Video(video_frame) => video_frame,
+            MediaFrame::Audio(audio_frame) => {
+                return Ok(MediaFrame::Audio(self.fit_encoder_channels(audio_frame)))
+            }
+        };
+
+        // A new group starts with a keyframe, so switch encoder settings there
+        if starts_group {
+            self.apply_pending_video_config()?;
+        }
+
+        // Pre-rotated quarter turns come out portrait; the encoder turns
+        // with them rather than squashing them, starting over with a
+        // keyframe
+        let mut keyframe = starts_group;
+        let portrait = self.rotation_mode == VideoRotationMode::PreRotate
+            && video_frame.rotation.swaps_dimensions();
+        if portrait != self.portrait_encoder {
+            self.portrait_encoder = portrait;
+            self.register_video_encoder(&self.video_config.clone())?;
+            keyframe = true;
+        }
+        // The first object of a group must decode on its own; the
+        // processor decides, whatever the source marked
+        video_frame.is_keyframe = keyframe;
+        if keyframe {
+            self.keyframe_requested = false;
+            self.frames_since_keyframe = 0;
+        } else {
+            self.frames_since_keyframe += 1;
+        }
+        if self.rotation_mode == VideoRotationMode::PreRotate {
+            video_frame = video_frame.to_upright();
+        }
+        let (width, height) = self.encoder_dimensions(&self.video_config);
+        Ok(MediaFrame::Video(video_frame.scale(width, height)))
+    }
+
+    /// Send audio with no more channels than a receiver taking `remote` plays
+    ///
+    /// The audio encoder is reconfigured to the negotiated layout, the
+    /// smaller of its own and `remote` (see [`ChannelLayout::negotiate`]),
+    /// and outgoing audio is remixed to it. Returns the negotiated layout.
+    pub fn negotiate_audio_layout(
+        &mut self,
+        remote: ChannelLayout,
+    ) -> Result<ChannelLayout, QuicRtcError> {
+        let codec = self.outgoing_codec(&MoqTrackType::Audio)?;
+        let info = SyncEncoder::get_codec_info(codec.as_ref());
+        let local = info
+            .channels
+            .and_then(ChannelLayout::from_channel_count)
+            .unwrap_or_default();
+        let layout = local.negotiate(remote);
+        if layout != local {
+            // Outgoing audio was checked to have a codec above
+            let name = self.audio_codec.clone().unwrap_or_default();
+            let mut config = CodecConfig::new(&name);
+            config.channels = Some(layout.channel_count());
+            let mut reconfigured = codec.clone_codec();
+            reconfigured.configure(&config)?;
+            self.codec_registry
+                .register_codec(&name, Arc::from(reconfigured))?;
+        }
+        Ok(layout)
+    }
+
+    /// Remix outgoing audio to the channels the audio encoder takes
+    fn fit_encoder_channels(&self, frame: AudioFrame) -> AudioFrame {
+        let channels = self
+            .outgoing_codec(&MoqTrackType::Audio)
+            .ok()
+            .and_then(|codec| SyncEncoder::get_codec_info(codec.as_ref()).channels);
+        match channels {
+            Some(channels) if channels != frame.channels => AudioFrame {
+                samples: remix_channel_count(&frame.samples, frame.channels, channels),
+                channels,
+                ..frame
+            },
+            _ => frame,
+        }
+    }
+
+    /// Codec outgoing frames of `track_type` are encoded with
+    ///
+    /// The codec can be used without borrowing the processor, e.g. from an
+    /// [`EncodePipeline`](crate::EncodePipeline) worker.
+    pub fn outgoing_codec(
+        &self,
+        track_type: &MoqTrackType,
+    ) -> Result<Arc<dyn Codec>, QuicRtcError> {
+        let (codec_name, kind) = match track_type {
+            MoqTrackType::Video => (self.video_codec.as_deref(), "video"),
+            MoqTrackType::Audio => (self.audio_codec.as_deref(), "audio"),
+            MoqTrackType::Data => (None, "data"),
+        };
+        codec_name
+            .and_then(|name| self.codec_registry.get_codec(name))
+            .ok_or_else(|| QuicRtcError::UnsupportedCodec {
+                codec: codec_name.unwrap_or(kind).to_string(),
+            })
+    }
+
+    /// Package a frame encoded elsewhere as a MoQ object
+    ///
+    /// The counterpart of [`Self::prepare_outgoing_frame`] for frames that
+    /// went through an encode pipeline. Video keyframes carry their format.
+    pub fn package_encoded_frame(
+        &self,
+        frame: EncodedFrame,
+        track_namespace: TrackNamespace,
+        group_id: u64,
+        object_id: u64,
+    ) -> Result<MoqObject, QuicRtcError> {
+        self.outgoing_object(
+            frame.format,
+            frame.is_keyframe,
+            frame.data,
+            track_namespace,
+            group_id,
+            object_id,
+        )
+    }
+
+    /// Encode a thumbnail as an object that opens its own group
+    ///
+    /// Each thumbnail is intra-coded so subscribers can show any one of
+    /// them, and carries its resolution like the first object of every
+    /// video group. The built-in H.264 encoder is set up for the
+    /// thumbnail's size and `bitrate`; other codecs encode it as configured.
+    pub fn prepare_thumbnail_object(
+        &self,
+        frame: VideoFrame,
+        track_namespace: TrackNamespace,
+        group_id: u64,
+        bitrate: u32,
+    ) -> Result<MoqObject, QuicRtcError> {
+        let mut metadata = MoqObjectMetadata::new();
+        metadata.set_video_resolution(frame.width, frame.height)?;
+
+        let frame = VideoFrame {
+            is_keyframe: true,
+            ..frame
+        };
+        let thumbnail_encoder =
+            (self.video_codec.as_deref() == Some("h264")).then_some(H264Config {
+                width: frame.width,
+                height: frame.height,
+                bitrate,
+                framerate: 1,
+                content_hint: self.video_config.content_hint,
+                keyframe_interval_frames: Some(1),
+                max_gop_duration: None,
+                max_slice_size: self.video_config.max_slice_size,
+            });
+        let frame = MediaFrame::Video(frame);
+        let encoded_data = match thumbnail_encoder {
+            Some(config) => H264Codec::with_config(config)?.encode_sync(&frame)?,
+            None => self.encode_media_frame(&frame)?,
+        };
 
-        // Create MoQ object with encoded data
         Ok(MoqObject {
             track_namespace,
             track_name: Self::get_track_name_from_frame(&frame),
             group_id,
-            object_id,
-            publisher_priority: 1,
+            object_id: 0,
+            publisher_priority: THUMBNAIL_PUBLISHER_PRIORITY,
+            size: encoded_data.len(),
             payload: encoded_data,
             object_status: MoqObjectStatus::Normal,
             created_at: std::time::Instant::now(),
-            size: data_size,
+            metadata,
         })
     }
 
@@ -89,9 +716,23 @@ impl MediaProcessor {
             object_status: MoqObjectStatus::EndOfGroup,
             created_at: std::time::Instant::now(),
             size: 0,
+            metadata: Default::default(),
         })
     }
 
+    /// Stop waiting for missing objects of a track once its groups are
+    /// older than `latency_budget`
+    ///
+    /// See [`MoqObjectAssembler::set_latency_budget`].
+    pub fn set_latency_budget(
+        &mut self,
+        track_namespace: &TrackNamespace,
+        latency_budget: Option<Duration>,
+    ) {
+        self.assembler
+            .set_latency_budget(track_namespace, latency_budget);
+    }
+
     /// Get next completed frame from the assembler buffer
     pub fn get_next_frame(&mut self, track: &TrackNamespace) -> Option<MediaFrame> {
         self.assembler.get_next_frame(track)
@@ -114,7 +755,7 @@ impl MediaProcessor {
         // Try to decode any salvageable partial frames
         let mut decoded_frames = Vec::new();
         for frame in partial_frames {
-            match self.decode_assembled_frame(frame) {
+            match self.decode_assembled_frame(frame, None) {
                 Ok(decoded) => decoded_frames.push(decoded),
                 Err(e) => {
                     tracing::warn!("Failed to decode partial frame: {}", e);
@@ -128,13 +769,59 @@ impl MediaProcessor {
 
     // Private helper methods
 
+    /// Swap in the pending video encoder configuration, if any
+    fn apply_pending_video_config(&mut self) -> Result<(), QuicRtcError> {
+        if let Some(config) = self.pending_video_config.take() {
+            tracing::debug!(
+                "Reconfiguring video encoder: {}x{} @ {}fps, {} bps",
+                config.width,
+                config.height,
+                config.framerate,
+                config.bitrate
+            );
+            self.register_video_encoder(&config)?;
+            self.video_config = config;
+        }
+        Ok(())
+    }
+
+    /// Size video is encoded at under `config`, turned for portrait frames
+    fn encoder_dimensions(&self, config: &H264Config) -> (u32, u32) {
+        if self.portrait_encoder {
+            (config.height, config.width)
+        } else {
+            (config.width, config.height)
+        }
+    }
+
+    /// Rebuild the video encoder for `config` at the current orientation
+    fn register_video_encoder(&mut self, config: &H264Config) -> Result<(), QuicRtcError> {
+        // Only the built-in H.264 encoder is rebuilt from H264Config;
+        // external codecs keep their own settings
+        if self.video_codec.as_deref() != Some("h264") {
+            return Ok(());
+        }
+        let (width, height) = self.encoder_dimensions(config);
+        let config = H264Config {
+            width,
+            height,
+            ..config.clone()
+        };
+        self.codec_registry
+            .register_codec("h264", std::sync::Arc::new(H264Codec::with_config(config)?))
+    }
+
     /// Decode an assembled frame if it contains encoded data
-    fn decode_assembled_frame(&self, frame: MediaFrame) -> Result<MediaFrame, QuicRtcError> {
+    fn decode_assembled_frame(
+        &self,
+        frame: MediaFrame,
+        codec: Option<&str>,
+    ) -> Result<MediaFrame, QuicRtcError> {
         match frame {
             MediaFrame::Video(video_frame) => {
                 // Check if the frame data looks like encoded content
                 if self.is_encoded_video_data(&video_frame.data) {
-                    self.decode_video_frame(video_frame)
+                    self.decode_video_frame(video_frame, codec)
                 } else {
                     // Already decoded or raw frame
                     Ok(MediaFrame::Video(video_frame))
@@ -149,35 +836,88 @@ impl MediaProcessor {
 
     /// Encode a media frame for MoQ transport
     fn encode_media_frame(&self, frame: &MediaFrame) -> Result<Vec<u8>, QuicRtcError> {
-        match frame {
-            MediaFrame::Video(_) => {
-                // Use H.264 codec for video
-                if let Some(h264_codec) = self.codec_registry.get_codec("h264") {
-                    h264_codec.as_ref().encode_sync(frame)
-                } else {
-                    Err(QuicRtcError::UnsupportedCodec {
-                        codec: "h264".to_string(),
-                    })
+        let track_type = match frame {
+            MediaFrame::Video(_) => MoqTrackType::Video,
+            MediaFrame::Audio(_) => MoqTrackType::Audio,
+        };
+        self.outgoing_codec(&track_type)?.encode_sync(frame)
+    }
+
+    /// Build an outgoing object around an encoded payload
+    fn outgoing_object(
+        &self,
+        format: FrameFormat,
+        is_keyframe: bool,
+        payload: Vec<u8>,
+        track_namespace: TrackNamespace,
+        group_id: u64,
+        object_id: u64,
+    ) -> Result<MoqObject, QuicRtcError> {
+        let mut metadata = MoqObjectMetadata::new();
+        let track_name = match format {
+            FrameFormat::Audio { channels, .. } => {
+                if let Some(layout) = ChannelLayout::from_channel_count(channels) {
+                    metadata.set_channel_layout(layout)?;
+                }
+                if self.audio_content_hint != AudioContentHint::Speech {
+                    metadata.set_audio_content_hint(self.audio_content_hint)?;
                 }
+                "audio"
             }
-            MediaFrame::Audio(_) => {
-                // Use Opus codec for audio
-                if let Some(opus_codec) = self.codec_registry.get_codec("opus") {
-                    opus_codec.as_ref().encode_sync(frame)
-                } else {
-                    Err(QuicRtcError::UnsupportedCodec {
-                        codec: "opus".to_string(),
-                    })
+            FrameFormat::Video {
+                width,
+                height,
+                rotation,
+            } => {
+                if rotation != VideoRotation::Deg0 {
+                    metadata.set_rotation(rotation)?;
+                }
+                // Every group carries its format so receivers can adapt at
+                // the keyframe, including ones that join mid-stream
+                if is_keyframe {
+                    metadata.set_video_resolution(width, height)?;
+                    metadata.set_framerate(self.video_config.framerate)?;
+                    metadata.set_content_hint(self.video_config.content_hint)?;
                 }
+                "video"
             }
-        }
+        };
+
+        Ok(MoqObject {
+            track_namespace,
+            track_name: track_name.to_string(),
+            group_id,
+            object_id,
+            publisher_priority: 1,
+            size: payload.len(),
+            payload,
+            object_status: MoqObjectStatus::Normal,
+            created_at: std::time::Instant::now(),
+            metadata,
+        })
     }
 
     /// Decode a video frame using available codecs
-    fn decode_video_frame(&self, video_frame: VideoFrame) -> Result<MediaFrame, QuicRtcError> {
-        // Try H.264 decoder first
-        if let Some(h264_codec) = self.codec_registry.get_codec("h264") {
-            h264_codec.as_ref().decode_sync(&video_frame.data)
+    fn decode_video_frame(
+        &self,
+        video_frame: VideoFrame,
+        codec: Option<&str>,
+    ) -> Result<MediaFrame, QuicRtcError> {
+        // Prefer the codec negotiated for the track, else our own video codec
+        let codec = codec
+            .or(self.video_codec.as_deref())
+            .and_then(|name| self.codec_registry.get_codec(name));
+        if let Some(codec) = codec {
+            // Rotation travels in object metadata, not in the bitstream
+            match codec.as_ref().decode_sync(&video_frame.data)? {
+                MediaFrame::Video(decoded) => {
+                    // Present at the resolution the sender signalled for this group
+                    let mut decoded = decoded.scale(video_frame.width, video_frame.height);
+                    decoded.rotation = video_frame.rotation;
+                    Ok(MediaFrame::Video(decoded))
+                }
+                frame => Ok(frame),
+            }
         } else {
             // If no codec available, return as-is
             Ok(MediaFrame::Video(video_frame))
@@ -222,6 +962,8 @@ pub struct MoqObjectAssembler {
     frame_buffer: FrameBuffer,
     /// Track state information
     track_state: HashMap<TrackNamespace, TrackState>,
+    /// Latency budgets set for tracks, overriding the default of their type
+    latency_budgets: HashMap<TrackNamespace, Option<Duration>>,
     /// Configuration
     config: AssemblerConfig,
 }
@@ -230,6 +972,9 @@ pub struct MoqObjectAssembler {
 #[derive(Debug, Clone)]
 pub struct AssemblerConfig {
     /// Maximum time to wait for missing objects before giving up
+    ///
+    /// Groups of a track with a shorter latency budget are given up on
+    /// once the budget runs out.
     pub max_wait_time: Duration,
     /// Maximum number of pending groups per track
     pub max_pending_groups: usize,
@@ -237,6 +982,9 @@ pub struct AssemblerConfig {
     pub max_frame_buffer_size: usize,
     /// Whether to request retransmission of missing objects
     pub enable_retransmission: bool,
+    /// Number of newest groups per track whose objects are remembered to
+    /// drop duplicates; objects of older groups are dropped as out of window
+    pub dedup_window_groups: u64,
 }
 
 impl Default for AssemblerConfig {
@@ -246,6 +994,7 @@ impl Default for AssemblerConfig {
             max_pending_groups: 10,
             max_frame_buffer_size: 50,
             enable_retransmission: true,
+            dedup_window_groups: 16,
         }
     }
 }
@@ -291,10 +1040,90 @@ struct TrackState {
     last_object_id: Option<u64>,
     /// Track type (audio/video)
     track_type: TrackType,
+    /// Objects seen in recent groups
+    dedup: DedupWindow,
     /// Statistics
     stats: TrackStats,
 }
 
+/// Objects seen in a track's newest groups, used to drop retransmitted,
+/// duplicated and stale objects before they reach group assembly
+#[derive(Debug, Default)]
+struct DedupWindow {
+    /// Seen objects per group, oldest group first
+    groups: BTreeMap<u64, SeenGroup>,
+}
+
+/// Objects seen in one group
+#[derive(Debug, Default)]
+struct SeenGroup {
+    /// IDs of objects accepted so far
+    objects: HashSet<u64>,
+    /// ID of the end-of-group marker, once seen
+    end_object_id: Option<u64>,
+    /// Whether the group was already assembled (or given up on)
+    assembled: bool,
+}
+
+/// Outcome of checking an incoming object against a [`DedupWindow`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ObjectCheck {
+    /// New object, assemble it
+    Accept,
+    /// Object already received
+    Duplicate,
+    /// Object of a group that is too old or already assembled
+    OutOfWindow,
+    /// Object past its group's end-of-group marker, or a marker before
+    /// objects already received
+    OutOfSequence,
+}
+
+impl DedupWindow {
+    /// Check an object and remember it if accepted
+    ///
+    /// Only the newest `window` groups are remembered.
+    fn check(&mut self, object: &MoqObject, window: u64) -> ObjectCheck {
+        let group_id = object.group_id;
+        let object_id = object.object_id;
+        let newest = self.groups.keys().next_back().copied();
+        if newest.is_some_and(|newest| group_id.saturating_add(window.max(1)) <= newest) {
+            return ObjectCheck::OutOfWindow;
+        }
+
+        let group = self.groups.entry(group_id).or_default();
+        if group.objects.contains(&object_id) {
+            return ObjectCheck::Duplicate;
+        }
+        if group.assembled {
+            return ObjectCheck::OutOfWindow;
+        }
+        if group.end_object_id.is_some_and(|end| object_id > end) {
+            return ObjectCheck::OutOfSequence;
+        }
+        if object.object_status == MoqObjectStatus::EndOfGroup {
+            if group.objects.iter().any(|&seen| seen > object_id) {
+                return ObjectCheck::OutOfSequence;
+            }
+            group.end_object_id = Some(object_id);
+        }
+        group.objects.insert(object_id);
+
+        // Forget groups that slid out of the window
+        let newest = newest.map_or(group_id, |newest| newest.max(group_id));
+        let oldest_kept = newest.saturating_sub(window.max(1) - 1);
+        self.groups = self.groups.split_off(&oldest_kept);
+        ObjectCheck::Accept
+    }
+
+    /// Mark a group as assembled so late objects for it are dropped
+    fn mark_assembled(&mut self, group_id: u64) {
+        if let Some(group) = self.groups.get_mut(&group_id) {
+            group.assembled = true;
+        }
+    }
+}
+
 /// Track type for assembly logic
 #[derive(Debug, Clone, PartialEq)]
 enum TrackType {
@@ -303,6 +1132,18 @@ enum TrackType {
     Data,
 }
 
+impl TrackType {
+    /// Default latency budget of the matching MoQ track type
+    fn default_latency_budget(&self) -> Option<Duration> {
+        match self {
+            TrackType::Audio => MoqTrackType::Audio,
+            TrackType::Video => MoqTrackType::Video,
+            TrackType::Data => MoqTrackType::Data,
+        }
+        .default_latency_budget()
+    }
+}
+
 /// Statistics for track processing
 #[derive(Debug, Default)]
 pub struct TrackStats {
@@ -316,6 +1157,12 @@ pub struct TrackStats {
     pub retransmission_requests: u64,
     /// Total frames assembled
     pub frames_assembled: u64,
+    /// Objects dropped because they were already received
+    pub duplicate_objects: u64,
+    /// Objects dropped because their group was too old or already assembled
+    pub out_of_window_objects: u64,
+    /// Objects dropped for arriving past their group's end-of-group marker
+    pub out_of_sequence_objects: u64,
 }
 
 impl MoqObjectAssembler {
@@ -333,10 +1180,40 @@ impl MoqObjectAssembler {
                 max_size_per_track: config.max_frame_buffer_size,
             },
             track_state: HashMap::new(),
+            latency_budgets: HashMap::new(),
             config,
         }
     }
 
+    /// Give up waiting for missing objects of a track once its groups are
+    /// older than `latency_budget`, usually the track's
+    /// [`quicrtc_core::MoqTrack::latency_budget`]
+    ///
+    /// Tracks without a budget set use the default of the type inferred
+    /// from their name. `None` waits up to `max_wait_time`.
+    pub fn set_latency_budget(
+        &mut self,
+        track_namespace: &TrackNamespace,
+        latency_budget: Option<Duration>,
+    ) {
+        self.latency_budgets
+            .insert(track_namespace.clone(), latency_budget);
+    }
+
+    /// How long to wait for missing objects of a track's groups
+    fn max_wait_time(&self, track_namespace: &TrackNamespace) -> Duration {
+        let latency_budget = match self.latency_budgets.get(track_namespace) {
+            Some(latency_budget) => *latency_budget,
+            None => self
+                .track_state
+                .get(track_namespace)
+                .and_then(|state| state.track_type.default_latency_budget()),
+        };
+        latency_budget.map_or(self.config.max_wait_time, |budget| {
+            budget.min(self.config.max_wait_time)
+        })
+    }
+
     /// Add MoQ object and potentially complete a media frame
     ///
     /// Returns Some(MediaFrame) if a complete frame was assembled,
@@ -349,6 +1226,33 @@ impl MoqObjectAssembler {
         // Update track state
         self.update_track_state(&track_namespace, &object);
 
+        // Drop retransmitted, duplicated and stale objects before assembly
+        if object.object_status != MoqObjectStatus::EndOfTrack {
+            let window = self.config.dedup_window_groups;
+            if let Some(track_state) = self.track_state.get_mut(&track_namespace) {
+                let check = track_state.dedup.check(&object, window);
+                let counter = match check {
+                    ObjectCheck::Accept => None,
+                    ObjectCheck::Duplicate => Some(&mut track_state.stats.duplicate_objects),
+                    ObjectCheck::OutOfWindow => Some(&mut track_state.stats.out_of_window_objects),
+                    ObjectCheck::OutOfSequence => {
+                        Some(&mut track_state.stats.out_of_sequence_objects)
+                    }
+                };
+                if let Some(counter) = counter {
+                    *counter += 1;
+                    tracing::debug!(
+                        "Dropping {:?} object {}/{} on track {:?}",
+                        check,
+                        group_id,
+                        object_id,
+                        track_namespace
+                    );
+                    return Ok(None);
+                }
+            }
+        }
+
         // Handle end-of-track objects
         if object.object_status == MoqObjectStatus::EndOfTrack {
             return self.handle_end_of_track(object);
@@ -363,7 +1267,9 @@ impl MoqObjectAssembler {
         // Handle end-of-group marker
         if object.object_status == MoqObjectStatus::EndOfGroup {
             group_assembly.end_of_group_received = true;
-            group_assembly.expected_objects = Some(group_assembly.objects.len());
+            // Object IDs count up from 0, so the marker's ID tells how many
+            // objects the group has, including any still in flight
+            group_assembly.expected_objects = Some(object_id as usize + 1);
         }
 
         // Check if group is complete
@@ -373,6 +1279,7 @@ impl MoqObjectAssembler {
 
             // Update statistics
             if let Some(track_state) = self.track_state.get_mut(&track_namespace) {
+                track_state.dedup.mark_assembled(group_id);
                 track_state.stats.groups_completed += 1;
                 track_state.stats.frames_assembled += 1;
             }
@@ -468,9 +1375,10 @@ impl MoqObjectAssembler {
 
         for (track_namespace, groups) in &self.pending_groups {
             let mut expired_groups = Vec::new();
+            let max_wait_time = self.max_wait_time(track_namespace);
 
             for (group_id, group_assembly) in groups.iter() {
-                if now.duration_since(group_assembly.started_at) > self.config.max_wait_time {
+                if now.duration_since(group_assembly.started_at) > max_wait_time {
                     expired_groups.push(*group_id);
                 }
             }
@@ -485,6 +1393,9 @@ impl MoqObjectAssembler {
             if let Some(groups) = self.pending_groups.get_mut(&track_namespace) {
                 for group_id in expired_groups {
                     if let Some(group_assembly) = groups.remove(&group_id) {
+                        if let Some(track_state) = self.track_state.get_mut(&track_namespace) {
+                            track_state.dedup.mark_assembled(group_id);
+                        }
                         tracing::warn!(
                             "Group {} for track {:?} expired after {:?}, assembling partial frame",
                             group_id,
@@ -526,6 +1437,7 @@ impl MoqObjectAssembler {
                 last_group_id: None,
                 last_object_id: None,
                 track_type,
+                dedup: DedupWindow::default(),
                 stats: TrackStats::default(),
             });
 
@@ -651,6 +1563,16 @@ impl MoqObjectAssembler {
     ) -> Result<MediaFrame, QuicRtcError> {
         // For video, concatenate all object payloads in order
         let mut frame_data = Vec::new();
+        let rotation = group_assembly
+            .objects
+            .values()
+            .find_map(|object| object.metadata.rotation())
+            .unwrap_or_default();
+        let (width, height) = group_assembly
+            .objects
+            .values()
+            .find_map(|object| object.metadata.video_resolution())
+            .unwrap_or((640, 480));
 
         for (_object_id, object) in group_assembly.objects {
             // Include all objects except empty end-of-group markers
@@ -665,14 +1587,15 @@ impl MoqObjectAssembler {
             });
         }
 
-        // For now, assume standard resolution - in a real implementation,
-        // this would be parsed from the codec data or track metadata
+        // Resolution is signalled on the first object of each group; fall
+        // back to the default encoder resolution for senders that omit it
         Ok(MediaFrame::Video(VideoFrame {
-            width: 640,
-            height: 480,
+            width,
+            height,
             data: frame_data,
             timestamp: group_assembly.group_id,
             is_keyframe: false, // TODO: Determine from MoQ object metadata
+            rotation,
         }))
     }
 
@@ -682,7 +1605,13 @@ impl MoqObjectAssembler {
         // For audio, we need to decode the objects and combine the samples
         let mut all_samples = Vec::new();
         let sample_rate = 48000; // Default
-        let channels = 2; // Default
+                                 // Channel layout travels with each object; fall back to stereo
+        let channels = group_assembly
+            .objects
+            .values()
+            .find_map(|object| object.metadata.channel_layout())
+            .unwrap_or_default()
+            .channel_count();
 
         for (_object_id, object) in group_assembly.objects {
             // Process all objects that have payload data
@@ -847,6 +1776,31 @@ pub struct QualityControlConfig {
     pub adaptation_step: f32,
     /// Enable aggressive adaptation for mobile
     pub mobile_mode: bool,
+    /// What to give up first when video quality has to drop
+    pub degradation_preference: DegradationPreference,
+}
+
+/// What to sacrifice first when video quality must be reduced
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DegradationPreference {
+    /// Lower resolution before framerate (motion content)
+    MaintainFramerate,
+    /// Lower framerate before resolution (detail and text content)
+    MaintainResolution,
+    /// Lower both as congestion worsens
+    #[default]
+    Balanced,
+}
+
+impl From<VideoContentHint> for DegradationPreference {
+    fn from(hint: VideoContentHint) -> Self {
+        match hint {
+            VideoContentHint::Motion => DegradationPreference::MaintainFramerate,
+            VideoContentHint::Detail | VideoContentHint::Text => {
+                DegradationPreference::MaintainResolution
+            }
+        }
+    }
 }
 
 impl Default for QualityControlConfig {
@@ -858,6 +1812,7 @@ impl Default for QualityControlConfig {
             congestion_threshold: 0.05, // 5% packet loss
             adaptation_step: 0.1,       // 10% steps
             mobile_mode: false,
+            degradation_preference: DegradationPreference::Balanced,
         }
     }
 }
@@ -872,6 +1827,7 @@ impl QualityControlConfig {
             congestion_threshold: 0.03, // 3% packet loss (more sensitive)
             adaptation_step: 0.15,      // 15% steps (more aggressive)
             mobile_mode: true,
+            degradation_preference: DegradationPreference::Balanced,
         }
     }
 
@@ -884,6 +1840,7 @@ impl QualityControlConfig {
             congestion_threshold: 0.08, // 8% packet loss
             adaptation_step: 0.08,      // 8% steps
             mobile_mode: false,
+            degradation_preference: DegradationPreference::Balanced,
         }
     }
 }
@@ -975,6 +1932,10 @@ enum AdaptationReason {
     BandwidthDecrease,
     /// Congestion detected
     CongestionDetected,
+    /// Congestion-experienced ECN marks
+    EcnCongestion,
+    /// Sustained backpressure from the send path
+    Backpressure,
     /// Buffer underrun
     BufferUnderrun,
     /// Buffer overrun
@@ -1086,6 +2047,54 @@ impl QualityController {
         }
     }
 
+    /// React to ECN marks on the media's packets since the last call
+    ///
+    /// Routers mark packets congestion-experienced (CE) before their queues
+    /// overflow, so backing off on marks keeps delay low where loss-based
+    /// adaptation would only react once packets are dropped. Mostly ECT(1)
+    /// traffic is taken as L4S, whose queues mark early and often, and the
+    /// bitrate is cut in proportion to the share of marked packets. Classic
+    /// ECN marks get a fixed backoff, gentler than the response to loss as
+    /// in RFC 8511.
+    pub fn handle_ecn_feedback(&mut self, counts: &EcnCounts) {
+        if counts.ce == 0 {
+            return;
+        }
+        let factor = if counts.is_l4s() {
+            1.0 - counts.ce_fraction() as f32 / 2.0
+        } else {
+            ECN_CLASSIC_BACKOFF
+        };
+        self.reduce_quality(factor, AdaptationReason::EcnCongestion);
+    }
+
+    /// React to sustained congestion reported by the send path
+    ///
+    /// Objects are already queueing up or missing their latency budget, so
+    /// quality comes down now rather than at the next [`Self::adapt_quality`].
+    pub fn handle_backpressure(&mut self, backpressure: &Backpressure) {
+        self.reduce_quality(
+            backpressure_backoff(backpressure),
+            AdaptationReason::Backpressure,
+        );
+    }
+
+    /// Quality controller starting from the video settings of `config`
+    fn following(config: &H264Config) -> Self {
+        let mut controller = Self::new();
+        controller.follow_video_config(config);
+        controller
+    }
+
+    /// Track the encoder configuration video is sent at, including a change
+    /// waiting for the next group, so the next adaptation starts from it
+    fn follow_video_config(&mut self, config: &H264Config) {
+        self.current_settings.video_bitrate = config.bitrate;
+        self.current_settings.video_width = config.width;
+        self.current_settings.video_height = config.height;
+        self.current_settings.video_framerate = config.framerate;
+    }
+
     /// Get current quality settings
     pub fn current_settings(&self) -> &QualitySettings {
         &self.current_settings
@@ -1111,6 +2120,16 @@ impl QualityController {
         &self.quality_adapter.adaptation_history
     }
 
+    /// Tune degradation for the content being sent
+    pub fn set_content_hint(&mut self, hint: VideoContentHint) {
+        self.config.degradation_preference = hint.into();
+    }
+
+    /// Get the current degradation preference
+    pub fn degradation_preference(&self) -> DegradationPreference {
+        self.config.degradation_preference
+    }
+
     // Private helper methods
 
     fn should_adapt(&self, metrics: &MoqDeliveryMetrics) -> bool {
@@ -1162,11 +2181,16 @@ impl QualityController {
         new_settings.audio_bitrate =
             ((new_settings.audio_bitrate as f32 * adaptation_factor) as u32).clamp(32_000, 128_000); // Audio bitrate limits
 
-        // Adjust resolution if needed (for significant changes)
+        // Adjust resolution or framerate if needed (for significant changes)
         if adaptation_factor < 0.8 {
-            // Reduce resolution
-            new_settings.video_width = (new_settings.video_width * 3 / 4).max(320);
-            new_settings.video_height = (new_settings.video_height * 3 / 4).max(240);
+            match self.config.degradation_preference {
+                DegradationPreference::MaintainResolution => {
+                    Self::reduce_framerate(&mut new_settings, 5)
+                }
+                DegradationPreference::MaintainFramerate | DegradationPreference::Balanced => {
+                    Self::reduce_resolution(&mut new_settings)
+                }
+            }
         } else if adaptation_factor > 1.2 && new_settings.video_width < 1280 {
             // Increase resolution
             new_settings.video_width = (new_settings.video_width * 4 / 3).min(1920);
@@ -1185,14 +2209,37 @@ impl QualityController {
         new_settings.audio_bitrate =
             ((new_settings.audio_bitrate as f32 * factor) as u32).max(32_000);
 
-        // Reduce framerate for heavy congestion
-        if factor < 0.7 {
-            new_settings.video_framerate = (new_settings.video_framerate * 2 / 3).max(15);
+        match self.config.degradation_preference {
+            DegradationPreference::Balanced => {
+                // Reduce framerate for heavy congestion
+                if factor < 0.7 {
+                    Self::reduce_framerate(&mut new_settings, 15);
+                }
+            }
+            DegradationPreference::MaintainFramerate => {
+                if factor < 0.9 {
+                    Self::reduce_resolution(&mut new_settings);
+                }
+            }
+            DegradationPreference::MaintainResolution => {
+                if factor < 0.9 {
+                    Self::reduce_framerate(&mut new_settings, 5);
+                }
+            }
         }
 
         self.apply_settings(new_settings, reason);
     }
 
+    fn reduce_resolution(settings: &mut QualitySettings) {
+        settings.video_width = (settings.video_width * 3 / 4).max(320);
+        settings.video_height = (settings.video_height * 3 / 4).max(240);
+    }
+
+    fn reduce_framerate(settings: &mut QualitySettings, min_framerate: u32) {
+        settings.video_framerate = (settings.video_framerate * 2 / 3).max(min_framerate);
+    }
+
     fn apply_settings(&mut self, new_settings: QualitySettings, reason: AdaptationReason) {
         let adaptation = QualityAdaptation {
             timestamp: Instant::now(),
@@ -1371,6 +2418,7 @@ mod tests {
             data: vec![128; 640 * 480 * 3], // Gray frame
             timestamp: 12345,
             is_keyframe: true,
+            rotation: VideoRotation::Deg0,
         });
 
         // Test encoding a frame to MoQ object
@@ -1444,6 +2492,7 @@ mod tests {
             data: large_video_data,
             timestamp: 67890,
             is_keyframe: true,
+            rotation: VideoRotation::Deg0,
         });
 
         // Encode to get the payload
@@ -1464,6 +2513,7 @@ mod tests {
                 object_status: MoqObjectStatus::Normal,
                 created_at: std::time::Instant::now(),
                 size: chunk.len(),
+                metadata: Default::default(),
             };
             objects.push(object);
         }
@@ -1479,6 +2529,7 @@ mod tests {
             object_status: MoqObjectStatus::EndOfGroup,
             created_at: std::time::Instant::now(),
             size: 0,
+            metadata: Default::default(),
         };
         objects.push(end_marker);
 
@@ -1566,6 +2617,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_audio_layout_negotiation() {
+        let mut processor = MediaProcessor::new();
+
+        // A stereo receiver leaves the stereo encoder alone
+        assert_eq!(
+            processor
+                .negotiate_audio_layout(ChannelLayout::Surround5_1)
+                .unwrap(),
+            ChannelLayout::Stereo
+        );
+
+        // A mono receiver gets mono audio
+        assert_eq!(
+            processor
+                .negotiate_audio_layout(ChannelLayout::Mono)
+                .unwrap(),
+            ChannelLayout::Mono
+        );
+        let stereo = MediaFrame::Audio(AudioFrame {
+            samples: vec![0.1; 1920],
+            sample_rate: 48000,
+            channels: 2,
+            timestamp: 0,
+        });
+        match processor.prepare_outgoing_frame(stereo, true).unwrap() {
+            MediaFrame::Audio(audio) => {
+                assert_eq!(audio.channels, 1);
+                assert_eq!(audio.samples.len(), 960);
+            }
+            _ => panic!("Expected audio frame"),
+        }
+    }
+
     #[test]
     fn test_media_processor_track_stats() {
         let mut processor = MediaProcessor::new();
@@ -1589,6 +2674,7 @@ mod tests {
             object_status: MoqObjectStatus::Normal,
             created_at: std::time::Instant::now(),
             size: 4,
+            metadata: Default::default(),
         };
 
         processor.process_incoming_object(object).unwrap();
@@ -1600,6 +2686,110 @@ mod tests {
         assert_eq!(stats.frames_assembled, 0);
     }
 
+    #[test]
+    fn test_assembler_drops_duplicate_and_stale_objects() {
+        let mut assembler = MoqObjectAssembler::with_config(AssemblerConfig {
+            dedup_window_groups: 2,
+            ..Default::default()
+        });
+        let track_namespace = TrackNamespace {
+            namespace: "test.com".to_string(),
+            track_name: "video".to_string(),
+        };
+        let object = |group_id, object_id, object_status| MoqObject {
+            track_namespace: track_namespace.clone(),
+            track_name: "video".to_string(),
+            group_id,
+            object_id,
+            publisher_priority: 1,
+            payload: vec![1, 2, 3, 4],
+            object_status,
+            created_at: std::time::Instant::now(),
+            size: 4,
+            metadata: Default::default(),
+        };
+
+        assert!(assembler
+            .add_object(object(1, 0, MoqObjectStatus::Normal))
+            .unwrap()
+            .is_none());
+        // A retransmitted copy is dropped
+        assert!(assembler
+            .add_object(object(1, 0, MoqObjectStatus::Normal))
+            .unwrap()
+            .is_none());
+        assert!(assembler
+            .add_object(object(1, 1, MoqObjectStatus::EndOfGroup))
+            .unwrap()
+            .is_some());
+
+        // Late objects for the assembled group never start a new frame
+        assembler
+            .add_object(object(1, 0, MoqObjectStatus::Normal))
+            .unwrap();
+        assembler
+            .add_object(object(1, 2, MoqObjectStatus::Normal))
+            .unwrap();
+
+        // Group 1 falls out of a two-group window once group 3 arrives
+        assembler
+            .add_object(object(3, 0, MoqObjectStatus::Normal))
+            .unwrap();
+        assembler
+            .add_object(object(1, 5, MoqObjectStatus::Normal))
+            .unwrap();
+
+        // Objects past the end-of-group marker are out of sequence
+        assembler
+            .add_object(object(3, 2, MoqObjectStatus::EndOfGroup))
+            .unwrap();
+        assembler
+            .add_object(object(3, 4, MoqObjectStatus::Normal))
+            .unwrap();
+
+        let stats = assembler.get_track_stats(&track_namespace).unwrap();
+        assert_eq!(stats.objects_received, 9);
+        assert_eq!(stats.duplicate_objects, 2);
+        assert_eq!(stats.out_of_window_objects, 2);
+        assert_eq!(stats.out_of_sequence_objects, 1);
+        assert_eq!(stats.groups_completed, 1);
+    }
+
+    #[test]
+    fn test_assembler_gives_up_within_latency_budget() {
+        let mut assembler = MoqObjectAssembler::with_config(AssemblerConfig {
+            max_wait_time: Duration::from_secs(1),
+            ..Default::default()
+        });
+        let track_namespace = TrackNamespace {
+            namespace: "test.com".to_string(),
+            track_name: "chat".to_string(),
+        };
+        assembler
+            .add_object(MoqObject {
+                track_namespace: track_namespace.clone(),
+                track_name: "chat".to_string(),
+                group_id: 1,
+                object_id: 0,
+                publisher_priority: 1,
+                payload: vec![1, 2, 3, 4],
+                object_status: MoqObjectStatus::Normal,
+                created_at: std::time::Instant::now(),
+                size: 4,
+                metadata: Default::default(),
+            })
+            .unwrap();
+
+        // Data tracks have no budget, so the group waits up to max_wait_time
+        std::thread::sleep(Duration::from_millis(20));
+        assembler.cleanup_expired_groups().unwrap();
+        assert!(assembler.pending_groups.contains_key(&track_namespace));
+
+        assembler.set_latency_budget(&track_namespace, Some(Duration::from_millis(10)));
+        assembler.cleanup_expired_groups().unwrap();
+        assert!(assembler.pending_groups.is_empty());
+    }
+
     #[test]
     fn test_assembler_configuration() {
         let config = AssemblerConfig {
@@ -1607,6 +2797,7 @@ mod tests {
             max_pending_groups: 5,
             max_frame_buffer_size: 20,
             enable_retransmission: false,
+            dedup_window_groups: 8,
         };
 
         let processor = MediaProcessor::with_assembler_config(config);
@@ -1617,4 +2808,592 @@ mod tests {
             track_name: "video".to_string(),
         }));
     }
+
+    #[test]
+    fn test_video_rotation_modes() {
+        let track_namespace = TrackNamespace {
+            namespace: "test.com".to_string(),
+            track_name: "video".to_string(),
+        };
+        let video_frame = MediaFrame::Video(VideoFrame {
+            width: 640,
+            height: 480,
+            data: vec![64; 640 * 480 * 3],
+            timestamp: 1,
+            is_keyframe: true,
+            rotation: VideoRotation::Deg180,
+        });
+
+        // Renderer mode carries rotation to the receiver as metadata
+        let mut processor = MediaProcessor::new();
+        assert_eq!(processor.rotation_mode(), VideoRotationMode::Renderer);
+        let object = processor
+            .prepare_outgoing_object(video_frame.clone(), track_namespace.clone(), 1, 0)
+            .unwrap();
+        assert_eq!(object.metadata.rotation(), Some(VideoRotation::Deg180));
+
+        // Pre-rotation bakes it into the encoded frame instead
+        let mut processor = MediaProcessor::new().with_rotation_mode(VideoRotationMode::PreRotate);
+        let object = processor
+            .prepare_outgoing_object(video_frame, track_namespace.clone(), 1, 0)
+            .unwrap();
+        assert_eq!(object.metadata.rotation(), None);
+        assert_eq!(object.metadata.video_resolution(), Some((640, 480)));
+
+        // A quarter turn is encoded portrait, not squashed into landscape
+        let portrait_frame = |rotation| {
+            MediaFrame::Video(VideoFrame {
+                width: 640,
+                height: 480,
+                data: vec![64; 640 * 480 * 3],
+                timestamp: 2,
+                is_keyframe: false,
+                rotation,
+            })
+        };
+        for rotation in [VideoRotation::Deg90, VideoRotation::Deg270] {
+            let mut processor =
+                MediaProcessor::new().with_rotation_mode(VideoRotationMode::PreRotate);
+            let object = processor
+                .prepare_outgoing_object(portrait_frame(rotation), track_namespace.clone(), 1, 0)
+                .unwrap();
+            assert_eq!(object.metadata.rotation(), None);
+            assert_eq!(object.metadata.video_resolution(), Some((480, 640)));
+
+            // The device turning back mid-group restarts with a keyframe
+            let object = processor
+                .prepare_outgoing_object(
+                    portrait_frame(VideoRotation::Deg0),
+                    track_namespace.clone(),
+                    1,
+                    1,
+                )
+                .unwrap();
+            assert_eq!(object.metadata.video_resolution(), Some((640, 480)));
+            let object = processor
+                .prepare_outgoing_object(
+                    portrait_frame(VideoRotation::Deg0),
+                    track_namespace.clone(),
+                    1,
+                    2,
+                )
+                .unwrap();
+            assert_eq!(object.metadata.video_resolution(), None);
+        }
+    }
+
+    #[test]
+    fn test_pipelined_encode_packages_like_inline_encode() {
+        use crate::pipeline::{EncodePipeline, EncodePipelineConfig};
+
+        let track_namespace = TrackNamespace {
+            namespace: "test.com".to_string(),
+            track_name: "video".to_string(),
+        };
+        let frame = |is_keyframe| {
+            MediaFrame::Video(VideoFrame {
+                width: 640,
+                height: 480,
+                data: vec![64; 640 * 480 * 3],
+                timestamp: 1,
+                is_keyframe,
+                rotation: VideoRotation::Deg90,
+            })
+        };
+
+        let mut processor = MediaProcessor::new().with_rotation_mode(VideoRotationMode::PreRotate);
+        // The processor decides keyframes, not the source
+        let first = processor
+            .prepare_outgoing_frame(frame(false), true)
+            .unwrap();
+        let second = processor
+            .prepare_outgoing_frame(frame(true), false)
+            .unwrap();
+
+        // The encoder is taken once the group has settled the portrait format
+        let codec = processor.outgoing_codec(&MoqTrackType::Video).unwrap();
+        let mut pipeline = EncodePipeline::new(EncodePipelineConfig::default());
+        let mut encoded = pipeline.add_track("video", codec.clone_encoder()).unwrap();
+        pipeline.submit("video", first).unwrap();
+        pipeline.submit("video", second).unwrap();
+
+        let first = encoded.blocking_recv().unwrap();
+        assert!(first.is_keyframe);
+        let object = processor
+            .package_encoded_frame(first, track_namespace.clone(), 1, 0)
+            .unwrap();
+        assert_eq!(object.metadata.rotation(), None);
+        assert_eq!(object.metadata.video_resolution(), Some((480, 640)));
+
+        let second = encoded.blocking_recv().unwrap();
+        assert!(!second.is_keyframe);
+        let object = processor
+            .package_encoded_frame(second, track_namespace, 1, 1)
+            .unwrap();
+        assert_eq!(object.metadata.video_resolution(), None);
+        assert_eq!(pipeline.stats("video").unwrap().frames_encoded, 2);
+    }
+
+    #[test]
+    fn test_audio_content_hint_round_trip() {
+        let track_namespace = TrackNamespace {
+            namespace: "test.com".to_string(),
+            track_name: "audio".to_string(),
+        };
+        let audio_frame = MediaFrame::Audio(AudioFrame {
+            samples: vec![0.1; 1920],
+            sample_rate: 48000,
+            channels: 2,
+            timestamp: 1,
+        });
+
+        // Speech is the default and is not signalled
+        let mut processor = MediaProcessor::new();
+        let object = processor
+            .prepare_outgoing_object(audio_frame.clone(), track_namespace.clone(), 1, 0)
+            .unwrap();
+        assert_eq!(object.metadata.audio_content_hint(), None);
+
+        let mut sender = MediaProcessor::new().with_audio_content_hint(AudioContentHint::Music);
+        let object = sender
+            .prepare_outgoing_object(audio_frame, track_namespace.clone(), 1, 0)
+            .unwrap();
+        assert_eq!(
+            object.metadata.audio_content_hint(),
+            Some(AudioContentHint::Music)
+        );
+
+        // The receiver remembers what the publisher sent
+        let mut receiver = MediaProcessor::new();
+        assert_eq!(
+            receiver.track_audio_content_hint(&track_namespace),
+            AudioContentHint::Speech
+        );
+        receiver.process_incoming_object(object).unwrap();
+        assert_eq!(
+            receiver.track_audio_content_hint(&track_namespace),
+            AudioContentHint::Music
+        );
+    }
+
+    #[test]
+    fn test_video_content_hint_reaches_encoder() {
+        let track_namespace = TrackNamespace {
+            namespace: "test.com".to_string(),
+            track_name: "screen".to_string(),
+        };
+        let capture_frame = || {
+            MediaFrame::Video(VideoFrame {
+                width: 640,
+                height: 480,
+                data: vec![90; 640 * 480 * 3],
+                timestamp: 0,
+                is_keyframe: true,
+                rotation: VideoRotation::Deg0,
+            })
+        };
+
+        let mut processor = MediaProcessor::new().with_video_content_hint(VideoContentHint::Text);
+        assert_eq!(processor.video_content_hint(), VideoContentHint::Text);
+        assert_eq!(
+            processor.quality_controller().degradation_preference(),
+            DegradationPreference::MaintainResolution
+        );
+
+        // The encoder switches at the first group and keyframes say so
+        let keyframe = processor
+            .prepare_outgoing_object(capture_frame(), track_namespace.clone(), 1, 0)
+            .unwrap();
+        assert_eq!(
+            processor.video_config().content_hint,
+            VideoContentHint::Text
+        );
+        assert_eq!(processor.video_config().keyframe_interval(), 300);
+        assert_eq!(
+            keyframe.metadata.content_hint(),
+            Some(VideoContentHint::Text)
+        );
+        let delta = processor
+            .prepare_outgoing_object(capture_frame(), track_namespace.clone(), 1, 1)
+            .unwrap();
+        assert_eq!(delta.metadata.content_hint(), None);
+
+        // Rate adaptation keeps the hint
+        processor.request_video_reconfiguration(&QualitySettings::default());
+        processor.set_video_content_hint(VideoContentHint::Motion);
+        processor
+            .prepare_outgoing_object(capture_frame(), track_namespace, 2, 0)
+            .unwrap();
+        assert_eq!(
+            processor.video_config().content_hint,
+            VideoContentHint::Motion
+        );
+        assert_eq!(
+            processor.quality_controller().degradation_preference(),
+            DegradationPreference::MaintainFramerate
+        );
+    }
+
+    #[test]
+    fn test_content_hint_degradation_preference() {
+        // Text content keeps its resolution and gives up framerate
+        let mut controller = QualityController::new();
+        controller.set_content_hint(VideoContentHint::Text);
+        assert_eq!(
+            controller.degradation_preference(),
+            DegradationPreference::MaintainResolution
+        );
+        controller.handle_congestion(CongestionLevel::Moderate);
+        let settings = controller.current_settings();
+        assert_eq!((settings.video_width, settings.video_height), (640, 480));
+        assert_eq!(settings.video_framerate, 20);
+
+        // Motion content keeps its framerate and gives up resolution
+        let mut controller = QualityController::new();
+        controller.set_content_hint(VideoContentHint::Motion);
+        controller.handle_congestion(CongestionLevel::Heavy);
+        let settings = controller.current_settings();
+        assert_eq!((settings.video_width, settings.video_height), (480, 360));
+        assert_eq!(settings.video_framerate, 30);
+    }
+
+    #[test]
+    fn test_ecn_feedback_reduces_bitrate() {
+        let mut controller = QualityController::new();
+        let initial = controller.current_settings().video_bitrate;
+
+        // No marks, no reaction
+        controller.handle_ecn_feedback(&EcnCounts {
+            ect0: 100,
+            ect1: 0,
+            ce: 0,
+        });
+        assert_eq!(controller.current_settings().video_bitrate, initial);
+
+        // L4S backs off in proportion to the share of marked packets
+        controller.handle_ecn_feedback(&EcnCounts {
+            ect0: 0,
+            ect1: 90,
+            ce: 10,
+        });
+        let l4s = controller.current_settings().video_bitrate;
+        assert_eq!(l4s, (initial as f32 * 0.95) as u32);
+
+        // Classic ECN backs off by a fixed factor however many are marked
+        controller.handle_ecn_feedback(&EcnCounts {
+            ect0: 99,
+            ect1: 0,
+            ce: 1,
+        });
+        assert_eq!(
+            controller.current_settings().video_bitrate,
+            (l4s as f32 * ECN_CLASSIC_BACKOFF) as u32
+        );
+    }
+
+    #[test]
+    fn test_backpressure_cuts_bitrate_without_waiting_for_abr() {
+        let backpressure = Backpressure {
+            reason: BackpressureReason::LatencyBudgetExceeded,
+            track_namespace: None,
+            incidents: 3,
+        };
+
+        let mut controller = QualityController::new();
+        let initial = controller.current_settings().video_bitrate;
+        controller.handle_backpressure(&backpressure);
+        assert_eq!(
+            controller.current_settings().video_bitrate,
+            (initial as f32 * 0.7) as u32
+        );
+
+        // The encoder starts a new group at the lower bitrate right away
+        let mut processor = MediaProcessor::new();
+        let initial = processor.video_config().bitrate;
+        processor.handle_backpressure(&backpressure);
+        assert!(processor.has_pending_video_reconfiguration());
+        assert!(processor.keyframe_due());
+        processor
+            .prepare_outgoing_object(
+                MediaFrame::Video(VideoFrame {
+                    width: 640,
+                    height: 480,
+                    data: vec![90; 640 * 480 * 3],
+                    timestamp: 0,
+                    is_keyframe: true,
+                    rotation: VideoRotation::Deg0,
+                }),
+                TrackNamespace {
+                    namespace: "test.com".to_string(),
+                    track_name: "video".to_string(),
+                },
+                2,
+                0,
+            )
+            .unwrap();
+        assert_eq!(
+            processor.video_config().bitrate,
+            (initial as f32 * 0.7) as u32
+        );
+    }
+
+    #[test]
+    fn test_quality_adaptations_reach_encoder() {
+        let mut processor = MediaProcessor::new();
+        processor.handle_backpressure(&Backpressure {
+            reason: BackpressureReason::LatencyBudgetExceeded,
+            track_namespace: None,
+            incidents: 3,
+        });
+        processor.apply_pending_video_config().unwrap();
+        assert_eq!(processor.video_config().bitrate, 700_000);
+
+        // Congestion adapts from what the encoder runs at, and waits for
+        // the next group like any other reconfiguration
+        processor.handle_congestion(CongestionLevel::Heavy);
+        assert!(processor.has_pending_video_reconfiguration());
+        assert_eq!(processor.video_config().bitrate, 700_000);
+        processor.apply_pending_video_config().unwrap();
+        assert_eq!(processor.video_config().bitrate, 350_000);
+        assert_eq!(processor.video_config().framerate, 20);
+
+        processor.handle_ecn_feedback(&EcnCounts {
+            ect0: 99,
+            ect1: 0,
+            ce: 1,
+        });
+        processor.apply_pending_video_config().unwrap();
+        assert_eq!(
+            processor.video_config().bitrate,
+            (350_000.0 * ECN_CLASSIC_BACKOFF) as u32
+        );
+        assert_eq!(
+            processor
+                .quality_controller()
+                .current_settings()
+                .video_bitrate,
+            processor.video_config().bitrate
+        );
+    }
+
+    #[test]
+    fn test_path_mtu_caps_video_slices() {
+        let mut processor = MediaProcessor::new();
+        assert_eq!(processor.video_config().max_slice_size, None);
+
+        processor.handle_path_mtu(1452);
+        assert!(processor.has_pending_video_reconfiguration());
+        assert!(!processor.keyframe_due());
+        let slice_size = 1452 - DATAGRAM_OBJECT_OVERHEAD as u32;
+        processor.apply_pending_video_config().unwrap();
+        assert_eq!(processor.video_config().max_slice_size, Some(slice_size));
+
+        // An unchanged path queues nothing
+        processor.handle_path_mtu(1452);
+        assert!(!processor.has_pending_video_reconfiguration());
+
+        // A path that shrinks below the overhead still leaves room for a slice
+        processor.handle_path_mtu(100);
+        processor.apply_pending_video_config().unwrap();
+        assert_eq!(
+            processor.video_config().max_slice_size,
+            Some(MIN_VIDEO_SLICE_SIZE as u32)
+        );
+    }
+
+    #[test]
+    fn test_video_reconfiguration_waits_for_group_boundary() {
+        let track_namespace = TrackNamespace {
+            namespace: "test.com".to_string(),
+            track_name: "video".to_string(),
+        };
+        let capture_frame = || {
+            MediaFrame::Video(VideoFrame {
+                width: 640,
+                height: 480,
+                data: vec![90; 640 * 480 * 3],
+                timestamp: 0,
+                is_keyframe: true,
+                rotation: VideoRotation::Deg0,
+            })
+        };
+
+        let mut processor = MediaProcessor::new();
+        let first = processor
+            .prepare_outgoing_object(capture_frame(), track_namespace.clone(), 1, 0)
+            .unwrap();
+        assert_eq!(first.metadata.video_resolution(), Some((640, 480)));
+        assert_eq!(first.metadata.framerate(), Some(30));
+
+        // ABR asks twice within a group; only the latest request is kept
+        let mut settings = QualitySettings::default();
+        settings.video_width = 480;
+        settings.video_height = 360;
+        settings.video_framerate = 20;
+        processor.request_video_reconfiguration(&settings);
+        settings.video_width = 320;
+        settings.video_height = 240;
+        processor.request_video_reconfiguration(&settings);
+        assert!(processor.has_pending_video_reconfiguration());
+
+        // Objects later in the current group keep the old encoder settings
+        let mid_group = processor
+            .prepare_outgoing_object(capture_frame(), track_namespace.clone(), 1, 1)
+            .unwrap();
+        assert_eq!(mid_group.metadata.video_resolution(), None);
+        assert_eq!(processor.video_config().width, 640);
+
+        // The next group switches and signals the new format
+        let next_group = processor
+            .prepare_outgoing_object(capture_frame(), track_namespace.clone(), 2, 0)
+            .unwrap();
+        assert!(!processor.has_pending_video_reconfiguration());
+        assert_eq!(processor.video_config().width, 320);
+        assert_eq!(next_group.metadata.video_resolution(), Some((320, 240)));
+        assert_eq!(next_group.metadata.framerate(), Some(20));
+
+        // Receivers present the group at the signalled resolution
+        let mut receiver = MediaProcessor::new();
+        receiver.process_incoming_object(next_group).unwrap();
+        let end_marker = receiver
+            .prepare_end_of_group_object(track_namespace, 2, 1)
+            .unwrap();
+        match receiver.process_incoming_object(end_marker).unwrap() {
+            Some(MediaFrame::Video(frame)) => assert_eq!((frame.width, frame.height), (320, 240)),
+            other => panic!("Expected video frame, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_keyframe_interval_and_requests() {
+        let track_namespace = TrackNamespace {
+            namespace: "test.com".to_string(),
+            track_name: "video".to_string(),
+        };
+        let capture_frame = || {
+            MediaFrame::Video(VideoFrame {
+                width: 640,
+                height: 480,
+                data: vec![90; 640 * 480 * 3],
+                timestamp: 0,
+                is_keyframe: false,
+                rotation: VideoRotation::Deg0,
+            })
+        };
+
+        let mut processor = MediaProcessor::new();
+        processor.set_keyframe_interval(Some(3), None);
+        processor
+            .prepare_outgoing_object(capture_frame(), track_namespace.clone(), 1, 0)
+            .unwrap();
+        assert_eq!(processor.video_config().keyframe_interval(), 3);
+        assert!(!processor.keyframe_due());
+
+        processor
+            .prepare_outgoing_object(capture_frame(), track_namespace.clone(), 1, 1)
+            .unwrap();
+        assert!(!processor.keyframe_due());
+        processor
+            .prepare_outgoing_object(capture_frame(), track_namespace.clone(), 1, 2)
+            .unwrap();
+        assert!(processor.keyframe_due());
+
+        // A new group resets the count; a request makes the next frame a keyframe
+        processor
+            .prepare_outgoing_object(capture_frame(), track_namespace.clone(), 2, 0)
+            .unwrap();
+        assert!(!processor.keyframe_due());
+        processor.request_keyframe();
+        assert!(processor.keyframe_due());
+        processor
+            .prepare_outgoing_object(capture_frame(), track_namespace, 3, 0)
+            .unwrap();
+        assert!(!processor.keyframe_due());
+
+        // A GOP duration cap applies on top of the interval
+        processor.set_keyframe_interval(None, Some(Duration::from_millis(100)));
+        assert!(processor.has_pending_video_reconfiguration());
+    }
+
+    #[test]
+    fn test_thumbnail_objects() {
+        let track_namespace = TrackNamespace {
+            namespace: "test.com".to_string(),
+            track_name: "camera-thumbnail".to_string(),
+        };
+        let capture_frame = VideoFrame {
+            width: 640,
+            height: 480,
+            data: vec![90; 640 * 480 * 3 / 2],
+            timestamp: 0,
+            is_keyframe: false,
+            rotation: VideoRotation::Deg0,
+        };
+
+        let mut thumbnails = crate::thumbnail::ThumbnailGenerator::default();
+        let start = Instant::now();
+        let thumbnail = thumbnails.offer(&capture_frame, start).unwrap();
+        assert_eq!((thumbnail.width, thumbnail.height), (212, 160));
+        assert!(thumbnails
+            .offer(&capture_frame, start + Duration::from_millis(500))
+            .is_none());
+        assert!(thumbnails
+            .offer(&capture_frame, start + Duration::from_secs(1))
+            .is_some());
+
+        let processor = MediaProcessor::new();
+        let object = processor
+            .prepare_thumbnail_object(thumbnail, track_namespace, 7, 50_000)
+            .unwrap();
+        assert_eq!((object.group_id, object.object_id), (7, 0));
+        assert_eq!(object.metadata.video_resolution(), Some((212, 160)));
+        assert!(object.publisher_priority > 1);
+        // Encoding at the thumbnail size leaves the main encoder untouched
+        assert_eq!(processor.video_config().width, 640);
+    }
+
+    #[test]
+    fn test_media_processor_codec_selection() {
+        let mut registry = CodecRegistry::new();
+        registry
+            .register_codec("h264", std::sync::Arc::new(H264Codec::new().unwrap()))
+            .unwrap();
+        let mut processor = MediaProcessor::new().with_codec_registry(registry);
+
+        assert_eq!(processor.video_codec(), Some("h264"));
+        assert_eq!(processor.audio_codec(), None);
+        assert!(processor.set_encoder_codec("opus").is_err());
+
+        let track = TrackNamespace {
+            namespace: "test.com".to_string(),
+            track_name: "bob/camera".to_string(),
+        };
+        let negotiated = processor
+            .negotiate_track_codec(
+                track.clone(),
+                &MoqTrackType::Video,
+                &["h265".to_string(), "video/h264".to_string()],
+            )
+            .unwrap();
+        assert_eq!(negotiated, "h264");
+        assert_eq!(processor.track_codec(&track), Some("h264"));
+
+        // No shared codec
+        assert!(matches!(
+            processor.negotiate_track_codec(track, &MoqTrackType::Video, &["vp9".to_string()]),
+            Err(QuicRtcError::UnsupportedCodec { .. })
+        ));
+
+        // Audio cannot be encoded without an audio codec
+        let audio = MediaFrame::Audio(AudioFrame {
+            samples: vec![0.0; 960],
+            sample_rate: 48000,
+            channels: 2,
+            timestamp: 0,
+        });
+        assert!(matches!(
+            processor.encode_media_frame(&audio),
+            Err(QuicRtcError::UnsupportedCodec { .. })
+        ));
+    }
 }
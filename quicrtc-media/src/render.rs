@@ -3,14 +3,21 @@
 //! This module provides interfaces and implementations for rendering audio
 //! to speakers and video to displays.
 
+use crate::image_ops::PixelOps;
 use crate::tracks::{AudioFrame, VideoFrame};
+use quicrtc_core::{ChannelLayout, VideoRotation};
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 use tokio::sync::mpsc;
 
 // Real audio rendering dependencies
+#[cfg(feature = "devices")]
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+#[cfg(feature = "devices")]
+use quicrtc_core::{DiagnosticsSink, LogLevel};
 use std::collections::VecDeque;
+#[cfg(feature = "devices")]
 use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Errors that can occur during rendering
@@ -106,6 +113,87 @@ impl Default for AudioRenderConfig {
     }
 }
 
+/// How received media is buffered and played out
+///
+/// Interactive playback plays media as soon as it arrives. Broadcast-style
+/// playback holds a larger buffer to ride out network stalls, and plays
+/// slightly faster than real time to catch up when the buffer grows past
+/// its target, e.g. after the backlog of a stall arrives at once.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaybackConfig {
+    /// Media buffered before playout starts or resumes after a stall
+    /// (zero plays media as it arrives)
+    pub target_buffer: Duration,
+    /// Fastest playback rate used to catch up (1.0 disables catch-up)
+    pub max_playback_rate: f32,
+}
+
+impl PlaybackConfig {
+    /// Slowest rate used when catching up
+    pub const MIN_CATCH_UP_RATE: f32 = 1.05;
+    /// Fastest rate allowed when catching up
+    pub const MAX_CATCH_UP_RATE: f32 = 1.25;
+
+    /// Play media as soon as it arrives, for calls and other two-way sessions
+    pub fn interactive() -> Self {
+        Self {
+            target_buffer: Duration::ZERO,
+            max_playback_rate: 1.0,
+        }
+    }
+
+    /// Buffer two seconds of media and catch up at up to 1.25x, for viewers
+    /// that only watch and listen
+    pub fn broadcast() -> Self {
+        Self {
+            target_buffer: Duration::from_secs(2),
+            max_playback_rate: Self::MAX_CATCH_UP_RATE,
+        }
+    }
+
+    /// Buffer 120 ms of audio without catch-up, for music that should play
+    /// steadily rather than with the lowest possible delay
+    pub fn music() -> Self {
+        Self {
+            target_buffer: Duration::from_millis(120),
+            max_playback_rate: 1.0,
+        }
+    }
+
+    /// Set the target buffer
+    pub fn with_target_buffer(mut self, target_buffer: Duration) -> Self {
+        self.target_buffer = target_buffer;
+        self
+    }
+
+    /// Set the fastest catch-up rate
+    pub fn with_max_playback_rate(mut self, rate: f32) -> Self {
+        self.max_playback_rate = rate;
+        self
+    }
+
+    /// Check that the catch-up rate is 1.0 (off) or within the supported range
+    pub fn validate(&self) -> Result<(), RenderError> {
+        let rate = self.max_playback_rate;
+        if rate != 1.0 && !(Self::MIN_CATCH_UP_RATE..=Self::MAX_CATCH_UP_RATE).contains(&rate) {
+            return Err(RenderError::ConfigurationNotSupported {
+                reason: format!(
+                    "Playback rate must be 1.0 or between {} and {}",
+                    Self::MIN_CATCH_UP_RATE,
+                    Self::MAX_CATCH_UP_RATE
+                ),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Default for PlaybackConfig {
+    fn default() -> Self {
+        Self::interactive()
+    }
+}
+
 /// Audio output device information
 #[derive(Debug, Clone)]
 pub struct AudioOutputDevice {
@@ -316,6 +404,11 @@ pub trait AudioRenderer: Send + Sync {
 
     /// Get current volume
     fn volume(&self) -> f32;
+
+    /// Set how received audio is buffered and played out
+    ///
+    /// Takes effect the next time rendering starts.
+    fn set_playback_config(&mut self, config: PlaybackConfig) -> Result<(), RenderError>;
 }
 
 /// Trait for video rendering implementations
@@ -413,12 +506,555 @@ impl AudioBuffer {
     }
 }
 
+/// Remix interleaved samples from one channel layout to another
+///
+/// Upmixing places mono in the centre channel for 5.1 and on both front
+/// channels otherwise; missing channels are silent. Downmixing folds centre
+/// and rear channels into the fronts at -3 dB and drops LFE.
+pub fn remix_channels(samples: &[f32], from: ChannelLayout, to: ChannelLayout) -> Vec<f32> {
+    if from == to {
+        return samples.to_vec();
+    }
+
+    const MIX: f32 = std::f32::consts::FRAC_1_SQRT_2;
+
+    let input_channels = from.channel_count() as usize;
+    let output_channels = to.channel_count() as usize;
+    let mut output = Vec::with_capacity(samples.len() / input_channels * output_channels);
+
+    for frame in samples.chunks_exact(input_channels) {
+        // Expand into SMPTE 5.1 slots: FL, FR, C, LFE, RL, RR
+        let [fl, fr, c, lfe, rl, rr] = match from {
+            ChannelLayout::Mono if to == ChannelLayout::Surround5_1 => {
+                [0.0, 0.0, frame[0], 0.0, 0.0, 0.0]
+            }
+            ChannelLayout::Mono => [frame[0], frame[0], 0.0, 0.0, 0.0, 0.0],
+            ChannelLayout::Stereo => [frame[0], frame[1], 0.0, 0.0, 0.0, 0.0],
+            ChannelLayout::Quad => [frame[0], frame[1], 0.0, 0.0, frame[2], frame[3]],
+            ChannelLayout::Surround5_1 => {
+                [frame[0], frame[1], frame[2], frame[3], frame[4], frame[5]]
+            }
+        };
+
+        match to {
+            ChannelLayout::Mono => {
+                let left = fl + c * MIX + rl * MIX;
+                let right = fr + c * MIX + rr * MIX;
+                output.push(((left + right) * 0.5).clamp(-1.0, 1.0));
+            }
+            ChannelLayout::Stereo => {
+                output.push((fl + c * MIX + rl * MIX).clamp(-1.0, 1.0));
+                output.push((fr + c * MIX + rr * MIX).clamp(-1.0, 1.0));
+            }
+            ChannelLayout::Quad => {
+                output.push((fl + c * MIX).clamp(-1.0, 1.0));
+                output.push((fr + c * MIX).clamp(-1.0, 1.0));
+                output.push(rl);
+                output.push(rr);
+            }
+            ChannelLayout::Surround5_1 => {
+                output.extend_from_slice(&[fl, fr, c, lfe, rl, rr]);
+            }
+        }
+    }
+
+    output
+}
+
+/// Remix between raw channel counts, falling back to a straight channel
+/// copy (with silence padding) when either side has no known layout
+pub(crate) fn remix_channel_count(
+    samples: &[f32],
+    input_channels: u8,
+    output_channels: u8,
+) -> Vec<f32> {
+    match (
+        ChannelLayout::from_channel_count(input_channels),
+        ChannelLayout::from_channel_count(output_channels),
+    ) {
+        (Some(from), Some(to)) => remix_channels(samples, from, to),
+        _ => {
+            let input_channels = input_channels.max(1) as usize;
+            let output_channels = output_channels.max(1) as usize;
+            samples
+                .chunks_exact(input_channels)
+                .flat_map(|frame| {
+                    (0..output_channels).map(move |ch| frame.get(ch).copied().unwrap_or(0.0))
+                })
+                .collect()
+        }
+    }
+}
+
+/// Decides when buffered media plays and how fast
+///
+/// Renderers report how much media is waiting to play before taking the
+/// next frame and play it at the returned rate.
+#[derive(Debug, Clone)]
+pub struct PlaybackController {
+    config: PlaybackConfig,
+    /// Waiting for the buffer to fill before playing
+    buffering: bool,
+    /// Playing fast until the buffer is back down to the target
+    catching_up: bool,
+    /// Times the buffer ran dry during playout
+    stalls: u64,
+}
+
+impl PlaybackController {
+    /// Create a controller; buffered playback starts out buffering
+    pub fn new(config: PlaybackConfig) -> Self {
+        Self {
+            buffering: !config.target_buffer.is_zero(),
+            catching_up: false,
+            stalls: 0,
+            config,
+        }
+    }
+
+    /// Playback configuration
+    pub fn config(&self) -> &PlaybackConfig {
+        &self.config
+    }
+
+    /// Whether playout is paused until the target buffer is filled
+    pub fn is_buffering(&self) -> bool {
+        self.buffering
+    }
+
+    /// Number of times the buffer ran dry during playout
+    pub fn stalls(&self) -> u64 {
+        self.stalls
+    }
+
+    /// Playback rate for the next frame, given how much media is buffered
+    ///
+    /// Returns 0.0 while buffering: playout starts, and resumes after the
+    /// buffer runs dry, once the target buffer is filled. Once more than
+    /// 1.25x the target is buffered, media plays faster until the buffer is
+    /// back down to the target. The rate rises from
+    /// [`PlaybackConfig::MIN_CATCH_UP_RATE`] to the configured maximum as
+    /// the excess approaches a whole target buffer.
+    pub fn playback_rate(&mut self, buffered: Duration) -> f32 {
+        let target = self.config.target_buffer;
+        if target.is_zero() {
+            return 1.0;
+        }
+
+        if self.buffering {
+            if buffered < target {
+                return 0.0;
+            }
+            self.buffering = false;
+        } else if buffered.is_zero() {
+            self.buffering = true;
+            self.catching_up = false;
+            self.stalls += 1;
+            return 0.0;
+        }
+
+        let max_rate = self.config.max_playback_rate;
+        if max_rate <= 1.0 {
+            return 1.0;
+        }
+        if buffered > target.mul_f32(1.25) {
+            self.catching_up = true;
+        } else if buffered <= target {
+            self.catching_up = false;
+        }
+        if !self.catching_up {
+            return 1.0;
+        }
+
+        let min_rate = PlaybackConfig::MIN_CATCH_UP_RATE.min(max_rate);
+        let excess = (buffered - target).as_secs_f32() / target.as_secs_f32();
+        min_rate + (max_rate - min_rate) * excess.min(1.0)
+    }
+}
+
+/// Shorten interleaved audio by `rate` without changing its pitch
+///
+/// The samples to drop are cut from the middle of the block and the cut is
+/// crossfaded, which is inaudible at catch-up rates of a few percent. Rates
+/// at or below 1.0 return the samples unchanged.
+pub fn time_stretch(samples: &[f32], channels: u8, rate: f32) -> Vec<f32> {
+    let channels = channels.max(1) as usize;
+    let frames = samples.len() / channels;
+    let output_frames = (frames as f32 / rate).round() as usize;
+    if rate <= 1.0 || output_frames >= frames {
+        return samples.to_vec();
+    }
+
+    let cut = frames - output_frames;
+    let fade = cut.min(output_frames);
+    let start = (output_frames - fade) / 2;
+
+    let mut output = Vec::with_capacity(output_frames * channels);
+    output.extend_from_slice(&samples[..start * channels]);
+    for i in 0..fade {
+        let fade_in = (i as f32 + 0.5) / fade as f32;
+        let outgoing = &samples[(start + i) * channels..(start + i + 1) * channels];
+        let incoming = &samples[(start + cut + i) * channels..(start + cut + i + 1) * channels];
+        output.extend(
+            outgoing
+                .iter()
+                .zip(incoming)
+                .map(|(a, b)| a * (1.0 - fade_in) + b * fade_in),
+        );
+    }
+    output.extend_from_slice(&samples[(start + cut + fade) * channels..frames * channels]);
+    output
+}
+
+/// Total play time of queued audio frames
+fn buffered_duration<'a>(frames: impl IntoIterator<Item = &'a AudioFrame>) -> Duration {
+    let seconds: f64 = frames
+        .into_iter()
+        .map(|frame| {
+            let channels = frame.channels.max(1) as f64;
+            frame.samples.len() as f64 / channels / frame.sample_rate.max(1) as f64
+        })
+        .sum();
+    Duration::from_secs_f64(seconds)
+}
+
+/// Gain applied to a single [`AudioMixer`] input
+///
+/// Implementations are shared with the owner of the source (e.g. a remote
+/// participant) so volume changes take effect on the next mixed frame.
+pub trait MixerGain: Send + Sync + std::fmt::Debug {
+    /// Linear gain for the source (0.0 silences it)
+    fn gain(&self) -> f32;
+}
+
+/// Energy-based detector of speech in local capture
+///
+/// A frame is speech when its RMS level reaches the threshold. Speech is
+/// held through pauses shorter than the hangover so it doesn't flap between
+/// words.
+#[derive(Debug, Clone)]
+pub struct VoiceActivityDetector {
+    threshold_dbfs: f32,
+    hangover: Duration,
+    quiet_for: Duration,
+    active: bool,
+}
+
+impl VoiceActivityDetector {
+    /// Detector treating frames at or above `threshold_dbfs` as speech
+    pub fn new(threshold_dbfs: f32, hangover: Duration) -> Self {
+        Self {
+            threshold_dbfs,
+            hangover,
+            quiet_for: Duration::ZERO,
+            active: false,
+        }
+    }
+
+    /// Feed a captured frame, returning whether speech is active
+    pub fn process(&mut self, frame: &AudioFrame) -> bool {
+        if frame.samples.is_empty() {
+            return self.active;
+        }
+        let energy: f32 = frame.samples.iter().map(|sample| sample * sample).sum();
+        let rms = (energy / frame.samples.len() as f32).sqrt();
+        let level_dbfs = 20.0 * rms.max(f32::MIN_POSITIVE).log10();
+
+        if level_dbfs >= self.threshold_dbfs {
+            self.active = true;
+            self.quiet_for = Duration::ZERO;
+        } else if self.active {
+            self.quiet_for += buffered_duration([frame]);
+            if self.quiet_for >= self.hangover {
+                self.active = false;
+            }
+        }
+        self.active
+    }
+
+    /// Whether speech is active
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+}
+
+impl Default for VoiceActivityDetector {
+    fn default() -> Self {
+        Self::new(-40.0, Duration::from_millis(300))
+    }
+}
+
+/// Lowers an [`AudioMixer`]'s output while the local user is speaking
+///
+/// Keeps remote participants and played files from masking the local user,
+/// e.g. for live translation or voice assistants. The gain moves to the
+/// ducked level over `attack` and back over `release` rather than jumping,
+/// which would click.
+#[derive(Debug, Clone)]
+pub struct AudioDucker {
+    ducked_gain: f32,
+    attack: Duration,
+    release: Duration,
+    detector: VoiceActivityDetector,
+    speaking: bool,
+    gain: f32,
+}
+
+impl AudioDucker {
+    /// Attenuate by `attenuation_db` while the local user speaks
+    pub fn new(attenuation_db: f32, attack: Duration, release: Duration) -> Self {
+        Self {
+            ducked_gain: 10f32.powf(-attenuation_db.abs() / 20.0),
+            attack,
+            release,
+            detector: VoiceActivityDetector::default(),
+            speaking: false,
+            gain: 1.0,
+        }
+    }
+
+    /// Detect local speech with `detector` in [`AudioDucker::push_local_frame`]
+    pub fn with_detector(mut self, detector: VoiceActivityDetector) -> Self {
+        self.detector = detector;
+        self
+    }
+
+    /// Feed a frame of local capture to the voice activity detector,
+    /// returning whether the local user is speaking
+    pub fn push_local_frame(&mut self, frame: &AudioFrame) -> bool {
+        self.speaking = self.detector.process(frame);
+        self.speaking
+    }
+
+    /// Set whether the local user is speaking, for apps with their own
+    /// voice activity detection
+    pub fn set_speaking(&mut self, speaking: bool) {
+        self.speaking = speaking;
+    }
+
+    /// Whether the local user is speaking
+    pub fn is_speaking(&self) -> bool {
+        self.speaking
+    }
+
+    /// Gain currently applied to the mix
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Scale interleaved samples, moving the gain towards its target
+    fn apply(&mut self, samples: &mut [f32], channels: u8, sample_rate: u32) {
+        let (target, ramp) = if self.speaking {
+            (self.ducked_gain, self.attack)
+        } else {
+            (1.0, self.release)
+        };
+        let ramp_frames = ramp.as_secs_f32() * sample_rate as f32;
+        let step = if ramp_frames < 1.0 {
+            f32::INFINITY
+        } else {
+            (1.0 - self.ducked_gain) / ramp_frames
+        };
+
+        for frame in samples.chunks_mut(usize::from(channels.max(1))) {
+            if self.gain > target {
+                self.gain = (self.gain - step).max(target);
+            } else if self.gain < target {
+                self.gain = (self.gain + step).min(target);
+            }
+            for sample in frame {
+                *sample *= self.gain;
+            }
+        }
+    }
+}
+
+/// A single mixer input
+#[derive(Debug)]
+struct MixerInput {
+    gain: Arc<dyn MixerGain>,
+    pending: VecDeque<Vec<f32>>,
+}
+
+/// Mixes audio from several sources into a single output stream
+///
+/// Each source's frames are remixed to the output layout and scaled by the
+/// source's [`MixerGain`] before being summed. With an [`AudioDucker`] set,
+/// the mix is lowered while the local user speaks.
+#[derive(Debug)]
+pub struct AudioMixer {
+    sample_rate: u32,
+    layout: ChannelLayout,
+    sources: std::collections::HashMap<String, MixerInput>,
+    max_pending_frames: usize,
+    ducker: Option<AudioDucker>,
+}
+
+impl AudioMixer {
+    /// Create a mixer producing frames at the given rate and layout
+    pub fn new(sample_rate: u32, layout: ChannelLayout) -> Self {
+        Self {
+            sample_rate,
+            layout,
+            sources: std::collections::HashMap::new(),
+            max_pending_frames: 10,
+            ducker: None,
+        }
+    }
+
+    /// Lower the mix while the local user speaks, or stop with `None`
+    pub fn set_ducking(&mut self, ducker: Option<AudioDucker>) {
+        self.ducker = ducker;
+    }
+
+    /// Ducking applied to the mix, if any
+    pub fn ducking(&self) -> Option<&AudioDucker> {
+        self.ducker.as_ref()
+    }
+
+    /// Feed a frame of local capture to the ducker's voice activity
+    /// detector, returning whether the local user is speaking
+    ///
+    /// Always `false` without ducking.
+    pub fn push_local_frame(&mut self, frame: &AudioFrame) -> bool {
+        self.ducker
+            .as_mut()
+            .is_some_and(|ducker| ducker.push_local_frame(frame))
+    }
+
+    /// Set whether the local user is speaking, for apps with their own
+    /// voice activity detection
+    pub fn set_local_speaking(&mut self, speaking: bool) {
+        if let Some(ducker) = self.ducker.as_mut() {
+            ducker.set_speaking(speaking);
+        }
+    }
+
+    /// Add (or replace) a source with its gain control
+    pub fn add_source(&mut self, source_id: &str, gain: Arc<dyn MixerGain>) {
+        self.sources.insert(
+            source_id.to_string(),
+            MixerInput {
+                gain,
+                pending: VecDeque::new(),
+            },
+        );
+    }
+
+    /// Remove a source, discarding any pending audio
+    pub fn remove_source(&mut self, source_id: &str) -> bool {
+        self.sources.remove(source_id).is_some()
+    }
+
+    /// Check if a source is registered
+    pub fn has_source(&self, source_id: &str) -> bool {
+        self.sources.contains_key(source_id)
+    }
+
+    /// Number of registered sources
+    pub fn source_count(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// Output sample rate in Hz
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Output channel layout
+    pub fn layout(&self) -> ChannelLayout {
+        self.layout
+    }
+
+    /// Queue a frame from a source for the next mix
+    pub fn push_frame(&mut self, source_id: &str, frame: AudioFrame) -> Result<(), RenderError> {
+        if frame.sample_rate != self.sample_rate {
+            return Err(RenderError::ConfigurationNotSupported {
+                reason: format!(
+                    "Mixer expects {} Hz, got {} Hz",
+                    self.sample_rate, frame.sample_rate
+                ),
+            });
+        }
+
+        let output_channels = self.layout.channel_count();
+        let input = self
+            .sources
+            .get_mut(source_id)
+            .ok_or_else(|| RenderError::DeviceNotFound {
+                device: source_id.to_string(),
+            })?;
+
+        let samples = if frame.channels != output_channels {
+            remix_channel_count(&frame.samples, frame.channels, output_channels)
+        } else {
+            frame.samples
+        };
+
+        input.pending.push_back(samples);
+        while input.pending.len() > self.max_pending_frames {
+            input.pending.pop_front();
+        }
+
+        Ok(())
+    }
+
+    /// Mix the next queued frame from every source
+    ///
+    /// Returns `None` when no source has audio queued.
+    pub fn mix(&mut self) -> Option<AudioFrame> {
+        let mut mixed: Vec<f32> = Vec::new();
+        let mut any = false;
+
+        for input in self.sources.values_mut() {
+            let Some(samples) = input.pending.pop_front() else {
+                continue;
+            };
+            any = true;
+
+            let gain = input.gain.gain();
+            if gain <= 0.0 {
+                continue;
+            }
+
+            if mixed.len() < samples.len() {
+                mixed.resize(samples.len(), 0.0);
+            }
+            for (out, sample) in mixed.iter_mut().zip(samples) {
+                *out += sample * gain;
+            }
+        }
+
+        if !any {
+            return None;
+        }
+
+        if let Some(ducker) = self.ducker.as_mut() {
+            ducker.apply(&mut mixed, self.layout.channel_count(), self.sample_rate);
+        }
+        for sample in mixed.iter_mut() {
+            *sample = sample.clamp(-1.0, 1.0);
+        }
+
+        Some(AudioFrame {
+            samples: mixed,
+            sample_rate: self.sample_rate,
+            channels: self.layout.channel_count(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+        })
+    }
+}
+
 /// Default audio renderer implementation
 pub struct DefaultAudioRenderer {
     config: Option<AudioRenderConfig>,
     stats: AudioRenderStats,
     is_rendering: bool,
     volume: f32,
+    playback: PlaybackConfig,
     buffer: Option<Arc<std::sync::Mutex<AudioBuffer>>>,
     _render_handle: Option<tokio::task::JoinHandle<()>>,
 }
@@ -438,6 +1074,7 @@ impl DefaultAudioRenderer {
             },
             is_rendering: false,
             volume: 1.0,
+            playback: PlaybackConfig::default(),
             buffer: None,
             _render_handle: None,
         }
@@ -452,36 +1089,6 @@ impl DefaultAudioRenderer {
         }
     }
 
-    /// Convert mono to stereo if needed
-    fn convert_channels(
-        &self,
-        input: &[f32],
-        output: &mut [f32],
-        input_channels: u8,
-        output_channels: u8,
-    ) {
-        match (input_channels, output_channels) {
-            (1, 2) => {
-                // Mono to stereo - duplicate samples
-                for (i, &sample) in input.iter().enumerate() {
-                    output[i * 2] = sample;
-                    output[i * 2 + 1] = sample;
-                }
-            }
-            (2, 1) => {
-                // Stereo to mono - average channels
-                for i in 0..input.len() / 2 {
-                    output[i] = (input[i * 2] + input[i * 2 + 1]) * 0.5;
-                }
-            }
-            _ => {
-                // Same channel count - direct copy
-                let len = input.len().min(output.len());
-                output[..len].copy_from_slice(&input[..len]);
-            }
-        }
-    }
-
     /// Simulate audio rendering (for testing and platforms without audio support)
     async fn simulate_render(
         &mut self,
@@ -494,15 +1101,35 @@ impl DefaultAudioRenderer {
         // Start playback task
         let playback_buffer = buffer.clone();
         let playback_config = config.clone();
+        let mut playback = PlaybackController::new(self.playback.clone());
         let _playback_handle = tokio::spawn(async move {
+            let channels = playback_config.channels.max(1) as usize;
             let samples_per_frame = (playback_config.sample_rate as f32 * 0.02) as usize;
-            let mut output_samples =
-                vec![0.0f32; samples_per_frame * playback_config.channels as usize];
+            let mut output_samples = vec![0.0f32; samples_per_frame * channels];
 
             loop {
                 {
                     let mut buf = playback_buffer.lock().unwrap();
-                    buf.read(&mut output_samples);
+                    let buffered = Duration::from_secs_f64(
+                        buf.available_read() as f64
+                            / channels as f64
+                            / playback_config.sample_rate.max(1) as f64,
+                    );
+                    let rate = playback.playback_rate(buffered);
+                    if rate > 1.0 {
+                        // Consume more than a frame's worth and compress it into one
+                        let mut input =
+                            vec![0.0f32; (samples_per_frame as f32 * rate) as usize * channels];
+                        buf.read(&mut input);
+                        let stretched = time_stretch(&input, playback_config.channels, rate);
+                        let len = stretched.len().min(output_samples.len());
+                        output_samples[..len].copy_from_slice(&stretched[..len]);
+                        output_samples[len..].fill(0.0);
+                    } else if rate > 0.0 {
+                        buf.read(&mut output_samples);
+                    } else {
+                        output_samples.fill(0.0);
+                    }
                 }
 
                 // Simulate audio output (in real implementation, this would go to audio hardware)
@@ -517,20 +1144,7 @@ impl DefaultAudioRenderer {
 
             // Convert channels if needed
             let output_samples = if frame.channels != config.channels {
-                let output_len = if config.channels > frame.channels {
-                    processed_samples.len() * config.channels as usize / frame.channels as usize
-                } else {
-                    processed_samples.len() * config.channels as usize / frame.channels as usize
-                };
-
-                let mut output = vec![0.0f32; output_len];
-                self.convert_channels(
-                    &processed_samples,
-                    &mut output,
-                    frame.channels,
-                    config.channels,
-                );
-                output
+                remix_channel_count(&processed_samples, frame.channels, config.channels)
             } else {
                 processed_samples
             };
@@ -569,8 +1183,10 @@ impl AudioRenderer for DefaultAudioRenderer {
 
         let (sender, receiver) = mpsc::channel(32);
 
-        // Create audio buffer
-        let buffer_size = config.buffer_size as usize * config.channels as usize * 4; // 4x buffer for safety
+        // Create audio buffer: 4x for safety, and room for twice the playback target
+        let target_frames = self.playback.target_buffer.as_secs_f64() * config.sample_rate as f64;
+        let buffer_size = (config.buffer_size as usize * 4).max(target_frames as usize * 2)
+            * config.channels as usize;
         let buffer = Arc::new(std::sync::Mutex::new(AudioBuffer::new(buffer_size)));
 
         self.config = Some(config.clone());
@@ -582,6 +1198,7 @@ impl AudioRenderer for DefaultAudioRenderer {
         let mut render_instance = DefaultAudioRenderer::new();
         render_instance.is_rendering = true;
         render_instance.volume = self.volume;
+        render_instance.playback = self.playback.clone();
 
         let handle = tokio::spawn(async move {
             render_instance
@@ -647,6 +1264,12 @@ impl AudioRenderer for DefaultAudioRenderer {
     fn volume(&self) -> f32 {
         self.volume
     }
+
+    fn set_playback_config(&mut self, config: PlaybackConfig) -> Result<(), RenderError> {
+        config.validate()?;
+        self.playback = config;
+        Ok(())
+    }
 }
 
 impl Default for DefaultAudioRenderer {
@@ -656,24 +1279,38 @@ impl Default for DefaultAudioRenderer {
 }
 
 /// Real audio renderer implementation using CPAL
+#[cfg(feature = "devices")]
 pub struct CpalAudioRenderer {
     is_rendering: Arc<AtomicBool>,
     stats: AudioRenderStats,
     volume: f32,
+    playback: PlaybackConfig,
     // Audio buffer for storing incoming frames
     audio_buffer: Arc<std::sync::Mutex<VecDeque<AudioFrame>>>,
+    /// Configuration rendering was started with, device included
+    config: Option<AudioRenderConfig>,
+    /// Output device to play to; `None` for the system default
+    device: Option<String>,
+    /// Thread holding the open output stream while rendering
+    output: Option<OutputThread>,
+    /// Where stream errors are reported
+    diagnostics: Arc<dyn DiagnosticsSink>,
 }
 
+#[cfg(feature = "devices")]
 impl std::fmt::Debug for CpalAudioRenderer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("CpalAudioRenderer")
             .field("is_rendering", &self.is_rendering.load(Ordering::Relaxed))
             .field("stats", &self.stats)
             .field("volume", &self.volume)
+            .field("playback", &self.playback)
+            .field("device", &self.device)
             .finish()
     }
 }
 
+#[cfg(feature = "devices")]
 impl CpalAudioRenderer {
     /// Create a new CPAL audio renderer instance
     pub fn new() -> Self {
@@ -688,10 +1325,96 @@ impl CpalAudioRenderer {
                 latency_ms: 20.0,
             },
             volume: 1.0,
+            playback: PlaybackConfig::default(),
             audio_buffer: Arc::new(std::sync::Mutex::new(VecDeque::new())),
+            config: None,
+            device: None,
+            output: None,
+            diagnostics: quicrtc_core::default_sink(),
         }
     }
 
+    /// Report stream errors to `sink`
+    ///
+    /// Each error is logged and counted as `audio.render.stream_errors`.
+    /// Takes effect the next time an output stream is opened.
+    pub fn set_diagnostics_sink(&mut self, sink: Arc<dyn DiagnosticsSink>) {
+        self.diagnostics = sink;
+    }
+
+    /// Move playback to another output device, keeping queued audio
+    ///
+    /// `None` selects the system default device. While rendering, the new
+    /// device is opened held silent before the old one is released, so
+    /// playback carries on where it was if the switch fails. Audio the old
+    /// stream had converted but not yet played is put back in the queue
+    /// and picked up by the new stream. When not rendering, the device is
+    /// used the next time rendering starts.
+    pub fn switch_device(&mut self, device_name: Option<String>) -> Result<(), RenderError> {
+        let Some(config) = &self.config else {
+            if let Some(name) = &device_name {
+                if !self.list_devices()?.iter().any(|device| device.id == *name) {
+                    return Err(RenderError::DeviceNotFound {
+                        device: name.clone(),
+                    });
+                }
+            }
+            self.device = device_name;
+            return Ok(());
+        };
+        let config = AudioRenderConfig {
+            device_name,
+            ..config.clone()
+        };
+
+        let output = self.open_output(&config, true)?;
+        if let Some(previous) = self.output.take() {
+            if let Some(leftover) = previous.stop() {
+                self.audio_buffer.lock().unwrap().push_front(leftover);
+            }
+        }
+        output.release();
+        self.output = Some(output);
+        self.device = config.device_name.clone();
+        self.config = Some(config);
+        Ok(())
+    }
+
+    /// Output device played to; `None` for the system default
+    pub fn device_name(&self) -> Option<&str> {
+        self.device.as_deref()
+    }
+
+    /// Open the output device of `config` on a thread of its own
+    ///
+    /// A `held` stream plays silence until released.
+    fn open_output(
+        &self,
+        config: &AudioRenderConfig,
+        held: bool,
+    ) -> Result<OutputThread, RenderError> {
+        let playout = Playout {
+            playback: PlaybackController::new(self.playback.clone()),
+            pending: VecDeque::new(),
+            channels: config.channels as u16,
+            sample_rate: config.sample_rate,
+            volume: self.volume,
+            held,
+        };
+        OutputThread::spawn(
+            config.clone(),
+            playout,
+            self.is_rendering.clone(),
+            self.audio_buffer.clone(),
+            self.diagnostics.clone(),
+        )
+    }
+
+    /// Most audio kept queued; older frames are dropped beyond this
+    fn max_buffered(playback: &PlaybackConfig) -> Duration {
+        (playback.target_buffer * 2).max(Duration::from_millis(200))
+    }
+
     /// Apply volume and effects to audio samples
     fn process_audio(&self, samples: &mut [f32]) {
         // Apply master volume
@@ -709,42 +1432,25 @@ impl CpalAudioRenderer {
         output_channels: u16,
         output_sample_rate: u32,
     ) -> Vec<f32> {
-        let mut output = input.to_vec();
-
         // Handle channel conversion
-        if input_channels != output_channels as u8 {
-            output = match (input_channels, output_channels) {
-                (1, 2) => {
-                    // Mono to stereo - duplicate samples
-                    input
-                        .iter()
-                        .flat_map(|&sample| vec![sample, sample])
-                        .collect()
-                }
-                (2, 1) => {
-                    // Stereo to mono - average channels
-                    input
-                        .chunks_exact(2)
-                        .map(|chunk| (chunk[0] + chunk[1]) * 0.5)
-                        .collect()
-                }
-                _ => output, // Same channel count or unsupported conversion
-            };
-        }
+        let mut output = if input_channels as u16 != output_channels {
+            remix_channel_count(input, input_channels, output_channels as u8)
+        } else {
+            input.to_vec()
+        };
 
-        // Handle sample rate conversion (simple nearest-neighbor for now)
+        // Handle sample rate conversion (simple nearest-neighbor per frame for now)
         if input_sample_rate != output_sample_rate {
+            let channels = (output_channels as usize).max(1);
+            let input_frames = output.len() / channels;
             let ratio = output_sample_rate as f32 / input_sample_rate as f32;
-            let new_length = (output.len() as f32 * ratio) as usize;
-            let mut resampled = Vec::with_capacity(new_length);
+            let new_frames = (input_frames as f32 * ratio) as usize;
+            let mut resampled = Vec::with_capacity(new_frames * channels);
 
-            for i in 0..new_length {
-                let src_index = (i as f32 / ratio) as usize;
-                if src_index < output.len() {
-                    resampled.push(output[src_index]);
-                } else {
-                    resampled.push(0.0);
-                }
+            for i in 0..new_frames {
+                let src_frame = ((i as f32 / ratio) as usize).min(input_frames.saturating_sub(1));
+                resampled
+                    .extend_from_slice(&output[src_frame * channels..(src_frame + 1) * channels]);
             }
             output = resampled;
         }
@@ -753,6 +1459,85 @@ impl CpalAudioRenderer {
     }
 }
 
+/// Output callback state of a [`CpalAudioRenderer`] stream
+#[cfg(feature = "devices")]
+struct Playout {
+    playback: PlaybackController,
+    /// Device-format samples not yet handed to the device
+    pending: VecDeque<f32>,
+    channels: u16,
+    sample_rate: u32,
+    volume: f32,
+    /// Play silence and leave the queue alone, as while a device switch
+    /// is under way
+    held: bool,
+}
+
+#[cfg(feature = "devices")]
+impl Playout {
+    /// Fill `output` from queued frames, padding with silence when none play
+    fn fill(&mut self, frames: &std::sync::Mutex<VecDeque<AudioFrame>>, output: &mut [f32]) {
+        if self.held {
+            output.fill(0.0);
+            return;
+        }
+
+        let channels = self.channels.max(1) as usize;
+        while self.pending.len() < output.len() {
+            let next = {
+                let mut frames = frames.lock().unwrap();
+                let pending = Duration::from_secs_f64(
+                    self.pending.len() as f64 / channels as f64 / self.sample_rate.max(1) as f64,
+                );
+                let rate = self
+                    .playback
+                    .playback_rate(buffered_duration(frames.iter()) + pending);
+                if rate > 0.0 {
+                    frames.pop_front().map(|frame| (frame, rate))
+                } else {
+                    None
+                }
+            };
+            let Some((frame, rate)) = next else {
+                break;
+            };
+
+            let mut samples = CpalAudioRenderer::convert_audio_format(
+                &frame.samples,
+                frame.channels,
+                frame.sample_rate,
+                self.channels,
+                self.sample_rate,
+            );
+            if rate > 1.0 {
+                samples = time_stretch(&samples, self.channels as u8, rate);
+            }
+            self.pending.extend(samples);
+        }
+
+        let available = self.pending.len().min(output.len());
+        for (out, sample) in output.iter_mut().zip(self.pending.drain(..available)) {
+            *out = (sample * self.volume).clamp(-1.0, 1.0);
+        }
+        output[available..].fill(0.0);
+    }
+
+    /// Take the converted samples not yet played, as a frame in the
+    /// device format
+    fn take_pending(&mut self) -> Option<AudioFrame> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        Some(AudioFrame {
+            samples: self.pending.drain(..).collect(),
+            sample_rate: self.sample_rate,
+            channels: self.channels as u8,
+            timestamp: 0,
+        })
+    }
+}
+
+#[cfg(feature = "devices")]
 impl AudioRenderer for CpalAudioRenderer {
     fn start(
         &mut self,
@@ -764,49 +1549,12 @@ impl AudioRenderer for CpalAudioRenderer {
             });
         }
 
-        // Get the default host
-        let host = cpal::default_host();
-
-        // Get the default output device
-        let device = if let Some(device_name) = &config.device_name {
-            host.output_devices()
-                .map_err(|e| RenderError::HardwareError {
-                    reason: format!("Failed to enumerate devices: {}", e),
-                })?
-                .find(|d| d.name().unwrap_or_default() == *device_name)
-                .ok_or_else(|| RenderError::DeviceNotFound {
-                    device: device_name.clone(),
-                })?
-        } else {
-            host.default_output_device()
-                .ok_or_else(|| RenderError::DeviceNotFound {
-                    device: "default output device".to_string(),
-                })?
-        };
-
-        // Get the default output config
-        let supported_config =
-            device
-                .default_output_config()
-                .map_err(|e| RenderError::ConfigurationNotSupported {
-                    reason: format!("Failed to get default output config: {}", e),
-                })?;
-
-        // Build the output stream config
-        let stream_config = cpal::StreamConfig {
-            channels: config.channels as cpal::ChannelCount,
-            sample_rate: cpal::SampleRate(config.sample_rate),
-            buffer_size: cpal::BufferSize::Fixed(config.buffer_size),
-        };
-
         let (sender, mut receiver) = mpsc::channel::<AudioFrame>(32);
-        let is_rendering = self.is_rendering.clone();
-        let audio_buffer = self.audio_buffer.clone();
-        let volume = self.volume;
+        let max_buffered = Self::max_buffered(&self.playback);
 
         // Start a task to receive frames and put them in the buffer
-        let buffer_task = audio_buffer.clone();
-        let task_is_rendering = is_rendering.clone();
+        let buffer_task = self.audio_buffer.clone();
+        let task_is_rendering = self.is_rendering.clone();
         tokio::spawn(async move {
             while let Some(frame) = receiver.recv().await {
                 if !task_is_rendering.load(Ordering::Relaxed) {
@@ -818,186 +1566,33 @@ impl AudioRenderer for CpalAudioRenderer {
                     buffer.push_back(frame);
 
                     // Keep buffer size reasonable
-                    while buffer.len() > 10 {
+                    while buffer.len() > 1 && buffered_duration(buffer.iter()) > max_buffered {
                         buffer.pop_front();
                     }
                 }
             }
         });
 
-        // Create the output stream
-        let stream = match supported_config.sample_format() {
-            cpal::SampleFormat::I16 => {
-                device.build_output_stream(
-                    &stream_config,
-                    move |data: &mut [i16], _: &cpal::OutputCallbackInfo| {
-                        if !is_rendering.load(Ordering::Relaxed) {
-                            // Fill with silence
-                            data.fill(0);
-                            return;
-                        }
-
-                        // Get data from buffer
-                        let frame_data = {
-                            let mut buffer = audio_buffer.lock().unwrap();
-                            buffer.pop_front()
-                        };
-
-                        if let Some(mut frame) = frame_data {
-                            // Convert format if needed
-                            let mut processed_samples = Self::convert_audio_format(
-                                &frame.samples,
-                                frame.channels,
-                                frame.sample_rate,
-                                stream_config.channels,
-                                stream_config.sample_rate.0,
-                            );
-
-                            // Apply volume
-                            for sample in processed_samples.iter_mut() {
-                                *sample *= volume;
-                                *sample = sample.clamp(-1.0, 1.0);
-                            }
-
-                            // Convert to i16 and copy to output
-                            let samples_to_copy = data.len().min(processed_samples.len());
-                            for (i, &sample) in
-                                processed_samples.iter().take(samples_to_copy).enumerate()
-                            {
-                                data[i] = (sample * i16::MAX as f32) as i16;
-                            }
-
-                            // Fill remaining with silence if needed
-                            if samples_to_copy < data.len() {
-                                data[samples_to_copy..].fill(0);
-                            }
-                        } else {
-                            // No data available, fill with silence
-                            data.fill(0);
-                        }
-                    },
-                    move |err| {
-                        eprintln!("Audio render stream error: {}", err);
-                    },
-                    None,
-                )
-            }
-            cpal::SampleFormat::U16 => device.build_output_stream(
-                &stream_config,
-                move |data: &mut [u16], _: &cpal::OutputCallbackInfo| {
-                    if !is_rendering.load(Ordering::Relaxed) {
-                        data.fill(u16::MAX / 2);
-                        return;
-                    }
-
-                    let frame_data = {
-                        let mut buffer = audio_buffer.lock().unwrap();
-                        buffer.pop_front()
-                    };
-
-                    if let Some(frame) = frame_data {
-                        let mut processed_samples = Self::convert_audio_format(
-                            &frame.samples,
-                            frame.channels,
-                            frame.sample_rate,
-                            stream_config.channels,
-                            stream_config.sample_rate.0,
-                        );
-
-                        for sample in processed_samples.iter_mut() {
-                            *sample *= volume;
-                            *sample = sample.clamp(-1.0, 1.0);
-                        }
-
-                        let samples_to_copy = data.len().min(processed_samples.len());
-                        for (i, &sample) in
-                            processed_samples.iter().take(samples_to_copy).enumerate()
-                        {
-                            data[i] = ((sample + 1.0) * (u16::MAX as f32 / 2.0)) as u16;
-                        }
-
-                        if samples_to_copy < data.len() {
-                            data[samples_to_copy..].fill(u16::MAX / 2);
-                        }
-                    } else {
-                        data.fill(u16::MAX / 2);
-                    }
-                },
-                move |err| {
-                    eprintln!("Audio render stream error: {}", err);
-                },
-                None,
-            ),
-            cpal::SampleFormat::F32 => device.build_output_stream(
-                &stream_config,
-                move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
-                    if !is_rendering.load(Ordering::Relaxed) {
-                        data.fill(0.0);
-                        return;
-                    }
-
-                    let frame_data = {
-                        let mut buffer = audio_buffer.lock().unwrap();
-                        buffer.pop_front()
-                    };
-
-                    if let Some(frame) = frame_data {
-                        let mut processed_samples = Self::convert_audio_format(
-                            &frame.samples,
-                            frame.channels,
-                            frame.sample_rate,
-                            stream_config.channels,
-                            stream_config.sample_rate.0,
-                        );
-
-                        for sample in processed_samples.iter_mut() {
-                            *sample *= volume;
-                            *sample = sample.clamp(-1.0, 1.0);
-                        }
-
-                        let samples_to_copy = data.len().min(processed_samples.len());
-                        data[..samples_to_copy]
-                            .copy_from_slice(&processed_samples[..samples_to_copy]);
-
-                        if samples_to_copy < data.len() {
-                            data[samples_to_copy..].fill(0.0);
-                        }
-                    } else {
-                        data.fill(0.0);
-                    }
-                },
-                move |err| {
-                    eprintln!("Audio render stream error: {}", err);
-                },
-                None,
-            ),
-            sample_format => {
-                return Err(RenderError::ConfigurationNotSupported {
-                    reason: format!("Unsupported sample format: {:?}", sample_format),
-                });
-            }
-        }
-        .map_err(|e| RenderError::StreamError {
-            reason: format!("Failed to build output stream: {}", e),
-        })?;
-
-        // Start the stream
-        stream.play().map_err(|e| RenderError::StreamError {
-            reason: format!("Failed to start stream: {}", e),
-        })?;
-
+        let config = AudioRenderConfig {
+            device_name: config.device_name.or_else(|| self.device.clone()),
+            ..config
+        };
+        self.output = Some(self.open_output(&config, false)?);
+        self.device = config.device_name.clone();
+        self.config = Some(config);
         self.is_rendering.store(true, Ordering::Relaxed);
         self.stats.is_rendering = true;
 
-        // Keep stream alive
-        std::mem::forget(stream);
-
         Ok(sender)
     }
 
     fn stop(&mut self) -> Result<(), RenderError> {
         self.is_rendering.store(false, Ordering::Relaxed);
         self.stats.is_rendering = false;
+        if let Some(output) = self.output.take() {
+            output.stop();
+        }
+        self.config = None;
 
         // Clear audio buffer
         {
@@ -1014,7 +1609,8 @@ impl AudioRenderer for CpalAudioRenderer {
         // Update buffer level
         {
             let buffer = self.audio_buffer.lock().unwrap();
-            stats.buffer_level = buffer.len() as f32 / 10.0; // Max buffer size is 10
+            stats.buffer_level = buffered_duration(buffer.iter()).as_secs_f32()
+                / Self::max_buffered(&self.playback).as_secs_f32();
         }
 
         stats
@@ -1088,6 +1684,19 @@ impl AudioRenderer for CpalAudioRenderer {
             });
         }
 
+        audio_devices.extend(
+            crate::virtual_device::speakers()
+                .into_iter()
+                .map(|speaker| AudioOutputDevice {
+                    id: speaker.id().to_string(),
+                    name: speaker.id().to_string(),
+                    is_default: false,
+                    supported_sample_rates: vec![48000],
+                    supported_channels: vec![1, 2],
+                    max_volume: 1.0,
+                }),
+        );
+
         Ok(audio_devices)
     }
 
@@ -1109,14 +1718,304 @@ impl AudioRenderer for CpalAudioRenderer {
     fn volume(&self) -> f32 {
         self.volume
     }
+
+    fn set_playback_config(&mut self, config: PlaybackConfig) -> Result<(), RenderError> {
+        config.validate()?;
+        self.playback = config;
+        Ok(())
+    }
 }
 
+#[cfg(feature = "devices")]
 impl Default for CpalAudioRenderer {
     fn default() -> Self {
         Self::new()
     }
 }
 
+#[cfg(feature = "devices")]
+impl Drop for CpalAudioRenderer {
+    fn drop(&mut self) {
+        if let Some(output) = self.output.take() {
+            output.stop();
+        }
+    }
+}
+
+/// Thread owning an open CPAL output stream
+///
+/// `cpal::Stream` isn't `Send` on every platform, so the stream is built,
+/// played and dropped on this thread and the renderer only keeps what it
+/// needs to stop it.
+#[cfg(feature = "devices")]
+struct OutputThread {
+    /// Dropping this ends the thread, and with it the stream
+    stop: std::sync::mpsc::Sender<()>,
+    handle: std::thread::JoinHandle<()>,
+    /// Callback state, shared with the stream
+    playout: Arc<std::sync::Mutex<Playout>>,
+}
+
+#[cfg(feature = "devices")]
+impl OutputThread {
+    /// Open the output device of `config` and play `playout` on it
+    ///
+    /// Returns once the stream is playing, or with the error that kept it
+    /// from starting.
+    fn spawn(
+        config: AudioRenderConfig,
+        playout: Playout,
+        is_rendering: Arc<AtomicBool>,
+        audio_buffer: Arc<std::sync::Mutex<VecDeque<AudioFrame>>>,
+        diagnostics: Arc<dyn DiagnosticsSink>,
+    ) -> Result<Self, RenderError> {
+        let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+        let (stop, stop_rx) = std::sync::mpsc::channel::<()>();
+        let playout = Arc::new(std::sync::Mutex::new(playout));
+        let stream_playout = playout.clone();
+        let handle = std::thread::Builder::new()
+            .name("quicrtc-audio-output".to_string())
+            .spawn(move || {
+                if let Some(speaker) = config
+                    .device_name
+                    .as_deref()
+                    .and_then(crate::virtual_device::speaker)
+                {
+                    let _ = ready_tx.send(Ok(()));
+                    play_virtual(
+                        &config,
+                        &stream_playout,
+                        &is_rendering,
+                        &audio_buffer,
+                        speaker.recording(),
+                        &stop_rx,
+                    );
+                    return;
+                }
+
+                let stream = match open_output_stream(
+                    &config,
+                    stream_playout,
+                    is_rendering,
+                    audio_buffer,
+                    diagnostics,
+                ) {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(e));
+                        return;
+                    }
+                };
+                let _ = ready_tx.send(Ok(()));
+
+                // Nothing is ever sent; this returns once the sender is dropped
+                let _ = stop_rx.recv();
+                drop(stream);
+            })
+            .map_err(|e| RenderError::StreamError {
+                reason: format!("Failed to spawn audio output thread: {}", e),
+            })?;
+
+        match ready_rx.recv() {
+            Ok(Ok(())) => Ok(Self {
+                stop,
+                handle,
+                playout,
+            }),
+            Ok(Err(e)) => {
+                let _ = handle.join();
+                Err(e)
+            }
+            Err(_) => {
+                let _ = handle.join();
+                Err(RenderError::StreamError {
+                    reason: "Audio output thread exited before the stream started".to_string(),
+                })
+            }
+        }
+    }
+
+    /// Start playing queued audio on a stream opened held
+    fn release(&self) {
+        self.playout.lock().unwrap().held = false;
+    }
+
+    /// Close the stream and wait until the device is released
+    ///
+    /// Returns the audio the stream had converted but not yet played.
+    fn stop(self) -> Option<AudioFrame> {
+        drop(self.stop);
+        let _ = self.handle.join();
+        self.playout.lock().unwrap().take_pending()
+    }
+}
+
+/// Play `playout` to a virtual speaker in real time until `stop` hangs up
+#[cfg(feature = "devices")]
+fn play_virtual(
+    config: &AudioRenderConfig,
+    playout: &std::sync::Mutex<Playout>,
+    is_rendering: &AtomicBool,
+    audio_buffer: &std::sync::Mutex<VecDeque<AudioFrame>>,
+    recording: &crate::virtual_device::LoopbackRecording,
+    stop: &std::sync::mpsc::Receiver<()>,
+) {
+    const PERIOD: Duration = Duration::from_millis(10);
+    let mut samples =
+        vec![0.0; (config.sample_rate as usize / 100).max(1) * config.channels.max(1) as usize];
+    let mut next = std::time::Instant::now();
+    loop {
+        next += PERIOD;
+        let wait = next.saturating_duration_since(std::time::Instant::now());
+        if !matches!(
+            stop.recv_timeout(wait),
+            Err(std::sync::mpsc::RecvTimeoutError::Timeout)
+        ) {
+            break;
+        }
+
+        if is_rendering.load(Ordering::Relaxed) {
+            playout.lock().unwrap().fill(audio_buffer, &mut samples);
+        } else {
+            samples.fill(0.0);
+        }
+        recording.record(&samples, config.sample_rate, config.channels);
+    }
+}
+
+/// Error callback of an output stream, reporting to `diagnostics`
+#[cfg(feature = "devices")]
+fn stream_error_callback(
+    diagnostics: Arc<dyn DiagnosticsSink>,
+) -> impl FnMut(cpal::StreamError) + Send + 'static {
+    move |err| {
+        diagnostics.counter("audio.render.stream_errors", 1);
+        diagnostics.log(
+            LogLevel::Error,
+            module_path!(),
+            &format!("Audio render stream error: {}", err),
+        );
+    }
+}
+
+/// Build and start an output stream playing `playout`
+#[cfg(feature = "devices")]
+fn open_output_stream(
+    config: &AudioRenderConfig,
+    playout: Arc<std::sync::Mutex<Playout>>,
+    is_rendering: Arc<AtomicBool>,
+    audio_buffer: Arc<std::sync::Mutex<VecDeque<AudioFrame>>>,
+    diagnostics: Arc<dyn DiagnosticsSink>,
+) -> Result<cpal::Stream, RenderError> {
+    // Get the default host
+    let host = cpal::default_host();
+
+    // Get the default output device
+    let device = if let Some(device_name) = &config.device_name {
+        host.output_devices()
+            .map_err(|e| RenderError::HardwareError {
+                reason: format!("Failed to enumerate devices: {}", e),
+            })?
+            .find(|d| d.name().unwrap_or_default() == *device_name)
+            .ok_or_else(|| RenderError::DeviceNotFound {
+                device: device_name.clone(),
+            })?
+    } else {
+        host.default_output_device()
+            .ok_or_else(|| RenderError::DeviceNotFound {
+                device: "default output device".to_string(),
+            })?
+    };
+
+    // Get the default output config
+    let supported_config =
+        device
+            .default_output_config()
+            .map_err(|e| RenderError::ConfigurationNotSupported {
+                reason: format!("Failed to get default output config: {}", e),
+            })?;
+
+    // Build the output stream config
+    let stream_config = cpal::StreamConfig {
+        channels: config.channels as cpal::ChannelCount,
+        sample_rate: cpal::SampleRate(config.sample_rate),
+        buffer_size: cpal::BufferSize::Fixed(config.buffer_size),
+    };
+
+    // Create the output stream
+    let stream = match supported_config.sample_format() {
+        cpal::SampleFormat::I16 => {
+            let mut samples = Vec::new();
+            device.build_output_stream(
+                &stream_config,
+                move |data: &mut [i16], _: &cpal::OutputCallbackInfo| {
+                    if !is_rendering.load(Ordering::Relaxed) {
+                        // Fill with silence
+                        data.fill(0);
+                        return;
+                    }
+
+                    samples.resize(data.len(), 0.0);
+                    playout.lock().unwrap().fill(&audio_buffer, &mut samples);
+                    for (out, &sample) in data.iter_mut().zip(samples.iter()) {
+                        *out = (sample * i16::MAX as f32) as i16;
+                    }
+                },
+                stream_error_callback(diagnostics),
+                None,
+            )
+        }
+        cpal::SampleFormat::U16 => {
+            let mut samples = Vec::new();
+            device.build_output_stream(
+                &stream_config,
+                move |data: &mut [u16], _: &cpal::OutputCallbackInfo| {
+                    if !is_rendering.load(Ordering::Relaxed) {
+                        data.fill(u16::MAX / 2);
+                        return;
+                    }
+
+                    samples.resize(data.len(), 0.0);
+                    playout.lock().unwrap().fill(&audio_buffer, &mut samples);
+                    for (out, &sample) in data.iter_mut().zip(samples.iter()) {
+                        *out = ((sample + 1.0) * (u16::MAX as f32 / 2.0)) as u16;
+                    }
+                },
+                stream_error_callback(diagnostics),
+                None,
+            )
+        }
+        cpal::SampleFormat::F32 => device.build_output_stream(
+            &stream_config,
+            move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                if !is_rendering.load(Ordering::Relaxed) {
+                    data.fill(0.0);
+                    return;
+                }
+
+                playout.lock().unwrap().fill(&audio_buffer, data);
+            },
+            stream_error_callback(diagnostics),
+            None,
+        ),
+        sample_format => {
+            return Err(RenderError::ConfigurationNotSupported {
+                reason: format!("Unsupported sample format: {:?}", sample_format),
+            });
+        }
+    }
+    .map_err(|e| RenderError::StreamError {
+        reason: format!("Failed to build output stream: {}", e),
+    })?;
+
+    // Start the stream
+    stream.play().map_err(|e| RenderError::StreamError {
+        reason: format!("Failed to start stream: {}", e),
+    })?;
+
+    Ok(stream)
+}
+
 /// Frame buffer for managing video display timing
 #[derive(Debug)]
 struct VideoFrameBuffer {
@@ -1165,6 +2064,7 @@ pub struct DefaultVideoRenderer {
     is_rendering: bool,
     buffer: Option<Arc<std::sync::Mutex<VideoFrameBuffer>>>,
     _render_handle: Option<tokio::task::JoinHandle<()>>,
+    pixel_ops: PixelOps,
 }
 
 impl DefaultVideoRenderer {
@@ -1185,11 +2085,17 @@ impl DefaultVideoRenderer {
             is_rendering: false,
             buffer: None,
             _render_handle: None,
+            pixel_ops: PixelOps::new(),
         }
     }
 
     /// Apply display processing to video frames
     fn process_video_frame(&self, frame: &mut VideoFrame) {
+        // Rotate first so display adjustments operate on the upright image
+        if frame.rotation != VideoRotation::Deg0 {
+            *frame = frame.to_upright();
+        }
+
         if self.display_config.brightness_adjustment != 0.0 {
             self.apply_brightness_adjustment(frame);
         }
@@ -1209,68 +2115,43 @@ impl DefaultVideoRenderer {
 
     /// Apply brightness adjustment to video frame
     fn apply_brightness_adjustment(&self, frame: &mut VideoFrame) {
-        let adjustment = self.display_config.brightness_adjustment;
+        let offset = (self.display_config.brightness_adjustment * 128.0) as i16;
 
         if frame.data.len() >= (frame.width * frame.height * 3 / 2) as usize {
             let y_size = (frame.width * frame.height) as usize;
 
             // Adjust Y channel (luminance) for brightness
-            for i in 0..y_size {
-                let original = frame.data[i] as f32;
-                let adjusted = original + (adjustment * 128.0);
-                frame.data[i] = adjusted.clamp(0.0, 255.0) as u8;
-            }
+            self.pixel_ops
+                .adjust_brightness(&mut frame.data[..y_size], offset);
         }
     }
 
     /// Apply contrast adjustment to video frame
     fn apply_contrast_adjustment(&self, frame: &mut VideoFrame) {
-        let adjustment = self.display_config.contrast_adjustment;
-        let contrast_factor = 1.0 + adjustment;
+        let contrast_factor = 1.0 + self.display_config.contrast_adjustment;
 
         if frame.data.len() >= (frame.width * frame.height * 3 / 2) as usize {
             let y_size = (frame.width * frame.height) as usize;
 
-            // Adjust Y channel (luminance) for contrast
-            for i in 0..y_size {
-                let original = frame.data[i] as f32;
-                // Apply contrast around middle gray (128)
-                let adjusted = 128.0 + (original - 128.0) * contrast_factor;
-                frame.data[i] = adjusted.clamp(0.0, 255.0) as u8;
-            }
+            // Adjust Y channel (luminance) around middle gray (128)
+            self.pixel_ops
+                .scale_around_midpoint(&mut frame.data[..y_size], contrast_factor);
         }
     }
 
     /// Apply saturation adjustment to video frame
     fn apply_saturation_adjustment(&self, frame: &mut VideoFrame) {
-        let adjustment = self.display_config.saturation_adjustment;
-        let saturation_factor = 1.0 + adjustment;
+        let saturation_factor = 1.0 + self.display_config.saturation_adjustment;
 
         if frame.data.len() >= (frame.width * frame.height * 3 / 2) as usize {
-            let width = frame.width as usize;
-            let height = frame.height as usize;
-            let y_size = width * height;
+            let y_size = (frame.width * frame.height) as usize;
             let uv_size = y_size / 4;
 
-            // Adjust U and V channels (chrominance) for saturation
-            for i in 0..uv_size {
-                let u_idx = y_size + i;
-                let v_idx = y_size + uv_size + i;
-
-                if u_idx < frame.data.len() && v_idx < frame.data.len() {
-                    // Convert to signed values (U and V are typically 128-centered)
-                    let u = (frame.data[u_idx] as i32 - 128) as f32;
-                    let v = (frame.data[v_idx] as i32 - 128) as f32;
-
-                    // Apply saturation
-                    let u_adj = (u * saturation_factor).clamp(-128.0, 127.0);
-                    let v_adj = (v * saturation_factor).clamp(-128.0, 127.0);
-
-                    // Convert back to unsigned
-                    frame.data[u_idx] = (u_adj + 128.0) as u8;
-                    frame.data[v_idx] = (v_adj + 128.0) as u8;
-                }
-            }
+            // Adjust U and V channels (chrominance), which are 128-centered
+            self.pixel_ops.scale_around_midpoint(
+                &mut frame.data[y_size..y_size + 2 * uv_size],
+                saturation_factor,
+            );
         }
     }
 
@@ -1282,11 +2163,7 @@ impl DefaultVideoRenderer {
             let y_size = (frame.width * frame.height) as usize;
 
             // Apply gamma correction to Y channel (luminance)
-            for i in 0..y_size {
-                let normalized = frame.data[i] as f32 / 255.0;
-                let corrected = normalized.powf(1.0 / gamma);
-                frame.data[i] = (corrected * 255.0).clamp(0.0, 255.0) as u8;
-            }
+            self.pixel_ops.apply_gamma(&mut frame.data[..y_size], gamma);
         }
     }
 
@@ -0,0 +1,313 @@
+//! Telephony audio
+//!
+//! Phone calls carry 8 kHz mono audio companded with G.711, either μ-law
+//! (North America, Japan) or A-law (elsewhere). [`G711Codec`] converts
+//! between that and PCM, and [`TelephonyTranscoder`] bridges a call's
+//! G.711 packets to and from the Opus audio used in rooms.
+
+use crate::audio_source::resample;
+use crate::codecs::{
+    Codec, CodecConfig, CodecInfo, CodecResult, OpusCodec, OpusConfig, SyncDecoder, SyncEncoder,
+};
+use crate::tracks::{AudioFrame, MediaFrame};
+use quicrtc_core::QuicRtcError;
+
+/// Sample rate of G.711 audio
+pub const G711_SAMPLE_RATE: u32 = 8000;
+
+/// Bias added before μ-law encoding
+const MULAW_BIAS: i32 = 0x84;
+/// Largest magnitude μ-law encodes before clipping
+const MULAW_CLIP: i32 = 32635;
+
+/// G.711 companding law
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum G711Law {
+    /// μ-law, RTP payload type 0 (PCMU)
+    MuLaw,
+    /// A-law, RTP payload type 8 (PCMA)
+    ALaw,
+}
+
+impl G711Law {
+    /// Codec name as used in SDP and capability lists
+    pub fn name(&self) -> &'static str {
+        match self {
+            G711Law::MuLaw => "pcmu",
+            G711Law::ALaw => "pcma",
+        }
+    }
+
+    /// Compand one 16-bit sample
+    pub fn encode(&self, sample: i16) -> u8 {
+        match self {
+            G711Law::MuLaw => encode_mulaw(sample),
+            G711Law::ALaw => encode_alaw(sample),
+        }
+    }
+
+    /// Expand one companded sample
+    pub fn decode(&self, value: u8) -> i16 {
+        match self {
+            G711Law::MuLaw => decode_mulaw(value),
+            G711Law::ALaw => decode_alaw(value),
+        }
+    }
+}
+
+fn encode_mulaw(sample: i16) -> u8 {
+    let sign = if sample < 0 { 0x80 } else { 0 };
+    let magnitude = (sample as i32).abs().min(MULAW_CLIP) + MULAW_BIAS;
+    let exponent = (magnitude >> 7).max(1).ilog2() as i32;
+    let mantissa = (magnitude >> (exponent + 3)) & 0x0F;
+    !(sign | (exponent << 4) as u8 | mantissa as u8)
+}
+
+fn decode_mulaw(value: u8) -> i16 {
+    let value = !value;
+    let exponent = ((value >> 4) & 0x07) as i32;
+    let mantissa = (value & 0x0F) as i32;
+    let magnitude = (((mantissa << 3) + MULAW_BIAS) << exponent) - MULAW_BIAS;
+    if value & 0x80 != 0 {
+        (-magnitude) as i16
+    } else {
+        magnitude as i16
+    }
+}
+
+fn encode_alaw(sample: i16) -> u8 {
+    let sign = if sample >= 0 { 0x80 } else { 0 };
+    let magnitude = ((sample as i32).abs().min(32767) >> 3) as u16;
+    let value = if magnitude < 32 {
+        (magnitude >> 1) as u8
+    } else {
+        let exponent = (magnitude >> 5).ilog2() as u8 + 1;
+        let mantissa = ((magnitude >> exponent) & 0x0F) as u8;
+        (exponent << 4) | mantissa
+    };
+    (sign | value) ^ 0x55
+}
+
+fn decode_alaw(value: u8) -> i16 {
+    let value = value ^ 0x55;
+    let exponent = ((value >> 4) & 0x07) as i32;
+    let mantissa = (value & 0x0F) as i32;
+    let magnitude = if exponent == 0 {
+        (mantissa << 4) + 8
+    } else {
+        ((mantissa << 4) + 0x108) << (exponent - 1)
+    };
+    if value & 0x80 != 0 {
+        magnitude as i16
+    } else {
+        (-magnitude) as i16
+    }
+}
+
+/// G.711 audio codec
+///
+/// Encoding downmixes to mono and resamples to 8 kHz as needed; decoding
+/// yields 8 kHz mono frames.
+#[derive(Debug, Clone)]
+pub struct G711Codec {
+    law: G711Law,
+}
+
+impl G711Codec {
+    /// Create a codec using `law`
+    pub fn new(law: G711Law) -> Self {
+        Self { law }
+    }
+
+    /// Companding law in use
+    pub fn law(&self) -> G711Law {
+        self.law
+    }
+}
+
+impl SyncEncoder for G711Codec {
+    fn encode_sync(&self, frame: &MediaFrame) -> CodecResult<Vec<u8>> {
+        let MediaFrame::Audio(audio) = frame else {
+            return Err(QuicRtcError::InvalidMediaType {
+                expected: "Audio".to_string(),
+                actual: "Video".to_string(),
+            });
+        };
+
+        let channels = audio.channels.max(1) as usize;
+        let mono: Vec<f32> = audio
+            .samples
+            .chunks_exact(channels)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+            .collect();
+        let mono = resample(&mono, 1, audio.sample_rate, G711_SAMPLE_RATE);
+
+        Ok(mono
+            .iter()
+            .map(|&s| self.law.encode((s.clamp(-1.0, 1.0) * 32767.0) as i16))
+            .collect())
+    }
+
+    fn get_codec_info(&self) -> CodecInfo {
+        g711_codec_info(self.law)
+    }
+
+    fn configure(&mut self, config: &CodecConfig) -> CodecResult<()> {
+        match config.sample_rate {
+            Some(rate) if rate != G711_SAMPLE_RATE => Err(QuicRtcError::InvalidData {
+                reason: format!("G.711 only runs at {} Hz, not {}", G711_SAMPLE_RATE, rate),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl SyncDecoder for G711Codec {
+    fn decode_sync(&self, data: &[u8]) -> CodecResult<MediaFrame> {
+        if data.is_empty() {
+            return Err(QuicRtcError::InvalidData {
+                reason: "Empty G.711 data".to_string(),
+            });
+        }
+
+        Ok(MediaFrame::Audio(AudioFrame {
+            samples: data
+                .iter()
+                .map(|&value| self.law.decode(value) as f32 / 32768.0)
+                .collect(),
+            sample_rate: G711_SAMPLE_RATE,
+            channels: 1,
+            timestamp: 0,
+        }))
+    }
+
+    fn get_codec_info(&self) -> CodecInfo {
+        g711_codec_info(self.law)
+    }
+}
+
+impl Codec for G711Codec {
+    fn clone_codec(&self) -> Box<dyn Codec> {
+        Box::new(self.clone())
+    }
+
+    fn clone_encoder(&self) -> Box<dyn SyncEncoder> {
+        Box::new(self.clone())
+    }
+
+    fn clone_decoder(&self) -> Box<dyn SyncDecoder> {
+        Box::new(self.clone())
+    }
+}
+
+fn g711_codec_info(law: G711Law) -> CodecInfo {
+    CodecInfo {
+        name: law.name().to_uppercase(),
+        mime_type: format!("audio/{}", law.name().to_uppercase()),
+        sample_rate: Some(G711_SAMPLE_RATE),
+        channels: Some(1),
+    }
+}
+
+/// Converts a phone call's G.711 audio to and from room Opus audio
+///
+/// Phone packets may carry any whole number of milliseconds; audio is
+/// buffered until it fills an Opus frame.
+#[derive(Debug)]
+pub struct TelephonyTranscoder {
+    g711: G711Codec,
+    opus: OpusCodec,
+    /// Phone audio at the Opus rate not yet encoded
+    pending: Vec<f32>,
+}
+
+impl TelephonyTranscoder {
+    /// Create a transcoder for calls using `law`, encoding mono Opus
+    pub fn new(law: G711Law) -> CodecResult<Self> {
+        let opus = OpusCodec::with_config(OpusConfig {
+            channels: 1,
+            bitrate: 24000,
+            ..OpusConfig::default()
+        })?;
+        Ok(Self {
+            g711: G711Codec::new(law),
+            opus,
+            pending: Vec::new(),
+        })
+    }
+
+    /// Feed a G.711 packet from the call, returning the Opus packets it
+    /// completes
+    pub fn phone_to_room(&mut self, payload: &[u8]) -> CodecResult<Vec<Vec<u8>>> {
+        let MediaFrame::Audio(audio) = self.g711.decode_sync(payload)? else {
+            unreachable!("G.711 decodes to audio");
+        };
+        let sample_rate = self.opus.config().sample_rate;
+        self.pending
+            .extend(resample(&audio.samples, 1, G711_SAMPLE_RATE, sample_rate));
+
+        let frame_len = self.opus.samples_per_frame();
+        let mut packets = Vec::new();
+        while self.pending.len() >= frame_len {
+            let samples: Vec<f32> = self.pending.drain(..frame_len).collect();
+            let frame = MediaFrame::Audio(AudioFrame {
+                samples,
+                sample_rate,
+                channels: 1,
+                timestamp: 0,
+            });
+            packets.push(self.opus.encode_sync(&frame)?);
+        }
+        Ok(packets)
+    }
+
+    /// Convert an Opus packet from the room into a G.711 packet for the call
+    pub fn room_to_phone(&self, packet: &[u8]) -> CodecResult<Vec<u8>> {
+        let frame = self.opus.decode_sync(packet)?;
+        self.g711.encode_sync(&frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_g711_round_trip() {
+        for law in [G711Law::MuLaw, G711Law::ALaw] {
+            for sample in [
+                0i16, 1, -1, 100, -100, 1000, -1000, 12345, -12345, 32767, -32768,
+            ] {
+                let decoded = law.decode(law.encode(sample)) as i32;
+                // Companding keeps the error within a step of the sample's segment
+                let tolerance = (sample as i32).abs() / 16 + 16;
+                assert!(
+                    (decoded - sample as i32).abs() <= tolerance,
+                    "{:?}: {} decoded as {}",
+                    law,
+                    sample,
+                    decoded
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_g711_codec_resamples_to_8khz() {
+        let codec = G711Codec::new(G711Law::MuLaw);
+        let frame = MediaFrame::Audio(AudioFrame {
+            samples: vec![0.25; 960 * 2],
+            sample_rate: 48000,
+            channels: 2,
+            timestamp: 0,
+        });
+        let encoded = codec.encode_sync(&frame).unwrap();
+        assert_eq!(encoded.len(), 160);
+
+        let MediaFrame::Audio(decoded) = codec.decode_sync(&encoded).unwrap() else {
+            panic!("expected audio");
+        };
+        assert_eq!((decoded.sample_rate, decoded.channels), (8000, 1));
+        assert!(decoded.samples.iter().all(|s| (s - 0.25).abs() < 0.01));
+    }
+}
@@ -0,0 +1,79 @@
+//! Thumbnail renditions of published video
+//!
+//! A [`ThumbnailGenerator`] picks a frame from a full-rate video track every
+//! interval and scales it down, so large rooms can show a preview of each
+//! publisher without subscribing to their full video.
+
+use crate::tracks::VideoFrame;
+use std::time::{Duration, Instant};
+
+/// Default thumbnail height in pixels
+pub const DEFAULT_THUMBNAIL_HEIGHT: u32 = 160;
+/// Default time between thumbnails
+pub const DEFAULT_THUMBNAIL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Produces low-rate, downscaled copies of a video track
+#[derive(Debug, Clone)]
+pub struct ThumbnailGenerator {
+    height: u32,
+    interval: Duration,
+    last_emitted: Option<Instant>,
+}
+
+impl Default for ThumbnailGenerator {
+    fn default() -> Self {
+        Self::new(DEFAULT_THUMBNAIL_HEIGHT, DEFAULT_THUMBNAIL_INTERVAL)
+    }
+}
+
+impl ThumbnailGenerator {
+    /// Emit a `height`-pixel-high thumbnail at most once per `interval`
+    pub fn new(height: u32, interval: Duration) -> Self {
+        Self {
+            height: height.max(2),
+            interval,
+            last_emitted: None,
+        }
+    }
+
+    /// Target thumbnail height
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Time between thumbnails
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Whether a frame offered at `now` would become a thumbnail
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.last_emitted
+            .is_none_or(|last| now.saturating_duration_since(last) >= self.interval)
+    }
+
+    /// Offer a full-size raw frame, returning its thumbnail when one is due
+    ///
+    /// Thumbnails are upright, keep the frame's aspect ratio with even
+    /// dimensions, and are never larger than the frame. Each one is marked
+    /// as a keyframe since subscribers show them independently.
+    pub fn offer(&mut self, frame: &VideoFrame, now: Instant) -> Option<VideoFrame> {
+        if !self.is_due(now) || frame.width == 0 || frame.height == 0 {
+            return None;
+        }
+        self.last_emitted = Some(now);
+
+        let upright = frame.to_upright();
+        let (width, height) = self.thumbnail_size(upright.width, upright.height);
+        let mut thumbnail = upright.scale(width, height);
+        thumbnail.is_keyframe = true;
+        Some(thumbnail)
+    }
+
+    /// Thumbnail dimensions for a `width` x `height` frame
+    pub fn thumbnail_size(&self, width: u32, height: u32) -> (u32, u32) {
+        let target_height = self.height.min(height) & !1;
+        let target_width = (width as u64 * target_height as u64 / height.max(1) as u64) as u32 & !1;
+        (target_width.max(2), target_height.max(2))
+    }
+}
@@ -1,5 +1,7 @@
 //! Track abstractions and media frame types
 
+use quicrtc_core::VideoRotation;
+
 /// Audio frame representation
 #[derive(Debug, Clone)]
 pub struct AudioFrame {
@@ -26,6 +28,179 @@ pub struct VideoFrame {
     pub timestamp: u64,
     /// Whether this is a keyframe
     pub is_keyframe: bool,
+    /// Clockwise rotation needed to display the frame upright
+    pub rotation: VideoRotation,
+}
+
+impl VideoFrame {
+    /// Rotate raw frame data clockwise by `rotation`
+    ///
+    /// Packed RGB24/RGBA frames are detected from a data length of exactly 3
+    /// or 4 bytes per pixel; anything else at least I420-sized is treated as
+    /// planar I420. Width and height are swapped for 90 and 270 degree
+    /// rotations and the returned frame has no remaining rotation. Frames too
+    /// short for either layout are returned unchanged.
+    pub fn rotate(&self, rotation: VideoRotation) -> VideoFrame {
+        let width = self.width as usize;
+        let height = self.height as usize;
+        let pixels = width * height;
+        let chroma_width = width.div_ceil(2);
+        let chroma_height = height.div_ceil(2);
+        let chroma_size = chroma_width * chroma_height;
+
+        if rotation == VideoRotation::Deg0 {
+            return self.clone();
+        }
+
+        let data = if pixels > 0 && (self.data.len() == pixels * 3 || self.data.len() == pixels * 4)
+        {
+            let bytes_per_pixel = self.data.len() / pixels;
+            rotate_plane(&self.data, width, height, bytes_per_pixel, rotation)
+        } else if self.data.len() >= pixels + 2 * chroma_size {
+            let mut data = Vec::with_capacity(pixels + 2 * chroma_size);
+            data.extend(rotate_plane(
+                &self.data[..pixels],
+                width,
+                height,
+                1,
+                rotation,
+            ));
+            for plane in 0..2 {
+                let start = pixels + plane * chroma_size;
+                data.extend(rotate_plane(
+                    &self.data[start..start + chroma_size],
+                    chroma_width,
+                    chroma_height,
+                    1,
+                    rotation,
+                ));
+            }
+            data
+        } else {
+            return self.clone();
+        };
+
+        let (width, height) = if rotation.swaps_dimensions() {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        };
+
+        VideoFrame {
+            width,
+            height,
+            data,
+            timestamp: self.timestamp,
+            is_keyframe: self.is_keyframe,
+            rotation: VideoRotation::Deg0,
+        }
+    }
+
+    /// Apply the frame's own rotation so it can be displayed as-is
+    pub fn to_upright(&self) -> VideoFrame {
+        self.rotate(self.rotation)
+    }
+
+    /// Scale raw frame data to `width` x `height` (nearest neighbour)
+    ///
+    /// Uses the same layout detection as [`VideoFrame::rotate`]. Frames too
+    /// short for either layout, or a zero target size, are returned unchanged.
+    pub fn scale(&self, width: u32, height: u32) -> VideoFrame {
+        let src_width = self.width as usize;
+        let src_height = self.height as usize;
+        let dst_width = width as usize;
+        let dst_height = height as usize;
+        let pixels = src_width * src_height;
+        let chroma_size = src_width.div_ceil(2) * src_height.div_ceil(2);
+
+        if (width, height) == (self.width, self.height) || dst_width == 0 || dst_height == 0 {
+            return self.clone();
+        }
+
+        let data = if pixels > 0 && (self.data.len() == pixels * 3 || self.data.len() == pixels * 4)
+        {
+            let bytes_per_pixel = self.data.len() / pixels;
+            scale_plane(
+                &self.data,
+                (src_width, src_height),
+                (dst_width, dst_height),
+                bytes_per_pixel,
+            )
+        } else if self.data.len() >= pixels + 2 * chroma_size {
+            let src_chroma = (src_width.div_ceil(2), src_height.div_ceil(2));
+            let dst_chroma = (dst_width.div_ceil(2), dst_height.div_ceil(2));
+            let mut data = scale_plane(
+                &self.data[..pixels],
+                (src_width, src_height),
+                (dst_width, dst_height),
+                1,
+            );
+            for plane in 0..2 {
+                let start = pixels + plane * chroma_size;
+                data.extend(scale_plane(
+                    &self.data[start..start + chroma_size],
+                    src_chroma,
+                    dst_chroma,
+                    1,
+                ));
+            }
+            data
+        } else {
+            return self.clone();
+        };
+
+        VideoFrame {
+            width,
+            height,
+            data,
+            timestamp: self.timestamp,
+            is_keyframe: self.is_keyframe,
+            rotation: self.rotation,
+        }
+    }
+}
+
+/// Scale a single image plane with nearest neighbour sampling
+fn scale_plane(
+    plane: &[u8],
+    (src_width, src_height): (usize, usize),
+    (dst_width, dst_height): (usize, usize),
+    bytes_per_pixel: usize,
+) -> Vec<u8> {
+    let mut out = Vec::with_capacity(dst_width * dst_height * bytes_per_pixel);
+    for y in 0..dst_height {
+        let src_y = y * src_height / dst_height;
+        for x in 0..dst_width {
+            let src = (src_y * src_width + x * src_width / dst_width) * bytes_per_pixel;
+            out.extend_from_slice(&plane[src..src + bytes_per_pixel]);
+        }
+    }
+    out
+}
+
+/// Rotate a single image plane clockwise
+fn rotate_plane(
+    plane: &[u8],
+    width: usize,
+    height: usize,
+    bytes_per_pixel: usize,
+    rotation: VideoRotation,
+) -> Vec<u8> {
+    let mut out = vec![0u8; width * height * bytes_per_pixel];
+    for y in 0..height {
+        for x in 0..width {
+            let index = match rotation {
+                VideoRotation::Deg0 => y * width + x,
+                VideoRotation::Deg90 => x * height + (height - 1 - y),
+                VideoRotation::Deg180 => (height - 1 - y) * width + (width - 1 - x),
+                VideoRotation::Deg270 => (width - 1 - x) * height + y,
+            };
+            let src = (y * width + x) * bytes_per_pixel;
+            let dst = index * bytes_per_pixel;
+            out[dst..dst + bytes_per_pixel].copy_from_slice(&plane[src..src + bytes_per_pixel]);
+        }
+    }
+    out
 }
 
 /// Media frame types
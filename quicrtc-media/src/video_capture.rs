@@ -3,14 +3,21 @@
 //! This module provides comprehensive video capture capabilities across different platforms.
 //! The implementation is designed to be incrementally built up with platform-specific backends.
 
+#[cfg(feature = "devices")]
 use std::sync::Arc;
 use std::time::{Duration, Instant};
+#[cfg(feature = "devices")]
 use tokio::sync::broadcast;
 
 use crate::codecs::{H264Codec, H264Config};
 use crate::error::MediaError;
 use crate::tracks::VideoFrame;
+#[cfg(feature = "devices")]
+use crate::virtual_device::{self, VirtualCamera, VirtualCameraBackend};
+#[cfg(feature = "devices")]
 use parking_lot::RwLock;
+use quicrtc_core::VideoRotation;
+#[cfg(feature = "devices")]
 use tracing::{debug, info};
 
 /// Supported video pixel formats
@@ -146,6 +153,7 @@ pub struct FrameMetadata {
     pub resolution: VideoResolution,
     pub size: usize,
     pub quality: Option<f32>,
+    pub rotation: VideoRotation,
 }
 
 /// Video capture events
@@ -218,7 +226,11 @@ pub struct FrameProcessor {
 }
 
 /// Platform-specific video capture backend
-pub trait VideoCaptureBackend {
+///
+/// Backends must be `Send` so a [`VideoCaptureManager`] can be shared
+/// between tasks; platform handles that aren't belong on a thread of their
+/// own.
+pub trait VideoCaptureBackend: Send {
     fn enumerate_devices(&self) -> Result<Vec<VideoDevice>, MediaError>;
     fn open_device(
         &mut self,
@@ -234,25 +246,32 @@ pub trait VideoCaptureBackend {
 }
 
 /// Cross-platform video capture manager
+#[cfg(feature = "devices")]
 pub struct VideoCaptureManager {
     backend: Box<dyn VideoCaptureBackend>,
+    /// Platform backend, set aside while a virtual camera is captured from
+    platform_backend: Option<Box<dyn VideoCaptureBackend>>,
     config: Option<VideoCaptureConfig>,
     event_tx: broadcast::Sender<VideoCaptureEvent>,
     frame_processor: Option<Arc<RwLock<FrameProcessor>>>,
     stats: Arc<RwLock<CaptureStats>>,
     capture_task: Option<tokio::task::JoinHandle<()>>,
+    rotation: Arc<RwLock<VideoRotation>>,
 }
 
+#[cfg(feature = "devices")]
 impl std::fmt::Debug for VideoCaptureManager {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("VideoCaptureManager")
             .field("config", &self.config)
             .field("frame_processor", &self.frame_processor.is_some())
             .field("capture_task", &self.capture_task.is_some())
+            .field("rotation", &*self.rotation.read())
             .finish()
     }
 }
 
+#[cfg(feature = "devices")]
 impl VideoCaptureManager {
     /// Create new video capture manager
     pub fn new() -> Result<Self, MediaError> {
@@ -261,14 +280,31 @@ impl VideoCaptureManager {
 
         Ok(Self {
             backend,
+            platform_backend: None,
             config: None,
             event_tx,
             frame_processor: None,
             stats: Arc::new(RwLock::new(CaptureStats::default())),
             capture_task: None,
+            rotation: Arc::new(RwLock::new(VideoRotation::Deg0)),
         })
     }
 
+    /// Set the rotation needed to display captured frames upright
+    ///
+    /// Mobile platforms should update this as the device orientation
+    /// changes; it is stamped onto every subsequently captured frame,
+    /// whichever backend captured it. Backends themselves don't know the
+    /// device orientation and leave frames unrotated.
+    pub fn set_rotation(&self, rotation: VideoRotation) {
+        *self.rotation.write() = rotation;
+    }
+
+    /// Current capture rotation
+    pub fn rotation(&self) -> VideoRotation {
+        *self.rotation.read()
+    }
+
     /// Create platform-specific backend
     fn create_platform_backend() -> Result<Box<dyn VideoCaptureBackend>, MediaError> {
         // Use the simplified nokhwa capture backend for all platforms
@@ -297,9 +333,29 @@ impl VideoCaptureManager {
         Ok(())
     }
 
-    /// Enumerate available devices
+    /// Enumerate available devices, virtual cameras included
     pub fn enumerate_devices(&self) -> Result<Vec<VideoDevice>, MediaError> {
-        self.backend.enumerate_devices()
+        let mut devices = self
+            .platform_backend
+            .as_ref()
+            .unwrap_or(&self.backend)
+            .enumerate_devices()?;
+        devices.extend(virtual_device::cameras().iter().map(VirtualCamera::device));
+        Ok(devices)
+    }
+
+    /// Switch to the backend that captures from `device_id`
+    fn select_backend(&mut self, device_id: &str) {
+        let is_virtual = device_id.starts_with(virtual_device::VIRTUAL_DEVICE_PREFIX);
+        if is_virtual && self.platform_backend.is_none() {
+            let platform =
+                std::mem::replace(&mut self.backend, Box::new(VirtualCameraBackend::new()));
+            self.platform_backend = Some(platform);
+        } else if !is_virtual {
+            if let Some(platform) = self.platform_backend.take() {
+                self.backend = platform;
+            }
+        }
     }
 
     /// Start capture
@@ -312,6 +368,7 @@ impl VideoCaptureManager {
         config.validate()?;
 
         // Open device
+        self.select_backend(device_id);
         self.backend.open_device(device_id, &config)?;
 
         // Set up frame processor if needed
@@ -340,6 +397,7 @@ impl VideoCaptureManager {
         let stats = self.stats.clone();
         let frame_processor = self.frame_processor.clone();
         let event_tx = self.event_tx.clone();
+        let rotation = self.rotation.clone();
 
         // Spawn capture task
         let task = tokio::spawn(async move {
@@ -406,6 +464,7 @@ impl VideoCaptureManager {
                     resolution: VideoResolution::HD,
                     size: 1280 * 720 * 3 / 2,
                     quality: Some(0.8),
+                    rotation: *rotation.read(),
                 };
 
                 let _ = event_tx.send(VideoCaptureEvent::FrameCaptured { metadata });
@@ -444,6 +503,23 @@ impl VideoCaptureManager {
         self.backend.is_capturing()
     }
 
+    /// Take the next frame from the device being captured
+    ///
+    /// The frame and its metadata carry the current capture rotation, see
+    /// [`Self::set_rotation`]. Returns `None` when not capturing.
+    pub fn get_frame(&mut self) -> Result<Option<(VideoFrame, FrameMetadata)>, MediaError> {
+        let rotation = self.rotation();
+        Ok(self.backend.get_frame()?.map(|(frame, metadata)| {
+            (
+                VideoFrame { rotation, ..frame },
+                FrameMetadata {
+                    rotation,
+                    ..metadata
+                },
+            )
+        }))
+    }
+
     /// Get current statistics
     pub fn get_stats(&self) -> CaptureStats {
         (*self.stats.read()).clone()
@@ -462,6 +538,7 @@ impl VideoCaptureManager {
 
 /// Cross-platform video capture backend using nokhwa
 /// This provides real camera capture on macOS, Linux, Windows, and WASM
+#[cfg(feature = "devices")]
 pub struct NokhwaBackend {
     capture: crate::capture::NokhwaCapture,
     current_config: Option<VideoCaptureConfig>,
@@ -469,6 +546,7 @@ pub struct NokhwaBackend {
     frame_counter: u64,
 }
 
+#[cfg(feature = "devices")]
 impl NokhwaBackend {
     pub fn new() -> Self {
         Self {
@@ -480,6 +558,7 @@ impl NokhwaBackend {
     }
 }
 
+#[cfg(feature = "devices")]
 impl VideoCaptureBackend for NokhwaBackend {
     fn enumerate_devices(&self) -> Result<Vec<VideoDevice>, MediaError> {
         info!("🔍 Enumerating camera devices via simplified nokhwa");
@@ -569,6 +648,7 @@ impl VideoCaptureBackend for NokhwaBackend {
                     .unwrap()
                     .as_millis() as u64,
                 is_keyframe: true,
+                rotation: VideoRotation::Deg0,
             };
 
             let metadata = FrameMetadata {
@@ -579,6 +659,7 @@ impl VideoCaptureBackend for NokhwaBackend {
                 resolution: config.resolution,
                 size: frame_data.len(),
                 quality: Some(0.95),
+                rotation: video_frame.rotation,
             };
 
             debug!(
@@ -599,6 +680,7 @@ impl VideoCaptureBackend for NokhwaBackend {
             resolution: config.resolution,
             size: frame.data.len(),
             quality: Some(0.7),
+            rotation: frame.rotation,
         };
 
         Ok(Some((frame, metadata)))
@@ -619,6 +701,7 @@ impl VideoCaptureBackend for NokhwaBackend {
     }
 }
 
+#[cfg(feature = "devices")]
 impl NokhwaBackend {
     /// Create a fallback test pattern frame when camera isn't available
     fn create_fallback_frame(&self, config: &VideoCaptureConfig, frame_count: u64) -> VideoFrame {
@@ -646,6 +729,7 @@ impl NokhwaBackend {
                 .unwrap()
                 .as_millis() as u64,
             is_keyframe: true,
+            rotation: VideoRotation::Deg0,
         }
     }
 }
@@ -16,8 +16,10 @@
 //! - Integration with capture pipeline for live preview
 
 use crate::error::MediaError;
+use crate::render::{PlaybackConfig, PlaybackController};
 use crate::tracks::VideoFrame;
 use crate::video_capture::{VideoPixelFormat, VideoResolution};
+use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 use tokio::sync::{broadcast, mpsc};
 use tracing::{debug, info};
@@ -210,6 +212,8 @@ pub struct VideoRenderManager {
     event_tx: broadcast::Sender<VideoRenderEvent>,
     /// Current configuration
     config: Option<VideoRenderConfig>,
+    /// Buffering and playout speed of incoming frames
+    playback: PlaybackConfig,
     /// Background rendering task
     render_task: Option<tokio::task::JoinHandle<()>>,
     /// Frame input channel
@@ -228,6 +232,7 @@ impl VideoRenderManager {
             renderer,
             event_tx,
             config: None,
+            playback: PlaybackConfig::default(),
             render_task: None,
             frame_rx: Some(frame_rx),
             frame_tx,
@@ -292,6 +297,19 @@ impl VideoRenderManager {
         Ok(())
     }
 
+    /// Set how incoming frames are buffered and played out
+    ///
+    /// Takes effect when the renderer is initialized.
+    pub fn set_playback_config(&mut self, config: PlaybackConfig) -> Result<(), MediaError> {
+        config
+            .validate()
+            .map_err(|e| MediaError::InvalidConfiguration {
+                message: e.to_string(),
+            })?;
+        self.playback = config;
+        Ok(())
+    }
+
     /// Send frame for rendering
     pub fn render_frame(&self, frame: VideoFrame) -> Result<(), MediaError> {
         self.frame_tx
@@ -362,6 +380,7 @@ impl VideoRenderManager {
             .expect("Frame receiver should be available");
         let event_tx = self.event_tx.clone();
         let target_fps = self.config.as_ref().map(|c| c.target_fps).unwrap_or(60);
+        let mut playback = PlaybackController::new(self.playback.clone());
 
         let render_task = tokio::spawn(async move {
             let frame_duration = if target_fps > 0 {
@@ -369,11 +388,37 @@ impl VideoRenderManager {
             } else {
                 Duration::from_millis(1) // Minimal delay for unlimited FPS
             };
+            let buffered = !playback.config().target_buffer.is_zero();
 
             let mut frame_number = 0u64;
             let mut last_frame_time = Instant::now();
+            let mut queue = VecDeque::new();
+
+            loop {
+                // Wait for a frame when none are queued, then take all that arrived
+                if queue.is_empty() {
+                    match frame_rx.recv().await {
+                        Some(frame) => queue.push_back(frame),
+                        None => break,
+                    }
+                }
+                while let Ok(frame) = frame_rx.try_recv() {
+                    queue.push_back(frame);
+                }
+
+                let rate = playback.playback_rate(queued_duration(&queue, frame_duration));
+                if rate == 0.0 {
+                    // Buffering: wait for more frames before playing
+                    match frame_rx.recv().await {
+                        Some(frame) => queue.push_back(frame),
+                        None => break,
+                    }
+                    continue;
+                }
+                let Some(frame) = queue.pop_front() else {
+                    continue;
+                };
 
-            while let Some(frame) = frame_rx.recv().await {
                 let render_start = Instant::now();
 
                 // Simulate frame rendering (would call actual renderer)
@@ -393,12 +438,25 @@ impl VideoRenderManager {
 
                 frame_number += 1;
 
-                // Frame rate limiting
-                if target_fps > 0 {
-                    let elapsed = last_frame_time.elapsed();
-                    if elapsed < frame_duration {
-                        tokio::time::sleep(frame_duration - elapsed).await;
-                    }
+                // Buffered playback shows each frame until the next one is
+                // due, sped up by the playback rate; otherwise frames are
+                // only rate limited
+                let hold = if buffered {
+                    queue
+                        .front()
+                        .map(|next| {
+                            Duration::from_millis(next.timestamp.saturating_sub(frame.timestamp))
+                        })
+                        .unwrap_or(frame_duration)
+                        .div_f32(rate)
+                } else if target_fps > 0 {
+                    frame_duration
+                } else {
+                    Duration::ZERO
+                };
+                let elapsed = last_frame_time.elapsed();
+                if elapsed < hold {
+                    tokio::time::sleep(hold - elapsed).await;
                 }
                 last_frame_time = Instant::now();
             }
@@ -408,6 +466,16 @@ impl VideoRenderManager {
     }
 }
 
+/// Play time of queued frames, from their timestamps plus one frame
+fn queued_duration(queue: &VecDeque<VideoFrame>, frame_duration: Duration) -> Duration {
+    match (queue.front(), queue.back()) {
+        (Some(first), Some(last)) => {
+            Duration::from_millis(last.timestamp.saturating_sub(first.timestamp)) + frame_duration
+        }
+        _ => Duration::ZERO,
+    }
+}
+
 /// Software-based video renderer (fallback implementation)
 pub struct SoftwareRenderer {
     config: Option<VideoRenderConfig>,
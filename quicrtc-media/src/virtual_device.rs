@@ -0,0 +1,689 @@
+//! Virtual devices for automated tests
+//!
+//! CI machines usually have no camera, microphone or speakers. Virtual
+//! devices registered here are listed next to the real ones by
+//! `VideoCaptureManager`, `CpalAudioCapture` and `CpalAudioRenderer` and are
+//! opened by ID like any other device, so tests can run the full pipeline.
+//! A [`VirtualCamera`] plays color bars or raw I420 frames from a file, a
+//! [`VirtualMicrophone`] a sine tone or a WAV file, and a [`VirtualSpeaker`]
+//! keeps what it plays in a [`LoopbackRecording`]. [`compare_audio`] and
+//! [`video_psnr`] check received media against golden samples.
+
+use crate::audio_source::parse_wav;
+use crate::error::{MediaError, MediaResult};
+use crate::tracks::{AudioFrame, VideoFrame};
+use crate::video_capture::{
+    FrameMetadata, VideoCaptureBackend, VideoCaptureConfig, VideoDevice, VideoPixelFormat,
+    VideoResolution,
+};
+use quicrtc_core::VideoRotation;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Prefix of virtual device IDs
+pub const VIRTUAL_DEVICE_PREFIX: &str = "virtual:";
+
+/// Registered virtual devices
+static DEVICES: Mutex<Vec<VirtualDevice>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone)]
+enum VirtualDevice {
+    Camera(VirtualCamera),
+    Microphone(VirtualMicrophone),
+    Speaker(VirtualSpeaker),
+}
+
+impl VirtualDevice {
+    fn id(&self) -> &str {
+        match self {
+            VirtualDevice::Camera(camera) => &camera.id,
+            VirtualDevice::Microphone(microphone) => &microphone.id,
+            VirtualDevice::Speaker(speaker) => &speaker.id,
+        }
+    }
+}
+
+fn register(device: VirtualDevice) {
+    let mut devices = DEVICES.lock().unwrap();
+    devices.retain(|registered| registered.id() != device.id());
+    devices.push(device);
+}
+
+fn virtual_id(name: &str) -> String {
+    format!("{}{}", VIRTUAL_DEVICE_PREFIX, name)
+}
+
+/// Make `camera` available as a video capture device
+pub fn register_camera(camera: VirtualCamera) {
+    register(VirtualDevice::Camera(camera));
+}
+
+/// Make `microphone` available as an audio input device
+pub fn register_microphone(microphone: VirtualMicrophone) {
+    register(VirtualDevice::Microphone(microphone));
+}
+
+/// Make a speaker called `name` available as an audio output device
+///
+/// Returns the recording of what is played to it.
+pub fn register_speaker(name: &str) -> LoopbackRecording {
+    let speaker = VirtualSpeaker {
+        id: virtual_id(name),
+        recording: LoopbackRecording::default(),
+    };
+    let recording = speaker.recording.clone();
+    register(VirtualDevice::Speaker(speaker));
+    recording
+}
+
+/// Remove the virtual device `id`, returning whether it was registered
+pub fn unregister(id: &str) -> bool {
+    let mut devices = DEVICES.lock().unwrap();
+    let before = devices.len();
+    devices.retain(|device| device.id() != id);
+    devices.len() != before
+}
+
+/// Registered virtual cameras
+pub fn cameras() -> Vec<VirtualCamera> {
+    DEVICES
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|device| match device {
+            VirtualDevice::Camera(camera) => Some(camera.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Registered virtual microphones
+pub fn microphones() -> Vec<VirtualMicrophone> {
+    DEVICES
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|device| match device {
+            VirtualDevice::Microphone(microphone) => Some(microphone.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Registered virtual speakers
+pub fn speakers() -> Vec<VirtualSpeaker> {
+    DEVICES
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|device| match device {
+            VirtualDevice::Speaker(speaker) => Some(speaker.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Virtual camera with ID `id`
+pub fn camera(id: &str) -> Option<VirtualCamera> {
+    cameras().into_iter().find(|camera| camera.id == id)
+}
+
+/// Virtual microphone with ID `id`
+pub fn microphone(id: &str) -> Option<VirtualMicrophone> {
+    microphones()
+        .into_iter()
+        .find(|microphone| microphone.id == id)
+}
+
+/// Virtual speaker with ID `id`
+pub fn speaker(id: &str) -> Option<VirtualSpeaker> {
+    speakers().into_iter().find(|speaker| speaker.id == id)
+}
+
+/// What a [`VirtualMicrophone`] records
+#[derive(Debug, Clone)]
+pub enum VirtualAudioSignal {
+    /// Sine tone
+    Sine {
+        /// Frequency in Hz
+        frequency: f32,
+        /// Peak amplitude (0.0 to 1.0)
+        amplitude: f32,
+    },
+    /// Interleaved samples, played in a loop
+    Samples(Arc<[f32]>),
+}
+
+/// Virtual audio input device
+#[derive(Debug, Clone)]
+pub struct VirtualMicrophone {
+    id: String,
+    signal: VirtualAudioSignal,
+    sample_rate: u32,
+    channels: u8,
+}
+
+impl VirtualMicrophone {
+    /// Microphone called `name` recording a half-scale sine tone at 48 kHz
+    /// mono
+    pub fn sine(name: &str, frequency: f32) -> Self {
+        Self {
+            id: virtual_id(name),
+            signal: VirtualAudioSignal::Sine {
+                frequency,
+                amplitude: 0.5,
+            },
+            sample_rate: 48000,
+            channels: 1,
+        }
+    }
+
+    /// Microphone called `name` recording a PCM or IEEE float WAV file
+    pub fn from_wav_file(name: &str, path: impl AsRef<Path>) -> MediaResult<Self> {
+        let wav = parse_wav(&std::fs::read(path)?)?;
+        if wav.samples.is_empty() {
+            return Err(MediaError::InvalidConfiguration {
+                message: "Virtual microphone WAV file has no audio".to_string(),
+            });
+        }
+        Ok(Self {
+            id: virtual_id(name),
+            signal: VirtualAudioSignal::Samples(wav.samples.into()),
+            sample_rate: wav.sample_rate,
+            channels: wav.channels,
+        })
+    }
+
+    /// Device ID
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Sample rate recorded at
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Channels recorded
+    pub fn channels(&self) -> u8 {
+        self.channels
+    }
+
+    /// Record `frames` sample frames, starting `offset` sample frames into
+    /// the signal
+    pub fn read(&self, offset: u64, frames: usize) -> AudioFrame {
+        let channels = self.channels as usize;
+        let samples = match &self.signal {
+            VirtualAudioSignal::Sine {
+                frequency,
+                amplitude,
+            } => (0..frames as u64)
+                .flat_map(|frame| {
+                    let t = (offset + frame) as f64 / self.sample_rate as f64;
+                    let sample =
+                        (std::f64::consts::TAU * *frequency as f64 * t).sin() as f32 * amplitude;
+                    std::iter::repeat_n(sample, channels)
+                })
+                .collect(),
+            VirtualAudioSignal::Samples(samples) => {
+                let start = offset as usize * channels;
+                (0..frames * channels)
+                    .map(|i| samples[(start + i) % samples.len()])
+                    .collect()
+            }
+        };
+        AudioFrame {
+            samples,
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+            timestamp: offset * 1000 / self.sample_rate as u64,
+        }
+    }
+}
+
+/// What a [`VirtualCamera`] shows
+#[derive(Debug, Clone)]
+pub enum VirtualVideoPattern {
+    /// Eight color bars scrolling one pixel a frame
+    ColorBars,
+    /// I420 frames, played in a loop
+    Frames(Arc<[Vec<u8>]>),
+}
+
+/// Virtual video capture device
+#[derive(Debug, Clone)]
+pub struct VirtualCamera {
+    id: String,
+    pattern: VirtualVideoPattern,
+    resolution: VideoResolution,
+    framerate: f64,
+}
+
+/// 75% color bars as (Y, U, V): white, yellow, cyan, green, magenta, red,
+/// blue and black
+const COLOR_BARS: [(u8, u8, u8); 8] = [
+    (180, 128, 128),
+    (162, 44, 142),
+    (131, 156, 44),
+    (112, 72, 58),
+    (84, 184, 198),
+    (65, 100, 212),
+    (35, 212, 114),
+    (16, 128, 128),
+];
+
+impl VirtualCamera {
+    /// Camera called `name` showing color bars at 30 fps
+    pub fn test_pattern(name: &str, resolution: VideoResolution) -> Self {
+        Self {
+            id: virtual_id(name),
+            pattern: VirtualVideoPattern::ColorBars,
+            resolution,
+            framerate: 30.0,
+        }
+    }
+
+    /// Camera called `name` showing a raw I420 file of back-to-back frames
+    /// at `resolution`
+    pub fn from_i420_file(
+        name: &str,
+        path: impl AsRef<Path>,
+        resolution: VideoResolution,
+    ) -> MediaResult<Self> {
+        let frame_size = i420_size(resolution);
+        let data = std::fs::read(path)?;
+        if data.is_empty() || data.len() % frame_size != 0 {
+            return Err(MediaError::UnsupportedFormat {
+                format: format!(
+                    "I420 file of {} bytes, not whole {}x{} frames",
+                    data.len(),
+                    resolution.width,
+                    resolution.height
+                ),
+            });
+        }
+        Ok(Self {
+            id: virtual_id(name),
+            pattern: VirtualVideoPattern::Frames(
+                data.chunks(frame_size).map(<[u8]>::to_vec).collect(),
+            ),
+            resolution,
+            framerate: 30.0,
+        })
+    }
+
+    /// Set the frame rate
+    pub fn with_framerate(mut self, framerate: f64) -> Self {
+        self.framerate = framerate;
+        self
+    }
+
+    /// Device ID
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Device description as listed by capture backends
+    pub fn device(&self) -> VideoDevice {
+        VideoDevice {
+            id: self.id.clone(),
+            name: self.id.clone(),
+            description: "Virtual camera".to_string(),
+            supported_formats: vec![VideoPixelFormat::YUV420P],
+            supported_resolutions: vec![self.resolution],
+            max_framerate: self.framerate,
+            hardware_acceleration: false,
+        }
+    }
+
+    /// Frame number `index`, as I420
+    pub fn frame(&self, index: u64) -> VideoFrame {
+        let data = match &self.pattern {
+            VirtualVideoPattern::ColorBars => self.color_bars(index),
+            VirtualVideoPattern::Frames(frames) => {
+                frames[(index % frames.len() as u64) as usize].clone()
+            }
+        };
+        VideoFrame {
+            width: self.resolution.width,
+            height: self.resolution.height,
+            data,
+            timestamp: (index as f64 * 1000.0 / self.framerate) as u64,
+            is_keyframe: false,
+            rotation: VideoRotation::Deg0,
+        }
+    }
+
+    fn color_bars(&self, index: u64) -> Vec<u8> {
+        let width = self.resolution.width as usize;
+        let height = self.resolution.height as usize;
+        let bar = |x: usize| {
+            let x = (x + index as usize) % width;
+            COLOR_BARS[x * COLOR_BARS.len() / width]
+        };
+
+        let mut data = Vec::with_capacity(i420_size(self.resolution));
+        for _ in 0..height {
+            data.extend((0..width).map(|x| bar(x).0));
+        }
+        let chroma_width = width.div_ceil(2);
+        let chroma_height = height.div_ceil(2);
+        for plane in [1, 2] {
+            for _ in 0..chroma_height {
+                data.extend((0..chroma_width).map(|x| {
+                    let (_, u, v) = bar(x * 2);
+                    if plane == 1 {
+                        u
+                    } else {
+                        v
+                    }
+                }));
+            }
+        }
+        data
+    }
+}
+
+fn i420_size(resolution: VideoResolution) -> usize {
+    let width = resolution.width as usize;
+    let height = resolution.height as usize;
+    width * height + 2 * width.div_ceil(2) * height.div_ceil(2)
+}
+
+/// Virtual audio output device
+#[derive(Debug, Clone)]
+pub struct VirtualSpeaker {
+    id: String,
+    recording: LoopbackRecording,
+}
+
+impl VirtualSpeaker {
+    /// Device ID
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// What has been played to the speaker
+    pub fn recording(&self) -> &LoopbackRecording {
+        &self.recording
+    }
+}
+
+/// Audio played to a [`VirtualSpeaker`]
+#[derive(Debug, Clone, Default)]
+pub struct LoopbackRecording {
+    inner: Arc<Mutex<RecordedAudio>>,
+}
+
+#[derive(Debug, Default)]
+struct RecordedAudio {
+    samples: Vec<f32>,
+    sample_rate: u32,
+    channels: u8,
+}
+
+impl LoopbackRecording {
+    /// Append interleaved `samples` in the format the speaker was opened with
+    pub fn record(&self, samples: &[f32], sample_rate: u32, channels: u8) {
+        let mut recorded = self.inner.lock().unwrap();
+        if (recorded.sample_rate, recorded.channels) != (sample_rate, channels) {
+            recorded.samples.clear();
+            recorded.sample_rate = sample_rate;
+            recorded.channels = channels;
+        }
+        recorded.samples.extend_from_slice(samples);
+    }
+
+    /// Everything played so far, as one frame
+    pub fn audio(&self) -> AudioFrame {
+        let recorded = self.inner.lock().unwrap();
+        AudioFrame {
+            samples: recorded.samples.clone(),
+            sample_rate: recorded.sample_rate,
+            channels: recorded.channels,
+            timestamp: 0,
+        }
+    }
+
+    /// Forget what has been played
+    pub fn clear(&self) {
+        self.inner.lock().unwrap().samples.clear();
+    }
+}
+
+/// Capture backend playing the registered [`VirtualCamera`]s
+#[derive(Debug, Default)]
+pub struct VirtualCameraBackend {
+    camera: Option<VirtualCamera>,
+    config: Option<VideoCaptureConfig>,
+    capturing: bool,
+    next_frame: u64,
+}
+
+impl VirtualCameraBackend {
+    /// Create a backend with no camera open
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl VideoCaptureBackend for VirtualCameraBackend {
+    fn enumerate_devices(&self) -> Result<Vec<VideoDevice>, MediaError> {
+        Ok(cameras().iter().map(VirtualCamera::device).collect())
+    }
+
+    fn open_device(
+        &mut self,
+        device_id: &str,
+        config: &VideoCaptureConfig,
+    ) -> Result<(), MediaError> {
+        let camera = camera(device_id).ok_or_else(|| MediaError::DeviceNotFound {
+            device_id: device_id.to_string(),
+        })?;
+        self.camera = Some(VirtualCamera {
+            resolution: config.resolution,
+            framerate: config.framerate,
+            ..camera
+        });
+        self.config = Some(config.clone());
+        self.next_frame = 0;
+        Ok(())
+    }
+
+    fn start_capture(&mut self) -> Result<(), MediaError> {
+        if self.camera.is_none() {
+            return Err(MediaError::CaptureNotActive);
+        }
+        self.capturing = true;
+        Ok(())
+    }
+
+    fn stop_capture(&mut self) -> Result<(), MediaError> {
+        self.capturing = false;
+        Ok(())
+    }
+
+    fn get_frame(&mut self) -> Result<Option<(VideoFrame, FrameMetadata)>, MediaError> {
+        let Some(camera) = self.camera.as_ref().filter(|_| self.capturing) else {
+            return Ok(None);
+        };
+        let frame = camera.frame(self.next_frame);
+        let metadata = FrameMetadata {
+            sequence: self.next_frame,
+            timestamp: Instant::now(),
+            duration: Duration::from_secs_f64(1.0 / camera.framerate),
+            format: VideoPixelFormat::YUV420P,
+            resolution: camera.resolution,
+            size: frame.data.len(),
+            quality: None,
+            rotation: VideoRotation::Deg0,
+        };
+        self.next_frame += 1;
+        Ok(Some((frame, metadata)))
+    }
+
+    fn is_capturing(&self) -> bool {
+        self.capturing
+    }
+
+    fn get_config(&self) -> Option<&VideoCaptureConfig> {
+        self.config.as_ref()
+    }
+
+    fn set_config(&mut self, config: VideoCaptureConfig) -> Result<(), MediaError> {
+        config.validate()?;
+        if let Some(camera) = &mut self.camera {
+            camera.resolution = config.resolution;
+            camera.framerate = config.framerate;
+        }
+        self.config = Some(config);
+        Ok(())
+    }
+}
+
+/// How closely received audio matches a golden sample
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioComparison {
+    /// Samples the received audio lags the golden sample by
+    pub offset: usize,
+    /// Normalized correlation at that offset, 1.0 for identical shapes
+    pub correlation: f32,
+    /// Golden sample power over the power of the difference, in dB
+    pub snr_db: f32,
+}
+
+/// Compare received audio with a golden sample of the same format
+///
+/// Codecs and jitter buffers delay audio, so `actual` is searched for the
+/// best match up to `max_offset` samples after the start of `expected`.
+/// The comparison covers the overlap at that offset.
+pub fn compare_audio(expected: &[f32], actual: &[f32], max_offset: usize) -> AudioComparison {
+    let correlation_at = |offset: usize| {
+        let actual = actual.get(offset..).unwrap_or_default();
+        let (mut dot, mut expected_power, mut actual_power) = (0.0f64, 0.0f64, 0.0f64);
+        for (&e, &a) in expected.iter().zip(actual) {
+            dot += e as f64 * a as f64;
+            expected_power += e as f64 * e as f64;
+            actual_power += a as f64 * a as f64;
+        }
+        if expected_power == 0.0 || actual_power == 0.0 {
+            0.0
+        } else {
+            dot / (expected_power * actual_power).sqrt()
+        }
+    };
+
+    let (offset, correlation) = (0..=max_offset.min(actual.len()))
+        .map(|offset| (offset, correlation_at(offset)))
+        .fold((0, f64::MIN), |best, candidate| {
+            if candidate.1 > best.1 {
+                candidate
+            } else {
+                best
+            }
+        });
+
+    let (mut signal, mut noise) = (0.0f64, 0.0f64);
+    for (&e, &a) in expected.iter().zip(&actual[offset..]) {
+        signal += e as f64 * e as f64;
+        noise += (a as f64 - e as f64).powi(2);
+    }
+    let snr_db = if noise == 0.0 {
+        f32::INFINITY
+    } else {
+        (10.0 * (signal / noise).log10()) as f32
+    };
+
+    AudioComparison {
+        offset,
+        correlation: correlation as f32,
+        snr_db,
+    }
+}
+
+/// Peak signal-to-noise ratio of `actual` against the golden frame
+/// `expected`, in dB
+///
+/// Returns `None` when the frames differ in size; identical frames give
+/// infinity.
+pub fn video_psnr(expected: &VideoFrame, actual: &VideoFrame) -> Option<f64> {
+    if (expected.width, expected.height) != (actual.width, actual.height)
+        || expected.data.len() != actual.data.len()
+        || expected.data.is_empty()
+    {
+        return None;
+    }
+    let squared_error: f64 = expected
+        .data
+        .iter()
+        .zip(&actual.data)
+        .map(|(&e, &a)| (e as f64 - a as f64).powi(2))
+        .sum();
+    let mse = squared_error / expected.data.len() as f64;
+    Some(if mse == 0.0 {
+        f64::INFINITY
+    } else {
+        10.0 * (255.0 * 255.0 / mse).log10()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_virtual_microphone_matches_golden_tone() {
+        let tone = VirtualMicrophone::sine("test-tone", 440.0);
+        register_microphone(tone.clone());
+        assert!(microphone("virtual:test-tone").is_some());
+
+        let golden = tone.read(0, 4800).samples;
+        // Received audio arrives 120 samples late and slightly noisy
+        let mut received = vec![0.0; 120];
+        received.extend(
+            tone.read(0, 4800)
+                .samples
+                .iter()
+                .enumerate()
+                .map(|(i, s)| s + if i % 2 == 0 { 0.001 } else { -0.001 }),
+        );
+
+        let comparison = compare_audio(&golden, &received, 480);
+        assert_eq!(comparison.offset, 120);
+        assert!(comparison.correlation > 0.99);
+        assert!(comparison.snr_db > 40.0);
+        assert!(unregister(tone.id()));
+    }
+
+    #[test]
+    fn test_virtual_camera_backend() {
+        let resolution = VideoResolution::new(64, 48);
+        register_camera(VirtualCamera::test_pattern("test-bars", resolution));
+
+        let mut backend = VirtualCameraBackend::new();
+        assert!(backend
+            .enumerate_devices()
+            .unwrap()
+            .iter()
+            .any(|device| device.id == "virtual:test-bars"));
+        let config = VideoCaptureConfig {
+            resolution,
+            ..VideoCaptureConfig::default()
+        };
+        backend.open_device("virtual:test-bars", &config).unwrap();
+        backend.start_capture().unwrap();
+
+        let (first, metadata) = backend.get_frame().unwrap().unwrap();
+        assert_eq!(first.data.len(), 64 * 48 * 3 / 2);
+        assert_eq!(metadata.sequence, 0);
+        let (second, _) = backend.get_frame().unwrap().unwrap();
+        // The bars scroll, so consecutive frames differ but stay close
+        let psnr = video_psnr(&first, &second).unwrap();
+        assert!(psnr.is_finite() && psnr > 10.0);
+        assert_eq!(video_psnr(&first, &first), Some(f64::INFINITY));
+
+        assert!(unregister("virtual:test-bars"));
+        assert!(backend.open_device("virtual:test-bars", &config).is_err());
+    }
+}
@@ -280,6 +280,130 @@ async fn test_audio_format_conversion_concepts() {
     assert_eq!(stereo_samples, 2000);
 }
 
+#[tokio::test]
+async fn test_remix_channels_layouts() {
+    use quicrtc_core::ChannelLayout;
+
+    // Mono duplicates onto both stereo channels
+    let stereo = remix_channels(&[0.5, -0.25], ChannelLayout::Mono, ChannelLayout::Stereo);
+    assert_eq!(stereo, vec![0.5, 0.5, -0.25, -0.25]);
+
+    // Stereo averages down to mono
+    let mono = remix_channels(&[0.4, 0.2], ChannelLayout::Stereo, ChannelLayout::Mono);
+    assert!((mono[0] - 0.3).abs() < 1e-6);
+
+    // Mono lands in the centre channel of 5.1
+    let surround = remix_channels(&[0.5], ChannelLayout::Mono, ChannelLayout::Surround5_1);
+    assert_eq!(surround, vec![0.0, 0.0, 0.5, 0.0, 0.0, 0.0]);
+
+    // 5.1 folds centre into both fronts and drops LFE
+    let folded = remix_channels(
+        &[0.0, 0.0, 0.5, 1.0, 0.0, 0.0],
+        ChannelLayout::Surround5_1,
+        ChannelLayout::Stereo,
+    );
+    assert_eq!(folded.len(), 2);
+    assert!((folded[0] - 0.5 * std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+    assert_eq!(folded[0], folded[1]);
+
+    // Frame count is preserved across layouts
+    let quad = remix_channels(&[0.1; 12], ChannelLayout::Surround5_1, ChannelLayout::Quad);
+    assert_eq!(quad.len(), 8);
+}
+
+#[derive(Debug)]
+struct FixedGain(f32);
+
+impl MixerGain for FixedGain {
+    fn gain(&self) -> f32 {
+        self.0
+    }
+}
+
+#[tokio::test]
+async fn test_audio_mixer_applies_source_gain() {
+    use quicrtc_core::ChannelLayout;
+    use std::sync::Arc;
+
+    let mut mixer = AudioMixer::new(48000, ChannelLayout::Stereo);
+    mixer.add_source("alice", Arc::new(FixedGain(0.5)));
+    mixer.add_source("bob", Arc::new(FixedGain(0.0)));
+    assert_eq!(mixer.source_count(), 2);
+    assert!(mixer.mix().is_none());
+
+    let frame = |value: f32| AudioFrame {
+        samples: vec![value; 4],
+        sample_rate: 48000,
+        channels: 2,
+        timestamp: 0,
+    };
+
+    mixer.push_frame("alice", frame(0.8)).unwrap();
+    mixer.push_frame("bob", frame(0.8)).unwrap();
+    let mixed = mixer.mix().unwrap();
+    assert_eq!(mixed.channels, 2);
+    assert!(mixed.samples.iter().all(|&s| (s - 0.4).abs() < 1e-6));
+
+    // Mono sources are remixed to the output layout
+    let mono = AudioFrame {
+        samples: vec![0.2; 2],
+        sample_rate: 48000,
+        channels: 1,
+        timestamp: 0,
+    };
+    mixer.push_frame("alice", mono).unwrap();
+    assert_eq!(mixer.mix().unwrap().samples.len(), 4);
+
+    // Unknown sources and mismatched rates are rejected
+    assert!(mixer.push_frame("carol", frame(0.1)).is_err());
+    let mut wrong_rate = frame(0.1);
+    wrong_rate.sample_rate = 44100;
+    assert!(mixer.push_frame("alice", wrong_rate).is_err());
+
+    assert!(mixer.remove_source("bob"));
+    assert!(!mixer.has_source("bob"));
+}
+
+#[tokio::test]
+async fn test_audio_mixer_ducks_while_local_user_speaks() {
+    use quicrtc_core::ChannelLayout;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let mut mixer = AudioMixer::new(48000, ChannelLayout::Mono);
+    mixer.add_source("alice", Arc::new(FixedGain(1.0)));
+    mixer.set_ducking(Some(AudioDucker::new(
+        20.0,
+        Duration::ZERO,
+        Duration::from_millis(10),
+    )));
+
+    let frame = |value: f32| AudioFrame {
+        samples: vec![value; 480],
+        sample_rate: 48000,
+        channels: 1,
+        timestamp: 0,
+    };
+    let mut mix = |mixer: &mut AudioMixer| {
+        mixer.push_frame("alice", frame(0.5)).unwrap();
+        mixer.mix().unwrap().samples
+    };
+
+    assert!(mix(&mut mixer).iter().all(|&s| (s - 0.5).abs() < 1e-6));
+
+    // Speech in local capture lowers the mix by 20 dB
+    assert!(!mixer.push_local_frame(&frame(0.001)));
+    assert!(mixer.push_local_frame(&frame(0.3)));
+    assert!(mix(&mut mixer).iter().all(|&s| (s - 0.05).abs() < 1e-4));
+
+    // Short pauses keep it ducked; once speech ends the gain ramps back up
+    assert!(mixer.push_local_frame(&frame(0.0)));
+    mixer.set_local_speaking(false);
+    let released = mix(&mut mixer);
+    assert!(released[0] < 0.1);
+    assert!((released[479] - 0.5).abs() < 1e-4);
+}
+
 #[tokio::test]
 async fn test_video_format_conversion_concepts() {
     // Test video format conversion concepts
@@ -293,6 +417,272 @@ async fn test_video_format_conversion_concepts() {
     assert!(rgb24_size > yuv420_size); // RGB should be larger than YUV420
 }
 
+#[tokio::test]
+async fn test_video_frame_rotation() {
+    use quicrtc_core::VideoRotation;
+
+    // 2x2 luma plane with 1x1 chroma planes
+    let frame = VideoFrame {
+        width: 2,
+        height: 2,
+        data: vec![1, 2, 3, 4, 100, 200],
+        timestamp: 0,
+        is_keyframe: true,
+        rotation: VideoRotation::Deg90,
+    };
+
+    let upright = frame.to_upright();
+    assert_eq!(upright.rotation, VideoRotation::Deg0);
+    assert_eq!(upright.data, vec![3, 1, 4, 2, 100, 200]);
+
+    assert_eq!(
+        frame.rotate(VideoRotation::Deg180).data,
+        vec![4, 3, 2, 1, 100, 200]
+    );
+    assert_eq!(
+        frame.rotate(VideoRotation::Deg270).data,
+        vec![2, 4, 1, 3, 100, 200]
+    );
+
+    // Non-square frames swap dimensions
+    let wide = VideoFrame {
+        width: 4,
+        height: 2,
+        data: vec![0; 4 * 2 + 2 * 2],
+        timestamp: 0,
+        is_keyframe: false,
+        rotation: VideoRotation::Deg0,
+    };
+    let rotated = wide.rotate(VideoRotation::Deg90);
+    assert_eq!((rotated.width, rotated.height), (2, 4));
+    assert_eq!(rotated.data.len(), wide.data.len());
+    // Packed RGB24 frames rotate whole pixels
+    let tall = VideoFrame {
+        width: 1,
+        height: 2,
+        data: vec![1, 2, 3, 4, 5, 6],
+        timestamp: 0,
+        is_keyframe: false,
+        rotation: VideoRotation::Deg90,
+    };
+    let upright = tall.to_upright();
+    assert_eq!((upright.width, upright.height), (2, 1));
+    assert_eq!(upright.data, vec![4, 5, 6, 1, 2, 3]);
+}
+
+// ============================================================================
+// PLAYBACK CONTROL TESTS
+// ============================================================================
+
+#[test]
+fn test_playback_controller_buffers_and_catches_up() {
+    use std::time::Duration;
+
+    // Interactive playback never buffers or speeds up
+    let mut interactive = PlaybackController::new(PlaybackConfig::interactive());
+    assert_eq!(interactive.playback_rate(Duration::ZERO), 1.0);
+    assert_eq!(interactive.playback_rate(Duration::from_secs(10)), 1.0);
+
+    let config = PlaybackConfig::broadcast().with_target_buffer(Duration::from_secs(1));
+    assert!(config.validate().is_ok());
+    let mut controller = PlaybackController::new(config);
+
+    // Playout waits for the target buffer
+    assert!(controller.is_buffering());
+    assert_eq!(controller.playback_rate(Duration::from_millis(500)), 0.0);
+    assert_eq!(controller.playback_rate(Duration::from_secs(1)), 1.0);
+    assert!(!controller.is_buffering());
+
+    // Running dry is a stall and buffering starts over
+    assert_eq!(controller.playback_rate(Duration::ZERO), 0.0);
+    assert!(controller.is_buffering());
+    assert_eq!(controller.stalls(), 1);
+
+    // Once the backlog builds up, playback catches up within the allowed range
+    let rate = controller.playback_rate(Duration::from_millis(1500));
+    assert!(
+        (PlaybackConfig::MIN_CATCH_UP_RATE..=PlaybackConfig::MAX_CATCH_UP_RATE).contains(&rate)
+    );
+    let rate = controller.playback_rate(Duration::from_secs(5));
+    assert_eq!(rate, PlaybackConfig::MAX_CATCH_UP_RATE);
+    let rate = controller.playback_rate(Duration::from_millis(1100));
+    assert!(rate > 1.0);
+    assert_eq!(controller.playback_rate(Duration::from_secs(1)), 1.0);
+
+    // Catch-up rates outside 1.05-1.25x are rejected
+    assert!(PlaybackConfig::broadcast()
+        .with_max_playback_rate(2.0)
+        .validate()
+        .is_err());
+    assert!(PlaybackConfig::broadcast()
+        .with_max_playback_rate(1.01)
+        .validate()
+        .is_err());
+}
+
+#[test]
+fn test_time_stretch_shortens_without_changing_level() {
+    let samples = vec![0.5f32; 2 * 1000];
+
+    let stretched = time_stretch(&samples, 2, 1.25);
+    assert_eq!(stretched.len(), 2 * 800);
+    assert!(stretched.iter().all(|s| (s - 0.5).abs() < 1e-6));
+
+    // Normal speed leaves the audio untouched
+    assert_eq!(time_stretch(&samples, 2, 1.0), samples);
+}
+
+#[test]
+fn test_audio_framer_converts_to_encoder_frames() {
+    // 20 ms stereo frames at 48 kHz from 30 ms mono chunks at 16 kHz
+    let mut framer = AudioFramer::new(48000, 2, std::time::Duration::from_millis(20));
+    let chunk = AudioFrame {
+        samples: vec![0.5; 480],
+        sample_rate: 16000,
+        channels: 1,
+        timestamp: 0,
+    };
+
+    let frames: Vec<AudioFrame> = (0..2).flat_map(|_| framer.push(&chunk)).collect();
+    assert_eq!(frames.len(), 3);
+    for (index, frame) in frames.iter().enumerate() {
+        assert_eq!(frame.samples.len(), 960 * 2);
+        assert_eq!(frame.sample_rate, 48000);
+        assert_eq!(frame.channels, 2);
+        assert_eq!(frame.timestamp, index as u64 * 20);
+        assert!(frame.samples.iter().all(|&s| (s - 0.5).abs() < 1e-6));
+    }
+    assert!(framer.flush().is_none());
+
+    // A partial frame is padded with silence
+    framer.push(&AudioFrame {
+        samples: vec![0.25; 100],
+        sample_rate: 48000,
+        channels: 2,
+        timestamp: 0,
+    });
+    let last = framer.flush().unwrap();
+    assert_eq!(last.samples.len(), 960 * 2);
+    assert_eq!(last.samples[99], 0.25);
+    assert_eq!(last.samples[100], 0.0);
+}
+
+#[tokio::test]
+async fn test_audio_source_from_wav() {
+    // 16-bit stereo PCM, 30 ms at 8 kHz
+    let samples: Vec<i16> = (0..240).flat_map(|_| [16384i16, -16384]).collect();
+    let data: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+    let mut wav = Vec::new();
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&2u16.to_le_bytes()); // channels
+    wav.extend_from_slice(&8000u32.to_le_bytes());
+    wav.extend_from_slice(&(8000u32 * 4).to_le_bytes());
+    wav.extend_from_slice(&4u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    wav.extend_from_slice(&data);
+
+    let mut source = AudioSource::from_wav(&wav).unwrap();
+    let first = source.next_frame().await.unwrap();
+    assert_eq!((first.sample_rate, first.channels), (8000, 2));
+    assert_eq!(first.samples.len(), 160 * 2);
+    assert_eq!(&first.samples[..2], &[0.5, -0.5]);
+    let second = source.next_frame().await.unwrap();
+    assert_eq!(second.samples.len(), 80 * 2);
+    assert_eq!(second.timestamp, 20);
+    assert!(source.next_frame().await.is_none());
+
+    assert!(AudioSource::from_wav(b"not a wav file").is_err());
+}
+
+#[tokio::test]
+async fn test_audio_source_channel_ends_when_senders_drop() {
+    let (sender, mut source) = AudioSource::channel(4);
+    let frame = AudioFrame {
+        samples: vec![0.0; 960],
+        sample_rate: 48000,
+        channels: 1,
+        timestamp: 0,
+    };
+    sender.send(frame).await.unwrap();
+    drop(sender);
+
+    assert!(source.next_frame().await.is_some());
+    assert!(source.next_frame().await.is_none());
+}
+
+/// Solid I420 frame with the given luma
+fn solid_i420(width: u32, height: u32, luma: u8) -> VideoFrame {
+    let pixels = (width * height) as usize;
+    let mut data = vec![128u8; pixels + pixels / 2];
+    data[..pixels].fill(luma);
+    VideoFrame {
+        width,
+        height,
+        data,
+        timestamp: 0,
+        is_keyframe: false,
+        rotation: quicrtc_core::VideoRotation::Deg0,
+    }
+}
+
+#[test]
+fn test_compositor_grid_layout() {
+    let mut compositor = VideoCompositor::new(640, 480);
+    for (id, luma) in [("alice", 60), ("bob", 120), ("carol", 180)] {
+        compositor.update_source(id, &solid_i420(320, 240, luma));
+    }
+
+    // Three sources make a 2x2 grid with the last cell empty
+    let tiles = compositor.tiles();
+    assert_eq!(tiles.len(), 3);
+    assert_eq!((tiles[1].x, tiles[1].y), (320, 0));
+    assert_eq!((tiles[2].x, tiles[2].y), (0, 240));
+    assert!(tiles.iter().all(|t| (t.width, t.height) == (320, 240)));
+
+    let frame = compositor.compose(42);
+    assert_eq!((frame.width, frame.height, frame.timestamp), (640, 480, 42));
+    assert_eq!(frame.data.len(), 640 * 480 * 3 / 2);
+    let luma = |x: usize, y: usize| frame.data[y * 640 + x];
+    assert_eq!(luma(10, 10), 60);
+    assert_eq!(luma(400, 10), 120);
+    assert_eq!(luma(10, 300), 180);
+    assert_eq!(luma(400, 300), 16);
+
+    assert!(compositor.remove_source("bob"));
+    assert_eq!(compositor.tiles().len(), 2);
+}
+
+#[test]
+fn test_compositor_active_speaker_layout() {
+    let mut compositor =
+        VideoCompositor::new(640, 480).with_layout(CompositionLayout::ActiveSpeaker);
+    compositor.update_source("alice", &solid_i420(64, 48, 60));
+    compositor.update_source("bob", &solid_i420(64, 48, 120));
+    compositor.update_source("carol", &solid_i420(64, 48, 180));
+    compositor.set_active_speaker(Some("bob"));
+
+    let tiles = compositor.tiles();
+    assert_eq!(tiles[0].source_id, "bob");
+    assert_eq!((tiles[0].width, tiles[0].height), (480, 360));
+    // The others share the bottom strip, letterboxed to keep 4:3
+    assert_eq!(tiles[1].source_id, "alice");
+    assert_eq!(tiles[2].source_id, "carol");
+    assert!(tiles[1..].iter().all(|t| t.y >= 360 && t.height <= 120));
+
+    let frame = compositor.compose(0);
+    assert_eq!(frame.data[200 * 640 + 320], 120);
+
+    // An unknown speaker falls back to the first source
+    compositor.set_active_speaker(Some("dave"));
+    assert_eq!(compositor.tiles()[0].source_id, "alice");
+}
+
 // ============================================================================
 // ERROR HANDLING TESTS
 // ============================================================================
@@ -0,0 +1,333 @@
+//! Server configuration file
+//!
+//! Every section is optional; fields left out keep production defaults and
+//! durations are written as strings such as `"10s"`:
+//!
+//! ```toml
+//! shutdown_grace = "15s"
+//!
+//! [relay]
+//! bind = "0.0.0.0:4433"
+//! cert_path = "/etc/quicrtc/cert.pem"
+//! key_path = "/etc/quicrtc/key.pem"
+//!
+//! [auth.keys]
+//! my-api-key = "my-api-secret"
+//!
+//! [admin]
+//! bind = "127.0.0.1:8081"
+//! api_token = "change-me"
+//! ```
+
+use quicrtc_core::transport::{
+    CertificateConfig, QuicTransportConfig, ResourceLimits, ServerAuthConfig, ServerConfigUpdate,
+};
+use quicrtc_core::QuicRtcError;
+use quicrtc_signaling::{AdminConfig, TokenVerifier, WebhookConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::Path;
+use std::time::Duration;
+
+/// Complete server configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    /// Console log filter, overridden by `RUST_LOG`
+    pub log_filter: String,
+    /// How long in-flight relay sessions get to finish on shutdown
+    #[serde(with = "humantime_serde")]
+    pub shutdown_grace: Duration,
+    /// WebSocket signaling endpoint
+    pub signaling: SignalingSection,
+    /// QUIC media relay
+    pub relay: RelaySection,
+    /// Access token verification
+    pub auth: AuthSection,
+    /// Administrative REST API; disabled when absent
+    pub admin: Option<AdminSection>,
+    /// Prometheus metrics and health endpoint
+    pub metrics: MetricsSection,
+    /// Room and participant webhooks; disabled when absent
+    pub webhooks: Option<WebhookSection>,
+}
+
+/// `[signaling]` section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SignalingSection {
+    /// Address participants connect their WebSocket to
+    pub bind: SocketAddr,
+}
+
+/// `[relay]` section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RelaySection {
+    /// UDP address the relay accepts QUIC connections on
+    pub bind: SocketAddr,
+    /// PEM certificate chain; a self-signed certificate is generated when unset
+    pub cert_path: Option<String>,
+    /// PEM private key matching `cert_path`
+    pub key_path: Option<String>,
+    /// Name put in the self-signed certificate
+    pub server_name: String,
+    /// PEM file of CA certificates; when set, clients must present a
+    /// certificate signed by one of them
+    pub client_ca_path: Option<String>,
+    /// Concurrent connections accepted at most
+    pub max_connections: u32,
+}
+
+/// `[auth]` section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthSection {
+    /// API key to secret; participants must present a token signed with one
+    /// of them. With no keys, anyone can join any room.
+    pub keys: BTreeMap<String, String>,
+    /// Clock skew tolerated on token validity windows
+    #[serde(with = "humantime_serde")]
+    pub leeway: Duration,
+}
+
+/// `[admin]` section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AdminSection {
+    /// Address the admin API binds to
+    pub bind: SocketAddr,
+    /// Bearer token required on every request
+    pub api_token: String,
+}
+
+/// `[metrics]` section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsSection {
+    /// Serve `/metrics` and `/healthz`
+    pub enabled: bool,
+    /// Address the metrics endpoint binds to
+    pub bind: SocketAddr,
+}
+
+/// `[webhooks]` section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookSection {
+    /// URLs every event is delivered to
+    pub endpoints: Vec<String>,
+    /// Secret used to sign request bodies
+    pub signing_secret: String,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            log_filter: "info".to_string(),
+            shutdown_grace: Duration::from_secs(10),
+            signaling: SignalingSection::default(),
+            relay: RelaySection::default(),
+            auth: AuthSection::default(),
+            admin: None,
+            metrics: MetricsSection::default(),
+            webhooks: None,
+        }
+    }
+}
+
+impl Default for SignalingSection {
+    fn default() -> Self {
+        Self {
+            bind: SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 8080),
+        }
+    }
+}
+
+impl Default for RelaySection {
+    fn default() -> Self {
+        Self {
+            bind: SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 4433),
+            cert_path: None,
+            key_path: None,
+            server_name: "localhost".to_string(),
+            client_ca_path: None,
+            max_connections: 1000,
+        }
+    }
+}
+
+impl Default for AuthSection {
+    fn default() -> Self {
+        Self {
+            keys: BTreeMap::new(),
+            leeway: Duration::from_secs(30),
+        }
+    }
+}
+
+impl Default for MetricsSection {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            bind: SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 9090),
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Load and validate a TOML configuration file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, QuicRtcError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|e| QuicRtcError::Initialization {
+            reason: format!("Failed to read {}: {}", path.display(), e),
+        })?;
+        Self::from_toml(&text)
+    }
+
+    /// Parse and validate a TOML configuration
+    pub fn from_toml(text: &str) -> Result<Self, QuicRtcError> {
+        let config: Self = serde_path_to_error::deserialize(toml::Deserializer::new(text))
+            .map_err(|error| invalid(&error.path().to_string(), error.inner()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check that every field holds a usable value
+    pub fn validate(&self) -> Result<(), QuicRtcError> {
+        let relay = &self.relay;
+        if relay.cert_path.is_some() != relay.key_path.is_some() {
+            return Err(invalid(
+                "relay",
+                "cert_path and key_path must be set together",
+            ));
+        }
+        if relay.max_connections == 0 {
+            return Err(invalid("relay.max_connections", "must be at least 1"));
+        }
+        let mut binds = vec![
+            ("signaling.bind", self.signaling.bind),
+            ("metrics.bind", self.metrics.bind),
+        ];
+        if let Some(admin) = &self.admin {
+            if admin.api_token.is_empty() {
+                return Err(invalid("admin.api_token", "must not be empty"));
+            }
+            binds.push(("admin.bind", admin.bind));
+        }
+        // The relay listens on UDP, so only the TCP listeners can clash
+        if !self.metrics.enabled {
+            binds.retain(|(field, _)| *field != "metrics.bind");
+        }
+        for (i, (field, bind)) in binds.iter().enumerate() {
+            if binds[..i].iter().any(|(_, other)| other == bind) {
+                return Err(invalid(field, "is already used by another listener"));
+            }
+        }
+        if let Some((key, _)) = self.auth.keys.iter().find(|(_, secret)| secret.is_empty()) {
+            return Err(invalid(
+                &format!("auth.keys.{}", key),
+                "secret must not be empty",
+            ));
+        }
+        if let Some(webhooks) = &self.webhooks {
+            if webhooks.endpoints.is_empty() {
+                return Err(invalid("webhooks.endpoints", "must list at least one URL"));
+            }
+            if webhooks.signing_secret.is_empty() {
+                return Err(invalid("webhooks.signing_secret", "must not be empty"));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl AuthSection {
+    /// Token verifier for the configured keys, if any
+    pub fn verifier(&self) -> Option<TokenVerifier> {
+        if self.keys.is_empty() {
+            return None;
+        }
+        let verifier = self
+            .keys
+            .iter()
+            .fold(TokenVerifier::new(), |verifier, (key, secret)| {
+                verifier.with_key(key, secret)
+            });
+        Some(verifier.with_leeway(self.leeway))
+    }
+}
+
+impl RelaySection {
+    /// QUIC transport settings for the relay endpoint
+    pub fn transport_config(&self) -> QuicTransportConfig {
+        let certificate_config = match (&self.cert_path, &self.key_path) {
+            (Some(cert_path), Some(key_path)) => CertificateConfig::FromFile {
+                cert_path: cert_path.clone(),
+                key_path: key_path.clone(),
+            },
+            _ => CertificateConfig::SelfSigned {
+                common_name: self.server_name.clone(),
+                subject_alt_names: vec![self.server_name.clone()],
+            },
+        };
+        QuicTransportConfig {
+            certificate_config,
+            ..QuicTransportConfig::server()
+        }
+    }
+
+    /// Resource limits for the relay endpoint
+    pub fn resource_limits(&self) -> ResourceLimits {
+        ResourceLimits {
+            max_connections: Some(self.max_connections),
+            ..ResourceLimits::server()
+        }
+    }
+
+    /// Client authentication for the relay endpoint
+    pub fn auth(&self) -> ServerAuthConfig {
+        ServerAuthConfig {
+            client_ca_path: self.client_ca_path.clone(),
+            ..ServerAuthConfig::default()
+        }
+    }
+
+    /// Everything a running relay can pick up without restarting
+    pub fn update(&self) -> ServerConfigUpdate {
+        ServerConfigUpdate {
+            transport_config: Some(self.transport_config()),
+            resource_limits: Some(self.resource_limits()),
+            auth: Some(self.auth()),
+            ..ServerConfigUpdate::default()
+        }
+    }
+}
+
+impl AdminSection {
+    /// Admin API settings
+    pub fn admin_config(&self) -> AdminConfig {
+        AdminConfig {
+            bind_addr: self.bind,
+            api_token: self.api_token.clone(),
+        }
+    }
+}
+
+impl WebhookSection {
+    /// Webhook delivery settings with default retry behaviour
+    pub fn webhook_config(&self) -> WebhookConfig {
+        WebhookConfig {
+            endpoints: self.endpoints.clone(),
+            signing_secret: self.signing_secret.clone(),
+            ..WebhookConfig::default()
+        }
+    }
+}
+
+fn invalid(field: &str, reason: impl std::fmt::Display) -> QuicRtcError {
+    QuicRtcError::InvalidData {
+        reason: format!("Invalid value for `{}`: {}", field, reason),
+    }
+}
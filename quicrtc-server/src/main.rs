@@ -0,0 +1,206 @@
+//! Signaling and media relay server
+//!
+//! ```text
+//! quicrtc-server [--config <file.toml>] [--check]
+//! ```
+//!
+//! Runs the WebSocket signaling server, the QUIC relay forwarding media
+//! between participants, and optionally the admin API, behind one config
+//! file (see [`config`]). `--check` validates the config and exits.
+//!
+//! SIGINT or SIGTERM starts a graceful shutdown: `/healthz` reports
+//! draining, the signaling server stops and relay sessions get
+//! `shutdown_grace` to finish. On Unix, SIGHUP reloads the relay's
+//! certificate, limits and client authentication from the config file.
+
+mod config;
+mod metrics;
+mod relay;
+
+use config::ServerConfig;
+use quicrtc_core::transport::{QuicServer, ServerAuthConfig};
+use quicrtc_core::QuicRtcError;
+use quicrtc_signaling::{AdminServer, SignalingServer};
+use relay::Relay;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tracing::{info, warn};
+use tracing_subscriber::EnvFilter;
+
+const USAGE: &str = "usage: quicrtc-server [--config <file.toml>] [--check]";
+
+fn main() -> ExitCode {
+    let mut config_path = None;
+    let mut check = false;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--config" | "-c" => match args.next() {
+                Some(path) => config_path = Some(PathBuf::from(path)),
+                None => {
+                    eprintln!("{}", USAGE);
+                    return ExitCode::FAILURE;
+                }
+            },
+            "--check" => check = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            _ => {
+                eprintln!("{}", USAGE);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let config = match &config_path {
+        Some(path) => ServerConfig::load(path),
+        None => Ok(ServerConfig::default()),
+    };
+    let config = match config {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("quicrtc-server: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if check {
+        println!("configuration ok");
+        return ExitCode::SUCCESS;
+    }
+
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&config.log_filter));
+    tracing_subscriber::fmt().with_env_filter(filter).init();
+
+    let runtime = match tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("quicrtc-server: failed to start runtime: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let grace = config.shutdown_grace;
+    let result = runtime.block_on(run(config, config_path));
+    runtime.shutdown_timeout(grace);
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            tracing::error!("quicrtc-server failed: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(config: ServerConfig, config_path: Option<PathBuf>) -> Result<(), QuicRtcError> {
+    let mut signaling = SignalingServer::new(config.signaling.bind);
+    let verifier = config.auth.verifier();
+    match verifier.clone() {
+        Some(verifier) => signaling = signaling.with_token_verifier(verifier),
+        None => warn!("No API keys configured; anyone can join or create any room"),
+    }
+    if let Some(webhooks) = &config.webhooks {
+        signaling = signaling.with_webhooks(webhooks.webhook_config());
+    }
+
+    if config.relay.cert_path.is_none() {
+        warn!("No relay certificate configured; using a self-signed certificate");
+    }
+    let mut relay = Relay::new();
+    if let Some(verifier) = verifier {
+        relay = relay.with_token_verifier(verifier);
+    }
+    let relay = Arc::new(relay);
+    let quic = QuicServer::bind(
+        config.relay.bind,
+        config.relay.transport_config(),
+        config.relay.resource_limits(),
+    )
+    .await?;
+    if config.relay.auth() != ServerAuthConfig::default() {
+        quic.reload(config.relay.update()).await?;
+    }
+    let relay_addr = quic.local_addr();
+    #[cfg_attr(not(unix), allow(unused_mut))]
+    let mut quic = quic.run({
+        let relay = relay.clone();
+        move |connection| relay.clone().handle(connection)
+    });
+    #[cfg(unix)]
+    if let Some(path) = config_path {
+        quic.reload_on_sighup(move || Ok(ServerConfig::load(&path)?.relay.update()))?;
+    }
+    #[cfg(not(unix))]
+    let _ = config_path;
+
+    let draining = Arc::new(AtomicBool::new(false));
+    let mut background = Vec::new();
+    if let Some(admin) = &config.admin {
+        let admin = AdminServer::new(signaling.clone(), admin.admin_config());
+        background.push(tokio::spawn(async move { admin.start().await }));
+    }
+    if config.metrics.enabled {
+        let state = metrics::MetricsState {
+            signaling: signaling.clone(),
+            relay: relay.clone(),
+            draining: draining.clone(),
+        };
+        background.push(tokio::spawn(metrics::serve(config.metrics.bind, state)));
+    }
+    let mut signaling_task = tokio::spawn({
+        let signaling = signaling.clone();
+        async move { signaling.start().await }
+    });
+
+    info!(
+        "quicrtc-server ready: signaling on {}, relay on {}",
+        config.signaling.bind, relay_addr
+    );
+
+    let result = tokio::select! {
+        _ = shutdown_signal() => Ok(()),
+        result = &mut signaling_task => match result {
+            Ok(result) => result,
+            Err(e) => Err(QuicRtcError::Initialization {
+                reason: format!("Signaling server task failed: {}", e),
+            }),
+        },
+    };
+
+    info!("Shutting down");
+    draining.store(true, Ordering::Relaxed);
+    signaling_task.abort();
+    signaling.stop().await?;
+    quic.shutdown(config.shutdown_grace).await?;
+    for task in background {
+        task.abort();
+    }
+    info!("Shutdown complete");
+    result
+}
+
+/// Resolves on SIGINT, or SIGTERM on Unix
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+                return;
+            }
+            Err(e) => warn!("Failed to listen for SIGTERM: {}", e),
+        }
+    }
+    let _ = tokio::signal::ctrl_c().await;
+}
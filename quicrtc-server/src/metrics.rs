@@ -0,0 +1,236 @@
+//! Prometheus metrics and health endpoint
+//!
+//! | Path       | Response                                              |
+//! |------------|-------------------------------------------------------|
+//! | `/metrics` | Signaling, relay and process counters in Prometheus text format |
+//! | `/healthz` | `200 ok`, or `503 draining` once shutdown has started |
+
+use crate::relay::Relay;
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use quicrtc_core::QuicRtcError;
+use quicrtc_signaling::SignalingServer;
+use std::fmt::{Display, Write as _};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+/// State shared with the metrics handlers
+#[derive(Debug, Clone)]
+pub struct MetricsState {
+    /// Signaling server to report on
+    pub signaling: SignalingServer,
+    /// Relay to report on
+    pub relay: Arc<Relay>,
+    /// Set once the server starts shutting down
+    pub draining: Arc<AtomicBool>,
+}
+
+/// Serve the metrics endpoint on `bind` until the task is dropped
+pub async fn serve(bind: SocketAddr, state: MetricsState) -> Result<(), QuicRtcError> {
+    let listener = TcpListener::bind(bind)
+        .await
+        .map_err(|e| QuicRtcError::ServerStartFailed {
+            address: bind,
+            source: e.into(),
+        })?;
+    tracing::info!("Metrics listening on {}", bind);
+
+    let router = Router::new()
+        .route("/metrics", get(metrics))
+        .route("/healthz", get(health))
+        .with_state(state);
+    axum::serve(listener, router)
+        .await
+        .map_err(|e| QuicRtcError::ServerStartFailed {
+            address: bind,
+            source: e.into(),
+        })
+}
+
+async fn health(State(state): State<MetricsState>) -> impl IntoResponse {
+    if state.draining.load(Ordering::Relaxed) {
+        (StatusCode::SERVICE_UNAVAILABLE, "draining")
+    } else {
+        (StatusCode::OK, "ok")
+    }
+}
+
+async fn metrics(State(state): State<MetricsState>) -> impl IntoResponse {
+    let signaling = state.signaling.metrics().snapshot();
+    let relay = state.relay.stats();
+    let rooms = state.signaling.get_rooms().await.len();
+    let participants = state.signaling.total_participants().await;
+
+    let mut body = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, value: &dyn Display| {
+        let _ = writeln!(body, "# HELP quicrtc_{} {}", name, help);
+        let _ = writeln!(body, "# TYPE quicrtc_{} {}", name, kind);
+        let _ = writeln!(body, "quicrtc_{} {}", name, value);
+    };
+
+    metric(
+        "rooms",
+        "gauge",
+        "Rooms open on the signaling server",
+        &rooms,
+    );
+    metric(
+        "participants",
+        "gauge",
+        "Participants in all rooms",
+        &participants,
+    );
+    metric(
+        "signaling_connections_accepted_total",
+        "counter",
+        "WebSocket connections accepted",
+        &signaling.connections_accepted,
+    );
+    metric(
+        "signaling_messages_received_total",
+        "counter",
+        "Signaling messages read",
+        &signaling.messages_received,
+    );
+    metric(
+        "signaling_messages_throttled_total",
+        "counter",
+        "Signaling messages delayed by rate limits",
+        &signaling.messages_throttled,
+    );
+    metric(
+        "signaling_messages_rejected_total",
+        "counter",
+        "Signaling messages rejected as oversized or malformed",
+        &(signaling.messages_oversized + signaling.messages_invalid),
+    );
+    metric(
+        "signaling_connections_rate_limited_total",
+        "counter",
+        "WebSocket connections closed for flooding",
+        &signaling.connections_rate_limited,
+    );
+    metric(
+        "signaling_connections_too_slow_total",
+        "counter",
+        "WebSocket connections closed for not reading responses",
+        &signaling.connections_too_slow,
+    );
+    metric(
+        "signaling_participants_timed_out_total",
+        "counter",
+        "Participants removed for missing heartbeats",
+        &signaling.participants_timed_out,
+    );
+    metric(
+        "relay_sessions",
+        "gauge",
+        "MoQ sessions connected to the relay",
+        &relay.active_sessions,
+    );
+    metric(
+        "relay_sessions_accepted_total",
+        "counter",
+        "MoQ sessions accepted by the relay",
+        &relay.sessions_accepted,
+    );
+    metric(
+        "relay_sessions_refused_total",
+        "counter",
+        "MoQ sessions refused at SETUP",
+        &relay.sessions_refused,
+    );
+    metric(
+        "relay_objects_received_total",
+        "counter",
+        "Objects received from publishers",
+        &relay.objects_received,
+    );
+    metric(
+        "relay_objects_unroutable_total",
+        "counter",
+        "Objects dropped for not belonging to a track their sender announced",
+        &relay.objects_unroutable,
+    );
+    metric(
+        "relay_objects_forwarded_total",
+        "counter",
+        "Object copies delivered to subscribers",
+        &relay.objects_forwarded,
+    );
+    metric(
+        "relay_bytes_forwarded_total",
+        "counter",
+        "Payload bytes delivered to subscribers",
+        &relay.bytes_forwarded,
+    );
+    metric(
+        "relay_forward_failures_total",
+        "counter",
+        "Object copies that could not be delivered",
+        &relay.forward_failures,
+    );
+
+    if let Some(process) = process_stats() {
+        metric(
+            "process_cpu_seconds_total",
+            "counter",
+            "User and system CPU time spent by the server",
+            &process.cpu_seconds,
+        );
+        metric(
+            "process_resident_memory_bytes",
+            "gauge",
+            "Resident memory size",
+            &process.resident_bytes,
+        );
+        metric(
+            "process_threads",
+            "gauge",
+            "Operating system threads",
+            &process.threads,
+        );
+    }
+
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body)
+}
+
+/// Resource usage of the server process
+struct ProcessStats {
+    cpu_seconds: f64,
+    resident_bytes: u64,
+    threads: u64,
+}
+
+#[cfg(target_os = "linux")]
+fn process_stats() -> Option<ProcessStats> {
+    // utime and stime are fields 14 and 15, counted after the parenthesised
+    // command name, in USER_HZ ticks which Linux fixes at 100 per second
+    let stat = std::fs::read_to_string("/proc/self/stat").ok()?;
+    let mut fields = stat.get(stat.rfind(')')? + 2..)?.split_whitespace();
+    let utime: u64 = fields.nth(11)?.parse().ok()?;
+    let stime: u64 = fields.next()?.parse().ok()?;
+
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let field = |name: &str| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .and_then(|value| value.split_whitespace().next()?.parse::<u64>().ok())
+    };
+    Some(ProcessStats {
+        cpu_seconds: (utime + stime) as f64 / 100.0,
+        resident_bytes: field("VmRSS:")? * 1024,
+        threads: field("Threads:")?,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_stats() -> Option<ProcessStats> {
+    None
+}
@@ -0,0 +1,684 @@
+//! Selective forwarding relay
+//!
+//! Each QUIC connection accepted by the relay carries one MoQ session the
+//! client starts. Tracks a session announces are offered to the other
+//! sessions of the same room, and each object is forwarded only to the
+//! sessions subscribed to its track, so participants upload their media once
+//! however large the room is.
+//!
+//! With a [`TokenVerifier`], clients must send an access token in SETUP. The
+//! token's room bounds the tracks the session may announce and subscribe to,
+//! and its grants become the session's [`quicrtc_core::MoqPermissions`].
+
+use futures::future::join_all;
+use parking_lot::RwLock;
+use quicrtc_core::{
+    MoqObject, MoqOverQuicTransport, MoqSubscriptionState, MoqTrack, MoqTransportDriver,
+    MoqTransportEvent, QuicRtcError, QuinnDriver, TrackAccess, TrackAuthorizer, TrackNamespace,
+    TransportConnection,
+};
+use quicrtc_signaling::TokenVerifier;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// Forwards MoQ objects between the sessions connected to it
+#[derive(Debug, Default)]
+pub struct Relay {
+    sessions: RwLock<HashMap<Uuid, RelaySession>>,
+    verifier: Option<TokenVerifier>,
+    stats: RelayCounters,
+}
+
+/// A connected session and what the relay knows about it
+#[derive(Debug)]
+struct RelaySession {
+    transport: Arc<MoqOverQuicTransport>,
+    /// Room the session's token admits; `None` for any room
+    room: Option<String>,
+    /// Tracks the session announced
+    published: HashMap<TrackNamespace, MoqTrack>,
+}
+
+impl RelaySession {
+    /// Whether the session may see the tracks of `track_namespace`'s room
+    fn shares_room(&self, track_namespace: &TrackNamespace) -> bool {
+        track_namespace
+            .room_id()
+            .is_some_and(|room| self.room.as_deref().is_none_or(|own| own == room))
+    }
+
+    /// Whether the session subscribed to `track_namespace` and still wants it
+    fn subscribed(&self, track_namespace: &TrackNamespace) -> bool {
+        self.transport
+            .subscription(track_namespace)
+            .is_some_and(|subscription| subscription.state == MoqSubscriptionState::Active)
+    }
+}
+
+/// Keeps a session to room tracks, and to the room of its token
+#[derive(Debug)]
+struct RoomAuthorizer {
+    room: Option<String>,
+}
+
+impl TrackAuthorizer for RoomAuthorizer {
+    fn authorize(
+        &self,
+        _access: TrackAccess,
+        track_namespace: &TrackNamespace,
+        _token: Option<&str>,
+    ) -> Result<(), QuicRtcError> {
+        let room = track_namespace
+            .room_id()
+            .ok_or_else(|| QuicRtcError::Unauthorized {
+                reason: format!("{} is not a room track", track_namespace),
+            })?;
+        if self.room.as_deref().is_some_and(|own| own != room) {
+            return Err(QuicRtcError::Unauthorized {
+                reason: format!("Token is not valid for room {}", room),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct RelayCounters {
+    sessions_accepted: AtomicU64,
+    sessions_refused: AtomicU64,
+    objects_received: AtomicU64,
+    objects_unroutable: AtomicU64,
+    objects_forwarded: AtomicU64,
+    bytes_forwarded: AtomicU64,
+    forward_failures: AtomicU64,
+}
+
+/// Point-in-time relay counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RelayStats {
+    /// Sessions currently connected
+    pub active_sessions: u64,
+    /// Sessions accepted since startup
+    pub sessions_accepted: u64,
+    /// Sessions refused at SETUP, such as for a bad token
+    pub sessions_refused: u64,
+    /// Objects received from publishers
+    pub objects_received: u64,
+    /// Objects dropped for not belonging to a track their sender announced
+    pub objects_unroutable: u64,
+    /// Object copies delivered to subscribers
+    pub objects_forwarded: u64,
+    /// Payload bytes delivered to subscribers
+    pub bytes_forwarded: u64,
+    /// Object copies that could not be delivered
+    pub forward_failures: u64,
+}
+
+impl Relay {
+    /// Create a relay with no sessions that admits any client
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require clients to present a token `verifier` accepts in SETUP
+    pub fn with_token_verifier(mut self, verifier: TokenVerifier) -> Self {
+        self.verifier = Some(verifier);
+        self
+    }
+
+    /// Serve one connection until it closes
+    ///
+    /// Meant as the [`quicrtc_core::transport::QuicServer::run`] handler.
+    pub async fn handle(self: Arc<Self>, connection: Arc<RwLock<TransportConnection>>) {
+        let (quinn, connection_id, mode) = {
+            let connection = connection.read();
+            (
+                connection.quinn_connection(),
+                connection.connection_id(),
+                connection.current_transport_mode(),
+            )
+        };
+        let Some(quinn) = quinn else {
+            warn!(
+                "Relay only serves native QUIC connections, closing {}",
+                connection_id
+            );
+            return;
+        };
+        info!(
+            "Relay connection {} from {}",
+            connection_id,
+            quinn.remote_address()
+        );
+
+        let connection =
+            TransportConnection::from_quinn_connection(quinn, connection_id, mode, None);
+        let driver = Arc::new(QuinnDriver::new(connection));
+        self.serve(driver, connection_id.as_u64_pair().0).await
+    }
+
+    async fn serve(self: Arc<Self>, driver: Arc<dyn MoqTransportDriver>, session_id: u64) {
+        let connection_id = driver.connection_id();
+        let transport = Arc::new(MoqOverQuicTransport::with_driver(driver, session_id));
+        let Some(mut events) = transport.take_event_receiver() else {
+            return;
+        };
+
+        let mut claims = None;
+        let admitted = transport
+            .accept_session(|capabilities| {
+                let Some(verifier) = &self.verifier else {
+                    return Ok(());
+                };
+                let token = capabilities.auth_token.as_deref().ok_or_else(|| {
+                    QuicRtcError::Unauthorized {
+                        reason: "Relay requires an access token".to_string(),
+                    }
+                })?;
+                claims = Some(verifier.verify(token)?);
+                Ok(())
+            })
+            .await;
+        if let Err(e) = admitted {
+            warn!("Relay session {} refused: {}", connection_id, e);
+            self.stats.sessions_refused.fetch_add(1, Ordering::Relaxed);
+            let _ = transport.close().await;
+            return;
+        }
+
+        let room = claims
+            .as_ref()
+            .and_then(|claims| claims.grants.room.clone());
+        if let Some(claims) = &claims {
+            transport.set_permissions(claims.moq_permissions());
+        }
+        transport.set_track_authorizer(Arc::new(RoomAuthorizer { room: room.clone() }));
+        self.join(connection_id, transport.clone(), room);
+        self.stats.sessions_accepted.fetch_add(1, Ordering::Relaxed);
+        info!("Relay session {} opened", connection_id);
+
+        let mut requests = tokio::spawn({
+            let transport = transport.clone();
+            async move { transport.serve_peer_requests().await }
+        });
+        loop {
+            let event = tokio::select! {
+                event = events.recv() => event,
+                result = &mut requests => {
+                    if let Ok(Err(e)) = result {
+                        debug!("Relay session {} control stream ended: {}", connection_id, e);
+                    }
+                    break;
+                }
+            };
+            let Some(event) = event else {
+                break;
+            };
+            match event {
+                MoqTransportEvent::TrackAnnounced {
+                    track_namespace,
+                    track,
+                } => self.publish(connection_id, track_namespace, track),
+                MoqTransportEvent::ObjectReceived { .. } => {
+                    // Received objects are also queued on the transport;
+                    // draining the queue keeps it from growing
+                    while let Ok(object) = transport.receive_moq_object().await {
+                        self.forward(connection_id, object).await;
+                    }
+                }
+                MoqTransportEvent::TransportError { error } => {
+                    debug!("Relay session {} error: {}", connection_id, error);
+                }
+                MoqTransportEvent::SessionTerminated { reason, .. } => {
+                    debug!("Relay session {} ended: {}", connection_id, reason);
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        requests.abort();
+        self.leave(connection_id);
+        let _ = transport.close().await;
+        info!("Relay session {} closed", connection_id);
+    }
+
+    /// Add a session, offering it the tracks already published in its room
+    fn join(
+        &self,
+        connection_id: Uuid,
+        transport: Arc<MoqOverQuicTransport>,
+        room: Option<String>,
+    ) {
+        let session = RelaySession {
+            transport,
+            room,
+            published: HashMap::new(),
+        };
+        let mut sessions = self.sessions.write();
+        for track in sessions
+            .values()
+            .flat_map(|other| other.published.values())
+            .filter(|track| session.shares_room(&track.namespace))
+        {
+            session.transport.offer_track(track.clone());
+        }
+        sessions.insert(connection_id, session);
+    }
+
+    /// Remove a session, withdrawing its tracks from the rest of its room
+    fn leave(&self, connection_id: Uuid) {
+        let mut sessions = self.sessions.write();
+        let Some(session) = sessions.remove(&connection_id) else {
+            return;
+        };
+        for track_namespace in session.published.keys() {
+            for other in sessions.values() {
+                other.transport.withdraw_track(track_namespace);
+            }
+        }
+    }
+
+    /// Record a track `publisher` announced and offer it to the rest of its
+    /// room
+    fn publish(&self, publisher: Uuid, track_namespace: TrackNamespace, track: MoqTrack) {
+        let mut sessions = self.sessions.write();
+        for (_, session) in sessions
+            .iter()
+            .filter(|(id, session)| **id != publisher && session.shares_room(&track_namespace))
+        {
+            session.transport.offer_track(track.clone());
+        }
+        if let Some(session) = sessions.get_mut(&publisher) {
+            debug!("Relay session {} publishes {}", publisher, track_namespace);
+            session.published.insert(track_namespace, track);
+        }
+    }
+
+    /// Deliver `object` to the sessions of its room subscribed to its track
+    async fn forward(&self, publisher: Uuid, object: MoqObject) {
+        self.stats.objects_received.fetch_add(1, Ordering::Relaxed);
+        let track_namespace = &object.track_namespace;
+        let subscribers: Option<Vec<_>> = {
+            let sessions = self.sessions.read();
+            sessions
+                .get(&publisher)
+                .filter(|session| session.published.contains_key(track_namespace))
+                .map(|_| {
+                    sessions
+                        .iter()
+                        .filter(|(id, session)| {
+                            **id != publisher
+                                && session.shares_room(track_namespace)
+                                && session.subscribed(track_namespace)
+                        })
+                        .map(|(_, session)| session.transport.clone())
+                        .collect()
+                })
+        };
+        let Some(subscribers) = subscribers else {
+            self.stats
+                .objects_unroutable
+                .fetch_add(1, Ordering::Relaxed);
+            debug!(
+                "Dropping object on {}, which session {} did not announce",
+                track_namespace, publisher
+            );
+            return;
+        };
+
+        let size = object.payload.len() as u64;
+        let results = join_all(
+            subscribers
+                .iter()
+                .map(|transport| transport.send_moq_object(object.clone())),
+        )
+        .await;
+        for result in results {
+            match result {
+                Ok(()) => {
+                    self.stats.objects_forwarded.fetch_add(1, Ordering::Relaxed);
+                    self.stats
+                        .bytes_forwarded
+                        .fetch_add(size, Ordering::Relaxed);
+                }
+                Err(e) => {
+                    self.stats.forward_failures.fetch_add(1, Ordering::Relaxed);
+                    debug!("Failed to forward object: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Current counters
+    pub fn stats(&self) -> RelayStats {
+        RelayStats {
+            active_sessions: self.sessions.read().len() as u64,
+            sessions_accepted: self.stats.sessions_accepted.load(Ordering::Relaxed),
+            sessions_refused: self.stats.sessions_refused.load(Ordering::Relaxed),
+            objects_received: self.stats.objects_received.load(Ordering::Relaxed),
+            objects_unroutable: self.stats.objects_unroutable.load(Ordering::Relaxed),
+            objects_forwarded: self.stats.objects_forwarded.load(Ordering::Relaxed),
+            bytes_forwarded: self.stats.bytes_forwarded.load(Ordering::Relaxed),
+            forward_failures: self.stats.forward_failures.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use bytes::Bytes;
+    use quicrtc_core::transport::StreamType;
+    use quicrtc_core::{
+        DriverEvent, DriverStream, MoqCapabilities, MoqObjectMetadata, MoqObjectStatus,
+        MoqTrackType, StreamId, TrackNamespaceBuilder,
+    };
+    use quicrtc_signaling::AccessToken;
+    use std::sync::atomic::AtomicBool;
+    use std::time::{Duration, Instant};
+    use tokio::sync::mpsc;
+
+    /// One end of an in-memory stream
+    #[derive(Debug)]
+    struct MemoryStream {
+        id: StreamId,
+        stream_type: StreamType,
+        outgoing: Option<mpsc::UnboundedSender<Bytes>>,
+        incoming: mpsc::UnboundedReceiver<Bytes>,
+    }
+
+    #[async_trait]
+    impl DriverStream for MemoryStream {
+        fn id(&self) -> StreamId {
+            self.id
+        }
+
+        fn stream_type(&self) -> StreamType {
+            self.stream_type
+        }
+
+        async fn send(&mut self, data: &[u8]) -> Result<(), QuicRtcError> {
+            self.outgoing
+                .as_ref()
+                .and_then(|outgoing| outgoing.send(Bytes::copy_from_slice(data)).ok())
+                .ok_or_else(|| QuicRtcError::Transport {
+                    reason: "Stream closed".to_string(),
+                })
+        }
+
+        async fn recv(&mut self) -> Result<Option<Bytes>, QuicRtcError> {
+            Ok(self.incoming.recv().await)
+        }
+
+        async fn finish(&mut self) -> Result<(), QuicRtcError> {
+            self.outgoing = None;
+            Ok(())
+        }
+
+        async fn reset(&mut self, _error_code: u32) -> Result<(), QuicRtcError> {
+            self.outgoing = None;
+            Ok(())
+        }
+    }
+
+    /// One end of an in-memory connection
+    #[derive(Debug)]
+    struct MemoryDriver {
+        connection_id: Uuid,
+        next_stream_id: AtomicU64,
+        connected: AtomicBool,
+        own_events: mpsc::UnboundedSender<DriverEvent>,
+        peer_events: mpsc::UnboundedSender<DriverEvent>,
+        events: parking_lot::Mutex<Option<mpsc::UnboundedReceiver<DriverEvent>>>,
+    }
+
+    impl MemoryDriver {
+        /// Client and relay ends of a new connection
+        fn pair() -> (Arc<Self>, Arc<Self>) {
+            let (client_tx, client_rx) = mpsc::unbounded_channel();
+            let (relay_tx, relay_rx) = mpsc::unbounded_channel();
+            let end = |own_events: &mpsc::UnboundedSender<DriverEvent>,
+                       peer_events: &mpsc::UnboundedSender<DriverEvent>,
+                       events| {
+                Arc::new(Self {
+                    connection_id: Uuid::new_v4(),
+                    next_stream_id: AtomicU64::new(0),
+                    connected: AtomicBool::new(true),
+                    own_events: own_events.clone(),
+                    peer_events: peer_events.clone(),
+                    events: parking_lot::Mutex::new(Some(events)),
+                })
+            };
+            (
+                end(&client_tx, &relay_tx, client_rx),
+                end(&relay_tx, &client_tx, relay_rx),
+            )
+        }
+    }
+
+    #[async_trait]
+    impl MoqTransportDriver for MemoryDriver {
+        async fn open_stream(
+            &self,
+            stream_type: StreamType,
+        ) -> Result<Box<dyn DriverStream>, QuicRtcError> {
+            let id = self.next_stream_id.fetch_add(1, Ordering::Relaxed);
+            let (to_peer, from_us) = mpsc::unbounded_channel();
+            let (to_us, from_peer) = mpsc::unbounded_channel();
+            let remote = MemoryStream {
+                id: id | 1 << 63,
+                stream_type,
+                outgoing: Some(to_us),
+                incoming: from_us,
+            };
+            self.peer_events
+                .send(DriverEvent::StreamOpened(Box::new(remote)))
+                .map_err(|_| QuicRtcError::Transport {
+                    reason: "Connection closed".to_string(),
+                })?;
+            Ok(Box::new(MemoryStream {
+                id,
+                stream_type,
+                outgoing: Some(to_peer),
+                incoming: from_peer,
+            }))
+        }
+
+        async fn send_datagram(&self, data: Bytes) -> Result<(), QuicRtcError> {
+            let _ = self.peer_events.send(DriverEvent::DatagramReceived(data));
+            Ok(())
+        }
+
+        fn take_events(&self) -> Option<mpsc::UnboundedReceiver<DriverEvent>> {
+            self.events.lock().take()
+        }
+
+        fn connection_id(&self) -> Uuid {
+            self.connection_id
+        }
+
+        fn is_connected(&self) -> bool {
+            self.connected.load(Ordering::Relaxed)
+        }
+
+        async fn close(&self) -> Result<(), QuicRtcError> {
+            if self.connected.swap(false, Ordering::Relaxed) {
+                for events in [&self.own_events, &self.peer_events] {
+                    let _ = events.send(DriverEvent::Closed {
+                        reason: "closed".to_string(),
+                    });
+                }
+            }
+            Ok(())
+        }
+    }
+
+    const API_KEY: &str = "relay-key";
+    const API_SECRET: &str = "relay-secret";
+
+    fn token(identity: &str, room: &str) -> String {
+        AccessToken::new(API_KEY, API_SECRET)
+            .identity(identity)
+            .room(room)
+            .room_join(true)
+            .can_publish(true)
+            .can_subscribe(true)
+            .to_jwt()
+            .unwrap()
+    }
+
+    fn track(room: &str, participant: &str, name: &str) -> TrackNamespace {
+        TrackNamespaceBuilder::room(room)
+            .participant(participant)
+            .track(name)
+            .build()
+            .unwrap()
+    }
+
+    fn object(track_namespace: &TrackNamespace, object_id: u64) -> MoqObject {
+        let payload = vec![object_id as u8; 16];
+        MoqObject {
+            track_namespace: track_namespace.clone(),
+            track_name: track_namespace.track_name.clone(),
+            group_id: 0,
+            object_id,
+            publisher_priority: 1,
+            size: payload.len(),
+            payload,
+            object_status: MoqObjectStatus::Normal,
+            created_at: Instant::now(),
+            metadata: MoqObjectMetadata::new(),
+        }
+    }
+
+    /// Start a session with `relay` as a client presenting `token`
+    async fn connect(
+        relay: &Arc<Relay>,
+        session_id: u64,
+        token: Option<String>,
+    ) -> Result<MoqOverQuicTransport, QuicRtcError> {
+        let (client, server) = MemoryDriver::pair();
+        tokio::spawn(relay.clone().serve(server, session_id));
+        let transport = MoqOverQuicTransport::with_driver(client, session_id);
+        transport.set_capabilities(MoqCapabilities {
+            auth_token: token,
+            ..MoqCapabilities::default()
+        })?;
+        transport.establish_session().await?;
+        Ok(transport)
+    }
+
+    async fn eventually(condition: impl Fn() -> bool) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !condition() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("condition not met in time");
+    }
+
+    /// Objects received within a short wait
+    async fn received(transport: &MoqOverQuicTransport) -> Vec<MoqObject> {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let mut objects = Vec::new();
+        while let Ok(object) = transport.receive_moq_object().await {
+            objects.push(object);
+        }
+        objects
+    }
+
+    #[tokio::test]
+    async fn test_relay_forwards_to_subscribers_in_the_same_room() {
+        let verifier = TokenVerifier::new().with_key(API_KEY, API_SECRET);
+        let relay = Arc::new(Relay::new().with_token_verifier(verifier));
+
+        let alice = connect(&relay, 1, Some(token("alice", "standup")))
+            .await
+            .unwrap();
+        let bob = connect(&relay, 2, Some(token("bob", "standup")))
+            .await
+            .unwrap();
+        let carol = connect(&relay, 3, Some(token("carol", "retro")))
+            .await
+            .unwrap();
+        let dave = connect(&relay, 4, Some(token("dave", "standup")))
+            .await
+            .unwrap();
+
+        let camera = track("standup", "alice", "camera");
+        alice
+            .announce_track(MoqTrack::new(
+                camera.clone(),
+                "camera".to_string(),
+                MoqTrackType::Video,
+            ))
+            .await
+            .unwrap();
+        eventually(|| {
+            relay
+                .sessions
+                .read()
+                .values()
+                .any(|session| session.published.contains_key(&camera))
+        })
+        .await;
+
+        // Only members of the track's room may subscribe
+        bob.subscribe_to_track(camera.clone(), 1, None, None)
+            .await
+            .unwrap();
+        assert!(carol
+            .subscribe_to_track(camera.clone(), 1, None, None)
+            .await
+            .is_err());
+
+        for object_id in 0..3 {
+            alice
+                .send_moq_object(object(&camera, object_id))
+                .await
+                .unwrap();
+        }
+        // Objects on tracks the sender never announced go nowhere
+        let screen = track("standup", "alice", "screen");
+        alice.send_moq_object(object(&screen, 0)).await.unwrap();
+
+        let objects = received(&bob).await;
+        assert_eq!(objects.len(), 3);
+        assert!(objects
+            .iter()
+            .all(|object| object.track_namespace == camera));
+        assert!(received(&carol).await.is_empty());
+        // Dave is in the room but never subscribed
+        assert!(received(&dave).await.is_empty());
+
+        let stats = relay.stats();
+        assert_eq!(stats.active_sessions, 4);
+        assert_eq!(stats.objects_received, 4);
+        assert_eq!(stats.objects_unroutable, 1);
+        assert_eq!(stats.objects_forwarded, 3);
+    }
+
+    #[tokio::test]
+    async fn test_relay_refuses_sessions_without_a_valid_token() {
+        let verifier = TokenVerifier::new().with_key(API_KEY, API_SECRET);
+        let relay = Arc::new(Relay::new().with_token_verifier(verifier));
+
+        assert!(connect(&relay, 1, None).await.is_err());
+        let forged = AccessToken::new(API_KEY, "wrong-secret")
+            .identity("mallory")
+            .room("standup")
+            .to_jwt()
+            .unwrap();
+        assert!(connect(&relay, 2, Some(forged)).await.is_err());
+
+        let stats = relay.stats();
+        assert_eq!(stats.sessions_refused, 2);
+        assert_eq!(stats.active_sessions, 0);
+    }
+}
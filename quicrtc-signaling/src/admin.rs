@@ -0,0 +1,590 @@
+//! Administrative REST API for the signaling server
+//!
+//! Served on its own port, separate from the participant WebSocket
+//! endpoint. Every request must carry `Authorization: Bearer <token>`
+//! matching [`AdminConfig::api_token`].
+//!
+//! | Method | Path                                               | Response                   |
+//! |--------|----------------------------------------------------|----------------------------|
+//! | GET    | `/rooms`                                           | [`ListRoomsResponse`]      |
+//! | POST   | `/rooms`                                           | [`RoomSummary`]            |
+//! | DELETE | `/rooms/:room_id`                                  | [`CloseRoomResponse`]      |
+//! | GET    | `/rooms/:room_id/participants`                     | [`ListParticipantsResponse`] |
+//! | POST   | `/rooms/:room_id/participants/:participant_id/kick` | [`KickParticipantResponse`] |
+//! | GET    | `/rooms/:room_id/stats`                            | [`RoomStatsResponse`]      |
+//! | POST   | `/rooms/:room_id/recordings`                       | [`StartRecordingResponse`] |
+//! | DELETE | `/rooms/:room_id/recordings`                       | [`StopRecordingResponse`]  |
+//! | POST   | `/rooms/:room_id/transcriptions`                   | [`StartTranscriptionResponse`] |
+//! | DELETE | `/rooms/:room_id/transcriptions`                   | [`StopTranscriptionResponse`] |
+//!
+//! Starting a recording or transcription asks the room's participants for
+//! consent first and fails with `409 Conflict` if the room's
+//! [`ConsentPolicy`](crate::consent::ConsentPolicy) requires acknowledgement and a participant declines.
+
+use crate::schedule::RoomSchedule;
+use crate::server::{Participant, Room, RoomRecording, RoomTranscription, SignalingServer};
+use axum::extract::{Path, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use quicrtc_core::QuicRtcError;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+/// Admin API configuration
+#[derive(Debug, Clone)]
+pub struct AdminConfig {
+    /// Address the admin API binds to
+    pub bind_addr: SocketAddr,
+    /// Bearer token required on every request
+    pub api_token: String,
+}
+
+/// Summary of a room
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomSummary {
+    /// Room ID
+    pub room_id: String,
+    /// Room display name
+    pub room_name: Option<String>,
+    /// Number of participants in the room
+    pub participant_count: usize,
+    /// Maximum participants allowed
+    pub max_participants: usize,
+    /// Room creation timestamp
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Whether a recording is in progress
+    pub recording: bool,
+    /// Whether a transcription is in progress
+    #[serde(default)]
+    pub transcribing: bool,
+    /// Whether capture waits for every participant to consent
+    #[serde(default)]
+    pub require_consent: bool,
+    /// When the room's lifetime closes it, if it is set to close
+    #[serde(default)]
+    pub closes_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Number of participants waiting in the lobby for the host
+    #[serde(default)]
+    pub waiting_participants: usize,
+}
+
+impl From<Room> for RoomSummary {
+    fn from(room: Room) -> Self {
+        Self {
+            participant_count: room.participants.len(),
+            max_participants: room.max_participants,
+            created_at: room.created_at,
+            recording: room.recording.is_some(),
+            transcribing: room.transcription.is_some(),
+            require_consent: room.consent_policy.require_acknowledgement,
+            closes_at: room.closes_at().map(|(closes_at, _)| closes_at),
+            waiting_participants: room.lobby.len(),
+            room_id: room.id,
+            room_name: room.name,
+        }
+    }
+}
+
+/// Response to `GET /rooms`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListRoomsResponse {
+    /// All rooms on the server
+    pub rooms: Vec<RoomSummary>,
+}
+
+/// Body of `POST /rooms`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRoomRequest {
+    /// Room ID
+    pub room_id: String,
+    /// Room display name
+    pub room_name: Option<String>,
+    /// Maximum participants allowed
+    pub max_participants: Option<usize>,
+    /// Refuse to record or transcribe unless every participant consents
+    ///
+    /// Defaults to the signaling server's policy.
+    #[serde(default)]
+    pub require_consent: Option<bool>,
+    /// Seconds to wait for participants to consent
+    #[serde(default)]
+    pub consent_timeout_secs: Option<u64>,
+    /// Close the room once it has been empty this many seconds
+    #[serde(default)]
+    pub empty_timeout_secs: Option<u64>,
+    /// Close the room this many seconds after it opens
+    #[serde(default)]
+    pub max_duration_secs: Option<u64>,
+    /// Refuse joins before this time
+    #[serde(default)]
+    pub opens_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Close the room at this time
+    #[serde(default)]
+    pub closes_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Host and start time, for scheduled rooms
+    #[serde(default)]
+    pub schedule: Option<RoomSchedule>,
+}
+
+/// Response to `GET /rooms/:room_id/participants`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListParticipantsResponse {
+    /// Room ID
+    pub room_id: String,
+    /// Participants in the room
+    pub participants: Vec<Participant>,
+}
+
+/// Body of `POST /rooms/:room_id/participants/:participant_id/kick`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KickParticipantRequest {
+    /// Reason passed on to the kicked participant
+    pub reason: Option<String>,
+}
+
+/// Response to a kick request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KickParticipantResponse {
+    /// Room ID
+    pub room_id: String,
+    /// The participant that was removed
+    pub participant: Participant,
+}
+
+/// Response to `DELETE /rooms/:room_id`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloseRoomResponse {
+    /// Room ID
+    pub room_id: String,
+    /// Number of participants removed with the room
+    pub participants_removed: usize,
+}
+
+/// Response to `GET /rooms/:room_id/stats`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomStatsResponse {
+    /// Room ID
+    pub room_id: String,
+    /// Number of participants in the room
+    pub participant_count: usize,
+    /// Maximum participants allowed
+    pub max_participants: usize,
+    /// Room occupancy (0.0 to 1.0)
+    pub occupancy: f64,
+    /// Seconds since the room was created
+    pub uptime_secs: i64,
+    /// Participants advertising a direct QUIC endpoint
+    pub quic_participants: usize,
+    /// Recording in progress, if any
+    pub recording: Option<RoomRecording>,
+    /// Transcription in progress, if any
+    #[serde(default)]
+    pub transcription: Option<RoomTranscription>,
+}
+
+/// Body of `POST /rooms/:room_id/recordings`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StartRecordingRequest {
+    /// Where the recorder should write the output
+    pub output: Option<String>,
+}
+
+/// Response to a start recording request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartRecordingResponse {
+    /// Room ID
+    pub room_id: String,
+    /// The recording that was started
+    pub recording: RoomRecording,
+}
+
+/// Response to a stop recording request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StopRecordingResponse {
+    /// Room ID
+    pub room_id: String,
+    /// The recording that was stopped
+    pub recording: RoomRecording,
+}
+
+/// Body of `POST /rooms/:room_id/transcriptions`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StartTranscriptionRequest {
+    /// Spoken language hint for the transcriber
+    pub language: Option<String>,
+}
+
+/// Response to a start transcription request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartTranscriptionResponse {
+    /// Room ID
+    pub room_id: String,
+    /// The transcription that was started
+    pub transcription: RoomTranscription,
+}
+
+/// Response to a stop transcription request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StopTranscriptionResponse {
+    /// Room ID
+    pub room_id: String,
+    /// The transcription that was stopped
+    pub transcription: RoomTranscription,
+}
+
+/// Error body returned by the admin API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminErrorResponse {
+    /// Error message
+    pub error: String,
+    /// Error code for programmatic handling
+    pub error_code: String,
+}
+
+/// Administrative API server for a [`SignalingServer`]
+#[derive(Debug, Clone)]
+pub struct AdminServer {
+    config: AdminConfig,
+    signaling: SignalingServer,
+}
+
+#[derive(Debug, Clone)]
+struct AdminState {
+    signaling: SignalingServer,
+    api_token: Arc<str>,
+}
+
+/// Error wrapper mapping [`QuicRtcError`] to an HTTP response
+struct AdminError(QuicRtcError);
+
+impl From<QuicRtcError> for AdminError {
+    fn from(error: QuicRtcError) -> Self {
+        Self(error)
+    }
+}
+
+impl IntoResponse for AdminError {
+    fn into_response(self) -> Response {
+        let status = match &self.0 {
+            QuicRtcError::RoomNotFound { .. } | QuicRtcError::ParticipantNotFound { .. } => {
+                StatusCode::NOT_FOUND
+            }
+            QuicRtcError::RoomAlreadyExists { .. }
+            | QuicRtcError::InvalidOperation { .. }
+            | QuicRtcError::ConsentRefused { .. } => StatusCode::CONFLICT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        let body = AdminErrorResponse {
+            error: self.0.to_string(),
+            error_code: self.0.error_code(),
+        };
+        (status, Json(body)).into_response()
+    }
+}
+
+impl AdminServer {
+    /// Create an admin API for `signaling`
+    pub fn new(signaling: SignalingServer, config: AdminConfig) -> Self {
+        Self { config, signaling }
+    }
+
+    /// Bind to the configured address and serve the admin API
+    pub async fn start(&self) -> Result<(), QuicRtcError> {
+        let listener = TcpListener::bind(self.config.bind_addr)
+            .await
+            .map_err(|e| QuicRtcError::ServerStartFailed {
+                address: self.config.bind_addr,
+                source: e.into(),
+            })?;
+        self.serve(listener).await
+    }
+
+    /// Serve the admin API on an already bound listener
+    pub async fn serve(&self, listener: TcpListener) -> Result<(), QuicRtcError> {
+        let address = listener.local_addr().unwrap_or(self.config.bind_addr);
+        tracing::info!("Signaling admin API listening on {}", address);
+
+        axum::serve(listener, self.router())
+            .await
+            .map_err(|e| QuicRtcError::ServerStartFailed {
+                address,
+                source: e.into(),
+            })
+    }
+
+    /// Build the admin API routes
+    pub fn router(&self) -> Router {
+        let state = AdminState {
+            signaling: self.signaling.clone(),
+            api_token: self.config.api_token.as_str().into(),
+        };
+
+        Router::new()
+            .route("/rooms", get(list_rooms).post(create_room))
+            .route("/rooms/:room_id", axum::routing::delete(close_room))
+            .route("/rooms/:room_id/participants", get(list_participants))
+            .route(
+                "/rooms/:room_id/participants/:participant_id/kick",
+                post(kick_participant),
+            )
+            .route("/rooms/:room_id/stats", get(room_stats))
+            .route(
+                "/rooms/:room_id/recordings",
+                post(start_recording).delete(stop_recording),
+            )
+            .route(
+                "/rooms/:room_id/transcriptions",
+                post(start_transcription).delete(stop_transcription),
+            )
+            .route_layer(middleware::from_fn_with_state(state.clone(), require_token))
+            .with_state(state)
+    }
+}
+
+/// Reject requests without the configured bearer token
+async fn require_token(State(state): State<AdminState>, request: Request, next: Next) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| constant_time_eq(token.as_bytes(), state.api_token.as_bytes()));
+
+    if !authorized {
+        let body = AdminErrorResponse {
+            error: "Missing or invalid admin API token".to_string(),
+            error_code: "UNAUTHORIZED".to_string(),
+        };
+        return (StatusCode::UNAUTHORIZED, Json(body)).into_response();
+    }
+
+    next.run(request).await
+}
+
+/// Compare tokens without leaking the mismatch position through timing
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+async fn list_rooms(State(state): State<AdminState>) -> Json<ListRoomsResponse> {
+    let rooms = state
+        .signaling
+        .get_rooms()
+        .await
+        .into_iter()
+        .map(RoomSummary::from)
+        .collect();
+
+    Json(ListRoomsResponse { rooms })
+}
+
+async fn create_room(
+    State(state): State<AdminState>,
+    Json(request): Json<CreateRoomRequest>,
+) -> Result<(StatusCode, Json<RoomSummary>), AdminError> {
+    state
+        .signaling
+        .create_room(
+            request.room_id.clone(),
+            request.room_name,
+            request.max_participants,
+        )
+        .await?;
+    if request.require_consent.is_some() || request.consent_timeout_secs.is_some() {
+        let mut policy = state
+            .signaling
+            .get_room(&request.room_id)
+            .await
+            .map(|room| room.consent_policy)
+            .unwrap_or_default();
+        if let Some(require) = request.require_consent {
+            policy.require_acknowledgement = require;
+        }
+        if let Some(secs) = request.consent_timeout_secs {
+            policy.ack_timeout = std::time::Duration::from_secs(secs);
+        }
+        state
+            .signaling
+            .set_consent_policy(&request.room_id, policy)
+            .await?;
+    }
+    if request.schedule.is_some() {
+        state
+            .signaling
+            .set_room_schedule(&request.room_id, request.schedule)
+            .await?;
+    }
+    let lifetime_set = request.empty_timeout_secs.is_some()
+        || request.max_duration_secs.is_some()
+        || request.opens_at.is_some()
+        || request.closes_at.is_some();
+    if lifetime_set {
+        let mut lifetime = state
+            .signaling
+            .get_room(&request.room_id)
+            .await
+            .map(|room| room.lifetime)
+            .unwrap_or_default();
+        if let Some(secs) = request.empty_timeout_secs {
+            lifetime.empty_timeout = Some(std::time::Duration::from_secs(secs));
+        }
+        if let Some(secs) = request.max_duration_secs {
+            lifetime.max_duration = Some(std::time::Duration::from_secs(secs));
+        }
+        if request.opens_at.is_some() {
+            lifetime.opens_at = request.opens_at;
+        }
+        if request.closes_at.is_some() {
+            lifetime.closes_at = request.closes_at;
+        }
+        state
+            .signaling
+            .set_room_lifetime(&request.room_id, lifetime)
+            .await?;
+    }
+    let room = state
+        .signaling
+        .get_room(&request.room_id)
+        .await
+        .ok_or_else(|| QuicRtcError::RoomNotFound {
+            room_id: request.room_id,
+        })?;
+
+    Ok((StatusCode::CREATED, Json(RoomSummary::from(room))))
+}
+
+async fn list_participants(
+    State(state): State<AdminState>,
+    Path(room_id): Path<String>,
+) -> Result<Json<ListParticipantsResponse>, AdminError> {
+    let room =
+        state
+            .signaling
+            .get_room(&room_id)
+            .await
+            .ok_or_else(|| QuicRtcError::RoomNotFound {
+                room_id: room_id.clone(),
+            })?;
+
+    Ok(Json(ListParticipantsResponse {
+        room_id,
+        participants: room.participants.into_values().collect(),
+    }))
+}
+
+async fn kick_participant(
+    State(state): State<AdminState>,
+    Path((room_id, participant_id)): Path<(String, String)>,
+    request: Option<Json<KickParticipantRequest>>,
+) -> Result<Json<KickParticipantResponse>, AdminError> {
+    let reason = request.and_then(|Json(request)| request.reason);
+    let participant = state
+        .signaling
+        .kick_participant(&room_id, &participant_id, reason)
+        .await?;
+
+    Ok(Json(KickParticipantResponse {
+        room_id,
+        participant,
+    }))
+}
+
+async fn close_room(
+    State(state): State<AdminState>,
+    Path(room_id): Path<String>,
+) -> Result<Json<CloseRoomResponse>, AdminError> {
+    let room = state.signaling.close_room(&room_id).await?;
+
+    Ok(Json(CloseRoomResponse {
+        room_id,
+        participants_removed: room.participants.len(),
+    }))
+}
+
+async fn room_stats(
+    State(state): State<AdminState>,
+    Path(room_id): Path<String>,
+) -> Result<Json<RoomStatsResponse>, AdminError> {
+    let room =
+        state
+            .signaling
+            .get_room(&room_id)
+            .await
+            .ok_or_else(|| QuicRtcError::RoomNotFound {
+                room_id: room_id.clone(),
+            })?;
+
+    let participant_count = room.participants.len();
+    Ok(Json(RoomStatsResponse {
+        room_id,
+        participant_count,
+        max_participants: room.max_participants,
+        occupancy: if room.max_participants == 0 {
+            0.0
+        } else {
+            participant_count as f64 / room.max_participants as f64
+        },
+        uptime_secs: (chrono::Utc::now() - room.created_at).num_seconds(),
+        quic_participants: room
+            .participants
+            .values()
+            .filter(|participant| participant.quic_endpoint.is_some())
+            .count(),
+        recording: room.recording,
+        transcription: room.transcription,
+    }))
+}
+
+async fn start_recording(
+    State(state): State<AdminState>,
+    Path(room_id): Path<String>,
+    request: Option<Json<StartRecordingRequest>>,
+) -> Result<Json<StartRecordingResponse>, AdminError> {
+    let output = request.and_then(|Json(request)| request.output);
+    let recording = state.signaling.start_recording(&room_id, output).await?;
+
+    Ok(Json(StartRecordingResponse { room_id, recording }))
+}
+
+async fn stop_recording(
+    State(state): State<AdminState>,
+    Path(room_id): Path<String>,
+) -> Result<Json<StopRecordingResponse>, AdminError> {
+    let recording = state.signaling.stop_recording(&room_id).await?;
+
+    Ok(Json(StopRecordingResponse { room_id, recording }))
+}
+
+async fn start_transcription(
+    State(state): State<AdminState>,
+    Path(room_id): Path<String>,
+    request: Option<Json<StartTranscriptionRequest>>,
+) -> Result<Json<StartTranscriptionResponse>, AdminError> {
+    let language = request.and_then(|Json(request)| request.language);
+    let transcription = state
+        .signaling
+        .start_transcription(&room_id, language)
+        .await?;
+
+    Ok(Json(StartTranscriptionResponse {
+        room_id,
+        transcription,
+    }))
+}
+
+async fn stop_transcription(
+    State(state): State<AdminState>,
+    Path(room_id): Path<String>,
+) -> Result<Json<StopTranscriptionResponse>, AdminError> {
+    let transcription = state.signaling.stop_transcription(&room_id).await?;
+
+    Ok(Json(StopTranscriptionResponse {
+        room_id,
+        transcription,
+    }))
+}
@@ -0,0 +1,222 @@
+//! Typed participant capabilities
+//!
+//! A [`CapabilitySet`] says what a participant can receive and how it can
+//! connect: codecs with their parameters, transports, end-to-end encryption
+//! and simulcast. Participants advertise one when joining a room and in
+//! session offers, and the helpers here decide whether two participants can
+//! exchange media.
+//!
+//! Older peers advertised a plain list of names such as `["h264", "opus"]`.
+//! Such lists still deserialize: known codec names become codecs with no
+//! parameter limits, `"e2ee"` and `"simulcast"` set the matching flags, and
+//! anything else is dropped.
+
+use crate::codec::{select_codec, CodecCapability, CodecKind};
+use quicrtc_core::TransportMode;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Video codec names understood in legacy capability lists
+const LEGACY_VIDEO_CODECS: &[&str] = &["h264", "h265", "hevc", "vp8", "vp9", "av1"];
+/// Audio codec names understood in legacy capability lists
+const LEGACY_AUDIO_CODECS: &[&str] = &["opus", "aac", "g711", "g722", "pcmu", "pcma"];
+
+/// What a participant can receive and how it can connect
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CapabilitySet {
+    /// Codecs the participant can decode, most preferred first
+    pub codecs: Vec<CodecCapability>,
+    /// Transports the participant can connect over, most preferred first;
+    /// empty means unknown
+    pub transports: Vec<TransportMode>,
+    /// Whether media can be end-to-end encrypted
+    pub e2ee: bool,
+    /// Whether simulcast layers can be sent and selected
+    pub simulcast: bool,
+}
+
+impl CapabilitySet {
+    /// Nothing advertised
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Capabilities from a legacy list of names
+    pub fn from_names<S: AsRef<str>>(names: &[S]) -> Self {
+        let mut set = Self::new();
+        for name in names {
+            let name = name.as_ref().to_ascii_lowercase();
+            if LEGACY_VIDEO_CODECS.contains(&name.as_str()) {
+                set = set.with_codec(CodecCapability::video(&name));
+            } else if LEGACY_AUDIO_CODECS.contains(&name.as_str()) {
+                set = set.with_codec(CodecCapability::audio(&name));
+            } else if name == "e2ee" {
+                set.e2ee = true;
+            } else if name == "simulcast" {
+                set.simulcast = true;
+            }
+        }
+        set
+    }
+
+    /// Add a codec after those already listed, unless already present
+    pub fn with_codec(mut self, codec: CodecCapability) -> Self {
+        if self.codec(codec.kind, &codec.name).is_none() {
+            self.codecs.push(codec);
+        }
+        self
+    }
+
+    /// Add a transport after those already listed, unless already present
+    pub fn with_transport(mut self, transport: TransportMode) -> Self {
+        if !self.transports.contains(&transport) {
+            self.transports.push(transport);
+        }
+        self
+    }
+
+    /// Set end-to-end encryption support
+    pub fn with_e2ee(mut self, e2ee: bool) -> Self {
+        self.e2ee = e2ee;
+        self
+    }
+
+    /// Set simulcast support
+    pub fn with_simulcast(mut self, simulcast: bool) -> Self {
+        self.simulcast = simulcast;
+        self
+    }
+
+    /// Whether nothing at all is advertised
+    pub fn is_empty(&self) -> bool {
+        self.codecs.is_empty() && self.transports.is_empty() && !self.e2ee && !self.simulcast
+    }
+
+    /// Advertised codec of `kind` named `name`, compared ignoring case
+    pub fn codec(&self, kind: CodecKind, name: &str) -> Option<&CodecCapability> {
+        self.codecs
+            .iter()
+            .find(|codec| codec.kind == kind && codec.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Advertised codecs of `kind`, most preferred first
+    pub fn codecs_of(&self, kind: CodecKind) -> impl Iterator<Item = &CodecCapability> {
+        self.codecs.iter().filter(move |codec| codec.kind == kind)
+    }
+
+    /// Names of the advertised codecs of `kind`, most preferred first
+    pub fn codec_names(&self, kind: CodecKind) -> Vec<String> {
+        self.codecs_of(kind)
+            .map(|codec| codec.name.clone())
+            .collect()
+    }
+
+    /// Best codec of `kind` for media between us and `remote`
+    ///
+    /// `None` when either side advertises no codec of that kind, or they
+    /// have none in common.
+    pub fn common_codec(&self, remote: &CapabilitySet, kind: CodecKind) -> Option<CodecCapability> {
+        select_codec(&self.codecs, &remote.codecs, kind)
+    }
+
+    /// What both sides support, in our order of preference
+    pub fn common(&self, remote: &CapabilitySet) -> CapabilitySet {
+        CapabilitySet {
+            codecs: self
+                .codecs
+                .iter()
+                .filter_map(|ours| {
+                    remote
+                        .codecs
+                        .iter()
+                        .find_map(|theirs| ours.intersect(theirs))
+                })
+                .collect(),
+            transports: self
+                .transports
+                .iter()
+                .copied()
+                .filter(|transport| remote.transports.contains(transport))
+                .collect(),
+            e2ee: self.e2ee && remote.e2ee,
+            simulcast: self.simulcast && remote.simulcast,
+        }
+    }
+
+    /// Whether media of `kind` can flow between us and `remote`
+    ///
+    /// A side that advertises no codec of that kind, such as an older peer
+    /// or a relay forwarding whatever it gets, is taken to accept any.
+    pub fn can_exchange(&self, remote: &CapabilitySet, kind: CodecKind) -> bool {
+        self.codecs_of(kind).next().is_none()
+            || remote.codecs_of(kind).next().is_none()
+            || self.common_codec(remote, kind).is_some()
+    }
+
+    /// Whether we can set up a session with `remote`
+    ///
+    /// Each media kind must be exchangeable, see
+    /// [`can_exchange`](Self::can_exchange), and when both sides list
+    /// transports they must share one.
+    pub fn is_compatible_with(&self, remote: &CapabilitySet) -> bool {
+        let transports = self.transports.is_empty()
+            || remote.transports.is_empty()
+            || self
+                .transports
+                .iter()
+                .any(|transport| remote.transports.contains(transport));
+        transports
+            && self.can_exchange(remote, CodecKind::Audio)
+            && self.can_exchange(remote, CodecKind::Video)
+    }
+
+    /// Whether we support everything in `required`
+    ///
+    /// Every required codec must be supported with compatible parameters,
+    /// every required transport listed, and e2ee and simulcast present when
+    /// required.
+    pub fn satisfies(&self, required: &CapabilitySet) -> bool {
+        required.codecs.iter().all(|codec| {
+            self.codecs
+                .iter()
+                .any(|ours| ours.intersect(codec).is_some())
+        }) && required
+            .transports
+            .iter()
+            .all(|transport| self.transports.contains(transport))
+            && (!required.e2ee || self.e2ee)
+            && (!required.simulcast || self.simulcast)
+    }
+}
+
+impl<'de> Deserialize<'de> for CapabilitySet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Typed {
+            #[serde(default)]
+            codecs: Vec<CodecCapability>,
+            #[serde(default)]
+            transports: Vec<TransportMode>,
+            #[serde(default)]
+            e2ee: bool,
+            #[serde(default)]
+            simulcast: bool,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Wire {
+            Names(Vec<String>),
+            Typed(Typed),
+        }
+
+        Ok(match Wire::deserialize(deserializer)? {
+            Wire::Names(names) => Self::from_names(&names),
+            Wire::Typed(typed) => Self {
+                codecs: typed.codecs,
+                transports: typed.transports,
+                e2ee: typed.e2ee,
+                simulcast: typed.simulcast,
+            },
+        })
+    }
+}
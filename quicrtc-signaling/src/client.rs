@@ -0,0 +1,200 @@
+//! Signaling client
+//!
+//! Connects to a [`crate::SignalingServer`] over WebSocket, sends
+//! [`SignalingMessage`]s and hands decoded [`SignalingResponse`]s to the
+//! caller in arrival order. Each connection starts with the hello exchange,
+//! see [`SignalingClient::negotiation`]; messages then go out in the agreed
+//! encoding. Responses are decoded by frame type, JSON in text frames and
+//! CBOR in binary ones, since the welcome itself still arrives as JSON.
+
+use crate::protocol::{
+    FeatureSet, Negotiation, ProtocolFeature, SignalingMessage, SignalingResponse, WireEncoding,
+    PROTOCOL_VERSION,
+};
+use futures::stream::SplitSink;
+use futures::{SinkExt, StreamExt};
+use quicrtc_core::QuicRtcError;
+use std::collections::VecDeque;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, Mutex};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+type WsSink = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
+
+/// How long to wait for the server's answer to the hello
+const HELLO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// WebSocket connection to a signaling server
+#[derive(Debug)]
+pub struct SignalingClient {
+    url: String,
+    sink: Mutex<WsSink>,
+    responses: Mutex<mpsc::UnboundedReceiver<SignalingResponse>>,
+    /// Responses passed over by [`SignalingClient::expect`], oldest first
+    deferred: Mutex<VecDeque<SignalingResponse>>,
+    /// Outcome of the hello exchange
+    negotiation: Negotiation,
+}
+
+impl SignalingClient {
+    /// Connect to a signaling server, e.g. `ws://127.0.0.1:8080`
+    ///
+    /// Responses are read on a background task, so this must be called
+    /// from within a Tokio runtime. The client then says hello, offering
+    /// CBOR, and waits for the server's welcome; a server that predates the
+    /// exchange is spoken to with the baseline protocol.
+    pub async fn connect(url: &str) -> Result<Self, QuicRtcError> {
+        let (stream, _) = connect_async(url)
+            .await
+            .map_err(|e| QuicRtcError::Connection {
+                room_id: "unknown".to_string(),
+                reason: format!("Failed to connect to signaling server {}: {}", url, e),
+                retry_in: Some(Duration::from_secs(5)),
+                suggested_action: "Check the signaling URL and that the server is running"
+                    .to_string(),
+            })?;
+        let (sink, mut source) = stream.split();
+
+        let (response_tx, response_rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(Ok(message)) = source.next().await {
+                let response = match &message {
+                    Message::Text(text) => WireEncoding::Json.decode(text.as_bytes()),
+                    Message::Binary(data) => WireEncoding::Cbor.decode(data),
+                    Message::Close(_) => break,
+                    _ => continue,
+                };
+                match response {
+                    Ok(response) => {
+                        if response_tx.send(response).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => tracing::warn!("Ignoring undecodable signaling response: {}", e),
+                }
+            }
+            tracing::debug!("Signaling connection closed");
+        });
+
+        let mut client = Self {
+            url: url.to_string(),
+            sink: Mutex::new(sink),
+            responses: Mutex::new(response_rx),
+            deferred: Mutex::new(VecDeque::new()),
+            negotiation: Negotiation::baseline(),
+        };
+        client.negotiation = client.hello().await?;
+        tracing::debug!(
+            "Signaling with {} uses protocol {} in {:?}",
+            url,
+            client.negotiation.protocol_version,
+            client.negotiation.encoding
+        );
+        Ok(client)
+    }
+
+    /// Agree on the protocol version, encoding and features
+    async fn hello(&self) -> Result<Negotiation, QuicRtcError> {
+        self.send(&SignalingMessage::Hello {
+            protocol_version: PROTOCOL_VERSION,
+            encodings: vec![WireEncoding::Cbor, WireEncoding::Json],
+            features: FeatureSet::new().with(ProtocolFeature::BinaryEncoding),
+        })
+        .await?;
+        self.expect("hello", HELLO_TIMEOUT, |response| match response {
+            SignalingResponse::Welcome { .. } | SignalingResponse::Error { .. } => {
+                Some(Negotiation::from_response(&response))
+            }
+            _ => None,
+        })
+        .await
+    }
+
+    /// Server URL
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Protocol version, encoding and features agreed with the server
+    pub fn negotiation(&self) -> &Negotiation {
+        &self.negotiation
+    }
+
+    /// Send a message in the negotiated encoding
+    pub async fn send(&self, message: &SignalingMessage) -> Result<(), QuicRtcError> {
+        let encoding = self.negotiation.encoding;
+        let data = encoding.encode(message)?;
+        let frame = match encoding {
+            WireEncoding::Json => {
+                let text = String::from_utf8(data).map_err(|e| QuicRtcError::InvalidData {
+                    reason: format!("Signaling message is not UTF-8: {}", e),
+                })?;
+                Message::Text(text)
+            }
+            WireEncoding::Cbor => Message::Binary(data),
+        };
+        self.sink
+            .lock()
+            .await
+            .send(frame)
+            .await
+            .map_err(|e| QuicRtcError::Transport {
+                reason: format!("Failed to send signaling message: {}", e),
+            })
+    }
+
+    /// Wait for the next response; `None` once the connection is closed
+    ///
+    /// Responses [`SignalingClient::expect`] passed over come first.
+    pub async fn recv(&self) -> Option<SignalingResponse> {
+        if let Some(response) = self.deferred.lock().await.pop_front() {
+            return Some(response);
+        }
+        self.responses.lock().await.recv().await
+    }
+
+    /// Wait for the first response `matcher` accepts
+    ///
+    /// The responses it passes over are kept for [`SignalingClient::recv`],
+    /// so notifications arriving meanwhile aren't lost. Fails if `timeout`
+    /// elapses or the connection closes first.
+    pub async fn expect<T>(
+        &self,
+        operation: &str,
+        timeout: Duration,
+        mut matcher: impl FnMut(SignalingResponse) -> Option<T>,
+    ) -> Result<T, QuicRtcError> {
+        let wait = async {
+            while let Some(response) = self.responses.lock().await.recv().await {
+                match matcher(response.clone()) {
+                    Some(value) => return Ok(value),
+                    None => self.deferred.lock().await.push_back(response),
+                }
+            }
+            Err(QuicRtcError::Transport {
+                reason: format!("Signaling connection closed during {}", operation),
+            })
+        };
+
+        tokio::time::timeout(timeout, wait)
+            .await
+            .map_err(|_| QuicRtcError::Timeout {
+                operation: operation.to_string(),
+                duration: timeout,
+            })?
+    }
+
+    /// Close the connection
+    pub async fn close(&self) -> Result<(), QuicRtcError> {
+        self.sink
+            .lock()
+            .await
+            .close()
+            .await
+            .map_err(|e| QuicRtcError::Transport {
+                reason: format!("Failed to close signaling connection: {}", e),
+            })
+    }
+}
@@ -0,0 +1,177 @@
+//! Structured codec capabilities exchanged in session offers and answers
+//!
+//! Each side lists the codecs it can decode in preference order. The
+//! answerer picks, per media kind, its most preferred codec the offerer also
+//! supports and narrows the parameters to what both sides can handle: the
+//! common profiles, the smaller resolution and framerate limits, and SVC
+//! only if both support it.
+
+use serde::{Deserialize, Serialize};
+
+/// Media kind a codec carries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CodecKind {
+    /// Audio codec
+    Audio,
+    /// Video codec
+    Video,
+}
+
+/// What a participant supports for one codec
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodecCapability {
+    /// Codec name, e.g. "h264" or "opus"; compared ignoring case
+    pub name: String,
+    /// Media kind
+    pub kind: CodecKind,
+    /// Supported profiles, e.g. "constrained-baseline"; empty means any
+    #[serde(default)]
+    pub profiles: Vec<String>,
+    /// Largest frame width, for video
+    #[serde(default)]
+    pub max_width: Option<u32>,
+    /// Largest frame height, for video
+    #[serde(default)]
+    pub max_height: Option<u32>,
+    /// Highest framerate, for video
+    #[serde(default)]
+    pub max_framerate: Option<u32>,
+    /// Whether scalable video coding layers can be sent or received
+    #[serde(default)]
+    pub svc: bool,
+    /// Most channels, for audio
+    #[serde(default)]
+    pub max_channels: Option<u8>,
+}
+
+impl CodecCapability {
+    /// Audio codec with no parameter limits
+    pub fn audio(name: &str) -> Self {
+        Self::new(name, CodecKind::Audio)
+    }
+
+    /// Video codec with no parameter limits
+    pub fn video(name: &str) -> Self {
+        Self::new(name, CodecKind::Video)
+    }
+
+    fn new(name: &str, kind: CodecKind) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            profiles: Vec::new(),
+            max_width: None,
+            max_height: None,
+            max_framerate: None,
+            svc: false,
+            max_channels: None,
+        }
+    }
+
+    /// Restrict to the given profiles
+    pub fn with_profiles(mut self, profiles: &[&str]) -> Self {
+        self.profiles = profiles.iter().map(|profile| profile.to_string()).collect();
+        self
+    }
+
+    /// Limit the frame size
+    pub fn with_max_resolution(mut self, width: u32, height: u32) -> Self {
+        self.max_width = Some(width);
+        self.max_height = Some(height);
+        self
+    }
+
+    /// Limit the framerate
+    pub fn with_max_framerate(mut self, framerate: u32) -> Self {
+        self.max_framerate = Some(framerate);
+        self
+    }
+
+    /// Limit the channel count
+    pub fn with_max_channels(mut self, channels: u8) -> Self {
+        self.max_channels = Some(channels);
+        self
+    }
+
+    /// Set SVC support
+    pub fn with_svc(mut self, svc: bool) -> Self {
+        self.svc = svc;
+        self
+    }
+
+    /// Parameters both capabilities support, or `None` if they are incompatible
+    ///
+    /// The result keeps this capability's name.
+    pub fn intersect(&self, other: &CodecCapability) -> Option<CodecCapability> {
+        if self.kind != other.kind || !self.name.eq_ignore_ascii_case(&other.name) {
+            return None;
+        }
+
+        let profiles = match (self.profiles.is_empty(), other.profiles.is_empty()) {
+            (true, _) => other.profiles.clone(),
+            (_, true) => self.profiles.clone(),
+            _ => {
+                let common: Vec<String> = self
+                    .profiles
+                    .iter()
+                    .filter(|profile| {
+                        other
+                            .profiles
+                            .iter()
+                            .any(|theirs| theirs.eq_ignore_ascii_case(profile))
+                    })
+                    .cloned()
+                    .collect();
+                if common.is_empty() {
+                    return None;
+                }
+                common
+            }
+        };
+
+        Some(CodecCapability {
+            name: self.name.clone(),
+            kind: self.kind,
+            profiles,
+            max_width: min_limit(self.max_width, other.max_width),
+            max_height: min_limit(self.max_height, other.max_height),
+            max_framerate: min_limit(self.max_framerate, other.max_framerate),
+            svc: self.svc && other.svc,
+            max_channels: min_limit(self.max_channels, other.max_channels),
+        })
+    }
+}
+
+/// Pick the best codec of `kind` both sides support
+///
+/// `local` is in preference order; the first local codec compatible with
+/// any remote one wins, with parameters narrowed to both sides' limits.
+pub fn select_codec(
+    local: &[CodecCapability],
+    remote: &[CodecCapability],
+    kind: CodecKind,
+) -> Option<CodecCapability> {
+    local
+        .iter()
+        .filter(|capability| capability.kind == kind)
+        .find_map(|ours| remote.iter().find_map(|theirs| ours.intersect(theirs)))
+}
+
+/// Pick the best codec for every media kind both sides support
+pub fn select_codecs(
+    local: &[CodecCapability],
+    remote: &[CodecCapability],
+) -> Vec<CodecCapability> {
+    [CodecKind::Audio, CodecKind::Video]
+        .into_iter()
+        .filter_map(|kind| select_codec(local, remote, kind))
+        .collect()
+}
+
+/// Smaller of two optional limits; a missing limit means unlimited
+fn min_limit<T: Ord>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (limit, None) | (None, limit) => limit,
+    }
+}
@@ -0,0 +1,126 @@
+//! Participant consent for recording and transcription
+//!
+//! Before a room starts recording or transcribing, the server sends every
+//! participant a `CaptureConsentRequested` notice. Rooms whose
+//! [`ConsentPolicy`] requires acknowledgement only start capturing once each
+//! of those participants has answered with a `CaptureConsent` message that
+//! accepts; a decline, or silence past the timeout, refuses the capture.
+
+use quicrtc_core::QuicRtcError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// What a room is about to capture
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CaptureKind {
+    /// Media recording
+    Recording,
+    /// Speech transcription
+    Transcription,
+}
+
+/// Per-room rules for starting a recording or transcription
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsentPolicy {
+    /// Refuse to capture unless every participant accepts
+    ///
+    /// When false, participants are still notified but not asked to answer.
+    pub require_acknowledgement: bool,
+    /// How long to wait for every participant to answer
+    pub ack_timeout: Duration,
+}
+
+impl Default for ConsentPolicy {
+    fn default() -> Self {
+        Self {
+            require_acknowledgement: false,
+            ack_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl ConsentPolicy {
+    /// Policy that refuses to capture without every participant's consent
+    pub fn require_acknowledgement() -> Self {
+        Self {
+            require_acknowledgement: true,
+            ..Self::default()
+        }
+    }
+
+    /// Set how long to wait for participants to answer
+    pub fn with_ack_timeout(mut self, ack_timeout: Duration) -> Self {
+        self.ack_timeout = ack_timeout;
+        self
+    }
+}
+
+/// Change to an outstanding consent request
+#[derive(Debug)]
+pub(crate) enum ConsentUpdate {
+    /// A participant answered the request
+    Answered {
+        participant_id: String,
+        accepted: bool,
+    },
+    /// A participant left the room and no longer needs to answer
+    Left { participant_id: String },
+}
+
+/// Handle the server keeps for routing answers to a waiting request
+#[derive(Debug, Clone)]
+pub(crate) struct ConsentWaiter {
+    /// Room the request was sent to
+    pub room_id: String,
+    /// Participants asked to answer
+    pub participants: HashSet<String>,
+    /// Delivers answers to the task waiting on the request
+    pub updates: mpsc::UnboundedSender<ConsentUpdate>,
+}
+
+/// Wait until every participant in `pending` has accepted
+///
+/// Fails with the participants that declined, or those that had not
+/// answered when `timeout` ran out.
+pub(crate) async fn await_consent(
+    room_id: &str,
+    mut pending: HashSet<String>,
+    updates: &mut mpsc::UnboundedReceiver<ConsentUpdate>,
+    timeout: Duration,
+) -> Result<(), QuicRtcError> {
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    while !pending.is_empty() {
+        let update = match tokio::time::timeout_at(deadline, updates.recv()).await {
+            Ok(Some(update)) => update,
+            Ok(None) | Err(_) => break,
+        };
+        match update {
+            ConsentUpdate::Answered {
+                participant_id,
+                accepted: false,
+            } => {
+                return Err(QuicRtcError::ConsentRefused {
+                    room_id: room_id.to_string(),
+                    participants: vec![participant_id],
+                });
+            }
+            ConsentUpdate::Answered { participant_id, .. }
+            | ConsentUpdate::Left { participant_id } => {
+                pending.remove(&participant_id);
+            }
+        }
+    }
+
+    if pending.is_empty() {
+        return Ok(());
+    }
+    let mut participants: Vec<String> = pending.into_iter().collect();
+    participants.sort();
+    Err(QuicRtcError::ConsentRefused {
+        room_id: room_id.to_string(),
+        participants,
+    })
+}
@@ -1,5 +1,6 @@
 //! Peer discovery service
 
+use crate::capability::CapabilitySet;
 use chrono::{DateTime, Utc};
 use quicrtc_core::QuicRtcError;
 use std::collections::HashMap;
@@ -17,8 +18,8 @@ pub struct PeerInfo {
     pub room_id: String,
     /// QUIC endpoint for direct connection
     pub quic_endpoint: Option<std::net::SocketAddr>,
-    /// MoQ capabilities
-    pub capabilities: Vec<String>,
+    /// What the peer can receive and how it can connect
+    pub capabilities: CapabilitySet,
     /// Last seen timestamp
     pub last_seen: DateTime<Utc>,
     /// Peer status
@@ -318,22 +319,19 @@ impl PeerDiscovery {
         }
     }
 
-    /// Find peers with specific capabilities
+    /// Find peers supporting everything in `required`, see
+    /// [`CapabilitySet::satisfies`]
     pub async fn find_peers_with_capabilities(
         &self,
         room_id: &str,
-        required_capabilities: &[String],
+        required: &CapabilitySet,
     ) -> Result<Vec<PeerInfo>, QuicRtcError> {
         let peers = self.peers.read().await;
 
         if let Some(room_peers) = peers.get(room_id) {
             Ok(room_peers
                 .values()
-                .filter(|peer| {
-                    required_capabilities
-                        .iter()
-                        .all(|cap| peer.capabilities.contains(cap))
-                })
+                .filter(|peer| peer.capabilities.satisfies(required))
                 .cloned()
                 .collect())
         } else {
@@ -0,0 +1,270 @@
+//! Telephony gateway integration
+//!
+//! A [`TelephonyGateway`] places and ends calls on a SIP trunk or the
+//! PSTN. A [`PhoneBridge`] puts each call in a room as a synthetic
+//! participant that the signaling server serves itself, so a caller shows
+//! up to everyone else like any other participant. The call's audio stays
+//! G.711 on the phone side and Opus in the room; `quicrtc_media`'s
+//! `TelephonyTranscoder` converts between them in the gateway's media path.
+//!
+//! Dial-out goes through [`PhoneBridge::dial_out`]. For dial-in, the
+//! gateway answers the call, works out the room, for example from a PIN
+//! the caller enters, and hands it over with [`PhoneBridge::dial_in`].
+
+use crate::capability::CapabilitySet;
+use crate::codec::CodecCapability;
+use crate::server::{Participant, SignalingServer};
+use async_trait::async_trait;
+use dashmap::DashMap;
+use quicrtc_core::QuicRtcError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Prefix of the participant IDs given to phone calls
+pub const PHONE_PARTICIPANT_PREFIX: &str = "phone-";
+
+/// Phone number in E.164 form, such as `+14155550123`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PhoneNumber(String);
+
+impl PhoneNumber {
+    /// Parse a number, ignoring spaces, dashes, dots and parentheses
+    ///
+    /// The result must be `+` and a country code that does not start with
+    /// zero, at most 15 digits in all.
+    pub fn parse(number: &str) -> Result<Self, QuicRtcError> {
+        let normalized: String = number
+            .chars()
+            .filter(|c| !matches!(c, ' ' | '-' | '.' | '(' | ')'))
+            .collect();
+        let valid = normalized.strip_prefix('+').is_some_and(|digits| {
+            (2..=15).contains(&digits.len())
+                && digits.bytes().all(|b| b.is_ascii_digit())
+                && !digits.starts_with('0')
+        });
+        if !valid {
+            return Err(QuicRtcError::InvalidData {
+                reason: format!("Not an E.164 phone number: {}", number),
+            });
+        }
+        Ok(Self(normalized))
+    }
+
+    /// The number as `+` and digits
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The number with all but the last four digits hidden, for display to
+    /// other participants
+    pub fn masked(&self) -> String {
+        let digits = &self.0[1..];
+        let shown = digits.len().min(4);
+        format!(
+            "+{}{}",
+            "*".repeat(digits.len() - shown),
+            &digits[digits.len() - shown..]
+        )
+    }
+}
+
+impl fmt::Display for PhoneNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for PhoneNumber {
+    type Err = QuicRtcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for PhoneNumber {
+    type Error = QuicRtcError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<PhoneNumber> for String {
+    fn from(number: PhoneNumber) -> Self {
+        number.0
+    }
+}
+
+/// Which side placed a call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CallDirection {
+    /// The caller dialed in to the meeting
+    Inbound,
+    /// The room dialed out to the number
+    Outbound,
+}
+
+/// Phone call bridged into a room
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GatewayCall {
+    /// Unique call ID, shared with the gateway
+    pub call_id: String,
+    /// Room the call is bridged into
+    pub room_id: String,
+    /// Participant ID the call has in the room
+    pub participant_id: String,
+    /// Number on the other end
+    pub number: PhoneNumber,
+    /// Who placed the call
+    pub direction: CallDirection,
+    /// When the call was bridged into the room
+    pub started_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Telephony side of a [`PhoneBridge`], such as a SIP user agent
+#[async_trait]
+pub trait TelephonyGateway: Send + Sync + fmt::Debug {
+    /// Call `number`, returning once the call is answered
+    async fn dial(&self, call_id: &str, number: &PhoneNumber) -> Result<(), QuicRtcError>;
+
+    /// End a call
+    async fn hang_up(&self, call_id: &str) -> Result<(), QuicRtcError>;
+}
+
+/// Bridges phone calls into rooms as synthetic participants
+#[derive(Debug, Clone)]
+pub struct PhoneBridge {
+    server: SignalingServer,
+    gateway: Arc<dyn TelephonyGateway>,
+    calls: Arc<DashMap<String, GatewayCall>>,
+}
+
+impl PhoneBridge {
+    /// Bridge calls made through `gateway` into rooms on `server`
+    pub fn new(server: SignalingServer, gateway: Arc<dyn TelephonyGateway>) -> Self {
+        Self {
+            server,
+            gateway,
+            calls: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Call `number` and bring it into `room_id` once answered
+    pub async fn dial_out(
+        &self,
+        room_id: &str,
+        number: PhoneNumber,
+    ) -> Result<GatewayCall, QuicRtcError> {
+        if self.server.get_room(room_id).await.is_none() {
+            return Err(QuicRtcError::RoomNotFound {
+                room_id: room_id.to_string(),
+            });
+        }
+
+        let call_id = Uuid::new_v4().to_string();
+        self.gateway.dial(&call_id, &number).await?;
+        match self
+            .bridge(call_id.clone(), room_id, number, CallDirection::Outbound)
+            .await
+        {
+            Ok(call) => Ok(call),
+            Err(e) => {
+                if let Err(hang_up_error) = self.gateway.hang_up(&call_id).await {
+                    tracing::warn!("Failed to hang up call {}: {}", call_id, hang_up_error);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Bring an answered incoming call into `room_id`
+    ///
+    /// `call_id` is the gateway's ID for the call.
+    pub async fn dial_in(
+        &self,
+        call_id: &str,
+        number: PhoneNumber,
+        room_id: &str,
+    ) -> Result<GatewayCall, QuicRtcError> {
+        self.bridge(call_id.to_string(), room_id, number, CallDirection::Inbound)
+            .await
+    }
+
+    /// Hang up a call and remove it from its room
+    pub async fn hang_up(&self, call_id: &str) -> Result<GatewayCall, QuicRtcError> {
+        let call = self.call_ended(call_id).await?;
+        self.gateway.hang_up(call_id).await?;
+        Ok(call)
+    }
+
+    /// Remove a call the other end hung up from its room
+    pub async fn call_ended(&self, call_id: &str) -> Result<GatewayCall, QuicRtcError> {
+        let (_, call) =
+            self.calls
+                .remove(call_id)
+                .ok_or_else(|| QuicRtcError::InvalidOperation {
+                    operation: format!("end unknown call {}", call_id),
+                })?;
+        // The room may have closed under the call
+        if let Err(e) = self
+            .server
+            .leave_server_participant(&call.room_id, &call.participant_id)
+            .await
+        {
+            tracing::debug!("Call {} was no longer in its room: {}", call_id, e);
+        }
+        tracing::info!("Call {} with {} ended", call_id, call.number.masked());
+        Ok(call)
+    }
+
+    /// Calls currently bridged into rooms
+    pub fn calls(&self) -> Vec<GatewayCall> {
+        self.calls
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect()
+    }
+
+    async fn bridge(
+        &self,
+        call_id: String,
+        room_id: &str,
+        number: PhoneNumber,
+        direction: CallDirection,
+    ) -> Result<GatewayCall, QuicRtcError> {
+        let participant_id = format!("{}{}", PHONE_PARTICIPANT_PREFIX, call_id);
+        let participant = Participant {
+            id: participant_id.clone(),
+            name: Some(number.masked()),
+            connection_id: format!("gateway:{}", call_id),
+            // The gateway transcodes, so the room only ever sees Opus
+            capabilities: CapabilitySet::new().with_codec(CodecCapability::audio("opus")),
+            quic_endpoint: None,
+        };
+        self.server
+            .join_server_participant(room_id, participant)
+            .await?;
+
+        let call = GatewayCall {
+            call_id: call_id.clone(),
+            room_id: room_id.to_string(),
+            participant_id,
+            number,
+            direction,
+            started_at: chrono::Utc::now(),
+        };
+        tracing::info!(
+            "Call {} with {} bridged into room {}",
+            call_id,
+            call.number.masked(),
+            room_id
+        );
+        self.calls.insert(call_id, call.clone());
+        Ok(call)
+    }
+}
@@ -6,15 +6,37 @@
 #![deny(missing_docs)]
 #![warn(clippy::all)]
 
+pub mod admin;
+pub mod capability;
+pub mod client;
+pub mod codec;
+pub mod consent;
 pub mod discovery;
+pub mod gateway;
+pub mod lifetime;
 pub mod protocol;
+pub mod rate_limit;
+pub mod schedule;
 pub mod server;
+pub mod token;
+pub mod webhook;
 
 // Re-export main types
+pub use admin::{AdminConfig, AdminServer};
+pub use capability::CapabilitySet;
+pub use client::SignalingClient;
+pub use codec::{select_codec, select_codecs, CodecCapability, CodecKind};
+pub use consent::{CaptureKind, ConsentPolicy};
 pub use discovery::{
     DiscoveryConfig, DiscoveryEvent, PeerDiscovery, PeerInfo, PeerStatus, RoomStats,
 };
+pub use gateway::{CallDirection, GatewayCall, PhoneBridge, PhoneNumber, TelephonyGateway};
+pub use lifetime::{RoomCloseReason, RoomLifetime};
+pub use rate_limit::FloodProtectionConfig;
+pub use schedule::RoomSchedule;
 pub use server::SignalingServer;
+pub use token::{AccessToken, RoomGrants, TokenClaims, TokenVerifier};
+pub use webhook::{WebhookConfig, WebhookDispatcher, WebhookEvent, WebhookEventKind, WebhookStats};
 
 #[cfg(test)]
 mod tests {
@@ -43,13 +65,20 @@ mod tests {
             id: "test-participant".to_string(),
             name: Some("Test User".to_string()),
             connection_id: "conn-123".to_string(),
-            capabilities: vec!["h264".to_string(), "opus".to_string()],
+            capabilities: CapabilitySet::from_names(&["h264", "opus"]),
             quic_endpoint: Some(test_addr()),
         };
 
         assert_eq!(participant.id, "test-participant");
         assert_eq!(participant.name, Some("Test User".to_string()));
-        assert_eq!(participant.capabilities, vec!["h264", "opus"]);
+        assert!(participant
+            .capabilities
+            .codec(CodecKind::Video, "h264")
+            .is_some());
+        assert!(participant
+            .capabilities
+            .codec(CodecKind::Audio, "opus")
+            .is_some());
         assert!(participant.quic_endpoint.is_some());
     }
 
@@ -61,7 +90,7 @@ mod tests {
             id: "participant1".to_string(),
             name: Some("User 1".to_string()),
             connection_id: "conn-1".to_string(),
-            capabilities: vec![],
+            capabilities: CapabilitySet::new(),
             quic_endpoint: None,
         };
 
@@ -69,7 +98,7 @@ mod tests {
             id: "participant2".to_string(),
             name: Some("User 2".to_string()),
             connection_id: "conn-2".to_string(),
-            capabilities: vec![],
+            capabilities: CapabilitySet::new(),
             quic_endpoint: None,
         };
 
@@ -85,7 +114,7 @@ mod tests {
             id: "participant1".to_string(), // Same ID
             name: Some("Duplicate User".to_string()),
             connection_id: "conn-3".to_string(),
-            capabilities: vec![],
+            capabilities: CapabilitySet::new(),
             quic_endpoint: None,
         };
         assert!(room.add_participant(duplicate).is_err());
@@ -115,7 +144,7 @@ mod tests {
             id: "participant1".to_string(),
             name: None,
             connection_id: "conn-1".to_string(),
-            capabilities: vec![],
+            capabilities: CapabilitySet::new(),
             quic_endpoint: None,
         };
 
@@ -123,7 +152,7 @@ mod tests {
             id: "participant2".to_string(),
             name: None,
             connection_id: "conn-2".to_string(),
-            capabilities: vec![],
+            capabilities: CapabilitySet::new(),
             quic_endpoint: None,
         };
 
@@ -131,7 +160,7 @@ mod tests {
             id: "participant3".to_string(),
             name: None,
             connection_id: "conn-3".to_string(),
-            capabilities: vec![],
+            capabilities: CapabilitySet::new(),
             quic_endpoint: None,
         };
 
@@ -156,8 +185,9 @@ mod tests {
             room_id: "test-room".to_string(),
             participant_id: "user-123".to_string(),
             participant_name: Some("Test User".to_string()),
-            capabilities: vec!["h264".to_string()],
+            capabilities: CapabilitySet::from_names(&["h264"]),
             quic_endpoint: Some(test_addr()),
+            access_token: None,
         };
 
         // Test serialization
@@ -214,7 +244,17 @@ mod tests {
             moq_version: "draft-ietf-moq-transport-04".to_string(),
             publish_namespaces: vec!["video/camera".to_string()],
             subscribe_namespaces: vec!["video/camera".to_string(), "audio/mic".to_string()],
-            capabilities: vec!["h264".to_string(), "opus".to_string()],
+            capabilities: CapabilitySet::new()
+                .with_codec(CodecCapability::video("h264"))
+                .with_codec(CodecCapability::audio("opus")),
+            tracks: vec![PublishedTrack {
+                namespace: "video/camera".to_string(),
+                label: Some("Front camera".to_string()),
+                source: "camera".to_string(),
+                width: Some(1280),
+                height: Some(720),
+                codec: Some("h264".to_string()),
+            }],
             session_id: "session-123".to_string(),
         };
 
@@ -227,7 +267,11 @@ mod tests {
         let deserialized: MoqSessionOffer = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized.participant_id, "participant-1");
         assert_eq!(deserialized.session_id, "session-123");
-        assert_eq!(deserialized.capabilities.len(), 2);
+        assert_eq!(deserialized.capabilities.codecs.len(), 2);
+        let track = deserialized.track("video/camera").unwrap();
+        assert_eq!(track.label.as_deref(), Some("Front camera"));
+        assert_eq!((track.width, track.height), (Some(1280), Some(720)));
+        assert!(deserialized.track("audio/mic").is_none());
     }
 
     #[test]
@@ -240,6 +284,7 @@ mod tests {
             accepted_subscribe_namespaces: vec!["audio/mic".to_string()],
             session_id: "session-123".to_string(),
             accepted: true,
+            codecs: vec![CodecCapability::audio("opus")],
         };
 
         // Test serialization
@@ -262,8 +307,9 @@ mod tests {
                 room_id: "room1".to_string(),
                 participant_id: "user1".to_string(),
                 participant_name: None,
-                capabilities: vec![],
+                capabilities: CapabilitySet::new(),
                 quic_endpoint: None,
+                access_token: None,
             },
             SignalingMessage::LeaveRoom {
                 room_id: "room1".to_string(),
@@ -273,6 +319,8 @@ mod tests {
                 room_id: "room1".to_string(),
                 room_name: Some("Test Room".to_string()),
                 max_participants: Some(50),
+                access_token: None,
+                schedule: None,
             },
             SignalingMessage::ListRooms,
             SignalingMessage::GetRoomInfo {
@@ -303,7 +351,7 @@ mod tests {
             name: Some("Test Peer".to_string()),
             room_id: "test-room".to_string(),
             quic_endpoint: Some(test_addr()),
-            capabilities: vec!["h264".to_string()],
+            capabilities: CapabilitySet::from_names(&["h264"]),
             last_seen: Utc::now(),
             status: PeerStatus::Online,
         };
@@ -330,7 +378,7 @@ mod tests {
             id: "test-participant".to_string(),
             name: Some("Test User".to_string()),
             connection_id: "conn-123".to_string(),
-            capabilities: vec!["h264".to_string()],
+            capabilities: CapabilitySet::from_names(&["h264"]),
             quic_endpoint: Some(test_addr()),
         };
 
@@ -344,6 +392,180 @@ mod tests {
         let deserialized: Participant = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized.id, "test-participant");
         assert_eq!(deserialized.name, Some("Test User".to_string()));
-        assert_eq!(deserialized.capabilities, vec!["h264"]);
+        assert_eq!(deserialized.capabilities, participant.capabilities);
+    }
+
+    #[test]
+    fn test_message_rate_limiter() {
+        use crate::rate_limit::{Admission, MessageRateLimiter};
+        use std::time::{Duration, Instant};
+
+        let config = FloodProtectionConfig {
+            messages_per_second: 10.0,
+            burst: 3,
+            max_consecutive_throttled: 2,
+            ..FloodProtectionConfig::default()
+        };
+        let mut limiter = MessageRateLimiter::new(&config);
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert_eq!(limiter.admit(start), Admission::Allowed);
+        }
+        match limiter.admit(start) {
+            Admission::Throttled(wait) => assert!(wait.as_millis().abs_diff(100) <= 1),
+            other => panic!("Expected throttling, got {:?}", other),
+        }
+        assert!(matches!(limiter.admit(start), Admission::Throttled(_)));
+        assert_eq!(limiter.admit(start), Admission::Disconnect);
+
+        // Once the bucket refills the connection is allowed again
+        let later = start + Duration::from_secs(1);
+        assert_eq!(limiter.admit(later), Admission::Allowed);
+    }
+
+    #[test]
+    fn test_codec_capability_selection() {
+        let local = vec![
+            CodecCapability::video("h265").with_svc(true),
+            CodecCapability::video("h264")
+                .with_profiles(&["high", "constrained-baseline"])
+                .with_max_resolution(1920, 1080)
+                .with_max_framerate(60)
+                .with_svc(true),
+            CodecCapability::audio("opus"),
+        ];
+        let remote = vec![
+            CodecCapability::audio("OPUS"),
+            CodecCapability::video("h264")
+                .with_profiles(&["constrained-baseline"])
+                .with_max_resolution(1280, 720),
+        ];
+
+        // h265 is preferred locally but the remote lacks it
+        let video = select_codec(&local, &remote, CodecKind::Video).unwrap();
+        assert_eq!(video.name, "h264");
+        assert_eq!(video.profiles, vec!["constrained-baseline"]);
+        assert_eq!((video.max_width, video.max_height), (Some(1280), Some(720)));
+        assert_eq!(video.max_framerate, Some(60));
+        assert!(!video.svc);
+
+        // Disjoint profiles are incompatible
+        let main_only = CodecCapability::video("h264").with_profiles(&["main"]);
+        assert!(local[1].intersect(&main_only).is_none());
+        assert!(CodecCapability::audio("h264")
+            .intersect(&local[1])
+            .is_none());
+
+        // Offer/answer picks one codec per media kind
+        let offer = MoqSessionOffer {
+            participant_id: "participant-1".to_string(),
+            quic_endpoint: test_addr(),
+            moq_version: "draft-ietf-moq-transport-04".to_string(),
+            publish_namespaces: Vec::new(),
+            subscribe_namespaces: Vec::new(),
+            capabilities: CapabilitySet {
+                codecs: remote,
+                ..CapabilitySet::new()
+            },
+            tracks: Vec::new(),
+            session_id: "session-123".to_string(),
+        };
+        let answer = MoqSessionAnswer {
+            participant_id: "participant-2".to_string(),
+            quic_endpoint: test_addr(),
+            moq_version: "draft-ietf-moq-transport-04".to_string(),
+            accepted_publish_namespaces: Vec::new(),
+            accepted_subscribe_namespaces: Vec::new(),
+            session_id: "session-123".to_string(),
+            accepted: true,
+            codecs: offer.answer_codecs(&local),
+        };
+        assert_eq!(answer.codecs.len(), 2);
+        assert_eq!(answer.codec(CodecKind::Audio).unwrap().name, "opus");
+        assert_eq!(answer.codec(CodecKind::Video), Some(&video));
+
+        // Offers from older peers list capabilities by name
+        let json = r#"{"participant_id":"p","quic_endpoint":"127.0.0.1:0","moq_version":"v",
+            "publish_namespaces":[],"subscribe_namespaces":[],"capabilities":["h264","e2ee","x"],
+            "session_id":"s"}"#;
+        let legacy: MoqSessionOffer = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            legacy.capabilities.codecs,
+            vec![CodecCapability::video("h264")]
+        );
+        assert!(legacy.capabilities.e2ee);
+        assert!(!legacy.capabilities.simulcast);
+    }
+
+    #[test]
+    fn test_capability_compatibility() {
+        use quicrtc_core::TransportMode;
+
+        let local = CapabilitySet::new()
+            .with_codec(CodecCapability::video("h264").with_max_resolution(1920, 1080))
+            .with_codec(CodecCapability::audio("opus").with_max_channels(6))
+            .with_transport(TransportMode::QuicNative)
+            .with_transport(TransportMode::QuicOverWebSocket)
+            .with_simulcast(true);
+
+        // Older peers advertising nothing are accepted
+        assert!(local.is_compatible_with(&CapabilitySet::new()));
+
+        let remote = CapabilitySet::new()
+            .with_codec(CodecCapability::video("h264").with_max_resolution(1280, 720))
+            .with_codec(CodecCapability::video("vp8"))
+            .with_codec(CodecCapability::audio("opus").with_max_channels(2))
+            .with_transport(TransportMode::QuicOverWebSocket);
+        assert!(local.is_compatible_with(&remote));
+
+        let common = local.common(&remote);
+        assert_eq!(common.transports, vec![TransportMode::QuicOverWebSocket]);
+        assert!(!common.simulcast);
+        let video = common.codec(CodecKind::Video, "H264").unwrap();
+        assert_eq!((video.max_width, video.max_height), (Some(1280), Some(720)));
+        let audio = common.codec(CodecKind::Audio, "opus").unwrap();
+        assert_eq!(audio.max_channels, Some(2));
+
+        // No shared video codec
+        let vp8_only = CapabilitySet::from_names(&["vp8", "opus"]);
+        assert!(!local.can_exchange(&vp8_only, CodecKind::Video));
+        assert!(local.can_exchange(&vp8_only, CodecKind::Audio));
+        assert!(!local.is_compatible_with(&vp8_only));
+
+        // No shared transport
+        let relay_only = CapabilitySet::new().with_transport(TransportMode::WebRtcCompat);
+        assert!(!local.is_compatible_with(&relay_only));
+
+        // Requirements used by discovery
+        assert!(local.satisfies(&CapabilitySet::from_names(&["h264", "simulcast"])));
+        assert!(!local.satisfies(&CapabilitySet::from_names(&["h264", "e2ee"])));
+        assert!(!remote.satisfies(&CapabilitySet::new().with_transport(TransportMode::QuicNative)));
+
+        // Typed sets round-trip and plain name lists still decode
+        let json = serde_json::to_string(&local).unwrap();
+        assert_eq!(serde_json::from_str::<CapabilitySet>(&json).unwrap(), local);
+        let legacy: CapabilitySet = serde_json::from_str(r#"["opus","simulcast"]"#).unwrap();
+        assert_eq!(legacy.codec_names(CodecKind::Audio), vec!["opus"]);
+        assert!(legacy.simulcast);
+    }
+
+    #[test]
+    fn test_phone_number_validation() {
+        let number = PhoneNumber::parse("+1 (415) 555-0123").unwrap();
+        assert_eq!(number.as_str(), "+14155550123");
+        assert_eq!(number.masked(), "+*******0123");
+        assert_eq!(serde_json::to_string(&number).unwrap(), "\"+14155550123\"");
+
+        for invalid in [
+            "4155550123",
+            "+0123456",
+            "+1",
+            "+1234567890123456",
+            "+44 20 abcd",
+        ] {
+            assert!(PhoneNumber::parse(invalid).is_err(), "{}", invalid);
+            assert!(serde_json::from_str::<PhoneNumber>(&format!("\"{}\"", invalid)).is_err());
+        }
     }
 }
@@ -0,0 +1,132 @@
+//! Room lifetime policies
+//!
+//! A [`RoomLifetime`] lets the server close a room on its own: once it has
+//! been empty for a while, once it has been open for its maximum duration,
+//! or at a scheduled time. Scheduled rooms also refuse joins before they
+//! open. Participants get a `RoomClosing` notice [`RoomLifetime::closing_notice`]
+//! before a forced close, then `RoomClosed` with the [`RoomCloseReason`].
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+/// Why a room was closed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RoomCloseReason {
+    /// An administrator closed the room
+    #[default]
+    Closed,
+    /// The room stayed empty for its empty timeout
+    Empty,
+    /// The room reached its maximum duration
+    MaxDuration,
+    /// The room reached its scheduled closing time
+    Scheduled,
+}
+
+impl fmt::Display for RoomCloseReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RoomCloseReason::Closed => "closed by an administrator",
+            RoomCloseReason::Empty => "empty for too long",
+            RoomCloseReason::MaxDuration => "reached its maximum duration",
+            RoomCloseReason::Scheduled => "reached its scheduled closing time",
+        })
+    }
+}
+
+/// When a room closes on its own
+///
+/// The default never closes a room automatically.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoomLifetime {
+    /// Close the room once it has been empty this long
+    pub empty_timeout: Option<Duration>,
+    /// Close the room this long after it opened
+    pub max_duration: Option<Duration>,
+    /// Refuse joins before this time
+    pub opens_at: Option<DateTime<Utc>>,
+    /// Close the room at this time
+    pub closes_at: Option<DateTime<Utc>>,
+    /// How long before a forced close participants are warned
+    pub closing_notice: Duration,
+}
+
+impl Default for RoomLifetime {
+    fn default() -> Self {
+        Self {
+            empty_timeout: None,
+            max_duration: None,
+            opens_at: None,
+            closes_at: None,
+            closing_notice: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RoomLifetime {
+    /// Close the room once it has been empty for `timeout`
+    pub fn with_empty_timeout(mut self, timeout: Duration) -> Self {
+        self.empty_timeout = Some(timeout);
+        self
+    }
+
+    /// Close the room `duration` after it opened
+    pub fn with_max_duration(mut self, duration: Duration) -> Self {
+        self.max_duration = Some(duration);
+        self
+    }
+
+    /// Only let participants join from `opens_at`, and close the room at
+    /// `closes_at`
+    pub fn scheduled(
+        mut self,
+        opens_at: Option<DateTime<Utc>>,
+        closes_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        self.opens_at = opens_at;
+        self.closes_at = closes_at;
+        self
+    }
+
+    /// Warn participants `notice` before a forced close
+    pub fn with_closing_notice(mut self, notice: Duration) -> Self {
+        self.closing_notice = notice;
+        self
+    }
+
+    /// Whether participants may join at `now`
+    pub fn is_open(&self, now: DateTime<Utc>) -> bool {
+        self.opens_at.is_none_or(|opens_at| now >= opens_at)
+    }
+
+    /// When a room created at `created_at` must close and why, whether or
+    /// not anyone is in it
+    ///
+    /// The maximum duration counts from the scheduled opening, if later
+    /// than the creation.
+    pub fn deadline(&self, created_at: DateTime<Utc>) -> Option<(DateTime<Utc>, RoomCloseReason)> {
+        let opened_at = self
+            .opens_at
+            .map_or(created_at, |opens_at| opens_at.max(created_at));
+        let max_duration = self.max_duration.and_then(|duration| {
+            let duration = chrono::Duration::from_std(duration).ok()?;
+            Some((opened_at + duration, RoomCloseReason::MaxDuration))
+        });
+        let scheduled = self
+            .closes_at
+            .map(|closes_at| (closes_at, RoomCloseReason::Scheduled));
+
+        match (max_duration, scheduled) {
+            (Some(a), Some(b)) => Some(if b.0 <= a.0 { b } else { a }),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// When a room that became empty at `empty_since` closes for being empty
+    pub fn empty_deadline(&self, empty_since: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let timeout = chrono::Duration::from_std(self.empty_timeout?).ok()?;
+        Some(empty_since + timeout)
+    }
+}
@@ -1,8 +1,262 @@
 //! Signaling protocol messages
+//!
+//! Messages are JSON in WebSocket text frames by default. A client can send
+//! [`SignalingMessage::Hello`] to agree on a protocol version and switch the
+//! server's responses to CBOR in binary frames, which are smaller and cheaper
+//! to parse. The server always accepts either encoding, decided by frame type.
+//!
+//! The hello exchange also advertises optional [`ProtocolFeature`]s; both
+//! sides use only the features in the [`SignalingResponse::Welcome`], so
+//! newer and older peers fall back to what they have in common. A client
+//! talking to a server that predates the hello exchange gets an error back
+//! instead of a welcome; [`Negotiation::from_response`] maps that to the
+//! baseline protocol.
 
+use crate::capability::CapabilitySet;
+use crate::codec::{select_codecs, CodecCapability, CodecKind};
+use crate::lifetime::RoomCloseReason;
+use crate::schedule::RoomSchedule;
+use quicrtc_core::QuicRtcError;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::net::SocketAddr;
 
+/// Signaling protocol version implemented by this crate
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// MoQ transport version offered in [`MoqSessionOffer::moq_version`]
+pub const MOQ_VERSION: &str = "draft-ietf-moq-transport-13";
+
+/// Optional protocol features advertised during the hello exchange
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ProtocolFeature {
+    /// CBOR message encoding
+    BinaryEncoding,
+    /// End-to-end encrypted media
+    E2ee,
+    /// Simulcast publishing with layer selection
+    Simulcast,
+    /// Media over QUIC datagrams
+    Datagrams,
+}
+
+impl ProtocolFeature {
+    /// Name used on the wire
+    pub fn name(self) -> &'static str {
+        match self {
+            ProtocolFeature::BinaryEncoding => "binary_encoding",
+            ProtocolFeature::E2ee => "e2ee",
+            ProtocolFeature::Simulcast => "simulcast",
+            ProtocolFeature::Datagrams => "datagrams",
+        }
+    }
+
+    /// Parse a wire name; None for features this version doesn't know
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "binary_encoding" => Some(ProtocolFeature::BinaryEncoding),
+            "e2ee" => Some(ProtocolFeature::E2ee),
+            "simulcast" => Some(ProtocolFeature::Simulcast),
+            "datagrams" => Some(ProtocolFeature::Datagrams),
+            _ => None,
+        }
+    }
+}
+
+/// Set of protocol features
+///
+/// Serialized as a list of feature names. Names this version doesn't know
+/// are dropped when deserializing, so peers can advertise newer features
+/// without breaking older ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeatureSet(BTreeSet<ProtocolFeature>);
+
+impl FeatureSet {
+    /// No features
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every feature this version knows about
+    pub fn all() -> Self {
+        [
+            ProtocolFeature::BinaryEncoding,
+            ProtocolFeature::E2ee,
+            ProtocolFeature::Simulcast,
+            ProtocolFeature::Datagrams,
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    /// Add a feature
+    pub fn with(mut self, feature: ProtocolFeature) -> Self {
+        self.0.insert(feature);
+        self
+    }
+
+    /// Whether `feature` is in the set
+    pub fn contains(&self, feature: ProtocolFeature) -> bool {
+        self.0.contains(&feature)
+    }
+
+    /// Features in both sets
+    pub fn intersection(&self, other: &FeatureSet) -> FeatureSet {
+        self.0.intersection(&other.0).copied().collect()
+    }
+
+    /// Iterate over the features
+    pub fn iter(&self) -> impl Iterator<Item = ProtocolFeature> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+impl FromIterator<ProtocolFeature> for FeatureSet {
+    fn from_iter<I: IntoIterator<Item = ProtocolFeature>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Serialize for FeatureSet {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(ProtocolFeature::name))
+    }
+}
+
+impl<'de> Deserialize<'de> for FeatureSet {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let names = Vec::<String>::deserialize(deserializer)?;
+        Ok(names
+            .iter()
+            .filter_map(|name| ProtocolFeature::from_name(name))
+            .collect())
+    }
+}
+
+/// Outcome of the hello exchange as seen by a client
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Negotiation {
+    /// Protocol version both sides speak
+    pub protocol_version: u32,
+    /// Encoding the server sends
+    pub encoding: WireEncoding,
+    /// Features both sides support
+    pub features: FeatureSet,
+}
+
+impl Negotiation {
+    /// What to assume of a server that predates the hello exchange
+    pub fn baseline() -> Self {
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+            encoding: WireEncoding::Json,
+            features: FeatureSet::new(),
+        }
+    }
+
+    /// Interpret the server's reply to a hello
+    ///
+    /// Anything other than a welcome means the server doesn't understand
+    /// the exchange, and the client should stick to the baseline protocol.
+    pub fn from_response(response: &SignalingResponse) -> Self {
+        match response {
+            SignalingResponse::Welcome {
+                protocol_version,
+                encoding,
+                features,
+            } => Self {
+                protocol_version: *protocol_version,
+                encoding: *encoding,
+                features: features.clone(),
+            },
+            _ => Self::baseline(),
+        }
+    }
+
+    /// Whether both sides support `feature`
+    pub fn supports(&self, feature: ProtocolFeature) -> bool {
+        self.features.contains(feature)
+    }
+}
+
+/// Encoding of signaling messages on the wire
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WireEncoding {
+    /// JSON in text frames; readable, for debugging and browsers
+    #[default]
+    Json,
+    /// CBOR in binary frames; compact, for mobile clients
+    Cbor,
+}
+
+impl WireEncoding {
+    /// Serialize a message
+    pub fn encode<T: Serialize>(self, message: &T) -> Result<Vec<u8>, QuicRtcError> {
+        match self {
+            WireEncoding::Json => serde_json::to_vec(message).map_err(|e| e.to_string()),
+            WireEncoding::Cbor => {
+                let mut buf = Vec::new();
+                ciborium::into_writer(message, &mut buf)
+                    .map(|()| buf)
+                    .map_err(|e| e.to_string())
+            }
+        }
+        .map_err(|reason| QuicRtcError::InvalidData {
+            reason: format!("Failed to encode {:?} message: {}", self, reason),
+        })
+    }
+
+    /// Deserialize a message
+    pub fn decode<T: DeserializeOwned>(self, data: &[u8]) -> Result<T, QuicRtcError> {
+        let result: Result<T, Box<dyn std::error::Error + Send + Sync>> = match self {
+            WireEncoding::Json => serde_json::from_slice(data).map_err(Into::into),
+            WireEncoding::Cbor => ciborium::from_reader(data).map_err(Into::into),
+        };
+        result.map_err(|source| QuicRtcError::InvalidMessage {
+            message: match self {
+                WireEncoding::Json => String::from_utf8_lossy(data).into_owned(),
+                WireEncoding::Cbor => format!("<{} bytes of CBOR>", data.len()),
+            },
+            source,
+        })
+    }
+
+    /// Pick the encoding to use from those a client offers
+    ///
+    /// Prefers CBOR, falling back to JSON.
+    pub fn negotiate(offered: &[WireEncoding]) -> WireEncoding {
+        if offered.contains(&WireEncoding::Cbor) {
+            WireEncoding::Cbor
+        } else {
+            WireEncoding::Json
+        }
+    }
+}
+
+/// Publisher-set description of a track, announced with it in a session offer
+///
+/// Lets receivers label and lay out a track before any media arrives.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublishedTrack {
+    /// Canonical namespace of the track, as listed in `publish_namespaces`
+    pub namespace: String,
+    /// Human-readable label, e.g. "Front camera" or "Slides"
+    #[serde(default)]
+    pub label: Option<String>,
+    /// What produces the media, e.g. "camera", "screen" or "file"
+    pub source: String,
+    /// Initial frame width, for video
+    #[serde(default)]
+    pub width: Option<u32>,
+    /// Initial frame height, for video
+    #[serde(default)]
+    pub height: Option<u32>,
+    /// Codec the track is encoded with
+    #[serde(default)]
+    pub codec: Option<String>,
+}
+
 /// MoQ session offer for establishing peer connections
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MoqSessionOffer {
@@ -16,12 +270,32 @@ pub struct MoqSessionOffer {
     pub publish_namespaces: Vec<String>,
     /// Track namespaces this participant wants to subscribe to
     pub subscribe_namespaces: Vec<String>,
-    /// Additional capabilities
-    pub capabilities: Vec<String>,
+    /// What the offerer can receive and how it can connect
+    #[serde(default)]
+    pub capabilities: CapabilitySet,
+    /// Description of each published track
+    #[serde(default)]
+    pub tracks: Vec<PublishedTrack>,
     /// Session ID for correlation
     pub session_id: String,
 }
 
+impl MoqSessionOffer {
+    /// Description of the published track with this canonical namespace
+    pub fn track(&self, namespace: &str) -> Option<&PublishedTrack> {
+        self.tracks
+            .iter()
+            .find(|track| track.namespace == namespace)
+    }
+
+    /// Codecs to put in the answer: the best mutual codec per media kind
+    ///
+    /// `local` is the answerer's own codecs, most preferred first.
+    pub fn answer_codecs(&self, local: &[CodecCapability]) -> Vec<CodecCapability> {
+        select_codecs(local, &self.capabilities.codecs)
+    }
+}
+
 /// MoQ session answer responding to an offer
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MoqSessionAnswer {
@@ -39,11 +313,31 @@ pub struct MoqSessionAnswer {
     pub session_id: String,
     /// Whether the session is accepted
     pub accepted: bool,
+    /// Codec chosen for each media kind, see [`MoqSessionOffer::answer_codecs`]
+    #[serde(default)]
+    pub codecs: Vec<CodecCapability>,
+}
+
+impl MoqSessionAnswer {
+    /// Codec chosen for a media kind
+    pub fn codec(&self, kind: CodecKind) -> Option<&CodecCapability> {
+        self.codecs.iter().find(|codec| codec.kind == kind)
+    }
 }
 
 /// Signaling protocol messages for MoQ session negotiation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SignalingMessage {
+    /// Negotiate protocol version and encoding; optional, sent first
+    Hello {
+        /// Highest protocol version the client speaks
+        protocol_version: u32,
+        /// Encodings the client can receive, in any order
+        encodings: Vec<WireEncoding>,
+        /// Optional features the client supports
+        #[serde(default)]
+        features: FeatureSet,
+    },
     /// Join room request
     JoinRoom {
         /// Room ID
@@ -52,10 +346,14 @@ pub enum SignalingMessage {
         participant_id: String,
         /// Optional participant display name
         participant_name: Option<String>,
-        /// MoQ capabilities
-        capabilities: Vec<String>,
+        /// What the participant can receive and how it can connect
+        #[serde(default)]
+        capabilities: CapabilitySet,
         /// QUIC endpoint for direct connections
         quic_endpoint: Option<SocketAddr>,
+        /// Access token, required when the server verifies tokens
+        #[serde(default)]
+        access_token: Option<String>,
     },
     /// Leave room request
     LeaveRoom {
@@ -72,6 +370,12 @@ pub enum SignalingMessage {
         room_name: Option<String>,
         /// Maximum participants allowed
         max_participants: Option<usize>,
+        /// Access token, required when the server verifies tokens
+        #[serde(default)]
+        access_token: Option<String>,
+        /// Host and start time, for scheduled rooms
+        #[serde(default)]
+        schedule: Option<RoomSchedule>,
     },
     /// MoQ session offer to establish direct peer connection
     MoqSessionOffer {
@@ -98,11 +402,50 @@ pub enum SignalingMessage {
         /// Room ID to get info for
         room_id: String,
     },
+    /// Presence ping showing the connection's participants are still there
+    ///
+    /// Once a connection sends one, the server expects them regularly and
+    /// removes its participants when they stop, see
+    /// [`SignalingServer::with_presence_timeout`](crate::SignalingServer::with_presence_timeout).
+    Heartbeat,
+    /// Answer a recording or transcription consent request
+    CaptureConsent {
+        /// Room ID
+        room_id: String,
+        /// ID of the request being answered
+        request_id: String,
+        /// Whether the participant agrees to be captured
+        accepted: bool,
+    },
+}
+
+/// Why a participant left a room
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LeaveReason {
+    /// The participant asked to leave
+    #[default]
+    Left,
+    /// The participant's signaling connection closed
+    Disconnected,
+    /// An administrator removed the participant
+    Kicked,
+    /// The participant stopped sending heartbeats
+    TimedOut,
 }
 
 /// Server response messages
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SignalingResponse {
+    /// Negotiation result; later responses use `encoding`
+    Welcome {
+        /// Protocol version used for the rest of the session
+        protocol_version: u32,
+        /// Encoding the server will send from now on
+        encoding: WireEncoding,
+        /// Features both sides support
+        #[serde(default)]
+        features: FeatureSet,
+    },
     /// Successfully joined room
     JoinedRoom {
         /// Room ID
@@ -110,6 +453,18 @@ pub enum SignalingResponse {
         /// Participant ID
         participant_id: String,
     },
+    /// Joined the room's lobby; `JoinedRoom` follows once let in, see
+    /// [`RoomSchedule`]
+    WaitingForHost {
+        /// Room ID
+        room_id: String,
+        /// Participant ID
+        participant_id: String,
+        /// Participant ID of the host, if the room has one
+        host_id: Option<String>,
+        /// When the meeting is due to start
+        starts_at: Option<chrono::DateTime<chrono::Utc>>,
+    },
     /// Successfully left room
     LeftRoom {
         /// Room ID
@@ -135,6 +490,9 @@ pub enum SignalingResponse {
         room_id: String,
         /// Participant ID that left
         participant_id: String,
+        /// Why the participant left
+        #[serde(default)]
+        reason: LeaveReason,
     },
     /// MoQ session offer forwarded from another participant
     MoqSessionOffer {
@@ -172,6 +530,73 @@ pub enum SignalingResponse {
         /// Maximum participants allowed
         max_participants: usize,
     },
+    /// The receiving participant was removed from a room by an administrator
+    Kicked {
+        /// Room ID
+        room_id: String,
+        /// Reason given by the administrator
+        reason: Option<String>,
+    },
+    /// The room was closed
+    RoomClosed {
+        /// Room ID
+        room_id: String,
+        /// Why the room was closed
+        #[serde(default)]
+        reason: RoomCloseReason,
+    },
+    /// The room will be closed at `closes_at`, see [`RoomLifetime`](crate::RoomLifetime)
+    RoomClosing {
+        /// Room ID
+        room_id: String,
+        /// When the room closes
+        closes_at: chrono::DateTime<chrono::Utc>,
+        /// Why the room is closing
+        reason: RoomCloseReason,
+    },
+    /// Recording started in the room
+    RecordingStarted {
+        /// Room ID
+        room_id: String,
+        /// Recording details
+        recording: crate::server::RoomRecording,
+    },
+    /// Recording stopped in the room
+    RecordingStopped {
+        /// Room ID
+        room_id: String,
+        /// ID of the recording that stopped
+        recording_id: String,
+    },
+    /// The room is about to start recording or transcribing
+    ///
+    /// Sent to every participant before capture starts. When
+    /// `requires_acknowledgement` is set the capture only starts once every
+    /// participant accepts with [`SignalingMessage::CaptureConsent`].
+    CaptureConsentRequested {
+        /// Room ID
+        room_id: String,
+        /// ID to echo back in the answer
+        request_id: String,
+        /// What the room is about to capture
+        kind: crate::consent::CaptureKind,
+        /// Whether capture waits for the participant's answer
+        requires_acknowledgement: bool,
+    },
+    /// Transcription started in the room
+    TranscriptionStarted {
+        /// Room ID
+        room_id: String,
+        /// Transcription details
+        transcription: crate::server::RoomTranscription,
+    },
+    /// Transcription stopped in the room
+    TranscriptionStopped {
+        /// Room ID
+        room_id: String,
+        /// ID of the transcription that stopped
+        transcription_id: String,
+    },
     /// Error response
     Error {
         /// Error message
@@ -0,0 +1,97 @@
+//! Per-connection flood protection for signaling messages
+//!
+//! Each connection gets a token bucket. Messages within the burst are
+//! handled immediately; beyond it the server stops reading from the
+//! connection until the bucket refills, so a flooding client is slowed to
+//! the configured rate by TCP backpressure instead of consuming server time.
+//! Clients that stay over the limit for too long are disconnected.
+
+use std::time::{Duration, Instant};
+
+/// Flood protection limits applied to every signaling connection
+#[derive(Debug, Clone)]
+pub struct FloodProtectionConfig {
+    /// Largest accepted WebSocket message in bytes
+    pub max_message_size: usize,
+    /// Sustained messages per second allowed per connection
+    pub messages_per_second: f64,
+    /// Messages a connection may send back-to-back before being throttled
+    pub burst: u32,
+    /// Consecutive throttled messages after which the connection is closed
+    pub max_consecutive_throttled: u32,
+    /// Responses queued for a connection that is not reading them after
+    /// which it is closed, so slow clients cannot stall room broadcasts
+    pub max_queued_responses: usize,
+}
+
+impl Default for FloodProtectionConfig {
+    fn default() -> Self {
+        Self {
+            max_message_size: 64 * 1024,
+            messages_per_second: 20.0,
+            burst: 50,
+            max_consecutive_throttled: 200,
+            max_queued_responses: 256,
+        }
+    }
+}
+
+/// Outcome of admitting one message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Admission {
+    /// Handle the message now
+    Allowed,
+    /// Wait this long before handling the message
+    Throttled(Duration),
+    /// Close the connection
+    Disconnect,
+}
+
+/// Token bucket tracking one connection's message rate
+#[derive(Debug)]
+pub(crate) struct MessageRateLimiter {
+    rate: f64,
+    burst: f64,
+    max_consecutive_throttled: u32,
+    tokens: f64,
+    last_refill: Instant,
+    consecutive_throttled: u32,
+}
+
+impl MessageRateLimiter {
+    pub(crate) fn new(config: &FloodProtectionConfig) -> Self {
+        let burst = f64::from(config.burst.max(1));
+        Self {
+            rate: config.messages_per_second.max(f64::MIN_POSITIVE),
+            burst,
+            max_consecutive_throttled: config.max_consecutive_throttled,
+            tokens: burst,
+            last_refill: Instant::now(),
+            consecutive_throttled: 0,
+        }
+    }
+
+    /// Take a token for a message received at `now`
+    ///
+    /// A throttled message still consumes its token, so the caller must wait
+    /// the returned delay before reading the next one.
+    pub(crate) fn admit(&mut self, now: Instant) -> Admission {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+        self.last_refill = now;
+        self.tokens -= 1.0;
+
+        if self.tokens >= 0.0 {
+            self.consecutive_throttled = 0;
+            return Admission::Allowed;
+        }
+
+        self.consecutive_throttled += 1;
+        if self.consecutive_throttled > self.max_consecutive_throttled {
+            return Admission::Disconnect;
+        }
+        Admission::Throttled(Duration::from_secs_f64(-self.tokens / self.rate))
+    }
+}
@@ -0,0 +1,67 @@
+//! Scheduled rooms and the host lobby
+//!
+//! A [`RoomSchedule`] names the room's host and when the meeting starts.
+//! Unless joining before the host is allowed, participants who arrive while
+//! the host is away wait in the room's lobby and are told with
+//! `WaitingForHost`. The lobby is let in when the host joins or, for rooms
+//! without a host, once the start time passes. Participants who are let in
+//! get the usual `JoinedRoom`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Scheduling metadata for a room
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoomSchedule {
+    /// When the meeting is due to start
+    #[serde(default)]
+    pub starts_at: Option<DateTime<Utc>>,
+    /// Participant ID of the host
+    #[serde(default)]
+    pub host_id: Option<String>,
+    /// Let participants in while the host is away
+    #[serde(default)]
+    pub allow_join_before_host: bool,
+}
+
+impl RoomSchedule {
+    /// Schedule hosted by `host_id`
+    pub fn hosted_by(host_id: impl Into<String>) -> Self {
+        Self {
+            host_id: Some(host_id.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Set when the meeting is due to start
+    pub fn starting_at(mut self, starts_at: DateTime<Utc>) -> Self {
+        self.starts_at = Some(starts_at);
+        self
+    }
+
+    /// Set whether participants may join while the host is away
+    pub fn with_join_before_host(mut self, allow: bool) -> Self {
+        self.allow_join_before_host = allow;
+        self
+    }
+
+    /// Whether `participant_id` is the host
+    pub fn is_host(&self, participant_id: &str) -> bool {
+        self.host_id.as_deref() == Some(participant_id)
+    }
+
+    /// Whether `participant_id` may enter the room at `now`, rather than
+    /// wait in the lobby
+    ///
+    /// Rooms with a host admit the host, and everyone else while the host
+    /// is present. Rooms without one admit everyone from the start time.
+    pub fn admits(&self, participant_id: &str, host_present: bool, now: DateTime<Utc>) -> bool {
+        if self.allow_join_before_host || self.is_host(participant_id) {
+            return true;
+        }
+        match &self.host_id {
+            Some(_) => host_present,
+            None => self.starts_at.is_none_or(|starts_at| now >= starts_at),
+        }
+    }
+}
@@ -1,17 +1,30 @@
 //! Signaling server implementation
 
-use crate::protocol::{MoqSessionAnswer, MoqSessionOffer, SignalingMessage, SignalingResponse};
+use crate::capability::CapabilitySet;
+use crate::consent::{await_consent, CaptureKind, ConsentPolicy, ConsentUpdate, ConsentWaiter};
+use crate::lifetime::{RoomCloseReason, RoomLifetime};
+use crate::protocol::{
+    FeatureSet, LeaveReason, MoqSessionAnswer, MoqSessionOffer, ProtocolFeature, SignalingMessage,
+    SignalingResponse, WireEncoding, PROTOCOL_VERSION,
+};
+use crate::rate_limit::{Admission, FloodProtectionConfig, MessageRateLimiter};
+use crate::schedule::RoomSchedule;
+use crate::token::{TokenClaims, TokenVerifier};
+use crate::webhook::{WebhookConfig, WebhookDispatcher, WebhookEventKind};
 use dashmap::DashMap;
+use futures::stream::{SplitSink, SplitStream};
 use futures::{SinkExt, StreamExt};
 use quicrtc_core::QuicRtcError;
+use quicrtc_diagnostics::SignalingMetrics;
 use serde::{Deserialize, Serialize};
-use serde_json;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::RwLock;
-use tokio_tungstenite::{accept_async, tungstenite::Message, WebSocketStream};
+use tokio::sync::{mpsc, watch, RwLock};
+use tokio_tungstenite::tungstenite::{self, protocol::WebSocketConfig, Message};
+use tokio_tungstenite::{accept_async_with_config, WebSocketStream};
 use uuid::Uuid;
 
 /// Participant information in a room
@@ -23,8 +36,9 @@ pub struct Participant {
     pub name: Option<String>,
     /// WebSocket connection for signaling
     pub connection_id: String,
-    /// MoQ session capabilities
-    pub capabilities: Vec<String>,
+    /// What the participant can receive and how it can connect
+    #[serde(default)]
+    pub capabilities: CapabilitySet,
     /// QUIC endpoint address for direct connection
     pub quic_endpoint: Option<SocketAddr>,
 }
@@ -44,23 +58,72 @@ pub struct Room {
     pub max_participants: usize,
     /// Room metadata
     pub metadata: HashMap<String, String>,
+    /// Recording in progress, if any
+    pub recording: Option<RoomRecording>,
+    /// Transcription in progress, if any
+    pub transcription: Option<RoomTranscription>,
+    /// Rules for starting a recording or transcription
+    pub consent_policy: ConsentPolicy,
+    /// When the room closes on its own
+    pub lifetime: RoomLifetime,
+    /// When the room last became empty; `None` while anyone is in it
+    pub empty_since: Option<chrono::DateTime<chrono::Utc>>,
+    /// Closing time participants were last warned about
+    closing_announced: Option<chrono::DateTime<chrono::Utc>>,
+    /// Host and start time, for scheduled rooms
+    pub schedule: Option<RoomSchedule>,
+    /// Participants waiting for the host, in arrival order
+    pub lobby: Vec<Participant>,
+}
+
+/// Recording started on a room
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomRecording {
+    /// Unique recording ID
+    pub recording_id: String,
+    /// Where the recorder should write the output, if specified
+    pub output: Option<String>,
+    /// Recording start timestamp
+    pub started_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Transcription started on a room
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomTranscription {
+    /// Unique transcription ID
+    pub transcription_id: String,
+    /// Spoken language hint for the transcriber, if specified
+    pub language: Option<String>,
+    /// Transcription start timestamp
+    pub started_at: chrono::DateTime<chrono::Utc>,
 }
 
 impl Room {
     /// Create a new room
     pub fn new(id: String, name: Option<String>) -> Self {
+        let now = chrono::Utc::now();
         Self {
             id,
             name,
             participants: HashMap::new(),
-            created_at: chrono::Utc::now(),
+            created_at: now,
             max_participants: 100, // Default limit
             metadata: HashMap::new(),
+            recording: None,
+            transcription: None,
+            consent_policy: ConsentPolicy::default(),
+            lifetime: RoomLifetime::default(),
+            empty_since: Some(now),
+            closing_announced: None,
+            schedule: None,
+            lobby: Vec::new(),
         }
     }
 
     /// Add a participant to the room
     pub fn add_participant(&mut self, participant: Participant) -> Result<(), QuicRtcError> {
+        self.check_open()?;
+
         if self.participants.len() >= self.max_participants {
             return Err(QuicRtcError::RoomFull {
                 room_id: self.id.clone(),
@@ -77,12 +140,101 @@ impl Room {
 
         self.participants
             .insert(participant.id.clone(), participant);
+        self.empty_since = None;
         Ok(())
     }
 
     /// Remove a participant from the room
     pub fn remove_participant(&mut self, participant_id: &str) -> Option<Participant> {
-        self.participants.remove(participant_id)
+        let participant = self.participants.remove(participant_id)?;
+        self.note_if_empty();
+        Some(participant)
+    }
+
+    /// Whether `participant_id` may enter now rather than wait in the lobby
+    pub fn admits(&self, participant_id: &str, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.schedule.as_ref().is_none_or(|schedule| {
+            let host_present = schedule
+                .host_id
+                .as_ref()
+                .is_some_and(|host_id| self.participants.contains_key(host_id));
+            schedule.admits(participant_id, host_present, now)
+        })
+    }
+
+    /// Put a participant in the lobby until the room lets them in
+    pub fn enter_lobby(&mut self, participant: Participant) -> Result<(), QuicRtcError> {
+        self.check_open()?;
+
+        let waiting = self.lobby.iter().any(|p| p.id == participant.id);
+        if waiting || self.participants.contains_key(&participant.id) {
+            return Err(QuicRtcError::ParticipantAlreadyExists {
+                room_id: self.id.clone(),
+                participant_id: participant.id,
+            });
+        }
+
+        self.lobby.push(participant);
+        self.empty_since = None;
+        Ok(())
+    }
+
+    /// Take a participant out of the lobby
+    pub fn leave_lobby(&mut self, participant_id: &str) -> Option<Participant> {
+        let index = self.lobby.iter().position(|p| p.id == participant_id)?;
+        let participant = self.lobby.remove(index);
+        self.note_if_empty();
+        Some(participant)
+    }
+
+    /// Move lobby participants the room now admits into the room
+    ///
+    /// Participants who do not fit stay in the lobby. Returns those let in.
+    pub fn admit_lobby(&mut self, now: chrono::DateTime<chrono::Utc>) -> Vec<Participant> {
+        let mut admitted = Vec::new();
+        for participant in std::mem::take(&mut self.lobby) {
+            if self.admits(&participant.id, now)
+                && self.add_participant(participant.clone()).is_ok()
+            {
+                admitted.push(participant);
+            } else {
+                self.lobby.push(participant);
+            }
+        }
+        admitted
+    }
+
+    fn check_open(&self) -> Result<(), QuicRtcError> {
+        match self.lifetime.opens_at {
+            Some(opens_at) if !self.lifetime.is_open(chrono::Utc::now()) => {
+                Err(QuicRtcError::RoomNotOpen {
+                    room_id: self.id.clone(),
+                    opens_at: opens_at.to_rfc3339(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn note_if_empty(&mut self) {
+        if self.participants.is_empty() && self.lobby.is_empty() {
+            self.empty_since = Some(chrono::Utc::now());
+        }
+    }
+
+    /// When the room closes and why, if its lifetime sets a time
+    ///
+    /// The empty timeout only counts while the room is empty.
+    pub fn closes_at(&self) -> Option<(chrono::DateTime<chrono::Utc>, RoomCloseReason)> {
+        let deadline = self.lifetime.deadline(self.created_at);
+        let empty = self
+            .empty_since
+            .and_then(|since| self.lifetime.empty_deadline(since))
+            .map(|at| (at, RoomCloseReason::Empty));
+        match (deadline, empty) {
+            (Some(a), Some(b)) => Some(if b.0 < a.0 { b } else { a }),
+            (a, b) => a.or(b),
+        }
     }
 
     /// Get participant by ID
@@ -102,8 +254,23 @@ impl Room {
 /// WebSocket connection wrapper
 type WebSocketConnection = WebSocketStream<TcpStream>;
 
+/// Queue of messages for a connection's writer task
+///
+/// Dropping the last sender makes the writer flush what is queued and close
+/// the connection.
+type ConnectionSender = mpsc::Sender<Message>;
+
 /// Active WebSocket connections mapped by connection ID
-type Connections = Arc<DashMap<String, WebSocketConnection>>;
+type Connections = Arc<DashMap<String, ConnectionSender>>;
+
+/// Longest a single write may take before the client is considered gone
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default time without heartbeats after which participants are removed
+const DEFAULT_PRESENCE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// How often room lifetimes and lobbies are checked
+const ROOM_SWEEP: Duration = Duration::from_millis(500);
 
 /// Signaling server for peer discovery and room management
 #[derive(Debug, Clone)]
@@ -113,6 +280,19 @@ pub struct SignalingServer {
     rooms: Arc<RwLock<HashMap<String, Room>>>,
     connections: Connections,
     participant_to_connection: Arc<DashMap<String, String>>,
+    connection_encodings: Arc<DashMap<String, WireEncoding>>,
+    webhooks: Option<WebhookDispatcher>,
+    token_verifier: Option<Arc<TokenVerifier>>,
+    flood_protection: FloodProtectionConfig,
+    features: FeatureSet,
+    metrics: SignalingMetrics,
+    consent_policy: ConsentPolicy,
+    room_lifetime: RoomLifetime,
+    consent_requests: Arc<DashMap<String, ConsentWaiter>>,
+    shutdown: Arc<watch::Sender<bool>>,
+    /// Last message from each connection that sends heartbeats
+    last_heartbeat: Arc<DashMap<String, Instant>>,
+    presence_timeout: Duration,
 }
 
 impl SignalingServer {
@@ -123,7 +303,145 @@ impl SignalingServer {
             rooms: Arc::new(RwLock::new(HashMap::new())),
             connections: Arc::new(DashMap::new()),
             participant_to_connection: Arc::new(DashMap::new()),
+            connection_encodings: Arc::new(DashMap::new()),
+            webhooks: None,
+            token_verifier: None,
+            flood_protection: FloodProtectionConfig::default(),
+            features: FeatureSet::new().with(ProtocolFeature::BinaryEncoding),
+            metrics: SignalingMetrics::new(),
+            consent_policy: ConsentPolicy::default(),
+            room_lifetime: RoomLifetime::default(),
+            consent_requests: Arc::new(DashMap::new()),
+            shutdown: Arc::new(watch::Sender::new(false)),
+            last_heartbeat: Arc::new(DashMap::new()),
+            presence_timeout: DEFAULT_PRESENCE_TIMEOUT,
+        }
+    }
+
+    /// Set the optional features advertised to clients
+    ///
+    /// Defaults to binary encoding only; enable media features such as
+    /// simulcast once the deployment's media path supports them.
+    pub fn with_features(mut self, features: FeatureSet) -> Self {
+        self.features = features;
+        self
+    }
+
+    /// Optional features advertised to clients
+    pub fn features(&self) -> &FeatureSet {
+        &self.features
+    }
+
+    /// Replace the default per-connection message size and rate limits
+    pub fn with_flood_protection(mut self, config: FloodProtectionConfig) -> Self {
+        self.flood_protection = config;
+        self
+    }
+
+    /// Remove participants whose connection sent heartbeats and then went
+    /// quiet for `timeout`
+    ///
+    /// Connections that never send [`SignalingMessage::Heartbeat`] are left
+    /// alone. Stale participants are only found while [`serve`](Self::serve)
+    /// runs. Defaults to 90 seconds.
+    pub fn with_presence_timeout(mut self, timeout: Duration) -> Self {
+        self.presence_timeout = timeout;
+        self
+    }
+
+    /// Set the consent policy given to newly created rooms
+    pub fn with_consent_policy(mut self, policy: ConsentPolicy) -> Self {
+        self.consent_policy = policy;
+        self
+    }
+
+    /// Set the lifetime given to newly created rooms
+    ///
+    /// Rooms only close on their own while [`serve`](Self::serve) runs.
+    pub fn with_room_lifetime(mut self, lifetime: RoomLifetime) -> Self {
+        self.room_lifetime = lifetime;
+        self
+    }
+
+    /// Message and flood protection counters
+    pub fn metrics(&self) -> &SignalingMetrics {
+        &self.metrics
+    }
+
+    /// Require a valid access token to create or join rooms
+    pub fn with_token_verifier(mut self, verifier: TokenVerifier) -> Self {
+        self.token_verifier = Some(Arc::new(verifier));
+        self
+    }
+
+    /// Check that `access_token` lets `participant_id` join `room_id`
+    ///
+    /// Always succeeds, returning `None`, when no verifier is configured.
+    pub fn authorize_join(
+        &self,
+        access_token: Option<&str>,
+        room_id: &str,
+        participant_id: &str,
+    ) -> Result<Option<TokenClaims>, QuicRtcError> {
+        let Some(claims) = self.verify_token(access_token)? else {
+            return Ok(None);
+        };
+        if claims.sub != participant_id {
+            return Err(QuicRtcError::Unauthorized {
+                reason: format!("Access token was issued to {}", claims.sub),
+            });
+        }
+        if !claims.grants.allows_join(room_id) {
+            return Err(QuicRtcError::Unauthorized {
+                reason: format!("Access token does not grant joining room {}", room_id),
+            });
+        }
+        Ok(Some(claims))
+    }
+
+    /// Check that `access_token` lets its holder create `room_id`
+    ///
+    /// Always succeeds, returning `None`, when no verifier is configured.
+    pub fn authorize_create(
+        &self,
+        access_token: Option<&str>,
+        room_id: &str,
+    ) -> Result<Option<TokenClaims>, QuicRtcError> {
+        let Some(claims) = self.verify_token(access_token)? else {
+            return Ok(None);
+        };
+        if !claims.grants.allows_create(room_id) {
+            return Err(QuicRtcError::Unauthorized {
+                reason: format!("Access token does not grant creating room {}", room_id),
+            });
         }
+        Ok(Some(claims))
+    }
+
+    fn verify_token(
+        &self,
+        access_token: Option<&str>,
+    ) -> Result<Option<TokenClaims>, QuicRtcError> {
+        let Some(verifier) = &self.token_verifier else {
+            return Ok(None);
+        };
+        let token = access_token.ok_or_else(|| QuicRtcError::Unauthorized {
+            reason: "Access token required".to_string(),
+        })?;
+        verifier.verify(token).map(Some)
+    }
+
+    /// Deliver room lifecycle events to webhooks
+    ///
+    /// Starts the delivery task, so must be called within a Tokio runtime.
+    pub fn with_webhooks(mut self, config: WebhookConfig) -> Self {
+        self.webhooks = Some(WebhookDispatcher::new(config));
+        self
+    }
+
+    /// Get the webhook dispatcher, if webhooks are configured
+    pub fn webhooks(&self) -> Option<&WebhookDispatcher> {
+        self.webhooks.as_ref()
     }
 
     /// Start the signaling server
@@ -134,25 +452,60 @@ impl SignalingServer {
                 source: e.into(),
             }
         })?;
+        self.serve(listener).await
+    }
 
-        tracing::info!("Signaling server listening on {}", self.bind_addr);
+    /// Accept connections on an already bound listener until [`stop`](Self::stop)
+    ///
+    /// Each connection is served by its own task.
+    pub async fn serve(&self, listener: TcpListener) -> Result<(), QuicRtcError> {
+        let address = listener.local_addr().unwrap_or(self.bind_addr);
+        let mut shutdown = self.shutdown.subscribe();
+        self.shutdown.send_replace(false);
+        shutdown.mark_unchanged();
+        let mut presence_sweep =
+            tokio::time::interval((self.presence_timeout / 2).max(Duration::from_millis(1)));
+        presence_sweep.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut room_sweep = tokio::time::interval(ROOM_SWEEP);
+        room_sweep.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        tracing::info!("Signaling server listening on {}", address);
 
         loop {
-            match listener.accept().await {
-                Ok((stream, addr)) => {
-                    tracing::debug!("New connection from {}", addr);
-                    self.handle_connection(stream).await;
-                }
-                Err(e) => {
-                    tracing::error!("Failed to accept connection: {}", e);
+            tokio::select! {
+                _ = presence_sweep.tick() => self.evict_stale_connections().await,
+                _ = room_sweep.tick() => {
+                    self.enforce_room_lifetimes().await;
+                    self.admit_lobbies().await;
                 }
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, addr)) => {
+                        tracing::debug!("New connection from {}", addr);
+                        let server = self.clone();
+                        tokio::spawn(async move { server.handle_connection(stream).await });
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to accept connection: {}", e);
+                    }
+                },
+                // The returned guard is not Send, so drop it inside the block
+                _ = async { drop(shutdown.wait_for(|stopped| *stopped).await) } => break,
             }
         }
+
+        tracing::info!("Signaling server no longer listening on {}", address);
+        Ok(())
     }
 
     /// Handle incoming WebSocket connection
     async fn handle_connection(&self, stream: TcpStream) {
-        let ws_stream = match accept_async(stream).await {
+        let ws_config = WebSocketConfig {
+            max_message_size: Some(self.flood_protection.max_message_size),
+            max_frame_size: Some(self.flood_protection.max_message_size),
+            ..WebSocketConfig::default()
+        };
+
+        let ws_stream = match accept_async_with_config(stream, Some(ws_config)).await {
             Ok(ws) => ws,
             Err(e) => {
                 tracing::error!("WebSocket handshake failed: {}", e);
@@ -162,51 +515,103 @@ impl SignalingServer {
 
         let connection_id = Uuid::new_v4().to_string();
         tracing::debug!("WebSocket connection established: {}", connection_id);
+        self.metrics.record_connection_accepted();
 
-        // Store connection
-        self.connections.insert(connection_id.clone(), ws_stream);
+        // A writer task owns the sending half; this task keeps reading the other
+        let (sink, stream) = ws_stream.split();
+        let (sender, queue) = mpsc::channel(self.flood_protection.max_queued_responses.max(1));
+        self.connections.insert(connection_id.clone(), sender);
+        let mut writer = tokio::spawn(write_connection(connection_id.clone(), sink, queue));
 
-        // Handle messages for this connection
-        if let Err(e) = self.handle_messages(connection_id.clone()).await {
-            tracing::error!("Connection {} error: {}", connection_id, e);
+        // Handle messages until the client leaves or the writer closes the
+        // connection
+        tokio::select! {
+            result = self.handle_messages(connection_id.clone(), stream) => {
+                if let Err(e) = result {
+                    tracing::error!("Connection {} error: {}", connection_id, e);
+                }
+            }
+            _ = &mut writer => {}
         }
 
-        // Cleanup on disconnect
+        // Cleanup on disconnect; the writer flushes queued responses first
         self.cleanup_connection(&connection_id).await;
     }
 
     /// Handle messages from a WebSocket connection
-    async fn handle_messages(&self, connection_id: String) -> Result<(), QuicRtcError> {
-        while let Some(mut connection) = self.connections.get_mut(&connection_id) {
-            match connection.next().await {
-                Some(Ok(Message::Text(text))) => {
-                    match serde_json::from_str::<SignalingMessage>(&text) {
-                        Ok(message) => {
-                            if let Err(e) = self
-                                .handle_signaling_message(connection_id.clone(), message)
-                                .await
-                            {
-                                tracing::error!("Failed to handle message: {}", e);
-                                self.send_error(&connection_id, e).await;
-                            }
-                        }
-                        Err(e) => {
-                            tracing::warn!("Invalid message format: {}", e);
-                            self.send_error(
-                                &connection_id,
-                                QuicRtcError::InvalidMessage {
-                                    message: text,
-                                    source: e.into(),
-                                },
-                            )
-                            .await;
-                        }
+    async fn handle_messages(
+        &self,
+        connection_id: String,
+        mut stream: SplitStream<WebSocketConnection>,
+    ) -> Result<(), QuicRtcError> {
+        let mut rate_limiter = MessageRateLimiter::new(&self.flood_protection);
+
+        loop {
+            if !self.connections.contains_key(&connection_id) {
+                break;
+            }
+            let next = stream.next().await;
+
+            if let Some(Ok(Message::Text(_) | Message::Binary(_))) = &next {
+                self.metrics.record_message_received();
+                if let Some(mut heard) = self.last_heartbeat.get_mut(&connection_id) {
+                    *heard = Instant::now();
+                }
+                match rate_limiter.admit(Instant::now()) {
+                    Admission::Allowed => {}
+                    Admission::Throttled(wait) => {
+                        // Not reading while we wait pushes back on the client
+                        self.metrics.record_message_throttled();
+                        tokio::time::sleep(wait).await;
                     }
+                    Admission::Disconnect => {
+                        self.metrics.record_connection_rate_limited();
+                        tracing::warn!(
+                            "Closing connection {}: sustained message flood",
+                            connection_id
+                        );
+                        let refill = f64::from(self.flood_protection.burst)
+                            / self.flood_protection.messages_per_second;
+                        self.send_error(
+                            &connection_id,
+                            QuicRtcError::RateLimited {
+                                retry_after: Duration::from_secs_f64(refill),
+                            },
+                        )
+                        .await;
+                        break;
+                    }
+                }
+            }
+
+            match next {
+                Some(Ok(Message::Text(text))) => {
+                    self.handle_frame(&connection_id, WireEncoding::Json, text.as_bytes())
+                        .await;
+                }
+                Some(Ok(Message::Binary(data))) => {
+                    self.handle_frame(&connection_id, WireEncoding::Cbor, &data)
+                        .await;
                 }
                 Some(Ok(Message::Close(_))) => {
                     tracing::debug!("Connection {} closed", connection_id);
                     break;
                 }
+                Some(Err(tungstenite::Error::Capacity(e))) => {
+                    tracing::warn!("Closing connection {}: {}", connection_id, e);
+                    self.metrics.record_message_oversized();
+                    self.send_error(
+                        &connection_id,
+                        QuicRtcError::ResourceLimit {
+                            resource: format!(
+                                "message size (max {} bytes)",
+                                self.flood_protection.max_message_size
+                            ),
+                        },
+                    )
+                    .await;
+                    break;
+                }
                 Some(Err(e)) => {
                     tracing::error!("WebSocket error on connection {}: {}", connection_id, e);
                     break;
@@ -216,13 +621,33 @@ impl SignalingServer {
                     break;
                 }
                 _ => {
-                    // Ignore other message types (Binary, Ping, Pong)
+                    // Ignore other message types (Ping, Pong)
                 }
             }
         }
         Ok(())
     }
 
+    /// Decode and handle one data frame
+    async fn handle_frame(&self, connection_id: &str, encoding: WireEncoding, data: &[u8]) {
+        match encoding.decode::<SignalingMessage>(data) {
+            Ok(message) => {
+                if let Err(e) = self
+                    .handle_signaling_message(connection_id.to_string(), message)
+                    .await
+                {
+                    tracing::error!("Failed to handle message: {}", e);
+                    self.send_error(connection_id, e).await;
+                }
+            }
+            Err(e) => {
+                tracing::warn!("Invalid message format: {}", e);
+                self.metrics.record_message_invalid();
+                self.send_error(connection_id, e).await;
+            }
+        }
+    }
+
     /// Handle a signaling message
     async fn handle_signaling_message(
         &self,
@@ -230,13 +655,23 @@ impl SignalingServer {
         message: SignalingMessage,
     ) -> Result<(), QuicRtcError> {
         match message {
+            SignalingMessage::Hello {
+                protocol_version,
+                encodings,
+                features,
+            } => {
+                self.handle_hello(connection_id, protocol_version, encodings, features)
+                    .await
+            }
             SignalingMessage::JoinRoom {
                 room_id,
                 participant_id,
                 participant_name,
                 capabilities,
                 quic_endpoint,
+                access_token,
             } => {
+                self.authorize_join(access_token.as_deref(), &room_id, &participant_id)?;
                 self.handle_join_room(
                     connection_id,
                     room_id,
@@ -258,9 +693,18 @@ impl SignalingServer {
                 room_id,
                 room_name,
                 max_participants,
+                access_token,
+                schedule,
             } => {
-                self.handle_create_room(connection_id, room_id, room_name, max_participants)
-                    .await
+                self.authorize_create(access_token.as_deref(), &room_id)?;
+                self.handle_create_room(
+                    connection_id,
+                    room_id,
+                    room_name,
+                    max_participants,
+                    schedule,
+                )
+                .await
             }
             SignalingMessage::MoqSessionOffer {
                 room_id,
@@ -279,12 +723,66 @@ impl SignalingServer {
                     .await
             }
             SignalingMessage::ListRooms => self.handle_list_rooms(connection_id).await,
+            SignalingMessage::Heartbeat => {
+                self.last_heartbeat.insert(connection_id, Instant::now());
+                Ok(())
+            }
             SignalingMessage::GetRoomInfo { room_id } => {
                 self.handle_get_room_info(connection_id, room_id).await
             }
+            SignalingMessage::CaptureConsent {
+                room_id,
+                request_id,
+                accepted,
+            } => {
+                self.handle_capture_consent(connection_id, room_id, request_id, accepted)
+                    .await
+            }
         }
     }
 
+    /// Handle protocol negotiation
+    async fn handle_hello(
+        &self,
+        connection_id: String,
+        protocol_version: u32,
+        encodings: Vec<WireEncoding>,
+        mut client_features: FeatureSet,
+    ) -> Result<(), QuicRtcError> {
+        if protocol_version == 0 {
+            return Err(QuicRtcError::UnsupportedVersion {
+                version: protocol_version,
+            });
+        }
+        // Newer clients step down to our version
+        let protocol_version = protocol_version.min(PROTOCOL_VERSION);
+
+        // Offering CBOR implies the feature, for clients that only list encodings
+        if encodings.contains(&WireEncoding::Cbor) {
+            client_features = client_features.with(ProtocolFeature::BinaryEncoding);
+        }
+        let features = self.features.intersection(&client_features);
+        let encoding = if features.contains(ProtocolFeature::BinaryEncoding) {
+            WireEncoding::negotiate(&encodings)
+        } else {
+            WireEncoding::Json
+        };
+
+        // The answer goes out in the current encoding so the client can read it
+        self.send_response(
+            &connection_id,
+            SignalingResponse::Welcome {
+                protocol_version,
+                encoding,
+                features,
+            },
+        )
+        .await;
+        self.connection_encodings.insert(connection_id, encoding);
+
+        Ok(())
+    }
+
     /// Handle room join request
     async fn handle_join_room(
         &self,
@@ -292,7 +790,7 @@ impl SignalingServer {
         room_id: String,
         participant_id: String,
         participant_name: Option<String>,
-        capabilities: Vec<String>,
+        capabilities: CapabilitySet,
         quic_endpoint: Option<SocketAddr>,
     ) -> Result<(), QuicRtcError> {
         let participant = Participant {
@@ -303,8 +801,8 @@ impl SignalingServer {
             quic_endpoint,
         };
 
-        // Add participant to room
-        {
+        // Add participant to room, or to its lobby while the host is away
+        let (recording, transcription, admitted) = {
             let mut rooms = self.rooms.write().await;
             let room = rooms
                 .get_mut(&room_id)
@@ -312,36 +810,111 @@ impl SignalingServer {
                     room_id: room_id.clone(),
                 })?;
 
+            if !room.admits(&participant_id, chrono::Utc::now()) {
+                room.enter_lobby(participant)?;
+                let schedule = room.schedule.clone().unwrap_or_default();
+                drop(rooms);
+
+                self.participant_to_connection
+                    .insert(participant_id.clone(), connection_id.clone());
+                self.send_response(
+                    &connection_id,
+                    SignalingResponse::WaitingForHost {
+                        room_id: room_id.clone(),
+                        participant_id: participant_id.clone(),
+                        host_id: schedule.host_id,
+                        starts_at: schedule.starts_at,
+                    },
+                )
+                .await;
+                tracing::info!(
+                    "Participant {} waiting for the host of room {}",
+                    participant_id,
+                    room_id
+                );
+                return Ok(());
+            }
+
             room.add_participant(participant.clone())?;
-        }
+            // The host arriving lets the lobby in
+            let admitted = room.admit_lobby(chrono::Utc::now());
+            (room.recording.clone(), room.transcription.clone(), admitted)
+        };
 
         // Track participant connection
         self.participant_to_connection
             .insert(participant_id.clone(), connection_id.clone());
 
+        self.announce_join(&room_id, participant, &recording, &transcription)
+            .await;
+        for participant in admitted {
+            self.announce_join(&room_id, participant, &recording, &transcription)
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Tell a participant they are in the room, and the room about them
+    async fn announce_join(
+        &self,
+        room_id: &str,
+        participant: Participant,
+        recording: &Option<RoomRecording>,
+        transcription: &Option<RoomTranscription>,
+    ) {
+        let connection_id = participant.connection_id.clone();
+        let participant_id = participant.id.clone();
+
         // Send join success response
         self.send_response(
             &connection_id,
             SignalingResponse::JoinedRoom {
-                room_id: room_id.clone(),
+                room_id: room_id.to_string(),
                 participant_id: participant_id.clone(),
             },
         )
         .await;
 
+        // Joiners must see that the room is already being captured
+        if let Some(recording) = recording {
+            self.send_response(
+                &connection_id,
+                SignalingResponse::RecordingStarted {
+                    room_id: room_id.to_string(),
+                    recording: recording.clone(),
+                },
+            )
+            .await;
+        }
+        if let Some(transcription) = transcription {
+            self.send_response(
+                &connection_id,
+                SignalingResponse::TranscriptionStarted {
+                    room_id: room_id.to_string(),
+                    transcription: transcription.clone(),
+                },
+            )
+            .await;
+        }
+
+        self.emit_webhook(WebhookEventKind::ParticipantJoined {
+            room_id: room_id.to_string(),
+            participant_id: participant_id.clone(),
+            participant_name: participant.name.clone(),
+        });
+
         // Notify other participants
         self.broadcast_to_room(
-            &room_id,
+            room_id,
             &participant_id,
             SignalingResponse::ParticipantJoined {
-                room_id: room_id.clone(),
-                participant: participant,
+                room_id: room_id.to_string(),
+                participant,
             },
         )
         .await;
 
         tracing::info!("Participant {} joined room {}", participant_id, room_id);
-        Ok(())
     }
 
     /// Handle room leave request
@@ -351,45 +924,70 @@ impl SignalingServer {
         room_id: String,
         participant_id: String,
     ) -> Result<(), QuicRtcError> {
-        // Remove participant from room
-        let removed_participant = {
-            let mut rooms = self.rooms.write().await;
-            if let Some(room) = rooms.get_mut(&room_id) {
-                room.remove_participant(&participant_id)
-            } else {
-                None
-            }
-        };
-
-        if removed_participant.is_some() {
-            // Remove connection tracking
-            self.participant_to_connection.remove(&participant_id);
-
-            // Send leave success response
+        if self
+            .depart_room(&room_id, &participant_id, LeaveReason::Left)
+            .await
+            .is_some()
+        {
             self.send_response(
                 &connection_id,
                 SignalingResponse::LeftRoom {
-                    room_id: room_id.clone(),
-                    participant_id: participant_id.clone(),
+                    room_id,
+                    participant_id,
                 },
             )
             .await;
+        }
 
-            // Notify other participants
-            self.broadcast_to_room(
-                &room_id,
-                &participant_id,
-                SignalingResponse::ParticipantLeft {
-                    room_id: room_id.clone(),
-                    participant_id: participant_id.clone(),
-                },
-            )
-            .await;
+        Ok(())
+    }
 
-            tracing::info!("Participant {} left room {}", participant_id, room_id);
+    /// Remove a participant from a room and tell the rest of the room why
+    ///
+    /// Returns the participant if they were in the room.
+    async fn depart_room(
+        &self,
+        room_id: &str,
+        participant_id: &str,
+        reason: LeaveReason,
+    ) -> Option<Participant> {
+        let (participant, waiting) = {
+            let mut rooms = self.rooms.write().await;
+            let room = rooms.get_mut(room_id)?;
+            match room.remove_participant(participant_id) {
+                Some(participant) => (participant, false),
+                None => (room.leave_lobby(participant_id)?, true),
+            }
+        };
+
+        self.participant_to_connection.remove(participant_id);
+        // Nobody was told about participants still in the lobby
+        if waiting {
+            return Some(participant);
         }
+        self.release_consent(room_id, participant_id);
+        self.broadcast_to_room(
+            room_id,
+            participant_id,
+            SignalingResponse::ParticipantLeft {
+                room_id: room_id.to_string(),
+                participant_id: participant_id.to_string(),
+                reason,
+            },
+        )
+        .await;
 
-        Ok(())
+        self.emit_webhook(WebhookEventKind::ParticipantLeft {
+            room_id: room_id.to_string(),
+            participant_id: participant_id.to_string(),
+        });
+        tracing::info!(
+            "Participant {} left room {} ({:?})",
+            participant_id,
+            room_id,
+            reason
+        );
+        Some(participant)
     }
 
     /// Handle room creation request
@@ -399,19 +997,12 @@ impl SignalingServer {
         room_id: String,
         room_name: Option<String>,
         max_participants: Option<usize>,
+        schedule: Option<RoomSchedule>,
     ) -> Result<(), QuicRtcError> {
-        let mut room = Room::new(room_id.clone(), room_name);
-        if let Some(max) = max_participants {
-            room.max_participants = max;
-        }
-
-        // Create room
-        {
-            let mut rooms = self.rooms.write().await;
-            if rooms.contains_key(&room_id) {
-                return Err(QuicRtcError::RoomAlreadyExists { room_id });
-            }
-            rooms.insert(room_id.clone(), room);
+        self.create_room(room_id.clone(), room_name, max_participants)
+            .await?;
+        if schedule.is_some() {
+            self.set_room_schedule(&room_id, schedule).await?;
         }
 
         // Send creation success response
@@ -527,17 +1118,45 @@ impl SignalingServer {
 
     /// Send response to a specific connection
     async fn send_response(&self, connection_id: &str, response: SignalingResponse) {
-        if let Some(mut connection) = self.connections.get_mut(connection_id) {
-            let message = match serde_json::to_string(&response) {
-                Ok(json) => Message::Text(json),
+        let encoding = self
+            .connection_encodings
+            .get(connection_id)
+            .map(|encoding| *encoding)
+            .unwrap_or_default();
+
+        // Don't hold the map entry while encoding
+        let sender = self
+            .connections
+            .get(connection_id)
+            .map(|sender| sender.clone());
+        if let Some(sender) = sender {
+            let message = match encoding.encode(&response) {
+                Ok(data) => match encoding {
+                    WireEncoding::Json => {
+                        Message::Text(String::from_utf8_lossy(&data).into_owned())
+                    }
+                    WireEncoding::Cbor => Message::Binary(data),
+                },
                 Err(e) => {
                     tracing::error!("Failed to serialize response: {}", e);
                     return;
                 }
             };
 
-            if let Err(e) = connection.send(message).await {
-                tracing::error!("Failed to send message to {}: {}", connection_id, e);
+            // Never wait on one client: a full queue means it stopped reading
+            match sender.try_send(message) {
+                Ok(()) => {}
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    tracing::warn!(
+                        "Closing connection {}: not reading its responses",
+                        connection_id
+                    );
+                    self.metrics.record_connection_too_slow();
+                    self.connections.remove(connection_id);
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => {
+                    tracing::debug!("Dropping response to closed connection {}", connection_id);
+                }
             }
         }
     }
@@ -578,47 +1197,706 @@ impl SignalingServer {
         }
     }
 
-    /// Cleanup connection and associated participant
+    /// Queue a webhook event if webhooks are configured
+    fn emit_webhook(&self, event: WebhookEventKind) {
+        if let Some(webhooks) = &self.webhooks {
+            webhooks.dispatch(event);
+        }
+    }
+
+    /// Report a removed room, finishing its recording first
+    fn emit_room_destroyed(&self, room: &Room, reason: RoomCloseReason) {
+        if let Some(recording) = &room.recording {
+            self.emit_recording_finished(&room.id, recording);
+        }
+        self.emit_webhook(WebhookEventKind::RoomDestroyed {
+            room_id: room.id.clone(),
+            reason,
+        });
+    }
+
+    fn emit_recording_finished(&self, room_id: &str, recording: &RoomRecording) {
+        self.emit_webhook(WebhookEventKind::RecordingFinished {
+            room_id: room_id.to_string(),
+            recording_id: recording.recording_id.clone(),
+            output: recording.output.clone(),
+            duration_secs: (chrono::Utc::now() - recording.started_at).num_seconds(),
+        });
+    }
+
+    /// Cleanup connection and associated participants
     async fn cleanup_connection(&self, connection_id: &str) {
         // Remove connection
         self.connections.remove(connection_id);
+        self.connection_encodings.remove(connection_id);
+        self.last_heartbeat.remove(connection_id);
 
-        // Find and remove participant from rooms
-        let participant_id = self
+        self.remove_connection_participants(connection_id, LeaveReason::Disconnected)
+            .await;
+    }
+
+    /// Remove every participant joined over a connection from all rooms
+    async fn remove_connection_participants(&self, connection_id: &str, reason: LeaveReason) {
+        let participant_ids: Vec<String> = self
             .participant_to_connection
             .iter()
-            .find(|entry| entry.value() == connection_id)
-            .map(|entry| entry.key().clone());
+            .filter(|entry| entry.value() == connection_id)
+            .map(|entry| entry.key().clone())
+            .collect();
 
-        if let Some(participant_id) = participant_id {
-            // Remove from all rooms
+        for participant_id in participant_ids {
             let room_ids: Vec<String> = {
                 let rooms = self.rooms.read().await;
                 rooms
                     .iter()
-                    .filter(|(_, room)| room.participants.contains_key(&participant_id))
+                    .filter(|(_, room)| {
+                        room.participants.contains_key(&participant_id)
+                            || room.lobby.iter().any(|p| p.id == participant_id)
+                    })
                     .map(|(room_id, _)| room_id.clone())
                     .collect()
             };
 
             for room_id in room_ids {
-                let _ = self
-                    .handle_leave_room(connection_id.to_string(), room_id, participant_id.clone())
-                    .await;
+                if self
+                    .depart_room(&room_id, &participant_id, reason)
+                    .await
+                    .is_some()
+                    && reason == LeaveReason::TimedOut
+                {
+                    self.metrics.record_participant_timed_out();
+                }
             }
 
             self.participant_to_connection.remove(&participant_id);
         }
     }
 
+    /// Remove the participants of connections whose heartbeats stopped and
+    /// close those connections
+    async fn evict_stale_connections(&self) {
+        let now = Instant::now();
+        let stale: Vec<String> = self
+            .last_heartbeat
+            .iter()
+            .filter(|entry| now.saturating_duration_since(*entry.value()) > self.presence_timeout)
+            .map(|entry| entry.key().clone())
+            .collect();
+
+        for connection_id in stale {
+            tracing::info!(
+                "Closing connection {}: no heartbeat for {:?}",
+                connection_id,
+                self.presence_timeout
+            );
+            self.last_heartbeat.remove(&connection_id);
+            self.remove_connection_participants(&connection_id, LeaveReason::TimedOut)
+                .await;
+            // The writer closes the connection and its reader cleans up
+            self.connections.remove(&connection_id);
+        }
+    }
+
+    /// Create an empty room
+    pub async fn create_room(
+        &self,
+        room_id: String,
+        room_name: Option<String>,
+        max_participants: Option<usize>,
+    ) -> Result<(), QuicRtcError> {
+        let mut room = Room::new(room_id.clone(), room_name);
+        room.consent_policy = self.consent_policy.clone();
+        room.lifetime = self.room_lifetime.clone();
+        if let Some(max) = max_participants {
+            room.max_participants = max;
+        }
+
+        {
+            let mut rooms = self.rooms.write().await;
+            if rooms.contains_key(&room_id) {
+                return Err(QuicRtcError::RoomAlreadyExists { room_id });
+            }
+            rooms.insert(room_id.clone(), room);
+        }
+
+        self.emit_webhook(WebhookEventKind::RoomCreated { room_id });
+        Ok(())
+    }
+
+    /// Add a participant the server serves itself, such as a phone call
+    /// bridged in by a [`PhoneBridge`](crate::gateway::PhoneBridge)
+    ///
+    /// Its connection ID names no WebSocket connection, so it is sent
+    /// nothing; the rest of the room sees it like any other participant,
+    /// and it waits in the lobby like one too.
+    pub async fn join_server_participant(
+        &self,
+        room_id: &str,
+        participant: Participant,
+    ) -> Result<(), QuicRtcError> {
+        self.handle_join_room(
+            participant.connection_id,
+            room_id.to_string(),
+            participant.id,
+            participant.name,
+            participant.capabilities,
+            participant.quic_endpoint,
+        )
+        .await
+    }
+
+    /// Remove a participant added with
+    /// [`join_server_participant`](Self::join_server_participant)
+    pub async fn leave_server_participant(
+        &self,
+        room_id: &str,
+        participant_id: &str,
+    ) -> Result<Participant, QuicRtcError> {
+        self.depart_room(room_id, participant_id, LeaveReason::Left)
+            .await
+            .ok_or_else(|| QuicRtcError::ParticipantNotFound {
+                room_id: room_id.to_string(),
+                participant_id: participant_id.to_string(),
+            })
+    }
+
+    /// Remove a participant from a room and notify them and the room
+    pub async fn kick_participant(
+        &self,
+        room_id: &str,
+        participant_id: &str,
+        reason: Option<String>,
+    ) -> Result<Participant, QuicRtcError> {
+        let participant = {
+            let mut rooms = self.rooms.write().await;
+            let room = rooms
+                .get_mut(room_id)
+                .ok_or_else(|| QuicRtcError::RoomNotFound {
+                    room_id: room_id.to_string(),
+                })?;
+            room.remove_participant(participant_id).ok_or_else(|| {
+                QuicRtcError::ParticipantNotFound {
+                    room_id: room_id.to_string(),
+                    participant_id: participant_id.to_string(),
+                }
+            })?
+        };
+
+        self.participant_to_connection.remove(participant_id);
+        self.release_consent(room_id, participant_id);
+        self.send_response(
+            &participant.connection_id,
+            SignalingResponse::Kicked {
+                room_id: room_id.to_string(),
+                reason,
+            },
+        )
+        .await;
+        self.broadcast_to_room(
+            room_id,
+            participant_id,
+            SignalingResponse::ParticipantLeft {
+                room_id: room_id.to_string(),
+                participant_id: participant_id.to_string(),
+                reason: LeaveReason::Kicked,
+            },
+        )
+        .await;
+
+        self.emit_webhook(WebhookEventKind::ParticipantLeft {
+            room_id: room_id.to_string(),
+            participant_id: participant_id.to_string(),
+        });
+        tracing::info!(
+            "Participant {} kicked from room {}",
+            participant_id,
+            room_id
+        );
+        Ok(participant)
+    }
+
+    /// Close a room, notifying and removing all of its participants
+    pub async fn close_room(&self, room_id: &str) -> Result<Room, QuicRtcError> {
+        self.close_room_with_reason(room_id, RoomCloseReason::Closed)
+            .await
+    }
+
+    async fn close_room_with_reason(
+        &self,
+        room_id: &str,
+        reason: RoomCloseReason,
+    ) -> Result<Room, QuicRtcError> {
+        let room =
+            self.rooms
+                .write()
+                .await
+                .remove(room_id)
+                .ok_or_else(|| QuicRtcError::RoomNotFound {
+                    room_id: room_id.to_string(),
+                })?;
+
+        for participant in room.participants.values().chain(&room.lobby) {
+            self.participant_to_connection.remove(&participant.id);
+            self.release_consent(room_id, &participant.id);
+            self.send_response(
+                &participant.connection_id,
+                SignalingResponse::RoomClosed {
+                    room_id: room_id.to_string(),
+                    reason,
+                },
+            )
+            .await;
+        }
+
+        self.emit_room_destroyed(&room, reason);
+        tracing::info!("Room {} closed: {}", room_id, reason);
+        Ok(room)
+    }
+
+    /// Replace a room's lifetime
+    ///
+    /// Participants are warned again if the closing time moves.
+    pub async fn set_room_lifetime(
+        &self,
+        room_id: &str,
+        lifetime: RoomLifetime,
+    ) -> Result<(), QuicRtcError> {
+        let mut rooms = self.rooms.write().await;
+        let room = rooms
+            .get_mut(room_id)
+            .ok_or_else(|| QuicRtcError::RoomNotFound {
+                room_id: room_id.to_string(),
+            })?;
+        room.lifetime = lifetime;
+        Ok(())
+    }
+
+    /// Set or clear a room's host and start time
+    ///
+    /// Participants already waiting are let in if the new schedule admits
+    /// them.
+    pub async fn set_room_schedule(
+        &self,
+        room_id: &str,
+        schedule: Option<RoomSchedule>,
+    ) -> Result<(), QuicRtcError> {
+        {
+            let mut rooms = self.rooms.write().await;
+            let room = rooms
+                .get_mut(room_id)
+                .ok_or_else(|| QuicRtcError::RoomNotFound {
+                    room_id: room_id.to_string(),
+                })?;
+            room.schedule = schedule;
+        }
+        self.admit_lobbies().await;
+        Ok(())
+    }
+
+    /// Let in lobby participants whose rooms now admit them, such as when a
+    /// room without a host reaches its start time
+    async fn admit_lobbies(&self) {
+        let now = chrono::Utc::now();
+        let admitted: Vec<_> = {
+            let mut rooms = self.rooms.write().await;
+            rooms
+                .values_mut()
+                .filter(|room| !room.lobby.is_empty())
+                .map(|room| {
+                    (
+                        room.id.clone(),
+                        room.admit_lobby(now),
+                        room.recording.clone(),
+                        room.transcription.clone(),
+                    )
+                })
+                .collect()
+        };
+
+        for (room_id, participants, recording, transcription) in admitted {
+            for participant in participants {
+                self.announce_join(&room_id, participant, &recording, &transcription)
+                    .await;
+            }
+        }
+    }
+
+    /// Close rooms whose lifetime is over, warning participants of rooms
+    /// about to close
+    async fn enforce_room_lifetimes(&self) {
+        let now = chrono::Utc::now();
+        let mut due = Vec::new();
+        let mut notices = Vec::new();
+        {
+            let mut rooms = self.rooms.write().await;
+            for room in rooms.values_mut() {
+                let Some((closes_at, reason)) = room.closes_at() else {
+                    continue;
+                };
+                if closes_at <= now {
+                    due.push((room.id.clone(), reason));
+                    continue;
+                }
+                let notice = chrono::Duration::from_std(room.lifetime.closing_notice)
+                    .unwrap_or(chrono::Duration::MAX);
+                let announce = !room.participants.is_empty()
+                    && closes_at - now <= notice
+                    && room.closing_announced != Some(closes_at);
+                if announce {
+                    room.closing_announced = Some(closes_at);
+                    notices.push((room.id.clone(), closes_at, reason));
+                }
+            }
+        }
+
+        for (room_id, closes_at, reason) in notices {
+            tracing::info!("Room {} closing at {}: {}", room_id, closes_at, reason);
+            self.broadcast_to_room(
+                &room_id,
+                "",
+                SignalingResponse::RoomClosing {
+                    room_id: room_id.clone(),
+                    closes_at,
+                    reason,
+                },
+            )
+            .await;
+        }
+        for (room_id, reason) in due {
+            let _ = self.close_room_with_reason(&room_id, reason).await;
+        }
+    }
+
+    /// Mark a room as recording and tell its participants, so that a
+    /// recorder in the room starts capturing
+    ///
+    /// Participants are asked for consent first; see [`ConsentPolicy`].
+    pub async fn start_recording(
+        &self,
+        room_id: &str,
+        output: Option<String>,
+    ) -> Result<RoomRecording, QuicRtcError> {
+        self.obtain_consent(room_id, CaptureKind::Recording).await?;
+
+        let recording = {
+            let mut rooms = self.rooms.write().await;
+            let room = rooms
+                .get_mut(room_id)
+                .ok_or_else(|| QuicRtcError::RoomNotFound {
+                    room_id: room_id.to_string(),
+                })?;
+            if room.recording.is_some() {
+                return Err(QuicRtcError::InvalidOperation {
+                    operation: format!("Room {} is already recording", room_id),
+                });
+            }
+
+            let recording = RoomRecording {
+                recording_id: Uuid::new_v4().to_string(),
+                output,
+                started_at: chrono::Utc::now(),
+            };
+            room.recording = Some(recording.clone());
+            recording
+        };
+
+        self.broadcast_to_room(
+            room_id,
+            "",
+            SignalingResponse::RecordingStarted {
+                room_id: room_id.to_string(),
+                recording: recording.clone(),
+            },
+        )
+        .await;
+
+        tracing::info!(
+            "Recording {} started in room {}",
+            recording.recording_id,
+            room_id
+        );
+        Ok(recording)
+    }
+
+    /// Finish the recording in progress on a room
+    pub async fn stop_recording(&self, room_id: &str) -> Result<RoomRecording, QuicRtcError> {
+        let recording = {
+            let mut rooms = self.rooms.write().await;
+            let room = rooms
+                .get_mut(room_id)
+                .ok_or_else(|| QuicRtcError::RoomNotFound {
+                    room_id: room_id.to_string(),
+                })?;
+            room.recording
+                .take()
+                .ok_or_else(|| QuicRtcError::InvalidOperation {
+                    operation: format!("Room {} is not recording", room_id),
+                })?
+        };
+
+        self.broadcast_to_room(
+            room_id,
+            "",
+            SignalingResponse::RecordingStopped {
+                room_id: room_id.to_string(),
+                recording_id: recording.recording_id.clone(),
+            },
+        )
+        .await;
+
+        self.emit_recording_finished(room_id, &recording);
+        tracing::info!(
+            "Recording {} stopped in room {}",
+            recording.recording_id,
+            room_id
+        );
+        Ok(recording)
+    }
+
+    /// Mark a room as transcribing and tell its participants
+    ///
+    /// Participants are asked for consent first; see [`ConsentPolicy`].
+    pub async fn start_transcription(
+        &self,
+        room_id: &str,
+        language: Option<String>,
+    ) -> Result<RoomTranscription, QuicRtcError> {
+        self.obtain_consent(room_id, CaptureKind::Transcription)
+            .await?;
+
+        let transcription = {
+            let mut rooms = self.rooms.write().await;
+            let room = rooms
+                .get_mut(room_id)
+                .ok_or_else(|| QuicRtcError::RoomNotFound {
+                    room_id: room_id.to_string(),
+                })?;
+            if room.transcription.is_some() {
+                return Err(QuicRtcError::InvalidOperation {
+                    operation: format!("Room {} is already transcribing", room_id),
+                });
+            }
+
+            let transcription = RoomTranscription {
+                transcription_id: Uuid::new_v4().to_string(),
+                language,
+                started_at: chrono::Utc::now(),
+            };
+            room.transcription = Some(transcription.clone());
+            transcription
+        };
+
+        self.broadcast_to_room(
+            room_id,
+            "",
+            SignalingResponse::TranscriptionStarted {
+                room_id: room_id.to_string(),
+                transcription: transcription.clone(),
+            },
+        )
+        .await;
+
+        tracing::info!(
+            "Transcription {} started in room {}",
+            transcription.transcription_id,
+            room_id
+        );
+        Ok(transcription)
+    }
+
+    /// Finish the transcription in progress on a room
+    pub async fn stop_transcription(
+        &self,
+        room_id: &str,
+    ) -> Result<RoomTranscription, QuicRtcError> {
+        let transcription = {
+            let mut rooms = self.rooms.write().await;
+            let room = rooms
+                .get_mut(room_id)
+                .ok_or_else(|| QuicRtcError::RoomNotFound {
+                    room_id: room_id.to_string(),
+                })?;
+            room.transcription
+                .take()
+                .ok_or_else(|| QuicRtcError::InvalidOperation {
+                    operation: format!("Room {} is not transcribing", room_id),
+                })?
+        };
+
+        self.broadcast_to_room(
+            room_id,
+            "",
+            SignalingResponse::TranscriptionStopped {
+                room_id: room_id.to_string(),
+                transcription_id: transcription.transcription_id.clone(),
+            },
+        )
+        .await;
+
+        tracing::info!(
+            "Transcription {} stopped in room {}",
+            transcription.transcription_id,
+            room_id
+        );
+        Ok(transcription)
+    }
+
+    /// Replace the consent policy of an existing room
+    pub async fn set_consent_policy(
+        &self,
+        room_id: &str,
+        policy: ConsentPolicy,
+    ) -> Result<(), QuicRtcError> {
+        let mut rooms = self.rooms.write().await;
+        let room = rooms
+            .get_mut(room_id)
+            .ok_or_else(|| QuicRtcError::RoomNotFound {
+                room_id: room_id.to_string(),
+            })?;
+        room.consent_policy = policy;
+        Ok(())
+    }
+
+    /// Notify a room's participants that capture is about to start and,
+    /// if the room's policy requires it, wait for all of them to accept
+    async fn obtain_consent(&self, room_id: &str, kind: CaptureKind) -> Result<(), QuicRtcError> {
+        let (policy, participants) = {
+            let rooms = self.rooms.read().await;
+            let room = rooms
+                .get(room_id)
+                .ok_or_else(|| QuicRtcError::RoomNotFound {
+                    room_id: room_id.to_string(),
+                })?;
+            let capturing = match kind {
+                CaptureKind::Recording => room.recording.is_some(),
+                CaptureKind::Transcription => room.transcription.is_some(),
+            };
+            if capturing {
+                return Err(QuicRtcError::InvalidOperation {
+                    operation: format!("Room {} is already capturing {:?}", room_id, kind),
+                });
+            }
+            let participants: HashSet<String> = room.participants.keys().cloned().collect();
+            (room.consent_policy.clone(), participants)
+        };
+
+        let request_id = Uuid::new_v4().to_string();
+        let (updates_tx, mut updates) = mpsc::unbounded_channel();
+        // Register before notifying so no answer can arrive unrouted
+        let _request = policy.require_acknowledgement.then(|| {
+            self.consent_requests.insert(
+                request_id.clone(),
+                ConsentWaiter {
+                    room_id: room_id.to_string(),
+                    participants: participants.clone(),
+                    updates: updates_tx,
+                },
+            );
+            ConsentRequestGuard {
+                requests: &self.consent_requests,
+                request_id: request_id.clone(),
+            }
+        });
+
+        self.broadcast_to_room(
+            room_id,
+            "",
+            SignalingResponse::CaptureConsentRequested {
+                room_id: room_id.to_string(),
+                request_id,
+                kind,
+                requires_acknowledgement: policy.require_acknowledgement,
+            },
+        )
+        .await;
+
+        if !policy.require_acknowledgement {
+            return Ok(());
+        }
+        let result = await_consent(room_id, participants, &mut updates, policy.ack_timeout).await;
+        if let Err(e) = &result {
+            tracing::info!("{:?} in room {} refused: {}", kind, room_id, e);
+        }
+        result
+    }
+
+    /// Route a participant's answer to the consent request it names
+    async fn handle_capture_consent(
+        &self,
+        connection_id: String,
+        room_id: String,
+        request_id: String,
+        accepted: bool,
+    ) -> Result<(), QuicRtcError> {
+        // Answer for the participant on this connection, not one it names
+        let participant_id = {
+            let rooms = self.rooms.read().await;
+            rooms.get(&room_id).and_then(|room| {
+                room.participants
+                    .values()
+                    .find(|participant| participant.connection_id == connection_id)
+                    .map(|participant| participant.id.clone())
+            })
+        }
+        .ok_or_else(|| QuicRtcError::InvalidOperation {
+            operation: format!("Connection is not a participant of room {}", room_id),
+        })?;
+
+        let waiter = self
+            .consent_requests
+            .get(&request_id)
+            .filter(|waiter| {
+                waiter.room_id == room_id && waiter.participants.contains(&participant_id)
+            })
+            .map(|waiter| waiter.updates.clone())
+            .ok_or_else(|| QuicRtcError::InvalidOperation {
+                operation: format!("No pending consent request {}", request_id),
+            })?;
+
+        let _ = waiter.send(ConsentUpdate::Answered {
+            participant_id,
+            accepted,
+        });
+        Ok(())
+    }
+
+    /// Stop waiting on a departed participant's consent
+    fn release_consent(&self, room_id: &str, participant_id: &str) {
+        for waiter in self.consent_requests.iter() {
+            if waiter.room_id == room_id && waiter.participants.contains(participant_id) {
+                let _ = waiter.updates.send(ConsentUpdate::Left {
+                    participant_id: participant_id.to_string(),
+                });
+            }
+        }
+    }
+
+    /// Get a room by ID
+    pub async fn get_room(&self, room_id: &str) -> Option<Room> {
+        self.rooms.read().await.get(room_id).cloned()
+    }
+
     /// Stop the signaling server
+    ///
+    /// Stops accepting connections and closes the open ones.
     pub async fn stop(&self) -> Result<(), QuicRtcError> {
+        self.shutdown.send_replace(true);
+
         // Close all connections
         self.connections.clear();
         self.participant_to_connection.clear();
 
         // Clear all rooms
-        self.rooms.write().await.clear();
+        let rooms: Vec<Room> = self
+            .rooms
+            .write()
+            .await
+            .drain()
+            .map(|(_, room)| room)
+            .collect();
+        for room in &rooms {
+            self.emit_room_destroyed(room, RoomCloseReason::Closed);
+        }
 
         tracing::info!("Signaling server stopped");
         Ok(())
@@ -644,3 +1922,39 @@ impl SignalingServer {
         self.handle_connection(stream).await;
     }
 }
+
+/// Write queued messages to a connection until its queue closes
+///
+/// A failed or stalled write ends the task, which closes the connection.
+async fn write_connection(
+    connection_id: String,
+    mut sink: SplitSink<WebSocketConnection, Message>,
+    mut queue: mpsc::Receiver<Message>,
+) {
+    while let Some(message) = queue.recv().await {
+        match tokio::time::timeout(WRITE_TIMEOUT, sink.send(message)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                tracing::debug!("Failed to send message to {}: {}", connection_id, e);
+                return;
+            }
+            Err(_) => {
+                tracing::warn!("Closing connection {}: write timed out", connection_id);
+                return;
+            }
+        }
+    }
+    let _ = tokio::time::timeout(WRITE_TIMEOUT, sink.close()).await;
+}
+
+/// Unregisters a consent request when its waiter finishes or is dropped
+struct ConsentRequestGuard<'a> {
+    requests: &'a DashMap<String, ConsentWaiter>,
+    request_id: String,
+}
+
+impl Drop for ConsentRequestGuard<'_> {
+    fn drop(&mut self) {
+        self.requests.remove(&self.request_id);
+    }
+}
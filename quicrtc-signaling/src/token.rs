@@ -0,0 +1,360 @@
+//! Access tokens with room grants
+//!
+//! Application backends mint short-lived tokens for their users with
+//! [`AccessToken`]; the signaling server checks them with
+//! [`TokenVerifier`] before letting a participant create or join a room.
+//!
+//! Tokens are JWTs signed with HMAC-SHA256. The API key goes in the `iss`
+//! claim so the server can pick the matching secret, the participant
+//! identity in `sub`, and the room permissions in `grants`.
+//!
+//! ```
+//! use quicrtc_signaling::token::{AccessToken, TokenVerifier};
+//!
+//! let jwt = AccessToken::new("api-key", "api-secret")
+//!     .identity("alice")
+//!     .room("standup")
+//!     .can_publish(true)
+//!     .to_jwt()
+//!     .unwrap();
+//!
+//! let claims = TokenVerifier::new()
+//!     .with_key("api-key", "api-secret")
+//!     .verify(&jwt)
+//!     .unwrap();
+//! assert_eq!(claims.sub, "alice");
+//! assert!(claims.grants.allows_join("standup"));
+//! ```
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use quicrtc_core::{room_namespace, MoqPermissions, QuicRtcError, TrackFilter};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::time::Duration;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Default token lifetime
+pub const DEFAULT_TOKEN_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Permissions a token grants within rooms
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoomGrants {
+    /// Room the grants apply to (None = any room)
+    pub room: Option<String>,
+    /// May join the room
+    pub room_join: bool,
+    /// May create rooms
+    pub room_create: bool,
+    /// May administer the room
+    pub room_admin: bool,
+    /// May publish tracks
+    pub can_publish: bool,
+    /// May subscribe to tracks
+    pub can_subscribe: bool,
+    /// May publish data messages
+    pub can_publish_data: bool,
+}
+
+impl RoomGrants {
+    /// Whether these grants cover `room_id`
+    pub fn covers_room(&self, room_id: &str) -> bool {
+        self.room.as_deref().is_none_or(|room| room == room_id)
+    }
+
+    /// Whether these grants allow joining `room_id`
+    pub fn allows_join(&self, room_id: &str) -> bool {
+        self.room_join && self.covers_room(room_id)
+    }
+
+    /// Whether these grants allow creating `room_id`
+    pub fn allows_create(&self, room_id: &str) -> bool {
+        self.room_create && self.covers_room(room_id)
+    }
+}
+
+/// Claims carried by an access token
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenClaims {
+    /// API key the token was signed with
+    pub iss: String,
+    /// Participant identity
+    pub sub: String,
+    /// Participant display name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Not valid before (Unix seconds)
+    pub nbf: i64,
+    /// Expiry (Unix seconds)
+    pub exp: i64,
+    /// Room permissions
+    pub grants: RoomGrants,
+    /// Application metadata attached to the participant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<String>,
+}
+
+impl TokenClaims {
+    /// MoQ permissions to enforce on this participant's session
+    ///
+    /// Publishers may only announce tracks under their own identity
+    /// (`<sub>/...`); when the token is bound to a room, both announcing and
+    /// subscribing are limited to that room's `room.<id>` namespace.
+    pub fn moq_permissions(&self) -> MoqPermissions {
+        let mut permissions = MoqPermissions {
+            can_publish: self.grants.can_publish,
+            can_subscribe: self.grants.can_subscribe,
+            ..MoqPermissions::allow_all()
+        };
+
+        if let Some(room) = &self.grants.room {
+            let namespace = room_namespace(room);
+            permissions = permissions
+                .with_publish_filter(
+                    TrackFilter::namespace(namespace.clone())
+                        .with_track_prefix(format!("{}/", self.sub)),
+                )
+                .with_subscribe_filter(TrackFilter::namespace(namespace));
+        }
+
+        permissions
+    }
+}
+
+/// Builder for a signed access token
+#[derive(Debug, Clone)]
+pub struct AccessToken {
+    api_key: String,
+    secret: String,
+    identity: Option<String>,
+    name: Option<String>,
+    metadata: Option<String>,
+    ttl: Duration,
+    grants: RoomGrants,
+}
+
+impl AccessToken {
+    /// Start a token signed with `api_key`'s `secret`
+    ///
+    /// Grants default to joining and subscribing only.
+    pub fn new(api_key: impl Into<String>, secret: impl Into<String>) -> Self {
+        Self {
+            api_key: api_key.into(),
+            secret: secret.into(),
+            identity: None,
+            name: None,
+            metadata: None,
+            ttl: DEFAULT_TOKEN_TTL,
+            grants: RoomGrants {
+                room_join: true,
+                can_subscribe: true,
+                ..RoomGrants::default()
+            },
+        }
+    }
+
+    /// Set the participant identity; required
+    pub fn identity(mut self, identity: impl Into<String>) -> Self {
+        self.identity = Some(identity.into());
+        self
+    }
+
+    /// Set the participant display name
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Attach application metadata
+    pub fn metadata(mut self, metadata: impl Into<String>) -> Self {
+        self.metadata = Some(metadata.into());
+        self
+    }
+
+    /// Set how long the token stays valid
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Restrict the token to one room
+    pub fn room(mut self, room: impl Into<String>) -> Self {
+        self.grants.room = Some(room.into());
+        self
+    }
+
+    /// Allow or deny joining the room
+    pub fn room_join(mut self, allowed: bool) -> Self {
+        self.grants.room_join = allowed;
+        self
+    }
+
+    /// Allow or deny creating rooms
+    pub fn room_create(mut self, allowed: bool) -> Self {
+        self.grants.room_create = allowed;
+        self
+    }
+
+    /// Allow or deny administering the room
+    pub fn room_admin(mut self, allowed: bool) -> Self {
+        self.grants.room_admin = allowed;
+        self
+    }
+
+    /// Allow or deny publishing tracks
+    pub fn can_publish(mut self, allowed: bool) -> Self {
+        self.grants.can_publish = allowed;
+        self
+    }
+
+    /// Allow or deny subscribing to tracks
+    pub fn can_subscribe(mut self, allowed: bool) -> Self {
+        self.grants.can_subscribe = allowed;
+        self
+    }
+
+    /// Allow or deny publishing data messages
+    pub fn can_publish_data(mut self, allowed: bool) -> Self {
+        self.grants.can_publish_data = allowed;
+        self
+    }
+
+    /// Replace all grants at once
+    pub fn grants(mut self, grants: RoomGrants) -> Self {
+        self.grants = grants;
+        self
+    }
+
+    /// Sign the token
+    pub fn to_jwt(&self) -> Result<String, QuicRtcError> {
+        let identity = self
+            .identity
+            .clone()
+            .ok_or_else(|| QuicRtcError::MissingConfiguration {
+                field: "identity".to_string(),
+            })?;
+
+        let now = chrono::Utc::now().timestamp();
+        let exp = i64::try_from(self.ttl.as_secs())
+            .ok()
+            .and_then(|ttl| now.checked_add(ttl))
+            .ok_or_else(|| QuicRtcError::InvalidData {
+                reason: format!("Token lifetime of {:?} is too long", self.ttl),
+            })?;
+        let claims = TokenClaims {
+            iss: self.api_key.clone(),
+            sub: identity,
+            name: self.name.clone(),
+            nbf: now,
+            exp,
+            grants: self.grants.clone(),
+            metadata: self.metadata.clone(),
+        };
+
+        let header = URL_SAFE_NO_PAD.encode(br#"{"alg":"HS256","typ":"JWT"}"#);
+        let payload = serde_json::to_vec(&claims).map_err(|e| QuicRtcError::InvalidData {
+            reason: format!("Failed to encode token claims: {}", e),
+        })?;
+        let signing_input = format!("{}.{}", header, URL_SAFE_NO_PAD.encode(payload));
+        let signature = URL_SAFE_NO_PAD.encode(hmac_sha256(&self.secret, &signing_input));
+
+        Ok(format!("{}.{}", signing_input, signature))
+    }
+}
+
+/// Verifies access tokens against known API keys
+#[derive(Debug, Clone)]
+pub struct TokenVerifier {
+    keys: HashMap<String, String>,
+    leeway: Duration,
+}
+
+impl Default for TokenVerifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TokenVerifier {
+    /// Create a verifier with no keys and 30 seconds of clock leeway
+    pub fn new() -> Self {
+        Self {
+            keys: HashMap::new(),
+            leeway: Duration::from_secs(30),
+        }
+    }
+
+    /// Accept tokens signed with `api_key`'s `secret`
+    pub fn with_key(mut self, api_key: impl Into<String>, secret: impl Into<String>) -> Self {
+        self.keys.insert(api_key.into(), secret.into());
+        self
+    }
+
+    /// Set the clock skew tolerated on `nbf` and `exp`
+    pub fn with_leeway(mut self, leeway: Duration) -> Self {
+        self.leeway = leeway;
+        self
+    }
+
+    /// Check a token's signature and validity window, returning its claims
+    pub fn verify(&self, token: &str) -> Result<TokenClaims, QuicRtcError> {
+        let unauthorized = |reason: &str| QuicRtcError::Unauthorized {
+            reason: reason.to_string(),
+        };
+
+        let mut parts = token.split('.');
+        let (Some(header), Some(payload), Some(signature), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(unauthorized("Malformed access token"));
+        };
+
+        let header_json: serde_json::Value = URL_SAFE_NO_PAD
+            .decode(header)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or_else(|| unauthorized("Malformed access token header"))?;
+        if header_json["alg"] != "HS256" {
+            return Err(unauthorized("Unsupported access token algorithm"));
+        }
+
+        let claims: TokenClaims = URL_SAFE_NO_PAD
+            .decode(payload)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or_else(|| unauthorized("Malformed access token claims"))?;
+
+        let secret = self
+            .keys
+            .get(&claims.iss)
+            .ok_or_else(|| unauthorized("Unknown API key"))?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| unauthorized("Malformed access token signature"))?;
+
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key");
+        mac.update(&token.as_bytes()[..header.len() + payload.len() + 1]);
+        mac.verify_slice(&signature)
+            .map_err(|_| unauthorized("Invalid access token signature"))?;
+
+        let now = chrono::Utc::now().timestamp();
+        let leeway = self.leeway.as_secs() as i64;
+        if now + leeway < claims.nbf {
+            return Err(unauthorized("Access token not yet valid"));
+        }
+        if now - leeway >= claims.exp {
+            return Err(unauthorized("Access token expired"));
+        }
+
+        Ok(claims)
+    }
+}
+
+fn hmac_sha256(secret: &str, input: &str) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key");
+    mac.update(input.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
@@ -0,0 +1,346 @@
+//! Webhook delivery for room lifecycle events
+//!
+//! Events are queued without blocking the signaling server and POSTed as
+//! JSON to every configured endpoint by a background task. Each request
+//! carries an `X-QuicRtc-Signature` header of the form `t=<unix>,v1=<hex>`,
+//! where `v1` is the HMAC-SHA256 of `"<t>.<body>"` keyed with the signing
+//! secret. Receivers can check it with [`verify_signature`].
+
+use crate::lifetime::RoomCloseReason;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+/// Header carrying the request signature
+pub const SIGNATURE_HEADER: &str = "X-QuicRtc-Signature";
+/// Header carrying the event ID, stable across retries
+pub const EVENT_ID_HEADER: &str = "X-QuicRtc-Event-Id";
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Webhook delivery configuration
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// URLs every event is delivered to
+    pub endpoints: Vec<String>,
+    /// Secret used to sign request bodies
+    pub signing_secret: String,
+    /// Retries after the first failed attempt
+    pub max_retries: u32,
+    /// Delay before the first retry; doubles after each attempt
+    pub initial_backoff: Duration,
+    /// Upper bound on the retry delay
+    pub max_backoff: Duration,
+    /// Timeout for each delivery attempt
+    pub request_timeout: Duration,
+    /// Events buffered before new ones are dropped
+    pub queue_capacity: usize,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            endpoints: Vec::new(),
+            signing_secret: String::new(),
+            max_retries: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+            request_timeout: Duration::from_secs(10),
+            queue_capacity: 1024,
+        }
+    }
+}
+
+/// Room lifecycle event delivered to webhooks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEvent {
+    /// Unique event ID
+    pub id: String,
+    /// When the event happened
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// What happened
+    #[serde(flatten)]
+    pub kind: WebhookEventKind,
+}
+
+/// Kinds of room lifecycle events
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WebhookEventKind {
+    /// A room was created
+    RoomCreated {
+        /// Room ID
+        room_id: String,
+    },
+    /// A room was closed
+    RoomDestroyed {
+        /// Room ID
+        room_id: String,
+        /// Why the room was closed
+        #[serde(default)]
+        reason: RoomCloseReason,
+    },
+    /// A participant joined a room
+    ParticipantJoined {
+        /// Room ID
+        room_id: String,
+        /// Participant ID
+        participant_id: String,
+        /// Participant display name
+        participant_name: Option<String>,
+    },
+    /// A participant left or was removed from a room
+    ParticipantLeft {
+        /// Room ID
+        room_id: String,
+        /// Participant ID
+        participant_id: String,
+    },
+    /// A room recording finished
+    RecordingFinished {
+        /// Room ID
+        room_id: String,
+        /// Recording ID
+        recording_id: String,
+        /// Where the recording was written, if specified
+        output: Option<String>,
+        /// Recording length in seconds
+        duration_secs: i64,
+    },
+}
+
+impl WebhookEvent {
+    /// Create an event that happened now
+    pub fn new(kind: WebhookEventKind) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            created_at: chrono::Utc::now(),
+            kind,
+        }
+    }
+}
+
+/// Webhook delivery counters
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WebhookStats {
+    /// Events accepted into the delivery queue
+    pub events_queued: u64,
+    /// Events dropped because the queue was full
+    pub events_dropped: u64,
+    /// Deliveries acknowledged with a 2xx response
+    pub deliveries_succeeded: u64,
+    /// Deliveries abandoned after exhausting retries
+    pub deliveries_failed: u64,
+    /// Attempts that failed and were retried
+    pub retries: u64,
+}
+
+#[derive(Debug, Default)]
+struct WebhookCounters {
+    events_queued: AtomicU64,
+    events_dropped: AtomicU64,
+    deliveries_succeeded: AtomicU64,
+    deliveries_failed: AtomicU64,
+    retries: AtomicU64,
+}
+
+/// Queues webhook events and delivers them in the background
+#[derive(Debug, Clone)]
+pub struct WebhookDispatcher {
+    event_tx: mpsc::Sender<WebhookEvent>,
+    counters: Arc<WebhookCounters>,
+}
+
+impl WebhookDispatcher {
+    /// Start the delivery task; must be called within a Tokio runtime
+    pub fn new(config: WebhookConfig) -> Self {
+        let (event_tx, event_rx) = mpsc::channel(config.queue_capacity.max(1));
+        let counters = Arc::new(WebhookCounters::default());
+
+        tokio::spawn(Self::run_delivery(config, event_rx, counters.clone()));
+
+        Self { event_tx, counters }
+    }
+
+    /// Queue an event for delivery without waiting
+    ///
+    /// If the queue is full the event is dropped and counted in
+    /// [`WebhookStats::events_dropped`].
+    pub fn dispatch(&self, kind: WebhookEventKind) {
+        match self.event_tx.try_send(WebhookEvent::new(kind)) {
+            Ok(()) => {
+                self.counters.events_queued.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(e) => {
+                self.counters.events_dropped.fetch_add(1, Ordering::Relaxed);
+                tracing::warn!("Dropping webhook event: {}", e);
+            }
+        }
+    }
+
+    /// Snapshot of the delivery counters
+    pub fn stats(&self) -> WebhookStats {
+        WebhookStats {
+            events_queued: self.counters.events_queued.load(Ordering::Relaxed),
+            events_dropped: self.counters.events_dropped.load(Ordering::Relaxed),
+            deliveries_succeeded: self.counters.deliveries_succeeded.load(Ordering::Relaxed),
+            deliveries_failed: self.counters.deliveries_failed.load(Ordering::Relaxed),
+            retries: self.counters.retries.load(Ordering::Relaxed),
+        }
+    }
+
+    async fn run_delivery(
+        config: WebhookConfig,
+        mut event_rx: mpsc::Receiver<WebhookEvent>,
+        counters: Arc<WebhookCounters>,
+    ) {
+        let client = match reqwest::Client::builder()
+            .timeout(config.request_timeout)
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::error!("Failed to create webhook HTTP client: {}", e);
+                return;
+            }
+        };
+
+        // Events are delivered in order; a failing endpoint delays the
+        // events behind it by at most its retry budget
+        while let Some(event) = event_rx.recv().await {
+            let body = match serde_json::to_vec(&event) {
+                Ok(body) => body,
+                Err(e) => {
+                    tracing::error!("Failed to serialize webhook event {}: {}", event.id, e);
+                    continue;
+                }
+            };
+
+            for endpoint in &config.endpoints {
+                let delivered =
+                    Self::deliver(&client, &config, &counters, endpoint, &event.id, &body).await;
+                let counter = if delivered {
+                    &counters.deliveries_succeeded
+                } else {
+                    &counters.deliveries_failed
+                };
+                counter.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// POST one event to one endpoint, retrying with exponential backoff
+    async fn deliver(
+        client: &reqwest::Client,
+        config: &WebhookConfig,
+        counters: &WebhookCounters,
+        endpoint: &str,
+        event_id: &str,
+        body: &[u8],
+    ) -> bool {
+        let mut backoff = config.initial_backoff;
+
+        for attempt in 0..=config.max_retries {
+            if attempt > 0 {
+                counters.retries.fetch_add(1, Ordering::Relaxed);
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(config.max_backoff);
+            }
+
+            // Sign every attempt so receivers can enforce a freshness window
+            let signature = sign(&config.signing_secret, chrono::Utc::now().timestamp(), body);
+            let result = client
+                .post(endpoint)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(SIGNATURE_HEADER, signature)
+                .header(EVENT_ID_HEADER, event_id)
+                .body(body.to_vec())
+                .send()
+                .await;
+
+            match result {
+                Ok(response) if response.status().is_success() => return true,
+                Ok(response) => tracing::warn!(
+                    "Webhook {} to {} rejected with {} (attempt {})",
+                    event_id,
+                    endpoint,
+                    response.status(),
+                    attempt + 1
+                ),
+                Err(e) => tracing::warn!(
+                    "Webhook {} to {} failed: {} (attempt {})",
+                    event_id,
+                    endpoint,
+                    e,
+                    attempt + 1
+                ),
+            }
+        }
+
+        tracing::error!(
+            "Giving up on webhook {} to {} after {} attempts",
+            event_id,
+            endpoint,
+            config.max_retries + 1
+        );
+        false
+    }
+}
+
+/// Compute the signature header value for `body` sent at `timestamp`
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let digest = signature_digest(secret, timestamp, body);
+    let hex: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("t={},v1={}", timestamp, hex)
+}
+
+/// Check a signature header against `body`
+///
+/// Rejects signatures older or newer than `tolerance` relative to now, so
+/// captured requests cannot be replayed later.
+pub fn verify_signature(secret: &str, header: &str, body: &[u8], tolerance: Duration) -> bool {
+    let mut timestamp = None;
+    let mut signature = None;
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", value)) => timestamp = value.parse::<i64>().ok(),
+            Some(("v1", value)) => signature = decode_hex(value),
+            _ => {}
+        }
+    }
+    let (Some(timestamp), Some(signature)) = (timestamp, signature) else {
+        return false;
+    };
+
+    let age = chrono::Utc::now().timestamp().abs_diff(timestamp);
+    if age > tolerance.as_secs() {
+        return false;
+    }
+
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key");
+    mac.update(format!("{}.", timestamp).as_bytes());
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
+
+fn signature_digest(secret: &str, timestamp: i64, body: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key");
+    mac.update(format!("{}.", timestamp).as_bytes());
+    mac.update(body);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
@@ -0,0 +1,149 @@
+//! Tests for the signaling admin REST API
+
+use std::net::{Ipv4Addr, SocketAddr};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use quicrtc_signaling::admin::{ListRoomsResponse, RoomStatsResponse, StartRecordingResponse};
+use quicrtc_signaling::{AdminConfig, AdminServer, SignalingServer};
+
+const TOKEN: &str = "test-admin-token";
+
+async fn start_admin_server() -> (SignalingServer, SocketAddr) {
+    let signaling = SignalingServer::new(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 0));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let admin = AdminServer::new(
+        signaling.clone(),
+        AdminConfig {
+            bind_addr: addr,
+            api_token: TOKEN.to_string(),
+        },
+    );
+    tokio::spawn(async move {
+        let _ = admin.serve(listener).await;
+    });
+
+    (signaling, addr)
+}
+
+/// Send one HTTP/1.1 request and return the status code and body
+async fn request(
+    addr: SocketAddr,
+    method: &str,
+    path: &str,
+    token: Option<&str>,
+    body: Option<&str>,
+) -> (u16, String) {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    let mut request = format!(
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n",
+        method, path
+    );
+    if let Some(token) = token {
+        request.push_str(&format!("Authorization: Bearer {}\r\n", token));
+    }
+    let body = body.unwrap_or("");
+    if !body.is_empty() {
+        request.push_str("Content-Type: application/json\r\n");
+    }
+    request.push_str(&format!("Content-Length: {}\r\n\r\n{}", body.len(), body));
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+
+    let status = response
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .unwrap();
+    let body = response
+        .split_once("\r\n\r\n")
+        .map(|(_, body)| body.to_string())
+        .unwrap_or_default();
+    (status, body)
+}
+
+#[tokio::test]
+async fn test_admin_api_requires_token() {
+    let (_signaling, addr) = start_admin_server().await;
+
+    let (status, _) = request(addr, "GET", "/rooms", None, None).await;
+    assert_eq!(status, 401);
+
+    let (status, _) = request(addr, "GET", "/rooms", Some("wrong-token"), None).await;
+    assert_eq!(status, 401);
+
+    let (status, _) = request(addr, "GET", "/rooms", Some(TOKEN), None).await;
+    assert_eq!(status, 200);
+}
+
+#[tokio::test]
+async fn test_admin_api_room_management() {
+    let (signaling, addr) = start_admin_server().await;
+
+    let (status, _) = request(
+        addr,
+        "POST",
+        "/rooms",
+        Some(TOKEN),
+        Some(r#"{"room_id":"standup","room_name":"Daily standup","max_participants":4}"#),
+    )
+    .await;
+    assert_eq!(status, 201);
+
+    let (status, body) = request(addr, "GET", "/rooms", Some(TOKEN), None).await;
+    assert_eq!(status, 200);
+    let rooms: ListRoomsResponse = serde_json::from_str(&body).unwrap();
+    assert_eq!(rooms.rooms.len(), 1);
+    assert_eq!(rooms.rooms[0].room_id, "standup");
+    assert_eq!(rooms.rooms[0].max_participants, 4);
+
+    let (status, body) = request(
+        addr,
+        "POST",
+        "/rooms/standup/recordings",
+        Some(TOKEN),
+        Some(r#"{"output":"s3://recordings/standup"}"#),
+    )
+    .await;
+    assert_eq!(status, 200);
+    let recording: StartRecordingResponse = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        recording.recording.output.as_deref(),
+        Some("s3://recordings/standup")
+    );
+
+    // A second recording on the same room conflicts
+    let (status, _) = request(addr, "POST", "/rooms/standup/recordings", Some(TOKEN), None).await;
+    assert_eq!(status, 409);
+
+    let (status, body) = request(addr, "GET", "/rooms/standup/stats", Some(TOKEN), None).await;
+    assert_eq!(status, 200);
+    let stats: RoomStatsResponse = serde_json::from_str(&body).unwrap();
+    assert_eq!(stats.participant_count, 0);
+    assert_eq!(
+        stats.recording.map(|r| r.recording_id),
+        Some(recording.recording.recording_id)
+    );
+
+    let (status, _) = request(
+        addr,
+        "POST",
+        "/rooms/standup/participants/nobody/kick",
+        Some(TOKEN),
+        None,
+    )
+    .await;
+    assert_eq!(status, 404);
+
+    let (status, _) = request(addr, "DELETE", "/rooms/standup", Some(TOKEN), None).await;
+    assert_eq!(status, 200);
+    assert!(signaling.get_rooms().await.is_empty());
+
+    let (status, _) = request(addr, "GET", "/rooms/standup/stats", Some(TOKEN), None).await;
+    assert_eq!(status, 404);
+}
@@ -0,0 +1,175 @@
+//! Tests for recording and transcription consent
+
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+use quicrtc_core::QuicRtcError;
+use quicrtc_signaling::protocol::{SignalingMessage, SignalingResponse};
+use quicrtc_signaling::{
+    CapabilitySet, CaptureKind, ConsentPolicy, SignalingClient, SignalingServer,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+async fn start_server(policy: ConsentPolicy) -> (SignalingServer, String) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = SignalingServer::new(addr).with_consent_policy(policy);
+
+    let server_clone = server.clone();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let server = server_clone.clone();
+            tokio::spawn(async move { server.handle_test_connection(stream).await });
+        }
+    });
+
+    (server, format!("ws://{}", addr))
+}
+
+async fn join(url: &str, room_id: &str, participant_id: &str) -> SignalingClient {
+    let client = SignalingClient::connect(url).await.unwrap();
+    client
+        .send(&SignalingMessage::JoinRoom {
+            room_id: room_id.to_string(),
+            participant_id: participant_id.to_string(),
+            participant_name: None,
+            capabilities: CapabilitySet::new(),
+            quic_endpoint: None,
+            access_token: None,
+        })
+        .await
+        .unwrap();
+    client
+        .expect("join", TIMEOUT, |response| {
+            matches!(response, SignalingResponse::JoinedRoom { .. }).then_some(())
+        })
+        .await
+        .unwrap();
+    client
+}
+
+/// Wait for a consent request and answer it
+async fn answer(client: &SignalingClient, room_id: &str, accepted: bool) -> CaptureKind {
+    let (request_id, kind) = client
+        .expect("consent request", TIMEOUT, |response| match response {
+            SignalingResponse::CaptureConsentRequested {
+                request_id,
+                kind,
+                requires_acknowledgement,
+                ..
+            } => {
+                assert!(requires_acknowledgement);
+                Some((request_id, kind))
+            }
+            _ => None,
+        })
+        .await
+        .unwrap();
+    client
+        .send(&SignalingMessage::CaptureConsent {
+            room_id: room_id.to_string(),
+            request_id,
+            accepted,
+        })
+        .await
+        .unwrap();
+    kind
+}
+
+#[tokio::test]
+async fn test_recording_starts_once_everyone_accepts() {
+    let (server, url) = start_server(ConsentPolicy::require_acknowledgement()).await;
+    server
+        .create_room("board".to_string(), None, None)
+        .await
+        .unwrap();
+    let alice = join(&url, "board", "alice").await;
+    let bob = join(&url, "board", "bob").await;
+
+    let recording = tokio::spawn({
+        let server = server.clone();
+        async move { server.start_recording("board", None).await }
+    });
+    assert_eq!(answer(&alice, "board", true).await, CaptureKind::Recording);
+    assert_eq!(answer(&bob, "board", true).await, CaptureKind::Recording);
+    let recording = recording.await.unwrap().unwrap();
+
+    let started = bob
+        .expect("recording indicator", TIMEOUT, |response| match response {
+            SignalingResponse::RecordingStarted { recording, .. } => Some(recording),
+            _ => None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(started.recording_id, recording.recording_id);
+
+    // Late joiners are told about the recording in progress
+    let carol = join(&url, "board", "carol").await;
+    carol
+        .expect("recording indicator", TIMEOUT, |response| {
+            matches!(response, SignalingResponse::RecordingStarted { .. }).then_some(())
+        })
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_declined_transcription_is_refused() {
+    let (server, url) = start_server(ConsentPolicy::require_acknowledgement()).await;
+    server
+        .create_room("clinic".to_string(), None, None)
+        .await
+        .unwrap();
+    let alice = join(&url, "clinic", "alice").await;
+    let bob = join(&url, "clinic", "bob").await;
+
+    let transcription = tokio::spawn({
+        let server = server.clone();
+        async move { server.start_transcription("clinic", None).await }
+    });
+    assert_eq!(
+        answer(&alice, "clinic", true).await,
+        CaptureKind::Transcription
+    );
+    answer(&bob, "clinic", false).await;
+
+    match transcription.await.unwrap() {
+        Err(QuicRtcError::ConsentRefused { participants, .. }) => {
+            assert_eq!(participants, vec!["bob".to_string()]);
+        }
+        other => panic!("Expected consent refusal, got {:?}", other),
+    }
+    assert!(server
+        .get_room("clinic")
+        .await
+        .unwrap()
+        .transcription
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_unanswered_request_times_out() {
+    let policy =
+        ConsentPolicy::require_acknowledgement().with_ack_timeout(Duration::from_millis(200));
+    let (server, url) = start_server(policy).await;
+    server
+        .create_room("lobby".to_string(), None, None)
+        .await
+        .unwrap();
+    let _silent = join(&url, "lobby", "silent").await;
+
+    match server.start_recording("lobby", None).await {
+        Err(QuicRtcError::ConsentRefused { participants, .. }) => {
+            assert_eq!(participants, vec!["silent".to_string()]);
+        }
+        other => panic!("Expected consent refusal, got {:?}", other),
+    }
+
+    // Rooms without the requirement only notify participants
+    server
+        .set_consent_policy("lobby", ConsentPolicy::default())
+        .await
+        .unwrap();
+    server.start_recording("lobby", None).await.unwrap();
+}
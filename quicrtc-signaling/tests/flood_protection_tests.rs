@@ -0,0 +1,102 @@
+//! Tests for signaling message size and rate limits
+
+use futures::stream::SplitStream;
+use futures::{SinkExt, StreamExt};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+use quicrtc_signaling::protocol::{SignalingMessage, SignalingResponse};
+use quicrtc_signaling::{FloodProtectionConfig, SignalingServer};
+
+type WsRead = SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>;
+
+async fn start_server(config: FloodProtectionConfig) -> (SignalingServer, String) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = SignalingServer::new(addr).with_flood_protection(config);
+
+    let server_clone = server.clone();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let server = server_clone.clone();
+            tokio::spawn(async move { server.handle_test_connection(stream).await });
+        }
+    });
+
+    (server, format!("ws://{}", addr))
+}
+
+/// Read responses until the server closes the connection
+async fn read_until_closed(read: &mut WsRead) -> Vec<SignalingResponse> {
+    let mut responses = Vec::new();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(Ok(message)) = read.next().await {
+            match message {
+                Message::Text(text) => responses.push(serde_json::from_str(&text).unwrap()),
+                Message::Close(_) => break,
+                _ => {}
+            }
+        }
+    })
+    .await
+    .expect("server should close the connection");
+    responses
+}
+
+#[tokio::test]
+async fn test_flooding_client_is_throttled_then_disconnected() {
+    let (server, url) = start_server(FloodProtectionConfig {
+        messages_per_second: 50.0,
+        burst: 5,
+        max_consecutive_throttled: 10,
+        ..FloodProtectionConfig::default()
+    })
+    .await;
+
+    let (ws, _) = connect_async(&url).await.unwrap();
+    let (mut write, mut read) = ws.split();
+
+    let list_rooms = serde_json::to_string(&SignalingMessage::ListRooms).unwrap();
+    for _ in 0..30 {
+        if write.send(Message::Text(list_rooms.clone())).await.is_err() {
+            break;
+        }
+    }
+
+    let responses = read_until_closed(&mut read).await;
+    let room_lists = responses
+        .iter()
+        .filter(|r| matches!(r, SignalingResponse::RoomList { .. }))
+        .count();
+    // Burst plus throttled messages are answered before the disconnect
+    assert_eq!(room_lists, 15);
+    assert!(matches!(
+        responses.last(),
+        Some(SignalingResponse::Error { error_code, .. }) if error_code == "RATE_LIMITED"
+    ));
+
+    let metrics = server.metrics().snapshot();
+    assert_eq!(metrics.connections_accepted, 1);
+    assert_eq!(metrics.messages_throttled, 10);
+    assert_eq!(metrics.connections_rate_limited, 1);
+}
+
+#[tokio::test]
+async fn test_oversized_message_closes_connection() {
+    let (server, url) = start_server(FloodProtectionConfig {
+        max_message_size: 1024,
+        ..FloodProtectionConfig::default()
+    })
+    .await;
+
+    let (ws, _) = connect_async(&url).await.unwrap();
+    let (mut write, mut read) = ws.split();
+
+    write.send(Message::Text("x".repeat(4096))).await.unwrap();
+    read_until_closed(&mut read).await;
+
+    let metrics = server.metrics().snapshot();
+    assert_eq!(metrics.messages_oversized, 1);
+    assert_eq!(metrics.messages_received, 0);
+}
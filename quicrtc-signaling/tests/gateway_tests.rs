@@ -0,0 +1,120 @@
+//! Tests for bridging phone calls into rooms
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+use async_trait::async_trait;
+use quicrtc_core::QuicRtcError;
+use quicrtc_signaling::protocol::{SignalingMessage, SignalingResponse};
+use quicrtc_signaling::{
+    CallDirection, CapabilitySet, PhoneBridge, PhoneNumber, SignalingClient, SignalingServer,
+    TelephonyGateway,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Gateway that answers every call and remembers what it was asked to do
+#[derive(Debug, Default)]
+struct RecordingGateway {
+    actions: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl TelephonyGateway for RecordingGateway {
+    async fn dial(&self, call_id: &str, number: &PhoneNumber) -> Result<(), QuicRtcError> {
+        self.actions
+            .lock()
+            .unwrap()
+            .push(format!("dial {} {}", call_id, number));
+        Ok(())
+    }
+
+    async fn hang_up(&self, call_id: &str) -> Result<(), QuicRtcError> {
+        self.actions
+            .lock()
+            .unwrap()
+            .push(format!("hang up {}", call_id));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_dial_out_joins_phone_participant() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = SignalingServer::new(addr);
+    tokio::spawn({
+        let server = server.clone();
+        async move { server.serve(listener).await }
+    });
+    server
+        .create_room("meeting".to_string(), None, None)
+        .await
+        .unwrap();
+
+    let client = SignalingClient::connect(&format!("ws://{}", addr))
+        .await
+        .unwrap();
+    client
+        .send(&SignalingMessage::JoinRoom {
+            room_id: "meeting".to_string(),
+            participant_id: "alice".to_string(),
+            participant_name: None,
+            capabilities: CapabilitySet::new(),
+            quic_endpoint: None,
+            access_token: None,
+        })
+        .await
+        .unwrap();
+    client
+        .expect("join", TIMEOUT, |response| {
+            matches!(response, SignalingResponse::JoinedRoom { .. }).then_some(())
+        })
+        .await
+        .unwrap();
+
+    let gateway = Arc::new(RecordingGateway::default());
+    let bridge = PhoneBridge::new(server.clone(), gateway.clone());
+    let number = PhoneNumber::parse("+14155550123").unwrap();
+
+    // Calling into a room that does not exist never dials
+    assert!(bridge.dial_out("missing", number.clone()).await.is_err());
+    assert!(gateway.actions.lock().unwrap().is_empty());
+
+    // The answered call shows up to the room like any participant
+    let call = bridge.dial_out("meeting", number).await.unwrap();
+    assert_eq!(call.direction, CallDirection::Outbound);
+    let joined = client
+        .expect("phone joined", TIMEOUT, |response| match response {
+            SignalingResponse::ParticipantJoined { participant, .. } => Some(participant),
+            _ => None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(joined.id, call.participant_id);
+    assert_eq!(joined.name.as_deref(), Some("+*******0123"));
+    assert_eq!(bridge.calls(), vec![call.clone()]);
+
+    // Hanging up ends the call on both sides
+    bridge.hang_up(&call.call_id).await.unwrap();
+    let left = client
+        .expect("phone left", TIMEOUT, |response| match response {
+            SignalingResponse::ParticipantLeft { participant_id, .. } => Some(participant_id),
+            _ => None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(left, call.participant_id);
+    assert!(bridge.calls().is_empty());
+    assert_eq!(
+        *gateway.actions.lock().unwrap(),
+        vec![
+            format!("dial {} +14155550123", call.call_id),
+            format!("hang up {}", call.call_id),
+        ]
+    );
+
+    let room = server.get_room("meeting").await.unwrap();
+    assert_eq!(room.participants.len(), 1);
+}
@@ -13,20 +13,20 @@ use std::time::Duration;
 use tokio::time::timeout;
 
 use quicrtc_signaling::{
-    DiscoveryConfig, DiscoveryEvent, PeerDiscovery, PeerInfo, PeerStatus, RoomStats,
+    CapabilitySet, DiscoveryConfig, DiscoveryEvent, PeerDiscovery, PeerInfo, PeerStatus, RoomStats,
 };
 
 fn get_test_addr() -> SocketAddr {
     SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 0)
 }
 
-fn create_test_peer(id: &str, room_id: &str, capabilities: Vec<String>) -> PeerInfo {
+fn create_test_peer(id: &str, room_id: &str, capabilities: &[&str]) -> PeerInfo {
     PeerInfo {
         id: id.to_string(),
         name: Some(format!("Test Peer {}", id)),
         room_id: room_id.to_string(),
         quic_endpoint: Some(get_test_addr()),
-        capabilities,
+        capabilities: CapabilitySet::from_names(capabilities),
         last_seen: Utc::now(),
         status: PeerStatus::Online,
     }
@@ -38,21 +38,9 @@ async fn test_peer_capability_matching() {
     discovery.start().await.unwrap();
 
     // Add peers with different capabilities
-    let video_peer = create_test_peer(
-        "video-peer",
-        "media-room",
-        vec!["h264".to_string(), "vp8".to_string()],
-    );
-    let audio_peer = create_test_peer(
-        "audio-peer",
-        "media-room",
-        vec!["opus".to_string(), "g722".to_string()],
-    );
-    let full_peer = create_test_peer(
-        "full-peer",
-        "media-room",
-        vec!["h264".to_string(), "opus".to_string()],
-    );
+    let video_peer = create_test_peer("video-peer", "media-room", &["h264", "vp8"]);
+    let audio_peer = create_test_peer("audio-peer", "media-room", &["opus", "g722"]);
+    let full_peer = create_test_peer("full-peer", "media-room", &["h264", "opus"]);
 
     discovery.add_peer(video_peer).await.unwrap();
     discovery.add_peer(audio_peer).await.unwrap();
@@ -60,7 +48,7 @@ async fn test_peer_capability_matching() {
 
     // Find peers with video capabilities
     let video_peers = discovery
-        .find_peers_with_capabilities("media-room", &["h264".to_string()])
+        .find_peers_with_capabilities("media-room", &CapabilitySet::from_names(&["h264"]))
         .await
         .unwrap();
     assert_eq!(video_peers.len(), 2); // video-peer and full-peer
@@ -71,7 +59,7 @@ async fn test_peer_capability_matching() {
 
     // Find peers with audio capabilities
     let audio_peers = discovery
-        .find_peers_with_capabilities("media-room", &["opus".to_string()])
+        .find_peers_with_capabilities("media-room", &CapabilitySet::from_names(&["opus"]))
         .await
         .unwrap();
     assert_eq!(audio_peers.len(), 2); // audio-peer and full-peer
@@ -82,7 +70,7 @@ async fn test_peer_capability_matching() {
 
     // Find peers with both video and audio
     let multimedia_peers = discovery
-        .find_peers_with_capabilities("media-room", &["h264".to_string(), "opus".to_string()])
+        .find_peers_with_capabilities("media-room", &CapabilitySet::from_names(&["h264", "opus"]))
         .await
         .unwrap();
     assert_eq!(multimedia_peers.len(), 1); // Only full-peer
@@ -90,7 +78,7 @@ async fn test_peer_capability_matching() {
 
     // Find peers with non-existent capability
     let special_peers = discovery
-        .find_peers_with_capabilities("media-room", &["av1".to_string()])
+        .find_peers_with_capabilities("media-room", &CapabilitySet::from_names(&["av1"]))
         .await
         .unwrap();
     assert_eq!(special_peers.len(), 0);
@@ -102,9 +90,9 @@ async fn test_peer_status_filtering() {
     discovery.start().await.unwrap();
 
     // Add peers with different statuses
-    let online_peer = create_test_peer("online-peer", "status-room", vec!["h264".to_string()]);
-    let away_peer = create_test_peer("away-peer", "status-room", vec!["opus".to_string()]);
-    let offline_peer = create_test_peer("offline-peer", "status-room", vec!["vp8".to_string()]);
+    let online_peer = create_test_peer("online-peer", "status-room", &["h264"]);
+    let away_peer = create_test_peer("away-peer", "status-room", &["opus"]);
+    let offline_peer = create_test_peer("offline-peer", "status-room", &["vp8"]);
 
     discovery.add_peer(online_peer).await.unwrap();
     discovery.add_peer(away_peer).await.unwrap();
@@ -151,8 +139,8 @@ async fn test_room_synchronization_with_conflicts() {
     let mut event_receiver = discovery.subscribe_events();
 
     // Add initial peers
-    let peer1 = create_test_peer("peer-1", "sync-room", vec!["h264".to_string()]);
-    let peer2 = create_test_peer("peer-2", "sync-room", vec!["opus".to_string()]);
+    let peer1 = create_test_peer("peer-1", "sync-room", &["h264"]);
+    let peer2 = create_test_peer("peer-2", "sync-room", &["opus"]);
 
     discovery.add_peer(peer1.clone()).await.unwrap();
     discovery.add_peer(peer2.clone()).await.unwrap();
@@ -170,12 +158,12 @@ async fn test_room_synchronization_with_conflicts() {
             Ipv4Addr::new(192, 168, 1, 100).into(),
             8080,
         )),
-        capabilities: vec!["h264".to_string(), "vp9".to_string()], // Added capability
+        capabilities: CapabilitySet::from_names(&["h264", "vp9"]), // Added capability
         last_seen: Utc::now(),
         status: PeerStatus::Online,
     };
 
-    let new_peer3 = create_test_peer("peer-3", "sync-room", vec!["av1".to_string()]);
+    let new_peer3 = create_test_peer("peer-3", "sync-room", &["av1"]);
 
     // peer-2 is missing from the sync (simulating it went offline)
     discovery
@@ -212,7 +200,7 @@ async fn test_room_synchronization_with_conflicts() {
     // Verify peer-1 was updated
     let updated_peer = final_peers.iter().find(|p| p.id == "peer-1").unwrap();
     assert_eq!(updated_peer.name, Some("Updated Peer 1".to_string()));
-    assert_eq!(updated_peer.capabilities.len(), 2); // h264 and vp9
+    assert_eq!(updated_peer.capabilities.codecs.len(), 2); // h264 and vp9
 }
 
 #[tokio::test]
@@ -221,9 +209,9 @@ async fn test_room_statistics() {
     discovery.start().await.unwrap();
 
     // Add peers to a room
-    let peer1 = create_test_peer("stats-peer-1", "stats-room", vec!["h264".to_string()]);
-    let peer2 = create_test_peer("stats-peer-2", "stats-room", vec!["opus".to_string()]);
-    let peer3 = create_test_peer("stats-peer-3", "stats-room", vec!["vp8".to_string()]);
+    let peer1 = create_test_peer("stats-peer-1", "stats-room", &["h264"]);
+    let peer2 = create_test_peer("stats-peer-2", "stats-room", &["opus"]);
+    let peer3 = create_test_peer("stats-peer-3", "stats-room", &["vp8"]);
 
     discovery.add_peer(peer1).await.unwrap();
     discovery.add_peer(peer2).await.unwrap();
@@ -261,14 +249,14 @@ async fn test_discovery_with_room_capacity_limits() {
     discovery.start().await.unwrap();
 
     // Add peers up to capacity
-    let peer1 = create_test_peer("capacity-peer-1", "capacity-room", vec!["h264".to_string()]);
-    let peer2 = create_test_peer("capacity-peer-2", "capacity-room", vec!["opus".to_string()]);
+    let peer1 = create_test_peer("capacity-peer-1", "capacity-room", &["h264"]);
+    let peer2 = create_test_peer("capacity-peer-2", "capacity-room", &["opus"]);
 
     assert!(discovery.add_peer(peer1).await.is_ok());
     assert!(discovery.add_peer(peer2).await.is_ok());
 
     // Adding one more should fail
-    let peer3 = create_test_peer("capacity-peer-3", "capacity-room", vec!["vp8".to_string()]);
+    let peer3 = create_test_peer("capacity-peer-3", "capacity-room", &["vp8"]);
     let result = discovery.add_peer(peer3).await;
 
     assert!(result.is_err());
@@ -277,7 +265,7 @@ async fn test_discovery_with_room_capacity_limits() {
     }
 
     // But adding to a different room should work
-    let peer4 = create_test_peer("capacity-peer-4", "other-room", vec!["av1".to_string()]);
+    let peer4 = create_test_peer("capacity-peer-4", "other-room", &["av1"]);
     assert!(discovery.add_peer(peer4).await.is_ok());
 }
 
@@ -289,7 +277,7 @@ async fn test_event_driven_discovery_workflow() {
     let mut event_receiver = discovery.subscribe_events();
 
     // Create a test workflow: discovery -> status change -> removal
-    let peer = create_test_peer("workflow-peer", "workflow-room", vec!["h264".to_string()]);
+    let peer = create_test_peer("workflow-peer", "workflow-room", &["h264"]);
 
     // Step 1: Add peer (should trigger PeerDiscovered)
     discovery.add_peer(peer).await.unwrap();
@@ -364,10 +352,10 @@ async fn test_multiple_rooms_isolation() {
     discovery.start().await.unwrap();
 
     // Add peers to different rooms
-    let room1_peer1 = create_test_peer("r1-peer1", "room-1", vec!["h264".to_string()]);
-    let room1_peer2 = create_test_peer("r1-peer2", "room-1", vec!["opus".to_string()]);
-    let room2_peer1 = create_test_peer("r2-peer1", "room-2", vec!["vp8".to_string()]);
-    let room2_peer2 = create_test_peer("r2-peer2", "room-2", vec!["g722".to_string()]);
+    let room1_peer1 = create_test_peer("r1-peer1", "room-1", &["h264"]);
+    let room1_peer2 = create_test_peer("r1-peer2", "room-1", &["opus"]);
+    let room2_peer1 = create_test_peer("r2-peer1", "room-2", &["vp8"]);
+    let room2_peer2 = create_test_peer("r2-peer2", "room-2", &["g722"]);
 
     discovery.add_peer(room1_peer1).await.unwrap();
     discovery.add_peer(room1_peer2).await.unwrap();
@@ -395,14 +383,14 @@ async fn test_multiple_rooms_isolation() {
 
     // Test capability search within specific rooms
     let h264_peers_room1 = discovery
-        .find_peers_with_capabilities("room-1", &["h264".to_string()])
+        .find_peers_with_capabilities("room-1", &CapabilitySet::from_names(&["h264"]))
         .await
         .unwrap();
     assert_eq!(h264_peers_room1.len(), 1);
     assert_eq!(h264_peers_room1[0].id, "r1-peer1");
 
     let h264_peers_room2 = discovery
-        .find_peers_with_capabilities("room-2", &["h264".to_string()])
+        .find_peers_with_capabilities("room-2", &CapabilitySet::from_names(&["h264"]))
         .await
         .unwrap();
     assert_eq!(h264_peers_room2.len(), 0); // No H.264 peers in room-2
@@ -436,7 +424,7 @@ async fn test_peer_cleanup_edge_cases() {
     assert!(result.is_err()); // Should fail
 
     // Add a peer and then remove the entire room by removing all peers
-    let peer = create_test_peer("cleanup-peer", "cleanup-room", vec!["h264".to_string()]);
+    let peer = create_test_peer("cleanup-peer", "cleanup-room", &["h264"]);
     discovery.add_peer(peer).await.unwrap();
 
     // Verify room exists
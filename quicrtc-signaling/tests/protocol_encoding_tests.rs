@@ -0,0 +1,299 @@
+//! Tests for signaling wire encodings and version negotiation
+
+use futures::{SinkExt, StreamExt};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+use quicrtc_signaling::protocol::{
+    FeatureSet, Negotiation, ProtocolFeature, SignalingMessage, SignalingResponse, WireEncoding,
+    PROTOCOL_VERSION,
+};
+use quicrtc_signaling::{CapabilitySet, SignalingClient, SignalingServer};
+
+async fn start_server() -> String {
+    start_server_with_features(FeatureSet::new().with(ProtocolFeature::BinaryEncoding)).await
+}
+
+async fn start_server_with_features(features: FeatureSet) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = SignalingServer::new(addr).with_features(features);
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let server = server.clone();
+            tokio::spawn(async move { server.handle_test_connection(stream).await });
+        }
+    });
+
+    format!("ws://{}", addr)
+}
+
+#[test]
+fn test_cbor_round_trip_is_smaller_than_json() {
+    let message = SignalingMessage::JoinRoom {
+        room_id: "standup".to_string(),
+        participant_id: "alice".to_string(),
+        participant_name: Some("Alice".to_string()),
+        capabilities: CapabilitySet::from_names(&["h264", "opus"]),
+        quic_endpoint: Some("127.0.0.1:4433".parse().unwrap()),
+        access_token: None,
+    };
+
+    let json = WireEncoding::Json.encode(&message).unwrap();
+    let cbor = WireEncoding::Cbor.encode(&message).unwrap();
+    assert!(cbor.len() < json.len());
+
+    match WireEncoding::Cbor.decode(&cbor).unwrap() {
+        SignalingMessage::JoinRoom {
+            room_id,
+            quic_endpoint,
+            ..
+        } => {
+            assert_eq!(room_id, "standup");
+            assert_eq!(quic_endpoint, Some("127.0.0.1:4433".parse().unwrap()));
+        }
+        other => panic!("Wrong message type: {:?}", other),
+    }
+
+    assert!(WireEncoding::Cbor
+        .decode::<SignalingMessage>(&json)
+        .is_err());
+    assert_eq!(
+        WireEncoding::negotiate(&[WireEncoding::Json, WireEncoding::Cbor]),
+        WireEncoding::Cbor
+    );
+    assert_eq!(WireEncoding::negotiate(&[]), WireEncoding::Json);
+}
+
+#[tokio::test]
+async fn test_hello_switches_responses_to_cbor() {
+    let url = start_server().await;
+    let (mut ws, _) = connect_async(&url).await.unwrap();
+
+    let hello = SignalingMessage::Hello {
+        protocol_version: PROTOCOL_VERSION,
+        encodings: vec![WireEncoding::Cbor, WireEncoding::Json],
+        features: FeatureSet::new(),
+    };
+    ws.send(Message::Text(serde_json::to_string(&hello).unwrap()))
+        .await
+        .unwrap();
+
+    let welcome = tokio::time::timeout(Duration::from_secs(5), ws.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    let Message::Text(welcome) = welcome else {
+        panic!("Welcome should be JSON, got {:?}", welcome);
+    };
+    assert!(matches!(
+        serde_json::from_str(&welcome).unwrap(),
+        SignalingResponse::Welcome {
+            protocol_version: PROTOCOL_VERSION,
+            encoding: WireEncoding::Cbor,
+            ..
+        }
+    ));
+
+    // Requests may be CBOR too; responses now are
+    let list_rooms = WireEncoding::Cbor
+        .encode(&SignalingMessage::ListRooms)
+        .unwrap();
+    ws.send(Message::Binary(list_rooms)).await.unwrap();
+
+    let response = tokio::time::timeout(Duration::from_secs(5), ws.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    let Message::Binary(data) = response else {
+        panic!("Expected a binary frame, got {:?}", response);
+    };
+    assert!(matches!(
+        WireEncoding::Cbor.decode(&data).unwrap(),
+        SignalingResponse::RoomList { .. }
+    ));
+}
+
+/// Send a JSON hello and return the JSON reply
+async fn send_hello(url: &str, hello: SignalingMessage) -> SignalingResponse {
+    let (mut ws, _) = connect_async(url).await.unwrap();
+    ws.send(Message::Text(serde_json::to_string(&hello).unwrap()))
+        .await
+        .unwrap();
+
+    let response = tokio::time::timeout(Duration::from_secs(5), ws.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    let Message::Text(response) = response else {
+        panic!("Expected a JSON reply, got {:?}", response);
+    };
+    serde_json::from_str(&response).unwrap()
+}
+
+#[tokio::test]
+async fn test_hello_version_negotiation() {
+    let url = start_server().await;
+
+    // Newer clients are stepped down to the server's version
+    let response = send_hello(
+        &url,
+        SignalingMessage::Hello {
+            protocol_version: PROTOCOL_VERSION + 1,
+            encodings: vec![WireEncoding::Json],
+            features: FeatureSet::new(),
+        },
+    )
+    .await;
+    assert_eq!(
+        Negotiation::from_response(&response).protocol_version,
+        PROTOCOL_VERSION
+    );
+
+    let response = send_hello(
+        &url,
+        SignalingMessage::Hello {
+            protocol_version: 0,
+            encodings: vec![WireEncoding::Json],
+            features: FeatureSet::new(),
+        },
+    )
+    .await;
+    assert!(matches!(
+        &response,
+        SignalingResponse::Error { error_code, .. } if error_code == "UNSUPPORTED_VERSION"
+    ));
+    // A client treats any non-welcome reply as an older server
+    assert_eq!(
+        Negotiation::from_response(&response),
+        Negotiation::baseline()
+    );
+}
+
+#[tokio::test]
+async fn test_hello_feature_negotiation() {
+    let url = start_server_with_features(
+        FeatureSet::new()
+            .with(ProtocolFeature::Simulcast)
+            .with(ProtocolFeature::Datagrams),
+    )
+    .await;
+
+    let response = send_hello(
+        &url,
+        SignalingMessage::Hello {
+            protocol_version: PROTOCOL_VERSION,
+            encodings: vec![WireEncoding::Cbor, WireEncoding::Json],
+            features: FeatureSet::all(),
+        },
+    )
+    .await;
+    let negotiation = Negotiation::from_response(&response);
+
+    // Only features both sides support are enabled; without binary encoding
+    // on the server the session stays JSON
+    assert_eq!(negotiation.encoding, WireEncoding::Json);
+    assert!(negotiation.supports(ProtocolFeature::Simulcast));
+    assert!(negotiation.supports(ProtocolFeature::Datagrams));
+    assert!(!negotiation.supports(ProtocolFeature::E2ee));
+    assert!(!negotiation.supports(ProtocolFeature::BinaryEncoding));
+}
+
+#[tokio::test]
+async fn test_client_says_hello_on_connect() {
+    let url = start_server().await;
+    let client = SignalingClient::connect(&url).await.unwrap();
+    let negotiation = client.negotiation();
+    assert_eq!(negotiation.protocol_version, PROTOCOL_VERSION);
+    assert_eq!(negotiation.encoding, WireEncoding::Cbor);
+    assert!(negotiation.supports(ProtocolFeature::BinaryEncoding));
+
+    // Responses after the welcome arrive as CBOR and still decode
+    client.send(&SignalingMessage::ListRooms).await.unwrap();
+    client
+        .expect("list rooms", Duration::from_secs(5), |response| {
+            matches!(response, SignalingResponse::RoomList { .. }).then_some(())
+        })
+        .await
+        .unwrap();
+
+    // Servers without binary encoding keep the session JSON
+    let url = start_server_with_features(FeatureSet::new()).await;
+    let client = SignalingClient::connect(&url).await.unwrap();
+    assert_eq!(client.negotiation().encoding, WireEncoding::Json);
+    assert!(!client
+        .negotiation()
+        .supports(ProtocolFeature::BinaryEncoding));
+}
+
+#[tokio::test]
+async fn test_client_sends_in_negotiated_encoding() {
+    // A bare server that welcomes the client into CBOR
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+        // The hello goes out before anything is agreed, as JSON
+        let Some(Ok(Message::Text(hello))) = ws.next().await else {
+            panic!("Expected a JSON hello");
+        };
+        assert!(matches!(
+            serde_json::from_str(&hello).unwrap(),
+            SignalingMessage::Hello { .. }
+        ));
+        let welcome = SignalingResponse::Welcome {
+            protocol_version: PROTOCOL_VERSION,
+            encoding: WireEncoding::Cbor,
+            features: FeatureSet::new().with(ProtocolFeature::BinaryEncoding),
+        };
+        ws.send(Message::Text(serde_json::to_string(&welcome).unwrap()))
+            .await
+            .unwrap();
+
+        // Everything after the welcome is CBOR both ways
+        let Some(Ok(Message::Binary(request))) = ws.next().await else {
+            panic!("Expected a CBOR request");
+        };
+        assert!(matches!(
+            WireEncoding::Cbor.decode(&request).unwrap(),
+            SignalingMessage::ListRooms
+        ));
+        let rooms = SignalingResponse::RoomList { rooms: Vec::new() };
+        ws.send(Message::Binary(WireEncoding::Cbor.encode(&rooms).unwrap()))
+            .await
+            .unwrap();
+    });
+
+    let client = SignalingClient::connect(&url).await.unwrap();
+    assert_eq!(client.negotiation().encoding, WireEncoding::Cbor);
+    client.send(&SignalingMessage::ListRooms).await.unwrap();
+    client
+        .expect("list rooms", Duration::from_secs(5), |response| {
+            matches!(response, SignalingResponse::RoomList { .. }).then_some(())
+        })
+        .await
+        .unwrap();
+    server.await.unwrap();
+}
+
+#[test]
+fn test_unknown_features_are_ignored() {
+    let json = r#"{"Hello":{"protocol_version":2,"encodings":["Json"],"features":["e2ee","quantum_teleport"]}}"#;
+    match serde_json::from_str(json).unwrap() {
+        SignalingMessage::Hello { features, .. } => {
+            assert_eq!(features, FeatureSet::new().with(ProtocolFeature::E2ee));
+        }
+        other => panic!("Wrong message type: {:?}", other),
+    }
+
+    // Hellos from clients that predate feature negotiation still parse
+    let json = r#"{"Hello":{"protocol_version":1,"encodings":["Cbor"]}}"#;
+    assert!(serde_json::from_str::<SignalingMessage>(json).is_ok());
+}
@@ -0,0 +1,144 @@
+//! Tests for the MoQ session offer/answer exchange through the signaling client
+
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+use quicrtc_signaling::protocol::{
+    MoqSessionAnswer, MoqSessionOffer, SignalingMessage, SignalingResponse, MOQ_VERSION,
+};
+use quicrtc_signaling::{
+    CapabilitySet, CodecCapability, CodecKind, SignalingClient, SignalingServer,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+async fn start_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = SignalingServer::new(addr);
+    server
+        .create_room("standup".to_string(), None, None)
+        .await
+        .unwrap();
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let server = server.clone();
+            tokio::spawn(async move { server.handle_test_connection(stream).await });
+        }
+    });
+
+    format!("ws://{}", addr)
+}
+
+async fn join(url: &str, participant_id: &str, quic_endpoint: Option<&str>) -> SignalingClient {
+    let client = SignalingClient::connect(url).await.unwrap();
+    client
+        .send(&SignalingMessage::JoinRoom {
+            room_id: "standup".to_string(),
+            participant_id: participant_id.to_string(),
+            participant_name: None,
+            capabilities: CapabilitySet::from_names(&["opus"]),
+            quic_endpoint: quic_endpoint.map(|endpoint| endpoint.parse().unwrap()),
+            access_token: None,
+        })
+        .await
+        .unwrap();
+    client
+        .expect("join room", TIMEOUT, |response| match response {
+            SignalingResponse::JoinedRoom { .. } => Some(()),
+            _ => None,
+        })
+        .await
+        .unwrap();
+    client
+}
+
+#[tokio::test]
+async fn test_offer_and_answer_are_forwarded() {
+    let url = start_server().await;
+    let relay = join(&url, "relay", Some("10.0.0.1:4433")).await;
+    let alice = join(&url, "alice", None).await;
+
+    let offer = MoqSessionOffer {
+        participant_id: "alice".to_string(),
+        quic_endpoint: "0.0.0.0:0".parse().unwrap(),
+        moq_version: MOQ_VERSION.to_string(),
+        publish_namespaces: vec!["room.standup/alice/microphone".to_string()],
+        subscribe_namespaces: vec!["room.standup".to_string()],
+        capabilities: CapabilitySet::new().with_codec(CodecCapability::audio("opus")),
+        tracks: Vec::new(),
+        session_id: "session-1".to_string(),
+    };
+    alice
+        .send(&SignalingMessage::MoqSessionOffer {
+            room_id: "standup".to_string(),
+            target_participant: "relay".to_string(),
+            offer,
+        })
+        .await
+        .unwrap();
+
+    let (source, offer) = relay
+        .expect("session offer", TIMEOUT, |response| match response {
+            SignalingResponse::MoqSessionOffer {
+                source_participant,
+                offer,
+                ..
+            } => Some((source_participant, offer)),
+            _ => None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(source, "alice");
+    assert_eq!(offer.moq_version, MOQ_VERSION);
+
+    let answer = MoqSessionAnswer {
+        participant_id: "relay".to_string(),
+        quic_endpoint: "10.0.0.1:4433".parse().unwrap(),
+        moq_version: MOQ_VERSION.to_string(),
+        accepted_publish_namespaces: offer.publish_namespaces.clone(),
+        accepted_subscribe_namespaces: offer.subscribe_namespaces.clone(),
+        codecs: offer.answer_codecs(&[CodecCapability::audio("opus")]),
+        session_id: offer.session_id.clone(),
+        accepted: true,
+    };
+    relay
+        .send(&SignalingMessage::MoqSessionAnswer {
+            room_id: "standup".to_string(),
+            target_participant: source,
+            answer,
+        })
+        .await
+        .unwrap();
+
+    let answer = alice
+        .expect("session answer", TIMEOUT, |response| match response {
+            SignalingResponse::MoqSessionAnswer { answer, .. } => Some(answer),
+            _ => None,
+        })
+        .await
+        .unwrap();
+    assert!(answer.accepted);
+    assert_eq!(answer.session_id, "session-1");
+    assert_eq!(answer.quic_endpoint, "10.0.0.1:4433".parse().unwrap());
+    assert_eq!(answer.codec(CodecKind::Audio).unwrap().name, "opus");
+}
+
+#[tokio::test]
+async fn test_expect_times_out_without_a_match() {
+    let url = start_server().await;
+    let alice = join(&url, "alice", None).await;
+
+    let result = alice
+        .expect(
+            "session answer",
+            Duration::from_millis(100),
+            |response| match response {
+                SignalingResponse::MoqSessionAnswer { answer, .. } => Some(answer),
+                _ => None,
+            },
+        )
+        .await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,159 @@
+//! Tests for access token minting and verification
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use quicrtc_core::{QuicRtcError, TrackNamespace};
+use quicrtc_signaling::{AccessToken, SignalingServer, TokenVerifier};
+
+const API_KEY: &str = "api-key";
+const SECRET: &str = "api-secret";
+
+fn verifier() -> TokenVerifier {
+    TokenVerifier::new().with_key(API_KEY, SECRET)
+}
+
+#[test]
+fn test_access_token_round_trip() {
+    let jwt = AccessToken::new(API_KEY, SECRET)
+        .identity("alice")
+        .name("Alice")
+        .room("standup")
+        .can_publish(true)
+        .metadata(r#"{"team":"core"}"#)
+        .to_jwt()
+        .unwrap();
+    assert_eq!(jwt.split('.').count(), 3);
+
+    let claims = verifier().verify(&jwt).unwrap();
+    assert_eq!(claims.iss, API_KEY);
+    assert_eq!(claims.sub, "alice");
+    assert_eq!(claims.name.as_deref(), Some("Alice"));
+    assert!(claims.grants.can_publish);
+    assert!(claims.grants.can_subscribe);
+    assert!(!claims.grants.room_create);
+    assert!(claims.grants.allows_join("standup"));
+    assert!(!claims.grants.allows_join("other-room"));
+    assert_eq!(claims.exp - claims.nbf, 6 * 60 * 60);
+}
+
+#[test]
+fn test_access_token_rejections() {
+    let token = AccessToken::new(API_KEY, SECRET).identity("alice");
+
+    // Identity is required
+    assert!(AccessToken::new(API_KEY, SECRET).to_jwt().is_err());
+
+    // Wrong secret and unknown key
+    let forged = AccessToken::new(API_KEY, "wrong-secret")
+        .identity("alice")
+        .to_jwt()
+        .unwrap();
+    assert!(matches!(
+        verifier().verify(&forged),
+        Err(QuicRtcError::Unauthorized { .. })
+    ));
+    assert!(TokenVerifier::new()
+        .with_key("other-key", SECRET)
+        .verify(&token.to_jwt().unwrap())
+        .is_err());
+
+    // Tampered claims
+    let jwt = token.to_jwt().unwrap();
+    let parts: Vec<&str> = jwt.split('.').collect();
+    let other = token.clone().room_admin(true).to_jwt().unwrap();
+    let other_payload = other.split('.').nth(1).unwrap();
+    let tampered = format!("{}.{}.{}", parts[0], other_payload, parts[2]);
+    assert!(verifier().verify(&tampered).is_err());
+
+    // Expired, beyond the leeway
+    let expired = token.clone().ttl(Duration::ZERO).to_jwt().unwrap();
+    assert!(verifier()
+        .with_leeway(Duration::ZERO)
+        .verify(&expired)
+        .is_err());
+    assert!(verifier().verify(&expired).is_ok());
+
+    // Lifetimes past the end of time are refused rather than wrapping
+    assert!(matches!(
+        token.clone().ttl(Duration::MAX).to_jwt(),
+        Err(QuicRtcError::InvalidData { .. })
+    ));
+    assert!(token
+        .clone()
+        .ttl(Duration::from_secs(i64::MAX as u64))
+        .to_jwt()
+        .is_err());
+
+    assert!(verifier().verify("not-a-token").is_err());
+}
+
+#[test]
+fn test_server_enforces_room_grants() {
+    let addr = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 0);
+
+    // Without a verifier every request is allowed
+    let open = SignalingServer::new(addr);
+    assert!(open
+        .authorize_join(None, "room", "alice")
+        .unwrap()
+        .is_none());
+
+    let server = SignalingServer::new(addr).with_token_verifier(verifier());
+    assert!(server.authorize_join(None, "room", "alice").is_err());
+
+    let jwt = AccessToken::new(API_KEY, SECRET)
+        .identity("alice")
+        .room("room")
+        .to_jwt()
+        .unwrap();
+    let claims = server
+        .authorize_join(Some(&jwt), "room", "alice")
+        .unwrap()
+        .unwrap();
+    assert_eq!(claims.sub, "alice");
+
+    // Token is bound to one room and one identity
+    assert!(server.authorize_join(Some(&jwt), "other", "alice").is_err());
+    assert!(server
+        .authorize_join(Some(&jwt), "room", "mallory")
+        .is_err());
+
+    // Creating rooms needs its own grant
+    assert!(server.authorize_create(Some(&jwt), "room").is_err());
+    let creator = AccessToken::new(API_KEY, SECRET)
+        .identity("backend")
+        .room_create(true)
+        .to_jwt()
+        .unwrap();
+    assert!(server.authorize_create(Some(&creator), "room").is_ok());
+}
+
+#[test]
+fn test_token_moq_permissions() {
+    let track = |namespace: &str, track_name: &str| TrackNamespace {
+        namespace: namespace.to_string(),
+        track_name: track_name.to_string(),
+    };
+    let claims = |token: AccessToken| verifier().verify(&token.to_jwt().unwrap()).unwrap();
+
+    let viewer = claims(
+        AccessToken::new(API_KEY, SECRET)
+            .identity("viewer")
+            .room("a"),
+    )
+    .moq_permissions();
+    assert!(!viewer.allows_announce(&track("room.a", "viewer/camera")));
+    assert!(viewer.allows_subscribe(&track("room.a", "alice/camera")));
+    assert!(!viewer.allows_subscribe(&track("room.b", "alice/camera")));
+
+    let publisher = claims(
+        AccessToken::new(API_KEY, SECRET)
+            .identity("alice")
+            .room("a")
+            .can_publish(true),
+    )
+    .moq_permissions();
+    assert!(publisher.allows_announce(&track("room.a", "alice/camera")));
+    assert!(!publisher.allows_announce(&track("room.a", "bob/camera")));
+}
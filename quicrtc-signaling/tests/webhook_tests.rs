@@ -0,0 +1,137 @@
+//! Tests for webhook signing and delivery
+
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use quicrtc_signaling::webhook::{sign, verify_signature, SIGNATURE_HEADER};
+use quicrtc_signaling::{
+    RoomCloseReason, SignalingServer, WebhookConfig, WebhookEvent, WebhookEventKind,
+};
+
+const SECRET: &str = "webhook-secret";
+
+#[test]
+fn test_webhook_signature_round_trip() {
+    let body = br#"{"event":"room_created","room_id":"a"}"#;
+    let now = chrono::Utc::now().timestamp();
+    let header = sign(SECRET, now, body);
+    assert!(header.starts_with(&format!("t={},v1=", now)));
+
+    let tolerance = Duration::from_secs(300);
+    assert!(verify_signature(SECRET, &header, body, tolerance));
+    assert!(!verify_signature("other", &header, body, tolerance));
+    assert!(!verify_signature(SECRET, &header, b"tampered", tolerance));
+
+    // Stale signatures are rejected even when the MAC matches
+    let stale = sign(SECRET, now - 3600, body);
+    assert!(!verify_signature(SECRET, &stale, body, tolerance));
+    assert!(!verify_signature(SECRET, "garbage", body, tolerance));
+}
+
+#[test]
+fn test_webhook_event_serialization() {
+    let event = WebhookEvent::new(WebhookEventKind::ParticipantJoined {
+        room_id: "room".to_string(),
+        participant_id: "alice".to_string(),
+        participant_name: None,
+    });
+    let json = serde_json::to_value(&event).unwrap();
+    assert_eq!(json["event"], "participant_joined");
+    assert_eq!(json["participant_id"], "alice");
+    assert_eq!(json["id"], event.id);
+}
+
+#[tokio::test]
+async fn test_webhook_delivery_with_retry() {
+    // Receiver that rejects the first request, then records verified events
+    let received = Arc::new(Mutex::new(Vec::<WebhookEvent>::new()));
+    let attempts = Arc::new(Mutex::new(0u32));
+    let app = Router::new().route(
+        "/hooks",
+        post({
+            let received = received.clone();
+            let attempts = attempts.clone();
+            move |headers: HeaderMap, body: axum::body::Bytes| async move {
+                let signature = headers[SIGNATURE_HEADER].to_str().unwrap();
+                assert!(verify_signature(
+                    SECRET,
+                    signature,
+                    &body,
+                    Duration::from_secs(60)
+                ));
+
+                let mut attempts = attempts.lock().unwrap();
+                *attempts += 1;
+                if *attempts == 1 {
+                    return StatusCode::SERVICE_UNAVAILABLE;
+                }
+                received
+                    .lock()
+                    .unwrap()
+                    .push(serde_json::from_slice(&body).unwrap());
+                StatusCode::OK
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let server =
+        SignalingServer::new("127.0.0.1:0".parse().unwrap()).with_webhooks(WebhookConfig {
+            endpoints: vec![format!("http://{}/hooks", addr)],
+            signing_secret: SECRET.to_string(),
+            initial_backoff: Duration::from_millis(10),
+            ..WebhookConfig::default()
+        });
+
+    server
+        .create_room("town-hall".to_string(), None, None)
+        .await
+        .unwrap();
+    server.start_recording("town-hall", None).await.unwrap();
+    server.close_room("town-hall").await.unwrap();
+
+    let webhooks = server.webhooks().unwrap();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while webhooks.stats().deliveries_succeeded < 3 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+
+    let stats = webhooks.stats();
+    assert_eq!(stats.events_queued, 3);
+    assert_eq!(stats.retries, 1);
+    assert_eq!(stats.deliveries_failed, 0);
+
+    let kinds: Vec<_> = received
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|event| event.kind.clone())
+        .collect();
+    assert_eq!(
+        kinds[0],
+        WebhookEventKind::RoomCreated {
+            room_id: "town-hall".to_string()
+        }
+    );
+    assert!(matches!(
+        kinds[1],
+        WebhookEventKind::RecordingFinished { .. }
+    ));
+    assert_eq!(
+        kinds[2],
+        WebhookEventKind::RoomDestroyed {
+            room_id: "town-hall".to_string(),
+            reason: RoomCloseReason::Closed,
+        }
+    );
+}
@@ -0,0 +1,39 @@
+//! Error type for wire encoding and decoding
+
+use alloc::string::String;
+use core::fmt;
+
+/// Errors produced while encoding or decoding MoQ wire data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WireError {
+    /// Input is truncated or malformed
+    InvalidData {
+        /// What was wrong with the input
+        reason: String,
+    },
+    /// Message or field is not supported by this implementation
+    Protocol {
+        /// What was not supported
+        reason: String,
+    },
+    /// A size budget was exceeded
+    ResourceLimit {
+        /// Resource that exceeded its limit
+        resource: String,
+    },
+}
+
+impl fmt::Display for WireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WireError::InvalidData { reason } => write!(f, "Invalid data: {}", reason),
+            WireError::Protocol { reason } => write!(f, "MoQ protocol error: {}", reason),
+            WireError::ResourceLimit { resource } => {
+                write!(f, "Resource limit exceeded: {}", resource)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for WireError {}
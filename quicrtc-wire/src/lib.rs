@@ -0,0 +1,44 @@
+//! # QUIC RTC Wire
+//!
+//! IETF Media over QUIC (MoQ) wire format and the object, header and control
+//! message types it encodes. This crate has no async runtime or QUIC
+//! dependency, so embedded/edge relays, alternative transports and test
+//! fixtures can share the exact encoding used by `quicrtc-core`.
+//!
+//! The crate is `no_std` compatible (it needs `alloc`). The default `std`
+//! feature adds `std::error::Error` for [`WireError`] and creation
+//! timestamps on [`MoqObject`].
+//!
+//! ```rust
+//! use bytes::BytesMut;
+//! use quicrtc_wire::MoqWireFormat;
+//!
+//! let mut buf = BytesMut::new();
+//! MoqWireFormat::encode_varint(16384, &mut buf);
+//! assert_eq!(MoqWireFormat::decode_varint(&mut &buf[..]).unwrap(), 16384);
+//! ```
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(missing_docs)]
+#![warn(clippy::all)]
+
+extern crate alloc;
+
+pub mod error;
+pub mod message;
+pub mod namespace;
+pub mod object;
+pub mod wire_format;
+
+// Re-export main types
+pub use error::WireError;
+pub use message::{
+    MoqCapabilities, MoqControlMessage, MoqErrorCode, MoqRole, MoqTrack, MoqTrackType,
+    TrackNamespace,
+};
+pub use namespace::{room_namespace, TrackNamespaceBuilder, TrackNamespacePattern};
+pub use object::{
+    AudioContentHint, ChannelLayout, H264Frame, MoqObject, MoqObjectMetadata, MoqObjectStatus,
+    OpusFrame, VideoContentHint, VideoRotation,
+};
+pub use wire_format::MoqWireFormat;
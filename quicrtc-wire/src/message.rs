@@ -0,0 +1,367 @@
+//! MoQ control messages and the track types they carry
+
+use crate::namespace::TrackNamespacePattern;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::time::Duration;
+use serde::{Deserialize, Serialize};
+
+/// Track namespace following MoQ specification
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrackNamespace {
+    /// Namespace (e.g., "conference.example.com")
+    pub namespace: String,
+    /// Track name (e.g., "alice/camera")
+    pub track_name: String,
+}
+
+/// MoQ Track representation
+#[derive(Debug, Clone)]
+pub struct MoqTrack {
+    /// Track namespace
+    pub namespace: TrackNamespace,
+    /// Track name
+    pub name: String,
+    /// Track type
+    pub track_type: MoqTrackType,
+    /// How long after creation an object is still worth delivering; `None`
+    /// for tracks whose objects never go stale
+    pub latency_budget: Option<Duration>,
+}
+
+impl MoqTrack {
+    /// Create a track with the default latency budget of its type
+    pub fn new(namespace: TrackNamespace, name: String, track_type: MoqTrackType) -> Self {
+        Self {
+            namespace,
+            name,
+            latency_budget: track_type.default_latency_budget(),
+            track_type,
+        }
+    }
+
+    /// Use `latency_budget` instead of the track type's default
+    pub fn with_latency_budget(mut self, latency_budget: Option<Duration>) -> Self {
+        self.latency_budget = latency_budget;
+        self
+    }
+}
+
+/// MoQ track types
+#[derive(Debug, Clone, PartialEq)]
+pub enum MoqTrackType {
+    /// Audio track
+    Audio,
+    /// Video track
+    Video,
+    /// Data track
+    Data,
+}
+
+impl MoqTrackType {
+    /// Default latency budget of tracks of this type
+    ///
+    /// Audio played later than 150ms after capture disrupts conversation,
+    /// while video can trail by up to 400ms before lip sync suffers. Data
+    /// is delivered however late it is.
+    pub fn default_latency_budget(&self) -> Option<Duration> {
+        match self {
+            MoqTrackType::Audio => Some(Duration::from_millis(150)),
+            MoqTrackType::Video => Some(Duration::from_millis(400)),
+            MoqTrackType::Data => None,
+        }
+    }
+}
+
+/// Role an endpoint takes in a MoQ session, sent as the ROLE SETUP parameter
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MoqRole {
+    /// Only publishes tracks
+    Publisher,
+    /// Only subscribes to tracks
+    Subscriber,
+    /// Publishes and subscribes to tracks
+    #[default]
+    PubSub,
+}
+
+impl MoqRole {
+    /// Wire value of the role
+    pub fn code(self) -> u64 {
+        match self {
+            MoqRole::Publisher => 1,
+            MoqRole::Subscriber => 2,
+            MoqRole::PubSub => 3,
+        }
+    }
+
+    /// Parse a wire value; None for values the draft doesn't define
+    pub fn from_code(code: u64) -> Option<Self> {
+        match code {
+            1 => Some(Self::Publisher),
+            2 => Some(Self::Subscriber),
+            3 => Some(Self::PubSub),
+            _ => None,
+        }
+    }
+
+    /// Whether an endpoint in this role may announce tracks
+    pub fn can_publish(self) -> bool {
+        matches!(self, MoqRole::Publisher | MoqRole::PubSub)
+    }
+
+    /// Whether an endpoint in this role may subscribe to tracks
+    pub fn can_subscribe(self) -> bool {
+        matches!(self, MoqRole::Subscriber | MoqRole::PubSub)
+    }
+
+    /// Whether a session between this role and `peer` can carry any track,
+    /// that is one side publishes what the other subscribes to
+    pub fn is_compatible_with(self, peer: MoqRole) -> bool {
+        (self.can_publish() && peer.can_subscribe()) || (self.can_subscribe() && peer.can_publish())
+    }
+}
+
+/// MoQ session capabilities
+#[derive(Debug, Clone)]
+pub struct MoqCapabilities {
+    /// Supported MoQ version
+    pub version: u32,
+    /// Maximum number of concurrent tracks
+    pub max_tracks: u32,
+    /// Supported track types
+    pub supported_track_types: Vec<MoqTrackType>,
+    /// Maximum object size in bytes
+    pub max_object_size: u64,
+    /// Support for object caching
+    pub supports_caching: bool,
+    /// Role taken in the session
+    pub role: MoqRole,
+    /// Highest subscribe ID the peer may use, `None` to leave it to the
+    /// peer's default
+    pub max_subscribe_id: Option<u64>,
+    /// Authorization token for the relay, such as a JWT
+    pub auth_token: Option<String>,
+    /// Further SETUP parameters by key, for relay-specific options
+    ///
+    /// Keys must not be one of the parameters above; values are sent as
+    /// they are.
+    pub extensions: BTreeMap<u64, Vec<u8>>,
+}
+
+impl MoqCapabilities {
+    /// SETUP parameter carrying [`Self::role`]
+    pub const ROLE_PARAMETER: u64 = 0x00;
+    /// SETUP parameter carrying [`Self::max_subscribe_id`]
+    pub const MAX_SUBSCRIBE_ID_PARAMETER: u64 = 0x02;
+    /// SETUP parameter carrying [`Self::auth_token`]
+    pub const AUTHORIZATION_PARAMETER: u64 = 0x03;
+
+    /// Whether `key` is one of the SETUP parameters with a field of its own
+    pub fn is_standard_parameter(key: u64) -> bool {
+        matches!(
+            key,
+            Self::ROLE_PARAMETER | Self::MAX_SUBSCRIBE_ID_PARAMETER | Self::AUTHORIZATION_PARAMETER
+        )
+    }
+
+    /// Send `value` as SETUP parameter `key`
+    pub fn with_extension(mut self, key: u64, value: impl Into<Vec<u8>>) -> Self {
+        self.extensions.insert(key, value.into());
+        self
+    }
+}
+
+impl Default for MoqCapabilities {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            max_tracks: 100,
+            supported_track_types: vec![
+                MoqTrackType::Audio,
+                MoqTrackType::Video,
+                MoqTrackType::Data,
+            ],
+            max_object_size: 1024 * 1024, // 1MB
+            supports_caching: true,
+            role: MoqRole::default(),
+            max_subscribe_id: None,
+            auth_token: None,
+            extensions: BTreeMap::new(),
+        }
+    }
+}
+
+/// Error codes carried by SETUP_ERROR, ANNOUNCE_ERROR and SUBSCRIBE_ERROR
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum MoqErrorCode {
+    /// Peer requested an unsupported protocol version
+    UnsupportedVersion = 1,
+    /// Track type is not supported
+    UnsupportedTrackType = 2,
+    /// Too many tracks announced
+    TrackLimitExceeded = 3,
+    /// Subscribed track has not been announced
+    TrackNotFound = 4,
+    /// Peer is not permitted to announce or subscribe to the track
+    PermissionDenied = 5,
+    /// Request is inconsistent with the role the peer took at SETUP, such
+    /// as a subscriber announcing a track
+    RoleViolation = 6,
+    /// Neither side can publish what the other subscribes to
+    IncompatibleRoles = 7,
+    /// Authorization token is missing or doesn't grant access to the track
+    Unauthorized = 8,
+    /// Control messages arrived replayed or out of order, so the session
+    /// was terminated
+    ProtocolViolation = 9,
+}
+
+impl MoqErrorCode {
+    /// Wire value of the code
+    pub fn code(self) -> u32 {
+        self as u32
+    }
+
+    /// Parse a wire value; None for codes this implementation doesn't define
+    pub fn from_code(code: u32) -> Option<Self> {
+        match code {
+            1 => Some(Self::UnsupportedVersion),
+            2 => Some(Self::UnsupportedTrackType),
+            3 => Some(Self::TrackLimitExceeded),
+            4 => Some(Self::TrackNotFound),
+            5 => Some(Self::PermissionDenied),
+            6 => Some(Self::RoleViolation),
+            7 => Some(Self::IncompatibleRoles),
+            8 => Some(Self::Unauthorized),
+            9 => Some(Self::ProtocolViolation),
+            _ => None,
+        }
+    }
+}
+
+/// MoQ control messages for session management
+#[derive(Debug, Clone)]
+pub enum MoqControlMessage {
+    /// Session setup message
+    Setup {
+        /// MoQ protocol version
+        version: u32,
+        /// Session capabilities
+        capabilities: MoqCapabilities,
+    },
+    /// Session setup response
+    SetupOk {
+        /// Agreed MoQ protocol version
+        version: u32,
+        /// Peer capabilities
+        capabilities: MoqCapabilities,
+    },
+    /// Session setup error
+    SetupError {
+        /// Error code
+        code: u32,
+        /// Error reason
+        reason: String,
+    },
+    /// Announce a track
+    Announce {
+        /// Track namespace
+        track_namespace: TrackNamespace,
+        /// Track information
+        track: MoqTrack,
+        /// Token authorizing the announce, checked by the receiving side
+        auth_token: Option<String>,
+    },
+    /// Announce response
+    AnnounceOk {
+        /// Track namespace
+        track_namespace: TrackNamespace,
+    },
+    /// Announce error
+    AnnounceError {
+        /// Track namespace
+        track_namespace: TrackNamespace,
+        /// Error code
+        code: u32,
+        /// Error reason
+        reason: String,
+    },
+    /// Subscribe to a track
+    Subscribe {
+        /// Track namespace
+        track_namespace: TrackNamespace,
+        /// Subscription priority
+        priority: u8,
+        /// Start group ID (None for live)
+        start_group: Option<u64>,
+        /// End group ID (None for ongoing)
+        end_group: Option<u64>,
+        /// Token authorizing the subscription, checked by the receiving side
+        auth_token: Option<String>,
+    },
+    /// Subscribe response
+    SubscribeOk {
+        /// Track namespace
+        track_namespace: TrackNamespace,
+    },
+    /// Subscribe error
+    SubscribeError {
+        /// Track namespace
+        track_namespace: TrackNamespace,
+        /// Error code
+        code: u32,
+        /// Error reason
+        reason: String,
+    },
+    /// Change the priority or group range of an active subscription
+    SubscribeUpdate {
+        /// Track namespace
+        track_namespace: TrackNamespace,
+        /// New subscription priority
+        priority: u8,
+        /// New start group ID (None for live)
+        start_group: Option<u64>,
+        /// New end group ID (None for ongoing)
+        end_group: Option<u64>,
+    },
+    /// Unsubscribe from a track
+    Unsubscribe {
+        /// Track namespace
+        track_namespace: TrackNamespace,
+    },
+    /// Subscribe to the announcements of every track matching a pattern
+    SubscribeNamespace {
+        /// Tracks of interest, e.g. `room.standup/*`
+        pattern: TrackNamespacePattern,
+    },
+    /// Namespace subscription response
+    SubscribeNamespaceOk {
+        /// Pattern that was subscribed to
+        pattern: TrackNamespacePattern,
+    },
+    /// Namespace subscription error
+    SubscribeNamespaceError {
+        /// Pattern that was refused
+        pattern: TrackNamespacePattern,
+        /// Error code
+        code: u32,
+        /// Error reason
+        reason: String,
+    },
+    /// End a namespace subscription
+    UnsubscribeNamespace {
+        /// Pattern to stop receiving announcements for
+        pattern: TrackNamespacePattern,
+    },
+    /// Session termination
+    Terminate {
+        /// Termination code
+        code: u32,
+        /// Termination reason
+        reason: String,
+    },
+}
@@ -0,0 +1,428 @@
+//! Building, validating and matching track namespaces
+//!
+//! Tracks are named `<namespace>/<track name>`, e.g.
+//! `room.standup/alice/camera`: the namespace scopes a room (or any other
+//! grouping) and the track name is a `/`-separated path, usually the
+//! publishing participant followed by the media source. That string is the
+//! canonical form used in session offers and prefix patterns.
+
+use crate::error::WireError;
+use crate::message::TrackNamespace;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Prefix of the namespace carrying a room's tracks (`room.<room id>`)
+pub const ROOM_NAMESPACE_PREFIX: &str = "room.";
+
+/// Longest namespace accepted, in bytes
+pub const MAX_NAMESPACE_LEN: usize = 255;
+
+/// Longest track name accepted, in bytes
+pub const MAX_TRACK_NAME_LEN: usize = 255;
+
+/// Wildcard ending a [`TrackNamespacePattern`]
+pub const WILDCARD: char = '*';
+
+/// Separator between the namespace and track name, and between track name segments
+pub const SEPARATOR: char = '/';
+
+/// Namespace carrying a room's tracks
+pub fn room_namespace(room_id: &str) -> String {
+    format!("{}{}", ROOM_NAMESPACE_PREFIX, room_id)
+}
+
+/// Builds a [`TrackNamespace`] from its parts, validating each one
+///
+/// ```rust
+/// use quicrtc_wire::TrackNamespaceBuilder;
+///
+/// let track = TrackNamespaceBuilder::room("standup")
+///     .participant("alice")
+///     .track("camera")
+///     .build()
+///     .unwrap();
+/// assert_eq!(track.to_string(), "room.standup/alice/camera");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackNamespaceBuilder {
+    namespace: String,
+    segments: Vec<String>,
+}
+
+impl TrackNamespaceBuilder {
+    /// Start a track in `namespace`
+    pub fn new(namespace: impl Into<String>) -> Self {
+        Self {
+            namespace: namespace.into(),
+            segments: Vec::new(),
+        }
+    }
+
+    /// Start a track in a room's namespace (`room.<room_id>`)
+    pub fn room(room_id: &str) -> Self {
+        Self::new(room_namespace(room_id))
+    }
+
+    /// Add the publishing participant as the next track name segment
+    pub fn participant(self, participant_id: impl Into<String>) -> Self {
+        self.segment(participant_id)
+    }
+
+    /// Add the track itself (e.g. `camera`) as the next track name segment
+    pub fn track(self, name: impl Into<String>) -> Self {
+        self.segment(name)
+    }
+
+    /// Add a track name segment
+    pub fn segment(mut self, segment: impl Into<String>) -> Self {
+        self.segments.push(segment.into());
+        self
+    }
+
+    /// Validate the parts and assemble the namespace
+    pub fn build(self) -> Result<TrackNamespace, WireError> {
+        for segment in &self.segments {
+            validate_segment(segment, "Track name segment")?;
+        }
+        let track = TrackNamespace {
+            namespace: self.namespace,
+            track_name: self.segments.join("/"),
+        };
+        track.validate()?;
+        Ok(track)
+    }
+}
+
+impl TrackNamespace {
+    /// Parse the canonical `<namespace>/<track name>` form
+    pub fn parse(canonical: &str) -> Result<Self, WireError> {
+        let (namespace, track_name) =
+            canonical
+                .split_once(SEPARATOR)
+                .ok_or_else(|| WireError::InvalidData {
+                    reason: format!("Track '{}' has no track name", canonical),
+                })?;
+        let track = Self {
+            namespace: namespace.to_string(),
+            track_name: track_name.to_string(),
+        };
+        track.validate()?;
+        Ok(track)
+    }
+
+    /// Check the namespace and track name are well formed
+    ///
+    /// Both must be non-empty, within their length limits and free of
+    /// whitespace, control characters and the `*` wildcard. The namespace
+    /// can't contain `/` (nor be a bare `room.`); the track name's
+    /// `/`-separated segments can't be empty.
+    pub fn validate(&self) -> Result<(), WireError> {
+        validate_segment(&self.namespace, "Namespace")?;
+        if self.namespace == ROOM_NAMESPACE_PREFIX {
+            return Err(WireError::InvalidData {
+                reason: "Room namespace has an empty room id".to_string(),
+            });
+        }
+        if self.namespace.len() > MAX_NAMESPACE_LEN {
+            return Err(WireError::ResourceLimit {
+                resource: format!("Namespace longer than {} bytes", MAX_NAMESPACE_LEN),
+            });
+        }
+        if self.track_name.len() > MAX_TRACK_NAME_LEN {
+            return Err(WireError::ResourceLimit {
+                resource: format!("Track name longer than {} bytes", MAX_TRACK_NAME_LEN),
+            });
+        }
+        for segment in self.track_name.split(SEPARATOR) {
+            validate_segment(segment, "Track name segment")?;
+        }
+        Ok(())
+    }
+
+    /// Room the track belongs to, for namespaces of the form `room.<room id>`
+    pub fn room_id(&self) -> Option<&str> {
+        self.namespace
+            .strip_prefix(ROOM_NAMESPACE_PREFIX)
+            .filter(|room_id| !room_id.is_empty())
+    }
+
+    /// First track name segment, by convention the publishing participant
+    pub fn participant_id(&self) -> Option<&str> {
+        self.track_name
+            .split(SEPARATOR)
+            .next()
+            .filter(|participant| !participant.is_empty())
+    }
+
+    /// Canonical `<namespace>/<track name>` form; same as `to_string()`
+    pub fn canonical(&self) -> String {
+        self.to_string()
+    }
+
+    /// Track alias carried by the track's objects on the wire
+    ///
+    /// Derived from the canonical form (64-bit FNV-1a, cut to the 62 bits a
+    /// varint holds), so publisher and subscriber agree on it without an
+    /// exchange.
+    pub fn alias(&self) -> u64 {
+        const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+        let separator = [SEPARATOR as u8];
+        let bytes = self
+            .namespace
+            .bytes()
+            .chain(separator)
+            .chain(self.track_name.bytes());
+        let hash = bytes.fold(FNV_OFFSET, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+        });
+        hash & ((1 << 62) - 1)
+    }
+}
+
+impl fmt::Display for TrackNamespace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}{}", self.namespace, SEPARATOR, self.track_name)
+    }
+}
+
+/// Matches tracks by their canonical form, exactly or by prefix
+///
+/// A pattern ending in `*` matches every track whose canonical form starts
+/// with the rest of the pattern: `room.standup/*` matches every track in the
+/// room, `room.standup/alice/*` every track Alice publishes there and
+/// `room.*` every room. Without a wildcard the pattern names a single track.
+///
+/// ```rust
+/// use quicrtc_wire::{TrackNamespace, TrackNamespacePattern};
+///
+/// let pattern = TrackNamespacePattern::parse("room.standup/*").unwrap();
+/// let camera = TrackNamespace::parse("room.standup/alice/camera").unwrap();
+/// assert!(pattern.matches(&camera));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TrackNamespacePattern {
+    /// Pattern without its trailing wildcard
+    prefix: String,
+    /// Whether the pattern ended in a wildcard
+    wildcard: bool,
+}
+
+impl TrackNamespacePattern {
+    /// Parse a pattern; `*` is only allowed as the last character
+    pub fn parse(pattern: &str) -> Result<Self, WireError> {
+        let (prefix, wildcard) = match pattern.strip_suffix(WILDCARD) {
+            Some(prefix) => (prefix, true),
+            None => (pattern, false),
+        };
+        if prefix.contains(WILDCARD) {
+            return Err(WireError::InvalidData {
+                reason: format!("Pattern '{}' may only end in a wildcard", pattern),
+            });
+        }
+        if prefix.chars().any(|c| c.is_whitespace() || c.is_control()) {
+            return Err(WireError::InvalidData {
+                reason: format!("Pattern '{}' contains whitespace", pattern),
+            });
+        }
+        if !wildcard {
+            TrackNamespace::parse(prefix)?;
+        }
+        Ok(Self {
+            prefix: prefix.to_string(),
+            wildcard,
+        })
+    }
+
+    /// Match every track in `namespace`
+    pub fn namespace(namespace: &str) -> Self {
+        Self {
+            prefix: format!("{}{}", namespace, SEPARATOR),
+            wildcard: true,
+        }
+    }
+
+    /// Match exactly one track
+    pub fn exact(track: &TrackNamespace) -> Self {
+        Self {
+            prefix: track.to_string(),
+            wildcard: false,
+        }
+    }
+
+    /// Whether the pattern ends in a wildcard
+    pub fn is_prefix(&self) -> bool {
+        self.wildcard
+    }
+
+    /// Whether `track` matches the pattern
+    pub fn matches(&self, track: &TrackNamespace) -> bool {
+        let namespace = track.namespace.as_str();
+        match self.prefix.strip_prefix(namespace) {
+            // The pattern covers the whole namespace and maybe part of the track name
+            Some(rest) => match rest.strip_prefix(SEPARATOR) {
+                Some(track_part) if self.wildcard => track.track_name.starts_with(track_part),
+                Some(track_part) => track.track_name == track_part,
+                None => rest.is_empty() && self.wildcard,
+            },
+            // The pattern ends inside the namespace
+            None => self.wildcard && namespace.starts_with(self.prefix.as_str()),
+        }
+    }
+
+    /// Whether some track in `namespace` could match the pattern
+    pub fn matches_namespace(&self, namespace: &str) -> bool {
+        match self.prefix.strip_prefix(namespace) {
+            Some(rest) => rest.is_empty() || rest.starts_with(SEPARATOR),
+            None => self.wildcard && namespace.starts_with(self.prefix.as_str()),
+        }
+    }
+}
+
+impl fmt::Display for TrackNamespacePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.prefix)?;
+        if self.wildcard {
+            write!(f, "{}", WILDCARD)?;
+        }
+        Ok(())
+    }
+}
+
+/// Check one namespace or track name segment
+fn validate_segment(segment: &str, what: &str) -> Result<(), WireError> {
+    if segment.is_empty() {
+        return Err(WireError::InvalidData {
+            reason: format!("{} is empty", what),
+        });
+    }
+    if let Some(c) = segment
+        .chars()
+        .find(|&c| c == SEPARATOR || c == WILDCARD || c.is_whitespace() || c.is_control())
+    {
+        return Err(WireError::InvalidData {
+            reason: format!("{} '{}' contains invalid character {:?}", what, segment, c),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_assembles_and_validates() {
+        let track = TrackNamespaceBuilder::room("standup")
+            .participant("alice")
+            .track("camera")
+            .build()
+            .unwrap();
+        assert_eq!(track.namespace, "room.standup");
+        assert_eq!(track.track_name, "alice/camera");
+        assert_eq!(track.room_id(), Some("standup"));
+        assert_eq!(track.participant_id(), Some("alice"));
+        assert_eq!(TrackNamespace::parse(&track.canonical()).unwrap(), track);
+
+        // Aliases are stable, fit a varint and tell tracks apart
+        let microphone = TrackNamespace::parse("room.standup/alice/microphone").unwrap();
+        assert_eq!(track.alias(), track.clone().alias());
+        assert!(track.alias() < 1 << 62);
+        assert_ne!(track.alias(), microphone.alias());
+
+        // Empty parts, separators inside a part, wildcards and whitespace
+        assert!(TrackNamespaceBuilder::room("")
+            .track("camera")
+            .build()
+            .is_err());
+        assert!(TrackNamespaceBuilder::room("standup").build().is_err());
+        assert!(TrackNamespaceBuilder::room("standup")
+            .participant("")
+            .track("camera")
+            .build()
+            .is_err());
+        assert!(TrackNamespaceBuilder::room("a/b")
+            .track("camera")
+            .build()
+            .is_err());
+        assert!(TrackNamespaceBuilder::room("standup")
+            .participant("alice/bob")
+            .track("camera")
+            .build()
+            .is_err());
+        assert!(TrackNamespaceBuilder::room("standup")
+            .segment("alice/")
+            .build()
+            .is_err());
+        assert!(TrackNamespaceBuilder::room("standup")
+            .track("cam*")
+            .build()
+            .is_err());
+        assert!(TrackNamespaceBuilder::room("stand up")
+            .track("camera")
+            .build()
+            .is_err());
+        assert!(TrackNamespaceBuilder::room("standup")
+            .track("x".repeat(MAX_TRACK_NAME_LEN + 1))
+            .build()
+            .is_err());
+
+        // Parsing rejects empty segments anywhere in the track name
+        assert!(TrackNamespace::parse("room.standup").is_err());
+        assert!(TrackNamespace::parse("room.standup/").is_err());
+        assert!(TrackNamespace::parse("/alice/camera").is_err());
+        assert!(TrackNamespace::parse("room.standup/alice//camera").is_err());
+        assert!(TrackNamespace::parse("room.standup/alice/camera/").is_err());
+
+        let other = TrackNamespace {
+            namespace: "conference.example.com".to_string(),
+            track_name: "alice/camera".to_string(),
+        };
+        assert_eq!(other.room_id(), None);
+        assert!(TrackNamespace::parse("room./alice").is_err());
+    }
+
+    #[test]
+    fn test_patterns_match_exactly_or_by_prefix() {
+        let camera = TrackNamespace::parse("room.standup/alice/camera").unwrap();
+        let microphone = TrackNamespace::parse("room.standup/alice/microphone").unwrap();
+        let bob = TrackNamespace::parse("room.standup/bob/camera").unwrap();
+        let elsewhere = TrackNamespace::parse("room.standup2/alice/camera").unwrap();
+
+        let room = TrackNamespacePattern::parse("room.standup/*").unwrap();
+        assert!(room.is_prefix());
+        assert!(room.matches(&camera) && room.matches(&bob));
+        assert!(!room.matches(&elsewhere));
+        assert_eq!(room, TrackNamespacePattern::namespace("room.standup"));
+        assert!(room.matches_namespace("room.standup"));
+        assert!(!room.matches_namespace("room.standup2"));
+
+        let alice = TrackNamespacePattern::parse("room.standup/alice/*").unwrap();
+        assert!(alice.matches(&camera) && alice.matches(&microphone));
+        assert!(!alice.matches(&bob));
+
+        // A prefix ending mid-segment matches on characters, not segments
+        let cam = TrackNamespacePattern::parse("room.standup/alice/cam*").unwrap();
+        assert!(cam.matches(&camera) && !cam.matches(&microphone));
+
+        let rooms = TrackNamespacePattern::parse("room.*").unwrap();
+        assert!(rooms.matches(&camera) && rooms.matches(&elsewhere));
+        assert!(rooms.matches_namespace("room.anything"));
+        let everything = TrackNamespacePattern::parse("*").unwrap();
+        assert!(everything.matches(&camera));
+
+        let exact = TrackNamespacePattern::parse("room.standup/alice/camera").unwrap();
+        assert!(!exact.is_prefix());
+        assert!(exact.matches(&camera) && !exact.matches(&microphone));
+        assert_eq!(exact, TrackNamespacePattern::exact(&camera));
+        assert_eq!(exact.to_string(), "room.standup/alice/camera");
+        assert_eq!(room.to_string(), "room.standup/*");
+
+        // Wildcards only at the end, and exact patterns must name a track
+        assert!(TrackNamespacePattern::parse("room.*/camera").is_err());
+        assert!(TrackNamespacePattern::parse("room.standup").is_err());
+        assert!(TrackNamespacePattern::parse("room standup/*").is_err());
+    }
+}
@@ -0,0 +1,578 @@
+//! MoQ objects, their extension headers and the media frames they carry
+
+use crate::error::WireError;
+use crate::message::TrackNamespace;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// MoQ Object as defined by IETF specification
+#[derive(Debug, Clone)]
+pub struct MoqObject {
+    /// Track namespace
+    pub track_namespace: TrackNamespace,
+    /// Track name
+    pub track_name: String,
+    /// Group ID (for video: timestamp, for audio: time window)
+    pub group_id: u64,
+    /// Object ID (for video: frame sequence, for audio: sample sequence)
+    pub object_id: u64,
+    /// Publisher priority (lower number = higher priority)
+    pub publisher_priority: u8,
+    /// Payload data (direct codec output)
+    pub payload: Vec<u8>,
+    /// Object status
+    pub object_status: MoqObjectStatus,
+    /// Object creation timestamp (for delivery ordering)
+    #[cfg(feature = "std")]
+    pub created_at: std::time::Instant,
+    /// Object size in bytes (for caching decisions)
+    pub size: usize,
+    /// Per-object media metadata
+    pub metadata: MoqObjectMetadata,
+}
+
+impl MoqObject {
+    /// Create MoQ object from H.264 frame (no RTP packetization)
+    pub fn from_h264_frame(track_namespace: TrackNamespace, frame: H264Frame) -> Self {
+        let size = frame.nal_units.len();
+        Self {
+            track_namespace,
+            track_name: "video".to_string(),
+            group_id: frame.timestamp_us / 1000, // Convert to milliseconds for grouping
+            object_id: frame.sequence_number,
+            publisher_priority: if frame.is_keyframe { 1 } else { 2 }, // Keyframes have higher priority
+            payload: frame.nal_units,
+            object_status: MoqObjectStatus::Normal,
+            #[cfg(feature = "std")]
+            created_at: std::time::Instant::now(),
+            size,
+            metadata: MoqObjectMetadata::default(),
+        }
+    }
+
+    /// Create MoQ object from Opus audio frame (no RTP packetization)
+    pub fn from_opus_frame(track_namespace: TrackNamespace, frame: OpusFrame) -> Self {
+        let size = frame.opus_data.len();
+        let mut metadata = MoqObjectMetadata::new();
+        if let Some(layout) = ChannelLayout::from_channel_count(frame.channels) {
+            // A single one-byte header always fits the extension budget
+            let _ = metadata.set_channel_layout(layout);
+        }
+        Self {
+            track_namespace,
+            track_name: "audio".to_string(),
+            group_id: frame.timestamp_us / 20000, // 20ms audio groups
+            object_id: frame.sequence_number,
+            publisher_priority: 1, // Audio always high priority
+            payload: frame.opus_data,
+            object_status: MoqObjectStatus::Normal,
+            #[cfg(feature = "std")]
+            created_at: std::time::Instant::now(),
+            size,
+            metadata,
+        }
+    }
+
+    /// Create end-of-group marker object
+    pub fn end_of_group(
+        track_namespace: TrackNamespace,
+        track_name: String,
+        group_id: u64,
+        object_id: u64,
+    ) -> Self {
+        Self {
+            track_namespace,
+            track_name,
+            group_id,
+            object_id,
+            publisher_priority: 1, // End markers have high priority
+            payload: Vec::new(),
+            object_status: MoqObjectStatus::EndOfGroup,
+            #[cfg(feature = "std")]
+            created_at: std::time::Instant::now(),
+            size: 0,
+            metadata: MoqObjectMetadata::default(),
+        }
+    }
+
+    /// Create end-of-track marker object
+    pub fn end_of_track(
+        track_namespace: TrackNamespace,
+        track_name: String,
+        group_id: u64,
+        object_id: u64,
+    ) -> Self {
+        Self {
+            track_namespace,
+            track_name,
+            group_id,
+            object_id,
+            publisher_priority: 1, // End markers have high priority
+            payload: Vec::new(),
+            object_status: MoqObjectStatus::EndOfTrack,
+            #[cfg(feature = "std")]
+            created_at: std::time::Instant::now(),
+            size: 0,
+            metadata: MoqObjectMetadata::default(),
+        }
+    }
+
+    /// Get object priority for delivery ordering
+    pub fn delivery_priority(&self) -> u8 {
+        match self.object_status {
+            MoqObjectStatus::EndOfTrack => 0, // Highest priority
+            MoqObjectStatus::EndOfGroup => 1,
+            MoqObjectStatus::Normal => self.publisher_priority,
+        }
+    }
+
+    /// Check if object is a control/marker object
+    pub fn is_control_object(&self) -> bool {
+        matches!(
+            self.object_status,
+            MoqObjectStatus::EndOfGroup | MoqObjectStatus::EndOfTrack
+        )
+    }
+
+    /// Get object age since creation
+    #[cfg(feature = "std")]
+    pub fn age(&self) -> std::time::Duration {
+        self.created_at.elapsed()
+    }
+
+    /// Whether the object is older than `latency_budget`, so no longer
+    /// worth delivering; never for a `None` budget
+    #[cfg(feature = "std")]
+    pub fn exceeds_latency_budget(&self, latency_budget: Option<std::time::Duration>) -> bool {
+        latency_budget.is_some_and(|budget| self.age() > budget)
+    }
+}
+
+/// MoQ object delivery status
+#[derive(Debug, Clone, PartialEq)]
+pub enum MoqObjectStatus {
+    /// Normal object
+    Normal,
+    /// Last object in group (e.g., end of video frame)
+    EndOfGroup,
+    /// Track is ending
+    EndOfTrack,
+}
+
+/// Per-object extension headers
+///
+/// Metadata travels on the wire as key/value extension headers. Well-known
+/// keys have typed accessors; any other key can carry application data. The
+/// encoded size of all headers is capped at [`Self::MAX_ENCODED_SIZE`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MoqObjectMetadata {
+    extensions: BTreeMap<u64, Vec<u8>>,
+}
+
+impl MoqObjectMetadata {
+    /// Maximum encoded size of all extension headers in bytes
+    pub const MAX_ENCODED_SIZE: usize = 256;
+
+    /// Audio channel layout (1 byte: channel count)
+    pub const CHANNEL_LAYOUT: u64 = 0x01;
+    /// Capture timestamp in microseconds (8 bytes, big endian)
+    pub const CAPTURE_TIMESTAMP: u64 = 0x02;
+    /// Clockwise rotation needed for upright display (2 bytes: degrees, big endian)
+    pub const ROTATION: u64 = 0x03;
+    /// Video content hint (1 byte)
+    pub const CONTENT_HINT: u64 = 0x04;
+    /// Encoded video resolution (8 bytes: width and height, big endian)
+    pub const VIDEO_RESOLUTION: u64 = 0x05;
+    /// Encoded video framerate (4 bytes: frames per second, big endian)
+    pub const FRAMERATE: u64 = 0x06;
+    /// Integrity hash over the object (32 bytes)
+    pub const INTEGRITY_HASH: u64 = 0x07;
+    /// Audio content hint (1 byte)
+    pub const AUDIO_CONTENT_HINT: u64 = 0x08;
+    /// Fragment of a datagram object too large for one datagram (4 bytes:
+    /// fragment index and fragment count, big endian)
+    pub const FRAGMENT: u64 = 0x09;
+
+    /// Create empty metadata
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the raw value of an extension header
+    pub fn get(&self, key: u64) -> Option<&[u8]> {
+        self.extensions.get(&key).map(|value| value.as_slice())
+    }
+
+    /// Set an extension header, rejecting it if the size budget would be exceeded
+    pub fn set(&mut self, key: u64, value: Vec<u8>) -> Result<(), WireError> {
+        let current = self
+            .extensions
+            .get(&key)
+            .map(|old| Self::entry_size(key, old))
+            .unwrap_or(0);
+        let new_size = self.encoded_size() - current + Self::entry_size(key, &value);
+
+        if new_size > Self::MAX_ENCODED_SIZE {
+            return Err(WireError::ResourceLimit {
+                resource: format!(
+                    "object extension headers ({} > {} bytes)",
+                    new_size,
+                    Self::MAX_ENCODED_SIZE
+                ),
+            });
+        }
+
+        self.extensions.insert(key, value);
+        Ok(())
+    }
+
+    /// Remove an extension header
+    pub fn remove(&mut self, key: u64) -> Option<Vec<u8>> {
+        self.extensions.remove(&key)
+    }
+
+    /// Iterate over extension headers in key order
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &[u8])> {
+        self.extensions
+            .iter()
+            .map(|(key, value)| (*key, value.as_slice()))
+    }
+
+    /// Number of extension headers
+    pub fn len(&self) -> usize {
+        self.extensions.len()
+    }
+
+    /// Check if there are no extension headers
+    pub fn is_empty(&self) -> bool {
+        self.extensions.is_empty()
+    }
+
+    /// Encoded size of all extension headers in bytes
+    pub fn encoded_size(&self) -> usize {
+        self.extensions
+            .iter()
+            .map(|(key, value)| Self::entry_size(*key, value))
+            .sum()
+    }
+
+    /// Audio channel layout
+    pub fn channel_layout(&self) -> Option<ChannelLayout> {
+        match self.get(Self::CHANNEL_LAYOUT)? {
+            [channels] => ChannelLayout::from_channel_count(*channels),
+            _ => None,
+        }
+    }
+
+    /// Set audio channel layout
+    pub fn set_channel_layout(&mut self, layout: ChannelLayout) -> Result<(), WireError> {
+        self.set(Self::CHANNEL_LAYOUT, vec![layout.channel_count()])
+    }
+
+    /// Capture timestamp in microseconds
+    pub fn capture_timestamp_us(&self) -> Option<u64> {
+        let bytes: [u8; 8] = self.get(Self::CAPTURE_TIMESTAMP)?.try_into().ok()?;
+        Some(u64::from_be_bytes(bytes))
+    }
+
+    /// Set capture timestamp in microseconds
+    pub fn set_capture_timestamp_us(&mut self, timestamp_us: u64) -> Result<(), WireError> {
+        self.set(Self::CAPTURE_TIMESTAMP, timestamp_us.to_be_bytes().to_vec())
+    }
+
+    /// Clockwise rotation needed to display the video upright
+    pub fn rotation(&self) -> Option<VideoRotation> {
+        let bytes: [u8; 2] = self.get(Self::ROTATION)?.try_into().ok()?;
+        VideoRotation::from_degrees(u16::from_be_bytes(bytes))
+    }
+
+    /// Set clockwise rotation needed to display the video upright
+    pub fn set_rotation(&mut self, rotation: VideoRotation) -> Result<(), WireError> {
+        self.set(Self::ROTATION, rotation.degrees().to_be_bytes().to_vec())
+    }
+
+    /// Video content hint
+    pub fn content_hint(&self) -> Option<VideoContentHint> {
+        match self.get(Self::CONTENT_HINT)? {
+            [value] => VideoContentHint::from_u8(*value),
+            _ => None,
+        }
+    }
+
+    /// Set video content hint
+    pub fn set_content_hint(&mut self, hint: VideoContentHint) -> Result<(), WireError> {
+        self.set(Self::CONTENT_HINT, vec![hint.as_u8()])
+    }
+
+    /// Audio content hint
+    pub fn audio_content_hint(&self) -> Option<AudioContentHint> {
+        match self.get(Self::AUDIO_CONTENT_HINT)? {
+            [value] => AudioContentHint::from_u8(*value),
+            _ => None,
+        }
+    }
+
+    /// Set audio content hint
+    pub fn set_audio_content_hint(&mut self, hint: AudioContentHint) -> Result<(), WireError> {
+        self.set(Self::AUDIO_CONTENT_HINT, vec![hint.as_u8()])
+    }
+
+    /// Encoded video resolution as (width, height)
+    pub fn video_resolution(&self) -> Option<(u32, u32)> {
+        let bytes: [u8; 8] = self.get(Self::VIDEO_RESOLUTION)?.try_into().ok()?;
+        let width = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let height = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+        Some((width, height))
+    }
+
+    /// Set encoded video resolution
+    pub fn set_video_resolution(&mut self, width: u32, height: u32) -> Result<(), WireError> {
+        let mut value = width.to_be_bytes().to_vec();
+        value.extend_from_slice(&height.to_be_bytes());
+        self.set(Self::VIDEO_RESOLUTION, value)
+    }
+
+    /// Encoded video framerate
+    pub fn framerate(&self) -> Option<u32> {
+        let bytes: [u8; 4] = self.get(Self::FRAMERATE)?.try_into().ok()?;
+        Some(u32::from_be_bytes(bytes))
+    }
+
+    /// Set encoded video framerate
+    pub fn set_framerate(&mut self, framerate: u32) -> Result<(), WireError> {
+        self.set(Self::FRAMERATE, framerate.to_be_bytes().to_vec())
+    }
+
+    /// Integrity hash over the object
+    pub fn integrity_hash(&self) -> Option<[u8; 32]> {
+        self.get(Self::INTEGRITY_HASH)?.try_into().ok()
+    }
+
+    /// Set the integrity hash over the object
+    pub fn set_integrity_hash(&mut self, hash: [u8; 32]) -> Result<(), WireError> {
+        self.set(Self::INTEGRITY_HASH, hash.to_vec())
+    }
+
+    /// Position of this datagram in a fragmented object as (index, count)
+    pub fn fragment(&self) -> Option<(u16, u16)> {
+        let bytes: [u8; 4] = self.get(Self::FRAGMENT)?.try_into().ok()?;
+        let index = u16::from_be_bytes([bytes[0], bytes[1]]);
+        let count = u16::from_be_bytes([bytes[2], bytes[3]]);
+        (index < count).then_some((index, count))
+    }
+
+    /// Mark this datagram as fragment `index` of `count`
+    pub fn set_fragment(&mut self, index: u16, count: u16) -> Result<(), WireError> {
+        let mut value = index.to_be_bytes().to_vec();
+        value.extend_from_slice(&count.to_be_bytes());
+        self.set(Self::FRAGMENT, value)
+    }
+
+    /// Encoded size of a single header: varint key, varint length, value
+    fn entry_size(key: u64, value: &[u8]) -> usize {
+        varint_len(key) + varint_len(value.len() as u64) + value.len()
+    }
+}
+
+/// Length of a QUIC variable-length integer encoding
+fn varint_len(value: u64) -> usize {
+    match value {
+        0..=0x3F => 1,
+        0x40..=0x3FFF => 2,
+        0x4000..=0x3FFF_FFFF => 4,
+        _ => 8,
+    }
+}
+
+/// Video rotation in 90 degree steps (clockwise)
+#[derive(
+    Debug, Clone, Copy, Default, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub enum VideoRotation {
+    /// No rotation
+    #[default]
+    Deg0,
+    /// 90 degrees clockwise
+    Deg90,
+    /// 180 degrees
+    Deg180,
+    /// 270 degrees clockwise (90 counter-clockwise)
+    Deg270,
+}
+
+impl VideoRotation {
+    /// Rotation in degrees
+    pub fn degrees(&self) -> u16 {
+        match self {
+            VideoRotation::Deg0 => 0,
+            VideoRotation::Deg90 => 90,
+            VideoRotation::Deg180 => 180,
+            VideoRotation::Deg270 => 270,
+        }
+    }
+
+    /// Parse a rotation from degrees (must be a multiple of 90 below 360)
+    pub fn from_degrees(degrees: u16) -> Option<Self> {
+        match degrees {
+            0 => Some(VideoRotation::Deg0),
+            90 => Some(VideoRotation::Deg90),
+            180 => Some(VideoRotation::Deg180),
+            270 => Some(VideoRotation::Deg270),
+            _ => None,
+        }
+    }
+
+    /// Whether the rotation swaps width and height
+    pub fn swaps_dimensions(&self) -> bool {
+        matches!(self, VideoRotation::Deg90 | VideoRotation::Deg270)
+    }
+}
+
+/// What a video track mostly contains, used to tune encoding and adaptation
+#[derive(
+    Debug, Clone, Copy, Default, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub enum VideoContentHint {
+    /// Camera or other natural motion; smoothness matters more than sharpness
+    #[default]
+    Motion,
+    /// Detailed, mostly static content such as slides or artwork
+    Detail,
+    /// Text-heavy content such as screen shared documents or code
+    Text,
+}
+
+impl VideoContentHint {
+    /// Wire representation
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            VideoContentHint::Motion => 0,
+            VideoContentHint::Detail => 1,
+            VideoContentHint::Text => 2,
+        }
+    }
+
+    /// Parse from the wire representation
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(VideoContentHint::Motion),
+            1 => Some(VideoContentHint::Detail),
+            2 => Some(VideoContentHint::Text),
+            _ => None,
+        }
+    }
+}
+
+/// What an audio track mostly contains, so receivers can leave music
+/// untouched by processing meant for speech
+#[derive(
+    Debug, Clone, Copy, Default, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub enum AudioContentHint {
+    /// Talking; noise suppression, gain control and ducking apply
+    #[default]
+    Speech,
+    /// Music performance; played back as sent
+    Music,
+}
+
+impl AudioContentHint {
+    /// Wire representation
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            AudioContentHint::Speech => 0,
+            AudioContentHint::Music => 1,
+        }
+    }
+
+    /// Parse from the wire representation
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(AudioContentHint::Speech),
+            1 => Some(AudioContentHint::Music),
+            _ => None,
+        }
+    }
+}
+
+/// Audio channel layout
+///
+/// Samples are interleaved in the following order:
+/// - `Mono`: C
+/// - `Stereo`: L, R
+/// - `Quad`: FL, FR, RL, RR
+/// - `Surround5_1`: FL, FR, C, LFE, RL, RR (SMPTE order)
+#[derive(
+    Debug, Clone, Copy, Default, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub enum ChannelLayout {
+    /// Single channel
+    Mono,
+    /// Two channels (left, right)
+    #[default]
+    Stereo,
+    /// Four channels (front and rear pairs)
+    Quad,
+    /// Six channels (5.1 surround)
+    Surround5_1,
+}
+
+impl ChannelLayout {
+    /// Number of interleaved channels in this layout
+    pub fn channel_count(&self) -> u8 {
+        match self {
+            ChannelLayout::Mono => 1,
+            ChannelLayout::Stereo => 2,
+            ChannelLayout::Quad => 4,
+            ChannelLayout::Surround5_1 => 6,
+        }
+    }
+
+    /// Map a raw channel count to its default layout
+    pub fn from_channel_count(channels: u8) -> Option<Self> {
+        match channels {
+            1 => Some(ChannelLayout::Mono),
+            2 => Some(ChannelLayout::Stereo),
+            4 => Some(ChannelLayout::Quad),
+            6 => Some(ChannelLayout::Surround5_1),
+            _ => None,
+        }
+    }
+
+    /// Negotiate a layout both sides can handle (the smaller of the two)
+    pub fn negotiate(self, other: ChannelLayout) -> ChannelLayout {
+        self.min(other)
+    }
+}
+
+/// H.264 video frame for MoQ object creation
+#[derive(Debug, Clone)]
+pub struct H264Frame {
+    /// NAL units (direct H.264 output, no RTP headers)
+    pub nal_units: Vec<u8>,
+    /// Whether this is a keyframe (I-frame)
+    pub is_keyframe: bool,
+    /// Frame timestamp in microseconds
+    pub timestamp_us: u64,
+    /// Frame sequence number
+    pub sequence_number: u64,
+}
+
+/// Opus audio frame for MoQ object creation
+#[derive(Debug, Clone)]
+pub struct OpusFrame {
+    /// Opus encoded data (direct Opus output, no RTP headers)
+    pub opus_data: Vec<u8>,
+    /// Frame timestamp in microseconds
+    pub timestamp_us: u64,
+    /// Frame sequence number
+    pub sequence_number: u64,
+    /// Sample rate in Hz
+    pub sample_rate: u32,
+    /// Number of channels
+    pub channels: u8,
+}
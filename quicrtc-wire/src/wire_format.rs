@@ -0,0 +1,1384 @@
+//! IETF MoQ Wire Format Implementation
+//!
+//! This module implements the wire format for Media over QUIC (MoQ) protocol
+//! according to draft-ietf-moq-transport-13 specification.
+//!
+//! The implementation provides binary encoding and decoding for:
+//! - Control messages (Section 8 of the spec)
+//! - Data streams and datagrams (Section 9 of the spec)
+//! - Variable-length integer encoding (from QUIC RFC 9000)
+
+use crate::error::WireError;
+use crate::message::{
+    MoqCapabilities, MoqControlMessage, MoqRole, MoqTrack, MoqTrackType, TrackNamespace,
+};
+use crate::namespace::TrackNamespacePattern;
+use crate::object::{MoqObject, MoqObjectMetadata, MoqObjectStatus};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use bytes::{Buf, BufMut, BytesMut};
+
+/// MoQ Wire Format encoder/decoder
+#[derive(Debug)]
+pub struct MoqWireFormat;
+
+/// Variable-length integer encoding following QUIC specification (RFC 9000, Section 16)
+impl MoqWireFormat {
+    /// Encode a variable-length integer
+    pub fn encode_varint(value: u64, buf: &mut BytesMut) {
+        if value < 0x40 {
+            // 6-bit value, 1 byte
+            buf.put_u8(value as u8);
+        } else if value < 0x4000 {
+            // 14-bit value, 2 bytes
+            buf.put_u16((0x4000 | value) as u16);
+        } else if value < 0x40000000 {
+            // 30-bit value, 4 bytes
+            buf.put_u32((0x80000000 | value) as u32);
+        } else if value < 0x4000000000000000 {
+            // 62-bit value, 8 bytes
+            buf.put_u64(0xC000000000000000 | value);
+        } else {
+            // Value too large for varint encoding
+            panic!("Value too large for varint encoding: {}", value);
+        }
+    }
+
+    /// Decode a variable-length integer
+    pub fn decode_varint<B: Buf>(buf: &mut B) -> Result<u64, WireError> {
+        if !buf.has_remaining() {
+            return Err(WireError::InvalidData {
+                reason: "No data available for varint".to_string(),
+            });
+        }
+
+        let first_byte = buf.get_u8();
+        let prefix = first_byte >> 6;
+
+        match prefix {
+            0 => {
+                // 6-bit value
+                Ok((first_byte & 0x3F) as u64)
+            }
+            1 => {
+                // 14-bit value
+                if !buf.has_remaining() {
+                    return Err(WireError::InvalidData {
+                        reason: "Insufficient data for 14-bit varint".to_string(),
+                    });
+                }
+                let second_byte = buf.get_u8();
+                Ok((((first_byte & 0x3F) as u64) << 8) | (second_byte as u64))
+            }
+            2 => {
+                // 30-bit value
+                if buf.remaining() < 3 {
+                    return Err(WireError::InvalidData {
+                        reason: "Insufficient data for 30-bit varint".to_string(),
+                    });
+                }
+                let remaining = buf.get_uint(3);
+                Ok((((first_byte & 0x3F) as u64) << 24) | remaining)
+            }
+            3 => {
+                // 62-bit value
+                if buf.remaining() < 7 {
+                    return Err(WireError::InvalidData {
+                        reason: "Insufficient data for 62-bit varint".to_string(),
+                    });
+                }
+                let remaining = buf.get_uint(7);
+                Ok((((first_byte & 0x3F) as u64) << 56) | remaining)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Encode a length-prefixed byte string
+    pub fn encode_bytes(data: &[u8], buf: &mut BytesMut) {
+        Self::encode_varint(data.len() as u64, buf);
+        buf.extend_from_slice(data);
+    }
+
+    /// Decode a length-prefixed byte string
+    pub fn decode_bytes<B: Buf>(buf: &mut B) -> Result<Vec<u8>, WireError> {
+        let length = Self::decode_varint(buf)? as usize;
+
+        if buf.remaining() < length {
+            return Err(WireError::InvalidData {
+                reason: format!(
+                    "Insufficient data: need {} bytes, have {}",
+                    length,
+                    buf.remaining()
+                ),
+            });
+        }
+
+        let mut data = vec![0u8; length];
+        buf.copy_to_slice(&mut data);
+        Ok(data)
+    }
+}
+
+/// SETUP and request parameters (Section 8.3 of MoQ spec)
+impl MoqWireFormat {
+    /// Encode the SETUP parameters of `capabilities`
+    ///
+    /// Parameters are a count followed by key and length-prefixed value
+    /// pairs; integer values are varints within the value.
+    fn encode_setup_parameters(
+        capabilities: &MoqCapabilities,
+        buf: &mut BytesMut,
+    ) -> Result<(), WireError> {
+        if let Some(key) = capabilities
+            .extensions
+            .keys()
+            .find(|key| MoqCapabilities::is_standard_parameter(**key))
+        {
+            return Err(WireError::InvalidData {
+                reason: format!(
+                    "Extension parameter {:#x} collides with a standard SETUP parameter",
+                    key
+                ),
+            });
+        }
+
+        let mut parameters: Vec<(u64, Vec<u8>)> = Vec::new();
+        let varint = |value: u64| {
+            let mut value_buf = BytesMut::new();
+            Self::encode_varint(value, &mut value_buf);
+            value_buf.to_vec()
+        };
+        parameters.push((
+            MoqCapabilities::ROLE_PARAMETER,
+            varint(capabilities.role.code()),
+        ));
+        if let Some(max_subscribe_id) = capabilities.max_subscribe_id {
+            parameters.push((
+                MoqCapabilities::MAX_SUBSCRIBE_ID_PARAMETER,
+                varint(max_subscribe_id),
+            ));
+        }
+        if let Some(token) = &capabilities.auth_token {
+            parameters.push((
+                MoqCapabilities::AUTHORIZATION_PARAMETER,
+                token.as_bytes().to_vec(),
+            ));
+        }
+        parameters.extend(
+            capabilities
+                .extensions
+                .iter()
+                .map(|(key, value)| (*key, value.clone())),
+        );
+
+        Self::encode_varint(parameters.len() as u64, buf);
+        for (key, value) in &parameters {
+            Self::encode_varint(*key, buf);
+            Self::encode_bytes(value, buf);
+        }
+        Ok(())
+    }
+
+    /// Decode the capabilities and SETUP parameters following the version
+    ///
+    /// Parameters are optional, so a SETUP from a peer that sends none
+    /// decodes with the default role and no extensions.
+    fn decode_setup_capabilities<B: Buf>(
+        version: u32,
+        buf: &mut B,
+    ) -> Result<MoqCapabilities, WireError> {
+        let mut capabilities = MoqCapabilities {
+            version,
+            max_tracks: Self::decode_varint(buf)? as u32,
+            max_object_size: Self::decode_varint(buf)?,
+            ..MoqCapabilities::default()
+        };
+        if !buf.has_remaining() {
+            return Ok(capabilities);
+        }
+
+        let count = Self::decode_varint(buf)?;
+        let mut seen = Vec::new();
+        for _ in 0..count {
+            let key = Self::decode_varint(buf)?;
+            let value = Self::decode_bytes(buf)?;
+            if seen.contains(&key) {
+                return Err(WireError::InvalidData {
+                    reason: format!("Duplicate SETUP parameter {:#x}", key),
+                });
+            }
+            seen.push(key);
+
+            match key {
+                MoqCapabilities::ROLE_PARAMETER => {
+                    let code = Self::decode_varint_parameter(key, &value)?;
+                    capabilities.role =
+                        MoqRole::from_code(code).ok_or_else(|| WireError::InvalidData {
+                            reason: format!("Unknown role {}", code),
+                        })?;
+                }
+                MoqCapabilities::MAX_SUBSCRIBE_ID_PARAMETER => {
+                    capabilities.max_subscribe_id =
+                        Some(Self::decode_varint_parameter(key, &value)?);
+                }
+                MoqCapabilities::AUTHORIZATION_PARAMETER => {
+                    let token = String::from_utf8(value).map_err(|_| WireError::InvalidData {
+                        reason: "Authorization token is not valid UTF-8".to_string(),
+                    })?;
+                    capabilities.auth_token = Some(token);
+                }
+                _ => {
+                    capabilities.extensions.insert(key, value);
+                }
+            }
+        }
+        Ok(capabilities)
+    }
+
+    /// Encode the parameters of an ANNOUNCE or SUBSCRIBE, which carry at
+    /// most an authorization token
+    fn encode_request_parameters(auth_token: Option<&str>, buf: &mut BytesMut) {
+        match auth_token {
+            Some(token) => {
+                Self::encode_varint(1, buf);
+                Self::encode_varint(MoqCapabilities::AUTHORIZATION_PARAMETER, buf);
+                Self::encode_bytes(token.as_bytes(), buf);
+            }
+            None => Self::encode_varint(0, buf),
+        }
+    }
+
+    /// Decode the parameters of an ANNOUNCE or SUBSCRIBE, returning the
+    /// authorization token; other parameters are skipped
+    fn decode_request_parameters<B: Buf>(buf: &mut B) -> Result<Option<String>, WireError> {
+        if !buf.has_remaining() {
+            return Ok(None);
+        }
+
+        let mut auth_token = None;
+        for _ in 0..Self::decode_varint(buf)? {
+            let key = Self::decode_varint(buf)?;
+            let value = Self::decode_bytes(buf)?;
+            if key == MoqCapabilities::AUTHORIZATION_PARAMETER {
+                let token = String::from_utf8(value).map_err(|_| WireError::InvalidData {
+                    reason: "Authorization token is not valid UTF-8".to_string(),
+                })?;
+                auth_token = Some(token);
+            }
+        }
+        Ok(auth_token)
+    }
+
+    /// Decode a parameter value holding exactly one varint
+    fn decode_varint_parameter(key: u64, value: &[u8]) -> Result<u64, WireError> {
+        let mut value_buf = value;
+        let decoded = Self::decode_varint(&mut value_buf)?;
+        if value_buf.has_remaining() {
+            return Err(WireError::InvalidData {
+                reason: format!("Trailing bytes in SETUP parameter {:#x}", key),
+            });
+        }
+        Ok(decoded)
+    }
+}
+
+/// Control Message Wire Format (Section 8 of MoQ spec)
+impl MoqWireFormat {
+    /// Encode a control message
+    pub fn encode_control_message(
+        message: &MoqControlMessage,
+        buf: &mut BytesMut,
+    ) -> Result<(), WireError> {
+        match message {
+            MoqControlMessage::Setup {
+                version,
+                capabilities,
+            } => {
+                Self::encode_varint(0x20, buf); // CLIENT_SETUP message type
+
+                // Encode version
+                Self::encode_varint(*version as u64, buf);
+
+                // Encode capabilities
+                Self::encode_varint(capabilities.max_tracks as u64, buf);
+                Self::encode_varint(capabilities.max_object_size, buf);
+                Self::encode_setup_parameters(capabilities, buf)?;
+            }
+
+            MoqControlMessage::SetupOk {
+                version,
+                capabilities,
+            } => {
+                Self::encode_varint(0x21, buf); // SERVER_SETUP message type
+
+                // Encode version
+                Self::encode_varint(*version as u64, buf);
+
+                // Encode capabilities
+                Self::encode_varint(capabilities.max_tracks as u64, buf);
+                Self::encode_varint(capabilities.max_object_size, buf);
+                Self::encode_setup_parameters(capabilities, buf)?;
+            }
+
+            MoqControlMessage::SetupError { code, reason } => {
+                Self::encode_varint(0x22, buf); // SETUP_ERROR message type
+                Self::encode_varint(*code as u64, buf);
+                Self::encode_bytes(reason.as_bytes(), buf);
+            }
+
+            MoqControlMessage::Announce {
+                track_namespace,
+                track,
+                auth_token,
+            } => {
+                Self::encode_varint(0x06, buf); // ANNOUNCE message type
+
+                // Encode track namespace
+                Self::encode_track_namespace(track_namespace, buf)?;
+
+                // Encode track name
+                Self::encode_bytes(track.name.as_bytes(), buf);
+
+                Self::encode_request_parameters(auth_token.as_deref(), buf);
+            }
+
+            MoqControlMessage::AnnounceOk { track_namespace } => {
+                Self::encode_varint(0x07, buf); // ANNOUNCE_OK message type
+                Self::encode_track_namespace(track_namespace, buf)?;
+            }
+
+            MoqControlMessage::AnnounceError {
+                track_namespace,
+                code,
+                reason,
+            } => {
+                Self::encode_varint(0x08, buf); // ANNOUNCE_ERROR message type
+                Self::encode_track_namespace(track_namespace, buf)?;
+                Self::encode_varint(*code as u64, buf);
+                Self::encode_bytes(reason.as_bytes(), buf);
+            }
+
+            MoqControlMessage::Subscribe {
+                track_namespace,
+                priority,
+                start_group,
+                end_group,
+                auth_token,
+            } => {
+                Self::encode_varint(0x03, buf); // SUBSCRIBE message type
+
+                // Encode request ID (simplified)
+                Self::encode_varint(1, buf);
+
+                // Encode track namespace
+                Self::encode_track_namespace(track_namespace, buf)?;
+
+                // Encode subscription parameters
+                Self::encode_varint(*priority as u64, buf);
+
+                // Encode group range
+                Self::encode_group_range(*start_group, *end_group, buf);
+
+                Self::encode_request_parameters(auth_token.as_deref(), buf);
+            }
+
+            MoqControlMessage::SubscribeOk { track_namespace } => {
+                Self::encode_varint(0x04, buf); // SUBSCRIBE_OK message type
+
+                // Encode request ID
+                Self::encode_varint(1, buf);
+
+                // Encode track namespace
+                Self::encode_track_namespace(track_namespace, buf)?;
+            }
+
+            MoqControlMessage::SubscribeError {
+                track_namespace,
+                code,
+                reason,
+            } => {
+                Self::encode_varint(0x05, buf); // SUBSCRIBE_ERROR message type
+
+                // Encode request ID
+                Self::encode_varint(1, buf);
+
+                Self::encode_track_namespace(track_namespace, buf)?;
+                Self::encode_varint(*code as u64, buf);
+                Self::encode_bytes(reason.as_bytes(), buf);
+            }
+
+            MoqControlMessage::SubscribeUpdate {
+                track_namespace,
+                priority,
+                start_group,
+                end_group,
+            } => {
+                Self::encode_varint(0x02, buf); // SUBSCRIBE_UPDATE message type
+
+                // Encode request ID of the subscription being updated
+                Self::encode_varint(1, buf);
+
+                Self::encode_track_namespace(track_namespace, buf)?;
+                Self::encode_varint(*priority as u64, buf);
+                Self::encode_group_range(*start_group, *end_group, buf);
+            }
+
+            MoqControlMessage::Unsubscribe { track_namespace } => {
+                Self::encode_varint(0x0A, buf); // UNSUBSCRIBE message type
+
+                // Encode request ID
+                Self::encode_varint(1, buf);
+
+                // Encode track namespace
+                Self::encode_track_namespace(track_namespace, buf)?;
+            }
+
+            MoqControlMessage::SubscribeNamespace { pattern } => {
+                Self::encode_varint(0x11, buf); // SUBSCRIBE_NAMESPACE message type
+                Self::encode_bytes(pattern.to_string().as_bytes(), buf);
+            }
+
+            MoqControlMessage::SubscribeNamespaceOk { pattern } => {
+                Self::encode_varint(0x12, buf); // SUBSCRIBE_NAMESPACE_OK message type
+                Self::encode_bytes(pattern.to_string().as_bytes(), buf);
+            }
+
+            MoqControlMessage::SubscribeNamespaceError {
+                pattern,
+                code,
+                reason,
+            } => {
+                Self::encode_varint(0x13, buf); // SUBSCRIBE_NAMESPACE_ERROR message type
+                Self::encode_bytes(pattern.to_string().as_bytes(), buf);
+                Self::encode_varint(*code as u64, buf);
+                Self::encode_bytes(reason.as_bytes(), buf);
+            }
+
+            MoqControlMessage::UnsubscribeNamespace { pattern } => {
+                Self::encode_varint(0x14, buf); // UNSUBSCRIBE_NAMESPACE message type
+                Self::encode_bytes(pattern.to_string().as_bytes(), buf);
+            }
+
+            MoqControlMessage::Terminate { code, reason } => {
+                Self::encode_varint(0x10, buf); // GOAWAY message type (closest to terminate)
+
+                // Encode termination code
+                Self::encode_varint(*code as u64, buf);
+
+                // Encode reason phrase
+                Self::encode_bytes(reason.as_bytes(), buf);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Encode a control message preceded by its sequence number on the
+    /// control stream
+    ///
+    /// Each side numbers the control messages it sends from 0, so the
+    /// receiver can tell replayed or reordered messages apart.
+    pub fn encode_sequenced_control_message(
+        sequence: u64,
+        message: &MoqControlMessage,
+        buf: &mut BytesMut,
+    ) -> Result<(), WireError> {
+        Self::encode_varint(sequence, buf);
+        Self::encode_control_message(message, buf)
+    }
+
+    /// Decode a control message preceded by its sequence number
+    pub fn decode_sequenced_control_message(
+        data: &[u8],
+    ) -> Result<(u64, MoqControlMessage), WireError> {
+        let mut buf = data;
+        let sequence = Self::decode_varint(&mut buf)?;
+        Ok((sequence, Self::decode_control_message(buf)?))
+    }
+
+    /// Decode a control message
+    pub fn decode_control_message(data: &[u8]) -> Result<MoqControlMessage, WireError> {
+        let mut buf = data;
+
+        let message_type = Self::decode_varint(&mut buf)?;
+
+        match message_type {
+            0x20 => {
+                // CLIENT_SETUP
+                let version = Self::decode_varint(&mut buf)? as u32;
+                let capabilities = Self::decode_setup_capabilities(version, &mut buf)?;
+
+                Ok(MoqControlMessage::Setup {
+                    version,
+                    capabilities,
+                })
+            }
+
+            0x21 => {
+                // SERVER_SETUP
+                let version = Self::decode_varint(&mut buf)? as u32;
+                let capabilities = Self::decode_setup_capabilities(version, &mut buf)?;
+
+                Ok(MoqControlMessage::SetupOk {
+                    version,
+                    capabilities,
+                })
+            }
+
+            0x22 => {
+                // SETUP_ERROR
+                let code = Self::decode_varint(&mut buf)? as u32;
+                let reason_bytes = Self::decode_bytes(&mut buf)?;
+                let reason =
+                    String::from_utf8(reason_bytes).map_err(|_| WireError::InvalidData {
+                        reason: "Invalid UTF-8 in reason phrase".to_string(),
+                    })?;
+                Ok(MoqControlMessage::SetupError { code, reason })
+            }
+
+            0x06 => {
+                // ANNOUNCE
+                let track_namespace = Self::decode_track_namespace(&mut buf)?;
+                let track_name_bytes = Self::decode_bytes(&mut buf)?;
+                let track_name =
+                    String::from_utf8(track_name_bytes).map_err(|_| WireError::InvalidData {
+                        reason: "Invalid UTF-8 in track name".to_string(),
+                    })?;
+
+                let auth_token = Self::decode_request_parameters(&mut buf)?;
+
+                Ok(MoqControlMessage::Announce {
+                    track_namespace: track_namespace.clone(),
+                    track: MoqTrack::new(track_namespace, track_name, MoqTrackType::Data),
+                    auth_token,
+                })
+            }
+
+            0x07 => {
+                // ANNOUNCE_OK
+                let track_namespace = Self::decode_track_namespace(&mut buf)?;
+                Ok(MoqControlMessage::AnnounceOk { track_namespace })
+            }
+
+            0x08 => {
+                // ANNOUNCE_ERROR
+                let track_namespace = Self::decode_track_namespace(&mut buf)?;
+                let code = Self::decode_varint(&mut buf)? as u32;
+                let reason_bytes = Self::decode_bytes(&mut buf)?;
+                let reason =
+                    String::from_utf8(reason_bytes).map_err(|_| WireError::InvalidData {
+                        reason: "Invalid UTF-8 in reason phrase".to_string(),
+                    })?;
+                Ok(MoqControlMessage::AnnounceError {
+                    track_namespace,
+                    code,
+                    reason,
+                })
+            }
+
+            0x03 => {
+                // SUBSCRIBE
+                let _request_id = Self::decode_varint(&mut buf)?;
+                let track_namespace = Self::decode_track_namespace(&mut buf)?;
+                let priority = Self::decode_varint(&mut buf)? as u8;
+
+                // Decode group range
+                let (start_group, end_group) = Self::decode_group_range(&mut buf)?;
+                let auth_token = Self::decode_request_parameters(&mut buf)?;
+
+                Ok(MoqControlMessage::Subscribe {
+                    track_namespace,
+                    priority,
+                    start_group,
+                    end_group,
+                    auth_token,
+                })
+            }
+
+            0x04 => {
+                // SUBSCRIBE_OK
+                let _request_id = Self::decode_varint(&mut buf)?;
+                let track_namespace = Self::decode_track_namespace(&mut buf)?;
+                Ok(MoqControlMessage::SubscribeOk { track_namespace })
+            }
+
+            0x05 => {
+                // SUBSCRIBE_ERROR
+                let _request_id = Self::decode_varint(&mut buf)?;
+                let track_namespace = Self::decode_track_namespace(&mut buf)?;
+                let code = Self::decode_varint(&mut buf)? as u32;
+                let reason_bytes = Self::decode_bytes(&mut buf)?;
+                let reason =
+                    String::from_utf8(reason_bytes).map_err(|_| WireError::InvalidData {
+                        reason: "Invalid UTF-8 in reason phrase".to_string(),
+                    })?;
+                Ok(MoqControlMessage::SubscribeError {
+                    track_namespace,
+                    code,
+                    reason,
+                })
+            }
+
+            0x02 => {
+                // SUBSCRIBE_UPDATE
+                let _request_id = Self::decode_varint(&mut buf)?;
+                let track_namespace = Self::decode_track_namespace(&mut buf)?;
+                let priority = Self::decode_varint(&mut buf)? as u8;
+                let (start_group, end_group) = Self::decode_group_range(&mut buf)?;
+
+                Ok(MoqControlMessage::SubscribeUpdate {
+                    track_namespace,
+                    priority,
+                    start_group,
+                    end_group,
+                })
+            }
+
+            0x0A => {
+                // UNSUBSCRIBE
+                let _request_id = Self::decode_varint(&mut buf)?;
+                let track_namespace = Self::decode_track_namespace(&mut buf)?;
+                Ok(MoqControlMessage::Unsubscribe { track_namespace })
+            }
+
+            0x11 => {
+                // SUBSCRIBE_NAMESPACE
+                let pattern = Self::decode_namespace_pattern(&mut buf)?;
+                Ok(MoqControlMessage::SubscribeNamespace { pattern })
+            }
+
+            0x12 => {
+                // SUBSCRIBE_NAMESPACE_OK
+                let pattern = Self::decode_namespace_pattern(&mut buf)?;
+                Ok(MoqControlMessage::SubscribeNamespaceOk { pattern })
+            }
+
+            0x13 => {
+                // SUBSCRIBE_NAMESPACE_ERROR
+                let pattern = Self::decode_namespace_pattern(&mut buf)?;
+                let code = Self::decode_varint(&mut buf)? as u32;
+                let reason_bytes = Self::decode_bytes(&mut buf)?;
+                let reason =
+                    String::from_utf8(reason_bytes).map_err(|_| WireError::InvalidData {
+                        reason: "Invalid UTF-8 in reason phrase".to_string(),
+                    })?;
+                Ok(MoqControlMessage::SubscribeNamespaceError {
+                    pattern,
+                    code,
+                    reason,
+                })
+            }
+
+            0x14 => {
+                // UNSUBSCRIBE_NAMESPACE
+                let pattern = Self::decode_namespace_pattern(&mut buf)?;
+                Ok(MoqControlMessage::UnsubscribeNamespace { pattern })
+            }
+
+            0x10 => {
+                // GOAWAY (treated as terminate)
+                let code = Self::decode_varint(&mut buf)? as u32;
+                let reason_bytes = Self::decode_bytes(&mut buf)?;
+                let reason =
+                    String::from_utf8(reason_bytes).map_err(|_| WireError::InvalidData {
+                        reason: "Invalid UTF-8 in reason phrase".to_string(),
+                    })?;
+
+                Ok(MoqControlMessage::Terminate { code, reason })
+            }
+
+            _ => Err(WireError::Protocol {
+                reason: format!("Unknown control message type: {}", message_type),
+            }),
+        }
+    }
+
+    /// Encode track namespace according to MoQ specification
+    fn encode_track_namespace(
+        namespace: &TrackNamespace,
+        buf: &mut BytesMut,
+    ) -> Result<(), WireError> {
+        // Encode namespace tuple
+        Self::encode_bytes(namespace.namespace.as_bytes(), buf);
+
+        // Encode track name
+        Self::encode_bytes(namespace.track_name.as_bytes(), buf);
+
+        Ok(())
+    }
+
+    /// Encode an optional start and end group, each as a presence flag and value
+    fn encode_group_range(start_group: Option<u64>, end_group: Option<u64>, buf: &mut BytesMut) {
+        for group in [start_group, end_group] {
+            match group {
+                Some(group) => {
+                    Self::encode_varint(1, buf);
+                    Self::encode_varint(group, buf);
+                }
+                None => Self::encode_varint(0, buf),
+            }
+        }
+    }
+
+    /// Decode a group range written by [`Self::encode_group_range`]
+    fn decode_group_range<B: Buf>(buf: &mut B) -> Result<(Option<u64>, Option<u64>), WireError> {
+        let start_group = if Self::decode_varint(buf)? == 1 {
+            Some(Self::decode_varint(buf)?)
+        } else {
+            None
+        };
+        let end_group = if Self::decode_varint(buf)? == 1 {
+            Some(Self::decode_varint(buf)?)
+        } else {
+            None
+        };
+        Ok((start_group, end_group))
+    }
+
+    /// Decode a track namespace pattern written in its string form
+    fn decode_namespace_pattern<B: Buf>(buf: &mut B) -> Result<TrackNamespacePattern, WireError> {
+        let pattern_bytes = Self::decode_bytes(buf)?;
+        let pattern = String::from_utf8(pattern_bytes).map_err(|_| WireError::InvalidData {
+            reason: "Invalid UTF-8 in namespace pattern".to_string(),
+        })?;
+        TrackNamespacePattern::parse(&pattern)
+    }
+
+    /// Decode track namespace according to MoQ specification
+    fn decode_track_namespace<B: Buf>(buf: &mut B) -> Result<TrackNamespace, WireError> {
+        // Decode namespace
+        let namespace_bytes = Self::decode_bytes(buf)?;
+        let namespace = String::from_utf8(namespace_bytes).map_err(|_| WireError::InvalidData {
+            reason: "Invalid UTF-8 in namespace".to_string(),
+        })?;
+
+        // Decode track name
+        let track_name_bytes = Self::decode_bytes(buf)?;
+        let track_name =
+            String::from_utf8(track_name_bytes).map_err(|_| WireError::InvalidData {
+                reason: "Invalid UTF-8 in track name".to_string(),
+            })?;
+
+        Ok(TrackNamespace {
+            namespace,
+            track_name,
+        })
+    }
+}
+
+/// Data Stream and Datagram Wire Format (Section 9 of MoQ spec)
+impl MoqWireFormat {
+    /// Encode MoQ object for data stream transmission
+    pub fn encode_object_stream(
+        object: &MoqObject,
+        track_alias: u64,
+        buf: &mut BytesMut,
+    ) -> Result<(), WireError> {
+        // Subgroup Header format (Section 9.4.2)
+        Self::encode_varint(track_alias, buf);
+        Self::encode_varint(object.group_id, buf);
+        Self::encode_varint(object.object_id, buf); // Subgroup ID (simplified)
+        Self::encode_varint(object.publisher_priority as u64, buf);
+
+        // Object Header within subgroup
+        Self::encode_varint(object.object_id, buf);
+        Self::encode_object_extensions(&object.metadata, buf);
+        Self::encode_varint(object.payload.len() as u64, buf);
+
+        // Object status
+        let status = match object.object_status {
+            MoqObjectStatus::Normal => 0u8,
+            MoqObjectStatus::EndOfGroup => 1u8,
+            MoqObjectStatus::EndOfTrack => 2u8,
+        };
+        buf.put_u8(status);
+
+        // Object payload
+        buf.extend_from_slice(&object.payload);
+
+        Ok(())
+    }
+
+    /// Decode MoQ object from data stream
+    pub fn decode_object_stream(data: &[u8]) -> Result<(u64, MoqObject), WireError> {
+        let mut buf = data;
+
+        // Decode subgroup header
+        let track_alias = Self::decode_varint(&mut buf)?;
+        let group_id = Self::decode_varint(&mut buf)?;
+        let _subgroup_id = Self::decode_varint(&mut buf)?;
+        let publisher_priority = Self::decode_varint(&mut buf)? as u8;
+
+        // Decode object header
+        let object_id = Self::decode_varint(&mut buf)?;
+        let metadata = Self::decode_object_extensions(&mut buf)?;
+        let payload_length = Self::decode_varint(&mut buf)? as usize;
+
+        if buf.remaining() < payload_length + 1 {
+            return Err(WireError::InvalidData {
+                reason: "Insufficient data for object payload".to_string(),
+            });
+        }
+
+        // Decode object status
+        let status_byte = buf.get_u8();
+        let object_status = match status_byte {
+            0 => MoqObjectStatus::Normal,
+            1 => MoqObjectStatus::EndOfGroup,
+            2 => MoqObjectStatus::EndOfTrack,
+            _ => {
+                return Err(WireError::InvalidData {
+                    reason: format!("Invalid object status: {}", status_byte),
+                });
+            }
+        };
+
+        // Decode payload
+        let mut payload = vec![0u8; payload_length];
+        buf.copy_to_slice(&mut payload);
+
+        let object = MoqObject {
+            track_namespace: TrackNamespace {
+                namespace: "default".to_string(), // Would be resolved by track alias
+                track_name: "unknown".to_string(),
+            },
+            track_name: "unknown".to_string(),
+            group_id,
+            object_id,
+            publisher_priority,
+            payload,
+            object_status,
+            #[cfg(feature = "std")]
+            created_at: std::time::Instant::now(),
+            size: payload_length,
+            metadata,
+        };
+
+        Ok((track_alias, object))
+    }
+
+    /// Encode MoQ object for datagram transmission (Section 9.3)
+    pub fn encode_object_datagram(
+        object: &MoqObject,
+        track_alias: u64,
+        buf: &mut BytesMut,
+    ) -> Result<(), WireError> {
+        // Object Datagram format
+        Self::encode_varint(track_alias, buf);
+        Self::encode_varint(object.group_id, buf);
+        Self::encode_varint(object.object_id, buf);
+        Self::encode_varint(object.publisher_priority as u64, buf);
+        Self::encode_object_extensions(&object.metadata, buf);
+
+        // Object status
+        let status = match object.object_status {
+            MoqObjectStatus::Normal => 0u8,
+            MoqObjectStatus::EndOfGroup => 1u8,
+            MoqObjectStatus::EndOfTrack => 2u8,
+        };
+        buf.put_u8(status);
+
+        // Object payload (no length prefix for datagrams)
+        buf.extend_from_slice(&object.payload);
+
+        Ok(())
+    }
+
+    /// Decode MoQ object from datagram
+    pub fn decode_object_datagram(data: &[u8]) -> Result<(u64, MoqObject), WireError> {
+        let mut buf = data;
+
+        // Decode object datagram header
+        let track_alias = Self::decode_varint(&mut buf)?;
+        let group_id = Self::decode_varint(&mut buf)?;
+        let object_id = Self::decode_varint(&mut buf)?;
+        let publisher_priority = Self::decode_varint(&mut buf)? as u8;
+        let metadata = Self::decode_object_extensions(&mut buf)?;
+
+        if !buf.has_remaining() {
+            return Err(WireError::InvalidData {
+                reason: "No data for object status".to_string(),
+            });
+        }
+
+        // Decode object status
+        let status_byte = buf.get_u8();
+        let object_status = match status_byte {
+            0 => MoqObjectStatus::Normal,
+            1 => MoqObjectStatus::EndOfGroup,
+            2 => MoqObjectStatus::EndOfTrack,
+            _ => {
+                return Err(WireError::InvalidData {
+                    reason: format!("Invalid object status: {}", status_byte),
+                });
+            }
+        };
+
+        // Remaining data is payload
+        let remaining = buf.remaining();
+        let mut payload = vec![0u8; remaining];
+        buf.copy_to_slice(&mut payload);
+
+        let object = MoqObject {
+            track_namespace: TrackNamespace {
+                namespace: "default".to_string(),
+                track_name: "unknown".to_string(),
+            },
+            track_name: "unknown".to_string(),
+            group_id,
+            object_id,
+            publisher_priority,
+            payload,
+            object_status,
+            #[cfg(feature = "std")]
+            created_at: std::time::Instant::now(),
+            size: remaining,
+            metadata,
+        };
+
+        Ok((track_alias, object))
+    }
+
+    /// Encode object extension headers as a length-prefixed block of
+    /// (key, length, value) entries
+    pub fn encode_object_extensions(metadata: &MoqObjectMetadata, buf: &mut BytesMut) {
+        Self::encode_varint(metadata.encoded_size() as u64, buf);
+        for (key, value) in metadata.iter() {
+            Self::encode_varint(key, buf);
+            Self::encode_bytes(value, buf);
+        }
+    }
+
+    /// Decode object extension headers, enforcing the extension size budget
+    pub fn decode_object_extensions<B: Buf>(buf: &mut B) -> Result<MoqObjectMetadata, WireError> {
+        let block_length = Self::decode_varint(buf)? as usize;
+
+        if block_length > MoqObjectMetadata::MAX_ENCODED_SIZE {
+            return Err(WireError::InvalidData {
+                reason: format!(
+                    "Object extension headers too large: {} > {} bytes",
+                    block_length,
+                    MoqObjectMetadata::MAX_ENCODED_SIZE
+                ),
+            });
+        }
+
+        if buf.remaining() < block_length {
+            return Err(WireError::InvalidData {
+                reason: "Insufficient data for object extension headers".to_string(),
+            });
+        }
+
+        let mut block_buf = buf.copy_to_bytes(block_length);
+        let mut metadata = MoqObjectMetadata::new();
+
+        while block_buf.has_remaining() {
+            let key = Self::decode_varint(&mut block_buf)?;
+            let value = Self::decode_bytes(&mut block_buf)?;
+            metadata.set(key, value)?;
+        }
+
+        Ok(metadata)
+    }
+}
+
+/// Utility functions for wire format validation
+impl MoqWireFormat {
+    /// Validate that a buffer contains a complete varint
+    pub fn validate_varint(data: &[u8]) -> Result<(u64, usize), WireError> {
+        if data.is_empty() {
+            return Err(WireError::InvalidData {
+                reason: "Empty data for varint".to_string(),
+            });
+        }
+
+        let first_byte = data[0];
+        let prefix = first_byte >> 6;
+        let expected_length = match prefix {
+            0 => 1,
+            1 => 2,
+            2 => 4,
+            3 => 8,
+            _ => unreachable!(),
+        };
+
+        if data.len() < expected_length {
+            return Err(WireError::InvalidData {
+                reason: format!(
+                    "Insufficient data for varint: need {} bytes, have {}",
+                    expected_length,
+                    data.len()
+                ),
+            });
+        }
+
+        let mut buf = data;
+        let value = Self::decode_varint(&mut buf)?;
+        Ok((value, expected_length))
+    }
+
+    /// Calculate the encoded size of a varint
+    pub fn varint_size(value: u64) -> usize {
+        if value < 0x40 {
+            1
+        } else if value < 0x4000 {
+            2
+        } else if value < 0x40000000 {
+            4
+        } else if value < 0x4000000000000000 {
+            8
+        } else {
+            panic!("Value too large for varint encoding: {}", value);
+        }
+    }
+
+    /// Estimate the encoded size of a control message
+    pub fn estimate_control_message_size(message: &MoqControlMessage) -> usize {
+        match message {
+            MoqControlMessage::Setup { capabilities, .. }
+            | MoqControlMessage::SetupOk { capabilities, .. } => {
+                // Conservative estimate, plus the variable-size parameters
+                let parameters: usize = capabilities
+                    .auth_token
+                    .iter()
+                    .map(String::len)
+                    .chain(capabilities.extensions.values().map(Vec::len))
+                    .map(|len| len + 16)
+                    .sum();
+                32 + parameters
+            }
+            MoqControlMessage::Announce {
+                track_namespace,
+                track,
+                auth_token,
+            } => {
+                8 + track_namespace.namespace.len()
+                    + track_namespace.track_name.len()
+                    + track.name.len()
+                    + auth_token.as_ref().map_or(0, |token| token.len() + 16)
+            }
+            MoqControlMessage::Subscribe {
+                track_namespace,
+                auth_token,
+                ..
+            } => {
+                16 + track_namespace.namespace.len()
+                    + track_namespace.track_name.len()
+                    + auth_token.as_ref().map_or(0, |token| token.len() + 16)
+            }
+            _ => 64, // Conservative default
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {MoqCapabilities, MoqTrackType};
+
+    #[test]
+    fn test_varint_encoding_decoding() {
+        let test_values = vec![
+            0,
+            63,
+            64,
+            16383,
+            16384,
+            1073741823,
+            1073741824,
+            u64::MAX >> 2,
+        ];
+
+        for value in test_values {
+            let mut buf = BytesMut::new();
+            MoqWireFormat::encode_varint(value, &mut buf);
+
+            let mut cursor = &buf[..];
+            let decoded = MoqWireFormat::decode_varint(&mut cursor).unwrap();
+
+            assert_eq!(
+                value, decoded,
+                "Varint encoding/decoding mismatch for value {}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_control_message_encoding() {
+        let setup_message = MoqControlMessage::Setup {
+            version: 1,
+            capabilities: MoqCapabilities {
+                version: 1,
+                max_tracks: 100,
+                supported_track_types: vec![MoqTrackType::Audio, MoqTrackType::Video],
+                max_object_size: 1024 * 1024,
+                supports_caching: true,
+                ..MoqCapabilities::default()
+            },
+        };
+
+        let mut buf = BytesMut::new();
+        MoqWireFormat::encode_control_message(&setup_message, &mut buf).unwrap();
+
+        // Verify we can decode it back
+        let decoded = MoqWireFormat::decode_control_message(&buf).unwrap();
+
+        match decoded {
+            MoqControlMessage::Setup {
+                version,
+                capabilities,
+            } => {
+                assert_eq!(version, 1);
+                assert_eq!(capabilities.max_tracks, 100);
+            }
+            _ => panic!("Unexpected message type after decoding"),
+        }
+    }
+
+    #[test]
+    fn test_setup_parameters_round_trip() {
+        let capabilities = MoqCapabilities {
+            role: MoqRole::Subscriber,
+            max_subscribe_id: Some(500),
+            auth_token: Some("relay-token".to_string()),
+            ..MoqCapabilities::default()
+        }
+        .with_extension(0x40, b"eu-west".to_vec());
+        let setup_ok = MoqControlMessage::SetupOk {
+            version: 1,
+            capabilities,
+        };
+
+        let mut buf = BytesMut::new();
+        MoqWireFormat::encode_control_message(&setup_ok, &mut buf).unwrap();
+        match MoqWireFormat::decode_control_message(&buf).unwrap() {
+            MoqControlMessage::SetupOk { capabilities, .. } => {
+                assert_eq!(capabilities.role, MoqRole::Subscriber);
+                assert_eq!(capabilities.max_subscribe_id, Some(500));
+                assert_eq!(capabilities.auth_token.as_deref(), Some("relay-token"));
+                assert_eq!(
+                    capabilities.extensions.get(&0x40).map(Vec::as_slice),
+                    Some(&b"eu-west"[..])
+                );
+            }
+            _ => panic!("Unexpected message type after decoding"),
+        }
+
+        // Extensions can't stand in for the parameters with fields of their own
+        let setup = MoqControlMessage::Setup {
+            version: 1,
+            capabilities: MoqCapabilities::default()
+                .with_extension(MoqCapabilities::AUTHORIZATION_PARAMETER, b"x".to_vec()),
+        };
+        assert!(MoqWireFormat::encode_control_message(&setup, &mut BytesMut::new()).is_err());
+    }
+
+    #[test]
+    fn test_request_auth_token_round_trip() {
+        let subscribe = MoqControlMessage::Subscribe {
+            track_namespace: TrackNamespace {
+                namespace: "room.standup".to_string(),
+                track_name: "stage/camera".to_string(),
+            },
+            priority: 1,
+            start_group: None,
+            end_group: None,
+            auth_token: Some("moderator-token".to_string()),
+        };
+
+        let mut buf = BytesMut::new();
+        MoqWireFormat::encode_control_message(&subscribe, &mut buf).unwrap();
+        match MoqWireFormat::decode_control_message(&buf).unwrap() {
+            MoqControlMessage::Subscribe { auth_token, .. } => {
+                assert_eq!(auth_token.as_deref(), Some("moderator-token"))
+            }
+            other => panic!("Unexpected message after decoding: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_subscribe_update_round_trip() {
+        let update = MoqControlMessage::SubscribeUpdate {
+            track_namespace: TrackNamespace {
+                namespace: "room.standup".to_string(),
+                track_name: "alice/camera".to_string(),
+            },
+            priority: 7,
+            start_group: Some(42),
+            end_group: None,
+        };
+
+        let mut buf = BytesMut::new();
+        MoqWireFormat::encode_control_message(&update, &mut buf).unwrap();
+        let decoded = MoqWireFormat::decode_control_message(&buf).unwrap();
+        assert_eq!(format!("{:?}", decoded), format!("{:?}", update));
+    }
+
+    #[test]
+    fn test_error_replies_round_trip() {
+        let track_namespace = TrackNamespace {
+            namespace: "room.standup".to_string(),
+            track_name: "alice/camera".to_string(),
+        };
+        let messages = [
+            MoqControlMessage::SetupError {
+                code: 0x02,
+                reason: "Unauthorized".to_string(),
+            },
+            MoqControlMessage::AnnounceError {
+                track_namespace: track_namespace.clone(),
+                code: 0x03,
+                reason: "Not permitted to announce this track".to_string(),
+            },
+            MoqControlMessage::SubscribeError {
+                track_namespace,
+                code: 0x04,
+                reason: "Track not found".to_string(),
+            },
+        ];
+
+        for message in &messages {
+            let mut buf = BytesMut::new();
+            MoqWireFormat::encode_control_message(message, &mut buf).unwrap();
+            let decoded = MoqWireFormat::decode_control_message(&buf).unwrap();
+            assert_eq!(format!("{:?}", decoded), format!("{:?}", message));
+        }
+    }
+
+    #[test]
+    fn test_namespace_subscription_messages_round_trip() {
+        let pattern = TrackNamespacePattern::parse("room.standup/*").unwrap();
+        let messages = [
+            MoqControlMessage::SubscribeNamespace {
+                pattern: pattern.clone(),
+            },
+            MoqControlMessage::SubscribeNamespaceOk {
+                pattern: pattern.clone(),
+            },
+            MoqControlMessage::SubscribeNamespaceError {
+                pattern: pattern.clone(),
+                code: 0x03,
+                reason: "Not permitted".to_string(),
+            },
+            MoqControlMessage::UnsubscribeNamespace {
+                pattern: pattern.clone(),
+            },
+        ];
+
+        for message in &messages {
+            let mut buf = BytesMut::new();
+            MoqWireFormat::encode_control_message(message, &mut buf).unwrap();
+            let decoded = MoqWireFormat::decode_control_message(&buf).unwrap();
+            assert_eq!(format!("{:?}", decoded), format!("{:?}", message));
+        }
+
+        // A malformed pattern is rejected on decode
+        let mut buf = BytesMut::new();
+        MoqWireFormat::encode_varint(0x11, &mut buf);
+        MoqWireFormat::encode_bytes(b"room.*/camera", &mut buf);
+        assert!(MoqWireFormat::decode_control_message(&buf).is_err());
+    }
+
+    #[test]
+    fn test_track_namespace_encoding() {
+        let namespace = TrackNamespace {
+            namespace: "example.com".to_string(),
+            track_name: "video/camera1".to_string(),
+        };
+
+        let mut buf = BytesMut::new();
+        MoqWireFormat::encode_track_namespace(&namespace, &mut buf).unwrap();
+
+        let mut cursor = &buf[..];
+        let decoded = MoqWireFormat::decode_track_namespace(&mut cursor).unwrap();
+
+        assert_eq!(namespace.namespace, decoded.namespace);
+        assert_eq!(namespace.track_name, decoded.track_name);
+    }
+
+    #[test]
+    fn test_object_stream_encoding() {
+        use MoqObjectStatus;
+
+        let object = MoqObject {
+            track_namespace: TrackNamespace {
+                namespace: "test".to_string(),
+                track_name: "video".to_string(),
+            },
+            track_name: "video".to_string(),
+            group_id: 42,
+            object_id: 1,
+            publisher_priority: 5,
+            payload: vec![1, 2, 3, 4, 5],
+            object_status: MoqObjectStatus::Normal,
+            #[cfg(feature = "std")]
+            created_at: std::time::Instant::now(),
+            size: 5,
+            metadata: Default::default(),
+        };
+
+        let mut buf = BytesMut::new();
+        MoqWireFormat::encode_object_stream(&object, 123, &mut buf).unwrap();
+
+        let (track_alias, decoded_object) = MoqWireFormat::decode_object_stream(&buf).unwrap();
+
+        assert_eq!(track_alias, 123);
+        assert_eq!(decoded_object.group_id, 42);
+        assert_eq!(decoded_object.object_id, 1);
+        assert_eq!(decoded_object.publisher_priority, 5);
+        assert_eq!(decoded_object.payload, vec![1, 2, 3, 4, 5]);
+        assert!(decoded_object.metadata.is_empty());
+    }
+
+    #[test]
+    fn test_object_extension_headers_round_trip() {
+        use crate::object::VideoRotation;
+
+        let mut metadata = MoqObjectMetadata::new();
+        metadata.set_rotation(VideoRotation::Deg90).unwrap();
+        metadata.set_capture_timestamp_us(1_234_567).unwrap();
+        metadata.set(0x40, b"custom".to_vec()).unwrap();
+
+        let object = MoqObject {
+            track_namespace: TrackNamespace {
+                namespace: "test".to_string(),
+                track_name: "video".to_string(),
+            },
+            track_name: "video".to_string(),
+            group_id: 7,
+            object_id: 3,
+            publisher_priority: 1,
+            payload: vec![9, 8, 7],
+            object_status: MoqObjectStatus::Normal,
+            #[cfg(feature = "std")]
+            created_at: std::time::Instant::now(),
+            size: 3,
+            metadata: metadata.clone(),
+        };
+
+        let mut buf = BytesMut::new();
+        MoqWireFormat::encode_object_stream(&object, 1, &mut buf).unwrap();
+        let (_, decoded) = MoqWireFormat::decode_object_stream(&buf).unwrap();
+        assert_eq!(decoded.metadata, metadata);
+        assert_eq!(decoded.metadata.rotation(), Some(VideoRotation::Deg90));
+        assert_eq!(decoded.payload, vec![9, 8, 7]);
+
+        let mut buf = BytesMut::new();
+        MoqWireFormat::encode_object_datagram(&object, 1, &mut buf).unwrap();
+        let (_, decoded) = MoqWireFormat::decode_object_datagram(&buf).unwrap();
+        assert_eq!(decoded.metadata.capture_timestamp_us(), Some(1_234_567));
+        assert_eq!(decoded.metadata.get(0x40), Some(&b"custom"[..]));
+        assert_eq!(decoded.payload, vec![9, 8, 7]);
+
+        // Oversized extension blocks are rejected
+        let mut buf = BytesMut::new();
+        MoqWireFormat::encode_varint(MoqObjectMetadata::MAX_ENCODED_SIZE as u64 + 1, &mut buf);
+        buf.extend_from_slice(&[0u8; MoqObjectMetadata::MAX_ENCODED_SIZE + 1]);
+        let mut cursor = &buf[..];
+        assert!(MoqWireFormat::decode_object_extensions(&mut cursor).is_err());
+    }
+}
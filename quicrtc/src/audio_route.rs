@@ -0,0 +1,187 @@
+//! Audio routes on mobile
+//!
+//! Phones play calls through the earpiece, the loudspeaker, a wired headset
+//! or a Bluetooth headset. Bluetooth headsets come in two profiles: A2DP
+//! plays high quality audio but carries no microphone, while HFP carries
+//! the headset's microphone at call quality. The platform side, such as
+//! `AVAudioSession` on iOS or `AudioManager` on Android, is an
+//! [`AudioSession`] provided by the app; an [`AudioRoutePolicy`] picks from
+//! the routes it reports.
+
+use crate::QuicRtcError;
+
+/// Kind of audio route
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AudioRouteKind {
+    /// Handset earpiece, with the built-in microphone
+    Earpiece,
+    /// Built-in loudspeaker, with the built-in microphone
+    Speaker,
+    /// Wired headset or headphones
+    WiredHeadset,
+    /// Bluetooth headset in the hands-free profile, with its microphone
+    BluetoothHfp,
+    /// Bluetooth headset in the high quality playback profile, output only
+    BluetoothA2dp,
+    /// USB audio device
+    Usb,
+}
+
+impl AudioRouteKind {
+    /// Whether the route can record as well as play
+    pub fn has_microphone(&self) -> bool {
+        !matches!(self, AudioRouteKind::BluetoothA2dp)
+    }
+
+    /// Whether the route is a Bluetooth headset
+    pub fn is_bluetooth(&self) -> bool {
+        matches!(
+            self,
+            AudioRouteKind::BluetoothHfp | AudioRouteKind::BluetoothA2dp
+        )
+    }
+
+    /// Whether the route uses the phone's own speaker and microphone
+    pub fn is_built_in(&self) -> bool {
+        matches!(self, AudioRouteKind::Earpiece | AudioRouteKind::Speaker)
+    }
+}
+
+/// Audio route reported by an [`AudioSession`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioRoute {
+    /// Platform route or port ID
+    pub id: String,
+    /// Human-readable name, such as the headset's name
+    pub name: String,
+    /// Kind of route
+    pub kind: AudioRouteKind,
+}
+
+/// Routes audio is recorded from and played to
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AudioRouteSelection {
+    /// Route the microphone records from
+    pub input: Option<AudioRoute>,
+    /// Route playback goes to
+    pub output: Option<AudioRoute>,
+}
+
+/// Platform audio session, such as `AVAudioSession` on iOS or
+/// `AudioManager` on Android
+///
+/// Apps implement this over their platform bindings and hand it to
+/// [`RoomBuilder::audio_session`](crate::RoomBuilder::audio_session). When
+/// the platform reports a route change, for example a headset connecting,
+/// the app calls [`Room::refresh_audio_route`](crate::Room::refresh_audio_route).
+pub trait AudioSession: Send + Sync + std::fmt::Debug {
+    /// Routes currently available
+    fn available_routes(&self) -> Result<Vec<AudioRoute>, QuicRtcError>;
+
+    /// Record from and play to `selection`
+    fn set_route(&self, selection: &AudioRouteSelection) -> Result<(), QuicRtcError>;
+}
+
+/// How a room picks its audio route
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AudioRoutePolicy {
+    /// Play through the loudspeaker whatever else is connected
+    pub speakerphone: bool,
+    /// Whether the microphone is in use, so Bluetooth headsets are put in
+    /// the hands-free profile to record from them
+    pub microphone_active: bool,
+}
+
+impl AudioRoutePolicy {
+    /// Pick the input and output route from `routes`
+    ///
+    /// Headsets win over the phone's own speaker and earpiece. While the
+    /// microphone is in use a Bluetooth headset is used in the hands-free
+    /// profile, otherwise in the high quality one. Input comes from the
+    /// output route when it has a microphone and from the phone otherwise.
+    pub fn select(&self, routes: &[AudioRoute]) -> AudioRouteSelection {
+        use AudioRouteKind::*;
+
+        let preference: &[AudioRouteKind] = if self.speakerphone {
+            &[Speaker]
+        } else if self.microphone_active {
+            &[
+                BluetoothHfp,
+                WiredHeadset,
+                Usb,
+                BluetoothA2dp,
+                Earpiece,
+                Speaker,
+            ]
+        } else {
+            &[
+                BluetoothA2dp,
+                BluetoothHfp,
+                WiredHeadset,
+                Usb,
+                Speaker,
+                Earpiece,
+            ]
+        };
+        let output = preference
+            .iter()
+            .find_map(|kind| routes.iter().find(|route| route.kind == *kind))
+            .cloned();
+
+        let input = match &output {
+            Some(route) if route.kind.has_microphone() => Some(route.clone()),
+            _ => routes
+                .iter()
+                .find(|route| route.kind.is_built_in())
+                .cloned(),
+        };
+
+        AudioRouteSelection { input, output }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(kind: AudioRouteKind) -> AudioRoute {
+        AudioRoute {
+            id: format!("{:?}", kind),
+            name: format!("{:?}", kind),
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_bluetooth_headset_routing() {
+        let routes = [
+            route(AudioRouteKind::Earpiece),
+            route(AudioRouteKind::Speaker),
+            route(AudioRouteKind::BluetoothA2dp),
+            route(AudioRouteKind::BluetoothHfp),
+        ];
+
+        // In a call the headset's microphone is used over HFP
+        let calling = AudioRoutePolicy {
+            microphone_active: true,
+            ..AudioRoutePolicy::default()
+        };
+        let selection = calling.select(&routes);
+        assert_eq!(selection.input, Some(route(AudioRouteKind::BluetoothHfp)));
+        assert_eq!(selection.output, Some(route(AudioRouteKind::BluetoothHfp)));
+
+        // Listening only keeps the headset on A2DP, recording from the phone
+        let selection = AudioRoutePolicy::default().select(&routes);
+        assert_eq!(selection.output, Some(route(AudioRouteKind::BluetoothA2dp)));
+        assert_eq!(selection.input, Some(route(AudioRouteKind::Earpiece)));
+
+        // Speakerphone overrides the headset
+        let speakerphone = AudioRoutePolicy {
+            speakerphone: true,
+            ..calling
+        };
+        let selection = speakerphone.select(&routes);
+        assert_eq!(selection.output, Some(route(AudioRouteKind::Speaker)));
+        assert_eq!(selection.input, Some(route(AudioRouteKind::Speaker)));
+    }
+}
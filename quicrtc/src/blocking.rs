@@ -0,0 +1,233 @@
+//! Blocking API for applications without an async runtime
+//!
+//! GUI toolkits, game engines and plugin hosts usually own their main loop
+//! and cannot `.await`. The types here wrap [`crate::QuicRtc`] and
+//! [`crate::Room`] with an internal Tokio runtime so every call blocks the
+//! current thread until it completes.
+//!
+//! ```rust,no_run
+//! use quicrtc::blocking::QuicRtc;
+//! use std::time::Duration;
+//!
+//! fn main() -> Result<(), quicrtc::QuicRtcError> {
+//!     let quic_rtc = QuicRtc::init()?;
+//!     let mut room = quic_rtc.join(quic_rtc.room("my-room").participant("alice"))?;
+//!
+//!     loop {
+//!         // Call from the application's frame/tick callback
+//!         for event in room.poll_events() {
+//!             println!("Room event: {}", event.event_type());
+//!         }
+//!         std::thread::sleep(Duration::from_millis(16));
+//!     }
+//! }
+//! ```
+//!
+//! These functions must not be called from inside an async context; use the
+//! async API there instead.
+
+use crate::{
+    Event, EventStream, GlobalConfig, QuicRtcError, RemoteParticipant, RemoteTrackInfo,
+    RoomBuilder, RoomConfig,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use tracing::debug;
+
+/// Blocking handle to QUIC RTC
+///
+/// Owns the runtime that drives every room joined through it. Cloning is
+/// cheap and shares the runtime.
+#[derive(Debug, Clone)]
+pub struct QuicRtc {
+    inner: crate::QuicRtc,
+    runtime: Arc<Runtime>,
+}
+
+impl QuicRtc {
+    /// Initialize QUIC RTC with default settings
+    pub fn init() -> Result<Self, QuicRtcError> {
+        Self::init_with(GlobalConfig::default())
+    }
+
+    /// Initialize with custom global configuration
+    pub fn init_with(config: GlobalConfig) -> Result<Self, QuicRtcError> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .thread_name("quicrtc-blocking")
+            .build()
+            .map_err(|e| QuicRtcError::Initialization {
+                reason: format!("Failed to start blocking runtime: {}", e),
+            })?;
+        debug!("🧵 Started runtime for blocking API");
+
+        let inner = runtime.block_on(crate::QuicRtc::init_with(config))?;
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// Connect to a relay ahead of joining; see [`crate::QuicRtc::prewarm`]
+    pub fn prewarm(&self, relay_url: &str) -> Result<std::net::SocketAddr, QuicRtcError> {
+        self.runtime.block_on(self.inner.prewarm(relay_url))
+    }
+
+    /// Start configuring a room; pass the builder to [`QuicRtc::join`]
+    pub fn room(&self, id: &str) -> RoomBuilder {
+        self.inner.room(id)
+    }
+
+    /// Join a configured room
+    pub fn join(&self, builder: RoomBuilder) -> Result<Room, QuicRtcError> {
+        let room = self.runtime.block_on(builder.join())?;
+        Ok(Room::new(room, Arc::clone(&self.runtime)))
+    }
+
+    /// Create a configured room and join it
+    pub fn create_and_join(&self, builder: RoomBuilder) -> Result<Room, QuicRtcError> {
+        let room = self.runtime.block_on(builder.create_and_join())?;
+        Ok(Room::new(room, Arc::clone(&self.runtime)))
+    }
+
+    /// Get the underlying async handle
+    pub fn as_async(&self) -> &crate::QuicRtc {
+        &self.inner
+    }
+}
+
+/// Blocking handle to a joined room
+#[derive(Debug)]
+pub struct Room {
+    inner: crate::Room,
+    /// Events since join, drained by [`Room::poll_events`]
+    events: EventStream,
+    runtime: Arc<Runtime>,
+}
+
+impl Room {
+    fn new(inner: crate::Room, runtime: Arc<Runtime>) -> Self {
+        let events = inner.events();
+        Self {
+            inner,
+            events,
+            runtime,
+        }
+    }
+
+    /// Get room ID
+    pub fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    /// Get local participant ID
+    pub fn participant_id(&self) -> &str {
+        self.inner.participant_id()
+    }
+
+    /// Get room configuration
+    pub fn config(&self) -> &RoomConfig {
+        self.inner.config()
+    }
+
+    /// Publish camera video
+    #[cfg(feature = "media")]
+    pub fn publish_camera(&mut self) -> Result<crate::VideoTrack, QuicRtcError> {
+        self.runtime.block_on(self.inner.publish_camera())
+    }
+
+    /// Publish microphone audio
+    #[cfg(feature = "media")]
+    pub fn publish_microphone(&mut self) -> Result<crate::AudioTrack, QuicRtcError> {
+        self.runtime.block_on(self.inner.publish_microphone())
+    }
+
+    /// Take every event that has arrived since the last poll without waiting
+    pub fn poll_events(&mut self) -> Vec<Event> {
+        let mut events = Vec::new();
+        while let Ok(Some(event)) = self.events.try_next() {
+            events.push(event);
+        }
+        events
+    }
+
+    /// Wait up to `timeout` for the next event
+    pub fn next_event_timeout(&mut self, timeout: Duration) -> Option<Event> {
+        self.runtime
+            .block_on(tokio::time::timeout(timeout, self.events.next()))
+            .ok()
+            .flatten()
+    }
+
+    /// Get a remote participant by ID
+    pub fn remote_participant(&self, participant_id: &str) -> Option<RemoteParticipant> {
+        self.runtime
+            .block_on(self.inner.remote_participant(participant_id))
+    }
+
+    /// Get all remote participants currently in the room
+    pub fn remote_participants(&self) -> Vec<RemoteParticipant> {
+        self.runtime.block_on(self.inner.remote_participants())
+    }
+
+    /// Describe the tracks a remote participant has announced
+    pub fn remote_tracks(&self, participant_id: &str) -> Option<Vec<RemoteTrackInfo>> {
+        self.runtime
+            .block_on(self.inner.remote_tracks(participant_id))
+    }
+
+    /// Start receiving one of a remote participant's tracks
+    pub fn subscribe_track(
+        &self,
+        participant_id: &str,
+        track_id: &str,
+    ) -> Result<(), QuicRtcError> {
+        self.runtime
+            .block_on(self.inner.subscribe_track(participant_id, track_id))
+    }
+
+    /// Stop receiving one of a remote participant's tracks
+    pub fn unsubscribe_track(
+        &self,
+        participant_id: &str,
+        track_id: &str,
+    ) -> Result<(), QuicRtcError> {
+        self.runtime
+            .block_on(self.inner.unsubscribe_track(participant_id, track_id))
+    }
+
+    /// Leave the room, returning its call report if one was requested
+    pub fn leave(self) -> Result<Option<crate::CallReport>, QuicRtcError> {
+        self.runtime.block_on(self.inner.leave())
+    }
+
+    /// Get the underlying async room
+    ///
+    /// Async methods on it can be driven with [`Room::block_on`].
+    pub fn as_async(&self) -> &crate::Room {
+        &self.inner
+    }
+
+    /// Run a future on this room's runtime and wait for it
+    pub fn block_on<F: std::future::Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocking_init_and_join_validation() {
+        let quic_rtc = QuicRtc::init().unwrap();
+
+        // Builder validation errors surface synchronously
+        let result = quic_rtc.join(quic_rtc.room("test-room"));
+        assert!(matches!(
+            result,
+            Err(QuicRtcError::MissingConfiguration { .. })
+        ));
+    }
+}
@@ -1,12 +1,38 @@
 //! Configuration types and defaults
+//!
+//! Every config struct can be deserialized; fields left out keep their
+//! defaults, and durations are written as strings such as `"10s"` or
+//! `"500ms"`. [`GlobalConfig::from_file`] and [`GlobalConfig::from_env`]
+//! load and validate a configuration without recompiling:
+//!
+//! ```toml
+//! max_rooms = 4
+//! default_signaling_url = "wss://signaling.example.com"
+//!
+//! [resource_limits]
+//! max_bandwidth_kbps = 2000
+//!
+//! [codec_config]
+//! default_audio_bitrate = 32000
+//! ```
 
 #[cfg(feature = "media")]
-use crate::VideoQuality;
-use crate::{ConnectionPoolConfig, ResourceLimits};
+use crate::{AudioContentHint, PlaybackConfig, VideoContentHint, VideoQuality, VideoRotationMode};
+use crate::{
+    ChannelLayout, ConnectionPoolConfig, QuicRtcError, ResourceLimits, StatsHistoryConfig,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+/// Prefix of environment variables read by [`GlobalConfig::from_env`]
+pub const ENV_PREFIX: &str = "QUICRTC_";
+
 /// Global QUIC RTC configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct GlobalConfig {
     /// Enable debug logging
     pub debug_logging: bool,
@@ -29,7 +55,8 @@ pub struct GlobalConfig {
 }
 
 /// Codec system configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct CodecConfig {
     /// Enable Opus audio codec
     pub enable_opus: bool,
@@ -37,17 +64,25 @@ pub struct CodecConfig {
     pub enable_h264: bool,
     /// Default audio sample rate
     pub default_audio_sample_rate: u32,
+    /// Default audio channel layout (mono, stereo, quad or 5.1)
+    pub default_audio_channel_layout: ChannelLayout,
     /// Default audio bitrate (bps)
     pub default_audio_bitrate: u32,
     /// Default video bitrate (bps)
     pub default_video_bitrate: u32,
+    /// Frames between video keyframes; unset derives it from the content hint
+    pub video_keyframe_interval: Option<u32>,
+    /// Longest time between video keyframes
+    #[serde(with = "humantime_serde")]
+    pub video_max_gop_duration: Option<Duration>,
     /// Enable hardware acceleration when available
     pub enable_hardware_acceleration: bool,
 }
 
 /// Media system configuration
 #[cfg(feature = "media")]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct MediaConfig {
     /// Enable automatic device enumeration on startup
     pub enumerate_devices_on_startup: bool,
@@ -63,21 +98,150 @@ pub struct MediaConfig {
 
 /// Audio processing configuration
 #[cfg(feature = "media")]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct AudioProcessingConfig {
     /// Enable echo cancellation
     pub enable_echo_cancellation: bool,
     /// Enable noise suppression
     pub enable_noise_suppression: bool,
+    /// Enable automatic gain control
+    pub enable_auto_gain_control: bool,
+    /// Encoder preset; see [`AudioProfile`]
+    ///
+    /// Only the encoder follows this field. Use
+    /// [`RoomBuilder::audio_profile`](crate::RoomBuilder::audio_profile) to
+    /// set the processing toggles to match.
+    pub profile: Option<AudioProfile>,
     /// Audio buffer size
     pub buffer_size: usize,
     /// Audio render volume (0.0 to 1.0)
     pub default_volume: f32,
+    /// Move playback to the system default output device whenever it
+    /// changes, such as when a headset connects
+    pub follow_default_devices: bool,
+    /// Lower remote and played audio while the local user speaks
+    pub ducking: Option<DuckingConfig>,
+}
+
+/// Audio settings preset for what a room sends
+///
+/// Sets the Opus application mode, sample rate, channels and bitrate of the
+/// published audio together with echo cancellation, noise suppression and
+/// gain control, so they can't end up at odds with each other.
+#[cfg(feature = "media")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AudioProfile {
+    /// Voice calls: 16 kHz mono at 24 kbps with all speech processing
+    Speech,
+    /// Music with talking in between, e.g. lessons: 48 kHz stereo at
+    /// 128 kbps, echo cancellation only
+    Music,
+    /// Audio exactly as captured: 48 kHz stereo at 96 kbps, no processing
+    Raw,
+    /// Music performance: 48 kHz stereo at 256 kbps, no processing, a deeper
+    /// playback buffer, and a content hint so receivers leave it untouched too
+    HighFidelityMusic,
+}
+
+#[cfg(feature = "media")]
+impl AudioProfile {
+    /// Opus encoder settings of the profile
+    pub fn opus_config(&self) -> quicrtc_media::codecs::OpusConfig {
+        let (sample_rate, layout, bitrate, application) = match self {
+            AudioProfile::Speech => (
+                16000,
+                ChannelLayout::Mono,
+                24_000,
+                quicrtc_media::OpusApplication::Voip,
+            ),
+            AudioProfile::Music => (
+                48000,
+                ChannelLayout::Stereo,
+                128_000,
+                quicrtc_media::OpusApplication::Audio,
+            ),
+            AudioProfile::Raw => (
+                48000,
+                ChannelLayout::Stereo,
+                96_000,
+                quicrtc_media::OpusApplication::Audio,
+            ),
+            AudioProfile::HighFidelityMusic => (
+                48000,
+                ChannelLayout::Stereo,
+                256_000,
+                quicrtc_media::OpusApplication::Audio,
+            ),
+        };
+        quicrtc_media::codecs::OpusConfig {
+            sample_rate,
+            channels: layout.channel_count(),
+            bitrate,
+            frame_duration_ms: 20,
+            application,
+        }
+    }
+
+    /// Set the processing toggles of `config` to match the profile
+    pub fn apply(&self, config: &mut AudioProcessingConfig) {
+        let (echo_cancellation, noise_suppression, auto_gain_control) = match self {
+            AudioProfile::Speech => (true, true, true),
+            AudioProfile::Music => (true, false, false),
+            AudioProfile::Raw | AudioProfile::HighFidelityMusic => (false, false, false),
+        };
+        config.enable_echo_cancellation = echo_cancellation;
+        config.enable_noise_suppression = noise_suppression;
+        config.enable_auto_gain_control = auto_gain_control;
+        config.profile = Some(*self);
+    }
+
+    /// Content hint stamped on the audio the profile sends
+    pub fn content_hint(&self) -> AudioContentHint {
+        match self {
+            AudioProfile::HighFidelityMusic => AudioContentHint::Music,
+            _ => AudioContentHint::Speech,
+        }
+    }
+
+    /// Playback buffering for audio while the profile is in use, if it
+    /// needs more than the room's default
+    pub fn playback_config(&self) -> Option<PlaybackConfig> {
+        match self {
+            AudioProfile::HighFidelityMusic => Some(PlaybackConfig::music()),
+            _ => None,
+        }
+    }
+}
+
+/// Ducking of remote audio while the local user is speaking
+///
+/// Participants and played files are attenuated whenever voice activity is
+/// detected in local capture, so the local user isn't talked over, e.g. when
+/// listening to a live translation or a voice assistant.
+#[cfg(feature = "media")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DuckingConfig {
+    /// Attenuation in dB while the local user speaks
+    pub attenuation_db: f32,
+    /// Time to fade down once speech starts
+    #[serde(with = "humantime_serde")]
+    pub attack: Duration,
+    /// Time to fade back up once speech ends
+    #[serde(with = "humantime_serde")]
+    pub release: Duration,
+    /// Local capture level in dBFS counted as speech
+    pub speech_threshold_dbfs: f32,
+    /// Pause in speech before ducking ends
+    #[serde(with = "humantime_serde")]
+    pub hangover: Duration,
 }
 
 /// Video processing configuration
 #[cfg(feature = "media")]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct VideoProcessingConfig {
     /// Enable automatic exposure adjustment
     pub enable_auto_exposure: bool,
@@ -87,15 +251,42 @@ pub struct VideoProcessingConfig {
     pub default_framerate: f64,
     /// Enable video preprocessing
     pub enable_preprocessing: bool,
+    /// Apply capture rotation before encoding or at the remote renderer
+    pub rotation_mode: VideoRotationMode,
+    /// What outgoing video mostly shows, tuning encoding and adaptation
+    pub content_hint: VideoContentHint,
+    /// Publish a low-rate thumbnail track next to each video track the room sends
+    pub thumbnails: Option<ThumbnailConfig>,
+}
+
+/// Thumbnail rendition published next to outgoing video
+///
+/// Thumbnails are taken from the frames the room sends on a video track and
+/// go out on `<track>-thumbnail` in the publisher's namespace, so large-room
+/// UIs can show previews without subscribing to full video.
+#[cfg(feature = "media")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThumbnailConfig {
+    /// Thumbnail height in pixels; width follows the video's aspect ratio
+    pub height: u32,
+    /// Time between thumbnails
+    #[serde(with = "humantime_serde")]
+    pub interval: Duration,
+    /// Target bitrate of each encoded thumbnail in bits per second
+    pub bitrate: u32,
 }
 
 /// Signaling system configuration
 #[cfg(feature = "signaling")]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct SignalingConfig {
     /// Connection timeout for signaling server
+    #[serde(with = "humantime_serde")]
     pub connection_timeout: Duration,
     /// Heartbeat interval
+    #[serde(with = "humantime_serde")]
     pub heartbeat_interval: Duration,
     /// Reconnection attempt configuration
     pub reconnect_config: ReconnectConfig,
@@ -105,13 +296,16 @@ pub struct SignalingConfig {
 
 /// Reconnection configuration
 #[cfg(feature = "signaling")]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct ReconnectConfig {
     /// Enable automatic reconnection
     pub enabled: bool,
     /// Initial retry delay
+    #[serde(with = "humantime_serde")]
     pub initial_delay: Duration,
     /// Maximum retry delay
+    #[serde(with = "humantime_serde")]
     pub max_delay: Duration,
     /// Exponential backoff multiplier
     pub backoff_multiplier: f64,
@@ -142,8 +336,11 @@ impl Default for CodecConfig {
             enable_opus: true,
             enable_h264: true,
             default_audio_sample_rate: 48000,
+            default_audio_channel_layout: ChannelLayout::Stereo,
             default_audio_bitrate: 64000,
             default_video_bitrate: 1_000_000,
+            video_keyframe_interval: None,
+            video_max_gop_duration: None,
             enable_hardware_acceleration: true,
         }
     }
@@ -168,12 +365,41 @@ impl Default for AudioProcessingConfig {
         Self {
             enable_echo_cancellation: true,
             enable_noise_suppression: true,
+            enable_auto_gain_control: true,
+            profile: None,
             buffer_size: 960, // 20ms at 48kHz
             default_volume: 0.8,
+            follow_default_devices: false,
+            ducking: None,
         }
     }
 }
 
+#[cfg(feature = "media")]
+impl Default for DuckingConfig {
+    fn default() -> Self {
+        Self {
+            attenuation_db: 12.0,
+            attack: Duration::from_millis(50),
+            release: Duration::from_millis(400),
+            speech_threshold_dbfs: -40.0,
+            hangover: Duration::from_millis(300),
+        }
+    }
+}
+
+#[cfg(feature = "media")]
+impl DuckingConfig {
+    /// Ducker for an [`quicrtc_media::AudioMixer`] with these settings
+    pub(crate) fn ducker(&self) -> quicrtc_media::AudioDucker {
+        quicrtc_media::AudioDucker::new(self.attenuation_db, self.attack, self.release)
+            .with_detector(quicrtc_media::VoiceActivityDetector::new(
+                self.speech_threshold_dbfs,
+                self.hangover,
+            ))
+    }
+}
+
 #[cfg(feature = "media")]
 impl Default for VideoProcessingConfig {
     fn default() -> Self {
@@ -182,6 +408,20 @@ impl Default for VideoProcessingConfig {
             enable_auto_white_balance: true,
             default_framerate: 30.0,
             enable_preprocessing: true,
+            rotation_mode: VideoRotationMode::Renderer,
+            content_hint: VideoContentHint::Motion,
+            thumbnails: None,
+        }
+    }
+}
+
+#[cfg(feature = "media")]
+impl Default for ThumbnailConfig {
+    fn default() -> Self {
+        Self {
+            height: quicrtc_media::thumbnail::DEFAULT_THUMBNAIL_HEIGHT,
+            interval: quicrtc_media::thumbnail::DEFAULT_THUMBNAIL_INTERVAL,
+            bitrate: 50_000,
         }
     }
 }
@@ -211,8 +451,30 @@ impl Default for ReconnectConfig {
     }
 }
 
+#[cfg(feature = "signaling")]
+impl ReconnectConfig {
+    /// How long to wait before retry number `attempt`, counting from 1, or
+    /// `None` when retrying is disabled or the attempts are used up
+    ///
+    /// The delay grows by `backoff_multiplier` per attempt up to
+    /// `max_delay`, and a random part of up to half of it is taken off so
+    /// clients that failed together don't all retry at the same moment.
+    pub fn retry_delay(&self, attempt: u32) -> Option<Duration> {
+        if !self.enabled || attempt == 0 || attempt > self.max_attempts {
+            return None;
+        }
+        let backoff =
+            self.initial_delay.as_secs_f64() * self.backoff_multiplier.powi(attempt as i32 - 1);
+        let backoff = backoff.min(self.max_delay.as_secs_f64());
+        Some(Duration::from_secs_f64(
+            backoff * (1.0 - rand::random::<f64>() / 2.0),
+        ))
+    }
+}
+
 /// Room-specific configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct RoomConfig {
     /// Enable video
     pub video_enabled: bool,
@@ -225,6 +487,28 @@ pub struct RoomConfig {
     pub signaling_url: Option<String>,
     /// Enable mobile optimizations
     pub mobile_optimizations: bool,
+    /// How often track statistics are refreshed
+    #[serde(with = "humantime_serde")]
+    pub stats_interval: Duration,
+    /// Keep a history of statistics, see [`crate::Room::stats_history`]
+    pub stats_history: Option<StatsHistoryConfig>,
+    /// Produce a [`crate::CallReport`] when leaving the room
+    pub call_report: bool,
+    /// Also write the call report as JSON to this path; implies `call_report`
+    pub call_report_path: Option<PathBuf>,
+    /// Keep this much of the room's debug logs in memory (`diagnostics` feature)
+    #[serde(with = "humantime_serde")]
+    pub debug_log_window: Option<Duration>,
+    /// Record packet metadata of the media connection into this file
+    pub packet_capture_path: Option<PathBuf>,
+    /// Use less data, see [`crate::RoomBuilder::data_saver`]
+    pub data_saver: bool,
+    /// Give up joining after this long, retries included
+    #[serde(with = "humantime_serde")]
+    pub join_timeout: Option<Duration>,
+    /// Labels announced with published tracks, keyed by source name
+    /// (e.g. "camera"), see [`crate::RoomBuilder::track_label`]
+    pub track_labels: BTreeMap<String, String>,
 }
 
 impl Default for RoomConfig {
@@ -236,6 +520,554 @@ impl Default for RoomConfig {
             video_quality: VideoQuality::Standard,
             signaling_url: None,
             mobile_optimizations: false,
+            stats_interval: Duration::from_secs(1),
+            stats_history: None,
+            call_report: false,
+            call_report_path: None,
+            debug_log_window: None,
+            packet_capture_path: None,
+            data_saver: false,
+            join_timeout: None,
+            track_labels: BTreeMap::new(),
+        }
+    }
+}
+
+impl GlobalConfig {
+    /// Load and validate a configuration file
+    ///
+    /// The format is chosen from the extension: `.toml` or `.json`.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let config: Self = load_file(path.as_ref())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Load and validate a configuration from `QUICRTC_*` environment variables
+    ///
+    /// See [`GlobalConfig::from_env_vars`] for how variables map to fields.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_env_vars(std::env::vars())
+    }
+
+    /// Load and validate a configuration from environment-style variables
+    ///
+    /// Only variables starting with [`ENV_PREFIX`] are used. The rest of the
+    /// name is the field path in lowercase, with `__` between nested fields:
+    /// `QUICRTC_MAX_ROOMS=4` or `QUICRTC_CODEC_CONFIG__ENABLE_H264=false`.
+    /// Values are parsed as JSON scalars when possible and taken as strings
+    /// otherwise; `null` clears an optional field.
+    pub fn from_env_vars<I, K, V>(vars: I) -> Result<Self, ConfigError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut root = serde_json::Map::new();
+        for (key, value) in vars {
+            let Some(name) = key.as_ref().strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+            let path: Vec<String> = name.split("__").map(str::to_lowercase).collect();
+            insert_env_value(&mut root, &path, parse_env_value(value.as_ref()))?;
+        }
+
+        let config: Self = deserialize(serde_json::Value::Object(root))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check that every field holds a usable value
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.max_rooms > 0, "max_rooms", "must be at least 1")?;
+        if let Some(url) = &self.default_signaling_url {
+            validate_signaling_url("default_signaling_url", url)?;
+        }
+
+        let limits = &self.resource_limits;
+        ensure(
+            limits.warning_threshold > 0.0 && limits.warning_threshold <= 1.0,
+            "resource_limits.warning_threshold",
+            "must be greater than 0.0 and at most 1.0",
+        )?;
+
+        let pool = &self.connection_pool;
+        ensure(
+            pool.max_idle_connections <= pool.max_total_connections,
+            "connection_pool.max_idle_connections",
+            "cannot exceed connection_pool.max_total_connections",
+        )?;
+
+        let codecs = &self.codec_config;
+        ensure(
+            [8000, 12000, 16000, 24000, 48000].contains(&codecs.default_audio_sample_rate),
+            "codec_config.default_audio_sample_rate",
+            "must be one of 8000, 12000, 16000, 24000 or 48000",
+        )?;
+        ensure(
+            codecs.default_audio_bitrate > 0,
+            "codec_config.default_audio_bitrate",
+            "must be greater than 0",
+        )?;
+        ensure(
+            codecs.default_video_bitrate > 0,
+            "codec_config.default_video_bitrate",
+            "must be greater than 0",
+        )?;
+        ensure(
+            codecs.video_keyframe_interval != Some(0),
+            "codec_config.video_keyframe_interval",
+            "must be greater than 0",
+        )?;
+        ensure(
+            codecs.video_max_gop_duration != Some(Duration::ZERO),
+            "codec_config.video_max_gop_duration",
+            "must be greater than 0",
+        )?;
+
+        #[cfg(feature = "media")]
+        {
+            let media = &self.media_config;
+            ensure(
+                media.max_video_resolution.0 > 0 && media.max_video_resolution.1 > 0,
+                "media_config.max_video_resolution",
+                "width and height must be greater than 0",
+            )?;
+            ensure(
+                (0.0..=1.0).contains(&media.audio_processing.default_volume),
+                "media_config.audio_processing.default_volume",
+                "must be between 0.0 and 1.0",
+            )?;
+            ensure(
+                media.audio_processing.buffer_size > 0,
+                "media_config.audio_processing.buffer_size",
+                "must be greater than 0",
+            )?;
+            if let Some(ducking) = &media.audio_processing.ducking {
+                ensure(
+                    ducking.attenuation_db >= 0.0,
+                    "media_config.audio_processing.ducking.attenuation_db",
+                    "must not be negative",
+                )?;
+                ensure(
+                    ducking.speech_threshold_dbfs <= 0.0,
+                    "media_config.audio_processing.ducking.speech_threshold_dbfs",
+                    "must be at most 0.0",
+                )?;
+            }
+            ensure(
+                media.video_processing.default_framerate > 0.0,
+                "media_config.video_processing.default_framerate",
+                "must be greater than 0",
+            )?;
+            if let Some(thumbnails) = &media.video_processing.thumbnails {
+                ensure(
+                    thumbnails.height >= 2,
+                    "media_config.video_processing.thumbnails.height",
+                    "must be at least 2",
+                )?;
+                ensure(
+                    !thumbnails.interval.is_zero(),
+                    "media_config.video_processing.thumbnails.interval",
+                    "must be greater than 0",
+                )?;
+            }
+        }
+
+        #[cfg(feature = "signaling")]
+        {
+            let signaling = &self.signaling_config;
+            ensure(
+                !signaling.connection_timeout.is_zero(),
+                "signaling_config.connection_timeout",
+                "must be greater than 0",
+            )?;
+            ensure(
+                !signaling.heartbeat_interval.is_zero(),
+                "signaling_config.heartbeat_interval",
+                "must be greater than 0",
+            )?;
+            let reconnect = &signaling.reconnect_config;
+            ensure(
+                reconnect.backoff_multiplier >= 1.0,
+                "signaling_config.reconnect_config.backoff_multiplier",
+                "must be at least 1.0",
+            )?;
+            ensure(
+                reconnect.max_delay >= reconnect.initial_delay,
+                "signaling_config.reconnect_config.max_delay",
+                "cannot be shorter than initial_delay",
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl RoomConfig {
+    /// Load and validate a room configuration file (`.toml` or `.json`)
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let config: Self = load_file(path.as_ref())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check that every field holds a usable value
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if let Some(url) = &self.signaling_url {
+            validate_signaling_url("signaling_url", url)?;
+        }
+        ensure(
+            !self.stats_interval.is_zero(),
+            "stats_interval",
+            "must be greater than 0",
+        )?;
+        if let Some(history) = &self.stats_history {
+            ensure(
+                !history.sample_interval.is_zero(),
+                "stats_history.sample_interval",
+                "must be greater than 0",
+            )?;
+            ensure(
+                history.retention >= history.sample_interval,
+                "stats_history.retention",
+                "must be at least the sample interval",
+            )?;
+        }
+        ensure(
+            self.join_timeout != Some(Duration::ZERO),
+            "join_timeout",
+            "must be greater than 0",
+        )?;
+        Ok(())
+    }
+}
+
+/// Errors from loading or validating configuration
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    /// The config file could not be read
+    #[error("Failed to read config file {}: {source}", path.display())]
+    Io {
+        /// Path of the config file
+        path: PathBuf,
+        /// Underlying I/O error
+        source: std::io::Error,
+    },
+
+    /// The config file extension is not a supported format
+    #[error("Unsupported config format for {} (expected .toml or .json)", path.display())]
+    UnsupportedFormat {
+        /// Path of the config file
+        path: PathBuf,
+    },
+
+    /// The configuration could not be parsed
+    #[error("Invalid configuration at `{field}`: {reason}")]
+    Parse {
+        /// Dotted path of the offending field (`.` for the document root)
+        field: String,
+        /// Parser error message
+        reason: String,
+    },
+
+    /// A field holds a value outside its allowed range
+    #[error("Invalid value for `{field}`: {reason}")]
+    InvalidValue {
+        /// Dotted path of the offending field
+        field: String,
+        /// Why the value was rejected
+        reason: String,
+    },
+}
+
+impl ConfigError {
+    /// Dotted path of the offending field, if the error concerns one
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            ConfigError::Parse { field, .. } | ConfigError::InvalidValue { field, .. } => {
+                Some(field)
+            }
+            ConfigError::Io { .. } | ConfigError::UnsupportedFormat { .. } => None,
+        }
+    }
+}
+
+impl From<ConfigError> for QuicRtcError {
+    fn from(error: ConfigError) -> Self {
+        QuicRtcError::InvalidData {
+            reason: error.to_string(),
         }
     }
 }
+
+fn ensure(condition: bool, field: &str, reason: &str) -> Result<(), ConfigError> {
+    if condition {
+        Ok(())
+    } else {
+        Err(ConfigError::InvalidValue {
+            field: field.to_string(),
+            reason: reason.to_string(),
+        })
+    }
+}
+
+fn validate_signaling_url(field: &str, url: &str) -> Result<(), ConfigError> {
+    ensure(
+        url.starts_with("ws://") || url.starts_with("wss://"),
+        field,
+        "must be a ws:// or wss:// URL",
+    )
+}
+
+/// Read and parse a config file, recording the path of any offending field
+fn load_file<T: DeserializeOwned>(path: &Path) -> Result<T, ConfigError> {
+    let text = std::fs::read_to_string(path).map_err(|source| ConfigError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => deserialize(toml::Deserializer::new(&text)),
+        Some("json") => deserialize(&mut serde_json::Deserializer::from_str(&text)),
+        _ => Err(ConfigError::UnsupportedFormat {
+            path: path.to_path_buf(),
+        }),
+    }
+}
+
+fn deserialize<'de, T, D>(deserializer: D) -> Result<T, ConfigError>
+where
+    T: Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+    D::Error: std::fmt::Display,
+{
+    serde_path_to_error::deserialize(deserializer).map_err(|error| ConfigError::Parse {
+        field: error.path().to_string(),
+        reason: error.inner().to_string(),
+    })
+}
+
+fn parse_env_value(value: &str) -> serde_json::Value {
+    match serde_json::from_str(value) {
+        Ok(
+            parsed @ (serde_json::Value::Bool(_)
+            | serde_json::Value::Number(_)
+            | serde_json::Value::Null),
+        ) => parsed,
+        _ => serde_json::Value::String(value.to_string()),
+    }
+}
+
+fn insert_env_value(
+    object: &mut serde_json::Map<String, serde_json::Value>,
+    path: &[String],
+    value: serde_json::Value,
+) -> Result<(), ConfigError> {
+    let (key, rest) = path.split_first().expect("split always yields a segment");
+    if rest.is_empty() {
+        object.insert(key.clone(), value);
+        return Ok(());
+    }
+
+    let child = object
+        .entry(key.clone())
+        .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+    match child {
+        serde_json::Value::Object(child) => insert_env_value(child, rest, value),
+        _ => Err(ConfigError::Parse {
+            field: key.clone(),
+            reason: "set both as a value and as a section".to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_temp(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("quicrtc-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_global_config_from_toml_and_json() {
+        let path = write_temp(
+            "config.toml",
+            r#"
+                max_rooms = 4
+                default_signaling_url = "wss://signaling.example.com"
+
+                [resource_limits]
+                max_bandwidth_kbps = 2000
+                cleanup_timeout = "2s"
+
+                [codec_config]
+                default_audio_channel_layout = "Mono"
+            "#,
+        );
+        let config = GlobalConfig::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(config.max_rooms, 4);
+        assert_eq!(config.resource_limits.max_bandwidth_kbps, Some(2000));
+        assert_eq!(
+            config.resource_limits.cleanup_timeout,
+            Duration::from_secs(2)
+        );
+        // Unset fields keep their defaults
+        assert_eq!(config.resource_limits.max_connections, Some(20));
+        assert_eq!(
+            config.codec_config.default_audio_channel_layout,
+            ChannelLayout::Mono
+        );
+        assert!(config.codec_config.enable_opus);
+
+        let path = write_temp("config.json", r#"{"debug_logging": true}"#);
+        let config = GlobalConfig::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(config.debug_logging);
+    }
+
+    #[test]
+    fn test_config_errors_name_the_field() {
+        let path = write_temp(
+            "bad-type.toml",
+            "[codec_config]\ndefault_audio_bitrate = \"high\"\n",
+        );
+        let error = GlobalConfig::from_file(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(error.field(), Some("codec_config.default_audio_bitrate"));
+
+        let path = write_temp(
+            "bad-value.json",
+            r#"{"resource_limits": {"warning_threshold": 1.5}}"#,
+        );
+        let error = GlobalConfig::from_file(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(error, ConfigError::InvalidValue { .. }));
+        assert_eq!(error.field(), Some("resource_limits.warning_threshold"));
+
+        // Typos are rejected instead of silently ignored
+        let error = GlobalConfig::from_env_vars([("QUICRTC_MAX_ROMS", "3")]).unwrap_err();
+        assert!(error.to_string().contains("max_roms"));
+
+        let path = write_temp("config.yaml", "max_rooms: 3\n");
+        let error = GlobalConfig::from_file(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(error, ConfigError::UnsupportedFormat { .. }));
+    }
+
+    #[cfg(feature = "media")]
+    #[test]
+    fn test_thumbnail_config() {
+        let path = write_temp(
+            "thumbnails.toml",
+            r#"
+                [media_config.video_processing.thumbnails]
+                interval = "2s"
+            "#,
+        );
+        let config = GlobalConfig::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let thumbnails = config.media_config.video_processing.thumbnails.unwrap();
+        assert_eq!(thumbnails.interval, Duration::from_secs(2));
+        assert_eq!(thumbnails.height, ThumbnailConfig::default().height);
+
+        let path = write_temp(
+            "bad-thumbnails.toml",
+            "[media_config.video_processing.thumbnails]\ninterval = \"0s\"\n",
+        );
+        let error = GlobalConfig::from_file(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            error.field(),
+            Some("media_config.video_processing.thumbnails.interval")
+        );
+    }
+
+    #[cfg(feature = "media")]
+    #[test]
+    fn test_ducking_config() {
+        let path = write_temp(
+            "ducking.toml",
+            r#"
+                [media_config.audio_processing.ducking]
+                attenuation_db = 18.0
+                release = "1s"
+            "#,
+        );
+        let config = GlobalConfig::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let ducking = config.media_config.audio_processing.ducking.unwrap();
+        assert_eq!(ducking.attenuation_db, 18.0);
+        assert_eq!(ducking.release, Duration::from_secs(1));
+        assert_eq!(ducking.attack, DuckingConfig::default().attack);
+
+        let path = write_temp(
+            "bad-ducking.toml",
+            "[media_config.audio_processing.ducking]\nspeech_threshold_dbfs = 6.0\n",
+        );
+        let error = GlobalConfig::from_file(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            error.field(),
+            Some("media_config.audio_processing.ducking.speech_threshold_dbfs")
+        );
+    }
+
+    #[test]
+    fn test_global_config_from_env_vars() {
+        let config = GlobalConfig::from_env_vars([
+            ("QUICRTC_MAX_ROOMS", "2"),
+            ("QUICRTC_DEFAULT_SIGNALING_URL", "ws://localhost:8080"),
+            ("QUICRTC_RESOURCE_LIMITS__MAX_MEMORY_MB", "null"),
+            ("QUICRTC_CONNECTION_POOL__IDLE_TIMEOUT", "90s"),
+            ("QUICRTC_CODEC_CONFIG__ENABLE_H264", "false"),
+            ("PATH", "/usr/bin"),
+        ])
+        .unwrap();
+
+        assert_eq!(config.max_rooms, 2);
+        assert_eq!(
+            config.default_signaling_url.as_deref(),
+            Some("ws://localhost:8080")
+        );
+        assert_eq!(config.resource_limits.max_memory_mb, None);
+        assert_eq!(config.connection_pool.idle_timeout, Duration::from_secs(90));
+        assert!(!config.codec_config.enable_h264);
+
+        let error = GlobalConfig::from_env_vars([("QUICRTC_MAX_ROOMS", "0")]).unwrap_err();
+        assert_eq!(error.field(), Some("max_rooms"));
+    }
+
+    #[test]
+    fn test_room_config_validation() {
+        let config = RoomConfig {
+            signaling_url: Some("http://signaling.example.com".to_string()),
+            ..RoomConfig::default()
+        };
+        assert_eq!(
+            config.validate().unwrap_err().field(),
+            Some("signaling_url")
+        );
+        assert!(RoomConfig::default().validate().is_ok());
+
+        let config = RoomConfig {
+            stats_interval: Duration::ZERO,
+            ..RoomConfig::default()
+        };
+        assert_eq!(
+            config.validate().unwrap_err().field(),
+            Some("stats_interval")
+        );
+
+        let config = RoomConfig {
+            join_timeout: Some(Duration::ZERO),
+            ..RoomConfig::default()
+        };
+        assert_eq!(config.validate().unwrap_err().field(), Some("join_timeout"));
+    }
+}
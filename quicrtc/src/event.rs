@@ -1,9 +1,14 @@
 //! Event system for room and participant events
 
+use crate::audio_route::AudioRoute;
+use crate::participant::{ConnectionQuality, ParticipantRole};
+use crate::quality::QualityLevel;
+use crate::report::CallRecorder;
 use crate::{LocalTrack, RemoteParticipant, RemoteTrack};
-use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
-use tracing::debug;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, Notify};
+use tracing::{debug, warn};
 
 /// Room events that can occur during a session
 #[derive(Debug, Clone)]
@@ -18,6 +23,12 @@ pub enum Event {
         /// The participant that left
         participant: RemoteParticipant,
     },
+    /// A participant stopped sending heartbeats and the signaling server
+    /// removed them from the room
+    ParticipantTimedOut {
+        /// Participant ID
+        participant_id: String,
+    },
     /// A participant's connection state changed
     ParticipantConnectionChanged {
         /// Participant ID
@@ -64,6 +75,128 @@ pub enum Event {
         /// Whether the track is now muted
         muted: bool,
     },
+    /// We subscribed to a remote track's media
+    TrackSubscribed {
+        /// Track ID
+        track_id: String,
+        /// Participant ID that owns the track
+        participant_id: String,
+    },
+    /// We unsubscribed from a remote track's media
+    TrackUnsubscribed {
+        /// Track ID
+        track_id: String,
+        /// Participant ID that owns the track
+        participant_id: String,
+    },
+    /// The simulcast layers a remote participant offers for a track changed
+    TrackSimulcastLayersChanged {
+        /// Track ID
+        track_id: String,
+        /// Participant ID that owns the track
+        participant_id: String,
+        /// Layers now available
+        layers: Vec<crate::track::SimulcastLayer>,
+    },
+    /// A remote participant announced a track's label, source or format
+    TrackMetadataChanged {
+        /// Track ID
+        track_id: String,
+        /// Participant ID that owns the track
+        participant_id: String,
+        /// What produces the track's media
+        source: crate::track::TrackSource,
+        /// Label, dimensions and codec now announced
+        metadata: crate::track::TrackMetadata,
+    },
+    /// A remote participant's connection quality rating changed
+    ParticipantQualityChanged {
+        /// Participant ID
+        participant_id: String,
+        /// New quality rating
+        quality: ConnectionQuality,
+    },
+    /// Sending quality of a local track was reduced or restored
+    LocalTrackQualityLimited {
+        /// Track ID
+        track_id: String,
+        /// What is limiting the track, or `None` once it is restored
+        reason: QualityLimitationReason,
+    },
+    /// A media device became available
+    DeviceAdded {
+        /// Device kind
+        kind: DeviceKind,
+        /// Platform device ID
+        device_id: String,
+        /// Human-readable device name
+        name: String,
+    },
+    /// A media device was unplugged or became unavailable
+    DeviceRemoved {
+        /// Device kind
+        kind: DeviceKind,
+        /// Platform device ID
+        device_id: String,
+    },
+    /// The system default device of a kind changed
+    DefaultDeviceChanged {
+        /// Device kind
+        kind: DeviceKind,
+        /// Platform ID of the new default device
+        device_id: String,
+    },
+    /// The room switched the routes audio is recorded from and played to,
+    /// see [`Room::refresh_audio_route`](crate::Room::refresh_audio_route)
+    AudioRouteChanged {
+        /// Route the microphone records from
+        input: Option<AudioRoute>,
+        /// Route playback goes to
+        output: Option<AudioRoute>,
+    },
+    /// A moderator muted a participant's track
+    TrackMutedByModerator {
+        /// Participant ID that owns the track
+        participant_id: String,
+        /// Track ID
+        track_id: String,
+        /// Participant ID of the moderator
+        moderator_id: String,
+    },
+    /// A participant's role in the room changed
+    ParticipantRoleChanged {
+        /// Participant ID
+        participant_id: String,
+        /// New role
+        role: ParticipantRole,
+    },
+    /// A participant was removed from the room by a moderator
+    ParticipantRemoved {
+        /// Participant ID, which is our own ID if we were removed
+        participant_id: String,
+        /// Reason given by the moderator
+        reason: String,
+    },
+    /// The room is about to start recording or transcribing
+    ///
+    /// Answer with [`Room::respond_to_capture`](crate::Room::respond_to_capture)
+    /// when `requires_acknowledgement` is set; capture only starts once every
+    /// participant accepts.
+    CaptureConsentRequested {
+        /// ID of the request to answer
+        request_id: String,
+        /// What the room is about to capture
+        kind: CaptureKind,
+        /// Whether capture waits for our answer
+        requires_acknowledgement: bool,
+    },
+    /// The room started or stopped recording or transcribing
+    CaptureStateChanged {
+        /// What the room is capturing
+        kind: CaptureKind,
+        /// Whether capture is now in progress
+        active: bool,
+    },
     /// Room connection state changed
     RoomConnectionChanged {
         /// New connection state
@@ -76,6 +209,21 @@ pub enum Event {
         /// Detailed quality metrics
         metrics: NetworkQualityMetrics,
     },
+    /// Quality level of the connection or one of its tracks changed
+    ///
+    /// Levels change with hysteresis, see [`crate::quality`].
+    ConnectionQualityChanged {
+        /// Track the level is for; `None` for the connection as a whole
+        track_id: Option<String>,
+        /// New level
+        level: QualityLevel,
+    },
+    /// Video was paused or restored with
+    /// [`Room::set_audio_only`](crate::Room::set_audio_only)
+    AudioOnlyChanged {
+        /// Whether the room is now audio only
+        enabled: bool,
+    },
     /// An error occurred in the room
     RoomError {
         /// Error that occurred
@@ -83,6 +231,43 @@ pub enum Event {
         /// Whether this error is recoverable
         recoverable: bool,
     },
+    /// We are in the room's lobby until its host joins
+    ///
+    /// Raised while joining; take a stream from
+    /// [`RoomBuilder::events`](crate::RoomBuilder::events) to receive it.
+    WaitingForHost {
+        /// Participant ID of the host, if the room has one
+        host_id: Option<String>,
+        /// When the meeting is due to start
+        starts_at: Option<chrono::DateTime<chrono::Utc>>,
+    },
+    /// Joining the room got one step further
+    ///
+    /// Raised while joining; take a stream from
+    /// [`RoomBuilder::events`](crate::RoomBuilder::events) to receive it.
+    JoinProgress {
+        /// Step just completed
+        stage: JoinStage,
+    },
+    /// An attempt to join the room failed and will be retried
+    ///
+    /// Raised while joining; take a stream from
+    /// [`RoomBuilder::events`](crate::RoomBuilder::events) to receive it.
+    JoinAttemptFailed {
+        /// Failed attempt, counting from 1
+        attempt: u32,
+        /// Why the attempt failed
+        error: String,
+        /// Wait before the next attempt
+        retry_in: std::time::Duration,
+    },
+    /// The signaling server will close the room soon
+    RoomClosing {
+        /// Time left before the room closes
+        closes_in: std::time::Duration,
+        /// Why the room is closing
+        reason: String,
+    },
     /// Room was disconnected
     RoomDisconnected {
         /// Reason for disconnection
@@ -103,6 +288,7 @@ impl Event {
         match self {
             Event::ParticipantJoined { .. } => "participant_joined",
             Event::ParticipantLeft { .. } => "participant_left",
+            Event::ParticipantTimedOut { .. } => "participant_timed_out",
             Event::ParticipantConnectionChanged { .. } => "participant_connection_changed",
             Event::ParticipantStartedSpeaking { .. } => "participant_started_speaking",
             Event::ParticipantStoppedSpeaking { .. } => "participant_stopped_speaking",
@@ -111,9 +297,30 @@ impl Event {
             Event::LocalTrackPublished { .. } => "local_track_published",
             Event::LocalTrackUnpublished { .. } => "local_track_unpublished",
             Event::TrackMuteChanged { .. } => "track_mute_changed",
+            Event::TrackSubscribed { .. } => "track_subscribed",
+            Event::TrackUnsubscribed { .. } => "track_unsubscribed",
+            Event::TrackSimulcastLayersChanged { .. } => "track_simulcast_layers_changed",
+            Event::TrackMetadataChanged { .. } => "track_metadata_changed",
+            Event::ParticipantQualityChanged { .. } => "participant_quality_changed",
+            Event::LocalTrackQualityLimited { .. } => "local_track_quality_limited",
+            Event::DeviceAdded { .. } => "device_added",
+            Event::DeviceRemoved { .. } => "device_removed",
+            Event::DefaultDeviceChanged { .. } => "default_device_changed",
+            Event::AudioRouteChanged { .. } => "audio_route_changed",
+            Event::TrackMutedByModerator { .. } => "track_muted_by_moderator",
+            Event::ParticipantRoleChanged { .. } => "participant_role_changed",
+            Event::ParticipantRemoved { .. } => "participant_removed",
+            Event::CaptureConsentRequested { .. } => "capture_consent_requested",
+            Event::CaptureStateChanged { .. } => "capture_state_changed",
             Event::RoomConnectionChanged { .. } => "room_connection_changed",
             Event::NetworkQualityChanged { .. } => "network_quality_changed",
+            Event::ConnectionQualityChanged { .. } => "connection_quality_changed",
+            Event::AudioOnlyChanged { .. } => "audio_only_changed",
             Event::RoomError { .. } => "room_error",
+            Event::WaitingForHost { .. } => "waiting_for_host",
+            Event::JoinProgress { .. } => "join_progress",
+            Event::JoinAttemptFailed { .. } => "join_attempt_failed",
+            Event::RoomClosing { .. } => "room_closing",
             Event::RoomDisconnected { .. } => "room_disconnected",
             Event::RoomReconnecting { .. } => "room_reconnecting",
             Event::RoomReconnected => "room_reconnected",
@@ -126,6 +333,7 @@ impl Event {
             self,
             Event::ParticipantJoined { .. }
                 | Event::ParticipantLeft { .. }
+                | Event::ParticipantTimedOut { .. }
                 | Event::ParticipantConnectionChanged { .. }
                 | Event::ParticipantStartedSpeaking { .. }
                 | Event::ParticipantStoppedSpeaking { .. }
@@ -141,6 +349,10 @@ impl Event {
                 | Event::LocalTrackPublished { .. }
                 | Event::LocalTrackUnpublished { .. }
                 | Event::TrackMuteChanged { .. }
+                | Event::TrackSubscribed { .. }
+                | Event::TrackUnsubscribed { .. }
+                | Event::TrackSimulcastLayersChanged { .. }
+                | Event::TrackMetadataChanged { .. }
         )
     }
 
@@ -150,6 +362,11 @@ impl Event {
             self,
             Event::RoomConnectionChanged { .. }
                 | Event::NetworkQualityChanged { .. }
+                | Event::ConnectionQualityChanged { .. }
+                | Event::WaitingForHost { .. }
+                | Event::JoinProgress { .. }
+                | Event::JoinAttemptFailed { .. }
+                | Event::RoomClosing { .. }
                 | Event::RoomDisconnected { .. }
                 | Event::RoomReconnecting { .. }
                 | Event::RoomReconnected
@@ -160,6 +377,132 @@ impl Event {
     pub fn is_error_event(&self) -> bool {
         matches!(self, Event::RoomError { .. })
     }
+
+    /// Check if this is a media quality event
+    pub fn is_quality_event(&self) -> bool {
+        matches!(
+            self,
+            Event::NetworkQualityChanged { .. }
+                | Event::ConnectionQualityChanged { .. }
+                | Event::ParticipantQualityChanged { .. }
+                | Event::LocalTrackQualityLimited { .. }
+                | Event::AudioOnlyChanged { .. }
+        )
+    }
+
+    /// Check if this is a media device event
+    pub fn is_device_event(&self) -> bool {
+        matches!(
+            self,
+            Event::DeviceAdded { .. }
+                | Event::DeviceRemoved { .. }
+                | Event::DefaultDeviceChanged { .. }
+                | Event::AudioRouteChanged { .. }
+        )
+    }
+
+    /// Check if this is a moderation event
+    pub fn is_moderation_event(&self) -> bool {
+        matches!(
+            self,
+            Event::TrackMutedByModerator { .. }
+                | Event::ParticipantRoleChanged { .. }
+                | Event::ParticipantRemoved { .. }
+                | Event::CaptureConsentRequested { .. }
+                | Event::CaptureStateChanged { .. }
+        )
+    }
+
+    /// Check if this event belongs to a category
+    ///
+    /// An event can belong to more than one category; for example network
+    /// quality changes are both connection and quality events.
+    pub fn is_kind(&self, kind: EventKind) -> bool {
+        match kind {
+            EventKind::Participant => self.is_participant_event(),
+            EventKind::Track => self.is_track_event(),
+            EventKind::Connection => self.is_connection_event(),
+            EventKind::Error => self.is_error_event(),
+            EventKind::Quality => self.is_quality_event(),
+            EventKind::Device => self.is_device_event(),
+            EventKind::Moderation => self.is_moderation_event(),
+        }
+    }
+}
+
+/// Event categories for filtering
+///
+/// Kinds combine with `|` into an [`EventFilter`]:
+/// `room.events().filter(EventKind::Track | EventKind::Participant)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    /// Participants joining, leaving, speaking or changing connection state
+    Participant,
+    /// Local and remote track lifecycle and state
+    Track,
+    /// Room connection state
+    Connection,
+    /// Room errors
+    Error,
+    /// Network and media quality changes
+    Quality,
+    /// Media devices being added, removed or changing default
+    Device,
+    /// Moderator actions, including recording and transcription
+    Moderation,
+}
+
+impl std::ops::BitOr for EventKind {
+    type Output = EventFilter;
+
+    fn bitor(self, rhs: EventKind) -> EventFilter {
+        EventFilter::from(self) | rhs
+    }
+}
+
+/// Why a local track is being sent at reduced quality
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QualityLimitationReason {
+    /// Not limited; full quality has been restored
+    None,
+    /// Available bandwidth is too low
+    Bandwidth,
+    /// The encoder cannot keep up
+    Cpu,
+}
+
+/// Media device kinds reported in device events
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeviceKind {
+    /// Microphone or other audio input
+    AudioInput,
+    /// Speaker, headphones or other audio output
+    AudioOutput,
+    /// Camera or other video input
+    VideoInput,
+}
+
+/// Steps of joining a room, reported in [`Event::JoinProgress`]
+///
+/// They are reported in this order; signaling is skipped when the room has
+/// no signaling server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JoinStage {
+    /// Joined the room on the signaling server
+    SignalingConnected,
+    /// Connected the media transport and set up the MoQ session
+    TransportEstablished,
+    /// The room is connected and media can flow
+    SessionActive,
+}
+
+/// What a room records or transcribes, reported in capture events
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CaptureKind {
+    /// Media recording
+    Recording,
+    /// Speech transcription
+    Transcription,
 }
 
 /// Network quality metrics for detailed analysis
@@ -238,64 +581,257 @@ pub enum QualityRating {
     VeryPoor,
 }
 
+/// What happens when an event stream's buffer is full
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Discard the oldest buffered event to make room
+    #[default]
+    DropOldest,
+    /// Discard the incoming event
+    DropNewest,
+}
+
+/// Buffering for an event stream subscribed to a room
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventBufferConfig {
+    /// Maximum number of undelivered events
+    pub capacity: usize,
+    /// What to do when the buffer is full
+    pub overflow: OverflowPolicy,
+}
+
+impl Default for EventBufferConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 256,
+            overflow: OverflowPolicy::DropOldest,
+        }
+    }
+}
+
+/// Bounded queue feeding one [`EventStream`]
+#[derive(Debug)]
+struct EventQueue {
+    state: Mutex<EventQueueState>,
+    notify: Notify,
+    config: EventBufferConfig,
+}
+
+#[derive(Debug, Default)]
+struct EventQueueState {
+    events: VecDeque<Event>,
+    dropped: u64,
+    closed: bool,
+}
+
+impl EventQueue {
+    fn new(config: EventBufferConfig) -> Self {
+        Self {
+            state: Mutex::new(EventQueueState::default()),
+            notify: Notify::new(),
+            config: EventBufferConfig {
+                capacity: config.capacity.max(1),
+                ..config
+            },
+        }
+    }
+
+    /// Buffer an event; returns `false` once the stream has been closed
+    fn push(&self, event: Event) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.closed {
+            return false;
+        }
+
+        if state.events.len() >= self.config.capacity {
+            state.dropped += 1;
+            if state.dropped == 1 {
+                warn!(
+                    "⚠️ Event stream buffer full ({} events), dropping events",
+                    self.config.capacity
+                );
+            }
+            match self.config.overflow {
+                OverflowPolicy::DropOldest => {
+                    state.events.pop_front();
+                }
+                OverflowPolicy::DropNewest => return true,
+            }
+        }
+        state.events.push_back(event);
+        drop(state);
+
+        self.notify.notify_one();
+        true
+    }
+
+    fn pop(&self) -> Result<Option<Event>, mpsc::error::TryRecvError> {
+        let mut state = self.state.lock().unwrap();
+        match state.events.pop_front() {
+            Some(event) => Ok(Some(event)),
+            None if state.closed => Err(mpsc::error::TryRecvError::Disconnected),
+            None => Ok(None),
+        }
+    }
+
+    fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.notify.notify_one();
+    }
+}
+
+/// Fans room events out to every subscribed [`EventStream`]
+///
+/// Cloning is cheap; clones share the same subscribers. Streams end once
+/// every clone has been dropped.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EventBus {
+    inner: Arc<EventBusInner>,
+}
+
+#[derive(Debug, Default)]
+struct EventBusInner {
+    subscribers: Mutex<Vec<Arc<EventQueue>>>,
+    /// Timeline of the room's call report, if one is kept
+    recorder: Mutex<Option<Arc<CallRecorder>>>,
+}
+
+impl EventBus {
+    /// Subscribe a new stream
+    pub(crate) fn subscribe(&self, config: EventBufferConfig) -> EventStream {
+        let queue = Arc::new(EventQueue::new(config));
+        self.inner
+            .subscribers
+            .lock()
+            .unwrap()
+            .push(Arc::clone(&queue));
+        EventStream {
+            source: EventSource::Queue(queue),
+        }
+    }
+
+    /// Record every event emitted from now on into a call report
+    pub(crate) fn record_to(&self, recorder: Arc<CallRecorder>) {
+        *self.inner.recorder.lock().unwrap() = Some(recorder);
+    }
+
+    /// Deliver an event to every open stream
+    pub(crate) fn emit(&self, event: Event) {
+        debug!("📡 Emitting event: {}", event.event_type());
+        if let Some(recorder) = self.inner.recorder.lock().unwrap().as_ref() {
+            recorder.record_event(&event);
+        }
+        self.inner
+            .subscribers
+            .lock()
+            .unwrap()
+            .retain(|queue| queue.push(event.clone()));
+    }
+}
+
+impl Drop for EventBusInner {
+    fn drop(&mut self) {
+        for queue in self.subscribers.lock().unwrap().drain(..) {
+            queue.close();
+        }
+    }
+}
+
+#[derive(Debug)]
+enum EventSource {
+    Channel(mpsc::UnboundedReceiver<Event>),
+    Queue(Arc<EventQueue>),
+}
+
 /// Stream of room events for async iteration
 #[derive(Debug)]
 pub struct EventStream {
-    /// Receiver for events
-    receiver: mpsc::UnboundedReceiver<Event>,
-    /// Optional room inner reference for cleanup
-    _room_inner: Option<Arc<RwLock<crate::room::RoomInner>>>,
+    source: EventSource,
 }
 
 impl EventStream {
     /// Create a new event stream with a receiver
     pub fn new(receiver: mpsc::UnboundedReceiver<Event>) -> Self {
         Self {
-            receiver,
-            _room_inner: None,
+            source: EventSource::Channel(receiver),
         }
     }
 
-    /// Create an event stream from a room's internal state
-    pub fn from_room(room_inner: Arc<RwLock<crate::room::RoomInner>>) -> Self {
-        // Create a new receiver channel for this event stream
-        let (_tx, rx): (mpsc::UnboundedSender<Event>, mpsc::UnboundedReceiver<Event>) =
-            mpsc::unbounded_channel();
-
-        // In a real implementation, we would start a background task that
-        // forwards events from the room's event channel to this receiver.
-        // For now, we'll create a placeholder stream.
-
-        Self {
-            receiver: rx,
-            _room_inner: Some(room_inner),
-        }
+    /// Only yield events in the given categories
+    ///
+    /// ```rust,no_run
+    /// # use quicrtc::{event::EventKind, Room};
+    /// # async fn example(room: &Room) {
+    /// let mut events = room.events().filter(EventKind::Track | EventKind::Participant);
+    /// while let Some(event) = events.next().await {
+    ///     println!("{}", event.event_type());
+    /// }
+    /// # }
+    /// ```
+    pub fn filter(self, filter: impl Into<EventFilter>) -> FilteredEventStream {
+        FilteredEventStream::new(self, filter.into())
     }
 
     /// Get the next event from the stream
     pub async fn next(&mut self) -> Option<Event> {
-        self.receiver.recv().await
+        match &mut self.source {
+            EventSource::Channel(receiver) => receiver.recv().await,
+            EventSource::Queue(queue) => loop {
+                match queue.pop() {
+                    Ok(Some(event)) => return Some(event),
+                    Ok(None) => queue.notify.notified().await,
+                    Err(_) => return None,
+                }
+            },
+        }
     }
 
     /// Try to get the next event without blocking
     pub fn try_next(&mut self) -> Result<Option<Event>, mpsc::error::TryRecvError> {
-        match self.receiver.try_recv() {
-            Ok(event) => Ok(Some(event)),
-            Err(mpsc::error::TryRecvError::Empty) => Ok(None),
-            Err(mpsc::error::TryRecvError::Disconnected) => {
-                Err(mpsc::error::TryRecvError::Disconnected)
-            }
+        match &mut self.source {
+            EventSource::Channel(receiver) => match receiver.try_recv() {
+                Ok(event) => Ok(Some(event)),
+                Err(mpsc::error::TryRecvError::Empty) => Ok(None),
+                Err(mpsc::error::TryRecvError::Disconnected) => {
+                    Err(mpsc::error::TryRecvError::Disconnected)
+                }
+            },
+            EventSource::Queue(queue) => queue.pop(),
+        }
+    }
+
+    /// Number of events discarded because this stream's buffer was full
+    pub fn dropped_events(&self) -> u64 {
+        match &self.source {
+            EventSource::Channel(_) => 0,
+            EventSource::Queue(queue) => queue.state.lock().unwrap().dropped,
         }
     }
 
     /// Close the event stream
+    ///
+    /// Events already buffered can still be read.
     pub fn close(&mut self) {
-        self.receiver.close();
+        match &mut self.source {
+            EventSource::Channel(receiver) => receiver.close(),
+            EventSource::Queue(queue) => queue.close(),
+        }
     }
 
     /// Check if the event stream is closed
     pub fn is_closed(&self) -> bool {
-        self.receiver.is_closed()
+        match &self.source {
+            EventSource::Channel(receiver) => receiver.is_closed(),
+            EventSource::Queue(queue) => queue.state.lock().unwrap().closed,
+        }
+    }
+}
+
+impl Drop for EventStream {
+    fn drop(&mut self) {
+        if let EventSource::Queue(queue) = &self.source {
+            queue.close();
+        }
     }
 }
 
@@ -351,11 +887,31 @@ pub struct EventFilter {
     pub include_connection_events: bool,
     /// Whether to include error events
     pub include_error_events: bool,
+    /// Whether to include quality events
+    pub include_quality_events: bool,
+    /// Whether to include device events
+    pub include_device_events: bool,
+    /// Whether to include moderation events
+    pub include_moderation_events: bool,
     /// Specific event types to include (if specified, overrides other filters)
     pub specific_event_types: Option<Vec<String>>,
 }
 
 impl EventFilter {
+    /// Create a filter that includes no events
+    pub fn none() -> Self {
+        Self {
+            include_participant_events: false,
+            include_track_events: false,
+            include_connection_events: false,
+            include_error_events: false,
+            include_quality_events: false,
+            include_device_events: false,
+            include_moderation_events: false,
+            specific_event_types: None,
+        }
+    }
+
     /// Create a filter that includes all events
     pub fn all() -> Self {
         Self {
@@ -363,51 +919,51 @@ impl EventFilter {
             include_track_events: true,
             include_connection_events: true,
             include_error_events: true,
+            include_quality_events: true,
+            include_device_events: true,
+            include_moderation_events: true,
             specific_event_types: None,
         }
     }
 
     /// Create a filter that includes only participant events
     pub fn participant_only() -> Self {
-        Self {
-            include_participant_events: true,
-            include_track_events: false,
-            include_connection_events: false,
-            include_error_events: false,
-            specific_event_types: None,
-        }
+        EventKind::Participant.into()
     }
 
     /// Create a filter that includes only track events
     pub fn track_only() -> Self {
-        Self {
-            include_participant_events: false,
-            include_track_events: true,
-            include_connection_events: false,
-            include_error_events: false,
-            specific_event_types: None,
-        }
+        EventKind::Track.into()
     }
 
     /// Create a filter that includes only connection events
     pub fn connection_only() -> Self {
-        Self {
-            include_participant_events: false,
-            include_track_events: false,
-            include_connection_events: true,
-            include_error_events: false,
-            specific_event_types: None,
-        }
+        EventKind::Connection.into()
     }
 
     /// Create a filter for specific event types
     pub fn specific(event_types: Vec<String>) -> Self {
         Self {
-            include_participant_events: false,
-            include_track_events: false,
-            include_connection_events: false,
-            include_error_events: false,
             specific_event_types: Some(event_types),
+            ..Self::none()
+        }
+    }
+
+    /// Also include events of a category
+    pub fn with(mut self, kind: EventKind) -> Self {
+        *self.kind_flag(kind) = true;
+        self
+    }
+
+    fn kind_flag(&mut self, kind: EventKind) -> &mut bool {
+        match kind {
+            EventKind::Participant => &mut self.include_participant_events,
+            EventKind::Track => &mut self.include_track_events,
+            EventKind::Connection => &mut self.include_connection_events,
+            EventKind::Error => &mut self.include_error_events,
+            EventKind::Quality => &mut self.include_quality_events,
+            EventKind::Device => &mut self.include_device_events,
+            EventKind::Moderation => &mut self.include_moderation_events,
         }
     }
 
@@ -423,6 +979,23 @@ impl EventFilter {
             || (self.include_track_events && event.is_track_event())
             || (self.include_connection_events && event.is_connection_event())
             || (self.include_error_events && event.is_error_event())
+            || (self.include_quality_events && event.is_quality_event())
+            || (self.include_device_events && event.is_device_event())
+            || (self.include_moderation_events && event.is_moderation_event())
+    }
+}
+
+impl From<EventKind> for EventFilter {
+    fn from(kind: EventKind) -> Self {
+        Self::none().with(kind)
+    }
+}
+
+impl std::ops::BitOr<EventKind> for EventFilter {
+    type Output = EventFilter;
+
+    fn bitor(self, rhs: EventKind) -> EventFilter {
+        self.with(rhs)
     }
 }
 
@@ -499,14 +1072,14 @@ mod tests {
     }
 
     fn create_test_remote_track() -> RemoteTrack {
-        let moq_track = MoqTrack {
-            namespace: TrackNamespace {
+        let moq_track = MoqTrack::new(
+            TrackNamespace {
                 namespace: "test.room".to_string(),
                 track_name: "test/video".to_string(),
             },
-            name: "video".to_string(),
-            track_type: MoqTrackType::Video,
-        };
+            "video".to_string(),
+            MoqTrackType::Video,
+        );
 
         RemoteTrack::video(
             "test-track".to_string(),
@@ -517,14 +1090,14 @@ mod tests {
     }
 
     fn create_test_local_track() -> LocalTrack {
-        let moq_track = MoqTrack {
-            namespace: TrackNamespace {
+        let moq_track = MoqTrack::new(
+            TrackNamespace {
                 namespace: "test.room".to_string(),
                 track_name: "local/video".to_string(),
             },
-            name: "video".to_string(),
-            track_type: MoqTrackType::Video,
-        };
+            "video".to_string(),
+            MoqTrackType::Video,
+        );
 
         LocalTrack::video(
             "local-track".to_string(),
@@ -658,4 +1231,102 @@ mod tests {
         tx.send(Event::RoomReconnected).unwrap();
         assert!(filtered_stream.try_next().unwrap().is_none());
     }
+
+    #[test]
+    fn test_event_kind_filter() {
+        let filter = EventKind::Track | EventKind::Moderation;
+        let role_changed = Event::ParticipantRoleChanged {
+            participant_id: "bob".to_string(),
+            role: ParticipantRole::Admin,
+        };
+        let device_added = Event::DeviceAdded {
+            kind: DeviceKind::AudioInput,
+            device_id: "usb-mic".to_string(),
+            name: "USB Microphone".to_string(),
+        };
+        let track_event = Event::TrackReceived {
+            track: create_test_remote_track(),
+        };
+
+        assert!(filter.should_include(&role_changed));
+        assert!(filter.should_include(&track_event));
+        assert!(!filter.should_include(&device_added));
+        assert!(!filter.should_include(&Event::RoomReconnected));
+        assert!(EventFilter::from(EventKind::Device).should_include(&device_added));
+
+        // Network quality belongs to both categories
+        let network_quality = Event::NetworkQualityChanged {
+            quality_score: 40,
+            metrics: NetworkQualityMetrics::poor(),
+        };
+        assert!(network_quality.is_kind(EventKind::Quality));
+        assert!(network_quality.is_kind(EventKind::Connection));
+        assert!(!EventFilter::none().should_include(&network_quality));
+    }
+
+    #[tokio::test]
+    async fn test_event_bus_fan_out_and_filter() {
+        let bus = EventBus::default();
+        let mut all = bus.subscribe(EventBufferConfig::default());
+        let mut moderation = bus
+            .subscribe(EventBufferConfig::default())
+            .filter(EventKind::Moderation);
+
+        bus.emit(Event::RoomReconnected);
+        bus.emit(Event::ParticipantRemoved {
+            participant_id: "mallory".to_string(),
+            reason: "spam".to_string(),
+        });
+
+        assert_eq!(all.next().await.unwrap().event_type(), "room_reconnected");
+        assert_eq!(
+            all.next().await.unwrap().event_type(),
+            "participant_removed"
+        );
+        assert_eq!(
+            moderation.next().await.unwrap().event_type(),
+            "participant_removed"
+        );
+
+        // Dropped streams are unsubscribed on the next event
+        drop(moderation);
+        bus.emit(Event::RoomReconnected);
+        assert_eq!(bus.inner.subscribers.lock().unwrap().len(), 1);
+
+        // Streams end once the bus is gone, after draining buffered events
+        drop(bus);
+        assert!(all.next().await.is_some());
+        assert!(all.next().await.is_none());
+    }
+
+    #[test]
+    fn test_event_buffer_overflow_policies() {
+        let reconnecting = |attempt| Event::RoomReconnecting { attempt };
+        let attempt = |event: Option<Event>| match event {
+            Some(Event::RoomReconnecting { attempt }) => attempt,
+            other => panic!("Unexpected event: {:?}", other),
+        };
+
+        let bus = EventBus::default();
+        let mut drop_oldest = bus.subscribe(EventBufferConfig {
+            capacity: 2,
+            overflow: OverflowPolicy::DropOldest,
+        });
+        let mut drop_newest = bus.subscribe(EventBufferConfig {
+            capacity: 2,
+            overflow: OverflowPolicy::DropNewest,
+        });
+        for n in 1..=5 {
+            bus.emit(reconnecting(n));
+        }
+
+        assert_eq!(drop_oldest.dropped_events(), 3);
+        assert_eq!(attempt(drop_oldest.try_next().unwrap()), 4);
+        assert_eq!(attempt(drop_oldest.try_next().unwrap()), 5);
+        assert!(drop_oldest.try_next().unwrap().is_none());
+
+        assert_eq!(drop_newest.dropped_events(), 3);
+        assert_eq!(attempt(drop_newest.try_next().unwrap()), 1);
+        assert_eq!(attempt(drop_newest.try_next().unwrap()), 2);
+    }
 }
@@ -43,51 +43,119 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! ## Feature Flags
+//!
+//! - `full` (default): everything below
+//! - `media`: camera/microphone capture, rendering and codecs; links system
+//!   audio/video libraries
+//! - `signaling`: signaling server/client and peer discovery
+//! - `diagnostics`: connection diagnostics and reporting
+//! - `blocking`: synchronous wrappers in the `blocking` module
+//! - `transport-only`: QUIC/MoQ transport plus the signaling server, with no
+//!   capture or render dependencies. Use it with `default-features = false`
+//!   for relays, slim containers and cross-compilation:
+//!
+//! ```toml
+//! [dependencies]
+//! quicrtc = { version = "0.1", default-features = false, features = ["transport-only"] }
+//! ```
 
 #![deny(missing_docs)]
 #![warn(clippy::all)]
 
 // Re-export core types for easy access
 pub use quicrtc_core::{
-    ConnectionConfig, ConnectionPool, ConnectionPoolConfig, H264Frame, MoqCacheConfig,
-    MoqCacheStats, MoqDeliveryStats, MoqObject, MoqObjectCache, MoqObjectDelivery, MoqObjectStatus,
-    MoqSession, MoqTrack, NetworkPath, OpusFrame, QuicRtcError, ResourceLimits, ResourceManager,
-    ResourceUsage, ResourceWarning, TrackNamespace, TransportConnection, TransportMode,
-    WarningSeverity,
+    AudioContentHint, Backpressure, BackpressureConfig, BackpressureReason, BackpressureSignal,
+    ChannelLayout, ConnectionConfig, ConnectionPool, ConnectionPoolConfig, DiagnosticsSink,
+    DvrHandle, EncodedFrameTransform, FragmentConfig, FragmentStats, H264Frame, HistogramSummary,
+    IntegrityCheck, IntegrityStats, LogLevel, MetricsRecorder, MetricsSnapshot, MoqCacheBudget,
+    MoqCacheConfig, MoqCacheStats, MoqDeliveryStats, MoqEvictionPolicy, MoqNamespaceSubscription,
+    MoqObject, MoqObjectCache, MoqObjectDelivery, MoqObjectMetadata, MoqObjectStatus,
+    MoqSendBufferConfig, MoqSession, MoqSpillCache, MoqSpillConfig, MoqSpillKey,
+    MoqSpillSegmentReader, MoqSpillStats, MoqTrack, NetworkPath, ObjectIntegrity, OpusFrame,
+    PacketCapture, PacketCaptureConfig, PacketCaptureReader, QuicRtcError, ResourceLimits,
+    ResourceManager, ResourceUsage, ResourceWarning, SendBufferWatermark,
+    SendBufferWatermarkCallback, TracingSink, TrackNamespace, TrackNamespacePattern,
+    TrackTransferStats, TransformDirection, TransportConnection, TransportMode, VideoContentHint,
+    VideoRotation, WarningSeverity,
 };
 
 #[cfg(feature = "media")]
 pub use quicrtc_media::{
-    codecs::{Codec, CodecInfo, VideoQuality},
+    audio_source::AudioSource,
+    codecs::{Codec, CodecInfo, CodecRegistry, VideoQuality},
+    compositor::{CompositionLayout, VideoCompositor},
+    pipeline::{DecodePipelineConfig, EncodePipelineConfig, FrameDropPolicy},
+    processing::VideoRotationMode,
+    render::{AudioOutputDevice, PlaybackConfig},
     tracks::{AudioTrack, MediaFrame, VideoTrack},
 };
 
 #[cfg(feature = "signaling")]
-pub use quicrtc_signaling::{PeerDiscovery, SignalingServer};
+pub use quicrtc_signaling::{
+    CapabilitySet, CodecCapability, CodecKind, PeerDiscovery, RoomSchedule, SignalingServer,
+};
 
 #[cfg(feature = "diagnostics")]
-pub use quicrtc_diagnostics::{ConnectionInfo, ConnectionStats, NetworkProfiler};
+pub use quicrtc_diagnostics::{
+    ConnectionInfo, ConnectionStats, CrashBundle, CrashReporter, CrashReporterConfig, DebugLogger,
+    LogRecord, LogRingConfig, NetworkProfiler,
+};
 
 // Public API modules
+pub mod audio_route;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod config;
 pub mod event;
 pub mod participant;
+pub mod preflight;
+pub mod quality;
+pub mod report;
 pub mod room;
+pub mod stats_history;
 pub mod track;
 
 // Re-export main API types
+pub use audio_route::{
+    AudioRoute, AudioRouteKind, AudioRoutePolicy, AudioRouteSelection, AudioSession,
+};
 pub use config::{CodecConfig, GlobalConfig, RoomConfig};
 
 #[cfg(feature = "media")]
-pub use config::{AudioProcessingConfig, MediaConfig, VideoProcessingConfig};
+pub use config::{
+    AudioProcessingConfig, AudioProfile, DuckingConfig, MediaConfig, ThumbnailConfig,
+    VideoProcessingConfig,
+};
 
 #[cfg(feature = "signaling")]
 pub use config::{ReconnectConfig, SignalingConfig};
 
-pub use event::{Event, EventStream};
-pub use participant::{LocalParticipant, Participants, RemoteParticipant};
-pub use room::{Room, RoomBuilder};
-pub use track::{LocalTrack, RemoteTrack};
+pub use event::{Event, EventBufferConfig, EventKind, EventStream, OverflowPolicy};
+pub use participant::{
+    LocalParticipant, ParticipantRole, Participants, ParticipantsSnapshot, RemoteParticipant,
+};
+pub use preflight::{PreflightConfig, PreflightReport};
+pub use quality::{QualityLevel, QualityMonitor, QualitySample, QualityThresholds};
+pub use report::{CallReport, CallTimelineEntry, CallTrackReport};
+pub use room::{JoinedRoom, Room, RoomBuilder};
+pub use stats_history::{ConnectionSample, StatsHistory, StatsHistoryConfig, StatsSample};
+pub use tokio_util::sync::CancellationToken;
+#[cfg(feature = "media")]
+pub use track::{
+    AudioPlayback, CompositionPublication, PlayAudioOptions, PlaybackTarget, RemoteVideoFrame,
+};
+pub use track::{
+    LocalTrack, RemoteTrack, RemoteTrackInfo, SimulcastLayer, SubscribeOptions, TrackMetadata,
+};
+
+/// Camera capture shared by the rooms of a [`QuicRtc`] instance
+#[cfg(feature = "media")]
+type SharedVideoCapture = std::sync::Arc<tokio::sync::Mutex<quicrtc_media::VideoCaptureManager>>;
+
+/// Weak reference to a joined room's state; dead once the room is dropped
+type RoomHandle = std::sync::Weak<tokio::sync::RwLock<room::RoomInner>>;
 
 /// Main entry point for QUIC RTC
 #[derive(Debug, Clone)]
@@ -109,8 +177,33 @@ struct QuicRtcInner {
     /// Peer discovery service
     #[cfg(feature = "signaling")]
     peer_discovery: std::sync::Arc<quicrtc_signaling::PeerDiscovery>,
+    /// MoQ transports by media endpoint, shared by the rooms using them
+    transports: tokio::sync::Mutex<
+        std::collections::HashMap<
+            std::net::SocketAddr,
+            std::sync::Weak<quicrtc_core::MoqOverQuicTransport>,
+        >,
+    >,
+    /// Transports set up by [`QuicRtc::prewarm`], kept open until a room
+    /// picks them up
+    prewarmed: std::sync::Mutex<
+        std::collections::HashMap<
+            std::net::SocketAddr,
+            std::sync::Arc<quicrtc_core::MoqOverQuicTransport>,
+        >,
+    >,
+    /// Camera capture, shared by every room that publishes the camera
+    #[cfg(feature = "media")]
+    video_capture: tokio::sync::OnceCell<SharedVideoCapture>,
+    /// Rooms joined through this instance
+    rooms: std::sync::Mutex<Vec<(JoinedRoom, RoomHandle)>>,
+    /// Where transports and audio devices report log lines and metrics
+    diagnostics: std::sync::RwLock<std::sync::Arc<dyn DiagnosticsSink>>,
     /// Background task handles for cleanup
     _background_tasks: Vec<tokio::task::JoinHandle<()>>,
+    /// Keeps resource usage in crash bundles
+    #[cfg(feature = "diagnostics")]
+    _crash_state: quicrtc_diagnostics::CrashStateGuard,
 }
 
 impl QuicRtc {
@@ -153,6 +246,46 @@ impl QuicRtc {
     /// # }
     /// ```
     pub async fn init_with(config: GlobalConfig) -> Result<Self, QuicRtcError> {
+        Self::init_internal(
+            config,
+            #[cfg(feature = "media")]
+            None,
+        )
+        .await
+    }
+
+    /// Initialize with a caller-built codec registry
+    ///
+    /// Use this to ship codecs implemented outside this crate, such as Lyra
+    /// or H.265. The registry replaces the one built from
+    /// [`GlobalConfig::codec_config`]; its order is the preference order used
+    /// when publishing and negotiating.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use quicrtc::{Codec, CodecRegistry, GlobalConfig, QuicRtc};
+    /// use std::sync::Arc;
+    ///
+    /// # async fn example(h265: Arc<dyn Codec>) -> Result<(), quicrtc::QuicRtcError> {
+    /// let mut codecs = CodecRegistry::with_defaults()?;
+    /// codecs.register_codec("h265", h265)?;
+    /// codecs.prefer_codec("h265")?;
+    /// let quic_rtc = QuicRtc::init_with_codecs(GlobalConfig::default(), codecs).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "media")]
+    pub async fn init_with_codecs(
+        config: GlobalConfig,
+        codecs: quicrtc_media::CodecRegistry,
+    ) -> Result<Self, QuicRtcError> {
+        Self::init_internal(config, Some(codecs)).await
+    }
+
+    async fn init_internal(
+        config: GlobalConfig,
+        #[cfg(feature = "media")] codecs: Option<quicrtc_media::CodecRegistry>,
+    ) -> Result<Self, QuicRtcError> {
         tracing::info!("🚀 Initializing QUIC RTC with configuration: {:?}", config);
 
         // Initialize logging if requested
@@ -170,7 +303,10 @@ impl QuicRtc {
         #[cfg(feature = "media")]
         let codec_registry = {
             tracing::debug!("🎵 Initializing codec registry");
-            Self::init_codec_registry(&config.codec_config)?
+            match codecs {
+                Some(codecs) => std::sync::Arc::new(codecs),
+                None => Self::init_codec_registry(&config.codec_config)?,
+            }
         };
 
         // 3. Initialize peer discovery
@@ -196,6 +332,29 @@ impl QuicRtc {
         )
         .await?;
 
+        // 6. Report resource usage in crash bundles
+        #[cfg(feature = "diagnostics")]
+        let crash_state = {
+            let resource_manager = std::sync::Arc::downgrade(&resource_manager);
+            CrashReporter::register_state(
+                "resources",
+                std::sync::Arc::new(move || match resource_manager.upgrade() {
+                    Some(resource_manager) => {
+                        let usage = resource_manager.current_usage();
+                        serde_json::json!({
+                            "memory_mb": usage.memory_mb,
+                            "bandwidth_kbps": usage.bandwidth_kbps,
+                            "active_connections": usage.active_connections,
+                            "active_streams": usage.active_streams,
+                            "cached_objects": usage.cached_objects,
+                            "cpu_usage_percent": usage.cpu_usage_percent,
+                        })
+                    }
+                    None => serde_json::Value::Null,
+                }),
+            )
+        };
+
         tracing::info!("✅ QUIC RTC initialization complete");
 
         Ok(Self {
@@ -207,14 +366,26 @@ impl QuicRtc {
                 codec_registry,
                 #[cfg(feature = "signaling")]
                 peer_discovery,
+                transports: tokio::sync::Mutex::new(std::collections::HashMap::new()),
+                prewarmed: std::sync::Mutex::new(std::collections::HashMap::new()),
+                #[cfg(feature = "media")]
+                video_capture: tokio::sync::OnceCell::new(),
+                rooms: std::sync::Mutex::new(Vec::new()),
+                diagnostics: std::sync::RwLock::new(quicrtc_core::default_sink()),
                 _background_tasks: background_tasks,
+                #[cfg(feature = "diagnostics")]
+                _crash_state: crash_state,
             }),
         })
     }
 
     /// Initialize logging system
     fn init_logging() -> Result<(), QuicRtcError> {
+        // Rooms with a debug logger need its layer in the global subscriber
+        #[cfg(feature = "diagnostics")]
+        let _ = DebugLogger::init_logging("quicrtc=debug,info");
         // Only initialize if not already initialized
+        #[cfg(not(feature = "diagnostics"))]
         let _ = tracing_subscriber::fmt()
             .with_env_filter("quicrtc=debug,info")
             .try_init();
@@ -231,9 +402,10 @@ impl QuicRtc {
         if config.enable_opus {
             let opus_config = quicrtc_media::codecs::OpusConfig {
                 sample_rate: config.default_audio_sample_rate,
-                channels: 2, // Stereo by default
+                channels: config.default_audio_channel_layout.channel_count(),
                 bitrate: config.default_audio_bitrate,
                 frame_duration_ms: 20,
+                ..Default::default()
             };
             let opus_codec =
                 std::sync::Arc::new(quicrtc_media::codecs::OpusCodec::with_config(opus_config)?);
@@ -247,6 +419,10 @@ impl QuicRtc {
                 height: 720,
                 bitrate: config.default_video_bitrate,
                 framerate: 30,
+                content_hint: crate::VideoContentHint::Motion,
+                keyframe_interval_frames: config.video_keyframe_interval,
+                max_gop_duration: config.video_max_gop_duration,
+                max_slice_size: None,
             };
             let h264_codec =
                 std::sync::Arc::new(quicrtc_media::codecs::H264Codec::with_config(h264_config)?);
@@ -361,12 +537,207 @@ impl QuicRtc {
         &self.inner.codec_registry
     }
 
+    /// Structured codec capabilities for session offers, most preferred first
+    ///
+    /// Pass the remote side's list to [`quicrtc_signaling::select_codec`] to
+    /// pick the codec for a track.
+    #[cfg(all(feature = "signaling", feature = "media"))]
+    pub fn codec_offer(&self) -> Vec<CodecCapability> {
+        let registry = &self.inner.codec_registry;
+        registry
+            .list_codecs()
+            .into_iter()
+            .filter_map(|name| {
+                let codec = registry.get_codec(&name)?;
+                let info = quicrtc_media::SyncEncoder::get_codec_info(codec.as_ref());
+                match info.track_type()? {
+                    quicrtc_core::MoqTrackType::Audio => {
+                        let capability = CodecCapability::audio(&name);
+                        Some(match info.channels {
+                            Some(channels) => capability.with_max_channels(channels),
+                            None => capability,
+                        })
+                    }
+                    quicrtc_core::MoqTrackType::Video => Some(CodecCapability::video(&name)),
+                    quicrtc_core::MoqTrackType::Data => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Structured codec capabilities for session offers; none without media
+    #[cfg(all(feature = "signaling", not(feature = "media")))]
+    pub fn codec_offer(&self) -> Vec<CodecCapability> {
+        Vec::new()
+    }
+
+    /// Capabilities advertised to other participants when joining rooms and
+    /// offering sessions
+    #[cfg(feature = "signaling")]
+    pub fn capabilities(&self) -> CapabilitySet {
+        CapabilitySet {
+            codecs: self.codec_offer(),
+            ..CapabilitySet::new()
+        }
+        .with_transport(quicrtc_core::TransportMode::QuicNative)
+        .with_transport(quicrtc_core::TransportMode::QuicOverWebSocket)
+        .with_simulcast(true)
+    }
+
     /// Get peer discovery service (for manual peer management)
     #[cfg(feature = "signaling")]
     pub fn peer_discovery(&self) -> &quicrtc_signaling::PeerDiscovery {
         &self.inner.peer_discovery
     }
 
+    /// Rooms currently joined through this instance
+    ///
+    /// Rooms share this instance's codec registry, camera capture and MoQ
+    /// transports: rooms whose media goes to the same endpoint use a single
+    /// QUIC connection. A room is listed until it is dropped.
+    pub fn rooms(&self) -> Vec<JoinedRoom> {
+        let mut rooms = self.inner.rooms.lock().unwrap();
+        rooms.retain(|(_, room)| room.strong_count() > 0);
+        rooms.iter().map(|(joined, _)| joined.clone()).collect()
+    }
+
+    /// Record a room being joined
+    ///
+    /// Fails if the participant is already in the room through this instance.
+    pub(crate) fn register_room(
+        &self,
+        joined: JoinedRoom,
+        room: &std::sync::Arc<tokio::sync::RwLock<room::RoomInner>>,
+    ) -> Result<(), QuicRtcError> {
+        let mut rooms = self.inner.rooms.lock().unwrap();
+        rooms.retain(|(_, room)| room.strong_count() > 0);
+        if rooms.iter().any(|(existing, _)| *existing == joined) {
+            return Err(QuicRtcError::ParticipantAlreadyExists {
+                room_id: joined.room_id,
+                participant_id: joined.participant_id,
+            });
+        }
+        rooms.push((joined, std::sync::Arc::downgrade(room)));
+        Ok(())
+    }
+
+    /// Carry the media of rooms using `endpoint` over an existing transport
+    ///
+    /// Use this with [`quicrtc_core::MoqOverQuicTransport::with_driver`] to
+    /// run rooms over a third-party QUIC stack. The transport stays in use
+    /// for as long as the caller or a room holds it.
+    pub async fn use_transport(
+        &self,
+        endpoint: std::net::SocketAddr,
+        transport: &std::sync::Arc<quicrtc_core::MoqOverQuicTransport>,
+    ) {
+        self.inner
+            .transports
+            .lock()
+            .await
+            .insert(endpoint, std::sync::Arc::downgrade(transport));
+    }
+
+    /// Send log lines and metrics to `sink` instead of `tracing`
+    ///
+    /// Applies to the MoQ transports and audio devices of every room,
+    /// including transports already connected. Embedders use this to route
+    /// telemetry into their own logging and monitoring; see
+    /// [`MetricsRecorder`] for keeping metrics in memory.
+    pub async fn set_diagnostics_sink(&self, sink: std::sync::Arc<dyn DiagnosticsSink>) {
+        *self.inner.diagnostics.write().unwrap() = std::sync::Arc::clone(&sink);
+        for transport in self.inner.transports.lock().await.values() {
+            if let Some(transport) = transport.upgrade() {
+                transport.set_diagnostics_sink(std::sync::Arc::clone(&sink));
+            }
+        }
+    }
+
+    /// Sink log lines and metrics are reported to
+    pub fn diagnostics(&self) -> std::sync::Arc<dyn DiagnosticsSink> {
+        std::sync::Arc::clone(&self.inner.diagnostics.read().unwrap())
+    }
+
+    /// Connect to a relay ahead of joining a room, so the join only has to
+    /// announce and subscribe
+    ///
+    /// Resolves `relay_url`, given as `host:port` with an optional scheme
+    /// such as `moq://`, then completes the QUIC handshake and MoQ SETUP.
+    /// The connection is kept open until a room whose media endpoint is the
+    /// relay joins and takes it over; the endpoint is the one negotiated
+    /// over signaling, or the default one for rooms without signaling.
+    /// A connection rooms already have to the relay is reused.
+    ///
+    /// Returns the relay's resolved address.
+    pub async fn prewarm(&self, relay_url: &str) -> Result<std::net::SocketAddr, QuicRtcError> {
+        let endpoint = resolve_relay(relay_url).await?;
+        tracing::info!("🔥 Prewarming MoQ transport to {}", endpoint);
+        let transport = self
+            .shared_transport(endpoint, || async move {
+                let transport = quicrtc_core::MoqOverQuicTransport::new(
+                    endpoint,
+                    ConnectionConfig::default(),
+                    rand::random::<u64>(),
+                )
+                .await?;
+                transport.establish_session().await?;
+                Ok(transport)
+            })
+            .await?;
+        self.inner
+            .prewarmed
+            .lock()
+            .unwrap()
+            .insert(endpoint, transport);
+        Ok(endpoint)
+    }
+
+    /// Transport to a media endpoint, connecting with `connect` if no room
+    /// uses one yet
+    ///
+    /// Concurrent joins to the same endpoint wait for the first connection
+    /// rather than opening their own.
+    pub(crate) async fn shared_transport<F, Fut>(
+        &self,
+        endpoint: std::net::SocketAddr,
+        connect: F,
+    ) -> Result<std::sync::Arc<quicrtc_core::MoqOverQuicTransport>, QuicRtcError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<quicrtc_core::MoqOverQuicTransport, QuicRtcError>>,
+    {
+        let mut transports = self.inner.transports.lock().await;
+        if let Some(transport) = transports.get(&endpoint).and_then(std::sync::Weak::upgrade) {
+            tracing::debug!("♻️ Reusing MoQ transport to {}", endpoint);
+            // The room holds it from now on
+            self.inner.prewarmed.lock().unwrap().remove(&endpoint);
+            return Ok(transport);
+        }
+        let transport = std::sync::Arc::new(connect().await?);
+        transport.set_diagnostics_sink(self.diagnostics());
+        transports.insert(endpoint, std::sync::Arc::downgrade(&transport));
+        Ok(transport)
+    }
+
+    /// Camera capture shared by all rooms, opened by `open` on first use
+    #[cfg(feature = "media")]
+    pub(crate) async fn video_capture<F, Fut>(
+        &self,
+        open: F,
+    ) -> Result<SharedVideoCapture, QuicRtcError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<quicrtc_media::VideoCaptureManager, QuicRtcError>>,
+    {
+        self.inner
+            .video_capture
+            .get_or_try_init(|| async {
+                Ok(std::sync::Arc::new(tokio::sync::Mutex::new(open().await?)))
+            })
+            .await
+            .cloned()
+    }
+
     /// Create a room builder for the given room ID
     ///
     /// # Example
@@ -387,3 +758,20 @@ impl QuicRtc {
         RoomBuilder::new(self, id)
     }
 }
+
+/// Resolve a relay URL such as `moq://relay.example.com:4443` to its address
+async fn resolve_relay(relay_url: &str) -> Result<std::net::SocketAddr, QuicRtcError> {
+    let authority = relay_url
+        .split_once("://")
+        .map_or(relay_url, |(_, rest)| rest);
+    let authority = authority.split('/').next().unwrap_or_default();
+    tokio::net::lookup_host(authority)
+        .await
+        .map_err(|e| QuicRtcError::Transport {
+            reason: format!("Cannot resolve relay {}: {}", relay_url, e),
+        })?
+        .next()
+        .ok_or_else(|| QuicRtcError::Transport {
+            reason: format!("Relay {} has no addresses", relay_url),
+        })
+}
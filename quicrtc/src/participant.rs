@@ -1,8 +1,17 @@
 //! Participant management and abstractions
 
-use crate::{LocalTrack, RemoteTrack, RoomConfig};
+use crate::track::{
+    RemoteTrackInfo, SimulcastLayer, TrackKind, TrackMetadata, TrackSource, TrackState,
+};
+use crate::{Event, LocalTrack, RemoteTrack, RoomConfig};
+use quicrtc_core::{TrackNamespace, TrackTransferStats};
+#[cfg(feature = "media")]
+use quicrtc_media::{DecodeStats, EncodeStats};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
+use tokio::sync::watch;
 use tracing::{debug, info};
 
 /// Collection of participants in a room
@@ -12,8 +21,12 @@ pub struct Participants {
     remote_participants: HashMap<String, RemoteParticipant>,
     /// Maximum number of participants allowed
     max_participants: Option<usize>,
+    /// Audio preferences by participant ID, kept across leave/rejoin
+    audio_settings: HashMap<String, Arc<ParticipantAudioSettings>>,
     /// When this collection was created
     created_at: Instant,
+    /// Publishes a snapshot whenever membership or participant state changes
+    changes: watch::Sender<ParticipantsSnapshot>,
 }
 
 impl Participants {
@@ -22,23 +35,27 @@ impl Participants {
         Self {
             remote_participants: HashMap::new(),
             max_participants: None,
+            audio_settings: HashMap::new(),
             created_at: Instant::now(),
+            changes: watch::channel(ParticipantsSnapshot::default()).0,
         }
     }
 
     /// Create a new participants collection with maximum size
     pub fn with_max_participants(max: usize) -> Self {
         Self {
-            remote_participants: HashMap::new(),
             max_participants: Some(max),
-            created_at: Instant::now(),
+            ..Self::new()
         }
     }
 
     /// Add a remote participant
+    ///
+    /// Volume and audio preferences set on an earlier session of the same
+    /// participant are restored.
     pub fn add_remote_participant(
         &mut self,
-        participant: RemoteParticipant,
+        mut participant: RemoteParticipant,
     ) -> Result<(), ParticipantError> {
         // Check if we've reached the maximum
         if let Some(max) = self.max_participants {
@@ -54,16 +71,35 @@ impl Participants {
             });
         }
 
+        match self.audio_settings.get(&participant.id) {
+            Some(settings) => {
+                debug!(
+                    "🔊 Restoring audio preferences for participant: {}",
+                    participant.id
+                );
+                participant.audio_settings = settings.clone();
+            }
+            None => {
+                self.audio_settings
+                    .insert(participant.id.clone(), participant.audio_settings.clone());
+            }
+        }
+
         info!("👥 Adding remote participant: {}", participant.id);
         self.remote_participants
             .insert(participant.id.clone(), participant);
+        self.publish_snapshot();
         Ok(())
     }
 
     /// Remove a remote participant
     pub fn remove_remote_participant(&mut self, participant_id: &str) -> Option<RemoteParticipant> {
         info!("👋 Removing remote participant: {}", participant_id);
-        self.remote_participants.remove(participant_id)
+        let removed = self.remote_participants.remove(participant_id);
+        if removed.is_some() {
+            self.publish_snapshot();
+        }
+        removed
     }
 
     /// Get a remote participant by ID
@@ -72,6 +108,10 @@ impl Participants {
     }
 
     /// Get a mutable reference to a remote participant by ID
+    ///
+    /// Changes made through the returned reference are not published to
+    /// [`Participants::watch`] receivers; use
+    /// [`Participants::update_participant`] for state that UIs render.
     pub fn get_remote_participant_mut(
         &mut self,
         participant_id: &str,
@@ -79,11 +119,116 @@ impl Participants {
         self.remote_participants.get_mut(participant_id)
     }
 
+    /// Modify a remote participant and notify watchers
+    ///
+    /// Returns `None` if the participant is not in the room.
+    pub fn update_participant<R>(
+        &mut self,
+        participant_id: &str,
+        update: impl FnOnce(&mut RemoteParticipant) -> R,
+    ) -> Option<R> {
+        let participant = self.remote_participants.get_mut(participant_id)?;
+        let result = update(participant);
+        self.publish_snapshot();
+        Some(result)
+    }
+
+    /// Refresh the statistics of every remote track from transport counters
+    ///
+    /// Statistics change continuously, so watchers are not notified.
+    pub fn refresh_track_stats(
+        &mut self,
+        transfer: impl Fn(&TrackNamespace) -> Option<TrackTransferStats>,
+        interval: Duration,
+    ) {
+        for participant in self.remote_participants.values_mut() {
+            participant.refresh_track_stats(&transfer, interval);
+        }
+    }
+
+    /// Refresh the decoder statistics of every remote track, looked up by
+    /// participant and track ID
+    #[cfg(feature = "media")]
+    pub fn refresh_decode_stats(&mut self, decode: impl Fn(&str, &str) -> Option<DecodeStats>) {
+        for participant in self.remote_participants.values_mut() {
+            let participant_id = participant.id().to_string();
+            participant.refresh_decode_stats(|track_id| decode(&participant_id, track_id));
+        }
+    }
+
     /// Get all remote participants
     pub fn remote_participants(&self) -> impl Iterator<Item = &RemoteParticipant> {
         self.remote_participants.values()
     }
 
+    /// Iterate over remote participants in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = &RemoteParticipant> {
+        self.remote_participants.values()
+    }
+
+    /// Number of remote participants with the given role
+    pub fn count_by_role(&self, role: ParticipantRole) -> usize {
+        self.iter().filter(|p| p.role() == role).count()
+    }
+
+    /// Number of remote participants for every role present in the room
+    pub fn role_counts(&self) -> HashMap<ParticipantRole, usize> {
+        let mut counts = HashMap::new();
+        for participant in self.iter() {
+            *counts.entry(participant.role()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Remote participants that are currently speaking
+    pub fn speaking(&self) -> impl Iterator<Item = &RemoteParticipant> {
+        self.iter().filter(|p| p.is_speaking())
+    }
+
+    /// Tracks published by one remote participant
+    ///
+    /// Returns `None` if the participant is not in the room.
+    pub fn tracks_of(&self, participant_id: &str) -> Option<Vec<&RemoteTrack>> {
+        self.remote_participants
+            .get(participant_id)
+            .map(|p| p.remote_tracks().collect())
+    }
+
+    /// Tracks of every remote participant, keyed by participant ID
+    pub fn tracks_by_participant(&self) -> HashMap<&str, Vec<&RemoteTrack>> {
+        self.iter()
+            .map(|p| (p.id(), p.remote_tracks().collect()))
+            .collect()
+    }
+
+    /// Subscribe to membership and participant state changes
+    ///
+    /// The receiver always holds the latest [`ParticipantsSnapshot`], so a UI
+    /// can await `changed()` and rerender instead of polling. Intermediate
+    /// snapshots may be skipped if the receiver falls behind.
+    pub fn watch(&self) -> watch::Receiver<ParticipantsSnapshot> {
+        self.changes.subscribe()
+    }
+
+    /// Current snapshot of the collection
+    pub fn snapshot(&self) -> ParticipantsSnapshot {
+        self.changes.borrow().clone()
+    }
+
+    fn publish_snapshot(&self) {
+        let version = self.changes.borrow().version + 1;
+        let mut participant_ids: Vec<String> = self.remote_participants.keys().cloned().collect();
+        participant_ids.sort();
+        let mut speaking: Vec<String> = self.speaking().map(|p| p.id.clone()).collect();
+        speaking.sort();
+
+        self.changes.send_replace(ParticipantsSnapshot {
+            version,
+            participant_ids,
+            speaking,
+        });
+    }
+
     /// Get total participant count (remote only, local participant is tracked separately)
     pub fn count(&self) -> usize {
         self.remote_participants.len()
@@ -118,10 +263,11 @@ impl Participants {
         self.remote_participants.keys()
     }
 
-    /// Clear all participants
+    /// Clear all participants (audio preferences are kept for rejoins)
     pub fn clear(&mut self) {
         info!("🧹 Clearing all remote participants");
         self.remote_participants.clear();
+        self.publish_snapshot();
     }
 }
 
@@ -131,6 +277,38 @@ impl Default for Participants {
     }
 }
 
+impl<'a> IntoIterator for &'a Participants {
+    type Item = &'a RemoteParticipant;
+    type IntoIter = std::collections::hash_map::Values<'a, String, RemoteParticipant>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.remote_participants.values()
+    }
+}
+
+/// Membership and speaking state published by [`Participants::watch`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParticipantsSnapshot {
+    /// Incremented on every change
+    pub version: u64,
+    /// Remote participant IDs, sorted
+    pub participant_ids: Vec<String>,
+    /// IDs of remote participants currently speaking, sorted
+    pub speaking: Vec<String>,
+}
+
+/// What a participant is allowed to do in the room
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ParticipantRole {
+    /// Publishes and subscribes to media
+    #[default]
+    Publisher,
+    /// Only subscribes to media
+    Subscriber,
+    /// Publishes, subscribes and manages the room
+    Admin,
+}
+
 /// Local participant representation
 #[derive(Debug)]
 pub struct LocalParticipant {
@@ -218,11 +396,55 @@ impl LocalParticipant {
         self.local_tracks.get(track_id)
     }
 
+    /// Get a local track by ID for updating
+    pub(crate) fn get_local_track_mut(&mut self, track_id: &str) -> Option<&mut LocalTrack> {
+        self.local_tracks.get_mut(track_id)
+    }
+
     /// Get all local tracks
     pub fn local_tracks(&self) -> impl Iterator<Item = &LocalTrack> {
         self.local_tracks.values()
     }
 
+    /// Pause every publishing video track, or resume the paused ones
+    pub(crate) fn set_video_paused(&mut self, paused: bool) {
+        let (from, to) = if paused {
+            (TrackState::Publishing, TrackState::Paused)
+        } else {
+            (TrackState::Paused, TrackState::Publishing)
+        };
+        for track in self
+            .local_tracks
+            .values_mut()
+            .filter(|track| track.kind() == TrackKind::Video && track.state() == from)
+        {
+            track.set_state(to);
+        }
+    }
+
+    /// Refresh the statistics of every local track from transport counters
+    pub fn refresh_track_stats(
+        &mut self,
+        transfer: impl Fn(&TrackNamespace) -> Option<TrackTransferStats>,
+        interval: Duration,
+    ) {
+        for track in self.local_tracks.values_mut() {
+            if let Some(stats) = transfer(&track.moq_track().namespace) {
+                track.refresh_stats(&stats, interval);
+            }
+        }
+    }
+
+    /// Refresh the encoder statistics of every local track, looked up by track ID
+    #[cfg(feature = "media")]
+    pub fn refresh_encode_stats(&mut self, encode: impl Fn(&str) -> Option<EncodeStats>) {
+        for track in self.local_tracks.values_mut() {
+            if let Some(stats) = encode(track.id()) {
+                track.apply_encode_stats(&stats);
+            }
+        }
+    }
+
     /// Get metadata
     pub fn metadata(&self) -> &HashMap<String, String> {
         &self.metadata
@@ -332,6 +554,10 @@ pub struct RemoteParticipant {
     is_muted: bool,
     /// Whether this participant's video is disabled
     video_disabled: bool,
+    /// Role in the room
+    role: ParticipantRole,
+    /// Local playback preferences, shared with the room's audio mixer
+    audio_settings: Arc<ParticipantAudioSettings>,
 }
 
 impl RemoteParticipant {
@@ -350,6 +576,8 @@ impl RemoteParticipant {
             is_speaking: false,
             is_muted: false,
             video_disabled: false,
+            role: ParticipantRole::default(),
+            audio_settings: Arc::new(ParticipantAudioSettings::default()),
         }
     }
 
@@ -375,6 +603,22 @@ impl RemoteParticipant {
         self.name = name;
     }
 
+    /// Get role in the room
+    pub fn role(&self) -> ParticipantRole {
+        self.role
+    }
+
+    /// Set role in the room
+    pub fn set_role(&mut self, role: ParticipantRole) {
+        if self.role != role {
+            debug!(
+                "🎭 Remote participant {} role changed: {:?} -> {:?}",
+                self.id, self.role, role
+            );
+            self.role = role;
+        }
+    }
+
     /// Add a remote track
     pub fn add_remote_track(&mut self, track: RemoteTrack) {
         debug!("📺 Adding remote track: {}", track.id());
@@ -397,6 +641,147 @@ impl RemoteParticipant {
         self.remote_tracks.values()
     }
 
+    /// Refresh the statistics of every track from transport counters
+    pub fn refresh_track_stats(
+        &mut self,
+        transfer: impl Fn(&TrackNamespace) -> Option<TrackTransferStats>,
+        interval: Duration,
+    ) {
+        for track in self.remote_tracks.values_mut() {
+            if let Some(stats) = transfer(&track.moq_track().namespace) {
+                track.refresh_stats(&stats, interval);
+            }
+        }
+    }
+
+    /// Refresh the decoder statistics of every track, looked up by track ID
+    #[cfg(feature = "media")]
+    pub fn refresh_decode_stats(&mut self, decode: impl Fn(&str) -> Option<DecodeStats>) {
+        for track in self.remote_tracks.values_mut() {
+            if let Some(stats) = decode(track.id()) {
+                track.apply_decode_stats(&stats);
+            }
+        }
+    }
+
+    /// Describe every track this participant has announced, sorted by ID
+    pub fn tracks(&self) -> Vec<RemoteTrackInfo> {
+        let mut tracks: Vec<RemoteTrackInfo> =
+            self.remote_tracks.values().map(RemoteTrack::info).collect();
+        tracks.sort_by(|a, b| a.track_id.cmp(&b.track_id));
+        tracks
+    }
+
+    /// Describe one announced track
+    pub fn track_info(&self, track_id: &str) -> Option<RemoteTrackInfo> {
+        self.remote_tracks.get(track_id).map(RemoteTrack::info)
+    }
+
+    /// Update a track's mute state from the publisher
+    ///
+    /// Returns the event to dispatch if the state changed.
+    pub fn set_track_muted(&mut self, track_id: &str, muted: bool) -> Option<Event> {
+        let track = self.remote_tracks.get_mut(track_id)?;
+        if track.is_muted() == muted {
+            return None;
+        }
+        track.set_muted(muted);
+        Some(Event::TrackMuteChanged {
+            track_id: track_id.to_string(),
+            participant_id: self.id.clone(),
+            muted,
+        })
+    }
+
+    /// Update the simulcast layers the publisher offers for a track
+    ///
+    /// Returns the event to dispatch if the layers changed.
+    pub fn set_track_simulcast_layers(
+        &mut self,
+        track_id: &str,
+        layers: Vec<SimulcastLayer>,
+    ) -> Option<Event> {
+        let track = self.remote_tracks.get_mut(track_id)?;
+        if track.simulcast_layers() == layers.as_slice() {
+            return None;
+        }
+        track.set_simulcast_layers(layers.clone());
+        Some(Event::TrackSimulcastLayersChanged {
+            track_id: track_id.to_string(),
+            participant_id: self.id.clone(),
+            layers,
+        })
+    }
+
+    /// Update the publisher's description of a track
+    ///
+    /// Returns the event to dispatch if the description changed.
+    pub fn set_track_metadata(
+        &mut self,
+        track_id: &str,
+        source: TrackSource,
+        metadata: TrackMetadata,
+    ) -> Option<Event> {
+        let track = self.remote_tracks.get_mut(track_id)?;
+        if track.source() == source && track.metadata() == &metadata {
+            return None;
+        }
+        track.set_metadata(source, metadata.clone());
+        Some(Event::TrackMetadataChanged {
+            track_id: track_id.to_string(),
+            participant_id: self.id.clone(),
+            source,
+            metadata,
+        })
+    }
+
+    /// Record whether we are subscribed to a track
+    ///
+    /// Returns the event to dispatch if the state changed.
+    pub(crate) fn set_track_subscribed(
+        &mut self,
+        track_id: &str,
+        subscribed: bool,
+    ) -> Option<Event> {
+        let track = self.remote_tracks.get_mut(track_id)?;
+        if track.is_subscribed() == subscribed {
+            return None;
+        }
+        track.set_subscribed(subscribed);
+        let track_id = track_id.to_string();
+        let participant_id = self.id.clone();
+        Some(if subscribed {
+            Event::TrackSubscribed {
+                track_id,
+                participant_id,
+            }
+        } else {
+            Event::TrackUnsubscribed {
+                track_id,
+                participant_id,
+            }
+        })
+    }
+
+    /// Attach or detach a track's time-shift buffer
+    pub(crate) fn set_track_dvr(&mut self, track_id: &str, dvr: Option<quicrtc_core::DvrHandle>) {
+        if let Some(track) = self.remote_tracks.get_mut(track_id) {
+            track.set_dvr(dvr);
+        }
+    }
+
+    /// Record the playout configuration chosen for a track
+    #[cfg(feature = "media")]
+    pub(crate) fn set_track_playback(
+        &mut self,
+        track_id: &str,
+        playback: quicrtc_media::PlaybackConfig,
+    ) {
+        if let Some(track) = self.remote_tracks.get_mut(track_id) {
+            track.set_playback_config(playback);
+        }
+    }
+
     /// Get metadata
     pub fn metadata(&self) -> &HashMap<String, String> {
         &self.metadata
@@ -512,6 +897,46 @@ impl RemoteParticipant {
         }
     }
 
+    /// Get local playback volume for this participant (0.0 to 1.0)
+    pub fn volume(&self) -> f32 {
+        self.audio_settings.volume()
+    }
+
+    /// Set local playback volume for this participant (clamped to 0.0 to 1.0)
+    ///
+    /// Applies to every clone of this participant and takes effect in the
+    /// room's audio mixer on the next frame.
+    pub fn set_volume(&self, volume: f32) {
+        let volume = volume.clamp(0.0, 1.0);
+        debug!(
+            "🔊 Remote participant {} volume changed: {:.2}",
+            self.id, volume
+        );
+        self.audio_settings.set_volume(volume);
+    }
+
+    /// Check if this participant's audio is played locally
+    pub fn is_audio_enabled(&self) -> bool {
+        self.audio_settings.is_enabled()
+    }
+
+    /// Locally mute or unmute this participant's audio
+    ///
+    /// This only affects local playback; the participant keeps publishing.
+    pub fn set_audio_enabled(&self, enabled: bool) {
+        debug!(
+            "🔇 Remote participant {} local audio enabled: {}",
+            self.id, enabled
+        );
+        self.audio_settings.set_enabled(enabled);
+    }
+
+    /// Shared audio settings for mixer registration
+    #[cfg(feature = "media")]
+    pub(crate) fn audio_settings(&self) -> Arc<ParticipantAudioSettings> {
+        self.audio_settings.clone()
+    }
+
     /// Check if participant is actively connected
     pub fn is_connected(&self) -> bool {
         matches!(self.connection_state, ParticipantConnectionState::Connected)
@@ -527,6 +952,53 @@ impl RemoteParticipant {
     }
 }
 
+/// Local playback preferences for a remote participant's audio
+#[derive(Debug)]
+pub(crate) struct ParticipantAudioSettings {
+    /// Volume stored as `f32` bits
+    volume: AtomicU32,
+    /// Whether audio is played at all
+    enabled: AtomicBool,
+}
+
+impl ParticipantAudioSettings {
+    fn volume(&self) -> f32 {
+        f32::from_bits(self.volume.load(Ordering::Relaxed))
+    }
+
+    fn set_volume(&self, volume: f32) {
+        self.volume.store(volume.to_bits(), Ordering::Relaxed);
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+}
+
+impl Default for ParticipantAudioSettings {
+    fn default() -> Self {
+        Self {
+            volume: AtomicU32::new(1.0f32.to_bits()),
+            enabled: AtomicBool::new(true),
+        }
+    }
+}
+
+#[cfg(feature = "media")]
+impl quicrtc_media::MixerGain for ParticipantAudioSettings {
+    fn gain(&self) -> f32 {
+        if self.is_enabled() {
+            self.volume()
+        } else {
+            0.0
+        }
+    }
+}
+
 /// Participant connection state
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ParticipantConnectionState {
@@ -590,3 +1062,246 @@ pub enum ParticipantError {
         reason: String,
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remote_participant_audio_preferences() {
+        let participant = RemoteParticipant::new("bob".to_string());
+        assert_eq!(participant.volume(), 1.0);
+        assert!(participant.is_audio_enabled());
+
+        // Settings are shared between clones and volume is clamped
+        let clone = participant.clone();
+        clone.set_volume(1.5);
+        clone.set_audio_enabled(false);
+        assert_eq!(participant.volume(), 1.0);
+        assert!(!participant.is_audio_enabled());
+
+        participant.set_volume(0.25);
+        assert_eq!(clone.volume(), 0.25);
+    }
+
+    #[test]
+    fn test_audio_preferences_persist_across_rejoin() {
+        let mut participants = Participants::new();
+
+        let participant = RemoteParticipant::new("bob".to_string());
+        participants.add_remote_participant(participant).unwrap();
+        let bob = participants.get_remote_participant("bob").unwrap();
+        bob.set_volume(0.4);
+        bob.set_audio_enabled(false);
+
+        participants.remove_remote_participant("bob");
+        assert!(!participants.contains_participant("bob"));
+
+        // A fresh participant object with the same ID picks up the old settings
+        let rejoined = RemoteParticipant::new("bob".to_string());
+        participants.add_remote_participant(rejoined).unwrap();
+        let bob = participants.get_remote_participant("bob").unwrap();
+        assert_eq!(bob.volume(), 0.4);
+        assert!(!bob.is_audio_enabled());
+    }
+
+    #[test]
+    fn test_participants_queries() {
+        let mut participants = Participants::new();
+        for id in ["alice", "bob", "carol"] {
+            participants
+                .add_remote_participant(RemoteParticipant::new(id.to_string()))
+                .unwrap();
+        }
+        participants.update_participant("bob", |p| p.set_role(ParticipantRole::Subscriber));
+        participants.update_participant("carol", |p| {
+            p.set_role(ParticipantRole::Subscriber);
+            p.set_speaking(true);
+        });
+
+        assert_eq!(participants.iter().count(), 3);
+        assert_eq!((&participants).into_iter().count(), 3);
+        assert_eq!(participants.count_by_role(ParticipantRole::Publisher), 1);
+        assert_eq!(participants.role_counts()[&ParticipantRole::Subscriber], 2);
+        assert!(!participants
+            .role_counts()
+            .contains_key(&ParticipantRole::Admin));
+
+        let speaking: Vec<&str> = participants.speaking().map(|p| p.id()).collect();
+        assert_eq!(speaking, ["carol"]);
+
+        assert_eq!(participants.tracks_of("alice").unwrap().len(), 0);
+        assert!(participants.tracks_of("dave").is_none());
+        assert_eq!(participants.tracks_by_participant().len(), 3);
+        assert!(participants.update_participant("dave", |_| ()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_participants_watch() {
+        let mut participants = Participants::new();
+        let mut watcher = participants.watch();
+        assert_eq!(watcher.borrow().version, 0);
+
+        participants
+            .add_remote_participant(RemoteParticipant::new("bob".to_string()))
+            .unwrap();
+        participants
+            .add_remote_participant(RemoteParticipant::new("alice".to_string()))
+            .unwrap();
+        watcher.changed().await.unwrap();
+        {
+            let snapshot = watcher.borrow_and_update();
+            assert_eq!(snapshot.version, 2);
+            assert_eq!(snapshot.participant_ids, ["alice", "bob"]);
+        }
+
+        participants.update_participant("bob", |p| p.set_speaking(true));
+        watcher.changed().await.unwrap();
+        assert_eq!(watcher.borrow_and_update().speaking, ["bob"]);
+
+        // Removing someone who is not in the room is not a change
+        participants.remove_remote_participant("dave");
+        assert!(!watcher.has_changed().unwrap());
+
+        participants.remove_remote_participant("bob");
+        watcher.changed().await.unwrap();
+        assert_eq!(watcher.borrow().participant_ids, ["alice"]);
+        assert_eq!(participants.snapshot(), *watcher.borrow());
+    }
+
+    #[cfg(feature = "media")]
+    #[test]
+    fn test_remote_track_introspection() {
+        use crate::track::TrackSource;
+        use quicrtc_core::{MoqTrack, MoqTrackType, TrackNamespace};
+
+        let moq_track = MoqTrack::new(
+            TrackNamespace {
+                namespace: "room.standup".to_string(),
+                track_name: "bob/camera".to_string(),
+            },
+            "camera".to_string(),
+            MoqTrackType::Video,
+        );
+        let mut bob = RemoteParticipant::new("bob".to_string());
+        bob.add_remote_track(RemoteTrack::video(
+            "video-1".to_string(),
+            "bob".to_string(),
+            TrackSource::Camera,
+            moq_track,
+        ));
+
+        let tracks = bob.tracks();
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].name, "camera");
+        assert!(tracks[0].subscribed);
+        assert!(!tracks[0].muted);
+        assert!(tracks[0].simulcast_layers.is_empty());
+
+        // Changes produce typed events only when state actually changes
+        assert!(matches!(
+            bob.set_track_muted("video-1", true),
+            Some(Event::TrackMuteChanged { muted: true, .. })
+        ));
+        assert!(bob.set_track_muted("video-1", true).is_none());
+        assert!(bob.set_track_muted("missing", true).is_none());
+
+        let layers = vec![
+            SimulcastLayer::new("q", 320, 180, 150),
+            SimulcastLayer::new("f", 1280, 720, 1500),
+        ];
+        assert!(matches!(
+            bob.set_track_simulcast_layers("video-1", layers.clone()),
+            Some(Event::TrackSimulcastLayersChanged { .. })
+        ));
+        assert!(matches!(
+            bob.set_track_subscribed("video-1", false),
+            Some(Event::TrackUnsubscribed { .. })
+        ));
+
+        let metadata = TrackMetadata {
+            label: Some("Slides".to_string()),
+            dimensions: Some((1920, 1080)),
+            codec: Some("h264".to_string()),
+        };
+        assert!(matches!(
+            bob.set_track_metadata("video-1", TrackSource::Screen, metadata.clone()),
+            Some(Event::TrackMetadataChanged {
+                source: TrackSource::Screen,
+                ..
+            })
+        ));
+        assert!(bob
+            .set_track_metadata("video-1", TrackSource::Screen, metadata.clone())
+            .is_none());
+
+        let info = bob.track_info("video-1").unwrap();
+        assert!(info.muted);
+        assert!(!info.subscribed);
+        assert_eq!(info.simulcast_layers, layers);
+        assert_eq!(info.source, TrackSource::Screen);
+        assert_eq!(info.metadata, metadata);
+    }
+
+    #[cfg(feature = "media")]
+    #[test]
+    fn test_track_stats_refresh() {
+        use crate::track::TrackSource;
+        use quicrtc_core::{MoqObject, MoqObjectStatus, MoqTrack, MoqTrackType};
+
+        let namespace = TrackNamespace {
+            namespace: "room.standup".to_string(),
+            track_name: "bob/camera".to_string(),
+        };
+        let moq_track = MoqTrack::new(namespace.clone(), "camera".to_string(), MoqTrackType::Video);
+        let mut bob = RemoteParticipant::new("bob".to_string());
+        bob.add_remote_track(RemoteTrack::video(
+            "video-1".to_string(),
+            "bob".to_string(),
+            TrackSource::Camera,
+            moq_track,
+        ));
+
+        // 30 objects of 1000 bytes in one second, with object 10 missing
+        let start = Instant::now();
+        let mut transfer = TrackTransferStats::new();
+        for i in (0..31).filter(|i| *i != 10) {
+            let mut object = MoqObject {
+                track_namespace: namespace.clone(),
+                track_name: "camera".to_string(),
+                group_id: 0,
+                object_id: i,
+                publisher_priority: 1,
+                payload: vec![0; 1000],
+                object_status: MoqObjectStatus::Normal,
+                created_at: start,
+                size: 1000,
+                metadata: Default::default(),
+            };
+            object.metadata.set_video_resolution(1280, 720).unwrap();
+            transfer.record_received(&object, start + Duration::from_millis(i * 33));
+        }
+
+        let other = TrackNamespace {
+            namespace: "room.standup".to_string(),
+            track_name: "bob/microphone".to_string(),
+        };
+        bob.refresh_track_stats(
+            |track| (*track == namespace).then(|| transfer.clone()),
+            Duration::from_secs(1),
+        );
+        bob.refresh_track_stats(
+            |track| (*track == other).then(TrackTransferStats::new),
+            Duration::from_secs(1),
+        );
+
+        let stats = bob.get_remote_track("video-1").unwrap().stats();
+        assert_eq!(stats.objects_received, 30);
+        assert_eq!(stats.packets_lost, 1);
+        assert_eq!(stats.bytes_transferred, 30_000);
+        assert_eq!(stats.current_bitrate, 240_000);
+        assert_eq!(stats.current_framerate, 30.0);
+        assert_eq!(stats.current_resolution, Some((1280, 720)));
+        assert_eq!(stats.freeze_count, 0);
+    }
+}
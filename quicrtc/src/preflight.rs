@@ -0,0 +1,575 @@
+//! Pre-call hardware and network checks
+//!
+//! Runs a short echo test (record from the microphone, play it back through the
+//! speakers) and a network probe, and returns a [`PreflightReport`] the
+//! application can show before joining a room.
+//!
+//! ```rust,no_run
+//! use quicrtc::preflight::{self, PreflightConfig};
+//!
+//! # async fn example() -> Result<(), quicrtc::QuicRtcError> {
+//! let report = preflight::run(PreflightConfig::default()).await?;
+//! if !report.is_ready() {
+//!     for issue in report.issues() {
+//!         println!("⚠️ {}", issue);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::QuicRtcError;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Configuration for a pre-call check
+#[derive(Debug, Clone)]
+pub struct PreflightConfig {
+    /// Run the microphone/speaker echo test
+    pub check_audio: bool,
+    /// Play the recording back through the output device
+    pub playback: bool,
+    /// Run the network probe
+    pub check_network: bool,
+    /// How long to record from the microphone
+    pub record_duration: Duration,
+    /// Input device name (None for default device)
+    pub input_device: Option<String>,
+    /// Output device name (None for default device)
+    pub output_device: Option<String>,
+    /// Peak level below which the microphone is considered silent (dBFS)
+    pub min_input_level_dbfs: f32,
+    /// Latency above which the network is flagged
+    pub max_latency: Duration,
+    /// Packet loss rate above which the network is flagged (0.0 to 1.0)
+    pub max_packet_loss: f64,
+    /// Bandwidth below which the network is flagged (bits per second)
+    pub min_bandwidth: u64,
+}
+
+impl Default for PreflightConfig {
+    fn default() -> Self {
+        Self {
+            check_audio: true,
+            playback: true,
+            check_network: true,
+            record_duration: Duration::from_secs(3),
+            input_device: None,
+            output_device: None,
+            min_input_level_dbfs: -50.0,
+            max_latency: Duration::from_millis(150),
+            max_packet_loss: 0.05,
+            min_bandwidth: 500_000,
+        }
+    }
+}
+
+/// Outcome of a single preflight check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    /// Check passed
+    Passed,
+    /// Check completed but found something the user should know about
+    Warning,
+    /// Check failed
+    Failed,
+    /// Check was not run
+    Skipped,
+}
+
+/// Measured audio level
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioLevel {
+    /// RMS level in dBFS
+    pub rms_dbfs: f32,
+    /// Peak level in dBFS
+    pub peak_dbfs: f32,
+}
+
+impl AudioLevel {
+    /// Floor used for digital silence
+    pub const SILENCE_DBFS: f32 = -100.0;
+
+    /// Measure the level of a block of f32 PCM samples
+    pub fn from_samples(samples: &[f32]) -> Self {
+        if samples.is_empty() {
+            return Self {
+                rms_dbfs: Self::SILENCE_DBFS,
+                peak_dbfs: Self::SILENCE_DBFS,
+            };
+        }
+
+        let peak = samples.iter().fold(0.0f32, |acc, s| acc.max(s.abs()));
+        let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+
+        Self {
+            rms_dbfs: Self::to_dbfs(rms),
+            peak_dbfs: Self::to_dbfs(peak),
+        }
+    }
+
+    fn to_dbfs(linear: f32) -> f32 {
+        if linear <= 0.0 {
+            Self::SILENCE_DBFS
+        } else {
+            (20.0 * linear.log10()).max(Self::SILENCE_DBFS)
+        }
+    }
+}
+
+/// Microphone check result
+#[derive(Debug, Clone)]
+pub struct MicrophoneCheck {
+    /// Check outcome
+    pub status: CheckStatus,
+    /// Device that was recorded from (None for default device)
+    pub device: Option<String>,
+    /// Measured input level
+    pub level: Option<AudioLevel>,
+    /// Number of samples captured
+    pub samples_captured: usize,
+    /// Sample rate of the recording
+    pub sample_rate: u32,
+    /// Channel count of the recording
+    pub channels: u8,
+    /// Human-readable detail for warnings and failures
+    pub detail: Option<String>,
+}
+
+/// Speaker check result
+#[derive(Debug, Clone)]
+pub struct SpeakerCheck {
+    /// Check outcome
+    pub status: CheckStatus,
+    /// Device that was played back on (None for default device)
+    pub device: Option<String>,
+    /// Number of frames handed to the output device
+    pub frames_played: u64,
+    /// Human-readable detail for warnings and failures
+    pub detail: Option<String>,
+}
+
+/// Network probe result
+#[derive(Debug, Clone)]
+pub struct NetworkCheck {
+    /// Check outcome
+    pub status: CheckStatus,
+    /// Available bandwidth (bits per second)
+    pub bandwidth: Option<u64>,
+    /// Network latency
+    pub latency: Option<Duration>,
+    /// Packet loss rate (0.0 to 1.0)
+    pub packet_loss: Option<f64>,
+    /// Network jitter
+    pub jitter: Option<Duration>,
+    /// Human-readable detail for warnings and failures
+    pub detail: Option<String>,
+}
+
+/// Structured result of a pre-call check
+#[derive(Debug, Clone)]
+pub struct PreflightReport {
+    /// Microphone check
+    pub microphone: MicrophoneCheck,
+    /// Speaker check
+    pub speaker: SpeakerCheck,
+    /// Network check
+    pub network: NetworkCheck,
+    /// Total time spent running checks
+    pub elapsed: Duration,
+}
+
+impl PreflightReport {
+    /// Whether no check failed
+    pub fn is_ready(&self) -> bool {
+        self.statuses().iter().all(|s| *s != CheckStatus::Failed)
+    }
+
+    /// Whether any check produced a warning
+    pub fn has_warnings(&self) -> bool {
+        self.statuses().contains(&CheckStatus::Warning)
+    }
+
+    /// Details of every warning or failure, prefixed with the check name
+    pub fn issues(&self) -> Vec<String> {
+        [
+            (
+                "Microphone",
+                self.microphone.status,
+                &self.microphone.detail,
+            ),
+            ("Speaker", self.speaker.status, &self.speaker.detail),
+            ("Network", self.network.status, &self.network.detail),
+        ]
+        .into_iter()
+        .filter(|(_, status, _)| matches!(status, CheckStatus::Warning | CheckStatus::Failed))
+        .map(|(name, _, detail)| {
+            format!(
+                "{}: {}",
+                name,
+                detail.as_deref().unwrap_or("check did not pass")
+            )
+        })
+        .collect()
+    }
+
+    fn statuses(&self) -> [CheckStatus; 3] {
+        [
+            self.microphone.status,
+            self.speaker.status,
+            self.network.status,
+        ]
+    }
+}
+
+/// Run a pre-call check with the given configuration
+///
+/// Individual check failures are reported in the [`PreflightReport`] rather
+/// than returned as errors, so the application can show all of them at once.
+pub async fn run(config: PreflightConfig) -> Result<PreflightReport, QuicRtcError> {
+    info!("🩺 Running preflight checks");
+    let started = Instant::now();
+
+    let (microphone, speaker) = if config.check_audio {
+        check_audio(&config).await
+    } else {
+        (
+            skipped_microphone(&config, "Audio check disabled"),
+            skipped_speaker(&config, "Audio check disabled"),
+        )
+    };
+
+    let network = if config.check_network {
+        check_network(&config).await
+    } else {
+        skipped_network("Network check disabled")
+    };
+
+    let report = PreflightReport {
+        microphone,
+        speaker,
+        network,
+        elapsed: started.elapsed(),
+    };
+
+    if report.is_ready() {
+        info!("✅ Preflight checks completed in {:?}", report.elapsed);
+    } else {
+        warn!("⚠️ Preflight checks found problems: {:?}", report.issues());
+    }
+
+    Ok(report)
+}
+
+/// Record from the microphone and play the recording back
+#[cfg(feature = "media")]
+async fn check_audio(config: &PreflightConfig) -> (MicrophoneCheck, SpeakerCheck) {
+    use quicrtc_media::{
+        AudioCaptureConfig, AudioFrame, AudioRenderConfig, AudioRenderer, CpalAudioCapture,
+        CpalAudioRenderer,
+    };
+
+    tracing::debug!("🎙️ Recording {:?} from microphone", config.record_duration);
+    let capture = CpalAudioCapture::new();
+    let recording = match capture
+        .record(AudioCaptureConfig {
+            device_name: config.input_device.clone(),
+            duration: config.record_duration,
+        })
+        .await
+    {
+        Ok(recording) => recording,
+        Err(e) => {
+            let microphone = MicrophoneCheck {
+                status: CheckStatus::Failed,
+                detail: Some(format!("Recording failed: {}", e)),
+                ..skipped_microphone(config, "")
+            };
+            return (
+                microphone,
+                skipped_speaker(config, "No recording to play back"),
+            );
+        }
+    };
+
+    let level = AudioLevel::from_samples(&recording.samples);
+    let (status, detail) = if recording.samples.is_empty() {
+        (
+            CheckStatus::Failed,
+            Some("No audio was captured from the microphone".to_string()),
+        )
+    } else if level.peak_dbfs < config.min_input_level_dbfs {
+        (
+            CheckStatus::Warning,
+            Some(format!(
+                "Input level is very low ({:.1} dBFS peak) - check the microphone is not muted",
+                level.peak_dbfs
+            )),
+        )
+    } else {
+        (CheckStatus::Passed, None)
+    };
+
+    let microphone = MicrophoneCheck {
+        status,
+        device: config.input_device.clone(),
+        level: Some(level),
+        samples_captured: recording.samples.len(),
+        sample_rate: recording.sample_rate,
+        channels: recording.channels,
+        detail,
+    };
+
+    if !config.playback {
+        return (microphone, skipped_speaker(config, "Playback disabled"));
+    }
+    if recording.samples.is_empty() {
+        return (
+            microphone,
+            skipped_speaker(config, "No recording to play back"),
+        );
+    }
+
+    tracing::debug!("🔊 Playing recording back");
+    let mut renderer = CpalAudioRenderer::new();
+    let render_config = AudioRenderConfig {
+        sample_rate: recording.sample_rate,
+        channels: recording.channels,
+        device_name: config.output_device.clone(),
+        ..Default::default()
+    };
+
+    let sender = match renderer.start(render_config) {
+        Ok(sender) => sender,
+        Err(e) => {
+            let speaker = SpeakerCheck {
+                status: CheckStatus::Failed,
+                detail: Some(format!("Could not open output device: {}", e)),
+                ..skipped_speaker(config, "")
+            };
+            return (microphone, speaker);
+        }
+    };
+
+    // Hand the recording over in 20ms frames, paced in real time
+    let frame_len = (recording.sample_rate as usize / 50) * recording.channels.max(1) as usize;
+    let mut frames_played = 0u64;
+    let mut send_error = None;
+    for (i, chunk) in recording.samples.chunks(frame_len.max(1)).enumerate() {
+        let frame = AudioFrame {
+            samples: chunk.to_vec(),
+            sample_rate: recording.sample_rate,
+            channels: recording.channels,
+            timestamp: i as u64 * 20,
+        };
+        if let Err(e) = sender.send(frame).await {
+            send_error = Some(format!("Output stream closed during playback: {}", e));
+            break;
+        }
+        frames_played += 1;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    let _ = renderer.stop();
+
+    let speaker = SpeakerCheck {
+        status: if send_error.is_some() {
+            CheckStatus::Failed
+        } else {
+            CheckStatus::Passed
+        },
+        device: config.output_device.clone(),
+        frames_played,
+        detail: send_error,
+    };
+
+    (microphone, speaker)
+}
+
+#[cfg(not(feature = "media"))]
+async fn check_audio(config: &PreflightConfig) -> (MicrophoneCheck, SpeakerCheck) {
+    (
+        skipped_microphone(config, "Media support not enabled"),
+        skipped_speaker(config, "Media support not enabled"),
+    )
+}
+
+/// Run the network profiler probe
+#[cfg(feature = "diagnostics")]
+async fn check_network(config: &PreflightConfig) -> NetworkCheck {
+    use quicrtc_diagnostics::NetworkProfiler;
+
+    tracing::debug!("🌐 Probing network conditions");
+    let profiler = NetworkProfiler::new();
+    if let Err(e) = profiler.start_profiling().await {
+        return NetworkCheck {
+            status: CheckStatus::Failed,
+            detail: Some(format!("Network probe failed: {}", e)),
+            ..skipped_network("")
+        };
+    }
+    let conditions = profiler.get_network_conditions();
+    let _ = profiler.stop_profiling().await;
+
+    let mut problems = Vec::new();
+    if conditions.latency > config.max_latency {
+        problems.push(format!("high latency ({:?})", conditions.latency));
+    }
+    if conditions.packet_loss > config.max_packet_loss {
+        problems.push(format!(
+            "packet loss of {:.1}%",
+            conditions.packet_loss * 100.0
+        ));
+    }
+    if conditions.bandwidth < config.min_bandwidth {
+        problems.push(format!(
+            "low bandwidth ({} kbps)",
+            conditions.bandwidth / 1000
+        ));
+    }
+
+    NetworkCheck {
+        status: if problems.is_empty() {
+            CheckStatus::Passed
+        } else {
+            CheckStatus::Warning
+        },
+        bandwidth: Some(conditions.bandwidth),
+        latency: Some(conditions.latency),
+        packet_loss: Some(conditions.packet_loss),
+        jitter: Some(conditions.jitter),
+        detail: if problems.is_empty() {
+            None
+        } else {
+            Some(format!(
+                "Network may degrade call quality: {}",
+                problems.join(", ")
+            ))
+        },
+    }
+}
+
+#[cfg(not(feature = "diagnostics"))]
+async fn check_network(_config: &PreflightConfig) -> NetworkCheck {
+    skipped_network("Diagnostics support not enabled")
+}
+
+fn skipped_microphone(config: &PreflightConfig, reason: &str) -> MicrophoneCheck {
+    MicrophoneCheck {
+        status: CheckStatus::Skipped,
+        device: config.input_device.clone(),
+        level: None,
+        samples_captured: 0,
+        sample_rate: 0,
+        channels: 0,
+        detail: Some(reason.to_string()),
+    }
+}
+
+fn skipped_speaker(config: &PreflightConfig, reason: &str) -> SpeakerCheck {
+    SpeakerCheck {
+        status: CheckStatus::Skipped,
+        device: config.output_device.clone(),
+        frames_played: 0,
+        detail: Some(reason.to_string()),
+    }
+}
+
+fn skipped_network(reason: &str) -> NetworkCheck {
+    NetworkCheck {
+        status: CheckStatus::Skipped,
+        bandwidth: None,
+        latency: None,
+        packet_loss: None,
+        jitter: None,
+        detail: Some(reason.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audio_level_silence() {
+        let level = AudioLevel::from_samples(&[0.0; 480]);
+        assert_eq!(level.rms_dbfs, AudioLevel::SILENCE_DBFS);
+        assert_eq!(level.peak_dbfs, AudioLevel::SILENCE_DBFS);
+
+        let empty = AudioLevel::from_samples(&[]);
+        assert_eq!(empty.peak_dbfs, AudioLevel::SILENCE_DBFS);
+    }
+
+    #[test]
+    fn test_audio_level_full_scale() {
+        let samples: Vec<f32> = (0..480)
+            .map(|i| if i % 2 == 0 { 1.0 } else { -1.0 })
+            .collect();
+        let level = AudioLevel::from_samples(&samples);
+        assert!(level.peak_dbfs.abs() < 0.01);
+        assert!(level.rms_dbfs.abs() < 0.01);
+
+        let half = AudioLevel::from_samples(&[0.5, -0.5]);
+        assert!((half.peak_dbfs + 6.02).abs() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn test_preflight_with_all_checks_disabled() {
+        let config = PreflightConfig {
+            check_audio: false,
+            check_network: false,
+            ..Default::default()
+        };
+
+        let report = run(config).await.unwrap();
+        assert_eq!(report.microphone.status, CheckStatus::Skipped);
+        assert_eq!(report.speaker.status, CheckStatus::Skipped);
+        assert_eq!(report.network.status, CheckStatus::Skipped);
+        assert!(report.is_ready());
+        assert!(!report.has_warnings());
+        assert!(report.issues().is_empty());
+    }
+
+    #[test]
+    fn test_preflight_report_issues() {
+        let config = PreflightConfig::default();
+        let report = PreflightReport {
+            microphone: MicrophoneCheck {
+                status: CheckStatus::Failed,
+                detail: Some("Recording failed".to_string()),
+                ..skipped_microphone(&config, "")
+            },
+            speaker: skipped_speaker(&config, "No recording to play back"),
+            network: NetworkCheck {
+                status: CheckStatus::Warning,
+                detail: Some("high latency".to_string()),
+                ..skipped_network("")
+            },
+            elapsed: Duration::ZERO,
+        };
+
+        assert!(!report.is_ready());
+        assert!(report.has_warnings());
+        assert_eq!(
+            report.issues(),
+            vec![
+                "Microphone: Recording failed".to_string(),
+                "Network: high latency".to_string()
+            ]
+        );
+    }
+
+    #[cfg(feature = "diagnostics")]
+    #[tokio::test]
+    async fn test_preflight_network_probe() {
+        let config = PreflightConfig {
+            check_audio: false,
+            ..Default::default()
+        };
+
+        let report = run(config).await.unwrap();
+        assert_ne!(report.network.status, CheckStatus::Skipped);
+        assert!(report.network.latency.is_some());
+        assert!(report.network.bandwidth.is_some());
+    }
+}
@@ -0,0 +1,315 @@
+//! User-facing connection quality levels
+//!
+//! Apps show connection quality as a few bars rather than loss and RTT
+//! figures. A [`QualityMonitor`] turns the statistics refreshed every
+//! [`RoomBuilder::stats_interval`](crate::RoomBuilder::stats_interval) into
+//! a [`QualityLevel`], for the connection as a whole and for each track.
+//!
+//! Levels come from packet loss, round-trip time and the bitrate trend: a
+//! bitrate collapsing well below its recent average costs a level even
+//! while loss and RTT look fine. So the bars don't flicker, a new level is
+//! only taken once it has held for a few refreshes in a row, and
+//! recovering takes longer than degrading.
+
+use crate::stats_history::ConnectionSample;
+use crate::track::TrackStats;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Connection quality as shown to users
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum QualityLevel {
+    /// Calls can't work: heavy loss or very high latency
+    Critical,
+    /// Noticeable glitches and delay
+    Poor,
+    /// Minor degradation
+    Good,
+    /// No degradation
+    Excellent,
+}
+
+impl QualityLevel {
+    /// Bars of a signal indicator, from 1 for critical to 4 for excellent
+    pub fn bars(&self) -> u8 {
+        match self {
+            QualityLevel::Critical => 1,
+            QualityLevel::Poor => 2,
+            QualityLevel::Good => 3,
+            QualityLevel::Excellent => 4,
+        }
+    }
+
+    fn lower(self) -> Self {
+        match self {
+            QualityLevel::Excellent => QualityLevel::Good,
+            QualityLevel::Good => QualityLevel::Poor,
+            QualityLevel::Poor | QualityLevel::Critical => QualityLevel::Critical,
+        }
+    }
+}
+
+/// Measurements a quality level is derived from
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct QualitySample {
+    /// Packet loss percentage (0-100)
+    pub loss_percentage: f64,
+    /// Round-trip time in milliseconds, if measured
+    pub rtt_ms: Option<f64>,
+    /// Current bitrate in bps
+    pub bitrate: u64,
+}
+
+impl QualitySample {
+    /// Sample of one track's statistics
+    pub fn from_track(stats: &TrackStats) -> Self {
+        Self {
+            loss_percentage: stats.packet_loss_percentage(),
+            rtt_ms: stats.rtt_ms,
+            bitrate: u64::from(stats.current_bitrate),
+        }
+    }
+
+    /// Sample of the connection, from the totals of its tracks
+    pub fn from_connection(connection: &ConnectionSample, tracks: &[&TrackStats]) -> Self {
+        let (lost, transferred) = tracks.iter().fold((0, 0), |(lost, transferred), stats| {
+            (
+                lost + stats.packets_lost,
+                transferred + stats.packets_transferred,
+            )
+        });
+        let loss_percentage = if lost + transferred == 0 {
+            0.0
+        } else {
+            lost as f64 / (lost + transferred) as f64 * 100.0
+        };
+        Self {
+            loss_percentage,
+            rtt_ms: connection.rtt_ms,
+            bitrate: connection.send_bitrate + connection.receive_bitrate,
+        }
+    }
+}
+
+/// Where levels start and how quickly they change
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QualityThresholds {
+    /// Highest loss percentage for excellent, good and poor; anything above
+    /// is critical
+    pub loss_percentage: [f64; 3],
+    /// Highest round-trip time in milliseconds for excellent, good and
+    /// poor; anything above is critical
+    pub rtt_ms: [f64; 3],
+    /// Fraction of its recent average the bitrate can fall to before
+    /// costing a level
+    pub bitrate_drop: f64,
+    /// Refreshes in a row a lower level must hold before it is taken
+    pub degrade_after: u32,
+    /// Refreshes in a row a higher level must hold before it is taken
+    pub recover_after: u32,
+}
+
+impl Default for QualityThresholds {
+    fn default() -> Self {
+        Self {
+            loss_percentage: [1.0, 3.0, 10.0],
+            rtt_ms: [150.0, 300.0, 600.0],
+            bitrate_drop: 0.5,
+            degrade_after: 2,
+            recover_after: 5,
+        }
+    }
+}
+
+impl QualityThresholds {
+    fn level_of(&self, value: f64, limits: &[f64; 3]) -> QualityLevel {
+        if value <= limits[0] {
+            QualityLevel::Excellent
+        } else if value <= limits[1] {
+            QualityLevel::Good
+        } else if value <= limits[2] {
+            QualityLevel::Poor
+        } else {
+            QualityLevel::Critical
+        }
+    }
+}
+
+/// Weight of a new bitrate in the running average the trend compares with
+const BITRATE_AVERAGE_WEIGHT: f64 = 0.2;
+
+/// Quality level of one connection or track, with hysteresis
+#[derive(Debug, Clone)]
+pub struct QualityMonitor {
+    thresholds: QualityThresholds,
+    level: Option<QualityLevel>,
+    /// Level the measurements point at but that hasn't held long enough
+    pending: Option<(QualityLevel, u32)>,
+    average_bitrate: Option<f64>,
+}
+
+impl QualityMonitor {
+    /// Monitor deciding levels by `thresholds`
+    pub fn new(thresholds: QualityThresholds) -> Self {
+        Self {
+            thresholds,
+            level: None,
+            pending: None,
+            average_bitrate: None,
+        }
+    }
+
+    /// Current level, `None` before the first sample
+    pub fn level(&self) -> Option<QualityLevel> {
+        self.level
+    }
+
+    /// Feed the latest measurements
+    ///
+    /// Returns the new level when it changed. The first sample sets the
+    /// level straight away.
+    pub fn update(&mut self, sample: QualitySample) -> Option<QualityLevel> {
+        let measured = self.measure(&sample);
+        let Some(current) = self.level else {
+            self.level = Some(measured);
+            return self.level;
+        };
+        if measured == current {
+            self.pending = None;
+            return None;
+        }
+
+        let held = match self.pending {
+            Some((level, held)) if level == measured => held + 1,
+            _ => 1,
+        };
+        let needed = if measured < current {
+            self.thresholds.degrade_after
+        } else {
+            self.thresholds.recover_after
+        };
+        if held < needed.max(1) {
+            self.pending = Some((measured, held));
+            return None;
+        }
+        self.pending = None;
+        self.level = Some(measured);
+        self.level
+    }
+
+    /// Level the measurements point at, before hysteresis
+    fn measure(&mut self, sample: &QualitySample) -> QualityLevel {
+        let thresholds = &self.thresholds;
+        let mut level = thresholds.level_of(sample.loss_percentage, &thresholds.loss_percentage);
+        if let Some(rtt) = sample.rtt_ms {
+            level = level.min(thresholds.level_of(rtt, &thresholds.rtt_ms));
+        }
+
+        // A track that stopped sending, such as a muted one, isn't congested
+        if sample.bitrate == 0 {
+            return level;
+        }
+        let bitrate = sample.bitrate as f64;
+        if let Some(average) = self.average_bitrate {
+            if bitrate < average * thresholds.bitrate_drop {
+                level = level.lower();
+            }
+        }
+        self.average_bitrate = Some(match self.average_bitrate {
+            Some(average) => average + (bitrate - average) * BITRATE_AVERAGE_WEIGHT,
+            None => bitrate,
+        });
+        level
+    }
+}
+
+impl Default for QualityMonitor {
+    fn default() -> Self {
+        Self::new(QualityThresholds::default())
+    }
+}
+
+/// Quality monitors of a room's connection and tracks
+#[derive(Debug, Default)]
+pub(crate) struct RoomQuality {
+    connection: QualityMonitor,
+    tracks: HashMap<String, QualityMonitor>,
+}
+
+impl RoomQuality {
+    /// Feed the connection's measurements, returning its new level if it
+    /// changed
+    pub(crate) fn update_connection(&mut self, sample: QualitySample) -> Option<QualityLevel> {
+        self.connection.update(sample)
+    }
+
+    /// Feed a track's measurements, returning its new level if it changed
+    pub(crate) fn update_track(
+        &mut self,
+        track_id: &str,
+        sample: QualitySample,
+    ) -> Option<QualityLevel> {
+        self.tracks
+            .entry(track_id.to_string())
+            .or_default()
+            .update(sample)
+    }
+
+    /// Forget tracks other than `track_ids`
+    pub(crate) fn retain_tracks<'a>(&mut self, track_ids: impl IntoIterator<Item = &'a str>) {
+        let live: std::collections::HashSet<&str> = track_ids.into_iter().collect();
+        self.tracks
+            .retain(|track_id, _| live.contains(track_id.as_str()));
+    }
+
+    /// Current level of the connection
+    pub(crate) fn connection(&self) -> Option<QualityLevel> {
+        self.connection.level()
+    }
+
+    /// Current level of a track
+    pub(crate) fn track(&self, track_id: &str) -> Option<QualityLevel> {
+        self.tracks.get(track_id)?.level()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(loss_percentage: f64, rtt_ms: f64, bitrate: u64) -> QualitySample {
+        QualitySample {
+            loss_percentage,
+            rtt_ms: Some(rtt_ms),
+            bitrate,
+        }
+    }
+
+    #[test]
+    fn test_quality_levels_change_with_hysteresis() {
+        let mut monitor = QualityMonitor::default();
+        let good = sample(0.0, 50.0, 1_000_000);
+        assert_eq!(monitor.update(good), Some(QualityLevel::Excellent));
+
+        // One lossy refresh is not enough to drop a level
+        let lossy = sample(15.0, 50.0, 1_000_000);
+        assert_eq!(monitor.update(lossy), None);
+        assert_eq!(monitor.update(good), None);
+        assert_eq!(monitor.update(lossy), None);
+        assert_eq!(monitor.update(lossy), Some(QualityLevel::Critical));
+        assert_eq!(monitor.level().unwrap().bars(), 1);
+
+        // Recovering takes longer than degrading
+        for _ in 0..4 {
+            assert_eq!(monitor.update(good), None);
+        }
+        assert_eq!(monitor.update(good), Some(QualityLevel::Excellent));
+
+        // A collapsing bitrate costs a level on an otherwise clean network
+        assert_eq!(monitor.update(sample(0.0, 50.0, 100_000)), None);
+        assert_eq!(
+            monitor.update(sample(0.0, 50.0, 100_000)),
+            Some(QualityLevel::Good)
+        );
+    }
+}
@@ -0,0 +1,254 @@
+//! End-of-call diagnostics report
+//!
+//! Rooms built with [`RoomBuilder::call_report`](crate::RoomBuilder::call_report)
+//! record a timeline of their events while connected. [`Room::leave`](crate::Room::leave)
+//! turns it into a [`CallReport`]: a JSON-serializable summary of the call to
+//! attach to support tickets.
+
+use crate::event::{Event, QualityLimitationReason};
+use crate::stats_history::StatsSample;
+use crate::track::TrackStats;
+use crate::{QuicRtcError, TransportMode};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Timeline entries kept per call; older entries are dropped first
+const MAX_TIMELINE_ENTRIES: usize = 1000;
+
+/// Network quality score below which a quality change is reported as a warning
+const POOR_QUALITY_SCORE: u8 = 50;
+
+/// Summary of a call, produced when leaving a room
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallReport {
+    /// Room that was left
+    pub room_id: String,
+    /// Local participant
+    pub participant_id: String,
+    /// When the room was joined
+    pub joined_at: DateTime<Utc>,
+    /// When the room was left
+    pub left_at: DateTime<Utc>,
+    /// Time spent in the room
+    #[serde(with = "humantime_serde")]
+    pub duration: Duration,
+    /// Room events in the order they happened
+    pub timeline: Vec<CallTimelineEntry>,
+    /// Timeline entries dropped because the call produced too many
+    pub dropped_timeline_entries: u64,
+    /// Transport modes the media connection used, in order
+    pub transport_modes: Vec<TransportMode>,
+    /// Times the media connection moved: reconnections and transport mode changes
+    pub migrations: u32,
+    /// Statistics of every local and remote track when the room was left
+    pub tracks: Vec<CallTrackReport>,
+    /// Conditions that degraded the call
+    pub warnings: Vec<String>,
+    /// Errors reported by the room
+    pub errors: Vec<String>,
+    /// Recent debug logs, attached when the call had errors and the room
+    /// kept a debug log
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub debug_logs: Vec<String>,
+    /// Statistics sampled during the call, when the room kept a history
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stats_history: Vec<StatsSample>,
+}
+
+/// One entry of a [`CallReport`] timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallTimelineEntry {
+    /// Time since the room was joined
+    #[serde(with = "humantime_serde")]
+    pub at: Duration,
+    /// Event type, as in [`Event::event_type`]
+    pub event: String,
+    /// Event details
+    pub detail: String,
+}
+
+/// Final statistics of one track in a [`CallReport`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallTrackReport {
+    /// Track ID
+    pub track_id: String,
+    /// Publishing participant; `None` for our own tracks
+    pub participant_id: Option<String>,
+    /// Track kind (`audio` or `video`)
+    pub kind: String,
+    /// Statistics at the time the room was left
+    pub stats: TrackStats,
+}
+
+impl CallReport {
+    /// Serialize the report as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String, QuicRtcError> {
+        serde_json::to_string_pretty(self).map_err(|e| QuicRtcError::InvalidData {
+            reason: format!("Failed to serialize call report: {}", e),
+        })
+    }
+
+    /// Write the report as JSON to `path`
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), QuicRtcError> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_json()?).map_err(|e| QuicRtcError::InvalidOperation {
+            operation: format!("write call report to {}: {}", path.display(), e),
+        })
+    }
+}
+
+/// Records a room's events for its [`CallReport`]
+#[derive(Debug)]
+pub(crate) struct CallRecorder {
+    started: Instant,
+    joined_at: DateTime<Utc>,
+    state: Mutex<RecorderState>,
+}
+
+#[derive(Debug, Default)]
+struct RecorderState {
+    timeline: std::collections::VecDeque<CallTimelineEntry>,
+    dropped_timeline_entries: u64,
+    transport_modes: Vec<TransportMode>,
+    migrations: u32,
+    warnings: Vec<String>,
+    errors: Vec<String>,
+}
+
+impl CallRecorder {
+    pub(crate) fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            joined_at: Utc::now(),
+            state: Mutex::new(RecorderState::default()),
+        }
+    }
+
+    /// Add a room event to the timeline, noting warnings and errors
+    pub(crate) fn record_event(&self, event: &Event) {
+        let mut state = self.state.lock().unwrap();
+        match event {
+            Event::RoomError { error, .. } => state.errors.push(error.clone()),
+            Event::RoomDisconnected { reason } => {
+                state.warnings.push(format!("Disconnected: {}", reason))
+            }
+            Event::RoomReconnected => state.migrations += 1,
+            Event::LocalTrackQualityLimited { track_id, reason }
+                if *reason != QualityLimitationReason::None =>
+            {
+                state
+                    .warnings
+                    .push(format!("Track {} limited by {:?}", track_id, reason))
+            }
+            Event::NetworkQualityChanged { quality_score, .. }
+                if *quality_score < POOR_QUALITY_SCORE =>
+            {
+                state
+                    .warnings
+                    .push(format!("Network quality dropped to {}", quality_score))
+            }
+            _ => {}
+        }
+        self.push_entry(&mut state, event.event_type(), format!("{:?}", event));
+    }
+
+    /// Note the transport mode in use, counting a change as a migration
+    pub(crate) fn record_transport_mode(&self, mode: TransportMode) {
+        let mut state = self.state.lock().unwrap();
+        let previous = state.transport_modes.last().copied();
+        if previous == Some(mode) {
+            return;
+        }
+        if previous.is_some() {
+            state.migrations += 1;
+        }
+        state.transport_modes.push(mode);
+        self.push_entry(&mut state, "transport_mode", format!("{:?}", mode));
+    }
+
+    /// Add an entry that is not a room event to the timeline
+    pub(crate) fn record_note(&self, event: &str, detail: String) {
+        let mut state = self.state.lock().unwrap();
+        self.push_entry(&mut state, event, detail);
+    }
+
+    /// Build the report for a room being left
+    pub(crate) fn finish(
+        &self,
+        room_id: &str,
+        participant_id: &str,
+        tracks: Vec<CallTrackReport>,
+    ) -> CallReport {
+        let state = self.state.lock().unwrap();
+        CallReport {
+            room_id: room_id.to_string(),
+            participant_id: participant_id.to_string(),
+            joined_at: self.joined_at,
+            left_at: Utc::now(),
+            duration: self.started.elapsed(),
+            timeline: state.timeline.iter().cloned().collect(),
+            dropped_timeline_entries: state.dropped_timeline_entries,
+            transport_modes: state.transport_modes.clone(),
+            migrations: state.migrations,
+            tracks,
+            warnings: state.warnings.clone(),
+            errors: state.errors.clone(),
+            debug_logs: Vec::new(),
+            stats_history: Vec::new(),
+        }
+    }
+
+    fn push_entry(&self, state: &mut RecorderState, event: &str, detail: String) {
+        if state.timeline.len() == MAX_TIMELINE_ENTRIES {
+            state.timeline.pop_front();
+            state.dropped_timeline_entries += 1;
+        }
+        state.timeline.push_back(CallTimelineEntry {
+            at: self.started.elapsed(),
+            event: event.to_string(),
+            detail,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_call_recorder_builds_report() {
+        let recorder = CallRecorder::new();
+        recorder.record_transport_mode(TransportMode::QuicNative);
+        recorder.record_transport_mode(TransportMode::QuicNative);
+        recorder.record_event(&Event::RoomReconnecting { attempt: 1 });
+        recorder.record_event(&Event::RoomReconnected);
+        recorder.record_transport_mode(TransportMode::QuicOverWebSocket);
+        recorder.record_event(&Event::LocalTrackQualityLimited {
+            track_id: "camera".to_string(),
+            reason: QualityLimitationReason::Bandwidth,
+        });
+        recorder.record_event(&Event::RoomError {
+            error: "subscription failed".to_string(),
+            recoverable: true,
+        });
+
+        let report = recorder.finish("standup", "alice", Vec::new());
+        assert_eq!(
+            report.transport_modes,
+            vec![TransportMode::QuicNative, TransportMode::QuicOverWebSocket]
+        );
+        assert_eq!(report.migrations, 2);
+        assert_eq!(report.timeline.len(), 6);
+        assert_eq!(report.timeline[1].event, "room_reconnecting");
+        assert_eq!(report.warnings, vec!["Track camera limited by Bandwidth"]);
+        assert_eq!(report.errors, vec!["subscription failed"]);
+
+        // The JSON form round-trips for support tooling
+        let parsed: CallReport = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(parsed.room_id, "standup");
+        assert_eq!(parsed.timeline.len(), report.timeline.len());
+    }
+}
@@ -1,29 +1,110 @@
 //! Room management and API
 
+use crate::audio_route::{AudioRoutePolicy, AudioRouteSelection, AudioSession};
+#[cfg(feature = "signaling")]
+use crate::event::CaptureKind;
+#[cfg(feature = "media")]
+use crate::event::DeviceKind;
+use crate::event::{EventBufferConfig, EventBus, JoinStage};
+use crate::quality::{QualityLevel, QualitySample, RoomQuality};
+use crate::report::{CallRecorder, CallTrackReport};
+use crate::stats_history::{ConnectionSample, StatsHistory, StatsHistoryConfig, StatsSample};
+#[cfg(feature = "media")]
+use crate::track::{PlaybackGain, TrackSource, TrackState};
+use crate::track::{SubscribeOptions, TrackStats};
+#[cfg(feature = "media")]
+use crate::{AudioPlayback, CompositionPublication, PlayAudioOptions, PlaybackTarget};
 #[cfg(feature = "media")]
-use crate::{AudioProcessingConfig, MediaConfig, VideoProcessingConfig, VideoQuality};
-use crate::{QuicRtc, QuicRtcError, ResourceLimits, RoomConfig};
+use crate::{
+    AudioProcessingConfig, AudioProfile, DuckingConfig, MediaConfig, ThumbnailConfig,
+    VideoContentHint, VideoProcessingConfig, VideoQuality, VideoRotation,
+};
+use crate::{CancellationToken, LocalTrack, QuicRtc, QuicRtcError, ResourceLimits, RoomConfig};
 #[cfg(feature = "signaling")]
 use crate::{ReconnectConfig, SignalingConfig};
+#[cfg(feature = "diagnostics")]
+use quicrtc_diagnostics::{CrashReporter, CrashStateGuard, DebugLogger, LogRing, LogRingConfig};
+use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
 // Import core types for MoQ and transport
+#[cfg(feature = "signaling")]
+use quicrtc_core::room_namespace;
+#[cfg(any(feature = "signaling", feature = "media"))]
+use quicrtc_core::TrackNamespaceBuilder;
 use quicrtc_core::{
-    ConnectionConfig, MoqOverQuicTransport, MoqSession, MoqTrack, MoqTransportEvent,
-    TrackNamespace, TransportConnection, TransportMode,
+    ConnectionConfig, IntegrityStats, MoqObject, MoqOverQuicTransport, MoqSession, MoqTrack,
+    MoqTransportEvent, ObjectIntegrity, PacketCapture, PacketCaptureConfig, TrackNamespace,
+    TransformDirection, TransportConnection, TransportMode,
 };
 
 #[cfg(feature = "media")]
 use quicrtc_media::{
-    AudioRenderer, AudioTrack, CpalAudioRenderer, DefaultVideoRenderer, MediaError, MediaProcessor,
-    VideoCaptureManager, VideoTrack,
+    AudioDeviceChange, AudioDeviceDirection, AudioDeviceSnapshot, AudioFrame, AudioFramer,
+    AudioMixer, AudioRenderConfig, AudioRenderer, AudioSource, AudioTrack, Codec, CodecInfo,
+    CompositionLayout, CpalAudioRenderer, DecodePipeline, DecodePipelineConfig, DecodeStats,
+    DecodedFrame, DefaultVideoRenderer, EncodePipeline, EncodePipelineConfig, EncodedFrame,
+    FrameFormat, MediaError, MediaFrame, MediaProcessor, SyncEncoder, ThumbnailGenerator,
+    VideoCaptureManager, VideoCompositor, VideoFrame, VideoTrack, VoiceActivityDetector,
 };
 
 #[cfg(feature = "signaling")]
-use quicrtc_signaling::{PeerInfo, PeerStatus, SignalingServer};
+use quicrtc_signaling::{
+    protocol::{
+        LeaveReason, MoqSessionAnswer, MoqSessionOffer, PublishedTrack as OfferedTrack,
+        SignalingMessage, SignalingResponse, MOQ_VERSION,
+    },
+    server::Participant,
+    PeerInfo, PeerStatus, RoomSchedule, SignalingClient,
+};
+
+/// Media endpoint used when no session is negotiated over signaling
+const DEFAULT_MEDIA_ENDPOINT: SocketAddr =
+    SocketAddr::new(std::net::IpAddr::V4(std::net::Ipv4Addr::LOCALHOST), 7878);
+
+/// Format of audio played into published tracks, matching the default Opus
+/// encoder
+#[cfg(feature = "media")]
+const PLAYBACK_SAMPLE_RATE: u32 = 48000;
+#[cfg(feature = "media")]
+const PLAYBACK_LAYOUT: quicrtc_core::ChannelLayout = quicrtc_core::ChannelLayout::Stereo;
+#[cfg(feature = "media")]
+const PLAYBACK_FRAME_DURATION: Duration = Duration::from_millis(20);
+
+/// How far played audio is queued ahead of the mixer
+#[cfg(feature = "media")]
+const PLAYBACK_LEAD: Duration = Duration::from_millis(60);
+
+/// How often audio devices are checked for changes
+#[cfg(feature = "media")]
+const DEVICE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often the path MTU is checked for changes found by path MTU discovery
+#[cfg(feature = "media")]
+const PATH_MTU_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Decoded remote pictures held for [`Room::remote_video`] receivers that
+/// fall behind
+#[cfg(feature = "media")]
+const REMOTE_VIDEO_CAPACITY: usize = 8;
+
+/// Statistics are refreshed this many times less often in audio-only mode
+const AUDIO_ONLY_STATS_SLOWDOWN: u32 = 5;
+
+/// Bandwidth limit in data saver mode (kbps)
+const DATA_SAVER_BANDWIDTH_KBPS: u64 = 300;
+/// Outgoing video bitrate cap in data saver mode (bps)
+#[cfg(feature = "media")]
+const DATA_SAVER_VIDEO_BITRATE: u32 = 250_000;
+/// Time between outgoing keyframes in data saver mode
+#[cfg(feature = "media")]
+const DATA_SAVER_KEYFRAME_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Suffix of the MoQ track name of a video track's thumbnail rendition
+const THUMBNAIL_TRACK_SUFFIX: &str = "-thumbnail";
 
 /// Fluent builder for room configuration and connection
 #[derive(Debug)]
@@ -37,11 +118,22 @@ pub struct RoomBuilder {
     audio_config: Option<AudioProcessingConfig>,
     #[cfg(feature = "media")]
     video_config: Option<VideoProcessingConfig>,
+    #[cfg(feature = "media")]
+    encode_pipeline: EncodePipelineConfig,
+    #[cfg(feature = "media")]
+    decode_pipeline: DecodePipelineConfig,
     #[cfg(feature = "signaling")]
     signaling_config: Option<SignalingConfig>,
     resource_limits: Option<ResourceLimits>,
     custom_room_name: Option<String>,
     max_participants: Option<usize>,
+    #[cfg(feature = "signaling")]
+    schedule: Option<RoomSchedule>,
+    #[cfg(feature = "signaling")]
+    access_token: Option<String>,
+    audio_session: Option<Arc<dyn AudioSession>>,
+    cancellation: Option<CancellationToken>,
+    events: EventBus,
 }
 
 impl RoomBuilder {
@@ -55,11 +147,22 @@ impl RoomBuilder {
             audio_config: None,
             #[cfg(feature = "media")]
             video_config: None,
+            #[cfg(feature = "media")]
+            encode_pipeline: EncodePipelineConfig::default(),
+            #[cfg(feature = "media")]
+            decode_pipeline: DecodePipelineConfig::default(),
             #[cfg(feature = "signaling")]
             signaling_config: None,
             resource_limits: None,
             custom_room_name: None,
             max_participants: None,
+            #[cfg(feature = "signaling")]
+            schedule: None,
+            #[cfg(feature = "signaling")]
+            access_token: None,
+            audio_session: None,
+            cancellation: None,
+            events: EventBus::default(),
         }
     }
 
@@ -108,6 +211,17 @@ impl RoomBuilder {
         self
     }
 
+    /// Label a published track, e.g. "Front camera"
+    ///
+    /// The label is announced to other participants with the track so their
+    /// UIs can show it, see [`crate::RemoteTrack::label`].
+    pub fn track_label(mut self, source: crate::track::TrackSource, label: &str) -> Self {
+        self.config
+            .track_labels
+            .insert(source.to_string(), label.to_string());
+        self
+    }
+
     /// Set video quality preset
     #[cfg(feature = "media")]
     pub fn video_quality(mut self, quality: VideoQuality) -> Self {
@@ -131,6 +245,19 @@ impl RoomBuilder {
         self
     }
 
+    /// Publish a low-rate thumbnail track next to each video track the room sends
+    #[cfg(feature = "media")]
+    pub fn thumbnails(mut self, config: ThumbnailConfig) -> Self {
+        self.config.video_enabled = true;
+
+        let mut video_config = self
+            .video_config
+            .unwrap_or_else(VideoProcessingConfig::default);
+        video_config.thumbnails = Some(config);
+        self.video_config = Some(video_config);
+        self
+    }
+
     /// Configure advanced video processing options
     #[cfg(feature = "media")]
     pub fn video_processing(mut self, config: VideoProcessingConfig) -> Self {
@@ -182,6 +309,66 @@ impl RoomBuilder {
         self
     }
 
+    /// Follow the system default audio output device
+    ///
+    /// See [`Room::set_follow_default_devices`].
+    #[cfg(feature = "media")]
+    pub fn follow_default_devices(mut self) -> Self {
+        let mut audio_config = self.audio_config.unwrap_or_default();
+        audio_config.follow_default_devices = true;
+        self.audio_config = Some(audio_config);
+        self
+    }
+
+    /// Configure sent audio and its processing from a preset
+    ///
+    /// Overrides the codec defaults of [`GlobalConfig`](crate::GlobalConfig)
+    /// for this room and sets echo cancellation, noise suppression and gain
+    /// control; see [`AudioProfile`].
+    #[cfg(feature = "media")]
+    pub fn audio_profile(mut self, profile: AudioProfile) -> Self {
+        self.config.audio_enabled = true;
+        let mut audio_config = self.audio_config.unwrap_or_default();
+        profile.apply(&mut audio_config);
+        self.audio_config = Some(audio_config);
+        self
+    }
+
+    /// Lower remote and played audio while the local user speaks
+    ///
+    /// See [`Room::process_local_audio`].
+    #[cfg(feature = "media")]
+    pub fn audio_ducking(mut self, config: DuckingConfig) -> Self {
+        let mut audio_config = self.audio_config.unwrap_or_default();
+        audio_config.ducking = Some(config);
+        self.audio_config = Some(audio_config);
+        self
+    }
+
+    /// Bound the queue of frames waiting for the encoder on each sent track
+    ///
+    /// Sent audio and video are encoded on a worker thread per track so
+    /// producers never wait on the encoder. When it falls behind, frames
+    /// are dropped by the configured policy; see [`crate::TrackStats`] for
+    /// the resulting queue and drop counts.
+    #[cfg(feature = "media")]
+    pub fn encode_pipeline(mut self, config: EncodePipelineConfig) -> Self {
+        self.encode_pipeline = config;
+        self
+    }
+
+    /// Bound the queue of frames waiting for the decoder on each received
+    /// video track
+    ///
+    /// Subscribed video is decoded on a worker thread per track. Frames
+    /// that would be shown after their playout deadline are skipped; the
+    /// losses and decode time feed the track's quality score.
+    #[cfg(feature = "media")]
+    pub fn decode_pipeline(mut self, config: DecodePipelineConfig) -> Self {
+        self.decode_pipeline = config;
+        self
+    }
+
     // ============================================================================
     // Signaling and Connection Configuration
     // ============================================================================
@@ -199,6 +386,39 @@ impl RoomBuilder {
         self
     }
 
+    /// Schedule the room if this join creates it
+    ///
+    /// Participants other than the host then wait in the room's lobby until
+    /// the host joins, unless the schedule allows joining before the host.
+    /// A room that already exists keeps its own schedule.
+    #[cfg(feature = "signaling")]
+    pub fn schedule(mut self, schedule: RoomSchedule) -> Self {
+        self.schedule = Some(schedule);
+        self
+    }
+
+    /// Present an access token when creating and joining the room
+    ///
+    /// Servers that verify tokens only let the participant in if the
+    /// token's grants allow the room, see
+    /// [`AccessToken`](quicrtc_signaling::AccessToken). The token's identity
+    /// must match the participant ID.
+    #[cfg(feature = "signaling")]
+    pub fn access_token(mut self, token: &str) -> Self {
+        self.access_token = Some(token.to_string());
+        self
+    }
+
+    /// Route audio through the platform audio session on mobile
+    ///
+    /// The room picks the route when it joins and again on
+    /// [`Room::refresh_audio_route`], preferring headsets and recording
+    /// from Bluetooth headsets over HFP while the microphone is published.
+    pub fn audio_session(mut self, session: Arc<dyn AudioSession>) -> Self {
+        self.audio_session = Some(session);
+        self
+    }
+
     /// Set connection timeout
     #[cfg(feature = "signaling")]
     pub fn connection_timeout(mut self, timeout: Duration) -> Self {
@@ -253,6 +473,92 @@ impl RoomBuilder {
         self
     }
 
+    /// Abandon joining when `token` is cancelled
+    ///
+    /// The join then fails with [`QuicRtcError::Cancelled`] after leaving
+    /// the signaling room and closing any media connection it set up.
+    /// Dropping the join future, for example from a timeout, cleans up the
+    /// same way in the background.
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Get a stream of the room's events, starting before the join
+    ///
+    /// Events raised while joining, such as
+    /// [`Event::WaitingForHost`](crate::Event::WaitingForHost), only reach
+    /// streams taken here.
+    pub fn events(&self) -> crate::EventStream {
+        self.events.subscribe(EventBufferConfig::default())
+    }
+
+    /// Give up joining after `timeout`, including retries of failed attempts
+    ///
+    /// The join then fails with [`QuicRtcError::Timeout`] and whatever it
+    /// set up is torn down. Progress up to that point is reported as
+    /// [`Event::JoinProgress`](crate::Event::JoinProgress) on
+    /// [`Self::events`].
+    pub fn join_timeout(mut self, timeout: Duration) -> Self {
+        self.config.join_timeout = Some(timeout);
+        self
+    }
+
+    /// Set how often track statistics are refreshed (default 1s)
+    pub fn stats_interval(mut self, interval: Duration) -> Self {
+        self.config.stats_interval = interval;
+        self
+    }
+
+    /// Keep a history of track and connection statistics, sampled and
+    /// retained as `config` says
+    ///
+    /// Query it with [`Room::stats_history`] and [`Room::stats_between`];
+    /// call reports attach it.
+    pub fn stats_history(mut self, config: StatsHistoryConfig) -> Self {
+        self.config.stats_history = Some(config);
+        self
+    }
+
+    /// Produce a [`crate::CallReport`] when the room is left with [`Room::leave`]
+    pub fn call_report(mut self) -> Self {
+        self.config.call_report = true;
+        self
+    }
+
+    /// Write the call report as JSON to `path` when the room is left
+    ///
+    /// Implies [`RoomBuilder::call_report`].
+    pub fn call_report_path(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.config.call_report = true;
+        self.config.call_report_path = Some(path.into());
+        self
+    }
+
+    /// Record packet metadata of the media connection into `path`
+    ///
+    /// Timestamps, sizes, ECN marks, stream openings and loss signals are
+    /// written, never payloads; read the file with the `quicrtc-capture`
+    /// analyzer. Only applies when this room sets up the media connection
+    /// rather than sharing one with another room.
+    pub fn packet_capture(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.config.packet_capture_path = Some(path.into());
+        self
+    }
+
+    /// Keep the last `window` of debug logs in memory
+    ///
+    /// Logs are captured at debug level whatever `RUST_LOG` says and can be
+    /// read with [`Room::dump_debug_logs`]; they are also attached to the
+    /// call report when the call had errors. Needs the logger installed by
+    /// [`QuicRtc::init`], or [`crate::DebugLogger::global`] added to the
+    /// application's own subscriber.
+    #[cfg(feature = "diagnostics")]
+    pub fn debug_logger(mut self, window: Duration) -> Self {
+        self.config.debug_log_window = Some(window);
+        self
+    }
+
     // ============================================================================
     // Quality and Bandwidth Configuration
     // ============================================================================
@@ -315,6 +621,45 @@ impl RoomBuilder {
         self
     }
 
+    /// Use less data, e.g. on a metered connection
+    ///
+    /// Caps bandwidth at 300 kbps and outgoing video at low quality and
+    /// 250 kbps with a keyframe every 10 seconds. Received video is limited
+    /// to thumbnail renditions: subscribing to a video track subscribes to
+    /// its thumbnail track instead, and video without one is refused.
+    pub fn data_saver(mut self) -> Self {
+        self.config.data_saver = true;
+        #[cfg(feature = "media")]
+        {
+            self.config.video_quality = VideoQuality::Low;
+        }
+
+        let mut limits = self.resource_limits.unwrap_or_else(ResourceLimits::mobile);
+        limits.max_bandwidth_kbps = Some(
+            limits
+                .max_bandwidth_kbps
+                .map_or(DATA_SAVER_BANDWIDTH_KBPS, |kbps| {
+                    kbps.min(DATA_SAVER_BANDWIDTH_KBPS)
+                }),
+        );
+        self.resource_limits = Some(limits);
+        self
+    }
+
+    /// Pass on the platform's hint that the network is metered
+    ///
+    /// Apps forward what the OS reports, such as
+    /// `ConnectivityManager.isActiveNetworkMetered()` on Android or
+    /// `NWPath.isExpensive` on Apple platforms. A metered connection turns
+    /// on [`RoomBuilder::data_saver`].
+    pub fn metered_connection(self, metered: bool) -> Self {
+        if metered {
+            self.data_saver()
+        } else {
+            self
+        }
+    }
+
     // ============================================================================
     // Validation and Building
     // ============================================================================
@@ -373,6 +718,14 @@ impl RoomBuilder {
                         reason: "video framerate must be between 0.1 and 120.0".to_string(),
                     });
                 }
+                if let Some(thumbnails) = &video_config.thumbnails {
+                    if thumbnails.height < 2 || thumbnails.interval.is_zero() {
+                        return Err(QuicRtcError::InvalidData {
+                            reason: "thumbnail height must be at least 2 with an interval"
+                                .to_string(),
+                        });
+                    }
+                }
             }
         }
 
@@ -391,6 +744,28 @@ impl RoomBuilder {
             }
         }
 
+        if self.config.stats_interval.is_zero() {
+            return Err(QuicRtcError::InvalidData {
+                reason: "stats_interval must be greater than 0".to_string(),
+            });
+        }
+
+        if self.config.join_timeout == Some(Duration::ZERO) {
+            return Err(QuicRtcError::InvalidData {
+                reason: "join_timeout must be greater than 0".to_string(),
+            });
+        }
+
+        if let Some(history) = &self.config.stats_history {
+            if history.sample_interval.is_zero() || history.retention < history.sample_interval {
+                return Err(QuicRtcError::InvalidData {
+                    reason: "stats_history needs a sample interval greater than 0 and a \
+                             retention of at least one interval"
+                        .to_string(),
+                });
+            }
+        }
+
         // Validate resource limits
         if let Some(ref limits) = self.resource_limits {
             if let Some(bandwidth) = limits.max_bandwidth_kbps {
@@ -421,10 +796,21 @@ impl RoomBuilder {
             self.audio_config,
             #[cfg(feature = "media")]
             self.video_config,
+            #[cfg(feature = "media")]
+            self.encode_pipeline,
+            #[cfg(feature = "media")]
+            self.decode_pipeline,
             #[cfg(feature = "signaling")]
             self.signaling_config,
             self.resource_limits,
             self.max_participants,
+            #[cfg(feature = "signaling")]
+            self.schedule,
+            #[cfg(feature = "signaling")]
+            self.access_token,
+            self.audio_session,
+            self.cancellation,
+            self.events,
         )
         .await
     }
@@ -440,6 +826,15 @@ impl RoomBuilder {
     }
 }
 
+/// A room joined through a [`QuicRtc`] instance; see [`QuicRtc::rooms`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JoinedRoom {
+    /// Room ID
+    pub room_id: String,
+    /// Participant ID the room was joined as
+    pub participant_id: String,
+}
+
 /// A Room represents a real-time communication session
 #[derive(Debug)]
 pub struct Room {
@@ -454,6 +849,24 @@ pub struct Room {
     signaling_config: Option<SignalingConfig>,
     resource_limits: Option<ResourceLimits>,
     max_participants: Option<usize>,
+    /// Schedule to create the room with
+    #[cfg(feature = "signaling")]
+    schedule: Option<RoomSchedule>,
+    /// Token presented when creating and joining the room
+    #[cfg(feature = "signaling")]
+    access_token: Option<String>,
+    /// Room event fan-out, shared with the inner state
+    events: EventBus,
+    /// Timeline for the end-of-call report, if one was requested
+    call_recorder: Option<Arc<CallRecorder>>,
+    /// Sampled statistics, if a history was requested
+    stats_history: Option<StatsHistory>,
+    /// Recent debug logs, if a debug logger was requested
+    #[cfg(feature = "diagnostics")]
+    debug_log: Option<Arc<LogRing>>,
+    /// Keeps this room's state in crash bundles
+    #[cfg(feature = "diagnostics")]
+    _crash_state: CrashStateGuard,
 
     // Core room state
     inner: Arc<RwLock<RoomInner>>,
@@ -478,6 +891,16 @@ pub struct RoomInner {
     /// Audio renderer for microphone and speaker access
     #[cfg(feature = "media")]
     pub audio_renderer: Option<Arc<tokio::sync::Mutex<CpalAudioRenderer>>>,
+    /// Mixer combining remote participants' audio before rendering
+    #[cfg(feature = "media")]
+    pub audio_mixer: Option<Arc<tokio::sync::Mutex<AudioMixer>>>,
+    /// Subscribed remote audio tracks, mapped to the participant whose
+    /// mixer source they feed
+    #[cfg(feature = "media")]
+    remote_audio: Arc<std::sync::Mutex<std::collections::HashMap<TrackNamespace, String>>>,
+    /// Where mixed remote audio is rendered, once playout has started
+    #[cfg(feature = "media")]
+    audio_output: Option<tokio::sync::mpsc::Sender<AudioFrame>>,
     /// Participants in the room
     pub participants: crate::Participants,
     /// Local participant representation
@@ -485,12 +908,173 @@ pub struct RoomInner {
     /// Published tracks by this participant
     #[cfg(feature = "media")]
     pub published_tracks: std::collections::HashMap<String, PublishedTrack>,
-    /// Event sender for room events
-    pub event_tx: Option<mpsc::UnboundedSender<crate::Event>>,
+    /// Mixers sending audio from [`Room::play_audio`], keyed by track ID
+    #[cfg(feature = "media")]
+    pub outbound_audio: std::collections::HashMap<String, Arc<tokio::sync::Mutex<AudioMixer>>>,
+    /// Encode workers of sent tracks, keyed by track ID
+    #[cfg(feature = "media")]
+    encode_pipeline: Arc<std::sync::Mutex<EncodePipeline>>,
+    /// Decode workers of subscribed video tracks
+    #[cfg(feature = "media")]
+    video_decoding: Arc<std::sync::Mutex<VideoDecoding>>,
+    /// Decoded remote video, see [`Room::remote_video`]
+    #[cfg(feature = "media")]
+    remote_video: tokio::sync::broadcast::Sender<crate::RemoteVideoFrame>,
+    /// Detects local speech for ducking, if configured
+    #[cfg(feature = "media")]
+    local_voice: Option<VoiceActivityDetector>,
+    /// Whether video is paused to save data, see [`Room::set_audio_only`]
+    audio_only: bool,
+    /// Video subscriptions to restore when audio-only mode ends, as
+    /// (participant ID, track ID, options)
+    audio_only_subscriptions: Vec<(String, String, SubscribeOptions)>,
+    /// Camera capture to restart when audio-only mode ends
+    #[cfg(feature = "media")]
+    paused_camera: Option<quicrtc_media::NewVideoCaptureConfig>,
+    /// Whether playback moves to the system default output device when it
+    /// changes, see [`Room::set_follow_default_devices`]
+    #[cfg(feature = "media")]
+    follow_default_devices: bool,
+    /// Platform audio session routes are applied to
+    audio_session: Option<Arc<dyn AudioSession>>,
+    /// How the audio route is picked
+    audio_route_policy: AudioRoutePolicy,
+    /// Audio route last applied to the session
+    audio_route: Option<AudioRouteSelection>,
+    /// Quality levels of the connection and tracks
+    quality: RoomQuality,
+    /// Delivers room events to subscribed event streams
+    pub(crate) events: EventBus,
     /// Background task handles
     pub background_tasks: Vec<tokio::task::JoinHandle<()>>,
 }
 
+/// What ties a room to the network, taken out of [`RoomInner`] to be closed
+struct RoomConnections {
+    background_tasks: Vec<tokio::task::JoinHandle<()>>,
+    #[cfg(feature = "signaling")]
+    signaling_connection: Option<Arc<tokio::sync::Mutex<SignalingConnection>>>,
+    moq_transport: Option<Arc<MoqOverQuicTransport>>,
+}
+
+impl RoomConnections {
+    /// Stop background tasks, leave the room on the signaling server and
+    /// close the media transport unless another room shares it
+    #[cfg_attr(not(feature = "signaling"), allow(unused_variables))]
+    async fn close(self, room_id: &str, participant_id: &str) {
+        for task in self.background_tasks {
+            task.abort();
+            let _ = task.await;
+        }
+
+        #[cfg(feature = "signaling")]
+        if let Some(signaling) = self.signaling_connection {
+            let message = SignalingMessage::LeaveRoom {
+                room_id: room_id.to_string(),
+                participant_id: participant_id.to_string(),
+            };
+            let client = Arc::clone(&signaling.lock().await.client);
+            if let Err(e) = client.send(&message).await {
+                warn!("Failed to announce leaving room '{}': {}", room_id, e);
+            }
+        }
+
+        // The transport is shared with other rooms on the same endpoint
+        if let Some(transport) = self.moq_transport {
+            if Arc::strong_count(&transport) == 1 {
+                if let Err(e) = transport.close().await {
+                    warn!("Failed to close media transport: {}", e);
+                }
+            }
+        }
+    }
+}
+
+/// Join in progress, torn down unless completed
+///
+/// A join future dropped part way can't await the teardown, so it runs on
+/// a spawned task instead.
+struct PendingJoin {
+    inner: Option<Arc<RwLock<RoomInner>>>,
+    room_id: String,
+    participant_id: String,
+}
+
+impl PendingJoin {
+    fn new(room: &Room) -> Self {
+        Self {
+            inner: Some(Arc::clone(&room.inner)),
+            room_id: room.id.clone(),
+            participant_id: room.participant_id.clone(),
+        }
+    }
+
+    /// The room joined; keep everything
+    fn complete(mut self) {
+        self.inner = None;
+    }
+
+    /// Tear down whatever the join set up
+    async fn abort(mut self) {
+        if let Some(inner) = self.inner.take() {
+            Self::teardown(inner, &self.room_id, &self.participant_id).await;
+        }
+    }
+
+    async fn teardown(inner: Arc<RwLock<RoomInner>>, room_id: &str, participant_id: &str) {
+        let connections = inner.write().await.take_connections();
+        // Release the room so it can be joined again while closing
+        drop(inner);
+        connections.close(room_id, participant_id).await;
+    }
+}
+
+impl Drop for PendingJoin {
+    fn drop(&mut self) {
+        let Some(inner) = self.inner.take() else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        debug!("Join of room '{}' abandoned, cleaning up", self.room_id);
+        let (room_id, participant_id) = (self.room_id.clone(), self.participant_id.clone());
+        runtime.spawn(async move { Self::teardown(inner, &room_id, &participant_id).await });
+    }
+}
+
+/// Wait until a join is cancelled or has run for `join_timeout` since
+/// `started`, and return the error to fail it with
+async fn join_interrupted(
+    room_id: &str,
+    cancellation: Option<&CancellationToken>,
+    join_timeout: Option<Duration>,
+    started: tokio::time::Instant,
+) -> QuicRtcError {
+    let cancelled = async {
+        match cancellation {
+            Some(cancellation) => cancellation.cancelled().await,
+            None => std::future::pending().await,
+        }
+    };
+    let expired = async {
+        match join_timeout {
+            Some(timeout) => tokio::time::sleep_until(started + timeout).await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::select! {
+        biased;
+        _ = cancelled => QuicRtcError::Cancelled {
+            operation: format!("join room '{}'", room_id),
+        },
+        _ = expired => QuicRtcError::Timeout {
+            operation: format!("join room '{}'", room_id),
+            duration: join_timeout.unwrap_or_default(),
+        },
+    }
+}
+
 /// Room connection state
 #[derive(Debug, Clone, PartialEq)]
 pub enum RoomState {
@@ -506,673 +1090,4194 @@ pub enum RoomState {
     Disconnecting,
 }
 
-/// Signaling connection wrapper
+/// Whether `track` is video other than a thumbnail rendition
+fn is_full_video(track: &crate::RemoteTrack) -> bool {
+    track.kind() == crate::track::TrackKind::Video
+        && !track.moq_track().name.ends_with(THUMBNAIL_TRACK_SUFFIX)
+}
+
+/// Describe a participant reported by the signaling server
 #[cfg(feature = "signaling")]
-#[derive(Debug)]
-struct SignalingConnection {
-    /// Signaling server reference
-    server: Arc<SignalingServer>,
-    /// Our participant info for signaling
-    participant_info: PeerInfo,
-    /// Other participants discovered via signaling
-    discovered_peers: std::collections::HashMap<String, PeerInfo>,
+fn peer_info(room_id: &str, participant: &Participant) -> PeerInfo {
+    PeerInfo {
+        id: participant.id.clone(),
+        name: participant.name.clone(),
+        room_id: room_id.to_string(),
+        quic_endpoint: participant.quic_endpoint,
+        capabilities: participant.capabilities.clone(),
+        last_seen: chrono::Utc::now(),
+        status: PeerStatus::Online,
+    }
 }
 
-/// Published track metadata
-#[cfg(feature = "media")]
-#[derive(Debug)]
-struct PublishedTrack {
-    /// Track ID
-    track_id: String,
-    /// Track type (video/audio)
-    track_type: TrackType,
-    /// MoQ track for transport
-    moq_track: MoqTrack,
-    /// Whether track is currently muted
-    muted: bool,
-    /// Track publication time
-    published_at: std::time::Instant,
+/// Remote participant for someone the signaling server reported in the room
+#[cfg(feature = "signaling")]
+///
+/// The server only reports participants that have joined, so they start
+/// out connected.
+fn remote_participant(participant: &Participant) -> crate::RemoteParticipant {
+    let mut remote = match &participant.name {
+        Some(name) => crate::RemoteParticipant::new_with_name(participant.id.clone(), name.clone()),
+        None => crate::RemoteParticipant::new(participant.id.clone()),
+    };
+    remote.set_connection_state(crate::participant::ParticipantConnectionState::Connected);
+    remote
 }
 
-/// Track type enumeration
-#[cfg(feature = "media")]
-#[derive(Debug, Clone, PartialEq)]
-enum TrackType {
-    Video,
-    Audio,
+/// Tell another participant which tracks we publish
+///
+/// Sent as a session offer with a fresh session ID. Participants that don't
+/// accept media connections reject it, but still learn our tracks from it.
+#[cfg(feature = "signaling")]
+async fn announce_tracks(
+    client: &SignalingClient,
+    room_id: &str,
+    target: &str,
+    announcement: &MoqSessionOffer,
+) -> Result<(), QuicRtcError> {
+    let mut offer = announcement.clone();
+    offer.session_id = uuid::Uuid::new_v4().to_string();
+    client
+        .send(&SignalingMessage::MoqSessionOffer {
+            room_id: room_id.to_string(),
+            target_participant: target.to_string(),
+            offer,
+        })
+        .await
 }
 
-impl Room {
-    /// Quick join - simplest possible API
-    pub async fn quick_join(room_id: &str, participant_id: &str) -> Result<Self, QuicRtcError> {
-        QuicRtc::init()
-            .await?
-            .room(room_id)
-            .participant(participant_id)
-            .enable_video()
-            .enable_audio()
-            .join()
-            .await
+/// Map a signaling capture kind to the one reported in room events
+#[cfg(feature = "signaling")]
+fn capture_kind(kind: quicrtc_signaling::CaptureKind) -> CaptureKind {
+    match kind {
+        quicrtc_signaling::CaptureKind::Recording => CaptureKind::Recording,
+        quicrtc_signaling::CaptureKind::Transcription => CaptureKind::Transcription,
     }
+}
 
-    pub(crate) async fn join_internal(
-        quic_rtc: Arc<QuicRtc>,
-        room_id: String,
-        participant_id: String,
-        config: RoomConfig,
-        #[cfg(feature = "media")] audio_config: Option<AudioProcessingConfig>,
-        #[cfg(feature = "media")] video_config: Option<VideoProcessingConfig>,
-        #[cfg(feature = "signaling")] signaling_config: Option<SignalingConfig>,
-        resource_limits: Option<ResourceLimits>,
-        max_participants: Option<usize>,
-    ) -> Result<Self, QuicRtcError> {
-        info!(
-            "🏠 Joining room '{}' as participant '{}'",
-            room_id, participant_id
-        );
-
-        // Create event channel for room events
-        let (event_tx, _event_rx) = mpsc::unbounded_channel();
+/// Map an audio device change to the room event reporting it
+#[cfg(feature = "media")]
+fn device_event(change: AudioDeviceChange) -> Option<crate::Event> {
+    let kind = |direction: AudioDeviceDirection| match direction {
+        AudioDeviceDirection::Input => DeviceKind::AudioInput,
+        AudioDeviceDirection::Output => DeviceKind::AudioOutput,
+    };
+    Some(match change {
+        AudioDeviceChange::Added { direction, name } => crate::Event::DeviceAdded {
+            kind: kind(direction),
+            device_id: name.clone(),
+            name,
+        },
+        AudioDeviceChange::Removed { direction, name } => crate::Event::DeviceRemoved {
+            kind: kind(direction),
+            device_id: name,
+        },
+        AudioDeviceChange::DefaultChanged { direction, name } => {
+            crate::Event::DefaultDeviceChanged {
+                kind: kind(direction),
+                device_id: name?,
+            }
+        }
+    })
+}
 
-        // Initialize room with disconnected state
-        let room_inner = RoomInner {
-            state: RoomState::Disconnected,
-            moq_transport: None,
-            #[cfg(feature = "signaling")]
-            signaling_connection: None,
-            #[cfg(feature = "media")]
-            media_processor: None,
-            #[cfg(feature = "media")]
-            video_capture: None,
-            #[cfg(feature = "media")]
-            audio_renderer: None,
-            participants: crate::Participants::new(),
-            local_participant: None,
-            #[cfg(feature = "media")]
-            published_tracks: std::collections::HashMap::new(),
-            event_tx: Some(event_tx),
-            background_tasks: Vec::new(),
+/// Pace `source` into `mixer` in real time until it ends or is stopped
+#[cfg(feature = "media")]
+async fn feed_playback(
+    mut source: AudioSource,
+    mixer: Arc<tokio::sync::Mutex<AudioMixer>>,
+    playback_id: String,
+    stop: CancellationToken,
+    finished: CancellationToken,
+) {
+    // Also marks the playback finished if the room aborts this task
+    let _finished = finished.drop_guard();
+    let (sample_rate, channels) = {
+        let mixer = mixer.lock().await;
+        (mixer.sample_rate(), mixer.layout().channel_count())
+    };
+    let mut framer = AudioFramer::new(sample_rate, channels, PLAYBACK_FRAME_DURATION);
+    let started = tokio::time::Instant::now();
+    let mut queued = Duration::ZERO;
+
+    'play: loop {
+        let next = tokio::select! {
+            _ = stop.cancelled() => break,
+            next = source.next_frame() => next,
         };
-
-        let room = Self {
-            id: room_id.clone(),
-            participant_id: participant_id.clone(),
-            config,
-            #[cfg(feature = "media")]
-            audio_config,
-            #[cfg(feature = "media")]
-            video_config,
-            #[cfg(feature = "signaling")]
-            signaling_config,
-            resource_limits,
-            max_participants,
-            inner: Arc::new(RwLock::new(room_inner)),
+        let frames = match &next {
+            Some(frame) => framer.push(frame),
+            None => framer.flush().into_iter().collect(),
         };
 
-        // Start the connection process
-        room.connect(&quic_rtc).await?;
-
-        info!("✅ Successfully joined room '{}'", room_id);
-        Ok(room)
-    }
-
-    /// Internal connection logic
-    async fn connect(&self, quic_rtc: &QuicRtc) -> Result<(), QuicRtcError> {
-        let mut inner = self.inner.write().await;
-        inner.state = RoomState::Connecting;
-
-        // Step 1: Initialize media subsystems if enabled
-        #[cfg(feature = "media")]
-        {
-            if self.config.video_enabled || self.config.audio_enabled {
-                info!("🎥 Initializing media subsystems");
-                self.init_media_subsystems(&mut inner).await?;
+        for frame in frames {
+            // A source slower than real time restarts the clock instead of
+            // bursting to catch up
+            queued = queued.max(started.elapsed());
+            let due = started + queued.saturating_sub(PLAYBACK_LEAD);
+            tokio::select! {
+                _ = stop.cancelled() => break 'play,
+                _ = tokio::time::sleep_until(due) => {}
             }
+            if mixer.lock().await.push_frame(&playback_id, frame).is_err() {
+                break 'play;
+            }
+            queued += PLAYBACK_FRAME_DURATION;
         }
 
-        // Step 2: Connect to signaling server if configured
-        #[cfg(feature = "signaling")]
-        {
-            if let Some(signaling_url) = &self.config.signaling_url {
-                info!("📡 Connecting to signaling server: {}", signaling_url);
-                self.connect_signaling(&mut inner, signaling_url).await?;
+        if next.is_none() {
+            // Let the queued audio play out before removing the source
+            tokio::select! {
+                _ = stop.cancelled() => {}
+                _ = tokio::time::sleep_until(started + queued) => {}
             }
+            break;
         }
-
-        // Step 3: Establish MoQ transport
-        info!("🚀 Establishing MoQ over QUIC transport");
-        self.establish_moq_transport(&mut inner, quic_rtc).await?;
-
-        // Step 4: Initialize local participant
-        info!("👤 Initializing local participant");
-        inner.local_participant = Some(crate::LocalParticipant::new(
-            self.participant_id.clone(),
-            self.config.clone(),
-        ));
-
-        inner.state = RoomState::Connected;
-        info!("🎉 Room connection established successfully");
-
-        Ok(())
     }
 
-    /// Initialize media subsystems with permission checking
-    #[cfg(feature = "media")]
-    async fn init_media_subsystems(&self, inner: &mut RoomInner) -> Result<(), QuicRtcError> {
-        // Initialize media processor
-        inner.media_processor = Some(Arc::new(tokio::sync::Mutex::new(MediaProcessor::new())));
+    mixer.lock().await.remove_source(&playback_id);
+}
 
-        // Initialize video capture if video is enabled
-        if self.config.video_enabled {
-            debug!("📹 Initializing video capture with permission checks");
-            let mut video_capture =
-                VideoCaptureManager::new().map_err(|e| QuicRtcError::MediaProcessing {
-                    reason: format!("Failed to initialize video capture: {}", e),
-                })?;
+/// Render one mixed frame of remote audio every frame interval
+#[cfg(feature = "media")]
+async fn play_remote_audio(
+    mixer: Arc<tokio::sync::Mutex<AudioMixer>>,
+    output: tokio::sync::mpsc::Sender<AudioFrame>,
+) {
+    let mut ticker = tokio::time::interval(PLAYBACK_FRAME_DURATION);
+    loop {
+        ticker.tick().await;
+        let Some(frame) = mixer.lock().await.mix() else {
+            continue;
+        };
+        if output.send(frame).await.is_err() {
+            break;
+        }
+    }
+}
 
-            // Check camera permissions during initialization [[memory:3911748]]
-            match video_capture.enumerate_devices() {
-                Ok(devices) => {
-                    if devices.is_empty() {
-                        return Err(QuicRtcError::MediaProcessing {
-                            reason: "No camera devices available - check permissions".to_string(),
-                        });
-                    }
-                    info!(
-                        "✅ Camera permissions verified - {} devices available",
-                        devices.len()
-                    );
+/// Encode and send one mixed frame of played audio every frame interval
+#[cfg(feature = "media")]
+async fn send_outbound_audio(
+    mixer: Arc<tokio::sync::Mutex<AudioMixer>>,
+    mut encoder: TrackEncoder,
+    mut encoded: tokio::sync::mpsc::Receiver<EncodedFrame>,
+    transport: Arc<MoqOverQuicTransport>,
+) {
+    let mut ticker = tokio::time::interval(PLAYBACK_FRAME_DURATION);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                let Some(frame) = mixer.lock().await.mix() else {
+                    continue;
+                };
+                if let Err(e) = encoder.submit(MediaFrame::Audio(frame)).await {
+                    debug!("Failed to encode played audio: {}", e);
                 }
-                Err(e) => {
-                    error!(
-                        "❌ Camera permission check failed during initialization: {}",
-                        e
-                    );
-                    return Err(QuicRtcError::MediaProcessing {
-                        reason: format!("Camera access denied during initialization: {}", e),
-                    });
+            }
+            frame = encoded.recv() => {
+                let Some(frame) = frame else {
+                    break;
+                };
+                if let Err(e) = encoder.send(frame, &transport).await {
+                    debug!("Failed to send played audio: {}", e);
                 }
             }
-
-            inner.video_capture = Some(Arc::new(tokio::sync::Mutex::new(video_capture)));
         }
+    }
+}
 
-        // Initialize audio renderer if audio is enabled
-        if self.config.audio_enabled {
-            debug!("🎵 Initializing audio renderer with permission checks");
-            let audio_renderer = CpalAudioRenderer::new();
-
-            // Check microphone permissions during initialization [[memory:3911748]]
-            match audio_renderer.list_devices() {
-                Ok(devices) => {
-                    if devices.is_empty() {
-                        return Err(QuicRtcError::MediaProcessing {
-                            reason: "No audio input devices available - check permissions"
-                                .to_string(),
-                        });
-                    }
-                    info!(
-                        "✅ Microphone permissions verified - {} devices available",
-                        devices.len()
-                    );
-                }
-                Err(e) => {
-                    // If device enumeration fails, log warning but allow initialization
-                    warn!(
-                        "⚠️ Could not enumerate audio devices ({}), proceeding anyway",
-                        e
-                    );
-                }
-            }
+/// Encodes with the media processor's current codec for a track type
+///
+/// The processor is only locked to look the codec up, so it stays free for
+/// the publish path while the pipeline worker encodes.
+#[cfg(feature = "media")]
+#[derive(Debug)]
+struct ProcessorEncoder {
+    processor: Arc<tokio::sync::Mutex<MediaProcessor>>,
+    track_type: quicrtc_core::MoqTrackType,
+    info: CodecInfo,
+}
 
-            inner.audio_renderer = Some(Arc::new(tokio::sync::Mutex::new(audio_renderer)));
-        }
+#[cfg(feature = "media")]
+impl SyncEncoder for ProcessorEncoder {
+    fn encode_sync(&self, frame: &MediaFrame) -> Result<Vec<u8>, QuicRtcError> {
+        // Pipeline workers are plain threads, so waiting on the lock is fine
+        let codec = self
+            .processor
+            .blocking_lock()
+            .outgoing_codec(&self.track_type)?;
+        codec.encode_sync(frame)
+    }
 
-        Ok(())
+    fn get_codec_info(&self) -> CodecInfo {
+        self.info.clone()
     }
+}
 
-    /// Connect to signaling server
-    #[cfg(feature = "signaling")]
-    async fn connect_signaling(
-        &self,
-        inner: &mut RoomInner,
-        signaling_url: &str,
-    ) -> Result<(), QuicRtcError> {
-        // Create participant info for signaling
-        let participant_info = PeerInfo {
-            id: self.participant_id.clone(),
-            name: None, // Could be set from config in the future
-            room_id: self.id.clone(),
-            quic_endpoint: None, // Will be set when MoQ transport is ready
-            capabilities: vec!["h264".to_string(), "opus".to_string()], // Basic capabilities
-            last_seen: chrono::Utc::now(),
-            status: PeerStatus::Online,
+/// Sends an outgoing track through its encode pipeline worker
+///
+/// Frames are prepared by the media processor and queued without waiting
+/// for the encoder. Encoded frames come back on the stream returned by
+/// [`TrackEncoder::start`] and are numbered into groups, each keyframe
+/// opening a new one.
+#[cfg(feature = "media")]
+#[derive(Debug)]
+struct TrackEncoder {
+    track_id: String,
+    namespace: TrackNamespace,
+    pipeline: Arc<std::sync::Mutex<EncodePipeline>>,
+    processor: Arc<tokio::sync::Mutex<MediaProcessor>>,
+    frames_submitted: u64,
+    group_id: u64,
+    object_id: u64,
+}
+
+#[cfg(feature = "media")]
+impl TrackEncoder {
+    /// Start the encode worker of `track_id`
+    async fn start(
+        track_id: &str,
+        track_type: quicrtc_core::MoqTrackType,
+        namespace: TrackNamespace,
+        pipeline: Arc<std::sync::Mutex<EncodePipeline>>,
+        processor: Arc<tokio::sync::Mutex<MediaProcessor>>,
+    ) -> Result<(Self, tokio::sync::mpsc::Receiver<EncodedFrame>), QuicRtcError> {
+        let info = {
+            let processor = processor.lock().await;
+            let codec = processor.outgoing_codec(&track_type)?;
+            SyncEncoder::get_codec_info(codec.as_ref())
+        };
+        let encoder = ProcessorEncoder {
+            processor: processor.clone(),
+            track_type,
+            info,
         };
+        let encoded = pipeline
+            .lock()
+            .unwrap()
+            .add_track(track_id, Box::new(encoder))
+            .map_err(|e| QuicRtcError::MediaProcessing {
+                reason: format!("Failed to start encoder of track {}: {}", track_id, e),
+            })?;
 
-        let signaling_connection = SignalingConnection {
-            server: Arc::new(SignalingServer::new(signaling_url.parse().map_err(
-                |_| QuicRtcError::InvalidData {
-                    reason: "Invalid signaling URL".to_string(),
-                },
-            )?)),
-            participant_info,
-            discovered_peers: std::collections::HashMap::new(),
+        let encoder = Self {
+            track_id: track_id.to_string(),
+            namespace,
+            pipeline,
+            processor,
+            frames_submitted: 0,
+            group_id: 0,
+            object_id: 0,
         };
+        Ok((encoder, encoded))
+    }
 
-        inner.signaling_connection = Some(Arc::new(tokio::sync::Mutex::new(signaling_connection)));
-        Ok(())
+    /// Queue `frame` for encoding, dropping a frame if the encoder is behind
+    async fn submit(&mut self, frame: MediaFrame) -> Result<(), QuicRtcError> {
+        let frame = {
+            let mut processor = self.processor.lock().await;
+            let starts_group = self.frames_submitted == 0 || processor.keyframe_due();
+            processor.prepare_outgoing_frame(frame, starts_group)?
+        };
+        self.frames_submitted += 1;
+        self.pipeline
+            .lock()
+            .unwrap()
+            .submit(&self.track_id, frame)
+            .map_err(|e| QuicRtcError::MediaProcessing {
+                reason: e.to_string(),
+            })
     }
 
-    /// Establish MoQ over QUIC transport
-    async fn establish_moq_transport(
-        &self,
-        inner: &mut RoomInner,
-        quic_rtc: &QuicRtc,
+    /// Send a frame the worker encoded
+    async fn send(
+        &mut self,
+        frame: EncodedFrame,
+        transport: &MoqOverQuicTransport,
     ) -> Result<(), QuicRtcError> {
-        // Use default QUIC endpoint for now (could be configurable)
-        let endpoint = "127.0.0.1:7878"
-            .parse()
-            .map_err(|_| QuicRtcError::InvalidData {
-                reason: "Invalid QUIC endpoint".to_string(),
-            })?;
-
-        // Create connection config with resource limits
-        let mut connection_config = ConnectionConfig::default();
-        if let Some(limits) = &self.resource_limits {
-            // Convert our ResourceLimits to transport::ResourceLimits
-            let transport_limits = quicrtc_core::transport::ResourceLimits {
-                max_memory_mb: limits.max_memory_mb,
-                max_bandwidth_kbps: limits.max_bandwidth_kbps,
-                max_connections: limits.max_connections,
-                max_streams_per_connection: limits.max_streams_per_connection,
-                cleanup_timeout: limits.cleanup_timeout,
-                connection_pool_size: 2, // Default value for connection pool
-            };
-            connection_config.resource_limits = Some(transport_limits);
+        if frame.is_keyframe && self.object_id > 0 {
+            self.group_id += 1;
+            self.object_id = 0;
         }
+        let object = self.processor.lock().await.package_encoded_frame(
+            frame,
+            self.namespace.clone(),
+            self.group_id,
+            self.object_id,
+        )?;
+        self.object_id += 1;
+        transport.send_moq_object(object).await
+    }
+}
 
-        // Create MoQ session ID
-        let session_id = rand::random::<u64>();
-
-        // Establish MoQ over QUIC transport
-        let moq_transport =
-            MoqOverQuicTransport::new(endpoint, connection_config, session_id).await?;
+#[cfg(feature = "media")]
+impl Drop for TrackEncoder {
+    /// Stop the encode worker, dropping frames it has not encoded yet
+    fn drop(&mut self) {
+        if let Ok(mut pipeline) = self.pipeline.lock() {
+            pipeline.remove_track(&self.track_id);
+        }
+    }
+}
 
-        // Establish MoQ session
-        moq_transport.establish_session().await?;
+/// Subscribed video track with a decode worker
+#[cfg(feature = "media")]
+#[derive(Debug)]
+struct DecodedTrack {
+    participant_id: String,
+    track_id: String,
+    /// How long after arrival a frame is due on screen
+    playout_delay: Duration,
+}
 
-        inner.moq_transport = Some(Arc::new(moq_transport));
-        Ok(())
-    }
+/// Decode workers of subscribed video tracks
+///
+/// Workers are keyed by participant and track ID, as track IDs are only
+/// unique per participant.
+#[cfg(feature = "media")]
+#[derive(Debug)]
+struct VideoDecoding {
+    pipeline: DecodePipeline,
+    tracks: std::collections::HashMap<TrackNamespace, DecodedTrack>,
+}
 
-    /// Get room ID
-    pub fn id(&self) -> &str {
-        &self.id
+#[cfg(feature = "media")]
+impl VideoDecoding {
+    fn new(config: DecodePipelineConfig) -> Self {
+        Self {
+            pipeline: DecodePipeline::new(config),
+            tracks: std::collections::HashMap::new(),
+        }
     }
 
-    /// Get participant ID
-    pub fn participant_id(&self) -> &str {
-        &self.participant_id
+    fn worker_key(participant_id: &str, track_id: &str) -> String {
+        format!("{}/{}", participant_id, track_id)
     }
 
-    /// Get room configuration
-    pub fn config(&self) -> &RoomConfig {
-        &self.config
+    /// Start the decode worker of a track and return its decoded frames
+    fn add_track(
+        &mut self,
+        namespace: TrackNamespace,
+        track: DecodedTrack,
+        codec: &dyn Codec,
+    ) -> Result<tokio::sync::mpsc::Receiver<DecodedFrame>, QuicRtcError> {
+        let key = Self::worker_key(&track.participant_id, &track.track_id);
+        let frames = self
+            .pipeline
+            .add_track(&key, codec.clone_decoder())
+            .map_err(|e| QuicRtcError::MediaProcessing {
+                reason: format!("Failed to start decoder of track {}: {}", key, e),
+            })?;
+        self.tracks.insert(namespace, track);
+        Ok(frames)
     }
 
-    /// Get audio configuration
-    #[cfg(feature = "media")]
-    pub fn audio_config(&self) -> Option<&AudioProcessingConfig> {
-        self.audio_config.as_ref()
+    /// Stop the decode worker of a track, if it has one
+    fn remove_track(&mut self, namespace: &TrackNamespace) {
+        if let Some(track) = self.tracks.remove(namespace) {
+            self.pipeline
+                .remove_track(&Self::worker_key(&track.participant_id, &track.track_id));
+        }
     }
 
-    /// Get video configuration
-    #[cfg(feature = "media")]
-    pub fn video_config(&self) -> Option<&VideoProcessingConfig> {
-        self.video_config.as_ref()
+    fn is_decoding(&self, namespace: &TrackNamespace) -> bool {
+        self.tracks.contains_key(namespace)
     }
 
-    /// Get resource limits
-    pub fn resource_limits(&self) -> Option<&ResourceLimits> {
-        self.resource_limits.as_ref()
+    /// Queue an assembled group of a decoded track
+    ///
+    /// The group's frame is due a playout delay from now; if the worker
+    /// only gets to it later, it is skipped.
+    fn submit(&self, namespace: &TrackNamespace, frame: MediaFrame) -> Result<(), QuicRtcError> {
+        let format = FrameFormat::of(&frame);
+        let (Some(track), MediaFrame::Video(video)) = (self.tracks.get(namespace), frame) else {
+            return Ok(());
+        };
+        let encoded = EncodedFrame {
+            track_id: track.track_id.clone(),
+            data: video.data,
+            format,
+            timestamp: video.timestamp,
+            // Groups are assembled from their keyframe on
+            is_keyframe: true,
+            encode_time: Duration::ZERO,
+        };
+        self.pipeline
+            .submit(
+                &Self::worker_key(&track.participant_id, &track.track_id),
+                encoded,
+                std::time::Instant::now() + track.playout_delay,
+            )
+            .map_err(|e| QuicRtcError::MediaProcessing {
+                reason: e.to_string(),
+            })
     }
 
-    /// Get maximum participants
-    pub fn max_participants(&self) -> Option<usize> {
-        self.max_participants
+    fn stats(&self, participant_id: &str, track_id: &str) -> Option<DecodeStats> {
+        self.pipeline
+            .stats(&Self::worker_key(participant_id, track_id))
     }
 }
 
+/// Sends the thumbnail rendition of an outgoing video track
 #[cfg(feature = "media")]
-impl Room {
-    /// Publish camera with default settings
-    pub async fn publish_camera(&mut self) -> Result<crate::VideoTrack, crate::QuicRtcError> {
-        info!("📹 Publishing camera track");
-
-        // Note: Camera permissions are checked when video capture is initialized
-        // per user preferences [[memory:3911748]]
+#[derive(Debug)]
+struct ThumbnailSender {
+    generator: ThumbnailGenerator,
+    namespace: TrackNamespace,
+    bitrate: u32,
+    group_id: u64,
+}
 
-        // First, get the transport and do pre-flight checks
-        let (moq_transport, track_id) = {
-            let inner = self.inner.read().await;
+#[cfg(feature = "media")]
+impl ThumbnailSender {
+    /// Send a thumbnail of `frame` if one is due
+    async fn offer(
+        &mut self,
+        frame: &VideoFrame,
+        processor: &tokio::sync::Mutex<MediaProcessor>,
+        transport: &MoqOverQuicTransport,
+    ) {
+        let Some(thumbnail) = self.generator.offer(frame, std::time::Instant::now()) else {
+            return;
+        };
+        let object = processor.lock().await.prepare_thumbnail_object(
+            thumbnail,
+            self.namespace.clone(),
+            self.group_id,
+            self.bitrate,
+        );
+        self.group_id += 1;
+        match object {
+            Ok(object) => {
+                if let Err(e) = transport.send_moq_object(object).await {
+                    debug!("Failed to send thumbnail: {}", e);
+                }
+            }
+            Err(e) => debug!("Failed to encode thumbnail: {}", e),
+        }
+    }
+}
 
-            // Ensure we're connected
-            if inner.state != RoomState::Connected {
-                return Err(QuicRtcError::InvalidState {
-                    expected: "Connected".to_string(),
-                    actual: format!("{:?}", inner.state),
-                });
+/// Compose and send frames for a composition track until stopped
+#[cfg(feature = "media")]
+#[allow(clippy::too_many_arguments)]
+async fn send_composition(
+    compositor: Arc<tokio::sync::Mutex<VideoCompositor>>,
+    inner: std::sync::Weak<RwLock<RoomInner>>,
+    mut encoder: TrackEncoder,
+    mut encoded: tokio::sync::mpsc::Receiver<EncodedFrame>,
+    transport: Arc<MoqOverQuicTransport>,
+    frame_interval: Duration,
+    mut thumbnails: Option<ThumbnailSender>,
+    stop: CancellationToken,
+) {
+    let started = std::time::Instant::now();
+    let mut ticker = tokio::time::interval(frame_interval);
+    loop {
+        tokio::select! {
+            _ = stop.cancelled() => break,
+            frame = encoded.recv() => {
+                let Some(frame) = frame else {
+                    break;
+                };
+                if let Err(e) = encoder.send(frame, &transport).await {
+                    debug!("Failed to send composed frame: {}", e);
+                }
+                continue;
             }
-
-            // Ensure video is enabled in configuration
-            if !self.config.video_enabled {
-                return Err(QuicRtcError::InvalidData {
-                    reason: "video_enabled must be true to publish camera".to_string(),
-                });
+            _ = ticker.tick() => {}
+        }
+        let Some(room) = inner.upgrade() else {
+            break;
+        };
+        let speaking: Vec<String> = {
+            let inner = room.read().await;
+            if inner.audio_only {
+                continue;
             }
+            inner
+                .participants
+                .speaking()
+                .map(|participant| participant.id().to_string())
+                .collect()
+        };
+        drop(room);
+
+        let frame = {
+            let mut compositor = compositor.lock().await;
+            if compositor.layout() == CompositionLayout::ActiveSpeaker {
+                // Keep featuring the last speaker through pauses
+                if let Some(speaker) = speaking.iter().find(|id| compositor.has_source(id)) {
+                    compositor.set_active_speaker(Some(speaker.as_str()));
+                }
+            }
+            compositor.compose(started.elapsed().as_millis() as u64)
+        };
+        if let Some(thumbnails) = thumbnails.as_mut() {
+            thumbnails
+                .offer(&frame, &encoder.processor, &transport)
+                .await;
+        }
+        if let Err(e) = encoder.submit(MediaFrame::Video(frame)).await {
+            debug!("Failed to encode composed frame: {}", e);
+        }
+    }
+}
 
-            // Get transport reference
-            let transport = inner
-                .moq_transport
-                .as_ref()
-                .ok_or_else(|| QuicRtcError::InvalidState {
-                    expected: "MoQ transport connected".to_string(),
-                    actual: "MoQ transport not available".to_string(),
-                })?
-                .clone();
+/// Snapshot of a room for crash bundles, without waiting on its lock
+#[cfg(feature = "diagnostics")]
+fn crash_state(
+    room_id: &str,
+    participant_id: &str,
+    inner: &std::sync::Weak<RwLock<RoomInner>>,
+) -> serde_json::Value {
+    let Some(inner) = inner.upgrade() else {
+        return serde_json::json!({ "room_id": room_id, "state": "dropped" });
+    };
+    let Ok(inner) = inner.try_read() else {
+        return serde_json::json!({ "room_id": room_id, "state": "locked" });
+    };
+    let local_tracks: Vec<_> = inner
+        .local_participant
+        .iter()
+        .flat_map(|participant| participant.local_tracks())
+        .map(|track| {
+            serde_json::json!({
+                "id": track.id(),
+                "kind": track.kind().to_string(),
+                "stats": track.stats(),
+            })
+        })
+        .collect();
+    let remote_participants: Vec<_> = inner
+        .participants
+        .iter()
+        .map(|participant| {
+            let tracks: Vec<_> = participant
+                .remote_tracks()
+                .map(|track| {
+                    serde_json::json!({
+                        "id": track.id(),
+                        "kind": track.kind().to_string(),
+                        "stats": track.stats(),
+                    })
+                })
+                .collect();
+            serde_json::json!({ "id": participant.id(), "tracks": tracks })
+        })
+        .collect();
+    serde_json::json!({
+        "room_id": room_id,
+        "participant_id": participant_id,
+        "state": format!("{:?}", inner.state),
+        "transport_mode": inner
+            .moq_transport
+            .as_ref()
+            .map(|transport| format!("{:?}", transport.transport_mode())),
+        "local_tracks": local_tracks,
+        "remote_participants": remote_participants,
+    })
+}
 
-            let track_id = format!("camera-{}", uuid::Uuid::new_v4());
-            (transport, track_id)
-        };
+/// Signaling connection wrapper
+#[cfg(feature = "signaling")]
+#[derive(Debug)]
+struct SignalingConnection {
+    /// Connection to the signaling server
+    client: Arc<SignalingClient>,
+    /// Our participant info for signaling
+    participant_info: PeerInfo,
+    /// Other participants discovered via signaling
+    discovered_peers: std::collections::HashMap<String, PeerInfo>,
+    /// Answer that set up the media session, if one was negotiated
+    negotiated_session: Option<MoqSessionAnswer>,
+}
 
-        // Start video capture
-        {
-            let inner = self.inner.read().await;
-            let video_capture =
-                inner
-                    .video_capture
-                    .as_ref()
-                    .ok_or_else(|| QuicRtcError::InvalidState {
-                        expected: "Video capture initialized".to_string(),
-                        actual: "Video capture not available".to_string(),
-                    })?;
+/// Published track metadata
+#[cfg(feature = "media")]
+#[derive(Debug)]
+struct PublishedTrack {
+    /// Track ID
+    track_id: String,
+    /// Track type (video/audio)
+    track_type: TrackType,
+    /// Codec the track is encoded with
+    codec: String,
+    /// MoQ track for transport
+    moq_track: MoqTrack,
+    /// Whether track is currently muted
+    muted: bool,
+    /// Track publication time
+    published_at: std::time::Instant,
+}
 
-            let mut capture_manager = video_capture.lock().await;
+/// Track type enumeration
+#[cfg(feature = "media")]
+#[derive(Debug, Clone, PartialEq)]
+enum TrackType {
+    Video,
+    Audio,
+}
 
-            // Use video config or defaults
-            let video_config = self.video_config.as_ref();
-            let framerate = video_config.map(|c| c.default_framerate).unwrap_or(30.0);
+impl RoomInner {
+    /// Current statistics of every local and remote track
+    fn track_reports(&self) -> Vec<CallTrackReport> {
+        let local = self.local_participant.iter().flat_map(|participant| {
+            participant.local_tracks().map(|track| CallTrackReport {
+                track_id: track.id().to_string(),
+                participant_id: None,
+                kind: track.kind().to_string(),
+                stats: track.stats().clone(),
+            })
+        });
+        let remote = self.participants.iter().flat_map(|participant| {
+            participant.remote_tracks().map(|track| CallTrackReport {
+                track_id: track.id().to_string(),
+                participant_id: Some(participant.id().to_string()),
+                kind: track.kind().to_string(),
+                stats: track.stats().clone(),
+            })
+        });
+        local.chain(remote).collect()
+    }
 
-            // TODO: Use video quality from config to determine resolution
-            let (width, height) = match self.config.video_quality {
-                VideoQuality::Low => (320, 240),
-                VideoQuality::Standard => (640, 480),
-                VideoQuality::HD => (1280, 720),
-                VideoQuality::FullHD => (1920, 1080),
-            };
+    /// Derive quality levels from freshly refreshed track statistics and
+    /// report the ones that changed
+    fn update_quality(&mut self) {
+        let tracks = self.track_reports();
+        for track in &tracks {
+            let sample = QualitySample::from_track(&track.stats);
+            if let Some(level) = self.quality.update_track(&track.track_id, sample) {
+                self.events.emit(crate::Event::ConnectionQualityChanged {
+                    track_id: Some(track.track_id.clone()),
+                    level,
+                });
+            }
+        }
+        self.quality
+            .retain_tracks(tracks.iter().map(|track| track.track_id.as_str()));
+
+        let stats: Vec<&TrackStats> = tracks.iter().map(|track| &track.stats).collect();
+        let sample =
+            QualitySample::from_connection(&ConnectionSample::from_tracks(&tracks), &stats);
+        if let Some(level) = self.quality.update_connection(sample) {
+            self.events.emit(crate::Event::ConnectionQualityChanged {
+                track_id: None,
+                level,
+            });
+        }
+    }
 
-            // Initialize video capture
-            let device_id = "0"; // Use first available device
-            let capture_config = quicrtc_media::NewVideoCaptureConfig {
-                resolution: quicrtc_media::VideoResolution::new(width, height),
-                framerate,
-                pixel_format: quicrtc_media::VideoPixelFormat::YUV420P,
-                hardware_acceleration: true,
-                buffer_size: 3,
-                enable_processing: true,
-            };
+    /// Add a remote participant and register their audio with the mixer
+    ///
+    /// Volume and local mute preferences from an earlier session of the
+    /// same participant in this room are restored.
+    pub async fn add_remote_participant(
+        &mut self,
+        participant: crate::RemoteParticipant,
+    ) -> Result<(), crate::participant::ParticipantError> {
+        #[cfg(feature = "media")]
+        let participant_id = participant.id().to_string();
+
+        self.participants.add_remote_participant(participant)?;
 
-            capture_manager
-                .start_capture(device_id, capture_config)
+        #[cfg(feature = "media")]
+        if let (Some(mixer), Some(participant)) = (
+            &self.audio_mixer,
+            self.participants.get_remote_participant(&participant_id),
+        ) {
+            mixer
+                .lock()
                 .await
-                .map_err(|e| QuicRtcError::MediaProcessing {
-                    reason: format!("Video capture failed: {}", e),
-                })?;
+                .add_source(&participant_id, participant.audio_settings());
         }
 
-        // Create MoQ track for video
-        let track_namespace = TrackNamespace {
-            namespace: format!("room.{}", self.id),
-            track_name: format!("{}/camera", self.participant_id),
-        };
+        Ok(())
+    }
+
+    /// Remove a remote participant and stop mixing their audio
+    pub async fn remove_remote_participant(
+        &mut self,
+        participant_id: &str,
+    ) -> Option<crate::RemoteParticipant> {
+        #[cfg(feature = "media")]
+        {
+            self.remote_audio
+                .lock()
+                .unwrap()
+                .retain(|_, participant| participant != participant_id);
+            if let Some(mixer) = &self.audio_mixer {
+                mixer.lock().await.remove_source(participant_id);
+            }
+        }
 
-        let moq_track = MoqTrack {
-            namespace: track_namespace.clone(),
-            name: "camera".to_string(),
-            track_type: quicrtc_core::MoqTrackType::Video,
+        self.participants.remove_remote_participant(participant_id)
+    }
+
+    /// Start rendering the mixed remote audio, unless it already plays
+    ///
+    /// Playout starts with the first remote audio subscription; a room
+    /// without a working output device still receives, it just plays nothing.
+    #[cfg(feature = "media")]
+    async fn start_audio_playout(&mut self) {
+        if self.audio_output.is_some() {
+            return;
+        }
+        let (Some(renderer), Some(mixer)) = (&self.audio_renderer, &self.audio_mixer) else {
+            return;
         };
+        let config = {
+            let mixer = mixer.lock().await;
+            AudioRenderConfig {
+                sample_rate: mixer.sample_rate(),
+                channels: mixer.layout().channel_count(),
+                ..AudioRenderConfig::default()
+            }
+        };
+        let output = match renderer.lock().await.start(config) {
+            Ok(output) => output,
+            Err(e) => {
+                warn!("⚠️ Could not start playing remote audio: {}", e);
+                return;
+            }
+        };
+        let task = tokio::spawn(play_remote_audio(mixer.clone(), output.clone()));
+        self.background_tasks.push(task);
+        self.audio_output = Some(output);
+    }
 
-        // Announce track
-        moq_transport.announce_track(moq_track.clone()).await?;
+    /// Detach the room's background tasks, signaling connection and media
+    /// transport so they can be closed without holding the room lock
+    fn take_connections(&mut self) -> RoomConnections {
+        // Their sender tasks stop with the other background tasks
+        #[cfg(feature = "media")]
+        self.outbound_audio.clear();
+        RoomConnections {
+            background_tasks: std::mem::take(&mut self.background_tasks),
+            #[cfg(feature = "signaling")]
+            signaling_connection: self.signaling_connection.take(),
+            moq_transport: self.moq_transport.take(),
+        }
+    }
 
-        // Store published track info with write lock
+    /// Dispatch an event to every stream returned by [`Room::events`]
+    pub fn emit(&self, event: crate::Event) {
+        self.events.emit(event);
+    }
+
+    /// Apply a publisher's mute change to a remote track and emit
+    /// [`crate::Event::TrackMuteChanged`]
+    pub fn set_remote_track_muted(&mut self, participant_id: &str, track_id: &str, muted: bool) {
+        if let Some(Some(event)) = self
+            .participants
+            .update_participant(participant_id, |p| p.set_track_muted(track_id, muted))
         {
-            let mut inner = self.inner.write().await;
-            let published_track = PublishedTrack {
-                track_id: track_id.clone(),
-                track_type: TrackType::Video,
-                moq_track,
-                muted: false,
-                published_at: std::time::Instant::now(),
-            };
-            inner
-                .published_tracks
-                .insert(track_id.clone(), published_track);
+            self.emit(event);
         }
+    }
 
-        // Create and return video track
-        let video_track = VideoTrack::new(track_id);
-
-        info!("✅ Camera track published successfully");
-        Ok(video_track)
+    /// Apply a publisher's simulcast layer announcement to a remote track and
+    /// emit [`crate::Event::TrackSimulcastLayersChanged`]
+    pub fn set_remote_track_simulcast_layers(
+        &mut self,
+        participant_id: &str,
+        track_id: &str,
+        layers: Vec<crate::SimulcastLayer>,
+    ) {
+        if let Some(Some(event)) = self.participants.update_participant(participant_id, |p| {
+            p.set_track_simulcast_layers(track_id, layers)
+        }) {
+            self.emit(event);
+        }
     }
 
-    /// Publish microphone with default settings
-    pub async fn publish_microphone(&mut self) -> Result<crate::AudioTrack, crate::QuicRtcError> {
-        info!("🎵 Publishing microphone track");
+    /// Apply a publisher's description of a remote track and emit
+    /// [`crate::Event::TrackMetadataChanged`]
+    pub fn set_remote_track_metadata(
+        &mut self,
+        participant_id: &str,
+        track_id: &str,
+        source: crate::track::TrackSource,
+        metadata: crate::TrackMetadata,
+    ) {
+        if let Some(Some(event)) = self.participants.update_participant(participant_id, |p| {
+            p.set_track_metadata(track_id, source, metadata)
+        }) {
+            self.emit(event);
+        }
+    }
 
-        // Check microphone permissions first per user preferences [[memory:3911748]]
-        #[cfg(target_family = "unix")]
+    /// Add a participant announced by signaling and emit
+    /// [`crate::Event::ParticipantJoined`]
+    ///
+    /// Does nothing if the participant is already in the room.
+    #[cfg(feature = "signaling")]
+    async fn admit_remote_participant(&mut self, participant: crate::RemoteParticipant) {
+        if self
+            .participants
+            .get_remote_participant(participant.id())
+            .is_some()
         {
-            // Do permission check before acquiring any locks
-            let inner_read = self.inner.read().await;
-            if let Some(audio_renderer) = &inner_read.audio_renderer {
-                let renderer = audio_renderer.lock().await;
-                // Try to check if audio devices are available
-                match renderer.list_devices() {
-                    Ok(devices) => {
-                        if devices.is_empty() {
-                            return Err(QuicRtcError::MediaProcessing {
-                                reason: "No audio input devices available - check permissions"
-                                    .to_string(),
-                            });
-                        }
-                        debug!(
-                            "✅ Microphone permissions verified - {} devices available",
-                            devices.len()
-                        );
-                    }
-                    Err(e) => {
-                        // If device enumeration fails, assume it's available for now
-                        debug!(
-                            "⚠️ Could not enumerate audio devices ({}), assuming available",
-                            e
-                        );
-                    }
-                }
-            }
+            return;
+        }
+        let participant_id = participant.id().to_string();
+        if let Err(e) = self.add_remote_participant(participant).await {
+            warn!("Not adding participant {}: {}", participant_id, e);
+            return;
+        }
+        if let Some(participant) = self.participants.get_remote_participant(&participant_id) {
+            self.emit(crate::Event::ParticipantJoined {
+                participant: participant.clone(),
+            });
         }
+    }
 
-        // Get transport and do pre-flight checks
-        let (moq_transport, track_id) = {
-            let inner = self.inner.read().await;
+    /// Apply the track descriptions of a participant's session offer
+    ///
+    /// The participant and any tracks we didn't know of yet are added,
+    /// unsubscribed, emitting [`crate::Event::TrackReceived`] for each new
+    /// track. Tracks we already know of get the offered metadata.
+    #[cfg(feature = "signaling")]
+    async fn apply_published_tracks(&mut self, participant_id: &str, published: &[OfferedTrack]) {
+        use crate::track::TrackSource;
 
-            // Ensure we're connected
-            if inner.state != RoomState::Connected {
-                return Err(QuicRtcError::InvalidState {
-                    expected: "Connected".to_string(),
-                    actual: format!("{:?}", inner.state),
-                });
+        self.admit_remote_participant(crate::RemoteParticipant::new(participant_id.to_string()))
+            .await;
+        let Some(tracks) = self.participants.tracks_of(participant_id) else {
+            return;
+        };
+        let known: std::collections::HashMap<String, String> = tracks
+            .into_iter()
+            .map(|track| {
+                (
+                    track.moq_track().namespace.canonical(),
+                    track.id().to_string(),
+                )
+            })
+            .collect();
+
+        for published in published {
+            let source = TrackSource::from_name(&published.source);
+            let metadata = crate::TrackMetadata {
+                label: published.label.clone(),
+                dimensions: published.width.zip(published.height),
+                codec: published.codec.clone(),
+            };
+            if let Some(track_id) = known.get(&published.namespace) {
+                self.set_remote_track_metadata(participant_id, track_id, source, metadata);
+                continue;
             }
 
-            // Ensure audio is enabled in configuration
-            if !self.config.audio_enabled {
-                return Err(QuicRtcError::InvalidData {
-                    reason: "audio_enabled must be true to publish microphone".to_string(),
-                });
+            let namespace = match TrackNamespace::parse(&published.namespace) {
+                Ok(namespace) if namespace.participant_id() == Some(participant_id) => namespace,
+                Ok(_) => {
+                    warn!(
+                        "Ignoring track {} offered by {}: published by someone else",
+                        published.namespace, participant_id
+                    );
+                    continue;
+                }
+                Err(e) => {
+                    warn!(
+                        "Ignoring track {} offered by {}: {}",
+                        published.namespace, participant_id, e
+                    );
+                    continue;
+                }
+            };
+            // Track IDs are the track name without the publisher's segment,
+            // matching the IDs publishers use for their local tracks
+            let track_id = namespace
+                .track_name
+                .split_once('/')
+                .map(|(_, name)| name.to_string())
+                .unwrap_or_else(|| published.source.clone());
+            let audio = matches!(source, TrackSource::Microphone | TrackSource::SystemAudio);
+            let moq_track = MoqTrack::new(
+                namespace,
+                track_id.clone(),
+                if audio {
+                    quicrtc_core::MoqTrackType::Audio
+                } else {
+                    quicrtc_core::MoqTrackType::Video
+                },
+            );
+            let mut track = if audio {
+                crate::RemoteTrack::audio(track_id, participant_id.to_string(), source, moq_track)
+            } else {
+                crate::RemoteTrack::video(track_id, participant_id.to_string(), source, moq_track)
+            };
+            track.set_subscribed(false);
+            track.set_metadata(source, metadata);
+
+            let track_event = track.clone();
+            self.participants
+                .update_participant(participant_id, |p| p.add_remote_track(track));
+            self.emit(crate::Event::TrackReceived { track: track_event });
+        }
+    }
+
+    /// Start decoding a subscribed video track, sending its pictures to
+    /// [`Room::remote_video`] receivers
+    #[cfg(feature = "media")]
+    fn start_video_decode(
+        &mut self,
+        namespace: TrackNamespace,
+        track: DecodedTrack,
+        codec: &dyn Codec,
+    ) -> Result<(), QuicRtcError> {
+        let participant_id = track.participant_id.clone();
+        let track_id = track.track_id.clone();
+        let mut frames = self
+            .video_decoding
+            .lock()
+            .unwrap()
+            .add_track(namespace, track, codec)?;
+        let remote_video = self.remote_video.clone();
+
+        // Ends when the track's decode worker is stopped
+        let task = tokio::spawn(async move {
+            while let Some(decoded) = frames.recv().await {
+                if let MediaFrame::Video(frame) = decoded.frame {
+                    // Nobody watching is fine
+                    let _ = remote_video.send(crate::RemoteVideoFrame {
+                        participant_id: participant_id.clone(),
+                        track_id: track_id.clone(),
+                        frame,
+                    });
+                }
             }
+        });
+        self.background_tasks.push(task);
+        Ok(())
+    }
 
-            // Get transport reference
-            let transport = inner
-                .moq_transport
-                .as_ref()
-                .ok_or_else(|| QuicRtcError::InvalidState {
-                    expected: "MoQ transport connected".to_string(),
-                    actual: "MoQ transport not available".to_string(),
-                })?
-                .clone();
+    /// Record a subscription change on a remote track and emit the matching event
+    fn set_remote_track_subscribed(
+        &mut self,
+        participant_id: &str,
+        track_id: &str,
+        subscribed: bool,
+    ) {
+        if let Some(Some(event)) = self.participants.update_participant(participant_id, |p| {
+            p.set_track_subscribed(track_id, subscribed)
+        }) {
+            self.emit(event);
+        }
+    }
+}
+
+impl Room {
+    /// Quick join - simplest possible API
+    pub async fn quick_join(room_id: &str, participant_id: &str) -> Result<Self, QuicRtcError> {
+        QuicRtc::init()
+            .await?
+            .room(room_id)
+            .participant(participant_id)
+            .enable_video()
+            .enable_audio()
+            .join()
+            .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn join_internal(
+        quic_rtc: Arc<QuicRtc>,
+        room_id: String,
+        participant_id: String,
+        config: RoomConfig,
+        #[cfg(feature = "media")] audio_config: Option<AudioProcessingConfig>,
+        #[cfg(feature = "media")] video_config: Option<VideoProcessingConfig>,
+        #[cfg(feature = "media")] encode_pipeline: EncodePipelineConfig,
+        #[cfg(feature = "media")] decode_pipeline: DecodePipelineConfig,
+        #[cfg(feature = "signaling")] signaling_config: Option<SignalingConfig>,
+        resource_limits: Option<ResourceLimits>,
+        max_participants: Option<usize>,
+        #[cfg(feature = "signaling")] schedule: Option<RoomSchedule>,
+        #[cfg(feature = "signaling")] access_token: Option<String>,
+        audio_session: Option<Arc<dyn AudioSession>>,
+        cancellation: Option<CancellationToken>,
+        events: EventBus,
+    ) -> Result<Self, QuicRtcError> {
+        info!(
+            "🏠 Joining room '{}' as participant '{}'",
+            room_id, participant_id
+        );
+
+        // Room events fan out to every stream returned by `Room::events`
+        // and `RoomBuilder::events`
+        let call_recorder = (config.call_report || config.call_report_path.is_some()).then(|| {
+            let recorder = Arc::new(CallRecorder::new());
+            events.record_to(recorder.clone());
+            recorder
+        });
+        let stats_history = config.stats_history.map(StatsHistory::new);
+        #[cfg(feature = "diagnostics")]
+        let debug_log = config.debug_log_window.map(|max_age| {
+            let ring = Arc::new(LogRing::for_room(
+                room_id.clone(),
+                LogRingConfig {
+                    max_age,
+                    ..LogRingConfig::default()
+                },
+            ));
+            DebugLogger::global().attach(&ring);
+            ring
+        });
+
+        // Initialize room with disconnected state
+        let room_inner = RoomInner {
+            state: RoomState::Disconnected,
+            moq_transport: None,
+            #[cfg(feature = "signaling")]
+            signaling_connection: None,
+            #[cfg(feature = "media")]
+            media_processor: None,
+            #[cfg(feature = "media")]
+            video_capture: None,
+            #[cfg(feature = "media")]
+            audio_renderer: None,
+            #[cfg(feature = "media")]
+            audio_mixer: None,
+            #[cfg(feature = "media")]
+            remote_audio: Arc::default(),
+            #[cfg(feature = "media")]
+            audio_output: None,
+            participants: crate::Participants::new(),
+            local_participant: None,
+            #[cfg(feature = "media")]
+            published_tracks: std::collections::HashMap::new(),
+            #[cfg(feature = "media")]
+            outbound_audio: std::collections::HashMap::new(),
+            #[cfg(feature = "media")]
+            encode_pipeline: Arc::new(std::sync::Mutex::new(EncodePipeline::new(encode_pipeline))),
+            #[cfg(feature = "media")]
+            video_decoding: Arc::new(std::sync::Mutex::new(VideoDecoding::new(decode_pipeline))),
+            #[cfg(feature = "media")]
+            remote_video: tokio::sync::broadcast::channel(REMOTE_VIDEO_CAPACITY).0,
+            #[cfg(feature = "media")]
+            local_voice: audio_config
+                .as_ref()
+                .and_then(|config| config.ducking.as_ref())
+                .map(|ducking| {
+                    VoiceActivityDetector::new(ducking.speech_threshold_dbfs, ducking.hangover)
+                }),
+            audio_only: false,
+            audio_only_subscriptions: Vec::new(),
+            #[cfg(feature = "media")]
+            paused_camera: None,
+            #[cfg(feature = "media")]
+            follow_default_devices: audio_config
+                .as_ref()
+                .is_some_and(|config| config.follow_default_devices),
+            audio_session,
+            audio_route_policy: AudioRoutePolicy::default(),
+            audio_route: None,
+            quality: RoomQuality::default(),
+            events: events.clone(),
+            background_tasks: Vec::new(),
+        };
+        let inner = Arc::new(RwLock::new(room_inner));
+
+        #[cfg(feature = "diagnostics")]
+        let crash_state = {
+            let weak_inner = Arc::downgrade(&inner);
+            let (room_id, participant_id) = (room_id.clone(), participant_id.clone());
+            CrashReporter::register_state(
+                format!("room:{}", room_id),
+                Arc::new(move || crash_state(&room_id, &participant_id, &weak_inner)),
+            )
+        };
+
+        let room = Self {
+            id: room_id.clone(),
+            participant_id: participant_id.clone(),
+            config,
+            #[cfg(feature = "media")]
+            audio_config,
+            #[cfg(feature = "media")]
+            video_config,
+            #[cfg(feature = "signaling")]
+            signaling_config,
+            resource_limits,
+            max_participants,
+            #[cfg(feature = "signaling")]
+            schedule,
+            #[cfg(feature = "signaling")]
+            access_token,
+            events,
+            call_recorder,
+            stats_history,
+            #[cfg(feature = "diagnostics")]
+            debug_log,
+            #[cfg(feature = "diagnostics")]
+            _crash_state: crash_state,
+            inner,
+        };
+
+        quic_rtc.register_room(
+            JoinedRoom {
+                room_id: room_id.clone(),
+                participant_id,
+            },
+            &room.inner,
+        )?;
+
+        // Start the connection process; whatever an attempt set up is torn
+        // down if it fails, is cancelled, times out or is dropped part way
+        let started = tokio::time::Instant::now();
+        let interrupted = || {
+            join_interrupted(
+                &room_id,
+                cancellation.as_ref(),
+                room.config.join_timeout,
+                started,
+            )
+        };
+        let mut attempt = 1;
+        loop {
+            let pending = PendingJoin::new(&room);
+            let connected = tokio::select! {
+                biased;
+                interruption = interrupted() => Err(interruption),
+                connected = room.connect(&quic_rtc) => Ok(connected),
+            };
+            let e = match connected {
+                Ok(Ok(())) => {
+                    pending.complete();
+                    break;
+                }
+                Ok(Err(e)) => e,
+                Err(interruption) => {
+                    warn!("Joining room '{}' stopped: {}", room_id, interruption);
+                    pending.abort().await;
+                    return Err(interruption);
+                }
+            };
+            pending.abort().await;
+
+            let Some(retry_in) = room.join_retry_delay(&e, attempt) else {
+                warn!("Joining room '{}' failed: {}", room_id, e);
+                return Err(e);
+            };
+            warn!(
+                "Joining room '{}' failed (attempt {}), retrying in {:?}: {}",
+                room_id, attempt, retry_in, e
+            );
+            room.events.emit(crate::Event::JoinAttemptFailed {
+                attempt,
+                error: e.to_string(),
+                retry_in,
+            });
+            tokio::select! {
+                biased;
+                interruption = interrupted() => {
+                    warn!("Joining room '{}' stopped: {}", room_id, interruption);
+                    return Err(interruption);
+                }
+                _ = tokio::time::sleep(retry_in) => {}
+            }
+            attempt += 1;
+        }
+
+        info!("✅ Successfully joined room '{}'", room_id);
+        Ok(room)
+    }
+
+    /// Wait before retrying a join that failed with `error` on `attempt`,
+    /// or `None` if the error is permanent or the retries are used up
+    ///
+    /// Transient failures such as timeouts and transport setup errors are
+    /// retried as the signaling [`ReconnectConfig`] allows.
+    #[cfg(feature = "signaling")]
+    fn join_retry_delay(&self, error: &QuicRtcError, attempt: u32) -> Option<Duration> {
+        if !error.is_retryable() {
+            return None;
+        }
+        self.signaling_config
+            .as_ref()
+            .map(|config| config.reconnect_config.clone())
+            .unwrap_or_default()
+            .retry_delay(attempt)
+    }
+
+    /// Without signaling there is no reconnect configuration, so joins
+    /// aren't retried
+    #[cfg(not(feature = "signaling"))]
+    fn join_retry_delay(&self, _error: &QuicRtcError, _attempt: u32) -> Option<Duration> {
+        None
+    }
+
+    /// Internal connection logic
+    ///
+    /// The room lock is only taken briefly to store what each step set up,
+    /// never across network round trips, so the room stays observable while
+    /// it connects. Everything stored is torn down if the join fails.
+    async fn connect(&self, quic_rtc: &QuicRtc) -> Result<(), QuicRtcError> {
+        self.inner.write().await.state = RoomState::Connecting;
+
+        // Step 1: Initialize media subsystems if enabled
+        #[cfg(feature = "media")]
+        {
+            if self.config.video_enabled || self.config.audio_enabled {
+                info!("🎥 Initializing media subsystems");
+                self.init_media_subsystems(quic_rtc).await?;
+            }
+        }
+        if let Err(e) = self.update_audio_route().await {
+            warn!("⚠️ Could not set the audio route: {}", e);
+        }
+
+        // Step 2: Connect to signaling server if configured
+        #[cfg(feature = "signaling")]
+        {
+            if let Some(signaling_url) = &self.config.signaling_url {
+                info!("📡 Connecting to signaling server: {}", signaling_url);
+                self.connect_signaling(signaling_url, quic_rtc).await?;
+                self.events.emit(crate::Event::JoinProgress {
+                    stage: JoinStage::SignalingConnected,
+                });
+            }
+        }
+
+        // Step 3: Agree on the media endpoint through session offer/answer
+        #[cfg(feature = "signaling")]
+        let negotiated_endpoint = self.negotiate_session(quic_rtc).await?;
+        #[cfg(not(feature = "signaling"))]
+        let negotiated_endpoint: Option<SocketAddr> = None;
+
+        // Step 4: Establish MoQ transport
+        let endpoint = negotiated_endpoint.unwrap_or(DEFAULT_MEDIA_ENDPOINT);
+        info!("🚀 Establishing MoQ over QUIC transport to {}", endpoint);
+        self.establish_moq_transport(endpoint, quic_rtc).await?;
+        self.events.emit(crate::Event::JoinProgress {
+            stage: JoinStage::TransportEstablished,
+        });
+
+        let mut inner = self.inner.write().await;
+        #[cfg(feature = "signaling")]
+        {
+            self.spawn_signaling_listener(&mut inner, self.session_offer(quic_rtc));
+            self.spawn_presence_heartbeats(&mut inner);
+        }
+
+        // Step 5: Initialize local participant
+        info!("👤 Initializing local participant");
+        inner.local_participant = Some(crate::LocalParticipant::new(
+            self.participant_id.clone(),
+            self.config.clone(),
+        ));
+        self.spawn_stats_refresh(&mut inner);
+        self.spawn_stats_sampling(&mut inner);
+        self.spawn_object_receiver(&mut inner);
+        #[cfg(feature = "media")]
+        {
+            self.spawn_device_watch(&mut inner);
+            self.spawn_backpressure_watch(&mut inner);
+            self.spawn_path_mtu_watch(&mut inner);
+        }
+
+        inner.state = RoomState::Connected;
+        if let Some(recorder) = &self.call_recorder {
+            if let Some(transport) = &inner.moq_transport {
+                recorder.record_transport_mode(transport.transport_mode());
+            }
+            recorder.record_note("room_joined", self.id.clone());
+        }
+        self.events.emit(crate::Event::JoinProgress {
+            stage: JoinStage::SessionActive,
+        });
+        info!("🎉 Room connection established successfully");
+
+        Ok(())
+    }
+
+    /// Initialize media subsystems with permission checking
+    #[cfg(feature = "media")]
+    async fn init_media_subsystems(&self, quic_rtc: &QuicRtc) -> Result<(), QuicRtcError> {
+        // Initialize media processor
+        let rotation_mode = self
+            .video_config
+            .as_ref()
+            .map(|config| config.rotation_mode)
+            .unwrap_or_default();
+        let video_content_hint = self
+            .video_config
+            .as_ref()
+            .map(|config| config.content_hint)
+            .unwrap_or_default();
+        let mut registry = quic_rtc.codec_registry().clone();
+        if let Some(profile) = self.audio_profile() {
+            debug!("🎵 Encoding audio with the {:?} profile", profile);
+            let opus = quicrtc_media::OpusCodec::with_config(profile.opus_config())?;
+            registry.register_codec("opus", Arc::new(opus))?;
+        }
+        let audio_content_hint = self
+            .audio_profile()
+            .map(|profile| profile.content_hint())
+            .unwrap_or_default();
+        let mut processor = MediaProcessor::new()
+            .with_codec_registry(registry)
+            .with_rotation_mode(rotation_mode)
+            .with_audio_content_hint(audio_content_hint)
+            .with_video_content_hint(video_content_hint);
+        if self.config.data_saver {
+            let video = processor.video_config().clone();
+            processor.request_video_reconfiguration(&quicrtc_media::QualitySettings {
+                video_bitrate: video.bitrate.min(DATA_SAVER_VIDEO_BITRATE),
+                video_width: video.width,
+                video_height: video.height,
+                video_framerate: video.framerate,
+                ..Default::default()
+            });
+            processor.set_keyframe_interval(
+                Some(video.framerate * DATA_SAVER_KEYFRAME_INTERVAL.as_secs() as u32),
+                Some(DATA_SAVER_KEYFRAME_INTERVAL),
+            );
+        }
+        self.inner.write().await.media_processor =
+            Some(Arc::new(tokio::sync::Mutex::new(processor)));
+
+        // Initialize video capture if video is enabled
+        if self.config.video_enabled {
+            let video_capture = quic_rtc.video_capture(Self::open_video_capture).await?;
+            self.inner.write().await.video_capture = Some(video_capture);
+        }
+
+        // Initialize audio renderer if audio is enabled
+        if self.config.audio_enabled {
+            debug!("🎵 Initializing audio renderer with permission checks");
+            let mut audio_renderer = CpalAudioRenderer::new();
+            audio_renderer.set_diagnostics_sink(quic_rtc.diagnostics());
+
+            // Check microphone permissions during initialization [[memory:3911748]]
+            match audio_renderer.list_devices() {
+                Ok(devices) => {
+                    if devices.is_empty() {
+                        return Err(QuicRtcError::MediaProcessing {
+                            reason: "No audio input devices available - check permissions"
+                                .to_string(),
+                        });
+                    }
+                    info!(
+                        "✅ Microphone permissions verified - {} devices available",
+                        devices.len()
+                    );
+                }
+                Err(e) => {
+                    // If device enumeration fails, log warning but allow initialization
+                    warn!(
+                        "⚠️ Could not enumerate audio devices ({}), proceeding anyway",
+                        e
+                    );
+                }
+            }
+
+            let mut inner = self.inner.write().await;
+            inner.audio_renderer = Some(Arc::new(tokio::sync::Mutex::new(audio_renderer)));
+            let mut mixer = AudioMixer::new(48000, quicrtc_core::ChannelLayout::Stereo);
+            mixer.set_ducking(self.ducker());
+            inner.audio_mixer = Some(Arc::new(tokio::sync::Mutex::new(mixer)));
+        }
+
+        Ok(())
+    }
+
+    /// Open the camera capture shared by the rooms of a [`QuicRtc`] instance
+    #[cfg(feature = "media")]
+    async fn open_video_capture() -> Result<VideoCaptureManager, QuicRtcError> {
+        debug!("📹 Initializing video capture with permission checks");
+        let mut video_capture =
+            VideoCaptureManager::new().map_err(|e| QuicRtcError::MediaProcessing {
+                reason: format!("Failed to initialize video capture: {}", e),
+            })?;
+
+        // Check camera permissions during initialization [[memory:3911748]]
+        match video_capture.enumerate_devices() {
+            Ok(devices) => {
+                if devices.is_empty() {
+                    return Err(QuicRtcError::MediaProcessing {
+                        reason: "No camera devices available - check permissions".to_string(),
+                    });
+                }
+                info!(
+                    "✅ Camera permissions verified - {} devices available",
+                    devices.len()
+                );
+            }
+            Err(e) => {
+                error!(
+                    "❌ Camera permission check failed during initialization: {}",
+                    e
+                );
+                return Err(QuicRtcError::MediaProcessing {
+                    reason: format!("Camera access denied during initialization: {}", e),
+                });
+            }
+        }
+
+        Ok(video_capture)
+    }
+
+    /// Connect to signaling server
+    #[cfg(feature = "signaling")]
+    async fn connect_signaling(
+        &self,
+        signaling_url: &str,
+        quic_rtc: &QuicRtc,
+    ) -> Result<(), QuicRtcError> {
+        // Create participant info for signaling
+        let participant_info = PeerInfo {
+            id: self.participant_id.clone(),
+            name: None, // Could be set from config in the future
+            room_id: self.id.clone(),
+            quic_endpoint: None, // Will be set when MoQ transport is ready
+            capabilities: quic_rtc.capabilities(),
+            last_seen: chrono::Utc::now(),
+            status: PeerStatus::Online,
+        };
+
+        let timeout = self.signaling_timeout();
+        let client = tokio::time::timeout(timeout, SignalingClient::connect(signaling_url))
+            .await
+            .map_err(|_| QuicRtcError::Timeout {
+                operation: "signaling connect".to_string(),
+                duration: timeout,
+            })??;
+
+        // Create the room on first join; it already existing is fine
+        client
+            .send(&SignalingMessage::CreateRoom {
+                room_id: self.id.clone(),
+                room_name: None,
+                max_participants: self.max_participants,
+                access_token: self.access_token.clone(),
+                schedule: self.schedule.clone(),
+            })
+            .await?;
+        client
+            .expect("create room", timeout, |response| match response {
+                SignalingResponse::RoomCreated { .. } => Some(Ok(())),
+                SignalingResponse::Error { error_code, .. }
+                    if error_code == "ROOM_ALREADY_EXISTS" =>
+                {
+                    Some(Ok(()))
+                }
+                SignalingResponse::Error { error, error_code } => {
+                    Some(Err(self.signaling_error(error, error_code)))
+                }
+                _ => None,
+            })
+            .await??;
+
+        client
+            .send(&SignalingMessage::JoinRoom {
+                room_id: self.id.clone(),
+                participant_id: self.participant_id.clone(),
+                participant_name: participant_info.name.clone(),
+                capabilities: participant_info.capabilities.clone(),
+                quic_endpoint: None, // We don't accept direct media connections
+                access_token: self.access_token.clone(),
+            })
+            .await?;
+        let admitted = client
+            .expect("join room", timeout, |response| match response {
+                SignalingResponse::JoinedRoom { .. } => Some(Ok(true)),
+                SignalingResponse::WaitingForHost {
+                    host_id, starts_at, ..
+                } => {
+                    self.events
+                        .emit(crate::Event::WaitingForHost { host_id, starts_at });
+                    Some(Ok(false))
+                }
+                SignalingResponse::Error { error, error_code } => {
+                    Some(Err(self.signaling_error(error, error_code)))
+                }
+                _ => None,
+            })
+            .await??;
+        if !admitted {
+            // The lobby lasts until the host arrives; callers bound the wait
+            // with a cancellation token or by dropping the join
+            debug!("⏳ Waiting for the host of room '{}'", self.id);
+            client
+                .expect("wait for host", Duration::MAX, |response| match response {
+                    SignalingResponse::JoinedRoom { .. } => Some(Ok(())),
+                    SignalingResponse::RoomClosed { room_id, .. } => {
+                        Some(Err(QuicRtcError::RoomNotFound { room_id }))
+                    }
+                    SignalingResponse::Error { error, error_code } => {
+                        Some(Err(self.signaling_error(error, error_code)))
+                    }
+                    _ => None,
+                })
+                .await??;
+        }
+        debug!("✅ Joined room '{}' on signaling server", self.id);
+
+        let signaling_connection = SignalingConnection {
+            client: Arc::new(client),
+            participant_info,
+            discovered_peers: std::collections::HashMap::new(),
+            negotiated_session: None,
+        };
+
+        self.inner.write().await.signaling_connection =
+            Some(Arc::new(tokio::sync::Mutex::new(signaling_connection)));
+        Ok(())
+    }
+
+    /// Run the MoQ session offer/answer exchange with the room's media peer
+    ///
+    /// The offer goes to the first other participant that advertises a QUIC
+    /// endpoint, such as a relay. Returns the endpoint from its answer, or
+    /// `None` when nobody in the room accepts media connections.
+    #[cfg(feature = "signaling")]
+    async fn negotiate_session(
+        &self,
+        quic_rtc: &QuicRtc,
+    ) -> Result<Option<SocketAddr>, QuicRtcError> {
+        let Some(signaling) = self.inner.read().await.signaling_connection.clone() else {
+            return Ok(None);
+        };
+        let mut signaling = signaling.lock().await;
+        let client = Arc::clone(&signaling.client);
+        let timeout = self.signaling_timeout();
+
+        client
+            .send(&SignalingMessage::GetRoomInfo {
+                room_id: self.id.clone(),
+            })
+            .await?;
+        let participants = client
+            .expect("room info", timeout, |response| match response {
+                SignalingResponse::RoomInfo { participants, .. } => Some(Ok(participants)),
+                SignalingResponse::Error { error, error_code } => {
+                    Some(Err(self.signaling_error(error, error_code)))
+                }
+                _ => None,
+            })
+            .await??;
+
+        let others: Vec<&Participant> = participants
+            .iter()
+            .filter(|participant| participant.id != self.participant_id)
+            .collect();
+        for participant in &others {
+            signaling
+                .discovered_peers
+                .insert(participant.id.clone(), peer_info(&self.id, participant));
+            self.inner
+                .write()
+                .await
+                .admit_remote_participant(remote_participant(participant))
+                .await;
+        }
+
+        let offer = self.session_offer(quic_rtc);
+        let Some(target) = others.iter().find(|participant| {
+            participant.quic_endpoint.is_some()
+                && offer
+                    .capabilities
+                    .is_compatible_with(&participant.capabilities)
+        }) else {
+            debug!("No compatible participant accepts media connections; skipping session offer");
+            return Ok(None);
+        };
+
+        let session_id = offer.session_id.clone();
+        info!("🤝 Sending MoQ session offer to {}", target.id);
+        client
+            .send(&SignalingMessage::MoqSessionOffer {
+                room_id: self.id.clone(),
+                target_participant: target.id.clone(),
+                offer,
+            })
+            .await?;
+
+        let answer = client
+            .expect("session answer", timeout, |response| match response {
+                SignalingResponse::MoqSessionAnswer { answer, .. }
+                    if answer.session_id == session_id =>
+                {
+                    Some(Ok(answer))
+                }
+                SignalingResponse::Error { error, error_code } => {
+                    Some(Err(self.signaling_error(error, error_code)))
+                }
+                _ => None,
+            })
+            .await??;
+
+        if !answer.accepted {
+            let code = if answer.moq_version != MOQ_VERSION {
+                quicrtc_core::MoqErrorCode::UnsupportedVersion
+            } else {
+                quicrtc_core::MoqErrorCode::PermissionDenied
+            };
+            return Err(QuicRtcError::SessionSetupFailed {
+                code: code.code(),
+                reason: format!("{} rejected the session offer", answer.participant_id),
+            });
+        }
+
+        // Encode with the codecs the answerer picked
+        #[cfg(feature = "media")]
+        let media_processor = self.inner.read().await.media_processor.clone();
+        #[cfg(feature = "media")]
+        if let Some(processor) = media_processor {
+            let mut processor = processor.lock().await;
+            for codec in &answer.codecs {
+                if let Err(e) = processor.set_encoder_codec(&codec.name) {
+                    warn!("⚠️ Answer selected unusable codec {}: {}", codec.name, e);
+                    continue;
+                }
+                let Some(remote) = codec
+                    .max_channels
+                    .and_then(quicrtc_core::ChannelLayout::from_channel_count)
+                else {
+                    continue;
+                };
+                match processor.negotiate_audio_layout(remote) {
+                    Ok(layout) => {
+                        debug!("🔊 Sending {:?} audio to {}", layout, answer.participant_id)
+                    }
+                    Err(e) => warn!("⚠️ Could not negotiate audio channels: {}", e),
+                }
+            }
+        }
+
+        let endpoint = answer.quic_endpoint;
+        info!(
+            "✅ Session accepted by {}; media via {}",
+            answer.participant_id, endpoint
+        );
+        signaling.negotiated_session = Some(answer);
+        Ok(Some(endpoint))
+    }
+
+    /// Namespace of one of our own tracks (`room.<id>/<participant>/<source>`)
+    #[cfg(any(feature = "signaling", feature = "media"))]
+    fn local_track_namespace(&self, source: &str) -> Result<TrackNamespace, QuicRtcError> {
+        Ok(TrackNamespaceBuilder::room(&self.id)
+            .participant(self.participant_id.as_str())
+            .track(source)
+            .build()?)
+    }
+
+    /// Build our session offer from the room configuration
+    #[cfg(feature = "signaling")]
+    fn session_offer(&self, quic_rtc: &QuicRtc) -> MoqSessionOffer {
+        use crate::track::TrackSource;
+
+        let mut sources = Vec::new();
+        if self.config.video_enabled {
+            sources.push((TrackSource::Camera, quicrtc_signaling::CodecKind::Video));
+        }
+        if self.config.audio_enabled {
+            sources.push((TrackSource::Microphone, quicrtc_signaling::CodecKind::Audio));
+        }
+        let capabilities = quic_rtc.capabilities();
+        let tracks: Vec<OfferedTrack> = sources
+            .into_iter()
+            .filter_map(|(source, kind)| {
+                let name = source.to_string();
+                let track = match self.local_track_namespace(&name) {
+                    Ok(track) => track,
+                    Err(e) => {
+                        warn!("Not offering {} track: {}", name, e);
+                        return None;
+                    }
+                };
+                let dimensions = self.published_dimensions(source);
+                Some(OfferedTrack {
+                    namespace: track.canonical(),
+                    label: self.config.track_labels.get(&name).cloned(),
+                    width: dimensions.map(|(width, _)| width),
+                    height: dimensions.map(|(_, height)| height),
+                    // The answer may pick another codec; this is our preference
+                    codec: capabilities
+                        .codecs
+                        .iter()
+                        .find(|codec| codec.kind == kind)
+                        .map(|codec| codec.name.clone()),
+                    source: name,
+                })
+            })
+            .collect();
+
+        MoqSessionOffer {
+            participant_id: self.participant_id.clone(),
+            // We don't accept direct media connections
+            quic_endpoint: SocketAddr::from(([0, 0, 0, 0], 0)),
+            moq_version: MOQ_VERSION.to_string(),
+            publish_namespaces: tracks.iter().map(|t| t.namespace.clone()).collect(),
+            subscribe_namespaces: vec![room_namespace(&self.id)],
+            capabilities,
+            tracks,
+            session_id: uuid::Uuid::new_v4().to_string(),
+        }
+    }
+
+    /// Initial frame size announced for a published track
+    #[cfg(all(feature = "signaling", feature = "media"))]
+    fn published_dimensions(&self, source: crate::track::TrackSource) -> Option<(u32, u32)> {
+        (source == TrackSource::Camera).then(|| self.config.video_quality.dimensions())
+    }
+
+    /// Initial frame size announced for a published track
+    #[cfg(all(feature = "signaling", not(feature = "media")))]
+    fn published_dimensions(&self, _source: crate::track::TrackSource) -> Option<(u32, u32)> {
+        None
+    }
+
+    /// Keep discovered peers and remote participants current and answer
+    /// offers from other participants
+    ///
+    /// `announcement` goes to every participant that doesn't accept media
+    /// connections, now and as they join, so they learn which tracks we
+    /// publish. Rooms connect out to media peers and never accept media
+    /// connections themselves, so incoming offers are rejected once their
+    /// tracks are noted. Recording, transcription and room closing notices
+    /// are passed on as room events.
+    #[cfg(feature = "signaling")]
+    fn spawn_signaling_listener(&self, inner: &mut RoomInner, announcement: MoqSessionOffer) {
+        let Some(signaling) = inner.signaling_connection.clone() else {
+            return;
+        };
+        let room_id = self.id.clone();
+        let participant_id = self.participant_id.clone();
+        let events = self.events.clone();
+        let room_inner = Arc::downgrade(&self.inner);
+
+        let task = tokio::spawn(async move {
+            let (client, peers) = {
+                let signaling = signaling.lock().await;
+                let peers: Vec<String> = signaling
+                    .discovered_peers
+                    .values()
+                    .filter(|peer| peer.quic_endpoint.is_none())
+                    .map(|peer| peer.id.clone())
+                    .collect();
+                (Arc::clone(&signaling.client), peers)
+            };
+            for peer in peers {
+                if let Err(e) = announce_tracks(&client, &room_id, &peer, &announcement).await {
+                    warn!("⚠️ Failed to announce our tracks to {}: {}", peer, e);
+                }
+            }
+
+            while let Some(response) = client.recv().await {
+                match response {
+                    SignalingResponse::ParticipantJoined { participant, .. }
+                        if participant.id != participant_id =>
+                    {
+                        let peer = peer_info(&room_id, &participant);
+                        signaling
+                            .lock()
+                            .await
+                            .discovered_peers
+                            .insert(participant.id.clone(), peer);
+                        if let Some(room_inner) = room_inner.upgrade() {
+                            room_inner
+                                .write()
+                                .await
+                                .admit_remote_participant(remote_participant(&participant))
+                                .await;
+                        }
+                        if participant.quic_endpoint.is_none() {
+                            if let Err(e) =
+                                announce_tracks(&client, &room_id, &participant.id, &announcement)
+                                    .await
+                            {
+                                warn!(
+                                    "⚠️ Failed to announce our tracks to {}: {}",
+                                    participant.id, e
+                                );
+                            }
+                        }
+                    }
+                    SignalingResponse::ParticipantLeft {
+                        participant_id: left,
+                        reason,
+                        ..
+                    } => {
+                        signaling.lock().await.discovered_peers.remove(&left);
+                        if let Some(room_inner) = room_inner.upgrade() {
+                            let mut room_inner = room_inner.write().await;
+                            if let Some(participant) =
+                                room_inner.remove_remote_participant(&left).await
+                            {
+                                room_inner.emit(crate::Event::ParticipantLeft { participant });
+                            }
+                        }
+                        if reason == LeaveReason::TimedOut {
+                            events.emit(crate::Event::ParticipantTimedOut {
+                                participant_id: left,
+                            });
+                        }
+                    }
+                    SignalingResponse::MoqSessionOffer {
+                        source_participant,
+                        offer,
+                        ..
+                    } => {
+                        // The offer still tells us how its tracks should be shown
+                        if let Some(room_inner) = room_inner.upgrade() {
+                            room_inner
+                                .write()
+                                .await
+                                .apply_published_tracks(&source_participant, &offer.tracks)
+                                .await;
+                        }
+                        debug!("Rejecting session offer from {}", source_participant);
+                        let answer = MoqSessionAnswer {
+                            participant_id: participant_id.clone(),
+                            quic_endpoint: SocketAddr::from(([0, 0, 0, 0], 0)),
+                            moq_version: MOQ_VERSION.to_string(),
+                            accepted_publish_namespaces: Vec::new(),
+                            accepted_subscribe_namespaces: Vec::new(),
+                            session_id: offer.session_id,
+                            accepted: false,
+                            codecs: Vec::new(),
+                        };
+                        let message = SignalingMessage::MoqSessionAnswer {
+                            room_id: room_id.clone(),
+                            target_participant: source_participant,
+                            answer,
+                        };
+                        if let Err(e) = client.send(&message).await {
+                            warn!("⚠️ Failed to answer session offer: {}", e);
+                        }
+                    }
+                    SignalingResponse::CaptureConsentRequested {
+                        request_id,
+                        kind,
+                        requires_acknowledgement,
+                        ..
+                    } => events.emit(crate::Event::CaptureConsentRequested {
+                        request_id,
+                        kind: capture_kind(kind),
+                        requires_acknowledgement,
+                    }),
+                    SignalingResponse::RecordingStarted { .. } => {
+                        events.emit(crate::Event::CaptureStateChanged {
+                            kind: CaptureKind::Recording,
+                            active: true,
+                        })
+                    }
+                    SignalingResponse::RecordingStopped { .. } => {
+                        events.emit(crate::Event::CaptureStateChanged {
+                            kind: CaptureKind::Recording,
+                            active: false,
+                        })
+                    }
+                    SignalingResponse::TranscriptionStarted { .. } => {
+                        events.emit(crate::Event::CaptureStateChanged {
+                            kind: CaptureKind::Transcription,
+                            active: true,
+                        })
+                    }
+                    SignalingResponse::TranscriptionStopped { .. } => {
+                        events.emit(crate::Event::CaptureStateChanged {
+                            kind: CaptureKind::Transcription,
+                            active: false,
+                        })
+                    }
+                    SignalingResponse::RoomClosing {
+                        closes_at, reason, ..
+                    } => events.emit(crate::Event::RoomClosing {
+                        closes_in: (closes_at - chrono::Utc::now())
+                            .to_std()
+                            .unwrap_or_default(),
+                        reason: reason.to_string(),
+                    }),
+                    SignalingResponse::RoomClosed { reason, .. } => {
+                        events.emit(crate::Event::RoomDisconnected {
+                            reason: format!("Room {}", reason),
+                        })
+                    }
+                    _ => {}
+                }
+            }
+        });
+        inner.background_tasks.push(task);
+    }
+
+    /// Tell the signaling server we are still here every `heartbeat_interval`
+    ///
+    /// The server removes participants whose heartbeats stop, so peers learn
+    /// about us dropping off even when the connection does not close cleanly.
+    #[cfg(feature = "signaling")]
+    fn spawn_presence_heartbeats(&self, inner: &mut RoomInner) {
+        let Some(signaling) = inner.signaling_connection.clone() else {
+            return;
+        };
+        let period = self
+            .signaling_config
+            .as_ref()
+            .map(|config| config.heartbeat_interval)
+            .unwrap_or_else(|| SignalingConfig::default().heartbeat_interval);
+
+        let task = tokio::spawn(async move {
+            let client = Arc::clone(&signaling.lock().await.client);
+            let mut ticker = tokio::time::interval(period);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = client.send(&SignalingMessage::Heartbeat).await {
+                    debug!("Stopping presence heartbeats: {}", e);
+                    break;
+                }
+            }
+        });
+        inner.background_tasks.push(task);
+    }
+
+    /// Refresh local and remote track statistics every `stats_interval`
+    fn spawn_stats_refresh(&self, inner: &mut RoomInner) {
+        let Some(transport) = inner.moq_transport.clone() else {
+            return;
+        };
+        let room_inner = Arc::downgrade(&self.inner);
+        let period = self.config.stats_interval;
+        #[cfg(feature = "media")]
+        let encode_pipeline = inner.encode_pipeline.clone();
+        #[cfg(feature = "media")]
+        let video_decoding = inner.video_decoding.clone();
+
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(period);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            let mut last_refresh = ticker.tick().await;
+            let mut skipped = 0;
+            loop {
+                let now = ticker.tick().await;
+                let Some(room_inner) = room_inner.upgrade() else {
+                    break;
+                };
+                let mut inner = room_inner.write().await;
+                if inner.audio_only && skipped + 1 < AUDIO_ONLY_STATS_SLOWDOWN {
+                    skipped += 1;
+                    continue;
+                }
+                skipped = 0;
+                let interval = now - last_refresh;
+                last_refresh = now;
+
+                let transfer = |track: &TrackNamespace| transport.track_stats(track);
+                if let Some(local) = inner.local_participant.as_mut() {
+                    local.refresh_track_stats(transfer, interval);
+                    #[cfg(feature = "media")]
+                    if let Ok(pipeline) = encode_pipeline.lock() {
+                        local.refresh_encode_stats(|track_id| pipeline.stats(track_id));
+                    }
+                }
+                inner.participants.refresh_track_stats(transfer, interval);
+                #[cfg(feature = "media")]
+                if let Ok(decoding) = video_decoding.lock() {
+                    inner
+                        .participants
+                        .refresh_decode_stats(|participant_id, track_id| {
+                            decoding.stats(participant_id, track_id)
+                        });
+                }
+                inner.update_quality();
+            }
+        });
+        inner.background_tasks.push(task);
+    }
+
+    /// Record a sample of track and connection statistics into the history
+    /// every sample interval
+    fn spawn_stats_sampling(&self, inner: &mut RoomInner) {
+        let Some(history) = self.stats_history.clone() else {
+            return;
+        };
+        let room_inner = Arc::downgrade(&self.inner);
+
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(history.config().sample_interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let Some(room_inner) = room_inner.upgrade() else {
+                    break;
+                };
+                let tracks = room_inner.read().await.track_reports();
+                history.record(StatsSample::new(chrono::Utc::now(), tracks));
+            }
+        });
+        inner.background_tasks.push(task);
+    }
+
+    /// Report audio devices coming and going and the system default
+    /// changing, and reopen playback on the new default output device
+    /// while following it
+    ///
+    /// CPAL has no device change notifications, so devices are polled
+    /// every [`DEVICE_POLL_INTERVAL`]. Capture always opens the current
+    /// default input, so only playback needs switching.
+    #[cfg(feature = "media")]
+    fn spawn_device_watch(&self, inner: &mut RoomInner) {
+        let Some(renderer) = inner.audio_renderer.clone() else {
+            return;
+        };
+        let room_inner = Arc::downgrade(&self.inner);
+        let events = self.events.clone();
+
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(DEVICE_POLL_INTERVAL);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            let mut previous: Option<AudioDeviceSnapshot> = None;
+            loop {
+                ticker.tick().await;
+                let snapshot = match tokio::task::spawn_blocking(AudioDeviceSnapshot::current).await
+                {
+                    Ok(Ok(snapshot)) => snapshot,
+                    Ok(Err(e)) => {
+                        debug!("Could not list audio devices: {}", e);
+                        continue;
+                    }
+                    Err(_) => break,
+                };
+                let Some(last) = previous.replace(snapshot.clone()) else {
+                    continue;
+                };
+                let Some(room_inner) = room_inner.upgrade() else {
+                    break;
+                };
+
+                for change in snapshot.changes_since(&last) {
+                    if let AudioDeviceChange::DefaultChanged {
+                        direction: AudioDeviceDirection::Output,
+                        name: Some(name),
+                    } = &change
+                    {
+                        if room_inner.read().await.follow_default_devices {
+                            match renderer.lock().await.switch_device(None) {
+                                Ok(()) => info!("🔊 Audio output followed the default to {}", name),
+                                Err(e) => {
+                                    warn!("⚠️ Could not switch audio output to {}: {}", name, e)
+                                }
+                            }
+                        }
+                    }
+                    if let Some(event) = device_event(change) {
+                        events.emit(event);
+                    }
+                }
+            }
+        });
+        inner.background_tasks.push(task);
+    }
+
+    /// Lower the video bitrate as soon as the transport reports sustained
+    /// congestion, instead of at the next rate adaptation
+    #[cfg(feature = "media")]
+    fn spawn_backpressure_watch(&self, inner: &mut RoomInner) {
+        let (Some(transport), Some(processor)) =
+            (&inner.moq_transport, inner.media_processor.clone())
+        else {
+            return;
+        };
+        let mut signals = transport.backpressure().subscribe();
+
+        let task = tokio::spawn(async move {
+            loop {
+                match signals.recv().await {
+                    Ok(backpressure) => processor.lock().await.handle_backpressure(&backpressure),
+                    // Only the latest signal matters
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+        inner.background_tasks.push(task);
+    }
+
+    /// Size video slices to the path MTU whenever path MTU discovery
+    /// settles on a new one
+    #[cfg(feature = "media")]
+    fn spawn_path_mtu_watch(&self, inner: &mut RoomInner) {
+        let (Some(transport), Some(processor)) =
+            (&inner.moq_transport, inner.media_processor.clone())
+        else {
+            return;
+        };
+        let transport = Arc::downgrade(transport);
+
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(PATH_MTU_POLL_INTERVAL);
+            let mut last = None;
+            loop {
+                ticker.tick().await;
+                let Some(transport) = transport.upgrade() else {
+                    break;
+                };
+                // Without datagram support there is no path MTU to follow
+                let Some(max_datagram_size) = transport.max_datagram_size() else {
+                    continue;
+                };
+                if last != Some(max_datagram_size) {
+                    last = Some(max_datagram_size);
+                    processor.lock().await.handle_path_mtu(max_datagram_size);
+                }
+            }
+        });
+        inner.background_tasks.push(task);
+    }
+
+    /// Take received objects off the transport, feeding subscribed video to
+    /// its decode worker and subscribed audio to the mixer
+    ///
+    /// Runs without media support too: draining the transport's queue is what
+    /// counts objects into the remote track statistics and time-shift buffers.
+    fn spawn_object_receiver(&self, inner: &mut RoomInner) {
+        let Some(transport) = &inner.moq_transport else {
+            return;
+        };
+        let mut events = transport.subscribe_events();
+        let transport = Arc::downgrade(transport);
+        #[cfg(feature = "media")]
+        let processor = inner.media_processor.clone();
+        #[cfg(feature = "media")]
+        let decoding = inner.video_decoding.clone();
+        #[cfg(feature = "media")]
+        let (remote_audio, mixer) = (inner.remote_audio.clone(), inner.audio_mixer.clone());
+
+        let task = tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    // Received objects are queued on the transport; draining
+                    // the queue also keeps the receive statistics current
+                    Ok(MoqTransportEvent::ObjectReceived { .. })
+                    | Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {
+                        let Some(transport) = transport.upgrade() else {
+                            break;
+                        };
+                        while let Ok(object) = transport.receive_moq_object().await {
+                            #[cfg(feature = "media")]
+                            {
+                                let Some(processor) = &processor else {
+                                    continue;
+                                };
+                                let namespace = object.track_namespace.clone();
+                                let audio_source =
+                                    remote_audio.lock().unwrap().get(&namespace).cloned();
+                                if let (Some(participant_id), Some(mixer)) = (audio_source, &mixer)
+                                {
+                                    let decoded =
+                                        processor.lock().await.process_incoming_object(object);
+                                    let result = match decoded {
+                                        Ok(Some(MediaFrame::Audio(frame))) => mixer
+                                            .lock()
+                                            .await
+                                            .push_frame(&participant_id, frame)
+                                            .map_err(|e| QuicRtcError::MediaProcessing {
+                                                reason: e.to_string(),
+                                            }),
+                                        Ok(_) => Ok(()),
+                                        Err(e) => Err(e),
+                                    };
+                                    if let Err(e) = result {
+                                        debug!("Dropped received audio of {:?}: {}", namespace, e);
+                                    }
+                                    continue;
+                                }
+                                if !decoding.lock().unwrap().is_decoding(&namespace) {
+                                    continue;
+                                }
+                                let assembled =
+                                    processor.lock().await.assemble_incoming_object(object);
+                                let result = match assembled {
+                                    Ok(Some(frame)) => {
+                                        decoding.lock().unwrap().submit(&namespace, frame)
+                                    }
+                                    Ok(None) => Ok(()),
+                                    Err(e) => Err(e),
+                                };
+                                if let Err(e) = result {
+                                    debug!("Dropped received object of {:?}: {}", namespace, e);
+                                }
+                            }
+                            #[cfg(not(feature = "media"))]
+                            let _ = object;
+                        }
+                    }
+                    Ok(MoqTransportEvent::SessionTerminated { .. })
+                    | Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    Ok(_) => {}
+                }
+            }
+        });
+        inner.background_tasks.push(task);
+    }
+
+    /// Timeout for each signaling round trip
+    #[cfg(feature = "signaling")]
+    fn signaling_timeout(&self) -> Duration {
+        self.signaling_config
+            .as_ref()
+            .map(|config| config.connection_timeout)
+            .unwrap_or_else(|| SignalingConfig::default().connection_timeout)
+    }
+
+    /// Map a signaling server error response to a room connection error
+    ///
+    /// A refused access token is reported as [`QuicRtcError::Unauthorized`]
+    /// so the join isn't retried with the same token.
+    #[cfg(feature = "signaling")]
+    fn signaling_error(&self, error: String, error_code: String) -> QuicRtcError {
+        if error_code == "UNAUTHORIZED" {
+            return QuicRtcError::Unauthorized { reason: error };
+        }
+        QuicRtcError::Connection {
+            room_id: self.id.clone(),
+            reason: format!("Signaling server error {}: {}", error_code, error),
+            retry_in: None,
+            suggested_action: "Check the room ID and access rights".to_string(),
+        }
+    }
+
+    /// Establish MoQ over QUIC transport
+    ///
+    /// Rooms of the same [`QuicRtc`] instance with the same media endpoint
+    /// share one transport, set up with the first room's resource limits.
+    async fn establish_moq_transport(
+        &self,
+        endpoint: SocketAddr,
+        quic_rtc: &QuicRtc,
+    ) -> Result<(), QuicRtcError> {
+        // Create connection config with resource limits
+        let mut connection_config = ConnectionConfig::default();
+        if let Some(limits) = &self.resource_limits {
+            // Convert our ResourceLimits to transport::ResourceLimits
+            let transport_limits = quicrtc_core::transport::ResourceLimits {
+                max_memory_mb: limits.max_memory_mb,
+                max_bandwidth_kbps: limits.max_bandwidth_kbps,
+                max_connections: limits.max_connections,
+                max_streams_per_connection: limits.max_streams_per_connection,
+                cleanup_timeout: limits.cleanup_timeout,
+                connection_pool_size: 2, // Default value for connection pool
+            };
+            connection_config.resource_limits = Some(transport_limits);
+        }
+        if let Some(path) = &self.config.packet_capture_path {
+            connection_config.packet_capture =
+                Some(PacketCapture::create(PacketCaptureConfig::new(path))?);
+        }
+
+        let moq_transport = quic_rtc
+            .shared_transport(endpoint, || async move {
+                // Create MoQ session ID
+                let session_id = rand::random::<u64>();
+
+                // Establish MoQ over QUIC transport
+                let moq_transport =
+                    MoqOverQuicTransport::new(endpoint, connection_config, session_id).await?;
+
+                // Establish MoQ session
+                moq_transport.establish_session().await?;
+                Ok(moq_transport)
+            })
+            .await?;
+
+        self.inner.write().await.moq_transport = Some(moq_transport);
+        Ok(())
+    }
+
+    /// Get room ID
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Get participant ID
+    pub fn participant_id(&self) -> &str {
+        &self.participant_id
+    }
+
+    /// Get room configuration
+    pub fn config(&self) -> &RoomConfig {
+        &self.config
+    }
+
+    /// Get audio configuration
+    #[cfg(feature = "media")]
+    pub fn audio_config(&self) -> Option<&AudioProcessingConfig> {
+        self.audio_config.as_ref()
+    }
+
+    /// Get video configuration
+    #[cfg(feature = "media")]
+    pub fn video_config(&self) -> Option<&VideoProcessingConfig> {
+        self.video_config.as_ref()
+    }
+
+    /// Get resource limits
+    pub fn resource_limits(&self) -> Option<&ResourceLimits> {
+        self.resource_limits.as_ref()
+    }
+
+    /// Get maximum participants
+    pub fn max_participants(&self) -> Option<usize> {
+        self.max_participants
+    }
+}
+
+#[cfg(feature = "media")]
+impl Room {
+    /// Publish camera with default settings
+    pub async fn publish_camera(&mut self) -> Result<crate::VideoTrack, crate::QuicRtcError> {
+        info!("📹 Publishing camera track");
+
+        // Note: Camera permissions are checked when video capture is initialized
+        // per user preferences [[memory:3911748]]
+
+        // First, get the transport and do pre-flight checks
+        let (moq_transport, track_id) = {
+            let inner = self.inner.read().await;
+
+            // Ensure we're connected
+            if inner.state != RoomState::Connected {
+                return Err(QuicRtcError::InvalidState {
+                    expected: "Connected".to_string(),
+                    actual: format!("{:?}", inner.state),
+                });
+            }
+
+            // Ensure video is enabled in configuration
+            if !self.config.video_enabled {
+                return Err(QuicRtcError::InvalidData {
+                    reason: "video_enabled must be true to publish camera".to_string(),
+                });
+            }
+
+            // Get transport reference
+            let transport = inner
+                .moq_transport
+                .as_ref()
+                .ok_or_else(|| QuicRtcError::InvalidState {
+                    expected: "MoQ transport connected".to_string(),
+                    actual: "MoQ transport not available".to_string(),
+                })?
+                .clone();
+
+            let track_id = format!("camera-{}", uuid::Uuid::new_v4());
+            (transport, track_id)
+        };
+        let codec = self
+            .encoder_codec(&quicrtc_core::MoqTrackType::Video)
+            .await?;
+        debug!("📹 Encoding camera with {}", codec);
+
+        // Start video capture
+        {
+            let inner = self.inner.read().await;
+            let video_capture =
+                inner
+                    .video_capture
+                    .as_ref()
+                    .ok_or_else(|| QuicRtcError::InvalidState {
+                        expected: "Video capture initialized".to_string(),
+                        actual: "Video capture not available".to_string(),
+                    })?;
+
+            let mut capture_manager = video_capture.lock().await;
+
+            // Use video config or defaults
+            let video_config = self.video_config.as_ref();
+            let framerate = video_config.map(|c| c.default_framerate).unwrap_or(30.0);
+
+            let (width, height) = self.config.video_quality.dimensions();
+
+            // Initialize video capture
+            let device_id = "0"; // Use first available device
+            let capture_config = quicrtc_media::NewVideoCaptureConfig {
+                resolution: quicrtc_media::VideoResolution::new(width, height),
+                framerate,
+                pixel_format: quicrtc_media::VideoPixelFormat::YUV420P,
+                hardware_acceleration: true,
+                buffer_size: 3,
+                enable_processing: true,
+            };
+
+            // The camera may already be capturing for another room
+            if !capture_manager.is_capturing() {
+                capture_manager
+                    .start_capture(device_id, capture_config)
+                    .await
+                    .map_err(|e| QuicRtcError::MediaProcessing {
+                        reason: format!("Video capture failed: {}", e),
+                    })?;
+            }
+        }
+
+        // Create MoQ track for video
+        let track_namespace = self.local_track_namespace("camera")?;
+
+        let moq_track = MoqTrack::new(
+            track_namespace.clone(),
+            "camera".to_string(),
+            quicrtc_core::MoqTrackType::Video,
+        );
+
+        // Announce track
+        moq_transport.announce_track(moq_track.clone()).await?;
+
+        // Store published track info with write lock
+        {
+            let mut inner = self.inner.write().await;
+            let published_track = PublishedTrack {
+                track_id: track_id.clone(),
+                track_type: TrackType::Video,
+                codec,
+                moq_track: moq_track.clone(),
+                muted: false,
+                published_at: std::time::Instant::now(),
+            };
+            inner
+                .published_tracks
+                .insert(track_id.clone(), published_track);
+            if let Some(local) = inner.local_participant.as_mut() {
+                let mut track = LocalTrack::video(track_id.clone(), TrackSource::Camera, moq_track);
+                if let Some(config) = &self.video_config {
+                    track.set_content_hint(config.content_hint);
+                }
+                track.set_state(TrackState::Publishing);
+                local.add_local_track(track);
+            }
+        }
+
+        // Create and return video track
+        let video_track = VideoTrack::new(track_id);
+
+        info!("✅ Camera track published successfully");
+        Ok(video_track)
+    }
+
+    /// Publish microphone with default settings
+    pub async fn publish_microphone(&mut self) -> Result<crate::AudioTrack, crate::QuicRtcError> {
+        info!("🎵 Publishing microphone track");
+
+        // Check microphone permissions first per user preferences [[memory:3911748]]
+        #[cfg(target_family = "unix")]
+        {
+            // Do permission check before acquiring any locks
+            let inner_read = self.inner.read().await;
+            if let Some(audio_renderer) = &inner_read.audio_renderer {
+                let renderer = audio_renderer.lock().await;
+                // Try to check if audio devices are available
+                match renderer.list_devices() {
+                    Ok(devices) => {
+                        if devices.is_empty() {
+                            return Err(QuicRtcError::MediaProcessing {
+                                reason: "No audio input devices available - check permissions"
+                                    .to_string(),
+                            });
+                        }
+                        debug!(
+                            "✅ Microphone permissions verified - {} devices available",
+                            devices.len()
+                        );
+                    }
+                    Err(e) => {
+                        // If device enumeration fails, assume it's available for now
+                        debug!(
+                            "⚠️ Could not enumerate audio devices ({}), assuming available",
+                            e
+                        );
+                    }
+                }
+            }
+        }
+
+        // Get transport and do pre-flight checks
+        let (moq_transport, track_id) = {
+            let inner = self.inner.read().await;
+
+            // Ensure we're connected
+            if inner.state != RoomState::Connected {
+                return Err(QuicRtcError::InvalidState {
+                    expected: "Connected".to_string(),
+                    actual: format!("{:?}", inner.state),
+                });
+            }
+
+            // Ensure audio is enabled in configuration
+            if !self.config.audio_enabled {
+                return Err(QuicRtcError::InvalidData {
+                    reason: "audio_enabled must be true to publish microphone".to_string(),
+                });
+            }
+
+            // Get transport reference
+            let transport = inner
+                .moq_transport
+                .as_ref()
+                .ok_or_else(|| QuicRtcError::InvalidState {
+                    expected: "MoQ transport connected".to_string(),
+                    actual: "MoQ transport not available".to_string(),
+                })?
+                .clone();
+
+            let track_id = format!("microphone-{}", uuid::Uuid::new_v4());
+            (transport, track_id)
+        };
+        let codec = self
+            .encoder_codec(&quicrtc_core::MoqTrackType::Audio)
+            .await?;
+        debug!("🎵 Encoding microphone with {}", codec);
+
+        // Configure audio renderer
+        {
+            let inner = self.inner.read().await;
+            let audio_renderer =
+                inner
+                    .audio_renderer
+                    .as_ref()
+                    .ok_or_else(|| QuicRtcError::InvalidState {
+                        expected: "Audio renderer initialized".to_string(),
+                        actual: "Audio renderer not available".to_string(),
+                    })?;
+
+            let mut renderer = audio_renderer.lock().await;
+
+            // Use audio config or defaults
+            let audio_config = self.audio_config.as_ref();
+            let volume = audio_config.map(|c| c.default_volume).unwrap_or(0.8);
+
+            // Set volume
+            renderer
+                .set_volume(volume)
+                .map_err(|e| QuicRtcError::MediaProcessing {
+                    reason: format!("Failed to set audio volume: {}", e),
+                })?;
+        }
+
+        // Create MoQ track for audio
+        let track_namespace = self.local_track_namespace("microphone")?;
+
+        let moq_track = MoqTrack::new(
+            track_namespace.clone(),
+            "microphone".to_string(),
+            quicrtc_core::MoqTrackType::Audio,
+        );
+
+        // Announce track
+        moq_transport.announce_track(moq_track.clone()).await?;
+
+        // Store published track info with write lock
+        {
+            let mut inner = self.inner.write().await;
+            let published_track = PublishedTrack {
+                track_id: track_id.clone(),
+                track_type: TrackType::Audio,
+                codec,
+                moq_track: moq_track.clone(),
+                muted: false,
+                published_at: std::time::Instant::now(),
+            };
+            inner
+                .published_tracks
+                .insert(track_id.clone(), published_track);
+            if let Some(local) = inner.local_participant.as_mut() {
+                let mut track =
+                    LocalTrack::audio(track_id.clone(), TrackSource::Microphone, moq_track);
+                track.set_state(TrackState::Publishing);
+                local.add_local_track(track);
+            }
+        }
+
+        // Create and return audio track
+        let audio_track = AudioTrack::new(track_id);
+
+        self.inner
+            .write()
+            .await
+            .audio_route_policy
+            .microphone_active = true;
+        if let Err(e) = self.update_audio_route().await {
+            warn!("⚠️ Could not route audio for the microphone: {}", e);
+        }
+
+        info!("✅ Microphone track published successfully");
+        Ok(audio_track)
+    }
+
+    /// Play application audio into the published microphone track
+    ///
+    /// For bots, announcements and text-to-speech. Use
+    /// [`Room::play_audio_with_options`] to set the gain or send the audio
+    /// on a separate track.
+    pub async fn play_audio(&self, source: AudioSource) -> Result<AudioPlayback, QuicRtcError> {
+        self.play_audio_with_options(source, PlayAudioOptions::default())
+            .await
+    }
+
+    /// Play application audio with gain control and a choice of track
+    ///
+    /// The source is converted to the encoder's format, paced in real time
+    /// and mixed with any other audio playing into the same track.
+    pub async fn play_audio_with_options(
+        &self,
+        source: AudioSource,
+        options: PlayAudioOptions,
+    ) -> Result<AudioPlayback, QuicRtcError> {
+        let (track_id, mixer) = self.outbound_audio(options.target).await?;
+
+        let playback_id = format!("playback-{}", uuid::Uuid::new_v4());
+        let gain = Arc::new(PlaybackGain::new(options.gain));
+        mixer.lock().await.add_source(&playback_id, gain.clone());
+
+        let stop = CancellationToken::new();
+        let finished = CancellationToken::new();
+        let task = tokio::spawn(feed_playback(
+            source,
+            mixer,
+            playback_id.clone(),
+            stop.clone(),
+            finished.clone(),
+        ));
+        {
+            let mut inner = self.inner.write().await;
+            inner.background_tasks.retain(|task| !task.is_finished());
+            inner.background_tasks.push(task);
+        }
+
+        debug!("🔊 Playing audio {} on track {}", playback_id, track_id);
+        Ok(AudioPlayback::new(
+            playback_id,
+            track_id,
+            gain,
+            stop,
+            finished,
+        ))
+    }
+
+    /// Mixer sending played audio on the `target` track, starting it on
+    /// first use
+    async fn outbound_audio(
+        &self,
+        target: PlaybackTarget,
+    ) -> Result<(String, Arc<tokio::sync::Mutex<AudioMixer>>), QuicRtcError> {
+        let track_name = match target {
+            PlaybackTarget::Microphone => "microphone",
+            PlaybackTarget::SeparateTrack => "playback",
+        };
+        let find_track = |inner: &RoomInner| {
+            inner
+                .published_tracks
+                .values()
+                .find(|track| {
+                    track.track_type == TrackType::Audio && track.moq_track.name == track_name
+                })
+                .map(|track| (track.track_id.clone(), track.moq_track.clone()))
+        };
+
+        let (moq_transport, processor, existing) = {
+            let inner = self.inner.read().await;
+            if inner.state != RoomState::Connected {
+                return Err(QuicRtcError::InvalidState {
+                    expected: "Connected".to_string(),
+                    actual: format!("{:?}", inner.state),
+                });
+            }
+            let transport =
+                inner
+                    .moq_transport
+                    .clone()
+                    .ok_or_else(|| QuicRtcError::InvalidState {
+                        expected: "MoQ transport connected".to_string(),
+                        actual: "MoQ transport not available".to_string(),
+                    })?;
+            let processor =
+                inner
+                    .media_processor
+                    .clone()
+                    .ok_or_else(|| QuicRtcError::InvalidState {
+                        expected: "Media processor initialized".to_string(),
+                        actual: "Media processor not available".to_string(),
+                    })?;
+            (transport, processor, find_track(&inner))
+        };
+
+        let (track_id, moq_track) = match (existing, target) {
+            (Some(track), _) => track,
+            (None, PlaybackTarget::Microphone) => {
+                return Err(QuicRtcError::InvalidState {
+                    expected: "Microphone published".to_string(),
+                    actual: "No microphone track".to_string(),
+                });
+            }
+            (None, PlaybackTarget::SeparateTrack) => {
+                if !self.config.audio_enabled {
+                    return Err(QuicRtcError::InvalidData {
+                        reason: "audio_enabled must be true to publish a playback track"
+                            .to_string(),
+                    });
+                }
+                let codec = self
+                    .encoder_codec(&quicrtc_core::MoqTrackType::Audio)
+                    .await?;
+                let moq_track = MoqTrack::new(
+                    self.local_track_namespace(track_name)?,
+                    track_name.to_string(),
+                    quicrtc_core::MoqTrackType::Audio,
+                );
+                moq_transport.announce_track(moq_track.clone()).await?;
+
+                let mut inner = self.inner.write().await;
+                // Another playback may have published it meanwhile
+                match find_track(&inner) {
+                    Some(track) => track,
+                    None => {
+                        let track_id = format!("playback-{}", uuid::Uuid::new_v4());
+                        inner.published_tracks.insert(
+                            track_id.clone(),
+                            PublishedTrack {
+                                track_id: track_id.clone(),
+                                track_type: TrackType::Audio,
+                                codec,
+                                moq_track: moq_track.clone(),
+                                muted: false,
+                                published_at: std::time::Instant::now(),
+                            },
+                        );
+                        if let Some(local) = inner.local_participant.as_mut() {
+                            let mut track = LocalTrack::audio(
+                                track_id.clone(),
+                                TrackSource::File,
+                                moq_track.clone(),
+                            );
+                            track.set_state(TrackState::Publishing);
+                            local.add_local_track(track);
+                        }
+                        (track_id, moq_track)
+                    }
+                }
+            }
+        };
+
+        let mut inner = self.inner.write().await;
+        if let Some(mixer) = inner.outbound_audio.get(&track_id) {
+            return Ok((track_id, mixer.clone()));
+        }
+        let (sample_rate, layout) = match self.audio_profile() {
+            Some(profile) => {
+                let config = profile.opus_config();
+                let layout = config.channel_layout().unwrap_or(PLAYBACK_LAYOUT);
+                (config.sample_rate, layout)
+            }
+            None => (PLAYBACK_SAMPLE_RATE, PLAYBACK_LAYOUT),
+        };
+        let mut mixer = AudioMixer::new(sample_rate, layout);
+        mixer.set_ducking(self.ducker());
+        let mixer = Arc::new(tokio::sync::Mutex::new(mixer));
+        let (encoder, encoded) = TrackEncoder::start(
+            &track_id,
+            quicrtc_core::MoqTrackType::Audio,
+            moq_track.namespace,
+            inner.encode_pipeline.clone(),
+            processor,
+        )
+        .await?;
+        let task = tokio::spawn(send_outbound_audio(
+            mixer.clone(),
+            encoder,
+            encoded,
+            moq_transport,
+        ));
+        inner.background_tasks.push(task);
+        inner.outbound_audio.insert(track_id.clone(), mixer.clone());
+        Ok((track_id, mixer))
+    }
+
+    /// Publish several video sources composed into a single track
+    ///
+    /// Frames are drawn from `compositor` at the configured video frame rate
+    /// and encoded like the camera, so one subscription, such as a recorder
+    /// or RTMP egress, sees the whole call. Feed the compositor through the
+    /// returned handle. In the active speaker layout, sources keyed by
+    /// participant ID follow whoever in the room is speaking. With
+    /// [`ThumbnailConfig`] set, a `composition-thumbnail` track is published
+    /// alongside.
+    pub async fn publish_composition(
+        &self,
+        compositor: VideoCompositor,
+    ) -> Result<CompositionPublication, QuicRtcError> {
+        let (moq_transport, processor) = {
+            let inner = self.inner.read().await;
+            if inner.state != RoomState::Connected {
+                return Err(QuicRtcError::InvalidState {
+                    expected: "Connected".to_string(),
+                    actual: format!("{:?}", inner.state),
+                });
+            }
+            if !self.config.video_enabled {
+                return Err(QuicRtcError::InvalidData {
+                    reason: "video_enabled must be true to publish a composition".to_string(),
+                });
+            }
+            if inner
+                .published_tracks
+                .values()
+                .any(|track| track.moq_track.name == "composition")
+            {
+                return Err(QuicRtcError::InvalidState {
+                    expected: "No composition published".to_string(),
+                    actual: "Composition already published".to_string(),
+                });
+            }
+            let transport =
+                inner
+                    .moq_transport
+                    .clone()
+                    .ok_or_else(|| QuicRtcError::InvalidState {
+                        expected: "MoQ transport connected".to_string(),
+                        actual: "MoQ transport not available".to_string(),
+                    })?;
+            let processor =
+                inner
+                    .media_processor
+                    .clone()
+                    .ok_or_else(|| QuicRtcError::InvalidState {
+                        expected: "Media processor initialized".to_string(),
+                        actual: "Media processor not available".to_string(),
+                    })?;
+            (transport, processor)
+        };
+        let codec = self
+            .encoder_codec(&quicrtc_core::MoqTrackType::Video)
+            .await?;
+
+        let moq_track = MoqTrack::new(
+            self.local_track_namespace("composition")?,
+            "composition".to_string(),
+            quicrtc_core::MoqTrackType::Video,
+        );
+        moq_transport.announce_track(moq_track.clone()).await?;
+        let thumbnails = self
+            .publish_thumbnail_track("composition", &moq_transport)
+            .await?;
+
+        let framerate = self
+            .video_config
+            .as_ref()
+            .map(|c| c.default_framerate)
+            .unwrap_or(30.0);
+        let track_id = format!("composition-{}", uuid::Uuid::new_v4());
+        let pipeline = self.inner.read().await.encode_pipeline.clone();
+        let (encoder, encoded) = TrackEncoder::start(
+            &track_id,
+            quicrtc_core::MoqTrackType::Video,
+            moq_track.namespace.clone(),
+            pipeline,
+            processor,
+        )
+        .await?;
+        let compositor = Arc::new(tokio::sync::Mutex::new(compositor));
+        let stop = CancellationToken::new();
+        let task = tokio::spawn(send_composition(
+            compositor.clone(),
+            Arc::downgrade(&self.inner),
+            encoder,
+            encoded,
+            moq_transport,
+            Duration::from_secs_f64(1.0 / framerate.max(1.0)),
+            thumbnails,
+            stop.clone(),
+        ));
+
+        {
+            let mut inner = self.inner.write().await;
+            inner.published_tracks.insert(
+                track_id.clone(),
+                PublishedTrack {
+                    track_id: track_id.clone(),
+                    track_type: TrackType::Video,
+                    codec,
+                    moq_track: moq_track.clone(),
+                    muted: false,
+                    published_at: std::time::Instant::now(),
+                },
+            );
+            if let Some(local) = inner.local_participant.as_mut() {
+                let mut track =
+                    LocalTrack::video(track_id.clone(), TrackSource::Composition, moq_track);
+                track.set_state(TrackState::Publishing);
+                local.add_local_track(track);
+            }
+            inner.background_tasks.retain(|task| !task.is_finished());
+            inner.background_tasks.push(task);
+        }
+
+        info!("🎞️ Composition track {} published", track_id);
+        Ok(CompositionPublication::new(track_id, compositor, stop))
+    }
+
+    /// Announce the thumbnail track of the `source` video track, if
+    /// thumbnails are configured
+    async fn publish_thumbnail_track(
+        &self,
+        source: &str,
+        moq_transport: &MoqOverQuicTransport,
+    ) -> Result<Option<ThumbnailSender>, QuicRtcError> {
+        let Some(config) = self
+            .video_config
+            .as_ref()
+            .and_then(|config| config.thumbnails.clone())
+        else {
+            return Ok(None);
+        };
+        let codec = self
+            .encoder_codec(&quicrtc_core::MoqTrackType::Video)
+            .await?;
+
+        let track_name = format!("{}{}", source, THUMBNAIL_TRACK_SUFFIX);
+        let moq_track = MoqTrack::new(
+            self.local_track_namespace(&track_name)?,
+            track_name.clone(),
+            quicrtc_core::MoqTrackType::Video,
+        );
+        moq_transport.announce_track(moq_track.clone()).await?;
+
+        let track_id = format!("{}-{}", track_name, uuid::Uuid::new_v4());
+        self.inner.write().await.published_tracks.insert(
+            track_id.clone(),
+            PublishedTrack {
+                track_id,
+                track_type: TrackType::Video,
+                codec,
+                moq_track: moq_track.clone(),
+                muted: false,
+                published_at: std::time::Instant::now(),
+            },
+        );
+
+        debug!("🖼️ Publishing {} thumbnails", source);
+        Ok(Some(ThumbnailSender {
+            generator: ThumbnailGenerator::new(config.height, config.interval),
+            namespace: moq_track.namespace,
+            bitrate: config.bitrate,
+            group_id: 0,
+        }))
+    }
+
+    /// Ask the video encoder for a keyframe as soon as possible
+    ///
+    /// The next outgoing video group starts with it, so subscribers that
+    /// joined mid-stream or lost reference frames can decode again without
+    /// waiting for the regular keyframe interval.
+    pub async fn request_keyframe(&self) -> Result<(), QuicRtcError> {
+        let inner = self.inner.read().await;
+        let processor =
+            inner
+                .media_processor
+                .as_ref()
+                .ok_or_else(|| QuicRtcError::InvalidState {
+                    expected: "Media processor initialized".to_string(),
+                    actual: "Media processor not available".to_string(),
+                })?;
+        processor.lock().await.request_keyframe();
+        debug!("🔑 Keyframe requested");
+        Ok(())
+    }
+
+    /// Audio output devices, such as speakers and headsets, remote audio
+    /// can be played to
+    pub async fn audio_output_devices(
+        &self,
+    ) -> Result<Vec<quicrtc_media::AudioOutputDevice>, QuicRtcError> {
+        let renderer = self.audio_renderer().await?;
+        let devices =
+            renderer
+                .lock()
+                .await
+                .list_devices()
+                .map_err(|e| QuicRtcError::MediaProcessing {
+                    reason: format!("Failed to list audio output devices: {}", e),
+                })?;
+        Ok(devices)
+    }
+
+    /// Change what a published video track mostly shows
+    ///
+    /// The hint is handed to the video encoder, which retunes rate control
+    /// and keyframe spacing from the next group, and to rate adaptation,
+    /// which then gives up framerate before resolution for detail and text
+    /// content. The published [`LocalTrack`] records the hint as well.
+    pub async fn set_video_content_hint(
+        &self,
+        track_id: &str,
+        hint: VideoContentHint,
+    ) -> Result<(), QuicRtcError> {
+        let processor = {
+            let mut inner = self.inner.write().await;
+            let track = inner
+                .local_participant
+                .as_mut()
+                .and_then(|local| local.get_local_track_mut(track_id))
+                .filter(|track| track.kind() == crate::track::TrackKind::Video)
+                .ok_or_else(|| QuicRtcError::InvalidData {
+                    reason: format!("No published video track {}", track_id),
+                })?;
+            track.set_content_hint(hint);
+            inner.media_processor.clone()
+        };
+        if let Some(processor) = processor {
+            processor.lock().await.set_video_content_hint(hint);
+        }
+        info!("🎬 Video track {} content hint: {:?}", track_id, hint);
+        Ok(())
+    }
+
+    /// Set the rotation needed to display the camera upright
+    ///
+    /// Mobile apps call this as the device orientation changes; every
+    /// camera frame captured afterwards carries the rotation so receivers
+    /// can render it the right way up.
+    pub async fn set_camera_rotation(&self, rotation: VideoRotation) -> Result<(), QuicRtcError> {
+        let video_capture = self
+            .inner
+            .read()
+            .await
+            .video_capture
+            .clone()
+            .ok_or_else(|| QuicRtcError::InvalidState {
+                expected: "Video capture initialized".to_string(),
+                actual: "Video capture not available".to_string(),
+            })?;
+        video_capture.lock().await.set_rotation(rotation);
+        debug!("📹 Camera rotation: {:?}", rotation);
+        Ok(())
+    }
+
+    /// Play remote audio to another output device
+    ///
+    /// `device_id` is an [`AudioOutputDevice::id`](quicrtc_media::AudioOutputDevice::id)
+    /// from [`Room::audio_output_devices`], or `None` for the system
+    /// default. Playback switches mid-call without losing queued audio.
+    /// Picking a device stops following the system default.
+    pub async fn set_audio_output(&self, device_id: Option<&str>) -> Result<(), QuicRtcError> {
+        let renderer = self.audio_renderer().await?;
+        if device_id.is_some() {
+            self.inner.write().await.follow_default_devices = false;
+        }
+        renderer
+            .lock()
+            .await
+            .switch_device(device_id.map(str::to_string))
+            .map_err(|e| QuicRtcError::MediaProcessing {
+                reason: format!("Failed to switch audio output: {}", e),
+            })?;
+        info!(
+            "🔊 Audio output set to {}",
+            device_id.unwrap_or("the default device")
+        );
+        Ok(())
+    }
+
+    /// Audio output device remote audio plays to; `None` for the system
+    /// default
+    pub async fn audio_output(&self) -> Option<String> {
+        let renderer = self.audio_renderer().await.ok()?;
+        let device = renderer.lock().await.device_name().map(str::to_string);
+        device
+    }
+
+    /// Move playback to the system default output device whenever it
+    /// changes, such as when a headset connects
+    ///
+    /// Enabling switches to the current default right away. Switches drain
+    /// and reopen the output stream like [`Room::set_audio_output`] and are
+    /// reported with [`crate::Event::DefaultDeviceChanged`].
+    pub async fn set_follow_default_devices(&self, follow: bool) -> Result<(), QuicRtcError> {
+        if follow {
+            self.set_audio_output(None).await?;
+        }
+        self.inner.write().await.follow_default_devices = follow;
+        Ok(())
+    }
+
+    /// Whether playback follows the system default output device
+    pub async fn follows_default_devices(&self) -> bool {
+        self.inner.read().await.follow_default_devices
+    }
+
+    /// Feed a frame of local capture to voice activity detection
+    ///
+    /// With [`RoomBuilder::audio_ducking`], remote participants and played
+    /// audio are lowered while speech is detected. Returns whether the local
+    /// user is speaking; always `false` without ducking.
+    pub async fn process_local_audio(&self, frame: &AudioFrame) -> bool {
+        let speaking = {
+            let mut inner = self.inner.write().await;
+            match inner.local_voice.as_mut() {
+                Some(detector) => detector.process(frame),
+                None => return false,
+            }
+        };
+        self.set_local_speaking(speaking).await;
+        speaking
+    }
+
+    /// Set whether the local user is speaking, for apps with their own
+    /// voice activity detection
+    ///
+    /// Ducks audio like [`Room::process_local_audio`] when configured.
+    pub async fn set_local_speaking(&self, speaking: bool) {
+        let mixers: Vec<_> = {
+            let mut inner = self.inner.write().await;
+            if let Some(local) = inner.local_participant.as_mut() {
+                local.set_speaking(speaking);
+            }
+            inner
+                .audio_mixer
+                .iter()
+                .chain(inner.outbound_audio.values())
+                .cloned()
+                .collect()
+        };
+        for mixer in mixers {
+            mixer.lock().await.set_local_speaking(speaking);
+        }
+    }
+
+    /// Preset the room's audio is sent with, if any
+    fn audio_profile(&self) -> Option<AudioProfile> {
+        self.audio_config.as_ref()?.profile
+    }
+
+    /// Ducker for a new mixer, if ducking is configured
+    fn ducker(&self) -> Option<quicrtc_media::AudioDucker> {
+        self.audio_config
+            .as_ref()?
+            .ducking
+            .as_ref()
+            .map(DuckingConfig::ducker)
+    }
+
+    async fn audio_renderer(
+        &self,
+    ) -> Result<Arc<tokio::sync::Mutex<CpalAudioRenderer>>, QuicRtcError> {
+        self.inner
+            .read()
+            .await
+            .audio_renderer
+            .clone()
+            .ok_or_else(|| QuicRtcError::InvalidState {
+                expected: "Audio renderer initialized".to_string(),
+                actual: "Audio renderer not available".to_string(),
+            })
+    }
+
+    /// Codec the media processor encodes outgoing media of `track_type` with
+    async fn encoder_codec(
+        &self,
+        track_type: &quicrtc_core::MoqTrackType,
+    ) -> Result<String, QuicRtcError> {
+        let inner = self.inner.read().await;
+        let processor = inner
+            .media_processor
+            .as_ref()
+            .ok_or_else(|| QuicRtcError::InvalidState {
+                expected: "Media processor initialized".to_string(),
+                actual: "Media processor not available".to_string(),
+            })?
+            .lock()
+            .await;
+
+        let codec = match track_type {
+            quicrtc_core::MoqTrackType::Audio => processor.audio_codec(),
+            _ => processor.video_codec(),
+        };
+        codec
+            .map(str::to_string)
+            .ok_or_else(|| QuicRtcError::UnsupportedCodec {
+                codec: format!("no {:?} codec registered", track_type),
+            })
+    }
+
+    /// Pick the decoder for a remote track from the codecs its publisher advertised
+    ///
+    /// Publishers that advertised nothing, for example without signaling,
+    /// are decoded with our own preferred codec.
+    async fn negotiate_remote_codec(
+        &self,
+        participant_id: &str,
+        track: &crate::RemoteTrack,
+    ) -> Result<(), QuicRtcError> {
+        let inner = self.inner.read().await;
+
+        #[cfg(feature = "signaling")]
+        let remote_codecs = match &inner.signaling_connection {
+            Some(signaling) => {
+                let kind = match track.kind() {
+                    crate::track::TrackKind::Audio => quicrtc_signaling::CodecKind::Audio,
+                    crate::track::TrackKind::Video => quicrtc_signaling::CodecKind::Video,
+                };
+                signaling
+                    .lock()
+                    .await
+                    .discovered_peers
+                    .get(participant_id)
+                    .map(|peer| peer.capabilities.codec_names(kind))
+                    .filter(|names| !names.is_empty())
+            }
+            None => None,
+        };
+        #[cfg(not(feature = "signaling"))]
+        let remote_codecs: Option<Vec<String>> = None;
+
+        let (Some(remote_codecs), Some(processor)) = (remote_codecs, &inner.media_processor) else {
+            return Ok(());
+        };
+
+        let track_type = match track.kind() {
+            crate::track::TrackKind::Audio => quicrtc_core::MoqTrackType::Audio,
+            crate::track::TrackKind::Video => quicrtc_core::MoqTrackType::Video,
+        };
+        let codec = processor.lock().await.negotiate_track_codec(
+            track.moq_track().namespace.clone(),
+            &track_type,
+            &remote_codecs,
+        )?;
+        debug!(
+            "🎞️ Decoding {}'s track {} with {}",
+            participant_id,
+            track.id(),
+            codec
+        );
+        Ok(())
+    }
+
+    /// Check camera permissions (platform-specific implementation) - REMOVED
+    #[cfg(target_family = "unix")]
+    async fn _check_camera_permissions(&self) -> Result<(), QuicRtcError> {
+        // On Unix systems, camera permissions are typically handled by the system
+        // This is a placeholder for more sophisticated permission checking
+        debug!("🔐 Checking camera permissions");
+
+        // For now, we'll attempt to enumerate devices to test permissions
+        // In a real implementation, this might check system permission APIs
+        if let Some(video_capture) = &self.inner.read().await.video_capture {
+            let capture_manager = video_capture.lock().await;
+            match capture_manager.enumerate_devices() {
+                Ok(devices) => {
+                    if devices.is_empty() {
+                        return Err(QuicRtcError::MediaProcessing {
+                            reason: "No camera devices available - check permissions".to_string(),
+                        });
+                    }
+                    debug!(
+                        "✅ Camera permissions verified - {} devices available",
+                        devices.len()
+                    );
+                }
+                Err(e) => {
+                    error!("❌ Camera permission check failed: {}", e);
+                    return Err(QuicRtcError::MediaProcessing {
+                        reason: format!("Camera access denied: {}", e),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check microphone permissions (platform-specific implementation)
+    #[cfg(target_family = "unix")]
+    async fn check_microphone_permissions(&self) -> Result<(), QuicRtcError> {
+        debug!("🔐 Checking microphone permissions");
+
+        // Similar to camera, check by attempting to list audio devices
+        if let Some(audio_renderer) = &self.inner.read().await.audio_renderer {
+            let renderer = audio_renderer.lock().await;
+            // Try to check if audio devices are available
+            match renderer.list_devices() {
+                Ok(devices) => {
+                    if devices.is_empty() {
+                        return Err(QuicRtcError::MediaProcessing {
+                            reason: "No audio input devices available - check permissions"
+                                .to_string(),
+                        });
+                    }
+                    debug!(
+                        "✅ Microphone permissions verified - {} devices available",
+                        devices.len()
+                    );
+                }
+                Err(e) => {
+                    // If device enumeration fails, assume it's available for now
+                    debug!(
+                        "⚠️ Could not enumerate audio devices ({}), assuming available",
+                        e
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Room {
+    /// Render the room's recent debug logs, one record per line
+    ///
+    /// Returns `None` unless the room was built with
+    /// [`RoomBuilder::debug_logger`].
+    #[cfg(feature = "diagnostics")]
+    pub fn dump_debug_logs(&self) -> Option<String> {
+        self.debug_log.as_ref().map(|ring| ring.dump())
+    }
+
+    /// Get event stream
+    ///
+    /// Each call returns an independent stream that receives every event
+    /// from now on, buffered with [`EventBufferConfig::default`].
+    pub fn events(&self) -> crate::EventStream {
+        self.events.subscribe(EventBufferConfig::default())
+    }
+
+    /// Get an event stream with custom buffering
+    ///
+    /// Slow consumers lose events according to the buffer's overflow
+    /// policy instead of growing memory without bound.
+    pub fn events_with_buffer(&self, config: EventBufferConfig) -> crate::EventStream {
+        self.events.subscribe(config)
+    }
+
+    /// Accept or decline a recording or transcription request
+    ///
+    /// `request_id` comes from [`Event::CaptureConsentRequested`](crate::Event::CaptureConsentRequested).
+    /// Rooms that require acknowledgement refuse to capture unless every
+    /// participant accepts.
+    #[cfg(feature = "signaling")]
+    pub async fn respond_to_capture(
+        &self,
+        request_id: &str,
+        accepted: bool,
+    ) -> Result<(), QuicRtcError> {
+        let signaling = self.inner.read().await.signaling_connection.clone();
+        let Some(signaling) = signaling else {
+            return Err(QuicRtcError::InvalidState {
+                expected: "connected to a signaling server".to_string(),
+                actual: "no signaling connection".to_string(),
+            });
+        };
+        let client = Arc::clone(&signaling.lock().await.client);
+        client
+            .send(&SignalingMessage::CaptureConsent {
+                room_id: self.id.clone(),
+                request_id: request_id.to_string(),
+                accepted,
+            })
+            .await
+    }
+
+    /// Get a remote participant by ID
+    ///
+    /// Volume and local mute changes made through the returned handle apply
+    /// to the room's audio mixer immediately.
+    pub async fn remote_participant(
+        &self,
+        participant_id: &str,
+    ) -> Option<crate::RemoteParticipant> {
+        self.inner
+            .read()
+            .await
+            .participants
+            .get_remote_participant(participant_id)
+            .cloned()
+    }
+
+    /// Get one of our published tracks by ID
+    pub async fn local_track(&self, track_id: &str) -> Option<LocalTrack> {
+        self.inner
+            .read()
+            .await
+            .local_participant
+            .as_ref()?
+            .get_local_track(track_id)
+            .cloned()
+    }
+
+    /// History of track and connection statistics, if the room was built
+    /// with [`RoomBuilder::stats_history`]
+    pub fn stats_history(&self) -> Option<&StatsHistory> {
+        self.stats_history.as_ref()
+    }
+
+    /// Statistics sampled from `from` to `to`, oldest first
+    ///
+    /// Empty unless the room was built with [`RoomBuilder::stats_history`];
+    /// only samples within the history's retention are left.
+    pub fn stats_between(
+        &self,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Vec<StatsSample> {
+        self.stats_history
+            .as_ref()
+            .map(|history| history.stats_between(from, to))
+            .unwrap_or_default()
+    }
+
+    /// Quality level of the media connection, `None` until statistics were
+    /// first refreshed
+    ///
+    /// Changes are reported as [`crate::Event::ConnectionQualityChanged`].
+    pub async fn connection_quality(&self) -> Option<QualityLevel> {
+        self.inner.read().await.quality.connection()
+    }
+
+    /// Quality level of a published or remote track
+    pub async fn track_quality(&self, track_id: &str) -> Option<QualityLevel> {
+        self.inner.read().await.quality.track(track_id)
+    }
+
+    /// Latest statistics of a published or remote track
+    ///
+    /// Statistics are refreshed every [`RoomBuilder::stats_interval`].
+    pub async fn track_stats(&self, track_id: &str) -> Option<TrackStats> {
+        let inner = self.inner.read().await;
+        if let Some(track) = inner
+            .local_participant
+            .as_ref()
+            .and_then(|local| local.get_local_track(track_id))
+        {
+            return Some(track.stats().clone());
+        }
+        let stats = inner
+            .participants
+            .iter()
+            .find_map(|participant| participant.get_remote_track(track_id))
+            .map(|track| track.stats().clone());
+        stats
+    }
+
+    /// Get all remote participants currently in the room
+    pub async fn remote_participants(&self) -> Vec<crate::RemoteParticipant> {
+        self.inner
+            .read()
+            .await
+            .participants
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Watch for participants joining, leaving or changing state
+    ///
+    /// See [`crate::Participants::watch`].
+    pub async fn watch_participants(
+        &self,
+    ) -> tokio::sync::watch::Receiver<crate::participant::ParticipantsSnapshot> {
+        self.inner.read().await.participants.watch()
+    }
+
+    /// Describe the tracks a remote participant has announced
+    ///
+    /// Returns `None` if the participant is not in the room.
+    pub async fn remote_tracks(&self, participant_id: &str) -> Option<Vec<crate::RemoteTrackInfo>> {
+        self.inner
+            .read()
+            .await
+            .participants
+            .get_remote_participant(participant_id)
+            .map(|participant| participant.tracks())
+    }
+
+    /// Receive pictures decoded from subscribed remote video tracks
+    ///
+    /// Each receiver gets every picture from the time it subscribes. One
+    /// that falls behind skips the oldest pictures rather than delaying the
+    /// decoders.
+    #[cfg(feature = "media")]
+    pub async fn remote_video(&self) -> tokio::sync::broadcast::Receiver<crate::RemoteVideoFrame> {
+        self.inner.read().await.remote_video.subscribe()
+    }
+
+    /// Start receiving one of a remote participant's tracks
+    ///
+    /// Emits [`crate::Event::TrackSubscribed`]. Subscribing to a track that is
+    /// already subscribed does nothing.
+    pub async fn subscribe_track(
+        &self,
+        participant_id: &str,
+        track_id: &str,
+    ) -> Result<(), QuicRtcError> {
+        self.subscribe_track_with_options(participant_id, track_id, SubscribeOptions::default())
+            .await
+    }
+
+    /// Start receiving one of a remote participant's tracks with the given
+    /// delivery priority and playout buffering
+    ///
+    /// Remote audio is mixed into a single output, so the playout
+    /// configuration of the most recent audio subscription applies to all
+    /// remote audio.
+    pub async fn subscribe_track_with_options(
+        &self,
+        participant_id: &str,
+        track_id: &str,
+        options: SubscribeOptions,
+    ) -> Result<(), QuicRtcError> {
+        #[cfg(feature = "media")]
+        options
+            .playback
+            .validate()
+            .map_err(|e| QuicRtcError::InvalidData {
+                reason: format!("Invalid playback configuration: {}", e),
+            })?;
+        if options
+            .dvr_retention
+            .is_some_and(|retention| retention.is_zero())
+        {
+            return Err(QuicRtcError::InvalidData {
+                reason: "DVR retention must be greater than zero".to_string(),
+            });
+        }
+
+        let (transport, mut track) = self
+            .remote_track_for_update(participant_id, track_id)
+            .await?;
+        if self.config.data_saver && is_full_video(&track) {
+            track = self
+                .thumbnail_rendition(participant_id, &track)
+                .await
+                .ok_or_else(|| QuicRtcError::InvalidState {
+                    expected: "Thumbnail rendition of video track".to_string(),
+                    actual: format!("Data saver mode on and track {} has none", track_id),
+                })?;
+        }
+        let track_id = track.id().to_string();
+        let track_id = track_id.as_str();
+        if track.is_subscribed() {
+            return Ok(());
+        }
+        if track.kind() == crate::track::TrackKind::Video && self.inner.read().await.audio_only {
+            return Err(QuicRtcError::InvalidState {
+                expected: "Audio-only mode off".to_string(),
+                actual: "Audio-only mode on".to_string(),
+            });
+        }
+
+        #[cfg(feature = "media")]
+        self.negotiate_remote_codec(participant_id, &track).await?;
+
+        #[cfg(feature = "media")]
+        let playout_delay = options.playback.target_buffer;
+        #[cfg(feature = "media")]
+        self.apply_playback_config(participant_id, &track, options.playback)
+            .await?;
+
+        // Audio ahead of video, matching publisher object priorities
+        let priority = options.priority.unwrap_or(match track.kind() {
+            crate::track::TrackKind::Audio => 1,
+            crate::track::TrackKind::Video => 2,
+        });
+        let namespace = track.moq_track().namespace.clone();
+        let dvr = options
+            .dvr_retention
+            .map(|retention| transport.enable_dvr(namespace.clone(), retention));
+        if let Err(e) = transport
+            .subscribe_to_track(namespace.clone(), priority, None, None)
+            .await
+        {
+            transport.disable_dvr(&namespace);
+            return Err(e);
+        }
+
+        let mut inner = self.inner.write().await;
+        #[cfg(feature = "media")]
+        if let Some(processor) = inner.media_processor.clone() {
+            let mut processor = processor.lock().await;
+            processor.set_latency_budget(&namespace, track.moq_track().latency_budget);
+            if track.kind() == crate::track::TrackKind::Video {
+                let codec = processor.incoming_video_codec(&namespace).ok_or_else(|| {
+                    QuicRtcError::UnsupportedCodec {
+                        codec: track.metadata().codec.clone().unwrap_or_default(),
+                    }
+                })?;
+                let decoded = DecodedTrack {
+                    participant_id: participant_id.to_string(),
+                    track_id: track_id.to_string(),
+                    playout_delay,
+                };
+                inner.start_video_decode(namespace.clone(), decoded, codec.as_ref())?;
+            }
+        }
+        #[cfg(feature = "media")]
+        if track.kind() == crate::track::TrackKind::Audio {
+            inner
+                .remote_audio
+                .lock()
+                .unwrap()
+                .insert(namespace.clone(), participant_id.to_string());
+            inner.start_audio_playout().await;
+        }
+        inner
+            .participants
+            .update_participant(participant_id, |p| p.set_track_dvr(track_id, dvr));
+        inner.set_remote_track_subscribed(participant_id, track_id, true);
+        Ok(())
+    }
+
+    /// Store a track's playout configuration and apply it to the audio
+    /// renderer for audio tracks
+    #[cfg(feature = "media")]
+    async fn apply_playback_config(
+        &self,
+        participant_id: &str,
+        track: &crate::RemoteTrack,
+        mut playback: quicrtc_media::PlaybackConfig,
+    ) -> Result<(), QuicRtcError> {
+        let mut inner = self.inner.write().await;
+        if track.kind() == crate::track::TrackKind::Audio {
+            // Music profiles trade a little delay for uninterrupted playout
+            if let Some(music) = self.audio_profile().and_then(|p| p.playback_config()) {
+                playback.target_buffer = playback.target_buffer.max(music.target_buffer);
+            }
+            if let Some(audio_renderer) = &inner.audio_renderer {
+                audio_renderer
+                    .lock()
+                    .await
+                    .set_playback_config(playback.clone())
+                    .map_err(|e| QuicRtcError::MediaProcessing {
+                        reason: format!("Failed to configure audio playback: {}", e),
+                    })?;
+            }
+        }
+        inner.participants.update_participant(participant_id, |p| {
+            p.set_track_playback(track.id(), playback)
+        });
+        Ok(())
+    }
+
+    /// Stop receiving one of a remote participant's tracks
+    ///
+    /// Emits [`crate::Event::TrackUnsubscribed`]. The track stays listed in
+    /// [`Room::remote_tracks`] and can be subscribed again.
+    pub async fn unsubscribe_track(
+        &self,
+        participant_id: &str,
+        track_id: &str,
+    ) -> Result<(), QuicRtcError> {
+        let (transport, mut track) = self
+            .remote_track_for_update(participant_id, track_id)
+            .await?;
+        if self.config.data_saver && is_full_video(&track) {
+            match self.thumbnail_rendition(participant_id, &track).await {
+                Some(thumbnail) => track = thumbnail,
+                None => return Ok(()),
+            }
+        }
+        let track_id = track.id().to_string();
+        let track_id = track_id.as_str();
+        if !track.is_subscribed() {
+            return Ok(());
+        }
 
-            let track_id = format!("microphone-{}", uuid::Uuid::new_v4());
-            (transport, track_id)
-        };
+        transport
+            .unsubscribe_from_track(&track.moq_track().namespace)
+            .await?;
 
-        // Configure audio renderer
+        let mut inner = self.inner.write().await;
+        #[cfg(feature = "media")]
         {
-            let inner = self.inner.read().await;
-            let audio_renderer =
-                inner
-                    .audio_renderer
-                    .as_ref()
-                    .ok_or_else(|| QuicRtcError::InvalidState {
-                        expected: "Audio renderer initialized".to_string(),
-                        actual: "Audio renderer not available".to_string(),
-                    })?;
+            let namespace = &track.moq_track().namespace;
+            inner.video_decoding.lock().unwrap().remove_track(namespace);
+            inner.remote_audio.lock().unwrap().remove(namespace);
+        }
+        inner
+            .participants
+            .update_participant(participant_id, |p| p.set_track_dvr(track_id, None));
+        inner.set_remote_track_subscribed(participant_id, track_id, false);
+        Ok(())
+    }
 
-            let mut renderer = audio_renderer.lock().await;
+    /// Routes audio is recorded from and played to, for rooms built with
+    /// [`RoomBuilder::audio_session`]
+    pub async fn audio_route(&self) -> Option<AudioRouteSelection> {
+        self.inner.read().await.audio_route.clone()
+    }
 
-            // Use audio config or defaults
-            let audio_config = self.audio_config.as_ref();
-            let volume = audio_config.map(|c| c.default_volume).unwrap_or(0.8);
+    /// Play through the loudspeaker whatever else is connected, or go back
+    /// to the preferred route
+    pub async fn set_speakerphone(&self, enabled: bool) -> Result<(), QuicRtcError> {
+        {
+            let mut inner = self.inner.write().await;
+            if inner.audio_session.is_none() {
+                return Err(QuicRtcError::InvalidState {
+                    expected: "Audio session configured".to_string(),
+                    actual: "No audio session".to_string(),
+                });
+            }
+            inner.audio_route_policy.speakerphone = enabled;
+        }
+        self.update_audio_route().await
+    }
 
-            // Set volume
-            renderer
-                .set_volume(volume)
-                .map_err(|e| QuicRtcError::MediaProcessing {
-                    reason: format!("Failed to set audio volume: {}", e),
-                })?;
+    /// Pick the audio route again from the routes the session reports
+    ///
+    /// Call this when the platform reports a route change, such as a
+    /// headset connecting or disconnecting. Emits
+    /// [`crate::Event::AudioRouteChanged`] when the route changes.
+    pub async fn refresh_audio_route(&self) -> Result<(), QuicRtcError> {
+        if self.inner.read().await.audio_session.is_none() {
+            return Err(QuicRtcError::InvalidState {
+                expected: "Audio session configured".to_string(),
+                actual: "No audio session".to_string(),
+            });
         }
+        self.update_audio_route().await
+    }
 
-        // Create MoQ track for audio
-        let track_namespace = TrackNamespace {
-            namespace: format!("room.{}", self.id),
-            track_name: format!("{}/microphone", self.participant_id),
+    /// Apply the route the policy picks, if there is an audio session
+    async fn update_audio_route(&self) -> Result<(), QuicRtcError> {
+        let selection = {
+            let mut inner = self.inner.write().await;
+            let Some(session) = inner.audio_session.clone() else {
+                return Ok(());
+            };
+            let selection = inner
+                .audio_route_policy
+                .select(&session.available_routes()?);
+            if inner.audio_route.as_ref() == Some(&selection) {
+                return Ok(());
+            }
+            session.set_route(&selection)?;
+            inner.audio_route = Some(selection.clone());
+            selection
         };
 
-        let moq_track = MoqTrack {
-            namespace: track_namespace.clone(),
-            name: "microphone".to_string(),
-            track_type: quicrtc_core::MoqTrackType::Audio,
+        let name = |route: &Option<crate::AudioRoute>| {
+            route
+                .as_ref()
+                .map_or("none".to_string(), |route| route.name.clone())
         };
+        info!(
+            "🎧 Audio routed from {} to {}",
+            name(&selection.input),
+            name(&selection.output)
+        );
+        self.events.emit(crate::Event::AudioRouteChanged {
+            input: selection.input,
+            output: selection.output,
+        });
+        Ok(())
+    }
 
-        // Announce track
-        moq_transport.announce_track(moq_track.clone()).await?;
+    /// Whether video is paused by [`Room::set_audio_only`]
+    pub async fn is_audio_only(&self) -> bool {
+        self.inner.read().await.audio_only
+    }
 
-        // Store published track info with write lock
-        {
+    /// Switch to audio only to save data, or back to full media
+    ///
+    /// Enabling unsubscribes every remote video track and refuses new video
+    /// subscriptions, pauses camera capture and outgoing video, and
+    /// refreshes statistics less often. The QUIC keep-alive interval is
+    /// fixed when the connection is set up and does not change. Disabling
+    /// restarts the camera and resubscribes the video tracks with the
+    /// options they had; tracks removed in the meantime are skipped.
+    ///
+    /// Emits [`crate::Event::AudioOnlyChanged`].
+    pub async fn set_audio_only(&self, enabled: bool) -> Result<(), QuicRtcError> {
+        if self.inner.read().await.audio_only == enabled {
+            return Ok(());
+        }
+        if enabled {
+            self.enter_audio_only().await?;
+        } else {
+            self.leave_audio_only().await?;
+        }
+
+        info!(
+            "📉 Audio-only mode {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+        self.events.emit(crate::Event::AudioOnlyChanged { enabled });
+        Ok(())
+    }
+
+    /// Drop video subscriptions and pause outgoing video
+    async fn enter_audio_only(&self) -> Result<(), QuicRtcError> {
+        let (video_tracks, subscriptions) = {
             let mut inner = self.inner.write().await;
-            let published_track = PublishedTrack {
-                track_id: track_id.clone(),
-                track_type: TrackType::Audio,
-                moq_track,
-                muted: false,
-                published_at: std::time::Instant::now(),
+            // Set first so no video is subscribed while we unsubscribe
+            inner.audio_only = true;
+            let video_tracks: Vec<crate::RemoteTrack> = inner
+                .participants
+                .iter()
+                .flat_map(|participant| participant.remote_tracks())
+                .filter(|track| {
+                    track.kind() == crate::track::TrackKind::Video && track.is_subscribed()
+                })
+                .cloned()
+                .collect();
+            let subscriptions = inner
+                .moq_transport
+                .as_ref()
+                .map(|transport| transport.subscriptions())
+                .unwrap_or_default();
+            (video_tracks, subscriptions)
+        };
+
+        let mut restore = Vec::new();
+        for track in video_tracks {
+            let options = SubscribeOptions {
+                priority: subscriptions
+                    .get(&track.moq_track().namespace)
+                    .map(|subscription| subscription.priority),
+                #[cfg(feature = "media")]
+                playback: track.playback_config().clone(),
+                dvr_retention: track.dvr_retention(),
             };
-            inner
-                .published_tracks
-                .insert(track_id.clone(), published_track);
+            match self
+                .unsubscribe_track(track.participant_id(), track.id())
+                .await
+            {
+                Ok(()) => restore.push((
+                    track.participant_id().to_string(),
+                    track.id().to_string(),
+                    options,
+                )),
+                Err(e) => warn!("Failed to unsubscribe video track {}: {}", track.id(), e),
+            }
         }
 
-        // Create and return audio track
-        let audio_track = AudioTrack::new(track_id);
+        let mut inner = self.inner.write().await;
+        inner.audio_only_subscriptions = restore;
+        if let Some(local) = inner.local_participant.as_mut() {
+            local.set_video_paused(true);
+        }
 
-        info!("✅ Microphone track published successfully");
-        Ok(audio_track)
+        #[cfg(feature = "media")]
+        {
+            let publishing_camera = inner.local_participant.as_ref().is_some_and(|local| {
+                local
+                    .local_tracks()
+                    .any(|track| track.source() == TrackSource::Camera)
+            });
+            if let (true, Some(video_capture)) = (publishing_camera, inner.video_capture.clone()) {
+                let mut capture_manager = video_capture.lock().await;
+                if capture_manager.is_capturing() {
+                    inner.paused_camera = capture_manager.get_config().cloned();
+                    capture_manager.stop_capture().await.map_err(|e| {
+                        QuicRtcError::MediaProcessing {
+                            reason: format!("Failed to pause video capture: {}", e),
+                        }
+                    })?;
+                }
+            }
+        }
+        Ok(())
     }
 
-    /// Check camera permissions (platform-specific implementation) - REMOVED
-    #[cfg(target_family = "unix")]
-    async fn _check_camera_permissions(&self) -> Result<(), QuicRtcError> {
-        // On Unix systems, camera permissions are typically handled by the system
-        // This is a placeholder for more sophisticated permission checking
-        debug!("🔐 Checking camera permissions");
+    /// Restart outgoing video and restore the dropped video subscriptions
+    async fn leave_audio_only(&self) -> Result<(), QuicRtcError> {
+        let restore = {
+            let mut inner = self.inner.write().await;
+            inner.audio_only = false;
 
-        // For now, we'll attempt to enumerate devices to test permissions
-        // In a real implementation, this might check system permission APIs
-        if let Some(video_capture) = &self.inner.read().await.video_capture {
-            let capture_manager = video_capture.lock().await;
-            match capture_manager.enumerate_devices() {
-                Ok(devices) => {
-                    if devices.is_empty() {
-                        return Err(QuicRtcError::MediaProcessing {
-                            reason: "No camera devices available - check permissions".to_string(),
-                        });
-                    }
-                    debug!(
-                        "✅ Camera permissions verified - {} devices available",
-                        devices.len()
-                    );
-                }
-                Err(e) => {
-                    error!("❌ Camera permission check failed: {}", e);
-                    return Err(QuicRtcError::MediaProcessing {
-                        reason: format!("Camera access denied: {}", e),
-                    });
+            #[cfg(feature = "media")]
+            if let (Some(config), Some(video_capture)) =
+                (inner.paused_camera.take(), inner.video_capture.clone())
+            {
+                let mut capture_manager = video_capture.lock().await;
+                // Another room may have restarted the shared camera already
+                if !capture_manager.is_capturing() {
+                    capture_manager
+                        .start_capture("0", config)
+                        .await
+                        .map_err(|e| QuicRtcError::MediaProcessing {
+                            reason: format!("Failed to resume video capture: {}", e),
+                        })?;
                 }
             }
+
+            if let Some(local) = inner.local_participant.as_mut() {
+                local.set_video_paused(false);
+            }
+            std::mem::take(&mut inner.audio_only_subscriptions)
+        };
+
+        for (participant_id, track_id, options) in restore {
+            if let Err(e) = self
+                .subscribe_track_with_options(&participant_id, &track_id, options)
+                .await
+            {
+                debug!("Not resubscribing video track {}: {}", track_id, e);
+            }
         }
         Ok(())
     }
 
-    /// Check microphone permissions (platform-specific implementation)
-    #[cfg(target_family = "unix")]
-    async fn check_microphone_permissions(&self) -> Result<(), QuicRtcError> {
-        debug!("🔐 Checking microphone permissions");
+    /// Change the delivery priority of a subscribed track (lower is more
+    /// important)
+    ///
+    /// The publisher applies the new priority to the live subscription, so
+    /// there is no gap in the media.
+    pub async fn set_track_priority(
+        &self,
+        participant_id: &str,
+        track_id: &str,
+        priority: u8,
+    ) -> Result<(), QuicRtcError> {
+        self.update_track_subscription(participant_id, track_id, |subscription| {
+            subscription.priority = priority;
+        })
+        .await
+    }
 
-        // Similar to camera, check by attempting to list audio devices
-        if let Some(audio_renderer) = &self.inner.read().await.audio_renderer {
-            let renderer = audio_renderer.lock().await;
-            // Try to check if audio devices are available
-            match renderer.list_devices() {
-                Ok(devices) => {
-                    if devices.is_empty() {
-                        return Err(QuicRtcError::MediaProcessing {
-                            reason: "No audio input devices available - check permissions"
-                                .to_string(),
-                        });
-                    }
-                    debug!(
-                        "✅ Microphone permissions verified - {} devices available",
-                        devices.len()
-                    );
-                }
-                Err(e) => {
-                    // If device enumeration fails, assume it's available for now
-                    debug!(
-                        "⚠️ Could not enumerate audio devices ({}), assuming available",
-                        e
-                    );
-                }
+    /// Limit a subscribed track to a range of groups
+    ///
+    /// `None` for `start_group` means live, and `None` for `end_group`
+    /// means the track keeps going. The subscription stays open while the
+    /// publisher applies the new range.
+    pub async fn set_track_group_range(
+        &self,
+        participant_id: &str,
+        track_id: &str,
+        start_group: Option<u64>,
+        end_group: Option<u64>,
+    ) -> Result<(), QuicRtcError> {
+        if let (Some(start), Some(end)) = (start_group, end_group) {
+            if end < start {
+                return Err(QuicRtcError::InvalidData {
+                    reason: format!("End group {} is before start group {}", end, start),
+                });
             }
         }
+
+        self.update_track_subscription(participant_id, track_id, |subscription| {
+            subscription.start_group = start_group;
+            subscription.end_group = end_group;
+        })
+        .await
+    }
+
+    /// Send a subscription update for a subscribed remote track
+    async fn update_track_subscription(
+        &self,
+        participant_id: &str,
+        track_id: &str,
+        update: impl FnOnce(&mut quicrtc_core::MoqSubscription),
+    ) -> Result<(), QuicRtcError> {
+        let (transport, track) = self
+            .remote_track_for_update(participant_id, track_id)
+            .await?;
+        let namespace = &track.moq_track().namespace;
+        let mut subscription = transport
+            .subscriptions()
+            .remove(namespace)
+            .filter(|_| track.is_subscribed())
+            .ok_or_else(|| QuicRtcError::InvalidState {
+                expected: "Track subscribed".to_string(),
+                actual: "Track not subscribed".to_string(),
+            })?;
+
+        update(&mut subscription);
+        transport
+            .update_subscription(
+                namespace,
+                subscription.priority,
+                subscription.start_group,
+                subscription.end_group,
+            )
+            .await?;
         Ok(())
     }
-}
 
-impl Room {
-    /// Get event stream
-    pub fn events(&self) -> crate::EventStream {
-        // Create event stream that receives events from the room
-        crate::EventStream::from_room(Arc::clone(&self.inner))
+    /// Look up a remote track together with the transport used to (un)subscribe
+    /// The thumbnail track published next to a remote video track
+    async fn thumbnail_rendition(
+        &self,
+        participant_id: &str,
+        track: &crate::RemoteTrack,
+    ) -> Option<crate::RemoteTrack> {
+        let thumbnail_name = format!("{}{}", track.moq_track().name, THUMBNAIL_TRACK_SUFFIX);
+        let inner = self.inner.read().await;
+        let thumbnail = inner
+            .participants
+            .get_remote_participant(participant_id)?
+            .remote_tracks()
+            .find(|candidate| candidate.moq_track().name == thumbnail_name)
+            .cloned();
+        thumbnail
+    }
+
+    async fn remote_track_for_update(
+        &self,
+        participant_id: &str,
+        track_id: &str,
+    ) -> Result<(Arc<MoqOverQuicTransport>, crate::RemoteTrack), QuicRtcError> {
+        let inner = self.inner.read().await;
+        let participant = inner
+            .participants
+            .get_remote_participant(participant_id)
+            .ok_or_else(|| QuicRtcError::ParticipantNotFound {
+                room_id: self.id.clone(),
+                participant_id: participant_id.to_string(),
+            })?;
+        let track =
+            participant
+                .get_remote_track(track_id)
+                .ok_or_else(|| QuicRtcError::TrackNotFound {
+                    track_namespace: format!("{}/{}", participant_id, track_id),
+                })?;
+        let transport = inner
+            .moq_transport
+            .clone()
+            .ok_or_else(|| QuicRtcError::InvalidState {
+                expected: "MoQ transport connected".to_string(),
+                actual: "MoQ transport not available".to_string(),
+            })?;
+
+        Ok((transport, track.clone()))
+    }
+
+    /// Register a transform for encoded frames on a track (insertable streams)
+    ///
+    /// The transform runs on every encoded MoQ object for `track` right before
+    /// it is sent and right after it is received, enabling custom encryption,
+    /// watermarking or metadata injection. Any existing transform for the
+    /// track is replaced.
+    pub async fn set_encoded_frame_transform<F>(
+        &self,
+        track: &TrackNamespace,
+        transform: F,
+    ) -> Result<(), QuicRtcError>
+    where
+        F: Fn(TransformDirection, &mut MoqObject) -> Result<(), QuicRtcError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let inner = self.inner.read().await;
+        let transport = inner
+            .moq_transport
+            .as_ref()
+            .ok_or_else(|| QuicRtcError::InvalidState {
+                expected: "MoQ transport connected".to_string(),
+                actual: "MoQ transport not available".to_string(),
+            })?;
+
+        info!("🔐 Setting encoded frame transform for track: {:?}", track);
+        transport.set_encoded_frame_transform(track.clone(), Arc::new(transform));
+        Ok(())
+    }
+
+    /// Remove the encoded frame transform for a track
+    ///
+    /// Returns `true` if a transform was registered.
+    pub async fn clear_encoded_frame_transform(
+        &self,
+        track: &TrackNamespace,
+    ) -> Result<bool, QuicRtcError> {
+        let inner = self.inner.read().await;
+        let transport = inner
+            .moq_transport
+            .as_ref()
+            .ok_or_else(|| QuicRtcError::InvalidState {
+                expected: "MoQ transport connected".to_string(),
+                actual: "MoQ transport not available".to_string(),
+            })?;
+
+        Ok(transport.clear_encoded_frame_transform(track))
+    }
+
+    /// Attach integrity hashes to the objects this room sends and drop
+    /// received objects whose hash doesn't match, or stop with `None`
+    ///
+    /// Guards media against relays that aren't fully trusted, without
+    /// end-to-end encryption; see [`ObjectIntegrity`] for keyed and
+    /// unkeyed hashes.
+    pub async fn set_object_integrity(
+        &self,
+        integrity: Option<ObjectIntegrity>,
+    ) -> Result<(), QuicRtcError> {
+        let inner = self.inner.read().await;
+        let transport = inner
+            .moq_transport
+            .as_ref()
+            .ok_or_else(|| QuicRtcError::InvalidState {
+                expected: "MoQ transport connected".to_string(),
+                actual: "MoQ transport not available".to_string(),
+            })?;
+
+        transport.set_object_integrity(integrity);
+        Ok(())
+    }
+
+    /// Integrity counters of the objects received, all zero while integrity
+    /// hashes are off
+    pub async fn integrity_stats(&self) -> IntegrityStats {
+        let inner = self.inner.read().await;
+        inner
+            .moq_transport
+            .as_ref()
+            .map(|transport| transport.integrity_stats())
+            .unwrap_or_default()
+    }
+
+    /// Leave the room
+    ///
+    /// Stops background work, tells the signaling server we left and closes
+    /// the media connection unless another room shares it. Rooms built with
+    /// [`RoomBuilder::call_report`] return a [`crate::CallReport`] of the
+    /// call, also written to the configured path if there is one; failing to
+    /// write it is logged, not returned.
+    pub async fn leave(self) -> Result<Option<crate::CallReport>, QuicRtcError> {
+        info!("👋 Leaving room '{}'", self.id);
+        let mut inner = self.inner.write().await;
+        inner.state = RoomState::Disconnecting;
+
+        if let (Some(recorder), Some(transport)) = (&self.call_recorder, &inner.moq_transport) {
+            recorder.record_transport_mode(transport.transport_mode());
+        }
+
+        inner
+            .take_connections()
+            .close(&self.id, &self.participant_id)
+            .await;
+
+        inner.state = RoomState::Disconnected;
+        self.events.emit(crate::Event::RoomConnectionChanged {
+            state: RoomState::Disconnected,
+        });
+
+        let report = self.call_recorder.as_ref().map(|recorder| {
+            let mut report = recorder.finish(&self.id, &self.participant_id, inner.track_reports());
+            if let Some(history) = &self.stats_history {
+                report.stats_history = history.samples();
+            }
+            report
+        });
+
+        #[cfg(feature = "diagnostics")]
+        let report = report.map(|mut report| {
+            if let (false, Some(ring)) = (report.errors.is_empty(), &self.debug_log) {
+                report.debug_logs = ring.records().iter().map(ToString::to_string).collect();
+            }
+            report
+        });
+
+        drop(inner);
+
+        if let (Some(report), Some(path)) = (&report, &self.config.call_report_path) {
+            match report.save(path) {
+                Ok(()) => info!("📝 Call report written to {}", path.display()),
+                Err(e) => warn!("Failed to write call report: {}", e),
+            }
+        }
+
+        info!("✅ Left room '{}'", self.id);
+        Ok(report)
     }
 }
 
@@ -1180,7 +5285,7 @@ impl Room {
 mod tests {
     use super::*;
     #[cfg(feature = "media")]
-    use crate::{AudioProcessingConfig, VideoProcessingConfig, VideoQuality};
+    use crate::{AudioContentHint, AudioProcessingConfig, VideoProcessingConfig, VideoQuality};
     #[cfg(feature = "signaling")]
     use crate::{ReconnectConfig, SignalingConfig};
     use std::time::Duration;
@@ -1297,6 +5402,53 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "media")]
+    #[tokio::test]
+    async fn test_room_builder_audio_profile() {
+        let quic_rtc = test_quic_rtc().await;
+        let builder = quic_rtc
+            .room("test-room")
+            .participant("alice")
+            .audio_profile(AudioProfile::Raw);
+
+        assert!(builder.validate().is_ok());
+        assert!(builder.config.audio_enabled);
+        let audio_config = builder.audio_config.unwrap();
+        assert_eq!(audio_config.profile, Some(AudioProfile::Raw));
+        assert!(!audio_config.enable_echo_cancellation);
+        assert!(!audio_config.enable_noise_suppression);
+        assert!(!audio_config.enable_auto_gain_control);
+
+        let speech = AudioProfile::Speech.opus_config();
+        assert_eq!((speech.sample_rate, speech.channels), (16000, 1));
+        assert_eq!(
+            AudioProfile::Music.opus_config().application,
+            quicrtc_media::OpusApplication::Audio
+        );
+        assert_eq!(
+            AudioProfile::Speech.content_hint(),
+            AudioContentHint::Speech
+        );
+
+        // High-fidelity music sends everything the codec can carry, as captured
+        let builder = quic_rtc
+            .room("test-room")
+            .participant("alice")
+            .audio_profile(AudioProfile::HighFidelityMusic);
+        let audio_config = builder.audio_config.unwrap();
+        assert!(!audio_config.enable_echo_cancellation);
+        assert!(!audio_config.enable_noise_suppression);
+        assert!(!audio_config.enable_auto_gain_control);
+        let music = AudioProfile::HighFidelityMusic.opus_config();
+        assert_eq!((music.sample_rate, music.channels), (48000, 2));
+        assert_eq!(music.bitrate, 256_000);
+        assert_eq!(
+            AudioProfile::HighFidelityMusic.content_hint(),
+            AudioContentHint::Music
+        );
+        assert!(AudioProfile::HighFidelityMusic.playback_config().is_some());
+    }
+
     #[cfg(feature = "media")]
     #[tokio::test]
     async fn test_room_builder_video_resolution() {
@@ -1438,10 +5590,45 @@ mod tests {
             }
         }
 
-        if let Some(ref limits) = builder.resource_limits {
-            assert_eq!(limits.max_bandwidth_kbps, Some(500));
-            assert_eq!(limits.max_connections, Some(2));
-        }
+        if let Some(ref limits) = builder.resource_limits {
+            assert_eq!(limits.max_bandwidth_kbps, Some(500));
+            assert_eq!(limits.max_connections, Some(2));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_room_builder_data_saver_preset() {
+        let quic_rtc = test_quic_rtc().await;
+        let builder = quic_rtc
+            .room("test-room")
+            .participant("alice")
+            .metered_connection(true);
+
+        assert!(builder.validate().is_ok());
+        assert!(builder.config.data_saver);
+        #[cfg(feature = "media")]
+        assert_eq!(builder.config.video_quality, VideoQuality::Low);
+        assert_eq!(
+            builder.resource_limits.as_ref().unwrap().max_bandwidth_kbps,
+            Some(DATA_SAVER_BANDWIDTH_KBPS)
+        );
+
+        // An explicit lower limit is kept
+        let builder = quic_rtc
+            .room("test-room")
+            .participant("alice")
+            .bandwidth_limit(100)
+            .data_saver();
+        assert_eq!(
+            builder.resource_limits.as_ref().unwrap().max_bandwidth_kbps,
+            Some(100)
+        );
+
+        let builder = quic_rtc
+            .room("test-room")
+            .participant("alice")
+            .metered_connection(false);
+        assert!(!builder.config.data_saver);
     }
 
     #[tokio::test]
@@ -1554,6 +5741,155 @@ mod tests {
         assert!(!builder.config.audio_enabled);
     }
 
+    #[tokio::test]
+    async fn test_room_join_cancelled() {
+        let quic_rtc = test_quic_rtc().await;
+        let token = CancellationToken::new();
+        token.cancel();
+
+        // A cancelled join gives the participant slot back, so it can be
+        // joined again
+        for _ in 0..2 {
+            let result = quic_rtc
+                .room("test-room")
+                .participant("alice")
+                .cancellation_token(token.clone())
+                .join()
+                .await;
+            assert!(matches!(result, Err(QuicRtcError::Cancelled { .. })));
+        }
+    }
+
+    #[cfg(feature = "signaling")]
+    #[tokio::test]
+    async fn test_room_observable_while_connecting() {
+        // A signaling server that accepts connections but never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                connections.push(stream);
+            }
+        });
+
+        let quic_rtc = test_quic_rtc().await;
+        let join = tokio::spawn({
+            let quic_rtc = quic_rtc.clone();
+            async move {
+                quic_rtc
+                    .room("slow-room")
+                    .participant("alice")
+                    .signaling_server(&format!("ws://{}", address))
+                    .join()
+                    .await
+            }
+        });
+
+        // The room can be read while signaling is still connecting
+        let observed = tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                let room = quic_rtc
+                    .inner
+                    .rooms
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .find_map(|(_, room)| room.upgrade());
+                if let Some(room) = room {
+                    if room.read().await.state == RoomState::Connecting {
+                        break;
+                    }
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await;
+        assert!(observed.is_ok(), "room stayed locked while connecting");
+
+        join.abort();
+        server.abort();
+    }
+
+    #[cfg(feature = "signaling")]
+    #[tokio::test]
+    async fn test_room_join_retries_transient_failures() {
+        // Nothing listens on the port, so every signaling connect fails
+        let address = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let quic_rtc = test_quic_rtc().await;
+        let builder = quic_rtc
+            .room("retry-room")
+            .participant("alice")
+            .signaling_server(&format!("ws://{}", address))
+            .reconnect_config(ReconnectConfig {
+                enabled: true,
+                initial_delay: Duration::from_millis(10),
+                max_delay: Duration::from_millis(20),
+                backoff_multiplier: 2.0,
+                max_attempts: 2,
+            });
+        let mut events = builder.events();
+        let result = builder.join().await;
+        assert!(matches!(result, Err(QuicRtcError::Connection { .. })));
+
+        // Each failed attempt but the last is reported with its backoff
+        let attempts: Vec<_> = std::iter::from_fn(|| events.try_next().ok().flatten())
+            .filter_map(|event| match event {
+                crate::Event::JoinAttemptFailed {
+                    attempt, retry_in, ..
+                } => Some((attempt, retry_in)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(attempts.len(), 2);
+        for (expected, (attempt, retry_in)) in (1..).zip(attempts) {
+            // Backoff doubles from 10ms, less up to half of it as jitter
+            let backoff = Duration::from_millis(10) * 2u32.pow(attempt - 1);
+            assert_eq!(attempt, expected);
+            assert!(retry_in <= backoff && retry_in >= backoff / 2);
+        }
+    }
+
+    #[cfg(feature = "signaling")]
+    #[tokio::test]
+    async fn test_room_join_timeout() {
+        // A signaling server that accepts connections but never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                connections.push(stream);
+            }
+        });
+
+        let quic_rtc = test_quic_rtc().await;
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            quic_rtc
+                .room("silent-room")
+                .participant("alice")
+                .signaling_server(&format!("ws://{}", address))
+                .join_timeout(Duration::from_millis(100))
+                .join(),
+        )
+        .await
+        .expect("join outlived its timeout");
+        match result {
+            Err(QuicRtcError::Timeout { duration, .. }) => {
+                assert_eq!(duration, Duration::from_millis(100))
+            }
+            other => panic!("Expected join timeout, got {:?}", other.map(|room| room.id)),
+        }
+
+        server.abort();
+    }
+
     #[tokio::test]
     async fn test_room_quick_join() {
         // Test the Room::quick_join convenience method
@@ -1589,4 +5925,872 @@ mod tests {
             assert_eq!(limits.max_bandwidth_kbps, Some(2000));
         }
     }
+
+    /// QUIC stack stand-in that accepts the MoQ session and discards media
+    #[derive(Debug)]
+    struct StubDriver {
+        connection_id: uuid::Uuid,
+        next_stream_id: std::sync::atomic::AtomicU64,
+        /// Answer announcements and subscriptions like a relay would
+        answer_requests: bool,
+        /// What the peer sends, if the test delivers anything
+        events: std::sync::Mutex<
+            Option<tokio::sync::mpsc::UnboundedReceiver<quicrtc_core::DriverEvent>>,
+        >,
+    }
+
+    impl StubDriver {
+        fn new() -> Self {
+            Self {
+                connection_id: uuid::Uuid::new_v4(),
+                next_stream_id: std::sync::atomic::AtomicU64::new(0),
+                answer_requests: false,
+                events: std::sync::Mutex::new(None),
+            }
+        }
+
+        /// Stand-in for a relay
+        fn relay() -> Self {
+            Self {
+                answer_requests: true,
+                ..Self::new()
+            }
+        }
+
+        /// Stand-in for a relay, with the sender of what it delivers
+        fn delivering_relay() -> (
+            Self,
+            tokio::sync::mpsc::UnboundedSender<quicrtc_core::DriverEvent>,
+        ) {
+            let (event_tx, event_rx) = tokio::sync::mpsc::unbounded_channel();
+            let driver = Self {
+                events: std::sync::Mutex::new(Some(event_rx)),
+                ..Self::relay()
+            };
+            (driver, event_tx)
+        }
+    }
+
+    #[derive(Debug)]
+    struct StubStream {
+        id: u64,
+        stream_type: quicrtc_core::transport::StreamType,
+        /// Reply to the session setup, sent on the control stream
+        setup_ok: Option<bytes::Bytes>,
+        /// Sequence number of the next answer and the answers not yet
+        /// read, if this control stream answers requests
+        answers: Option<(u64, std::collections::VecDeque<bytes::Bytes>)>,
+    }
+
+    #[async_trait::async_trait]
+    impl quicrtc_core::DriverStream for StubStream {
+        fn id(&self) -> u64 {
+            self.id
+        }
+
+        fn stream_type(&self) -> quicrtc_core::transport::StreamType {
+            self.stream_type
+        }
+
+        async fn send(&mut self, data: &[u8]) -> Result<(), QuicRtcError> {
+            let Some((sequence, answers)) = self.answers.as_mut() else {
+                return Ok(());
+            };
+            let (_, request) = quicrtc_core::MoqWireFormat::decode_sequenced_control_message(data)?;
+            let answer = match request {
+                quicrtc_core::MoqControlMessage::Announce {
+                    track_namespace, ..
+                } => quicrtc_core::MoqControlMessage::AnnounceOk { track_namespace },
+                quicrtc_core::MoqControlMessage::Subscribe {
+                    track_namespace, ..
+                } => quicrtc_core::MoqControlMessage::SubscribeOk { track_namespace },
+                _ => return Ok(()),
+            };
+            let mut buffer = bytes::BytesMut::new();
+            quicrtc_core::MoqWireFormat::encode_sequenced_control_message(
+                *sequence,
+                &answer,
+                &mut buffer,
+            )?;
+            *sequence += 1;
+            answers.push_back(buffer.freeze());
+            Ok(())
+        }
+
+        async fn recv(&mut self) -> Result<Option<bytes::Bytes>, QuicRtcError> {
+            if let Some(setup_ok) = self.setup_ok.take() {
+                return Ok(Some(setup_ok));
+            }
+            match self.answers.as_mut() {
+                Some((_, answers)) => match answers.pop_front() {
+                    Some(answer) => Ok(Some(answer)),
+                    // Requests are answered as they are sent
+                    None => std::future::pending().await,
+                },
+                None => Ok(None),
+            }
+        }
+
+        async fn finish(&mut self) -> Result<(), QuicRtcError> {
+            Ok(())
+        }
+
+        async fn reset(&mut self, _error_code: u32) -> Result<(), QuicRtcError> {
+            Ok(())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl quicrtc_core::MoqTransportDriver for StubDriver {
+        async fn open_stream(
+            &self,
+            stream_type: quicrtc_core::transport::StreamType,
+        ) -> Result<Box<dyn quicrtc_core::DriverStream>, QuicRtcError> {
+            let id = self
+                .next_stream_id
+                .fetch_add(4, std::sync::atomic::Ordering::Relaxed);
+            let mut setup_ok = bytes::BytesMut::new();
+            quicrtc_core::MoqWireFormat::encode_sequenced_control_message(
+                0,
+                &quicrtc_core::MoqControlMessage::SetupOk {
+                    version: 1,
+                    capabilities: Default::default(),
+                },
+                &mut setup_ok,
+            )
+            .unwrap();
+            Ok(Box::new(StubStream {
+                id,
+                stream_type,
+                setup_ok: (id == 0).then(|| setup_ok.freeze()),
+                answers: (id == 0 && self.answer_requests)
+                    .then(|| (1, std::collections::VecDeque::new())),
+            }))
+        }
+
+        async fn send_datagram(&self, _data: bytes::Bytes) -> Result<(), QuicRtcError> {
+            Ok(())
+        }
+
+        fn take_events(
+            &self,
+        ) -> Option<tokio::sync::mpsc::UnboundedReceiver<quicrtc_core::DriverEvent>> {
+            self.events.lock().unwrap().take()
+        }
+
+        fn connection_id(&self) -> uuid::Uuid {
+            self.connection_id
+        }
+
+        fn is_connected(&self) -> bool {
+            true
+        }
+
+        async fn close(&self) -> Result<(), QuicRtcError> {
+            Ok(())
+        }
+    }
+
+    /// Have rooms joined through `quic_rtc` run over a transport on `driver`
+    async fn use_stub_transport(
+        quic_rtc: &QuicRtc,
+        driver: StubDriver,
+    ) -> Arc<MoqOverQuicTransport> {
+        let transport = Arc::new(MoqOverQuicTransport::with_driver(Arc::new(driver), 1));
+        transport.establish_session().await.unwrap();
+        quic_rtc
+            .use_transport(DEFAULT_MEDIA_ENDPOINT, &transport)
+            .await;
+        transport
+    }
+
+    /// Wait for the first event `select` picks out
+    async fn wait_for_event<T>(
+        events: &mut crate::EventStream,
+        mut select: impl FnMut(crate::Event) -> Option<T>,
+    ) -> T {
+        let wait = async {
+            while let Some(event) = events.next().await {
+                if let Some(value) = select(event) {
+                    return value;
+                }
+            }
+            panic!("Event stream ended");
+        };
+        tokio::time::timeout(Duration::from_secs(5), wait)
+            .await
+            .expect("Expected event did not arrive")
+    }
+
+    #[tokio::test]
+    async fn test_multiple_rooms_share_one_transport() {
+        let quic_rtc = test_quic_rtc().await;
+        let transport = Arc::new(MoqOverQuicTransport::with_driver(
+            Arc::new(StubDriver::new()),
+            1,
+        ));
+        transport.establish_session().await.unwrap();
+        quic_rtc
+            .use_transport(DEFAULT_MEDIA_ENDPOINT, &transport)
+            .await;
+
+        let (standup, design, review) = tokio::join!(
+            quic_rtc.room("standup").participant("alice").join(),
+            quic_rtc.room("design").participant("alice").join(),
+            quic_rtc.room("review").participant("alice").join(),
+        );
+        let (standup, design, review) = (standup.unwrap(), design.unwrap(), review.unwrap());
+
+        let mut joined: Vec<_> = quic_rtc
+            .rooms()
+            .into_iter()
+            .map(|room| room.room_id)
+            .collect();
+        joined.sort();
+        assert_eq!(joined, vec!["design", "review", "standup"]);
+
+        // Every room runs over the same connection
+        for room in [&standup, &design, &review] {
+            let inner = room.inner.read().await;
+            assert!(Arc::ptr_eq(
+                inner.moq_transport.as_ref().unwrap(),
+                &transport
+            ));
+        }
+
+        // The same participant can't join a room twice
+        let duplicate = quic_rtc.room("design").participant("alice").join().await;
+        assert!(matches!(
+            duplicate,
+            Err(QuicRtcError::ParticipantAlreadyExists { .. })
+        ));
+
+        // Dropped rooms leave the registry
+        drop(design);
+        assert_eq!(quic_rtc.rooms().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_room_join_progress() {
+        let quic_rtc = test_quic_rtc().await;
+        let transport = Arc::new(MoqOverQuicTransport::with_driver(
+            Arc::new(StubDriver::new()),
+            1,
+        ));
+        transport.establish_session().await.unwrap();
+        quic_rtc
+            .use_transport(DEFAULT_MEDIA_ENDPOINT, &transport)
+            .await;
+
+        let builder = quic_rtc
+            .room("progress")
+            .participant("alice")
+            .join_timeout(Duration::from_secs(5));
+        let mut events = builder.events();
+        let _room = builder.join().await.unwrap();
+
+        let stages: Vec<_> = std::iter::from_fn(|| events.try_next().ok().flatten())
+            .filter_map(|event| match event {
+                crate::Event::JoinProgress { stage } => Some(stage),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            stages,
+            vec![JoinStage::TransportEstablished, JoinStage::SessionActive]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prewarmed_transport_handed_to_room() {
+        for url in [
+            "127.0.0.1:7878",
+            "moq://127.0.0.1:7878",
+            "https://127.0.0.1:7878/moq",
+        ] {
+            assert_eq!(
+                crate::resolve_relay(url).await.unwrap(),
+                DEFAULT_MEDIA_ENDPOINT
+            );
+        }
+
+        // Stand in for the connection QuicRtc::prewarm would have set up
+        let quic_rtc = test_quic_rtc().await;
+        let transport = Arc::new(MoqOverQuicTransport::with_driver(
+            Arc::new(StubDriver::new()),
+            1,
+        ));
+        transport.establish_session().await.unwrap();
+        quic_rtc
+            .use_transport(DEFAULT_MEDIA_ENDPOINT, &transport)
+            .await;
+        quic_rtc
+            .inner
+            .prewarmed
+            .lock()
+            .unwrap()
+            .insert(DEFAULT_MEDIA_ENDPOINT, transport);
+
+        // The room takes the connection over instead of opening its own
+        let room = quic_rtc
+            .room("warm")
+            .participant("alice")
+            .join()
+            .await
+            .unwrap();
+        assert!(quic_rtc.inner.prewarmed.lock().unwrap().is_empty());
+        assert!(room.inner.read().await.moq_transport.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_room_audio_only_mode() {
+        let quic_rtc = test_quic_rtc().await;
+        let transport = Arc::new(MoqOverQuicTransport::with_driver(
+            Arc::new(StubDriver::new()),
+            1,
+        ));
+        transport.establish_session().await.unwrap();
+        quic_rtc
+            .use_transport(DEFAULT_MEDIA_ENDPOINT, &transport)
+            .await;
+
+        let room = quic_rtc
+            .room("commute")
+            .participant("alice")
+            .join()
+            .await
+            .unwrap();
+        let mut events = room.events();
+        assert!(!room.is_audio_only().await);
+
+        room.set_audio_only(true).await.unwrap();
+        assert!(room.is_audio_only().await);
+        // Asking again changes nothing
+        room.set_audio_only(true).await.unwrap();
+
+        room.set_audio_only(false).await.unwrap();
+        assert!(!room.is_audio_only().await);
+
+        let mut changes = Vec::new();
+        while changes.len() < 2 {
+            match tokio::time::timeout(Duration::from_secs(1), events.next()).await {
+                Ok(Some(crate::Event::AudioOnlyChanged { enabled })) => changes.push(enabled),
+                Ok(Some(_)) => {}
+                _ => break,
+            }
+        }
+        assert_eq!(changes, vec![true, false]);
+    }
+
+    #[cfg(feature = "signaling")]
+    #[tokio::test]
+    async fn test_offer_from_unknown_participant_adds_their_tracks() {
+        let quic_rtc = test_quic_rtc().await;
+        let _transport = use_stub_transport(&quic_rtc, StubDriver::new()).await;
+
+        let room = quic_rtc
+            .room("standup")
+            .participant("alice")
+            .join()
+            .await
+            .unwrap();
+        let mut events = room.events();
+        assert_eq!(room.inner.read().await.participants.count(), 0);
+
+        let offered = vec![
+            OfferedTrack {
+                namespace: "room.standup/bob/camera".to_string(),
+                label: Some("Front camera".to_string()),
+                source: "camera".to_string(),
+                width: Some(1280),
+                height: Some(720),
+                codec: Some("h264".to_string()),
+            },
+            OfferedTrack {
+                namespace: "room.standup/bob/microphone".to_string(),
+                label: None,
+                source: "microphone".to_string(),
+                width: None,
+                height: None,
+                codec: Some("opus".to_string()),
+            },
+            // Someone else's track can't be offered on their behalf
+            OfferedTrack {
+                namespace: "room.standup/carol/camera".to_string(),
+                label: None,
+                source: "camera".to_string(),
+                width: None,
+                height: None,
+                codec: None,
+            },
+        ];
+        room.inner
+            .write()
+            .await
+            .apply_published_tracks("bob", &offered)
+            .await;
+
+        let inner = room.inner.read().await;
+        let bob = inner.participants.get_remote_participant("bob").unwrap();
+        let tracks = bob.tracks();
+        assert_eq!(tracks.len(), 2);
+        let camera = bob.get_remote_track("camera").unwrap();
+        assert_eq!(camera.kind(), crate::track::TrackKind::Video);
+        assert_eq!(camera.source(), crate::track::TrackSource::Camera);
+        assert_eq!(
+            camera.moq_track().namespace.canonical(),
+            "room.standup/bob/camera"
+        );
+        assert_eq!(camera.label(), Some("Front camera"));
+        assert_eq!(camera.metadata().dimensions, Some((1280, 720)));
+        // Nothing is received until asked for
+        assert!(!camera.is_subscribed());
+        let microphone = bob.get_remote_track("microphone").unwrap();
+        assert_eq!(microphone.kind(), crate::track::TrackKind::Audio);
+        assert!(inner.participants.get_remote_participant("carol").is_none());
+        drop(inner);
+
+        let mut joined = false;
+        let mut received = Vec::new();
+        while let Ok(Some(event)) = events.try_next() {
+            match event {
+                crate::Event::ParticipantJoined { participant } => {
+                    joined = participant.id() == "bob";
+                }
+                crate::Event::TrackReceived { track } => received.push(track.id().to_string()),
+                _ => {}
+            }
+        }
+        assert!(joined);
+        received.sort();
+        assert_eq!(received, vec!["camera", "microphone"]);
+    }
+
+    /// Serve signaling on a free local port, returning its URL
+    #[cfg(feature = "signaling")]
+    async fn start_signaling_server() -> String {
+        serve_signaling(std::convert::identity).await
+    }
+
+    /// Serve signaling with a server set up by `configure`, returning its URL
+    #[cfg(feature = "signaling")]
+    async fn serve_signaling(
+        configure: impl FnOnce(quicrtc_signaling::SignalingServer) -> quicrtc_signaling::SignalingServer,
+    ) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = configure(quicrtc_signaling::SignalingServer::new(addr));
+        tokio::spawn(async move { server.serve(listener).await });
+        format!("ws://{}", addr)
+    }
+
+    /// Join `room_id` through the signaling server at `url`, publishing a
+    /// camera and microphone over a stub relay on `driver`
+    #[cfg(feature = "signaling")]
+    async fn join_signaled(
+        room_id: &str,
+        participant_id: &str,
+        url: &str,
+        driver: StubDriver,
+    ) -> (QuicRtc, Room, crate::EventStream) {
+        #[cfg(feature = "media")]
+        {
+            use quicrtc_media::virtual_device::{self, VirtualCamera, VirtualMicrophone};
+            virtual_device::register_camera(VirtualCamera::test_pattern(
+                "test-camera",
+                quicrtc_media::VideoResolution::HD,
+            ));
+            virtual_device::register_microphone(VirtualMicrophone::sine("test-microphone", 440.0));
+            virtual_device::register_speaker("test-speaker");
+        }
+
+        let quic_rtc = test_quic_rtc().await;
+        let _transport = use_stub_transport(&quic_rtc, driver).await;
+        let builder = quic_rtc
+            .room(room_id)
+            .participant(participant_id)
+            .enable_video()
+            .enable_audio()
+            .stats_interval(Duration::from_millis(20))
+            .signaling_server(url);
+        let events = builder.events();
+        let room = builder.join().await.unwrap();
+        (quic_rtc, room, events)
+    }
+
+    #[cfg(feature = "signaling")]
+    #[tokio::test]
+    async fn test_join_presents_access_token() {
+        use quicrtc_signaling::{AccessToken, TokenVerifier};
+
+        let url = serve_signaling(|server| {
+            server.with_token_verifier(TokenVerifier::new().with_key("api-key", "api-secret"))
+        })
+        .await;
+        let quic_rtc = test_quic_rtc().await;
+        let _transport = use_stub_transport(&quic_rtc, StubDriver::relay()).await;
+
+        // The server turns away participants without a token, for good
+        let refused = quic_rtc
+            .room("standup")
+            .participant("alice")
+            .signaling_server(&url)
+            .join()
+            .await;
+        assert!(matches!(refused, Err(QuicRtcError::Unauthorized { .. })));
+
+        let token = AccessToken::new("api-key", "api-secret")
+            .identity("alice")
+            .room("standup")
+            .room_create(true)
+            .to_jwt()
+            .unwrap();
+        let room = quic_rtc
+            .room("standup")
+            .participant("alice")
+            .signaling_server(&url)
+            .access_token(&token)
+            .join()
+            .await
+            .unwrap();
+        assert_eq!(room.inner.read().await.state, RoomState::Connected);
+    }
+
+    #[cfg(feature = "signaling")]
+    #[tokio::test]
+    async fn test_rooms_learn_each_others_tracks_through_signaling() {
+        let url = start_signaling_server().await;
+
+        let (_alice_rtc, alice, mut alice_events) =
+            join_signaled("standup", "alice", &url, StubDriver::relay()).await;
+        let (_bob_rtc, bob, mut bob_events) =
+            join_signaled("standup", "bob", &url, StubDriver::relay()).await;
+
+        // Bob finds Alice already in the room and learns her tracks
+        wait_for_event(&mut bob_events, |event| match event {
+            crate::Event::ParticipantJoined { participant } if participant.id() == "alice" => {
+                Some(())
+            }
+            _ => None,
+        })
+        .await;
+        let mut received = Vec::new();
+        while received.len() < 2 {
+            let track = wait_for_event(&mut bob_events, |event| match event {
+                crate::Event::TrackReceived { track } => Some(track),
+                _ => None,
+            })
+            .await;
+            assert_eq!(track.participant_id(), "alice");
+            received.push(track.id().to_string());
+        }
+        received.sort();
+        assert_eq!(received, vec!["camera", "microphone"]);
+
+        // Alice sees Bob arrive and learns his tracks too
+        wait_for_event(&mut alice_events, |event| match event {
+            crate::Event::ParticipantJoined { participant } if participant.id() == "bob" => {
+                Some(())
+            }
+            _ => None,
+        })
+        .await;
+        for _ in 0..2 {
+            wait_for_event(&mut alice_events, |event| match event {
+                crate::Event::TrackReceived { track } if track.participant_id() == "bob" => {
+                    Some(())
+                }
+                _ => None,
+            })
+            .await;
+        }
+        assert_eq!(alice.remote_tracks("bob").await.unwrap().len(), 2);
+
+        // Bob can subscribe to what he learned of and drop it again
+        bob.subscribe_track("alice", "microphone").await.unwrap();
+        let subscribed = wait_for_event(&mut bob_events, |event| match event {
+            crate::Event::TrackSubscribed {
+                track_id,
+                participant_id,
+            } => Some((track_id, participant_id)),
+            _ => None,
+        })
+        .await;
+        assert_eq!(subscribed, ("microphone".to_string(), "alice".to_string()));
+        let tracks = bob.remote_tracks("alice").await.unwrap();
+        let microphone = tracks.iter().find(|t| t.track_id == "microphone").unwrap();
+        assert!(microphone.subscribed);
+
+        bob.unsubscribe_track("alice", "microphone").await.unwrap();
+        let unsubscribed = wait_for_event(&mut bob_events, |event| match event {
+            crate::Event::TrackUnsubscribed { track_id, .. } => Some(track_id),
+            _ => None,
+        })
+        .await;
+        assert_eq!(unsubscribed, "microphone");
+
+        // Alice leaving takes her and her tracks out of Bob's room
+        alice.leave().await.unwrap();
+        let left = wait_for_event(&mut bob_events, |event| match event {
+            crate::Event::ParticipantLeft { participant } => Some(participant),
+            _ => None,
+        })
+        .await;
+        assert_eq!(left.id(), "alice");
+        assert!(bob.remote_participant("alice").await.is_none());
+        assert!(bob.remote_tracks("alice").await.is_none());
+    }
+
+    #[cfg(feature = "signaling")]
+    #[tokio::test]
+    async fn test_participant_watchers_follow_signaling() {
+        let url = start_signaling_server().await;
+
+        let (_alice_rtc, alice, _) =
+            join_signaled("design", "alice", &url, StubDriver::relay()).await;
+        let mut participants = alice.watch_participants().await;
+        assert!(participants.borrow_and_update().participant_ids.is_empty());
+
+        let (_bob_rtc, bob, _) = join_signaled("design", "bob", &url, StubDriver::relay()).await;
+        let joined = tokio::time::timeout(
+            Duration::from_secs(5),
+            participants.wait_for(|snapshot| snapshot.participant_ids == ["bob"]),
+        )
+        .await;
+        assert!(joined.is_ok_and(|changed| changed.is_ok()));
+        let bob_seen = alice.remote_participant("bob").await.unwrap();
+        assert_eq!(
+            bob_seen.connection_state(),
+            crate::participant::ParticipantConnectionState::Connected
+        );
+
+        bob.leave().await.unwrap();
+        let left = tokio::time::timeout(
+            Duration::from_secs(5),
+            participants.wait_for(|snapshot| snapshot.participant_ids.is_empty()),
+        )
+        .await;
+        assert!(left.is_ok_and(|changed| changed.is_ok()));
+    }
+
+    /// Namespace `room` knows a remote participant's track by
+    #[cfg(feature = "signaling")]
+    async fn remote_namespace(room: &Room, participant_id: &str, track_id: &str) -> TrackNamespace {
+        room.remote_participant(participant_id)
+            .await
+            .unwrap()
+            .get_remote_track(track_id)
+            .unwrap()
+            .moq_track()
+            .namespace
+            .clone()
+    }
+
+    /// An object of `namespace` as a relay delivers it, named only by the
+    /// track's alias
+    #[cfg(feature = "signaling")]
+    fn relayed_object(
+        namespace: &TrackNamespace,
+        group_id: u64,
+        object_id: u64,
+        object_status: quicrtc_core::MoqObjectStatus,
+        payload: Vec<u8>,
+    ) -> quicrtc_core::DriverEvent {
+        let object = quicrtc_core::MoqObject {
+            track_namespace: namespace.clone(),
+            track_name: namespace.track_name.clone(),
+            group_id,
+            object_id,
+            publisher_priority: 1,
+            size: payload.len(),
+            payload,
+            object_status,
+            created_at: std::time::Instant::now(),
+            metadata: Default::default(),
+        };
+        let mut datagram = bytes::BytesMut::new();
+        quicrtc_core::MoqWireFormat::encode_object_datagram(
+            &object,
+            namespace.alias(),
+            &mut datagram,
+        )
+        .unwrap();
+        quicrtc_core::DriverEvent::DatagramReceived(datagram.freeze())
+    }
+
+    #[cfg(feature = "signaling")]
+    #[tokio::test]
+    async fn test_remote_track_stats_count_received_objects() {
+        let url = start_signaling_server().await;
+        let (_alice_rtc, _alice, _) =
+            join_signaled("review", "alice", &url, StubDriver::relay()).await;
+        let (driver, relay) = StubDriver::delivering_relay();
+        let (_bob_rtc, bob, mut bob_events) = join_signaled("review", "bob", &url, driver).await;
+        wait_for_event(&mut bob_events, |event| match event {
+            crate::Event::TrackReceived { track } if track.id() == "microphone" => Some(()),
+            _ => None,
+        })
+        .await;
+        bob.subscribe_track("alice", "microphone").await.unwrap();
+
+        let namespace = remote_namespace(&bob, "alice", "microphone").await;
+        for object_id in 0..5 {
+            let object = relayed_object(
+                &namespace,
+                0,
+                object_id,
+                quicrtc_core::MoqObjectStatus::Normal,
+                vec![0; 100],
+            );
+            relay.send(object).unwrap();
+        }
+
+        let counted = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                // Bob publishes a microphone too, so look on Alice's side
+                let alice = bob.remote_participant("alice").await.unwrap();
+                let stats = alice
+                    .get_remote_track("microphone")
+                    .unwrap()
+                    .stats()
+                    .clone();
+                if stats.objects_received == 5 {
+                    break stats;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Received objects were not counted");
+        assert_eq!(counted.bytes_transferred, 500);
+    }
+
+    #[cfg(feature = "signaling")]
+    #[tokio::test]
+    async fn test_time_shift_of_track_learned_through_signaling() {
+        let url = start_signaling_server().await;
+        let (_alice_rtc, _alice, _) =
+            join_signaled("replay", "alice", &url, StubDriver::relay()).await;
+        let (driver, relay) = StubDriver::delivering_relay();
+        let (_bob_rtc, bob, mut bob_events) = join_signaled("replay", "bob", &url, driver).await;
+        wait_for_event(&mut bob_events, |event| match event {
+            crate::Event::TrackReceived { track } if track.id() == "camera" => Some(()),
+            _ => None,
+        })
+        .await;
+        bob.subscribe_track_with_options(
+            "alice",
+            "camera",
+            SubscribeOptions::default().with_dvr(Duration::from_secs(30)),
+        )
+        .await
+        .unwrap();
+        let alice = bob.remote_participant("alice").await.unwrap();
+        let camera = alice.get_remote_track("camera").unwrap();
+        assert!(camera.has_dvr());
+
+        // Video keeps arriving while paused and is buffered for later
+        camera.pause().unwrap();
+        let namespace = remote_namespace(&bob, "alice", "camera").await;
+        for object_id in 0..3 {
+            let object = relayed_object(
+                &namespace,
+                0,
+                object_id,
+                quicrtc_core::MoqObjectStatus::Normal,
+                vec![1; 100],
+            );
+            relay.send(object).unwrap();
+        }
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let alice = bob.remote_participant("alice").await.unwrap();
+                let track = alice.get_remote_track("camera").unwrap();
+                if track.stats().objects_received == 3 {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Buffered objects were not counted");
+        assert!(camera.is_playout_paused());
+        assert!(camera.time_shift() > Duration::ZERO);
+
+        // Seeking lands on the buffered group; going live drops the lag
+        let behind = camera.seek(Duration::from_secs(60)).unwrap();
+        assert!(behind > Duration::ZERO && behind < Duration::from_secs(30));
+        camera.go_live().unwrap();
+        assert!(!camera.is_playout_paused());
+        assert_eq!(camera.time_shift(), Duration::ZERO);
+
+        bob.unsubscribe_track("alice", "camera").await.unwrap();
+        let alice = bob.remote_participant("alice").await.unwrap();
+        assert!(!alice.get_remote_track("camera").unwrap().has_dvr());
+    }
+
+    #[cfg(all(feature = "media", feature = "signaling"))]
+    #[tokio::test]
+    async fn test_subscribed_remote_audio_is_mixed_and_played() {
+        let url = start_signaling_server().await;
+        let (_alice_rtc, _alice, _) =
+            join_signaled("music", "alice", &url, StubDriver::relay()).await;
+        let (driver, relay) = StubDriver::delivering_relay();
+        let (_bob_rtc, bob, mut bob_events) = join_signaled("music", "bob", &url, driver).await;
+        wait_for_event(&mut bob_events, |event| match event {
+            crate::Event::TrackReceived { track } if track.id() == "microphone" => Some(()),
+            _ => None,
+        })
+        .await;
+        let speaker = quicrtc_media::virtual_device::register_speaker("remote-audio-speaker");
+        bob.set_audio_output(Some("virtual:remote-audio-speaker"))
+            .await
+            .unwrap();
+        bob.subscribe_track("alice", "microphone").await.unwrap();
+
+        // One audio frame per group, closed by its end marker
+        let namespace = remote_namespace(&bob, "alice", "microphone").await;
+        for group_id in 0..10 {
+            let frame = relayed_object(
+                &namespace,
+                group_id,
+                0,
+                quicrtc_core::MoqObjectStatus::Normal,
+                vec![200; 160],
+            );
+            let end = relayed_object(
+                &namespace,
+                group_id,
+                1,
+                quicrtc_core::MoqObjectStatus::EndOfGroup,
+                Vec::new(),
+            );
+            relay.send(frame).unwrap();
+            relay.send(end).unwrap();
+        }
+
+        let played = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let audio = speaker.audio();
+                if audio.samples.iter().any(|sample| *sample != 0.0) {
+                    break audio;
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await
+        .expect("Remote audio was not played");
+        assert_eq!(played.sample_rate, 48000);
+
+        // Unsubscribing stops feeding the mixer
+        bob.unsubscribe_track("alice", "microphone").await.unwrap();
+        assert!(bob
+            .inner
+            .read()
+            .await
+            .remote_audio
+            .lock()
+            .unwrap()
+            .is_empty());
+    }
 }
@@ -0,0 +1,224 @@
+//! History of room statistics
+//!
+//! Track statistics are only kept as their latest values. Rooms built with
+//! [`RoomBuilder::stats_history`](crate::RoomBuilder::stats_history) also
+//! sample them, together with connection totals, every
+//! [`StatsHistoryConfig::sample_interval`] into a ring buffer covering the
+//! last [`StatsHistoryConfig::retention`]. Dashboards plot the series, call
+//! reports attach them, and apps query them with
+//! [`StatsHistory::stats_between`].
+
+use crate::report::CallTrackReport;
+use crate::track::TrackStats;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How often statistics are sampled and for how long samples are kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StatsHistoryConfig {
+    /// Time between two samples
+    #[serde(with = "humantime_serde")]
+    pub sample_interval: Duration,
+    /// How far back samples are kept; older ones are dropped first
+    #[serde(with = "humantime_serde")]
+    pub retention: Duration,
+}
+
+impl Default for StatsHistoryConfig {
+    fn default() -> Self {
+        Self {
+            sample_interval: Duration::from_secs(1),
+            retention: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+impl StatsHistoryConfig {
+    /// Samples the ring buffer holds
+    pub fn capacity(&self) -> usize {
+        if self.sample_interval.is_zero() {
+            return 1;
+        }
+        let samples = self.retention.as_secs_f64() / self.sample_interval.as_secs_f64();
+        (samples.ceil() as usize).max(1)
+    }
+}
+
+/// Connection totals of one sample, summed over its tracks
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionSample {
+    /// Bitrate of our published tracks in bps
+    pub send_bitrate: u64,
+    /// Bitrate of remote tracks in bps
+    pub receive_bitrate: u64,
+    /// Packets lost so far, all tracks together
+    pub packets_lost: u64,
+    /// Highest round-trip time any track reports, in milliseconds
+    pub rtt_ms: Option<f64>,
+}
+
+impl ConnectionSample {
+    /// Sum the statistics of `tracks`
+    pub fn from_tracks(tracks: &[CallTrackReport]) -> Self {
+        let mut sample = Self::default();
+        for track in tracks {
+            let bitrate = u64::from(track.stats.current_bitrate);
+            match track.participant_id {
+                None => sample.send_bitrate += bitrate,
+                Some(_) => sample.receive_bitrate += bitrate,
+            }
+            sample.packets_lost += track.stats.packets_lost;
+            if let Some(rtt) = track.stats.rtt_ms {
+                sample.rtt_ms = Some(sample.rtt_ms.map_or(rtt, |max| max.max(rtt)));
+            }
+        }
+        sample
+    }
+}
+
+/// Statistics of a room at one point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatsSample {
+    /// When the sample was taken
+    pub at: DateTime<Utc>,
+    /// Statistics of every local and remote track
+    pub tracks: Vec<CallTrackReport>,
+    /// Connection totals
+    pub connection: ConnectionSample,
+}
+
+impl StatsSample {
+    /// Sample of `tracks` taken at `at`
+    pub fn new(at: DateTime<Utc>, tracks: Vec<CallTrackReport>) -> Self {
+        let connection = ConnectionSample::from_tracks(&tracks);
+        Self {
+            at,
+            tracks,
+            connection,
+        }
+    }
+
+    /// Statistics of one track in this sample
+    pub fn track(&self, track_id: &str) -> Option<&TrackStats> {
+        self.tracks
+            .iter()
+            .find(|track| track.track_id == track_id)
+            .map(|track| &track.stats)
+    }
+}
+
+/// Ring buffer of [`StatsSample`]s
+///
+/// Clones share the same samples.
+#[derive(Debug, Clone)]
+pub struct StatsHistory {
+    config: StatsHistoryConfig,
+    samples: Arc<Mutex<VecDeque<StatsSample>>>,
+}
+
+impl StatsHistory {
+    /// Empty history keeping samples by `config`
+    pub fn new(config: StatsHistoryConfig) -> Self {
+        Self {
+            config,
+            samples: Arc::new(Mutex::new(VecDeque::with_capacity(config.capacity()))),
+        }
+    }
+
+    /// Sampling interval and retention
+    pub fn config(&self) -> StatsHistoryConfig {
+        self.config
+    }
+
+    /// Add a sample, dropping the oldest once the buffer is full
+    pub fn record(&self, sample: StatsSample) {
+        let mut samples = self.samples.lock().unwrap();
+        while samples.len() >= self.config.capacity() {
+            samples.pop_front();
+        }
+        samples.push_back(sample);
+    }
+
+    /// Every sample kept, oldest first
+    pub fn samples(&self) -> Vec<StatsSample> {
+        self.samples.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Most recent sample
+    pub fn latest(&self) -> Option<StatsSample> {
+        self.samples.lock().unwrap().back().cloned()
+    }
+
+    /// Samples taken from `from` to `to`, both included, oldest first
+    pub fn stats_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<StatsSample> {
+        self.samples
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|sample| sample.at >= from && sample.at <= to)
+            .cloned()
+            .collect()
+    }
+
+    /// Time series of one track's statistics, oldest first
+    pub fn track_series(&self, track_id: &str) -> Vec<(DateTime<Utc>, TrackStats)> {
+        self.samples
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|sample| Some((sample.at, sample.track(track_id)?.clone())))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(at: DateTime<Utc>, bitrate: u32) -> StatsSample {
+        let track = |participant_id: Option<&str>| CallTrackReport {
+            track_id: format!("{}-camera", participant_id.unwrap_or("me")),
+            participant_id: participant_id.map(str::to_string),
+            kind: "video".to_string(),
+            stats: TrackStats {
+                current_bitrate: bitrate,
+                ..TrackStats::default()
+            },
+        };
+        StatsSample::new(at, vec![track(None), track(Some("bob"))])
+    }
+
+    #[test]
+    fn test_history_keeps_retention_window() {
+        let history = StatsHistory::new(StatsHistoryConfig {
+            sample_interval: Duration::from_secs(1),
+            retention: Duration::from_secs(3),
+        });
+        let start = Utc::now();
+        let at = |secs| start + chrono::Duration::seconds(secs);
+        for secs in 0..5 {
+            history.record(sample(at(secs), 1000 * (secs as u32 + 1)));
+        }
+
+        // Only the last three seconds are kept
+        let samples = history.samples();
+        assert_eq!(samples.len(), 3);
+        assert_eq!(samples[0].at, at(2));
+        assert_eq!(history.latest().unwrap().connection.send_bitrate, 5000);
+        assert_eq!(samples[0].connection.receive_bitrate, 3000);
+
+        let between = history.stats_between(at(1), at(3));
+        assert_eq!(
+            between.iter().map(|sample| sample.at).collect::<Vec<_>>(),
+            vec![at(2), at(3)]
+        );
+
+        let series = history.track_series("bob-camera");
+        assert_eq!(series.len(), 3);
+        assert_eq!(series[2].1.current_bitrate, 5000);
+    }
+}
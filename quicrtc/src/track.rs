@@ -1,11 +1,13 @@
 //! Track management and abstractions
 
-use quicrtc_core::{MoqTrack, TrackNamespace};
-use std::time::Instant;
+use quicrtc_core::{
+    DvrHandle, MoqTrack, QuicRtcError, TrackNamespace, TrackTransferStats, VideoContentHint,
+};
+use std::time::{Duration, Instant};
 use tracing::{debug, info};
 
 #[cfg(feature = "media")]
-use quicrtc_media::{AudioTrack, VideoTrack};
+use quicrtc_media::{AudioTrack, DecodeStats, EncodeStats, PlaybackConfig, VideoFrame, VideoTrack};
 
 /// Local track representation for tracks published by this participant
 #[derive(Debug, Clone)]
@@ -46,7 +48,12 @@ impl LocalTrack {
             muted: false,
             published_at: Instant::now(),
             state: TrackState::Ready,
-            settings: TrackSettings::video_default(),
+            settings: match source {
+                TrackSource::Screen | TrackSource::Application => {
+                    TrackSettings::video_screen_share()
+                }
+                _ => TrackSettings::video_default(),
+            },
             stats: TrackStats::default(),
         }
     }
@@ -148,6 +155,22 @@ impl LocalTrack {
         self.settings = settings;
     }
 
+    /// Get the video content hint
+    pub fn content_hint(&self) -> Option<VideoContentHint> {
+        self.settings.content_hint
+    }
+
+    /// Set the video content hint (ignored for audio tracks)
+    ///
+    /// This only records the hint; `Room::set_video_content_hint` also
+    /// retunes the encoder of a published track.
+    pub fn set_content_hint(&mut self, hint: VideoContentHint) {
+        if self.kind == TrackKind::Video {
+            debug!("⚙️ Local track {} content hint: {:?}", self.id, hint);
+            self.settings.content_hint = Some(hint);
+        }
+    }
+
     /// Get track statistics
     pub fn stats(&self) -> &TrackStats {
         &self.stats
@@ -158,6 +181,31 @@ impl LocalTrack {
         self.stats = stats;
     }
 
+    /// Refresh statistics from the transport's counters for this track
+    ///
+    /// `interval` is the time since the previous refresh; current rates
+    /// are computed over it. Each object carries one encoded frame.
+    pub fn refresh_stats(&mut self, transfer: &TrackTransferStats, interval: Duration) {
+        self.stats.objects_sent = transfer.objects_sent;
+        self.stats.packets_transferred = transfer.objects_sent;
+        self.stats.current_resolution = transfer
+            .resolution
+            .or(self.settings.target_resolution)
+            .filter(|_| self.kind == TrackKind::Video);
+        self.stats.update_rates(
+            transfer.bytes_sent,
+            transfer.objects_sent,
+            interval,
+            self.published_at.elapsed(),
+        );
+    }
+
+    /// Take the encoder's queue and drop counts into the statistics
+    #[cfg(feature = "media")]
+    pub fn apply_encode_stats(&mut self, stats: &EncodeStats) {
+        self.stats.apply_encode_stats(stats);
+    }
+
     /// Get publication time
     pub fn published_at(&self) -> Instant {
         self.published_at
@@ -207,11 +255,21 @@ pub struct RemoteTrack {
     settings: TrackSettings,
     /// Reception statistics
     stats: TrackStats,
+    /// Simulcast layers the publisher announced (empty if not simulcast)
+    simulcast_layers: Vec<SimulcastLayer>,
+    /// Label, dimensions and codec the publisher announced
+    metadata: TrackMetadata,
+    /// Whether we are subscribed to this track's media
+    subscribed: bool,
+    /// How received media is buffered and paced for playout
+    #[cfg(feature = "media")]
+    playback: PlaybackConfig,
+    /// Time-shift buffer, if subscribed with DVR enabled
+    dvr: Option<DvrHandle>,
 }
 
 impl RemoteTrack {
     /// Create a new remote video track
-    #[cfg(feature = "media")]
     pub fn video(
         id: String,
         participant_id: String,
@@ -233,11 +291,16 @@ impl RemoteTrack {
             state: TrackState::Receiving,
             settings: TrackSettings::video_default(),
             stats: TrackStats::default(),
+            simulcast_layers: Vec::new(),
+            metadata: TrackMetadata::default(),
+            subscribed: true,
+            #[cfg(feature = "media")]
+            playback: PlaybackConfig::default(),
+            dvr: None,
         }
     }
 
     /// Create a new remote audio track
-    #[cfg(feature = "media")]
     pub fn audio(
         id: String,
         participant_id: String,
@@ -259,6 +322,12 @@ impl RemoteTrack {
             state: TrackState::Receiving,
             settings: TrackSettings::audio_default(),
             stats: TrackStats::default(),
+            simulcast_layers: Vec::new(),
+            metadata: TrackMetadata::default(),
+            subscribed: true,
+            #[cfg(feature = "media")]
+            playback: PlaybackConfig::default(),
+            dvr: None,
         }
     }
 
@@ -272,6 +341,11 @@ impl RemoteTrack {
         &self.participant_id
     }
 
+    /// Get the track name announced by the publisher
+    pub fn name(&self) -> &str {
+        &self.moq_track.name
+    }
+
     /// Get track kind
     pub fn kind(&self) -> TrackKind {
         self.kind
@@ -340,6 +414,44 @@ impl RemoteTrack {
         self.stats = stats;
     }
 
+    /// Refresh statistics from the transport's counters for this track
+    ///
+    /// `interval` is the time since the previous refresh; current rates
+    /// are computed over it. Each object carries one encoded frame.
+    pub fn refresh_stats(&mut self, transfer: &TrackTransferStats, interval: Duration) {
+        self.stats.objects_received = transfer.objects_received;
+        self.stats.packets_transferred = transfer.objects_received;
+        self.stats.packets_lost = transfer.objects_lost;
+        self.stats.jitter_ms = Some(transfer.jitter.as_secs_f64() * 1000.0);
+        self.stats.freeze_count = transfer.freeze_count;
+        self.stats.total_freeze_duration = transfer.total_freeze_duration;
+        if self.kind == TrackKind::Video {
+            self.stats.current_resolution = transfer.resolution;
+        }
+        self.stats.update_rates(
+            transfer.bytes_received,
+            transfer.objects_received,
+            interval,
+            self.received_at.elapsed(),
+        );
+    }
+
+    /// Score playback from the decoder's losses and load
+    ///
+    /// The time budget per frame follows the received framerate, taken as
+    /// 30fps until frames arrive. Each call replaces the previous score.
+    #[cfg(feature = "media")]
+    pub fn apply_decode_stats(&mut self, stats: &DecodeStats) {
+        let framerate = if self.stats.current_framerate > 0.0 {
+            self.stats.current_framerate
+        } else {
+            30.0
+        };
+        self.stats.quality_score = None;
+        self.stats
+            .apply_decode_stats(stats, Duration::from_secs_f64(1.0 / framerate));
+    }
+
     /// Get reception time
     pub fn received_at(&self) -> Instant {
         self.received_at
@@ -350,6 +462,148 @@ impl RemoteTrack {
         self.received_at.elapsed()
     }
 
+    /// Get the simulcast layers available from the publisher
+    pub fn simulcast_layers(&self) -> &[SimulcastLayer] {
+        &self.simulcast_layers
+    }
+
+    /// Set the available simulcast layers (from remote announcements)
+    pub fn set_simulcast_layers(&mut self, layers: Vec<SimulcastLayer>) {
+        debug!(
+            "📶 Remote track {} simulcast layers: {}",
+            self.id,
+            layers.len()
+        );
+        self.simulcast_layers = layers;
+    }
+
+    /// Label, dimensions and codec the publisher announced
+    pub fn metadata(&self) -> &TrackMetadata {
+        &self.metadata
+    }
+
+    /// Get the publisher's label for the track, if it set one
+    pub fn label(&self) -> Option<&str> {
+        self.metadata.label.as_deref()
+    }
+
+    /// Set the publisher's description of the track (from remote announcements)
+    pub fn set_metadata(&mut self, source: TrackSource, metadata: TrackMetadata) {
+        debug!(
+            "🏷️ Remote track {} announced as {:?} ({})",
+            self.id, metadata.label, source
+        );
+        self.source = source;
+        self.metadata = metadata;
+    }
+
+    /// Check if we are subscribed to this track's media
+    pub fn is_subscribed(&self) -> bool {
+        self.subscribed
+    }
+
+    /// Set subscription state
+    pub(crate) fn set_subscribed(&mut self, subscribed: bool) {
+        if self.subscribed != subscribed {
+            debug!(
+                "📥 Remote track {} subscribed state changed: {}",
+                self.id, subscribed
+            );
+            self.subscribed = subscribed;
+        }
+    }
+
+    /// How received media is buffered and paced for playout
+    #[cfg(feature = "media")]
+    pub fn playback_config(&self) -> &PlaybackConfig {
+        &self.playback
+    }
+
+    /// Set the playout configuration chosen when subscribing
+    #[cfg(feature = "media")]
+    pub(crate) fn set_playback_config(&mut self, playback: PlaybackConfig) {
+        self.playback = playback;
+    }
+
+    /// Whether the track can be paused and seeked back
+    pub fn has_dvr(&self) -> bool {
+        self.dvr.is_some()
+    }
+
+    /// How much media the time-shift buffer keeps, if DVR is enabled
+    pub(crate) fn dvr_retention(&self) -> Option<Duration> {
+        self.dvr.as_ref().map(DvrHandle::retention)
+    }
+
+    /// Set the time-shift buffer chosen when subscribing
+    pub(crate) fn set_dvr(&mut self, dvr: Option<DvrHandle>) {
+        self.dvr = dvr;
+    }
+
+    /// Pause playout of a live track; media received meanwhile is buffered
+    ///
+    /// Requires a subscription with DVR enabled, see
+    /// [`SubscribeOptions::with_dvr`].
+    pub fn pause(&self) -> Result<(), QuicRtcError> {
+        self.require_dvr()?.pause();
+        Ok(())
+    }
+
+    /// Resume playout where it was paused, behind the live edge
+    pub fn resume(&self) -> Result<(), QuicRtcError> {
+        self.require_dvr()?.resume();
+        Ok(())
+    }
+
+    /// Move playout to `behind` the live edge
+    ///
+    /// Playout restarts at the nearest keyframe at or before that point,
+    /// limited to the retention window. Returns how far playout now lags
+    /// the live edge.
+    pub fn seek(&self, behind: Duration) -> Result<Duration, QuicRtcError> {
+        Ok(self.require_dvr()?.seek(behind))
+    }
+
+    /// Return to live playout
+    pub fn go_live(&self) -> Result<(), QuicRtcError> {
+        self.require_dvr()?.go_live();
+        Ok(())
+    }
+
+    /// How far playout lags the live edge (zero without DVR)
+    pub fn time_shift(&self) -> Duration {
+        self.dvr
+            .as_ref()
+            .map_or(Duration::ZERO, DvrHandle::time_shift)
+    }
+
+    /// Check if playout was paused with [`Self::pause`]
+    pub fn is_playout_paused(&self) -> bool {
+        self.dvr.as_ref().is_some_and(DvrHandle::is_paused)
+    }
+
+    fn require_dvr(&self) -> Result<&DvrHandle, QuicRtcError> {
+        self.dvr.as_ref().ok_or_else(|| QuicRtcError::InvalidState {
+            expected: "Track subscribed with DVR enabled".to_string(),
+            actual: format!("Track {} has no time-shift buffer", self.id),
+        })
+    }
+
+    /// Summary of this track for enumeration
+    pub fn info(&self) -> RemoteTrackInfo {
+        RemoteTrackInfo {
+            track_id: self.id.clone(),
+            participant_id: self.participant_id.clone(),
+            name: self.moq_track.name.clone(),
+            kind: self.kind,
+            source: self.source,
+            muted: self.muted,
+            subscribed: self.subscribed,
+            simulcast_layers: self.simulcast_layers.clone(),
+            metadata: self.metadata.clone(),
+        }
+    }
+
     /// Check if track is actively receiving data
     pub fn is_receiving(&self) -> bool {
         matches!(self.state, TrackState::Receiving)
@@ -366,6 +620,327 @@ impl RemoteTrack {
     }
 }
 
+/// Point-in-time description of a remote participant's track
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteTrackInfo {
+    /// Track ID
+    pub track_id: String,
+    /// Participant ID that owns the track
+    pub participant_id: String,
+    /// Track name announced by the publisher
+    pub name: String,
+    /// Track kind (audio/video)
+    pub kind: TrackKind,
+    /// Track source (camera, microphone, screen)
+    pub source: TrackSource,
+    /// Whether the publisher has muted the track
+    pub muted: bool,
+    /// Whether we are subscribed to the track's media
+    pub subscribed: bool,
+    /// Simulcast layers available from the publisher
+    pub simulcast_layers: Vec<SimulcastLayer>,
+    /// Label, dimensions and codec the publisher announced
+    pub metadata: TrackMetadata,
+}
+
+/// Publisher-set description of a track, announced with it over signaling
+///
+/// Lets UIs label and size a track's tile before media arrives.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrackMetadata {
+    /// Human-readable label, e.g. "Front camera" or "Slides"
+    pub label: Option<String>,
+    /// Initial frame size as (width, height), for video
+    pub dimensions: Option<(u32, u32)>,
+    /// Codec the track is encoded with
+    pub codec: Option<String>,
+}
+
+/// One encoding of a simulcast video track
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulcastLayer {
+    /// Layer identifier, e.g. "q", "h" or "f"
+    pub rid: String,
+    /// Frame width in pixels
+    pub width: u32,
+    /// Frame height in pixels
+    pub height: u32,
+    /// Target bitrate in kbps
+    pub max_bitrate_kbps: u32,
+}
+
+impl SimulcastLayer {
+    /// Create a layer description
+    pub fn new(rid: impl Into<String>, width: u32, height: u32, max_bitrate_kbps: u32) -> Self {
+        Self {
+            rid: rid.into(),
+            width,
+            height,
+            max_bitrate_kbps,
+        }
+    }
+}
+
+/// Options for subscribing to a remote track
+///
+/// The defaults suit calls: media plays as soon as it arrives. Viewers that
+/// only watch and listen can use [`SubscribeOptions::broadcast`] to trade
+/// latency for smooth playback.
+#[derive(Debug, Clone, Default)]
+pub struct SubscribeOptions {
+    /// Delivery priority requested from the publisher (lower is more
+    /// important); `None` puts audio ahead of video
+    pub priority: Option<u8>,
+    /// How received media is buffered and paced for playout
+    #[cfg(feature = "media")]
+    pub playback: PlaybackConfig,
+    /// Keep received media this long so playout can be paused and seeked
+    /// back; `None` plays live only
+    pub dvr_retention: Option<Duration>,
+}
+
+impl SubscribeOptions {
+    /// Options for non-interactive viewers
+    ///
+    /// Media is buffered before playout and played at up to 1.25x after
+    /// a stall until the buffer is back to its target.
+    pub fn broadcast() -> Self {
+        Self {
+            priority: None,
+            #[cfg(feature = "media")]
+            playback: PlaybackConfig::broadcast(),
+            dvr_retention: None,
+        }
+    }
+
+    /// Set the delivery priority
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Keep `retention` of received media for pausing and seeking back
+    ///
+    /// Media is buffered in memory, so long windows on high-bitrate video
+    /// cost a lot of memory.
+    pub fn with_dvr(mut self, retention: Duration) -> Self {
+        self.dvr_retention = Some(retention);
+        self
+    }
+
+    /// Set the playout configuration
+    #[cfg(feature = "media")]
+    pub fn with_playback(mut self, playback: PlaybackConfig) -> Self {
+        self.playback = playback;
+        self
+    }
+}
+
+/// Picture decoded from a subscribed remote video track, see
+/// [`Room::remote_video`](crate::Room::remote_video)
+#[cfg(feature = "media")]
+#[derive(Debug, Clone)]
+pub struct RemoteVideoFrame {
+    /// Participant publishing the track
+    pub participant_id: String,
+    /// Track the picture belongs to
+    pub track_id: String,
+    /// Picture at the size and rotation the publisher signalled
+    pub frame: VideoFrame,
+}
+
+/// Track that [`Room::play_audio`](crate::Room::play_audio) sends audio on
+#[cfg(feature = "media")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlaybackTarget {
+    /// Mix into the published microphone track
+    #[default]
+    Microphone,
+    /// Mix into the room's playback track, published on first use and
+    /// shared by every playback that targets it
+    SeparateTrack,
+}
+
+/// Options for [`Room::play_audio_with_options`](crate::Room::play_audio_with_options)
+#[cfg(feature = "media")]
+#[derive(Debug, Clone)]
+pub struct PlayAudioOptions {
+    /// Linear gain applied to the source (0.0 silences it)
+    pub gain: f32,
+    /// Track the audio is sent on
+    pub target: PlaybackTarget,
+}
+
+#[cfg(feature = "media")]
+impl Default for PlayAudioOptions {
+    fn default() -> Self {
+        Self {
+            gain: 1.0,
+            target: PlaybackTarget::Microphone,
+        }
+    }
+}
+
+#[cfg(feature = "media")]
+impl PlayAudioOptions {
+    /// Set the initial gain
+    pub fn with_gain(mut self, gain: f32) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    /// Send the audio on the room's playback track instead of the microphone
+    pub fn on_separate_track(mut self) -> Self {
+        self.target = PlaybackTarget::SeparateTrack;
+        self
+    }
+}
+
+/// Gain of a playing source, shared with the outgoing audio mixer
+#[cfg(feature = "media")]
+#[derive(Debug)]
+pub(crate) struct PlaybackGain(std::sync::atomic::AtomicU32);
+
+#[cfg(feature = "media")]
+impl PlaybackGain {
+    pub(crate) fn new(gain: f32) -> Self {
+        Self(std::sync::atomic::AtomicU32::new(gain.max(0.0).to_bits()))
+    }
+
+    fn set(&self, gain: f32) {
+        self.0.store(
+            gain.max(0.0).to_bits(),
+            std::sync::atomic::Ordering::Relaxed,
+        );
+    }
+}
+
+#[cfg(feature = "media")]
+impl quicrtc_media::MixerGain for PlaybackGain {
+    fn gain(&self) -> f32 {
+        f32::from_bits(self.0.load(std::sync::atomic::Ordering::Relaxed))
+    }
+}
+
+/// Handle to audio started with [`Room::play_audio`](crate::Room::play_audio)
+///
+/// Dropping the handle does not stop playback; call [`AudioPlayback::stop`].
+#[cfg(feature = "media")]
+#[derive(Debug, Clone)]
+pub struct AudioPlayback {
+    id: String,
+    track_id: String,
+    gain: std::sync::Arc<PlaybackGain>,
+    stop: tokio_util::sync::CancellationToken,
+    finished: tokio_util::sync::CancellationToken,
+}
+
+#[cfg(feature = "media")]
+impl AudioPlayback {
+    pub(crate) fn new(
+        id: String,
+        track_id: String,
+        gain: std::sync::Arc<PlaybackGain>,
+        stop: tokio_util::sync::CancellationToken,
+        finished: tokio_util::sync::CancellationToken,
+    ) -> Self {
+        Self {
+            id,
+            track_id,
+            gain,
+            stop,
+            finished,
+        }
+    }
+
+    /// Playback ID
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// ID of the published track the audio is sent on
+    pub fn track_id(&self) -> &str {
+        &self.track_id
+    }
+
+    /// Current linear gain
+    pub fn gain(&self) -> f32 {
+        quicrtc_media::MixerGain::gain(self.gain.as_ref())
+    }
+
+    /// Change the gain; applies from the next mixed frame
+    pub fn set_gain(&self, gain: f32) {
+        self.gain.set(gain);
+    }
+
+    /// Stop playing, discarding audio not yet sent
+    pub fn stop(&self) {
+        self.stop.cancel();
+    }
+
+    /// Whether the source has finished playing or was stopped
+    pub fn is_finished(&self) -> bool {
+        self.finished.is_cancelled()
+    }
+
+    /// Wait until the source has finished playing or was stopped
+    pub async fn finished(&self) {
+        self.finished.cancelled().await;
+    }
+}
+
+/// Handle to a composition started with
+/// [`Room::publish_composition`](crate::Room::publish_composition)
+///
+/// Dropping the handle does not stop publishing; call
+/// [`CompositionPublication::stop`].
+#[cfg(feature = "media")]
+#[derive(Debug, Clone)]
+pub struct CompositionPublication {
+    track_id: String,
+    compositor: std::sync::Arc<tokio::sync::Mutex<quicrtc_media::VideoCompositor>>,
+    stop: tokio_util::sync::CancellationToken,
+}
+
+#[cfg(feature = "media")]
+impl CompositionPublication {
+    pub(crate) fn new(
+        track_id: String,
+        compositor: std::sync::Arc<tokio::sync::Mutex<quicrtc_media::VideoCompositor>>,
+        stop: tokio_util::sync::CancellationToken,
+    ) -> Self {
+        Self {
+            track_id,
+            compositor,
+            stop,
+        }
+    }
+
+    /// ID of the published composition track
+    pub fn track_id(&self) -> &str {
+        &self.track_id
+    }
+
+    /// Compositor the published frames are drawn from
+    ///
+    /// Feed it frames and change its layout while publishing.
+    pub fn compositor(
+        &self,
+    ) -> &std::sync::Arc<tokio::sync::Mutex<quicrtc_media::VideoCompositor>> {
+        &self.compositor
+    }
+
+    /// Stop sending composed frames
+    pub fn stop(&self) {
+        self.stop.cancel();
+    }
+
+    /// Whether publishing was stopped
+    pub fn is_stopped(&self) -> bool {
+        self.stop.is_cancelled()
+    }
+}
+
 /// Track kind enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TrackKind {
@@ -399,10 +974,30 @@ pub enum TrackSource {
     SystemAudio,
     /// File playback
     File,
+    /// Several tracks composed into one
+    Composition,
     /// Other/unknown source
     Unknown,
 }
 
+impl TrackSource {
+    /// Parse the name written by [`Display`](std::fmt::Display), e.g. "camera"
+    ///
+    /// Unrecognized names map to [`TrackSource::Unknown`].
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "camera" => TrackSource::Camera,
+            "microphone" => TrackSource::Microphone,
+            "screen" => TrackSource::Screen,
+            "application" => TrackSource::Application,
+            "system_audio" => TrackSource::SystemAudio,
+            "file" => TrackSource::File,
+            "composition" => TrackSource::Composition,
+            _ => TrackSource::Unknown,
+        }
+    }
+}
+
 impl std::fmt::Display for TrackSource {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -412,6 +1007,7 @@ impl std::fmt::Display for TrackSource {
             TrackSource::Application => write!(f, "application"),
             TrackSource::SystemAudio => write!(f, "system_audio"),
             TrackSource::File => write!(f, "file"),
+            TrackSource::Composition => write!(f, "composition"),
             TrackSource::Unknown => write!(f, "unknown"),
         }
     }
@@ -455,6 +1051,8 @@ pub struct TrackSettings {
     pub adaptive_bitrate: bool,
     /// Enable degradation (resolution/framerate reduction under poor conditions)
     pub enable_degradation: bool,
+    /// Content hint used to tune encoding and degradation (for video)
+    pub content_hint: Option<VideoContentHint>,
 }
 
 impl TrackSettings {
@@ -469,6 +1067,7 @@ impl TrackSettings {
             target_resolution: Some((1280, 720)),
             adaptive_bitrate: true,
             enable_degradation: true,
+            content_hint: Some(VideoContentHint::Motion),
         }
     }
 
@@ -483,6 +1082,7 @@ impl TrackSettings {
             target_resolution: None,
             adaptive_bitrate: true,
             enable_degradation: false, // Audio degradation is typically binary (on/off)
+            content_hint: None,
         }
     }
 
@@ -497,6 +1097,7 @@ impl TrackSettings {
             target_resolution: Some((1920, 1080)),
             adaptive_bitrate: true,
             enable_degradation: true,
+            content_hint: Some(VideoContentHint::Motion),
         }
     }
 
@@ -511,6 +1112,22 @@ impl TrackSettings {
             target_resolution: Some((320, 240)),
             adaptive_bitrate: true,
             enable_degradation: true,
+            content_hint: Some(VideoContentHint::Motion),
+        }
+    }
+
+    /// Screen share settings: full resolution at a low framerate
+    pub fn video_screen_share() -> Self {
+        Self {
+            max_bitrate: Some(2_500_000),    // 2.5 Mbps
+            target_bitrate: Some(1_500_000), // 1.5 Mbps
+            max_framerate: Some(15.0),
+            target_framerate: Some(5.0),
+            max_resolution: Some((2560, 1440)),
+            target_resolution: Some((1920, 1080)),
+            adaptive_bitrate: true,
+            enable_degradation: true,
+            content_hint: Some(VideoContentHint::Detail),
         }
     }
 
@@ -525,6 +1142,7 @@ impl TrackSettings {
             target_resolution: None,
             adaptive_bitrate: true,
             enable_degradation: false,
+            content_hint: None,
         }
     }
 }
@@ -536,7 +1154,7 @@ impl Default for TrackSettings {
 }
 
 /// Track statistics for monitoring
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct TrackStats {
     /// Total bytes sent/received
     pub bytes_transferred: u64,
@@ -562,9 +1180,50 @@ pub struct TrackStats {
     pub jitter_ms: Option<f64>,
     /// Network quality score (0-100)
     pub quality_score: Option<u8>,
+    /// Average decode time in milliseconds (remote tracks)
+    pub decode_time_ms: Option<f64>,
+    /// Average encode time in milliseconds (local tracks)
+    pub encode_time_ms: Option<f64>,
+    /// Share of the encode queue in use, from 0.0 to 1.0 (local tracks)
+    pub encode_queue_occupancy: Option<f32>,
+    /// Frames dropped because the encoder fell behind (local tracks)
+    #[serde(default)]
+    pub encoder_frames_dropped: u64,
+    /// Objects sent (local tracks)
+    pub objects_sent: u64,
+    /// Objects received (remote tracks)
+    pub objects_received: u64,
+    /// Number of times playback froze (remote tracks)
+    pub freeze_count: u64,
+    /// Total time spent frozen (remote tracks)
+    #[serde(with = "humantime_serde")]
+    pub total_freeze_duration: Duration,
 }
 
 impl TrackStats {
+    /// Update byte and frame totals and the bitrates and framerates derived from them
+    ///
+    /// Current rates cover the last `interval`, averages the track's whole
+    /// `lifetime`.
+    fn update_rates(&mut self, bytes: u64, frames: u64, interval: Duration, lifetime: Duration) {
+        let new_bytes = bytes.saturating_sub(self.bytes_transferred);
+        let new_frames = frames.saturating_sub(self.frames_transferred);
+        self.bytes_transferred = bytes;
+        self.frames_transferred = frames;
+
+        let per_second = |count: u64, over: Duration| {
+            if over.is_zero() {
+                0.0
+            } else {
+                count as f64 / over.as_secs_f64()
+            }
+        };
+        self.current_bitrate = per_second(new_bytes * 8, interval) as u32;
+        self.current_framerate = per_second(new_frames, interval);
+        self.avg_bitrate = per_second(bytes * 8, lifetime) as u32;
+        self.avg_framerate = per_second(frames, lifetime);
+    }
+
     /// Calculate packet loss percentage
     pub fn packet_loss_percentage(&self) -> f64 {
         if self.packets_transferred == 0 {
@@ -575,6 +1234,28 @@ impl TrackStats {
         }
     }
 
+    /// Fold decode pipeline statistics into the quality score
+    ///
+    /// The score becomes the lower of the current score and the decode
+    /// score, so a struggling decoder is reported even on a clean network.
+    #[cfg(feature = "media")]
+    pub fn apply_decode_stats(&mut self, stats: &DecodeStats, frame_interval: Duration) {
+        self.decode_time_ms = Some(stats.average_decode_time.as_secs_f64() * 1000.0);
+        let decode_score = stats.quality_score(frame_interval);
+        self.quality_score = Some(
+            self.quality_score
+                .map_or(decode_score, |score| score.min(decode_score)),
+        );
+    }
+
+    /// Take encode pipeline statistics
+    #[cfg(feature = "media")]
+    pub fn apply_encode_stats(&mut self, stats: &EncodeStats) {
+        self.encode_time_ms = Some(stats.average_encode_time.as_secs_f64() * 1000.0);
+        self.encode_queue_occupancy = Some(stats.occupancy());
+        self.encoder_frames_dropped = stats.frames_dropped;
+    }
+
     /// Check if stats indicate good quality
     pub fn is_good_quality(&self) -> bool {
         let packet_loss = self.packet_loss_percentage();